@@ -0,0 +1,30 @@
+//! Sample data shared by the benchmarks
+
+use std::fmt::Write;
+
+/// An LSX document with `count` `GameObjects` nodes under `Templates`
+///
+/// `attributes(lsx, i)` appends the attributes of node `i`, usually through
+/// [`attribute`].
+pub fn templates_lsx(count: u32, attributes: impl Fn(&mut String, u32)) -> String {
+    let mut lsx = String::from(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<save>\n\
+         <version major=\"4\" minor=\"0\" revision=\"9\" build=\"331\" />\n\
+         <region id=\"Templates\">\n<node id=\"Templates\">\n<children>\n",
+    );
+    for i in 0..count {
+        lsx.push_str("<node id=\"GameObjects\">");
+        attributes(&mut lsx, i);
+        lsx.push_str("</node>\n");
+    }
+    lsx.push_str("</children>\n</node>\n</region>\n</save>\n");
+    lsx
+}
+
+/// Append an `<attribute>` element
+pub fn attribute(lsx: &mut String, id: &str, type_name: &str, value: impl std::fmt::Display) {
+    let _ = write!(
+        lsx,
+        "<attribute id=\"{id}\" type=\"{type_name}\" value=\"{value}\" />"
+    );
+}
@@ -0,0 +1,149 @@
+//! Compression level trade-offs on LSF- and DDS-like payloads
+//!
+//! Run with `cargo bench --bench compression_levels`. Pass real files to
+//! measure them instead of the built-in samples:
+//!
+//! ```text
+//! cargo bench --bench compression_levels -- path/to/file.lsf path/to/file.dds
+//! ```
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use maclarian::compression::{CompressionSettings, compress_with};
+
+/// Settings compared, in table order
+const SETTINGS: &[&str] = &[
+    "lz4", "lz4:hc3", "lz4:hc9", "lz4:hc12", "zlib:1", "zlib:6", "zlib:9",
+];
+
+/// Minimum time spent per setting and payload
+const MIN_DURATION: Duration = Duration::from_millis(300);
+
+fn main() {
+    let files: Vec<PathBuf> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(PathBuf::from)
+        .collect();
+
+    let payloads: Vec<(String, Vec<u8>)> = if files.is_empty() {
+        vec![
+            ("LSF sample".to_string(), sample_lsf()),
+            ("DDS sample".to_string(), sample_dds()),
+        ]
+    } else {
+        files
+            .iter()
+            .map(|path| {
+                let data =
+                    std::fs::read(path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+                (path.display().to_string(), data)
+            })
+            .collect()
+    };
+
+    for (name, data) in &payloads {
+        println!("{name} ({} bytes)", data.len());
+        println!(
+            "  {:<10} {:>10} {:>8} {:>10}",
+            "setting", "size", "ratio", "MB/s"
+        );
+        for setting in SETTINGS {
+            let settings: CompressionSettings = setting.parse().expect("valid setting");
+            let (size, throughput) = measure(data, &settings);
+            println!(
+                "  {setting:<10} {size:>10} {:>8.3} {throughput:>10.1}",
+                size as f64 / data.len() as f64
+            );
+        }
+        println!();
+    }
+}
+
+/// Compressed size and throughput in MB/s
+fn measure(data: &[u8], settings: &CompressionSettings) -> (usize, f64) {
+    let start = Instant::now();
+    let mut runs = 0u32;
+    let mut size = 0;
+    while runs == 0 || start.elapsed() < MIN_DURATION {
+        size = compress_with(data, settings).expect("compression").len();
+        runs += 1;
+    }
+    let seconds = start.elapsed().as_secs_f64() / f64::from(runs);
+    (size, data.len() as f64 / seconds / 1_000_000.0)
+}
+
+/// Deterministic pseudo-random numbers (xorshift)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Uncompressed LSF-like data: node records, repeated names, GUIDs and floats
+fn sample_lsf() -> Vec<u8> {
+    const NAMES: &[&str] = &[
+        "MapKey",
+        "Name",
+        "DisplayName",
+        "Description",
+        "Icon",
+        "ParentTemplateId",
+        "VisualTemplate",
+        "Stats",
+        "Type",
+        "LevelName",
+        "Flag",
+        "Tags",
+    ];
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let mut out = Vec::new();
+    for node in 0u32..20_000 {
+        out.extend_from_slice(&(node % 97).to_le_bytes());
+        out.extend_from_slice(&(node.saturating_sub(1)).to_le_bytes());
+        out.extend_from_slice(&((node * 3) as u16).to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+
+        let name = NAMES[(rng.next() % NAMES.len() as u64) as usize];
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        let guid = format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            rng.next() as u32,
+            rng.next() as u16,
+            rng.next() as u16,
+            rng.next() as u16,
+            rng.next() & 0xFFFF_FFFF_FFFF
+        );
+        out.extend_from_slice(guid.as_bytes());
+        out.extend_from_slice(&((rng.next() % 1000) as f32 / 10.0).to_le_bytes());
+    }
+    out
+}
+
+/// BC1-like blocks of a smooth texture with some noise
+fn sample_dds() -> Vec<u8> {
+    let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+    let mut out = Vec::new();
+    for y in 0u16..128 {
+        for x in 0u16..128 {
+            let base = ((x / 4) << 11) | ((y / 2) << 5) | ((x + y) / 8);
+            let noise = (rng.next() % 4) as u16;
+            out.extend_from_slice(&base.wrapping_add(noise).to_le_bytes());
+            out.extend_from_slice(&base.wrapping_sub(noise).to_le_bytes());
+            let indices = if rng.next() % 3 == 0 {
+                rng.next() as u32
+            } else {
+                0x5555_5555
+            };
+            out.extend_from_slice(&indices.to_le_bytes());
+        }
+    }
+    out
+}
@@ -0,0 +1,100 @@
+//! Full LSF parse versus lazy and header-only reads
+//!
+//! Run with `cargo bench --bench lsf_lazy`. Pass real files (a large
+//! `_merged.lsf` is the interesting case) to measure them instead of the
+//! built-in sample:
+//!
+//! ```text
+//! cargo bench --bench lsf_lazy -- path/to/_merged.lsf
+//! ```
+
+mod common;
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use maclarian::converter::convert_bytes;
+use maclarian::formats::lsf::{LsfDocument, parse_lsf_bytes, read_header_info};
+
+use common::{attribute, templates_lsx};
+
+/// Minimum time spent per method and file
+const MIN_DURATION: Duration = Duration::from_millis(500);
+
+fn main() {
+    let files: Vec<PathBuf> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(PathBuf::from)
+        .collect();
+
+    let payloads: Vec<(String, Vec<u8>)> = if files.is_empty() {
+        vec![("LSF sample".to_string(), sample_lsf())]
+    } else {
+        files
+            .iter()
+            .map(|path| {
+                let data =
+                    std::fs::read(path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+                (path.display().to_string(), data)
+            })
+            .collect()
+    };
+
+    for (name, data) in &payloads {
+        println!("{name} ({} bytes)", data.len());
+        println!("  {:<28} {:>10} {:>8}", "method", "ms", "speedup");
+
+        // Every method lists region and node names; only the full parse decodes values
+        let full = measure(|| {
+            let doc = parse_lsf_bytes(data).expect("valid LSF");
+            (0..doc.nodes.len())
+                .filter_map(|i| doc.node_name(i))
+                .count()
+        });
+        let lazy = measure(|| {
+            let doc = LsfDocument::open_lazy(data).expect("valid LSF");
+            (0..doc.node_count())
+                .filter_map(|i| doc.node_name(i))
+                .count()
+        });
+        let header = measure(|| read_header_info(data).expect("valid LSF").regions.len());
+
+        for (method, seconds) in [
+            ("parse_lsf_bytes", full),
+            ("LsfDocument::open_lazy", lazy),
+            ("read_header_info", header),
+        ] {
+            println!(
+                "  {method:<28} {:>10.2} {:>7.1}x",
+                seconds * 1000.0,
+                full / seconds
+            );
+        }
+        println!();
+    }
+}
+
+/// Average seconds per run of `f`
+fn measure(mut f: impl FnMut() -> usize) -> f64 {
+    let start = Instant::now();
+    let mut runs = 0u32;
+    while runs == 0 || start.elapsed() < MIN_DURATION {
+        std::hint::black_box(f());
+        runs += 1;
+    }
+    start.elapsed().as_secs_f64() / f64::from(runs)
+}
+
+/// A `_merged.lsf`-like file: many templates with long string values
+fn sample_lsf() -> Vec<u8> {
+    let lsx = templates_lsx(20_000, |lsx, i| {
+        let map_key = format!("{i:08x}-0000-4000-8000-000000000000");
+        attribute(lsx, "MapKey", "FixedString", map_key);
+        attribute(lsx, "Name", "LSString", format!("TEMPLATE_{i}"));
+        let description = "Lorem ipsum dolor sit amet ".repeat(1 + (i % 8) as usize);
+        attribute(lsx, "Description", "LSString", description);
+        attribute(lsx, "Scale", "float", f64::from(i % 100) / 10.0);
+    });
+    convert_bytes(lsx.as_bytes(), "lsx", "lsf").expect("sample converts")
+}
@@ -0,0 +1,106 @@
+//! Converting a PAK's LSF files to LSX: extract then convert, versus
+//! converting straight from the PAK
+//!
+//! Run with `cargo bench --bench pak_convert`. Pass a real PAK (`Shared.pak`
+//! is a good size) to measure it instead of the built-in sample:
+//!
+//! ```text
+//! cargo bench --bench pak_convert -- path/to/Shared.pak
+//! ```
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use maclarian::compression::CompressionSettings;
+use maclarian::converter::{
+    PakConvertOptions, convert_bytes, convert_lsf_to_lsx, convert_pak_entries,
+};
+use maclarian::pak::{FilterSet, PakBuilder, PakOperations};
+use walkdir::WalkDir;
+
+use common::{attribute, templates_lsx};
+
+/// Runs per method; the fastest is reported
+const RUNS: usize = 3;
+
+fn main() {
+    let work = tempfile::tempdir().expect("temp dir");
+    let pak = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with("--"))
+        .map_or_else(|| sample_pak(work.path()), PathBuf::from);
+
+    let filter = FilterSet::new(&["*.lsf"], &[], true).expect("valid filter");
+    println!("{}", pak.display());
+    println!("  {:<28} {:>10} {:>8}", "method", "ms", "speedup");
+
+    let extract_then_convert = fastest(|| {
+        let out = work.path().join("extracted");
+        let _ = std::fs::remove_dir_all(&out);
+        PakOperations::extract_filtered(&pak, &out, &filter, &|_| {}).expect("extract");
+        let mut converted = 0;
+        for entry in WalkDir::new(&out).into_iter().filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "lsf") {
+                convert_lsf_to_lsx(path, &path.with_extension("lsx")).expect("convert");
+                converted += 1;
+            }
+        }
+        converted
+    });
+    let from_pak = fastest(|| {
+        let out = work.path().join("direct");
+        let _ = std::fs::remove_dir_all(&out);
+        let report =
+            convert_pak_entries(&pak, &filter, &out, &PakConvertOptions::default(), &|_| {})
+                .expect("convert");
+        report.written.len()
+    });
+
+    for (method, seconds) in [
+        ("extract + convert_lsf_to_lsx", extract_then_convert),
+        ("convert_pak_entries", from_pak),
+    ] {
+        println!(
+            "  {method:<28} {:>10.2} {:>7.1}x",
+            seconds * 1000.0,
+            extract_then_convert / seconds
+        );
+    }
+}
+
+/// Seconds taken by the fastest of [`RUNS`] runs of `f`
+fn fastest(mut f: impl FnMut() -> usize) -> f64 {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(f());
+            start.elapsed().as_secs_f64()
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// A PAK of small LSF files spread over a few folders
+fn sample_pak(dir: &Path) -> PathBuf {
+    let path = dir.join("Sample.pak");
+    let mut builder = PakBuilder::write_to(&path).expect("create PAK");
+    for i in 0..2_000u32 {
+        let lsx = templates_lsx(20, |lsx, j| {
+            let map_key = format!("{i:08x}-{j:04x}-4000-8000-000000000000");
+            attribute(lsx, "MapKey", "FixedString", map_key);
+            attribute(lsx, "Name", "LSString", format!("TEMPLATE_{i}_{j}"));
+        });
+        let lsf = convert_bytes(lsx.as_bytes(), "lsx", "lsf").expect("sample converts");
+        builder
+            .add_file(
+                &format!("Public/Sample/Folder{}/File{i}.lsf", i % 16),
+                &lsf,
+                CompressionSettings::lz4(),
+            )
+            .expect("add file");
+    }
+    builder.finish().expect("finish PAK");
+    path
+}
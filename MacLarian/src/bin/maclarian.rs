@@ -1,5 +1,5 @@
 //! `MacLarian` CLI binary entry point
 
-fn main() -> anyhow::Result<()> {
+fn main() -> std::process::ExitCode {
     maclarian::cli::run_cli()
 }
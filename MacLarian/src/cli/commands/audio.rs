@@ -0,0 +1,189 @@
+//! CLI commands for WEM audio conversion
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::cli::progress::TaskProgress;
+use crate::error::Error;
+use crate::formats::wem::{CODEBOOK_LIBRARY_NAME, CodebookLibrary, codec_name, convert_wem_file};
+
+/// Pick the codebook library: an explicit file, inline codebooks, or
+/// `packed_codebooks_aoTuV_603.bin` next to the config file or the executable
+fn resolve_codebooks(
+    explicit: Option<&Path>,
+    inline: bool,
+) -> anyhow::Result<Option<CodebookLibrary>> {
+    if inline {
+        return Ok(Some(CodebookLibrary::inline()));
+    }
+    if let Some(path) = explicit {
+        return Ok(Some(CodebookLibrary::load(path)?));
+    }
+
+    let config_dir = crate::cli::config_path()
+        .map(Path::to_path_buf)
+        .or_else(crate::config::Config::default_path)
+        .and_then(|p| p.parent().map(Path::to_path_buf));
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf));
+    for dir in config_dir.into_iter().chain(exe_dir) {
+        let candidate = dir.join(CODEBOOK_LIBRARY_NAME);
+        if candidate.is_file() {
+            tracing::debug!("Using codebook library {}", candidate.display());
+            return Ok(Some(CodebookLibrary::load(candidate)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Convert a single `.wem` file
+///
+/// Wwise Vorbis becomes `.ogg` and PCM becomes `.wav`. Without `output`, the
+/// file is written next to the source; an existing directory as `output`
+/// receives the file under the source's name.
+///
+/// # Errors
+/// Returns an error if the file cannot be converted (including unsupported
+/// codecs, reported with their id) or the output cannot be written.
+pub fn convert(
+    source: &Path,
+    output: Option<&Path>,
+    codebooks: Option<&Path>,
+    inline_codebooks: bool,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let library = resolve_codebooks(codebooks, inline_codebooks)?;
+    let converted = convert_wem_file(source, library.as_ref())?;
+    let extension = converted.format.extension();
+
+    let dest = match output {
+        Some(dir) if dir.is_dir() => dir.join(
+            source
+                .with_extension(extension)
+                .file_name()
+                .unwrap_or_default(),
+        ),
+        Some(path) => {
+            let matches = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(extension));
+            if !matches {
+                anyhow::bail!(
+                    "{} converts to .{extension}, but the output is {}",
+                    source.display(),
+                    path.display()
+                );
+            }
+            path.to_path_buf()
+        }
+        None => source.with_extension(extension),
+    };
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&dest, &converted.data)?;
+
+    if !quiet {
+        println!("Converted {} -> {}", source.display(), dest.display());
+    }
+    Ok(())
+}
+
+/// Convert every `.wem` file under a directory
+///
+/// Output keeps the layout relative to `source`, under `output` or next to
+/// each file. Files with codecs that can't be converted are skipped and
+/// summarized by codec id.
+///
+/// # Errors
+/// Returns an error if the directory cannot be walked, and
+/// [`Error::ValidationFailed`] if any file failed to convert for a reason
+/// other than an unsupported codec.
+pub fn batch(
+    source: &Path,
+    output: Option<&Path>,
+    codebooks: Option<&Path>,
+    inline_codebooks: bool,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let library = resolve_codebooks(codebooks, inline_codebooks)?;
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    for entry in WalkDir::new(source) {
+        let entry = entry.map_err(Error::from)?;
+        let is_wem = entry
+            .path()
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wem"));
+        if entry.file_type().is_file() && is_wem {
+            files.push(entry.into_path());
+        }
+    }
+    files.sort();
+
+    if files.is_empty() {
+        println!("No .wem files found in {}", source.display());
+        return Ok(());
+    }
+    if !quiet {
+        println!("Converting {} WEM files", files.len());
+    }
+
+    let mut converted = 0;
+    let mut failed = 0;
+    let mut unsupported: BTreeMap<u16, usize> = BTreeMap::new();
+    let cancel = crate::cli::cancel_token();
+    let progress = TaskProgress::new("Converting", files.len() as u64, !quiet);
+
+    for file in &files {
+        if cancel.is_cancelled() {
+            break;
+        }
+        progress.start_item(&file.display().to_string());
+        let result = convert_wem_file(file, library.as_ref()).and_then(|audio| {
+            let relative = file.strip_prefix(source).unwrap_or(file);
+            let dest = match output {
+                Some(dir) => dir.join(relative),
+                None => file.clone(),
+            }
+            .with_extension(audio.format.extension());
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&dest, &audio.data)?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => converted += 1,
+            Err(Error::UnsupportedWemCodec { codec, .. }) => {
+                *unsupported.entry(codec).or_default() += 1;
+            }
+            Err(e) => {
+                progress.eprintln(&format!("Failed to convert {}: {e}", file.display()));
+                failed += 1;
+            }
+        }
+        progress.inc();
+    }
+    progress.finish();
+
+    println!();
+    println!("Audio conversion complete:");
+    println!("  Converted: {converted}");
+    for (codec, count) in &unsupported {
+        println!(
+            "  Skipped: {count} (unsupported codec {codec:#06x}, {})",
+            codec_name(*codec)
+        );
+    }
+    cancel.checkpoint()?;
+    if failed > 0 {
+        println!("  Failed: {failed}");
+        return Err(Error::ValidationFailed { failures: failed }.into());
+    }
+    Ok(())
+}
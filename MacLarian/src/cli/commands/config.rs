@@ -0,0 +1,96 @@
+//! CLI commands for the shared settings file
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::cli::output::{OutputContext, print_json};
+use crate::cli::{config as loaded_config, config_path};
+use crate::config::{CONFIG_KEYS, Config};
+
+/// `config show --json` output
+#[derive(Debug, Serialize)]
+struct ConfigReport<'a> {
+    path: Option<&'a Path>,
+    config: &'a Config,
+}
+
+/// Print the current settings
+///
+/// # Errors
+/// Returns an error if a setting cannot be read.
+pub fn show(ctx: &OutputContext) -> Result<()> {
+    if ctx.is_json() {
+        return print_json(&ConfigReport {
+            path: config_path(),
+            config: loaded_config(),
+        });
+    }
+
+    match config_path() {
+        Some(path) if path.exists() => println!("Config file: {}", path.display()),
+        Some(path) => println!("Config file: {} (not created yet)", path.display()),
+        None => println!("Config file: (no location available)"),
+    }
+    println!();
+
+    let config = loaded_config();
+    for key in CONFIG_KEYS {
+        let value = config.get(key)?.unwrap_or_else(|| "(unset)".to_string());
+        println!("  {key:<14} {value}");
+    }
+
+    Ok(())
+}
+
+/// Set a value and write the settings file
+///
+/// # Errors
+/// Returns an error if the key is unknown or the file cannot be written.
+pub fn set(key: &str, value: &str) -> Result<()> {
+    let path = config_path().context("Could not determine config file location (HOME not set)")?;
+
+    let mut config = loaded_config().clone();
+    config.set(key, value)?;
+    config
+        .save(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    match config.get(key)? {
+        Some(value) => println!("{key} = {value}"),
+        None => println!("{key} cleared"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_show_json_shape() {
+        let mut config = Config::default();
+        config.set("bg3-path", "/games/bg3/Data").unwrap();
+        config.set("compression", "lz4").unwrap();
+        let report = ConfigReport {
+            path: Some(Path::new("/home/me/.config/macpak/config.toml")),
+            config: &config,
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "path": "/home/me/.config/macpak/config.toml",
+                "config": {
+                    "bg3-path": "/games/bg3/Data",
+                    "index-dir": null,
+                    "output-dir": null,
+                    "compression": "lz4",
+                    "texture-roots": [],
+                    "log-level": null,
+                },
+            })
+        );
+    }
+}
@@ -1,38 +1,154 @@
 //! CLI interface for format conversion
 
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
+use anyhow::Context;
+
 use super::expand_globs;
-use crate::cli::progress::simple_spinner;
+use crate::cli::output::{OutputContext, print_json};
+use crate::cli::progress::{TaskProgress, simple_spinner};
+use crate::converter::{
+    PakConvertOptions, PrettyOptions, convert_bytes, convert_pak_entries_cancellable,
+    pretty_print_xml,
+};
+use crate::error::Error;
+use crate::formats::lsv::{is_lsv_path, list_lsv};
+use crate::pak::{FilterSet, PakOperations};
 
 /// Execute format conversion for the given sources and destination.
 ///
+/// A source or destination of `-` reads from stdin or writes to stdout.
+/// With `pretty`, LSX and XML output is re-indented.
+///
 /// # Errors
 /// Returns an error if glob expansion, file reading, or conversion fails.
 pub fn execute(
     sources: &[PathBuf],
     destination: &Path,
+    input_format: Option<&str>,
     output_format: Option<&str>,
     texture_format: &str,
+    pretty: Option<&PrettyOptions>,
     quiet: bool,
 ) -> anyhow::Result<()> {
+    if is_stdio(destination) || sources.iter().any(|s| is_stdio(s)) {
+        let [source] = sources else {
+            anyhow::bail!("Only a single source can be converted when using stdin or stdout");
+        };
+        return convert_stream(
+            source,
+            destination,
+            input_format,
+            output_format,
+            texture_format,
+            pretty,
+        );
+    }
+
     // Expand glob patterns
     let sources = expand_globs(sources)?;
 
     // Handle multiple sources (batch conversion)
     if sources.len() > 1 {
-        return convert_batch(&sources, destination, output_format, texture_format, quiet);
+        return convert_batch(
+            &sources,
+            destination,
+            input_format,
+            output_format,
+            texture_format,
+            pretty,
+            quiet,
+        );
     }
 
     let source = &sources[0];
-    convert_single(source, destination, output_format, texture_format, quiet)
+    if is_lsv_path(source) {
+        return convert_lsv(source, destination, output_format, pretty, quiet);
+    }
+    convert_single(
+        source,
+        destination,
+        input_format,
+        output_format,
+        texture_format,
+        pretty,
+        quiet,
+    )
+}
+
+/// Convert the documents inside a PAK into `destination` without
+/// extracting it first
+///
+/// # Errors
+/// Returns an error if the filter or PAK is invalid, or if any entry fails
+/// to convert.
+pub fn execute_from_pak(
+    pak: &Path,
+    filter: &[String],
+    destination: &Path,
+    output_format: Option<&str>,
+    pretty: Option<&PrettyOptions>,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let filter = FilterSet::new(filter, &[], true)?;
+    let options = PakConvertOptions {
+        output_format: output_format.map_or_else(|| "lsx".to_string(), str::to_lowercase),
+        pretty: pretty.copied(),
+        ..PakConvertOptions::default()
+    };
+
+    let cancel = crate::cli::cancel_token();
+    let progress = TaskProgress::new("Converting", 0, ctx.show_progress());
+    let report =
+        convert_pak_entries_cancellable(pak, &filter, destination, &options, &cancel, &|p| {
+            progress.report(p);
+        })?;
+    progress.finish();
+
+    if ctx.is_json() {
+        let failed: Vec<_> = report
+            .failed
+            .iter()
+            .map(|(path, e)| serde_json::json!({ "path": path, "error": e.to_string() }))
+            .collect();
+        print_json(&serde_json::json!({
+            "converted": report.written.len(),
+            "failed": failed,
+            "cancelled": report.cancelled,
+            "files": report.written,
+        }))?;
+    } else if !ctx.quiet || !report.failed.is_empty() {
+        println!(
+            "Converted {} files from {}",
+            report.written.len(),
+            pak.display()
+        );
+        if !report.failed.is_empty() {
+            println!("  Failed: {}", report.failed.len());
+            for (path, e) in &report.failed {
+                eprintln!("    {path}: {e}");
+            }
+        }
+    }
+
+    cancel.checkpoint()?;
+    if !report.failed.is_empty() {
+        return Err(Error::ValidationFailed {
+            failures: report.failed.len(),
+        }
+        .into());
+    }
+    Ok(())
 }
 
 fn convert_single(
     source: &Path,
     destination: &Path,
+    input_format: Option<&str>,
     output_format: Option<&str>,
     texture_format: &str,
+    pretty: Option<&PrettyOptions>,
     quiet: bool,
 ) -> anyhow::Result<()> {
     if !quiet {
@@ -43,12 +159,18 @@ fn convert_single(
         );
     }
 
-    // Auto-detect input format
-    let input = source
-        .extension()
-        .and_then(|s| s.to_str())
-        .map(str::to_lowercase)
-        .ok_or_else(|| anyhow::anyhow!("Cannot detect input format from source file extension"))?;
+    // Use provided input format or auto-detect
+    let input = if let Some(fmt) = input_format {
+        fmt.to_lowercase()
+    } else {
+        source
+            .extension()
+            .and_then(|s| s.to_str())
+            .map(str::to_lowercase)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Cannot detect input format from source file extension")
+            })?
+    };
 
     // Use provided output format or auto-detect
     let output = if let Some(fmt) = output_format {
@@ -63,19 +185,28 @@ fn convert_single(
             })?
     };
 
-    // Check for unsupported LSB format
-    if input == "lsb" || output == "lsb" {
+    if output == "lsb" {
         anyhow::bail!(
-            "LSB format is not supported.\n\
-             \n\
-             The LSB format was deprecated by Larian Studios in Patch 6 (February 2024)\n\
-             and replaced with the LSF format. Modern BG3 uses LSF files exclusively.\n\
+            "Writing LSB is not supported.\n\
              \n\
-             If you need to work with legacy Early Access save files, please use\n\
-             the original LSLib tool: https://github.com/Norbyte/lslib"
+             LSB is the legacy binary format that preceded LSF; it can be read and\n\
+             converted to LSX, LSJ or LSF, but not written."
         );
     }
 
+    // Formatting a document in place of a copy
+    if let Some(options) = pretty
+        && input == output
+        && is_xml_format(&output)
+    {
+        let text = std::fs::read_to_string(source)?;
+        std::fs::write(destination, pretty_print_xml(&text, options)?)?;
+        if !quiet {
+            println!("Formatting complete");
+        }
+        return Ok(());
+    }
+
     // Execute conversion based on input/output format
     match (input.as_str(), output.as_str()) {
         // LSF conversions
@@ -162,6 +293,25 @@ fn convert_single(
             }
         }
 
+        // LSB conversions (read-only)
+        ("lsb", "lsx") => {
+            if !quiet {
+                let pb = simple_spinner("Converting LSB -> LSX...");
+                crate::converter::lsb_to_lsx_with_progress(source, destination, &|p| {
+                    if let Some(ref msg) = p.current_file {
+                        pb.set_message(msg.clone());
+                    }
+                })?;
+                pb.finish_and_clear();
+            } else {
+                crate::converter::lsb_to_lsx(source, destination)?;
+            }
+        }
+        ("lsb", "lsj" | "lsf" | "lsbc" | "lsbs" | "lsfx") => {
+            let data = std::fs::read(source)?;
+            std::fs::write(destination, convert_bytes(&data, "lsb", &output)?)?;
+        }
+
         // GR2/glTF conversions
         ("gr2", "glb") => {
             if !quiet {
@@ -191,7 +341,6 @@ fn convert_single(
         }
         ("glb" | "gltf", "gr2") => {
             if !quiet {
-                println!("Note: Output will be uncompressed (compression not yet implemented)");
                 let pb = simple_spinner("Converting glTF -> GR2...");
                 crate::converter::convert_gltf_to_gr2_with_progress(source, destination, &|p| {
                     if let Some(ref msg) = p.current_file {
@@ -281,6 +430,13 @@ fn convert_single(
         }
     }
 
+    if let Some(options) = pretty
+        && is_xml_format(&output)
+    {
+        let text = std::fs::read_to_string(destination)?;
+        std::fs::write(destination, pretty_print_xml(&text, options)?)?;
+    }
+
     if !quiet {
         println!("Conversion complete");
     }
@@ -290,8 +446,10 @@ fn convert_single(
 fn convert_batch(
     sources: &[PathBuf],
     destination: &Path,
+    input_format: Option<&str>,
     output_format: Option<&str>,
     texture_format: &str,
+    pretty: Option<&PrettyOptions>,
     quiet: bool,
 ) -> anyhow::Result<()> {
     // Ensure destination directory exists
@@ -299,10 +457,16 @@ fn convert_batch(
 
     println!("Batch converting {} files", sources.len());
 
+    let cancel = crate::cli::cancel_token();
     let mut success = 0;
     let mut failed = 0;
+    let progress = TaskProgress::new("Converting", sources.len() as u64, !quiet);
 
     for source in sources {
+        if cancel.is_cancelled() {
+            break;
+        }
+
         // Determine output filename
         let stem = source
             .file_stem()
@@ -314,9 +478,11 @@ fn convert_batch(
             fmt.to_lowercase()
         } else {
             // Infer from input extension
-            let in_ext = source.extension().and_then(|s| s.to_str()).unwrap_or("");
+            let in_ext = input_format
+                .or_else(|| source.extension().and_then(|s| s.to_str()))
+                .unwrap_or("");
             match in_ext.to_lowercase().as_str() {
-                "lsf" | "lsbc" | "lsbs" | "lsfx" => "lsx".to_string(),
+                "lsf" | "lsbc" | "lsbs" | "lsfx" | "lsb" => "lsx".to_string(),
                 "lsx" => "lsf".to_string(),
                 "lsj" => "lsx".to_string(),
                 "gr2" => "glb".to_string(),
@@ -330,19 +496,25 @@ fn convert_batch(
 
         let dest_file = destination.join(format!("{stem}.{out_ext}"));
 
-        match convert_single(source, &dest_file, output_format, texture_format, true) {
-            Ok(()) => {
-                if !quiet {
-                    println!("Converted: {}", source.display());
-                }
-                success += 1;
-            }
+        progress.start_item(&source.display().to_string());
+        match convert_single(
+            source,
+            &dest_file,
+            input_format,
+            output_format,
+            texture_format,
+            pretty,
+            true,
+        ) {
+            Ok(()) => success += 1,
             Err(e) => {
-                eprintln!("Failed to convert {}: {e}", source.display());
+                progress.eprintln(&format!("Failed to convert {}: {e}", source.display()));
                 failed += 1;
             }
         }
+        progress.inc();
     }
+    progress.finish();
 
     println!();
     println!("Batch conversion complete:");
@@ -351,17 +523,162 @@ fn convert_batch(
         println!("  Failed: {failed}");
     }
 
+    cancel.checkpoint()?;
     Ok(())
 }
 
+/// Convert every LSF file in a save (`meta.lsf`, `Globals.lsf`, level caches)
+/// into `destination`, keeping their paths inside the save
+fn convert_lsv(
+    source: &Path,
+    destination: &Path,
+    output_format: Option<&str>,
+    pretty: Option<&PrettyOptions>,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let output = output_format.map_or_else(|| "lsx".to_string(), str::to_lowercase);
+    let members: Vec<String> = list_lsv(source)?
+        .into_iter()
+        .filter(|name| name.to_lowercase().ends_with(".lsf"))
+        .collect();
+    if members.is_empty() {
+        anyhow::bail!("No LSF files in {}", source.display());
+    }
+
+    std::fs::create_dir_all(destination)?;
+    if !quiet {
+        println!(
+            "Converting {} files from {} to {output}",
+            members.len(),
+            source.display()
+        );
+    }
+
+    let mut contents: Vec<_> = PakOperations::read_files_bytes(source, &members)?
+        .into_iter()
+        .collect();
+    contents.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let progress = TaskProgress::new("Converting", contents.len() as u64, !quiet);
+    for (name, data) in &contents {
+        progress.start_item(name);
+        let converted = convert_bytes(data, "lsf", &output)
+            .and_then(|converted| prettify(converted, &output, pretty))
+            .with_context(|| format!("Failed to convert {name}"))?;
+        let dest = destination.join(name).with_extension(&output);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest, converted)?;
+        progress.inc();
+    }
+    progress.finish();
+
+    if !quiet {
+        println!("Conversion complete");
+    }
+    Ok(())
+}
+
+/// Whether `--pretty` applies to a format
+fn is_xml_format(format: &str) -> bool {
+    matches!(format, "lsx" | "xml")
+}
+
+/// Re-indent converted `data` if it is LSX or XML and `pretty` is set
+fn prettify(
+    data: Vec<u8>,
+    format: &str,
+    pretty: Option<&PrettyOptions>,
+) -> crate::error::Result<Vec<u8>> {
+    match pretty {
+        Some(options) if is_xml_format(format) => {
+            Ok(pretty_print_xml(&String::from_utf8(data)?, options)?.into_bytes())
+        }
+        _ => Ok(data),
+    }
+}
+
+/// Whether a path argument means stdin/stdout
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// Convert a single file in memory, reading stdin and/or writing stdout
+///
+/// Nothing but the converted data is written to stdout.
+fn convert_stream(
+    source: &Path,
+    destination: &Path,
+    input_format: Option<&str>,
+    output_format: Option<&str>,
+    texture_format: &str,
+    pretty: Option<&PrettyOptions>,
+) -> anyhow::Result<()> {
+    let data = if is_stdio(source) {
+        let mut data = Vec::new();
+        std::io::stdin().lock().read_to_end(&mut data)?;
+        data
+    } else {
+        std::fs::read(source)?
+    };
+
+    // Explicit format, then extension, then magic bytes
+    let input = input_format
+        .map(str::to_lowercase)
+        .or_else(|| extension(source))
+        .or_else(|| crate::converter::sniff_format(&data).map(str::to_string))
+        .ok_or_else(|| {
+            anyhow::anyhow!("Cannot detect input format; use --input-format when reading stdin")
+        })?;
+    let output = output_format
+        .map(str::to_lowercase)
+        .or_else(|| extension(destination))
+        .ok_or_else(|| {
+            anyhow::anyhow!("Cannot detect output format; use --output-format when writing stdout")
+        })?;
+
+    let converted = if (input.as_str(), output.as_str()) == ("png", "dds") {
+        let img = image::load_from_memory(&data)?;
+        crate::converter::png_image_to_dds_bytes(&img, parse_dds_format(texture_format)?)?
+    } else if pretty.is_some() && input == output && is_xml_format(&output) {
+        data
+    } else {
+        convert_bytes(&data, &input, &output)?
+    };
+    let converted = prettify(converted, &output, pretty)?;
+
+    if is_stdio(destination) {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(&converted)?;
+        stdout.flush()?;
+    } else {
+        std::fs::write(destination, converted)?;
+    }
+    Ok(())
+}
+
+/// Lowercase extension of a real (non-`-`) path
+fn extension(path: &Path) -> Option<String> {
+    if is_stdio(path) {
+        return None;
+    }
+    path.extension()
+        .and_then(|s| s.to_str())
+        .map(str::to_lowercase)
+}
+
 /// Parse DDS format string
-fn parse_dds_format(s: &str) -> anyhow::Result<crate::converter::DdsFormat> {
+pub(super) fn parse_dds_format(s: &str) -> anyhow::Result<crate::converter::DdsFormat> {
     use crate::converter::DdsFormat;
     match s.to_lowercase().as_str() {
         "bc1" | "dxt1" => Ok(DdsFormat::BC1),
         "bc2" | "dxt3" => Ok(DdsFormat::BC2),
         "bc3" | "dxt5" => Ok(DdsFormat::BC3),
+        "bc5" | "ati2" => Ok(DdsFormat::BC5),
         "rgba" | "uncompressed" => Ok(DdsFormat::Rgba),
-        other => anyhow::bail!("Unknown DDS format: '{other}'. Valid options: bc1, bc2, bc3, rgba"),
+        other => {
+            anyhow::bail!("Unknown DDS format: '{other}'. Valid options: bc1, bc2, bc3, bc5, rgba")
+        }
     }
 }
@@ -1,9 +1,10 @@
 //! Subcommand enum definitions for CLI
 
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 use super::LayerArg;
+use crate::virtual_texture::VTexNaming;
 
 /// PAK archive commands
 #[derive(Subcommand)]
@@ -14,11 +15,29 @@ pub enum PakCommands {
 Extracts files from one or more PAK archives. Supports glob patterns for batch
 extraction and filtering by internal file paths.
 
+-f and -x take globs (`**` spans folders, `{a,b}` lists alternatives, matching
+ignores case) and can be repeated: a file is extracted if it matches any -f
+and no -x. `pak list` accepts the same flags and shows the same files.
+
 Examples:
   maclarian pak extract Shared.pak ./output/
   maclarian pak extract \"*.pak\" ./output/
   maclarian pak extract Shared.pak ./output/ -f \"*.lsf\"
-  maclarian pak extract Shared.pak ./output/ --file \"Public/Shared/meta.lsx\"")]
+  maclarian pak extract MyMod.pak ./output/ -f \"Public/MyMod/**/*.{lsf,lsx}\" -x \"**/Localization/**\"
+  maclarian pak extract Shared.pak ./output/ --file \"Public/Shared/meta.lsx\"
+  maclarian pak extract Models.pak ./output/ -f \"*Leather*.GR2\" --bundle
+  maclarian pak extract Models.pak ./output/ -f \"*.GR2\" --convert-gr2 --delete-gr2
+  maclarian pak extract Models.pak ./output/ -f \"*.GR2\" --bundle --png --keep-dds
+  maclarian pak extract Models.pak ./output/ -f \"*Leather*.GR2\" --bundle --manifest bundle.json
+  maclarian pak extract Models.pak ./output/ -f \"*.GR2\" --bundle --vt-naming lslib
+
+GR2 processing:
+  With --bundle, --convert-gr2 or --extract-textures, each extracted GR2 is
+  moved into its own folder, next to its GLB and textures. --manifest writes
+  a JSON file listing each model's source GR2, GLB, meshes and textures
+  (layer, source PAK or virtual texture, internal path and output file).
+  Virtual textures are named {Visual}_{Layer}.dds, or {GTex}_{index}.dds
+  with --vt-naming lslib (see 'vt extract --help').")]
     Extract {
         /// Source PAK file(s) or wildcard pattern
         #[arg(required = true)]
@@ -27,17 +46,57 @@ Examples:
         /// Output directory
         destination: PathBuf,
 
-        /// Only extract files matching glob pattern (e.g., "*.lsf")
+        /// Only extract files matching a glob (repeatable), e.g. "*.{lsf,lsx}"
         #[arg(short = 'f', long, conflicts_with = "file")]
-        filter: Option<String>,
+        filter: Vec<String>,
+
+        /// Skip files matching a glob (repeatable), applied after --filter
+        #[arg(short = 'x', long, conflicts_with = "file")]
+        exclude: Vec<String>,
 
         /// Extract specific file(s) by internal path (comma-separated)
-        #[arg(long, conflicts_with = "filter")]
+        #[arg(long)]
         file: Option<String>,
 
-        /// Suppress progress bar
-        #[arg(short, long)]
-        quiet: bool,
+        /// Exit with an error if any PAK of a batch fails to extract
+        #[arg(long)]
+        strict: bool,
+
+        /// Convert extracted GR2s to GLB and extract their textures
+        #[arg(long)]
+        bundle: bool,
+
+        /// Convert extracted GR2s to GLB
+        #[arg(long)]
+        convert_gr2: bool,
+
+        /// Extract the DDS and virtual textures used by extracted GR2s
+        #[arg(long)]
+        extract_textures: bool,
+
+        /// Convert extracted textures to PNG (implies --extract-textures)
+        #[arg(long)]
+        png: bool,
+
+        /// Delete each GR2 once it has been converted to GLB (with --convert-gr2 or --bundle)
+        #[arg(long)]
+        delete_gr2: bool,
+
+        /// Keep the DDS files after converting textures to PNG
+        #[arg(long, requires = "png")]
+        keep_dds: bool,
+
+        /// Write a JSON manifest of the processed GR2s and their textures
+        #[arg(long, value_name = "FILE")]
+        manifest: Option<PathBuf>,
+
+        /// File naming for extracted virtual textures
+        #[arg(long, value_enum, default_value_t)]
+        vt_naming: VtNamingArg,
+
+        /// Path to BG3 install folder (for texture lookup if not auto-detected)
+        #[arg(long)]
+        bg3_path: Option<PathBuf>,
     },
 
     /// Create PAK file(s) from directory(ies)
@@ -46,14 +105,26 @@ Examples:
 Packages a directory into a PAK archive. Supports batch creation with glob patterns
 and configurable compression.
 
-Compression methods:
-  lz4   - Fast compression, good ratio (default)
-  zlib  - Better ratio, slower
-  none  - No compression
+Compression methods (method[:level]):
+  lz4        - Fastest, good ratio (default)
+  lz4:hc9    - LZ4 HC, levels hc1-hc12; slower to create, same load speed
+  zlib       - zlib level 6; better ratio, slower to load
+  zlib:1-9   - zlib at a specific level
+  none       - No compression
+
+Trade-off on sample payloads (ratio = compressed/original size):
+  setting    speed     LSF ratio  DDS ratio
+  lz4        ~490 MB/s   0.88       0.72
+  lz4:hc9     ~40 MB/s   0.69       0.66
+  zlib:1     ~100 MB/s   0.59       0.59
+  zlib:9      ~10 MB/s   0.55       0.53
+Run `cargo bench --bench compression_levels` for numbers on your machine.
 
 Examples:
   maclarian pak create ./MyMod MyMod.pak
-  maclarian pak create ./MyMod MyMod.pak -c lz4
+  maclarian pak create ./MyMod MyMod.pak -c lz4:hc9
+  maclarian pak create ./MyMod MyMod.pak -x \"*.psd\" -x .git
+  maclarian pak create ./MyMod MyMod.pak --priority 10
   maclarian pak create \"./Mods/*\" ./output/")]
     Create {
         /// Source directory(ies) to pack (supports wildcards)
@@ -63,26 +134,32 @@ Examples:
         /// Output PAK file (single source) or directory (multiple sources)
         destination: PathBuf,
 
-        /// Compression method (lz4, zlib, none)
-        #[arg(short, long, default_value = "lz4")]
-        compression: String,
-
-        /// Suppress progress bar
+        /// Compression method[:level] (lz4, lz4:hc9, zlib:6, none) [default: config value or lz4]
         #[arg(short, long)]
-        quiet: bool,
+        compression: Option<String>,
+
+        /// Leave out files and folders matching a glob (repeatable), e.g. "*.psd" or .git
+        #[arg(short = 'x', long)]
+        exclude: Vec<String>,
+
+        /// Load priority stored in the PAK header; higher priorities override lower ones
+        #[arg(long, default_value_t = 0)]
+        priority: u8,
     },
 
     /// List contents of a PAK file
     #[command(long_about = "List contents of a PAK file
 
 Shows files contained in a PAK archive. Use -d for detailed size and compression
-info, -f to filter by glob pattern, -c for count only.
+info, -f to filter by glob pattern, -x to leave matches out, -c for count only.
+Both -f and -x can be repeated; a file is shown if it matches any -f and no -x.
 
 Examples:
   maclarian pak list Shared.pak
   maclarian pak list Shared.pak -d
   maclarian pak list Shared.pak -f \"*.lsf\"
-  maclarian pak list Shared.pak -f \"Public/**/*\" -c")]
+  maclarian pak list Shared.pak -f \"Public/**/*\" -c
+  maclarian pak list MyMod.pak -f \"*.{lsf,lsx}\" -x \"**/Localization/**\"")]
     List {
         /// PAK file
         source: PathBuf,
@@ -91,17 +168,71 @@ Examples:
         #[arg(short, long)]
         detailed: bool,
 
-        /// Filter by glob pattern (auto-detects and normalizes UUIDs)
+        /// Filter by glob pattern (repeatable; auto-detects and normalizes UUIDs)
         #[arg(short = 'f', long)]
-        filter: Option<String>,
+        filter: Vec<String>,
+
+        /// Hide files matching a glob (repeatable), applied after --filter
+        #[arg(short = 'x', long)]
+        exclude: Vec<String>,
 
         /// Only show count of matching files
         #[arg(short, long)]
         count: bool,
+    },
 
-        /// Suppress extra output
-        #[arg(short, long)]
-        quiet: bool,
+    /// Show size totals of a PAK file and find duplicate content
+    #[command(
+        long_about = "Show size totals of a PAK file and find duplicate content
+
+Prints the total and compressed size, the compression ratio per extension and
+the largest entries, read from the file table only.
+
+--dupes also decompresses every entry whose size matches another entry's and
+groups byte-identical files (copied icons, repeated banks), reporting how much
+space removing the extra copies would save. It reads the whole PAK, so it is
+off by default.
+
+Examples:
+  maclarian pak info MyMod.pak
+  maclarian pak info MyMod.pak --top 25
+  maclarian pak info MyMod.pak --dupes --json"
+    )]
+    Info {
+        /// PAK file
+        source: PathBuf,
+
+        /// Number of largest entries to list
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Find entries with identical contents (decompresses the PAK)
+        #[arg(long)]
+        dupes: bool,
+    },
+
+    /// Check PAK entry paths for problems
+    #[command(long_about = "Check PAK entry paths for problems
+
+Reports entry paths that break on Windows or in the game, each with a
+suggested fix:
+  - backslashes used as separators
+  - characters Windows doesn't allow (<>:\"|?* and control characters)
+  - reserved device names (CON, PRN, AUX, NUL, COM0-9, LPT0-9)
+  - names ending in a dot or space
+  - paths that differ only by case or Unicode normalization from an earlier entry
+  - paths over 240 characters (warning)
+  - paths not in Unicode NFC form, as packed from macOS file names (warning)
+
+Exits non-zero if any errors are found; warnings don't affect the exit code.
+
+Examples:
+  maclarian pak lint MyMod.pak
+  maclarian pak lint \"Mods/*.pak\"")]
+    Lint {
+        /// PAK file(s) or wildcard pattern
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
     },
 }
 
@@ -162,10 +293,6 @@ Examples:
         /// Path to BG3 install folder (required for --textures if not auto-detected)
         #[arg(long = "bg3-path")]
         bg3_path: Option<PathBuf>,
-
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
     },
 
     /// Convert glTF/GLB to GR2 format
@@ -173,11 +300,17 @@ Examples:
     #[command(long_about = "Convert glTF/GLB to GR2 format
 
 Converts glTF/GLB models back to Granny2 format for use in BG3 mods.
-Note: Output is currently uncompressed (Oodle compression not yet implemented).
+Sections are written uncompressed unless --compress is given, which
+compresses them with BitKnit like the game's own files.
+
+--fix-weights renormalizes each vertex's bone weights, keeping its four
+largest influences, so the stored weights always sum to 255. Weights that
+don't sum to 1.0 after export are a common cause of exploded meshes.
 
 Examples:
   maclarian gr2 to-gr2 model.glb model.GR2
-  maclarian gr2 to-gr2 model.gltf model.GR2
+  maclarian gr2 to-gr2 model.gltf model.GR2 --compress
+  maclarian gr2 to-gr2 model.glb model.GR2 --fix-weights
   maclarian gr2 to-gr2 \"*.glb\" ./output/")]
     ToGr2 {
         /// Source GLB or glTF file(s) or wildcard pattern
@@ -187,9 +320,31 @@ Examples:
         /// Output GR2 file (single source) or directory (multiple sources)
         destination: PathBuf,
 
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
+        /// Compress sections with `BitKnit`
+        #[arg(long)]
+        compress: bool,
+
+        /// Renormalize bone weights, keeping the four largest per vertex
+        #[arg(long)]
+        fix_weights: bool,
+    },
+
+    /// Check bone weights in GR2 or glTF/GLB files
+    #[command(long_about = "Check bone weights in GR2 or glTF/GLB files
+
+Reports how the bone weight sums of each file's skinned vertices are
+distributed and how many vertices don't sum to 1.0 or have more than four
+influences. Nothing is changed; use 'gr2 to-gr2 --fix-weights' to fix them
+when converting.
+
+Examples:
+  maclarian gr2 validate model.GR2
+  maclarian gr2 validate model.glb
+  maclarian gr2 validate \"*.GR2\" --json")]
+    Validate {
+        /// GR2, GLB or glTF file(s) or wildcard pattern
+        #[arg(required = true)]
+        sources: Vec<PathBuf>,
     },
 }
 
@@ -199,12 +354,13 @@ pub enum VirtualTextureCommands {
     /// List metadata from a GTS file
     #[command(long_about = "List metadata from a GTS file
 
-Displays information about a virtual texture set including texture names,
-dimensions, layer count, and page file references.
+Displays information about a virtual texture set including texture names and
+GUIDs, dimensions, layer count, and page file references.
 
 Examples:
   maclarian vt list Textures.gts
   maclarian vt list Textures.gts -d
+  maclarian vt list Textures.gts -m
   maclarian vt list Textures.gts -o metadata.json")]
     List {
         /// Path to .gts file
@@ -214,27 +370,72 @@ Examples:
         #[arg(short, long)]
         detailed: bool,
 
+        /// Print the full `FourCC` metadata tree
+        #[arg(short, long)]
+        metadata: bool,
+
         /// Output to JSON file
         #[arg(short, long)]
         output: Option<PathBuf>,
     },
 
+    /// Rename a texture or change its GUID in a GTS file
+    #[command(long_about = "Rename a texture or change its GUID in a GTS file
+
+Rewrites only the FourCC metadata section of the GTS; tiles, page file
+references and the GTP files are left untouched. When the GTS lists more than
+one texture, choose the one to edit with --texture.
+
+Examples:
+  maclarian vt set-meta Textures.gts --name MyTexture
+  maclarian vt set-meta Textures.gts --guid 3f2a9c1e-58b4-4d7a-9e61-0c2b7d4f8a15
+  maclarian vt set-meta Textures.gts -t OldName --name NewName")]
+    #[command(group(clap::ArgGroup::new("change").required(true).multiple(true).args(["name", "guid"])))]
+    SetMeta {
+        /// Path to .gts file
+        path: PathBuf,
+
+        /// Current name of the texture to edit (needed if the GTS has several)
+        #[arg(short, long)]
+        texture: Option<String>,
+
+        /// New texture name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New texture GUID
+        #[arg(long)]
+        guid: Option<String>,
+    },
+
     /// Extract textures from GTS/GTP files to DDS
     #[command(long_about = "Extract textures from GTS/GTP files to DDS
 
-Extracts virtual textures to DDS files. Can filter by texture name and layer.
+Extracts virtual textures to DDS files. Can filter by texture name and layer,
+or pick a single texture by the GUID stored in the GTS metadata. Texture names
+can collide across sets; GUIDs don't. A GUID prefix of 8 or more hex digits is
+enough as long as it matches only one texture.
 
 Layer names (case-insensitive):
   0, BaseMap, BM, Base       - Albedo/diffuse texture
   1, NormalMap, NM, Normal   - Normal map
   2, PhysicalMap, PM, Physical - PBR physical properties
 
+Output naming (--naming):
+  macpak  <output>/<GTS>/<GTP>/<TileSet>_<GTex>_<Layer>.dds, with Layer one of
+          BaseMap, NormalMap, PhysicalMap (default)
+  lslib   <output>/<GTex>_<index>.dds, with index 0, 1 or 2 - the names
+          LSLib's ConverterApp and VTexTool write, for scripts and tutorials
+          that expect them
+
 Examples:
   maclarian vt extract Textures.gts ./output/
   maclarian vt extract Textures.gts ./output/ -t MyTexture
   maclarian vt extract Textures.gts ./output/ --layer BM
   maclarian vt extract Textures.gts ./output/ --layer BM,NM,PM
-  maclarian vt extract \"*.gts\" ./output/")]
+  maclarian vt extract \"*.gts\" ./output/
+  maclarian vt extract \"*.gts\" ./output/ --guid 0a1b2c3d
+  maclarian vt extract \"*.gts\" ./output/ --naming lslib")]
     Extract {
         /// Source GTS/GTP file(s) or wildcard pattern
         #[arg(required = true)]
@@ -247,22 +448,33 @@ Examples:
         #[arg(short = 't', long = "gtex")]
         gtex: Option<String>,
 
+        /// Extract only the texture with this GUID (or unique GUID prefix)
+        #[arg(long, conflicts_with = "gtex")]
+        guid: Option<String>,
+
         /// Layer(s) to extract: 0/BaseMap/BM/Base, 1/NormalMap/NM/Normal, 2/PhysicalMap/PM/Physical
         /// Can be specified multiple times (--layer BM --layer NM) or comma-separated (--layer BM,NM)
         #[arg(short, long, value_delimiter = ',')]
         layer: Vec<LayerArg>,
 
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
+        /// Output file naming and folder layout
+        #[arg(long, value_enum, default_value_t)]
+        naming: VtNamingArg,
+
+        /// Exit with an error if any file of a batch fails to extract
+        #[arg(long)]
+        strict: bool,
     },
 
-    /// Create a virtual texture set from DDS source textures
-    #[command(long_about = "Create a virtual texture set from DDS source textures
+    /// Create a virtual texture set from DDS, PNG or TGA textures
+    #[command(long_about = "Create a virtual texture set from source textures
 
-Creates GTS/GTP virtual texture files from DDS source textures. Auto-detects
+Creates GTS/GTP virtual texture files from source textures. Auto-detects
 layer types from common suffixes (_BM, _NM, _PM) or specify paths manually.
 
+DDS layers must already be BC3 (base, physical) or BC5 (normal). PNG and TGA
+layers are encoded to those formats with mips and must be power-of-two sized.
+
 Note: Virtual texture injection requires BG3 Script Extender (Windows-only).
 macOS users should be aware of this limitation before creating custom textures.
 
@@ -270,9 +482,10 @@ Examples:
   maclarian vt create ./textures/ ./output/
   maclarian vt create ./textures/ ./output/ -t MyTexture
   maclarian vt create ./textures/ ./output/ --base albedo.dds --normal normal.dds
+  maclarian vt create ./textures/ ./output/ --base albedo.png --normal normal.tga
   maclarian vt create ./textures/ ./output/ -c raw")]
     Create {
-        /// Source directory containing DDS files
+        /// Source directory containing DDS, PNG or TGA files
         source: PathBuf,
 
         /// Output directory (for .gts and .gtp files)
@@ -282,32 +495,51 @@ Examples:
         #[arg(short = 't', long = "gtex")]
         gtex: Option<String>,
 
-        /// Path to base map DDS (if not auto-detected)
+        /// Path to base map DDS, PNG or TGA (if not auto-detected)
         #[arg(long)]
         base: Option<PathBuf>,
 
-        /// Path to normal map DDS (if not auto-detected)
+        /// Path to normal map DDS, PNG or TGA (if not auto-detected)
         #[arg(long)]
         normal: Option<PathBuf>,
 
-        /// Path to physical map DDS (if not auto-detected)
+        /// Path to physical map DDS, PNG or TGA (if not auto-detected)
         #[arg(long)]
         physical: Option<PathBuf>,
 
-        /// Compression method: raw, fastlz (default: fastlz)
+        /// Format of the layer files: auto, dds, png or tga (default: auto)
+        #[arg(long, default_value = "auto")]
+        source_format: String,
+
+        /// Compression method: raw, fastlz or fastlz:2 (default: fastlz)
         #[arg(short, long, default_value = "fastlz")]
         compression: String,
 
         /// Disable embedding mip levels in tiles (use for DDS without mips)
         #[arg(long)]
         no_embed_mip: bool,
-
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
     },
 }
 
+/// Output naming for extracted virtual textures
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum VtNamingArg {
+    /// `<GTS>/<GTP>/<TileSet>_<GTex>_<Layer>.dds`
+    #[default]
+    Macpak,
+    /// `<GTex>_<index>.dds`, as written by `LSLib`'s tools
+    Lslib,
+}
+
+impl From<VtNamingArg> for VTexNaming {
+    fn from(arg: VtNamingArg) -> Self {
+        match arg {
+            VtNamingArg::Macpak => VTexNaming::MacPak,
+            VtNamingArg::Lslib => VTexNaming::LsLib,
+        }
+    }
+}
+
 /// LOCA localization file commands
 #[derive(Subcommand)]
 pub enum LocaCommands {
@@ -335,10 +567,76 @@ Examples:
         /// Maximum results to return
         #[arg(short, long, default_value = "50")]
         limit: usize,
+    },
 
-        /// Suppress extra output
+    /// Look up the text for `TranslatedString` handles
+    #[command(long_about = "Look up the text for TranslatedString handles
+
+Reads every .loca file for the language from the given PAKs (or directories
+of PAKs) and prints each handle's text along with the file that defines it.
+Without --pak, the game's Localization folder is used (see 'config set bg3-path').
+Handles may include a ';version' suffix. With no handles on the command line,
+they are read from stdin, one per line.
+
+Where each handle lives is cached next to the search indexes, so repeated
+lookups against unchanged PAKs only decompress the files they need.
+
+Examples:
+  maclarian loca resolve h0a1b2c3dg4e5fg4a6bg8c7dg9e0f1a2b3c4d
+  maclarian loca resolve h0a1b2c3dg4e5fg4a6bg8c7dg9e0f1a2b3c4d --pak ~/BG3/Data/Localization/English.pak
+  maclarian loca resolve --language French < handles.txt")]
+    Resolve {
+        /// Handles to resolve (reads stdin if omitted)
+        handles: Vec<String>,
+
+        /// PAK files, or directories of PAKs, containing loca files (repeatable)
         #[arg(short, long)]
-        quiet: bool,
+        pak: Vec<PathBuf>,
+
+        /// Language folder to read
+        #[arg(short, long, default_value = "English")]
+        language: String,
+
+        /// Ignore and don't update the handle cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+
+    /// Export every loca file of a language from PAKs as XML
+    #[command(name = "extract-language")]
+    #[command(long_about = "Export every loca file of a language from PAKs as XML
+
+Finds the language's .loca files in the given PAKs (or directories of PAKs,
+including the game's Data folder), converts them to XML and writes them under
+the output directory, keeping their paths inside the PAK. Languages can be
+given by name or code (German, de, pt-BR). Without --pak, the game's
+Localization folder is used (see 'config set bg3-path').
+
+Examples:
+  maclarian loca extract-language --language German -o ./loca/
+  maclarian loca extract-language --pak ~/BG3/Data --language de -o ./loca/
+  maclarian loca extract-language --pak Localization.pak --all-languages -o ./loca/")]
+    ExtractLanguage {
+        /// PAK files, or directories of PAKs, containing loca files (repeatable)
+        #[arg(short, long)]
+        pak: Vec<PathBuf>,
+
+        /// Language to export, by name or code
+        #[arg(
+            short,
+            long,
+            required_unless_present = "all_languages",
+            conflicts_with = "all_languages"
+        )]
+        language: Option<String>,
+
+        /// Export every language found
+        #[arg(long)]
+        all_languages: bool,
+
+        /// Output directory
+        #[arg(short, long)]
+        output: PathBuf,
     },
 }
 
@@ -348,15 +646,424 @@ pub enum TextureCommands {
     /// Show info about a DDS texture file
     #[command(long_about = "Show info about a DDS texture file
 
-Displays metadata about a DDS texture including dimensions, format,
-mip levels, and compression type.
+Displays every field of the DDS header, including the pixel format, the
+DX10 extension header and the color space (sRGB or linear, which only DX10
+formats record), and warns about common problems: a pitch that
+doesn't match the format, more mip levels than the dimensions allow,
+block-compressed dimensions that aren't multiples of 4, missing pixel
+data, and BC7 textures without a DX10 header.
 
 Examples:
-  maclarian texture info albedo.dds")]
+  maclarian texture info albedo.dds
+  maclarian texture info albedo.dds --json")]
     Info {
         /// DDS file to analyze
         path: PathBuf,
     },
+
+    /// Convert a DDS texture to PNG, or PNG to DDS
+    #[command(long_about = "Convert a DDS texture to PNG, or PNG to DDS
+
+The direction follows the source's extension. The color space is kept across
+the conversion: a DDS with an sRGB DX10 format gives a PNG with an sRGB chunk,
+and a PNG with an sRGB (or linear gAMA) chunk gives a DDS with the matching
+DX10 format. BG3 expects base color textures as sRGB and normal and physical
+maps as linear; use --srgb or --linear to set it when the source doesn't say.
+Without either, an untagged PNG gets a legacy DDS header.
+
+Examples:
+  maclarian texture convert albedo.dds
+  maclarian texture convert albedo.png -o albedo.dds --srgb
+  maclarian texture convert normal.png --texture-format bc5 --linear")]
+    Convert {
+        /// DDS or PNG file to convert
+        source: PathBuf,
+
+        /// Output file (default: next to the source)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// DDS compression format when converting to DDS (bc1, bc2, bc3, bc5, rgba)
+        #[arg(long, default_value = "bc3")]
+        texture_format: String,
+
+        /// Mark the output as sRGB (base color textures)
+        #[arg(long, conflicts_with = "linear")]
+        srgb: bool,
+
+        /// Mark the output as linear (normal, physical and mask textures)
+        #[arg(long)]
+        linear: bool,
+    },
+
+    /// Convert a folder of DDS textures to PNG, or PNG to DDS
+    #[command(long_about = "Convert a folder of DDS textures to PNG, or PNG to DDS
+
+Walks the directory recursively and converts files in parallel. Output keeps
+the folder layout, under the output directory or next to each file. A bad file
+doesn't stop the batch: failures are listed in the summary at the end.
+Color spaces are kept as with `texture convert`; --srgb or --linear applies
+one to every DDS written.
+
+Examples:
+  maclarian texture batch-convert ./Textures
+  maclarian texture batch-convert ./Textures -o ./Png --skip-existing
+  maclarian texture batch-convert ./Edited --to dds --texture-format bc1
+  maclarian texture batch-convert ./Albedo --to dds --srgb")]
+    BatchConvert {
+        /// Directory containing textures
+        source: PathBuf,
+
+        /// Output directory (default: next to each file)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Format to convert to; files in the other format are converted
+        #[arg(long, value_enum, default_value_t)]
+        to: TextureTargetArg,
+
+        /// DDS compression format when converting to DDS (bc1, bc2, bc3, bc5, rgba)
+        #[arg(long, default_value = "bc3")]
+        texture_format: String,
+
+        /// Skip files whose output is at least as new as the source
+        #[arg(long)]
+        skip_existing: bool,
+
+        /// Mark DDS output as sRGB (base color textures)
+        #[arg(long, conflicts_with = "linear")]
+        srgb: bool,
+
+        /// Mark DDS output as linear (normal, physical and mask textures)
+        #[arg(long)]
+        linear: bool,
+    },
+}
+
+/// Target format for `texture batch-convert`
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum TextureTargetArg {
+    /// DDS to PNG
+    #[default]
+    Png,
+    /// PNG to DDS
+    Dds,
+}
+
+/// Dialog resource commands
+#[derive(Subcommand)]
+pub enum DialogCommands {
+    /// Export a dialog as a Markdown tree or Graphviz graph
+    #[command(long_about = "Export a dialog as a Markdown tree or Graphviz graph
+
+Reads a dialog resource (LSF, LSX or LSJ) and writes its node graph: each
+node's type, speaker, text, check/set flags and links. With --loca, text
+handles are resolved through the given .loca files or PAKs of loca files;
+otherwise the text stored in the dialog (or the handle) is shown.
+
+Markdown output is an indented tree from each root node; nodes reached a
+second time are shown as references. DOT output can be rendered with
+Graphviz (dot -Tsvg).
+
+Examples:
+  maclarian dialog export CAMP_Shadowheart.lsf --loca english.loca
+  maclarian dialog export CAMP_Shadowheart.lsf --loca ~/BG3/Data/Localization/English.pak
+  maclarian dialog export CAMP_Shadowheart.lsf --format dot -o shadowheart.dot")]
+    Export {
+        /// Dialog file (.lsf, .lsx or .lsj)
+        file: PathBuf,
+
+        /// .loca file or PAK of loca files to resolve text with (repeatable)
+        #[arg(long)]
+        loca: Vec<PathBuf>,
+
+        /// Language folder to read from loca PAKs
+        #[arg(short, long, default_value = "English")]
+        language: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t)]
+        format: DialogFormatArg,
+
+        /// Output file [default: stdout]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Output format for `dialog export`
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum DialogFormatArg {
+    /// Indented Markdown tree
+    #[default]
+    #[value(alias = "markdown")]
+    Md,
+    /// Graphviz DOT graph
+    Dot,
+}
+
+/// WEM audio commands
+#[derive(Subcommand)]
+pub enum AudioCommands {
+    /// Convert a WEM file to Ogg Vorbis or WAV
+    #[command(long_about = "Convert a WEM file to Ogg Vorbis or WAV
+
+Wwise Vorbis (the codec most BG3 audio uses) is rebuilt as standard Ogg Vorbis;
+PCM is written as WAV. Files using other codecs fail with their codec id.
+
+Wwise Vorbis files reference shared codebooks. Pass the library with
+--codebooks, or place packed_codebooks_aoTuV_603.bin next to config.toml or
+the maclarian executable.
+
+Examples:
+  maclarian audio convert 12345678.wem
+  maclarian audio convert 12345678.wem -o voice.ogg
+  maclarian audio convert 12345678.wem --codebooks packed_codebooks_aoTuV_603.bin")]
+    Convert {
+        /// WEM file to convert
+        source: PathBuf,
+
+        /// Output file or directory (default: next to the source)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Packed codebook library for Wwise Vorbis
+        #[arg(long)]
+        codebooks: Option<PathBuf>,
+
+        /// Read codebooks from the file itself instead of a library
+        #[arg(long, conflicts_with = "codebooks")]
+        inline_codebooks: bool,
+    },
+
+    /// Convert every WEM file in a directory
+    #[command(long_about = "Convert every WEM file in a directory
+
+Walks the directory recursively. Output keeps the folder layout, under the
+output directory or next to each file. Files with unsupported codecs are
+skipped and counted by codec id.
+
+Examples:
+  maclarian audio batch ./Sounds
+  maclarian audio batch ./Sounds -o ./Converted")]
+    Batch {
+        /// Directory containing .wem files
+        source: PathBuf,
+
+        /// Output directory (default: next to each file)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Packed codebook library for Wwise Vorbis
+        #[arg(long)]
+        codebooks: Option<PathBuf>,
+
+        /// Read codebooks from the files themselves instead of a library
+        #[arg(long, conflicts_with = "codebooks")]
+        inline_codebooks: bool,
+    },
+}
+
+/// Savegame (`.lsv`) commands
+#[derive(Subcommand)]
+pub enum SaveCommands {
+    /// Show the save name, game version and active mods
+    #[command(long_about = "Show the save name, game version and active mods
+
+Reads meta.lsf from the save and prints the save name, the game version it
+was written with, and the mods that were active, in load order.
+
+Examples:
+  maclarian save info QuickSave_12.lsv
+  maclarian save info QuickSave_12.lsv --json")]
+    Info {
+        /// Save file
+        path: PathBuf,
+    },
+
+    /// List the files in a save
+    List {
+        /// Save file
+        path: PathBuf,
+    },
+
+    /// Extract every file in a save
+    #[command(long_about = "Extract every file in a save
+
+Examples:
+  maclarian save extract QuickSave_12.lsv ./save
+  maclarian convert ./save/Globals.lsf Globals.lsx")]
+    Extract {
+        /// Save file
+        path: PathBuf,
+
+        /// Output directory (defaults to output-dir from the config)
+        destination: Option<PathBuf>,
+    },
+}
+
+/// Stats `.txt` file commands
+#[derive(Subcommand)]
+pub enum StatsCommands {
+    /// Check a stats file for structural problems
+    #[command(long_about = "Check a stats file for structural problems
+
+Parses a Stats/Generated .txt file (Object.txt, Armor.txt, TreasureTable.txt, ...)
+and reports duplicate entries, data lines outside an entry, malformed subtable
+headers, and lines the parser doesn't recognize. Exits non-zero if any errors
+(not warnings) are found.
+
+Examples:
+  maclarian stats lint Object.txt
+  maclarian stats lint \"Public/MyMod/Stats/Generated/*.txt\"")]
+    Lint {
+        /// Stats file(s) or wildcard pattern
+        #[arg(required = true)]
+        path: Vec<PathBuf>,
+    },
+
+    /// Show which treasure tables drop an item, or what a table drops
+    #[command(
+        long_about = "Show which treasure tables drop an item, or what a table drops
+
+Reads the stats .txt files under Stats folders in the given PAKs, directories
+of PAKs, or extracted mod folders, and follows T_<table> references between
+treasure tables. Without --index, the PAKs in the game's Data folder are read
+(see 'config set bg3-path'). Later sources override earlier ones; a table
+with CanMerge 1 adds its subtables instead.
+
+With --item, every chain of tables leading to the item is printed from the
+outermost table down, each step showing the subtable header, how many items
+it drops and the odds of picking the next step. With --table, the table's
+contents are printed as a tree with nested tables resolved. Reference cycles
+are cut where they close and reported as warnings.
+
+Examples:
+  maclarian stats treasure --item I_MyItem
+  maclarian stats treasure --index Shared.pak --index Gustav.pak --item POT_Healing
+  maclarian stats treasure --index ~/Mods/MyMod --table TUT_Chest_Potions"
+    )]
+    #[command(group(clap::ArgGroup::new("query").required(true).args(["item", "table"])))]
+    Treasure {
+        /// PAK files, directories of PAKs, or extracted mod folders (repeatable)
+        #[arg(long)]
+        index: Vec<PathBuf>,
+
+        /// Item to find (I_<name> or <name>)
+        #[arg(long)]
+        item: Option<String>,
+
+        /// Table to list the contents of
+        #[arg(long)]
+        table: Option<String>,
+    },
+}
+
+/// PAK content search commands
+#[derive(Subcommand)]
+pub enum SearchCommands {
+    /// Print the lines of PAK text files that match a pattern
+    #[command(long_about = "Print the lines of PAK text files that match a pattern
+
+Streams the searchable files of each PAK in batches, so no index is needed.
+Each match is printed as pak:path:line: text. LSF files are searched by their
+node names and string values, one per line.
+
+Several PAKs, wildcards and directories can be searched at once. Directories
+are searched for .pak files in game load order, so passing a game install
+searches the whole game. PAKs that can't be read are reported and skipped.
+
+Examples:
+  maclarian search grep Shared.pak \"Shadowheart\"
+  maclarian search grep Shared.pak Gustav.pak \"shadowheart\" -i
+  maclarian search grep \"*.pak\" \"S_GLO_\\w+\" --regex --type lsx,lsf
+  maclarian search grep ~/BG3/Data ./Mods \"ORI_Karlach\"")]
+    Grep {
+        /// PAK files, wildcard patterns or directories of PAKs
+        #[arg(required = true)]
+        paks: Vec<PathBuf>,
+
+        /// Text (or regular expression with --regex) to find
+        pattern: String,
+
+        /// Treat the pattern as a regular expression
+        #[arg(short, long)]
+        regex: bool,
+
+        /// Ignore case when matching
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// File types to search (lsx, lsf, lsb, lsj, xml, json); all by default
+        #[arg(short = 't', long = "type", value_delimiter = ',')]
+        types: Vec<String>,
+    },
+}
+
+/// Settings file commands
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Show the current settings and where they are loaded from
+    Show,
+
+    /// Set a value in the settings file
+    #[command(long_about = "Set a value in the settings file
+
+Keys:
+  bg3-path       BG3 Data directory (used when --bg3-path is omitted)
+  index-dir      Directory for search indexes
+  output-dir     Default destination for extracted and converted files (GUI)
+  compression    Default PAK compression (lz4, zlib, none)
+  texture-roots  Comma-separated list of extra texture directories
+  log-level      GUI log level (error, warn, info, debug, trace)
+
+An empty value clears the setting.
+
+Examples:
+  maclarian config set bg3-path \"~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/Baldur's Gate 3.app/Contents/Data\"
+  maclarian config set compression zlib
+  maclarian config set texture-roots \"~/BG3/Textures,~/BG3/VT\"")]
+    Set {
+        /// Setting name
+        #[arg(value_parser = clap::builder::PossibleValuesParser::new(crate::config::CONFIG_KEYS))]
+        key: String,
+
+        /// New value (empty to clear)
+        value: String,
+    },
+}
+
+/// UUID commands
+#[derive(Subcommand)]
+pub enum UuidCommands {
+    /// Show a GUID in all of its text forms
+    #[command(long_about = "Show a GUID in all of its text forms
+
+Accepts hyphenated, bare (no hyphens), braced or handle (h...g...) input and
+prints the hyphenated, bare, byte-swapped and handle forms. The byte-swapped
+form is how LSF files store GUIDs when bswap_guids is set; swapping is its own
+inverse, so a swapped value converts back to the original.
+
+Examples:
+  maclarian uuid convert 12345678-9abc-def0-1122-334455667788
+  maclarian uuid convert 123456789abcdef01122334455667788 --json")]
+    Convert {
+        /// GUID to convert
+        value: String,
+    },
+}
+
+/// Text format for `uuid` output
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum UuidFormatArg {
+    /// Hyphenated GUID (`FixedString` UUIDs in LSX)
+    #[default]
+    Standard,
+    /// 32 hex digits without hyphens
+    Compact,
+    /// `TranslatedString` handle (h...g...)
+    #[value(alias = "larian")]
+    Handle,
 }
 
 /// Mod utility commands
@@ -368,18 +1075,31 @@ pub enum ModCommands {
 Checks that a mod has the correct directory structure, valid meta.lsx,
 and that PAK files are not corrupted. Supports glob patterns for batch validation.
 
+In mod folders, .lsx files with an .lsf of the same name are compared after
+conversion, and a warning is shown when they differ, since the game only
+reads the .lsf. Formatting-only differences are ignored. --sync regenerates
+the .lsf from the .lsx, or the .lsx from the .lsf with --prefer-lsf.
+
+File paths are also checked the way 'pak lint' checks PAK entries, so names
+that would break on Windows are caught before packing.
+
 Examples:
   maclarian mods validate MyMod.pak
   maclarian mods validate ./MyModFolder/
+  maclarian mods validate ./MyModFolder/ --sync
   maclarian mods validate \"*.pak\"")]
     Validate {
         /// Path(s) to mod directory or PAK file(s) - supports glob patterns
         #[arg(required = true)]
         source: Vec<PathBuf>,
 
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
+        /// Regenerate the .lsf of each .lsx/.lsf pair that differs
+        #[arg(long)]
+        sync: bool,
+
+        /// With --sync, regenerate the .lsx from the .lsf instead
+        #[arg(long, requires = "sync")]
+        prefer_lsf: bool,
     },
 
     /// Package mod for `BaldursModManager` (generates info.json alongside PAK)
@@ -394,25 +1114,115 @@ Output structure:
     ├── ModName.pak
     └── info.json
 
+With --extended, info.json also lists the mod's dependencies from meta.lsx,
+the directories inside the PAK, and a SHA-256 hash. Mod managers that only
+understand the basic schema ignore the extra fields.
+
 Examples:
   maclarian mods package ./MyMod ./dist/
   maclarian mods package MyMod.pak ./dist/ -c zip
-  maclarian mods package ./MyMod ./dist/ -c 7z"
+  maclarian mods package ./MyMod ./dist/ -c 7z
+  maclarian mods package MyMod.pak ./dist/ --extended"
     )]
     Package {
         /// Path to .pak file or mod directory
         source: PathBuf,
 
-        /// Output directory (creates `<destination>/ModName/ModName.pak` + `info.json`)
-        destination: PathBuf,
+        /// Output directory (creates `<destination>/ModName/ModName.pak` + `info.json`);
+        /// defaults to output-dir from the config
+        destination: Option<PathBuf>,
 
         /// Compress output as zip or 7z
         #[arg(short, long, value_parser = ["zip", "7z"])]
         compress: Option<String>,
 
-        /// Suppress progress output
+        /// Include dependencies, PAK directories and SHA-256 in info.json
+        #[arg(long)]
+        extended: bool,
+    },
+
+    /// Generate info.json for a mod without packaging it
+    #[command(long_about = "Generate info.json for a mod without packaging it
+
+Reads meta.lsx from a .pak file (or a mod directory containing one) and writes
+info.json for BaldursModManager. Prints to stdout unless an output file is given.
+
+With --extended, the mod's dependencies from meta.lsx, the directories inside
+the PAK, and a SHA-256 hash are included as well.
+
+Examples:
+  maclarian mods info-json MyMod.pak
+  maclarian mods info-json MyMod.pak -o info.json --extended")]
+    InfoJson {
+        /// Path to .pak file or mod directory
+        source: PathBuf,
+
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Include dependencies, PAK directories and SHA-256
+        #[arg(long)]
+        extended: bool,
+    },
+
+    /// Create a new mod from a built-in template
+    #[command(long_about = "Create a new mod from a built-in template
+
+Scaffolds <output>/<Folder>/ with Mods/<Folder>/meta.lsx (fresh UUID), the
+template's Public/<Folder>/ files and a Localization/English stub. GUIDs and
+localization handles inside the template are generated fresh for each mod.
+With --script-extender, a Script Extender config and Lua bootstrap are added.
+
+Templates:
+  equipment     Armor stats, root template, treasure table entry
+  spell         Projectile spell with localized name and description
+  class-blank   Class description and level 1 progression
+
+Examples:
+  maclarian mods new \"My Armor\" --template equipment -a \"Author\"
+  maclarian mods new \"My Class\" -t class-blank -a \"Author\" -o ~/Mods --script-extender
+  maclarian mods new --list-templates")]
+    New {
+        /// Mod display name
+        #[arg(required_unless_present = "list_templates")]
+        name: Option<String>,
+
+        /// Template to start from
+        #[arg(short, long, required_unless_present = "list_templates")]
+        template: Option<String>,
+
+        /// Directory to create the mod folder in
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+
+        /// Author name
+        #[arg(short, long, default_value = "")]
+        author: String,
+
+        /// Mod description
+        #[arg(short, long, default_value = "")]
+        description: String,
+
+        /// Folder name (defaults to sanitized mod name)
+        #[arg(short, long)]
+        folder: Option<String>,
+
+        /// UUID (auto-generated if not provided)
         #[arg(short, long)]
-        quiet: bool,
+        uuid: Option<String>,
+
+        /// Version in format "major.minor.patch.build"
+        #[arg(long, default_value = "1.0.0.0")]
+        version: String,
+
+        /// Add a Script Extender config and Lua bootstrap
+        #[arg(long)]
+        script_extender: bool,
+
+        /// List the available templates and exit
+        #[arg(long)]
+        list_templates: bool,
     },
 
     /// Generate meta.lsx metadata file for a mod
@@ -424,7 +1234,7 @@ and mod info. The file is placed in the correct location within the mod structur
 Examples:
   maclarian mods meta ./MyMod -n \"My Cool Mod\" -a \"Author Name\"
   maclarian mods meta ./MyMod -n \"My Mod\" -a \"Author\" -d \"A description\"
-  maclarian mods meta ./MyMod -n \"My Mod\" -a \"Author\" -v 1.2.0.0")]
+  maclarian mods meta ./MyMod -n \"My Mod\" -a \"Author\" --version 1.2.0.0")]
     Meta {
         /// Mod source directory (creates `<source>/Mods/<Folder>/meta.lsx`)
         source: PathBuf,
@@ -450,10 +1260,55 @@ Examples:
         uuid: Option<String>,
 
         /// Version in format "major.minor.patch.build" (default: 1.0.0.0)
-        #[arg(short, long, default_value = "1.0.0.0")]
+        #[arg(long, default_value = "1.0.0.0")]
         version: String,
     },
 
+    /// Show the decoded version from a meta.lsx file
+    #[command(long_about = "Show the decoded version from a meta.lsx file
+
+BG3 stores mod versions as a packed Version64 integer (36028797018963968 = 1.0.0.0).
+Prints the version in major.minor.patch.build form along with the raw value.
+
+Examples:
+  maclarian mods version ./MyMod/Mods/MyMod/meta.lsx")]
+    Version {
+        /// Path to meta.lsx
+        path: PathBuf,
+    },
+
+    /// Increment the version in a meta.lsx file
+    #[command(long_about = "Increment the version in a meta.lsx file
+
+Bumps the ModuleInfo Version64 and the PublishVersion node to the same value.
+Lower components are reset (bumping minor turns 1.2.3.4 into 1.3.0.0), except
+for --build which only increments the build number.
+
+Examples:
+  maclarian mods bump ./MyMod/Mods/MyMod/meta.lsx --patch
+  maclarian mods bump ./MyMod/Mods/MyMod/meta.lsx --minor")]
+    #[command(group(clap::ArgGroup::new("part").required(true).args(["major", "minor", "patch", "build"])))]
+    Bump {
+        /// Path to meta.lsx
+        path: PathBuf,
+
+        /// Increment the major version
+        #[arg(long)]
+        major: bool,
+
+        /// Increment the minor version
+        #[arg(long)]
+        minor: bool,
+
+        /// Increment the patch version
+        #[arg(long)]
+        patch: bool,
+
+        /// Increment the build number
+        #[arg(long)]
+        build: bool,
+    },
+
     /// Find files modified by multiple mods (potential conflicts)
     #[command(
         long_about = "Find files modified by multiple mods (potential conflicts)
@@ -470,9 +1325,251 @@ Examples:
         /// PAK files or mod directories to compare (2 or more)
         #[arg(required = true, num_args = 2..)]
         sources: Vec<PathBuf>,
+    },
+
+    /// Compare a mod source directory against a built PAK
+    #[command(long_about = "Compare a mod source directory against a built PAK
+
+Lists files that exist on only one side and files whose contents differ.
+Source .lsx/.lsj files are converted in memory and compared against the
+matching .lsf in the PAK (and .xml against .loca), so a converted build
+still compares clean. Exits with code 5 if any difference is found.
+
+Examples:
+  maclarian mods diff --source ./MyMod/ --pak MyMod.pak
+  maclarian mods diff --source ./MyMod/ --pak MyMod.pak --json")]
+    Diff {
+        /// Mod source directory
+        #[arg(short, long)]
+        source: PathBuf,
+
+        /// Built PAK file
+        #[arg(short, long)]
+        pak: PathBuf,
+    },
+
+    /// Show what an asset references, or what references it
+    #[command(long_about = "Show what an asset references, or what references it
+
+Builds a reference graph from the .lsx/.lsf files in a mod directory (and any
+PAKs given with --pak) and prints the transitive dependencies of an asset id,
+nearest first. References come from attributes such as ParentTemplateId,
+VisualTemplate, MaterialID, ColorPreset and Icon. Ids that nothing in the
+source set defines are marked unresolved; add the game's PAKs to resolve them.
+Reference cycles are reported once and do not loop.
+
+With --reverse, prints the assets that depend on the id instead. --dot writes
+the same subgraph as a Graphviz file.
+
+Examples:
+  maclarian mods deps 1c3c9c74-34a1-4685-989e-410dc080be6f --mod ./MyMod/
+  maclarian mods deps <uuid> --mod ./MyMod/ --pak ~/BG3/Data/Shared.pak
+  maclarian mods deps <uuid> --mod ./MyMod/ --reverse --dot deps.dot")]
+    Deps {
+        /// Asset id (UUID, or a `FixedString` key such as an icon name)
+        id: String,
+
+        /// Mod source directory
+        #[arg(short, long = "mod", value_name = "DIR")]
+        mod_dir: PathBuf,
+
+        /// PAK files, or directories of PAKs, to include (repeatable)
+        #[arg(short, long)]
+        pak: Vec<PathBuf>,
+
+        /// Show dependents instead of dependencies
+        #[arg(short, long)]
+        reverse: bool,
+
+        /// Write the subgraph as Graphviz DOT
+        #[arg(long, value_name = "FILE")]
+        dot: Option<PathBuf>,
+    },
+
+    /// Check that every referenced localization handle has text
+    #[command(long_about = "Check that every referenced localization handle has text
+
+Collects the TranslatedString handles referenced by the .lsx/.lsf/.lsj files
+(root templates, dialogs, ...), the stats .txt files and the Osiris and Script
+Extender scripts (.txt, .khn, .lua) in a mod directory, and checks them against
+the .loca and .xml files under Localization/<language>/. Prints a matrix of
+handles missing (or empty) in each language, followed by orphan entries that
+are defined but never referenced.
+
+With --strict, exits with code 5 if the primary language (--language) has
+any gaps. With --handle, lists the files and lines referencing that handle
+instead.
+
+Examples:
+  maclarian mods loca-coverage --source ./MyMod/
+  maclarian mods loca-coverage --source ./MyMod/ --strict
+  maclarian mods loca-coverage --source ./MyMod/ --language French --json
+  maclarian mods loca-coverage --source ./MyMod/ --handle h1a2b3c4dg...")]
+    LocaCoverage {
+        /// Mod source directory
+        #[arg(short, long)]
+        source: PathBuf,
+
+        /// Primary language
+        #[arg(short, long, default_value = "English")]
+        language: String,
+
+        /// Exit with an error if the primary language has gaps
+        #[arg(long)]
+        strict: bool,
+
+        /// List where this handle is referenced instead of checking coverage
+        #[arg(long, conflicts_with = "strict")]
+        handle: Option<String>,
+    },
+
+    /// Install a mod PAK into the game's Mods folder and enable it
+    #[command(
+        long_about = "Install a mod PAK into the game's Mods folder and enable it
+
+Copies the PAK into the Mods folder of the detected BG3 install (or --mods-dir)
+and adds it to PlayerProfiles/Public/modsettings.lsx next to that folder, using
+the name, UUID and version from the meta.lsx inside the PAK. A PAK of the same
+name already in the Mods folder is replaced. With --symlink the PAK is linked
+instead of copied, so rebuilding it needs no redeploy.
+
+Refuses to run if the Mods folder can't be found and --mods-dir isn't given.
+
+Examples:
+  maclarian mods deploy MyMod.pak
+  maclarian mods deploy MyMod.pak --symlink
+  maclarian mods deploy MyMod.pak --dry-run
+  maclarian mods deploy MyMod.pak --mods-dir \"~/Documents/Larian Studios/Baldur's Gate 3/Mods\""
+    )]
+    Deploy {
+        /// Mod PAK to install
+        pak: PathBuf,
+
+        /// Symlink the PAK instead of copying it
+        #[arg(long)]
+        symlink: bool,
+
+        /// Mods folder to install into [default: detected from the game install]
+        #[arg(long)]
+        mods_dir: Option<PathBuf>,
+
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Remove a deployed mod from the Mods folder and modsettings.lsx
+    #[command(
+        long_about = "Remove a deployed mod from the Mods folder and modsettings.lsx
+
+Takes the mod's UUID or its PAK file name. Deletes the PAK (or link) from the
+Mods folder and removes the mod's entry from modsettings.lsx.
+
+Examples:
+  maclarian mods undeploy MyMod.pak
+  maclarian mods undeploy 11111111-2222-3333-4444-555555555555
+  maclarian mods undeploy MyMod.pak --dry-run"
+    )]
+    Undeploy {
+        /// Mod UUID or PAK file name
+        #[arg(value_name = "UUID_OR_PAK")]
+        mod_ref: String,
+
+        /// Mods folder to remove from [default: detected from the game install]
+        #[arg(long)]
+        mods_dir: Option<PathBuf>,
+
+        /// Print what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// List and edit `VisualBank`, `MaterialBank` and `CharacterVisualBank` resources
+    Visuals {
+        /// Visual bank subcommand to execute.
+        #[command(subcommand)]
+        command: VisualsCommands,
+    },
+}
+
+/// `VisualBank` and `MaterialBank` commands
+#[derive(Subcommand)]
+pub enum VisualsCommands {
+    /// List the visual, material and character visual resources in a file
+    #[command(
+        long_about = "List the visual, material and character visual resources in a file
+
+Reads an LSF/LSX/LSJ file (usually Public/<Folder>/Content/**/_merged.lsf) and
+prints each VisualBank resource with its slot, source GR2 and mesh objects,
+each MaterialBank resource with its textures, and each CharacterVisualBank
+resource with the visual worn in each slot.
 
-        /// Suppress progress output
+Examples:
+  maclarian mods visuals list _merged.lsf
+  maclarian mods visuals list _merged.lsf --json"
+    )]
+    List {
+        /// Bank file (.lsf, .lsx, .lsj)
+        file: PathBuf,
+    },
+
+    /// Set the equipment slot of a visual, or the visual a character wears in a slot
+    #[command(
+        long_about = "Set the equipment slot of a visual, or the visual a character wears in a slot
+
+RESOURCE is a resource ID or name. Without --visual, sets the Slot of that
+VisualBank resource. With --visual, RESOURCE is a CharacterVisualBank resource
+and its SLOT entry is pointed at the given visual resource (the entry is
+added if missing). Writes the file in place unless --output is given.
+
+Examples:
+  maclarian mods visuals set-slot _merged.lsf HUM_M_ARM_MyRobe_Body Cloak
+  maclarian mods visuals set-slot _merged.lsf HUM_M_Guard Body --visual <uuid>"
+    )]
+    SetSlot {
+        /// Bank file (.lsf, .lsx, .lsj)
+        file: PathBuf,
+
+        /// Resource ID or name
+        resource: String,
+
+        /// Slot name (e.g. Body, Boots, Hair)
+        slot: String,
+
+        /// Visual resource to wear in the slot (RESOURCE is then a character visual)
+        #[arg(long, value_name = "ID")]
+        visual: Option<String>,
+
+        /// Write the result here instead of changing the file in place
         #[arg(short, long)]
-        quiet: bool,
+        output: Option<PathBuf>,
+    },
+
+    /// Set the material a mesh object of a visual is drawn with
+    #[command(long_about = "Set the material a mesh object of a visual is drawn with
+
+RESOURCE is a VisualBank resource ID or name and OBJECT the ObjectID of one of
+its Objects entries. Every LOD of the object is changed. Writes the file in
+place unless --output is given.
+
+Examples:
+  maclarian mods visuals set-material _merged.lsf HUM_M_ARM_MyRobe_Body \\
+      HUM_M_ARM_MyRobe_Body.Robe_Mesh <material-uuid>")]
+    SetMaterial {
+        /// Bank file (.lsf, .lsx, .lsj)
+        file: PathBuf,
+
+        /// Visual resource ID or name
+        resource: String,
+
+        /// Mesh object ID
+        object: String,
+
+        /// `MaterialBank` resource ID
+        material: String,
+
+        /// Write the result here instead of changing the file in place
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 }
@@ -0,0 +1,83 @@
+//! CLI commands for dialog resources
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use super::definitions::DialogFormatArg;
+use crate::cli::output::OutputContext;
+use crate::formats::dialog::{DialogExportFormat, export_dialog, read_dialog};
+use crate::formats::loca::LocaResolver;
+
+/// Export a dialog as Markdown or DOT
+///
+/// Writes to `output`, or stdout if not given. Text handles are resolved
+/// through `loca` (`.loca` files or PAKs of loca files) when given.
+///
+/// # Errors
+/// Returns an error if the dialog or a loca source cannot be read, or the
+/// output cannot be written.
+pub fn export(
+    file: &Path,
+    loca: &[PathBuf],
+    language: &str,
+    format: DialogFormatArg,
+    output: Option<&Path>,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let dialog =
+        read_dialog(file).with_context(|| format!("Failed to read dialog {}", file.display()))?;
+
+    let resolver = if loca.is_empty() {
+        None
+    } else {
+        Some(load_loca(loca, language)?)
+    };
+
+    let format = match format {
+        DialogFormatArg::Md => DialogExportFormat::Markdown,
+        DialogFormatArg::Dot => DialogExportFormat::Dot,
+    };
+    let rendered = export_dialog(&dialog, format, resolver.as_ref());
+
+    match output {
+        Some(path) => {
+            std::fs::write(path, rendered)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            if !ctx.quiet {
+                println!(
+                    "Exported {} nodes to {}",
+                    dialog.node_count(),
+                    path.display()
+                );
+            }
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Read `.loca` files and loca PAKs into one resolver
+fn load_loca(sources: &[PathBuf], language: &str) -> anyhow::Result<LocaResolver> {
+    let mut resolver = LocaResolver::new(language);
+    for source in sources {
+        let is_pak = source
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"));
+        if is_pak {
+            resolver
+                .add_pak(source)
+                .with_context(|| format!("Failed to read {}", source.display()))?;
+        } else {
+            let data = std::fs::read(source)
+                .with_context(|| format!("Failed to read {}", source.display()))?;
+            resolver
+                .add_bytes(&data, None, &source.display().to_string())
+                .with_context(|| format!("Failed to parse {}", source.display()))?;
+        }
+    }
+    if resolver.is_empty() {
+        anyhow::bail!("No {language} loca entries found in the given files");
+    }
+    Ok(resolver)
+}
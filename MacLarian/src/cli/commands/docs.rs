@@ -0,0 +1,56 @@
+//! Shell completion and man page generation
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::Command;
+use clap_complete::Shell;
+
+/// Write a completion script for `shell` to stdout
+pub fn completions(shell: Shell) {
+    let mut cmd = crate::cli::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
+}
+
+/// Write man pages for the command tree into `dir`
+///
+/// # Errors
+/// Returns an error if the directory or a page cannot be written.
+pub fn manpages(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let mut cmd = crate::cli::command();
+    cmd.build();
+    let count = write_manpages(&cmd, dir, "")?;
+
+    println!("Wrote {count} man pages to {}", dir.display());
+    Ok(())
+}
+
+/// Render `cmd` and its subcommands recursively, returning the number of pages written
+fn write_manpages(cmd: &Command, dir: &Path, prefix: &str) -> Result<usize> {
+    let name = if prefix.is_empty() {
+        cmd.get_name().to_string()
+    } else {
+        format!("{prefix}-{}", cmd.get_name())
+    };
+
+    // Man pages are named after the full command path (maclarian-pak-extract)
+    let path = dir.join(format!("{name}.1"));
+    let mut buffer = Vec::new();
+    clap_mangen::Man::new(cmd.clone())
+        .title(name.to_uppercase())
+        .render(&mut buffer)?;
+    std::fs::write(&path, buffer).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    let mut count = 1;
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+        if sub.get_name() == "help" {
+            continue;
+        }
+        count += write_manpages(sub, dir, &name)?;
+    }
+    Ok(count)
+}
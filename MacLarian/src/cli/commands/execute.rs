@@ -2,36 +2,92 @@
 
 use super::Commands;
 use super::definitions::{
-    Gr2Commands, LocaCommands, ModCommands, PakCommands, TextureCommands, VirtualTextureCommands,
+    AudioCommands, ConfigCommands, DialogCommands, Gr2Commands, LocaCommands, ModCommands,
+    PakCommands, SaveCommands, SearchCommands, StatsCommands, TextureCommands, UuidCommands,
+    VirtualTextureCommands, VisualsCommands,
 };
-use super::{convert, gr2, loca, mod_cmd, pak, texture, virtual_texture};
+use super::{
+    audio, config, convert, dialog, docs, gr2, loca, mod_cmd, pak, save, search, stats, texture,
+    uuid_cmd, virtual_texture,
+};
+use crate::cli::output::OutputContext;
+use crate::converter::PrettyOptions;
+use crate::mods::{NewModOptions, PairSide, VersionPart};
+use crate::pak::{ExtractionPipeline, FilterSet, Gr2ExtractionOptions};
 
 impl Commands {
     /// Execute the selected command.
     ///
     /// # Errors
     /// Returns an error if the underlying command fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
-            Commands::Pak { command } => command.execute(),
+            Commands::Pak { command } => command.execute(ctx),
             Commands::Convert {
                 source,
                 destination,
+                from_pak,
+                filter,
+                input_format,
                 output_format,
                 texture_format,
-                quiet,
-            } => convert::execute(
-                source,
-                destination,
-                output_format.as_deref(),
-                texture_format,
-                *quiet,
-            ),
-            Commands::Gr2 { command } => command.execute(),
-            Commands::VirtualTexture { command } => command.execute(),
-            Commands::Mods { command } => command.execute(),
-            Commands::Loca { command } => command.execute(),
-            Commands::Texture { command } => command.execute(),
+                pretty,
+                indent,
+                sort_attributes,
+            } => {
+                let pretty = pretty.then_some(PrettyOptions {
+                    indent: *indent,
+                    sort_attributes: *sort_attributes,
+                });
+                let pretty = pretty.as_ref();
+                if *from_pak {
+                    let [pak] = source.as_slice() else {
+                        anyhow::bail!("--from-pak converts a single PAK at a time");
+                    };
+                    return convert::execute_from_pak(
+                        pak,
+                        filter,
+                        destination,
+                        output_format.as_deref(),
+                        pretty,
+                        ctx,
+                    );
+                }
+                convert::execute(
+                    source,
+                    destination,
+                    input_format.as_deref(),
+                    output_format.as_deref(),
+                    texture_format,
+                    pretty,
+                    !ctx.show_progress(),
+                )
+            }
+            Commands::Gr2 { command } => command.execute(ctx),
+            Commands::VirtualTexture { command } => command.execute(ctx),
+            Commands::Mods { command } => command.execute(ctx),
+            Commands::Loca { command } => command.execute(ctx),
+            Commands::Texture { command } => command.execute(ctx),
+            Commands::Audio { command } => command.execute(ctx),
+            Commands::Dialog { command } => command.execute(ctx),
+            Commands::Save { command } => command.execute(ctx),
+            Commands::Search { command } => command.execute(ctx),
+            Commands::Stats { command } => command.execute(ctx),
+            Commands::Config { command } => command.execute(ctx),
+            Commands::Uuid {
+                command: Some(UuidCommands::Convert { value }),
+                ..
+            } => uuid_cmd::convert(value, ctx),
+            Commands::Uuid {
+                command: None,
+                count,
+                format,
+            } => uuid_cmd::generate(*count, *format, ctx),
+            Commands::Completions { shell } => {
+                docs::completions(*shell);
+                Ok(())
+            }
+            Commands::Manpages { dir } => docs::manpages(dir),
         }
     }
 }
@@ -41,34 +97,75 @@ impl PakCommands {
     ///
     /// # Errors
     /// Returns an error if the underlying PAK operation fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
             PakCommands::Extract {
                 source,
                 destination,
                 filter,
+                exclude,
                 file,
-                quiet,
-            } => pak::extract(
-                source,
-                destination,
-                filter.as_deref(),
-                file.as_deref(),
-                *quiet,
-            ),
+                strict,
+                bundle,
+                convert_gr2,
+                extract_textures,
+                png,
+                delete_gr2,
+                keep_dds,
+                manifest,
+                vt_naming,
+                bg3_path,
+            } => {
+                let textures = *bundle || *extract_textures || *png;
+                let options = Gr2ExtractionOptions::new()
+                    .with_convert_to_glb(*bundle || *convert_gr2)
+                    .with_extract_textures(textures)
+                    .with_extract_virtual_textures(textures)
+                    .with_png_conversion(*png)
+                    .with_keep_original(!*delete_gr2)
+                    .with_keep_original_dds(*keep_dds)
+                    .with_vt_naming((*vt_naming).into())
+                    .with_bg3_path(bg3_path.clone())
+                    .with_texture_roots(crate::cli::config().texture_roots.clone());
+                pak::extract(
+                    source,
+                    destination,
+                    &FilterSet::new(filter, exclude, true)?,
+                    file.as_deref(),
+                    *strict,
+                    &ExtractionPipeline::new(options).with_manifest(manifest.clone()),
+                    !ctx.show_progress(),
+                )
+            }
             PakCommands::Create {
                 source,
                 destination,
                 compression,
-                quiet,
-            } => pak::create(source, destination, compression, *quiet),
+                exclude,
+                priority,
+            } => pak::create(
+                source,
+                destination,
+                compression.as_deref(),
+                exclude,
+                *priority,
+                !ctx.show_progress(),
+            ),
             PakCommands::List {
                 source,
                 detailed,
                 filter,
+                exclude,
                 count,
-                quiet,
-            } => pak::list(source, *detailed, filter.as_deref(), *count, *quiet),
+            } => pak::list(
+                source,
+                *detailed,
+                &FilterSet::new(filter, exclude, true)?,
+                *count,
+                ctx,
+            ),
+            PakCommands::Info { source, top, dupes } => pak::info(source, *top, *dupes, ctx),
+            PakCommands::Lint { sources } => pak::lint(sources, ctx),
         }
     }
 }
@@ -78,29 +175,36 @@ impl Gr2Commands {
     ///
     /// # Errors
     /// Returns an error if the underlying GR2 operation fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
-            Gr2Commands::Inspect { path, output } => gr2::inspect(path, output.as_deref()),
+            Gr2Commands::Inspect { path, output } => gr2::inspect(path, output.as_deref(), ctx),
             Gr2Commands::FromGr2 {
                 source,
                 destination,
                 format,
                 textures,
                 bg3_path,
-                quiet,
             } => gr2::from_gr2(
                 source,
                 destination,
                 format,
                 textures.as_deref(),
                 bg3_path.as_deref(),
-                *quiet,
+                !ctx.show_progress(),
             ),
             Gr2Commands::ToGr2 {
                 source,
                 destination,
-                quiet,
-            } => gr2::to_gr2(source, destination, *quiet),
+                compress,
+                fix_weights,
+            } => gr2::to_gr2(
+                source,
+                destination,
+                *compress,
+                *fix_weights,
+                !ctx.show_progress(),
+            ),
+            Gr2Commands::Validate { sources } => gr2::validate(sources, ctx),
         }
     }
 }
@@ -110,22 +214,46 @@ impl VirtualTextureCommands {
     ///
     /// # Errors
     /// Returns an error if the underlying virtual texture operation fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
             VirtualTextureCommands::List {
                 path,
                 detailed,
+                metadata,
                 output,
-            } => virtual_texture::list(path, *detailed, output.as_deref()),
+            } => virtual_texture::list(path, *detailed, *metadata, output.as_deref(), ctx),
+            VirtualTextureCommands::SetMeta {
+                path,
+                texture,
+                name,
+                guid,
+            } => virtual_texture::set_meta(
+                path,
+                texture.as_deref(),
+                name.as_deref(),
+                guid.as_deref(),
+                ctx,
+            ),
             VirtualTextureCommands::Extract {
                 source,
                 destination,
                 gtex,
+                guid,
                 layer,
-                quiet,
+                naming,
+                strict,
             } => {
                 let layers: Vec<usize> = layer.iter().map(|l| l.0).collect();
-                virtual_texture::extract(source, destination, gtex.as_deref(), &layers, *quiet)
+                virtual_texture::extract(
+                    source,
+                    destination,
+                    gtex.as_deref(),
+                    guid.as_deref(),
+                    &layers,
+                    (*naming).into(),
+                    *strict,
+                    !ctx.show_progress(),
+                )
             }
             VirtualTextureCommands::Create {
                 source,
@@ -134,9 +262,9 @@ impl VirtualTextureCommands {
                 base,
                 normal,
                 physical,
+                source_format,
                 compression,
                 no_embed_mip,
-                quiet,
             } => virtual_texture::create(
                 source,
                 destination,
@@ -144,9 +272,10 @@ impl VirtualTextureCommands {
                 base.as_deref(),
                 normal.as_deref(),
                 physical.as_deref(),
+                source_format.parse()?,
                 compression,
                 *no_embed_mip,
-                *quiet,
+                !ctx.show_progress(),
             ),
         }
     }
@@ -157,15 +286,26 @@ impl LocaCommands {
     ///
     /// # Errors
     /// Returns an error if the underlying LOCA operation fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
             LocaCommands::Search {
                 path,
                 query,
                 handle,
                 limit,
-                quiet,
-            } => loca::search(path, query, *handle, *limit, *quiet),
+            } => loca::search(path, query, *handle, *limit, ctx),
+            LocaCommands::Resolve {
+                handles,
+                pak,
+                language,
+                no_cache,
+            } => loca::resolve(handles, pak, language, *no_cache, ctx),
+            LocaCommands::ExtractLanguage {
+                pak,
+                language,
+                all_languages: _,
+                output,
+            } => loca::extract_language(pak, language.as_deref(), output, ctx),
         }
     }
 }
@@ -175,9 +315,142 @@ impl TextureCommands {
     ///
     /// # Errors
     /// Returns an error if the underlying texture operation fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            TextureCommands::Info { path } => texture::info(path, ctx),
+            TextureCommands::Convert {
+                source,
+                output,
+                texture_format,
+                srgb,
+                linear,
+            } => texture::convert(
+                source,
+                output.as_deref(),
+                texture_format,
+                texture::color_space_arg(*srgb, *linear),
+                ctx,
+            ),
+            TextureCommands::BatchConvert {
+                source,
+                output,
+                to,
+                texture_format,
+                skip_existing,
+                srgb,
+                linear,
+            } => texture::batch_convert(
+                source,
+                output.as_deref(),
+                *to,
+                texture_format,
+                *skip_existing,
+                texture::color_space_arg(*srgb, *linear),
+                ctx,
+            ),
+        }
+    }
+}
+
+impl AudioCommands {
+    /// Execute the selected audio command.
+    ///
+    /// # Errors
+    /// Returns an error if the underlying conversion fails.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            AudioCommands::Convert {
+                source,
+                output,
+                codebooks,
+                inline_codebooks,
+            } => audio::convert(
+                source,
+                output.as_deref(),
+                codebooks.as_deref(),
+                *inline_codebooks,
+                ctx.quiet,
+            ),
+            AudioCommands::Batch {
+                source,
+                output,
+                codebooks,
+                inline_codebooks,
+            } => audio::batch(
+                source,
+                output.as_deref(),
+                codebooks.as_deref(),
+                *inline_codebooks,
+                !ctx.show_progress(),
+            ),
+        }
+    }
+}
+
+impl SaveCommands {
+    /// Execute the selected savegame command.
+    ///
+    /// # Errors
+    /// Returns an error if the save cannot be read.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            SaveCommands::Info { path } => save::info(path, ctx),
+            SaveCommands::List { path } => save::list(path, ctx),
+            SaveCommands::Extract { path, destination } => {
+                let destination = crate::cli::output_dir(destination.as_deref())?;
+                save::extract(path, &destination, ctx.quiet)
+            }
+        }
+    }
+}
+
+impl SearchCommands {
+    /// Execute the selected search command.
+    ///
+    /// # Errors
+    /// Returns an error if the underlying search fails.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
-            TextureCommands::Info { path } => texture::info(path),
+            SearchCommands::Grep {
+                paks,
+                pattern,
+                regex,
+                ignore_case,
+                types,
+            } => search::grep(paks, pattern, *regex, *ignore_case, types, ctx),
+        }
+    }
+}
+
+impl DialogCommands {
+    /// Execute the selected dialog command.
+    ///
+    /// # Errors
+    /// Returns an error if the underlying dialog operation fails.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            DialogCommands::Export {
+                file,
+                loca,
+                language,
+                format,
+                output,
+            } => dialog::export(file, loca, language, *format, output.as_deref(), ctx),
+        }
+    }
+}
+
+impl StatsCommands {
+    /// Execute the selected stats command.
+    ///
+    /// # Errors
+    /// Returns an error if the underlying stats operation fails.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            StatsCommands::Lint { path } => stats::lint(path, ctx),
+            StatsCommands::Treasure { index, item, table } => {
+                stats::treasure(index, item.as_deref(), table.as_deref(), ctx)
+            }
         }
     }
 }
@@ -187,15 +460,60 @@ impl ModCommands {
     ///
     /// # Errors
     /// Returns an error if the underlying mod operation fails.
-    pub fn execute(&self) -> anyhow::Result<()> {
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
         match self {
-            ModCommands::Validate { source, quiet } => mod_cmd::validate(source, *quiet),
+            ModCommands::Validate {
+                source,
+                sync,
+                prefer_lsf,
+            } => {
+                let sync = sync.then_some(if *prefer_lsf {
+                    PairSide::Lsf
+                } else {
+                    PairSide::Lsx
+                });
+                mod_cmd::validate(source, sync, ctx)
+            }
             ModCommands::Package {
                 source,
                 destination,
                 compress,
-                quiet,
-            } => mod_cmd::package(source, destination, compress.as_deref(), *quiet),
+                extended,
+            } => mod_cmd::package(
+                source,
+                &crate::cli::output_dir(destination.as_deref())?,
+                compress.as_deref(),
+                *extended,
+                !ctx.show_progress(),
+            ),
+            ModCommands::InfoJson {
+                source,
+                output,
+                extended,
+            } => mod_cmd::info_json(source, output.as_deref(), *extended),
+            ModCommands::New {
+                name,
+                template,
+                output,
+                author,
+                description,
+                folder,
+                uuid,
+                version,
+                script_extender,
+                list_templates,
+            } => match (name, template) {
+                // clap requires both unless --list-templates is given
+                (Some(name), Some(template)) if !*list_templates => {
+                    let mut options = NewModOptions::new(name, author);
+                    options.description.clone_from(description);
+                    options.folder.clone_from(folder);
+                    options.uuid.clone_from(uuid);
+                    options.script_extender = *script_extender;
+                    mod_cmd::new_mod(output, template, options, version, ctx)
+                }
+                _ => mod_cmd::list_templates(ctx),
+            },
             ModCommands::Meta {
                 source,
                 name,
@@ -213,7 +531,110 @@ impl ModCommands {
                 uuid.as_deref(),
                 version,
             ),
-            ModCommands::Conflicts { sources, quiet } => mod_cmd::conflicts(sources, *quiet),
+            ModCommands::Version { path } => mod_cmd::version(path),
+            ModCommands::Bump {
+                path,
+                major,
+                minor,
+                patch,
+                build: _,
+            } => {
+                let part = if *major {
+                    VersionPart::Major
+                } else if *minor {
+                    VersionPart::Minor
+                } else if *patch {
+                    VersionPart::Patch
+                } else {
+                    VersionPart::Build
+                };
+                mod_cmd::bump(path, part)
+            }
+            ModCommands::Conflicts { sources } => mod_cmd::conflicts(sources, !ctx.show_progress()),
+            ModCommands::Diff { source, pak } => mod_cmd::diff(source, pak, ctx),
+            ModCommands::Deps {
+                id,
+                mod_dir,
+                pak,
+                reverse,
+                dot,
+            } => mod_cmd::deps(id, mod_dir, pak, *reverse, dot.as_deref(), ctx),
+            ModCommands::LocaCoverage {
+                source,
+                handle: Some(handle),
+                ..
+            } => mod_cmd::handle_references(source, handle, ctx),
+            ModCommands::LocaCoverage {
+                source,
+                language,
+                strict,
+                handle: None,
+            } => mod_cmd::loca_coverage(source, language, *strict, ctx),
+            ModCommands::Deploy {
+                pak,
+                symlink,
+                mods_dir,
+                dry_run,
+            } => mod_cmd::deploy(pak, *symlink, mods_dir.as_deref(), *dry_run),
+            ModCommands::Undeploy {
+                mod_ref,
+                mods_dir,
+                dry_run,
+            } => mod_cmd::undeploy(mod_ref, mods_dir.as_deref(), *dry_run),
+            ModCommands::Visuals { command } => command.execute(ctx),
+        }
+    }
+}
+
+impl VisualsCommands {
+    /// Execute the selected visual bank command.
+    ///
+    /// # Errors
+    /// Returns an error if the bank file cannot be read, edited or written.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            VisualsCommands::List { file } => mod_cmd::visuals_list(file, ctx),
+            VisualsCommands::SetSlot {
+                file,
+                resource,
+                slot,
+                visual,
+                output,
+            } => mod_cmd::visuals_set_slot(
+                file,
+                resource,
+                slot,
+                visual.as_deref(),
+                output.as_deref(),
+                ctx,
+            ),
+            VisualsCommands::SetMaterial {
+                file,
+                resource,
+                object,
+                material,
+                output,
+            } => mod_cmd::visuals_set_material(
+                file,
+                resource,
+                object,
+                material,
+                output.as_deref(),
+                ctx,
+            ),
+        }
+    }
+}
+
+impl ConfigCommands {
+    /// Execute the selected config command.
+    ///
+    /// # Errors
+    /// Returns an error if the settings file cannot be read or written.
+    pub fn execute(&self, ctx: &OutputContext) -> anyhow::Result<()> {
+        match self {
+            ConfigCommands::Show => config::show(ctx),
+            ConfigCommands::Set { key, value } => config::set(key, value),
         }
     }
 }
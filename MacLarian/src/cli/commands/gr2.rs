@@ -4,43 +4,44 @@
 
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
+
 use super::expand_globs;
+use crate::cli::output::{OutputContext, print_json};
 use crate::cli::progress::{
-    CUBE, DISK, GEAR, LOOKING_GLASS, print_done, print_step, simple_spinner,
+    CUBE, DISK, GEAR, LOOKING_GLASS, TaskProgress, print_done, print_step, simple_spinner,
 };
 use crate::converter::{
-    Gr2Phase, convert_gltf_to_gr2_with_progress, convert_gr2_to_glb_with_progress,
-    convert_gr2_to_gltf_with_progress,
+    Gr2BatchTarget, Gr2Compression, Gr2Phase, Gr2WriteOptions, WEIGHT_SUM_RANGES, WeightReport,
+    batch_convert_gr2_cancellable, convert_gltf_to_gr2_with_options,
+    convert_gr2_to_glb_with_progress, convert_gr2_to_gltf_with_progress, gltf_weight_report,
+    gr2_weight_report,
 };
+use crate::error::Error;
 use crate::formats::gr2::{extract_gr2_info, inspect_gr2};
-
-/// Default BG3 installation paths
-const BG3_PATHS: &[&str] = &[
-    // macOS
-    "~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/Baldur's Gate 3.app/Contents/Data",
-    // Windows
-    "C:/Program Files (x86)/Steam/steamapps/common/Baldurs Gate 3/Data",
-    // Linux
-    "~/.steam/steam/steamapps/common/Baldurs Gate 3/Data",
-];
-
-/// Try to find BG3 installation path
-fn find_bg3_path() -> Option<std::path::PathBuf> {
-    for path in BG3_PATHS {
-        let expanded = shellexpand::tilde(path);
-        let path = std::path::Path::new(expanded.as_ref());
-        if path.exists() {
-            return Some(path.to_path_buf());
-        }
-    }
-    None
+use crate::utils::BG3_PATH_ENV;
+
+/// `gr2 inspect --json` output: the file structure and, if it can be
+/// extracted, the model
+fn inspect_json(path: &Path) -> anyhow::Result<serde_json::Value> {
+    let info = inspect_gr2(path)?;
+    let model = extract_gr2_info(path).ok();
+    Ok(serde_json::json!({
+        "path": path,
+        "file": info,
+        "model": model,
+    }))
 }
 
 /// Inspect a GR2 file and display its structure.
 ///
 /// # Errors
 /// Returns an error if the file cannot be read or is not a valid GR2.
-pub fn inspect(path: &Path, output: Option<&Path>) -> anyhow::Result<()> {
+pub fn inspect(path: &Path, output: Option<&Path>, ctx: &OutputContext) -> anyhow::Result<()> {
+    if ctx.is_json() && output.is_none() {
+        return print_json(&inspect_json(path)?);
+    }
+
     if let Some(out_path) = output {
         // Output to JSON file
         let model_info = extract_gr2_info(path)?;
@@ -158,22 +159,17 @@ fn from_gr2_single(
 
     // Find BG3 path if needed for textures
     let bg3_install = if texture_mode.is_some() {
-        if let Some(path) = bg3_path {
-            Some(path.to_path_buf())
-        } else if let Some(path) = find_bg3_path() {
-            if !quiet {
-                println!("Auto-detected BG3 path: {}", path.display());
-            }
-            Some(path)
-        } else {
+        let Some(path) = crate::cli::config().resolve_bg3_path(bg3_path) else {
             anyhow::bail!(
-                "BG3 installation not found. Please provide --bg3-path.\n\
-                 Searched locations:\n\
-                 - macOS: ~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/\n\
-                 - Windows: C:/Program Files (x86)/Steam/steamapps/common/Baldurs Gate 3/\n\
-                 - Linux: ~/.steam/steam/steamapps/common/Baldurs Gate 3/"
+                "BG3 installation not found. Please provide --bg3-path, run \
+                 'maclarian config set bg3-path <path>', or set {BG3_PATH_ENV}.\n\
+                 Searched the standard Steam, Mac App Store and GOG locations."
             );
+        };
+        if bg3_path.is_none() && !quiet {
+            println!("Note: --bg3-path not given, using {}", path.display());
         }
+        Some(path)
     } else {
         None
     };
@@ -257,7 +253,8 @@ fn from_gr2_single(
                 .with_convert_to_glb(!use_gltf)
                 .with_extract_textures(true)
                 .with_extract_virtual_textures(true)
-                .with_bg3_path(bg3_install);
+                .with_bg3_path(bg3_install)
+                .with_texture_roots(crate::cli::config().texture_roots.clone());
 
             if !quiet {
                 let pb = simple_spinner("Processing GR2 with textures...");
@@ -368,30 +365,34 @@ fn from_gr2_batch(
         out_ext.to_uppercase()
     );
 
+    let cancel = crate::cli::cancel_token();
     let mut success = 0;
     let mut failed = 0;
+    let progress = TaskProgress::new("Converting", sources.len() as u64, !quiet);
 
     for source in sources {
+        if cancel.is_cancelled() {
+            break;
+        }
         let stem = source
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("output");
         let dest_file = destination.join(format!("{stem}.{out_ext}"));
 
-        if !quiet {
-            println!("Converting: {}", source.display());
-        }
-
+        progress.start_item(&source.display().to_string());
         match from_gr2_single(source, &dest_file, format, textures, bg3_path, true) {
             Ok(()) => {
                 success += 1;
             }
             Err(e) => {
-                eprintln!("Failed to convert {}: {e}", source.display());
+                progress.eprintln(&format!("Failed to convert {}: {e}", source.display()));
                 failed += 1;
             }
         }
+        progress.inc();
     }
+    progress.finish();
 
     println!();
     println!("Batch conversion complete:");
@@ -400,6 +401,7 @@ fn from_gr2_batch(
         println!("  Failed: {failed}");
     }
 
+    cancel.checkpoint()?;
     Ok(())
 }
 
@@ -407,34 +409,59 @@ fn from_gr2_batch(
 ///
 /// # Errors
 /// Returns an error if glob expansion, file reading, or conversion fails.
-pub fn to_gr2(sources: &[PathBuf], destination: &Path, quiet: bool) -> anyhow::Result<()> {
+pub fn to_gr2(
+    sources: &[PathBuf],
+    destination: &Path,
+    compress: bool,
+    fix_weights: bool,
+    quiet: bool,
+) -> anyhow::Result<()> {
     // Expand glob patterns
     let sources = expand_globs(sources)?;
+    let mut options = if compress {
+        Gr2WriteOptions::bitknit()
+    } else {
+        Gr2WriteOptions::default()
+    };
+    options.fix_weights = fix_weights;
 
     // Handle batch conversion
     if sources.len() > 1 {
-        return to_gr2_batch(&sources, destination, quiet);
+        return to_gr2_batch(&sources, destination, options, quiet);
     }
 
     let source = &sources[0];
-    to_gr2_single(source, destination, quiet)
+    to_gr2_single(source, destination, options, quiet)
 }
 
 /// Convert a single glTF/GLB file to GR2
-fn to_gr2_single(source: &Path, destination: &Path, quiet: bool) -> anyhow::Result<()> {
+fn to_gr2_single(
+    source: &Path,
+    destination: &Path,
+    options: Gr2WriteOptions,
+    quiet: bool,
+) -> anyhow::Result<()> {
     if !quiet {
         println!("Converting glTF to GR2...");
         println!("  Source:      {}", source.display());
         println!("  Destination: {}", destination.display());
-        println!();
-        println!("Note: Output will be uncompressed (compression not yet implemented)");
+        if options.compression == Gr2Compression::BitKnit {
+            println!("  Compression: BitKnit");
+        }
+        if options.fix_weights {
+            let report = gltf_weight_report(source)?;
+            println!(
+                "  Weights:     renormalizing ({} of {} vertices not summing to 1)",
+                report.affected, report.vertices
+            );
+        }
         println!();
     }
 
     let start = std::time::Instant::now();
 
     if !quiet {
-        convert_gltf_to_gr2_with_progress(source, destination, &|progress| {
+        convert_gltf_to_gr2_with_options(source, destination, &options, &|progress| {
             let emoji = match progress.phase {
                 Gr2Phase::LoadingFile => LOOKING_GLASS,
                 Gr2Phase::ParsingModel => CUBE,
@@ -455,59 +482,151 @@ fn to_gr2_single(source: &Path, destination: &Path, quiet: bool) -> anyhow::Resu
         print_done(start.elapsed());
         println!("  Output size: {output_size} bytes");
     } else {
-        crate::converter::convert_gltf_to_gr2(source, destination)?;
+        convert_gltf_to_gr2_with_options(source, destination, &options, &|_| {})?;
     }
 
     Ok(())
 }
 
 /// Batch convert multiple glTF/GLB files to GR2
-fn to_gr2_batch(sources: &[PathBuf], destination: &Path, quiet: bool) -> anyhow::Result<()> {
-    // Ensure destination directory exists
-    std::fs::create_dir_all(destination)?;
-
+fn to_gr2_batch(
+    sources: &[PathBuf],
+    destination: &Path,
+    options: Gr2WriteOptions,
+    quiet: bool,
+) -> anyhow::Result<()> {
     println!("Batch converting {} glTF/GLB files to GR2", sources.len());
-    if !quiet {
-        println!("Note: Output will be uncompressed (compression not yet implemented)");
+
+    let cancel = crate::cli::cancel_token();
+    let progress = TaskProgress::new("Converting", sources.len() as u64, !quiet);
+    let report = batch_convert_gr2_cancellable(
+        sources,
+        destination,
+        Gr2BatchTarget::Gr2(options),
+        &cancel,
+        &|p| progress.report(p),
+    )?;
+    progress.finish();
+    for (source, e) in &report.failed {
+        eprintln!("Failed to convert {}: {e}", source.display());
     }
 
-    let mut success = 0;
-    let mut failed = 0;
+    println!();
+    println!("Batch conversion complete:");
+    println!("  Success: {}", report.written.len());
+    if !report.failed.is_empty() {
+        println!("  Failed: {}", report.failed.len());
+    }
 
-    for source in sources {
-        let stem = source
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        let dest_file = destination.join(format!("{stem}.GR2"));
+    cancel.checkpoint()?;
+    Ok(())
+}
 
-        if !quiet {
-            println!("Converting: {}", source.display());
-        }
+/// One file's entry in `gr2 validate --json` output
+#[derive(Debug, Serialize)]
+struct WeightValidation {
+    path: String,
+    #[serde(flatten)]
+    report: WeightReport,
+}
 
-        match to_gr2_single(source, &dest_file, true) {
-            Ok(()) => {
-                success += 1;
-            }
-            Err(e) => {
-                eprintln!("Failed to convert {}: {e}", source.display());
-                failed += 1;
-            }
+/// Report the bone weight sums of GR2 or glTF/GLB files
+///
+/// Files are read as glTF when their extension is `glb` or `gltf`, and as
+/// GR2 otherwise.
+///
+/// # Errors
+/// Returns an error if glob expansion fails or a file can't be read, and
+/// [`Error::ValidationFailed`] if any file has unnormalized weights.
+pub fn validate(sources: &[PathBuf], ctx: &OutputContext) -> anyhow::Result<()> {
+    let paths = expand_globs(sources)?;
+    let mut results = Vec::new();
+
+    for path in &paths {
+        let is_gltf = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("glb") || ext.eq_ignore_ascii_case("gltf"));
+        let report = if is_gltf {
+            gltf_weight_report(path)?
+        } else {
+            gr2_weight_report(path)?
+        };
+
+        let show = !ctx.quiet || !report.is_clean();
+        if show && !ctx.is_json() {
+            print_weight_report(path, &report);
         }
+        results.push(WeightValidation {
+            path: path.display().to_string(),
+            report,
+        });
     }
 
-    println!();
-    println!("Batch conversion complete:");
-    println!("  Success: {success}");
-    if failed > 0 {
-        println!("  Failed: {failed}");
+    let failures = results.iter().filter(|r| !r.report.is_clean()).count();
+    if ctx.is_json() {
+        print_json(&results)?;
+    } else if !ctx.quiet && paths.len() > 1 {
+        println!(
+            "{} file(s) checked: {failures} with weight issues",
+            paths.len()
+        );
     }
 
+    if failures > 0 {
+        return Err(Error::ValidationFailed { failures }.into());
+    }
     Ok(())
 }
 
+fn print_weight_report(path: &Path, report: &WeightReport) {
+    println!("{}", path.display());
+    if report.vertices == 0 {
+        println!("  No skinned vertices");
+        println!();
+        return;
+    }
+    println!("  Skinned vertices: {}", report.vertices);
+    println!("  Not summing to 1: {}", report.affected);
+    if report.over_limit > 0 {
+        println!("  Over 4 influences: {}", report.over_limit);
+    }
+    println!("  Weight sums:");
+    for (range, count) in WEIGHT_SUM_RANGES.iter().zip(report.distribution) {
+        println!("    {range:>10}  {count}");
+    }
+    println!();
+}
+
 #[derive(Debug, Clone, Copy)]
 enum TextureMode {
     Extract,
     Embedded,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::triangle_gltf;
+
+    #[test]
+    fn test_inspect_json_parses() {
+        let dir = tempfile::tempdir().unwrap();
+        let gltf = dir.path().join("Tri_Body.gltf");
+        std::fs::write(&gltf, triangle_gltf()).unwrap();
+        let gr2 = dir.path().join("Tri_Body.GR2");
+        convert_gltf_to_gr2_with_options(&gltf, &gr2, &Gr2WriteOptions::default(), &|_| {})
+            .unwrap();
+
+        let text = serde_json::to_string_pretty(&inspect_json(&gr2).unwrap()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["path"], gr2.to_str().unwrap());
+        assert_eq!(json["file"]["version"], 7);
+        assert_eq!(json["file"]["is_64bit"], true);
+        assert!(!json["file"]["sections"].as_array().unwrap().is_empty());
+        let mesh = &json["model"]["meshes"][0];
+        assert_eq!(mesh["name"], "Tri_Body");
+        assert_eq!(mesh["vertex_count"], 3);
+        assert_eq!(mesh["triangle_count"], 1);
+    }
+}
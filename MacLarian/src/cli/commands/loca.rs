@@ -1,8 +1,58 @@
 //! CLI commands for LOCA localization file operations
 
-use std::path::Path;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 
-use crate::formats::loca::read_loca;
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::cli::output::{OutputContext, print_json};
+use crate::cli::progress::TaskProgress;
+use crate::converter::extract_languages;
+use crate::error::Error;
+use crate::formats::loca::{
+    LocaCache, LocaResolver, LocaResource, LocalizedText, ResolvedText, normalize_language,
+    read_loca,
+};
+use crate::pak::VfsView;
+
+/// A matching entry in `loca search --json` output
+#[derive(Debug, Serialize)]
+struct LocaMatch<'a> {
+    key: &'a str,
+    version: u16,
+    text: &'a str,
+}
+
+impl<'a> From<&'a LocalizedText> for LocaMatch<'a> {
+    fn from(entry: &'a LocalizedText) -> Self {
+        Self {
+            key: &entry.key,
+            version: entry.version,
+            text: &entry.text,
+        }
+    }
+}
+
+/// A handle in `loca get --json` output (fields are null if not found)
+#[derive(Debug, Serialize)]
+struct LocaLookup<'a> {
+    handle: &'a str,
+    text: Option<&'a str>,
+    version: Option<u16>,
+    source: Option<String>,
+}
+
+impl<'a> LocaLookup<'a> {
+    fn new(handle: &'a str, result: Option<&'a ResolvedText>) -> Self {
+        Self {
+            handle,
+            text: result.map(|r| r.text.as_str()),
+            version: result.map(|r| r.version),
+            source: result.map(ResolvedText::source),
+        }
+    }
+}
 
 /// Search for entries in a LOCA file
 ///
@@ -15,10 +65,19 @@ pub fn search(
     query: &str,
     search_handle: bool,
     limit: usize,
-    quiet: bool,
+    ctx: &OutputContext,
 ) -> anyhow::Result<()> {
     let resource = read_loca(path)?;
     let query_lower = query.to_lowercase();
+    let quiet = !ctx.show_progress();
+
+    if ctx.is_json() {
+        let matches: Vec<LocaMatch> = find_matches(&resource, &query_lower, search_handle, limit)
+            .into_iter()
+            .map(LocaMatch::from)
+            .collect();
+        return print_json(&matches);
+    }
 
     if search_handle {
         // Search by handle/key
@@ -29,9 +88,40 @@ pub fn search(
     }
 }
 
+/// Matching entries, preferring an exact handle match when searching handles
+fn find_matches<'a>(
+    resource: &'a LocaResource,
+    query_lower: &str,
+    search_handle: bool,
+    limit: usize,
+) -> Vec<&'a LocalizedText> {
+    if search_handle {
+        if let Some(entry) = resource
+            .entries
+            .iter()
+            .find(|e| e.key.to_lowercase() == *query_lower)
+        {
+            return vec![entry];
+        }
+        resource
+            .entries
+            .iter()
+            .filter(|e| e.key.to_lowercase().contains(query_lower))
+            .take(limit)
+            .collect()
+    } else {
+        resource
+            .entries
+            .iter()
+            .filter(|e| e.text.to_lowercase().contains(query_lower))
+            .take(limit)
+            .collect()
+    }
+}
+
 /// Search entries by handle/key name
 fn search_by_handle(
-    resource: &crate::formats::loca::LocaResource,
+    resource: &LocaResource,
     query_lower: &str,
     limit: usize,
     quiet: bool,
@@ -101,7 +191,7 @@ fn search_by_handle(
 
 /// Search entries by text content
 fn search_by_text(
-    resource: &crate::formats::loca::LocaResource,
+    resource: &LocaResource,
     query_lower: &str,
     limit: usize,
     quiet: bool,
@@ -144,3 +234,287 @@ fn search_by_text(
 
     Ok(())
 }
+
+/// Resolve `TranslatedString` handles to text
+///
+/// Handles come from the arguments, or stdin when none are given. The
+/// handle -> file index is cached per language and reused while the PAKs are
+/// unchanged.
+///
+/// # Errors
+/// Returns an error if no loca PAKs can be found or read, and
+/// [`Error::ValidationFailed`] if any handle is not found.
+pub fn resolve(
+    handles: &[String],
+    paks: &[PathBuf],
+    language: &str,
+    no_cache: bool,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let handles: Vec<String> = if handles.is_empty() {
+        std::io::stdin()
+            .lock()
+            .lines()
+            .map(|line| line.map(|l| l.trim().to_string()))
+            .filter(|line| !line.as_ref().is_ok_and(String::is_empty))
+            .collect::<Result<_, _>>()?
+    } else {
+        handles.to_vec()
+    };
+    if handles.is_empty() {
+        anyhow::bail!("No handles given");
+    }
+
+    let language = normalize_language(language).unwrap_or(language);
+    let paks = loca_paks(paks, Some(language))?;
+    let cache_path = if no_cache { None } else { cache_path(language) };
+    let cache = cache_path
+        .as_deref()
+        .and_then(|path| LocaCache::load(path).ok())
+        .filter(|cache| cache.is_fresh(language, &paks));
+
+    let results: Vec<Option<ResolvedText>> = if let Some(cache) = cache {
+        tracing::debug!("Using cached loca index");
+        cache.resolve_many(&handles)?
+    } else {
+        let mut resolver = LocaResolver::new(language);
+        let progress = TaskProgress::new("Reading", paks.len() as u64, ctx.show_progress());
+        for pak in &paks {
+            progress.start_item(&pak.display().to_string());
+            resolver
+                .add_pak(pak)
+                .with_context(|| format!("Failed to read {}", pak.display()))?;
+            progress.inc();
+        }
+        progress.finish();
+        if resolver.file_count() == 0 {
+            anyhow::bail!("No {language} .loca files found in the given PAKs");
+        }
+
+        if let Some(path) = &cache_path
+            && let Err(e) = LocaCache::from_resolver(&resolver, &paks).and_then(|c| c.save(path))
+        {
+            tracing::warn!("Failed to write loca cache {}: {e}", path.display());
+        }
+        handles
+            .iter()
+            .map(|h| resolver.resolve(h).cloned())
+            .collect()
+    };
+
+    let missing = results.iter().filter(|r| r.is_none()).count();
+    if ctx.is_json() {
+        let entries: Vec<_> = handles
+            .iter()
+            .zip(&results)
+            .map(|(handle, result)| LocaLookup::new(handle, result.as_ref()))
+            .collect();
+        print_json(&entries)?;
+    } else {
+        for (handle, result) in handles.iter().zip(&results) {
+            match result {
+                Some(entry) if handles.len() == 1 => {
+                    println!("{}", entry.text);
+                    if !ctx.quiet {
+                        println!("  ({})", entry.source());
+                    }
+                }
+                Some(entry) => {
+                    println!("{handle}\t{}", entry.text.replace('\n', "\\n"));
+                    if !ctx.quiet {
+                        println!("  ({})", entry.source());
+                    }
+                }
+                None => eprintln!("{handle}: not found"),
+            }
+        }
+    }
+
+    if missing > 0 {
+        return Err(Error::ValidationFailed { failures: missing }.into());
+    }
+    Ok(())
+}
+
+/// Export whole languages from PAKs as XML
+///
+/// Exports `language`, or every language found when it's `None`.
+///
+/// # Errors
+/// Returns an error if no loca PAKs can be found or read, no loca files
+/// match, or an XML file cannot be written.
+pub fn extract_language(
+    paks: &[PathBuf],
+    language: Option<&str>,
+    output: &Path,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let language = language.map(|l| normalize_language(l).unwrap_or(l));
+    let paks = loca_paks(paks, language)?;
+    let vfs = VfsView::new(&paks)?;
+
+    let progress = TaskProgress::new("Converting", 0, ctx.show_progress());
+    let extracted = extract_languages(&vfs, language.as_slice(), output, &|p| {
+        progress.report(p);
+    })?;
+    progress.finish();
+    if extracted.is_empty() {
+        match language {
+            Some(language) => anyhow::bail!("No {language} .loca files found in the given PAKs"),
+            None => anyhow::bail!("No .loca files for a known language found in the given PAKs"),
+        }
+    }
+
+    if ctx.is_json() {
+        return print_json(&extracted);
+    }
+    if ctx.quiet {
+        return Ok(());
+    }
+
+    for file in &extracted {
+        println!(
+            "{} -> {} ({} entries)",
+            file.source,
+            file.output.display(),
+            file.entries
+        );
+    }
+    let mut languages: Vec<&str> = extracted.iter().map(|e| e.language.as_str()).collect();
+    languages.sort_unstable();
+    languages.dedup();
+    let entries: usize = extracted.iter().map(|e| e.entries).sum();
+    println!(
+        "\nExported {} file(s), {entries} entries ({}) to {}",
+        extracted.len(),
+        languages.join(", "),
+        output.display()
+    );
+    Ok(())
+}
+
+/// PAKs to read: explicit files and directories, or the game's Localization
+/// folder (just the language's PAK when there is one)
+///
+/// Directories are searched recursively, and a game `Data` folder is
+/// narrowed to its `Localization` folder.
+fn loca_paks(paks: &[PathBuf], language: Option<&str>) -> anyhow::Result<Vec<PathBuf>> {
+    let sources = if paks.is_empty() {
+        let dir = crate::cli::config()
+            .resolve_bg3_path(None)
+            .map(|data| data.join("Localization"))
+            .filter(|dir| dir.is_dir())
+            .context("No --pak given and the game's Localization folder wasn't found")?;
+        let language_pak = language.and_then(|language| {
+            [
+                dir.join(format!("{language}.pak")),
+                dir.join(language).join(format!("{language}.pak")),
+            ]
+            .into_iter()
+            .find(|pak| pak.is_file())
+        });
+        vec![language_pak.unwrap_or(dir)]
+    } else {
+        paks.to_vec()
+    };
+
+    let mut found = Vec::new();
+    for source in sources {
+        if source.is_dir() {
+            let localization = source.join("Localization");
+            let dir = if localization.is_dir() {
+                localization
+            } else {
+                source
+            };
+            let mut in_dir = Vec::new();
+            collect_paks(&dir, &mut in_dir)?;
+            in_dir.sort();
+            found.extend(in_dir);
+        } else {
+            found.push(source);
+        }
+    }
+    Ok(found)
+}
+
+/// Every `.pak` file under `dir`
+fn collect_paks(dir: &Path, found: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_paks(&path, found)?;
+        } else if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pak"))
+        {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Cache file for a language, in the configured index directory or next to
+/// the config file
+fn cache_path(language: &str) -> Option<PathBuf> {
+    let dir = crate::cli::config().index_dir.clone().or_else(|| {
+        crate::cli::config_path()
+            .map(Path::to_path_buf)
+            .or_else(crate::config::Config::default_path)
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+    })?;
+    Some(dir.join(format!("loca-{}.json", language.to_lowercase())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_search_json_shape() {
+        let resource = LocaResource {
+            entries: vec![LocalizedText {
+                key: "h123".to_string(),
+                version: 1,
+                text: "Hello".to_string(),
+            }],
+        };
+        let matches: Vec<LocaMatch> = find_matches(&resource, "hello", false, 10)
+            .into_iter()
+            .map(LocaMatch::from)
+            .collect();
+        assert_eq!(
+            serde_json::to_value(&matches).unwrap(),
+            serde_json::json!([{ "key": "h123", "version": 1, "text": "Hello" }])
+        );
+    }
+
+    #[test]
+    fn test_get_json_shape() {
+        let found = ResolvedText {
+            handle: "h123".to_string(),
+            version: 2,
+            text: "Hello".to_string(),
+            pak: Some(PathBuf::from("English.pak")),
+            file: "Localization/English/english.loca".to_string(),
+            offset: 0,
+        };
+        let entries = [
+            LocaLookup::new("h123", Some(&found)),
+            LocaLookup::new("h456", None),
+        ];
+        assert_eq!(
+            serde_json::to_value(&entries).unwrap(),
+            serde_json::json!([
+                {
+                    "handle": "h123",
+                    "text": "Hello",
+                    "version": 2,
+                    "source": "English.pak:Localization/English/english.loca",
+                },
+                { "handle": "h456", "text": null, "version": null, "source": null },
+            ])
+        );
+    }
+}
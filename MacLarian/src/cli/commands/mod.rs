@@ -4,6 +4,8 @@ use glob::glob;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+use crate::converter::Indent;
+
 /// Expand glob patterns in paths (cross-platform)
 ///
 /// If a path contains glob characters (*, ?, [), expands it.
@@ -61,12 +63,20 @@ impl FromStr for LayerArg {
 }
 
 // Command implementation modules
+pub mod audio;
+pub mod config;
 pub mod convert;
+pub mod dialog;
+pub mod docs;
 pub mod gr2;
 pub mod loca;
 pub mod mod_cmd;
 pub mod pak;
+pub mod save;
+pub mod search;
+pub mod stats;
 pub mod texture;
+pub mod uuid_cmd;
 pub mod virtual_texture;
 
 // Command definitions and execution
@@ -75,7 +85,10 @@ mod execute;
 
 // Re-export subcommand enums
 pub use definitions::{
-    Gr2Commands, LocaCommands, ModCommands, PakCommands, TextureCommands, VirtualTextureCommands,
+    AudioCommands, ConfigCommands, DialogCommands, DialogFormatArg, Gr2Commands, LocaCommands,
+    ModCommands, PakCommands, SaveCommands, SearchCommands, StatsCommands, TextureCommands,
+    TextureTargetArg, UuidCommands, UuidFormatArg, VirtualTextureCommands, VisualsCommands,
+    VtNamingArg,
 };
 
 /// Top-level CLI commands.
@@ -103,43 +116,84 @@ Examples:
         long_about = "Convert file formats (LSF/LSX/LSJ, GR2/GLB, LOCA/XML, DDS/PNG)
 
 Auto-detects input/output formats from file extensions. Supports batch conversion
-with glob patterns. Output format can be overridden with -o/--output-format.
+with glob patterns. Formats can be overridden with -i/--input-format and
+-o/--output-format.
+
+Use - as the source or destination to read stdin or write stdout. Without an
+extension, the input format is detected from magic bytes; the output format
+must be given with -o.
+
+--pretty re-indents LSX and XML output, keeping all content as written. Give
+the same format on both sides (e.g. meta.lsx meta.lsx) to only format a file.
 
 Supported conversions:
   LSF <-> LSX    Binary to/from XML document format
   LSF <-> LSJ    Binary to/from JSON document format
   LSX <-> LSJ    XML to/from JSON document format
+  LSB -> LSX     Legacy (DOS2-era) binary to XML, LSJ or LSF; read-only
   GR2 <-> GLB    Granny2 mesh to/from glTF binary
   GR2 <-> glTF   Granny2 mesh to/from glTF
   LOCA <-> XML   Localization binary to/from XML
   DDS <-> PNG    DirectDraw Surface to/from PNG image
+  LSV -> LSX     Save's LSF files (meta.lsf, Globals.lsf, ...) to XML
+
+--from-pak converts the documents inside a PAK without extracting it first,
+writing them under the destination directory with their paths inside the PAK.
+Entries are read in archive order and converted in parallel, a batch at a
+time; entries that fail are listed at the end. --filter limits which entries
+are converted.
 
 Examples:
   maclarian convert meta.lsf meta.lsx
   maclarian convert meta.lsx meta.lsj
+  maclarian convert meta.lsb meta.lsx
   maclarian convert \"*.lsf\" ./output/
   maclarian convert texture.dds texture.png
-  maclarian convert texture.png texture.dds --texture-format bc3"
+  maclarian convert texture.png texture.dds --texture-format bc3
+  maclarian convert meta.lsx meta.lsx --pretty --indent 2 --sort-attributes
+  maclarian convert --from-pak Shared.pak ./out/ --filter \"*.lsf\"
+  cat meta.lsf | maclarian convert - - --from lsf --to lsx > meta.lsx"
     )]
     Convert {
-        /// Source file(s) or wildcard pattern
+        /// Source file(s), wildcard pattern, or - for stdin
         #[arg(required = true)]
         source: Vec<PathBuf>,
 
-        /// Output file (single source) or directory (multiple sources)
+        /// Output file (single source), directory (multiple sources or --from-pak),
+        /// or - for stdout
         destination: PathBuf,
 
+        /// Source is a PAK: convert the documents inside it
+        #[arg(long)]
+        from_pak: bool,
+
+        /// Only convert PAK entries matching these glob patterns (with --from-pak)
+        #[arg(long, requires = "from_pak")]
+        filter: Vec<String>,
+
+        /// Override input format (auto-detected from extension or magic bytes if not specified)
+        #[arg(short = 'i', long, visible_alias = "from")]
+        input_format: Option<String>,
+
         /// Override output format (auto-detected from extension if not specified)
-        #[arg(short = 'o', long)]
+        #[arg(short = 'o', long, visible_alias = "to")]
         output_format: Option<String>,
 
         /// DDS compression format when converting to DDS (bc1, bc2, bc3, rgba)
         #[arg(long, default_value = "bc3")]
         texture_format: String,
 
-        /// Suppress progress output
-        #[arg(short, long)]
-        quiet: bool,
+        /// Re-indent LSX/XML output (or an LSX/XML file converted to itself)
+        #[arg(long)]
+        pretty: bool,
+
+        /// Indentation for --pretty: "tab" or a number of spaces
+        #[arg(long, default_value = "tab", requires = "pretty")]
+        indent: Indent,
+
+        /// Sort attributes by name with --pretty instead of keeping their order
+        #[arg(long, requires = "pretty")]
+        sort_attributes: bool,
     },
 
     /// GR2 mesh file operations
@@ -229,4 +283,159 @@ Examples:
         #[command(subcommand)]
         command: TextureCommands,
     },
+
+    /// WEM audio conversion (Ogg Vorbis, WAV)
+    #[command(long_about = "WEM audio conversion (Ogg Vorbis, WAV)
+
+Convert Wwise .wem audio to playable files. Wwise Vorbis becomes Ogg Vorbis
+and PCM becomes WAV; other codecs are reported by codec id.
+
+Examples:
+  maclarian audio convert 12345678.wem -o voice.ogg
+  maclarian audio batch ./Sounds -o ./Converted")]
+    Audio {
+        /// Audio subcommand to execute.
+        #[command(subcommand)]
+        command: AudioCommands,
+    },
+
+    /// Dialog resource operations
+    #[command(long_about = "Dialog resource operations
+
+Work with the dialog graphs under Story/Dialogs: nodes, speakers, text
+handles, flags and links.
+
+Examples:
+  maclarian dialog export CAMP_Shadowheart.lsf --loca english.loca
+  maclarian dialog export CAMP_Shadowheart.lsf --format dot -o shadowheart.dot")]
+    Dialog {
+        /// Dialog subcommand to execute.
+        #[command(subcommand)]
+        command: DialogCommands,
+    },
+
+    /// Savegame (.lsv) inspection
+    #[command(long_about = "Savegame (.lsv) inspection
+
+BG3 saves are LSPK packages, like PAK files. Show a save's metadata, list its
+files or extract them. Saves are read-only.
+
+Examples:
+  maclarian save info QuickSave_12.lsv
+  maclarian save list QuickSave_12.lsv
+  maclarian save extract QuickSave_12.lsv ./save
+  maclarian convert QuickSave_12.lsv ./save-lsx/")]
+    Save {
+        /// Savegame subcommand to execute.
+        #[command(subcommand)]
+        command: SaveCommands,
+    },
+
+    /// Search PAK contents without building an index
+    #[command(long_about = "Search PAK contents without building an index
+
+Reads the text files of a PAK (LSX, LSF, LSJ, XML, JSON) and prints the lines
+that match. LSF files are searched by their names and string values.
+
+Examples:
+  maclarian search grep Shared.pak \"Shadowheart\"
+  maclarian search grep Gustav.pak \"MapKey.*S_GLO\" --regex --type lsx,lsf")]
+    Search {
+        /// Search subcommand to execute.
+        #[command(subcommand)]
+        command: SearchCommands,
+    },
+
+    /// Stats .txt file operations
+    #[command(long_about = "Stats .txt file operations
+
+Work with the plain-text stats files under Stats/Generated (Object.txt,
+Armor.txt, Weapon.txt, TreasureTable.txt, ItemCombos.txt).
+
+Examples:
+  maclarian stats lint Object.txt
+  maclarian stats lint TreasureTable.txt")]
+    Stats {
+        /// Stats subcommand to execute.
+        #[command(subcommand)]
+        command: StatsCommands,
+    },
+
+    /// Show or change saved settings (config.toml)
+    #[command(long_about = "Show or change saved settings (config.toml)
+
+Settings are read from ~/.config/macpak/config.toml (or the file given with
+--config) and shared with the MacPak GUI. Command-line arguments override
+settings, which override auto-detection.
+
+Examples:
+  maclarian config show
+  maclarian config set bg3-path /path/to/BG3/Data
+  maclarian config set compression zlib")]
+    Config {
+        /// Config subcommand to execute.
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Generate UUIDs and `TranslatedString` handles
+    #[command(
+        args_conflicts_with_subcommands = true,
+        long_about = "Generate UUIDs and TranslatedString handles
+
+Prints new random UUIDs, one per line. Use 'uuid convert' to show an existing
+GUID in its other forms.
+
+Formats:
+  standard  Hyphenated GUID (8-4-4-4-12), used for FixedString UUIDs
+  compact   32 hex digits without hyphens
+  handle    TranslatedString handle (h...g...), alias: larian
+
+Examples:
+  maclarian uuid
+  maclarian uuid --count 5
+  maclarian uuid --format handle
+  maclarian uuid convert 12345678-9abc-def0-1122-334455667788"
+    )]
+    Uuid {
+        /// UUID subcommand (omit to generate)
+        #[command(subcommand)]
+        command: Option<UuidCommands>,
+
+        /// Number of UUIDs to generate
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t)]
+        format: UuidFormatArg,
+    },
+
+    /// Generate a shell completion script
+    #[command(long_about = "Generate a shell completion script
+
+Writes the completion script for the given shell to stdout.
+
+Examples:
+  maclarian completions zsh > ~/.zfunc/_maclarian
+  maclarian completions bash > /usr/local/etc/bash_completion.d/maclarian
+  maclarian completions fish > ~/.config/fish/completions/maclarian.fish")]
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Generate man pages for all commands
+    #[command(long_about = "Generate man pages for all commands
+
+Writes one roff page per command and subcommand (maclarian.1, maclarian-pak.1,
+maclarian-pak-extract.1, ...) into the given directory.
+
+Examples:
+  maclarian manpages ./man
+  man ./man/maclarian-vt-extract.1")]
+    Manpages {
+        /// Output directory
+        dir: PathBuf,
+    },
 }
@@ -6,54 +6,95 @@ use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use serde::Serialize;
 use sevenz_rust::SevenZWriter;
 use zip::ZipWriter;
 use zip::write::SimpleFileOptions;
 
 use super::expand_globs;
+use crate::cli::output::{OutputContext, print_json};
 use crate::cli::progress::simple_spinner;
+use crate::error::Error;
+use crate::formats::banks::{BankDocument, CharacterVisual, MaterialResource, VisualResource};
 use crate::mods::{
-    generate_meta_lsx, parse_version_string, to_folder_name, validate_mod_structure,
+    CompanionPair, DependencyGraph, DeployAction, DeployOptions, DeployTarget, Direction,
+    InfoJsonOptions, LocaCoverage, MOD_TEMPLATES, NewModOptions, PairSide, PakIntegrityResult,
+    Version64, VersionPart, bump_meta_version, check_companion_pairs, create_mod, deploy_mod,
+    diff_against_pak, find_references, find_template, generate_info_json_from_source_with_options,
+    generate_meta_lsx, loca_coverage as check_loca_coverage, parse_version_string,
+    read_meta_version, to_folder_name, undeploy_mod, validate_mod_structure,
     validate_pak_mod_structure,
 };
-use crate::pak::PakOperations;
+use crate::pak::{PakOperations, lint_dir_paths, lint_pak};
+
+/// `mods validate` result for one source
+#[derive(Debug, Serialize)]
+struct ValidationReport {
+    source: PathBuf,
+    valid: bool,
+    structure: Vec<String>,
+    warnings: Vec<String>,
+    integrity: Option<PakIntegrityResult>,
+    /// `.lsx`/`.lsf` pairs that differ
+    companions: Vec<CompanionPair>,
+    /// Files regenerated by `--sync`
+    synced: Vec<PathBuf>,
+}
 
 /// Validate mod structure and PAK integrity
 ///
+/// In mod folders, `.lsx`/`.lsf` pairs that differ are reported as warnings.
+/// With `sync`, the file on the other side of each such pair is regenerated
+/// from the given side.
+///
 /// # Errors
-/// Returns an error if glob expansion or validation fails.
-pub fn validate(sources: &[PathBuf], quiet: bool) -> Result<()> {
+/// Returns an error if glob expansion or validation fails, and
+/// [`Error::ValidationFailed`] if any mod is invalid.
+pub fn validate(sources: &[PathBuf], sync: Option<PairSide>, ctx: &OutputContext) -> Result<()> {
     // Expand glob patterns
     let sources = expand_globs(sources)?;
+    let quiet = !ctx.show_progress();
 
-    // Single source validation
-    if sources.len() == 1 {
-        return validate_single(&sources[0], quiet);
-    }
-
-    // Multiple sources - validate each
-    let mut all_valid = true;
+    let mut reports = Vec::with_capacity(sources.len());
     for source in &sources {
-        if !quiet {
+        if !quiet && sources.len() > 1 {
             println!("Validating: {}", source.display());
         }
-        if validate_single(source, quiet).is_err() {
-            all_valid = false;
+        let report = match check_mod(source, sync, quiet) {
+            Ok(report) => report,
+            Err(e) if sources.len() > 1 => {
+                eprintln!("Error: {}: {e:#}", source.display());
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if !ctx.is_json() {
+            print_report(&report);
+            if sources.len() > 1 {
+                println!();
+            }
         }
-        if !quiet {
-            println!();
+        reports.push(report);
+    }
+
+    if ctx.is_json() {
+        if let [report] = reports.as_slice() {
+            print_json(report)?;
+        } else {
+            print_json(&reports)?;
         }
     }
 
-    if all_valid {
+    let failures = sources.len() - reports.iter().filter(|r| r.valid).count();
+    if failures == 0 {
         Ok(())
     } else {
-        std::process::exit(1);
+        Err(Error::ValidationFailed { failures }.into())
     }
 }
 
-/// Validate a single mod
-fn validate_single(source: &Path, quiet: bool) -> Result<()> {
+/// Run structure and integrity checks on a single mod
+fn check_mod(source: &Path, sync: Option<PairSide>, quiet: bool) -> Result<ValidationReport> {
     let is_pak = source
         .extension()
         .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"));
@@ -94,28 +135,83 @@ fn validate_single(source: &Path, quiet: bool) -> Result<()> {
         None
     };
 
+    // The game reads the .lsf, so a stale one hides edits to the .lsx
+    let mut warnings = result.warnings;
+    let mut companions = Vec::new();
+    let mut synced = Vec::new();
+    if !is_pak {
+        if let Some(ref pb) = pb {
+            pb.set_message("Comparing .lsx/.lsf pairs");
+        }
+        companions = check_companion_pairs(source);
+        companions.retain(|pair| !pair.is_in_sync());
+        for pair in &companions {
+            warnings.push(pair.warning(source));
+            if let Some(from) = sync {
+                match pair.sync(from) {
+                    Ok(written) => synced.push(written.to_path_buf()),
+                    Err(e) => warnings.push(format!("Failed to sync {}: {e}", pair.lsx.display())),
+                }
+            }
+        }
+    }
+
+    // Names that Windows or the game can't handle
+    if let Some(ref pb) = pb {
+        pb.set_message("Checking file paths");
+    }
+    let path_issues = if is_pak {
+        lint_pak(source)?
+    } else {
+        lint_dir_paths(source)
+    };
+    let path_errors = path_issues.iter().any(|i| !i.kind.is_warning());
+    warnings.extend(path_issues.iter().map(ToString::to_string));
+
     if let Some(ref pb) = pb {
         pb.finish_and_clear();
     }
 
+    let valid = result.valid && !path_errors && integrity_result.as_ref().is_none_or(|i| i.valid);
+    Ok(ValidationReport {
+        source: source.to_path_buf(),
+        valid,
+        structure: result.structure,
+        warnings,
+        integrity: integrity_result,
+        companions,
+        synced,
+    })
+}
+
+/// Print a validation report as text
+fn print_report(report: &ValidationReport) {
     // Print structure elements
-    if !result.structure.is_empty() {
+    if !report.structure.is_empty() {
         println!("Structure:");
-        for item in &result.structure {
+        for item in &report.structure {
             println!("  {item}");
         }
     }
 
     // Print warnings
-    if !result.warnings.is_empty() {
+    if !report.warnings.is_empty() {
         println!("\nWarnings:");
-        for warning in &result.warnings {
+        for warning in &report.warnings {
             println!("  - {warning}");
         }
     }
 
+    if !report.synced.is_empty() {
+        println!("\nRegenerated:");
+        for path in &report.synced {
+            let path = path.strip_prefix(&report.source).unwrap_or(path);
+            println!("  {}", path.display());
+        }
+    }
+
     // Print integrity results
-    if let Some(ref integrity) = integrity_result {
+    if let Some(ref integrity) = report.integrity {
         println!("\nIntegrity:");
         println!("  Files: {}", integrity.file_count);
         println!("  Size: {} bytes", integrity.total_size);
@@ -128,16 +224,36 @@ fn validate_single(source: &Path, quiet: bool) -> Result<()> {
     }
 
     // Print result
-    let valid = result.valid && integrity_result.as_ref().is_none_or(|i| i.valid);
-    if valid {
+    if report.valid {
         println!("\nValidation: PASSED");
-        Ok(())
     } else {
         println!("\nValidation: FAILED");
-        std::process::exit(1);
     }
 }
 
+/// Generate info.json for a mod and print it or write it to a file
+///
+/// # Errors
+/// Returns an error if meta.lsx cannot be found or the output cannot be written.
+pub fn info_json(source: &Path, output: Option<&Path>, extended: bool) -> Result<()> {
+    let options = InfoJsonOptions { extended };
+    let result = generate_info_json_from_source_with_options(source, options, &|_| {});
+
+    let Some(json_content) = result.content.filter(|_| result.success) else {
+        anyhow::bail!("{}", result.message);
+    };
+
+    if let Some(output) = output {
+        fs::write(output, &json_content)
+            .with_context(|| format!("Failed to write info.json to {}", output.display()))?;
+        println!("Wrote {}", output.display());
+    } else {
+        println!("{json_content}");
+    }
+
+    Ok(())
+}
+
 /// Package mod for `BaldursModManager` (generates info.json alongside PAK)
 ///
 /// # Errors
@@ -149,6 +265,7 @@ pub fn package(
     source: &Path,
     destination: &Path,
     compress: Option<&str>,
+    extended: bool,
     quiet: bool,
 ) -> Result<()> {
     // Validate mod structure first (checks for meta.lsx)
@@ -182,7 +299,8 @@ pub fn package(
     };
 
     // Generate info.json and get mod metadata
-    let result = crate::mods::generate_info_json_from_source(source, &|p| {
+    let options = InfoJsonOptions { extended };
+    let result = generate_info_json_from_source_with_options(source, options, &|p| {
         if let Some(ref pb) = pb {
             if let Some(ref msg) = p.current_file {
                 pb.set_message(msg.clone());
@@ -370,6 +488,73 @@ fn compress_to_7z(
     Ok(archive_path)
 }
 
+/// `mods new --list-templates` entry
+#[derive(Serialize)]
+struct TemplateInfo {
+    id: &'static str,
+    description: &'static str,
+    files: Vec<&'static str>,
+}
+
+/// List the built-in mod templates
+///
+/// # Errors
+/// Returns an error if JSON output fails.
+pub fn list_templates(ctx: &OutputContext) -> Result<()> {
+    if ctx.is_json() {
+        let templates: Vec<TemplateInfo> = MOD_TEMPLATES
+            .iter()
+            .map(|t| TemplateInfo {
+                id: t.id,
+                description: t.description,
+                files: t.file_paths().collect(),
+            })
+            .collect();
+        return print_json(&templates);
+    }
+
+    for template in MOD_TEMPLATES {
+        println!("{:<14} {}", template.id, template.description);
+    }
+    Ok(())
+}
+
+/// Create a new mod from a built-in template
+///
+/// # Errors
+/// Returns an error if the template is unknown, the version is invalid, or
+/// the mod directory already exists or cannot be written.
+pub fn new_mod(
+    parent: &Path,
+    template: &str,
+    mut options: NewModOptions,
+    version: &str,
+    ctx: &OutputContext,
+) -> Result<()> {
+    let template = find_template(template)?;
+    let (major, minor, patch, build) = parse_version_string(version).with_context(|| {
+        format!(
+            "Invalid version format: {version}. Expected: major.minor.patch.build (e.g., 1.0.0.0)"
+        )
+    })?;
+    options.version = Version64::new(major, minor, patch, build);
+
+    let created = create_mod(parent, template, &options)?;
+    if ctx.is_json() {
+        return print_json(&created);
+    }
+
+    println!("Created {} mod: {}", template.id, created.root.display());
+    println!("  Folder:  {}", created.folder);
+    println!("  UUID:    {}", created.uuid);
+    println!("  Version: {}", options.version);
+    println!("Files:");
+    for file in &created.files {
+        println!("  {file}");
+    }
+    Ok(())
+}
+
 /// Generate meta.lsx metadata file for a mod
 ///
 /// # Errors
@@ -429,6 +614,28 @@ pub fn meta(
     Ok(())
 }
 
+/// Print the decoded version from a meta.lsx file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or has no version.
+pub fn version(path: &Path) -> Result<()> {
+    let version = read_meta_version(path)
+        .with_context(|| format!("Failed to read version from {}", path.display()))?;
+    println!("{version} ({})", version.to_i64());
+    Ok(())
+}
+
+/// Increment the version in a meta.lsx file
+///
+/// # Errors
+/// Returns an error if the file cannot be read, has no version, or cannot be written.
+pub fn bump(path: &Path, part: VersionPart) -> Result<()> {
+    let (old, new) = bump_meta_version(path, part)
+        .with_context(|| format!("Failed to bump version in {}", path.display()))?;
+    println!("{old} -> {new} ({})", new.to_i64());
+    Ok(())
+}
+
 /// Find files modified by multiple mods (potential conflicts)
 ///
 /// # Errors
@@ -523,6 +730,443 @@ pub fn conflicts(sources: &[PathBuf], quiet: bool) -> Result<()> {
     Ok(())
 }
 
+/// Compare a mod source directory against a built PAK
+///
+/// # Errors
+/// Returns an error if either side cannot be read, and
+/// [`Error::ValidationFailed`] if they differ.
+pub fn diff(source: &Path, pak: &Path, ctx: &OutputContext) -> Result<()> {
+    let diff = diff_against_pak(source, pak).with_context(|| {
+        format!(
+            "Failed to compare {} with {}",
+            source.display(),
+            pak.display()
+        )
+    })?;
+    let failures = diff.problem_count();
+
+    if ctx.is_json() {
+        print_json(&diff)?;
+    } else {
+        for path in &diff.only_in_source {
+            println!("- {path} (missing from PAK)");
+        }
+        for path in &diff.only_in_pak {
+            println!("+ {path} (not in source)");
+        }
+        for path in &diff.mismatched {
+            println!("~ {path} (contents differ)");
+        }
+        for (path, sources) in &diff.collisions {
+            println!("! {path} (built from {})", sources.join(", "));
+        }
+        if failures > 0 {
+            println!();
+        }
+        println!(
+            "{} matching, {} converted, {} missing from PAK, {} extra in PAK, {} differ, {} ambiguous",
+            diff.matched,
+            diff.converted.len(),
+            diff.only_in_source.len(),
+            diff.only_in_pak.len(),
+            diff.mismatched.len(),
+            diff.collisions.len()
+        );
+    }
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(Error::ValidationFailed { failures }.into())
+    }
+}
+
+/// Install a mod PAK into the Mods folder and enable it in modsettings.lsx
+///
+/// # Errors
+/// Returns an error if the Mods folder can't be found, the PAK has no
+/// meta.lsx, or copying the PAK or writing modsettings.lsx fails.
+pub fn deploy(pak: &Path, symlink: bool, mods_dir: Option<&Path>, dry_run: bool) -> Result<()> {
+    let target = deploy_target(mods_dir)?;
+    let options = DeployOptions { symlink, dry_run };
+    let actions = deploy_mod(pak, &target, &options)
+        .with_context(|| format!("Failed to deploy {}", pak.display()))?;
+    print_deploy_actions(&actions, &target, dry_run);
+    Ok(())
+}
+
+/// Remove a deployed mod's PAK and modsettings.lsx entry
+///
+/// # Errors
+/// Returns an error if the Mods folder can't be found, nothing matches
+/// `mod_ref`, or removing the PAK or writing modsettings.lsx fails.
+pub fn undeploy(mod_ref: &str, mods_dir: Option<&Path>, dry_run: bool) -> Result<()> {
+    let target = deploy_target(mods_dir)?;
+    let actions = undeploy_mod(mod_ref, &target, dry_run)
+        .with_context(|| format!("Failed to undeploy {mod_ref}"))?;
+    print_deploy_actions(&actions, &target, dry_run);
+    Ok(())
+}
+
+/// The Mods folder to deploy to, with a hint when it can't be found
+fn deploy_target(mods_dir: Option<&Path>) -> Result<DeployTarget> {
+    DeployTarget::locate(mods_dir)
+        .context("Set MACPAK_BG3_PATH or pass --mods-dir with the game's Mods folder")
+}
+
+fn print_deploy_actions(actions: &[DeployAction], target: &DeployTarget, dry_run: bool) {
+    if actions.is_empty() {
+        println!("Already deployed; nothing to change");
+        return;
+    }
+    if dry_run {
+        println!("Would change (dry run):");
+    }
+    for action in actions {
+        println!("  {action}");
+    }
+    println!("Mods folder: {}", target.mods_dir.display());
+    println!("Mod settings: {}", target.modsettings.display());
+}
+
+/// Report referenced localization handles missing from each language
+///
+/// # Errors
+/// Returns an error if the mod directory cannot be read, and
+/// [`Error::ValidationFailed`] if `strict` is set and the primary language
+/// has gaps.
+pub fn loca_coverage(
+    source: &Path,
+    language: &str,
+    strict: bool,
+    ctx: &OutputContext,
+) -> Result<()> {
+    let pb = ctx
+        .show_progress()
+        .then(|| simple_spinner("Reading mod..."));
+    let coverage = check_loca_coverage(source, language)
+        .with_context(|| format!("Failed to read {}", source.display()))?;
+    if let Some(pb) = pb {
+        pb.finish_and_clear();
+    }
+
+    if ctx.is_json() {
+        print_json(&coverage)?;
+    } else {
+        print_loca_coverage(&coverage);
+    }
+
+    let failures = coverage.primary_gaps().len();
+    if strict && failures > 0 {
+        Err(Error::ValidationFailed { failures }.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// List the files and lines referencing a localization handle
+///
+/// # Errors
+/// Returns an error if the mod directory cannot be read.
+pub fn handle_references(source: &Path, handle: &str, ctx: &OutputContext) -> Result<()> {
+    let references = find_references(source, handle)
+        .with_context(|| format!("Failed to read {}", source.display()))?;
+
+    if ctx.is_json() {
+        return print_json(&references);
+    }
+    for reference in &references {
+        match reference.line {
+            Some(line) => println!("{}:{line}", reference.file),
+            None => println!("{}", reference.file),
+        }
+    }
+    println!("{} references to {handle}", references.len());
+    Ok(())
+}
+
+fn print_loca_coverage(coverage: &LocaCoverage) {
+    let incomplete = coverage.incomplete_handles();
+    if !incomplete.is_empty() {
+        let width = incomplete.iter().map(|h| h.len()).max().unwrap_or(0);
+        print!("{:width$}", "Handle");
+        for language in &coverage.languages {
+            print!("  {language:>7}");
+        }
+        println!();
+        for handle in &incomplete {
+            print!("{handle:width$}");
+            for language in &coverage.languages {
+                let mark = if coverage.is_missing(language, handle) {
+                    "missing"
+                } else {
+                    "ok"
+                };
+                print!("  {mark:>column$}", column = language.len().max(7));
+            }
+            println!();
+        }
+        println!();
+    }
+
+    for language in &coverage.languages {
+        let orphans = &coverage.orphans[language];
+        if orphans.is_empty() {
+            continue;
+        }
+        println!("Orphans in {language} (defined but never referenced):");
+        for handle in orphans {
+            println!("  {handle}");
+        }
+        println!();
+    }
+
+    let missing: Vec<String> = coverage
+        .languages
+        .iter()
+        .map(|l| format!("{} missing from {l}", coverage.missing[l].len()))
+        .collect();
+    let orphans: usize = coverage.orphans.values().map(Vec::len).sum();
+    println!(
+        "{} handles referenced, {}, {orphans} orphans",
+        coverage.referenced.len(),
+        missing.join(", ")
+    );
+}
+
+/// Show the transitive dependencies (or dependents) of an asset
+///
+/// # Errors
+/// Returns an error if the mod directory or a PAK cannot be read, or the DOT
+/// file cannot be written.
+pub fn deps(
+    id: &str,
+    mod_dir: &Path,
+    paks: &[PathBuf],
+    reverse: bool,
+    dot: Option<&Path>,
+    ctx: &OutputContext,
+) -> Result<()> {
+    let direction = if reverse {
+        Direction::Dependents
+    } else {
+        Direction::Dependencies
+    };
+
+    let pb = ctx
+        .show_progress()
+        .then(|| simple_spinner("Reading mod..."));
+    let mut graph = DependencyGraph::new();
+    graph
+        .add_dir(mod_dir)
+        .with_context(|| format!("Failed to read {}", mod_dir.display()))?;
+
+    let mut pak_files = Vec::new();
+    for path in paks {
+        if path.is_dir() {
+            let mut found: Vec<PathBuf> = fs::read_dir(path)?
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("pak")))
+                .collect();
+            found.sort();
+            pak_files.extend(found);
+        } else {
+            pak_files.push(path.clone());
+        }
+    }
+    for pak in &pak_files {
+        if let Some(ref pb) = pb {
+            pb.set_message(format!("Reading {}...", pak.display()));
+        }
+        graph
+            .add_pak(pak)
+            .with_context(|| format!("Failed to read {}", pak.display()))?;
+    }
+    if let Some(pb) = pb {
+        pb.finish_and_clear();
+    }
+
+    let entries = graph.closure(id, direction);
+    if let Some(dot_path) = dot {
+        fs::write(dot_path, graph.to_dot(id, direction))
+            .with_context(|| format!("Failed to write {}", dot_path.display()))?;
+    }
+
+    if ctx.is_json() {
+        return print_json(&serde_json::json!({
+            "id": id,
+            "asset": graph.node(id),
+            "direction": if reverse { "dependents" } else { "dependencies" },
+            "entries": entries,
+        }));
+    }
+
+    match graph.node(id) {
+        Some(node) => println!(
+            "{} ({}, {})",
+            node.name.as_deref().unwrap_or(&node.id),
+            node.kind,
+            node.source
+        ),
+        None => println!("{id} (not defined in the source set)"),
+    }
+    for entry in &entries {
+        let indent = "  ".repeat(entry.depth);
+        let label = match graph.node(&entry.id) {
+            Some(node) => format!(
+                "{} [{}]",
+                node.name.as_deref().unwrap_or(&entry.id),
+                node.kind
+            ),
+            None => format!("{} (unresolved)", entry.id),
+        };
+        println!("{indent}{label} <- {}", entry.attribute);
+    }
+
+    if !ctx.quiet {
+        let unresolved = entries.iter().filter(|e| !e.resolved).count();
+        println!();
+        println!(
+            "{} {}, {unresolved} unresolved, from {} documents",
+            entries.len(),
+            if reverse {
+                "dependents"
+            } else {
+                "dependencies"
+            },
+            graph.document_count()
+        );
+        if let Some(dot_path) = dot {
+            println!("Wrote {}", dot_path.display());
+        }
+    }
+    Ok(())
+}
+
+/// `mods visuals list` output
+#[derive(Debug, Serialize)]
+struct BankListing {
+    visuals: Vec<VisualResource>,
+    materials: Vec<MaterialResource>,
+    character_visuals: Vec<CharacterVisual>,
+}
+
+/// List the resources in a visual/material bank file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed.
+pub fn visuals_list(file: &Path, ctx: &OutputContext) -> Result<()> {
+    let banks = read_banks(file)?;
+    let listing = BankListing {
+        visuals: banks.visuals(),
+        materials: banks.materials(),
+        character_visuals: banks.character_visuals(),
+    };
+    if ctx.is_json() {
+        return print_json(&listing);
+    }
+
+    for visual in &listing.visuals {
+        println!("{} {}", visual.id, visual.name);
+        if !visual.slot.is_empty() {
+            println!("  Slot: {}", visual.slot);
+        }
+        println!("  Source: {}", visual.source_file);
+        for object in &visual.objects {
+            println!(
+                "  LOD {}: {} -> {}",
+                object.lod, object.object_id, object.material_id
+            );
+        }
+    }
+    for material in &listing.materials {
+        println!("{} {} (material)", material.id, material.name);
+        for texture in &material.textures {
+            println!("  {}: {}", texture.parameter, texture.texture_id);
+        }
+    }
+    for character in &listing.character_visuals {
+        println!("{} {} (character)", character.id, character.name);
+        if !character.body_set_visual.is_empty() {
+            println!("  Body set: {}", character.body_set_visual);
+        }
+        for slot in &character.slots {
+            println!("  {}: {}", slot.slot, slot.visual_resource);
+        }
+    }
+    println!(
+        "{} visuals, {} materials, {} character visuals",
+        listing.visuals.len(),
+        listing.materials.len(),
+        listing.character_visuals.len()
+    );
+    Ok(())
+}
+
+/// Set a visual's slot, or the visual a character visual wears in a slot
+///
+/// # Errors
+/// Returns an error if the file cannot be read or written, or the resource
+/// isn't in it.
+pub fn visuals_set_slot(
+    file: &Path,
+    resource: &str,
+    slot: &str,
+    visual: Option<&str>,
+    output: Option<&Path>,
+    ctx: &OutputContext,
+) -> Result<()> {
+    let mut banks = read_banks(file)?;
+    match visual {
+        Some(visual) => banks.set_character_slot(resource, slot, visual)?,
+        None => banks.set_slot(resource, slot)?,
+    }
+    let written = write_banks(&banks, file, output)?;
+    if !ctx.quiet {
+        println!("Set {resource} slot {slot} in {}", written.display());
+    }
+    Ok(())
+}
+
+/// Set the material a visual's mesh object is drawn with
+///
+/// # Errors
+/// Returns an error if the file cannot be read or written, or the resource
+/// or object isn't in it.
+pub fn visuals_set_material(
+    file: &Path,
+    resource: &str,
+    object: &str,
+    material: &str,
+    output: Option<&Path>,
+    ctx: &OutputContext,
+) -> Result<()> {
+    let mut banks = read_banks(file)?;
+    banks.set_object_material(resource, object, material)?;
+    let written = write_banks(&banks, file, output)?;
+    if !ctx.quiet {
+        println!(
+            "Set {object} material to {material} in {}",
+            written.display()
+        );
+    }
+    Ok(())
+}
+
+fn read_banks(file: &Path) -> Result<BankDocument> {
+    BankDocument::read(file).with_context(|| format!("Failed to read {}", file.display()))
+}
+
+/// Write edited banks to `output`, or back to `file`
+fn write_banks(banks: &BankDocument, file: &Path, output: Option<&Path>) -> Result<PathBuf> {
+    let path = output.unwrap_or(file);
+    banks
+        .write(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path.to_path_buf())
+}
+
 /// Collect all file paths from a mod directory (relative paths)
 fn collect_mod_files(dir: &Path) -> Result<Vec<String>> {
     let mut files = Vec::new();
@@ -550,3 +1194,39 @@ fn collect_files_recursive(base: &Path, current: &Path, files: &mut Vec<String>)
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_validate_json_shape() {
+        let report = ValidationReport {
+            source: PathBuf::from("MyMod.pak"),
+            valid: true,
+            structure: vec!["+ Mods/".to_string()],
+            warnings: Vec::new(),
+            integrity: Some(PakIntegrityResult {
+                valid: true,
+                file_count: 3,
+                total_size: 1024,
+                issues: Vec::new(),
+            }),
+            companions: Vec::new(),
+            synced: Vec::new(),
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "source": "MyMod.pak",
+                "valid": true,
+                "structure": ["+ Mods/"],
+                "warnings": [],
+                "integrity": { "valid": true, "file_count": 3, "total_size": 1024, "issues": [] },
+                "companions": [],
+                "synced": [],
+            })
+        );
+    }
+}
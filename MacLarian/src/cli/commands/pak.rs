@@ -5,10 +5,18 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use console::style;
 
+use serde::Serialize;
+
 use super::expand_globs;
-use crate::cli::progress::simple_bar;
+use crate::cli::output::{OutputContext, print_json, report_batch_failures};
+use crate::cli::progress::{TaskProgress, simple_bar};
+use crate::compression::CompressionSettings;
+use crate::error::Error;
 use crate::mods::validate_mod_structure;
-use crate::pak::{CompressionMethod, PakOperations};
+use crate::pak::{
+    CompressionMethod, DuplicateGroup, DuplicateReport, ExtractionPipeline, FilterSet,
+    PakCreateOptions, PakInfo, PakIssue, PakOperations, find_duplicates, lint_pak, pak_info,
+};
 
 /// Default BG3 installation paths
 const BG3_PATHS: &[&str] = &[
@@ -42,84 +50,6 @@ fn warn_if_bg3_path(destination: &Path) {
     }
 }
 
-/// Simple glob pattern matching (supports * and ?)
-fn matches_glob(pattern: &str, text: &str) -> bool {
-    let pattern_chars: Vec<char> = pattern.chars().collect();
-    let text_chars: Vec<char> = text.chars().collect();
-    matches_glob_recursive(&pattern_chars, &text_chars, 0, 0)
-}
-
-fn matches_glob_recursive(pattern: &[char], text: &[char], pi: usize, ti: usize) -> bool {
-    if pi == pattern.len() && ti == text.len() {
-        return true;
-    }
-    if pi == pattern.len() {
-        return false;
-    }
-
-    match pattern[pi] {
-        '*' => {
-            for i in ti..=text.len() {
-                if matches_glob_recursive(pattern, text, pi + 1, i) {
-                    return true;
-                }
-            }
-            false
-        }
-        '?' => {
-            if ti < text.len() {
-                matches_glob_recursive(pattern, text, pi + 1, ti + 1)
-            } else {
-                false
-            }
-        }
-        c => {
-            if ti < text.len() && text[ti].eq_ignore_ascii_case(&c) {
-                matches_glob_recursive(pattern, text, pi + 1, ti + 1)
-            } else {
-                false
-            }
-        }
-    }
-}
-
-/// Check if a string looks like a UUID (with or without dashes)
-fn looks_like_uuid(s: &str) -> bool {
-    let clean: String = s.chars().filter(char::is_ascii_hexdigit).collect();
-    clean.len() == 32
-}
-
-/// Normalize a UUID for matching (remove dashes, lowercase)
-fn normalize_uuid(s: &str) -> String {
-    s.chars()
-        .filter(char::is_ascii_hexdigit)
-        .collect::<String>()
-        .to_lowercase()
-}
-
-/// Check if a path contains a UUID (normalized comparison)
-fn path_contains_uuid(path: &str, uuid: &str) -> bool {
-    let normalized_uuid = normalize_uuid(uuid);
-    let path_lower = path.to_lowercase();
-
-    // Check for UUID with dashes
-    if normalized_uuid.len() == 32 {
-        let with_dashes = format!(
-            "{}-{}-{}-{}-{}",
-            &normalized_uuid[0..8],
-            &normalized_uuid[8..12],
-            &normalized_uuid[12..16],
-            &normalized_uuid[16..20],
-            &normalized_uuid[20..32]
-        );
-        if path_lower.contains(&with_dashes) || path_lower.contains(&normalized_uuid) {
-            return true;
-        }
-    }
-
-    false
-}
-
 /// Format byte size for human-readable output
 fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
@@ -139,13 +69,21 @@ fn format_size(bytes: u64) -> String {
 
 /// Extract files from PAK archive(s)
 ///
+/// When several PAKs are given, one that fails to extract doesn't stop the
+/// others; the failures are listed at the end. Extracted GR2s are processed
+/// by `pipeline` when it has any GR2 processing enabled.
+///
 /// # Errors
-/// Returns an error if glob expansion, PAK reading, or file writing fails.
+/// Returns an error if glob expansion, PAK reading, or file writing fails,
+/// and with `strict`, [`Error::ValidationFailed`]
+/// if any PAK of a batch failed.
 pub fn extract(
     sources: &[PathBuf],
     destination: &Path,
-    filter: Option<&str>,
+    filter: &FilterSet,
     file: Option<&str>,
+    strict: bool,
+    pipeline: &ExtractionPipeline,
     quiet: bool,
 ) -> anyhow::Result<()> {
     // Expand glob patterns
@@ -154,9 +92,18 @@ pub fn extract(
     // Warn if destination is BG3 install path
     warn_if_bg3_path(destination);
 
+    if pipeline.manifest_path().is_some() {
+        if !pipeline.processes_gr2() {
+            anyhow::bail!("--manifest requires --bundle, --convert-gr2 or --extract-textures");
+        }
+        if sources.len() > 1 {
+            anyhow::bail!("--manifest only supports a single source PAK");
+        }
+    }
+
     // Handle multiple sources (batch extraction)
     if sources.len() > 1 {
-        return extract_batch(&sources, destination, filter, quiet);
+        return extract_batch(&sources, destination, filter, strict, pipeline, quiet);
     }
 
     let source = &sources[0];
@@ -169,6 +116,9 @@ pub fn extract(
             paths.len(),
             source.display()
         );
+        if pipeline.processes_gr2() {
+            return extract_with_pipeline(source, destination, &paths, pipeline, quiet);
+        }
         let dest = destination.to_path_buf();
         PakOperations::extract_files(source, &dest, &paths)?;
         println!("Extraction complete");
@@ -176,28 +126,20 @@ pub fn extract(
     }
 
     // Filtered extraction
-    if let Some(pattern) = filter {
-        println!("Extracting files matching: {pattern}");
+    if !filter.is_empty() {
+        println!("Extracting files matching: {filter}");
 
-        let all_files = PakOperations::list(source)?;
-        let matching: Vec<String> = all_files
-            .iter()
-            .filter(|f| {
-                let filename = Path::new(f)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or(f);
-                matches_glob(pattern, filename) || matches_glob(pattern, f)
-            })
-            .cloned()
-            .collect();
+        let matching = PakOperations::list_filtered(source, filter)?;
 
         if matching.is_empty() {
-            println!("No files match pattern: {pattern}");
+            println!("No files match: {filter}");
             return Ok(());
         }
 
         println!("Found {} matching files", matching.len());
+        if pipeline.processes_gr2() {
+            return extract_with_pipeline(source, destination, &matching, pipeline, quiet);
+        }
 
         let dest = destination.to_path_buf();
         if !quiet {
@@ -232,7 +174,13 @@ pub fn extract(
     }
 
     // Full extraction
+    if pipeline.processes_gr2() {
+        let files = PakOperations::list(source)?;
+        println!("Extracting {} files from {}", files.len(), source.display());
+        return extract_with_pipeline(source, destination, &files, pipeline, quiet);
+    }
     let dest = destination.to_path_buf();
+    let cancel = crate::cli::cancel_token();
     if !quiet {
         let files = PakOperations::list(source)?;
         let total = files.len() as u64;
@@ -241,7 +189,7 @@ pub fn extract(
         let pb = simple_bar(total, "Extracting");
         let count = AtomicUsize::new(0);
 
-        PakOperations::extract_with_progress(source, &dest, &|progress| {
+        PakOperations::extract_cancellable(source, &dest, &cancel, &|progress| {
             let n = count.fetch_add(1, Ordering::SeqCst) + 1;
             pb.set_position(n as u64);
             if let Some(name) = &progress.current_file {
@@ -259,26 +207,64 @@ pub fn extract(
             source.display(),
             destination.display()
         );
-        PakOperations::extract(source, &dest)?;
+        PakOperations::extract_cancellable(source, &dest, &cancel, &|_| {})?;
     }
 
     println!("Extraction complete");
     Ok(())
 }
 
+/// Extract files and process the GR2s among them, then print a summary
+fn extract_with_pipeline<S: AsRef<str>>(
+    source: &Path,
+    destination: &Path,
+    files: &[S],
+    pipeline: &ExtractionPipeline,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let progress = TaskProgress::new("Extracting", files.len() as u64, !quiet);
+    let cancel = crate::cli::cancel_token();
+    let result = pipeline.extract_files_cancellable(source, destination, files, &cancel, &|p| {
+        progress.report(p);
+    })?;
+    progress.finish();
+
+    println!("Extraction complete");
+    println!("  Files extracted: {}", result.files_extracted);
+    println!("  GR2 files processed: {}", result.gr2s_processed);
+    println!("  GLB files created: {}", result.glb_files_created);
+    println!("  Textures extracted: {}", result.textures_extracted);
+    if let Some(manifest) = pipeline.manifest_path() {
+        println!("  Manifest: {}", manifest.display());
+    }
+    if !result.warnings.is_empty() {
+        println!("\nWarnings:");
+        for warning in &result.warnings {
+            println!("  - {warning}");
+        }
+    }
+    Ok(())
+}
+
 /// Batch extract multiple PAK files
 fn extract_batch(
     sources: &[PathBuf],
     destination: &Path,
-    filter: Option<&str>,
+    filter: &FilterSet,
+    strict: bool,
+    pipeline: &ExtractionPipeline,
     quiet: bool,
 ) -> anyhow::Result<()> {
     println!("Batch extracting {} PAK files", sources.len());
 
+    let cancel = crate::cli::cancel_token();
     let mut success = 0;
-    let mut failed = 0;
+    let mut failures: Vec<(PathBuf, String)> = Vec::new();
 
     for source in sources {
+        if cancel.is_cancelled() {
+            break;
+        }
         let pak_name = source
             .file_stem()
             .and_then(|s| s.to_str())
@@ -289,23 +275,29 @@ fn extract_batch(
             println!("Extracting: {}", source.display());
         }
 
-        match extract(std::slice::from_ref(source), &pak_dest, filter, None, quiet) {
+        match extract(
+            std::slice::from_ref(source),
+            &pak_dest,
+            filter,
+            None,
+            strict,
+            pipeline,
+            quiet,
+        ) {
             Ok(()) => success += 1,
-            Err(e) => {
-                eprintln!("Failed to extract {}: {e}", source.display());
-                failed += 1;
-            }
+            Err(e) => failures.push((source.clone(), format!("{e:#}"))),
         }
     }
 
     println!();
     println!("Batch extraction complete:");
     println!("  Success: {success}");
-    if failed > 0 {
-        println!("  Failed: {failed}");
+    if !failures.is_empty() {
+        println!("  Failed: {}", failures.len());
     }
 
-    Ok(())
+    cancel.checkpoint()?;
+    report_batch_failures(&failures, strict)
 }
 
 /// Create PAK file(s) from directory(ies)
@@ -315,7 +307,9 @@ fn extract_batch(
 pub fn create(
     sources: &[PathBuf],
     destination: &Path,
-    compression: &str,
+    compression: Option<&str>,
+    exclude: &[String],
+    priority: u8,
     quiet: bool,
 ) -> anyhow::Result<()> {
     // Expand glob patterns
@@ -324,18 +318,25 @@ pub fn create(
     // Warn if destination is BG3 install path
     warn_if_bg3_path(destination);
 
-    let method = match compression.to_lowercase().as_str() {
-        "lz4" => CompressionMethod::Lz4,
-        "zlib" => CompressionMethod::Zlib,
-        "none" => CompressionMethod::None,
-        other => {
-            anyhow::bail!("Unknown compression method: '{other}'. Valid options: lz4, zlib, none");
-        }
+    let compression = compression
+        .or(crate::cli::config().compression.as_deref())
+        .unwrap_or("lz4");
+    let method: CompressionSettings = compression.parse()?;
+    if CompressionMethod::from_settings(&method).is_none() {
+        anyhow::bail!(
+            "Unknown compression method: '{compression}'. Valid options: lz4, lz4:hc1-12, zlib, zlib:1-9, none"
+        );
+    }
+
+    let options = PakCreateOptions {
+        settings: method,
+        exclude: exclude.to_vec(),
+        priority,
     };
 
     // Handle multiple sources (batch creation)
     if sources.len() > 1 {
-        return create_batch(&sources, destination, method, quiet);
+        return create_batch(&sources, destination, &options, quiet);
     }
 
     let source = &sources[0];
@@ -351,27 +352,32 @@ pub fn create(
 
     // For single source, destination is the PAK filename
     println!(
-        "Creating PAK from {} to {} (compression: {:?})",
+        "Creating PAK from {} to {} (compression: {method})",
         source.display(),
         destination.display(),
-        method
     );
 
     let dest = destination.to_path_buf();
-    if !quiet {
-        let pb = simple_bar(100, "Creating PAK");
-        PakOperations::create_with_compression_and_progress(source, &dest, method, &|p| {
-            pb.set_position((p.percentage() * 100.0) as u64);
-            if let Some(ref file) = p.current_file {
-                pb.set_message(file.clone());
-            }
-        })?;
-        pb.finish_and_clear();
-    } else {
-        PakOperations::create_with_compression(source, &dest, method)?;
-    }
+    let cancel = crate::cli::cancel_token();
+    let progress = TaskProgress::new("Creating PAK", 0, !quiet);
+    let result = PakOperations::create_cancellable(source, &dest, &options, &cancel, &|p| {
+        // Count bytes while packing so large files keep the bar moving
+        if p.bytes_total > 0 {
+            let item = p.current_file.as_deref().unwrap_or(p.phase.as_str());
+            progress.update_bytes(p.bytes_done, p.bytes_total, item);
+        } else {
+            progress.report(p);
+        }
+    });
+    progress.finish();
+    let summary = result?;
 
-    println!("PAK created successfully");
+    println!(
+        "PAK created successfully: {} files, {}, MD5 {}",
+        summary.file_count,
+        format_size(summary.size),
+        summary.md5
+    );
     Ok(())
 }
 
@@ -379,7 +385,7 @@ pub fn create(
 fn create_batch(
     sources: &[PathBuf],
     destination: &Path,
-    method: CompressionMethod,
+    options: &PakCreateOptions,
     quiet: bool,
 ) -> anyhow::Result<()> {
     println!("Batch creating {} PAK files", sources.len());
@@ -389,17 +395,21 @@ fn create_batch(
 
     let mut success = 0;
     let mut failed = 0;
+    let cancel = crate::cli::cancel_token();
+    let progress = TaskProgress::new("Creating PAKs", sources.len() as u64, !quiet);
 
     for source in sources {
+        cancel.checkpoint()?;
         // Validate mod structure (checks for meta.lsx)
         let validation = validate_mod_structure(source);
         if !validation.valid {
-            println!(
+            progress.println(&format!(
                 "{} {}: No meta.lsx found. Use 'maclarian mods meta' to generate one first.",
                 style("Skipping").yellow().bold(),
                 style(source.display()).dim()
-            );
+            ));
             failed += 1;
+            progress.inc();
             continue;
         }
 
@@ -409,40 +419,23 @@ fn create_batch(
             .unwrap_or("unknown");
         let pak_dest = destination.join(format!("{pak_name}.pak"));
 
-        if !quiet {
-            println!("Creating: {}", pak_dest.display());
-        }
-
-        if !quiet {
-            let pb = simple_bar(100, "Creating");
-            match PakOperations::create_with_compression_and_progress(
-                source,
-                &pak_dest,
-                method,
-                &|p| {
-                    pb.set_position((p.percentage() * 100.0) as u64);
-                },
-            ) {
-                Ok(()) => {
-                    pb.finish_and_clear();
-                    success += 1;
-                }
-                Err(e) => {
-                    pb.finish_and_clear();
-                    eprintln!("Failed to create {}: {e}", pak_dest.display());
-                    failed += 1;
+        progress.start_item(&pak_dest.display().to_string());
+        match PakOperations::create_cancellable(source, &pak_dest, options, &cancel, &|_| {}) {
+            Ok(_) => {
+                if !quiet {
+                    progress.println(&format!("Created: {}", pak_dest.display()));
                 }
+                success += 1;
             }
-        } else {
-            match PakOperations::create_with_compression(source, &pak_dest, method) {
-                Ok(()) => success += 1,
-                Err(e) => {
-                    eprintln!("Failed to create {}: {e}", pak_dest.display());
-                    failed += 1;
-                }
+            Err(Error::Cancelled) => return Err(Error::Cancelled.into()),
+            Err(e) => {
+                progress.eprintln(&format!("Failed to create {}: {e}", pak_dest.display()));
+                failed += 1;
             }
         }
+        progress.inc();
     }
+    progress.finish();
 
     println!();
     println!("Batch creation complete:");
@@ -454,6 +447,44 @@ fn create_batch(
     Ok(())
 }
 
+/// A file in `pak list --detailed --json` output
+#[derive(Debug, Serialize)]
+struct PakListEntry {
+    path: String,
+    size: u64,
+    compressed_size: u64,
+}
+
+/// `pak list --detailed --json` output
+#[derive(Debug, Serialize)]
+struct PakListReport {
+    file_count: usize,
+    total_size: u64,
+    total_compressed_size: u64,
+    files: Vec<PakListEntry>,
+}
+
+impl PakListReport {
+    fn detailed(files: Vec<PakListEntry>) -> Self {
+        Self {
+            file_count: files.len(),
+            total_size: files.iter().map(|f| f.size).sum(),
+            total_compressed_size: files.iter().map(|f| f.compressed_size).sum(),
+            files,
+        }
+    }
+}
+
+/// Print a file count as text or `{"count": n}`
+fn print_count(count: usize, ctx: OutputContext) -> anyhow::Result<()> {
+    if ctx.is_json() {
+        print_json(&serde_json::json!({ "count": count }))
+    } else {
+        println!("{count}");
+        Ok(())
+    }
+}
+
 /// List contents of a PAK file
 ///
 /// # Errors
@@ -461,44 +492,31 @@ fn create_batch(
 pub fn list(
     source: &Path,
     detailed: bool,
-    filter: Option<&str>,
+    filter: &FilterSet,
     count: bool,
-    _quiet: bool,
+    ctx: &OutputContext,
 ) -> anyhow::Result<()> {
-    // Check if filter looks like a UUID for smart matching
-    let is_uuid_filter = filter.is_some_and(looks_like_uuid);
-
     if detailed {
         let entries = PakOperations::list_detailed(source)?;
-
-        // Filter entries
-        let filtered: Vec<_> = if let Some(pattern) = filter {
-            if is_uuid_filter {
-                entries
-                    .iter()
-                    .filter(|e| path_contains_uuid(&e.path.to_string_lossy(), pattern))
-                    .collect()
-            } else {
-                entries
-                    .iter()
-                    .filter(|e| {
-                        let path_str = e.path.to_string_lossy();
-                        let filename = e
-                            .path
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or(&path_str);
-                        matches_glob(pattern, filename) || matches_glob(pattern, &path_str)
-                    })
-                    .collect()
-            }
-        } else {
-            entries.iter().collect()
-        };
+        let filtered: Vec<_> = entries
+            .iter()
+            .filter(|e| filter.matches(&e.path.to_string_lossy()))
+            .collect();
 
         if count {
-            println!("{}", filtered.len());
-            return Ok(());
+            return print_count(filtered.len(), *ctx);
+        }
+
+        if ctx.is_json() {
+            let files: Vec<PakListEntry> = filtered
+                .iter()
+                .map(|e| PakListEntry {
+                    path: e.path.to_string_lossy().replace('\\', "/"),
+                    size: u64::from(e.size_decompressed),
+                    compressed_size: u64::from(e.size_compressed),
+                })
+                .collect();
+            return print_json(&PakListReport::detailed(files));
         }
 
         // Print header
@@ -543,34 +561,14 @@ pub fn list(
         );
     } else {
         // Simple listing
-        let files = PakOperations::list(source)?;
-
-        // Filter files
-        let filtered: Vec<_> = if let Some(pattern) = filter {
-            if is_uuid_filter {
-                files
-                    .iter()
-                    .filter(|f| path_contains_uuid(f, pattern))
-                    .collect()
-            } else {
-                files
-                    .iter()
-                    .filter(|f| {
-                        let filename = Path::new(f)
-                            .file_name()
-                            .and_then(|n| n.to_str())
-                            .unwrap_or(f);
-                        matches_glob(pattern, filename) || matches_glob(pattern, f)
-                    })
-                    .collect()
-            }
-        } else {
-            files.iter().collect()
-        };
+        let filtered = PakOperations::list_filtered(source, filter)?;
 
         if count {
-            println!("{}", filtered.len());
-            return Ok(());
+            return print_count(filtered.len(), *ctx);
+        }
+
+        if ctx.is_json() {
+            return print_json(&filtered);
         }
 
         for file in filtered {
@@ -580,3 +578,251 @@ pub fn list(
 
     Ok(())
 }
+
+/// Duplicate groups in `pak info --dupes --json` output
+#[derive(Debug, Serialize)]
+struct PakDupesJson<'a> {
+    files_hashed: usize,
+    wasted: u64,
+    groups: &'a [DuplicateGroup],
+}
+
+/// `pak info --json` output
+#[derive(Debug, Serialize)]
+struct PakInfoReport<'a> {
+    pak: String,
+    #[serde(flatten)]
+    info: &'a PakInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicates: Option<PakDupesJson<'a>>,
+}
+
+/// Show size totals of a PAK and, with `dupes`, its duplicate entries
+///
+/// # Errors
+/// Returns an error if the PAK cannot be read or the duplicate pass is
+/// cancelled.
+pub fn info(source: &Path, top: usize, dupes: bool, ctx: &OutputContext) -> anyhow::Result<()> {
+    let info = pak_info(source, top)?;
+    let duplicates = if dupes {
+        let cancel = crate::cli::cancel_token();
+        let progress = TaskProgress::new("Hashing", 0, ctx.show_progress());
+        let report = find_duplicates(source, &cancel, &|p| progress.report(p));
+        progress.finish();
+        Some(report?)
+    } else {
+        None
+    };
+
+    if ctx.is_json() {
+        return print_json(&PakInfoReport {
+            pak: source.display().to_string(),
+            info: &info,
+            duplicates: duplicates.as_ref().map(|d| PakDupesJson {
+                files_hashed: d.files_hashed,
+                wasted: d.wasted(),
+                groups: &d.groups,
+            }),
+        });
+    }
+
+    println!("{}", source.display());
+    println!("  Files: {}", info.files);
+    println!("  Priority: {}", info.priority);
+    println!(
+        "  Size:  {} ({} compressed, {:.1}%)",
+        format_size(info.size),
+        format_size(info.compressed_size),
+        info.ratio() * 100.0
+    );
+
+    if !info.extensions.is_empty() {
+        println!();
+        println!(
+            "{:<8}  {:>6}  {:>10}  {:>10}  {:>6}",
+            "EXT", "FILES", "SIZE", "COMPRESSED", "RATIO"
+        );
+        for ext in &info.extensions {
+            println!(
+                "{:<8}  {:>6}  {:>10}  {:>10}  {:>5.1}%",
+                if ext.extension.is_empty() {
+                    "(none)"
+                } else {
+                    &ext.extension
+                },
+                ext.files,
+                format_size(ext.size),
+                format_size(ext.compressed_size),
+                ext.ratio() * 100.0
+            );
+        }
+    }
+
+    if !info.largest.is_empty() {
+        println!();
+        println!("Largest entries:");
+        for entry in &info.largest {
+            println!(
+                "{:>10}  {:>10}  {}",
+                format_size(entry.size),
+                format_size(entry.compressed_size),
+                entry.path
+            );
+        }
+    }
+
+    if let Some(report) = &duplicates {
+        print_duplicates(report);
+    }
+    Ok(())
+}
+
+fn print_duplicates(report: &DuplicateReport) {
+    println!();
+    if report.groups.is_empty() {
+        println!(
+            "No duplicate content ({} entries compared)",
+            report.files_hashed
+        );
+        return;
+    }
+
+    let copies: usize = report.groups.iter().map(|g| g.paths.len() - 1).sum();
+    println!(
+        "{}: {} group(s), {copies} extra cop{}, {} wasted",
+        style("Duplicates").bold(),
+        report.groups.len(),
+        if copies == 1 { "y" } else { "ies" },
+        format_size(report.wasted())
+    );
+    for group in &report.groups {
+        println!(
+            "  {} wasted: {} copies of {}",
+            format_size(group.wasted()),
+            group.paths.len(),
+            format_size(group.size)
+        );
+        for path in &group.paths {
+            println!("    {path}");
+        }
+    }
+}
+
+/// An issue in `pak lint --json` output
+#[derive(Debug, Serialize)]
+struct PakLintEntry {
+    pak: String,
+    path: String,
+    kind: &'static str,
+    severity: &'static str,
+    suggestion: String,
+}
+
+impl PakLintEntry {
+    fn new(pak: &Path, issue: &PakIssue) -> Self {
+        Self {
+            pak: pak.display().to_string(),
+            path: issue.path.clone(),
+            kind: issue.kind.as_str(),
+            severity: if issue.kind.is_warning() {
+                "warning"
+            } else {
+                "error"
+            },
+            suggestion: issue.suggestion.clone(),
+        }
+    }
+}
+
+/// `pak lint --json` output
+#[derive(Debug, Serialize)]
+struct PakLintReport {
+    paks_checked: usize,
+    errors: usize,
+    warnings: usize,
+    issues: Vec<PakLintEntry>,
+}
+
+/// Lint the entry paths of one or more PAK files
+///
+/// Prints each issue as `pak: path: kind: suggestion`. Warnings (see
+/// [`PakIssueKind::is_warning`]) don't affect the exit code.
+///
+/// [`PakIssueKind::is_warning`]: crate::pak::PakIssueKind::is_warning
+///
+/// # Errors
+/// Returns an error if glob expansion fails or a PAK cannot be read, and
+/// [`Error::ValidationFailed`] if any errors were found.
+pub fn lint(sources: &[PathBuf], ctx: &OutputContext) -> anyhow::Result<()> {
+    let paks = expand_globs(sources)?;
+    let quiet = ctx.quiet;
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut report = Vec::new();
+
+    for pak in &paks {
+        for issue in &lint_pak(pak)? {
+            if ctx.is_json() {
+                report.push(PakLintEntry::new(pak, issue));
+            }
+            if issue.kind.is_warning() {
+                warnings += 1;
+                if quiet {
+                    continue;
+                }
+            } else {
+                errors += 1;
+            }
+            if !ctx.is_json() {
+                println!("{}: {issue}", pak.display());
+            }
+        }
+    }
+
+    if ctx.is_json() {
+        print_json(&PakLintReport {
+            paks_checked: paks.len(),
+            errors,
+            warnings,
+            issues: report,
+        })?;
+    } else if !quiet {
+        println!(
+            "\n{} PAK(s) checked: {errors} error(s), {warnings} warning(s)",
+            paks.len()
+        );
+    }
+
+    if errors > 0 {
+        return Err(Error::ValidationFailed { failures: errors }.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_list_json_shape() {
+        let report = PakListReport::detailed(vec![PakListEntry {
+            path: "Mods/MyMod/meta.lsx".to_string(),
+            size: 100,
+            compressed_size: 40,
+        }]);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "file_count": 1,
+                "total_size": 100,
+                "total_compressed_size": 40,
+                "files": [
+                    { "path": "Mods/MyMod/meta.lsx", "size": 100, "compressed_size": 40 }
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! CLI commands for `.lsv` savegame inspection
+
+use std::path::Path;
+
+use crate::cli::output::{OutputContext, print_json};
+use crate::formats::lsv::{extract_lsv, list_lsv, read_save_info};
+use crate::mods::Version64;
+
+/// Show the save name, game version and active mods of a save
+///
+/// # Errors
+/// Returns an error if the save cannot be read or has no valid `meta.lsf`.
+pub fn info(path: &Path, ctx: &OutputContext) -> anyhow::Result<()> {
+    let info = read_save_info(path)?;
+    let version = |v: Option<i64>| v.map(|v| Version64::from(v).to_string());
+
+    if ctx.is_json() {
+        let mods: Vec<_> = info
+            .mods
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "name": m.name,
+                    "folder": m.folder,
+                    "uuid": m.uuid,
+                    "version": version(m.version64),
+                })
+            })
+            .collect();
+        return print_json(&serde_json::json!({
+            "path": path,
+            "save_name": info.save_name,
+            "game_version": info.game_version,
+            "leader_name": info.leader_name,
+            "level_name": info.level_name,
+            "mods": mods,
+        }));
+    }
+
+    println!("Save: {}", path.display());
+    println!();
+    println!(
+        "Save name:    {}",
+        info.save_name.as_deref().unwrap_or("(not recorded)")
+    );
+    println!("Game version: {}", info.game_version);
+    if let Some(leader) = &info.leader_name {
+        println!("Leader:       {leader}");
+    }
+    if let Some(level) = &info.level_name {
+        println!("Level:        {level}");
+    }
+    println!();
+    println!("Mods ({}):", info.mods.len());
+    for m in &info.mods {
+        match version(m.version64) {
+            Some(v) => println!("  {} v{v} ({})", m.name, m.uuid),
+            None => println!("  {} ({})", m.name, m.uuid),
+        }
+    }
+    Ok(())
+}
+
+/// List the files in a save
+///
+/// # Errors
+/// Returns an error if the save cannot be read.
+pub fn list(path: &Path, ctx: &OutputContext) -> anyhow::Result<()> {
+    let files = list_lsv(path)?;
+    if ctx.is_json() {
+        return print_json(&files);
+    }
+    for file in &files {
+        println!("{file}");
+    }
+    if !ctx.quiet {
+        println!();
+        println!("{} files", files.len());
+    }
+    Ok(())
+}
+
+/// Extract every file in a save
+///
+/// # Errors
+/// Returns an error if the save cannot be read or the files cannot be written.
+pub fn extract(path: &Path, destination: &Path, quiet: bool) -> anyhow::Result<()> {
+    extract_lsv(path, destination)?;
+    if !quiet {
+        println!("Extracted {} to {}", path.display(), destination.display());
+    }
+    Ok(())
+}
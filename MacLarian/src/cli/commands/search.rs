@@ -0,0 +1,202 @@
+//! CLI commands for searching PAK contents
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::expand_globs;
+use crate::cli::output::{OutputContext, print_json};
+use crate::cli::progress::{ProgressEvent, TaskProgress};
+use crate::error::Error;
+use crate::pak::{LineMatch, SEARCHABLE_EXTENSIONS, TextMatcher, find_game_paks, grep_pak};
+
+/// A matching file in `search grep --json` output
+#[derive(Debug, Serialize)]
+struct GrepEntry {
+    pak: String,
+    path: String,
+    lines: Vec<LineMatch>,
+}
+
+/// Expand wildcards and directories into the PAKs to search
+///
+/// Directories (including a game install) are searched for `.pak` files in
+/// load order, see [`find_game_paks`]. A PAK given more than once is only
+/// searched once.
+fn pak_sources(sources: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paks = Vec::new();
+    for source in expand_globs(sources)? {
+        if source.is_dir() {
+            let found = find_game_paks(&source, false)?;
+            if found.is_empty() {
+                anyhow::bail!("No .pak files found in {}", source.display());
+            }
+            paks.extend(found);
+        } else {
+            paks.push(source);
+        }
+    }
+    let mut seen = HashSet::new();
+    paks.retain(|pak| seen.insert(pak.clone()));
+    Ok(paks)
+}
+
+/// Search the text files of PAKs for a literal string or regex
+///
+/// `paks` may contain wildcard patterns and directories, which are expanded
+/// to the PAKs inside them. A PAK that can't be read is reported and
+/// skipped.
+///
+/// Prints each match as `pak:path:line: text`.
+///
+/// # Errors
+/// Returns an error if glob expansion fails, the pattern or a type is
+/// invalid, no PAK can be read, or the search is cancelled.
+pub fn grep(
+    paks: &[PathBuf],
+    pattern: &str,
+    regex: bool,
+    ignore_case: bool,
+    types: &[String],
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let paks = pak_sources(paks)?;
+    let matcher = TextMatcher::new(pattern, regex, !ignore_case)?;
+
+    let types: Vec<String> = types
+        .iter()
+        .map(|t| t.trim().trim_start_matches('.').to_lowercase())
+        .collect();
+    if let Some(unknown) = types
+        .iter()
+        .find(|t| !SEARCHABLE_EXTENSIONS.contains(&t.as_str()))
+    {
+        anyhow::bail!(
+            "Cannot search '{unknown}' files; searchable types are {}",
+            SEARCHABLE_EXTENSIONS.join(", ")
+        );
+    }
+    let types: Vec<&str> = types.iter().map(String::as_str).collect();
+
+    let cancel = crate::cli::cancel_token();
+    let mut report = Vec::new();
+    let mut matches = 0;
+    let mut failed = 0;
+    let mut first_error = None;
+
+    let progress = TaskProgress::new("Searching", paks.len() as u64, ctx.show_progress());
+    for pak in &paks {
+        let name = pak.file_name().map_or_else(
+            || pak.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        progress.start_item(&name);
+        let hits = match grep_pak(pak, &matcher, &types, &cancel, &|p| {
+            progress.start_item(&format!("{name}: {}", p.item().unwrap_or_default()));
+        }) {
+            Ok(hits) => hits,
+            Err(Error::Cancelled) => return Err(Error::Cancelled.into()),
+            Err(e) => {
+                progress.eprintln(&format!("Failed to search {}: {e}", pak.display()));
+                first_error.get_or_insert(e);
+                failed += 1;
+                progress.inc();
+                continue;
+            }
+        };
+        progress.inc();
+
+        for hit in hits {
+            matches += hit.lines.len();
+            if ctx.is_json() {
+                report.push(GrepEntry {
+                    pak: pak.display().to_string(),
+                    path: hit.path,
+                    lines: hit.lines,
+                });
+                continue;
+            }
+            for line in &hit.lines {
+                progress.println(&format!(
+                    "{}:{}:{}: {}",
+                    pak.display(),
+                    hit.path,
+                    line.line_number,
+                    line.line
+                ));
+            }
+        }
+    }
+    progress.finish();
+
+    if let Some(e) = first_error.filter(|_| failed == paks.len()) {
+        return Err(anyhow::Error::from(e)
+            .context(format!("None of the {failed} PAK(s) could be searched")));
+    }
+    if ctx.is_json() {
+        print_json(&report)?;
+    } else if !ctx.quiet {
+        let skipped = if failed > 0 {
+            format!(", {failed} unreadable")
+        } else {
+            String::new()
+        };
+        println!(
+            "\n{matches} matching line(s) in {} PAK(s){skipped}",
+            paks.len() - failed
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pak_sources_expand_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = dir.path().join("Data");
+        std::fs::create_dir_all(data.join("Localization")).unwrap();
+        for pak in [
+            "Shared.pak",
+            "Gustav.pak",
+            "Textures.pak",
+            "Textures_1.pak",
+            "Localization/English.pak",
+        ] {
+            std::fs::write(data.join(pak), b"").unwrap();
+        }
+        let mods = dir.path().join("Mods");
+        std::fs::create_dir_all(&mods).unwrap();
+        std::fs::write(mods.join("MyMod.PAK"), b"").unwrap();
+
+        let paks = pak_sources(&[
+            dir.path().to_path_buf(),
+            data.join("Shared.pak"),
+            mods.clone(),
+        ])
+        .unwrap();
+        let names: Vec<_> = paks
+            .iter()
+            .map(|p| p.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        let expected: Vec<PathBuf> = [
+            "Data/Shared.pak",
+            "Data/Gustav.pak",
+            "Data/Textures.pak",
+            "Data/Localization/English.pak",
+            "Mods/MyMod.PAK",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        assert_eq!(names, expected);
+
+        let empty = dir.path().join("Empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        assert!(pak_sources(&[empty]).is_err());
+    }
+}
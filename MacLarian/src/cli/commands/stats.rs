@@ -0,0 +1,331 @@
+//! CLI commands for stats `.txt` file operations
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Serialize;
+
+use super::expand_globs;
+use crate::cli::output::{OutputContext, print_json};
+use crate::cli::progress::TaskProgress;
+use crate::error::Error;
+use crate::formats::stats::{
+    ResolvedKind, ResolvedTable, StatsIssue, TreasureIndex, drop_quantity, lint_stats,
+};
+
+/// An issue in `stats lint --json` output
+#[derive(Debug, Serialize)]
+struct LintIssue {
+    file: String,
+    line: usize,
+    kind: &'static str,
+    severity: &'static str,
+    message: String,
+}
+
+impl LintIssue {
+    fn new(path: &Path, issue: &StatsIssue) -> Self {
+        Self {
+            file: path.display().to_string(),
+            line: issue.line,
+            kind: issue.kind.as_str(),
+            severity: if issue.kind.is_warning() {
+                "warning"
+            } else {
+                "error"
+            },
+            message: issue.message.clone(),
+        }
+    }
+}
+
+/// `stats lint --json` output
+#[derive(Debug, Serialize)]
+struct LintReport {
+    files_checked: usize,
+    errors: usize,
+    warnings: usize,
+    issues: Vec<LintIssue>,
+}
+
+/// Lint one or more stats files
+///
+/// Prints each issue as `file:line: kind: message`. Warnings (unknown lines,
+/// duplicate data keys) don't affect the exit code.
+///
+/// # Errors
+/// Returns an error if glob expansion fails or a file cannot be read, and
+/// [`Error::ValidationFailed`] if any errors were found.
+pub fn lint(paths: &[PathBuf], ctx: &OutputContext) -> anyhow::Result<()> {
+    let paths = expand_globs(paths)?;
+    let quiet = ctx.quiet;
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    let mut report = Vec::new();
+
+    for path in &paths {
+        let issues = lint_stats(path)?;
+        for issue in &issues {
+            if ctx.is_json() {
+                report.push(LintIssue::new(path, issue));
+            }
+            if issue.kind.is_warning() {
+                warnings += 1;
+                if quiet {
+                    continue;
+                }
+            } else {
+                errors += 1;
+            }
+            if !ctx.is_json() {
+                println!("{}:{issue}", path.display());
+            }
+        }
+    }
+
+    if ctx.is_json() {
+        print_json(&LintReport {
+            files_checked: paths.len(),
+            errors,
+            warnings,
+            issues: report,
+        })?;
+    } else if !quiet {
+        println!(
+            "\n{} file(s) checked: {errors} error(s), {warnings} warning(s)",
+            paths.len()
+        );
+    }
+
+    if errors > 0 {
+        return Err(Error::ValidationFailed { failures: errors }.into());
+    }
+
+    Ok(())
+}
+
+/// A step of a chain in `stats treasure --item --json` output
+#[derive(Debug, Serialize)]
+struct ChainLink {
+    table: String,
+    subtable: String,
+    quantity: String,
+    odds: String,
+    object: String,
+}
+
+/// `stats treasure --item --json` output
+#[derive(Debug, Serialize)]
+struct ItemReport {
+    item: String,
+    chains: Vec<Vec<ChainLink>>,
+    cycles: Vec<Vec<String>>,
+    truncated: bool,
+}
+
+/// `stats treasure --table --json` output
+#[derive(Debug, Serialize)]
+struct TableReport<'a> {
+    table: &'a str,
+    items: Vec<&'a str>,
+    cycles: &'a [Vec<String>],
+}
+
+/// Show the treasure tables that drop `item`, or what `table` drops
+///
+/// `sources` are PAKs, directories of PAKs, or extracted mod folders; the
+/// game's Data folder is used when empty.
+///
+/// # Errors
+/// Returns an error if a source cannot be read, no treasure tables are
+/// found, or `table` isn't defined.
+pub fn treasure(
+    sources: &[PathBuf],
+    item: Option<&str>,
+    table: Option<&str>,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let index = load_treasure_index(sources, ctx.show_progress())?;
+
+    let cycles = if let Some(item) = item {
+        let found = index.sources_of(item);
+        if ctx.is_json() {
+            print_json(&ItemReport {
+                item: found.item.clone(),
+                chains: found
+                    .chains
+                    .iter()
+                    .map(|chain| {
+                        chain
+                            .links
+                            .iter()
+                            .map(|link| ChainLink {
+                                table: link.table.clone(),
+                                subtable: link.subtable.clone(),
+                                quantity: drop_quantity(&link.drops),
+                                odds: link.odds(),
+                                object: link.object.clone(),
+                            })
+                            .collect()
+                    })
+                    .collect(),
+                cycles: found.cycles.clone(),
+                truncated: found.truncated,
+            })?;
+        } else if found.chains.is_empty() {
+            println!("No treasure table drops {}", found.item);
+        } else {
+            println!(
+                "{} drops from {} chain(s) in {} table(s):",
+                found.item,
+                found.chains.len(),
+                index.len()
+            );
+            for chain in &found.chains {
+                println!("  {chain}");
+            }
+            if found.truncated {
+                println!("  ... (stopped after {} chains)", found.chains.len());
+            }
+        }
+        found.cycles
+    } else if let Some(table) = table {
+        let contents = index
+            .contents(table)
+            .with_context(|| format!("No treasure table named {table}"))?;
+        if ctx.is_json() {
+            print_json(&TableReport {
+                table: &contents.table.name,
+                items: contents.table.items(),
+                cycles: &contents.cycles,
+            })?;
+        } else {
+            print_table(&contents.table, 0);
+            println!("\n{} distinct item(s)", contents.table.items().len());
+        }
+        contents.cycles
+    } else {
+        anyhow::bail!("Give --item or --table");
+    };
+
+    for cycle in &cycles {
+        eprintln!(
+            "Warning: treasure table cycle {} > {}",
+            cycle.join(" > "),
+            cycle[0]
+        );
+    }
+    Ok(())
+}
+
+/// Read the treasure tables of every source
+fn load_treasure_index(sources: &[PathBuf], show_progress: bool) -> anyhow::Result<TreasureIndex> {
+    let sources = if sources.is_empty() {
+        vec![
+            crate::cli::config()
+                .resolve_bg3_path(None)
+                .filter(|dir| dir.is_dir())
+                .context("No --index given and the game's Data folder wasn't found")?,
+        ]
+    } else {
+        expand_globs(sources)?
+    };
+
+    // Directories of PAKs are read PAK by PAK; other directories as loose files
+    let mut paks = Vec::new();
+    let mut dirs = Vec::new();
+    for source in sources {
+        if source.is_dir() {
+            let mut in_dir: Vec<PathBuf> = std::fs::read_dir(&source)?
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("pak")))
+                .collect();
+            in_dir.sort();
+            if in_dir.is_empty() {
+                dirs.push(source);
+            } else {
+                paks.extend(in_dir);
+            }
+        } else {
+            paks.push(source);
+        }
+    }
+
+    let mut index = TreasureIndex::new();
+    let progress = TaskProgress::new("Reading", (paks.len() + dirs.len()) as u64, show_progress);
+    for pak in &paks {
+        progress.start_item(&pak.display().to_string());
+        index
+            .add_pak(pak)
+            .with_context(|| format!("Failed to read {}", pak.display()))?;
+        progress.inc();
+    }
+    for dir in &dirs {
+        progress.start_item(&dir.display().to_string());
+        index
+            .add_dir(dir)
+            .with_context(|| format!("Failed to read {}", dir.display()))?;
+        progress.inc();
+    }
+    progress.finish();
+
+    if index.is_empty() {
+        anyhow::bail!("No treasure tables found in the given sources");
+    }
+    Ok(index)
+}
+
+/// Print a resolved table as an indented tree
+fn print_table(table: &ResolvedTable, depth: usize) {
+    let indent = "  ".repeat(depth);
+    if depth == 0 {
+        println!("{}", table.name);
+    }
+    for subtable in &table.subtables {
+        println!(
+            "{indent}  subtable \"{}\" ({})",
+            subtable.header,
+            drop_quantity(&subtable.drops)
+        );
+        for object in &subtable.objects {
+            let note = match &object.kind {
+                ResolvedKind::Item | ResolvedKind::Table(_) => "",
+                ResolvedKind::Cycle => " [cycle]",
+                ResolvedKind::Category => " [category]",
+            };
+            println!(
+                "{indent}    {} ({}){note}",
+                object.name,
+                subtable.odds(object)
+            );
+            if let ResolvedKind::Table(nested) = &object.kind {
+                print_table(nested, depth + 2);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::stats::lint_stats_str;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_lint_json_shape() {
+        let issues = lint_stats_str("data \"Damage\" \"1d6\"\n");
+        let issue = LintIssue::new(Path::new("Data.txt"), &issues[0]);
+        assert_eq!(
+            serde_json::to_value(&issue).unwrap(),
+            serde_json::json!({
+                "file": "Data.txt",
+                "line": 1,
+                "kind": "data outside entry",
+                "severity": "error",
+                "message": "data \"Damage\" \"1d6\"",
+            })
+        );
+    }
+}
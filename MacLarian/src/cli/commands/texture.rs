@@ -1,34 +1,307 @@
 //! CLI commands for texture operations
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use super::convert::parse_dds_format;
+use super::definitions::TextureTargetArg;
+use crate::cli::output::{OutputContext, print_json};
+use crate::cli::progress::TaskProgress;
+use crate::converter::{
+    ColorSpace, DdsInfo, TextureBatchOptions, TextureBatchStatus,
+    batch_convert_textures_cancellable, convert_png_to_dds_with_color_space,
+    dds_bytes_to_png_bytes, dds_info, tag_png_color_space,
+};
+use crate::error::{Error, ResultExt};
 
 /// Show info about a DDS texture file
 ///
 /// # Errors
 /// Returns an error if the file cannot be read or is not a valid DDS.
-pub fn info(path: &Path) -> anyhow::Result<()> {
-    let file = std::fs::File::open(path)?;
-    let dds = ddsfile::Dds::read(file).map_err(|e| anyhow::anyhow!("Failed to read DDS: {e}"))?;
+pub fn info(path: &Path, ctx: &OutputContext) -> anyhow::Result<()> {
+    let data = std::fs::read(path)?;
+    let info = dds_info(&data).with_path(path)?;
+    let warnings: Vec<String> = info.issues.iter().map(ToString::to_string).collect();
+
+    if ctx.is_json() {
+        return print_json(&info_json(path, &info, &warnings));
+    }
+
+    let optional = |value: Option<u32>| value.map_or_else(|| "-".to_string(), |v| v.to_string());
 
     println!("DDS Information: {}", path.display());
     println!();
-    println!("Dimensions: {}x{}", dds.get_width(), dds.get_height());
-    println!("Depth: {}", dds.get_depth());
-    println!("Mip levels: {}", dds.get_num_mipmap_levels());
-    println!("Array layers: {}", dds.get_num_array_layers());
-
-    if let Some(dxgi) = dds.get_dxgi_format() {
-        println!("Format: {dxgi:?} (DXGI)");
-    } else if let Some(d3d) = dds.get_d3d_format() {
-        println!("Format: {d3d:?} (D3D)");
+    println!("Dimensions: {}x{}", info.width, info.height);
+    println!("Depth: {}", info.depth);
+    println!("Format: {}", info.format);
+    println!(
+        "Color space: {}",
+        info.color_space
+            .map_or("unspecified (no DX10 header)", ColorSpace::as_str)
+    );
+    println!("Mip levels: {}", info.mip_count);
+    println!("Array layers: {}", info.array_size);
+    println!("Pitch: {}", optional(info.pitch));
+    println!("Linear size: {}", optional(info.linear_size));
+    println!("Data size: {} bytes", info.data_size);
+    println!("Caps: {:#010x}", info.caps);
+    println!("Caps2: {:#010x}", info.caps2);
+
+    println!();
+    println!("Pixel format:");
+    println!("  Flags: {:#010x}", info.pixel_format_flags);
+    println!("  FourCC: {}", info.fourcc.as_deref().unwrap_or("-"));
+    println!("  RGB bit count: {}", optional(info.rgb_bit_count));
+    if let Some([r, g, b, a]) = info.bit_masks {
+        println!("  Masks (RGBA): {r:#010x} {g:#010x} {b:#010x} {a:#010x}");
+    }
+
+    if let Some(dxgi_format) = &info.dxgi_format {
+        println!();
+        println!("DX10 header:");
+        println!("  DXGI format: {dxgi_format}");
+        if let Some(dimension) = &info.resource_dimension {
+            println!("  Resource dimension: {dimension}");
+        }
+        println!("  Array size: {}", info.array_size);
+    }
+
+    if !warnings.is_empty() {
+        println!();
+        println!("Warnings:");
+        for warning in &warnings {
+            println!("  - {warning}");
+        }
+    }
+
+    Ok(())
+}
+
+/// `texture info --json` output
+fn info_json(path: &Path, info: &DdsInfo, warnings: &[String]) -> serde_json::Value {
+    serde_json::json!({
+        "path": path,
+        "info": info,
+        "warnings": warnings,
+    })
+}
+
+/// Color space chosen by the `--srgb` and `--linear` flags
+#[must_use]
+pub fn color_space_arg(srgb: bool, linear: bool) -> Option<ColorSpace> {
+    if srgb {
+        Some(ColorSpace::Srgb)
+    } else if linear {
+        Some(ColorSpace::Linear)
     } else {
-        println!("Format: Unknown");
+        None
     }
+}
+
+/// Convert one DDS file to PNG or PNG file to DDS, keeping its color space
+///
+/// `color_space` overrides the one the source records.
+///
+/// # Errors
+/// Returns an error if the source is not a DDS or PNG file, or cannot be
+/// read or converted.
+pub fn convert(
+    source: &Path,
+    output: Option<&Path>,
+    texture_format: &str,
+    color_space: Option<ColorSpace>,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let extension = source
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let target = match extension.as_str() {
+        "dds" => "png",
+        "png" => "dds",
+        _ => anyhow::bail!("{} is not a DDS or PNG file", source.display()),
+    };
+    let output = output.map_or_else(|| source.with_extension(target), Path::to_path_buf);
 
-    // Calculate data size
-    if let Ok(data) = dds.get_data(0) {
-        println!("Data size (mip 0): {} bytes", data.len());
+    let data = std::fs::read(source)?;
+    let written = if target == "png" {
+        let detected = dds_info(&data).with_path(source)?.color_space;
+        let png = dds_bytes_to_png_bytes(&data).with_path(source)?;
+        let png = match color_space {
+            Some(color_space) => tag_png_color_space(png, color_space),
+            None => png,
+        };
+        std::fs::write(&output, png)?;
+        color_space.or(detected)
+    } else {
+        convert_png_to_dds_with_color_space(
+            source,
+            &output,
+            parse_dds_format(texture_format)?,
+            color_space,
+            &|_| {},
+        )
+        .with_path(source)?;
+        dds_info(&std::fs::read(&output)?)
+            .with_path(&output)?
+            .color_space
+    };
+    let untagged_png = target == "dds" && written.is_none();
+
+    if ctx.is_json() {
+        return print_json(&serde_json::json!({
+            "source": source,
+            "output": output,
+            "color_space": written,
+        }));
+    }
+    if !ctx.quiet {
+        let color_space = written.map_or("color space unspecified", ColorSpace::as_str);
+        println!(
+            "Converted {} -> {} ({color_space})",
+            source.display(),
+            output.display()
+        );
+        if untagged_png {
+            println!("  The PNG has no color space; pass --srgb or --linear to record one");
+        }
+    }
+    Ok(())
+}
+
+/// Convert every DDS (or PNG) file under a directory
+///
+/// Output keeps the layout relative to `source`, under `output` or next to
+/// each file. Files are converted in parallel; a summary is printed at the
+/// end.
+///
+/// # Errors
+/// Returns an error if the directory cannot be walked or the DDS format is
+/// unknown, [`Error::ValidationFailed`] if any file failed to convert, and
+/// [`Error::Cancelled`] if interrupted.
+pub fn batch_convert(
+    source: &Path,
+    output: Option<&Path>,
+    to: TextureTargetArg,
+    texture_format: &str,
+    skip_existing: bool,
+    color_space: Option<ColorSpace>,
+    ctx: &OutputContext,
+) -> anyhow::Result<()> {
+    let input_ext = match to {
+        TextureTargetArg::Png => "dds",
+        TextureTargetArg::Dds => "png",
+    };
+    let options = TextureBatchOptions {
+        dds_format: parse_dds_format(texture_format)?,
+        color_space,
+        skip_existing,
+        root: Some(source.to_path_buf()),
+        ..TextureBatchOptions::default()
+    };
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    for entry in WalkDir::new(source) {
+        let entry = entry.map_err(Error::from)?;
+        let matches = entry
+            .path()
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(input_ext));
+        if entry.file_type().is_file() && matches {
+            files.push(entry.into_path());
+        }
     }
+    files.sort();
 
+    if files.is_empty() {
+        if !ctx.is_json() {
+            println!("No .{input_ext} files found in {}", source.display());
+        }
+        return Ok(());
+    }
+
+    let cancel = crate::cli::cancel_token();
+    let progress = TaskProgress::new("Converting", files.len() as u64, ctx.show_progress());
+    let report = batch_convert_textures_cancellable(&files, output, &options, &cancel, &|p| {
+        progress.report(p);
+    });
+    progress.finish();
+
+    if ctx.is_json() {
+        let files: Vec<_> = report
+            .entries
+            .iter()
+            .map(|entry| {
+                let (status, error) = match &entry.status {
+                    TextureBatchStatus::Converted => ("converted", None),
+                    TextureBatchStatus::Skipped => ("skipped", None),
+                    TextureBatchStatus::Failed(e) => ("failed", Some(e.to_string())),
+                };
+                serde_json::json!({
+                    "source": entry.source,
+                    "output": entry.output,
+                    "status": status,
+                    "error": error,
+                })
+            })
+            .collect();
+        print_json(&serde_json::json!({
+            "converted": report.converted(),
+            "skipped": report.skipped(),
+            "failed": report.failed(),
+            "cancelled": report.cancelled,
+            "files": files,
+        }))?;
+    } else if !ctx.quiet || report.failed() > 0 {
+        println!();
+        println!("Texture conversion complete:");
+        println!("  Converted: {}", report.converted());
+        if report.skipped() > 0 {
+            println!("  Skipped (up to date): {}", report.skipped());
+        }
+        if report.failed() > 0 {
+            println!("  Failed: {}", report.failed());
+            for (path, e) in report.failures() {
+                eprintln!("    {}: {e}", path.display());
+            }
+        }
+    }
+
+    cancel.checkpoint()?;
+    if report.failed() > 0 {
+        return Err(Error::ValidationFailed {
+            failures: report.failed(),
+        }
+        .into());
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::{DdsFormat, png_image_to_dds_bytes};
+    use image::{DynamicImage, Rgba, RgbaImage};
+
+    #[test]
+    fn test_info_json_parses() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 4, Rgba([255, 0, 0, 255])));
+        let mut dds = png_image_to_dds_bytes(&img, DdsFormat::BC1).unwrap();
+        dds.truncate(dds.len() - 8);
+        let info = dds_info(&dds).unwrap();
+        let warnings: Vec<String> = info.issues.iter().map(ToString::to_string).collect();
+
+        let path = Path::new("Red.dds");
+        let text = serde_json::to_string_pretty(&info_json(path, &info, &warnings)).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["path"], "Red.dds");
+        assert_eq!(json["info"]["width"], 8);
+        assert_eq!(json["info"]["height"], 4);
+        assert_eq!(json["info"]["fourcc"], "DXT1");
+        assert_eq!(json["info"]["mip_count"], 1);
+        // The truncated pixel data is reported as a warning
+        assert_eq!(json["warnings"].as_array().unwrap().len(), 1);
+        assert_eq!(json["warnings"][0], warnings[0].as_str());
+    }
+}
@@ -0,0 +1,60 @@
+//! CLI commands for UUID generation and GUID conversion
+
+use anyhow::{Result, bail};
+
+use super::definitions::UuidFormatArg;
+use crate::cli::output::{OutputContext, print_json};
+use crate::utils::{
+    UuidFormat, byte_swap_guid, format_guid, format_uuid, generate_uuids, parse_guid,
+};
+
+/// Print `count` new UUIDs, one per line
+///
+/// # Errors
+/// Returns an error if JSON output cannot be written.
+pub fn generate(count: usize, format: UuidFormatArg, ctx: &OutputContext) -> Result<()> {
+    let format = match format {
+        UuidFormatArg::Standard => UuidFormat::Standard,
+        UuidFormatArg::Compact => UuidFormat::Compact,
+        UuidFormatArg::Handle => UuidFormat::Larian,
+    };
+    let uuids = generate_uuids(format, count);
+
+    if ctx.is_json() {
+        return print_json(&uuids);
+    }
+    for uuid in uuids {
+        println!("{uuid}");
+    }
+    Ok(())
+}
+
+/// Print a GUID in hyphenated, bare, byte-swapped and handle forms
+///
+/// # Errors
+/// Returns an error if the value isn't a GUID.
+pub fn convert(value: &str, ctx: &OutputContext) -> Result<()> {
+    let Some(bytes) = parse_guid(value) else {
+        bail!("Not a GUID: {value} (expected 32 hex digits, optionally hyphenated)");
+    };
+
+    let hyphenated = format_guid(&bytes);
+    let bare = format_uuid(&bytes, UuidFormat::Compact);
+    let swapped = format_guid(&byte_swap_guid(&bytes));
+    let handle = format_uuid(&bytes, UuidFormat::Larian);
+
+    if ctx.is_json() {
+        return print_json(&serde_json::json!({
+            "hyphenated": hyphenated,
+            "bare": bare,
+            "byte_swapped": swapped,
+            "handle": handle,
+        }));
+    }
+
+    println!("Hyphenated:    {hyphenated}");
+    println!("Bare:          {bare}");
+    println!("Byte-swapped:  {swapped}");
+    println!("Handle:        {handle}");
+    Ok(())
+}
@@ -8,18 +8,29 @@ use anyhow::{Context, Result};
 use indicatif::ProgressBar;
 
 use super::expand_globs;
-use crate::cli::progress::{bar_style, spinner_style};
+use crate::cli::output::{OutputContext, print_json, report_batch_failures};
+use crate::cli::progress::{TaskProgress, bar_style, spinner_style};
+use crate::compression::CompressionSettings;
+use crate::utils::ErrorPolicy;
 use crate::virtual_texture;
 use crate::virtual_texture::builder::{
-    SourceTexture, TileCompressionPreference, VirtualTextureBuilder,
+    SourceFormat, SourceTexture, TileCompressionPreference, VirtualTextureBuilder,
 };
-use crate::virtual_texture::{VTexPhase, VTexProgress};
+use crate::virtual_texture::{ExtractOptions, VTexNaming, VTexPhase, VTexProgress};
 
 /// List textures in a GTS file
 ///
+/// With `metadata`, the text output ends with the full `FourCC` tree.
+///
 /// # Errors
 /// Returns an error if the GTS file cannot be read or parsed.
-pub fn list(gts_path: &Path, detailed: bool, output: Option<&Path>) -> Result<()> {
+pub fn list(
+    gts_path: &Path,
+    detailed: bool,
+    metadata: bool,
+    output: Option<&Path>,
+    ctx: &OutputContext,
+) -> Result<()> {
     let info = virtual_texture::list_gts(gts_path)
         .with_context(|| format!("Failed to parse GTS file: {}", gts_path.display()))?;
 
@@ -31,6 +42,10 @@ pub fn list(gts_path: &Path, detailed: bool, output: Option<&Path>) -> Result<()
         return Ok(());
     }
 
+    if ctx.is_json() {
+        return print_json(&info);
+    }
+
     // Print to CLI
     println!("Virtual Texture Set: {}", gts_path.display());
     println!("GUID: {:02x?}", info.guid);
@@ -44,6 +59,20 @@ pub fn list(gts_path: &Path, detailed: bool, output: Option<&Path>) -> Result<()
     println!("Page files: {}", info.page_files.len());
     println!();
 
+    if !info.textures.is_empty() {
+        println!("Textures:");
+        for texture in &info.textures {
+            println!(
+                "  {}  {}  ({}x{})",
+                texture.guid.as_deref().unwrap_or("-"),
+                texture.name,
+                texture.width,
+                texture.height
+            );
+        }
+        println!();
+    }
+
     if detailed {
         println!("Page files:");
         for (i, pf) in info.page_files.iter().enumerate() {
@@ -56,29 +85,102 @@ pub fn list(gts_path: &Path, detailed: bool, output: Option<&Path>) -> Result<()
         );
     }
 
+    if metadata {
+        let tree = virtual_texture::read_gts_metadata(gts_path)?;
+        println!();
+        println!("Metadata:");
+        print!("{tree}");
+    }
+
+    Ok(())
+}
+
+/// Rename a texture and/or change its GUID in a GTS file
+///
+/// # Errors
+/// Returns an error if the GTS metadata cannot be read or written, the GUID
+/// is malformed, or the texture to edit can't be determined.
+pub fn set_meta(
+    gts_path: &Path,
+    texture: Option<&str>,
+    name: Option<&str>,
+    guid: Option<&str>,
+    ctx: &OutputContext,
+) -> Result<()> {
+    let info = virtual_texture::set_texture_metadata(gts_path, texture, name, guid)?;
+
+    if ctx.is_json() {
+        return print_json(&info);
+    }
+
+    println!(
+        "Updated {}: {}  {}",
+        gts_path.display(),
+        info.guid.as_deref().unwrap_or("-"),
+        info.name
+    );
     Ok(())
 }
 
 /// Extract textures from GTS/GTP files
 ///
+/// `gtex_filter` keeps only the GTP files whose name contains it. `guid`
+/// instead picks the one texture with that GUID (or GUID prefix) out of all
+/// the sources. `naming` picks the output file names and folder layout.
+///
 /// # Errors
-/// Returns an error if glob expansion, GTS/GTP reading, or file writing fails.
+/// Returns an error if glob expansion, GTS/GTP reading, or file writing fails,
+/// if `guid` doesn't identify exactly one texture, and with `strict`,
+/// [`Error::ValidationFailed`](crate::Error::ValidationFailed) if any file of a
+/// batch failed.
 pub fn extract(
     sources: &[PathBuf],
     output_dir: &Path,
     gtex_filter: Option<&str>,
+    guid: Option<&str>,
     layers: &[usize],
+    naming: VTexNaming,
+    strict: bool,
     quiet: bool,
 ) -> Result<()> {
     // Expand glob patterns
     let sources = expand_globs(sources)?;
+    let options = ExtractOptions {
+        layers: layers.to_vec(),
+        naming,
+        ..ExtractOptions::default()
+    };
+
+    if let Some(guid) = guid {
+        let (gts_path, texture) = virtual_texture::find_texture_by_guid(&sources, guid)?;
+        if !quiet {
+            println!(
+                "Texture {} ({}) in {}",
+                texture.name,
+                texture.guid.as_deref().unwrap_or(guid),
+                gts_path.display()
+            );
+        }
+        return extract_single(&gts_path, output_dir, Some(&texture.name), &options, quiet);
+    }
 
     // Handle multiple sources (batch extraction)
     if sources.len() > 1 {
-        return extract_batch(&sources, output_dir, layers, quiet);
+        return extract_batch(&sources, output_dir, gtex_filter, &options, strict, quiet);
     }
 
-    let input_path = &sources[0];
+    extract_single(&sources[0], output_dir, gtex_filter, &options, quiet)
+}
+
+/// Extract one GTS or GTP file, keeping only GTP files whose name contains
+/// `gtex_filter` (case-insensitive)
+fn extract_single(
+    input_path: &Path,
+    output_dir: &Path,
+    gtex_filter: Option<&str>,
+    options: &ExtractOptions,
+    quiet: bool,
+) -> Result<()> {
     let ext = input_path
         .extension()
         .map(|e| e.to_string_lossy().to_lowercase())
@@ -90,8 +192,8 @@ pub fn extract(
         if let Some(gtex) = gtex_filter {
             println!("Texture filter: {gtex}");
         }
-        if !layers.is_empty() {
-            println!("Layer filter: {layers:?}");
+        if !options.layers.is_empty() {
+            println!("Layer filter: {:?}", options.layers);
         }
     }
 
@@ -108,9 +210,16 @@ pub fn extract(
         Some(pb)
     };
 
-    let result = virtual_texture::extract_gts_file(
+    let name_filter = gtex_filter.map(str::to_lowercase);
+    let result = virtual_texture::extract_gts_textures(
         input_path,
         Some(output_dir),
+        |filename: &str| {
+            name_filter
+                .as_deref()
+                .is_none_or(|f| filename.to_lowercase().contains(f))
+        },
+        options,
         |progress: &VTexProgress| {
             if let Some(ref pb) = pb {
                 let desc = progress
@@ -134,6 +243,12 @@ pub fn extract(
         pb.finish_and_clear();
     }
 
+    if let Some(gtex) = gtex_filter
+        && result.gtp_count == 0
+    {
+        anyhow::bail!("No GTP files in {} match '{gtex}'", input_path.display());
+    }
+
     if !quiet {
         if is_gtp {
             println!(
@@ -158,13 +273,18 @@ pub fn extract(
 fn extract_batch(
     sources: &[PathBuf],
     output_dir: &Path,
-    layers: &[usize],
+    gtex_filter: Option<&str>,
+    options: &ExtractOptions,
+    strict: bool,
     quiet: bool,
 ) -> Result<()> {
     if !quiet {
         println!("Batch extracting {} files", sources.len());
-        if !layers.is_empty() {
-            println!("Layer filter: {layers:?}");
+        if let Some(gtex) = gtex_filter {
+            println!("Texture filter: {gtex}");
+        }
+        if !options.layers.is_empty() {
+            println!("Layer filter: {:?}", options.layers);
         }
     }
 
@@ -176,53 +296,37 @@ fn extract_batch(
         )
     })?;
 
-    // Create progress bar
-    let pb = if quiet {
-        None
-    } else {
-        let pb = ProgressBar::new(sources.len() as u64);
-        pb.set_style(bar_style());
-        Some(pb)
-    };
-
-    let result =
-        virtual_texture::extract_batch(sources, Some(output_dir), |progress: &VTexProgress| {
-            if let Some(ref pb) = pb {
-                let desc = progress
-                    .current_file
-                    .as_deref()
-                    .unwrap_or(progress.phase.as_str());
-                pb.set_position(progress.current as u64);
-                pb.set_message(desc.to_string());
-            }
-        });
-
-    if let Some(ref pb) = pb {
-        pb.finish_and_clear();
-    }
+    let cancel = crate::cli::cancel_token();
+    let pb = TaskProgress::new("Extracting", sources.len() as u64, !quiet);
+    let outcome = virtual_texture::extract_batch_with_policy(
+        sources,
+        Some(output_dir),
+        gtex_filter,
+        options,
+        ErrorPolicy::Collect,
+        &cancel,
+        |progress: &VTexProgress| pb.report(progress),
+    );
+    pb.finish();
 
     if !quiet {
+        let texture_count: usize = outcome
+            .succeeded
+            .iter()
+            .map(|(_, result)| result.texture_count)
+            .sum();
         println!();
         println!("Batch extraction complete:");
-        println!("  Succeeded: {}", result.success_count);
-        println!("  Failed: {}", result.error_count);
-        println!("  Total textures: {}", result.texture_count);
-
-        if result.error_count > 0 {
-            println!();
-            println!("Errors:");
-            for msg in &result.results {
-                if msg.starts_with("Failed") {
-                    println!("  {msg}");
-                }
-            }
-        }
+        println!("  Succeeded: {}", outcome.succeeded.len());
+        println!("  Failed: {}", outcome.failure_count());
+        println!("  Total textures: {texture_count}");
     }
 
-    Ok(())
+    cancel.checkpoint()?;
+    report_batch_failures(&outcome.failed, strict)
 }
 
-/// Create a virtual texture set from DDS source textures
+/// Create a virtual texture set from DDS, PNG or TGA source textures
 ///
 /// # Errors
 /// Returns an error if texture loading, processing, or output writing fails.
@@ -233,6 +337,7 @@ pub fn create(
     base_map: Option<&Path>,
     normal_map: Option<&Path>,
     physical_map: Option<&Path>,
+    source_format: SourceFormat,
     compression: &str,
     no_embed_mip: bool,
     quiet: bool,
@@ -246,7 +351,7 @@ pub fn create(
     });
 
     // Build source texture - either from explicit paths or auto-detect
-    let mut texture = SourceTexture::new(name);
+    let mut texture = SourceTexture::new(name).with_source_format(source_format);
 
     if let Some(p) = base_map {
         texture = texture.with_base_map(p);
@@ -290,7 +395,7 @@ pub fn create(
     if !texture.has_any_layer() {
         anyhow::bail!(
             "No texture layers found. Provide --base, --normal, or --physical, or ensure\n\
-             DDS files are named with suffixes like _BaseMap, _NormalMap, _PhysicalMap"
+             DDS, PNG or TGA files are named with suffixes like _BaseMap, _NormalMap, _PhysicalMap"
         );
     }
 
@@ -298,12 +403,18 @@ pub fn create(
     let mut builder = VirtualTextureBuilder::new().name(name).add_texture(texture);
 
     // Set compression preference
-    let pref = match compression.to_lowercase().as_str() {
-        "raw" => TileCompressionPreference::Raw,
-        "fastlz" => TileCompressionPreference::FastLZ,
-        _ => anyhow::bail!("Unknown compression: {compression}. Use: raw or fastlz (default)"),
+    let settings: CompressionSettings = if compression.eq_ignore_ascii_case("raw") {
+        CompressionSettings::none()
+    } else {
+        compression.parse()?
+    };
+    let Some(pref) = TileCompressionPreference::from_settings(&settings) else {
+        anyhow::bail!("Unknown compression: {compression}. Use: raw, fastlz (default) or fastlz:2");
     };
     builder = builder.compression(pref);
+    if let Some(level) = settings.level {
+        builder = builder.compression_level(level);
+    }
 
     // Disable mip embedding if requested
     if no_embed_mip {
@@ -334,9 +445,10 @@ pub fn create(
 
     // Track current phase to detect transitions
     let last_phase = AtomicUsize::new(0);
+    let cancel = crate::cli::cancel_token();
 
     let result = builder
-        .build_with_progress(output_dir, |progress: &VTexProgress| {
+        .build_cancellable(output_dir, &cancel, |progress: &VTexProgress| {
             if let Some(ref pb) = pb {
                 let phase_num = progress.phase as usize;
                 let prev_phase = last_phase.swap(phase_num, Ordering::SeqCst);
@@ -388,28 +500,82 @@ pub fn create(
     Ok(())
 }
 
-/// Find a DDS file with one of the given suffixes in a directory
+/// Layer file extensions, in order of preference
+const LAYER_EXTENSIONS: [&str; 3] = ["dds", "png", "tga"];
+
+/// Find a DDS, PNG or TGA file with one of the given suffixes in a directory
 fn find_layer_file(dir: &Path, suffixes: &[&str]) -> Option<PathBuf> {
     if !dir.is_dir() {
         return None;
     }
 
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext.eq_ignore_ascii_case("dds") {
-                        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
-                        for suffix in suffixes {
-                            if stem.to_lowercase().ends_with(&suffix.to_lowercase()) {
-                                return Some(path);
-                            }
-                        }
-                    }
-                }
+    let files: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+
+    // Prefer DDS over images when a layer exists in several formats
+    for layer_ext in LAYER_EXTENSIONS {
+        for path in &files {
+            let is_ext = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(layer_ext));
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            if is_ext
+                && suffixes
+                    .iter()
+                    .any(|suffix| stem.ends_with(&suffix.to_lowercase()))
+            {
+                return Some(path.clone());
             }
         }
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virtual_texture::writer::fourcc::build_metadata_tree;
+    use crate::virtual_texture::writer::gts_writer::GtsWriter;
+
+    #[test]
+    fn test_list_json_parses() {
+        let guid = *b"0123456789abcdef";
+        let mut writer = GtsWriter::new(guid, 144, 144, 8, 0x0010_0000);
+        writer.set_fourcc_tree(build_metadata_tree(
+            "Stone",
+            512,
+            256,
+            0,
+            0,
+            &[("BaseMap", "BaseColor")],
+            &guid,
+        ));
+        let dir = tempfile::tempdir().unwrap();
+        let gts = dir.path().join("Stone.gts");
+        writer.write(&mut fs::File::create(&gts).unwrap()).unwrap();
+
+        let info = virtual_texture::list_gts(&gts).unwrap();
+        let text = serde_json::to_string_pretty(&info).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["tile_width"], 144);
+        assert_eq!(json["tile_border"], 8);
+        assert_eq!(json["page_files"], serde_json::json!([]));
+        assert_eq!(
+            json["textures"],
+            serde_json::json!([{
+                "name": "Stone",
+                "guid": "30313233-3435-3637-3839-616263646566",
+                "width": 512,
+                "height": 256,
+            }])
+        );
+    }
+}
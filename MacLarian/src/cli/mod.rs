@@ -2,10 +2,31 @@
 
 /// CLI command definitions and implementations.
 pub mod commands;
+pub mod output;
 pub mod progress;
 
-use clap::Parser;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::OnceLock;
+
+use anyhow::Context;
+use clap::{CommandFactory, Parser};
 use commands::Commands;
+use output::{ErrorReport, OutputContext, OutputFormat};
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::utils::CancelToken;
+
+/// Settings loaded at startup, with the file they came from
+struct LoadedConfig {
+    path: Option<PathBuf>,
+    config: Config,
+}
+
+static CONFIG: OnceLock<LoadedConfig> = OnceLock::new();
+
+static CANCEL: OnceLock<CancelToken> = OnceLock::new();
 
 #[derive(Parser)]
 #[command(name = "maclarian")]
@@ -22,22 +43,160 @@ Examples:
   maclarian convert meta.lsf meta.lsx
   maclarian gr2 from-gr2 model.GR2 model.glb
 
+Exit codes:
+  0 success, 1 other error, 2 usage, 3 IO/not found, 4 format/parse error,
+  5 validation failures, 130 cancelled
+
 Documentation: https://github.com/CyberDeco/MacPak/wiki/MacLarian-CLI-Commands")]
 struct Cli {
+    /// Settings file to use instead of ~/.config/macpak/config.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Print results as JSON on stdout (diagnostics go to stderr)
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Suppress progress output and only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Increase log verbosity (-v info, -vv debug, -vvv trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// On failure, print a JSON error report to stderr
+    #[arg(long, global = true)]
+    error_json: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
-/// Run the `MacLarian` CLI
+/// Run the `MacLarian` CLI and return the process exit code
 ///
-/// # Errors
-/// Returns an error if the CLI command execution fails.
-pub fn run_cli() -> anyhow::Result<()> {
-    // Setup logging
-    tracing_subscriber::fmt::init();
+/// Exit codes: `0` success, `1` other failure, `2` usage error, `3` IO/not
+/// found, `4` format/parse error, `5` validation failures, `130` cancelled.
+#[must_use]
+pub fn run_cli() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // The flag wasn't parsed, so look for it directly
+        Err(e) if e.use_stderr() && std::env::args_os().any(|a| a == "--error-json") => {
+            let report = ErrorReport::usage(e.render().to_string().trim().to_string());
+            print_error_report(&report);
+            return ExitCode::from(report.exit_code);
+        }
+        Err(e) => e.exit(),
+    };
+
+    let error_json = cli.error_json;
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            let report = ErrorReport::new(&err);
+            if error_json {
+                print_error_report(&report);
+            } else {
+                eprintln!("Error: {err:?}");
+            }
+            ExitCode::from(report.exit_code)
+        }
+    }
+}
+
+fn print_error_report(report: &ErrorReport) {
+    match serde_json::to_string(report) {
+        Ok(json) => eprintln!("{json}"),
+        Err(_) => eprintln!("Error: {}", report.message),
+    }
+}
 
-    let cli = Cli::parse();
-    cli.command.execute()?;
+fn run(cli: &Cli) -> anyhow::Result<()> {
+    let ctx = OutputContext {
+        format: if cli.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
+        },
+        quiet: cli.quiet,
+        verbose: cli.verbose,
+    };
+
+    // Setup logging (stderr, so stdout stays clean for --json)
+    tracing_subscriber::fmt()
+        .with_max_level(ctx.log_level())
+        .with_writer(std::io::stderr)
+        .init();
+
+    // First Ctrl-C stops the running command at its next checkpoint; a second one exits
+    let cancel = cancel_token();
+    if let Err(e) = ctrlc::set_handler(move || {
+        if cancel.is_cancelled() {
+            std::process::exit(130);
+        }
+        cancel.cancel();
+    }) {
+        tracing::debug!("Failed to install Ctrl-C handler: {e}");
+    }
+
+    let path = cli.config.clone().or_else(Config::default_path);
+    let config = match (&cli.config, &path) {
+        (Some(explicit), _) if !explicit.exists() => {
+            return Err(Error::FileNotFound {
+                path: explicit.clone(),
+            })
+            .context("Failed to load --config");
+        }
+        (_, Some(path)) => Config::load(path)
+            .with_context(|| format!("Failed to load config from {}", path.display()))?,
+        (_, None) => Config::default(),
+    };
+    let _ = CONFIG.set(LoadedConfig { path, config });
+
+    cli.command.execute(&ctx)?;
 
     Ok(())
 }
+
+/// Settings loaded from the config file (defaults if none was loaded)
+#[must_use]
+pub fn config() -> &'static Config {
+    &CONFIG
+        .get_or_init(|| LoadedConfig {
+            path: None,
+            config: Config::default(),
+        })
+        .config
+}
+
+/// Output directory: the explicit one, else `output-dir` from the config
+///
+/// # Errors
+/// Returns [`Error::OutputDirNotSet`] if neither is available.
+pub fn output_dir(explicit: Option<&Path>) -> crate::error::Result<PathBuf> {
+    explicit
+        .map(Path::to_path_buf)
+        .or_else(|| config().output_dir.clone())
+        .ok_or(Error::OutputDirNotSet)
+}
+
+/// Path of the active config file, if one could be determined
+#[must_use]
+pub fn config_path() -> Option<&'static Path> {
+    CONFIG.get().and_then(|c| c.path.as_deref())
+}
+
+/// Token cancelled by Ctrl-C, for commands that run long library operations
+#[must_use]
+pub fn cancel_token() -> CancelToken {
+    CANCEL.get_or_init(CancelToken::new).clone()
+}
+
+/// Build the top-level clap [`Command`](clap::Command) without parsing arguments
+///
+/// Used for shell completions and man page generation.
+#[must_use]
+pub fn command() -> clap::Command {
+    Cli::command()
+}
@@ -0,0 +1,200 @@
+//! Output formatting shared by all CLI commands
+//!
+//! Informational commands print human-readable text by default and a single
+//! JSON document on stdout with `--json`. Progress and diagnostics always go
+//! to stderr so JSON output can be piped.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::error::{Error, ErrorKind};
+
+/// How informational commands render their results
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// JSON document on stdout.
+    Json,
+}
+
+/// Global output options passed to every command
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OutputContext {
+    /// Result format.
+    pub format: OutputFormat,
+    /// Suppress progress bars and extra output.
+    pub quiet: bool,
+    /// Verbosity level from repeated `-v` flags.
+    pub verbose: u8,
+}
+
+impl OutputContext {
+    /// Whether results should be printed as JSON
+    #[must_use]
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Whether progress bars and extra text should be shown
+    ///
+    /// Always false for JSON output so stdout stays machine-readable.
+    #[must_use]
+    pub fn show_progress(&self) -> bool {
+        !self.quiet && !self.is_json()
+    }
+
+    /// Maximum tracing level for these options
+    #[must_use]
+    pub fn log_level(&self) -> tracing::Level {
+        if self.quiet {
+            return tracing::Level::ERROR;
+        }
+        match self.verbose {
+            0 => tracing::Level::WARN,
+            1 => tracing::Level::INFO,
+            2 => tracing::Level::DEBUG,
+            _ => tracing::Level::TRACE,
+        }
+    }
+}
+
+/// Print a value as pretty JSON on stdout
+///
+/// # Errors
+/// Returns an error if the value cannot be serialized.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Print the items a batch command failed on to stderr
+///
+/// With `strict`, any failure is turned into an error so the command exits
+/// non-zero; otherwise the batch still counts as a success.
+///
+/// # Errors
+/// Returns [`Error::ValidationFailed`] if `strict` is set and any item failed.
+pub fn report_batch_failures<E: std::fmt::Display>(
+    failures: &[(PathBuf, E)],
+    strict: bool,
+) -> anyhow::Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+    eprintln!();
+    eprintln!("{} item(s) failed:", failures.len());
+    for (path, error) in failures {
+        eprintln!("  {}: {error}", path.display());
+    }
+    if strict {
+        return Err(Error::ValidationFailed {
+            failures: failures.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Structured error printed to stderr with `--error-json`
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    /// Error category (`usage`, `io`, `format`, `validation`, `cancelled`, `other`).
+    pub kind: &'static str,
+    /// Process exit code.
+    pub exit_code: u8,
+    /// Full error message including context.
+    pub message: String,
+    /// File the error refers to, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// PAK entry the error refers to, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pak_path: Option<String>,
+    /// Byte offset the error refers to, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+impl ErrorReport {
+    /// Build a report from a command error
+    ///
+    /// The category comes from the first [`maclarian::Error`](Error) in the
+    /// chain; a bare IO error counts as [`ErrorKind::Io`].
+    #[must_use]
+    pub fn new(err: &anyhow::Error) -> Self {
+        let lib_error = err.chain().find_map(|e| e.downcast_ref::<Error>());
+        let is_io = err
+            .chain()
+            .any(<dyn std::error::Error>::is::<std::io::Error>);
+        let kind = match lib_error {
+            Some(e) => e.kind(),
+            None if is_io => ErrorKind::Io,
+            None => ErrorKind::Other,
+        };
+        Self::with_kind(kind, format!("{err:#}"), lib_error)
+    }
+
+    /// Build a report for a command-line usage error
+    #[must_use]
+    pub fn usage(message: String) -> Self {
+        Self::with_kind(ErrorKind::Usage, message, None)
+    }
+
+    fn with_kind(kind: ErrorKind, message: String, lib_error: Option<&Error>) -> Self {
+        Self {
+            kind: kind.as_str(),
+            exit_code: kind.exit_code(),
+            message,
+            file: lib_error.and_then(Error::path).map(PathBuf::from),
+            pak_path: lib_error.and_then(Error::pak_path).map(str::to_string),
+            offset: lib_error.and_then(Error::offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_error_report_json() {
+        let err = Err::<(), _>(Error::FileNotFound {
+            path: PathBuf::from("Shared.pak"),
+        })
+        .context("Failed to list PAK")
+        .unwrap_err();
+
+        assert_eq!(
+            serde_json::to_value(ErrorReport::new(&err)).unwrap(),
+            serde_json::json!({
+                "kind": "io",
+                "exit_code": 3,
+                "message": "Failed to list PAK: file not found: Shared.pak",
+                "file": "Shared.pak",
+            })
+        );
+
+        let err = anyhow::Error::from(
+            Error::InvalidAttributeType(97)
+                .at_offset(0x1c)
+                .with_pak_path("Public/Test/meta.lsf")
+                .with_path("Test.pak"),
+        );
+        assert_eq!(
+            serde_json::to_value(ErrorReport::new(&err)).unwrap(),
+            serde_json::json!({
+                "kind": "format",
+                "exit_code": 4,
+                "message": "invalid attribute type: 97 (in Test.pak, entry Public/Test/meta.lsf, at offset 0x1c)",
+                "file": "Test.pak",
+                "pak_path": "Public/Test/meta.lsf",
+                "offset": 0x1c,
+            })
+        );
+    }
+}
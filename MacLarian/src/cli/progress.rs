@@ -3,11 +3,18 @@
 //! Provides yarnish-style progress display with step indicators, emojis,
 //! and multi-progress support for batch operations.
 
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use console::{Emoji, style};
 use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
 
+use crate::converter::{ConvertProgress, Gr2Progress, ImageProgress};
+use crate::mods::ModProgress;
+use crate::pak::PakProgress;
+use crate::virtual_texture::VTexProgress;
+
 // =============================================================================
 // Emoji Constants (with ASCII fallbacks for terminals without emoji support)
 // =============================================================================
@@ -102,6 +109,42 @@ pub fn bar_style_with_percent() -> ProgressStyle {
         .expect("valid template")
 }
 
+/// Progress bar style with ETA and a per-item line
+///
+/// Format:
+/// ```text
+/// Creating PAK [████████░░░░░░░░] 50/100 (12s)
+///   Public/MyMod/Stats/Generated/Data/Armor.txt
+/// ```
+///
+/// # Panics
+/// Panics if the template string is invalid (this is a compile-time constant).
+#[must_use]
+pub fn eta_bar_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{prefix:.bold} [{bar:40.cyan/blue}] {pos}/{len} ({eta})\n  {wide_msg:.dim}")
+        .expect("valid template")
+}
+
+/// Progress bar style counting bytes, with ETA and a per-item line
+///
+/// Format:
+/// ```text
+/// Creating PAK [████████░░░░░░░░] 1.2 GiB/2.4 GiB (12s)
+///   Public/MyMod/Assets/Textures/Atlas.dds
+/// ```
+///
+/// # Panics
+/// Panics if the template string is invalid (this is a compile-time constant).
+#[must_use]
+pub fn bytes_bar_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template(
+            "{prefix:.bold} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})\n  {wide_msg:.dim}",
+        )
+        .expect("valid template")
+}
+
 // =============================================================================
 // Multi-Progress Helpers (Option B)
 // =============================================================================
@@ -171,3 +214,131 @@ pub fn simple_bar(total: u64, msg: &str) -> ProgressBar {
     pb.set_message(msg.to_string());
     pb
 }
+
+// =============================================================================
+// Library Progress Adapter
+// =============================================================================
+
+/// Library progress reports that can drive a [`TaskProgress`] bar
+pub trait ProgressEvent {
+    /// `(current, total)` item counts.
+    fn position(&self) -> (usize, usize);
+    /// Item currently being processed, if any.
+    fn item(&self) -> Option<&str>;
+}
+
+macro_rules! impl_progress_event {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl ProgressEvent for $ty {
+                fn position(&self) -> (usize, usize) {
+                    (self.current, self.total)
+                }
+
+                fn item(&self) -> Option<&str> {
+                    self.current_file.as_deref().or(Some(self.phase.as_str()))
+                }
+            }
+        )*
+    };
+}
+
+impl_progress_event!(
+    PakProgress,
+    VTexProgress,
+    ConvertProgress,
+    Gr2Progress,
+    ImageProgress,
+    ModProgress
+);
+
+/// Progress bar for a long-running command, fed by library callbacks
+///
+/// Hidden when disabled (`--quiet`, `--json`) or when stdout isn't a
+/// terminal, so callers can report progress unconditionally.
+pub struct TaskProgress {
+    bar: ProgressBar,
+    /// Whether the bar is counting bytes rather than items
+    bytes: AtomicBool,
+}
+
+impl TaskProgress {
+    /// Create a bar titled `title` with `total` items (0 if not yet known)
+    #[must_use]
+    pub fn new(title: &str, total: u64, enabled: bool) -> Self {
+        let bar = if enabled && std::io::stdout().is_terminal() {
+            ProgressBar::new(total)
+        } else {
+            ProgressBar::hidden()
+        };
+        bar.set_style(eta_bar_style());
+        bar.set_prefix(title.to_string());
+        Self {
+            bar,
+            bytes: AtomicBool::new(false),
+        }
+    }
+
+    /// Update from a library progress report
+    pub fn report<E: ProgressEvent>(&self, event: &E) {
+        let (current, total) = event.position();
+        self.update(current, total, event.item().unwrap_or_default());
+    }
+
+    /// Update from a `(current, total, item)` callback
+    pub fn update(&self, current: usize, total: usize, item: &str) {
+        self.count_bytes(false);
+        self.set(current as u64, total as u64, item);
+    }
+
+    /// Update from a byte count, showing sizes instead of item counts
+    ///
+    /// Later [`update`](Self::update) calls switch back to counting items.
+    pub fn update_bytes(&self, done: u64, total: u64, item: &str) {
+        self.count_bytes(true);
+        self.set(done, total, item);
+    }
+
+    fn count_bytes(&self, bytes: bool) {
+        if self.bytes.swap(bytes, Ordering::Relaxed) != bytes {
+            self.bar.set_style(if bytes {
+                bytes_bar_style()
+            } else {
+                eta_bar_style()
+            });
+        }
+    }
+
+    fn set(&self, position: u64, length: u64, item: &str) {
+        if self.bar.length() != Some(length) {
+            self.bar.set_length(length);
+        }
+        self.bar.set_position(position);
+        self.bar.set_message(item.to_string());
+    }
+
+    /// Show the item now being processed without advancing
+    pub fn start_item(&self, item: &str) {
+        self.bar.set_message(item.to_string());
+    }
+
+    /// Advance by one finished item
+    pub fn inc(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Print a line without corrupting the bar (printed even when hidden)
+    pub fn println(&self, line: &str) {
+        self.bar.suspend(|| println!("{line}"));
+    }
+
+    /// Print a line to stderr without corrupting the bar
+    pub fn eprintln(&self, line: &str) {
+        self.bar.suspend(|| eprintln!("{line}"));
+    }
+
+    /// Remove the bar from the terminal
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
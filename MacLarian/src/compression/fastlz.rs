@@ -1,4 +1,4 @@
-//! `FastLZ` (LZ77) decompression for virtual texture tiles
+//! `FastLZ` (LZ77) compression and decompression for virtual texture tiles
 
 use crate::error::{Error, Result};
 
@@ -20,16 +20,21 @@ pub fn decompress(compressed: &[u8], output_size: usize) -> Result<Vec<u8>> {
     })
 }
 
-/// Compress data using `FastLZ`.
+/// Compress data using `FastLZ` at level 1 (faster) or 2 (smaller).
 ///
 /// # Errors
 /// Returns an error if compression fails.
-pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
+pub fn compress_level(data: &[u8], level: u32) -> Result<Vec<u8>> {
     use fastlz_rs::{CompressState, CompressionLevel};
 
+    let level = if level >= 2 {
+        CompressionLevel::Level2
+    } else {
+        CompressionLevel::Level1
+    };
     let mut state = CompressState::new();
     state
-        .compress_to_vec(data, CompressionLevel::Level1)
+        .compress_to_vec(data, level)
         .map_err(|e| Error::FastLzCompressionFailed {
             message: e.to_string(),
         })
@@ -1,4 +1,40 @@
 //! LZ4 compression wrapper
 //!
-//! This module exists for potential future LZ4-specific utilities.
-//! The main compression functions are in the parent module.
+//! The fast compressor is `lz4_flex`; HC levels go through liblz4. Both
+//! produce standard LZ4 data, so readers don't need to know which was used.
+
+use std::io::Write;
+
+use super::settings::Lz4Mode;
+use crate::error::{Error, Result};
+
+/// Compress with the fast compressor, or LZ4 HC when `level` is set
+pub(crate) fn compress(data: &[u8], level: Option<u32>, mode: Lz4Mode) -> Result<Vec<u8>> {
+    match (mode, level) {
+        (Lz4Mode::Block, None) => Ok(lz4_flex::block::compress(data)),
+        (Lz4Mode::Frame, None) => {
+            let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+            encoder.write_all(data)?;
+            encoder
+                .finish()
+                .map_err(|e| Error::CompressionError(format!("LZ4 frame: {e}")))
+        }
+        (Lz4Mode::Block, Some(level)) => {
+            let mode = ::lz4::block::CompressionMode::HIGHCOMPRESSION(hc_level(level));
+            Ok(::lz4::block::compress(data, Some(mode), false)?)
+        }
+        (Lz4Mode::Frame, Some(level)) => {
+            let mut encoder = ::lz4::EncoderBuilder::new()
+                .level(level)
+                .build(Vec::new())?;
+            encoder.write_all(data)?;
+            let (output, result) = encoder.finish();
+            result?;
+            Ok(output)
+        }
+    }
+}
+
+fn hc_level(level: u32) -> i32 {
+    i32::try_from(level.min(super::settings::LZ4_HC_MAX_LEVEL)).unwrap_or(9)
+}
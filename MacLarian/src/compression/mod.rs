@@ -1,10 +1,22 @@
 //! Compression utilities
+//!
+//! [`compress`] and friends are fixed-behavior LZ4 helpers; [`compress_with`]
+//! takes a [`CompressionSettings`] for callers that pick their own
+//! speed/ratio trade-off.
+
+use std::io::Write;
 
 use crate::error::{Error, Result};
 
 // Internal compression backends (not public API)
 pub(crate) mod fastlz;
 pub(crate) mod lz4;
+mod settings;
+
+pub use settings::{
+    CompressionAlgorithm, CompressionSettings, FASTLZ_MAX_LEVEL, LZ4_HC_MAX_LEVEL, Lz4Mode,
+    ZLIB_MAX_LEVEL,
+};
 
 /// Compress data using LZ4
 ///
@@ -14,6 +26,31 @@ pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
     Ok(lz4_flex::compress_prepend_size(data))
 }
 
+/// Compress data with explicit settings
+///
+/// The output has no size prefix; LZ4 output is a block or a frame per
+/// [`CompressionSettings::lz4_mode`].
+///
+/// # Errors
+/// Returns an error if compression fails.
+pub fn compress_with(data: &[u8], settings: &CompressionSettings) -> Result<Vec<u8>> {
+    match settings.method {
+        CompressionAlgorithm::None => Ok(data.to_vec()),
+        CompressionAlgorithm::Lz4 => lz4::compress(data, settings.level, settings.lz4_mode),
+        CompressionAlgorithm::Zlib => {
+            use flate2::Compression;
+            use flate2::write::ZlibEncoder;
+            let level = settings
+                .level
+                .map_or_else(Compression::default, Compression::new);
+            let mut encoder = ZlibEncoder::new(Vec::new(), level);
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionAlgorithm::FastLz => fastlz::compress_level(data, settings.level.unwrap_or(1)),
+    }
+}
+
 /// Decompress LZ4 data
 ///
 /// # Errors
@@ -33,3 +70,78 @@ pub fn decompress_with_size(data: &[u8]) -> Result<Vec<u8>> {
         message: e.to_string(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    /// Text-like data with enough repetition for every method to shrink it
+    fn sample() -> Vec<u8> {
+        (0..8_000u32)
+            .flat_map(|i| {
+                format!("<attribute id=\"Slot{}\" value=\"{i}\"/>\n", i % 13).into_bytes()
+            })
+            .collect()
+    }
+
+    /// Undo [`compress_with`] for `settings`
+    fn decompress_for(data: &[u8], settings: &CompressionSettings, size: usize) -> Vec<u8> {
+        match (settings.method, settings.lz4_mode) {
+            (CompressionAlgorithm::None, _) => data.to_vec(),
+            (CompressionAlgorithm::Lz4, Lz4Mode::Block) => decompress(data, size).unwrap(),
+            (CompressionAlgorithm::Lz4, Lz4Mode::Frame) => {
+                let mut out = Vec::new();
+                lz4_flex::frame::FrameDecoder::new(data)
+                    .read_to_end(&mut out)
+                    .unwrap();
+                out
+            }
+            (CompressionAlgorithm::Zlib, _) => {
+                let mut out = Vec::new();
+                flate2::read::ZlibDecoder::new(data)
+                    .read_to_end(&mut out)
+                    .unwrap();
+                out
+            }
+            (CompressionAlgorithm::FastLz, _) => fastlz::decompress(data, size).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_every_method_and_level() {
+        let mut all = vec![
+            CompressionSettings::none(),
+            CompressionSettings::lz4(),
+            CompressionSettings::lz4().with_lz4_mode(Lz4Mode::Frame),
+            CompressionSettings {
+                method: CompressionAlgorithm::Zlib,
+                ..CompressionSettings::default()
+            },
+        ];
+        for level in 1..=LZ4_HC_MAX_LEVEL {
+            all.push(CompressionSettings::lz4_hc(level));
+            all.push(CompressionSettings::lz4_hc(level).with_lz4_mode(Lz4Mode::Frame));
+        }
+        all.extend((1..=ZLIB_MAX_LEVEL).map(CompressionSettings::zlib));
+        all.extend((1..=FASTLZ_MAX_LEVEL).map(|level| CompressionSettings {
+            level: Some(level),
+            ..CompressionSettings::fastlz()
+        }));
+
+        let data = sample();
+        for settings in all {
+            let compressed = compress_with(&data, &settings).unwrap();
+            if settings.method != CompressionAlgorithm::None {
+                assert!(compressed.len() < data.len(), "{settings} didn't shrink");
+            }
+            assert_eq!(
+                decompress_for(&compressed, &settings, data.len()),
+                data,
+                "{settings} ({:?})",
+                settings.lz4_mode
+            );
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! Per-call compression configuration
+//!
+//! [`CompressionSettings`] describes the algorithm, level and (for LZ4) the
+//! container format to compress with. The default is fast LZ4 blocks, which
+//! matches what the PAK and LSF writers produced before levels were
+//! configurable.
+//!
+//! Settings parse from the `method[:level]` strings used on the command line:
+//!
+//! | Setting    | Speed     | Ratio  | Notes                              |
+//! |------------|-----------|--------|------------------------------------|
+//! | `none`     | -         | 1.00   | Stored as-is                       |
+//! | `lz4`      | fastest   | good   | Default for PAKs and LSF           |
+//! | `lz4:hc9`  | slow      | better | LZ4 HC, levels `hc1`-`hc12`        |
+//! | `zlib:1`   | medium    | better | zlib levels `1`-`9` (default `6`)  |
+//! | `zlib:9`   | slowest   | best   |                                    |
+//! | `fastlz:2` | fast      | good   | Virtual texture tiles, levels 1-2  |
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Highest LZ4 HC level
+pub const LZ4_HC_MAX_LEVEL: u32 = 12;
+
+/// Highest zlib level
+pub const ZLIB_MAX_LEVEL: u32 = 9;
+
+/// Highest `FastLZ` level
+pub const FASTLZ_MAX_LEVEL: u32 = 2;
+
+/// Compression algorithm
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompressionAlgorithm {
+    /// Store data uncompressed
+    None,
+    /// LZ4 (fast, or HC when a level is set)
+    #[default]
+    Lz4,
+    /// Zlib/DEFLATE
+    Zlib,
+    /// `FastLZ` (virtual texture tiles)
+    FastLz,
+}
+
+impl CompressionAlgorithm {
+    /// Name used in setting strings
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Lz4 => "lz4",
+            Self::Zlib => "zlib",
+            Self::FastLz => "fastlz",
+        }
+    }
+}
+
+/// LZ4 container format
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lz4Mode {
+    /// Raw LZ4 block, no header (PAK entries, LSF strings)
+    #[default]
+    Block,
+    /// LZ4 frame with magic and block headers (other LSF sections)
+    Frame,
+}
+
+/// Algorithm, level and format for one compression call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompressionSettings {
+    /// Algorithm
+    pub method: CompressionAlgorithm,
+    /// Level, or `None` for the algorithm's default
+    ///
+    /// For LZ4 any level selects LZ4 HC; without one the fast compressor is
+    /// used.
+    pub level: Option<u32>,
+    /// LZ4 container format (ignored by other algorithms)
+    pub lz4_mode: Lz4Mode,
+}
+
+impl CompressionSettings {
+    /// No compression
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            method: CompressionAlgorithm::None,
+            ..Self::default()
+        }
+    }
+
+    /// Fast LZ4
+    #[must_use]
+    pub fn lz4() -> Self {
+        Self::default()
+    }
+
+    /// LZ4 HC at `level` (clamped to 1-12)
+    #[must_use]
+    pub fn lz4_hc(level: u32) -> Self {
+        Self {
+            level: Some(level.clamp(1, LZ4_HC_MAX_LEVEL)),
+            ..Self::default()
+        }
+    }
+
+    /// Zlib at `level` (clamped to 1-9)
+    #[must_use]
+    pub fn zlib(level: u32) -> Self {
+        Self {
+            method: CompressionAlgorithm::Zlib,
+            level: Some(level.clamp(1, ZLIB_MAX_LEVEL)),
+            ..Self::default()
+        }
+    }
+
+    /// `FastLZ` at its default level
+    #[must_use]
+    pub fn fastlz() -> Self {
+        Self {
+            method: CompressionAlgorithm::FastLz,
+            ..Self::default()
+        }
+    }
+
+    /// Same settings with a different LZ4 container format
+    #[must_use]
+    pub fn with_lz4_mode(mut self, mode: Lz4Mode) -> Self {
+        self.lz4_mode = mode;
+        self
+    }
+
+    /// Whether these settings use LZ4 HC
+    #[must_use]
+    pub fn is_lz4_hc(&self) -> bool {
+        self.method == CompressionAlgorithm::Lz4 && self.level.is_some()
+    }
+}
+
+impl fmt::Display for CompressionSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.method.as_str())?;
+        match (self.method, self.level) {
+            (CompressionAlgorithm::Lz4, Some(level)) => write!(f, ":hc{level}"),
+            (CompressionAlgorithm::None, _) | (_, None) => Ok(()),
+            (_, Some(level)) => write!(f, ":{level}"),
+        }
+    }
+}
+
+impl FromStr for CompressionSettings {
+    type Err = Error;
+
+    /// Parse `none`, `lz4`, `lz4:fast`, `lz4hc`, `lz4:hcN`, `zlib`, `zlib:N`,
+    /// `fastlz` or `fastlz:N` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self> {
+        let lower = s.trim().to_lowercase();
+        let (method, level) = lower.split_once(':').unwrap_or((&lower, ""));
+        let invalid = || {
+            Error::InvalidFormat(format!(
+                "unknown compression '{s}': expected none, lz4, lz4:hc1-12, zlib:1-9 or fastlz:1-2"
+            ))
+        };
+        let parse_level = |level: &str, max: u32| match level.parse::<u32>() {
+            Ok(n) if (1..=max).contains(&n) => Ok(Some(n)),
+            _ => Err(invalid()),
+        };
+
+        let settings = match (method, level) {
+            ("none", "") => Self::none(),
+            ("lz4", "" | "fast") => Self::lz4(),
+            ("lz4hc", "") | ("lz4", "hc") => Self::lz4_hc(9),
+            ("lz4", level) => Self {
+                level: parse_level(
+                    level.strip_prefix("hc").ok_or_else(invalid)?,
+                    LZ4_HC_MAX_LEVEL,
+                )?,
+                ..Self::lz4()
+            },
+            ("zlib", "") => Self {
+                method: CompressionAlgorithm::Zlib,
+                ..Self::default()
+            },
+            ("zlib", level) => Self {
+                level: parse_level(level, ZLIB_MAX_LEVEL)?,
+                ..Self::zlib(1)
+            },
+            ("fastlz", "") => Self::fastlz(),
+            ("fastlz", level) => Self {
+                level: parse_level(level, FASTLZ_MAX_LEVEL)?,
+                ..Self::fastlz()
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let hc: CompressionSettings = "LZ4:HC9".parse().unwrap();
+        assert_eq!(hc, CompressionSettings::lz4_hc(9));
+        assert_eq!(hc.to_string(), "lz4:hc9");
+
+        let zlib: CompressionSettings = "zlib:3".parse().unwrap();
+        assert_eq!(zlib, CompressionSettings::zlib(3));
+        assert_eq!(
+            "lz4".parse::<CompressionSettings>().unwrap(),
+            CompressionSettings::default()
+        );
+        assert_eq!("zlib".parse::<CompressionSettings>().unwrap().level, None);
+
+        assert!("lz4:hc13".parse::<CompressionSettings>().is_err());
+        assert!("zlib:0".parse::<CompressionSettings>().is_err());
+        assert!("lz4:9".parse::<CompressionSettings>().is_err());
+        assert!("brotli".parse::<CompressionSettings>().is_err());
+    }
+}
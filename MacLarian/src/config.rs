@@ -0,0 +1,223 @@
+//! Shared settings file for the CLI and GUI
+//!
+//! Settings live in `~/.config/macpak/config.toml` (or `$XDG_CONFIG_HOME/macpak/config.toml`):
+//!
+//! ```toml
+//! bg3-path = "/path/to/Baldur's Gate 3.app/Contents/Data"
+//! index-dir = "~/.cache/macpak/index"
+//! output-dir = "~/BG3/Extracted"
+//! compression = "lz4"
+//! log-level = "info"
+//! texture-roots = ["~/BG3/Extracted/Textures"]
+//! ```
+//!
+//! Explicit arguments take precedence over these values, which take precedence
+//! over auto-detection.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::utils::find_game_install;
+
+/// Keys accepted by [`Config::set`] and [`Config::get`]
+pub const CONFIG_KEYS: &[&str] = &[
+    "bg3-path",
+    "index-dir",
+    "output-dir",
+    "compression",
+    "texture-roots",
+    "log-level",
+];
+
+/// Values accepted for `log-level`
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+/// Settings shared by the CLI and GUI
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    /// BG3 `Data` directory.
+    pub bg3_path: Option<PathBuf>,
+    /// Directory for search indexes.
+    pub index_dir: Option<PathBuf>,
+    /// Default output directory (CLI `save extract` and `mods package`, GUI
+    /// extract dialogs).
+    pub output_dir: Option<PathBuf>,
+    /// Default PAK compression (`lz4`, `lz4:hc9`, `zlib`, `zlib:9`, `none`).
+    pub compression: Option<String>,
+    /// Directories with pre-extracted DDS textures, searched before the
+    /// texture PAKs when bundling GR2 textures.
+    pub texture_roots: Vec<PathBuf>,
+    /// GUI log level (see [`LOG_LEVELS`]).
+    pub log_level: Option<String>,
+}
+
+impl Config {
+    /// Default config file location
+    ///
+    /// Returns `None` if neither `XDG_CONFIG_HOME` nor `HOME` is set.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(base.join("macpak").join("config.toml"))
+    }
+
+    /// Load settings from a file, returning defaults if it doesn't exist
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| Error::TomlError(e.to_string()))
+    }
+
+    /// Load settings from the default location
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load_default() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Write settings to a file, creating parent directories as needed
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| Error::TomlError(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Set a value by key (see [`CONFIG_KEYS`])
+    ///
+    /// An empty value clears the setting. `texture-roots` takes a
+    /// comma-separated list.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownConfigKey`] if the key isn't recognized, or
+    /// [`Error::InvalidConfigValue`] for a `log-level` outside [`LOG_LEVELS`].
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        let path = || (!value.is_empty()).then(|| expand_path(value));
+
+        match key {
+            "bg3-path" => self.bg3_path = path(),
+            "index-dir" => self.index_dir = path(),
+            "output-dir" => self.output_dir = path(),
+            "compression" => {
+                self.compression = (!value.is_empty()).then(|| value.to_lowercase());
+            }
+            "texture-roots" => {
+                self.texture_roots = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(expand_path)
+                    .collect();
+            }
+            "log-level" => {
+                let level = value.to_lowercase();
+                if !level.is_empty() && !LOG_LEVELS.contains(&level.as_str()) {
+                    return Err(Error::InvalidConfigValue {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    });
+                }
+                self.log_level = (!level.is_empty()).then_some(level);
+            }
+            _ => {
+                return Err(Error::UnknownConfigKey {
+                    key: key.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Get a value by key as display text (`None` if unset)
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownConfigKey`] if the key isn't recognized.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let display = |p: &Option<PathBuf>| p.as_ref().map(|p| p.display().to_string());
+
+        Ok(match key {
+            "bg3-path" => display(&self.bg3_path),
+            "index-dir" => display(&self.index_dir),
+            "output-dir" => display(&self.output_dir),
+            "compression" => self.compression.clone(),
+            "texture-roots" => (!self.texture_roots.is_empty()).then(|| {
+                self.texture_roots
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }),
+            "log-level" => self.log_level.clone(),
+            _ => {
+                return Err(Error::UnknownConfigKey {
+                    key: key.to_string(),
+                });
+            }
+        })
+    }
+
+    /// BG3 `Data` directory: explicit path, then config, then auto-detection
+    #[must_use]
+    pub fn resolve_bg3_path(&self, explicit: Option<&Path>) -> Option<PathBuf> {
+        explicit
+            .map(Path::to_path_buf)
+            .or_else(|| self.bg3_path.clone())
+            .or_else(|| find_game_install().map(|install| install.data_dir))
+    }
+}
+
+fn expand_path(value: &str) -> PathBuf {
+    PathBuf::from(shellexpand::tilde(value).as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_set_and_roundtrip() {
+        let mut config = Config::default();
+        config.set("bg3-path", "/games/bg3/Data").unwrap();
+        config.set("compression", "LZ4").unwrap();
+        config.set("texture-roots", "/a, /b").unwrap();
+        config.set("log-level", "Debug").unwrap();
+        assert!(config.set("nope", "1").is_err());
+        assert!(config.set("log-level", "loud").is_err());
+
+        assert_eq!(config.get("compression").unwrap().as_deref(), Some("lz4"));
+        assert_eq!(
+            config.get("texture-roots").unwrap().as_deref(),
+            Some("/a, /b")
+        );
+        assert_eq!(config.get("log-level").unwrap().as_deref(), Some("debug"));
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        assert!(text.contains("bg3-path = \"/games/bg3/Data\""));
+        assert_eq!(toml::from_str::<Config>(&text).unwrap(), config);
+
+        config.set("bg3-path", "").unwrap();
+        assert_eq!(config.bg3_path, None);
+    }
+}
@@ -0,0 +1,314 @@
+//! Converting many files at once
+//!
+//! [`convert_dir`] converts every file of one format under a directory,
+//! keeping its folder layout; [`batch_convert_gr2`] converts a list of models
+//! between GR2 and glTF. Files are converted on the rayon thread pool, and a
+//! file that fails is recorded in the [`BatchConvertReport`] instead of
+//! stopping the rest.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+use super::{ConvertPhase, ConvertProgress, ConvertProgressCallback};
+use super::{
+    Gr2WriteOptions, convert_bytes, convert_gltf_to_gr2_with_options, convert_gr2_to_glb,
+    convert_gr2_to_gltf,
+};
+use crate::error::{Error, Result};
+use crate::utils::CancelToken;
+
+/// Outcome of [`convert_dir`] and [`batch_convert_gr2`]
+#[derive(Debug, Default)]
+pub struct BatchConvertReport {
+    /// Files written, in input order
+    pub written: Vec<PathBuf>,
+    /// Inputs that couldn't be converted, with their errors
+    pub failed: Vec<(PathBuf, Error)>,
+    /// Whether conversion was cancelled before every input was processed
+    pub cancelled: bool,
+}
+
+/// What [`batch_convert_gr2`] writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gr2BatchTarget {
+    /// Binary glTF, from GR2 inputs
+    Glb,
+    /// glTF with a separate buffer, from GR2 inputs
+    Gltf,
+    /// GR2, from glTF or GLB inputs
+    Gr2(Gr2WriteOptions),
+}
+
+impl Gr2BatchTarget {
+    /// Extension of the files written
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Glb => "glb",
+            Self::Gltf => "gltf",
+            Self::Gr2(_) => "GR2",
+        }
+    }
+}
+
+/// Convert every `input_format` file under `source_dir` to `output_format`
+///
+/// Outputs keep their path relative to `source_dir`, under `dest_dir`, with
+/// the extension replaced. Any pair of formats [`convert_bytes`] supports can
+/// be used.
+///
+/// # Errors
+/// Returns an error if `dest_dir` can't be created. Failures of single files
+/// are reported in [`BatchConvertReport::failed`] instead.
+pub fn convert_dir(
+    source_dir: &Path,
+    dest_dir: &Path,
+    input_format: &str,
+    output_format: &str,
+    progress: ConvertProgressCallback,
+) -> Result<BatchConvertReport> {
+    convert_dir_cancellable(
+        source_dir,
+        dest_dir,
+        input_format,
+        output_format,
+        &CancelToken::new(),
+        progress,
+    )
+}
+
+/// Convert a directory, stopping early once `cancel` is triggered
+///
+/// Files already being converted are finished; the rest are left out of the
+/// report and [`BatchConvertReport::cancelled`] is set.
+///
+/// # Errors
+/// Returns an error if `dest_dir` can't be created.
+pub fn convert_dir_cancellable(
+    source_dir: &Path,
+    dest_dir: &Path,
+    input_format: &str,
+    output_format: &str,
+    cancel: &CancelToken,
+    progress: ConvertProgressCallback,
+) -> Result<BatchConvertReport> {
+    let input_format = input_format.trim_start_matches('.');
+    let output_format = output_format.trim_start_matches('.');
+
+    let mut sources: Vec<PathBuf> = WalkDir::new(source_dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| {
+            e.file_type().is_file()
+                && e.path()
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(input_format))
+        })
+        .map(walkdir::DirEntry::into_path)
+        .collect();
+    sources.sort();
+
+    let jobs: Vec<(PathBuf, PathBuf)> = sources
+        .into_iter()
+        .map(|source| {
+            let relative = source.strip_prefix(source_dir).unwrap_or(&source);
+            let output = dest_dir.join(relative).with_extension(output_format);
+            (source, output)
+        })
+        .collect();
+
+    std::fs::create_dir_all(dest_dir)?;
+    Ok(run_batch(&jobs, cancel, progress, |source, output| {
+        let data = std::fs::read(source)?;
+        let converted = convert_bytes(&data, input_format, output_format)?;
+        std::fs::write(output, converted)?;
+        Ok(())
+    }))
+}
+
+/// Convert GR2 models to glTF, or glTF models to GR2
+///
+/// Each input is written to `dest_dir` under its own file stem, with the
+/// extension of `target`.
+///
+/// # Errors
+/// Returns an error if `dest_dir` can't be created. Failures of single files
+/// are reported in [`BatchConvertReport::failed`] instead.
+pub fn batch_convert_gr2(
+    inputs: &[PathBuf],
+    dest_dir: &Path,
+    target: Gr2BatchTarget,
+    progress: ConvertProgressCallback,
+) -> Result<BatchConvertReport> {
+    batch_convert_gr2_cancellable(inputs, dest_dir, target, &CancelToken::new(), progress)
+}
+
+/// Convert models, stopping early once `cancel` is triggered
+///
+/// Files already being converted are finished; the rest are left out of the
+/// report and [`BatchConvertReport::cancelled`] is set.
+///
+/// # Errors
+/// Returns an error if `dest_dir` can't be created.
+pub fn batch_convert_gr2_cancellable(
+    inputs: &[PathBuf],
+    dest_dir: &Path,
+    target: Gr2BatchTarget,
+    cancel: &CancelToken,
+    progress: ConvertProgressCallback,
+) -> Result<BatchConvertReport> {
+    let jobs: Vec<(PathBuf, PathBuf)> = inputs
+        .iter()
+        .map(|source| {
+            let stem = source
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("output");
+            let output = dest_dir.join(format!("{stem}.{}", target.extension()));
+            (source.clone(), output)
+        })
+        .collect();
+
+    std::fs::create_dir_all(dest_dir)?;
+    Ok(run_batch(
+        &jobs,
+        cancel,
+        progress,
+        |source, output| match target {
+            Gr2BatchTarget::Glb => convert_gr2_to_glb(source, output),
+            Gr2BatchTarget::Gltf => convert_gr2_to_gltf(source, output),
+            Gr2BatchTarget::Gr2(options) => {
+                convert_gltf_to_gr2_with_options(source, output, &options, &|_| {})
+            }
+        },
+    ))
+}
+
+/// Run `convert` on each `(source, output)` pair in parallel
+fn run_batch(
+    jobs: &[(PathBuf, PathBuf)],
+    cancel: &CancelToken,
+    progress: ConvertProgressCallback,
+    convert: impl Fn(&Path, &Path) -> Result<()> + Sync,
+) -> BatchConvertReport {
+    let total = jobs.len();
+    let done = AtomicUsize::new(0);
+
+    let results: Vec<Option<Result<()>>> = jobs
+        .par_iter()
+        .map(|(source, output)| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let result = match output.parent() {
+                Some(parent) => std::fs::create_dir_all(parent).map_err(Error::from),
+                None => Ok(()),
+            }
+            .and_then(|()| convert(source, output));
+
+            let current = done.fetch_add(1, Ordering::SeqCst) + 1;
+            progress(&ConvertProgress::with_file(
+                ConvertPhase::Converting,
+                current,
+                total,
+                source.display().to_string(),
+            ));
+            Some(result)
+        })
+        .collect();
+
+    let processed = done.load(Ordering::SeqCst);
+    progress(&ConvertProgress::new(
+        ConvertPhase::Complete,
+        processed,
+        total,
+    ));
+
+    let mut report = BatchConvertReport {
+        cancelled: processed < total,
+        ..BatchConvertReport::default()
+    };
+    for ((source, output), result) in jobs.iter().zip(results) {
+        match result {
+            Some(Ok(())) => report.written.push(output.clone()),
+            Some(Err(e)) => report.failed.push((source.clone(), e)),
+            None => {}
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{CONFIG_LSX, triangle_gltf};
+
+    #[test]
+    fn test_convert_dir_keeps_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::create_dir_all(source.join("Public/Test")).unwrap();
+        std::fs::write(source.join("Public/Test/Doc.lsx"), CONFIG_LSX).unwrap();
+        std::fs::write(source.join("Public/Broken.lsx"), "</save>").unwrap();
+        std::fs::write(source.join("Public/notes.txt"), "not converted").unwrap();
+
+        let out = dir.path().join("out");
+        let report = convert_dir(&source, &out, "lsx", "lsf", &|_| {}).unwrap();
+
+        assert!(!report.cancelled);
+        assert_eq!(report.written, [out.join("Public/Test/Doc.lsf")]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, source.join("Public/Broken.lsx"));
+        let lsf = std::fs::read(out.join("Public/Test/Doc.lsf")).unwrap();
+        assert!(convert_bytes(&lsf, "lsf", "lsx").is_ok());
+        assert!(!out.join("Public/notes.lsf").exists());
+    }
+
+    #[test]
+    fn test_batch_convert_gr2_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let gltf = dir.path().join("Tri_Body.gltf");
+        std::fs::write(&gltf, triangle_gltf()).unwrap();
+
+        let gr2_dir = dir.path().join("gr2");
+        let target = Gr2BatchTarget::Gr2(Gr2WriteOptions::default());
+        let report = batch_convert_gr2(&[gltf], &gr2_dir, target, &|_| {}).unwrap();
+        assert!(report.failed.is_empty(), "{:?}", report.failed);
+        assert_eq!(report.written, [gr2_dir.join("Tri_Body.GR2")]);
+
+        let glb_dir = dir.path().join("glb");
+        let report =
+            batch_convert_gr2(&report.written, &glb_dir, Gr2BatchTarget::Glb, &|_| {}).unwrap();
+        assert!(report.failed.is_empty(), "{:?}", report.failed);
+        assert_eq!(report.written, [glb_dir.join("Tri_Body.glb")]);
+    }
+
+    #[test]
+    fn test_cancelled_batches_convert_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::create_dir_all(&source).unwrap();
+        std::fs::write(source.join("Doc.lsx"), CONFIG_LSX).unwrap();
+        std::fs::write(source.join("Tri_Body.gltf"), triangle_gltf()).unwrap();
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let out = dir.path().join("out");
+        let report =
+            convert_dir_cancellable(&source, &out, "lsx", "lsf", &cancel, &|_| {}).unwrap();
+        assert!(report.cancelled);
+        assert!(report.written.is_empty() && report.failed.is_empty());
+
+        let models = [source.join("Tri_Body.gltf")];
+        let target = Gr2BatchTarget::Gr2(Gr2WriteOptions::default());
+        let report =
+            batch_convert_gr2_cancellable(&models, &out, target, &cancel, &|_| {}).unwrap();
+        assert!(report.cancelled);
+        assert!(report.written.is_empty());
+        assert!(!out.join("Doc.lsf").exists() && !out.join("Tri_Body.GR2").exists());
+    }
+}
@@ -0,0 +1,157 @@
+//! In-memory conversions between byte buffers
+//!
+//! Used when there is no file to read from or write to, e.g. converting
+//! stdin to stdout. Formats are named by their usual file extension.
+
+use crate::error::{Error, Result};
+use crate::formats::gr2::magic as gr2_magic;
+use crate::formats::{loca, lsb, lsf, lsj, lsx};
+
+use super::lsf_lsx_lsj::{from_lsx, to_lsj, to_lsx};
+
+/// Detect a format from the first bytes of a file
+///
+/// Returns the format's extension (`lsf`, `lsb`, `lsx`, `lsj`, `loca`, `xml`,
+/// `gr2`, `glb`, `gltf`, `dds`, `png`), or `None` if nothing matches.
+#[must_use]
+pub fn sniff_format(data: &[u8]) -> Option<&'static str> {
+    let magic = data.get(..4)?;
+    match magic {
+        b"LSOF" => return Some("lsf"),
+        b"LSFM" | b"\0\0\0\x40" => return Some("lsb"),
+        b"LOCA" => return Some("loca"),
+        b"DDS " => return Some("dds"),
+        b"glTF" => return Some("glb"),
+        b"\x89PNG" => return Some("png"),
+        _ => {}
+    }
+
+    if data.get(..16).is_some_and(|sig| {
+        sig == gr2_magic::LE32 || sig == gr2_magic::LE64 || sig == gr2_magic::LE64_V2
+    }) {
+        return Some("gr2");
+    }
+
+    // Text formats: look past the BOM and leading whitespace
+    let text = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace())?;
+    let head = String::from_utf8_lossy(&text[start..text.len().min(start + 512)]);
+    if head.starts_with('<') {
+        Some(if head.contains("<contentList") {
+            "xml"
+        } else {
+            "lsx"
+        })
+    } else if head.starts_with('{') {
+        Some(if head.contains("\"asset\"") {
+            "gltf"
+        } else {
+            "lsj"
+        })
+    } else {
+        None
+    }
+}
+
+/// Convert a buffer from one format to another
+///
+/// Supports the LSF/LSX/LSJ document formats, reading LSB, LOCA <-> XML,
+/// GR2 -> GLB, glTF/GLB -> GR2 and DDS -> PNG. `lsbc`, `lsbs` and `lsfx` are
+/// treated as LSF.
+///
+/// # Errors
+/// Returns [`Error::ConversionError`] if the conversion isn't supported, or
+/// the underlying parse/write error.
+pub fn convert_bytes(data: &[u8], input: &str, output: &str) -> Result<Vec<u8>> {
+    let input = normalize(input);
+    let output = normalize(output);
+
+    match (input.as_str(), output.as_str()) {
+        (a, b) if a == b => Ok(data.to_vec()),
+
+        ("lsf", "lsx") => Ok(to_lsx(&lsf::parse_lsf_bytes(data)?)?.into_bytes()),
+        ("lsf", "lsj") => {
+            let lsx_doc = lsx::parse_lsx(&to_lsx(&lsf::parse_lsf_bytes(data)?)?)?;
+            Ok(lsj::serialize_lsj(&to_lsj(&lsx_doc)?)?.into_bytes())
+        }
+        ("lsx", "lsf") => lsf::serialize_lsf(&from_lsx(text(data)?)?),
+        ("lsb", "lsx") => Ok(lsx::serialize_lsx(&lsb::parse_lsb_bytes(data)?)?.into_bytes()),
+        ("lsb", "lsj") => {
+            let lsx_doc = lsb::parse_lsb_bytes(data)?;
+            Ok(lsj::serialize_lsj(&to_lsj(&lsx_doc)?)?.into_bytes())
+        }
+        ("lsb", "lsf") => {
+            let xml = lsx::serialize_lsx(&lsb::parse_lsb_bytes(data)?)?;
+            lsf::serialize_lsf(&from_lsx(&xml)?)
+        }
+        ("lsx", "lsj") => {
+            let lsx_doc = lsx::parse_lsx(text(data)?)?;
+            Ok(lsj::serialize_lsj(&to_lsj(&lsx_doc)?)?.into_bytes())
+        }
+        ("lsj", "lsx") => {
+            let lsx_doc = lsj_to_lsx(data)?;
+            Ok(lsx::serialize_lsx(&lsx_doc)?.into_bytes())
+        }
+        ("lsj", "lsf") => {
+            let xml = lsx::serialize_lsx(&lsj_to_lsx(data)?)?;
+            lsf::serialize_lsf(&from_lsx(&xml)?)
+        }
+
+        ("loca", "xml") => {
+            Ok(super::loca_to_xml_string(&loca::parse_loca_bytes(data)?)?.into_bytes())
+        }
+        ("xml", "loca") => loca::serialize_loca(&super::loca_from_xml(text(data)?)?),
+
+        ("gr2", "glb") => super::convert_gr2_bytes_to_glb(data),
+        ("glb" | "gltf", "gr2") => super::convert_gltf_bytes_to_gr2(data),
+
+        ("dds", "png") => super::dds_bytes_to_png_bytes(data),
+
+        (input, output) => Err(Error::ConversionError(format!(
+            "in-memory conversion from {input} to {output} is not supported"
+        ))),
+    }
+}
+
+/// Lowercase a format name and map LSF variants to `lsf`
+fn normalize(format: &str) -> String {
+    let format = format.trim_start_matches('.').to_lowercase();
+    match format.as_str() {
+        "lsbc" | "lsbs" | "lsfx" => "lsf".to_string(),
+        _ => format,
+    }
+}
+
+fn text(data: &[u8]) -> Result<&str> {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    std::str::from_utf8(data).map_err(|e| Error::InvalidFormat(format!("input is not UTF-8: {e}")))
+}
+
+fn lsj_to_lsx(data: &[u8]) -> Result<lsx::LsxDocument> {
+    super::lsf_lsx_lsj::lsj_document_to_lsx(&lsj::parse_lsj(text(data)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::CONFIG_LSX;
+
+    #[test]
+    fn test_sniff_format() {
+        assert_eq!(sniff_format(b"LSOF\x07\0\0\0"), Some("lsf"));
+        assert_eq!(sniff_format(b"\0\0\0\x40\0\0\0\0"), Some("lsb"));
+        assert_eq!(sniff_format(CONFIG_LSX.as_bytes()), Some("lsx"));
+        assert_eq!(sniff_format(b"\xEF\xBB\xBF<contentList>"), Some("xml"));
+        assert_eq!(sniff_format(b"  {\"save\": {}}"), Some("lsj"));
+        assert_eq!(sniff_format(b"hello"), None);
+    }
+
+    #[test]
+    fn test_lsx_lsf_roundtrip() {
+        let lsf_bytes = convert_bytes(CONFIG_LSX.as_bytes(), "lsx", "lsf").unwrap();
+        assert_eq!(sniff_format(&lsf_bytes), Some("lsf"));
+
+        let xml = convert_bytes(&lsf_bytes, "LSF", "lsx").unwrap();
+        assert!(String::from_utf8(xml).unwrap().contains("value=\"Test\""));
+    }
+}
@@ -0,0 +1,278 @@
+//! Parallel DDS ↔ PNG conversion of many files
+//!
+//! Each input is converted by its extension: DDS to PNG, PNG to DDS. Files
+//! are converted on the rayon thread pool and every input gets an entry in the
+//! [`TextureBatchReport`], so one bad file doesn't stop the batch or get lost
+//! in the output.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
+use super::color_space::ColorSpace;
+use super::encode::DdsFormat;
+use super::types::{ImagePhase, ImageProgress, ImageProgressCallback};
+use super::{convert_dds_to_png, convert_png_to_dds_with_color_space};
+use crate::error::{Error, Result};
+use crate::utils::CancelToken;
+
+/// Options for [`batch_convert_textures`]
+#[derive(Debug, Clone, Default)]
+pub struct TextureBatchOptions {
+    /// Compression used for PNG → DDS conversions
+    pub dds_format: DdsFormat,
+    /// Color space for PNG → DDS conversions; `None` keeps each PNG's own
+    pub color_space: Option<ColorSpace>,
+    /// Skip files whose output exists and is at least as new as the input
+    pub skip_existing: bool,
+    /// Delete each input after it converts successfully
+    pub delete_source: bool,
+    /// Directory the output layout is relative to; defaults to the deepest
+    /// directory containing every input
+    pub root: Option<PathBuf>,
+}
+
+/// What happened to one input of a batch conversion
+#[derive(Debug)]
+pub enum TextureBatchStatus {
+    /// The file was converted
+    Converted,
+    /// The output was already up to date
+    Skipped,
+    /// Conversion failed
+    Failed(Error),
+}
+
+/// One input of a batch conversion
+#[derive(Debug)]
+pub struct TextureBatchEntry {
+    /// The input file
+    pub source: PathBuf,
+    /// Where the output was (or would have been) written
+    pub output: PathBuf,
+    /// Outcome for this file
+    pub status: TextureBatchStatus,
+}
+
+/// Per-file results of [`batch_convert_textures`], in input order
+#[derive(Debug, Default)]
+pub struct TextureBatchReport {
+    /// One entry per input that was processed
+    pub entries: Vec<TextureBatchEntry>,
+    /// Whether the batch was cancelled before every input was processed
+    pub cancelled: bool,
+}
+
+impl TextureBatchReport {
+    /// Number of files converted
+    #[must_use]
+    pub fn converted(&self) -> usize {
+        self.count(|status| matches!(status, TextureBatchStatus::Converted))
+    }
+
+    /// Number of files skipped because their output was up to date
+    #[must_use]
+    pub fn skipped(&self) -> usize {
+        self.count(|status| matches!(status, TextureBatchStatus::Skipped))
+    }
+
+    /// Number of files that failed to convert
+    #[must_use]
+    pub fn failed(&self) -> usize {
+        self.count(|status| matches!(status, TextureBatchStatus::Failed(_)))
+    }
+
+    /// Entries that failed, with their errors
+    pub fn failures(&self) -> impl Iterator<Item = (&Path, &Error)> {
+        self.entries.iter().filter_map(|entry| match &entry.status {
+            TextureBatchStatus::Failed(e) => Some((entry.source.as_path(), e)),
+            _ => None,
+        })
+    }
+
+    fn count(&self, pred: impl Fn(&TextureBatchStatus) -> bool) -> usize {
+        self.entries.iter().filter(|e| pred(&e.status)).count()
+    }
+}
+
+/// Convert DDS files to PNG and PNG files to DDS in parallel
+///
+/// With `output_dir`, outputs keep their directory layout relative to
+/// [`TextureBatchOptions::root`]; without it, each output is written next to
+/// its input.
+#[must_use]
+pub fn batch_convert_textures(
+    inputs: &[PathBuf],
+    output_dir: Option<&Path>,
+    options: &TextureBatchOptions,
+    progress: ImageProgressCallback,
+) -> TextureBatchReport {
+    batch_convert_textures_cancellable(inputs, output_dir, options, &CancelToken::new(), progress)
+}
+
+/// Convert textures in parallel, stopping early once `cancel` is triggered
+///
+/// Files already being converted are finished; the rest are left out of the
+/// report and [`TextureBatchReport::cancelled`] is set.
+#[must_use]
+pub fn batch_convert_textures_cancellable(
+    inputs: &[PathBuf],
+    output_dir: Option<&Path>,
+    options: &TextureBatchOptions,
+    cancel: &CancelToken,
+    progress: ImageProgressCallback,
+) -> TextureBatchReport {
+    let total = inputs.len();
+    let root = options.root.clone().unwrap_or_else(|| common_root(inputs));
+    let done = AtomicUsize::new(0);
+
+    let entries: Vec<Option<TextureBatchEntry>> = inputs
+        .par_iter()
+        .map(|source| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let output = output_path(source, &root, output_dir);
+            let status = match convert_one(source, &output, options) {
+                Ok(true) => TextureBatchStatus::Converted,
+                Ok(false) => TextureBatchStatus::Skipped,
+                Err(e) => TextureBatchStatus::Failed(e),
+            };
+
+            let current = done.fetch_add(1, Ordering::SeqCst) + 1;
+            progress(&ImageProgress::with_file(
+                ImagePhase::Converting,
+                current,
+                total,
+                source.display().to_string(),
+            ));
+            Some(TextureBatchEntry {
+                source: source.clone(),
+                output,
+                status,
+            })
+        })
+        .collect();
+
+    let processed = done.load(Ordering::SeqCst);
+    progress(&ImageProgress::new(ImagePhase::Complete, processed, total));
+    TextureBatchReport {
+        entries: entries.into_iter().flatten().collect(),
+        cancelled: processed < total,
+    }
+}
+
+/// Convert one file, returning false if it was skipped as up to date
+fn convert_one(source: &Path, output: &Path, options: &TextureBatchOptions) -> Result<bool> {
+    if options.skip_existing && is_up_to_date(source, output) {
+        return Ok(false);
+    }
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    match extension(source).as_str() {
+        "dds" => convert_dds_to_png(source, output)?,
+        "png" => convert_png_to_dds_with_color_space(
+            source,
+            output,
+            options.dds_format,
+            options.color_space,
+            &|_| {},
+        )?,
+        _ => {
+            return Err(Error::InvalidFormat(format!(
+                "{} is not a DDS or PNG file",
+                source.display()
+            )));
+        }
+    }
+
+    if options.delete_source {
+        std::fs::remove_file(source)?;
+    }
+    Ok(true)
+}
+
+/// Whether `output` exists and was modified no earlier than `source`
+fn is_up_to_date(source: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified());
+    match (modified(source), modified(output)) {
+        (Ok(source), Ok(output)) => output >= source,
+        _ => false,
+    }
+}
+
+/// Output path for `source`: the other format, under `output_dir` if given
+fn output_path(source: &Path, root: &Path, output_dir: Option<&Path>) -> PathBuf {
+    let target = if extension(source) == "png" {
+        "dds"
+    } else {
+        "png"
+    };
+    let file = source.with_extension(target);
+    match output_dir {
+        Some(dir) => dir.join(file.strip_prefix(root).unwrap_or(&file)),
+        None => file,
+    }
+}
+
+/// Deepest directory containing every input
+fn common_root(inputs: &[PathBuf]) -> PathBuf {
+    let mut parents = inputs.iter().filter_map(|p| p.parent());
+    let Some(first) = parents.next() else {
+        return PathBuf::new();
+    };
+    let mut root = first.to_path_buf();
+    for parent in parents {
+        while !parent.starts_with(&root) {
+            if !root.pop() {
+                return PathBuf::new();
+            }
+        }
+    }
+    root
+}
+
+/// Lowercase extension of a path
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_convert_keeps_layout_and_skips_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src");
+        std::fs::create_dir_all(source.join("Body")).unwrap();
+        let img = image::RgbaImage::from_pixel(4, 4, image::Rgba([200, 100, 50, 255]));
+        img.save(source.join("Body/Skin.png")).unwrap();
+        std::fs::write(source.join("Broken.dds"), b"not a dds").unwrap();
+
+        let inputs = vec![source.join("Body/Skin.png"), source.join("Broken.dds")];
+        let out = dir.path().join("out");
+        let options = TextureBatchOptions {
+            skip_existing: true,
+            ..TextureBatchOptions::default()
+        };
+
+        let report = batch_convert_textures(&inputs, Some(&out), &options, &|_| {});
+        assert_eq!((report.converted(), report.failed()), (1, 1));
+        assert_eq!(report.entries[0].output, out.join("Body/Skin.dds"));
+        assert!(out.join("Body/Skin.dds").is_file());
+        assert_eq!(
+            report.failures().next().unwrap().0,
+            source.join("Broken.dds")
+        );
+
+        let report = batch_convert_textures(&inputs, Some(&out), &options, &|_| {});
+        assert_eq!((report.skipped(), report.failed()), (1, 1));
+        assert!(!report.cancelled);
+    }
+}
@@ -0,0 +1,180 @@
+//! Color space (sRGB vs linear) of textures
+//!
+//! BG3 samples base color textures as sRGB and normal and physical maps as
+//! linear. DDS files say which through the DX10 format (`BC1_UNorm_sRGB` vs
+//! `BC1_UNorm`); PNG files through their `sRGB` or `gAMA` chunk. Carrying the
+//! flag across conversions keeps a DDS → PNG → DDS round trip from turning a
+//! base color washed out or too dark in game.
+
+use std::fmt;
+
+use ddsfile::{Dds, DxgiFormat};
+use serde::Serialize;
+
+/// How a texture's color values are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorSpace {
+    /// Gamma-encoded color, as used by base color and icon textures
+    Srgb,
+    /// Linear data, as used by normal, physical and mask textures
+    Linear,
+}
+
+impl ColorSpace {
+    /// Display name
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Srgb => "sRGB",
+            Self::Linear => "linear",
+        }
+    }
+}
+
+impl fmt::Display for ColorSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Color space a DDS declares, if it has a DX10 header
+///
+/// Legacy headers (`DXT1`, `DXT5`, ...) don't record one.
+pub(crate) fn dds_color_space(dds: &Dds) -> Option<ColorSpace> {
+    let format = dds.header10.as_ref()?.dxgi_format;
+    Some(if is_srgb_format(format) {
+        ColorSpace::Srgb
+    } else {
+        ColorSpace::Linear
+    })
+}
+
+fn is_srgb_format(format: DxgiFormat) -> bool {
+    matches!(
+        format,
+        DxgiFormat::R8G8B8A8_UNorm_sRGB
+            | DxgiFormat::B8G8R8A8_UNorm_sRGB
+            | DxgiFormat::B8G8R8X8_UNorm_sRGB
+            | DxgiFormat::BC1_UNorm_sRGB
+            | DxgiFormat::BC2_UNorm_sRGB
+            | DxgiFormat::BC3_UNorm_sRGB
+            | DxgiFormat::BC7_UNorm_sRGB
+    )
+}
+
+/// The sRGB or linear variant of a DXGI format
+///
+/// Formats without an sRGB variant (such as BC4 and BC5) are returned as-is.
+pub(crate) fn dxgi_format_for(format: DxgiFormat, color_space: ColorSpace) -> DxgiFormat {
+    use DxgiFormat as F;
+    match (color_space, format) {
+        (ColorSpace::Srgb, F::R8G8B8A8_UNorm) => F::R8G8B8A8_UNorm_sRGB,
+        (ColorSpace::Srgb, F::B8G8R8A8_UNorm) => F::B8G8R8A8_UNorm_sRGB,
+        (ColorSpace::Srgb, F::BC1_UNorm) => F::BC1_UNorm_sRGB,
+        (ColorSpace::Srgb, F::BC2_UNorm) => F::BC2_UNorm_sRGB,
+        (ColorSpace::Srgb, F::BC3_UNorm) => F::BC3_UNorm_sRGB,
+        (ColorSpace::Srgb, F::BC7_UNorm) => F::BC7_UNorm_sRGB,
+        (ColorSpace::Linear, F::R8G8B8A8_UNorm_sRGB) => F::R8G8B8A8_UNorm,
+        (ColorSpace::Linear, F::B8G8R8A8_UNorm_sRGB) => F::B8G8R8A8_UNorm,
+        (ColorSpace::Linear, F::BC1_UNorm_sRGB) => F::BC1_UNorm,
+        (ColorSpace::Linear, F::BC2_UNorm_sRGB) => F::BC2_UNorm,
+        (ColorSpace::Linear, F::BC3_UNorm_sRGB) => F::BC3_UNorm,
+        (ColorSpace::Linear, F::BC7_UNorm_sRGB) => F::BC7_UNorm,
+        (_, format) => format,
+    }
+}
+
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+/// `gAMA` value (gamma × 100000) of linear data
+const LINEAR_GAMMA: u32 = 100_000;
+/// `gAMA` value of the sRGB curve's 1/2.2 approximation
+const SRGB_GAMMA: u32 = 45_455;
+
+/// Color space a PNG declares through its `sRGB` or `gAMA` chunk
+///
+/// PNGs without either chunk (most image editors' default) return `None`.
+#[must_use]
+pub fn png_color_space(png: &[u8]) -> Option<ColorSpace> {
+    for (kind, data) in png_chunks(png) {
+        match kind {
+            b"sRGB" => return Some(ColorSpace::Srgb),
+            b"gAMA" if data.len() == 4 => {
+                let gamma = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+                if gamma == LINEAR_GAMMA {
+                    return Some(ColorSpace::Linear);
+                }
+                if gamma.abs_diff(SRGB_GAMMA) < 100 {
+                    return Some(ColorSpace::Srgb);
+                }
+            }
+            // Color space chunks must come before the image data
+            b"IDAT" => break,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Add an `sRGB` chunk (or a linear `gAMA` chunk) after a PNG's header,
+/// replacing any `sRGB` or `gAMA` chunk it already has
+///
+/// Returns the data unchanged if it isn't a PNG.
+#[must_use]
+pub fn tag_png_color_space(mut png: Vec<u8>, color_space: ColorSpace) -> Vec<u8> {
+    // Signature, then IHDR: length, type, 13 bytes of data, CRC
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+    if png.len() < IHDR_END || !png.starts_with(PNG_SIGNATURE) || &png[12..16] != b"IHDR" {
+        return png;
+    }
+
+    // Byte ranges of the chunks to drop
+    let mut stale = Vec::new();
+    let mut pos = PNG_SIGNATURE.len();
+    for (kind, data) in png_chunks(&png) {
+        let end = pos + 12 + data.len();
+        match kind {
+            b"sRGB" | b"gAMA" => stale.push(pos..end),
+            b"IDAT" => break,
+            _ => {}
+        }
+        pos = end;
+    }
+    for range in stale.into_iter().rev() {
+        png.drain(range);
+    }
+
+    let (kind, data): (&[u8; 4], Vec<u8>) = match color_space {
+        // Rendering intent 0: perceptual
+        ColorSpace::Srgb => (b"sRGB", vec![0]),
+        ColorSpace::Linear => (b"gAMA", LINEAR_GAMMA.to_be_bytes().to_vec()),
+    };
+    let mut chunk = Vec::with_capacity(12 + data.len());
+    chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(&data);
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(kind);
+    hasher.update(&data);
+    chunk.extend_from_slice(&hasher.finalize().to_be_bytes());
+
+    png.splice(IHDR_END..IHDR_END, chunk);
+    png
+}
+
+/// `(type, data)` of each chunk in a PNG, stopping at the first truncated one
+fn png_chunks(png: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut pos = if png.starts_with(PNG_SIGNATURE) {
+        PNG_SIGNATURE.len()
+    } else {
+        png.len()
+    };
+    std::iter::from_fn(move || {
+        let header = png.get(pos..pos + 8)?;
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let data = png.get(pos + 8..(pos + 8).checked_add(len)?)?;
+        let kind = &header[4..8];
+        pos += 12 + len;
+        Some((kind, data))
+    })
+}
@@ -9,19 +9,23 @@
     clippy::trivially_copy_pass_by_ref
 )]
 
+use super::color_space::{ColorSpace, dxgi_format_for};
 use crate::error::{Error, Result};
 use ddsfile::{AlphaMode, D3DFormat, Dds, DxgiFormat, NewDxgiParams};
 
 /// DDS compression format for PNG to DDS conversion
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum DdsFormat {
     /// BC1/DXT1 - Good for opaque textures or 1-bit alpha
     BC1,
     /// BC2/DXT3 - Explicit 4-bit alpha, good for sharp alpha transitions
     BC2,
     /// BC3/DXT5 - Interpolated alpha, good for smooth alpha gradients
+    #[default]
     BC3,
+    /// BC5/ATI2 - Red and green channels only, used for normal maps
+    BC5,
     /// Uncompressed RGBA
     Rgba,
 }
@@ -35,17 +39,76 @@ pub fn encode_to_dds(pixels: &[u8], width: u32, height: u32, format: DdsFormat)
         DdsFormat::BC1 => encode_bc1_dds(pixels, width, height),
         DdsFormat::BC2 => encode_bc2_dds(pixels, width, height),
         DdsFormat::BC3 => encode_bc3_dds(pixels, width, height),
-        DdsFormat::Rgba => encode_rgba_dds(pixels, width, height),
+        DdsFormat::BC5 => build_dds_with_dxgi_format(
+            width,
+            height,
+            DxgiFormat::BC5_UNorm,
+            &encode_bc5(pixels, width as usize, height as usize),
+        ),
+        DdsFormat::Rgba => {
+            build_dds_with_dxgi_format(width, height, DxgiFormat::R8G8B8A8_UNorm, pixels)
+        }
+    }
+}
+
+/// Encode RGBA pixels to DDS, recording `color_space` in a DX10 header
+///
+/// With `None` this is [`encode_to_dds`]: BC1-BC3 get a legacy header, which
+/// carries no color space. BC5 has no sRGB variant and stays linear.
+///
+/// # Errors
+/// Returns an error if encoding fails.
+pub fn encode_to_dds_with_color_space(
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+    format: DdsFormat,
+    color_space: Option<ColorSpace>,
+) -> Result<Vec<u8>> {
+    let Some(color_space) = color_space else {
+        return encode_to_dds(pixels, width, height, format);
+    };
+    let dxgi_format = match format {
+        DdsFormat::BC1 => DxgiFormat::BC1_UNorm,
+        DdsFormat::BC2 => DxgiFormat::BC2_UNorm,
+        DdsFormat::BC3 => DxgiFormat::BC3_UNorm,
+        DdsFormat::BC5 => DxgiFormat::BC5_UNorm,
+        DdsFormat::Rgba => DxgiFormat::R8G8B8A8_UNorm,
+    };
+    build_dds_with_dxgi_format(
+        width,
+        height,
+        dxgi_format_for(dxgi_format, color_space),
+        &encode_blocks(pixels, width, height, format),
+    )
+}
+
+/// Encode RGBA pixels to raw block data, without a DDS header
+///
+/// `Rgba` returns the pixels unchanged.
+pub(crate) fn encode_blocks(pixels: &[u8], width: u32, height: u32, format: DdsFormat) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    match format {
+        DdsFormat::BC1 => encode_bc1(pixels, width, height),
+        DdsFormat::BC2 => encode_bc2(pixels, width, height),
+        DdsFormat::BC3 => encode_bc3(pixels, width, height),
+        DdsFormat::BC5 => encode_bc5(pixels, width, height),
+        DdsFormat::Rgba => pixels.to_vec(),
     }
 }
 
-/// Encode as uncompressed RGBA DDS
-fn encode_rgba_dds(pixels: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+/// Build a DDS file with a DXGI format (DX10 header)
+fn build_dds_with_dxgi_format(
+    width: u32,
+    height: u32,
+    format: DxgiFormat,
+    data: &[u8],
+) -> Result<Vec<u8>> {
     let mut dds = Dds::new_dxgi(NewDxgiParams {
         height,
         width,
         depth: None,
-        format: DxgiFormat::R8G8B8A8_UNorm,
+        format,
         mipmap_levels: None,
         array_layers: None,
         caps2: None,
@@ -55,10 +118,10 @@ fn encode_rgba_dds(pixels: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
     })
     .map_err(|e| Error::DdsError(format!("Failed to create DDS: {e}")))?;
 
-    let data = dds
+    let dds_data = dds
         .get_mut_data(0)
         .map_err(|e| Error::DdsError(format!("No DDS data layer: {e}")))?;
-    data.copy_from_slice(pixels);
+    dds_data.copy_from_slice(data);
 
     let mut output = Vec::new();
     dds.write(&mut output)
@@ -231,7 +294,7 @@ fn encode_bc3_block(block: &[[u8; 4]; 16]) -> [u8; 16] {
     let mut output = [0u8; 16];
 
     // First 8 bytes: interpolated alpha block
-    let alpha_block = encode_bc3_alpha_block(block);
+    let alpha_block = encode_channel_block(block, 3);
     output[0..8].copy_from_slice(&alpha_block);
 
     // Last 8 bytes: BC1 color block
@@ -241,14 +304,14 @@ fn encode_bc3_block(block: &[[u8; 4]; 16]) -> [u8; 16] {
     output
 }
 
-/// Encode alpha channel for BC3 (8 bytes)
-fn encode_bc3_alpha_block(block: &[[u8; 4]; 16]) -> [u8; 8] {
+/// Encode one channel as a BC3 alpha / BC4 block (8 bytes)
+fn encode_channel_block(block: &[[u8; 4]; 16], channel: usize) -> [u8; 8] {
     // Find min/max alpha
     let mut min_alpha = 255u8;
     let mut max_alpha = 0u8;
     for pixel in block {
-        min_alpha = min_alpha.min(pixel[3]);
-        max_alpha = max_alpha.max(pixel[3]);
+        min_alpha = min_alpha.min(pixel[channel]);
+        max_alpha = max_alpha.max(pixel[channel]);
     }
 
     // Use 8-value interpolation (a0 > a1)
@@ -283,7 +346,7 @@ fn encode_bc3_alpha_block(block: &[[u8; 4]; 16]) -> [u8; 8] {
     // Find best index for each pixel
     let mut indices: u64 = 0;
     for (i, pixel) in block.iter().enumerate() {
-        let alpha = pixel[3];
+        let alpha = pixel[channel];
         let mut best_idx = 0u64;
         let mut best_dist = 256i32;
         for (j, &palette_alpha) in alphas.iter().enumerate() {
@@ -310,6 +373,28 @@ fn encode_bc3_alpha_block(block: &[[u8; 4]; 16]) -> [u8; 8] {
     output
 }
 
+// ============================================================================
+// BC5 (ATI2) Encoding
+// ============================================================================
+
+/// Encode the red and green channels of RGBA pixels to BC5 format
+fn encode_bc5(pixels: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let blocks_x = width.div_ceil(4);
+    let blocks_y = height.div_ceil(4);
+    let mut output = vec![0u8; blocks_x * blocks_y * 16];
+
+    for by in 0..blocks_y {
+        for bx in 0..blocks_x {
+            let block = extract_block(pixels, width, height, bx * 4, by * 4);
+            let offset = (by * blocks_x + bx) * 16;
+            output[offset..offset + 8].copy_from_slice(&encode_channel_block(&block, 0));
+            output[offset + 8..offset + 16].copy_from_slice(&encode_channel_block(&block, 1));
+        }
+    }
+
+    output
+}
+
 // ============================================================================
 // Shared Helpers
 // ============================================================================
@@ -0,0 +1,341 @@
+//! DDS header inspection and validation
+//!
+//! [`DdsInfo`] exposes every field of the DDS header and its DX10 extension,
+//! along with [`DdsIssue`]s for the problems that make other tools (and the
+//! virtual texture builder) fail later in less obvious ways.
+
+use std::fmt;
+
+use ddsfile::Dds;
+use serde::Serialize;
+
+use super::color_space::{ColorSpace, dds_color_space};
+
+/// Header details of a DDS texture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DdsInfo {
+    /// Width of the top mip level in pixels
+    pub width: u32,
+    /// Height of the top mip level in pixels
+    pub height: u32,
+    /// Depth of a volume texture (1 for 2D textures)
+    pub depth: u32,
+    /// Pixel format name (e.g. `BC7_UNorm`, `DXT5`)
+    pub format: String,
+    /// Number of mip levels (1 if the texture has no mipmaps)
+    pub mip_count: u32,
+    /// Bytes per row of an uncompressed texture, if the header sets it
+    pub pitch: Option<u32>,
+    /// Bytes in the top level of a compressed texture, if the header sets it
+    pub linear_size: Option<u32>,
+    /// `DDPF_*` pixel format flags
+    pub pixel_format_flags: u32,
+    /// Four-character code of the pixel format, if any
+    pub fourcc: Option<String>,
+    /// Bits per pixel of an uncompressed format
+    pub rgb_bit_count: Option<u32>,
+    /// Red, green, blue and alpha bit masks of an uncompressed format
+    pub bit_masks: Option<[u32; 4]>,
+    /// `DDSCAPS_*` flags
+    pub caps: u32,
+    /// `DDSCAPS2_*` flags (cube map faces, volume)
+    pub caps2: u32,
+    /// DXGI format from the DX10 extension header
+    pub dxgi_format: Option<String>,
+    /// Resource dimension from the DX10 extension header
+    pub resource_dimension: Option<String>,
+    /// sRGB or linear, from the DX10 format (legacy headers don't say)
+    pub color_space: Option<ColorSpace>,
+    /// Number of array layers (6 for a cube map without a DX10 header)
+    pub array_size: u32,
+    /// Bytes of pixel data after the headers
+    pub data_size: usize,
+    /// Problems found in the header
+    pub issues: Vec<DdsIssue>,
+}
+
+impl DdsInfo {
+    pub(crate) fn from_dds(dds: &Dds) -> Self {
+        let spf = &dds.header.spf;
+        let fourcc = spf.fourcc.as_ref().map(|code| fourcc_string(code.0));
+        let format = if let Some(dxgi) = dds.get_dxgi_format() {
+            format!("{dxgi:?}")
+        } else if let Some(d3d) = dds.get_d3d_format() {
+            format!("{d3d:?}")
+        } else {
+            fourcc.clone().unwrap_or_else(|| "Unknown".to_string())
+        };
+        let bit_masks = match (
+            spf.r_bit_mask,
+            spf.g_bit_mask,
+            spf.b_bit_mask,
+            spf.a_bit_mask,
+        ) {
+            (None, None, None, None) => None,
+            (r, g, b, a) => Some([
+                r.unwrap_or(0),
+                g.unwrap_or(0),
+                b.unwrap_or(0),
+                a.unwrap_or(0),
+            ]),
+        };
+
+        Self {
+            width: dds.get_width(),
+            height: dds.get_height(),
+            depth: dds.get_depth(),
+            format,
+            mip_count: dds.get_num_mipmap_levels().max(1),
+            pitch: dds.header.pitch,
+            linear_size: dds.header.linear_size,
+            pixel_format_flags: spf.flags.bits(),
+            fourcc,
+            rgb_bit_count: spf.rgb_bit_count,
+            bit_masks,
+            caps: dds.header.caps.bits(),
+            caps2: dds.header.caps2.bits(),
+            dxgi_format: dds
+                .header10
+                .as_ref()
+                .map(|h| format!("{:?}", h.dxgi_format)),
+            resource_dimension: dds
+                .header10
+                .as_ref()
+                .map(|h| format!("{:?}", h.resource_dimension)),
+            color_space: dds_color_space(dds),
+            array_size: dds.get_num_array_layers(),
+            data_size: dds.data.len(),
+            issues: dds_issues(dds),
+        }
+    }
+}
+
+/// A problem with a DDS header
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DdsIssue {
+    /// The pitch or linear size field disagrees with the format
+    PitchMismatch {
+        /// `pitch` or `linear size`
+        field: &'static str,
+        /// Value in the header
+        actual: u32,
+        /// Value the format and width call for
+        expected: u32,
+    },
+    /// More mip levels than the dimensions allow
+    TooManyMips {
+        /// Mip count in the header
+        mip_count: u32,
+        /// Length of a full mip chain for the dimensions
+        max: u32,
+    },
+    /// Less pixel data than the header's mips and layers need
+    DataTooShort {
+        /// Bytes of pixel data present
+        actual: usize,
+        /// Bytes the header calls for
+        expected: usize,
+    },
+    /// Block-compressed texture whose dimensions aren't multiples of 4
+    UnalignedBlockDimensions {
+        /// Width in pixels
+        width: u32,
+        /// Height in pixels
+        height: u32,
+    },
+    /// BC7 data marked with a non-standard fourcc instead of a DX10 header
+    MissingDx10Header {
+        /// The fourcc found
+        fourcc: String,
+    },
+}
+
+impl fmt::Display for DdsIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PitchMismatch {
+                field,
+                actual,
+                expected,
+            } => write!(
+                f,
+                "{field} is {actual} bytes, expected {expected} for this format"
+            ),
+            Self::TooManyMips { mip_count, max } => write!(
+                f,
+                "header lists {mip_count} mip levels, but the dimensions allow at most {max}"
+            ),
+            Self::DataTooShort { actual, expected } => write!(
+                f,
+                "pixel data is {actual} bytes, but the header's mips and layers need {expected}"
+            ),
+            Self::UnalignedBlockDimensions { width, height } => write!(
+                f,
+                "{width}x{height} is not a multiple of 4, which block-compressed formats need"
+            ),
+            Self::MissingDx10Header { fourcc } => write!(
+                f,
+                "fourcc '{fourcc}' is not standard; BC7 textures need a DX10 header"
+            ),
+        }
+    }
+}
+
+/// Check a parsed DDS for header problems
+#[must_use]
+fn dds_issues(dds: &Dds) -> Vec<DdsIssue> {
+    let width = dds.get_width();
+    let height = dds.get_height();
+    let mut issues = Vec::new();
+
+    if dds.header10.is_none()
+        && let Some(fourcc) = &dds.header.spf.fourcc
+    {
+        let code = fourcc_string(fourcc.0);
+        if code.to_ascii_uppercase().starts_with("BC7") {
+            issues.push(DdsIssue::MissingDx10Header { fourcc: code });
+        }
+    }
+
+    let format = dds.get_format();
+    let block_size = format.as_ref().and_then(|f| f.get_block_size());
+    if block_size.is_some() && (width % 4 != 0 || height % 4 != 0) {
+        issues.push(DdsIssue::UnalignedBlockDimensions { width, height });
+    }
+
+    let max_mips = 32 - width.max(height).max(1).leading_zeros();
+    let mip_count = dds.get_num_mipmap_levels().max(1);
+    if mip_count > max_mips {
+        issues.push(DdsIssue::TooManyMips {
+            mip_count,
+            max: max_mips,
+        });
+    }
+
+    let Some(level_size) = level_size_fn(dds, block_size) else {
+        return issues;
+    };
+
+    let (row, top) = level_size(width, height);
+    for (field, actual, expected) in [
+        ("pitch", dds.header.pitch, row),
+        ("linear size", dds.header.linear_size, top),
+    ] {
+        if let Some(actual) = actual.filter(|&v| v != 0)
+            && u64::from(actual) != expected
+        {
+            issues.push(DdsIssue::PitchMismatch {
+                field,
+                actual,
+                expected: u32::try_from(expected).unwrap_or(u32::MAX),
+            });
+        }
+    }
+
+    let mut expected: u64 = 0;
+    for level in 0..mip_count.min(max_mips) {
+        let depth = (dds.get_depth() >> level).max(1);
+        expected +=
+            level_size((width >> level).max(1), (height >> level).max(1)).1 * u64::from(depth);
+    }
+    expected *= u64::from(dds.get_num_array_layers().max(1));
+    let actual = dds.data.len();
+    if (actual as u64) < expected {
+        issues.push(DdsIssue::DataTooShort {
+            actual,
+            expected: usize::try_from(expected).unwrap_or(usize::MAX),
+        });
+    }
+
+    issues
+}
+
+/// `(row pitch, level size)` in bytes for a mip of the given size, if the
+/// format is known
+fn level_size_fn(dds: &Dds, block_size: Option<u32>) -> Option<impl Fn(u32, u32) -> (u64, u64)> {
+    let bits_per_pixel = match block_size {
+        Some(_) => 0,
+        None => u64::from(dds.get_bits_per_pixel()?),
+    };
+    Some(move |width: u32, height: u32| {
+        if let Some(size) = block_size {
+            let row = u64::from(width.div_ceil(4)) * u64::from(size);
+            (row, row * u64::from(height.div_ceil(4)))
+        } else {
+            let row = (u64::from(width) * bits_per_pixel).div_ceil(8);
+            (row, row * u64::from(height))
+        }
+    })
+}
+
+/// A fourcc as text, with unprintable bytes replaced
+fn fourcc_string(code: u32) -> String {
+    code.to_le_bytes()
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '?'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddsfile::{AlphaMode, D3D10ResourceDimension, DxgiFormat, NewDxgiParams};
+
+    fn bc7(width: u32, height: u32, mips: u32) -> Dds {
+        Dds::new_dxgi(NewDxgiParams {
+            height,
+            width,
+            depth: None,
+            format: DxgiFormat::BC7_UNorm,
+            mipmap_levels: Some(mips),
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: D3D10ResourceDimension::Texture2D,
+            alpha_mode: AlphaMode::Unknown,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dds_issues() {
+        let dds = bc7(64, 32, 7);
+        let info = DdsInfo::from_dds(&dds);
+        assert_eq!(info.dxgi_format.as_deref(), Some("BC7_UNorm"));
+        assert_eq!(info.fourcc.as_deref(), Some("DX10"));
+        assert_eq!(info.array_size, 1);
+        assert!(info.issues.is_empty(), "{:?}", info.issues);
+
+        let mut dds = bc7(64, 30, 9);
+        dds.data.truncate(100);
+        let issues = dds_issues(&dds);
+        assert!(issues.contains(&DdsIssue::UnalignedBlockDimensions {
+            width: 64,
+            height: 30
+        }));
+        assert!(issues.contains(&DdsIssue::TooManyMips {
+            mip_count: 9,
+            max: 7
+        }));
+        assert!(
+            issues
+                .iter()
+                .any(|issue| matches!(issue, DdsIssue::DataTooShort { actual: 100, .. }))
+        );
+
+        let mut dds = bc7(8, 8, 1);
+        dds.header10 = None;
+        dds.header.spf.fourcc = Some(ddsfile::FourCC(u32::from_le_bytes(*b"BC7 ")));
+        assert!(dds_issues(&dds).contains(&DdsIssue::MissingDx10Header {
+            fourcc: "BC7 ".to_string()
+        }));
+    }
+}
@@ -7,8 +7,11 @@
 
 #![allow(clippy::cast_possible_truncation)]
 
+mod batch;
+mod color_space;
 mod decode;
 mod encode;
+mod info;
 pub mod types;
 
 use crate::error::{Error, Result};
@@ -18,7 +21,14 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
+pub use batch::{
+    TextureBatchEntry, TextureBatchOptions, TextureBatchReport, TextureBatchStatus,
+    batch_convert_textures, batch_convert_textures_cancellable,
+};
+pub use color_space::{ColorSpace, png_color_space, tag_png_color_space};
 pub use encode::DdsFormat;
+pub(crate) use encode::encode_blocks;
+pub use info::{DdsInfo, DdsIssue};
 pub use types::{ImagePhase, ImageProgress, ImageProgressCallback};
 
 /// Convert a DDS file to PNG
@@ -57,10 +67,7 @@ pub fn convert_dds_to_png_with_progress<P: AsRef<Path>, Q: AsRef<Path>>(
     progress(&ImageProgress::new(ImagePhase::Encoding, 3, 4));
     let img: RgbaImage = ImageBuffer::from_raw(dds.get_width(), dds.get_height(), rgba)
         .ok_or_else(|| Error::DdsError("Failed to create image buffer".to_string()))?;
-    let mut png_data = Vec::new();
-    let encoder = image::codecs::png::PngEncoder::new(&mut png_data);
-    img.write_with_encoder(encoder)
-        .map_err(|e| Error::DdsError(format!("Failed to encode PNG: {e}")))?;
+    let png_data = encode_png(&img, color_space::dds_color_space(&dds))?;
 
     progress(&ImageProgress::with_file(
         ImagePhase::WritingFile,
@@ -75,25 +82,51 @@ pub fn convert_dds_to_png_with_progress<P: AsRef<Path>, Q: AsRef<Path>>(
     Ok(())
 }
 
-/// Convert DDS bytes to PNG bytes
+/// Read the header of a DDS texture without decoding any pixels
 ///
 /// # Errors
-/// Returns an error if the DDS data cannot be parsed or decoded.
-pub fn dds_bytes_to_png_bytes(dds_data: &[u8]) -> Result<Vec<u8>> {
+/// Returns an error if the DDS header cannot be parsed.
+pub fn dds_info(dds_data: &[u8]) -> Result<DdsInfo> {
     let dds = Dds::read(&mut std::io::Cursor::new(dds_data))
         .map_err(|e| Error::DdsError(format!("Failed to parse DDS: {e}")))?;
+    Ok(DdsInfo::from_dds(&dds))
+}
 
-    let rgba = decode::decode_dds_to_rgba(&dds)?;
+/// Decode the top mip level of a DDS texture to an RGBA image
+///
+/// # Errors
+/// Returns an error if the DDS data cannot be parsed or its format is unsupported.
+pub fn decode_dds_bytes(dds_data: &[u8]) -> Result<(DdsInfo, RgbaImage)> {
+    let dds = Dds::read(&mut std::io::Cursor::new(dds_data))
+        .map_err(|e| Error::DdsError(format!("Failed to parse DDS: {e}")))?;
 
+    let rgba = decode::decode_dds_to_rgba(&dds)?;
     let img: RgbaImage = ImageBuffer::from_raw(dds.get_width(), dds.get_height(), rgba)
         .ok_or_else(|| Error::DdsError("Failed to create image buffer".to_string()))?;
 
+    Ok((DdsInfo::from_dds(&dds), img))
+}
+
+/// Convert DDS bytes to PNG bytes
+///
+/// # Errors
+/// Returns an error if the DDS data cannot be parsed or decoded.
+pub fn dds_bytes_to_png_bytes(dds_data: &[u8]) -> Result<Vec<u8>> {
+    let (info, img) = decode_dds_bytes(dds_data)?;
+    encode_png(&img, info.color_space)
+}
+
+/// Encode an image as PNG, tagged with the DDS's color space if it has one
+fn encode_png(img: &RgbaImage, color_space: Option<ColorSpace>) -> Result<Vec<u8>> {
     let mut png_data = Vec::new();
     let encoder = image::codecs::png::PngEncoder::new(&mut png_data);
     img.write_with_encoder(encoder)
         .map_err(|e| Error::DdsError(format!("Failed to encode PNG: {e}")))?;
 
-    Ok(png_data)
+    Ok(match color_space {
+        Some(color_space) => tag_png_color_space(png_data, color_space),
+        None => png_data,
+    })
 }
 
 /// Convert a PNG file to DDS with default BC3 compression
@@ -137,6 +170,25 @@ pub fn convert_png_to_dds_with_format_and_progress<P: AsRef<Path>, Q: AsRef<Path
     dds_path: Q,
     format: DdsFormat,
     progress: ImageProgressCallback,
+) -> Result<()> {
+    convert_png_to_dds_with_color_space(png_path, dds_path, format, None, progress)
+}
+
+/// Convert a PNG file to DDS, keeping or overriding its color space
+///
+/// The DDS gets a DX10 header with the sRGB or linear variant of `format`.
+/// Without an override, the color space comes from the PNG's `sRGB` or
+/// `gAMA` chunk; an untagged PNG gets the same legacy header as
+/// [`convert_png_to_dds_with_format`].
+///
+/// # Errors
+/// Returns an error if the file cannot be read or conversion fails.
+pub fn convert_png_to_dds_with_color_space<P: AsRef<Path>, Q: AsRef<Path>>(
+    png_path: P,
+    dds_path: Q,
+    format: DdsFormat,
+    color_space: Option<ColorSpace>,
+    progress: ImageProgressCallback,
 ) -> Result<()> {
     progress(&ImageProgress::with_file(
         ImagePhase::ReadingFile,
@@ -144,7 +196,9 @@ pub fn convert_png_to_dds_with_format_and_progress<P: AsRef<Path>, Q: AsRef<Path
         4,
         png_path.as_ref().display().to_string(),
     ));
-    let img = image::open(png_path.as_ref())
+    let data = std::fs::read(png_path.as_ref())?;
+    let color_space = color_space.or_else(|| png_color_space(&data));
+    let img = image::load_from_memory(&data)
         .map_err(|e| Error::DdsError(format!("Failed to open PNG: {e}")))?;
 
     progress(&ImageProgress::new(ImagePhase::Decoding, 2, 4));
@@ -159,7 +213,8 @@ pub fn convert_png_to_dds_with_format_and_progress<P: AsRef<Path>, Q: AsRef<Path
         4,
         format!("{format:?}"),
     ));
-    let dds_data = encode::encode_to_dds(pixels, width, height, format)?;
+    let dds_data =
+        encode::encode_to_dds_with_color_space(pixels, width, height, format, color_space)?;
 
     progress(&ImageProgress::with_file(
         ImagePhase::WritingFile,
@@ -179,10 +234,102 @@ pub fn convert_png_to_dds_with_format_and_progress<P: AsRef<Path>, Q: AsRef<Path
 /// # Errors
 /// Returns an error if encoding fails.
 pub fn png_image_to_dds_bytes(img: &DynamicImage, format: DdsFormat) -> Result<Vec<u8>> {
+    png_image_to_dds_bytes_with_color_space(img, format, None)
+}
+
+/// Convert an image to DDS bytes, recording its color space in a DX10 header
+///
+/// With `None` this is [`png_image_to_dds_bytes`].
+///
+/// # Errors
+/// Returns an error if encoding fails.
+pub fn png_image_to_dds_bytes_with_color_space(
+    img: &DynamicImage,
+    format: DdsFormat,
+    color_space: Option<ColorSpace>,
+) -> Result<Vec<u8>> {
     let rgba = img.to_rgba8();
     let width = rgba.width();
     let height = rgba.height();
     let pixels = rgba.as_raw();
 
-    encode::encode_to_dds(pixels, width, height, format)
+    encode::encode_to_dds_with_color_space(pixels, width, height, format, color_space)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_dds_bytes() {
+        let img =
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 4, image::Rgba([255, 0, 0, 255])));
+        let dds = png_image_to_dds_bytes(&img, DdsFormat::BC1).unwrap();
+
+        let info = dds_info(&dds).unwrap();
+        let (decoded_info, decoded) = decode_dds_bytes(&dds).unwrap();
+
+        assert_eq!((info.width, info.height), (8, 4));
+        assert_eq!(info.mip_count, 1);
+        assert_eq!(info, decoded_info);
+        assert_eq!(decoded.get_pixel(3, 2).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_color_space_round_trip() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 8, image::Rgba([200; 4])));
+
+        for (color_space, bc3, rgba) in [
+            (ColorSpace::Srgb, "BC3_UNorm_sRGB", "R8G8B8A8_UNorm_sRGB"),
+            (ColorSpace::Linear, "BC3_UNorm", "R8G8B8A8_UNorm"),
+        ] {
+            let dds =
+                png_image_to_dds_bytes_with_color_space(&img, DdsFormat::BC3, Some(color_space))
+                    .unwrap();
+            let info = dds_info(&dds).unwrap();
+            assert_eq!(info.dxgi_format.as_deref(), Some(bc3));
+            assert_eq!(info.color_space, Some(color_space));
+
+            // The PNG carries the flag, so converting it back restores the format
+            let dds =
+                png_image_to_dds_bytes_with_color_space(&img, DdsFormat::Rgba, Some(color_space))
+                    .unwrap();
+            let png = dds_bytes_to_png_bytes(&dds).unwrap();
+            assert_eq!(png_color_space(&png), Some(color_space));
+            let other = match color_space {
+                ColorSpace::Srgb => ColorSpace::Linear,
+                ColorSpace::Linear => ColorSpace::Srgb,
+            };
+            let retagged = tag_png_color_space(png.clone(), other);
+            assert_eq!(png_color_space(&retagged), Some(other));
+            // Tagging replaces the old chunk instead of adding a second one
+            assert_eq!(tag_png_color_space(retagged, color_space), png);
+            assert!(image::load_from_memory(&png).is_ok());
+
+            let dir = tempfile::tempdir().unwrap();
+            let (png_path, dds_path) = (dir.path().join("t.png"), dir.path().join("t.dds"));
+            std::fs::write(&png_path, &png).unwrap();
+            convert_png_to_dds_with_color_space(
+                &png_path,
+                &dds_path,
+                DdsFormat::Rgba,
+                None,
+                &|_| {},
+            )
+            .unwrap();
+            let info = dds_info(&std::fs::read(&dds_path).unwrap()).unwrap();
+            assert_eq!(info.dxgi_format.as_deref(), Some(rgba));
+        }
+
+        // BC5 has no sRGB variant; legacy headers don't record a color space
+        let dds =
+            png_image_to_dds_bytes_with_color_space(&img, DdsFormat::BC5, Some(ColorSpace::Srgb))
+                .unwrap();
+        assert_eq!(
+            dds_info(&dds).unwrap().dxgi_format.as_deref(),
+            Some("BC5_UNorm")
+        );
+        let dds = png_image_to_dds_bytes(&img, DdsFormat::BC1).unwrap();
+        assert_eq!(dds_info(&dds).unwrap().color_space, None);
+    }
 }
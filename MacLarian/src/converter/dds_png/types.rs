@@ -73,6 +73,8 @@ pub enum ImagePhase {
     Encoding,
     /// Writing the output file
     WritingFile,
+    /// Converting a batch of files
+    Converting,
     /// Operation complete
     Complete,
 }
@@ -86,6 +88,7 @@ impl ImagePhase {
             Self::Decoding => "Decoding",
             Self::Encoding => "Encoding",
             Self::WritingFile => "Writing file",
+            Self::Converting => "Converting",
             Self::Complete => "Complete",
         }
     }
@@ -8,9 +8,12 @@ pub(crate) mod shared;
 pub mod to_gltf;
 pub mod to_gr2;
 pub mod types;
+pub mod weights;
 
-// Re-export progress types
+// Re-export progress and write option types
+pub use types::{Gr2Compression, Gr2WriteOptions};
 pub use types::{Gr2Phase, Gr2Progress, Gr2ProgressCallback};
+pub use weights::{WEIGHT_SUM_RANGES, WeightReport, gltf_weight_report, gr2_weight_report};
 
 // Re-export GR2 → glTF conversion functions
 pub use to_gltf::{TexturedGlbResult, convert_gr2_bytes_to_glb_with_textures};
@@ -23,3 +26,4 @@ pub use to_gltf::{
 // Re-export glTF → GR2 conversion functions
 pub use to_gr2::{convert_gltf_bytes_to_gr2, convert_gltf_to_gr2};
 pub use to_gr2::{convert_gltf_bytes_to_gr2_with_progress, convert_gltf_to_gr2_with_progress};
+pub use to_gr2::{convert_gltf_bytes_to_gr2_with_options, convert_gltf_to_gr2_with_options};
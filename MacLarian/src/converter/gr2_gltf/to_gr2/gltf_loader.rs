@@ -12,6 +12,7 @@ use std::path::Path;
 
 use super::utils::encode_qtangent;
 use crate::converter::gr2_gltf::to_gltf::{Bg3MeshProfile, Bg3SkeletonProfile};
+use crate::converter::gr2_gltf::weights::{normalize_influences, quantize_weights};
 use crate::error::{Error, Result};
 
 // ============================================================================
@@ -117,29 +118,33 @@ pub struct GltfModel {
 impl GltfModel {
     /// Load a glTF or GLB file.
     ///
+    /// With `fix_weights`, bone weights are renormalized (see
+    /// [`normalize_influences`]) before they're converted to bytes.
+    ///
     /// # Errors
     /// Returns an error if the file cannot be read or has no meshes.
-    pub fn load(path: &Path) -> Result<Self> {
+    pub fn load(path: &Path, fix_weights: bool) -> Result<Self> {
         let (document, buffers, _images) = gltf::import(path)
             .map_err(|e| Error::ConversionError(format!("Failed to load glTF: {e}")))?;
 
-        Self::load_from_document(&document, &buffers)
+        Self::load_from_document(&document, &buffers, fix_weights)
     }
 
     /// Load from GLB bytes.
     ///
     /// # Errors
     /// Returns an error if the data cannot be parsed or has no meshes.
-    pub fn load_from_bytes(data: &[u8]) -> Result<Self> {
+    pub fn load_from_bytes(data: &[u8], fix_weights: bool) -> Result<Self> {
         let (document, buffers, _images) = gltf::import_slice(data)
             .map_err(|e| Error::ConversionError(format!("Failed to load glTF: {e}")))?;
 
-        Self::load_from_document(&document, &buffers)
+        Self::load_from_document(&document, &buffers, fix_weights)
     }
 
     fn load_from_document(
         document: &gltf::Document,
         buffers: &[gltf::buffer::Data],
+        fix_weights: bool,
     ) -> Result<Self> {
         let mut meshes = Vec::new();
         let mut skeleton = None;
@@ -184,7 +189,9 @@ impl GltfModel {
                         mesh.name().unwrap_or(node_name).to_string()
                     };
 
-                    if let Some(mut mesh_data) = load_primitive(&primitive, buffers, &name)? {
+                    if let Some(mut mesh_data) =
+                        load_primitive(&primitive, buffers, &name, fix_weights)?
+                    {
                         // Extract extension data before overwriting the profile
                         if let Some(ref profile) = bg3_profile {
                             extract_mesh_extension_data(&mut mesh_data, profile);
@@ -414,6 +421,7 @@ fn load_primitive(
     primitive: &gltf::Primitive,
     buffers: &[gltf::buffer::Data],
     name: &str,
+    fix_weights: bool,
 ) -> Result<Option<MeshData>> {
     // Only support triangles
     if primitive.mode() != gltf::mesh::Mode::Triangles {
@@ -468,6 +476,23 @@ fn load_primitive(
         |iter| iter.into_f32().collect(),
     );
 
+    // A second set of joints and weights only matters when fixing weights,
+    // which keeps the largest four of all eight
+    let (extra_joints, extra_weights): (Vec<[u16; 4]>, Vec<[f32; 4]>) = if fix_weights {
+        (
+            reader
+                .read_joints(1)
+                .map(|iter| iter.into_u16().collect())
+                .unwrap_or_default(),
+            reader
+                .read_weights(1)
+                .map(|iter| iter.into_f32().collect())
+                .unwrap_or_default(),
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
     // Read indices
     let indices: Vec<u32> = reader.read_indices().map_or_else(
         || (0..positions.len() as u32).collect(),
@@ -498,13 +523,26 @@ fn load_primitive(
         // Encode to QTangent
         let qtangent = encode_qtangent(&gr2_normal, &gr2_tangent);
 
-        // Convert weights to u8 (0-255)
-        let bone_weights = [
-            (weight[0] * 255.0).clamp(0.0, 255.0) as u8,
-            (weight[1] * 255.0).clamp(0.0, 255.0) as u8,
-            (weight[2] * 255.0).clamp(0.0, 255.0) as u8,
-            (weight[3] * 255.0).clamp(0.0, 255.0) as u8,
-        ];
+        let (joint, bone_weights) = if fix_weights {
+            let mut influences: Vec<(u16, f32)> = joint.into_iter().zip(weight).collect();
+            if let (Some(joints), Some(weights)) = (extra_joints.get(i), extra_weights.get(i)) {
+                influences.extend(joints.iter().copied().zip(weights.iter().copied()));
+            }
+            let normalized = normalize_influences(&influences);
+            (
+                normalized.map(|(joint, _)| joint),
+                quantize_weights(normalized.map(|(_, weight)| weight)),
+            )
+        } else {
+            // Convert weights to u8 (0-255)
+            let bytes = [
+                (weight[0] * 255.0).clamp(0.0, 255.0) as u8,
+                (weight[1] * 255.0).clamp(0.0, 255.0) as u8,
+                (weight[2] * 255.0).clamp(0.0, 255.0) as u8,
+                (weight[3] * 255.0).clamp(0.0, 255.0) as u8,
+            ];
+            (joint, bytes)
+        };
 
         // Convert joint indices (glTF u16 to GR2 u8)
         let bone_indices = [
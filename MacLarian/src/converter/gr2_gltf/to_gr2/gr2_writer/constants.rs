@@ -16,6 +16,10 @@ pub const VERSION: u32 = 7;
 /// Section 6: Index data
 pub const NUM_SECTIONS: u32 = 7;
 
+// Section compression types
+pub const COMPRESSION_NONE: u32 = 0;
+pub const COMPRESSION_BITKNIT: u32 = 4;
+
 // Member types
 pub const MEMBER_NONE: u32 = 0;
 pub const MEMBER_REFERENCE: u32 = 2;
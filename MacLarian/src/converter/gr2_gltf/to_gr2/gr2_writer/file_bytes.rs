@@ -1,12 +1,15 @@
 //! GR2 file bytes generation
 
+use std::borrow::Cow;
+
 use crate::error::Result;
 
 use super::super::utils::crc32;
 use super::Gr2Writer;
-use super::constants::{NUM_SECTIONS, TAG_BG3, VERSION};
+use super::constants::{COMPRESSION_BITKNIT, COMPRESSION_NONE, NUM_SECTIONS, TAG_BG3, VERSION};
 use super::section::Section;
-use crate::formats::gr2::magic;
+use crate::converter::gr2_gltf::Gr2Compression;
+use crate::formats::gr2::{compress_bitknit, magic};
 
 impl Gr2Writer {
     pub(super) fn build_file_bytes(
@@ -15,7 +18,40 @@ impl Gr2Writer {
     ) -> Result<Vec<u8>> {
         let (sections, root_offset, root_type_offset) = sections_data;
 
-        // Calculate offsets - NO COMPRESSION, write uncompressed
+        let compress = self.options.compression == Gr2Compression::BitKnit;
+
+        // Section and relocation payloads as stored in the file. Empty
+        // sections stay uncompressed so readers treat them as empty.
+        let payloads: Vec<(u32, Cow<[u8]>, Vec<u8>)> = sections
+            .iter()
+            .map(|section| {
+                let mut relocs = Vec::with_capacity(section.fixups.len() * 12);
+                for fixup in &section.fixups {
+                    relocs.extend_from_slice(&fixup.offset_in_section.to_le_bytes());
+                    relocs.extend_from_slice(&fixup.target_section.to_le_bytes());
+                    relocs.extend_from_slice(&fixup.target_offset.to_le_bytes());
+                }
+                if !compress || section.data.is_empty() {
+                    return (
+                        COMPRESSION_NONE,
+                        Cow::Borrowed(section.data.as_slice()),
+                        relocs,
+                    );
+                }
+                if !relocs.is_empty() {
+                    let packed = compress_bitknit(&relocs);
+                    relocs = (packed.len() as u32).to_le_bytes().to_vec();
+                    relocs.extend_from_slice(&packed);
+                }
+                (
+                    COMPRESSION_BITKNIT,
+                    Cow::Owned(compress_bitknit(&section.data)),
+                    relocs,
+                )
+            })
+            .collect();
+
+        // Calculate offsets
         let magic_size = 32;
         let header_size = 72; // v7 header
         let section_header_size = 44 * NUM_SECTIONS as usize;
@@ -24,23 +60,21 @@ impl Gr2Writer {
         // Align to 16 bytes
         let data_start = (headers_total + 15) & !15;
 
-        // Calculate section offsets (uncompressed)
+        // Calculate section offsets
         let mut section_offsets = Vec::new();
         let mut current_offset = data_start;
-        for section in sections {
+        for (_, data, _) in &payloads {
             section_offsets.push(current_offset);
-            current_offset += section.len();
+            current_offset += data.len();
             // Align each section to 4 bytes
             current_offset = (current_offset + 3) & !3;
         }
 
-        // Calculate relocation table offsets (uncompressed)
+        // Calculate relocation table offsets
         let mut reloc_offsets = Vec::new();
-        for section in sections {
+        for (_, _, relocs) in &payloads {
             reloc_offsets.push(current_offset);
-            if !section.fixups.is_empty() {
-                current_offset += section.fixups.len() * 12;
-            }
+            current_offset += relocs.len();
         }
 
         let file_size = current_offset;
@@ -75,15 +109,22 @@ impl Gr2Writer {
         // reserved (12 bytes)
         output.extend_from_slice(&[0u8; 12]);
 
-        // Write section headers (uncompressed format)
-        for (i, section) in sections.iter().enumerate() {
-            output.extend_from_slice(&0u32.to_le_bytes()); // compression = 0 (none)
+        // Write section headers
+        for (i, (section, (compression, data, _))) in sections.iter().zip(&payloads).enumerate() {
+            // Granny records where the 16-bit and 8-bit data starts; with no
+            // mixed marshalling that is the end of the section.
+            let first_small = if *compression == COMPRESSION_NONE {
+                0
+            } else {
+                section.len() as u32
+            };
+            output.extend_from_slice(&compression.to_le_bytes());
             output.extend_from_slice(&(section_offsets[i] as u32).to_le_bytes());
-            output.extend_from_slice(&(section.len() as u32).to_le_bytes()); // compressed = uncompressed
+            output.extend_from_slice(&(data.len() as u32).to_le_bytes());
             output.extend_from_slice(&(section.len() as u32).to_le_bytes());
             output.extend_from_slice(&4u32.to_le_bytes()); // alignment
-            output.extend_from_slice(&0u32.to_le_bytes()); // first_16bit
-            output.extend_from_slice(&0u32.to_le_bytes()); // first_8bit
+            output.extend_from_slice(&first_small.to_le_bytes()); // first_16bit
+            output.extend_from_slice(&first_small.to_le_bytes()); // first_8bit
             output.extend_from_slice(&(reloc_offsets[i] as u32).to_le_bytes());
             output.extend_from_slice(&(section.fixups.len() as u32).to_le_bytes());
             output.extend_from_slice(&0u32.to_le_bytes()); // mixed_marshalling_offset
@@ -95,29 +136,25 @@ impl Gr2Writer {
             output.push(0);
         }
 
-        // Write section data (uncompressed)
-        for (i, section) in sections.iter().enumerate() {
+        // Write section data
+        for (i, (_, data, _)) in payloads.iter().enumerate() {
             while output.len() < section_offsets[i] {
                 output.push(0);
             }
-            output.extend_from_slice(&section.data);
+            output.extend_from_slice(data);
             // Align to 4 bytes
             while output.len() % 4 != 0 {
                 output.push(0);
             }
         }
 
-        // Write relocation tables (uncompressed)
-        for (i, section) in sections.iter().enumerate() {
-            if !section.fixups.is_empty() {
+        // Write relocation tables
+        for (i, (_, _, relocs)) in payloads.iter().enumerate() {
+            if !relocs.is_empty() {
                 while output.len() < reloc_offsets[i] {
                     output.push(0);
                 }
-                for fixup in &section.fixups {
-                    output.extend_from_slice(&fixup.offset_in_section.to_le_bytes());
-                    output.extend_from_slice(&fixup.target_section.to_le_bytes());
-                    output.extend_from_slice(&fixup.target_offset.to_le_bytes());
-                }
+                output.extend_from_slice(relocs);
             }
         }
 
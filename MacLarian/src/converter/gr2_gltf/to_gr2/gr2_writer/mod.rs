@@ -3,7 +3,8 @@
 //! Writes meshes and skeletons to GR2 format compatible with
 //! Baldur's Gate 3 and Divinity: Original Sin 2.
 //!
-//! Note: Compression is currently disabled. Files are written uncompressed.
+//! Sections are written uncompressed unless [`Gr2WriteOptions`] selects
+//! `BitKnit` compression.
 
 #![allow(clippy::vec_init_then_push)]
 
@@ -18,6 +19,7 @@ use std::io::Write;
 use std::path::Path;
 
 use super::gltf_loader::{MeshData, ModelData, Skeleton};
+use crate::converter::gr2_gltf::Gr2WriteOptions;
 use crate::error::Result;
 
 // Section is used internally by build_sections and file_bytes
@@ -26,6 +28,7 @@ pub struct Gr2Writer {
     meshes: Vec<MeshData>,
     skeleton: Option<Skeleton>,
     model: Option<ModelData>,
+    options: Gr2WriteOptions,
 }
 
 impl Gr2Writer {
@@ -35,9 +38,15 @@ impl Gr2Writer {
             meshes: Vec::new(),
             skeleton: None,
             model: None,
+            options: Gr2WriteOptions::default(),
         }
     }
 
+    /// Set the options used when building the file
+    pub fn set_options(&mut self, options: Gr2WriteOptions) {
+        self.options = options;
+    }
+
     pub fn add_mesh(&mut self, mesh: &MeshData) {
         self.meshes.push(mesh.clone());
     }
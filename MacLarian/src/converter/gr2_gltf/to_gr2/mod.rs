@@ -2,10 +2,9 @@
 //!
 //! Converts glTF 2.0 files to Granny2 GR2 format.
 //!
-//! Note: Compression is currently disabled/broken. GR2 files are written
-//! with uncompressed data for now.
-//!
-//!
+//! GR2 sections are written uncompressed by default; pass
+//! [`Gr2WriteOptions`] with `Gr2Compression::BitKnit` to compress them like
+//! the game's own files.
 
 #![allow(
     clippy::cast_possible_truncation,
@@ -19,6 +18,7 @@ mod gltf_loader;
 mod gr2_writer;
 mod utils;
 
+use crate::converter::gr2_gltf::Gr2WriteOptions;
 use crate::error::Result;
 use gltf_loader::GltfModel;
 use gr2_writer::Gr2Writer;
@@ -40,6 +40,24 @@ pub fn convert_gltf_to_gr2_with_progress(
     input_path: &Path,
     output_path: &Path,
     progress: crate::converter::gr2_gltf::Gr2ProgressCallback,
+) -> Result<()> {
+    convert_gltf_to_gr2_with_options(
+        input_path,
+        output_path,
+        &Gr2WriteOptions::default(),
+        progress,
+    )
+}
+
+/// Convert a glTF/GLB file to GR2 format with write options and progress callback.
+///
+/// # Errors
+/// Returns an error if conversion fails.
+pub fn convert_gltf_to_gr2_with_options(
+    input_path: &Path,
+    output_path: &Path,
+    options: &Gr2WriteOptions,
+    progress: crate::converter::gr2_gltf::Gr2ProgressCallback,
 ) -> Result<()> {
     use crate::converter::gr2_gltf::{Gr2Phase, Gr2Progress};
 
@@ -49,7 +67,7 @@ pub fn convert_gltf_to_gr2_with_progress(
         4,
         input_path.display().to_string(),
     ));
-    let model = GltfModel::load(input_path)?;
+    let model = GltfModel::load(input_path, options.fix_weights)?;
 
     progress(&Gr2Progress::with_file(
         Gr2Phase::BuildingGr2,
@@ -58,6 +76,7 @@ pub fn convert_gltf_to_gr2_with_progress(
         format!("{} meshes", model.meshes.len()),
     ));
     let mut writer = Gr2Writer::new();
+    writer.set_options(*options);
 
     if let Some(ref skeleton) = model.skeleton {
         writer.add_skeleton(skeleton);
@@ -98,11 +117,23 @@ pub fn convert_gltf_bytes_to_gr2(gltf_data: &[u8]) -> Result<Vec<u8>> {
 pub fn convert_gltf_bytes_to_gr2_with_progress(
     gltf_data: &[u8],
     progress: crate::converter::gr2_gltf::Gr2ProgressCallback,
+) -> Result<Vec<u8>> {
+    convert_gltf_bytes_to_gr2_with_options(gltf_data, &Gr2WriteOptions::default(), progress)
+}
+
+/// Convert glTF data bytes to GR2 data bytes with write options and progress callback.
+///
+/// # Errors
+/// Returns an error if conversion fails.
+pub fn convert_gltf_bytes_to_gr2_with_options(
+    gltf_data: &[u8],
+    options: &Gr2WriteOptions,
+    progress: crate::converter::gr2_gltf::Gr2ProgressCallback,
 ) -> Result<Vec<u8>> {
     use crate::converter::gr2_gltf::{Gr2Phase, Gr2Progress};
 
     progress(&Gr2Progress::new(Gr2Phase::LoadingFile, 1, 4));
-    let model = GltfModel::load_from_bytes(gltf_data)?;
+    let model = GltfModel::load_from_bytes(gltf_data, options.fix_weights)?;
 
     progress(&Gr2Progress::with_file(
         Gr2Phase::BuildingGr2,
@@ -111,6 +142,7 @@ pub fn convert_gltf_bytes_to_gr2_with_progress(
         format!("{} meshes", model.meshes.len()),
     ));
     let mut writer = Gr2Writer::new();
+    writer.set_options(*options);
 
     if let Some(ref skeleton) = model.skeleton {
         writer.add_skeleton(skeleton);
@@ -1,4 +1,4 @@
-//! Types for GR2/glTF conversion progress tracking
+//! Types for GR2/glTF conversion progress tracking and GR2 output options
 //!
 //!
 
@@ -113,3 +113,39 @@ impl Gr2Phase {
         }
     }
 }
+
+// ============================================================================
+// Write Options
+// ============================================================================
+
+/// Section compression used when writing GR2 files
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Gr2Compression {
+    /// Store sections uncompressed
+    #[default]
+    None,
+    /// Compress sections and relocation tables with `BitKnit`, as the game does
+    BitKnit,
+}
+
+/// Options for writing GR2 files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Gr2WriteOptions {
+    /// Section compression
+    pub compression: Gr2Compression,
+    /// Keep each vertex's four largest bone influences and renormalize them
+    /// before they're stored as bytes, so every vertex's weights sum to 255
+    pub fix_weights: bool,
+}
+
+impl Gr2WriteOptions {
+    /// Options that compress sections with `BitKnit`
+    #[must_use]
+    pub fn bitknit() -> Self {
+        Self {
+            compression: Gr2Compression::BitKnit,
+            ..Self::default()
+        }
+    }
+}
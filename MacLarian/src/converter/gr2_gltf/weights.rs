@@ -0,0 +1,251 @@
+//! Bone weight normalization and reporting
+//!
+//! Meshes exported from DCC tools often have influences that don't sum to
+//! 1.0 (or 255 once stored as bytes), which shows up in game as exploded
+//! vertices. [`normalize_influences`] keeps the four largest influences of a
+//! vertex and rescales them to sum to 1.0, and [`quantize_weights`] turns
+//! them into bytes with the largest remainder method, so rounding is spread
+//! over the influences and the bytes sum to exactly 255.
+//!
+//! [`gr2_weight_report`] and [`gltf_weight_report`] describe how far a file's
+//! skinned vertices are from normalized without changing anything.
+
+use std::path::Path;
+
+use super::to_gltf::Gr2Reader;
+use crate::error::{Error, Result, ResultExt};
+
+/// Influences a GR2 vertex can store
+pub const MAX_INFLUENCES: usize = 4;
+
+/// Labels of the [`WeightReport::distribution`] ranges
+pub const WEIGHT_SUM_RANGES: [&str; 6] = ["0", "< 0.9", "0.9 - 1.0", "1.0", "1.0 - 1.1", "> 1.1"];
+
+/// Sums closer to 1.0 than half a byte step count as normalized
+const SUM_TOLERANCE: f32 = 0.5 / 255.0;
+
+/// How the bone weight sums of a model's skinned vertices are distributed
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct WeightReport {
+    /// Skinned vertices checked
+    pub vertices: usize,
+    /// Vertices whose weights don't sum to 1.0
+    pub affected: usize,
+    /// Vertices with more than [`MAX_INFLUENCES`] non-zero influences
+    pub over_limit: usize,
+    /// Vertex counts per weight sum, in the ranges of [`WEIGHT_SUM_RANGES`]
+    pub distribution: [usize; 6],
+}
+
+impl WeightReport {
+    /// Whether every skinned vertex is normalized and within the influence limit
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.affected == 0 && self.over_limit == 0
+    }
+
+    /// Count one vertex given its weights
+    fn add(&mut self, weights: impl IntoIterator<Item = f32>) {
+        let mut sum = 0.0;
+        let mut influences = 0;
+        for weight in weights {
+            sum += weight;
+            if weight > 0.0 {
+                influences += 1;
+            }
+        }
+
+        self.vertices += 1;
+        if influences > MAX_INFLUENCES {
+            self.over_limit += 1;
+        }
+        let range = if sum <= 0.0 {
+            0
+        } else if sum < 0.9 {
+            1
+        } else if sum < 1.0 - SUM_TOLERANCE {
+            2
+        } else if sum <= 1.0 + SUM_TOLERANCE {
+            3
+        } else if sum <= 1.1 {
+            4
+        } else {
+            5
+        };
+        self.distribution[range] += 1;
+        if range != 3 {
+            self.affected += 1;
+        }
+    }
+}
+
+/// Keep the [`MAX_INFLUENCES`] largest influences of a vertex and rescale
+/// them to sum to 1.0
+///
+/// Influences on the same joint are merged first, and negative or non-finite
+/// weights are dropped. A vertex left with no weight at all is bound fully to
+/// its first joint. Unused slots are `(0, 0.0)`.
+#[must_use]
+pub fn normalize_influences(influences: &[(u16, f32)]) -> [(u16, f32); MAX_INFLUENCES] {
+    let mut merged: Vec<(u16, f32)> = Vec::with_capacity(influences.len());
+    for &(joint, weight) in influences {
+        if !weight.is_finite() || weight <= 0.0 {
+            continue;
+        }
+        match merged.iter_mut().find(|(j, _)| *j == joint) {
+            Some((_, w)) => *w += weight,
+            None => merged.push((joint, weight)),
+        }
+    }
+    // Stable, so equal weights keep their original order
+    merged.sort_by(|a, b| b.1.total_cmp(&a.1));
+    merged.truncate(MAX_INFLUENCES);
+
+    let mut result = [(0, 0.0); MAX_INFLUENCES];
+    let total: f32 = merged.iter().map(|(_, w)| w).sum();
+    if merged.is_empty() {
+        result[0] = (influences.first().map_or(0, |&(joint, _)| joint), 1.0);
+        return result;
+    }
+    for (slot, (joint, weight)) in result.iter_mut().zip(merged) {
+        *slot = (joint, weight / total);
+    }
+    result
+}
+
+/// Convert weights summing to 1.0 into bytes summing to exactly 255
+///
+/// Each weight is scaled and rounded down, then the bytes still missing go
+/// to the weights that lost the most in rounding (largest remainder method).
+/// Weights that sum to 0 give all zero bytes.
+#[must_use]
+pub fn quantize_weights(weights: [f32; MAX_INFLUENCES]) -> [u8; MAX_INFLUENCES] {
+    if weights.iter().sum::<f32>() <= 0.0 {
+        return [0; MAX_INFLUENCES];
+    }
+
+    let scaled = weights.map(|w| w.clamp(0.0, 1.0) * 255.0);
+    let mut bytes = scaled.map(|s| s.floor() as u8);
+    let assigned: u32 = bytes.iter().map(|&b| u32::from(b)).sum();
+    let missing = 255u32.saturating_sub(assigned) as usize;
+
+    let mut order = [0, 1, 2, 3];
+    order.sort_by(|&a, &b| {
+        let remainder = |i: usize| scaled[i] - scaled[i].floor();
+        remainder(b).total_cmp(&remainder(a))
+    });
+    for &i in order.iter().take(missing) {
+        bytes[i] = bytes[i].saturating_add(1);
+    }
+    bytes
+}
+
+/// Report the bone weight sums of a GR2 file's skinned meshes
+///
+/// # Errors
+/// Returns an error if the file can't be read or parsed.
+pub fn gr2_weight_report(path: &Path) -> Result<WeightReport> {
+    let data = std::fs::read(path).with_path(path)?;
+    let reader = Gr2Reader::new(&data).with_path(path)?;
+    let mut report = WeightReport::default();
+    for mesh in reader.parse_meshes(&data).with_path(path)? {
+        // Rigid meshes have no bindings and no weights to check
+        if mesh.bone_bindings.is_empty() {
+            continue;
+        }
+        for vertex in &mesh.vertices {
+            report.add(vertex.bone_weights.map(|w| f32::from(w) / 255.0));
+        }
+    }
+    Ok(report)
+}
+
+/// Report the bone weight sums of a glTF/GLB file's skinned primitives
+///
+/// Both `WEIGHTS_0` and `WEIGHTS_1` are counted, so vertices with more than
+/// four influences show up in [`WeightReport::over_limit`].
+///
+/// # Errors
+/// Returns an error if the file can't be loaded.
+pub fn gltf_weight_report(path: &Path) -> Result<WeightReport> {
+    let (document, buffers, _images) = gltf::import(path)
+        .map_err(|e| Error::ConversionError(format!("Failed to load glTF: {e}")))?;
+    let mut report = WeightReport::default();
+    for mesh in document.meshes() {
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let Some(weights) = reader.read_weights(0) else {
+                continue;
+            };
+            let mut extra = reader
+                .read_weights(1)
+                .map(|iter| iter.into_f32().collect::<Vec<_>>().into_iter());
+            for weights in weights.into_f32() {
+                let more = extra.as_mut().and_then(Iterator::next).unwrap_or_default();
+                report.add(weights.into_iter().chain(more));
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_weights_sum_to_255() {
+        let vertices: [&[(u16, f32)]; 5] = [
+            // Sums to 0.9 after export
+            &[(3, 0.5), (4, 0.3), (5, 0.1)],
+            // Sums to 3.0
+            &[(1, 1.0), (2, 1.0), (3, 1.0)],
+            // Six influences: the two smallest are dropped
+            &[(0, 0.3), (1, 0.05), (2, 0.2), (3, 0.2), (4, 0.05), (5, 0.4)],
+            // The same joint twice is merged
+            &[(7, 0.25), (7, 0.25), (8, 0.25)],
+            // Nothing usable left
+            &[(9, 0.0), (2, f32::NAN)],
+        ];
+
+        for influences in vertices {
+            let normalized = normalize_influences(influences);
+            let bytes = quantize_weights(normalized.map(|(_, w)| w));
+            let sum: u32 = bytes.iter().map(|&b| u32::from(b)).sum();
+            assert_eq!(sum, 255, "{influences:?} -> {bytes:?}");
+        }
+
+        let normalized = normalize_influences(vertices[2]);
+        assert_eq!(normalized.map(|(joint, _)| joint), [5, 0, 2, 3]);
+        assert_eq!(
+            quantize_weights(normalized.map(|(_, w)| w)),
+            [93, 70, 46, 46]
+        );
+        assert_eq!(
+            quantize_weights([1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0, 0.0]),
+            [85, 85, 85, 0]
+        );
+        assert_eq!(
+            normalize_influences(vertices[3])[..2],
+            [(7, 2.0 / 3.0), (8, 1.0 / 3.0)]
+        );
+        assert_eq!(normalize_influences(vertices[4])[0], (9, 1.0));
+    }
+
+    #[test]
+    fn test_weight_report_distribution() {
+        let mut report = WeightReport::default();
+        report.add([1.0, 0.0, 0.0, 0.0]);
+        report.add([0.5, 0.3, 0.15, 0.0]);
+        report.add([0.6, 0.6, 0.0, 0.0]);
+        report.add([0.0; 4]);
+        report.add([0.2; 5]);
+        report.add([128.0 / 255.0, 126.0 / 255.0, 0.0, 0.0]);
+
+        assert_eq!(report.vertices, 6);
+        assert_eq!(report.affected, 4);
+        assert_eq!(report.over_limit, 1);
+        assert_eq!(report.distribution, [1, 0, 2, 2, 0, 1]);
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,153 @@
+//! Whole-language LOCA export from PAKs
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::loca_to_xml::to_xml;
+use crate::converter::{ConvertPhase, ConvertProgress, ConvertProgressCallback};
+use crate::error::{Error, Result};
+use crate::formats::loca::{LocaResolver, language_of_path, parse_loca_bytes};
+use crate::pak::VfsView;
+
+/// A loca file written by [`extract_languages`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractedLoca {
+    /// Language folder name, e.g. `German`
+    pub language: String,
+    /// Path of the loca file inside the PAKs
+    pub source: String,
+    /// PAK the file was read from
+    pub pak: Option<PathBuf>,
+    /// XML file written
+    pub output: PathBuf,
+    /// Number of entries converted
+    pub entries: usize,
+}
+
+/// Convert every loca file of `languages` in `vfs` to XML under `output`
+///
+/// The PAK layout is kept: `Localization/German/german.loca` is written to
+/// `<output>/Localization/German/german.xml`. Language names or codes are
+/// accepted (see [`normalize_language`](crate::formats::loca::normalize_language)).
+/// With no languages given, every file under a known language folder is
+/// exported. Where several PAKs contain a file, the last one wins.
+///
+/// # Errors
+/// Returns an error if a loca file cannot be read or parsed, or an XML file
+/// cannot be written.
+pub fn extract_languages(
+    vfs: &VfsView,
+    languages: &[&str],
+    output: &Path,
+    progress: ConvertProgressCallback,
+) -> Result<Vec<ExtractedLoca>> {
+    let matchers: Vec<LocaResolver> = languages.iter().map(|l| LocaResolver::new(l)).collect();
+    let files: Vec<(String, String)> = vfs
+        .list("")
+        .into_iter()
+        .filter_map(|file| {
+            let language = if matchers.is_empty() {
+                language_of_path(&file)?.to_string()
+            } else {
+                matchers
+                    .iter()
+                    .find(|m| m.is_language_file(&file))?
+                    .language()
+                    .to_string()
+            };
+            // `is_language_file` checks the extension; `language_of_path` doesn't
+            let is_loca = Path::new(&file)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("loca"));
+            is_loca.then_some((file, language))
+        })
+        .collect();
+
+    let total = files.len();
+    let mut extracted = Vec::with_capacity(total);
+    for (i, (file, language)) in files.into_iter().enumerate() {
+        progress(&ConvertProgress::with_file(
+            ConvertPhase::Converting,
+            i + 1,
+            total,
+            file.clone(),
+        ));
+
+        let data = vfs.read(&file)?;
+        let resource =
+            parse_loca_bytes(&data).map_err(|e| Error::ConversionError(format!("{file}: {e}")))?;
+        let dest = output.join(&file).with_extension("xml");
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest, to_xml(&resource)?)?;
+
+        extracted.push(ExtractedLoca {
+            language,
+            pak: vfs.source_of(&file).map(Path::to_path_buf),
+            source: file,
+            output: dest,
+            entries: resource.entries.len(),
+        });
+    }
+
+    progress(&ConvertProgress::new(ConvertPhase::Complete, total, total));
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::formats::loca::{LocaResource, LocalizedText, serialize_loca};
+    use crate::pak::PakBuilder;
+
+    #[test]
+    fn test_extract_languages_keeps_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Localization.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        for (file, text) in [
+            ("Localization/English/english.loca", "Sword"),
+            ("Localization/German/german.loca", "Schwert"),
+            ("Localization/German/Voice/voice.loca", "Hallo"),
+            ("Localization/German/readme.txt", "not loca"),
+        ] {
+            let resource = LocaResource::new(vec![LocalizedText::new(
+                "h0001g01".to_string(),
+                1,
+                text.to_string(),
+            )]);
+            builder
+                .add_file(
+                    file,
+                    &serialize_loca(&resource).unwrap(),
+                    CompressionSettings::default(),
+                )
+                .unwrap();
+        }
+        builder.finish().unwrap();
+        let vfs = VfsView::new(&[&pak]).unwrap();
+
+        let out = dir.path().join("de");
+        let extracted = extract_languages(&vfs, &["de"], &out, &|_| {}).unwrap();
+        let sources: Vec<&str> = extracted.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            [
+                "Localization/German/Voice/voice.loca",
+                "Localization/German/german.loca"
+            ]
+        );
+        assert!(extracted.iter().all(|e| e.language == "German"));
+        let xml = std::fs::read_to_string(out.join("Localization/German/german.xml")).unwrap();
+        assert!(xml.contains("Schwert"));
+        assert!(!out.join("Localization/English").exists());
+
+        let out = dir.path().join("all");
+        let extracted = extract_languages(&vfs, &[], &out, &|_| {}).unwrap();
+        assert_eq!(extracted.len(), 3);
+        assert!(out.join("Localization/English/english.xml").is_file());
+    }
+}
@@ -3,10 +3,14 @@
 //! Handles conversions between Larian's localization formats:
 //! - LOCA (binary) - Compact binary localization format used in PAK files
 //! - XML - Human-readable XML format for editing
+//!
+//! [`extract_languages`] exports whole languages from PAKs as XML.
 
+mod extract;
 mod loca_to_xml;
 mod xml_to_loca;
 
+pub use extract::{ExtractedLoca, extract_languages};
 pub use loca_to_xml::{
     convert_loca_to_xml, convert_loca_to_xml_with_progress, to_xml as loca_to_xml_string,
 };
@@ -0,0 +1,52 @@
+//! LSB to LSX conversion
+//!
+//! LSB files are read straight into an LSX document, so this only writes it
+//! out. Other targets go through the same document (see `convert_bytes`).
+
+use crate::error::Result;
+use crate::formats::{lsb, lsx};
+use std::path::Path;
+
+/// Convert LSB file to LSX format
+///
+/// # Errors
+/// Returns an error if reading or writing fails.
+pub fn convert_lsb_to_lsx<P: AsRef<Path>>(source: P, dest: P) -> Result<()> {
+    convert_lsb_to_lsx_with_progress(source, dest, &|_| {})
+}
+
+/// Convert LSB file to LSX format with progress callback
+///
+/// # Errors
+/// Returns an error if reading or writing fails.
+pub fn convert_lsb_to_lsx_with_progress<P: AsRef<Path>>(
+    source: P,
+    dest: P,
+    progress: crate::converter::ConvertProgressCallback,
+) -> Result<()> {
+    use crate::converter::{ConvertPhase, ConvertProgress};
+    tracing::info!(
+        "Converting LSB→LSX: {:?} → {:?}",
+        source.as_ref(),
+        dest.as_ref()
+    );
+
+    progress(&ConvertProgress::with_file(
+        ConvertPhase::ReadingSource,
+        1,
+        2,
+        "Reading LSB file...",
+    ));
+    let lsx_doc = lsb::read_lsb(&source)?;
+
+    progress(&ConvertProgress::with_file(
+        ConvertPhase::WritingOutput,
+        2,
+        2,
+        "Writing LSX file...",
+    ));
+    lsx::write_lsx(&lsx_doc, dest)?;
+
+    tracing::info!("Conversion complete");
+    Ok(())
+}
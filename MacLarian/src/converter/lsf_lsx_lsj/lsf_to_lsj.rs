@@ -1,6 +1,6 @@
 //! LSF to LSJ conversion (via LSX intermediate)
 
-use crate::error::Result;
+use crate::error::{Result, ResultExt};
 use crate::formats::{lsf, lsj, lsx};
 use std::path::Path;
 
@@ -47,7 +47,7 @@ pub fn convert_lsf_to_lsj_with_progress<P: AsRef<Path>>(
         5,
         format!("Converting {node_count} nodes to XML..."),
     ));
-    let lsx_xml = super::lsf_to_lsx::to_lsx(&lsf_doc)?;
+    let lsx_xml = super::lsf_to_lsx::to_lsx(&lsf_doc).with_path(&source)?;
 
     // Step 3: Parse LSX XML
     progress(&ConvertProgress::with_file(
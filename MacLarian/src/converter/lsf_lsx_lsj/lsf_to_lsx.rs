@@ -9,7 +9,7 @@
 )]
 
 use crate::converter::{ConvertPhase, ConvertProgress, ConvertProgressCallback};
-use crate::error::Result;
+use crate::error::{Result, ResultExt};
 use crate::formats::common::{extract_translated_string, extract_value, get_type_name};
 use crate::formats::lsf::{self, LsfDocument, LsfMetadataFormat};
 
@@ -76,7 +76,7 @@ pub fn convert_lsf_to_lsx_with_progress<P: AsRef<Path>>(
         3,
         "Converting to LSX",
     ));
-    let lsx_xml = to_lsx(&lsf_doc)?;
+    let lsx_xml = to_lsx(&lsf_doc).with_path(&source)?;
 
     progress(&ConvertProgress::with_file(
         ConvertPhase::WritingOutput,
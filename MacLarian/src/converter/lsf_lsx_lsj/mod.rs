@@ -4,14 +4,17 @@
 //! - LSF (binary) - Compact binary format used in PAK files
 //! - LSX (XML) - Human-readable XML format
 //! - LSJ (JSON) - JSON format used for dialogs and some configs
+//! - LSB (legacy binary) - Read-only, DOS2-era predecessor of LSF
 //!
 //! Conversion paths:
 //! - LSF ↔ LSX: Direct conversion
 //! - LSX ↔ LSJ: Direct conversion
 //! - LSF ↔ LSJ: Via LSX intermediate
+//! - LSB → LSX: Direct conversion
 //!
 //!
 
+mod lsb_to_lsx;
 mod lsf_to_lsj;
 mod lsf_to_lsx;
 mod lsj_to_lsf;
@@ -20,14 +23,18 @@ mod lsx_to_lsf;
 mod lsx_to_lsj;
 
 // Re-export conversion functions
+pub use lsb_to_lsx::{convert_lsb_to_lsx, convert_lsb_to_lsx_with_progress};
 pub use lsf_to_lsj::{convert_lsf_to_lsj, convert_lsf_to_lsj_with_progress};
 pub use lsf_to_lsx::{convert_lsf_to_lsx, convert_lsf_to_lsx_with_progress, to_lsx};
 pub use lsj_to_lsf::{convert_lsj_to_lsf, convert_lsj_to_lsf_with_progress};
+pub(crate) use lsj_to_lsx::to_lsx as lsj_document_to_lsx;
 pub use lsj_to_lsx::{convert_lsj_to_lsx, convert_lsj_to_lsx_with_progress};
 pub use lsx_to_lsf::{convert_lsx_to_lsf, convert_lsx_to_lsf_with_progress, from_lsx};
 pub use lsx_to_lsj::{convert_lsx_to_lsj, convert_lsx_to_lsj_with_progress, to_lsj};
 
 // Convenience aliases matching the module names
+pub use lsb_to_lsx::convert_lsb_to_lsx as lsb_to_lsx;
+pub use lsb_to_lsx::convert_lsb_to_lsx_with_progress as lsb_to_lsx_with_progress;
 pub use lsf_to_lsj::convert_lsf_to_lsj as lsf_to_lsj;
 pub use lsf_to_lsj::convert_lsf_to_lsj_with_progress as lsf_to_lsj_with_progress;
 pub use lsf_to_lsx::convert_lsf_to_lsx as lsf_to_lsx;
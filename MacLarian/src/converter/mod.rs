@@ -2,14 +2,22 @@
 //!
 //! This module handles conversions between different Larian file formats:
 //! - LSF (binary) ↔ LSX (XML) ↔ LSJ (JSON) - Document formats
+//! - LSB (legacy binary) → LSX - Read-only document format
 //! - LOCA ↔ XML - Localization formats
 //! - GR2 (Granny2) ↔ glTF - 3D model conversion
 //! - DDS ↔ PNG - Texture conversion
+//! - LSX/XML pretty-printing
+//! - Documents inside a PAK, converted without extracting them first
+//! - Whole directories and batches of models, with cancellation
 
+mod batch;
+mod bytes;
 mod dds_png;
 pub mod gr2_gltf;
 pub mod loca;
 pub(crate) mod lsf_lsx_lsj;
+mod pak_entries;
+mod pretty;
 
 /// Progress callback type for conversion operations
 pub type ConvertProgressCallback<'a> = &'a (dyn Fn(&ConvertProgress) + Sync + Send);
@@ -99,6 +107,8 @@ impl ConvertPhase {
 // Re-export LSF/LSX/LSJ conversions - primary API only
 pub use lsf_lsx_lsj::{
     // Primary conversion functions
+    convert_lsb_to_lsx,
+    convert_lsb_to_lsx_with_progress,
     convert_lsf_to_lsj,
     // With-progress variants
     convert_lsf_to_lsj_with_progress,
@@ -115,6 +125,8 @@ pub use lsf_lsx_lsj::{
     // In-memory conversion functions
     from_lsx,
     // Convenience aliases (shorter names)
+    lsb_to_lsx,
+    lsb_to_lsx_with_progress,
     lsf_to_lsj,
     // Convenience aliases with progress
     lsf_to_lsj_with_progress,
@@ -132,26 +144,54 @@ pub use lsf_lsx_lsj::{
     to_lsx,
 };
 
+// In-memory conversion exports
+pub use bytes::{convert_bytes, sniff_format};
+
+// Batch conversion exports
+pub use batch::{
+    BatchConvertReport, Gr2BatchTarget, batch_convert_gr2, batch_convert_gr2_cancellable,
+    convert_dir, convert_dir_cancellable,
+};
+
+// PAK entry conversion exports
+pub use pak_entries::{
+    PAK_CONVERT_BATCH_SIZE, PakConvertOptions, PakConvertReport, convert_pak_entries,
+    convert_pak_entries_cancellable,
+};
+
+// Pretty-printing exports
+pub use pretty::{Indent, PrettyOptions, pretty_print_xml};
+
 // GR2/glTF conversion exports
+pub use gr2_gltf::{Gr2Compression, Gr2WriteOptions};
 pub use gr2_gltf::{Gr2Phase, Gr2Progress, Gr2ProgressCallback};
 pub use gr2_gltf::{convert_gltf_bytes_to_gr2, convert_gltf_to_gr2};
+pub use gr2_gltf::{convert_gltf_bytes_to_gr2_with_options, convert_gltf_to_gr2_with_options};
 pub use gr2_gltf::{
     convert_gltf_bytes_to_gr2_with_progress, convert_gltf_to_gr2_with_progress,
     convert_gr2_bytes_to_glb_with_progress, convert_gr2_to_glb_with_progress,
     convert_gr2_to_gltf_with_progress,
 };
 pub use gr2_gltf::{convert_gr2_bytes_to_glb, convert_gr2_to_glb, convert_gr2_to_gltf};
+pub use gr2_gltf::{WEIGHT_SUM_RANGES, WeightReport, gltf_weight_report, gr2_weight_report};
 
 // LOCA conversion exports
 pub use loca::{
-    convert_loca_to_xml, convert_loca_to_xml_with_progress, convert_xml_to_loca,
-    convert_xml_to_loca_with_progress, loca_from_xml, loca_to_xml_string,
+    ExtractedLoca, convert_loca_to_xml, convert_loca_to_xml_with_progress, convert_xml_to_loca,
+    convert_xml_to_loca_with_progress, extract_languages, loca_from_xml, loca_to_xml_string,
 };
 
 // DDS/PNG conversion exports
+pub(crate) use dds_png::encode_blocks;
 pub use dds_png::{
-    DdsFormat, ImagePhase, ImageProgress, ImageProgressCallback, convert_dds_to_png,
-    convert_dds_to_png_with_progress, convert_png_to_dds, convert_png_to_dds_with_format,
+    ColorSpace, DdsFormat, DdsInfo, DdsIssue, ImagePhase, ImageProgress, ImageProgressCallback,
+    convert_dds_to_png, convert_dds_to_png_with_progress, convert_png_to_dds,
+    convert_png_to_dds_with_color_space, convert_png_to_dds_with_format,
     convert_png_to_dds_with_format_and_progress, convert_png_to_dds_with_progress,
-    dds_bytes_to_png_bytes, png_image_to_dds_bytes,
+    dds_bytes_to_png_bytes, dds_info, decode_dds_bytes, png_color_space, png_image_to_dds_bytes,
+    png_image_to_dds_bytes_with_color_space, tag_png_color_space,
+};
+pub use dds_png::{
+    TextureBatchEntry, TextureBatchOptions, TextureBatchReport, TextureBatchStatus,
+    batch_convert_textures, batch_convert_textures_cancellable,
 };
@@ -0,0 +1,261 @@
+//! Converting documents straight out of a PAK
+//!
+//! [`convert_pak_entries`] converts matching LSF (and other document) entries
+//! without extracting them first. Entries are read in the order their data is
+//! stored, [`PakConvertOptions::batch_size`] at a time, so memory stays
+//! bounded and each part file is read front to back. Every batch is converted
+//! and written in parallel; an entry that fails is recorded in the
+//! [`PakConvertReport`] and doesn't stop the rest.
+
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use super::{ConvertPhase, ConvertProgress, ConvertProgressCallback};
+use super::{PrettyOptions, convert_bytes, pretty_print_xml};
+use crate::error::{Error, Result};
+use crate::pak::{FilterSet, PakReaderCache};
+use crate::utils::CancelToken;
+
+/// Default number of entries read and converted at a time
+pub const PAK_CONVERT_BATCH_SIZE: usize = 256;
+
+/// Entry extensions that are converted; the rest are skipped
+const DOCUMENT_EXTENSIONS: &[&str] = &["lsf", "lsbc", "lsbs", "lsfx", "lsb", "lsj", "lsx"];
+
+/// Options for [`convert_pak_entries`]
+#[derive(Debug, Clone)]
+pub struct PakConvertOptions {
+    /// Format to write: `lsx` (default), `lsj` or `lsf`
+    pub output_format: String,
+    /// Re-indent LSX output
+    pub pretty: Option<PrettyOptions>,
+    /// Entries held in memory at once
+    pub batch_size: usize,
+}
+
+impl Default for PakConvertOptions {
+    fn default() -> Self {
+        Self {
+            output_format: "lsx".to_string(),
+            pretty: None,
+            batch_size: PAK_CONVERT_BATCH_SIZE,
+        }
+    }
+}
+
+/// Outcome of [`convert_pak_entries`]
+#[derive(Debug, Default)]
+pub struct PakConvertReport {
+    /// Files written, in archive order
+    pub written: Vec<PathBuf>,
+    /// Entries that couldn't be read or converted, with their errors
+    pub failed: Vec<(String, Error)>,
+    /// Whether conversion was cancelled before every entry was processed
+    pub cancelled: bool,
+}
+
+/// Convert the document entries of a PAK that match `filter`
+///
+/// Outputs mirror the entries' paths inside `dest_dir`, with the extension
+/// of [`PakConvertOptions::output_format`]. Entries already in that format
+/// and entries that aren't documents are skipped.
+///
+/// # Errors
+/// Returns an error if the output format isn't a document format, the PAK
+/// can't be read or `dest_dir` can't be created. Failures of single entries
+/// are reported in [`PakConvertReport::failed`] instead.
+pub fn convert_pak_entries(
+    pak: &Path,
+    filter: &FilterSet,
+    dest_dir: &Path,
+    options: &PakConvertOptions,
+    progress: ConvertProgressCallback,
+) -> Result<PakConvertReport> {
+    convert_pak_entries_cancellable(
+        pak,
+        filter,
+        dest_dir,
+        options,
+        &CancelToken::new(),
+        progress,
+    )
+}
+
+/// Convert the document entries of a PAK, stopping early once `cancel` is
+/// triggered
+///
+/// The batch being converted is finished; later entries are left out of the
+/// report and [`PakConvertReport::cancelled`] is set.
+///
+/// # Errors
+/// See [`convert_pak_entries`].
+pub fn convert_pak_entries_cancellable(
+    pak: &Path,
+    filter: &FilterSet,
+    dest_dir: &Path,
+    options: &PakConvertOptions,
+    cancel: &CancelToken,
+    progress: ConvertProgressCallback,
+) -> Result<PakConvertReport> {
+    let output_format = options.output_format.to_lowercase();
+    if !matches!(output_format.as_str(), "lsx" | "lsj" | "lsf") {
+        return Err(Error::InvalidFormat(format!(
+            "can't convert PAK entries to {output_format} (expected lsx, lsj or lsf)"
+        )));
+    }
+
+    let mut cache = PakReaderCache::new(1);
+    let entries: Vec<String> = cache
+        .list_files_by_offset(pak)?
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| {
+            let ext = extension(path);
+            ext != output_format
+                && DOCUMENT_EXTENSIONS.contains(&ext.as_str())
+                && filter.matches(path)
+        })
+        .collect();
+    std::fs::create_dir_all(dest_dir)?;
+
+    let total = entries.len();
+    let mut report = PakConvertReport::default();
+    for (i, batch) in entries.chunks(options.batch_size.max(1)).enumerate() {
+        if cancel.is_cancelled() {
+            report.cancelled = true;
+            break;
+        }
+        progress(&ConvertProgress::with_file(
+            ConvertPhase::Converting,
+            i * options.batch_size.max(1),
+            total,
+            &batch[0],
+        ));
+
+        let paths: Vec<&str> = batch.iter().map(String::as_str).collect();
+        let contents = cache.read_files_bulk(pak, &paths)?;
+        let results: Vec<Result<PathBuf>> = batch
+            .par_iter()
+            .map(|path| {
+                let data = contents.get(path).ok_or_else(|| {
+                    Error::DecompressionError(format!("{path} could not be read from the PAK"))
+                })?;
+                convert_entry(
+                    path,
+                    data,
+                    dest_dir,
+                    &output_format,
+                    options.pretty.as_ref(),
+                )
+            })
+            .collect();
+
+        for (path, result) in batch.iter().zip(results) {
+            match result {
+                Ok(output) => report.written.push(output),
+                Err(e) => report.failed.push((path.clone(), e)),
+            }
+        }
+    }
+
+    progress(&ConvertProgress::new(ConvertPhase::Complete, total, total));
+    Ok(report)
+}
+
+/// Convert one entry and write it under `dest_dir`
+fn convert_entry(
+    path: &str,
+    data: &[u8],
+    dest_dir: &Path,
+    output_format: &str,
+    pretty: Option<&PrettyOptions>,
+) -> Result<PathBuf> {
+    let mut converted = convert_bytes(data, &extension(path), output_format)?;
+    if let Some(options) = pretty
+        && output_format == "lsx"
+    {
+        converted = pretty_print_xml(&String::from_utf8(converted)?, options)?.into_bytes();
+    }
+
+    let output = dest_dir
+        .join(path.trim_start_matches('/'))
+        .with_extension(output_format);
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&output, converted)?;
+    Ok(output)
+}
+
+/// Lowercase extension of a PAK path
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::pak::PakBuilder;
+    use crate::test_fixtures::CONFIG_LSX;
+
+    #[test]
+    fn test_convert_pak_entries_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Shared.pak");
+        let lsf = convert_bytes(CONFIG_LSX.as_bytes(), "lsx", "lsf").unwrap();
+
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        for name in ["A", "B", "C"] {
+            builder
+                .add_file(
+                    &format!("Public/Shared/{name}.lsf"),
+                    &lsf,
+                    CompressionSettings::lz4(),
+                )
+                .unwrap();
+        }
+        builder
+            .add_file("Public/Shared/Bad.lsf", b"LSOF", CompressionSettings::lz4())
+            .unwrap();
+        builder
+            .add_file(
+                "Public/Shared/Skip.lsx",
+                CONFIG_LSX.as_bytes(),
+                CompressionSettings::lz4(),
+            )
+            .unwrap();
+        builder
+            .add_file(
+                "Public/Shared/Icon.dds",
+                b"DDS ",
+                CompressionSettings::lz4(),
+            )
+            .unwrap();
+        builder.finish().unwrap();
+
+        let out = dir.path().join("out");
+        let options = PakConvertOptions {
+            batch_size: 2,
+            ..PakConvertOptions::default()
+        };
+        let report = convert_pak_entries(&pak, &FilterSet::all(), &out, &options, &|_| {}).unwrap();
+
+        assert_eq!(report.written.len(), 3);
+        assert!(!report.cancelled);
+        let failed: Vec<&str> = report.failed.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(failed, ["Public/Shared/Bad.lsf"]);
+        let lsx = std::fs::read_to_string(out.join("Public/Shared/B.lsx")).unwrap();
+        assert!(lsx.contains(r#"value="Test""#));
+        assert!(!out.join("Public/Shared/Skip.lsx").exists());
+
+        let filter = FilterSet::new(&["A.lsf"], &[], true).unwrap();
+        let report =
+            convert_pak_entries(&pak, &filter, &dir.path().join("a"), &options, &|_| {}).unwrap();
+        assert_eq!(report.written, [dir.path().join("a/Public/Shared/A.lsx")]);
+    }
+}
@@ -0,0 +1,244 @@
+//! Pretty-printing for LSX and other XML documents
+//!
+//! Re-indents a document without touching its content: element names,
+//! attribute values, text and comments are copied as written (entities stay
+//! escaped), only whitespace between tags is replaced. The output is
+//! deterministic, so formatting an already formatted document is a no-op.
+
+use std::fmt;
+use std::str::FromStr;
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::error::{Error, Result};
+
+/// Indentation used for each nesting level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Indent {
+    /// One tab per level (what the game and `LSLib` write)
+    #[default]
+    Tab,
+    /// The given number of spaces per level
+    Spaces(u8),
+}
+
+impl Indent {
+    fn write(self, out: &mut String, depth: usize) {
+        match self {
+            Self::Tab => out.extend(std::iter::repeat_n('\t', depth)),
+            Self::Spaces(n) => out.extend(std::iter::repeat_n(' ', depth * usize::from(n))),
+        }
+    }
+}
+
+impl FromStr for Indent {
+    type Err = String;
+
+    /// Parse `tab` or a number of spaces
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("tab") || s.eq_ignore_ascii_case("tabs") {
+            return Ok(Self::Tab);
+        }
+        s.parse::<u8>()
+            .map(Self::Spaces)
+            .map_err(|_| format!("invalid indent '{s}', expected 'tab' or a number of spaces"))
+    }
+}
+
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tab => write!(f, "tab"),
+            Self::Spaces(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// Options for [`pretty_print_xml`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PrettyOptions {
+    /// Indentation per nesting level
+    pub indent: Indent,
+    /// Sort attributes by name instead of keeping their written order
+    pub sort_attributes: bool,
+}
+
+/// What the last written item was, to decide where the next one goes
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Last {
+    Start,
+    InlineText,
+    Other,
+}
+
+/// Re-indent an XML document
+///
+/// Whitespace-only text between tags is dropped and every element, comment
+/// and processing instruction goes on its own line. Elements holding only
+/// text stay on one line. A leading BOM and CRLF line endings are kept.
+///
+/// # Errors
+/// Returns [`Error::XmlSyntax`] if the document is not well-formed.
+pub fn pretty_print_xml(text: &str, options: &PrettyOptions) -> Result<String> {
+    let (bom, body) = match text.strip_prefix('\u{feff}') {
+        Some(rest) => ("\u{feff}", rest),
+        None => ("", text),
+    };
+    let newline = if body.contains("\r\n") { "\r\n" } else { "\n" };
+
+    let mut reader = Reader::from_str(body);
+    let mut out = String::with_capacity(text.len());
+    out.push_str(bom);
+
+    let mut open: Vec<String> = Vec::new();
+    let mut last = Last::Other;
+    let mut line_open = false;
+
+    let start_line = |out: &mut String, line_open: &mut bool, depth: usize| {
+        if *line_open {
+            out.push_str(newline);
+        }
+        options.indent.write(out, depth);
+        *line_open = true;
+    };
+
+    loop {
+        let before = reader.buffer_position();
+        let event = reader
+            .read_event()
+            .map_err(|e| parse_error(body, reader.buffer_position(), &e.to_string()))?;
+        let raw = body[before..reader.buffer_position()].trim();
+
+        match event {
+            Event::Start(tag) => {
+                start_line(&mut out, &mut line_open, open.len());
+                write_tag(&mut out, &tag, options.sort_attributes, false)?;
+                open.push(String::from_utf8_lossy(tag.name().as_ref()).into_owned());
+                last = Last::Start;
+            }
+            Event::Empty(tag) => {
+                start_line(&mut out, &mut line_open, open.len());
+                write_tag(&mut out, &tag, options.sort_attributes, true)?;
+                last = Last::Other;
+            }
+            Event::End(_) => {
+                let name = open.pop().unwrap_or_default();
+                if last == Last::Other {
+                    start_line(&mut out, &mut line_open, open.len());
+                }
+                out.push_str("</");
+                out.push_str(&name);
+                out.push('>');
+                last = Last::Other;
+            }
+            Event::Text(_) | Event::CData(_) => {
+                if raw.is_empty() {
+                    continue;
+                }
+                if last == Last::Start {
+                    last = Last::InlineText;
+                } else {
+                    start_line(&mut out, &mut line_open, open.len());
+                    last = Last::Other;
+                }
+                out.push_str(raw);
+            }
+            Event::Decl(_) | Event::PI(_) | Event::DocType(_) | Event::Comment(_) => {
+                start_line(&mut out, &mut line_open, open.len());
+                out.push_str(raw);
+                last = Last::Other;
+            }
+            Event::Eof => break,
+        }
+    }
+
+    if let Some(name) = open.last() {
+        return Err(parse_error(
+            body,
+            body.len(),
+            &format!("unexpected end of document, <{name}> is not closed"),
+        ));
+    }
+    if line_open {
+        out.push_str(newline);
+    }
+    Ok(out)
+}
+
+/// Write `<name attr="value" ...>` (or `... />` for an empty element)
+fn write_tag(out: &mut String, tag: &BytesStart<'_>, sort: bool, empty: bool) -> Result<()> {
+    let mut attributes = Vec::new();
+    for attr in tag.attributes() {
+        let attr = attr?;
+        attributes.push((
+            String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+            String::from_utf8_lossy(&attr.value).into_owned(),
+        ));
+    }
+    if sort {
+        attributes.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+
+    out.push('<');
+    out.push_str(&String::from_utf8_lossy(tag.name().as_ref()));
+    for (key, value) in &attributes {
+        // Values are still escaped; only a literal `"` forces single quotes
+        let quote = if value.contains('"') { '\'' } else { '"' };
+        out.push(' ');
+        out.push_str(key);
+        out.push('=');
+        out.push(quote);
+        out.push_str(value);
+        out.push(quote);
+    }
+    out.push_str(if empty { " />" } else { ">" });
+    Ok(())
+}
+
+/// An [`Error::XmlSyntax`] at the line of byte `offset`
+fn parse_error(text: &str, offset: usize, message: &str) -> Error {
+    let offset = offset.min(text.len());
+    let line = text.as_bytes()[..offset].split(|&b| b == b'\n').count();
+    Error::XmlSyntax {
+        line,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_print_round_trip() {
+        let messy = "<?xml version=\"1.0\"?><save><version major=\"4\"/>\
+            <region id=\"Config\">  <node id=\"root\"><attribute type=\"FixedString\" id=\"Name\" value=\"a &amp; b\"/>\
+            <!-- note --><name>text</name><empty></empty></node></region></save>";
+        let options = PrettyOptions {
+            indent: Indent::Spaces(2),
+            sort_attributes: true,
+        };
+
+        let pretty = pretty_print_xml(messy, &options).unwrap();
+        assert_eq!(
+            pretty,
+            "<?xml version=\"1.0\"?>\n\
+             <save>\n  \
+               <version major=\"4\" />\n  \
+               <region id=\"Config\">\n    \
+                 <node id=\"root\">\n      \
+                   <attribute id=\"Name\" type=\"FixedString\" value=\"a &amp; b\" />\n      \
+                   <!-- note -->\n      \
+                   <name>text</name>\n      \
+                   <empty></empty>\n    \
+                 </node>\n  \
+               </region>\n\
+             </save>\n"
+        );
+        assert_eq!(pretty_print_xml(&pretty, &options).unwrap(), pretty);
+
+        let err = pretty_print_xml("<save>\n<region>\n</save>", &options).unwrap_err();
+        assert!(matches!(err, Error::XmlSyntax { line: 3, .. }), "{err}");
+    }
+}
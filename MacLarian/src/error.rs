@@ -1,6 +1,7 @@
 //! Error types for `MacLarian`
 
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
 use lz4_flex::frame::Error as Lz4FrameError;
 use thiserror::Error;
@@ -14,6 +15,13 @@ pub enum Error {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// An input file does not exist.
+    #[error("file not found: {}", path.display())]
+    FileNotFound {
+        /// The missing file.
+        path: PathBuf,
+    },
+
     // ==================== PAK Archive Errors ====================
     /// The file is not a valid PAK archive (missing LSPK magic).
     #[error("invalid PAK magic: expected LSPK")]
@@ -98,6 +106,15 @@ pub enum Error {
     #[error("invalid attribute type: {0}")]
     InvalidAttributeType(u32),
 
+    // ==================== LSB Format Errors ====================
+    /// The file is not a valid LSB file.
+    #[error("invalid LSB magic: expected 0x40000000 or LSFM, found {0:?}")]
+    InvalidLsbMagic([u8; 4]),
+
+    /// The LSB file is big-endian, which is not supported.
+    #[error("big-endian LSB files are not supported")]
+    UnsupportedLsbByteOrder,
+
     // ==================== LOCA Format Errors ====================
     /// The file is not a valid LOCA file.
     #[error("invalid LOCA magic: expected LOCA, found {0:?}")]
@@ -161,6 +178,13 @@ pub enum Error {
         format: String,
     },
 
+    /// The DDS header is inconsistent with its contents.
+    #[error("invalid DDS: {issue}")]
+    DdsInvalid {
+        /// The problem found in the header.
+        issue: crate::converter::DdsIssue,
+    },
+
     /// Failed to create an image buffer from texture data.
     #[error("failed to create image buffer")]
     ImageBufferFailed,
@@ -179,6 +203,15 @@ pub enum Error {
         message: String,
     },
 
+    /// Failed to decode a PNG or TGA image.
+    #[error("failed to decode {format} image: {message}")]
+    ImageDecodeFailed {
+        /// The image format.
+        format: String,
+        /// The decoder error message.
+        message: String,
+    },
+
     /// Failed to create a DDS texture.
     #[error("failed to create DDS: {message}")]
     DdsCreateFailed {
@@ -251,6 +284,67 @@ pub enum Error {
         hash: String,
     },
 
+    /// No texture in the GTS metadata has the GUID.
+    #[error("no virtual texture with GUID '{guid}'")]
+    VirtualTextureGuidNotFound {
+        /// The GUID or GUID prefix.
+        guid: String,
+    },
+
+    /// A GUID prefix matches more than one texture.
+    #[error("GUID '{guid}' matches more than one texture: {matches}")]
+    VirtualTextureGuidAmbiguous {
+        /// The GUID prefix.
+        guid: String,
+        /// The matching textures and their GTS files.
+        matches: String,
+    },
+
+    /// A GUID or GUID prefix is malformed.
+    #[error("invalid GUID '{guid}': expected a GUID or a prefix of at least 8 hex digits")]
+    InvalidGuid {
+        /// The rejected value.
+        guid: String,
+    },
+
+    /// A search pattern is not a valid regular expression.
+    #[error("invalid pattern '{pattern}': {message}")]
+    InvalidPattern {
+        /// The rejected pattern.
+        pattern: String,
+        /// Why it was rejected.
+        message: String,
+    },
+
+    /// A virtual texture has no tile at the requested position.
+    #[error("{texture} has no {layer} tile at {x},{y} on level {level}")]
+    VirtualTextureTileNotFound {
+        /// The texture name.
+        texture: String,
+        /// The layer name.
+        layer: &'static str,
+        /// The mip level.
+        level: u8,
+        /// Tile column within the texture.
+        x: u32,
+        /// Tile row within the texture.
+        y: u32,
+    },
+
+    /// No texture in the GTS metadata has the name.
+    #[error("no texture named '{name}' in the GTS metadata")]
+    GtsTextureNotFound {
+        /// The texture name.
+        name: String,
+    },
+
+    /// The GTS lists several textures and none was chosen.
+    #[error("GTS lists {count} textures; choose one by name")]
+    GtsTextureNotSpecified {
+        /// Number of textures in the metadata.
+        count: usize,
+    },
+
     /// Virtual texture builder has no textures added.
     #[error("no textures added to virtual texture builder")]
     VirtualTextureNoTextures,
@@ -273,6 +367,17 @@ pub enum Error {
         path: PathBuf,
     },
 
+    /// A virtual texture source image cannot be tiled at its size.
+    #[error(
+        "virtual texture source is {width}x{height}; width and height must be powers of two of at least 4"
+    )]
+    VirtualTextureSourceSize {
+        /// Image width in pixels.
+        width: u32,
+        /// Image height in pixels.
+        height: u32,
+    },
+
     /// Requested mip level not available in texture.
     #[error("mip level {level} not available in texture")]
     VirtualTextureMipNotAvailable {
@@ -280,6 +385,29 @@ pub enum Error {
         level: usize,
     },
 
+    // ==================== WEM Audio Errors ====================
+    /// The WEM file is invalid or corrupted.
+    #[error("invalid WEM file: {message}")]
+    InvalidWem {
+        /// Description of what is invalid.
+        message: String,
+    },
+
+    /// The WEM file uses a codec that can't be converted.
+    #[error("unsupported WEM codec {codec:#06x} ({name})")]
+    UnsupportedWemCodec {
+        /// The format tag from the `fmt` chunk.
+        codec: u16,
+        /// Name of the codec, or "unknown".
+        name: &'static str,
+    },
+
+    /// Wwise Vorbis conversion needs a packed codebook library.
+    #[error(
+        "Wwise Vorbis conversion needs a packed codebook library (packed_codebooks_aoTuV_603.bin)"
+    )]
+    WemCodebooksRequired,
+
     // ==================== Compression/Decompression Errors ====================
     /// LZ4 decompression failed.
     #[error("LZ4 decompression failed: {message}")]
@@ -336,6 +464,81 @@ pub enum Error {
     #[error("could not determine VirtualTextures.pak path")]
     VirtualTexturesPakPathNotFound,
 
+    /// Could not find the user's BG3 `Mods` directory.
+    #[error("could not find the BG3 Mods directory")]
+    ModsDirNotFound,
+
+    /// No PAK or modsettings.lsx entry matches a mod to undeploy.
+    #[error("no deployed mod matches '{name}'")]
+    ModNotDeployed {
+        /// The UUID or PAK name that was given.
+        name: String,
+    },
+
+    /// `mods new` was asked for a template that isn't built in.
+    #[error("unknown mod template '{name}' (available: {available})")]
+    UnknownModTemplate {
+        /// The template id that was given.
+        name: String,
+        /// Comma-separated ids of the built-in templates.
+        available: String,
+    },
+
+    /// A new mod would be created in a directory that already has files.
+    #[error("mod directory already exists and is not empty: {}", path.display())]
+    ModDirectoryExists {
+        /// The mod's root directory.
+        path: PathBuf,
+    },
+
+    /// A version component can't be bumped because it's at its maximum.
+    #[error("cannot bump {part} of version {version}: component is at its maximum")]
+    VersionOutOfRange {
+        /// The component that was bumped (`major`, `minor`, `patch` or `build`).
+        part: String,
+        /// The version being bumped.
+        version: String,
+    },
+
+    /// No resource in a VisualBank/MaterialBank has the given ID or name.
+    #[error("no {bank} resource matches '{resource}'")]
+    BankResourceNotFound {
+        /// The bank that was searched (e.g. `VisualBank`).
+        bank: String,
+        /// The ID or name that was given.
+        resource: String,
+    },
+
+    /// A visual resource has no `Objects` entry with the given object ID.
+    #[error("visual resource '{resource}' has no object '{object}'")]
+    BankObjectNotFound {
+        /// The visual resource's ID.
+        resource: String,
+        /// The object ID that was given.
+        object: String,
+    },
+
+    // ==================== Config Errors ====================
+    /// Unknown key in the config file or `config set`.
+    #[error("unknown config key: {key}")]
+    UnknownConfigKey {
+        /// The key that was given.
+        key: String,
+    },
+
+    /// Value not allowed for a config key.
+    #[error("invalid value for {key}: {value}")]
+    InvalidConfigValue {
+        /// The key being set.
+        key: String,
+        /// The rejected value.
+        value: String,
+    },
+
+    /// No output directory was given and none is configured.
+    #[error("no output directory given and output-dir is not set in the config")]
+    OutputDirNotSet,
+
     // ==================== Parsing Errors ====================
     /// XML parsing error.
     #[error("XML parse error: {0}")]
@@ -345,10 +548,23 @@ pub enum Error {
     #[error("XML attribute error: {0}")]
     XmlAttrError(String),
 
+    /// A document that isn't well-formed XML, with the line of the problem.
+    #[error("XML error on line {line}: {message}")]
+    XmlSyntax {
+        /// 1-based line number.
+        line: usize,
+        /// What is wrong.
+        message: String,
+    },
+
     /// JSON parsing or serialization error.
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    /// TOML parsing or serialization error.
+    #[error("TOML error: {0}")]
+    TomlError(String),
+
     /// UTF-8 conversion error.
     #[error("UTF-8 conversion error: {0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
@@ -366,6 +582,40 @@ pub enum Error {
     #[error("unexpected end of file")]
     UnexpectedEof,
 
+    /// Data ends before a structure that starts at a known offset.
+    #[error("unexpected end of data at offset {offset:#x} ({needed} bytes needed)")]
+    TruncatedData {
+        /// Byte offset where the truncated structure starts.
+        offset: u64,
+        /// Number of bytes the structure needs.
+        needed: usize,
+    },
+
+    // ==================== Operation Status Errors ====================
+    /// One or more validation or lint checks failed.
+    #[error("{failures} validation failure(s)")]
+    ValidationFailed {
+        /// Number of failed checks.
+        failures: usize,
+    },
+
+    /// The operation was cancelled.
+    #[error("operation cancelled")]
+    Cancelled,
+
+    /// An error with the file, PAK entry or byte offset it happened at.
+    ///
+    /// Added by [`Error::with_path`], [`Error::with_pak_path`],
+    /// [`Error::at_offset`] and [`ResultExt`]; use [`Error::root`] to match on
+    /// the underlying error.
+    #[error("{error} ({context})")]
+    WithContext {
+        /// The underlying error.
+        error: Box<Error>,
+        /// Where it happened.
+        context: ErrorContext,
+    },
+
     // ==================== Generic/Fallback Errors ====================
     // These are kept for edge cases but should be used sparingly.
     /// Generic decompression error (use specific variants when possible).
@@ -407,6 +657,290 @@ pub enum Error {
     DdsError(String),
 }
 
+/// Where an error happened
+///
+/// Every field is optional; readers fill in what they know and callers add
+/// the rest as the error travels up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// File on disk.
+    pub path: Option<PathBuf>,
+    /// Path of the entry inside a PAK archive.
+    pub pak_path: Option<String>,
+    /// Byte offset in the file or PAK entry.
+    pub offset: Option<u64>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::with_capacity(3);
+        if let Some(path) = &self.path {
+            parts.push(format!("in {}", path.display()));
+        }
+        if let Some(pak_path) = &self.pak_path {
+            parts.push(format!("entry {pak_path}"));
+        }
+        if let Some(offset) = self.offset {
+            parts.push(format!("at offset {offset:#x}"));
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// Broad category of an [`Error`], used for CLI exit codes and error reports
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Invalid arguments or settings.
+    Usage,
+    /// A file, PAK entry or install path couldn't be found or read.
+    Io,
+    /// Input data is malformed or unsupported.
+    Format,
+    /// Validation or lint checks reported failures.
+    Validation,
+    /// The operation was cancelled.
+    Cancelled,
+    /// Anything else.
+    Other,
+}
+
+impl ErrorKind {
+    /// Process exit code for this kind
+    ///
+    /// `2` usage, `3` IO/not found, `4` format/parse, `5` validation,
+    /// `130` cancelled, `1` otherwise.
+    #[must_use]
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Self::Usage => 2,
+            Self::Io => 3,
+            Self::Format => 4,
+            Self::Validation => 5,
+            Self::Cancelled => 130,
+            Self::Other => 1,
+        }
+    }
+
+    /// Stable `snake_case` name
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::Io => "io",
+            Self::Format => "format",
+            Self::Validation => "validation",
+            Self::Cancelled => "cancelled",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl Error {
+    /// Broad category of this error
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::WithContext { error, .. } => error.kind(),
+
+            // Short reads while parsing in-memory data are format problems
+            Error::Io(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::InvalidData
+                ) =>
+            {
+                ErrorKind::Format
+            }
+
+            Error::Io(_)
+            | Error::FileNotFound { .. }
+            | Error::ArchivePartNotFound { .. }
+            | Error::ArchivePartMissing { .. }
+            | Error::RequestedFilesNotFound
+            | Error::FileNotFoundInPak(_)
+            | Error::GtsNotFoundForGtp { .. }
+            | Error::GtsHashNotFound { .. }
+            | Error::GtpNotInGtsMetadata { .. }
+            | Error::GtexHashNotFound { .. }
+            | Error::VirtualTextureGuidNotFound { .. }
+            | Error::GtsTextureNotFound { .. }
+            | Error::VirtualTextureTileNotFound { .. }
+            | Error::VirtualTextureSourceNotFound { .. }
+            | Error::Bg3PathNotFound
+            | Error::VirtualTexturesPakPathNotFound
+            | Error::ModsDirNotFound
+            | Error::ModNotDeployed { .. }
+            | Error::BankResourceNotFound { .. }
+            | Error::BankObjectNotFound { .. }
+            | Error::InvalidPath(_)
+            | Error::WalkDirError(_) => ErrorKind::Io,
+
+            Error::UnknownConfigKey { .. }
+            | Error::InvalidConfigValue { .. }
+            | Error::OutputDirNotSet
+            | Error::InvalidLayerIndex { .. }
+            | Error::InvalidGuid { .. }
+            | Error::InvalidPattern { .. }
+            | Error::UnknownModTemplate { .. }
+            | Error::ModDirectoryExists { .. }
+            | Error::VersionOutOfRange { .. }
+            | Error::VirtualTextureGuidAmbiguous { .. }
+            | Error::GtsTextureNotSpecified { .. }
+            | Error::VirtualTextureNoTextures
+            | Error::VirtualTextureConfigInvalid { .. }
+            | Error::VirtualTextureOutputNotSet
+            | Error::WemCodebooksRequired => ErrorKind::Usage,
+
+            Error::ValidationFailed { .. } => ErrorKind::Validation,
+            Error::Cancelled => ErrorKind::Cancelled,
+
+            Error::PakExtractionPartialFailure { .. }
+            | Error::PakHeaderNotRead
+            | Error::PakFooterNotRead
+            | Error::FastLzCompressionFailed { .. }
+            | Error::CompressionError(_)
+            | Error::PngEncodeFailed { .. }
+            | Error::DdsCreateFailed { .. }
+            | Error::DdsWriteFailed { .. }
+            | Error::GltfSerializationFailed { .. }
+            | Error::SearchError(_) => ErrorKind::Other,
+
+            _ => ErrorKind::Format,
+        }
+    }
+
+    /// File the error refers to, when known
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Error::FileNotFound { path }
+            | Error::ArchivePartMissing { path }
+            | Error::VirtualTextureSourceNotFound { path }
+            | Error::ModDirectoryExists { path } => Some(path),
+            Error::WithContext { error, context } => {
+                context.path.as_deref().or_else(|| error.path())
+            }
+            _ => None,
+        }
+    }
+
+    /// Path of the PAK entry the error refers to, when known
+    #[must_use]
+    pub fn pak_path(&self) -> Option<&str> {
+        match self {
+            Error::FileNotFoundInPak(pak_path) => Some(pak_path),
+            Error::WithContext { error, context } => {
+                context.pak_path.as_deref().or_else(|| error.pak_path())
+            }
+            _ => None,
+        }
+    }
+
+    /// Byte offset the error refers to, when known
+    #[must_use]
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            Error::TruncatedData { offset, .. } => Some(*offset),
+            Error::WithContext { error, context } => context.offset.or_else(|| error.offset()),
+            _ => None,
+        }
+    }
+
+    /// The error without any [`Error::WithContext`] wrapping
+    #[must_use]
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::WithContext { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// Attach the file the error happened in
+    ///
+    /// Does nothing if the error already names a file.
+    #[must_use]
+    pub fn with_path(self, path: impl AsRef<Path>) -> Self {
+        if self.path().is_some() {
+            return self;
+        }
+        self.add_context(|context| context.path = Some(path.as_ref().to_path_buf()))
+    }
+
+    /// Attach the PAK entry the error happened in
+    ///
+    /// Does nothing if the error already names an entry.
+    #[must_use]
+    pub fn with_pak_path(self, pak_path: impl Into<String>) -> Self {
+        if self.pak_path().is_some() {
+            return self;
+        }
+        self.add_context(|context| context.pak_path = Some(pak_path.into()))
+    }
+
+    /// Attach the byte offset the error happened at
+    ///
+    /// Does nothing if the error already has an offset, so the innermost
+    /// (most precise) offset wins.
+    #[must_use]
+    pub fn at_offset(self, offset: u64) -> Self {
+        if self.offset().is_some() {
+            return self;
+        }
+        self.add_context(|context| context.offset = Some(offset))
+    }
+
+    fn add_context(self, update: impl FnOnce(&mut ErrorContext)) -> Self {
+        let (error, mut context) = match self {
+            Error::WithContext { error, context } => (error, context),
+            error => (Box::new(error), ErrorContext::default()),
+        };
+        update(&mut context);
+        Error::WithContext { error, context }
+    }
+}
+
+/// Context helpers for results whose error converts into [`Error`]
+///
+/// ```no_run
+/// use maclarian::error::ResultExt;
+///
+/// let data = std::fs::read("meta.lsf").with_path("meta.lsf")?;
+/// # Ok::<(), maclarian::Error>(())
+/// ```
+pub trait ResultExt<T> {
+    /// Attach the file the error happened in
+    ///
+    /// # Errors
+    /// Returns the original error with the file attached.
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T>;
+
+    /// Attach the PAK entry the error happened in
+    ///
+    /// # Errors
+    /// Returns the original error with the entry attached.
+    fn with_pak_path(self, pak_path: impl Into<String>) -> Result<T>;
+
+    /// Attach the byte offset the error happened at
+    ///
+    /// # Errors
+    /// Returns the original error with the offset attached.
+    fn at_offset(self, offset: u64) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> ResultExt<T> for std::result::Result<T, E> {
+    fn with_path(self, path: impl AsRef<Path>) -> Result<T> {
+        self.map_err(|e| e.into().with_path(path))
+    }
+
+    fn with_pak_path(self, pak_path: impl Into<String>) -> Result<T> {
+        self.map_err(|e| e.into().with_pak_path(pak_path))
+    }
+
+    fn at_offset(self, offset: u64) -> Result<T> {
+        self.map_err(|e| e.into().at_offset(offset))
+    }
+}
+
 // Add conversion from quick_xml::events::attributes::AttrError
 impl From<quick_xml::events::attributes::AttrError> for Error {
     fn from(err: quick_xml::events::attributes::AttrError) -> Self {
@@ -423,3 +957,57 @@ impl From<walkdir::Error> for Error {
 
 /// A specialized Result type for `MacLarian` operations.
 pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        let not_found = Error::FileNotFound {
+            path: PathBuf::from("Shared.pak"),
+        };
+        assert_eq!(not_found.kind().exit_code(), 3);
+        assert_eq!(not_found.path(), Some(Path::new("Shared.pak")));
+        assert_eq!(Error::InvalidPakMagic.kind().exit_code(), 4);
+        assert_eq!(
+            Error::ValidationFailed { failures: 2 }.kind().exit_code(),
+            5
+        );
+        assert_eq!(Error::Cancelled.kind().exit_code(), 130);
+
+        let truncated = Error::TruncatedData {
+            offset: 0x40,
+            needed: 16,
+        };
+        assert_eq!(truncated.kind(), ErrorKind::Format);
+        assert_eq!(truncated.offset(), Some(0x40));
+    }
+
+    #[test]
+    fn test_error_context() {
+        let err = Err::<(), _>(Error::InvalidAttributeType(97))
+            .at_offset(0x1c)
+            .with_pak_path("Public/Test/meta.lsf")
+            .with_path("Test.pak")
+            .at_offset(0x400)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid attribute type: 97 (in Test.pak, entry Public/Test/meta.lsf, at offset 0x1c)"
+        );
+        assert_eq!(err.kind(), ErrorKind::Format);
+        assert_eq!(err.path(), Some(Path::new("Test.pak")));
+        assert_eq!(err.pak_path(), Some("Public/Test/meta.lsf"));
+        assert_eq!(err.offset(), Some(0x1c));
+        assert!(matches!(err.root(), Error::InvalidAttributeType(97)));
+
+        // Errors that already name a file keep it
+        let not_found = Error::FileNotFound {
+            path: PathBuf::from("a.lsf"),
+        }
+        .with_path("b.lsf");
+        assert!(matches!(not_found, Error::FileNotFound { .. }));
+    }
+}
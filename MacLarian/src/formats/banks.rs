@@ -0,0 +1,776 @@
+//! `VisualBank`, `MaterialBank` and `CharacterVisualBank` resources
+//!
+//! Equipment and character mods ship these banks in
+//! `Public/<Folder>/Content/**/_merged.lsf`. Each bank is a node holding one
+//! `Resource` node per entry:
+//!
+//! - `VisualBank`: a mesh (`SourceFile` GR2) with its `Slot` and one
+//!   `Objects` node per mesh object, each pointing at a material
+//! - `MaterialBank`: a material with `Texture2DParameters` pointing at textures
+//! - `CharacterVisualBank`: a character's body set and the visual resource
+//!   worn in each of its `Slots`
+//!
+//! [`BankDocument`] reads these into typed structs and edits them in place,
+//! keeping every attribute it doesn't model, so a file can be read, changed
+//! and written back without losing data.
+//!
+//! ```no_run
+//! use maclarian::formats::banks::BankDocument;
+//!
+//! let mut banks = BankDocument::read("_merged.lsf")?;
+//! for visual in banks.visuals() {
+//!     println!("{} {} ({})", visual.id, visual.name, visual.slot);
+//! }
+//! banks.set_slot("HUM_M_ARM_MyRobe_Body", "Body")?;
+//! banks.write("_merged.lsf")?;
+//! # Ok::<(), maclarian::Error>(())
+//! ```
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::converter::lsf_lsx_lsj::lsj_document_to_lsx;
+use crate::converter::{from_lsx, sniff_format, to_lsj, to_lsx};
+use crate::error::{Error, Result, ResultExt};
+use crate::formats::lsb::parse_lsb_bytes;
+use crate::formats::lsf::{parse_lsf_bytes, serialize_lsf};
+use crate::formats::lsj::{parse_lsj, serialize_lsj};
+use crate::formats::lsx::{
+    LsxAttribute, LsxDocument, LsxNode, LsxRegion, parse_lsx, serialize_lsx,
+};
+
+const VISUAL_BANK: &str = "VisualBank";
+const MATERIAL_BANK: &str = "MaterialBank";
+const CHARACTER_VISUAL_BANK: &str = "CharacterVisualBank";
+
+/// A mesh resource from `VisualBank`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VisualResource {
+    /// Resource ID (GUID)
+    pub id: String,
+    /// Resource name (e.g. `HUM_M_ARM_Leather_A_Body`)
+    pub name: String,
+    /// GR2 file the mesh comes from
+    pub source_file: String,
+    /// Skeleton/template reference inside the GR2
+    pub template: String,
+    /// Equipment slot the visual occupies (e.g. `Body`, `Boots`)
+    pub slot: String,
+    /// Mesh objects and their materials
+    pub objects: Vec<VisualObject>,
+}
+
+/// One `Objects` entry of a visual resource
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VisualObject {
+    /// Mesh object inside the GR2 (e.g. `HUM_M_ARM_Leather_A_Body.HUM_M_ARM_Leather_A_Body_Mesh`)
+    pub object_id: String,
+    /// `MaterialBank` resource the object is drawn with
+    pub material_id: String,
+    /// Level of detail (0 is the full mesh)
+    pub lod: u8,
+}
+
+/// A material resource from `MaterialBank`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MaterialResource {
+    /// Resource ID (GUID)
+    pub id: String,
+    /// Resource name
+    pub name: String,
+    /// Shader graph the material instances
+    pub source_file: String,
+    /// Texture parameters and the `TextureBank` resources bound to them
+    pub textures: Vec<MaterialTexture>,
+}
+
+/// One `Texture2DParameters` entry of a material
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MaterialTexture {
+    /// Shader parameter (e.g. `basecolor`, `NormalMap`)
+    pub parameter: String,
+    /// `TextureBank` resource ID
+    pub texture_id: String,
+}
+
+/// A character visual from `CharacterVisualBank`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CharacterVisual {
+    /// Resource ID (GUID)
+    pub id: String,
+    /// Resource name
+    pub name: String,
+    /// `VisualBank` resource of the body set
+    pub body_set_visual: String,
+    /// `VisualBank` resource of the base skeleton
+    pub base_visual: String,
+    /// Visual resources worn in each slot
+    pub slots: Vec<CharacterSlot>,
+}
+
+/// One `Slots` entry of a character visual
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CharacterSlot {
+    /// Slot name (e.g. `Hair`, `Head`)
+    pub slot: String,
+    /// Bone the visual is attached to, if any
+    pub bone: String,
+    /// `VisualBank` resource worn in the slot
+    pub visual_resource: String,
+}
+
+/// A document holding `VisualBank`, `MaterialBank` or `CharacterVisualBank`
+/// resources
+#[derive(Debug, Clone)]
+pub struct BankDocument {
+    doc: LsxDocument,
+}
+
+impl Default for BankDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BankDocument {
+    /// An empty document; banks are added as resources are inserted
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            doc: LsxDocument::new(4, 0, 9, 328),
+        }
+    }
+
+    /// Wrap an already parsed document
+    #[must_use]
+    pub fn from_document(doc: LsxDocument) -> Self {
+        Self { doc }
+    }
+
+    /// The underlying document
+    #[must_use]
+    pub fn document(&self) -> &LsxDocument {
+        &self.doc
+    }
+
+    /// Parse LSF, LSX, LSJ or LSB bytes
+    ///
+    /// The format is detected from the data.
+    ///
+    /// # Errors
+    /// Returns an error if the data is not a document in one of those formats.
+    pub fn parse_bytes(data: &[u8]) -> Result<Self> {
+        let doc = match sniff_format(data) {
+            Some("lsf") => parse_lsx(&to_lsx(&parse_lsf_bytes(data)?)?)?,
+            Some("lsx") => parse_lsx(text(data)?)?,
+            Some("lsj") => lsj_document_to_lsx(&parse_lsj(text(data)?)?)?,
+            Some("lsb") => parse_lsb_bytes(data)?,
+            other => {
+                return Err(Error::InvalidFormat(format!(
+                    "expected an LSF, LSX, LSJ or LSB document, found {}",
+                    other.unwrap_or("unknown data")
+                )));
+            }
+        };
+        Ok(Self { doc })
+    }
+
+    /// Read a bank file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).with_path(path)?;
+        Self::parse_bytes(&data).with_path(path)
+    }
+
+    /// Resources in `VisualBank`, in file order
+    #[must_use]
+    pub fn visuals(&self) -> Vec<VisualResource> {
+        self.resources(VISUAL_BANK).map(visual_resource).collect()
+    }
+
+    /// Resources in `MaterialBank`, in file order
+    #[must_use]
+    pub fn materials(&self) -> Vec<MaterialResource> {
+        self.resources(MATERIAL_BANK)
+            .map(material_resource)
+            .collect()
+    }
+
+    /// Resources in `CharacterVisualBank`, in file order
+    #[must_use]
+    pub fn character_visuals(&self) -> Vec<CharacterVisual> {
+        self.resources(CHARACTER_VISUAL_BANK)
+            .map(character_visual)
+            .collect()
+    }
+
+    /// The visual resource with this ID or name
+    #[must_use]
+    pub fn visual(&self, resource: &str) -> Option<VisualResource> {
+        self.resources(VISUAL_BANK)
+            .find(|n| is_resource(n, resource))
+            .map(visual_resource)
+    }
+
+    /// The material resource with this ID or name
+    #[must_use]
+    pub fn material(&self, resource: &str) -> Option<MaterialResource> {
+        self.resources(MATERIAL_BANK)
+            .find(|n| is_resource(n, resource))
+            .map(material_resource)
+    }
+
+    /// Add a visual resource, or update the one with its ID
+    ///
+    /// The resource's `Objects` are replaced; attributes it doesn't model
+    /// (bounds, LOD distances, ...) are kept. Returns `true` if the resource
+    /// is new.
+    pub fn upsert_visual(&mut self, visual: &VisualResource) -> bool {
+        let (node, added) = upsert_resource(self.bank_mut(VISUAL_BANK), &visual.id);
+        set_attribute(node, "Name", "LSString", &visual.name);
+        set_attribute(node, "SourceFile", "LSString", &visual.source_file);
+        set_optional(node, "Template", "FixedString", &visual.template);
+        set_optional(node, "Slot", "FixedString", &visual.slot);
+
+        // New objects go where the old ones were, ahead of other children
+        let at = node
+            .children
+            .iter()
+            .position(|c| c.id == "Objects")
+            .unwrap_or(node.children.len());
+        node.children.retain(|c| c.id != "Objects");
+        let objects = visual.objects.iter().map(|object| {
+            let mut child = LsxNode::new("Objects".to_string());
+            set_attribute(&mut child, "LOD", "uint8", &object.lod.to_string());
+            set_attribute(&mut child, "MaterialID", "FixedString", &object.material_id);
+            set_attribute(&mut child, "ObjectID", "FixedString", &object.object_id);
+            child
+        });
+        let at = at.min(node.children.len());
+        node.children.splice(at..at, objects);
+        added
+    }
+
+    /// Add a material resource, or update the one with its ID
+    ///
+    /// Each texture is bound to its parameter, adding the parameter if the
+    /// material doesn't have it; other parameters are kept. Returns `true`
+    /// if the resource is new.
+    pub fn upsert_material(&mut self, material: &MaterialResource) -> bool {
+        let (node, added) = upsert_resource(self.bank_mut(MATERIAL_BANK), &material.id);
+        set_attribute(node, "Name", "LSString", &material.name);
+        set_attribute(node, "SourceFile", "LSString", &material.source_file);
+        for texture in &material.textures {
+            set_texture(node, texture);
+        }
+        added
+    }
+
+    /// Remove a visual resource by ID or name, returning it if it was present
+    pub fn remove_visual(&mut self, resource: &str) -> Option<VisualResource> {
+        let bank = self.doc.find_node_mut(VISUAL_BANK)?;
+        let index = bank
+            .children
+            .iter()
+            .position(|n| n.id == "Resource" && is_resource(n, resource))?;
+        Some(visual_resource(&bank.children.remove(index)))
+    }
+
+    /// Set the equipment slot of a visual resource
+    ///
+    /// # Errors
+    /// Returns [`Error::BankResourceNotFound`] if no visual resource has this
+    /// ID or name.
+    pub fn set_slot(&mut self, resource: &str, slot: &str) -> Result<()> {
+        let node = self.resource_mut(VISUAL_BANK, resource)?;
+        set_attribute(node, "Slot", "FixedString", slot);
+        Ok(())
+    }
+
+    /// Set the material one object of a visual resource is drawn with
+    ///
+    /// Every LOD of the object is changed.
+    ///
+    /// # Errors
+    /// Returns [`Error::BankResourceNotFound`] if no visual resource has this
+    /// ID or name, and [`Error::BankObjectNotFound`] if it has no such object.
+    pub fn set_object_material(
+        &mut self,
+        resource: &str,
+        object_id: &str,
+        material_id: &str,
+    ) -> Result<()> {
+        let node = self.resource_mut(VISUAL_BANK, resource)?;
+        let mut found = false;
+        for object in node
+            .children
+            .iter_mut()
+            .filter(|c| c.id == "Objects" && c.attribute_value("ObjectID") == Some(object_id))
+        {
+            set_attribute(object, "MaterialID", "FixedString", material_id);
+            found = true;
+        }
+        if found {
+            Ok(())
+        } else {
+            Err(Error::BankObjectNotFound {
+                resource: node.attribute_value("ID").unwrap_or(resource).to_string(),
+                object: object_id.to_string(),
+            })
+        }
+    }
+
+    /// Set the visual resource a character visual wears in a slot, adding
+    /// the slot if it has none
+    ///
+    /// # Errors
+    /// Returns [`Error::BankResourceNotFound`] if no character visual has
+    /// this ID or name.
+    pub fn set_character_slot(
+        &mut self,
+        resource: &str,
+        slot: &str,
+        visual_resource: &str,
+    ) -> Result<()> {
+        let node = self.resource_mut(CHARACTER_VISUAL_BANK, resource)?;
+        if let Some(entry) = node
+            .children
+            .iter_mut()
+            .find(|c| c.id == "Slots" && c.attribute_value("Slot") == Some(slot))
+        {
+            set_attribute(entry, "VisualResource", "FixedString", visual_resource);
+        } else {
+            let mut entry = LsxNode::new("Slots".to_string());
+            set_attribute(&mut entry, "Bone", "FixedString", "");
+            set_attribute(&mut entry, "Slot", "FixedString", slot);
+            set_attribute(&mut entry, "VisualResource", "FixedString", visual_resource);
+            node.children.push(entry);
+        }
+        Ok(())
+    }
+
+    /// Serialize to LSX
+    ///
+    /// # Errors
+    /// Returns an error if XML serialization fails.
+    pub fn to_lsx(&self) -> Result<String> {
+        serialize_lsx(&self.doc)
+    }
+
+    /// Serialize to LSF
+    ///
+    /// # Errors
+    /// Returns an error if conversion to LSF fails.
+    pub fn to_lsf(&self) -> Result<Vec<u8>> {
+        serialize_lsf(&from_lsx(&self.to_lsx()?)?)
+    }
+
+    /// Write to disk in the format given by the extension (`.lsf`, `.lsx` or
+    /// `.lsj`)
+    ///
+    /// # Errors
+    /// Returns an error if the extension is not one of those or the file
+    /// cannot be written.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let data = match ext.as_str() {
+            "lsf" => self.to_lsf()?,
+            "lsx" => self.to_lsx()?.into_bytes(),
+            "lsj" => serialize_lsj(&to_lsj(&self.doc)?)?.into_bytes(),
+            _ => {
+                return Err(Error::InvalidFormat(format!(
+                    "can't write banks as '.{ext}', expected .lsf, .lsx or .lsj"
+                )));
+            }
+        };
+        std::fs::write(path, data).with_path(path)?;
+        Ok(())
+    }
+
+    /// `Resource` nodes of a bank
+    fn resources<'a>(&'a self, bank: &str) -> impl Iterator<Item = &'a LsxNode> {
+        self.doc
+            .find_node(bank)
+            .into_iter()
+            .flat_map(|b| &b.children)
+            .filter(|n| n.id == "Resource")
+    }
+
+    /// The `Resource` node of a bank with this ID or name
+    fn resource_mut(&mut self, bank: &str, resource: &str) -> Result<&mut LsxNode> {
+        self.doc
+            .find_node_mut(bank)
+            .and_then(|b| {
+                b.children
+                    .iter_mut()
+                    .find(|n| n.id == "Resource" && is_resource(n, resource))
+            })
+            .ok_or_else(|| Error::BankResourceNotFound {
+                bank: bank.to_string(),
+                resource: resource.to_string(),
+            })
+    }
+
+    /// A bank's node, created in a region of its own if missing
+    fn bank_mut(&mut self, bank: &str) -> &mut LsxNode {
+        if self.doc.find_node(bank).is_none() {
+            self.doc.regions.push(LsxRegion {
+                id: bank.to_string(),
+                nodes: vec![LsxNode::new(bank.to_string())],
+            });
+        }
+        self.doc
+            .find_node_mut(bank)
+            .expect("bank node was just created")
+    }
+}
+
+/// Whether a `Resource` node has this ID (any case) or name
+fn is_resource(node: &LsxNode, resource: &str) -> bool {
+    node.attribute_value("ID")
+        .is_some_and(|v| v.eq_ignore_ascii_case(resource))
+        || node.attribute_value("Name") == Some(resource)
+}
+
+/// The `Resource` node for `id` in a bank, added if missing
+fn upsert_resource<'a>(bank: &'a mut LsxNode, id: &str) -> (&'a mut LsxNode, bool) {
+    let existing = bank.children.iter().position(|n| {
+        n.id == "Resource"
+            && n.attribute_value("ID")
+                .is_some_and(|v| v.eq_ignore_ascii_case(id))
+    });
+    if let Some(index) = existing {
+        return (&mut bank.children[index], false);
+    }
+    let mut node = LsxNode::new("Resource".to_string());
+    set_attribute(&mut node, "ID", "FixedString", id);
+    bank.children.push(node);
+    (
+        bank.children.last_mut().expect("resource was just added"),
+        true,
+    )
+}
+
+/// An attribute's value, or an empty string
+fn value(node: &LsxNode, id: &str) -> String {
+    node.attribute_value(id).unwrap_or_default().to_string()
+}
+
+fn visual_resource(node: &LsxNode) -> VisualResource {
+    VisualResource {
+        id: value(node, "ID"),
+        name: value(node, "Name"),
+        source_file: value(node, "SourceFile"),
+        template: value(node, "Template"),
+        slot: value(node, "Slot"),
+        objects: node
+            .children
+            .iter()
+            .filter(|c| c.id == "Objects")
+            .map(|c| VisualObject {
+                object_id: value(c, "ObjectID"),
+                material_id: value(c, "MaterialID"),
+                lod: c
+                    .attribute_value("LOD")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+fn material_resource(node: &LsxNode) -> MaterialResource {
+    MaterialResource {
+        id: value(node, "ID"),
+        name: value(node, "Name"),
+        source_file: value(node, "SourceFile"),
+        textures: node
+            .children
+            .iter()
+            .filter(|c| c.id == "Texture2DParameters")
+            .map(|c| MaterialTexture {
+                parameter: value(c, "ParameterName"),
+                texture_id: value(c, "ID"),
+            })
+            .collect(),
+    }
+}
+
+fn character_visual(node: &LsxNode) -> CharacterVisual {
+    CharacterVisual {
+        id: value(node, "ID"),
+        name: value(node, "Name"),
+        body_set_visual: value(node, "BodySetVisual"),
+        base_visual: value(node, "BaseVisual"),
+        slots: node
+            .children
+            .iter()
+            .filter(|c| c.id == "Slots")
+            .map(|c| CharacterSlot {
+                slot: value(c, "Slot"),
+                bone: value(c, "Bone"),
+                visual_resource: value(c, "VisualResource"),
+            })
+            .collect(),
+    }
+}
+
+/// Bind a texture to a material parameter, adding the parameter if missing
+fn set_texture(material: &mut LsxNode, texture: &MaterialTexture) {
+    if let Some(param) = material.children.iter_mut().find(|c| {
+        c.id == "Texture2DParameters"
+            && c.attribute_value("ParameterName") == Some(texture.parameter.as_str())
+    }) {
+        set_attribute(param, "ID", "FixedString", &texture.texture_id);
+        return;
+    }
+    let mut param = LsxNode::new("Texture2DParameters".to_string());
+    set_attribute(&mut param, "Enabled", "bool", "True");
+    set_attribute(&mut param, "ID", "FixedString", &texture.texture_id);
+    set_attribute(
+        &mut param,
+        "ParameterName",
+        "FixedString",
+        &texture.parameter,
+    );
+    material.children.push(param);
+}
+
+/// Set an attribute's value, adding it if missing
+fn set_attribute(node: &mut LsxNode, id: &str, type_name: &str, value: &str) {
+    if let Some(attr) = node.attribute_mut(id) {
+        attr.value = value.to_string();
+    } else {
+        node.attributes.push(LsxAttribute::new(
+            id.to_string(),
+            type_name.to_string(),
+            value.to_string(),
+        ));
+    }
+}
+
+/// Set an attribute, but don't add an empty one
+fn set_optional(node: &mut LsxNode, id: &str, type_name: &str, value: &str) {
+    if !value.is_empty() || node.attribute(id).is_some() {
+        set_attribute(node, id, type_name, value);
+    }
+}
+
+/// Text of an LSX/LSJ document, without a BOM
+fn text(data: &[u8]) -> Result<&str> {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    std::str::from_utf8(data).map_err(|e| Error::InvalidFormat(format!("input is not UTF-8: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cut-down equipment `_merged.lsx`: one visual, its material and a
+    /// character visual wearing it
+    const BANKS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="328" />
+    <region id="VisualBank">
+        <node id="VisualBank">
+            <children>
+                <node id="Resource">
+                    <attribute id="BoundsMax" type="fvec3" value="0.5 1.9 0.3" />
+                    <attribute id="ID" type="FixedString" value="8b3f7c1e-1a2b-4c3d-9e8f-0a1b2c3d4e5f" />
+                    <attribute id="Name" type="LSString" value="HUM_M_ARM_MyRobe_Body" />
+                    <attribute id="Slot" type="FixedString" value="Body" />
+                    <attribute id="SourceFile" type="LSString" value="Generated/Public/MyRobe/Assets/HUM_M_ARM_MyRobe_Body.GR2" />
+                    <attribute id="Template" type="FixedString" value="HUM_M_ARM_MyRobe_Body.Dummy_Root.0" />
+                    <children>
+                        <node id="Objects">
+                            <attribute id="LOD" type="uint8" value="0" />
+                            <attribute id="MaterialID" type="FixedString" value="mat-robe" />
+                            <attribute id="ObjectID" type="FixedString" value="HUM_M_ARM_MyRobe_Body.Robe_Mesh" />
+                        </node>
+                        <node id="Objects">
+                            <attribute id="LOD" type="uint8" value="1" />
+                            <attribute id="MaterialID" type="FixedString" value="mat-robe" />
+                            <attribute id="ObjectID" type="FixedString" value="HUM_M_ARM_MyRobe_Body.Robe_Mesh" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+    <region id="MaterialBank">
+        <node id="MaterialBank">
+            <children>
+                <node id="Resource">
+                    <attribute id="ID" type="FixedString" value="mat-robe" />
+                    <attribute id="Name" type="LSString" value="MyRobe_Material" />
+                    <attribute id="SourceFile" type="LSString" value="Public/Shared/Assets/Materials/Characters/CHAR_BASE.lsf" />
+                    <children>
+                        <node id="Texture2DParameters">
+                            <attribute id="Enabled" type="bool" value="True" />
+                            <attribute id="ID" type="FixedString" value="tex-robe-bm" />
+                            <attribute id="ParameterName" type="FixedString" value="basecolor" />
+                        </node>
+                        <node id="ScalarParameters">
+                            <attribute id="ParameterName" type="FixedString" value="Roughness" />
+                            <attribute id="Value" type="float" value="0.5" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+    <region id="CharacterVisualBank">
+        <node id="CharacterVisualBank">
+            <children>
+                <node id="Resource">
+                    <attribute id="BaseVisual" type="FixedString" value="base-hum-m" />
+                    <attribute id="BodySetVisual" type="FixedString" value="body-hum-m" />
+                    <attribute id="ID" type="FixedString" value="cv-guard" />
+                    <attribute id="Name" type="LSString" value="HUM_M_Guard" />
+                    <children>
+                        <node id="Slots">
+                            <attribute id="Bone" type="FixedString" value="" />
+                            <attribute id="Slot" type="FixedString" value="Hair" />
+                            <attribute id="VisualResource" type="FixedString" value="hair-short" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#;
+
+    fn banks() -> BankDocument {
+        BankDocument::parse_bytes(BANKS.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_banks() {
+        let banks = banks();
+
+        let visuals = banks.visuals();
+        assert_eq!(visuals.len(), 1);
+        let robe = &visuals[0];
+        assert_eq!(robe.name, "HUM_M_ARM_MyRobe_Body");
+        assert_eq!(robe.slot, "Body");
+        assert!(robe.source_file.ends_with("HUM_M_ARM_MyRobe_Body.GR2"));
+        assert_eq!(robe.objects.len(), 2);
+        assert_eq!(robe.objects[1].lod, 1);
+        assert_eq!(robe.objects[0].material_id, "mat-robe");
+        assert_eq!(banks.visual("HUM_M_ARM_MyRobe_Body").as_ref(), Some(robe));
+        assert_eq!(
+            banks
+                .visual("8B3F7C1E-1A2B-4C3D-9E8F-0A1B2C3D4E5F")
+                .as_ref(),
+            Some(robe)
+        );
+
+        let material = banks.material("mat-robe").unwrap();
+        assert_eq!(
+            material.textures,
+            [MaterialTexture {
+                parameter: "basecolor".to_string(),
+                texture_id: "tex-robe-bm".to_string(),
+            }]
+        );
+
+        let guard = &banks.character_visuals()[0];
+        assert_eq!(guard.body_set_visual, "body-hum-m");
+        assert_eq!(guard.slots[0].slot, "Hair");
+        assert_eq!(guard.slots[0].visual_resource, "hair-short");
+    }
+
+    #[test]
+    fn test_edits_survive_lsf_round_trip() {
+        let mut banks = banks();
+        banks.set_slot("HUM_M_ARM_MyRobe_Body", "Cloak").unwrap();
+        banks
+            .set_object_material(
+                "HUM_M_ARM_MyRobe_Body",
+                "HUM_M_ARM_MyRobe_Body.Robe_Mesh",
+                "mat-dyed",
+            )
+            .unwrap();
+        banks
+            .set_character_slot(
+                "HUM_M_Guard",
+                "Body",
+                "8b3f7c1e-1a2b-4c3d-9e8f-0a1b2c3d4e5f",
+            )
+            .unwrap();
+        assert!(!banks.upsert_material(&MaterialResource {
+            id: "mat-robe".to_string(),
+            name: "MyRobe_Material".to_string(),
+            source_file: "Public/Shared/Assets/Materials/Characters/CHAR_BASE.lsf".to_string(),
+            textures: vec![MaterialTexture {
+                parameter: "NormalMap".to_string(),
+                texture_id: "tex-robe-nm".to_string(),
+            }],
+        }));
+        assert!(matches!(
+            banks.set_slot("missing", "Body"),
+            Err(Error::BankResourceNotFound { .. })
+        ));
+        assert!(matches!(
+            banks.set_object_material("HUM_M_ARM_MyRobe_Body", "Nope", "mat-dyed"),
+            Err(Error::BankObjectNotFound { .. })
+        ));
+
+        let banks = BankDocument::parse_bytes(&banks.to_lsf().unwrap()).unwrap();
+        let robe = banks.visual("HUM_M_ARM_MyRobe_Body").unwrap();
+        assert_eq!(robe.slot, "Cloak");
+        assert!(robe.objects.iter().all(|o| o.material_id == "mat-dyed"));
+        // Attributes the struct doesn't model are kept
+        let doc = banks.document();
+        assert_eq!(
+            doc.find_node("VisualBank").unwrap().children[0].attribute_value("BoundsMax"),
+            Some("0.5 1.9 0.3")
+        );
+
+        let material = banks.material("MyRobe_Material").unwrap();
+        assert_eq!(material.textures.len(), 2);
+        assert_eq!(material.textures[1].texture_id, "tex-robe-nm");
+        assert!(doc.find_node("ScalarParameters").is_some());
+
+        let slots = &banks.character_visuals()[0].slots;
+        assert_eq!(slots.len(), 2);
+        assert_eq!(slots[1].slot, "Body");
+    }
+
+    #[test]
+    fn test_build_new_bank() {
+        let mut banks = BankDocument::new();
+        let visual = VisualResource {
+            id: "new-visual".to_string(),
+            name: "HUM_F_ARM_MyBoots".to_string(),
+            source_file: "Generated/Public/MyBoots/Assets/HUM_F_ARM_MyBoots.GR2".to_string(),
+            template: String::new(),
+            slot: "Boots".to_string(),
+            objects: vec![VisualObject {
+                object_id: "HUM_F_ARM_MyBoots.Boots_Mesh".to_string(),
+                material_id: "mat-boots".to_string(),
+                lod: 0,
+            }],
+        };
+        assert!(banks.upsert_visual(&visual));
+        assert!(!banks.upsert_visual(&visual));
+
+        let lsx = banks.to_lsx().unwrap();
+        let banks = BankDocument::parse_bytes(lsx.as_bytes()).unwrap();
+        assert_eq!(banks.visuals(), [visual]);
+        // An empty template isn't written
+        assert!(!lsx.contains("\"Template\""));
+
+        let mut banks = banks;
+        assert!(banks.remove_visual("HUM_F_ARM_MyBoots").is_some());
+        assert!(banks.visuals().is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+//! Markdown and Graphviz export of dialog graphs
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use super::types::{Dialog, DialogNode, FlagGroup};
+use crate::formats::loca::LocaResolver;
+
+/// Speaker index the game uses for the narrator
+const NARRATOR_SPEAKER: i32 = -666;
+
+/// Output format for [`export_dialog`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DialogExportFormat {
+    /// Indented Markdown tree, one list item per node
+    #[default]
+    Markdown,
+    /// Graphviz DOT graph
+    Dot,
+}
+
+impl DialogExportFormat {
+    /// Usual file extension for the format
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Dot => "dot",
+        }
+    }
+}
+
+/// Export a dialog, resolving text through `loca` when given
+#[must_use]
+pub fn export_dialog(
+    dialog: &Dialog,
+    format: DialogExportFormat,
+    loca: Option<&LocaResolver>,
+) -> String {
+    match format {
+        DialogExportFormat::Markdown => to_markdown(dialog, loca),
+        DialogExportFormat::Dot => to_dot(dialog, loca),
+    }
+}
+
+/// Text of a node's first line
+///
+/// Looks the handle up in `loca`, falling back to the text stored in the
+/// dialog itself. Returns `None` if the node has no text or it can't be
+/// resolved.
+#[must_use]
+pub fn node_text(
+    dialog: &Dialog,
+    node: &DialogNode,
+    loca: Option<&LocaResolver>,
+) -> Option<String> {
+    let entry = dialog.get_node_text(node)?;
+    loca.and_then(|loca| loca.resolve(&entry.handle))
+        .map(|resolved| resolved.text.clone())
+        .or_else(|| entry.value.clone())
+        .filter(|text| !text.is_empty())
+}
+
+/// Render a dialog as an indented Markdown tree
+///
+/// Nodes are listed under the root they're reached from. A node reached a
+/// second time is shown as a reference instead of being expanded again, and
+/// nodes no root reaches are listed at the end.
+#[must_use]
+pub fn to_markdown(dialog: &Dialog, loca: Option<&LocaResolver>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Dialog {}", dialog.uuid);
+    if let Some(synopsis) = dialog
+        .editor_data
+        .synopsis
+        .as_deref()
+        .filter(|s| !s.is_empty())
+    {
+        let _ = writeln!(out, "\n_{}_", one_line(synopsis));
+    }
+    out.push('\n');
+
+    let mut visited = HashSet::new();
+    for root in &dialog.root_nodes {
+        markdown_node(dialog, root, 0, loca, &mut visited, &mut out);
+    }
+
+    let unreachable: Vec<&String> = dialog
+        .node_order
+        .iter()
+        .filter(|uuid| !visited.contains(uuid.as_str()))
+        .collect();
+    if !unreachable.is_empty() {
+        out.push_str("\n## Not reachable from a root\n\n");
+        for uuid in unreachable {
+            markdown_node(dialog, uuid, 0, loca, &mut visited, &mut out);
+        }
+    }
+    out
+}
+
+fn markdown_node<'a>(
+    dialog: &'a Dialog,
+    uuid: &'a str,
+    depth: usize,
+    loca: Option<&LocaResolver>,
+    visited: &mut HashSet<&'a str>,
+    out: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+    let Some(node) = dialog.get_node(uuid) else {
+        let _ = writeln!(out, "{indent}- _missing node `{uuid}`_");
+        return;
+    };
+    if !visited.insert(uuid) {
+        let _ = writeln!(out, "{indent}- ↪ _see {}_", node_label(dialog, node, loca));
+        return;
+    }
+
+    let _ = write!(out, "{indent}- {}", node_label(dialog, node, loca));
+    for (name, groups) in [("checks", &node.check_flags), ("sets", &node.set_flags)] {
+        let flags = format_flags(groups);
+        if !flags.is_empty() {
+            let _ = write!(out, " · {name} `{flags}`");
+        }
+    }
+    if let Some(target) = &node.jump_target {
+        let _ = write!(out, " · jumps to `{target}`");
+    }
+    if let Some(source) = &node.source_node {
+        let _ = write!(out, " · alias of `{source}`");
+    }
+    if node.end_node {
+        out.push_str(" · _end_");
+    }
+    out.push('\n');
+
+    for child in &node.children {
+        markdown_node(dialog, child, depth + 1, loca, visited, out);
+    }
+}
+
+/// Render a dialog as a Graphviz DOT digraph
+///
+/// Root nodes get a double border; jumps and alias sources are drawn as
+/// dashed and dotted edges.
+#[must_use]
+pub fn to_dot(dialog: &Dialog, loca: Option<&LocaResolver>) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph \"{}\" {{", dot_escape(&dialog.uuid));
+    out.push_str("  node [shape=box, style=rounded];\n");
+
+    let roots: HashSet<&str> = dialog.root_nodes.iter().map(String::as_str).collect();
+    for uuid in &dialog.node_order {
+        let Some(node) = dialog.get_node(uuid) else {
+            continue;
+        };
+        let mut label = node.constructor.display_name().to_string();
+        if let Some(speaker) = speaker_label(node) {
+            let _ = write!(label, " · {speaker}");
+        }
+        if let Some(text) = node_text(dialog, node, loca) {
+            let _ = write!(label, "\n{}", one_line(&text));
+        }
+        for (name, groups) in [("checks", &node.check_flags), ("sets", &node.set_flags)] {
+            let flags = format_flags(groups);
+            if !flags.is_empty() {
+                let _ = write!(label, "\n{name}: {flags}");
+            }
+        }
+        let _ = write!(out, "  \"{uuid}\" [label=\"{}\"", dot_escape(&label));
+        if roots.contains(uuid.as_str()) {
+            out.push_str(", peripheries=2");
+        }
+        if node.end_node {
+            out.push_str(", style=\"rounded,bold\"");
+        }
+        out.push_str("];\n");
+    }
+
+    for uuid in &dialog.node_order {
+        let Some(node) = dialog.get_node(uuid) else {
+            continue;
+        };
+        for child in &node.children {
+            let _ = writeln!(out, "  \"{uuid}\" -> \"{child}\";");
+        }
+        if let Some(target) = &node.jump_target {
+            let _ = writeln!(
+                out,
+                "  \"{uuid}\" -> \"{target}\" [style=dashed, label=\"jump\"];"
+            );
+        }
+        if let Some(source) = &node.source_node {
+            let _ = writeln!(
+                out,
+                "  \"{uuid}\" -> \"{source}\" [style=dotted, label=\"alias\"];"
+            );
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// `**Question** Speaker 1: "text"`, with the handle if there's no text
+fn node_label(dialog: &Dialog, node: &DialogNode, loca: Option<&LocaResolver>) -> String {
+    let mut label = format!("**{}**", node.constructor.display_name());
+    if let Some(speaker) = speaker_label(node) {
+        let _ = write!(label, " {speaker}");
+    }
+    if let Some(text) = node_text(dialog, node, loca) {
+        let _ = write!(label, ": \"{}\"", one_line(&text));
+    } else if let Some(entry) = dialog.get_node_text(node) {
+        let _ = write!(label, ": `{}`", entry.handle);
+    } else if node.is_roll()
+        && let Some(skill) = node.skill.as_ref().or(node.ability.as_ref())
+    {
+        let _ = write!(label, ": {skill}");
+    }
+    label
+}
+
+fn speaker_label(node: &DialogNode) -> Option<String> {
+    match node.speaker? {
+        NARRATOR_SPEAKER => Some("Narrator".to_string()),
+        index if index >= 0 => Some(format!("Speaker {index}")),
+        _ => None,
+    }
+}
+
+/// `Global:FLAG, !Local:OTHER` for a node's flag groups
+fn format_flags(groups: &[FlagGroup]) -> String {
+    groups
+        .iter()
+        .flat_map(|group| {
+            group.flags.iter().map(move |flag| {
+                format!(
+                    "{}{}:{}",
+                    if flag.value { "" } else { "!" },
+                    group.flag_type.as_str(),
+                    flag.name.as_deref().unwrap_or(&flag.uuid)
+                )
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Collapse line breaks so text fits on one line
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
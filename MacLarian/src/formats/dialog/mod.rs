@@ -0,0 +1,247 @@
+//! Dialog resources (`Story/Dialogs/**/*.lsf`)
+//!
+//! Dialogs are LSF/LSX/LSJ documents with a `dialog` region holding a graph
+//! of nodes: each node has a constructor (`TagQuestion`, `TagAnswer`,
+//! `ActiveRoll`, ...), a speaker index, `TaggedTexts` with `TranslatedString`
+//! handles, check/set flag groups and links to its children. [`parse_dialog`]
+//! reads that graph into a [`Dialog`], and [`to_markdown`] and [`to_dot`]
+//! export it, resolving text through a [`LocaResolver`] when one is given.
+//!
+//! ```no_run
+//! use maclarian::formats::dialog::{read_dialog, to_markdown};
+//! use maclarian::formats::loca::LocaResolver;
+//!
+//! let dialog = read_dialog("CAMP_Shadowheart_Romance.lsf")?;
+//! let mut loca = LocaResolver::new("English");
+//! loca.add_bytes(&std::fs::read("english.loca")?, None, "english.loca")?;
+//! println!("{}", to_markdown(&dialog, Some(&loca)));
+//! # Ok::<(), maclarian::Error>(())
+//! ```
+//!
+//! [`LocaResolver`]: crate::formats::loca::LocaResolver
+
+mod export;
+mod parser;
+mod types;
+
+pub use export::{DialogExportFormat, export_dialog, node_text, to_dot, to_markdown};
+pub use parser::parse_dialog;
+pub use types::*;
+
+use std::path::Path;
+
+use crate::converter::{sniff_format, to_lsj, to_lsx};
+use crate::error::{Error, Result, ResultExt};
+use crate::formats::lsb::parse_lsb_bytes;
+use crate::formats::lsf::parse_lsf_bytes;
+use crate::formats::lsj::parse_lsj;
+use crate::formats::lsx::parse_lsx;
+
+/// Parse a dialog from LSF, LSX or LSJ bytes
+///
+/// The format is detected from the data.
+///
+/// # Errors
+/// Returns an error if the data is not an LSF/LSX/LSJ document or has no
+/// `dialog` region.
+pub fn parse_dialog_bytes(data: &[u8]) -> Result<Dialog> {
+    let lsx_doc = match sniff_format(data) {
+        Some("lsj") => return parse_dialog(&parse_lsj(text(data)?)?),
+        Some("lsf") => parse_lsx(&to_lsx(&parse_lsf_bytes(data)?)?)?,
+        Some("lsx") => parse_lsx(text(data)?)?,
+        Some("lsb") => parse_lsb_bytes(data)?,
+        other => {
+            return Err(Error::InvalidFormat(format!(
+                "expected an LSF, LSX or LSJ dialog, found {}",
+                other.unwrap_or("unknown data")
+            )));
+        }
+    };
+    parse_dialog(&to_lsj(&lsx_doc)?)
+}
+
+/// Read a dialog from an LSF, LSX or LSJ file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed as a dialog.
+pub fn read_dialog<P: AsRef<Path>>(path: P) -> Result<Dialog> {
+    let path = path.as_ref();
+    let data = std::fs::read(path).with_path(path)?;
+    parse_dialog_bytes(&data).with_path(path)
+}
+
+/// Text of an LSX/LSJ document, without a BOM
+fn text(data: &[u8]) -> Result<&str> {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    std::str::from_utf8(data).map_err(|e| Error::InvalidFormat(format!("input is not UTF-8: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::loca::{LocaResolver, LocaResource, LocalizedText};
+
+    /// Cut-down vanilla dialog: a greeting with a flag-gated answer, a
+    /// persuasion roll and a jump back to the greeting
+    const DIALOG: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="328" />
+    <region id="dialog">
+        <node id="dialog">
+            <attribute id="UUID" type="FixedString" value="d0a1b2c3-0000-0000-0000-000000000001" />
+            <attribute id="category" type="LSString" value="Generic NPC Dialog" />
+            <children>
+                <node id="speakerlist">
+                    <children>
+                        <node id="speaker">
+                            <attribute id="index" type="FixedString" value="0" />
+                            <attribute id="list" type="LSString" value="3ed74f06-3c60-42dc-83f6-f034cb47c679" />
+                        </node>
+                    </children>
+                </node>
+                <node id="nodes">
+                    <children>
+                        <node id="RootNodes">
+                            <attribute id="RootNodes" type="FixedString" value="node-greeting" />
+                        </node>
+                        <node id="node">
+                            <attribute id="UUID" type="FixedString" value="node-greeting" />
+                            <attribute id="constructor" type="FixedString" value="TagQuestion" />
+                            <attribute id="speaker" type="int32" value="0" />
+                            <children>
+                                <node id="children">
+                                    <children>
+                                        <node id="child">
+                                            <attribute id="UUID" type="FixedString" value="node-answer" />
+                                        </node>
+                                        <node id="child">
+                                            <attribute id="UUID" type="FixedString" value="node-roll" />
+                                        </node>
+                                    </children>
+                                </node>
+                                <node id="TaggedTexts">
+                                    <children>
+                                        <node id="TaggedText">
+                                            <attribute id="HasTagRule" type="bool" value="True" />
+                                            <children>
+                                                <node id="TagTexts">
+                                                    <children>
+                                                        <node id="TagText">
+                                                            <attribute id="LineId" type="guid" value="0f4e6bd4-94b3-4d39-a1f8-2a6de11d4a36" />
+                                                            <attribute id="TagText" type="TranslatedString" handle="h1f2e3d4cg0001g4000g8000g000000000001" version="1" />
+                                                            <attribute id="stub" type="bool" value="True" />
+                                                        </node>
+                                                    </children>
+                                                </node>
+                                            </children>
+                                        </node>
+                                    </children>
+                                </node>
+                            </children>
+                        </node>
+                        <node id="node">
+                            <attribute id="UUID" type="FixedString" value="node-answer" />
+                            <attribute id="constructor" type="FixedString" value="TagAnswer" />
+                            <attribute id="endnode" type="bool" value="True" />
+                            <children>
+                                <node id="checkflags">
+                                    <children>
+                                        <node id="flaggroup">
+                                            <attribute id="type" type="FixedString" value="Global" />
+                                            <children>
+                                                <node id="flag">
+                                                    <attribute id="UUID" type="FixedString" value="flag-met-before" />
+                                                    <attribute id="value" type="bool" value="False" />
+                                                </node>
+                                            </children>
+                                        </node>
+                                    </children>
+                                </node>
+                                <node id="TaggedTexts">
+                                    <children>
+                                        <node id="TaggedText">
+                                            <children>
+                                                <node id="TagTexts">
+                                                    <children>
+                                                        <node id="TagText">
+                                                            <attribute id="TagText" type="TranslatedString" handle="h1f2e3d4cg0001g4000g8000g000000000002" version="1" />
+                                                        </node>
+                                                    </children>
+                                                </node>
+                                            </children>
+                                        </node>
+                                    </children>
+                                </node>
+                            </children>
+                        </node>
+                        <node id="node">
+                            <attribute id="UUID" type="FixedString" value="node-roll" />
+                            <attribute id="constructor" type="FixedString" value="ActiveRoll" />
+                            <attribute id="Skill" type="FixedString" value="Persuasion" />
+                            <attribute id="jumptarget" type="FixedString" value="node-greeting" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#;
+
+    #[test]
+    fn test_parse_and_export_dialog() {
+        let dialog = parse_dialog_bytes(DIALOG.as_bytes()).unwrap();
+        assert_eq!(dialog.uuid, "d0a1b2c3-0000-0000-0000-000000000001");
+        assert_eq!(dialog.root_nodes, ["node-greeting"]);
+        assert_eq!(
+            dialog.node_order,
+            ["node-greeting", "node-answer", "node-roll"]
+        );
+        assert_eq!(dialog.speakers.len(), 1);
+
+        let greeting = dialog.get_node("node-greeting").unwrap();
+        assert!(greeting.is_question());
+        assert_eq!(greeting.speaker, Some(0));
+        assert_eq!(greeting.children, ["node-answer", "node-roll"]);
+        let text = dialog.get_node_text(greeting).unwrap();
+        assert_eq!(text.handle, "h1f2e3d4cg0001g4000g8000g000000000001");
+        assert!(text.stub);
+
+        let answer = dialog.get_node("node-answer").unwrap();
+        assert!(answer.end_node);
+        assert_eq!(answer.check_flags[0].flag_type, FlagType::Global);
+        assert!(!answer.check_flags[0].flags[0].value);
+
+        let mut loca = LocaResolver::new("English");
+        loca.add_resource(
+            LocaResource {
+                entries: vec![LocalizedText::new(
+                    "h1f2e3d4cg0001g4000g8000g000000000001".to_string(),
+                    1,
+                    "Well met,\ntraveller.".to_string(),
+                )],
+            },
+            None,
+            "english.loca",
+        );
+
+        let markdown = to_markdown(&dialog, Some(&loca));
+        assert_eq!(
+            markdown,
+            "# Dialog d0a1b2c3-0000-0000-0000-000000000001
+
+- **Question** Speaker 0: \"Well met, traveller.\"
+  - **Answer**: `h1f2e3d4cg0001g4000g8000g000000000002` · checks `!Global:flag-met-before` · _end_
+  - **Active Roll**: Persuasion · jumps to `node-greeting`
+"
+        );
+
+        let dot = to_dot(&dialog, Some(&loca));
+        assert!(dot.contains(
+            "\"node-greeting\" [label=\"Question · Speaker 0\\nWell met, traveller.\", peripheries=2];"
+        ));
+        assert!(dot.contains("\"node-greeting\" -> \"node-answer\";"));
+        assert!(dot.contains("\"node-roll\" -> \"node-greeting\" [style=dashed, label=\"jump\"];"));
+
+        assert!(parse_dialog_bytes(b"LSOF").is_err());
+    }
+}
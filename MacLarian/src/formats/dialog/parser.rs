@@ -6,13 +6,14 @@ use super::types::{
     Dialog, DialogEditorData, DialogNode, Flag, FlagGroup, FlagType, GameData, NodeConstructor,
     Rule, RuleGroup, SpeakerInfo, TagTextEntry, TaggedText,
 };
-use maclarian::formats::lsj::{LsjAttribute, LsjDocument, LsjNode};
+use crate::error::{Error, Result};
+use crate::formats::lsj::{LsjAttribute, LsjDocument, LsjNode};
 
 /// Parse a dialog from an LSJ document
 ///
 /// # Errors
-/// Returns an error if the document is missing required regions or has invalid format.
-pub fn parse_dialog(doc: &LsjDocument) -> Result<Dialog, DialogParseError> {
+/// Returns [`Error::InvalidFormat`] if the document has no `dialog` region.
+pub fn parse_dialog(doc: &LsjDocument) -> Result<Dialog> {
     let mut dialog = Dialog::new();
 
     // Get the dialog region
@@ -21,7 +22,7 @@ pub fn parse_dialog(doc: &LsjDocument) -> Result<Dialog, DialogParseError> {
         .regions
         .get("dialog")
         .or_else(|| doc.save.regions.get("Dialog"))
-        .ok_or(DialogParseError::MissingRegion("dialog".to_string()))?;
+        .ok_or_else(|| Error::InvalidFormat("missing region: dialog".to_string()))?;
 
     // Parse dialog-level attributes
     if let Some(uuid_attr) = dialog_region.attributes.get("UUID") {
@@ -487,7 +488,6 @@ fn parse_tagged_text(node: &LsjNode) -> Option<TaggedText> {
                                 LsjAttribute::Simple { value, .. } => {
                                     (value.as_str().unwrap_or("").to_string(), None, None)
                                 }
-                                _ => (String::new(), None, None),
                             }
                         } else {
                             (String::new(), None, None)
@@ -615,7 +615,6 @@ fn get_string_value(attr: &LsjAttribute) -> String {
         LsjAttribute::TranslatedFSString { value, handle, .. } => {
             value.clone().unwrap_or_else(|| handle.clone())
         }
-        _ => String::new(),
     }
 }
 
@@ -656,29 +655,3 @@ fn get_bool_value(attr: &LsjAttribute) -> bool {
         _ => false,
     }
 }
-
-/// Error type for dialog parsing
-#[derive(Debug)]
-pub enum DialogParseError {
-    MissingRegion(String),
-    InvalidFormat(String),
-    IoError(std::io::Error),
-}
-
-impl std::fmt::Display for DialogParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            DialogParseError::MissingRegion(r) => write!(f, "Missing region: {r}"),
-            DialogParseError::InvalidFormat(s) => write!(f, "Invalid format: {s}"),
-            DialogParseError::IoError(e) => write!(f, "IO error: {e}"),
-        }
-    }
-}
-
-impl std::error::Error for DialogParseError {}
-
-impl From<std::io::Error> for DialogParseError {
-    fn from(err: std::io::Error) -> Self {
-        DialogParseError::IoError(err)
-    }
-}
@@ -129,10 +129,10 @@ pub struct DialogNode {
     /// Approval rating ID (companion reactions)
     pub approval_rating_id: Option<String>,
 
-    // Validated flags
+    /// Whether the node's flags have been validated in the editor
     pub validated_has_value: bool,
 
-    // Game data
+    /// Game data (AI personalities, sounds)
     pub game_data: Option<GameData>,
 
     /// Editor-specific key-value data
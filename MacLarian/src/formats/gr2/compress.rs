@@ -0,0 +1,399 @@
+//! `BitKnit` compression for Granny2 files
+//!
+//! The encoder mirrors the decoder in `decompress.rs`: it runs the same
+//! adaptive models forward over an LZ77 parse of each 64KB quantum, records
+//! every symbol with the frequencies the decoder will see, then encodes the
+//! two interleaved rANS streams in reverse. Quanta that don't shrink are
+//! stored raw, which the format marks with a leading zero word.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use super::decompress::{
+    BITKNIT_MAGIC, DeferredAdaptiveModel, QUANTUM_SIZE, RANS_THRESHOLD, RegisterLRUCache,
+};
+
+/// Longest copy a command can express
+const MAX_COPY_LENGTH: usize = 8223;
+
+/// Shortest copy worth encoding with an explicit offset
+const MIN_EXPLICIT_COPY: usize = 4;
+
+/// Largest offset an explicit copy can express
+const MAX_COPY_OFFSET: usize = (32 << 20) - 1;
+
+/// Match finder hash table size (log2)
+const HASH_BITS: u32 = 16;
+
+/// Candidates examined per position
+const MAX_CHAIN: usize = 32;
+
+/// One step of the decoder's bitstream, in decode order
+#[derive(Clone, Copy)]
+enum Op {
+    /// Raw bits popped from the current rANS state
+    Bits { value: u32, count: usize },
+    /// A symbol popped from the current rANS state through a model
+    Symbol { cumul: u32, freq: u32 },
+    /// A word read directly from the stream (high offset bits)
+    Word(u16),
+}
+
+/// Model state carried from one quantum to the next
+#[derive(Clone)]
+struct EncoderState {
+    command_models: [DeferredAdaptiveModel; 4],
+    cache_reference_models: [DeferredAdaptiveModel; 4],
+    copy_offset_model: DeferredAdaptiveModel,
+    copy_offset_cache: RegisterLRUCache,
+    delta_offset: usize,
+}
+
+impl EncoderState {
+    fn new() -> Self {
+        Self {
+            command_models: std::array::from_fn(|_| DeferredAdaptiveModel::command()),
+            cache_reference_models: std::array::from_fn(|_| {
+                DeferredAdaptiveModel::cache_reference()
+            }),
+            copy_offset_model: DeferredAdaptiveModel::copy_offset(),
+            copy_offset_cache: RegisterLRUCache::new(),
+            delta_offset: 1,
+        }
+    }
+
+    /// Current offset held in cache slot `index`
+    fn cached_offset(&self, index: usize) -> usize {
+        let slot = (self.copy_offset_cache.entry_order >> (index * 4)) & 0xF;
+        self.copy_offset_cache.entries[slot as usize] as usize
+    }
+}
+
+/// Push `symbol` through `model`, recording the frequencies used
+fn push_symbol(ops: &mut Vec<Op>, model: &mut DeferredAdaptiveModel, symbol: usize) {
+    let freq = u32::from(model.cdf.frequency(symbol));
+    debug_assert!(freq > 0, "BitKnit model has no probability for {symbol}");
+    ops.push(Op::Symbol {
+        cumul: u32::from(model.cdf.sum_below(symbol)),
+        freq,
+    });
+    model.observe_symbol(symbol);
+}
+
+/// Hash-chain match finder over the whole input
+struct MatchFinder {
+    head: Vec<u32>,
+    prev: Vec<u32>,
+    inserted: usize,
+}
+
+impl MatchFinder {
+    fn new(len: usize) -> Self {
+        Self {
+            head: vec![u32::MAX; 1 << HASH_BITS],
+            prev: vec![u32::MAX; len],
+            inserted: 0,
+        }
+    }
+
+    fn hash(data: &[u8], pos: usize) -> usize {
+        let v = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        (v.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    }
+
+    /// Index every position before `pos`
+    fn insert_until(&mut self, data: &[u8], pos: usize) {
+        while self.inserted < pos {
+            let i = self.inserted;
+            if i + 4 <= data.len() {
+                let h = Self::hash(data, i);
+                self.prev[i] = self.head[h];
+                self.head[h] = i as u32;
+            }
+            self.inserted += 1;
+        }
+    }
+
+    /// Longest earlier match for `pos`, as `(offset, length)`
+    fn find(&self, data: &[u8], pos: usize, max_len: usize) -> Option<(usize, usize)> {
+        if pos + 4 > data.len() || max_len < MIN_EXPLICIT_COPY {
+            return None;
+        }
+        let mut best: Option<(usize, usize)> = None;
+        let mut candidate = self.head[Self::hash(data, pos)];
+        for _ in 0..MAX_CHAIN {
+            if candidate == u32::MAX {
+                break;
+            }
+            let start = candidate as usize;
+            let offset = pos - start;
+            if offset > MAX_COPY_OFFSET {
+                break;
+            }
+            let len = match_length(data, start, pos, max_len);
+            if len >= MIN_EXPLICIT_COPY && best.is_none_or(|(_, l)| len > l) {
+                best = Some((offset, len));
+                if len == max_len {
+                    break;
+                }
+            }
+            candidate = self.prev[start];
+        }
+        best
+    }
+}
+
+/// Number of bytes at `pos` matching those at `start`, up to `max_len`
+fn match_length(data: &[u8], start: usize, pos: usize, max_len: usize) -> usize {
+    let mut len = 0;
+    while len < max_len && data[start + len] == data[pos + len] {
+        len += 1;
+    }
+    len
+}
+
+/// Parse one quantum into decoder ops, advancing the model state
+fn parse_quantum(
+    data: &[u8],
+    start: usize,
+    end: usize,
+    state: &mut EncoderState,
+    finder: &mut MatchFinder,
+) -> Vec<Op> {
+    let mut ops = Vec::new();
+    let mut index = start;
+
+    if index == 0 {
+        ops.push(Op::Bits {
+            value: u32::from(data[0]),
+            count: 8,
+        });
+        index = 1;
+    }
+
+    while index < end {
+        let model_index = index % 4;
+        let max_len = (end - index).min(MAX_COPY_LENGTH);
+
+        // Prefer a recent offset, which costs almost nothing to encode
+        let mut cached: Option<(usize, usize)> = None;
+        for slot in 0..8 {
+            let offset = state.cached_offset(slot);
+            if offset == 0 || offset > index {
+                continue;
+            }
+            let len = match_length(data, index - offset, index, max_len);
+            if len >= 2 && cached.is_none_or(|(_, l)| len > l) {
+                cached = Some((slot, len));
+            }
+        }
+
+        finder.insert_until(data, index);
+        let explicit = finder.find(data, index, max_len);
+
+        let copy = match (cached, explicit) {
+            (Some((slot, len)), Some((_, explicit_len))) if len + 2 >= explicit_len => {
+                Some((Some(slot), state.cached_offset(slot), len))
+            }
+            (_, Some((offset, len))) => Some((None, offset, len)),
+            (Some((slot, len)), None) => Some((Some(slot), state.cached_offset(slot), len)),
+            (None, None) => None,
+        };
+
+        let Some((slot, offset, len)) = copy else {
+            let delta = if index >= state.delta_offset {
+                data[index - state.delta_offset]
+            } else {
+                0
+            };
+            let literal = data[index].wrapping_sub(delta);
+            push_symbol(
+                &mut ops,
+                &mut state.command_models[model_index],
+                usize::from(literal),
+            );
+            index += 1;
+            continue;
+        };
+
+        // Copy length
+        if len < 34 {
+            push_symbol(&mut ops, &mut state.command_models[model_index], len + 254);
+        } else {
+            let extra = len - 32;
+            let length_bits = (usize::BITS - 1 - extra.leading_zeros()) as usize;
+            push_symbol(
+                &mut ops,
+                &mut state.command_models[model_index],
+                287 + length_bits,
+            );
+            ops.push(Op::Bits {
+                value: (extra - (1 << length_bits)) as u32,
+                count: length_bits,
+            });
+        }
+
+        // Copy offset, from the cache or explicit
+        if let Some(slot) = slot {
+            push_symbol(
+                &mut ops,
+                &mut state.cache_reference_models[model_index],
+                slot,
+            );
+            state.copy_offset_cache.hit(slot);
+        } else {
+            let quotient = (offset - 1) / 32;
+            let remainder = (offset - 1) % 32 + 1;
+            push_symbol(
+                &mut ops,
+                &mut state.cache_reference_models[model_index],
+                remainder + 7,
+            );
+
+            let biased = quotient + 1;
+            let offset_bits = (usize::BITS - 1 - biased.leading_zeros()) as usize;
+            let value = (biased - (1 << offset_bits)) as u32;
+            push_symbol(&mut ops, &mut state.copy_offset_model, offset_bits);
+            if offset_bits >= 16 {
+                ops.push(Op::Bits {
+                    value: value >> 16,
+                    count: offset_bits - 16,
+                });
+                ops.push(Op::Word(value as u16));
+            } else {
+                ops.push(Op::Bits {
+                    value,
+                    count: offset_bits,
+                });
+            }
+            state.copy_offset_cache.insert(offset as u32);
+        }
+
+        state.delta_offset = offset;
+        index += len;
+    }
+
+    ops
+}
+
+/// Encode one rANS step onto `state`, spilling a word when it would overflow
+fn encode_step(state: &mut u32, cumul: u32, freq: u32, bits: usize, words: &mut Vec<u16>) {
+    if u64::from(*state) >= u64::from(freq) << (32 - bits) {
+        words.push(*state as u16);
+        *state >>= 16;
+    }
+    *state = ((*state / freq) << bits) + (*state % freq) + cumul;
+}
+
+/// Words that make the decoder start with states `first` and `second`
+fn initial_state_words(first: u32, second: u32) -> Vec<u16> {
+    // The second state's top bit position is implied by the split
+    let split = 15 - second.leading_zeros();
+    let low = (second >> 16) & ((1 << split) - 1);
+
+    let mut words = Vec::with_capacity(5);
+    let (merged, first_refill) = if u64::from(first) << split < 1 << 32 {
+        ((first << split) | low, None)
+    } else {
+        (((first >> 16) << split) | low, Some(first as u16))
+    };
+
+    if merged < 1 << 28 {
+        let header = (merged << 4) | split;
+        words.extend([(header >> 16) as u16, header as u16]);
+    } else {
+        let header = ((merged >> 16) << 4) | split;
+        words.extend([(header >> 16) as u16, header as u16, merged as u16]);
+    }
+    words.extend(first_refill);
+    words.push(second as u16);
+    words
+}
+
+/// Encode parsed ops as a compressed quantum
+fn encode_quantum(ops: &[Op]) -> Vec<u8> {
+    let steps = ops.iter().filter(|op| !matches!(op, Op::Word(_))).count();
+    let mut states = [RANS_THRESHOLD; 2];
+    let mut words: Vec<u16> = Vec::new();
+
+    let mut step = steps;
+    for op in ops.iter().rev() {
+        match *op {
+            Op::Word(word) => words.push(word),
+            Op::Bits { value, count } => {
+                step -= 1;
+                encode_step(&mut states[step % 2], value, 1, count, &mut words);
+            }
+            Op::Symbol { cumul, freq } => {
+                step -= 1;
+                encode_step(&mut states[step % 2], cumul, freq, 15, &mut words);
+            }
+        }
+    }
+
+    let header = initial_state_words(states[0], states[1]);
+    header
+        .iter()
+        .chain(words.iter().rev())
+        .flat_map(|w| w.to_le_bytes())
+        .collect()
+}
+
+/// Compress data with Granny2 `BitKnit` (format 4)
+///
+/// The output decompresses with the same routine used to read GR2 files.
+#[must_use]
+pub fn compress_bitknit(data: &[u8]) -> Vec<u8> {
+    let mut output = BITKNIT_MAGIC.to_le_bytes().to_vec();
+    let mut state = EncoderState::new();
+    let mut finder = MatchFinder::new(data.len());
+
+    let mut start = 0;
+    while start < data.len() {
+        let end = ((start & !(QUANTUM_SIZE - 1)) + QUANTUM_SIZE).min(data.len());
+
+        let snapshot = state.clone();
+        let ops = parse_quantum(data, start, end, &mut state, &mut finder);
+        let encoded = encode_quantum(&ops);
+
+        if encoded.len() < end - start + 2 {
+            output.extend_from_slice(&encoded);
+        } else {
+            // Raw quantum: the decoder leaves every model untouched
+            state = snapshot;
+            output.extend_from_slice(&[0, 0]);
+            output.extend_from_slice(&data[start..end]);
+        }
+        start = end;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decompress::decompress_bitknit;
+    use super::*;
+
+    #[test]
+    fn test_bitknit_roundtrip() {
+        let mut data = Vec::new();
+        for i in 0u32..50_000 {
+            // Vertex-like records: repeated structure with slowly changing values
+            data.extend_from_slice(&(i / 7).to_le_bytes());
+            data.extend_from_slice(&[0x3F, 0x80, (i % 13) as u8, 0]);
+        }
+        let mut noise = 0x1234_5678u32;
+        for _ in 0..70_000 {
+            noise ^= noise << 13;
+            noise ^= noise >> 17;
+            noise ^= noise << 5;
+            data.push(noise as u8);
+        }
+
+        for input in [&data[..], &data[..1], &data[..3], &[][..]] {
+            let compressed = compress_bitknit(input);
+            let restored = decompress_bitknit(&compressed, input.len()).unwrap();
+            assert_eq!(restored, input);
+        }
+        assert!(compress_bitknit(&data[..400_000]).len() < 200_000);
+    }
+}
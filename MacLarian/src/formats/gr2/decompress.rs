@@ -15,20 +15,21 @@ use crate::error::{Error, Result};
 // ============================================================================
 
 /// `BitKnit` magic number (little-endian)
-const BITKNIT_MAGIC: u16 = 0x75b1;
+pub(super) const BITKNIT_MAGIC: u16 = 0x75b1;
 
 /// rANS normalization threshold
-const RANS_THRESHOLD: u32 = 0x10000;
+pub(super) const RANS_THRESHOLD: u32 = 0x10000;
 
 /// Quantum size (64KB)
-const QUANTUM_SIZE: usize = 0x10000;
+pub(super) const QUANTUM_SIZE: usize = 0x10000;
 
 // ============================================================================
 // Frequency Table
 // ============================================================================
 
-struct FrequencyTable {
-    frequency_bits: usize,
+#[derive(Clone)]
+pub(super) struct FrequencyTable {
+    pub(super) frequency_bits: usize,
     vocab_size: usize,
     lookup_shift: usize,
     sums: Vec<u16>,
@@ -73,11 +74,11 @@ impl FrequencyTable {
         }
     }
 
-    fn frequency(&self, sym: usize) -> u16 {
+    pub(super) fn frequency(&self, sym: usize) -> u16 {
         self.sums[sym + 1] - self.sums[sym]
     }
 
-    fn sum_below(&self, sym: usize) -> u16 {
+    pub(super) fn sum_below(&self, sym: usize) -> u16 {
         self.sums[sym]
     }
 }
@@ -86,11 +87,12 @@ impl FrequencyTable {
 // Deferred Adaptive Model
 // ============================================================================
 
-struct DeferredAdaptiveModel {
+#[derive(Clone)]
+pub(super) struct DeferredAdaptiveModel {
     adaptation_interval: usize,
     frequency_incr: u16,
     last_frequency_incr: u16,
-    cdf: FrequencyTable,
+    pub(super) cdf: FrequencyTable,
     frequency_accumulator: Vec<u16>,
     adaptation_counter: usize,
 }
@@ -136,7 +138,22 @@ impl DeferredAdaptiveModel {
         }
     }
 
-    fn observe_symbol(&mut self, symbol: usize) {
+    /// Model for literals and copy lengths (one per `index % 4`)
+    pub(super) fn command() -> Self {
+        Self::new(1024, 300, 36, 15, 10)
+    }
+
+    /// Model for copy offset cache references (one per `index % 4`)
+    pub(super) fn cache_reference() -> Self {
+        Self::new(1024, 40, 0, 15, 10)
+    }
+
+    /// Model for the bit length of explicit copy offsets
+    pub(super) fn copy_offset() -> Self {
+        Self::new(1024, 21, 0, 15, 10)
+    }
+
+    pub(super) fn observe_symbol(&mut self, symbol: usize) {
         self.frequency_accumulator[symbol] += self.frequency_incr;
         self.adaptation_counter = (self.adaptation_counter + 1) % self.adaptation_interval;
 
@@ -263,27 +280,28 @@ impl<'a> BitKnitStream<'a> {
 // Register LRU Cache
 // ============================================================================
 
-struct RegisterLRUCache {
-    entries: [u32; 8],
-    entry_order: u32,
+#[derive(Clone)]
+pub(super) struct RegisterLRUCache {
+    pub(super) entries: [u32; 8],
+    pub(super) entry_order: u32,
 }
 
 impl RegisterLRUCache {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         Self {
             entries: [1; 8],
             entry_order: 0x76543210,
         }
     }
 
-    fn insert(&mut self, value: u32) {
+    pub(super) fn insert(&mut self, value: u32) {
         let idx7 = ((self.entry_order >> 28) & 0xF) as usize;
         let idx6 = ((self.entry_order >> 24) & 0xF) as usize;
         self.entries[idx7] = self.entries[idx6];
         self.entries[idx6] = value;
     }
 
-    fn hit(&mut self, index: usize) -> u32 {
+    pub(super) fn hit(&mut self, index: usize) -> u32 {
         let slot = ((self.entry_order >> (index * 4)) & 0xF) as usize;
         // C# uses unchecked arithmetic where overflow wraps. Match that behavior.
         let rotate_mask = 16u32.wrapping_shl((index * 4) as u32).wrapping_sub(1);
@@ -312,19 +330,11 @@ impl Bitknit2State {
         Self {
             output: vec![0u8; expected_size],
             index: 0,
-            command_models: [
-                DeferredAdaptiveModel::new(1024, 300, 36, 15, 10),
-                DeferredAdaptiveModel::new(1024, 300, 36, 15, 10),
-                DeferredAdaptiveModel::new(1024, 300, 36, 15, 10),
-                DeferredAdaptiveModel::new(1024, 300, 36, 15, 10),
-            ],
-            cache_reference_models: [
-                DeferredAdaptiveModel::new(1024, 40, 0, 15, 10),
-                DeferredAdaptiveModel::new(1024, 40, 0, 15, 10),
-                DeferredAdaptiveModel::new(1024, 40, 0, 15, 10),
-                DeferredAdaptiveModel::new(1024, 40, 0, 15, 10),
-            ],
-            copy_offset_model: DeferredAdaptiveModel::new(1024, 21, 0, 15, 10),
+            command_models: std::array::from_fn(|_| DeferredAdaptiveModel::command()),
+            cache_reference_models: std::array::from_fn(|_| {
+                DeferredAdaptiveModel::cache_reference()
+            }),
+            copy_offset_model: DeferredAdaptiveModel::copy_offset(),
             copy_offset_cache: RegisterLRUCache::new(),
             delta_offset: 1,
         }
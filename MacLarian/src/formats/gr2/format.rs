@@ -10,7 +10,10 @@
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::io::{Read, Seek, SeekFrom};
 
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, ResultExt};
+
+/// Size of one section header in bytes
+const SECTION_HEADER_SIZE: u64 = 44;
 
 /// Magic signatures for GR2 formats (little-endian only)
 pub mod magic {
@@ -315,24 +318,25 @@ impl Gr2File {
         let mut cursor = std::io::Cursor::new(data);
 
         // Read magic block (offset 0)
-        let magic = Gr2Magic::read(&mut cursor)?;
+        let magic = Gr2Magic::read(&mut cursor).at_offset(0)?;
         if !magic.is_valid() {
-            return Err(Error::DecompressionError(
-                "Invalid GR2 magic signature".to_string(),
-            ));
+            return Err(
+                Error::DecompressionError("Invalid GR2 magic signature".to_string()).at_offset(0),
+            );
         }
 
         // Read main header (offset 0x20)
         cursor.seek(SeekFrom::Start(0x20))?;
-        let header = Gr2Header::read(&mut cursor)?;
+        let header = Gr2Header::read(&mut cursor).at_offset(0x20)?;
 
         // Read section headers
         let section_header_offset = 0x20 + u64::from(header.sections_offset);
         cursor.seek(SeekFrom::Start(section_header_offset))?;
 
         let mut sections = Vec::with_capacity(header.num_sections as usize);
-        for _ in 0..header.num_sections {
-            sections.push(SectionHeader::read(&mut cursor)?);
+        for i in 0..u64::from(header.num_sections) {
+            let offset = section_header_offset + i * SECTION_HEADER_SIZE;
+            sections.push(SectionHeader::read(&mut cursor).at_offset(offset)?);
         }
 
         Ok(Self {
@@ -361,7 +365,10 @@ impl Gr2File {
         let end = start + section.compressed_size as usize;
 
         if end > self.data.len() {
-            return Err(Error::UnexpectedEof);
+            return Err(Error::TruncatedData {
+                offset: start as u64,
+                needed: section.compressed_size as usize,
+            });
         }
 
         Ok(&self.data[start..end])
@@ -375,3 +382,40 @@ impl Gr2File {
         self.magic.pointer_size()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Magic block and a version 6 header followed by `sections`
+    fn gr2_bytes(sections: &[[u32; 11]]) -> Vec<u8> {
+        let mut data = magic::LE64.to_vec();
+        data.resize(0x20, 0);
+        // version, file size, crc, sections offset, section count; refs and tags stay zero
+        let mut header = [0u32; 14];
+        header[0] = 6;
+        header[3] = 56;
+        header[4] = sections.len() as u32;
+        for value in header.iter().chain(sections.iter().flatten()) {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_corrupt_gr2_reports_offset() {
+        let valid = gr2_bytes(&[[0; 11]]);
+        assert!(Gr2File::from_bytes(&valid).is_ok());
+
+        let err = Gr2File::from_bytes(&[0; 0x20]).unwrap_err();
+        assert_eq!(err.offset(), Some(0));
+
+        let err = Gr2File::from_bytes(&valid[..0x30]).unwrap_err();
+        assert_eq!(err.offset(), Some(0x20));
+
+        let mut bad_compression = [0; 11];
+        bad_compression[0] = 99;
+        let err = Gr2File::from_bytes(&gr2_bytes(&[[0; 11], bad_compression])).unwrap_err();
+        assert_eq!(err.offset(), Some(0x20 + 56 + SECTION_HEADER_SIZE));
+    }
+}
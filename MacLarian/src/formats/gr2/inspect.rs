@@ -8,12 +8,12 @@
 
 use super::{Compression, Gr2File, PointerSize};
 use crate::converter::gr2_gltf::to_gltf::Gr2Reader;
-use crate::error::Result;
+use crate::error::{Result, ResultExt};
 use std::path::Path;
 
 /// Information about a GR2 file.
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct Gr2Info {
     /// GR2 format version number.
     pub version: u32,
@@ -29,7 +29,7 @@ pub struct Gr2Info {
 
 /// Information about a GR2 section.
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct SectionInfo {
     /// Section index (0-based).
     pub index: usize,
@@ -48,9 +48,10 @@ pub struct SectionInfo {
 /// # Errors
 /// Returns an error if the file cannot be read or has an invalid format.
 pub fn inspect_gr2<P: AsRef<Path>>(source: P) -> Result<Gr2Info> {
-    let data = std::fs::read(source.as_ref())?;
+    let source = source.as_ref();
+    let data = std::fs::read(source).with_path(source)?;
     let file_size = data.len() as u64;
-    let gr2 = Gr2File::from_bytes(&data)?;
+    let gr2 = Gr2File::from_bytes(&data).with_path(source)?;
 
     let is_64bit = matches!(gr2.pointer_size()?, PointerSize::Bit64);
 
@@ -138,23 +139,26 @@ pub struct Gr2ModelInfo {
 /// Returns an error if the file cannot be read or parsed.
 pub fn extract_gr2_info<P: AsRef<Path>>(source: P) -> Result<Gr2ModelInfo> {
     let source_path = source.as_ref();
-    let data = std::fs::read(source_path)?;
-    let reader = Gr2Reader::new(&data)?;
-
-    let skeleton_info = reader.parse_skeleton(&data)?.map(|skel| Gr2SkeletonInfo {
-        name: skel.name,
-        bone_count: skel.bones.len(),
-        bones: skel
-            .bones
-            .iter()
-            .map(|b| Gr2BoneInfo {
-                name: b.name.clone(),
-                parent_index: b.parent_index,
-            })
-            .collect(),
-    });
-
-    let meshes = reader.parse_meshes(&data)?;
+    let data = std::fs::read(source_path).with_path(source_path)?;
+    let reader = Gr2Reader::new(&data).with_path(source_path)?;
+
+    let skeleton_info = reader
+        .parse_skeleton(&data)
+        .with_path(source_path)?
+        .map(|skel| Gr2SkeletonInfo {
+            name: skel.name,
+            bone_count: skel.bones.len(),
+            bones: skel
+                .bones
+                .iter()
+                .map(|b| Gr2BoneInfo {
+                    name: b.name.clone(),
+                    parent_index: b.parent_index,
+                })
+                .collect(),
+        });
+
+    let meshes = reader.parse_meshes(&data).with_path(source_path)?;
     let mesh_infos: Vec<Gr2MeshInfo> = meshes
         .iter()
         .map(|m| Gr2MeshInfo {
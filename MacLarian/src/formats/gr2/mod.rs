@@ -1,12 +1,11 @@
 //! GR2 (Granny2) file format support
 //!
-//! This module provides parsing and decompression for Granny2 GR2 files
-//! used by Baldur's Gate 3 and Divinity: Original Sin 2.
-//!
-//!
+//! This module provides parsing, decompression and `BitKnit` compression for
+//! Granny2 GR2 files used by Baldur's Gate 3 and Divinity: Original Sin 2.
 
 #![allow(clippy::cast_possible_truncation)]
 
+mod compress;
 mod decompress;
 mod format;
 mod inspect;
@@ -20,13 +19,16 @@ pub(crate) use decompress::decompress_bitknit as bitknit_decompress;
 pub(crate) use format::PointerSize;
 pub(crate) use format::magic;
 
+// BitKnit section compression (used by the GR2 writer)
+pub use compress::compress_bitknit;
+
 // Public inspection API
 pub use inspect::{
     Gr2BoneInfo, Gr2Info, Gr2MeshInfo, Gr2ModelInfo, Gr2SkeletonInfo, SectionInfo,
     extract_gr2_info, inspect_gr2,
 };
 
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, ResultExt};
 
 /// Decompress a GR2 file and return the decompressed section data.
 ///
@@ -59,7 +61,8 @@ pub fn decompress_gr2(data: &[u8]) -> Result<Vec<u8>> {
         let decompressed = match section.compression {
             Compression::None => compressed.to_vec(),
             Compression::BitKnit => {
-                decompress_bitknit(compressed, section.uncompressed_size as usize)?
+                decompress_bitknit(compressed, section.uncompressed_size as usize)
+                    .at_offset(u64::from(section.offset_in_file))?
             }
             Compression::Oodle0 | Compression::Oodle1 => {
                 let compression = section.compression;
@@ -0,0 +1,139 @@
+//! Language names used for `Localization/<language>` folders
+//!
+//! The game names its localization folders and PAKs after the language in
+//! English (`German`, `BrazilianPortuguese`), while users and other tools
+//! often use ISO codes (`de`, `pt-BR`). [`normalize_language`] maps either
+//! form to the folder name.
+
+/// Languages the game ships, as named in its `Localization` folder
+pub const LANGUAGES: [&str; 15] = [
+    "English",
+    "French",
+    "German",
+    "Italian",
+    "Polish",
+    "Russian",
+    "Spanish",
+    "LatinSpanish",
+    "BrazilianPortuguese",
+    "Chinese",
+    "ChineseTraditional",
+    "Japanese",
+    "Korean",
+    "Turkish",
+    "Ukrainian",
+];
+
+/// Other names for each language, lowercased with separators removed
+const ALIASES: [(&str, &[&str]); 15] = [
+    ("English", &["en", "enus", "engb", "eng"]),
+    ("French", &["fr", "frfr", "fra", "fre"]),
+    ("German", &["de", "dede", "deu", "ger"]),
+    ("Italian", &["it", "itit", "ita"]),
+    ("Polish", &["pl", "plpl", "pol"]),
+    ("Russian", &["ru", "ruru", "rus"]),
+    ("Spanish", &["es", "eses", "spa"]),
+    (
+        "LatinSpanish",
+        &["es419", "esmx", "latam", "mexicanspanish"],
+    ),
+    (
+        "BrazilianPortuguese",
+        &["pt", "ptbr", "por", "portuguese", "brazilian"],
+    ),
+    (
+        "Chinese",
+        &[
+            "zh",
+            "zhcn",
+            "zhhans",
+            "chs",
+            "simplifiedchinese",
+            "chinesesimplified",
+        ],
+    ),
+    (
+        "ChineseTraditional",
+        &["zhtw", "zhhant", "cht", "traditionalchinese"],
+    ),
+    ("Japanese", &["ja", "jajp", "jpn", "jp"]),
+    ("Korean", &["ko", "kokr", "kor", "kr"]),
+    ("Turkish", &["tr", "trtr", "tur"]),
+    ("Ukrainian", &["uk", "ukua", "ukr"]),
+];
+
+/// The game's folder name for a language name or code
+///
+/// Case, spaces, `-` and `_` are ignored, so `German`, `de`, `de-DE` and
+/// `latin_spanish` are all recognized. Returns `None` for unknown names.
+#[must_use]
+pub fn normalize_language(name: &str) -> Option<&'static str> {
+    let key: String = name
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect();
+    ALIASES
+        .iter()
+        .find(|(language, aliases)| {
+            language.eq_ignore_ascii_case(&key) || aliases.contains(&key.as_str())
+        })
+        .map(|(language, _)| *language)
+}
+
+/// The language a localization file belongs to, from its path
+///
+/// Looks for a folder or file stem named after one of [`LANGUAGES`], e.g.
+/// `Localization/German/german.loca`. Codes aren't matched here, since short
+/// folder names like `it` are too likely to mean something else.
+#[must_use]
+pub fn language_of_path(path: &str) -> Option<&'static str> {
+    let path = path.replace('\\', "/");
+    let (dirs, file) = path.rsplit_once('/').unwrap_or(("", &path));
+    let stem = file.split_once('.').map_or(file, |(stem, _)| stem);
+    dirs.split('/').chain([stem]).find_map(|part| {
+        LANGUAGES
+            .iter()
+            .find(|language| language.eq_ignore_ascii_case(part))
+            .copied()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("German"), Some("German"));
+        assert_eq!(normalize_language("german"), Some("German"));
+        assert_eq!(normalize_language("de"), Some("German"));
+        assert_eq!(normalize_language("de-DE"), Some("German"));
+        assert_eq!(normalize_language("pt_BR"), Some("BrazilianPortuguese"));
+        assert_eq!(normalize_language("Latin Spanish"), Some("LatinSpanish"));
+        assert_eq!(normalize_language("es-419"), Some("LatinSpanish"));
+        assert_eq!(normalize_language("zh-Hant"), Some("ChineseTraditional"));
+        assert_eq!(normalize_language("Klingon"), None);
+        for (language, _) in ALIASES {
+            assert!(LANGUAGES.contains(&language));
+            assert_eq!(normalize_language(language), Some(language));
+        }
+    }
+
+    #[test]
+    fn test_language_of_path() {
+        assert_eq!(
+            language_of_path("Localization/German/german.loca"),
+            Some("German")
+        );
+        assert_eq!(
+            language_of_path("Mods\\MyMod\\Localization\\LatinSpanish\\MyMod.loca"),
+            Some("LatinSpanish")
+        );
+        assert_eq!(
+            language_of_path("Localization/english.loca"),
+            Some("English")
+        );
+        assert_eq!(language_of_path("Localization/it/strings.loca"), None);
+    }
+}
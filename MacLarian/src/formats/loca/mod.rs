@@ -4,13 +4,22 @@
 //!
 //! Binary format for Baldur's Gate 3 localization strings.
 //! Use `read_loca` / `write_loca` to read and write files,
-//! or convert to XML for editing.
+//! or convert to XML for editing. `LocaResolver` looks up handles
+//! across every loca file of a language, and `LocaLibrary` across any
+//! language of a set of PAKs.
 
+mod language;
 mod reader;
+mod resolver;
 mod writer;
 
+pub use language::{LANGUAGES, language_of_path, normalize_language};
 pub use reader::{parse_loca_bytes, read_loca};
-pub use writer::write_loca;
+pub use resolver::{
+    DEFAULT_LANGUAGE, LocaCache, LocaLibrary, LocaResolver, ResolvedText, normalize_handle,
+};
+pub use writer::{LocaVersion, serialize_loca, write_loca};
+pub use writer::{serialize_loca_with_version, write_loca_with_version};
 
 /// "LOCA" magic signature (little-endian)
 pub const LOCA_SIGNATURE: u32 = 0x41434F4C;
@@ -18,6 +27,9 @@ pub const LOCA_SIGNATURE: u32 = 0x41434F4C;
 /// Size of each entry in the entry table (64 + 2 + 4 = 70 bytes)
 pub const ENTRY_SIZE: usize = 70;
 
+/// Size of each entry in files without per-entry versions (64 + 4 = 68 bytes)
+pub const LEGACY_ENTRY_SIZE: usize = 68;
+
 /// Size of the key field in each entry
 pub const KEY_SIZE: usize = 64;
 
@@ -40,3 +52,19 @@ pub struct LocaResource {
     /// The localized text entries in this resource.
     pub entries: Vec<LocalizedText>,
 }
+
+impl LocalizedText {
+    /// Creates a new localized text entry.
+    #[must_use]
+    pub fn new(key: String, version: u16, text: String) -> Self {
+        LocalizedText { key, version, text }
+    }
+}
+
+impl LocaResource {
+    /// Creates a resource holding the given entries.
+    #[must_use]
+    pub fn new(entries: Vec<LocalizedText>) -> Self {
+        LocaResource { entries }
+    }
+}
@@ -4,10 +4,10 @@
 
 #![allow(clippy::cast_possible_truncation)]
 
-use super::{KEY_SIZE, LOCA_SIGNATURE, LocaResource, LocalizedText};
-use crate::error::{Error, Result};
+use super::{KEY_SIZE, LEGACY_ENTRY_SIZE, LOCA_SIGNATURE, LocaResource, LocalizedText};
+use crate::error::{Error, Result, ResultExt};
+use crate::utils::open_file;
 use byteorder::{LittleEndian, ReadBytesExt};
-use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 
@@ -15,16 +15,19 @@ use std::path::Path;
 ///
 /// # Errors
 ///
+/// Returns [`Error::FileNotFound`] if the file doesn't exist.
 /// Returns [`Error::Io`] if the file cannot be opened or read.
 /// Returns [`Error::InvalidLocaMagic`] if the file does not have a valid LOCA header.
 ///
+/// [`Error::FileNotFound`]: crate::Error::FileNotFound
 /// [`Error::Io`]: crate::Error::Io
 /// [`Error::InvalidLocaMagic`]: crate::Error::InvalidLocaMagic
 pub fn read_loca<P: AsRef<Path>>(path: P) -> Result<LocaResource> {
-    let mut file = File::open(path)?;
+    let path = path.as_ref();
+    let mut file = open_file(path)?;
     let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-    parse_loca_bytes(&buffer)
+    file.read_to_end(&mut buffer).with_path(path)?;
+    parse_loca_bytes(&buffer).with_path(path)
 }
 
 /// Parse .loca data from bytes
@@ -41,34 +44,41 @@ pub fn parse_loca_bytes(data: &[u8]) -> Result<LocaResource> {
 
     // Read header (12 bytes)
     let mut magic = [0u8; 4];
-    cursor.read_exact(&mut magic)?;
+    cursor.read_exact(&mut magic).at_offset(0)?;
 
     let signature = u32::from_le_bytes(magic);
     if signature != LOCA_SIGNATURE {
-        return Err(Error::InvalidLocaMagic(magic));
+        return Err(Error::InvalidLocaMagic(magic).at_offset(0));
     }
 
-    let num_entries = cursor.read_u32::<LittleEndian>()? as usize;
-    let texts_offset = u64::from(cursor.read_u32::<LittleEndian>()?);
+    let num_entries = cursor.read_u32::<LittleEndian>().at_offset(4)? as usize;
+    let texts_offset = u64::from(cursor.read_u32::<LittleEndian>().at_offset(8)?);
+    let versioned = has_entry_versions(num_entries, texts_offset);
 
     // Read entry metadata
     let mut entries = Vec::with_capacity(num_entries);
     let mut entry_metadata = Vec::with_capacity(num_entries);
 
     for _ in 0..num_entries {
+        let entry_offset = cursor.position();
+
         // Key: 64 bytes UTF-8 null-padded
         let mut key_bytes = [0u8; KEY_SIZE];
-        cursor.read_exact(&mut key_bytes)?;
+        cursor.read_exact(&mut key_bytes).at_offset(entry_offset)?;
 
         // Find null terminator and extract key string
         let key_len = key_bytes.iter().position(|&b| b == 0).unwrap_or(KEY_SIZE);
         let key = String::from_utf8_lossy(&key_bytes[..key_len]).into_owned();
 
-        // Version: u16
-        let version = cursor.read_u16::<LittleEndian>()?;
+        // Version: u16, missing from legacy entries
+        let version = if versioned {
+            cursor.read_u16::<LittleEndian>().at_offset(entry_offset)?
+        } else {
+            0
+        };
 
         // Length: u32 (includes null terminator)
-        let length = cursor.read_u32::<LittleEndian>()? as usize;
+        let length = cursor.read_u32::<LittleEndian>().at_offset(entry_offset)? as usize;
 
         entry_metadata.push((key, version, length));
     }
@@ -79,13 +89,15 @@ pub fn parse_loca_bytes(data: &[u8]) -> Result<LocaResource> {
     // Read text data for each entry
     for (key, version, length) in entry_metadata {
         if length > 0 {
+            let text_offset = cursor.position();
+
             // Read text bytes (length - 1 for actual text, 1 for null terminator)
             let text_len = length.saturating_sub(1);
             let mut text_bytes = vec![0u8; text_len];
-            cursor.read_exact(&mut text_bytes)?;
+            cursor.read_exact(&mut text_bytes).at_offset(text_offset)?;
 
             // Skip the null terminator
-            cursor.read_u8()?;
+            cursor.read_u8().at_offset(text_offset)?;
 
             let text = String::from_utf8_lossy(&text_bytes).into_owned();
             entries.push(LocalizedText { key, version, text });
@@ -101,3 +113,37 @@ pub fn parse_loca_bytes(data: &[u8]) -> Result<LocaResource> {
 
     Ok(LocaResource { entries })
 }
+
+/// Whether entries carry a version field, judged by where the texts start
+///
+/// Texts follow the entry table directly, so its size gives the entry size.
+/// Anything that isn't exactly the legacy layout is read as the current one.
+fn has_entry_versions(num_entries: usize, texts_offset: u64) -> bool {
+    let table_size = texts_offset.saturating_sub(12);
+    num_entries == 0 || table_size != (num_entries * LEGACY_ENTRY_SIZE) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::loca::{ENTRY_SIZE, serialize_loca};
+
+    #[test]
+    fn test_corrupt_loca_reports_offset() {
+        let resource = LocaResource::new(vec![
+            LocalizedText::new("h1".to_string(), 1, "Hello".to_string()),
+            LocalizedText::new("h2".to_string(), 1, "World".to_string()),
+        ]);
+        let data = serialize_loca(&resource).unwrap();
+
+        let mut bad_magic = data.clone();
+        bad_magic[0] = b'X';
+        let err = parse_loca_bytes(&bad_magic).unwrap_err();
+        assert!(matches!(err.root(), Error::InvalidLocaMagic(_)));
+        assert_eq!(err.offset(), Some(0));
+
+        // Second text starts after the header, two entries and "Hello\0"
+        let err = parse_loca_bytes(&data[..data.len() - 2]).unwrap_err();
+        assert_eq!(err.offset(), Some((12 + 2 * ENTRY_SIZE + 6) as u64));
+    }
+}
@@ -0,0 +1,561 @@
+//! Handle -> text lookup across `.loca` files
+//!
+//! [`LocaResolver`] collects the entries of every `.loca` file for one
+//! language, read from disk or from PAKs, and resolves `TranslatedString`
+//! handles to their text. [`LocaCache`] persists where each handle was found
+//! (file and text offset) so later lookups only decompress the files they
+//! need instead of decoding every loca file again. [`LocaLibrary`] holds a
+//! resolver per language over the same PAKs, loading each one when a handle
+//! is first looked up in that language.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use super::language::{language_of_path, normalize_language};
+use super::{ENTRY_SIZE, KEY_SIZE, LocaResource, parse_loca_bytes};
+use crate::error::{Error, Result};
+use crate::pak::{PakOperations, VfsView};
+
+/// Language used when none is given
+pub const DEFAULT_LANGUAGE: &str = "English";
+
+/// A resolved handle
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedText {
+    /// Handle as stored in the loca file
+    pub handle: String,
+    /// Entry version
+    pub version: u16,
+    /// Localized text
+    pub text: String,
+    /// PAK the defining file was read from, if any
+    pub pak: Option<PathBuf>,
+    /// Defining file (path inside `pak`, or on disk)
+    pub file: String,
+    /// Byte offset of the text in the decompressed file
+    pub offset: u64,
+}
+
+impl ResolvedText {
+    /// `pak:file`, or just `file` when it wasn't read from a PAK
+    #[must_use]
+    pub fn source(&self) -> String {
+        match &self.pak {
+            Some(pak) => format!("{}:{}", pak.display(), self.file),
+            None => self.file.clone(),
+        }
+    }
+}
+
+/// Resolves `TranslatedString` handles for one language
+#[derive(Debug, Clone)]
+pub struct LocaResolver {
+    language: String,
+    entries: HashMap<String, ResolvedText>,
+    files: usize,
+}
+
+impl LocaResolver {
+    /// Create an empty resolver for `language` (e.g. `English`)
+    ///
+    /// Codes such as `de` are mapped to the game's folder name (see
+    /// [`normalize_language`]); unknown names are kept as given.
+    #[must_use]
+    pub fn new(language: &str) -> Self {
+        Self {
+            language: normalize_language(language).unwrap_or(language).to_string(),
+            entries: HashMap::new(),
+            files: 0,
+        }
+    }
+
+    /// Language this resolver reads
+    #[must_use]
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Number of handles known
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no handles are known
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of loca files added
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.files
+    }
+
+    /// Whether `path` is a `.loca` file for this resolver's language
+    ///
+    /// Matches any path with a directory or file stem named after the
+    /// language, e.g. `Localization/English/english.loca`.
+    #[must_use]
+    pub fn is_language_file(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        let Some(stem) = path
+            .strip_suffix(".loca")
+            .or_else(|| path.strip_suffix(".LOCA"))
+        else {
+            return false;
+        };
+        stem.split('/')
+            .any(|part| part.eq_ignore_ascii_case(&self.language))
+    }
+
+    /// Add a parsed resource; later entries replace earlier ones
+    pub fn add_resource(&mut self, resource: LocaResource, pak: Option<&Path>, file: &str) {
+        self.files += 1;
+        for entry in resource.entries {
+            let resolved = ResolvedText {
+                handle: entry.key,
+                version: entry.version,
+                text: entry.text,
+                pak: pak.map(Path::to_path_buf),
+                file: file.to_string(),
+                offset: 0,
+            };
+            self.entries
+                .insert(normalize_handle(&resolved.handle), resolved);
+        }
+    }
+
+    /// Parse and add a `.loca` file's contents, recording text offsets
+    ///
+    /// # Errors
+    /// Returns an error if the data is not a valid loca file.
+    pub fn add_bytes(&mut self, data: &[u8], pak: Option<&Path>, file: &str) -> Result<()> {
+        let resource = parse_loca_bytes(data)?;
+        let offsets = text_offsets(data).unwrap_or_default();
+        let keys: Vec<String> = resource.entries.iter().map(|e| e.key.clone()).collect();
+        self.add_resource(resource, pak, file);
+        for (key, offset) in keys.iter().zip(offsets) {
+            if let Some(entry) = self.entries.get_mut(&normalize_handle(key)) {
+                entry.offset = offset;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add every loca file for this language in a PAK
+    ///
+    /// Returns the number of files added.
+    ///
+    /// # Errors
+    /// Returns an error if the PAK or one of its loca files cannot be read.
+    pub fn add_pak(&mut self, pak: &Path) -> Result<usize> {
+        let files: Vec<String> = PakOperations::list(pak)?
+            .into_iter()
+            .filter(|f| self.is_language_file(f))
+            .collect();
+        let contents = PakOperations::read_files_bytes(pak, &files)?;
+        for file in &files {
+            if let Some(data) = contents.get(file) {
+                self.add_bytes(data, Some(pak), file)?;
+            }
+        }
+        Ok(files.len())
+    }
+
+    /// Add every loca file for this language visible through a [`VfsView`]
+    ///
+    /// Only the highest-priority copy of each file is read, so text from
+    /// later PAKs overrides earlier ones the way the game loads it. Returns
+    /// the number of files added.
+    ///
+    /// # Errors
+    /// Returns an error if one of the loca files cannot be read.
+    pub fn add_vfs(&mut self, vfs: &VfsView) -> Result<usize> {
+        let mut files: Vec<String> = vfs
+            .list("")
+            .into_iter()
+            .filter(|f| self.is_language_file(f))
+            .collect();
+        // Add in load order so higher-priority handles replace earlier ones
+        files.sort_by_key(|f| vfs.priority_of(f));
+        let paths: Vec<&str> = files.iter().map(String::as_str).collect();
+        let contents = vfs.read_many(&paths)?;
+        for file in &files {
+            if let Some(data) = contents.get(file) {
+                self.add_bytes(data, vfs.source_of(file), file)?;
+            }
+        }
+        Ok(files.len())
+    }
+
+    /// Look up a handle (case-insensitive, `;version` suffix ignored)
+    #[must_use]
+    pub fn resolve(&self, handle: &str) -> Option<&ResolvedText> {
+        self.entries.get(&normalize_handle(handle))
+    }
+}
+
+/// Handle lookup in any language of a set of PAKs
+///
+/// Only the PAKs' file tables are read up front. A language's loca files are
+/// read the first time it's asked for, and kept for later lookups.
+pub struct LocaLibrary {
+    vfs: VfsView,
+    /// Lowercased language -> its loaded resolver
+    resolvers: HashMap<String, LocaResolver>,
+}
+
+impl LocaLibrary {
+    /// Index the file tables of `paks_in_load_order`
+    ///
+    /// # Errors
+    /// Returns an error if any PAK's file table cannot be read.
+    pub fn new<P: AsRef<Path>>(paks_in_load_order: &[P]) -> Result<Self> {
+        Ok(Self::from_vfs(VfsView::new(paks_in_load_order)?))
+    }
+
+    /// Resolve handles through an existing [`VfsView`]
+    #[must_use]
+    pub fn from_vfs(vfs: VfsView) -> Self {
+        Self {
+            vfs,
+            resolvers: HashMap::new(),
+        }
+    }
+
+    /// Known languages with at least one loca file, sorted
+    #[must_use]
+    pub fn languages(&self) -> Vec<&'static str> {
+        let mut languages: Vec<&'static str> = self
+            .vfs
+            .list("")
+            .iter()
+            .filter(|f| f.to_ascii_lowercase().ends_with(".loca"))
+            .filter_map(|f| language_of_path(f))
+            .collect();
+        languages.sort_unstable();
+        languages.dedup();
+        languages
+    }
+
+    /// Whether `language` has been loaded yet
+    #[must_use]
+    pub fn is_loaded(&self, language: &str) -> bool {
+        self.resolvers.contains_key(&language_key(language))
+    }
+
+    /// The resolver for `language`, reading its loca files on first use
+    ///
+    /// # Errors
+    /// Returns an error if one of the language's loca files cannot be read.
+    pub fn resolver(&mut self, language: &str) -> Result<&LocaResolver> {
+        let key = language_key(language);
+        if !self.resolvers.contains_key(&key) {
+            let mut resolver = LocaResolver::new(language);
+            resolver.add_vfs(&self.vfs)?;
+            self.resolvers.insert(key.clone(), resolver);
+        }
+        Ok(&self.resolvers[&key])
+    }
+
+    /// Look up a handle in `language`
+    ///
+    /// # Errors
+    /// Returns an error if the language's loca files cannot be read.
+    pub fn resolve(&mut self, language: &str, handle: &str) -> Result<Option<&ResolvedText>> {
+        Ok(self.resolver(language)?.resolve(handle))
+    }
+}
+
+/// [`LocaLibrary`] key for a language name or code
+fn language_key(language: &str) -> String {
+    normalize_language(language)
+        .unwrap_or(language)
+        .to_ascii_lowercase()
+}
+
+/// A PAK recorded in a [`LocaCache`], with the stamp used to detect changes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedPak {
+    path: PathBuf,
+    size: u64,
+    modified: u64,
+}
+
+impl CachedPak {
+    fn stamp(path: &Path) -> Result<Self> {
+        let meta = std::fs::metadata(path)?;
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+        Ok(Self {
+            path: path.to_path_buf(),
+            size: meta.len(),
+            modified,
+        })
+    }
+}
+
+/// On-disk index of handle -> (file, offset) for a set of PAKs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LocaCache {
+    language: String,
+    paks: Vec<CachedPak>,
+    /// `(pak index, path inside the PAK)`
+    files: Vec<(usize, String)>,
+    /// Normalized handle -> `(file index, version, text offset, text length)`
+    handles: BTreeMap<String, (usize, u16, u64, usize)>,
+}
+
+impl LocaCache {
+    /// Record where every PAK-sourced handle in `resolver` lives
+    ///
+    /// # Errors
+    /// Returns an error if a PAK's metadata cannot be read.
+    pub fn from_resolver(resolver: &LocaResolver, paks: &[PathBuf]) -> Result<Self> {
+        let mut cache = Self {
+            language: resolver.language.clone(),
+            paks: paks
+                .iter()
+                .map(|p| CachedPak::stamp(p))
+                .collect::<Result<_>>()?,
+            ..Self::default()
+        };
+        let mut file_ids: HashMap<(usize, &str), usize> = HashMap::new();
+        for (handle, entry) in &resolver.entries {
+            let Some(pak) = entry
+                .pak
+                .as_ref()
+                .and_then(|p| paks.iter().position(|q| q == p))
+            else {
+                continue;
+            };
+            let file = *file_ids.entry((pak, &entry.file)).or_insert_with(|| {
+                cache.files.push((pak, entry.file.clone()));
+                cache.files.len() - 1
+            });
+            cache.handles.insert(
+                handle.clone(),
+                (file, entry.version, entry.offset, entry.text.len()),
+            );
+        }
+        Ok(cache)
+    }
+
+    /// Load a cache file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data)
+            .map_err(|e| Error::InvalidFormat(format!("{}: {e}", path.display())))
+    }
+
+    /// Write the cache file, creating its directory
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_vec(self)
+            .map_err(|e| Error::InvalidFormat(format!("{}: {e}", path.display())))?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Whether the cache was built from exactly these PAKs, unchanged, for `language`
+    #[must_use]
+    pub fn is_fresh(&self, language: &str, paks: &[PathBuf]) -> bool {
+        self.language.eq_ignore_ascii_case(language)
+            && self.paks.len() == paks.len()
+            && self
+                .paks
+                .iter()
+                .zip(paks)
+                .all(|(cached, path)| CachedPak::stamp(path).is_ok_and(|now| now == *cached))
+    }
+
+    /// Resolve handles, reading each needed loca file once
+    ///
+    /// Returns one entry per handle, `None` for handles not in the cache.
+    ///
+    /// # Errors
+    /// Returns an error if a recorded file can no longer be read.
+    pub fn resolve_many(&self, handles: &[String]) -> Result<Vec<Option<ResolvedText>>> {
+        let mut by_file: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (i, handle) in handles.iter().enumerate() {
+            if let Some((file, ..)) = self.handles.get(&normalize_handle(handle)) {
+                by_file.entry(*file).or_default().push(i);
+            }
+        }
+
+        let mut results = vec![None; handles.len()];
+        for (file, indices) in by_file {
+            let (pak, path) = &self.files[file];
+            let pak = &self.paks[*pak].path;
+            let data = PakOperations::read_file_bytes(pak, path)?;
+            for i in indices {
+                let key = normalize_handle(&handles[i]);
+                let (_, version, offset, len) = self.handles[&key];
+                let start = (offset as usize).min(data.len());
+                let end = (start + len).min(data.len());
+                results[i] = Some(ResolvedText {
+                    handle: key,
+                    version,
+                    text: String::from_utf8_lossy(&data[start..end]).into_owned(),
+                    pak: Some(pak.clone()),
+                    file: path.clone(),
+                    offset,
+                });
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Lowercased handle without a trailing `;version`
+#[must_use]
+pub fn normalize_handle(handle: &str) -> String {
+    let handle = handle.trim();
+    let handle = handle.split_once(';').map_or(handle, |(h, _)| h);
+    handle.to_ascii_lowercase()
+}
+
+/// Offset of each entry's text within a loca file, in entry order
+fn text_offsets(data: &[u8]) -> Option<Vec<u64>> {
+    let read_u32 = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| u64::from(u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+    };
+    let count = read_u32(4)? as usize;
+    let mut offset = read_u32(8)?;
+    let mut offsets = Vec::with_capacity(count);
+    for i in 0..count {
+        offsets.push(offset);
+        offset += read_u32(12 + i * ENTRY_SIZE + KEY_SIZE + 2)?;
+    }
+    Some(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::loca::{LocalizedText, serialize_loca};
+
+    #[test]
+    fn test_resolve_with_offsets() {
+        let resource = LocaResource::new(vec![
+            LocalizedText::new("h0001g01".to_string(), 1, "Shadowheart".to_string()),
+            LocalizedText::new("h0002g02".to_string(), 3, "Astarion".to_string()),
+        ]);
+        let data = serialize_loca(&resource).unwrap();
+
+        let mut resolver = LocaResolver::new(DEFAULT_LANGUAGE);
+        assert!(resolver.is_language_file("Localization/English/english.loca"));
+        assert!(!resolver.is_language_file("Localization/French/french.loca"));
+        resolver
+            .add_bytes(&data, None, "Localization/English/english.loca")
+            .unwrap();
+
+        let entry = resolver.resolve("H0002G02;3").unwrap();
+        assert_eq!(entry.text, "Astarion");
+        assert_eq!(entry.version, 3);
+        let start = entry.offset as usize;
+        assert_eq!(&data[start..start + 8], b"Astarion");
+        assert!(resolver.resolve("h0003g03").is_none());
+    }
+
+    #[test]
+    fn test_add_vfs_prefers_later_paks() {
+        use crate::compression::CompressionSettings;
+        use crate::pak::PakBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = "Localization/English/english.loca";
+        let mut paks = Vec::new();
+        for (name, text) in [("Base.pak", "Gale"), ("Patch.pak", "Gale of Waterdeep")] {
+            let resource = LocaResource::new(vec![LocalizedText::new(
+                "h0001g01".to_string(),
+                1,
+                text.to_string(),
+            )]);
+            let pak = dir.path().join(name);
+            let mut builder = PakBuilder::write_to(&pak).unwrap();
+            builder
+                .add_file(
+                    file,
+                    &serialize_loca(&resource).unwrap(),
+                    CompressionSettings::default(),
+                )
+                .unwrap();
+            builder.finish().unwrap();
+            paks.push(pak);
+        }
+
+        let vfs = VfsView::new(&paks).unwrap();
+        let mut resolver = LocaResolver::new(DEFAULT_LANGUAGE);
+        assert_eq!(resolver.add_vfs(&vfs).unwrap(), 1);
+        let entry = resolver.resolve("h0001g01").unwrap();
+        assert_eq!(entry.text, "Gale of Waterdeep");
+        assert_eq!(entry.pak.as_deref(), Some(paks[1].as_path()));
+    }
+
+    #[test]
+    fn test_library_loads_languages_lazily() {
+        use crate::compression::CompressionSettings;
+        use crate::pak::PakBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Localization.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        for (file, text) in [
+            ("Localization/English/english.loca", "Sword"),
+            ("Localization/German/german.loca", "Schwert"),
+        ] {
+            let resource = LocaResource::new(vec![LocalizedText::new(
+                "h0001g01".to_string(),
+                1,
+                text.to_string(),
+            )]);
+            builder
+                .add_file(
+                    file,
+                    &serialize_loca(&resource).unwrap(),
+                    CompressionSettings::default(),
+                )
+                .unwrap();
+        }
+        builder.finish().unwrap();
+
+        let mut library = LocaLibrary::new(&[&pak]).unwrap();
+        assert_eq!(library.languages(), ["English", "German"]);
+        assert!(!library.is_loaded("German"));
+
+        let entry = library.resolve("de", "h0001g01").unwrap().unwrap();
+        assert_eq!(entry.text, "Schwert");
+        assert!(library.is_loaded("German"));
+        assert!(!library.is_loaded("English"));
+        assert_eq!(
+            library
+                .resolve("English", "h0001g01")
+                .unwrap()
+                .unwrap()
+                .text,
+            "Sword"
+        );
+        assert!(library.resolve("French", "h0001g01").unwrap().is_none());
+    }
+}
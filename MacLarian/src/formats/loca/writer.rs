@@ -4,7 +4,7 @@
 
 #![allow(clippy::cast_possible_truncation)]
 
-use super::{ENTRY_SIZE, KEY_SIZE, LOCA_SIGNATURE, LocaResource};
+use super::{ENTRY_SIZE, KEY_SIZE, LEGACY_ENTRY_SIZE, LOCA_SIGNATURE, LocaResource};
 use crate::error::Result;
 use byteorder::{LittleEndian, WriteBytesExt};
 use std::fs::File;
@@ -14,16 +14,81 @@ use std::path::Path;
 /// Header size in bytes
 const HEADER_SIZE: u32 = 12;
 
+/// Entry layout to write
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocaVersion {
+    /// 68-byte entries without a version field; entry versions are dropped
+    Legacy,
+    /// 70-byte entries with a `u16` version (default)
+    #[default]
+    Versioned,
+}
+
+impl LocaVersion {
+    /// Every layout that can be written, oldest first
+    pub const ALL: [Self; 2] = [Self::Legacy, Self::Versioned];
+
+    fn entry_size(self) -> usize {
+        match self {
+            Self::Legacy => LEGACY_ENTRY_SIZE,
+            Self::Versioned => ENTRY_SIZE,
+        }
+    }
+}
+
 /// Write a .loca file to disk
 ///
 /// # Errors
 /// Returns an error if file writing fails.
 pub fn write_loca<P: AsRef<Path>>(path: P, resource: &LocaResource) -> Result<()> {
+    write_loca_with_version(path, resource, LocaVersion::default())
+}
+
+/// Write a .loca file to disk with the given entry layout
+///
+/// # Errors
+/// Returns an error if file writing fails.
+pub fn write_loca_with_version<P: AsRef<Path>>(
+    path: P,
+    resource: &LocaResource,
+    version: LocaVersion,
+) -> Result<()> {
     let file = File::create(path)?;
     let mut writer = BufWriter::new(file);
+    write_loca_to(&mut writer, resource, version)?;
+    writer.flush()?;
+    Ok(())
+}
 
+/// Serialize a .loca resource to bytes
+///
+/// # Errors
+/// Returns an error if serialization fails.
+pub fn serialize_loca(resource: &LocaResource) -> Result<Vec<u8>> {
+    serialize_loca_with_version(resource, LocaVersion::default())
+}
+
+/// Serialize a .loca resource to bytes with the given entry layout
+///
+/// # Errors
+/// Returns an error if serialization fails.
+pub fn serialize_loca_with_version(
+    resource: &LocaResource,
+    version: LocaVersion,
+) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    write_loca_to(&mut data, resource, version)?;
+    Ok(data)
+}
+
+fn write_loca_to<W: Write>(
+    writer: &mut W,
+    resource: &LocaResource,
+    version: LocaVersion,
+) -> Result<()> {
     let num_entries = resource.entries.len() as u32;
-    let texts_offset = HEADER_SIZE + (ENTRY_SIZE as u32) * num_entries;
+    let texts_offset = HEADER_SIZE + (version.entry_size() as u32) * num_entries;
 
     // Write header
     writer.write_u32::<LittleEndian>(LOCA_SIGNATURE)?;
@@ -53,7 +118,9 @@ pub fn write_loca<P: AsRef<Path>>(path: P, resource: &LocaResource) -> Result<()
         writer.write_all(&key_buf)?;
 
         // Version: u16
-        writer.write_u16::<LittleEndian>(entry.version)?;
+        if version == LocaVersion::Versioned {
+            writer.write_u16::<LittleEndian>(entry.version)?;
+        }
 
         // Length: u32
         writer.write_u32::<LittleEndian>(length)?;
@@ -67,6 +134,32 @@ pub fn write_loca<P: AsRef<Path>>(path: P, resource: &LocaResource) -> Result<()
         }
     }
 
-    writer.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::loca::parse_loca_bytes;
+
+    #[test]
+    fn test_conformance_fixtures_round_trip() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance/loca");
+        for (name, version) in [
+            ("legacy", LocaVersion::Legacy),
+            ("versioned", LocaVersion::Versioned),
+        ] {
+            let data = std::fs::read(dir.join(format!("{name}.loca"))).unwrap();
+            let resource = parse_loca_bytes(&data).unwrap();
+            let texts: Vec<&str> = resource.entries.iter().map(|e| e.text.as_str()).collect();
+            assert_eq!(texts, ["Hello", "Gale of Waterdeep", ""]);
+            let expected_version = if version == LocaVersion::Legacy { 0 } else { 3 };
+            assert_eq!(resource.entries[1].version, expected_version);
+
+            assert_eq!(
+                serialize_loca_with_version(&resource, version).unwrap(),
+                data
+            );
+        }
+    }
+}
@@ -1,5 +1,394 @@
-//! Binary format variants
-//! These use the same LSF structure with different headers
+//! LSB (legacy binary) format reading
+//!
+//! LSB is the binary resource format that preceded LSF. It is found in
+//! DOS2-era data and in the output of some older tools. Files are read into
+//! an [`LsxDocument`], so they convert to LSX, LSJ and LSF like any other
+//! document. Writing LSB is not supported.
+//!
+//! Based on `LSLib`'s `LSBReader.cs` implementation.
 
-// Re-export LSF functionality
-pub use super::lsf::*;
\ No newline at end of file
+// Binary format parsing requires many intentional casts between integer types
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+
+use super::common::types::{
+    TYPE_BOOL, TYPE_DOUBLE, TYPE_FIXEDSTRING, TYPE_FLOAT, TYPE_FVEC2, TYPE_FVEC3, TYPE_FVEC4,
+    TYPE_GUID, TYPE_INT8, TYPE_INT16, TYPE_INT32, TYPE_INT64, TYPE_IVEC2, TYPE_IVEC3, TYPE_IVEC4,
+    TYPE_LSSTRING, TYPE_LSWSTRING, TYPE_MAT2X2, TYPE_MAT3X3, TYPE_MAT3X4, TYPE_MAT4X3, TYPE_MAT4X4,
+    TYPE_NONE, TYPE_OLD_INT64, TYPE_PATH, TYPE_SCRATCHBUFFER, TYPE_STRING, TYPE_TRANSLATEDSTRING,
+    TYPE_UINT8, TYPE_UINT16, TYPE_UINT32, TYPE_UINT64, TYPE_WSTRING,
+};
+use super::common::{TypeId, extract_value, format_uuid, get_type_name};
+use super::lsx::{LsxAttribute, LsxDocument, LsxNode, LsxRegion};
+use crate::error::{Error, Result, ResultExt};
+use crate::utils::open_file;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Signature of LSB files written by DOS2-era tools (0x40000000)
+const SIGNATURE_LEGACY: [u8; 4] = [0x00, 0x00, 0x00, 0x40];
+/// Signature of LSB files written by later tools
+const SIGNATURE_LSFM: [u8; 4] = *b"LSFM";
+
+/// Deepest node nesting accepted, so corrupt offsets can't recurse forever
+const MAX_DEPTH: usize = 256;
+
+/// Read an LSB file from disk
+///
+/// # Errors
+///
+/// Returns [`Error::FileNotFound`] if the file doesn't exist.
+/// Returns [`Error::InvalidLsbMagic`] if the file does not have an LSB signature.
+/// Returns [`Error::UnsupportedLsbByteOrder`] if the file is big-endian.
+/// Returns [`Error::UnexpectedEof`] if the data is truncated.
+///
+/// [`Error::FileNotFound`]: crate::Error::FileNotFound
+/// [`Error::InvalidLsbMagic`]: crate::Error::InvalidLsbMagic
+/// [`Error::UnsupportedLsbByteOrder`]: crate::Error::UnsupportedLsbByteOrder
+/// [`Error::UnexpectedEof`]: crate::Error::UnexpectedEof
+pub fn read_lsb<P: AsRef<Path>>(path: P) -> Result<LsxDocument> {
+    let path = path.as_ref();
+    let mut file = open_file(path)?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).with_path(path)?;
+    parse_lsb_bytes(&buffer).with_path(path)
+}
+
+/// Parse LSB data from bytes
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidLsbMagic`] if the data does not have an LSB signature.
+/// Returns [`Error::UnsupportedLsbByteOrder`] if the data is big-endian.
+/// Returns [`Error::InvalidStringIndex`] if a name isn't in the string table.
+/// Returns [`Error::InvalidAttributeType`] if an attribute has an unknown type.
+/// Returns [`Error::UnexpectedEof`] if the data is truncated.
+///
+/// [`Error::InvalidLsbMagic`]: crate::Error::InvalidLsbMagic
+/// [`Error::UnsupportedLsbByteOrder`]: crate::Error::UnsupportedLsbByteOrder
+/// [`Error::InvalidStringIndex`]: crate::Error::InvalidStringIndex
+/// [`Error::InvalidAttributeType`]: crate::Error::InvalidAttributeType
+/// [`Error::UnexpectedEof`]: crate::Error::UnexpectedEof
+pub fn parse_lsb_bytes(data: &[u8]) -> Result<LsxDocument> {
+    let mut reader = LsbReader {
+        cursor: Cursor::new(data),
+        strings: HashMap::new(),
+        bswap_guids: false,
+    };
+    reader.read_document()
+}
+
+struct LsbReader<'a> {
+    cursor: Cursor<&'a [u8]>,
+    /// Static string table (index -> string)
+    strings: HashMap<u32, String>,
+    /// Whether GUIDs are written in the byte-swapped BG3 order
+    bswap_guids: bool,
+}
+
+impl LsbReader<'_> {
+    fn read_document(&mut self) -> Result<LsxDocument> {
+        let mut magic = [0u8; 4];
+        self.cursor.read_exact(&mut magic).at_offset(0)?;
+        if magic != SIGNATURE_LEGACY && magic != SIGNATURE_LSFM {
+            return Err(Error::InvalidLsbMagic(magic).at_offset(0));
+        }
+
+        let _total_size = self.cursor.read_u32::<LittleEndian>()?;
+        if self.cursor.read_u32::<LittleEndian>()? != 0 {
+            return Err(Error::UnsupportedLsbByteOrder.at_offset(8));
+        }
+        let _unknown = self.cursor.read_u32::<LittleEndian>()?;
+        let _timestamp = self.cursor.read_u64::<LittleEndian>()?;
+
+        let mut doc = LsxDocument::new(
+            self.cursor.read_u32::<LittleEndian>()?,
+            self.cursor.read_u32::<LittleEndian>()?,
+            self.cursor.read_u32::<LittleEndian>()?,
+            self.cursor.read_u32::<LittleEndian>()?,
+        );
+        // Matches the lslib_meta the LSX writer declares for this version
+        self.bswap_guids = doc.major >= 4;
+
+        self.read_strings()?;
+
+        let region_count = self.cursor.read_u32::<LittleEndian>()?;
+        for _ in 0..region_count {
+            let name = self.read_name()?;
+            let offset = self.cursor.read_u32::<LittleEndian>()?;
+            let resume = self.cursor.position();
+
+            self.cursor.set_position(u64::from(offset));
+            let node = self.read_node(0).at_offset(u64::from(offset))?;
+            self.cursor.seek(SeekFrom::Start(resume))?;
+
+            doc.regions.push(LsxRegion {
+                id: name,
+                nodes: vec![node],
+            });
+        }
+
+        Ok(doc)
+    }
+
+    fn read_strings(&mut self) -> Result<()> {
+        let count = self.cursor.read_u32::<LittleEndian>()?;
+        for _ in 0..count {
+            let value = self.read_string(false)?;
+            let index = self.cursor.read_u32::<LittleEndian>()?;
+            self.strings.insert(index, value);
+        }
+        Ok(())
+    }
+
+    /// Read a string table index and look it up
+    fn read_name(&mut self) -> Result<String> {
+        let index = self.cursor.read_u32::<LittleEndian>()?;
+        self.strings.get(&index).cloned().ok_or_else(|| {
+            Error::InvalidStringIndex(format!(
+                "{index} (string table has {} entries)",
+                self.strings.len()
+            ))
+        })
+    }
+
+    fn read_node(&mut self, depth: usize) -> Result<LsxNode> {
+        if depth > MAX_DEPTH {
+            return Err(Error::InvalidFormat(format!(
+                "LSB nodes nested deeper than {MAX_DEPTH} levels"
+            )));
+        }
+
+        let mut node = LsxNode::new(self.read_name()?);
+        let attribute_count = self.cursor.read_u32::<LittleEndian>()?;
+        let child_count = self.cursor.read_u32::<LittleEndian>()?;
+
+        for _ in 0..attribute_count {
+            let id = self.read_name()?;
+            let type_id = self.cursor.read_u32::<LittleEndian>()?;
+            node.attributes.push(self.read_attribute(id, type_id)?);
+        }
+
+        for _ in 0..child_count {
+            node.children.push(self.read_node(depth + 1)?);
+        }
+
+        Ok(node)
+    }
+
+    fn read_attribute(&mut self, id: String, type_id: TypeId) -> Result<LsxAttribute> {
+        let mut handle = None;
+        let value = match type_id {
+            TYPE_STRING | TYPE_PATH | TYPE_FIXEDSTRING | TYPE_LSSTRING => self.read_string(true)?,
+            TYPE_WSTRING | TYPE_LSWSTRING => self.read_wide_string()?,
+            TYPE_TRANSLATEDSTRING => {
+                let value = self.read_string(true)?;
+                handle = Some(self.read_string(true)?);
+                value
+            }
+            TYPE_SCRATCHBUFFER => {
+                let length = self.cursor.read_u32::<LittleEndian>()? as usize;
+                let bytes = self.read_bytes(length)?;
+                extract_value(&bytes, 0, length, type_id)?
+            }
+            TYPE_GUID if !self.bswap_guids => format_guid(&self.read_bytes(16)?),
+            TYPE_GUID => format_uuid(&self.read_bytes(16)?),
+            _ => {
+                let length = fixed_size(type_id).ok_or(Error::InvalidAttributeType(type_id))?;
+                let bytes = self.read_bytes(length)?;
+                extract_value(&bytes, 0, length, type_id)?
+            }
+        };
+
+        Ok(LsxAttribute {
+            id,
+            type_name: get_type_name(type_id).to_string(),
+            value,
+            handle,
+            version: None,
+        })
+    }
+
+    /// Read a length-prefixed UTF-8 string, dropping stray trailing nulls
+    ///
+    /// The length of a null-terminated string includes its terminator.
+    fn read_string(&mut self, null_terminated: bool) -> Result<String> {
+        let length = self.cursor.read_u32::<LittleEndian>()? as usize;
+        let mut bytes = self.read_bytes(length)?;
+        if null_terminated && bytes.pop().is_some_and(|b| b != 0) {
+            return Err(Error::InvalidFormat(
+                "LSB string is missing its null terminator".to_string(),
+            ));
+        }
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Read a null-terminated UTF-16 string whose length counts characters
+    fn read_wide_string(&mut self) -> Result<String> {
+        let length = self.cursor.read_u32::<LittleEndian>()? as usize;
+        let bytes = self.read_bytes(length.saturating_mul(2))?;
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .take_while(|&unit| unit != 0)
+            .collect();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    /// Read `length` bytes, failing before allocating if the data is shorter
+    fn read_bytes(&mut self, length: usize) -> Result<Vec<u8>> {
+        let remaining = (self.cursor.get_ref().len() as u64).saturating_sub(self.cursor.position());
+        if length as u64 > remaining {
+            return Err(Error::UnexpectedEof.at_offset(self.cursor.position()));
+        }
+        let mut bytes = vec![0u8; length];
+        self.cursor.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// Size in bytes of a fixed-size attribute type
+fn fixed_size(type_id: TypeId) -> Option<usize> {
+    Some(match type_id {
+        TYPE_NONE => 0,
+        TYPE_UINT8 | TYPE_INT8 | TYPE_BOOL => 1,
+        TYPE_INT16 | TYPE_UINT16 => 2,
+        TYPE_INT32 | TYPE_UINT32 | TYPE_FLOAT => 4,
+        TYPE_DOUBLE | TYPE_UINT64 | TYPE_OLD_INT64 | TYPE_INT64 | TYPE_IVEC2 | TYPE_FVEC2 => 8,
+        TYPE_IVEC3 | TYPE_FVEC3 => 12,
+        TYPE_IVEC4 | TYPE_FVEC4 | TYPE_MAT2X2 => 16,
+        TYPE_MAT3X3 => 36,
+        TYPE_MAT3X4 | TYPE_MAT4X3 => 48,
+        TYPE_MAT4X4 => 64,
+        _ => return None,
+    })
+}
+
+/// Format a GUID in the standard (not byte-swapped) Windows order
+fn format_guid(bytes: &[u8]) -> String {
+    let mut swapped = bytes.to_vec();
+    // `format_uuid` swaps each pair of the last eight bytes; undo that
+    for pair in swapped[8..].chunks_exact_mut(2) {
+        pair.swap(0, 1);
+    }
+    format_uuid(&swapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::lsx::serialize_lsx;
+
+    /// Minimal LSB writer for building fixtures
+    #[derive(Default)]
+    struct Fixture(Vec<u8>);
+
+    impl Fixture {
+        fn u32(&mut self, value: u32) -> &mut Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn string(&mut self, value: &str, null_terminated: bool) -> &mut Self {
+            self.u32((value.len() + usize::from(null_terminated)) as u32);
+            self.0.extend_from_slice(value.as_bytes());
+            if null_terminated {
+                self.0.push(0);
+            }
+            self
+        }
+
+        fn attribute(&mut self, name: u32, type_id: TypeId) -> &mut Self {
+            self.u32(name).u32(type_id)
+        }
+    }
+
+    /// Hand-assembled bytes in the layout of a DOS2 `meta.lsb`, with one
+    /// attribute of each common kind
+    fn dos2_meta() -> Vec<u8> {
+        let mut f = Fixture::default();
+        f.0.extend_from_slice(&SIGNATURE_LEGACY);
+        f.u32(0).u32(0).u32(0);
+        f.0.extend_from_slice(&0u64.to_le_bytes());
+        f.u32(3).u32(6).u32(2).u32(0);
+
+        let strings = [
+            "Config",
+            "ModuleInfo",
+            "Name",
+            "Version",
+            "UUID",
+            "Tags",
+            "Tag",
+        ];
+        f.u32(strings.len() as u32);
+        for (i, s) in strings.iter().enumerate() {
+            f.string(s, false).u32(i as u32);
+        }
+
+        let region_offset = f.0.len() as u32 + 4 + 8;
+        f.u32(1).u32(0).u32(region_offset);
+
+        // Config { ModuleInfo { Name, Version, UUID, Tags {}, Tag { Name } } }
+        f.u32(0).u32(0).u32(1);
+        f.u32(1).u32(3).u32(2);
+        f.attribute(2, TYPE_FIXEDSTRING).string("Shared", true);
+        f.attribute(3, TYPE_UINT32).u32(268_435_456);
+        f.attribute(4, TYPE_GUID);
+        f.0.extend_from_slice(&[
+            0x33, 0x22, 0x11, 0x00, 0x55, 0x44, 0x77, 0x66, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ]);
+        f.u32(5).u32(0).u32(0);
+        f.u32(6).u32(1).u32(0);
+        f.attribute(2, TYPE_TRANSLATEDSTRING)
+            .string("Hello", true)
+            .string("h12345678g1234g4567g89ab", true);
+        f.0
+    }
+
+    #[test]
+    fn test_lsb_to_lsx_snapshot() {
+        let doc = parse_lsb_bytes(&dos2_meta()).unwrap();
+        let xml = serialize_lsx(&doc).unwrap().replace("\r\n", "\n");
+        assert_eq!(
+            xml.trim_start_matches('\u{feff}'),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+	<version major="3" minor="6" revision="2" build="0" lslib_meta="v1" />
+	<region id="Config">
+		<node id="Config">
+			<children>
+				<node id="ModuleInfo">
+					<attribute id="Name" type="FixedString" value="Shared" />
+					<attribute id="Version" type="uint32" value="268435456" />
+					<attribute id="UUID" type="guid" value="00112233-4455-6677-8899-aabbccddeeff" />
+					<children>
+						<node id="Tags" />
+						<node id="Tag">
+							<attribute id="Name" type="TranslatedString" handle="h12345678g1234g4567g89ab" value="Hello" />
+						</node>
+					</children>
+				</node>
+			</children>
+		</node>
+	</region>
+</save>"#
+        );
+    }
+
+    #[test]
+    fn test_lsb_rejects_bad_magic_and_truncation() {
+        let mut bad = dos2_meta();
+        bad[..4].copy_from_slice(b"LSOF");
+        assert!(matches!(
+            parse_lsb_bytes(&bad).unwrap_err().root(),
+            Error::InvalidLsbMagic(_)
+        ));
+        assert!(parse_lsb_bytes(&dos2_meta()[..80]).is_err());
+    }
+}
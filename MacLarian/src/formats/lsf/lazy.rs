@@ -0,0 +1,202 @@
+//! Lazily decoded LSF documents
+//!
+//! [`LsfDocument::open_lazy`] parses the names, nodes, attributes and keys
+//! sections up front and leaves the values section, usually most of the
+//! file, as stored. Values are decompressed the first time one is read, and
+//! borrowed rather than copied when the file is stored uncompressed.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use super::document::{LsfAttribute, LsfDocument};
+use super::reader::{RawValues, parse_lsf_structure};
+use crate::error::Result;
+use crate::formats::common::extract_value;
+
+/// An LSF document whose values are decoded on first access
+pub struct LazyLsfDocument<'a> {
+    /// Everything but the values, which stay empty here
+    doc: LsfDocument,
+    raw_values: RawValues<'a>,
+    values: OnceLock<Cow<'a, [u8]>>,
+}
+
+impl LsfDocument {
+    /// Parse the structure of an LSF file, deferring its values
+    ///
+    /// Node and attribute tables are available immediately; the values
+    /// section is decoded from `data` when first needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`parse_lsf_bytes`](super::parse_lsf_bytes),
+    /// except that a corrupt values section is only reported when read.
+    pub fn open_lazy(data: &[u8]) -> Result<LazyLsfDocument<'_>> {
+        let (doc, raw_values) = parse_lsf_structure(data)?;
+        Ok(LazyLsfDocument {
+            doc,
+            raw_values,
+            values: OnceLock::new(),
+        })
+    }
+}
+
+impl LazyLsfDocument<'_> {
+    /// Engine version that created this document
+    #[must_use]
+    pub fn engine_version(&self) -> u64 {
+        self.doc.engine_version
+    }
+
+    /// String name table, organized as buckets of strings
+    #[must_use]
+    pub fn names(&self) -> &[Vec<String>] {
+        &self.doc.names
+    }
+
+    /// Number of nodes in the document
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.doc.nodes.len()
+    }
+
+    /// All attributes in the document
+    #[must_use]
+    pub fn attributes(&self) -> &[LsfAttribute] {
+        &self.doc.attributes
+    }
+
+    /// Get the name of a node
+    #[must_use]
+    pub fn node_name(&self, node_idx: usize) -> Option<&str> {
+        self.doc.node_name(node_idx)
+    }
+
+    /// Get the key of a node, if the document has one for it
+    #[must_use]
+    pub fn node_key(&self, node_idx: usize) -> Option<&str> {
+        self.doc.node_keys.get(node_idx)?.as_deref()
+    }
+
+    /// Get indices of root nodes (regions)
+    #[must_use]
+    pub fn root_nodes(&self) -> Vec<usize> {
+        self.doc.root_nodes()
+    }
+
+    /// Get indices of all children of a node
+    #[must_use]
+    pub fn children_of(&self, parent_idx: usize) -> Vec<usize> {
+        self.doc.children_of(parent_idx)
+    }
+
+    /// Find child nodes with a specific name
+    #[must_use]
+    pub fn find_children_by_name(&self, parent_idx: usize, name: &str) -> Vec<usize> {
+        self.doc.find_children_by_name(parent_idx, name)
+    }
+
+    /// Iterate over attributes of a node, yielding (index, name, `type_id`, `value_offset`, `value_length`)
+    #[must_use]
+    pub fn attributes_of(&self, node_idx: usize) -> Vec<(usize, &str, u32, usize, usize)> {
+        self.doc.attributes_of(node_idx)
+    }
+
+    /// Whether the values section has been decoded yet
+    #[must_use]
+    pub fn values_loaded(&self) -> bool {
+        self.values.get().is_some()
+    }
+
+    /// Raw attribute value bytes, decoding the values section on first call
+    ///
+    /// # Errors
+    /// Returns [`Error::DecompressionError`] if the values section cannot be
+    /// decompressed.
+    ///
+    /// [`Error::DecompressionError`]: crate::Error::DecompressionError
+    pub fn values(&self) -> Result<&[u8]> {
+        if let Some(values) = self.values.get() {
+            return Ok(values);
+        }
+        let values = self.raw_values.decode()?;
+        Ok(self.values.get_or_init(|| values))
+    }
+
+    /// Value of a node's attribute as text, as it would appear in LSX
+    ///
+    /// Returns `None` if the node has no attribute named `attr_name`.
+    ///
+    /// # Errors
+    /// Returns an error if the values section cannot be decoded.
+    pub fn attribute_value(&self, node_idx: usize, attr_name: &str) -> Result<Option<String>> {
+        let Some((_, _, type_id, offset, length)) = self
+            .attributes_of(node_idx)
+            .into_iter()
+            .find(|(_, name, ..)| *name == attr_name)
+        else {
+            return Ok(None);
+        };
+        extract_value(self.values()?, offset, length, type_id).map(Some)
+    }
+
+    /// Decode the remaining values and return the full document
+    ///
+    /// # Errors
+    /// Returns an error if the values section cannot be decoded.
+    pub fn into_document(self) -> Result<LsfDocument> {
+        let Self {
+            mut doc,
+            raw_values,
+            values,
+        } = self;
+        let values = match values.into_inner() {
+            Some(values) => values,
+            None => raw_values.decode()?,
+        };
+        doc.values = values.into_owned();
+        Ok(doc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::convert_bytes;
+    use crate::formats::lsf::{parse_lsf_bytes, read_header_info};
+    use crate::test_fixtures::MODULE_LSX;
+
+    #[test]
+    fn test_lazy_document_matches_full_parse() {
+        let data = convert_bytes(MODULE_LSX.as_bytes(), "lsx", "lsf").unwrap();
+
+        let info = read_header_info(&data).unwrap();
+        assert_eq!(info.regions, ["Config", "Dependencies"]);
+        assert_eq!(info.node_count, 3);
+
+        let lazy = LsfDocument::open_lazy(&data).unwrap();
+        assert!(!lazy.values_loaded());
+        let module = lazy.find_children_by_name(lazy.root_nodes()[0], "ModuleInfo")[0];
+        assert!(!lazy.values_loaded());
+        assert_eq!(
+            lazy.attribute_value(module, "Name").unwrap().as_deref(),
+            Some("Test")
+        );
+        assert_eq!(
+            lazy.attribute_value(module, "Version64")
+                .unwrap()
+                .as_deref(),
+            Some("36028797018963968")
+        );
+        assert_eq!(lazy.attribute_value(module, "Missing").unwrap(), None);
+        assert!(lazy.values_loaded());
+
+        let full = parse_lsf_bytes(&data).unwrap();
+        assert_eq!(lazy.into_document().unwrap().values, full.values);
+
+        // Header info never reads past the nodes section
+        let mut truncated = data.clone();
+        truncated.truncate(data.len() - 1);
+        assert!(read_header_info(&truncated).is_ok());
+    }
+}
@@ -3,13 +3,17 @@
 //!
 
 mod document;
+mod lazy;
 mod reader;
 mod writer;
 
 // Public API
 pub use document::{LsfAttribute, LsfDocument, LsfNode};
-pub use reader::{parse_lsf_bytes, read_lsf};
+pub use lazy::LazyLsfDocument;
+pub use reader::{LsfHeaderInfo, parse_lsf_bytes, read_header_info, read_lsf};
+pub use writer::{LsfFormat, LsfVersion, LsfWriteOptions};
 pub use writer::{serialize_lsf, serialize_lsf_with_format, write_lsf, write_lsf_with_format};
+pub use writer::{serialize_lsf_with_options, write_lsf_with_options};
 
 // Internal API (used by converter module)
 pub(crate) use document::LsfMetadataFormat;
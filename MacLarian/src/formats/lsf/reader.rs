@@ -12,9 +12,10 @@
 )]
 
 use super::document::{LsfAttribute, LsfDocument, LsfMetadataFormat, LsfNode};
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, ResultExt};
+use crate::utils::open_file;
 use byteorder::{LittleEndian, ReadBytesExt};
-use std::fs::File;
+use std::borrow::Cow;
 use std::io::{Cursor, Read};
 use std::path::Path;
 
@@ -22,30 +23,37 @@ use std::path::Path;
 // V1: Initial format
 // V2: Added chunked/frame compression (auto-detect on read)
 // V3: Extended node format (16-byte vs 12-byte)
-// V4: BG3 extended header (handled implicitly)
-// V6: BG3 node keys section
+// V4: BG3, engine version still packed into 32 bits
+// V5: BG3 extended header (64-bit engine version)
+// V6: BG3 header lists a keys section
+// V7: BG3 node keys, written by current game builds
 const LSF_VER_INITIAL: u32 = 1;
 const LSF_VER_EXTENDED_NODES: u32 = 3;
-const LSF_VER_BG3_NODE_KEYS: u32 = 6;
+const LSF_VER_BG3_EXTENDED_HEADER: u32 = 5;
+const LSF_VER_BG3_KEYS_SECTION: u32 = 6;
+const LSF_VER_MAX: u32 = 7;
 
 /// Read an LSF file from disk
 ///
 /// # Errors
 ///
+/// Returns [`Error::FileNotFound`] if the file doesn't exist.
 /// Returns [`Error::Io`] if the file cannot be opened or read.
 /// Returns [`Error::InvalidLsfMagic`] if the file does not have a valid LSF header.
 /// Returns [`Error::UnsupportedLsfVersion`] if the LSF version is not in the range 1-7.
 /// Returns [`Error::DecompressionError`] if LZ4 decompression of sections fails.
 ///
+/// [`Error::FileNotFound`]: crate::Error::FileNotFound
 /// [`Error::Io`]: crate::Error::Io
 /// [`Error::InvalidLsfMagic`]: crate::Error::InvalidLsfMagic
 /// [`Error::UnsupportedLsfVersion`]: crate::Error::UnsupportedLsfVersion
 /// [`Error::DecompressionError`]: crate::Error::DecompressionError
 pub fn read_lsf<P: AsRef<Path>>(path: P) -> Result<LsfDocument> {
-    let mut file = File::open(path)?;
+    let path = path.as_ref();
+    let mut file = open_file(path)?;
     let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
-    parse_lsf_bytes(&buffer)
+    file.read_to_end(&mut buffer).with_path(path)?;
+    parse_lsf_bytes(&buffer).with_path(path)
 }
 
 /// Parse LSF data from bytes
@@ -62,118 +70,301 @@ pub fn read_lsf<P: AsRef<Path>>(path: P) -> Result<LsfDocument> {
 /// [`Error::DecompressionError`]: crate::Error::DecompressionError
 /// [`Error::UnexpectedEof`]: crate::Error::UnexpectedEof
 pub fn parse_lsf_bytes(data: &[u8]) -> Result<LsfDocument> {
+    let (mut doc, values) = parse_lsf_structure(data)?;
+    doc.values = values.decode()?.into_owned();
+    Ok(doc)
+}
+
+/// Version and regions of an LSF file, see [`read_header_info`]
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LsfHeaderInfo {
+    /// LSF format version (1-7).
+    pub version: u32,
+    /// Engine version that created the file.
+    pub engine_version: u64,
+    /// Names of the root nodes, which become regions in LSX.
+    pub regions: Vec<String>,
+    /// Total number of nodes.
+    pub node_count: usize,
+    /// Size of the values section once decompressed.
+    pub values_size: usize,
+}
+
+/// Read the version and region names of an LSF file
+///
+/// Only the header, names and nodes sections are decoded; attributes and
+/// values are skipped, so this is cheap even for large `_merged.lsf` files.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidLsfMagic`] if the data does not have a valid LSF header.
+/// Returns [`Error::UnsupportedLsfVersion`] if the LSF version is not in the range 1-7.
+/// Returns [`Error::DecompressionError`] if LZ4 decompression of sections fails.
+/// Returns [`Error::UnexpectedEof`] if the data is truncated.
+///
+/// [`Error::InvalidLsfMagic`]: crate::Error::InvalidLsfMagic
+/// [`Error::UnsupportedLsfVersion`]: crate::Error::UnsupportedLsfVersion
+/// [`Error::DecompressionError`]: crate::Error::DecompressionError
+/// [`Error::UnexpectedEof`]: crate::Error::UnexpectedEof
+pub fn read_header_info(data: &[u8]) -> Result<LsfHeaderInfo> {
     let mut cursor = Cursor::new(data);
+    let header = read_header(&mut cursor)?;
+
+    let section_start = cursor.position();
+    let names =
+        read_names(&mut cursor, header.strings, header.is_compressed).at_offset(section_start)?;
+
+    let section_start = cursor.position();
+    let nodes = read_nodes(
+        &mut cursor,
+        header.nodes,
+        header.is_compressed,
+        header.node_extended_format(),
+    )
+    .at_offset(section_start)?;
+
+    let doc = LsfDocument {
+        names,
+        nodes,
+        ..LsfDocument::new()
+    };
+    let regions = doc
+        .root_nodes()
+        .into_iter()
+        .filter_map(|idx| doc.node_name(idx).map(str::to_string))
+        .collect();
+
+    Ok(LsfHeaderInfo {
+        version: header.version,
+        engine_version: header.engine_version,
+        regions,
+        node_count: doc.nodes.len(),
+        values_size: header.values.uncompressed,
+    })
+}
+
+/// Uncompressed and stored size of one section, as listed in the header
+#[derive(Clone, Copy)]
+struct SectionSize {
+    uncompressed: usize,
+    compressed: usize,
+}
+
+impl SectionSize {
+    fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        // ORDER IS: (uncompressed_size, compressed_size) per LSLib
+        Ok(Self {
+            uncompressed: reader.read_u32::<LittleEndian>()? as usize,
+            compressed: reader.read_u32::<LittleEndian>()? as usize,
+        })
+    }
+
+    /// Whether the section is stored compressed
+    fn is_compressed(self, file_compressed: bool) -> bool {
+        file_compressed && self.compressed > 0
+    }
+
+    /// Number of bytes the section takes up in the file
+    fn stored_len(self, file_compressed: bool) -> usize {
+        if self.uncompressed == 0 {
+            0
+        } else if self.is_compressed(file_compressed) {
+            self.compressed
+        } else {
+            self.uncompressed
+        }
+    }
+}
+
+/// Fixed-size LSF header
+struct LsfHeader {
+    version: u32,
+    engine_version: u64,
+    strings: SectionSize,
+    keys: SectionSize,
+    nodes: SectionSize,
+    attributes: SectionSize,
+    values: SectionSize,
+    is_compressed: bool,
+    metadata_format: LsfMetadataFormat,
+}
+
+impl LsfHeader {
+    /// Whether nodes and attributes use the 16-byte (V3+) layout
+    fn node_extended_format(&self) -> bool {
+        // V3+ format is only used when MetadataFormat is KeysAndAdjacency
+        let has_extended_nodes = self.version >= LSF_VER_EXTENDED_NODES
+            && self.metadata_format == LsfMetadataFormat::KeysAndAdjacency;
+        // Detect node format - this also determines attribute format since they must match
+        detect_extended_format(self.nodes.uncompressed, has_extended_nodes)
+    }
+}
 
+fn read_header(cursor: &mut Cursor<&[u8]>) -> Result<LsfHeader> {
     // Read magic
     let mut magic = [0u8; 4];
-    cursor.read_exact(&mut magic)?;
+    cursor.read_exact(&mut magic).at_offset(0)?;
     if &magic != b"LSOF" {
-        return Err(Error::InvalidLsfMagic(magic));
+        return Err(Error::InvalidLsfMagic(magic).at_offset(0));
     }
 
-    let version = cursor.read_u32::<LittleEndian>()?;
-    if !(LSF_VER_INITIAL..=7).contains(&version) {
-        return Err(Error::UnsupportedLsfVersion { version });
+    let version = cursor.read_u32::<LittleEndian>().at_offset(4)?;
+    if !(LSF_VER_INITIAL..=LSF_VER_MAX).contains(&version) {
+        return Err(Error::UnsupportedLsfVersion { version }.at_offset(4));
     }
 
-    let engine_version = cursor.read_u64::<LittleEndian>()?;
+    // Older headers pack the engine version into 32 bits
+    let engine_version = if version >= LSF_VER_BG3_EXTENDED_HEADER {
+        cursor.read_u64::<LittleEndian>()?
+    } else {
+        unpack_engine_version(cursor.read_u32::<LittleEndian>()?)
+    };
 
-    // Read section sizes - ORDER IS: (uncompressed_size, compressed_size) per LSLib
-    let strings_uncompressed = cursor.read_u32::<LittleEndian>()? as usize;
-    let strings_compressed = cursor.read_u32::<LittleEndian>()? as usize;
+    let strings = SectionSize::read(cursor)?;
 
     // Keys section only exists in v6+
-    let (keys_uncompressed, keys_compressed) = if version >= LSF_VER_BG3_NODE_KEYS {
-        let u = cursor.read_u32::<LittleEndian>()? as usize;
-        let c = cursor.read_u32::<LittleEndian>()? as usize;
-        (u, c)
+    let keys = if version >= LSF_VER_BG3_KEYS_SECTION {
+        SectionSize::read(cursor)?
     } else {
-        (0, 0)
+        SectionSize {
+            uncompressed: 0,
+            compressed: 0,
+        }
     };
 
-    let nodes_uncompressed = cursor.read_u32::<LittleEndian>()? as usize;
-    let nodes_compressed = cursor.read_u32::<LittleEndian>()? as usize;
-
-    let attributes_uncompressed = cursor.read_u32::<LittleEndian>()? as usize;
-    let attributes_compressed = cursor.read_u32::<LittleEndian>()? as usize;
-
-    let values_uncompressed = cursor.read_u32::<LittleEndian>()? as usize;
-    let values_compressed = cursor.read_u32::<LittleEndian>()? as usize;
+    let nodes = SectionSize::read(cursor)?;
+    let attributes = SectionSize::read(cursor)?;
+    let values = SectionSize::read(cursor)?;
 
     let compression_flags = cursor.read_u32::<LittleEndian>()?;
-    let metadata_format_raw = cursor.read_u32::<LittleEndian>()?;
-    let metadata_format = LsfMetadataFormat::from(metadata_format_raw);
+    let metadata_format = LsfMetadataFormat::from(cursor.read_u32::<LittleEndian>()?);
 
     // Compression method is in lower 4 bits
-    let compression_method = compression_flags & 0x0F;
-    let is_compressed = compression_method != 0;
-
-    // Determine if using extended node/attribute format
-    // V3+ format is only used when MetadataFormat is KeysAndAdjacency
-    let has_extended_nodes =
-        version >= LSF_VER_EXTENDED_NODES && metadata_format == LsfMetadataFormat::KeysAndAdjacency;
+    let is_compressed = compression_flags & 0x0F != 0;
 
-    // Read sections in FILE ORDER: Strings, Nodes, Attributes, Values, [Keys]
-    let names = read_names(
-        &mut cursor,
-        strings_uncompressed,
-        strings_compressed,
+    Ok(LsfHeader {
+        version,
+        engine_version,
+        strings,
+        keys,
+        nodes,
+        attributes,
+        values,
         is_compressed,
-    )?;
+        metadata_format,
+    })
+}
+
+/// Widen a 32-bit packed engine version (4/4/8/16 bits) to the 64-bit
+/// layout (7/8/16/31 bits) used by v5+ headers
+fn unpack_engine_version(packed: u32) -> u64 {
+    let major = u64::from(packed >> 28);
+    let minor = u64::from((packed >> 24) & 0x0F);
+    let revision = u64::from((packed >> 16) & 0xFF);
+    let build = u64::from(packed & 0xFFFF);
+    (major << 55) | (minor << 47) | (revision << 31) | build
+}
+
+/// Values section of an LSF file, left as stored
+pub(super) struct RawValues<'a> {
+    data: &'a [u8],
+    size: SectionSize,
+    is_compressed: bool,
+    offset: u64,
+}
+
+impl<'a> RawValues<'a> {
+    /// Decompress the section, borrowing it when it is stored uncompressed
+    pub(super) fn decode(&self) -> Result<Cow<'a, [u8]>> {
+        if self.size.uncompressed == 0 {
+            return Ok(Cow::Borrowed(&[]));
+        }
+        if self.size.is_compressed(self.is_compressed) {
+            decompress_section(self.data, self.size.uncompressed)
+                .map(Cow::Owned)
+                .at_offset(self.offset)
+        } else {
+            Ok(Cow::Borrowed(self.data))
+        }
+    }
+}
 
-    // Detect node format - this also determines attribute format since they must match
-    let node_extended_format = detect_extended_format(nodes_uncompressed, has_extended_nodes);
+/// Parse every section except values, which are returned undecoded
+///
+/// The returned document has empty `values`.
+pub(super) fn parse_lsf_structure(data: &[u8]) -> Result<(LsfDocument, RawValues<'_>)> {
+    let mut cursor = Cursor::new(data);
+    let header = read_header(&mut cursor)?;
+    let is_compressed = header.is_compressed;
+    let node_extended_format = header.node_extended_format();
 
+    // Read sections in FILE ORDER: Strings, Nodes, Attributes, Values, [Keys]
+    // Errors point at the start of the section they were found in
+    let section_start = cursor.position();
+    let names = read_names(&mut cursor, header.strings, is_compressed).at_offset(section_start)?;
+
+    let section_start = cursor.position();
     let nodes = read_nodes(
         &mut cursor,
-        nodes_uncompressed,
-        nodes_compressed,
+        header.nodes,
         is_compressed,
         node_extended_format,
-    )?;
+    )
+    .at_offset(section_start)?;
 
     // Use the same format detected for nodes - they must be consistent
+    let section_start = cursor.position();
     let attributes = read_attributes(
         &mut cursor,
-        attributes_uncompressed,
-        attributes_compressed,
+        header.attributes,
         is_compressed,
         node_extended_format,
         &nodes,
-    )?;
-
-    let values = read_section(
-        &mut cursor,
-        values_uncompressed,
-        values_compressed,
+    )
+    .at_offset(section_start)?;
+
+    // Values are only located here; callers decode them when needed
+    let section_start = cursor.position();
+    let stored_len = header.values.stored_len(is_compressed);
+    let values_data = data
+        .get(section_start as usize..section_start as usize + stored_len)
+        .ok_or(Error::TruncatedData {
+            offset: section_start,
+            needed: stored_len,
+        })
+        .at_offset(section_start)?;
+    cursor.set_position(section_start + stored_len as u64);
+    let values = RawValues {
+        data: values_data,
+        size: header.values,
         is_compressed,
-    )?;
+        offset: section_start,
+    };
 
     // Keys section comes AFTER values (only in v6+)
-    let node_keys = if version >= LSF_VER_BG3_NODE_KEYS && keys_uncompressed > 0 {
-        let keys_data = read_section(
-            &mut cursor,
-            keys_uncompressed,
-            keys_compressed,
-            is_compressed,
-        )?;
-        parse_keys(&keys_data, &names, nodes.len())?
+    let has_keys_section =
+        header.version >= LSF_VER_BG3_KEYS_SECTION && header.keys.uncompressed > 0;
+    let node_keys = if has_keys_section {
+        let section_start = cursor.position();
+        let keys_data =
+            read_section(&mut cursor, header.keys, is_compressed).at_offset(section_start)?;
+        parse_keys(&keys_data, &names, nodes.len()).at_offset(section_start)?
     } else {
         vec![None; nodes.len()]
     };
 
-    let has_keys_section = version >= LSF_VER_BG3_NODE_KEYS && keys_uncompressed > 0;
-
-    Ok(LsfDocument {
-        engine_version,
+    let doc = LsfDocument {
+        engine_version: header.engine_version,
         names,
         nodes,
         attributes,
-        values,
+        values: Vec::new(),
         node_keys,
         has_keys_section,
-        metadata_format,
-    })
+        metadata_format: header.metadata_format,
+    };
+    Ok((doc, values))
 }
 
 /// Detect if extended format (16-byte) or V2 format (12-byte) based on data size
@@ -190,46 +381,42 @@ fn detect_extended_format(data_size: usize, version_hint: bool) -> bool {
 
 fn read_section<R: Read>(
     reader: &mut R,
-    uncompressed_size: usize,
-    compressed_size: usize,
+    size: SectionSize,
     is_compressed: bool,
 ) -> Result<Vec<u8>> {
-    if uncompressed_size == 0 {
+    if size.uncompressed == 0 {
         return Ok(Vec::new());
     }
 
-    let read_size = if is_compressed && compressed_size > 0 {
-        compressed_size
-    } else {
-        uncompressed_size
-    };
-
-    let mut buffer = vec![0u8; read_size];
+    let mut buffer = vec![0u8; size.stored_len(is_compressed)];
     reader.read_exact(&mut buffer)?;
 
-    if is_compressed && compressed_size > 0 {
-        // Try LZ4 frame format first
-        let mut decoder = lz4_flex::frame::FrameDecoder::new(Cursor::new(&buffer));
-        let mut decompressed = Vec::new();
-        if decoder.read_to_end(&mut decompressed).is_ok() {
-            return Ok(decompressed);
-        }
-
-        // Fall back to LZ4 block decompression
-        lz4_flex::block::decompress(&buffer, uncompressed_size)
-            .map_err(|e| Error::DecompressionError(format!("LZ4: {e}")))
+    if size.is_compressed(is_compressed) {
+        decompress_section(&buffer, size.uncompressed)
     } else {
         Ok(buffer)
     }
 }
 
+fn decompress_section(data: &[u8], uncompressed_size: usize) -> Result<Vec<u8>> {
+    // Try LZ4 frame format first
+    let mut decoder = lz4_flex::frame::FrameDecoder::new(data);
+    let mut decompressed = Vec::new();
+    if decoder.read_to_end(&mut decompressed).is_ok() {
+        return Ok(decompressed);
+    }
+
+    // Fall back to LZ4 block decompression
+    lz4_flex::block::decompress(data, uncompressed_size)
+        .map_err(|e| Error::DecompressionError(format!("LZ4: {e}")))
+}
+
 fn read_names<R: Read>(
     reader: &mut R,
-    uncompressed_size: usize,
-    compressed_size: usize,
+    size: SectionSize,
     is_compressed: bool,
 ) -> Result<Vec<Vec<String>>> {
-    let data = read_section(reader, uncompressed_size, compressed_size, is_compressed)?;
+    let data = read_section(reader, size, is_compressed)?;
     if data.is_empty() {
         return Ok(Vec::new());
     }
@@ -255,12 +442,11 @@ fn read_names<R: Read>(
 
 fn read_nodes<R: Read>(
     reader: &mut R,
-    uncompressed_size: usize,
-    compressed_size: usize,
+    size: SectionSize,
     is_compressed: bool,
     extended_format: bool,
 ) -> Result<Vec<LsfNode>> {
-    let data = read_section(reader, uncompressed_size, compressed_size, is_compressed)?;
+    let data = read_section(reader, size, is_compressed)?;
     if data.is_empty() {
         return Ok(Vec::new());
     }
@@ -309,13 +495,12 @@ fn read_nodes<R: Read>(
 
 fn read_attributes<R: Read>(
     reader: &mut R,
-    uncompressed_size: usize,
-    compressed_size: usize,
+    size: SectionSize,
     is_compressed: bool,
     extended_format: bool,
     _nodes: &[LsfNode],
 ) -> Result<Vec<LsfAttribute>> {
-    let data = read_section(reader, uncompressed_size, compressed_size, is_compressed)?;
+    let data = read_section(reader, size, is_compressed)?;
     if data.is_empty() {
         return Ok(Vec::new());
     }
@@ -439,3 +624,37 @@ fn parse_keys(
 
     Ok(keys)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::convert_bytes;
+    use crate::test_fixtures::CONFIG_LSX;
+
+    #[test]
+    fn test_corrupt_lsf_reports_context() {
+        let data = convert_bytes(CONFIG_LSX.as_bytes(), "lsx", "lsf").unwrap();
+        assert!(parse_lsf_bytes(&data).is_ok());
+
+        let mut bad_version = data.clone();
+        bad_version[4] = 99;
+        let err = parse_lsf_bytes(&bad_version).unwrap_err();
+        assert!(matches!(
+            err.root(),
+            Error::UnsupportedLsfVersion { version: 99 }
+        ));
+        assert_eq!(err.offset(), Some(4));
+
+        // Cut into the last section: the error points past the header
+        let truncated = &data[..data.len() - 2];
+        let err = parse_lsf_bytes(truncated).unwrap_err();
+        assert!(err.offset().is_some_and(|offset| offset > 8));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("corrupt.lsf");
+        std::fs::write(&path, truncated).unwrap();
+        let err = read_lsf(&path).unwrap_err();
+        assert_eq!(err.path(), Some(path.as_path()));
+        assert!(err.to_string().contains(&path.display().to_string()));
+    }
+}
@@ -11,7 +11,19 @@
 //! and `allowChunked=true` uses Frame format. The compression flags in the header
 //! (0x22 = LZ4 + `DefaultCompress`) indicate the method but not per-section format.
 //!
+//! [`LsfWriteOptions`] can switch sections to LZ4 HC (flags 0x42, `MaxCompress`)
+//! or store them uncompressed (flags 0x00, compressed sizes written as 0).
 //!
+//! # Header Versions
+//!
+//! Files are written as version 6, the header this writer has always produced,
+//! unless [`LsfWriteOptions::target_version`] asks for another. v7 shares the
+//! v6 layout and only changes the version number. Before v6 the header has no
+//! keys section, so node keys are dropped; before v5 the engine version is
+//! packed into 32 bits.
+//!
+//! The default moves to v7 once `test_game_files_round_trip` (ignored, needs a
+//! game install) passes against current game files.
 
 // Binary format writing requires many intentional casts between integer types
 #![allow(
@@ -21,9 +33,9 @@
 )]
 
 use super::document::LsfDocument;
-use crate::error::Result;
+use crate::compression::{CompressionAlgorithm, CompressionSettings, Lz4Mode, compress_with};
+use crate::error::{Error, Result};
 use byteorder::{LittleEndian, WriteBytesExt};
-use std::io::Write;
 use std::path::Path;
 
 /// Node/attribute format for LSF files
@@ -39,22 +51,71 @@ pub enum LsfFormat {
     V3,
 }
 
-/// Compress data using LZ4 block format (used for strings section)
-fn compress_lz4_block(data: &[u8]) -> Vec<u8> {
-    if data.is_empty() {
-        return Vec::new();
+/// LSF header version to write
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LsfVersion {
+    /// V4: 32-bit engine version, no keys section
+    V4,
+    /// V5: 64-bit engine version, no keys section
+    V5,
+    /// V6: adds the keys section (default)
+    #[default]
+    V6,
+    /// V7: same layout as V6
+    V7,
+}
+
+impl LsfVersion {
+    /// Every version that can be written, oldest first
+    pub const ALL: [Self; 4] = [Self::V4, Self::V5, Self::V6, Self::V7];
+
+    /// Version number stored in the header
+    #[must_use]
+    pub fn number(self) -> u32 {
+        match self {
+            Self::V4 => 4,
+            Self::V5 => 5,
+            Self::V6 => 6,
+            Self::V7 => 7,
+        }
+    }
+
+    /// Whether the engine version is stored in 64 bits
+    fn has_extended_header(self) -> bool {
+        self >= Self::V5
+    }
+
+    /// Whether the header lists a keys section
+    fn has_keys_section(self) -> bool {
+        self >= Self::V6
     }
-    lz4_flex::block::compress(data)
 }
 
-/// Compress data using LZ4 frame format (used for nodes, attributes, values, keys)
-fn compress_lz4_frame(data: &[u8]) -> std::io::Result<Vec<u8>> {
-    if data.is_empty() {
+/// Options for writing LSF files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LsfWriteOptions {
+    /// Node/attribute format
+    pub format: LsfFormat,
+    /// Section compression: fast LZ4 (default), LZ4 HC or none
+    ///
+    /// The LZ4 mode is chosen per section; other algorithms are rejected.
+    pub compression: CompressionSettings,
+    /// Header version, V6 by default until V7 output is checked against
+    /// game files
+    pub target_version: LsfVersion,
+}
+
+/// Compress one section, leaving empty sections empty
+fn compress_section(
+    data: &[u8],
+    compression: &CompressionSettings,
+    mode: Lz4Mode,
+) -> Result<Vec<u8>> {
+    if data.is_empty() || compression.method == CompressionAlgorithm::None {
         return Ok(Vec::new());
     }
-    let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
-    encoder.write_all(data)?;
-    Ok(encoder.finish()?)
+    compress_with(data, &compression.with_lz4_mode(mode))
 }
 
 /// Write an LSF document to disk (LZ4 compressed, V2 format)
@@ -92,16 +153,73 @@ pub fn serialize_lsf(doc: &LsfDocument) -> Result<Vec<u8>> {
 /// # Errors
 /// Returns an error if serialization fails.
 pub fn serialize_lsf_with_format(doc: &LsfDocument, format: LsfFormat) -> Result<Vec<u8>> {
+    serialize_lsf_with_options(
+        doc,
+        &LsfWriteOptions {
+            format,
+            ..LsfWriteOptions::default()
+        },
+    )
+}
+
+/// Write an LSF document to disk with format and compression options
+///
+/// # Errors
+/// Returns an error if the compression isn't LZ4 or none, or if
+/// serialization or file writing fails.
+pub fn write_lsf_with_options<P: AsRef<Path>>(
+    doc: &LsfDocument,
+    path: P,
+    options: &LsfWriteOptions,
+) -> Result<()> {
+    let bytes = serialize_lsf_with_options(doc, options)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Serialize LSF document to bytes with format and compression options
+///
+/// # Errors
+/// Returns an error if the compression isn't LZ4 or none, or if
+/// serialization fails.
+pub fn serialize_lsf_with_options(doc: &LsfDocument, options: &LsfWriteOptions) -> Result<Vec<u8>> {
+    let LsfWriteOptions {
+        format,
+        compression,
+        target_version,
+    } = *options;
+    // Compression flags: method in the low nibble, level in the high nibble
+    // (0x20 = DefaultCompress, 0x40 = MaxCompress)
+    let compression_flags = match compression.method {
+        CompressionAlgorithm::None => 0x00u32,
+        CompressionAlgorithm::Lz4 if compression.is_lz4_hc() => 0x42,
+        CompressionAlgorithm::Lz4 => 0x22,
+        _ => {
+            return Err(Error::CompressionError(format!(
+                "LSF files can't use {compression} compression (expected lz4 or none)"
+            )));
+        }
+    };
+    let compressed = compression.method != CompressionAlgorithm::None;
+
     let mut output = Vec::new();
 
     // Write header
     output.extend_from_slice(b"LSOF");
-    output.write_u32::<LittleEndian>(6)?;
-    output.write_u64::<LittleEndian>(doc.engine_version)?;
+    output.write_u32::<LittleEndian>(target_version.number())?;
+    if target_version.has_extended_header() {
+        output.write_u64::<LittleEndian>(doc.engine_version)?;
+    } else {
+        output.write_u32::<LittleEndian>(pack_engine_version(doc.engine_version))?;
+    }
 
     // Prepare sections
     let names_data = write_names(doc)?;
-    let keys_data = write_keys(doc)?;
+    let keys_data = if target_version.has_keys_section() {
+        write_keys(doc)?
+    } else {
+        Vec::new()
+    };
     let nodes_data = write_nodes(doc, format)?;
     let attributes_data = write_attributes(doc, format)?;
     let values_data = &doc.values;
@@ -109,11 +227,11 @@ pub fn serialize_lsf_with_format(doc: &LsfDocument, format: LsfFormat) -> Result
     // Compress sections per LSLib convention:
     // - Strings: LZ4 Block (allowChunked=false) - raw compressed data
     // - All others: LZ4 Frame (allowChunked=true) - with frame header magic
-    let names_compressed = compress_lz4_block(&names_data);
-    let keys_compressed = compress_lz4_frame(&keys_data)?;
-    let nodes_compressed = compress_lz4_frame(&nodes_data)?;
-    let attributes_compressed = compress_lz4_frame(&attributes_data)?;
-    let values_compressed = compress_lz4_frame(values_data)?;
+    let names_compressed = compress_section(&names_data, &compression, Lz4Mode::Block)?;
+    let keys_compressed = compress_section(&keys_data, &compression, Lz4Mode::Frame)?;
+    let nodes_compressed = compress_section(&nodes_data, &compression, Lz4Mode::Frame)?;
+    let attributes_compressed = compress_section(&attributes_data, &compression, Lz4Mode::Frame)?;
+    let values_compressed = compress_section(values_data, &compression, Lz4Mode::Frame)?;
 
     // Write section sizes - uncompressed size first, then compressed size
     // Header order for v6+: Strings, Keys, Nodes, Attributes, Values
@@ -122,9 +240,11 @@ pub fn serialize_lsf_with_format(doc: &LsfDocument, format: LsfFormat) -> Result
     output.write_u32::<LittleEndian>(names_data.len() as u32)?;
     output.write_u32::<LittleEndian>(names_compressed.len() as u32)?;
 
-    // Keys section
-    output.write_u32::<LittleEndian>(keys_data.len() as u32)?;
-    output.write_u32::<LittleEndian>(keys_compressed.len() as u32)?;
+    // Keys section (v6+ only)
+    if target_version.has_keys_section() {
+        output.write_u32::<LittleEndian>(keys_data.len() as u32)?;
+        output.write_u32::<LittleEndian>(keys_compressed.len() as u32)?;
+    }
 
     // Nodes section
     output.write_u32::<LittleEndian>(nodes_data.len() as u32)?;
@@ -138,9 +258,9 @@ pub fn serialize_lsf_with_format(doc: &LsfDocument, format: LsfFormat) -> Result
     output.write_u32::<LittleEndian>(values_data.len() as u32)?;
     output.write_u32::<LittleEndian>(values_compressed.len() as u32)?;
 
-    // Compression flags: 0x22 = MethodLZ4 (0x02) | DefaultCompress (0x20)
+    // Compression flags (0x22 by default, see above)
     // Note: This indicates compression method, not per-section format (Block vs Frame)
-    output.write_u32::<LittleEndian>(0x22)?;
+    output.write_u32::<LittleEndian>(compression_flags)?;
 
     // Metadata format determines node/attribute format:
     // - 0 (None) = V2 format (12-byte nodes/attrs)
@@ -154,15 +274,33 @@ pub fn serialize_lsf_with_format(doc: &LsfDocument, format: LsfFormat) -> Result
 
     // Write section data
     // File order: Strings, Nodes, Attributes, Values, Keys
-    output.extend_from_slice(&names_compressed);
-    output.extend_from_slice(&nodes_compressed);
-    output.extend_from_slice(&attributes_compressed);
-    output.extend_from_slice(&values_compressed);
-    output.extend_from_slice(&keys_compressed);
+    if compressed {
+        output.extend_from_slice(&names_compressed);
+        output.extend_from_slice(&nodes_compressed);
+        output.extend_from_slice(&attributes_compressed);
+        output.extend_from_slice(&values_compressed);
+        output.extend_from_slice(&keys_compressed);
+    } else {
+        output.extend_from_slice(&names_data);
+        output.extend_from_slice(&nodes_data);
+        output.extend_from_slice(&attributes_data);
+        output.extend_from_slice(values_data);
+        output.extend_from_slice(&keys_data);
+    }
 
     Ok(output)
 }
 
+/// Narrow a 64-bit engine version (7/8/16/31 bits) to the 32-bit layout
+/// (4/4/8/16 bits) used by headers before v5
+fn pack_engine_version(version: u64) -> u32 {
+    let major = ((version >> 55) & 0x0F) as u32;
+    let minor = ((version >> 47) & 0x0F) as u32;
+    let revision = ((version >> 31) & 0xFF) as u32;
+    let build = (version & 0xFFFF) as u32;
+    (major << 28) | (minor << 24) | (revision << 16) | build
+}
+
 /// Serialize names section
 fn write_names(doc: &LsfDocument) -> Result<Vec<u8>> {
     let mut buffer = Vec::new();
@@ -280,3 +418,101 @@ fn build_attr_to_node_map(doc: &LsfDocument) -> std::collections::HashMap<usize,
 
     map
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::convert_bytes;
+    use crate::formats::lsf::{parse_lsf_bytes, read_header_info};
+
+    #[test]
+    fn test_default_writes_v6() {
+        let data = std::fs::read(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance/lsf/v7.lsf"),
+        )
+        .unwrap();
+        let doc = parse_lsf_bytes(&data).unwrap();
+        let written = serialize_lsf(&doc).unwrap();
+        assert_eq!(read_header_info(&written).unwrap().version, 6);
+    }
+
+    /// Reads LSF files from every base game PAK, writes each back at its own
+    /// header version and at V7, and compares the output
+    ///
+    /// Run with `MACPAK_BG3_PATH=<game> cargo test -- --ignored` after a game
+    /// patch, and before changing the default version.
+    #[test]
+    #[ignore = "needs a BG3 install (MACPAK_BG3_PATH)"]
+    fn test_game_files_round_trip() {
+        use crate::pak::PakOperations;
+
+        const FILES_PER_PAK: usize = 50;
+
+        let install = crate::utils::find_game_install().expect("no BG3 install found");
+        let mut checked = 0;
+        for pak in &install.base_paks {
+            let paths: Vec<String> = PakOperations::list(pak)
+                .unwrap()
+                .into_iter()
+                .filter(|p| p.ends_with(".lsf"))
+                .take(FILES_PER_PAK)
+                .collect();
+            for (path, data) in PakOperations::read_files_bytes(pak, &paths).unwrap() {
+                let number = read_header_info(&data).unwrap().version;
+                let Some(version) = LsfVersion::ALL.into_iter().find(|v| v.number() == number)
+                else {
+                    panic!("{path}: unknown LSF version {number}");
+                };
+                let doc = parse_lsf_bytes(&data).unwrap_or_else(|e| panic!("{path}: {e}"));
+                let expected = convert_bytes(&data, "lsf", "lsx").unwrap();
+                for target_version in [version, LsfVersion::V7] {
+                    let options = LsfWriteOptions {
+                        target_version,
+                        ..LsfWriteOptions::default()
+                    };
+                    let written = serialize_lsf_with_options(&doc, &options).unwrap();
+                    assert_eq!(
+                        convert_bytes(&written, "lsf", "lsx").unwrap(),
+                        expected,
+                        "{path} written as {target_version:?}"
+                    );
+                }
+                checked += 1;
+            }
+        }
+        assert!(checked > 0, "no LSF files found in {:?}", install.data_dir);
+    }
+
+    // The fixtures were written by this crate, so they catch regressions in
+    // each header layout but don't prove it matches the game's files; see
+    // test_game_files_round_trip for that.
+    #[test]
+    fn test_conformance_fixtures_round_trip() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance/lsf");
+        let mut engine_version = None;
+        for version in LsfVersion::ALL {
+            let data = std::fs::read(dir.join(format!("v{}.lsf", version.number()))).unwrap();
+            assert_eq!(read_header_info(&data).unwrap().version, version.number());
+            let doc = parse_lsf_bytes(&data).unwrap();
+            // The 32-bit engine version of old headers reads as the same version
+            assert_eq!(
+                *engine_version.get_or_insert(doc.engine_version),
+                doc.engine_version
+            );
+
+            let options = LsfWriteOptions {
+                target_version: version,
+                ..LsfWriteOptions::default()
+            };
+            let written = serialize_lsf_with_options(&doc, &options).unwrap();
+            assert_eq!(
+                read_header_info(&written).unwrap().version,
+                version.number()
+            );
+            assert_eq!(
+                convert_bytes(&written, "lsf", "lsx").unwrap(),
+                convert_bytes(&data, "lsf", "lsx").unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! `.lsv` savegame packages
+//!
+//! BG3 saves use the same LSPK container as PAK files. A save holds
+//! `meta.lsf` (save name, game version and active mods), `Globals.lsf`
+//! (world state), per-level caches and a screenshot. Saves can be listed,
+//! extracted and inspected; writing them is not supported.
+
+use std::io::Read;
+use std::path::Path;
+
+use super::lsf::parse_lsf_bytes;
+use super::lsx::{LsxDocument, LsxNode, parse_lsx};
+use super::meta::ModDependency;
+use crate::converter::to_lsx;
+use crate::error::{Error, Result};
+use crate::pak::PakOperations;
+use crate::utils::paths_match;
+
+/// Save metadata file inside an LSV package
+pub const META_FILE: &str = "meta.lsf";
+
+/// World state file inside an LSV package
+pub const GLOBALS_FILE: &str = "Globals.lsf";
+
+/// Summary of a save, read from its `meta.lsf`
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SaveInfo {
+    /// Name shown in the load menu, if recorded.
+    pub save_name: Option<String>,
+    /// Game version the save was written with.
+    pub game_version: String,
+    /// Name of the party leader, if recorded.
+    pub leader_name: Option<String>,
+    /// Level the save was made in, if recorded.
+    pub level_name: Option<String>,
+    /// Mods active in the save, in load order.
+    pub mods: Vec<ModDependency>,
+}
+
+/// Whether `path` has the `.lsv` extension
+#[must_use]
+pub fn is_lsv_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lsv"))
+}
+
+/// Whether `path` is an `.lsv` file with an LSPK header
+#[must_use]
+pub fn is_lsv(path: &Path) -> bool {
+    if !is_lsv_path(path) {
+        return false;
+    }
+    let mut magic = [0u8; 4];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok_and(|()| &magic == b"LSPK")
+}
+
+/// List the files in a save
+///
+/// # Errors
+/// Returns an error if the save cannot be opened or is not an LSPK package.
+pub fn list_lsv<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+    PakOperations::list(path)
+}
+
+/// Extract every file in a save to `output_dir`
+///
+/// # Errors
+/// Returns an error if the save cannot be read or the files cannot be written.
+pub fn extract_lsv<P: AsRef<Path>, Q: AsRef<Path>>(path: P, output_dir: Q) -> Result<()> {
+    PakOperations::extract(path.as_ref(), output_dir.as_ref())
+}
+
+/// Read one file from a save, matching its path regardless of case and
+/// Unicode normalization
+///
+/// # Errors
+/// Returns [`Error::FileNotFoundInPak`] if the save has no such file, or an
+/// error if the save cannot be read.
+///
+/// [`Error::FileNotFoundInPak`]: crate::Error::FileNotFoundInPak
+pub fn read_lsv_file<P: AsRef<Path>>(path: P, name: &str) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+    let entry = list_lsv(path)?
+        .into_iter()
+        .find(|entry| paths_match(entry, name))
+        .ok_or_else(|| Error::FileNotFoundInPak(name.to_string()))?;
+    PakOperations::read_file_bytes(path, &entry)
+}
+
+/// Read the save name, game version and mod list of a save
+///
+/// # Errors
+/// Returns an error if the save cannot be read or its `meta.lsf` is missing
+/// or invalid.
+pub fn read_save_info<P: AsRef<Path>>(path: P) -> Result<SaveInfo> {
+    let meta = read_lsv_file(path, META_FILE)?;
+    let doc = parse_lsx(&to_lsx(&parse_lsf_bytes(&meta)?)?)?;
+    Ok(parse_save_meta(&doc))
+}
+
+/// Summarize a save's `meta.lsf`, already converted to LSX
+///
+/// The game version falls back to the document's own version when the
+/// metadata doesn't record one.
+#[must_use]
+pub fn parse_save_meta(doc: &LsxDocument) -> SaveInfo {
+    let nodes: Vec<&LsxNode> = doc.regions.iter().flat_map(|r| &r.nodes).collect();
+    let find = |ids: &[&str]| {
+        ids.iter()
+            .find_map(|id| nodes.iter().find_map(|n| find_attribute(n, id)))
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let mods = doc
+        .find_node("Mods")
+        .map(|mods| {
+            mods.children
+                .iter()
+                .filter(|n| n.id == "ModuleShortDesc")
+                .map(|n| ModDependency {
+                    uuid: n.attribute_value("UUID").unwrap_or_default().to_string(),
+                    name: n.attribute_value("Name").unwrap_or_default().to_string(),
+                    folder: n.attribute_value("Folder").unwrap_or_default().to_string(),
+                    version64: n.attribute_value("Version64").and_then(|v| v.parse().ok()),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    SaveInfo {
+        save_name: find(&["SaveName", "Name"]),
+        game_version: find(&["GameVersion"]).unwrap_or_else(|| doc.version_string()),
+        leader_name: find(&["LeaderName"]),
+        level_name: find(&["LevelName", "CurrentLevel"]),
+        mods,
+    }
+}
+
+/// First value of attribute `id` on `node` or its descendants, skipping the
+/// mod list (whose entries also have a `Name`)
+fn find_attribute<'a>(node: &'a LsxNode, id: &str) -> Option<&'a str> {
+    if let Some(value) = node.attribute_value(id) {
+        return Some(value);
+    }
+    node.children
+        .iter()
+        .filter(|c| c.id != "ModuleSettings" && c.id != "Mods")
+        .find_map(|c| find_attribute(c, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::converter::convert_bytes;
+    use crate::pak::PakBuilder;
+    use crate::test_fixtures::CONFIG_LSX;
+
+    /// `meta.lsf` of a save with one mod besides the base game, as LSX
+    const SAVE_META_LSX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="7" revision="1" build="3" />
+    <region id="MetaData">
+        <node id="MetaData">
+            <attribute id="LeaderName" type="LSString" value="Tav" />
+            <attribute id="LevelName" type="FixedString" value="WLD_Main_A" />
+            <attribute id="SaveName" type="LSString" value="QuickSave_12" />
+            <children>
+                <node id="ModuleSettings">
+                    <children>
+                        <node id="Mods">
+                            <children>
+                                <node id="ModuleShortDesc">
+                                    <attribute id="Folder" type="LSString" value="GustavDev" />
+                                    <attribute id="Name" type="LSString" value="GustavDev" />
+                                    <attribute id="UUID" type="FixedString" value="28ac9ce2-2aba-8cda-b3b5-6e922f71b6b8" />
+                                    <attribute id="Version64" type="int64" value="36028797018963968" />
+                                </node>
+                                <node id="ModuleShortDesc">
+                                    <attribute id="Folder" type="LSString" value="MyMod" />
+                                    <attribute id="Name" type="LSString" value="My Mod" />
+                                    <attribute id="UUID" type="FixedString" value="11111111-2222-3333-4444-555555555555" />
+                                </node>
+                            </children>
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#;
+
+    /// A save laid out like the game's: metadata, world state and one level
+    /// cache, all LSF
+    fn save_fixture(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("QuickSave_12.lsv");
+        let lsf = |lsx: &str| convert_bytes(lsx.as_bytes(), "lsx", "lsf").unwrap();
+        let mut builder = PakBuilder::write_to(&path).unwrap();
+        let settings = CompressionSettings::default();
+        builder
+            .add_file(META_FILE, &lsf(SAVE_META_LSX), settings)
+            .unwrap();
+        builder
+            .add_file(GLOBALS_FILE, &lsf(CONFIG_LSX), settings)
+            .unwrap();
+        builder
+            .add_file("LevelCache/WLD_Main_A.lsf", &lsf(CONFIG_LSX), settings)
+            .unwrap();
+        builder.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_save_meta() {
+        let info = parse_save_meta(&parse_lsx(SAVE_META_LSX).unwrap());
+        assert_eq!(info.save_name.as_deref(), Some("QuickSave_12"));
+        assert_eq!(info.game_version, "4.7.1.3");
+        assert_eq!(info.leader_name.as_deref(), Some("Tav"));
+        assert_eq!(info.level_name.as_deref(), Some("WLD_Main_A"));
+        assert_eq!(info.mods.len(), 2);
+        assert_eq!(info.mods[1].name, "My Mod");
+        assert_eq!(info.mods[1].version64, None);
+    }
+
+    #[test]
+    fn test_read_save_fixture() {
+        let dir = tempfile::tempdir().unwrap();
+        let save = save_fixture(dir.path());
+        assert!(is_lsv(&save));
+
+        let mut files = list_lsv(&save).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            ["Globals.lsf", "LevelCache/WLD_Main_A.lsf", "meta.lsf"]
+        );
+
+        let info = read_save_info(&save).unwrap();
+        assert_eq!(info.save_name.as_deref(), Some("QuickSave_12"));
+        assert_eq!(info.mods.len(), 2);
+        assert_eq!(info.mods[0].uuid, "28ac9ce2-2aba-8cda-b3b5-6e922f71b6b8");
+
+        // Entry names match regardless of case
+        assert!(read_lsv_file(&save, "globals.LSF").is_ok());
+        assert!(matches!(
+            read_lsv_file(&save, "SaveInfo.json"),
+            Err(Error::FileNotFoundInPak(_))
+        ));
+
+        let out = dir.path().join("extracted");
+        extract_lsv(&save, &out).unwrap();
+        assert!(out.join("LevelCache/WLD_Main_A.lsf").is_file());
+    }
+
+    #[test]
+    fn test_is_lsv_needs_extension_and_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let save = save_fixture(dir.path());
+        let pak = dir.path().join("QuickSave_12.pak");
+        std::fs::copy(&save, &pak).unwrap();
+        let fake = dir.path().join("Fake.lsv");
+        std::fs::write(&fake, b"LSOF").unwrap();
+
+        assert!(is_lsv(&save));
+        assert!(!is_lsv(&pak));
+        assert!(!is_lsv(&fake));
+    }
+}
@@ -77,6 +77,23 @@ impl LsxDocument {
             self.major, self.minor, self.revision, self.build
         )
     }
+
+    /// Find the first node with the given ID in any region (depth-first)
+    #[must_use]
+    pub fn find_node(&self, id: &str) -> Option<&LsxNode> {
+        self.regions
+            .iter()
+            .flat_map(|r| &r.nodes)
+            .find_map(|n| n.find_node(id))
+    }
+
+    /// Find the first node with the given ID in any region (depth-first, mutable)
+    pub fn find_node_mut(&mut self, id: &str) -> Option<&mut LsxNode> {
+        self.regions
+            .iter_mut()
+            .flat_map(|r| &mut r.nodes)
+            .find_map(|n| n.find_node_mut(id))
+    }
 }
 
 impl LsxNode {
@@ -90,6 +107,40 @@ impl LsxNode {
             children: Vec::new(),
         }
     }
+
+    /// Find this node or the first descendant with the given ID (depth-first)
+    #[must_use]
+    pub fn find_node(&self, id: &str) -> Option<&LsxNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter().find_map(|c| c.find_node(id))
+    }
+
+    /// Find this node or the first descendant with the given ID (depth-first, mutable)
+    pub fn find_node_mut(&mut self, id: &str) -> Option<&mut LsxNode> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter_mut().find_map(|c| c.find_node_mut(id))
+    }
+
+    /// Get an attribute by ID
+    #[must_use]
+    pub fn attribute(&self, id: &str) -> Option<&LsxAttribute> {
+        self.attributes.iter().find(|a| a.id == id)
+    }
+
+    /// Get a mutable attribute by ID
+    pub fn attribute_mut(&mut self, id: &str) -> Option<&mut LsxAttribute> {
+        self.attributes.iter_mut().find(|a| a.id == id)
+    }
+
+    /// Get an attribute's value by ID
+    #[must_use]
+    pub fn attribute_value(&self, id: &str) -> Option<&str> {
+        self.attribute(id).map(|a| a.value.as_str())
+    }
 }
 
 impl LsxAttribute {
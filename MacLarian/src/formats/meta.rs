@@ -1,4 +1,9 @@
-//! Mod metadata parsing from meta.lsx files
+//! Mod metadata parsing from meta.lsx and meta.lsf files
+
+use super::lsf::LsfDocument;
+use super::lsx::parse_lsx;
+use crate::error::Result;
+use crate::mods::Version64;
 
 /// Full mod metadata extracted from meta.lsx
 #[non_exhaustive]
@@ -16,20 +21,29 @@ pub struct ModMetadata {
     pub description: String,
     /// Encoded version number (use `version_string()` to decode).
     pub version64: Option<i64>,
+    /// Mods listed in the `Dependencies` node.
+    pub dependencies: Vec<ModDependency>,
+}
+
+/// A dependency entry (`ModuleShortDesc`) from the meta.lsx `Dependencies` node
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModDependency {
+    /// Dependency mod UUID.
+    pub uuid: String,
+    /// Dependency display name.
+    pub name: String,
+    /// Dependency folder name.
+    pub folder: String,
+    /// Required version (encoded Version64), if specified.
+    pub version64: Option<i64>,
 }
 
 impl ModMetadata {
     /// Convert Version64 to version string (e.g., "1.0.0.0")
-    /// Version64 encoding: major << 55 | minor << 47 | revision << 31 | build
     #[must_use]
     pub fn version_string(&self) -> Option<String> {
-        self.version64.map(|v| {
-            let major = (v >> 55) & 0x7F;
-            let minor = (v >> 47) & 0xFF;
-            let revision = (v >> 31) & 0xFFFF;
-            let build = v & 0x7FFFFFFF;
-            format!("{major}.{minor}.{revision}.{build}")
-        })
+        self.version64.map(|v| Version64::from(v).to_string())
     }
 
     /// Check if this metadata is valid (has at minimum a UUID)
@@ -106,5 +120,73 @@ pub fn parse_meta_lsx(lsx_content: &str) -> ModMetadata {
         }
     }
 
+    metadata.dependencies = parse_meta_dependencies(lsx_content);
     metadata
 }
+
+/// Parse a binary meta.lsf to extract full mod metadata
+///
+/// Only the `ModuleInfo` and `Dependencies` nodes are read, without
+/// converting the file to LSX.
+///
+/// # Errors
+/// Returns an error if the data is not a valid LSF file.
+pub fn parse_meta_lsf(bytes: &[u8]) -> Result<ModMetadata> {
+    let doc = LsfDocument::open_lazy(bytes)?;
+    let find_node =
+        |name: &str| (0..doc.node_count()).find(|&idx| doc.node_name(idx) == Some(name));
+    let value = |node: usize, attr: &str| -> Result<String> {
+        Ok(doc.attribute_value(node, attr)?.unwrap_or_default())
+    };
+
+    let mut metadata = ModMetadata::default();
+    if let Some(info) = find_node("ModuleInfo") {
+        metadata.name = value(info, "Name")?;
+        metadata.folder = value(info, "Folder")?;
+        metadata.uuid = value(info, "UUID")?;
+        metadata.author = value(info, "Author")?;
+        metadata.description = value(info, "Description")?;
+        metadata.version64 = value(info, "Version64")?.parse().ok();
+    }
+
+    if let Some(deps) = find_node("Dependencies") {
+        for node in doc.find_children_by_name(deps, "ModuleShortDesc") {
+            let dependency = ModDependency {
+                uuid: value(node, "UUID")?,
+                name: value(node, "Name")?,
+                folder: value(node, "Folder")?,
+                version64: value(node, "Version64")?.parse().ok(),
+            };
+            if !dependency.uuid.is_empty() {
+                metadata.dependencies.push(dependency);
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Parse the `Dependencies` node of a meta.lsx file
+///
+/// Returns an empty list if the document can't be parsed or has no dependencies.
+#[must_use]
+pub fn parse_meta_dependencies(lsx_content: &str) -> Vec<ModDependency> {
+    let Ok(doc) = parse_lsx(lsx_content) else {
+        return Vec::new();
+    };
+    let Some(deps) = doc.find_node("Dependencies") else {
+        return Vec::new();
+    };
+
+    deps.children
+        .iter()
+        .filter(|n| n.id == "ModuleShortDesc")
+        .map(|n| ModDependency {
+            uuid: n.attribute_value("UUID").unwrap_or_default().to_string(),
+            name: n.attribute_value("Name").unwrap_or_default().to_string(),
+            folder: n.attribute_value("Folder").unwrap_or_default().to_string(),
+            version64: n.attribute_value("Version64").and_then(|v| v.parse().ok()),
+        })
+        .filter(|d| !d.uuid.is_empty())
+        .collect()
+}
@@ -3,23 +3,36 @@
 //! Note: `virtual_texture` has been promoted to a top-level module.
 //! It is re-exported here for backwards compatibility.
 
+pub mod banks;
 pub mod common;
+pub mod dialog;
 pub mod gr2;
 pub mod loca;
+pub mod lsb;
 pub mod lsf;
 pub mod lsj;
+pub mod lsv;
 pub mod lsx;
 pub mod meta;
+pub mod stats;
+pub mod wem;
 
 // Re-export common types for convenience
 pub use common::{TypeId, get_type_name, type_name_to_id};
 
 // Re-export main document types
+pub use banks::{BankDocument, MaterialResource, VisualResource};
 pub use loca::{LocaResource, LocalizedText, read_loca, write_loca};
+pub use lsb::{parse_lsb_bytes, read_lsb};
 pub use lsf::{LsfAttribute, LsfDocument, LsfNode};
 pub use lsj::{LsjAttribute, LsjDocument, LsjNode};
+pub use lsv::{SaveInfo, read_save_info};
 pub use lsx::{LsxAttribute, LsxDocument, LsxNode, LsxRegion};
-pub use meta::{ModMetadata, parse_meta_lsx};
+pub use meta::{
+    ModDependency, ModMetadata, parse_meta_dependencies, parse_meta_lsf, parse_meta_lsx,
+};
+pub use stats::{StatEntry, StatsDocument, TreasureTable, parse_stats, read_stats};
+pub use wem::{WemInfo, convert_wem, read_wem_info};
 
 // Re-export GR2 decompression utilities
 pub use gr2::decompress_gr2;
@@ -0,0 +1,94 @@
+//! Stats `.txt` structural checks
+
+use std::fmt;
+use std::path::Path;
+
+use super::parser::parse_stats_with_issues;
+use crate::error::Result;
+
+/// Kind of problem found in a stats file
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatsIssueKind {
+    /// The same `new <keyword> "Name"` block appears more than once.
+    DuplicateEntry,
+    /// The same `data` key appears twice in one entry (the last value wins).
+    DuplicateDataKey,
+    /// A `data` line appears before any `new entry` block.
+    DataOutsideEntry,
+    /// A `new subtable` header isn't `-N` or `amount,chance[;amount,chance...]`.
+    MalformedSubtableHeader,
+    /// A `new subtable` line appears outside a treasure table.
+    SubtableOutsideTreasureTable,
+    /// An `object category` line appears before any subtable.
+    ObjectOutsideSubtable,
+    /// A recognized line is missing quotes or values.
+    MalformedLine,
+    /// A line the parser doesn't recognize.
+    UnknownLine,
+}
+
+impl StatsIssueKind {
+    /// Whether this issue is only a warning (the game tolerates it)
+    #[must_use]
+    pub fn is_warning(self) -> bool {
+        matches!(self, Self::DuplicateDataKey | Self::UnknownLine)
+    }
+
+    /// Short human-readable label
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DuplicateEntry => "duplicate entry",
+            Self::DuplicateDataKey => "duplicate data key",
+            Self::DataOutsideEntry => "data outside entry",
+            Self::MalformedSubtableHeader => "malformed subtable header",
+            Self::SubtableOutsideTreasureTable => "subtable outside treasure table",
+            Self::ObjectOutsideSubtable => "object outside subtable",
+            Self::MalformedLine => "malformed line",
+            Self::UnknownLine => "unknown line",
+        }
+    }
+}
+
+/// A problem found while parsing a stats file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatsIssue {
+    /// 1-based line number.
+    pub line: usize,
+    /// Issue category.
+    pub kind: StatsIssueKind,
+    /// Offending line or description.
+    pub message: String,
+}
+
+impl fmt::Display for StatsIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {}",
+            self.line,
+            self.kind.as_str(),
+            self.message
+        )
+    }
+}
+
+/// Lint a stats file on disk
+///
+/// # Errors
+/// Returns [`Error::Io`] if the file cannot be read.
+///
+/// [`Error::Io`]: crate::Error::Io
+pub fn lint_stats<P: AsRef<Path>>(path: P) -> Result<Vec<StatsIssue>> {
+    let bytes = std::fs::read(path)?;
+    Ok(lint_stats_str(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Lint stats text, returning issues ordered by line
+#[must_use]
+pub fn lint_stats_str(text: &str) -> Vec<StatsIssue> {
+    let (_, mut issues) = parse_stats_with_issues(text);
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
@@ -0,0 +1,378 @@
+//! Stats `.txt` file format
+//!
+//! Plain-text data files found under `Public/<Mod>/Stats/Generated/`
+//! (`Data/Object.txt`, `Data/Armor.txt`, `TreasureTable.txt`, `ItemCombos.txt`, ...).
+//!
+//! Use [`parse_stats`] / [`read_stats`] to load a file into a [`StatsDocument`]
+//! and [`write_stats`] / [`serialize_stats`] to write it back. Block order and
+//! `data` key order are preserved, so writing a parsed document and parsing
+//! the output gives the same document. The text itself is normalized: blocks
+//! are separated by one blank line, comments before the first block or inside
+//! a treasure table are dropped, and other comments stay with the entry above
+//! them, after its `data` lines.
+//! [`lint_stats`] reports structural problems without failing the parse.
+//! [`TreasureIndex`] follows treasure table references across files.
+
+mod lint;
+mod parser;
+mod treasure;
+mod writer;
+
+use indexmap::IndexMap;
+
+pub use lint::{StatsIssue, StatsIssueKind, lint_stats, lint_stats_str};
+pub use parser::{parse_stats, parse_stats_with_issues, read_stats};
+pub use treasure::{
+    DropChain, ITEM_REFERENCE_PREFIX, ItemSources, ResolvedKind, ResolvedObject, ResolvedSubtable,
+    ResolvedTable, TABLE_REFERENCE_PREFIX, TableContents, TreasureIndex, TreasureLink,
+    drop_quantity,
+};
+pub use writer::{serialize_stats, write_stats};
+
+/// Keyword used by regular stat entries (`new entry "Name"`)
+pub const ENTRY_KEYWORD: &str = "entry";
+
+/// Keyword used by treasure tables (`new treasuretable "Name"`)
+pub const TREASURE_TABLE_KEYWORD: &str = "treasuretable";
+
+/// A parsed stats file
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatsDocument {
+    /// Blocks in file order.
+    pub blocks: Vec<StatsBlock>,
+}
+
+impl StatsDocument {
+    /// Iterate over all stat entries (`new entry`, `new ItemCombination`, ...)
+    pub fn entries(&self) -> impl Iterator<Item = &StatEntry> {
+        self.blocks.iter().filter_map(|b| match b {
+            StatsBlock::Entry(entry) => Some(entry),
+            StatsBlock::TreasureTable(_) => None,
+        })
+    }
+
+    /// Iterate over all treasure tables
+    pub fn treasure_tables(&self) -> impl Iterator<Item = &TreasureTable> {
+        self.blocks.iter().filter_map(|b| match b {
+            StatsBlock::TreasureTable(table) => Some(table),
+            StatsBlock::Entry(_) => None,
+        })
+    }
+
+    /// Find a stat entry by name
+    #[must_use]
+    pub fn entry(&self, name: &str) -> Option<&StatEntry> {
+        self.entries().find(|e| e.name == name)
+    }
+
+    /// Find a treasure table by name
+    #[must_use]
+    pub fn treasure_table(&self, name: &str) -> Option<&TreasureTable> {
+        self.treasure_tables().find(|t| t.name == name)
+    }
+}
+
+/// A top-level block in a stats file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsBlock {
+    /// A keyed entry with `type`, `using` and `data` lines.
+    Entry(StatEntry),
+    /// A `new treasuretable` block.
+    TreasureTable(TreasureTable),
+}
+
+/// A stat entry (`new entry "Name"` followed by `type`/`using`/`data` lines)
+///
+/// Other keyed blocks that use the same `data "Key" "Value"` layout, such as
+/// `new ItemCombination` in `ItemCombos.txt`, are stored here as well with
+/// their keyword preserved.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatEntry {
+    /// Entry name (the quoted value after `new entry`).
+    pub name: String,
+    /// Block keyword, `entry` for regular stat entries.
+    pub keyword: String,
+    /// Value of the `type` line (e.g. `Object`, `Armor`, `Weapon`).
+    pub entry_type: Option<String>,
+    /// Parent entry from the `using` line.
+    pub using: Option<String>,
+    /// `data "Key" "Value"` pairs in file order.
+    pub data: IndexMap<String, String>,
+    /// Lines inside the block that aren't `type`/`using`/`data` (kept verbatim).
+    pub extra_lines: Vec<String>,
+}
+
+impl StatEntry {
+    /// Create an empty `new entry` block
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::with_keyword(ENTRY_KEYWORD, name)
+    }
+
+    /// Create an empty block with a custom keyword (e.g. `ItemCombination`)
+    #[must_use]
+    pub fn with_keyword(keyword: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            keyword: keyword.into(),
+            entry_type: None,
+            using: None,
+            data: IndexMap::new(),
+            extra_lines: Vec::new(),
+        }
+    }
+
+    /// Get a `data` value by key
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.data.get(key).map(String::as_str)
+    }
+}
+
+/// A treasure table (`new treasuretable "Name"`)
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreasureTable {
+    /// Table name.
+    pub name: String,
+    /// Table-level properties before the first subtable (`CanMerge 1`, `MinLevel "5"`, ...).
+    pub properties: IndexMap<String, String>,
+    /// Subtables in file order.
+    pub subtables: Vec<TreasureSubtable>,
+}
+
+impl TreasureTable {
+    /// Create an empty treasure table
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            properties: IndexMap::new(),
+            subtables: Vec::new(),
+        }
+    }
+}
+
+/// A `new subtable "..."` block inside a treasure table
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreasureSubtable {
+    /// Raw header value (e.g. `1,1`, `-1`, `1,2;2,1`).
+    pub header: String,
+    /// Parsed drop rules from the header, empty if the header is malformed.
+    pub drops: Vec<SubtableDrop>,
+    /// Subtable properties (`StartLevel "1"`, `EndLevel "4"`, ...).
+    pub properties: IndexMap<String, String>,
+    /// Objects that can drop from this subtable.
+    pub objects: Vec<TreasureObject>,
+}
+
+impl TreasureSubtable {
+    /// Create a subtable from a header string
+    #[must_use]
+    pub fn new(header: impl Into<String>) -> Self {
+        let header = header.into();
+        let drops = parse_subtable_header(&header).unwrap_or_default();
+        Self {
+            header,
+            drops,
+            properties: IndexMap::new(),
+            objects: Vec::new(),
+        }
+    }
+}
+
+/// A single drop rule from a subtable header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubtableDrop {
+    /// `-N`: every object in the subtable drops, N times.
+    All(u32),
+    /// `amount,chance`: drop `amount` items with relative weight `chance`.
+    Weighted {
+        /// Number of items to roll.
+        amount: u32,
+        /// Relative weight of this option.
+        chance: u32,
+    },
+}
+
+/// An `object category "Name",freq,common,uncommon,rare,epic,legendary,divine,unique` line
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreasureObject {
+    /// Referenced item (`I_<entry>`) or nested treasure table / category name.
+    pub name: String,
+    /// Selection frequency within the subtable.
+    pub frequency: i32,
+    /// Per-rarity drop counts (common through unique), as written in the file.
+    pub rarity_counts: Vec<i32>,
+}
+
+impl TreasureObject {
+    /// Create an object reference with the standard `1,0,0,0,0,0,0,0` counts
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            frequency: 1,
+            rarity_counts: vec![0; 7],
+        }
+    }
+
+    /// The stat entry name if this references an item (`I_` prefix stripped)
+    #[must_use]
+    pub fn item_name(&self) -> Option<&str> {
+        self.name.strip_prefix("I_")
+    }
+}
+
+/// Parse a subtable header (`-1`, `1,1`, `1,2;2,1`)
+///
+/// Returns `None` if the header doesn't match either form.
+#[must_use]
+pub fn parse_subtable_header(header: &str) -> Option<Vec<SubtableDrop>> {
+    let header = header.trim();
+    if let Some(count) = header.strip_prefix('-') {
+        return count.parse().ok().map(|n| vec![SubtableDrop::All(n)]);
+    }
+
+    let mut drops = Vec::new();
+    for part in header.split(';') {
+        let (amount, chance) = part.split_once(',')?;
+        drops.push(SubtableDrop::Weighted {
+            amount: amount.trim().parse().ok()?,
+            chance: chance.trim().parse().ok()?,
+        });
+    }
+    (!drops.is_empty()).then_some(drops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SAMPLE: &str = r#"new entry "MyMod_Dye_Red"
+type "Object"
+using "_Dyes"
+data "RootTemplate" "1a750a66-e5c2-40be-9f62-0a4bf3ddb403"
+data "Weight" "0.01"
+
+new ItemCombination "MyMod_Dye_Red"
+data "Type 1" "Object"
+data "Object 1" "MyMod_Dye_Red"
+
+new treasuretable "MyMod_Dyes"
+CanMerge 1
+new subtable "1,1"
+object category "I_MyMod_Dye_Red",1,0,0,0,0,0,0,0
+new subtable "-1"
+StartLevel "1"
+object category "I_MyMod_DyePouch",1,0,0,0,0,0,0,0
+"#;
+
+    #[test]
+    fn test_parse_sample() {
+        let doc = parse_stats(SAMPLE);
+        assert_eq!(doc.blocks.len(), 3);
+
+        let entry = doc.entry("MyMod_Dye_Red").unwrap();
+        assert_eq!(entry.entry_type.as_deref(), Some("Object"));
+        assert_eq!(entry.using.as_deref(), Some("_Dyes"));
+        assert_eq!(entry.get("Weight"), Some("0.01"));
+        assert_eq!(
+            entry.data.keys().collect::<Vec<_>>(),
+            ["RootTemplate", "Weight"]
+        );
+
+        let table = doc.treasure_table("MyMod_Dyes").unwrap();
+        assert_eq!(
+            table.properties.get("CanMerge").map(String::as_str),
+            Some("1")
+        );
+        assert_eq!(table.subtables.len(), 2);
+        assert_eq!(
+            table.subtables[0].drops,
+            [SubtableDrop::Weighted {
+                amount: 1,
+                chance: 1
+            }]
+        );
+        assert_eq!(table.subtables[1].drops, [SubtableDrop::All(1)]);
+        assert_eq!(
+            table.subtables[1].objects[0].item_name(),
+            Some("MyMod_DyePouch")
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let doc = parse_stats(SAMPLE);
+        let written = serialize_stats(&doc);
+        assert_eq!(written, SAMPLE);
+        assert_eq!(parse_stats(&written), doc);
+    }
+
+    #[test]
+    fn test_roundtrip_normalizes_comments() {
+        let text = r#"// Dyes added by MyMod
+
+new entry "MyMod_Dye_Red"
+type "Object"
+// Inherit the dye defaults
+using "_Dyes"
+data "Weight" "0.01"
+
+
+// Treasure
+new treasuretable "MyMod_Dyes"
+// One dye per chest
+new subtable "1,1"
+object category "I_MyMod_Dye_Red",1,0,0,0,0,0,0,0
+"#;
+        let doc = parse_stats(text);
+        let written = serialize_stats(&doc);
+        assert_eq!(
+            written,
+            r#"new entry "MyMod_Dye_Red"
+type "Object"
+using "_Dyes"
+data "Weight" "0.01"
+// Inherit the dye defaults
+// Treasure
+
+new treasuretable "MyMod_Dyes"
+new subtable "1,1"
+object category "I_MyMod_Dye_Red",1,0,0,0,0,0,0,0
+"#
+        );
+        assert_eq!(parse_stats(&written), doc);
+        assert_eq!(serialize_stats(&parse_stats(&written)), written);
+    }
+
+    #[test]
+    fn test_lint() {
+        let text = r#"data "Orphan" "1"
+new entry "A"
+type "Object"
+new entry "A"
+type "Object"
+new treasuretable "T"
+new subtable "one"
+object category "I_A",1,0,0,0,0,0,0,0
+"#;
+        let issues = lint_stats_str(text);
+        let kinds: Vec<_> = issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                StatsIssueKind::DataOutsideEntry,
+                StatsIssueKind::DuplicateEntry,
+                StatsIssueKind::MalformedSubtableHeader,
+            ]
+        );
+        assert_eq!(issues[1].line, 4);
+    }
+}
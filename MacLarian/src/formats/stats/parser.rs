@@ -0,0 +1,237 @@
+//! Stats `.txt` parsing
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::lint::{StatsIssue, StatsIssueKind};
+use super::{
+    StatEntry, StatsBlock, StatsDocument, TREASURE_TABLE_KEYWORD, TreasureObject, TreasureSubtable,
+    TreasureTable, parse_subtable_header,
+};
+use crate::error::Result;
+
+/// Read and parse a stats file from disk
+///
+/// # Errors
+/// Returns [`Error::Io`] if the file cannot be read.
+///
+/// [`Error::Io`]: crate::Error::Io
+pub fn read_stats<P: AsRef<Path>>(path: P) -> Result<StatsDocument> {
+    let bytes = std::fs::read(path)?;
+    Ok(parse_stats(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Parse stats text into a document
+///
+/// Parsing is lenient: malformed lines are skipped. Use
+/// [`parse_stats_with_issues`] to also get the problems found.
+#[must_use]
+pub fn parse_stats(text: &str) -> StatsDocument {
+    parse_stats_with_issues(text).0
+}
+
+/// Parse stats text, returning the document and any structural issues found
+#[must_use]
+pub fn parse_stats_with_issues(text: &str) -> (StatsDocument, Vec<StatsIssue>) {
+    let mut parser = Parser::default();
+    for (index, raw) in text.lines().enumerate() {
+        parser.line(index + 1, raw.trim());
+    }
+    parser.flush();
+    (parser.doc, parser.issues)
+}
+
+#[derive(Default)]
+struct Parser {
+    doc: StatsDocument,
+    issues: Vec<StatsIssue>,
+    current: Option<StatsBlock>,
+    seen: HashSet<(String, String)>,
+}
+
+impl Parser {
+    fn line(&mut self, line: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        if let Some(rest) = text.strip_prefix("new ") {
+            let Some((keyword, name)) = split_keyword_value(rest) else {
+                self.issue(line, StatsIssueKind::MalformedLine, text);
+                return;
+            };
+
+            if keyword.eq_ignore_ascii_case("subtable") {
+                self.start_subtable(line, name);
+                return;
+            }
+
+            self.flush();
+            let key = (keyword.to_lowercase(), name.clone());
+            if !self.seen.insert(key) {
+                self.issue(
+                    line,
+                    StatsIssueKind::DuplicateEntry,
+                    format!("duplicate {keyword} \"{name}\""),
+                );
+            }
+
+            self.current = Some(if keyword.eq_ignore_ascii_case(TREASURE_TABLE_KEYWORD) {
+                StatsBlock::TreasureTable(TreasureTable::new(name))
+            } else {
+                StatsBlock::Entry(StatEntry::with_keyword(keyword, name))
+            });
+            return;
+        }
+
+        match self.current.as_mut() {
+            Some(StatsBlock::Entry(entry)) => {
+                if let Some(issue) = parse_entry_line(entry, text) {
+                    self.issue(line, issue, text);
+                }
+            }
+            Some(StatsBlock::TreasureTable(table)) => {
+                if let Some(issue) = parse_table_line(table, text) {
+                    self.issue(line, issue, text);
+                }
+            }
+            None if text.starts_with("//") => {}
+            None if text.starts_with("data ") => {
+                self.issue(line, StatsIssueKind::DataOutsideEntry, text);
+            }
+            None => self.issue(line, StatsIssueKind::UnknownLine, text),
+        }
+    }
+
+    fn start_subtable(&mut self, line: usize, header: String) {
+        let Some(StatsBlock::TreasureTable(table)) = self.current.as_mut() else {
+            self.issue(
+                line,
+                StatsIssueKind::SubtableOutsideTreasureTable,
+                format!("new subtable \"{header}\""),
+            );
+            return;
+        };
+
+        if parse_subtable_header(&header).is_none() {
+            self.issues.push(StatsIssue {
+                line,
+                kind: StatsIssueKind::MalformedSubtableHeader,
+                message: format!(
+                    "malformed subtable header \"{header}\" in \"{}\"",
+                    table.name
+                ),
+            });
+        }
+        table.subtables.push(TreasureSubtable::new(header));
+    }
+
+    fn flush(&mut self) {
+        if let Some(block) = self.current.take() {
+            self.doc.blocks.push(block);
+        }
+    }
+
+    fn issue(&mut self, line: usize, kind: StatsIssueKind, message: impl Into<String>) {
+        self.issues.push(StatsIssue {
+            line,
+            kind,
+            message: message.into(),
+        });
+    }
+}
+
+/// Parse a line inside an entry block
+fn parse_entry_line(entry: &mut StatEntry, text: &str) -> Option<StatsIssueKind> {
+    if let Some(rest) = text.strip_prefix("data ") {
+        let Some((key, rest)) = take_quoted(rest) else {
+            return Some(StatsIssueKind::MalformedLine);
+        };
+        let Some((value, _)) = take_quoted(rest.trim_start()) else {
+            return Some(StatsIssueKind::MalformedLine);
+        };
+        if entry.data.insert(key, value).is_some() {
+            return Some(StatsIssueKind::DuplicateDataKey);
+        }
+        return None;
+    }
+
+    if let Some(rest) = text.strip_prefix("type ") {
+        entry.entry_type = take_quoted(rest).map(|(v, _)| v);
+        return entry
+            .entry_type
+            .is_none()
+            .then_some(StatsIssueKind::MalformedLine);
+    }
+
+    if let Some(rest) = text.strip_prefix("using ") {
+        entry.using = take_quoted(rest).map(|(v, _)| v);
+        return entry
+            .using
+            .is_none()
+            .then_some(StatsIssueKind::MalformedLine);
+    }
+
+    entry.extra_lines.push(text.to_string());
+    (!text.starts_with("//")).then_some(StatsIssueKind::UnknownLine)
+}
+
+/// Parse a line inside a treasure table block
+fn parse_table_line(table: &mut TreasureTable, text: &str) -> Option<StatsIssueKind> {
+    if let Some(rest) = text.strip_prefix("object category ") {
+        let Some(object) = parse_object(rest) else {
+            return Some(StatsIssueKind::MalformedLine);
+        };
+        let Some(subtable) = table.subtables.last_mut() else {
+            return Some(StatsIssueKind::ObjectOutsideSubtable);
+        };
+        subtable.objects.push(object);
+        return None;
+    }
+
+    if text.starts_with("//") {
+        return None;
+    }
+
+    let Some((key, value)) = text.split_once(char::is_whitespace) else {
+        return Some(StatsIssueKind::UnknownLine);
+    };
+    let properties = match table.subtables.last_mut() {
+        Some(subtable) => &mut subtable.properties,
+        None => &mut table.properties,
+    };
+    properties.insert(key.to_string(), value.trim().to_string());
+    None
+}
+
+/// Parse the remainder of an `object category` line
+fn parse_object(rest: &str) -> Option<TreasureObject> {
+    let (name, rest) = take_quoted(rest)?;
+    let mut numbers = rest
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::parse::<i32>);
+
+    let frequency = numbers.next()?.ok()?;
+    let rarity_counts = numbers.collect::<std::result::Result<Vec<_>, _>>().ok()?;
+    Some(TreasureObject {
+        name,
+        frequency,
+        rarity_counts,
+    })
+}
+
+/// Split `keyword "value"` into its parts
+fn split_keyword_value(text: &str) -> Option<(&str, String)> {
+    let (keyword, rest) = text.split_once(char::is_whitespace)?;
+    let (value, _) = take_quoted(rest.trim_start())?;
+    Some((keyword, value))
+}
+
+/// Take a leading `"quoted"` value, returning it and the remaining text
+fn take_quoted(text: &str) -> Option<(String, &str)> {
+    let inner = text.strip_prefix('"')?;
+    let end = inner.find('"')?;
+    Some((inner[..end].to_string(), &inner[end + 1..]))
+}
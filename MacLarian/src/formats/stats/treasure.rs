@@ -0,0 +1,596 @@
+//! Treasure table analysis: what drops where
+//!
+//! [`TreasureIndex`] collects the treasure tables of many stats files, read
+//! from disk or from PAKs, and follows the `T_<table>` references between
+//! them. It answers both "which tables can produce this item"
+//! ([`TreasureIndex::sources_of`]) and "what can this table produce"
+//! ([`TreasureIndex::contents`]). Reference cycles are cut where they close
+//! and reported alongside the result.
+//!
+//! Tables are merged in the order they are added: a later table with the
+//! same name replaces the earlier one, unless it sets `CanMerge 1`, in which
+//! case its subtables are appended.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use walkdir::WalkDir;
+
+use super::{StatsDocument, SubtableDrop, TreasureSubtable, TreasureTable, parse_stats};
+use crate::error::Result;
+use crate::pak::PakOperations;
+
+/// Prefix of object names that reference another treasure table
+pub const TABLE_REFERENCE_PREFIX: &str = "T_";
+
+/// Prefix of object names that reference an item stat entry
+pub const ITEM_REFERENCE_PREFIX: &str = "I_";
+
+/// Drop chains collected before [`TreasureIndex::sources_of`] stops
+const MAX_CHAINS: usize = 10_000;
+
+/// Object name -> every table and subtable listing it, with the object's index
+type Parents<'a> = HashMap<&'a str, Vec<(&'a TreasureTable, &'a TreasureSubtable, usize)>>;
+
+/// Treasure tables from any number of stats files
+#[derive(Debug, Clone, Default)]
+pub struct TreasureIndex {
+    tables: HashMap<String, TreasureTable>,
+    files: usize,
+}
+
+/// One step of a drop chain: a table whose subtable lists an object
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreasureLink {
+    /// Table name
+    pub table: String,
+    /// Subtable header (`1,1`, `-1`, ...)
+    pub subtable: String,
+    /// Parsed drop rules of the subtable
+    pub drops: Vec<SubtableDrop>,
+    /// Object listed in the subtable (`I_<item>` or `T_<table>`)
+    pub object: String,
+    /// Frequency of the object in the subtable
+    pub frequency: i32,
+    /// Sum of the frequencies of every object in the subtable
+    pub total_frequency: i32,
+}
+
+/// Tables leading from a top-level table down to an item
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropChain {
+    /// Links from the outermost table to the one listing the item
+    pub links: Vec<TreasureLink>,
+}
+
+/// Result of [`TreasureIndex::sources_of`]
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemSources {
+    /// Item object name (`I_<item>`)
+    pub item: String,
+    /// Every chain of tables that can drop the item
+    pub chains: Vec<DropChain>,
+    /// Reference cycles met on the way, each as the tables in the loop
+    pub cycles: Vec<Vec<String>>,
+    /// Whether the search stopped after too many chains
+    pub truncated: bool,
+}
+
+/// A treasure table with its nested tables resolved
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedTable {
+    /// Table name
+    pub name: String,
+    /// Subtables in file order
+    pub subtables: Vec<ResolvedSubtable>,
+}
+
+/// A subtable with its objects resolved
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSubtable {
+    /// Subtable header (`1,1`, `-1`, ...)
+    pub header: String,
+    /// Parsed drop rules of the header
+    pub drops: Vec<SubtableDrop>,
+    /// Objects that can drop
+    pub objects: Vec<ResolvedObject>,
+}
+
+/// An object of a resolved subtable
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedObject {
+    /// Object name as written (`I_<item>`, `T_<table>` or a category)
+    pub name: String,
+    /// Frequency of the object in the subtable
+    pub frequency: i32,
+    /// Sum of the frequencies of every object in the subtable
+    pub total_frequency: i32,
+    /// What the name refers to
+    pub kind: ResolvedKind,
+}
+
+/// What a treasure object refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedKind {
+    /// An item stat entry
+    Item,
+    /// A nested table, resolved
+    Table(ResolvedTable),
+    /// A table already being resolved further up (a reference cycle)
+    Cycle,
+    /// A treasure category, or a table that isn't loaded
+    Category,
+}
+
+/// Result of [`TreasureIndex::contents`]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableContents {
+    /// The table, resolved
+    pub table: ResolvedTable,
+    /// Reference cycles met while resolving, each as the tables in the loop
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl TreasureIndex {
+    /// Create an empty index
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of distinct tables
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Whether no tables are loaded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Number of stats files that contained treasure tables
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.files
+    }
+
+    /// A table by name
+    #[must_use]
+    pub fn table(&self, name: &str) -> Option<&TreasureTable> {
+        self.tables.get(name)
+    }
+
+    /// Add the treasure tables of a parsed stats file
+    pub fn add_document(&mut self, doc: &StatsDocument) {
+        let mut added = false;
+        for table in doc.treasure_tables() {
+            added = true;
+            let merge = table
+                .properties
+                .get("CanMerge")
+                .is_some_and(|v| v.trim_matches('"') == "1");
+            match self.tables.get_mut(&table.name) {
+                Some(existing) if merge => {
+                    existing.subtables.extend(table.subtables.iter().cloned());
+                }
+                _ => {
+                    self.tables.insert(table.name.clone(), table.clone());
+                }
+            }
+        }
+        if added {
+            self.files += 1;
+        }
+    }
+
+    /// Add every stats `.txt` file under a `Stats` folder in a PAK
+    ///
+    /// Returns the number of files read.
+    ///
+    /// # Errors
+    /// Returns an error if the PAK cannot be read.
+    pub fn add_pak(&mut self, pak: &Path) -> Result<usize> {
+        let mut files: Vec<String> = PakOperations::list(pak)?
+            .into_iter()
+            .filter(|f| is_stats_file(f))
+            .collect();
+        files.sort();
+        let contents = PakOperations::read_files_bytes(pak, &files)?;
+        for file in &files {
+            if let Some(data) = contents.get(file) {
+                self.add_document(&parse_stats(&String::from_utf8_lossy(data)));
+            }
+        }
+        Ok(files.len())
+    }
+
+    /// Add every stats `.txt` file under a `Stats` folder in a directory
+    ///
+    /// Returns the number of files read.
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be walked or a file read.
+    pub fn add_dir(&mut self, dir: &Path) -> Result<usize> {
+        let mut count = 0;
+        for entry in WalkDir::new(dir).sort_by_file_name() {
+            let entry = entry?;
+            let path = entry.path();
+            let relative = path.strip_prefix(dir).unwrap_or(path);
+            if entry.file_type().is_file() && is_stats_file(&relative.to_string_lossy()) {
+                let data = std::fs::read(path)?;
+                self.add_document(&parse_stats(&String::from_utf8_lossy(&data)));
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Every chain of tables that can drop `item` (`I_MyItem` or `MyItem`)
+    ///
+    /// Chains run from a table that no other table references down to the
+    /// table listing the item. They are ordered by the order tables list
+    /// their objects, shortest first.
+    #[must_use]
+    pub fn sources_of(&self, item: &str) -> ItemSources {
+        let item = if item.starts_with(ITEM_REFERENCE_PREFIX) {
+            item.to_string()
+        } else {
+            format!("{ITEM_REFERENCE_PREFIX}{item}")
+        };
+
+        let mut parents = Parents::new();
+        for table in self.tables.values() {
+            for subtable in &table.subtables {
+                for (i, object) in subtable.objects.iter().enumerate() {
+                    parents
+                        .entry(object.name.as_str())
+                        .or_default()
+                        .push((table, subtable, i));
+                }
+            }
+        }
+        for list in parents.values_mut() {
+            list.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        }
+
+        let mut sources = ItemSources {
+            item: item.clone(),
+            ..ItemSources::default()
+        };
+        let mut walk = UpwardWalk {
+            parents: &parents,
+            path: Vec::new(),
+            visiting: Vec::new(),
+            sources: &mut sources,
+        };
+        walk.visit(&item);
+        sources.chains.sort_by_key(|chain| chain.links.len());
+        sources
+    }
+
+    /// `table` with its nested tables resolved, or `None` if it isn't loaded
+    ///
+    /// `T_<table>` and plain table names are both accepted.
+    #[must_use]
+    pub fn contents(&self, table: &str) -> Option<TableContents> {
+        let name = self.table_name(table)?;
+        let mut cycles = Vec::new();
+        let mut visiting = Vec::new();
+        let table = self.resolve(name, &mut visiting, &mut cycles);
+        Some(TableContents { table, cycles })
+    }
+
+    /// Name of the loaded table `reference` points to
+    fn table_name<'a>(&self, reference: &'a str) -> Option<&'a str> {
+        if self.tables.contains_key(reference) {
+            return Some(reference);
+        }
+        reference
+            .strip_prefix(TABLE_REFERENCE_PREFIX)
+            .filter(|name| self.tables.contains_key(*name))
+    }
+
+    fn resolve(
+        &self,
+        name: &str,
+        visiting: &mut Vec<String>,
+        cycles: &mut Vec<Vec<String>>,
+    ) -> ResolvedTable {
+        visiting.push(name.to_string());
+        let subtables = self.tables[name]
+            .subtables
+            .iter()
+            .map(|subtable| {
+                let total_frequency = subtable.objects.iter().map(|o| o.frequency).sum();
+                let objects = subtable
+                    .objects
+                    .iter()
+                    .map(|object| {
+                        let kind = if let Some(nested) = object
+                            .name
+                            .strip_prefix(TABLE_REFERENCE_PREFIX)
+                            .and_then(|_| self.table_name(&object.name))
+                        {
+                            if let Some(pos) = visiting.iter().position(|t| t == nested) {
+                                record_cycle(cycles, &visiting[pos..]);
+                                ResolvedKind::Cycle
+                            } else {
+                                ResolvedKind::Table(self.resolve(nested, visiting, cycles))
+                            }
+                        } else if object.name.starts_with(ITEM_REFERENCE_PREFIX) {
+                            ResolvedKind::Item
+                        } else {
+                            ResolvedKind::Category
+                        };
+                        ResolvedObject {
+                            name: object.name.clone(),
+                            frequency: object.frequency,
+                            total_frequency,
+                            kind,
+                        }
+                    })
+                    .collect();
+                ResolvedSubtable {
+                    header: subtable.header.clone(),
+                    drops: subtable.drops.clone(),
+                    objects,
+                }
+            })
+            .collect();
+        visiting.pop();
+        ResolvedTable {
+            name: name.to_string(),
+            subtables,
+        }
+    }
+}
+
+/// Depth-first walk from an object up to the tables that list it
+struct UpwardWalk<'a, 'b> {
+    parents: &'b Parents<'a>,
+    /// Links from the object being visited up towards the root (reversed)
+    path: Vec<TreasureLink>,
+    /// Tables on the current path
+    visiting: Vec<String>,
+    sources: &'b mut ItemSources,
+}
+
+impl UpwardWalk<'_, '_> {
+    fn visit(&mut self, object: &str) {
+        let mut extended = false;
+        for &(table, subtable, index) in self.parents.get(object).into_iter().flatten() {
+            if self.sources.chains.len() >= MAX_CHAINS {
+                self.sources.truncated = true;
+                return;
+            }
+            if let Some(pos) = self.visiting.iter().position(|t| *t == table.name) {
+                record_cycle(&mut self.sources.cycles, &self.visiting[pos..]);
+                continue;
+            }
+
+            let object = &subtable.objects[index];
+            self.path.push(TreasureLink {
+                table: table.name.clone(),
+                subtable: subtable.header.clone(),
+                drops: subtable.drops.clone(),
+                object: object.name.clone(),
+                frequency: object.frequency,
+                total_frequency: subtable.objects.iter().map(|o| o.frequency).sum(),
+            });
+            self.visiting.push(table.name.clone());
+            self.visit(&format!("{TABLE_REFERENCE_PREFIX}{}", table.name));
+            self.visiting.pop();
+            self.path.pop();
+            extended = true;
+        }
+
+        // Nothing (else) lists this table: the path so far is a full chain
+        if !extended && !self.path.is_empty() {
+            self.sources.chains.push(DropChain {
+                links: self.path.iter().rev().cloned().collect(),
+            });
+        }
+    }
+}
+
+/// Remember a cycle (the tables from where it closes to the current one)
+fn record_cycle(cycles: &mut Vec<Vec<String>>, tables: &[String]) {
+    let cycle = tables.to_vec();
+    if !cycles.contains(&cycle) {
+        cycles.push(cycle);
+    }
+}
+
+/// Whether a path is a stats `.txt` file (inside a `Stats` folder)
+fn is_stats_file(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    path.to_ascii_lowercase().ends_with(".txt")
+        && path
+            .split('/')
+            .any(|part| part.eq_ignore_ascii_case("Stats"))
+}
+
+impl TreasureLink {
+    /// How likely one roll of the subtable picks the object
+    /// (`1/3`, or `always` for `-N` subtables)
+    #[must_use]
+    pub fn odds(&self) -> String {
+        odds(&self.drops, self.frequency, self.total_frequency)
+    }
+}
+
+impl ResolvedSubtable {
+    /// How likely one roll of the subtable picks `object`
+    /// (`1/3`, or `always` for `-N` subtables)
+    #[must_use]
+    pub fn odds(&self, object: &ResolvedObject) -> String {
+        odds(&self.drops, object.frequency, object.total_frequency)
+    }
+}
+
+impl ResolvedTable {
+    /// Distinct items the table can drop, directly or through nested tables
+    #[must_use]
+    pub fn items(&self) -> Vec<&str> {
+        let mut items = Vec::new();
+        self.collect_items(&mut items);
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+
+    fn collect_items<'a>(&'a self, items: &mut Vec<&'a str>) {
+        for object in self.subtables.iter().flat_map(|s| &s.objects) {
+            match &object.kind {
+                ResolvedKind::Item => items.push(&object.name),
+                ResolvedKind::Table(table) => table.collect_items(items),
+                ResolvedKind::Cycle | ResolvedKind::Category => {}
+            }
+        }
+    }
+}
+
+fn odds(drops: &[SubtableDrop], frequency: i32, total: i32) -> String {
+    if drops.iter().any(|d| matches!(d, SubtableDrop::All(_))) {
+        "always".to_string()
+    } else {
+        format!("{frequency}/{total}")
+    }
+}
+
+/// Number of items a subtable drops, from its header (`×1`, `×1-2`, `all ×2`)
+#[must_use]
+pub fn drop_quantity(drops: &[SubtableDrop]) -> String {
+    let mut amounts: Vec<u32> = Vec::new();
+    for drop in drops {
+        match drop {
+            SubtableDrop::All(n) => return format!("all ×{n}"),
+            SubtableDrop::Weighted { amount, chance } if *chance > 0 => amounts.push(*amount),
+            SubtableDrop::Weighted { .. } => {}
+        }
+    }
+    match (amounts.iter().min(), amounts.iter().max()) {
+        (Some(min), Some(max)) if min == max => format!("×{min}"),
+        (Some(min), Some(max)) => format!("×{min}-{max}"),
+        _ => "×0".to_string(),
+    }
+}
+
+impl fmt::Display for DropChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, link) in self.links.iter().enumerate() {
+            if i > 0 {
+                write!(f, " > ")?;
+            }
+            write!(
+                f,
+                "{} [{} {}, {}]",
+                link.table,
+                link.subtable,
+                drop_quantity(&link.drops),
+                link.odds()
+            )?;
+        }
+        if let Some(last) = self.links.last() {
+            write!(f, " > {}", last.object)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TABLES: &str = r#"new treasuretable "TUT_Chest_Potions"
+new subtable "-1"
+object category "T_Potions_Common",1,0,0,0,0,0,0,0
+new subtable "1,1;2,1"
+object category "I_POT_Healing",2,0,0,0,0,0,0,0
+object category "Gold",1,0,0,0,0,0,0,0
+
+new treasuretable "Potions_Common"
+new subtable "1,1"
+object category "I_POT_Healing",1,0,0,0,0,0,0,0
+object category "I_MyItem",1,0,0,0,0,0,0,0
+object category "T_Loop_A",1,0,0,0,0,0,0,0
+
+new treasuretable "Loop_A"
+new subtable "1,1"
+object category "T_Loop_B",1,0,0,0,0,0,0,0
+
+new treasuretable "Loop_B"
+new subtable "1,1"
+object category "T_Loop_A",1,0,0,0,0,0,0,0
+object category "I_MyItem",1,0,0,0,0,0,0,0
+"#;
+
+    const MOD_TABLES: &str = r#"new treasuretable "TUT_Chest_Potions"
+CanMerge 1
+new subtable "1,1"
+object category "I_MyItem",1,0,0,0,0,0,0,0
+"#;
+
+    fn index() -> TreasureIndex {
+        let mut index = TreasureIndex::new();
+        index.add_document(&parse_stats(TABLES));
+        index.add_document(&parse_stats(MOD_TABLES));
+        index
+    }
+
+    #[test]
+    fn test_sources_of_item() {
+        let index = index();
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.file_count(), 2);
+
+        let sources = index.sources_of("MyItem");
+        let chains: Vec<String> = sources.chains.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            chains,
+            [
+                "TUT_Chest_Potions [1,1 ×1, 1/1] > I_MyItem",
+                "TUT_Chest_Potions [-1 all ×1, always] > Potions_Common [1,1 ×1, 1/3] > I_MyItem",
+                "TUT_Chest_Potions [-1 all ×1, always] > Potions_Common [1,1 ×1, 1/3] \
+                 > Loop_A [1,1 ×1, 1/1] > Loop_B [1,1 ×1, 1/2] > I_MyItem",
+            ]
+        );
+        assert_eq!(sources.cycles.len(), 1);
+        assert!(!sources.truncated);
+        assert!(index.sources_of("I_Missing").chains.is_empty());
+    }
+
+    #[test]
+    fn test_table_contents() {
+        let index = index();
+        let contents = index.contents("T_TUT_Chest_Potions").unwrap();
+        assert_eq!(contents.table.subtables.len(), 3);
+        assert_eq!(contents.table.items(), ["I_MyItem", "I_POT_Healing"]);
+        assert_eq!(
+            contents.cycles,
+            [vec!["Loop_A".to_string(), "Loop_B".to_string()]]
+        );
+
+        let rolled = &contents.table.subtables[1];
+        assert_eq!(rolled.odds(&rolled.objects[0]), "2/3");
+        let always = &contents.table.subtables[0];
+        assert_eq!(always.odds(&always.objects[0]), "always");
+        assert_eq!(drop_quantity(&contents.table.subtables[1].drops), "×1-2");
+        assert_eq!(
+            contents.table.subtables[1].objects[1].kind,
+            ResolvedKind::Category
+        );
+        assert!(index.contents("Missing").is_none());
+    }
+}
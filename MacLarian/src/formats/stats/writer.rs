@@ -0,0 +1,74 @@
+//! Stats `.txt` serialization
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::{StatEntry, StatsBlock, StatsDocument, TreasureTable};
+use crate::error::Result;
+
+/// Write a stats document to disk
+///
+/// # Errors
+/// Returns an error if file writing fails.
+pub fn write_stats<P: AsRef<Path>>(path: P, doc: &StatsDocument) -> Result<()> {
+    std::fs::write(path, serialize_stats(doc))?;
+    Ok(())
+}
+
+/// Serialize a stats document to text
+///
+/// Blocks are separated by a blank line and written in document order.
+#[must_use]
+pub fn serialize_stats(doc: &StatsDocument) -> String {
+    let blocks: Vec<String> = doc
+        .blocks
+        .iter()
+        .map(|block| match block {
+            StatsBlock::Entry(entry) => serialize_entry(entry),
+            StatsBlock::TreasureTable(table) => serialize_treasure_table(table),
+        })
+        .collect();
+    blocks.join("\n")
+}
+
+fn serialize_entry(entry: &StatEntry) -> String {
+    let mut out = format!("new {} \"{}\"\n", entry.keyword, entry.name);
+    if let Some(ref entry_type) = entry.entry_type {
+        let _ = writeln!(out, "type \"{entry_type}\"");
+    }
+    if let Some(ref using) = entry.using {
+        let _ = writeln!(out, "using \"{using}\"");
+    }
+    for (key, value) in &entry.data {
+        let _ = writeln!(out, "data \"{key}\" \"{value}\"");
+    }
+    for line in &entry.extra_lines {
+        let _ = writeln!(out, "{line}");
+    }
+    out
+}
+
+fn serialize_treasure_table(table: &TreasureTable) -> String {
+    let mut out = format!("new treasuretable \"{}\"\n", table.name);
+    for (key, value) in &table.properties {
+        let _ = writeln!(out, "{key} {value}");
+    }
+    for subtable in &table.subtables {
+        let _ = writeln!(out, "new subtable \"{}\"", subtable.header);
+        for (key, value) in &subtable.properties {
+            let _ = writeln!(out, "{key} {value}");
+        }
+        for object in &subtable.objects {
+            let _ = write!(
+                out,
+                "object category \"{}\",{}",
+                object.name, object.frequency
+            );
+            for count in &object.rarity_counts {
+                let _ = write!(out, ",{count}");
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
@@ -0,0 +1,190 @@
+//! LSB-first bit reader and writer, the bit order used by Vorbis packets
+
+use crate::error::{Error, Result};
+
+/// Reads values LSB-first from a byte slice
+pub(super) struct BitReader<'a> {
+    data: &'a [u8],
+    bit_pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data, bit_pos: 0 }
+    }
+
+    /// Read `bits` (at most 32) bits
+    pub(super) fn read(&mut self, bits: u32) -> Result<u32> {
+        debug_assert!(bits <= 32);
+        if self.bit_pos + bits as usize > self.data.len() * 8 {
+            return Err(Error::InvalidWem {
+                message: "Vorbis packet ends early".to_string(),
+            });
+        }
+        let mut value = 0u32;
+        for i in 0..bits {
+            let byte = self.data[self.bit_pos / 8];
+            if (byte >> (self.bit_pos % 8)) & 1 != 0 {
+                value |= 1 << i;
+            }
+            self.bit_pos += 1;
+        }
+        Ok(value)
+    }
+
+    pub(super) fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read(1)? != 0)
+    }
+
+    /// Number of bits read so far
+    pub(super) fn bits_read(&self) -> usize {
+        self.bit_pos
+    }
+}
+
+/// Writes values LSB-first into a growing byte buffer
+#[derive(Default)]
+pub(super) struct BitWriter {
+    data: Vec<u8>,
+    bit_pos: usize,
+}
+
+impl BitWriter {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the low `bits` (at most 32) bits of `value`
+    pub(super) fn write(&mut self, value: u32, bits: u32) {
+        debug_assert!(bits <= 32);
+        for i in 0..bits {
+            if self.bit_pos % 8 == 0 {
+                self.data.push(0);
+            }
+            if (value >> i) & 1 != 0 {
+                let last = self.data.len() - 1;
+                self.data[last] |= 1 << (self.bit_pos % 8);
+            }
+            self.bit_pos += 1;
+        }
+    }
+
+    pub(super) fn write_bool(&mut self, value: bool) {
+        self.write(u32::from(value), 1);
+    }
+
+    pub(super) fn write_bytes(&mut self, bytes: &[u8]) {
+        if self.bit_pos % 8 == 0 {
+            self.data.extend_from_slice(bytes);
+            self.bit_pos += bytes.len() * 8;
+        } else {
+            for &b in bytes {
+                self.write(u32::from(b), 8);
+            }
+        }
+    }
+
+    /// The written bytes, with the last byte zero-padded
+    pub(super) fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Number of bits needed to store `value` (Vorbis `ilog`)
+pub(super) fn ilog(value: u32) -> u32 {
+    32 - value.leading_zeros()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_are_lsb_first() {
+        let mut writer = BitWriter::new();
+        writer.write_bool(true);
+        writer.write(0b10, 2);
+        writer.write(0b1_0110, 5);
+        writer.write(0x3, 4);
+        let bytes = writer.into_bytes();
+        // 1, then 0b10, then 0b10110 fill the first byte from the bottom up;
+        // the last field starts a zero-padded second byte
+        assert_eq!(bytes, vec![0b1011_0101, 0b0000_0011]);
+
+        let mut reader = BitReader::new(&bytes);
+        assert!(reader.read_bool().unwrap());
+        assert_eq!(reader.read(2).unwrap(), 0b10);
+        assert_eq!(reader.read(5).unwrap(), 0b1_0110);
+        assert_eq!(reader.bits_read(), 8);
+        assert_eq!(reader.read(4).unwrap(), 0x3);
+        assert_eq!(reader.bits_read(), 12);
+    }
+
+    #[test]
+    fn test_round_trip_widths() {
+        let fields = [
+            (0u32, 0u32),
+            (1, 1),
+            (0x5, 3),
+            (0x1ABC, 14),
+            (0x56_43_42, 24),
+            (0xDEAD_BEEF, 32),
+            (u32::MAX, 32),
+        ];
+        let mut writer = BitWriter::new();
+        for &(value, bits) in &fields {
+            writer.write(value, bits);
+        }
+        let total: u32 = fields.iter().map(|&(_, bits)| bits).sum();
+        let bytes = writer.into_bytes();
+        assert_eq!(bytes.len(), total.div_ceil(8) as usize);
+
+        let mut reader = BitReader::new(&bytes);
+        for &(value, bits) in &fields {
+            assert_eq!(reader.read(bits).unwrap(), value, "{bits}-bit field");
+        }
+    }
+
+    #[test]
+    fn test_write_bytes_aligned_and_unaligned() {
+        let mut aligned = BitWriter::new();
+        aligned.write(0x01, 8);
+        aligned.write_bytes(b"vorbis");
+        assert_eq!(aligned.into_bytes(), b"\x01vorbis");
+
+        let mut unaligned = BitWriter::new();
+        unaligned.write_bool(true);
+        unaligned.write_bytes(&[0xFF, 0x00]);
+        unaligned.write_bool(true);
+        let bytes = unaligned.into_bytes();
+        assert_eq!(bytes, vec![0xFF, 0x01, 0x02]);
+
+        let mut reader = BitReader::new(&bytes);
+        assert!(reader.read_bool().unwrap());
+        assert_eq!(reader.read(8).unwrap(), 0xFF);
+        assert_eq!(reader.read(8).unwrap(), 0x00);
+        assert!(reader.read_bool().unwrap());
+    }
+
+    #[test]
+    fn test_read_past_end_fails() {
+        let mut reader = BitReader::new(&[0xAB]);
+        assert_eq!(reader.read(4).unwrap(), 0xB);
+        assert!(matches!(reader.read(5), Err(Error::InvalidWem { .. })));
+        // A failed read consumes nothing
+        assert_eq!(reader.bits_read(), 4);
+        assert_eq!(reader.read(4).unwrap(), 0xA);
+        assert!(reader.read_bool().is_err());
+    }
+
+    #[test]
+    fn test_ilog() {
+        assert_eq!(ilog(0), 0);
+        assert_eq!(ilog(1), 1);
+        assert_eq!(ilog(2), 2);
+        assert_eq!(ilog(3), 2);
+        assert_eq!(ilog(7), 3);
+        assert_eq!(ilog(8), 4);
+        assert_eq!(ilog(u32::MAX), 32);
+    }
+}
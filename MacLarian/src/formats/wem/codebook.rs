@@ -0,0 +1,456 @@
+//! Wwise packed Vorbis codebooks
+//!
+//! Wwise strips the codebooks from the Vorbis setup header. Most files only
+//! store a 10-bit id into a shared library of packed codebooks (the
+//! `packed_codebooks_aoTuV_603.bin` file distributed with ww2ogg); some
+//! store the packed codebooks inline. Either way each one has to be expanded
+//! back into a standard Vorbis codebook.
+
+use std::path::Path;
+
+use super::bits::{BitReader, BitWriter, ilog};
+use crate::error::{Error, Result};
+
+/// File name of the codebook library most Wwise Vorbis files need
+pub const CODEBOOK_LIBRARY_NAME: &str = "packed_codebooks_aoTuV_603.bin";
+
+/// Where the packed codebooks of a Wwise Vorbis file come from
+#[derive(Debug, Clone)]
+pub struct CodebookLibrary {
+    source: Source,
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+    /// Codebooks are stored in the setup packet itself
+    Inline,
+    /// Shared library: codebook data followed by a table of offsets
+    Packed { data: Vec<u8>, offsets: Vec<usize> },
+}
+
+impl CodebookLibrary {
+    /// Library for files that store their codebooks inline
+    #[must_use]
+    pub fn inline() -> Self {
+        Self {
+            source: Source::Inline,
+        }
+    }
+
+    /// Load a packed codebook library file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a codebook library.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(Error::FileNotFound {
+                path: path.to_path_buf(),
+            });
+        }
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Parse a packed codebook library
+    ///
+    /// The last 4 bytes give the offset of a table of `u32` codebook offsets;
+    /// the table's final entry marks the end of the last codebook.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidWem`] if the offset table is out of range.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let invalid = || Error::InvalidWem {
+            message: "invalid packed codebook library".to_string(),
+        };
+        if data.len() < 4 {
+            return Err(invalid());
+        }
+        let tail: [u8; 4] = data[data.len() - 4..].try_into().map_err(|_| invalid())?;
+        let table_offset = u32::from_le_bytes(tail) as usize;
+        if table_offset > data.len() - 4 || (data.len() - table_offset) % 4 != 0 {
+            return Err(invalid());
+        }
+
+        let offsets: Vec<usize> = data[table_offset..]
+            .chunks_exact(4)
+            .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]) as usize)
+            .collect();
+        if offsets.windows(2).any(|w| w[0] > w[1]) || offsets.iter().any(|&o| o > table_offset) {
+            return Err(invalid());
+        }
+
+        let mut data = data;
+        data.truncate(table_offset);
+        Ok(Self {
+            source: Source::Packed { data, offsets },
+        })
+    }
+
+    /// Whether codebooks are read from the setup packet
+    pub(super) fn is_inline(&self) -> bool {
+        matches!(self.source, Source::Inline)
+    }
+
+    /// Number of codebooks in a packed library
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match &self.source {
+            Source::Inline => 0,
+            Source::Packed { offsets, .. } => offsets.len().saturating_sub(1),
+        }
+    }
+
+    /// Whether the library holds no packed codebooks
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Expand the codebook with the given id into `out`
+    pub(super) fn rebuild_id(&self, id: u32, out: &mut BitWriter) -> Result<()> {
+        let Source::Packed { data, offsets } = &self.source else {
+            return Err(Error::InvalidWem {
+                message: "codebook library not loaded".to_string(),
+            });
+        };
+        let id = id as usize;
+        if id + 1 >= offsets.len() {
+            return Err(Error::InvalidWem {
+                message: format!(
+                    "codebook id {id} is not in the library ({} codebooks)",
+                    self.len()
+                ),
+            });
+        }
+        let packed = &data[offsets[id]..offsets[id + 1]];
+        let mut reader = BitReader::new(packed);
+        rebuild(&mut reader, out)?;
+
+        // All bits but the final byte's padding should be used
+        if reader.bits_read() / 8 + 1 != packed.len() {
+            return Err(Error::InvalidWem {
+                message: format!("codebook {id} size mismatch"),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Expand one packed codebook from `input` into a standard Vorbis codebook
+pub(super) fn rebuild(input: &mut BitReader, out: &mut BitWriter) -> Result<()> {
+    let dimensions = input.read(4)?;
+    let entries = input.read(14)?;
+
+    // Sync pattern "BCV", then the fields at full width
+    out.write(0x56_43_42, 24);
+    out.write(dimensions, 16);
+    out.write(entries, 24);
+
+    let ordered = input.read_bool()?;
+    out.write_bool(ordered);
+    if ordered {
+        let initial_length = input.read(5)?;
+        out.write(initial_length, 5);
+
+        let mut current = 0;
+        while current < entries {
+            let bits = ilog(entries - current);
+            let count = input.read(bits)?;
+            out.write(count, bits);
+            current += count;
+        }
+        if current > entries {
+            return Err(Error::InvalidWem {
+                message: "codebook entry count out of range".to_string(),
+            });
+        }
+    } else {
+        // Packed lengths use a variable width instead of 5 bits
+        let length_bits = input.read(3)?;
+        let sparse = input.read_bool()?;
+        if length_bits == 0 || length_bits > 5 {
+            return Err(Error::InvalidWem {
+                message: "invalid codebook codeword length width".to_string(),
+            });
+        }
+        out.write_bool(sparse);
+
+        for _ in 0..entries {
+            let present = if sparse {
+                let present = input.read_bool()?;
+                out.write_bool(present);
+                present
+            } else {
+                true
+            };
+            if present {
+                out.write(input.read(length_bits)?, 5);
+            }
+        }
+    }
+
+    // Lookup type is stored in 1 bit instead of 4
+    let lookup_type = input.read(1)?;
+    out.write(lookup_type, 4);
+    if lookup_type == 1 {
+        let min = input.read(32)?;
+        let max = input.read(32)?;
+        let value_length = input.read(4)?;
+        let sequence = input.read_bool()?;
+        out.write(min, 32);
+        out.write(max, 32);
+        out.write(value_length, 4);
+        out.write_bool(sequence);
+
+        for _ in 0..maptype1_quantvals(entries, dimensions) {
+            out.write(input.read(value_length + 1)?, value_length + 1);
+        }
+    }
+    Ok(())
+}
+
+/// Number of quantized values of a lookup type 1 codebook: the largest
+/// `vals` with `vals^dimensions <= entries`
+fn maptype1_quantvals(entries: u32, dimensions: u32) -> u32 {
+    if dimensions == 0 {
+        return 0;
+    }
+    let bits = ilog(entries);
+    let mut vals = entries >> ((bits.saturating_sub(1)) * (dimensions - 1) / dimensions);
+    loop {
+        let mut acc: u64 = 1;
+        let mut acc1: u64 = 1;
+        for _ in 0..dimensions {
+            acc = acc.saturating_mul(u64::from(vals));
+            acc1 = acc1.saturating_mul(u64::from(vals) + 1);
+        }
+        if acc <= u64::from(entries) && acc1 > u64::from(entries) {
+            return vals;
+        }
+        if acc > u64::from(entries) {
+            vals -= 1;
+        } else {
+            vals += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pack `(value, bits)` fields into bytes
+    fn pack(fields: &[(u32, u32)]) -> Vec<u8> {
+        let mut writer = BitWriter::new();
+        for &(value, bits) in fields {
+            writer.write(value, bits);
+        }
+        writer.into_bytes()
+    }
+
+    /// Read back fields of the given widths
+    fn unpack(bytes: &[u8], widths: &[u32]) -> Vec<u32> {
+        let mut reader = BitReader::new(bytes);
+        widths
+            .iter()
+            .map(|&bits| reader.read(bits).unwrap())
+            .collect()
+    }
+
+    fn rebuild_bytes(packed: &[u8]) -> Result<Vec<u8>> {
+        let mut out = BitWriter::new();
+        rebuild(&mut BitReader::new(packed), &mut out)?;
+        Ok(out.into_bytes())
+    }
+
+    /// 1 dimension, 2 entries of length 1, no lookup (26 bits)
+    fn unordered_codebook() -> Vec<u8> {
+        pack(&[
+            (1, 4),
+            (2, 14),
+            (0, 1),
+            (1, 3),
+            (0, 1),
+            (0, 1),
+            (0, 1),
+            (0, 1),
+        ])
+    }
+
+    /// 2 dimensions, 5 ordered entries, lookup type 1 (105 bits)
+    fn ordered_codebook() -> Vec<u8> {
+        pack(&[
+            (2, 4),
+            (5, 14),
+            (1, 1),
+            (1, 5),
+            (2, 3),
+            (3, 2),
+            (1, 1),
+            (0x6000_0000, 32),
+            (0x6080_0000, 32),
+            (2, 4),
+            (0, 1),
+            (5, 3),
+            (3, 3),
+        ])
+    }
+
+    #[test]
+    fn test_rebuild_unordered() {
+        let out = rebuild_bytes(&unordered_codebook()).unwrap();
+        // Sync, dimensions, entries, ordered, sparse, two 5-bit lengths,
+        // 4-bit lookup type
+        let widths = [24, 16, 24, 1, 1, 5, 5, 4];
+        assert_eq!(unpack(&out, &widths), vec![0x56_43_42, 1, 2, 0, 0, 0, 0, 0]);
+        assert_eq!(out.len(), 80usize.div_ceil(8));
+    }
+
+    #[test]
+    fn test_rebuild_sparse() {
+        // Entry 1 is absent, so only entries 0 and 2 carry a length
+        let packed = pack(&[
+            (1, 4),
+            (3, 14),
+            (0, 1),
+            (2, 3),
+            (1, 1),
+            (1, 1),
+            (3, 2),
+            (0, 1),
+            (1, 1),
+            (1, 2),
+            (0, 1),
+        ]);
+        let out = rebuild_bytes(&packed).unwrap();
+        let widths = [24, 16, 24, 1, 1, 1, 5, 1, 1, 5, 4];
+        assert_eq!(
+            unpack(&out, &widths),
+            vec![0x56_43_42, 1, 3, 0, 1, 1, 3, 0, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_rebuild_ordered_with_lookup() {
+        let out = rebuild_bytes(&ordered_codebook()).unwrap();
+        // Ordered runs keep their widths; the lookup type widens to 4 bits
+        // and maptype1_quantvals(5, 2) = 2 values follow
+        let widths = [24, 16, 24, 1, 5, 3, 2, 4, 32, 32, 4, 1, 3, 3];
+        assert_eq!(
+            unpack(&out, &widths),
+            vec![
+                0x56_43_42,
+                2,
+                5,
+                1,
+                1,
+                2,
+                3,
+                1,
+                0x6000_0000,
+                0x6080_0000,
+                2,
+                0,
+                5,
+                3
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rebuild_rejects_bad_codebooks() {
+        // Ordered run of 5 entries in a 4-entry codebook
+        let overrun = pack(&[(1, 4), (4, 14), (1, 1), (0, 5), (5, 3)]);
+        assert!(matches!(
+            rebuild_bytes(&overrun),
+            Err(Error::InvalidWem { .. })
+        ));
+
+        // Codeword lengths zero bits wide
+        let no_width = pack(&[(1, 4), (2, 14), (0, 1), (0, 3), (0, 1)]);
+        assert!(rebuild_bytes(&no_width).is_err());
+
+        // Truncated
+        assert!(rebuild_bytes(&unordered_codebook()[..2]).is_err());
+    }
+
+    #[test]
+    fn test_maptype1_quantvals() {
+        assert_eq!(maptype1_quantvals(5, 2), 2);
+        assert_eq!(maptype1_quantvals(9, 2), 3);
+        assert_eq!(maptype1_quantvals(100, 2), 10);
+        assert_eq!(maptype1_quantvals(81, 4), 3);
+        assert_eq!(maptype1_quantvals(80, 4), 2);
+        assert_eq!(maptype1_quantvals(8, 3), 2);
+        assert_eq!(maptype1_quantvals(1, 1), 1);
+        assert_eq!(maptype1_quantvals(300, 1), 300);
+        assert_eq!(maptype1_quantvals(10, 0), 0);
+    }
+
+    /// A library file holding `codebooks`, laid out like the ww2ogg one
+    fn library_bytes(codebooks: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut offsets = Vec::new();
+        for codebook in codebooks {
+            offsets.push(data.len() as u32);
+            data.extend_from_slice(codebook);
+        }
+        // The final table entry is both the end of the last codebook and the
+        // trailing table offset
+        let table_offset = data.len() as u32;
+        offsets.push(table_offset);
+        for offset in offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_library_rebuilds_by_id() {
+        let library =
+            CodebookLibrary::from_bytes(library_bytes(&[unordered_codebook(), ordered_codebook()]))
+                .unwrap();
+        assert_eq!(library.len(), 2);
+        assert!(!library.is_inline());
+
+        for (id, packed) in [(0, unordered_codebook()), (1, ordered_codebook())] {
+            let mut out = BitWriter::new();
+            library.rebuild_id(id, &mut out).unwrap();
+            assert_eq!(out.into_bytes(), rebuild_bytes(&packed).unwrap());
+        }
+
+        let err = library.rebuild_id(2, &mut BitWriter::new()).unwrap_err();
+        assert!(err.to_string().contains("codebook id 2"));
+    }
+
+    #[test]
+    fn test_library_size_mismatch() {
+        // A spare byte after the codebook's bits
+        let mut padded = unordered_codebook();
+        padded.push(0);
+        let library = CodebookLibrary::from_bytes(library_bytes(&[padded])).unwrap();
+        let err = library.rebuild_id(0, &mut BitWriter::new()).unwrap_err();
+        assert!(err.to_string().contains("size mismatch"));
+    }
+
+    #[test]
+    fn test_inline_library() {
+        let library = CodebookLibrary::inline();
+        assert!(library.is_inline());
+        assert!(library.is_empty());
+        assert!(library.rebuild_id(0, &mut BitWriter::new()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_library_bytes() {
+        assert!(CodebookLibrary::from_bytes(vec![0; 3]).is_err());
+        // Table offset past the end
+        assert!(CodebookLibrary::from_bytes(100u32.to_le_bytes().to_vec()).is_err());
+        // Offsets that go backwards
+        let mut data = vec![0; 8];
+        for offset in [4u32, 0, 8] {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        assert!(CodebookLibrary::from_bytes(data).is_err());
+    }
+}
@@ -0,0 +1,650 @@
+//! `.wem` Wwise audio files
+//!
+//! BG3 sound banks and loose `.wem` files are RIFF/WAVE containers written by
+//! Audiokinetic Wwise. Most use Wwise's own Vorbis encoding, which is turned
+//! back into standard Ogg Vorbis; PCM files are rewritten as plain WAV. Other
+//! codecs are reported by their format tag with [`Error::UnsupportedWemCodec`].
+//!
+//! Wwise Vorbis files usually reference shared codebooks instead of storing
+//! them, so converting them needs a [`CodebookLibrary`] (see
+//! [`CODEBOOK_LIBRARY_NAME`]).
+//!
+//! [`Error::UnsupportedWemCodec`]: crate::Error::UnsupportedWemCodec
+
+mod bits;
+mod codebook;
+mod ogg;
+mod vorbis;
+
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::utils::open_file;
+
+pub use codebook::{CODEBOOK_LIBRARY_NAME, CodebookLibrary};
+
+/// Format tag of integer PCM
+pub const CODEC_PCM: u16 = 0x0001;
+
+/// Format tag of `WAVE_FORMAT_EXTENSIBLE`, used by Wwise for PCM
+pub const CODEC_PCM_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Format tag of Wwise Vorbis
+pub const CODEC_VORBIS: u16 = 0xFFFF;
+
+/// Human-readable name of a WEM format tag
+#[must_use]
+pub fn codec_name(codec: u16) -> &'static str {
+    match codec {
+        CODEC_PCM | CODEC_PCM_EXTENSIBLE => "PCM",
+        0x0002 | 0x0069 => "IMA ADPCM",
+        0x0161 | 0x0162 => "xWMA",
+        0x0165 | 0x0166 => "XMA2",
+        0x3039..=0x3041 => "Opus",
+        0x8311 => "PTADPCM",
+        0xFFF0 => "DSP ADPCM",
+        CODEC_VORBIS => "Wwise Vorbis",
+        _ => "unknown",
+    }
+}
+
+/// Output format of a converted WEM file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    /// Ogg Vorbis, from Wwise Vorbis
+    Ogg,
+    /// WAV, from PCM
+    Wav,
+}
+
+impl AudioFormat {
+    /// File extension, without the dot
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ogg => "ogg",
+            Self::Wav => "wav",
+        }
+    }
+}
+
+/// Audio converted from a WEM file
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub struct ConvertedAudio {
+    /// Format of `data`.
+    pub format: AudioFormat,
+    /// The complete output file.
+    pub data: Vec<u8>,
+}
+
+/// Stream properties of a WEM file
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WemInfo {
+    /// Format tag from the `fmt` chunk.
+    pub codec: u16,
+    /// Number of channels.
+    pub channels: u16,
+    /// Sample rate in Hz.
+    pub sample_rate: u32,
+    /// Average bytes per second, as stored by Wwise.
+    pub avg_bytes_per_second: u32,
+    /// Samples per channel, when the file records it.
+    pub sample_count: Option<u32>,
+    /// Size of the `data` chunk in bytes.
+    pub data_size: usize,
+}
+
+impl WemInfo {
+    /// Name of the codec (see [`codec_name`])
+    #[must_use]
+    pub fn codec_name(&self) -> &'static str {
+        codec_name(self.codec)
+    }
+
+    /// Duration in seconds, when the sample count is known
+    #[must_use]
+    pub fn duration_secs(&self) -> Option<f64> {
+        let samples = self.sample_count?;
+        (self.sample_rate > 0).then(|| f64::from(samples) / f64::from(self.sample_rate))
+    }
+}
+
+/// Fields of the `fmt` chunk
+struct FmtChunk {
+    codec: u16,
+    channels: u16,
+    sample_rate: u32,
+    avg_bytes_per_second: u32,
+    bits_per_sample: u16,
+}
+
+/// The chunks of a WEM file this module uses
+struct RiffChunks<'a> {
+    fmt: FmtChunk,
+    fmt_bytes: &'a [u8],
+    vorb: Option<&'a [u8]>,
+    data: &'a [u8],
+}
+
+impl<'a> RiffChunks<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let invalid = |message: &str| Error::InvalidWem {
+            message: message.to_string(),
+        };
+        if bytes.len() < 12 {
+            return Err(invalid("file is too small"));
+        }
+        match &bytes[0..4] {
+            b"RIFF" => {}
+            b"RIFX" => return Err(invalid("big-endian (RIFX) files are not supported")),
+            _ => return Err(invalid("missing RIFF header")),
+        }
+        if &bytes[8..12] != b"WAVE" {
+            return Err(invalid("missing WAVE signature"));
+        }
+
+        let mut fmt_bytes = None;
+        let mut vorb = None;
+        let mut data = None;
+        // Wwise doesn't pad odd-sized chunks, so neither does this walk
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let id = &bytes[offset..offset + 4];
+            let size = u32::from_le_bytes([
+                bytes[offset + 4],
+                bytes[offset + 5],
+                bytes[offset + 6],
+                bytes[offset + 7],
+            ]) as usize;
+            let start = offset + 8;
+            // Ripped files are sometimes cut short; keep what is there
+            let end = start.saturating_add(size).min(bytes.len());
+            let body = &bytes[start..end];
+            match id {
+                b"fmt " => fmt_bytes = Some(body),
+                b"vorb" => vorb = Some(body),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+            offset = start.saturating_add(size);
+        }
+
+        let fmt_bytes = fmt_bytes.ok_or_else(|| invalid("missing fmt chunk"))?;
+        let data = data.ok_or_else(|| invalid("missing data chunk"))?;
+        if fmt_bytes.len() < 16 {
+            return Err(invalid("fmt chunk is too small"));
+        }
+        let u16_at = |o: usize| u16::from_le_bytes([fmt_bytes[o], fmt_bytes[o + 1]]);
+        let u32_at = |o: usize| {
+            u32::from_le_bytes([
+                fmt_bytes[o],
+                fmt_bytes[o + 1],
+                fmt_bytes[o + 2],
+                fmt_bytes[o + 3],
+            ])
+        };
+        let fmt = FmtChunk {
+            codec: u16_at(0),
+            channels: u16_at(2),
+            sample_rate: u32_at(4),
+            avg_bytes_per_second: u32_at(8),
+            bits_per_sample: u16_at(14),
+        };
+        if fmt.channels == 0 {
+            return Err(invalid("fmt chunk has no channels"));
+        }
+
+        Ok(Self {
+            fmt,
+            fmt_bytes,
+            vorb,
+            data,
+        })
+    }
+
+    /// Samples per channel: from the vorb data for Vorbis, from the data
+    /// size for PCM
+    fn sample_count(&self) -> Option<u32> {
+        match self.fmt.codec {
+            CODEC_VORBIS => {
+                let vorb = match self.vorb {
+                    Some(vorb) => vorb,
+                    None if self.fmt_bytes.len() == 0x42 => &self.fmt_bytes[0x18..],
+                    None => return None,
+                };
+                vorb.get(0..4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            }
+            CODEC_PCM | CODEC_PCM_EXTENSIBLE => {
+                let frame =
+                    usize::from(self.fmt.channels) * usize::from(self.fmt.bits_per_sample / 8);
+                (frame > 0).then(|| (self.data.len() / frame) as u32)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Read the stream properties of WEM data
+///
+/// # Errors
+/// Returns [`Error::InvalidWem`] if the data is not a RIFF/WAVE file with
+/// `fmt` and `data` chunks.
+///
+/// [`Error::InvalidWem`]: crate::Error::InvalidWem
+pub fn parse_wem_info(bytes: &[u8]) -> Result<WemInfo> {
+    let chunks = RiffChunks::parse(bytes)?;
+    Ok(WemInfo {
+        codec: chunks.fmt.codec,
+        channels: chunks.fmt.channels,
+        sample_rate: chunks.fmt.sample_rate,
+        avg_bytes_per_second: chunks.fmt.avg_bytes_per_second,
+        sample_count: chunks.sample_count(),
+        data_size: chunks.data.len(),
+    })
+}
+
+/// Read the stream properties of a `.wem` file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is not a valid WEM file.
+pub fn read_wem_info<P: AsRef<Path>>(path: P) -> Result<WemInfo> {
+    parse_wem_info(&read_file(path.as_ref())?)
+}
+
+/// Convert WEM data to Ogg Vorbis (Wwise Vorbis) or WAV (PCM)
+///
+/// `codebooks` is only used for Wwise Vorbis; pass `None` when converting
+/// files known to be PCM.
+///
+/// # Errors
+/// Returns [`Error::UnsupportedWemCodec`] for codecs other than PCM and Wwise
+/// Vorbis, [`Error::WemCodebooksRequired`] for Vorbis without `codebooks`,
+/// and [`Error::InvalidWem`] if the file is malformed.
+///
+/// [`Error::UnsupportedWemCodec`]: crate::Error::UnsupportedWemCodec
+/// [`Error::WemCodebooksRequired`]: crate::Error::WemCodebooksRequired
+/// [`Error::InvalidWem`]: crate::Error::InvalidWem
+pub fn convert_wem(bytes: &[u8], codebooks: Option<&CodebookLibrary>) -> Result<ConvertedAudio> {
+    let chunks = RiffChunks::parse(bytes)?;
+    match chunks.fmt.codec {
+        CODEC_VORBIS => {
+            let codebooks = codebooks.ok_or(Error::WemCodebooksRequired)?;
+            Ok(ConvertedAudio {
+                format: AudioFormat::Ogg,
+                data: vorbis::to_ogg(&chunks, codebooks)?,
+            })
+        }
+        CODEC_PCM | CODEC_PCM_EXTENSIBLE
+            if matches!(chunks.fmt.bits_per_sample, 8 | 16 | 24 | 32) =>
+        {
+            Ok(ConvertedAudio {
+                format: AudioFormat::Wav,
+                data: pcm_to_wav(&chunks.fmt, chunks.data),
+            })
+        }
+        codec => Err(Error::UnsupportedWemCodec {
+            codec,
+            name: codec_name(codec),
+        }),
+    }
+}
+
+/// Convert a `.wem` file to Ogg Vorbis or WAV
+///
+/// # Errors
+/// Returns an error if the file cannot be read or converted (see
+/// [`convert_wem`]).
+pub fn convert_wem_file<P: AsRef<Path>>(
+    path: P,
+    codebooks: Option<&CodebookLibrary>,
+) -> Result<ConvertedAudio> {
+    convert_wem(&read_file(path.as_ref())?, codebooks)
+}
+
+/// Interleaved PCM decoded from a WEM or WAV file
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcmSamples {
+    /// Number of channels.
+    pub channels: u16,
+    /// Sample rate in Hz.
+    pub sample_rate: u32,
+    /// Interleaved samples, scaled to 16 bits.
+    pub samples: Vec<i16>,
+}
+
+/// Decode PCM WEM data, or a plain PCM WAV file, to 16-bit samples
+///
+/// 8-bit samples are widened; 24- and 32-bit samples keep their upper 16
+/// bits.
+///
+/// # Errors
+/// Returns [`Error::UnsupportedWemCodec`] for anything but 8/16/24/32-bit
+/// PCM and [`Error::InvalidWem`] if the file is malformed.
+///
+/// [`Error::UnsupportedWemCodec`]: crate::Error::UnsupportedWemCodec
+/// [`Error::InvalidWem`]: crate::Error::InvalidWem
+pub fn decode_pcm(bytes: &[u8]) -> Result<PcmSamples> {
+    let chunks = RiffChunks::parse(bytes)?;
+    let fmt = &chunks.fmt;
+    let samples = match (fmt.codec, fmt.bits_per_sample) {
+        (CODEC_PCM | CODEC_PCM_EXTENSIBLE, 8) => chunks
+            .data
+            .iter()
+            .map(|&b| (i16::from(b) - 128) << 8)
+            .collect(),
+        (CODEC_PCM | CODEC_PCM_EXTENSIBLE, 16) => chunks
+            .data
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]))
+            .collect(),
+        (CODEC_PCM | CODEC_PCM_EXTENSIBLE, 24) => chunks
+            .data
+            .chunks_exact(3)
+            .map(|c| i16::from_le_bytes([c[1], c[2]]))
+            .collect(),
+        (CODEC_PCM | CODEC_PCM_EXTENSIBLE, 32) => chunks
+            .data
+            .chunks_exact(4)
+            .map(|c| i16::from_le_bytes([c[2], c[3]]))
+            .collect(),
+        (codec, _) => {
+            return Err(Error::UnsupportedWemCodec {
+                codec,
+                name: codec_name(codec),
+            });
+        }
+    };
+    Ok(PcmSamples {
+        channels: fmt.channels,
+        sample_rate: fmt.sample_rate,
+        samples,
+    })
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut bytes = Vec::new();
+    open_file(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A canonical 44-byte-header WAV around interleaved PCM samples
+fn pcm_to_wav(fmt: &FmtChunk, samples: &[u8]) -> Vec<u8> {
+    let block_align = fmt.channels * (fmt.bits_per_sample / 8);
+    let byte_rate = fmt.sample_rate * u32::from(block_align);
+    // Drop a trailing partial frame
+    let len = samples.len() - samples.len() % usize::from(block_align);
+
+    let mut out = Vec::with_capacity(44 + len);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + len as u32).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&CODEC_PCM.to_le_bytes());
+    out.extend_from_slice(&fmt.channels.to_le_bytes());
+    out.extend_from_slice(&fmt.sample_rate.to_le_bytes());
+    out.extend_from_slice(&byte_rate.to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&fmt.bits_per_sample.to_le_bytes());
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&(len as u32).to_le_bytes());
+    out.extend_from_slice(&samples[..len]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal RIFF file with the given fmt fields and data
+    fn wem(codec: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&codec.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&48000u32.to_le_bytes());
+        fmt.extend_from_slice(&(48000u32 * 4).to_le_bytes());
+        fmt.extend_from_slice(&(channels * bits / 8).to_le_bytes());
+        fmt.extend_from_slice(&bits.to_le_bytes());
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&((4 + 8 + fmt.len() + 8 + data.len()) as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVEfmt ");
+        out.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+        out.extend_from_slice(&fmt);
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    #[test]
+    fn test_pcm_to_wav() {
+        let samples = [1u8, 0, 2, 0, 3, 0, 4, 0];
+        let bytes = wem(CODEC_PCM_EXTENSIBLE, 2, 16, &samples);
+
+        let info = parse_wem_info(&bytes).unwrap();
+        assert_eq!(info.codec_name(), "PCM");
+        assert_eq!(info.sample_count, Some(2));
+
+        let converted = convert_wem(&bytes, None).unwrap();
+        assert_eq!(converted.format, AudioFormat::Wav);
+        assert_eq!(&converted.data[20..22], &CODEC_PCM.to_le_bytes());
+        assert_eq!(&converted.data[44..], &samples);
+    }
+
+    #[test]
+    fn test_decode_pcm_scales_to_16_bits() {
+        let pcm = decode_pcm(&wem(CODEC_PCM, 2, 16, &[1, 0, 0xFF, 0xFF])).unwrap();
+        assert_eq!((pcm.channels, pcm.sample_rate), (2, 48000));
+        assert_eq!(pcm.samples, [1, -1]);
+
+        let pcm = decode_pcm(&wem(CODEC_PCM, 1, 24, &[0xAA, 0x34, 0x12])).unwrap();
+        assert_eq!(pcm.samples, [0x1234]);
+
+        let pcm = decode_pcm(&wem(CODEC_PCM, 1, 8, &[128, 0])).unwrap();
+        assert_eq!(pcm.samples, [0, i16::MIN]);
+
+        assert!(matches!(
+            decode_pcm(&wem(CODEC_VORBIS, 2, 0, &[0; 4])),
+            Err(Error::UnsupportedWemCodec { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_codec_names_id() {
+        let bytes = wem(0x3040, 2, 0, &[0; 4]);
+        let err = convert_wem(&bytes, None).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UnsupportedWemCodec { codec: 0x3040, .. }
+        ));
+        assert!(err.to_string().contains("0x3040"));
+
+        let bytes = wem(CODEC_VORBIS, 2, 0, &[0; 4]);
+        assert!(matches!(
+            convert_wem(&bytes, None),
+            Err(Error::WemCodebooksRequired)
+        ));
+    }
+
+    /// Packed setup header of a mono stream with one codebook (inline, or
+    /// library id 0), one floor, one residue, one mapping and one short mode
+    fn vorbis_setup(inline_codebooks: bool) -> Vec<u8> {
+        let mut out = bits::BitWriter::new();
+        let mut field = |value: u32, bits: u32| out.write(value, bits);
+        field(0, 8); // codebook count - 1
+        if inline_codebooks {
+            field(1, 4); // dimensions
+            field(2, 14); // entries
+            field(0, 1); // unordered
+            field(1, 3); // codeword length width
+            field(0, 1); // not sparse
+            field(0, 1); // length - 1 of entry 0
+            field(0, 1); // length - 1 of entry 1
+            field(0, 1); // no lookup
+        } else {
+            field(0, 10); // codebook id
+        }
+        field(0, 6); // floor count - 1
+        field(1, 5); // partitions
+        field(0, 4); // partition class
+        field(0, 3); // class dimensions - 1
+        field(0, 2); // subclasses
+        field(0, 8); // subclass book + 1
+        field(1, 2); // multiplier - 1
+        field(4, 4); // range bits
+        field(8, 4); // X value
+        field(0, 6); // residue count - 1
+        field(1, 2); // residue type
+        field(0, 24); // begin
+        field(64, 24); // end
+        field(15, 24); // partition size - 1
+        field(0, 6); // classifications - 1
+        field(0, 8); // classbook
+        field(0, 3); // cascade low bits
+        field(0, 1); // no cascade high bits
+        field(0, 6); // mapping count - 1
+        field(0, 1); // no submaps
+        field(0, 1); // no coupling
+        field(0, 2); // reserved
+        field(0, 8); // time config
+        field(0, 8); // floor
+        field(0, 8); // residue
+        field(0, 6); // mode count - 1
+        field(0, 1); // short block
+        field(0, 8); // mapping
+        out.into_bytes()
+    }
+
+    /// The codebook `vorbis_setup(true)` stores inline, as a library
+    fn codebook_library() -> CodebookLibrary {
+        let mut out = bits::BitWriter::new();
+        for (value, bits) in [
+            (1, 4),
+            (2, 14),
+            (0, 1),
+            (1, 3),
+            (0, 1),
+            (0, 1),
+            (0, 1),
+            (0, 1),
+        ] {
+            out.write(value, bits);
+        }
+        let mut bytes = out.into_bytes();
+        let table_offset = bytes.len() as u32;
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&table_offset.to_le_bytes());
+        CodebookLibrary::from_bytes(bytes).unwrap()
+    }
+
+    /// A Wwise Vorbis file in the 0x2A `vorb` layout: the setup packet then
+    /// `audio_packets` silent short blocks with modified packet headers
+    fn vorbis_wem(inline_codebooks: bool, audio_packets: usize, sample_count: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        let setup = vorbis_setup(inline_codebooks);
+        data.extend_from_slice(&(setup.len() as u16).to_le_bytes());
+        data.extend_from_slice(&setup);
+        let first_audio_offset = data.len() as u32;
+        for _ in 0..audio_packets {
+            data.extend_from_slice(&1u16.to_le_bytes());
+            data.push(0);
+        }
+
+        let mut vorb = vec![0u8; 0x2A];
+        vorb[0..4].copy_from_slice(&sample_count.to_le_bytes());
+        vorb[0x14..0x18].copy_from_slice(&first_audio_offset.to_le_bytes());
+        vorb[0x28] = 8;
+        vorb[0x29] = 11;
+
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&CODEC_VORBIS.to_le_bytes());
+        fmt.extend_from_slice(&1u16.to_le_bytes());
+        fmt.extend_from_slice(&48000u32.to_le_bytes());
+        fmt.extend_from_slice(&8000u32.to_le_bytes());
+        fmt.extend_from_slice(&0u16.to_le_bytes());
+        fmt.extend_from_slice(&0u16.to_le_bytes());
+
+        let mut body = b"WAVE".to_vec();
+        for (id, chunk) in [(b"fmt ", &fmt), (b"vorb", &vorb), (b"data", &data)] {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            body.extend_from_slice(chunk);
+        }
+        let mut out = b"RIFF".to_vec();
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// Decode an Ogg Vorbis stream, returning the reader's headers and the
+    /// number of decoded samples
+    fn decode_ogg(ogg: &[u8]) -> (lewton::header::IdentHeader, String, usize) {
+        let mut reader =
+            lewton::inside_ogg::OggStreamReader::new(std::io::Cursor::new(ogg)).unwrap();
+        let mut samples = 0;
+        while let Some(packet) = reader.read_dec_packet_itl().unwrap() {
+            assert!(
+                packet.iter().all(|&s| s == 0),
+                "silent blocks decode to silence"
+            );
+            samples += packet.len();
+        }
+        (reader.ident_hdr, reader.comment_hdr.vendor, samples)
+    }
+
+    #[test]
+    fn test_vorbis_to_valid_ogg() {
+        let bytes = vorbis_wem(true, 3, 256);
+        let info = parse_wem_info(&bytes).unwrap();
+        assert_eq!(info.codec_name(), "Wwise Vorbis");
+        assert_eq!(info.sample_count, Some(256));
+
+        let converted = convert_wem(&bytes, Some(&CodebookLibrary::inline())).unwrap();
+        assert_eq!(converted.format, AudioFormat::Ogg);
+        assert_eq!(&converted.data[0..4], b"OggS");
+
+        let (ident, vendor, samples) = decode_ogg(&converted.data);
+        assert_eq!(ident.audio_channels, 1);
+        assert_eq!(ident.audio_sample_rate, 48000);
+        assert_eq!(ident.blocksize_0, 8);
+        assert_eq!(ident.blocksize_1, 11);
+        assert!(vendor.contains("Wwise"));
+        // Three 256-sample short blocks overlap into two halves of 128
+        assert_eq!(samples, 256);
+    }
+
+    #[test]
+    fn test_vorbis_with_codebook_library() {
+        let inline = convert_wem(&vorbis_wem(true, 3, 256), Some(&CodebookLibrary::inline()))
+            .unwrap()
+            .data;
+        let library = codebook_library();
+        let packed = convert_wem(&vorbis_wem(false, 3, 256), Some(&library))
+            .unwrap()
+            .data;
+        // Expanding codebook 0 from the library rebuilds the same stream
+        assert_eq!(packed, inline);
+
+        // The library can't stand in for inline codebooks, or vice versa
+        assert!(convert_wem(&vorbis_wem(true, 3, 256), Some(&library)).is_err());
+        assert!(convert_wem(&vorbis_wem(false, 3, 256), Some(&CodebookLibrary::inline())).is_err());
+    }
+
+    #[test]
+    fn test_vorbis_truncated_packet() {
+        let mut bytes = vorbis_wem(true, 3, 256);
+        // Drop the last audio packet's payload byte
+        bytes.pop();
+        assert!(matches!(
+            convert_wem(&bytes, Some(&CodebookLibrary::inline())),
+            Err(Error::InvalidWem { .. })
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! Ogg page writer
+
+/// Largest number of lacing values on one page
+const MAX_SEGMENTS: usize = 255;
+
+/// Granule position for pages on which no packet ends
+const NO_GRANULE: u64 = u64::MAX;
+
+/// Writes packets into a single logical Ogg stream, one packet per page
+pub(super) struct OggWriter {
+    out: Vec<u8>,
+    serial: u32,
+    sequence: u32,
+}
+
+impl OggWriter {
+    pub(super) fn new(serial: u32) -> Self {
+        Self {
+            out: Vec::new(),
+            serial,
+            sequence: 0,
+        }
+    }
+
+    /// Write `packet` on its own page, spilling onto continuation pages when
+    /// it needs more than 255 lacing values. `granule` is stored on the page
+    /// the packet ends on, and `last` marks the end of the stream.
+    pub(super) fn write_packet(&mut self, packet: &[u8], granule: u64, last: bool) {
+        // A packet is laced as runs of 255 followed by one value below 255
+        let mut lacing = vec![255u8; packet.len() / 255];
+        lacing.push((packet.len() % 255) as u8);
+
+        let mut data_offset = 0;
+        let mut continued = false;
+        for (i, segments) in lacing.chunks(MAX_SEGMENTS).enumerate() {
+            let is_final = (i + 1) * MAX_SEGMENTS >= lacing.len();
+            let len: usize = segments.iter().map(|&s| usize::from(s)).sum();
+            let body = &packet[data_offset..data_offset + len];
+            data_offset += len;
+
+            let mut flags = 0u8;
+            if continued {
+                flags |= 0x01;
+            }
+            if self.sequence == 0 {
+                flags |= 0x02;
+            }
+            if last && is_final {
+                flags |= 0x04;
+            }
+            let page_granule = if is_final { granule } else { NO_GRANULE };
+            self.write_page(flags, page_granule, segments, body);
+            continued = true;
+        }
+    }
+
+    fn write_page(&mut self, flags: u8, granule: u64, segments: &[u8], body: &[u8]) {
+        let start = self.out.len();
+        self.out.extend_from_slice(b"OggS");
+        self.out.push(0); // stream structure version
+        self.out.push(flags);
+        self.out.extend_from_slice(&granule.to_le_bytes());
+        self.out.extend_from_slice(&self.serial.to_le_bytes());
+        self.out.extend_from_slice(&self.sequence.to_le_bytes());
+        let crc_offset = self.out.len();
+        self.out.extend_from_slice(&[0; 4]);
+        self.out.push(segments.len() as u8);
+        self.out.extend_from_slice(segments);
+        self.out.extend_from_slice(body);
+
+        let crc = crc32(&self.out[start..]);
+        self.out[crc_offset..crc_offset + 4].copy_from_slice(&crc.to_le_bytes());
+        self.sequence += 1;
+    }
+
+    pub(super) fn into_bytes(self) -> Vec<u8> {
+        self.out
+    }
+}
+
+/// Ogg page checksum: CRC-32 with polynomial 0x04C11DB7, no reflection,
+/// zero initial value and no final XOR
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in data {
+        crc ^= u32::from(byte) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One parsed page: header fields, lacing values and body
+    struct Page {
+        flags: u8,
+        granule: u64,
+        serial: u32,
+        sequence: u32,
+        lacing: Vec<u8>,
+        body: Vec<u8>,
+    }
+
+    /// Split a stream into pages, checking each page's CRC
+    fn pages(bytes: &[u8]) -> Vec<Page> {
+        let mut pages = Vec::new();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let header = &bytes[offset..];
+            assert_eq!(&header[0..4], b"OggS");
+            assert_eq!(header[4], 0);
+            let segment_count = usize::from(header[26]);
+            let lacing = header[27..27 + segment_count].to_vec();
+            let body_len: usize = lacing.iter().map(|&s| usize::from(s)).sum();
+            let page_len = 27 + segment_count + body_len;
+
+            let mut unchecked = header[..page_len].to_vec();
+            unchecked[22..26].fill(0);
+            let stored = u32::from_le_bytes(header[22..26].try_into().unwrap());
+            assert_eq!(crc32(&unchecked), stored, "page CRC");
+
+            pages.push(Page {
+                flags: header[5],
+                granule: u64::from_le_bytes(header[6..14].try_into().unwrap()),
+                serial: u32::from_le_bytes(header[14..18].try_into().unwrap()),
+                sequence: u32::from_le_bytes(header[18..22].try_into().unwrap()),
+                lacing,
+                body: header[27 + segment_count..page_len].to_vec(),
+            });
+            offset += page_len;
+        }
+        pages
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        // The Ogg CRC is CRC-32/POSIX without its final XOR
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), !0x765E_7680);
+    }
+
+    #[test]
+    fn test_lacing_of_packet_sizes() {
+        let lacing_of = |len: usize| {
+            let mut ogg = OggWriter::new(7);
+            ogg.write_packet(&vec![0xA5; len], 0, true);
+            let pages = pages(&ogg.into_bytes());
+            assert_eq!(pages.len(), 1);
+            pages.into_iter().next().unwrap().lacing
+        };
+        assert_eq!(lacing_of(0), vec![0]);
+        assert_eq!(lacing_of(1), vec![1]);
+        assert_eq!(lacing_of(254), vec![254]);
+        // A multiple of 255 needs a terminating zero
+        assert_eq!(lacing_of(255), vec![255, 0]);
+        assert_eq!(lacing_of(600), vec![255, 255, 90]);
+    }
+
+    #[test]
+    fn test_pages_flags_and_granules() {
+        let mut ogg = OggWriter::new(0x1234_5678);
+        ogg.write_packet(b"first", 0, false);
+        ogg.write_packet(b"second", 128, false);
+        ogg.write_packet(b"third", 300, true);
+        let pages = pages(&ogg.into_bytes());
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(
+            pages.iter().map(|p| p.flags).collect::<Vec<_>>(),
+            vec![0x02, 0x00, 0x04]
+        );
+        assert_eq!(
+            pages.iter().map(|p| p.granule).collect::<Vec<_>>(),
+            vec![0, 128, 300]
+        );
+        assert!(pages.iter().all(|p| p.serial == 0x1234_5678));
+        assert_eq!(
+            pages.iter().map(|p| p.sequence).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(pages[1].body, b"second");
+    }
+
+    #[test]
+    fn test_large_packet_spills_onto_continuation_page() {
+        // 255 * 255 bytes take 255 lacing values of 255 plus a final 0
+        let packet: Vec<u8> = (0..255 * 255).map(|i| (i % 251) as u8).collect();
+        let mut ogg = OggWriter::new(1);
+        ogg.write_packet(&packet, 4096, true);
+        let pages = pages(&ogg.into_bytes());
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].lacing.len(), MAX_SEGMENTS);
+        assert_eq!(pages[0].flags, 0x02);
+        assert_eq!(pages[0].granule, NO_GRANULE);
+        assert_eq!(pages[1].lacing, vec![0]);
+        assert_eq!(pages[1].flags, 0x01 | 0x04);
+        assert_eq!(pages[1].granule, 4096);
+
+        let joined: Vec<u8> = pages.iter().flat_map(|p| p.body.clone()).collect();
+        assert_eq!(joined, packet);
+    }
+}
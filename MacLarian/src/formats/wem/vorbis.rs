@@ -0,0 +1,533 @@
+//! Wwise Vorbis to Ogg Vorbis
+//!
+//! Wwise stores Vorbis with its own packet framing and a stripped-down set of
+//! headers: the identification and comment headers are gone, the setup header
+//! is packed (narrower fields, codebooks replaced by ids) and, in newer files,
+//! audio packets lose their window flags. This rebuilds the three standard
+//! headers and the audio packets, then writes them out as an Ogg stream with
+//! granule positions computed from the block sizes.
+
+use super::bits::{BitReader, BitWriter, ilog};
+use super::codebook::{self, CodebookLibrary};
+use super::ogg::OggWriter;
+use super::{FmtChunk, RiffChunks};
+use crate::error::{Error, Result};
+
+/// Vendor string of the rebuilt comment header
+const VENDOR: &str = "converted from Audiokinetic Wwise by maclarian";
+
+/// Ogg stream serial number
+const STREAM_SERIAL: u32 = 1;
+
+/// Stream parameters from the `vorb` chunk (or the extended `fmt` chunk)
+struct VorbHeader {
+    sample_count: u32,
+    setup_offset: usize,
+    first_audio_offset: usize,
+    blocksize_0_exp: u8,
+    blocksize_1_exp: u8,
+    /// Audio packet headers carry only a size, no granule
+    no_granule: bool,
+    /// Audio packets are missing the packet type and window flag bits
+    mod_packets: bool,
+}
+
+impl VorbHeader {
+    fn parse(vorb: &[u8]) -> Result<Self> {
+        let u32_at = |offset: usize| -> Result<u32> {
+            vorb.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .ok_or(Error::InvalidWem {
+                    message: "vorb chunk ends early".to_string(),
+                })
+        };
+        let u8_at = |offset: usize| -> Result<u8> {
+            vorb.get(offset).copied().ok_or(Error::InvalidWem {
+                message: "vorb chunk ends early".to_string(),
+            })
+        };
+
+        let sample_count = u32_at(0)?;
+        match vorb.len() {
+            0x2A => {
+                // Packets modified unless the signal is one of the known
+                // unmodified values
+                let mod_signal = u32_at(0x04)?;
+                Ok(Self {
+                    sample_count,
+                    setup_offset: u32_at(0x10)? as usize,
+                    first_audio_offset: u32_at(0x14)? as usize,
+                    blocksize_0_exp: u8_at(0x28)?,
+                    blocksize_1_exp: u8_at(0x29)?,
+                    no_granule: true,
+                    mod_packets: !matches!(mod_signal, 0x4A | 0x4B | 0x69 | 0x70),
+                })
+            }
+            0x32 | 0x34 => Ok(Self {
+                sample_count,
+                setup_offset: u32_at(0x18)? as usize,
+                first_audio_offset: u32_at(0x1C)? as usize,
+                blocksize_0_exp: u8_at(0x30)?,
+                blocksize_1_exp: u8_at(0x31)?,
+                no_granule: false,
+                mod_packets: false,
+            }),
+            0x28 | 0x2C => Err(Error::InvalidWem {
+                message: "old Wwise Vorbis layout (separate header packets) is not supported"
+                    .to_string(),
+            }),
+            size => Err(Error::InvalidWem {
+                message: format!("unexpected vorb chunk size {size:#x}"),
+            }),
+        }
+    }
+}
+
+/// A Wwise packet: a 2-byte size, optionally a 4-byte granule, then payload
+struct Packet {
+    payload_offset: usize,
+    size: usize,
+    granule: u32,
+}
+
+impl Packet {
+    fn read(data: &[u8], offset: usize, no_granule: bool) -> Result<Self> {
+        let header_size = if no_granule { 2 } else { 6 };
+        let header = data
+            .get(offset..offset + header_size)
+            .ok_or(Error::InvalidWem {
+                message: format!("packet header at {offset:#x} is truncated"),
+            })?;
+        let size = usize::from(u16::from_le_bytes([header[0], header[1]]));
+        let granule = if no_granule {
+            0
+        } else {
+            u32::from_le_bytes([header[2], header[3], header[4], header[5]])
+        };
+        let packet = Self {
+            payload_offset: offset + header_size,
+            size,
+            granule,
+        };
+        if packet.next_offset() > data.len() {
+            return Err(Error::InvalidWem {
+                message: format!("packet at {offset:#x} is truncated"),
+            });
+        }
+        Ok(packet)
+    }
+
+    fn payload<'a>(&self, data: &'a [u8]) -> &'a [u8] {
+        &data[self.payload_offset..self.next_offset()]
+    }
+
+    fn next_offset(&self) -> usize {
+        self.payload_offset + self.size
+    }
+}
+
+/// Mode information needed to rebuild and time audio packets
+struct Modes {
+    blockflags: Vec<bool>,
+    bits: u32,
+}
+
+/// Convert the chunks of a Wwise Vorbis file to an Ogg Vorbis stream
+pub(super) fn to_ogg(chunks: &RiffChunks, codebooks: &CodebookLibrary) -> Result<Vec<u8>> {
+    let fmt = &chunks.fmt;
+    let vorb = match chunks.vorb {
+        Some(vorb) => vorb,
+        // Newer files fold the vorb data into an extended fmt chunk
+        None if chunks.fmt_bytes.len() == 0x42 => &chunks.fmt_bytes[0x18..0x42],
+        None => {
+            return Err(Error::InvalidWem {
+                message: "missing vorb chunk".to_string(),
+            });
+        }
+    };
+    let header = VorbHeader::parse(vorb)?;
+    let data = chunks.data;
+
+    let mut ogg = OggWriter::new(STREAM_SERIAL);
+    ogg.write_packet(&identification_packet(fmt, &header), 0, false);
+    ogg.write_packet(&comment_packet(), 0, false);
+
+    let setup = Packet::read(data, header.setup_offset, header.no_granule)?;
+    if setup.granule != 0 {
+        return Err(Error::InvalidWem {
+            message: "setup packet has a nonzero granule".to_string(),
+        });
+    }
+    let (setup_packet, modes) = rebuild_setup(setup.payload(data), fmt.channels, codebooks)?;
+    if setup.next_offset() != header.first_audio_offset {
+        return Err(Error::InvalidWem {
+            message: "first audio packet doesn't follow the setup packet".to_string(),
+        });
+    }
+    ogg.write_packet(&setup_packet, 0, false);
+
+    write_audio_packets(&mut ogg, data, &header, &modes)?;
+    Ok(ogg.into_bytes())
+}
+
+/// Vorbis packets start with their type and "vorbis"
+fn packet_header(out: &mut BitWriter, packet_type: u8) {
+    out.write(u32::from(packet_type), 8);
+    out.write_bytes(b"vorbis");
+}
+
+fn identification_packet(fmt: &FmtChunk, header: &VorbHeader) -> Vec<u8> {
+    let mut out = BitWriter::new();
+    packet_header(&mut out, 1);
+    out.write(0, 32); // version
+    out.write(u32::from(fmt.channels), 8);
+    out.write(fmt.sample_rate, 32);
+    out.write(0, 32); // maximum bitrate
+    out.write(fmt.avg_bytes_per_second.saturating_mul(8), 32); // nominal bitrate
+    out.write(0, 32); // minimum bitrate
+    out.write(u32::from(header.blocksize_0_exp), 4);
+    out.write(u32::from(header.blocksize_1_exp), 4);
+    out.write_bool(true); // framing
+    out.into_bytes()
+}
+
+fn comment_packet() -> Vec<u8> {
+    let mut out = BitWriter::new();
+    packet_header(&mut out, 3);
+    out.write(VENDOR.len() as u32, 32);
+    out.write_bytes(VENDOR.as_bytes());
+    out.write(0, 32); // user comment count
+    out.write_bool(true); // framing
+    out.into_bytes()
+}
+
+/// Expand the packed setup header into a standard one
+fn rebuild_setup(
+    packed: &[u8],
+    channels: u16,
+    codebooks: &CodebookLibrary,
+) -> Result<(Vec<u8>, Modes)> {
+    let invalid = |message: &str| Error::InvalidWem {
+        message: format!("invalid setup packet: {message}"),
+    };
+    let channels = u32::from(channels);
+    let mut input = BitReader::new(packed);
+    let mut out = BitWriter::new();
+    packet_header(&mut out, 5);
+
+    // Codebooks
+    let codebook_count = input.read(8)? + 1;
+    out.write(codebook_count - 1, 8);
+    for _ in 0..codebook_count {
+        if codebooks.is_inline() {
+            codebook::rebuild(&mut input, &mut out)?;
+        } else {
+            let id = input.read(10)?;
+            codebooks.rebuild_id(id, &mut out)?;
+        }
+    }
+
+    // Time domain transforms: one placeholder, stripped by Wwise
+    out.write(0, 6);
+    out.write(0, 16);
+
+    // Floors, always type 1
+    let floor_count = input.read(6)? + 1;
+    out.write(floor_count - 1, 6);
+    for _ in 0..floor_count {
+        out.write(1, 16);
+
+        let partitions = input.read(5)?;
+        out.write(partitions, 5);
+        let mut partition_classes = Vec::with_capacity(partitions as usize);
+        for _ in 0..partitions {
+            let class = input.read(4)?;
+            out.write(class, 4);
+            partition_classes.push(class);
+        }
+
+        // Classes 0 through the highest one used; none without partitions
+        let class_count = partition_classes.iter().max().map_or(0, |&max| max + 1);
+        let mut class_dimensions = Vec::with_capacity(class_count as usize);
+        for _ in 0..class_count {
+            let dimensions = input.read(3)? + 1;
+            out.write(dimensions - 1, 3);
+            class_dimensions.push(dimensions);
+
+            let subclasses = input.read(2)?;
+            out.write(subclasses, 2);
+            if subclasses != 0 {
+                let masterbook = input.read(8)?;
+                out.write(masterbook, 8);
+                if masterbook >= codebook_count {
+                    return Err(invalid("floor masterbook out of range"));
+                }
+            }
+            for _ in 0..(1u32 << subclasses) {
+                let book_plus_1 = input.read(8)?;
+                out.write(book_plus_1, 8);
+                if book_plus_1 > codebook_count {
+                    return Err(invalid("floor subclass book out of range"));
+                }
+            }
+        }
+
+        let multiplier = input.read(2)?;
+        out.write(multiplier, 2);
+        let range_bits = input.read(4)?;
+        out.write(range_bits, 4);
+        for &class in &partition_classes {
+            for _ in 0..class_dimensions[class as usize] {
+                out.write(input.read(range_bits)?, range_bits);
+            }
+        }
+    }
+
+    // Residues, type stored in 2 bits instead of 16
+    let residue_count = input.read(6)? + 1;
+    out.write(residue_count - 1, 6);
+    for _ in 0..residue_count {
+        let residue_type = input.read(2)?;
+        if residue_type > 2 {
+            return Err(invalid("residue type out of range"));
+        }
+        out.write(residue_type, 16);
+
+        let begin = input.read(24)?;
+        let end = input.read(24)?;
+        let partition_size = input.read(24)?;
+        let classifications = input.read(6)? + 1;
+        let classbook = input.read(8)?;
+        out.write(begin, 24);
+        out.write(end, 24);
+        out.write(partition_size, 24);
+        out.write(classifications - 1, 6);
+        out.write(classbook, 8);
+        if classbook >= codebook_count {
+            return Err(invalid("residue classbook out of range"));
+        }
+
+        let mut cascades = Vec::with_capacity(classifications as usize);
+        for _ in 0..classifications {
+            let low_bits = input.read(3)?;
+            out.write(low_bits, 3);
+            let has_high_bits = input.read_bool()?;
+            out.write_bool(has_high_bits);
+            let high_bits = if has_high_bits {
+                let high_bits = input.read(5)?;
+                out.write(high_bits, 5);
+                high_bits
+            } else {
+                0
+            };
+            cascades.push(high_bits * 8 + low_bits);
+        }
+        for cascade in cascades {
+            for bit in 0..8 {
+                if cascade & (1 << bit) != 0 {
+                    let book = input.read(8)?;
+                    out.write(book, 8);
+                    if book >= codebook_count {
+                        return Err(invalid("residue book out of range"));
+                    }
+                }
+            }
+        }
+    }
+
+    // Mappings, always type 0
+    let mapping_count = input.read(6)? + 1;
+    out.write(mapping_count - 1, 6);
+    for _ in 0..mapping_count {
+        out.write(0, 16);
+
+        let has_submaps = input.read_bool()?;
+        out.write_bool(has_submaps);
+        let submaps = if has_submaps {
+            let submaps = input.read(4)? + 1;
+            out.write(submaps - 1, 4);
+            submaps
+        } else {
+            1
+        };
+
+        let square_polar = input.read_bool()?;
+        out.write_bool(square_polar);
+        if square_polar {
+            let coupling_steps = input.read(8)? + 1;
+            out.write(coupling_steps - 1, 8);
+            let channel_bits = ilog(channels.saturating_sub(1));
+            for _ in 0..coupling_steps {
+                let magnitude = input.read(channel_bits)?;
+                let angle = input.read(channel_bits)?;
+                out.write(magnitude, channel_bits);
+                out.write(angle, channel_bits);
+                if magnitude == angle || magnitude >= channels || angle >= channels {
+                    return Err(invalid("invalid channel coupling"));
+                }
+            }
+        }
+
+        let reserved = input.read(2)?;
+        out.write(reserved, 2);
+        if reserved != 0 {
+            return Err(invalid("mapping reserved field is nonzero"));
+        }
+
+        if submaps > 1 {
+            for _ in 0..channels {
+                let mux = input.read(4)?;
+                out.write(mux, 4);
+                if mux >= submaps {
+                    return Err(invalid("mapping mux out of range"));
+                }
+            }
+        }
+        for _ in 0..submaps {
+            let time_config = input.read(8)?;
+            out.write(time_config, 8);
+            let floor = input.read(8)?;
+            out.write(floor, 8);
+            if floor >= floor_count {
+                return Err(invalid("mapping floor out of range"));
+            }
+            let residue = input.read(8)?;
+            out.write(residue, 8);
+            if residue >= residue_count {
+                return Err(invalid("mapping residue out of range"));
+            }
+        }
+    }
+
+    // Modes, window and transform types (always 0) stripped
+    let mode_count = input.read(6)? + 1;
+    out.write(mode_count - 1, 6);
+    let mut blockflags = Vec::with_capacity(mode_count as usize);
+    for _ in 0..mode_count {
+        let blockflag = input.read_bool()?;
+        out.write_bool(blockflag);
+        blockflags.push(blockflag);
+        out.write(0, 16);
+        out.write(0, 16);
+        let mapping = input.read(8)?;
+        out.write(mapping, 8);
+        if mapping >= mapping_count {
+            return Err(invalid("mode mapping out of range"));
+        }
+    }
+    out.write_bool(true); // framing
+
+    if input.bits_read().div_ceil(8) != packed.len() {
+        return Err(invalid("packet size doesn't match its contents"));
+    }
+
+    let modes = Modes {
+        bits: ilog(mode_count - 1),
+        blockflags,
+    };
+    Ok((out.into_bytes(), modes))
+}
+
+/// Rebuild the audio packets and write them with computed granule positions
+fn write_audio_packets(
+    ogg: &mut OggWriter,
+    data: &[u8],
+    header: &VorbHeader,
+    modes: &Modes,
+) -> Result<()> {
+    let blocksize = |long: bool| {
+        let exp = if long {
+            header.blocksize_1_exp
+        } else {
+            header.blocksize_0_exp
+        };
+        1u64 << exp.min(13)
+    };
+    let blockflag = |mode: u32| -> Result<bool> {
+        modes
+            .blockflags
+            .get(mode as usize)
+            .copied()
+            .ok_or(Error::InvalidWem {
+                message: format!("audio packet uses undefined mode {mode}"),
+            })
+    };
+
+    let mut offset = header.first_audio_offset;
+    let mut prev_long = false;
+    let mut prev_blocksize: Option<u64> = None;
+    let mut granule = 0u64;
+
+    while offset < data.len() {
+        let packet = Packet::read(data, offset, header.no_granule)?;
+        let payload = packet.payload(data);
+        let Some((&first, rest)) = payload.split_first() else {
+            return Err(Error::InvalidWem {
+                message: format!("empty audio packet at {offset:#x}"),
+            });
+        };
+
+        let (rebuilt, mode) = if header.mod_packets {
+            // Restore the packet type bit, and the previous/next window
+            // flags of long blocks
+            let mut input = BitReader::new(payload);
+            let mode = input.read(modes.bits)?;
+            let remainder = input.read(8 - modes.bits)?;
+            let long = blockflag(mode)?;
+
+            let mut out = BitWriter::new();
+            out.write(0, 1);
+            out.write(mode, modes.bits);
+            if long {
+                let next_long = next_packet_mode(data, packet.next_offset(), header, modes)?
+                    .map_or(Ok(false), blockflag)?;
+                out.write_bool(prev_long);
+                out.write_bool(next_long);
+            }
+            out.write(remainder, 8 - modes.bits);
+            out.write_bytes(rest);
+            (out.into_bytes(), mode)
+        } else {
+            let mode = (u32::from(first) >> 1) & ((1 << modes.bits) - 1);
+            (payload.to_vec(), mode)
+        };
+
+        // Each block after the first completes (previous + current) / 4 samples
+        let long = blockflag(mode)?;
+        let size = blocksize(long);
+        if let Some(prev) = prev_blocksize {
+            granule += (prev + size) / 4;
+        }
+        prev_blocksize = Some(size);
+        prev_long = long;
+
+        offset = packet.next_offset();
+        let last = offset >= data.len();
+        // The final granule trims the decoder's output to the real length
+        let page_granule = if last && header.sample_count > 0 {
+            granule.min(u64::from(header.sample_count))
+        } else {
+            granule
+        };
+        ogg.write_packet(&rebuilt, page_granule, last);
+    }
+    Ok(())
+}
+
+/// Mode number of the packet at `offset`, if there is a non-empty one
+fn next_packet_mode(
+    data: &[u8],
+    offset: usize,
+    header: &VorbHeader,
+    modes: &Modes,
+) -> Result<Option<u32>> {
+    if offset >= data.len() {
+        return Ok(None);
+    }
+    let packet = Packet::read(data, offset, header.no_granule)?;
+    let payload = packet.payload(data);
+    if payload.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(BitReader::new(payload).read(modes.bits)?))
+}
@@ -1,5 +1,6 @@
 //! DDS texture extraction from PAK files
 
+use super::manifest::{BundleTexture, TextureLayer, TextureSource};
 use crate::error::Result;
 use crate::merged::TextureRef;
 use crate::pak::PakOperations;
@@ -7,19 +8,24 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 /// Extract regular DDS textures from Textures.pak (or Textures_*.pak on macOS)
+///
+/// Textures already extracted under one of `texture_roots` are copied from
+/// there instead of being read from a PAK.
 pub fn extract_dds_textures(
     textures: &[&TextureRef],
     game_data: &Path,
+    texture_roots: &[PathBuf],
     output_dir: &Path,
-) -> Result<Vec<PathBuf>> {
-    let mut extracted_paths = Vec::new();
+) -> Result<Vec<BundleTexture>> {
+    let mut extracted = Vec::new();
 
+    let textures = copy_from_texture_roots(textures, texture_roots, output_dir, &mut extracted);
     if textures.is_empty() {
-        return Ok(extracted_paths);
+        return Ok(extracted);
     }
 
     // Log the textures we're trying to extract
-    for tex in textures {
+    for tex in &textures {
         tracing::info!(
             "Looking for texture: {} -> {} (source_pak: '{}')",
             tex.name,
@@ -39,7 +45,7 @@ pub fn extract_dds_textures(
     );
     if texture_paks.is_empty() {
         tracing::warn!("No texture PAK files found in: {}", game_data.display());
-        return Ok(extracted_paths);
+        return Ok(extracted);
     }
 
     // Group textures by source pak (if known)
@@ -47,7 +53,7 @@ pub fn extract_dds_textures(
         std::collections::HashMap::new();
     let mut unknown_pak: Vec<&TextureRef> = Vec::new();
 
-    for texture in textures {
+    for &texture in &textures {
         if texture.source_pak.is_empty() {
             unknown_pak.push(texture);
         } else {
@@ -62,7 +68,7 @@ pub fn extract_dds_textures(
             tracing::warn!("Source pak not found: {}", pak_path.display());
             continue;
         }
-        extract_textures_from_pak(&pak_path, &textures, output_dir, &mut extracted_paths);
+        extract_textures_from_pak(&pak_path, &textures, output_dir, &mut extracted);
     }
 
     // Extract textures with unknown source PAK - search across all texture PAKs
@@ -106,19 +112,14 @@ pub fn extract_dds_textures(
                     textures_in_pak.len(),
                     pak_path.display()
                 );
-                extract_textures_from_pak(
-                    pak_path,
-                    &textures_in_pak,
-                    output_dir,
-                    &mut extracted_paths,
-                );
+                extract_textures_from_pak(pak_path, &textures_in_pak, output_dir, &mut extracted);
             }
         }
 
         // Log if any textures weren't found
-        let found_paths: HashSet<&str> = extracted_paths
+        let found_paths: HashSet<&str> = extracted
             .iter()
-            .filter_map(|p| p.file_name().and_then(|n| n.to_str()))
+            .filter_map(|t| t.output.file_name().and_then(|n| n.to_str()))
             .collect();
         for tex in &unknown_pak {
             let tex_filename = Path::new(&tex.dds_path)
@@ -131,7 +132,76 @@ pub fn extract_dds_textures(
         }
     }
 
-    Ok(extracted_paths)
+    Ok(extracted)
+}
+
+/// Copy textures found under `texture_roots`, returning the ones still missing
+///
+/// A texture matches `<root>/<dds_path>` or, for flattened extractions,
+/// `<root>/<file name>`. Roots are tried in order.
+fn copy_from_texture_roots<'a>(
+    textures: &[&'a TextureRef],
+    texture_roots: &[PathBuf],
+    output_dir: &Path,
+    extracted: &mut Vec<BundleTexture>,
+) -> Vec<&'a TextureRef> {
+    let mut missing = Vec::new();
+
+    for &texture in textures {
+        let file_name = Path::new(&texture.dds_path).file_name().unwrap_or_default();
+        let found = texture_roots.iter().find_map(|root| {
+            [root.join(&texture.dds_path), root.join(file_name)]
+                .into_iter()
+                .find(|p| p.is_file())
+        });
+        let Some(source) = found else {
+            missing.push(texture);
+            continue;
+        };
+
+        let dest_path = output_dir.join(file_name);
+        if let Err(e) =
+            std::fs::create_dir_all(output_dir).and_then(|()| std::fs::copy(&source, &dest_path))
+        {
+            tracing::warn!(
+                "Failed to copy texture {} from {}: {}",
+                texture.name,
+                source.display(),
+                e
+            );
+            missing.push(texture);
+            continue;
+        }
+        tracing::info!(
+            "Found texture '{}' at {}",
+            texture.dds_path,
+            source.display()
+        );
+        extracted.push(BundleTexture {
+            layer: texture_layer(texture),
+            source: TextureSource::Directory,
+            pak: String::new(),
+            internal_path: texture.dds_path.clone(),
+            output: dest_path,
+        });
+    }
+
+    missing
+}
+
+/// Layer of a texture, from its file name or else its material parameter
+fn texture_layer(texture: &TextureRef) -> TextureLayer {
+    let name = Path::new(&texture.dds_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&texture.dds_path);
+    match TextureLayer::from_file_name(name) {
+        TextureLayer::Other => texture
+            .parameter_name
+            .as_deref()
+            .map_or(TextureLayer::Other, TextureLayer::from_file_name),
+        layer => layer,
+    }
 }
 
 /// Find all texture PAK files in game data folder
@@ -167,7 +237,7 @@ pub fn extract_textures_from_pak(
     pak_path: &Path,
     textures: &[&TextureRef],
     output_dir: &Path,
-    extracted_paths: &mut Vec<PathBuf>,
+    extracted: &mut Vec<BundleTexture>,
 ) {
     let dds_paths: Vec<&str> = textures.iter().map(|t| t.dds_path.as_str()).collect();
 
@@ -194,7 +264,16 @@ pub fn extract_textures_from_pak(
                         }
                         let _ = std::fs::remove_file(&full_extracted_path);
                     }
-                    extracted_paths.push(dest_path);
+                    extracted.push(BundleTexture {
+                        layer: texture_layer(texture),
+                        source: TextureSource::Pak,
+                        pak: pak_path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                        internal_path: texture.dds_path.clone(),
+                        output: dest_path,
+                    });
                 }
             }
 
@@ -224,3 +303,56 @@ pub fn cleanup_empty_dirs(dir: &Path) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gr2_extraction::TextureLayer;
+
+    fn texture(dds_path: &str) -> TextureRef {
+        TextureRef {
+            id: dds_path.to_string(),
+            name: dds_path.to_string(),
+            dds_path: dds_path.to_string(),
+            source_pak: "Textures.pak".to_string(),
+            width: 4,
+            height: 4,
+            parameter_name: None,
+        }
+    }
+
+    #[test]
+    fn test_texture_roots_before_paks() {
+        let dir = tempfile::tempdir().unwrap();
+        let game_data = dir.path().join("Data");
+        let nested = dir.path().join("nested");
+        let flat = dir.path().join("flat");
+        let output = dir.path().join("out");
+        std::fs::create_dir_all(&game_data).unwrap();
+
+        let body = "Generated/Public/Shared/Assets/HUM_M_Body_NM.dds";
+        std::fs::create_dir_all(nested.join(body).parent().unwrap()).unwrap();
+        std::fs::write(nested.join(body), b"nested").unwrap();
+        std::fs::create_dir_all(&flat).unwrap();
+        std::fs::write(flat.join("Helmet_BM.dds"), b"flat").unwrap();
+
+        let textures = [
+            texture(body),
+            texture("Generated/Public/Shared/Assets/Helmet_BM.dds"),
+            texture("Generated/Public/Shared/Assets/Missing_BM.dds"),
+        ];
+        let refs: Vec<&TextureRef> = textures.iter().collect();
+        let extracted = extract_dds_textures(&refs, &game_data, &[nested, flat], &output).unwrap();
+
+        assert_eq!(extracted.len(), 2);
+        assert!(
+            extracted
+                .iter()
+                .all(|t| t.source == TextureSource::Directory && t.pak.is_empty())
+        );
+        assert_eq!(extracted[0].layer, TextureLayer::Normal);
+        assert_eq!(extracted[0].output, output.join("HUM_M_Body_NM.dds"));
+        assert_eq!(std::fs::read(&extracted[0].output).unwrap(), b"nested");
+        assert_eq!(std::fs::read(&extracted[1].output).unwrap(), b"flat");
+    }
+}
@@ -0,0 +1,226 @@
+//! Bundle manifest: what a GR2 bundle extraction wrote and where it came from
+//!
+//! Written by `pak extract --bundle --manifest bundle.json`. Paths inside the
+//! file are relative to the manifest's folder when they're under it, so a
+//! bundle can be moved as a whole; [`BundleManifest::load`] resolves them
+//! against the manifest location again.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Manifest of the models and textures produced by a bundle extraction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Schema version, [`BundleManifest::VERSION`] when written
+    pub version: u32,
+    /// One entry per processed GR2
+    pub models: Vec<BundleModel>,
+}
+
+/// A processed GR2 and the files produced for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleModel {
+    /// Path of the GR2 inside its PAK (or on disk, if it wasn't extracted)
+    pub source_gr2: String,
+    /// Extracted GR2, unless it was deleted after conversion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gr2: Option<PathBuf>,
+    /// Converted GLB, if conversion succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glb: Option<PathBuf>,
+    /// Mesh names in the GR2
+    #[serde(default)]
+    pub meshes: Vec<String>,
+    /// Textures extracted for the model
+    #[serde(default)]
+    pub textures: Vec<BundleTexture>,
+}
+
+/// An extracted texture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleTexture {
+    /// Material slot the texture fills
+    pub layer: TextureLayer,
+    /// Whether it came from a texture PAK or a virtual texture
+    pub source: TextureSource,
+    /// PAK the texture was read from (empty for pre-extracted textures)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pak: String,
+    /// DDS path in the PAK, or the GTP path for virtual textures
+    pub internal_path: String,
+    /// File written (DDS, or PNG after conversion)
+    pub output: PathBuf,
+}
+
+/// Material slot of a texture
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureLayer {
+    /// Base color / albedo (`_BM`, `_BaseMap`)
+    BaseColor,
+    /// Normal map (`_NM`, `_NormalMap`)
+    Normal,
+    /// Physical map: roughness and metalness (`_PM`, `_PhysicalMap`)
+    Physical,
+    /// Masks and other maps
+    Other,
+}
+
+/// Where an extracted texture came from
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextureSource {
+    /// A DDS file in `Textures.pak` (or another texture PAK)
+    Pak,
+    /// A layer of a virtual texture (GTS/GTP)
+    VirtualTexture,
+    /// A pre-extracted DDS file under one of the configured texture roots
+    Directory,
+}
+
+impl TextureLayer {
+    /// Layer of a texture file, from the suffix of its name
+    /// (`HUM_M_Body_NM.dds`, `Helmet_BaseMap.png`)
+    #[must_use]
+    pub fn from_file_name(name: &str) -> Self {
+        let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        let suffix = stem
+            .rsplit_once('_')
+            .map_or(stem, |(_, suffix)| suffix)
+            .to_ascii_lowercase();
+        match suffix.as_str() {
+            "bm" | "bma" | "basemap" | "basecolor" | "albedo" => Self::BaseColor,
+            "nm" | "normalmap" | "normal" => Self::Normal,
+            "pm" | "physicalmap" | "physical" => Self::Physical,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl BundleManifest {
+    /// Current schema version
+    pub const VERSION: u32 = 1;
+
+    /// Manifest for the given models
+    #[must_use]
+    pub fn new(models: Vec<BundleModel>) -> Self {
+        Self {
+            version: Self::VERSION,
+            models,
+        }
+    }
+
+    /// Read a manifest, resolving its paths against the manifest's folder
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a manifest.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        let mut manifest: Self = serde_json::from_slice(&data)?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        manifest.map_paths(|p| base.join(p));
+        Ok(manifest)
+    }
+
+    /// Write the manifest as JSON, with paths relative to its folder where possible
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut manifest = self.clone();
+        let base = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        if let Ok(base) = std::path::absolute(base) {
+            manifest.map_paths(|p| {
+                std::path::absolute(p)
+                    .ok()
+                    .and_then(|abs| abs.strip_prefix(&base).ok().map(Path::to_path_buf))
+                    .unwrap_or_else(|| p.to_path_buf())
+            });
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&manifest)?)?;
+        Ok(())
+    }
+
+    /// The model converted to or extracted as `path` (a GLB or GR2)
+    ///
+    /// Falls back to matching the file name, for bundles that were moved
+    /// after the manifest was loaded.
+    #[must_use]
+    pub fn model_for(&self, path: &Path) -> Option<&BundleModel> {
+        let outputs = |m: &BundleModel| [m.glb.clone(), m.gr2.clone()].into_iter().flatten();
+        self.models
+            .iter()
+            .find(|m| outputs(m).any(|p| p == path))
+            .or_else(|| {
+                self.models
+                    .iter()
+                    .find(|m| outputs(m).any(|p| p.file_name() == path.file_name()))
+            })
+    }
+
+    fn map_paths(&mut self, f: impl Fn(&Path) -> PathBuf) {
+        for model in &mut self.models {
+            model.gr2 = model.gr2.as_deref().map(&f);
+            model.glb = model.glb.as_deref().map(&f);
+            for texture in &mut model.textures {
+                texture.output = f(&texture.output);
+            }
+        }
+    }
+}
+
+impl BundleModel {
+    /// First texture filling `layer`
+    #[must_use]
+    pub fn texture(&self, layer: TextureLayer) -> Option<&BundleTexture> {
+        self.textures.iter().find(|t| t.layer == layer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trip_relative_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("HUM_M_Body");
+        let manifest = BundleManifest::new(vec![BundleModel {
+            source_gr2: "Generated/Public/Shared/Assets/HUM_M_Body.GR2".to_string(),
+            gr2: None,
+            glb: Some(folder.join("HUM_M_Body.glb")),
+            meshes: vec!["HUM_M_Body_Mesh".to_string()],
+            textures: vec![BundleTexture {
+                layer: TextureLayer::from_file_name("HUM_M_Body_NM.dds"),
+                source: TextureSource::Pak,
+                pak: "Textures.pak".to_string(),
+                internal_path: "Generated/Public/Shared/Assets/HUM_M_Body_NM.dds".to_string(),
+                output: folder.join("HUM_M_Body_NM.dds"),
+            }],
+        }]);
+
+        let path = dir.path().join("bundle.json");
+        manifest.save(&path).unwrap();
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("\"layer\": \"normal\""));
+        assert!(!json.contains(&*dir.path().to_string_lossy()));
+
+        let loaded = BundleManifest::load(&path).unwrap();
+        assert_eq!(loaded, manifest);
+        let model = loaded.model_for(&folder.join("HUM_M_Body.glb")).unwrap();
+        assert_eq!(
+            model.texture(TextureLayer::Normal).unwrap().output,
+            folder.join("HUM_M_Body_NM.dds")
+        );
+        assert!(model.texture(TextureLayer::BaseColor).is_none());
+    }
+}
@@ -23,6 +23,7 @@
 )]
 
 mod dds;
+mod manifest;
 mod types;
 mod virtual_textures;
 
@@ -30,6 +31,7 @@ mod virtual_textures;
 pub(crate) use dds::extract_dds_textures;
 
 // Public types for external consumers
+pub use manifest::{BundleManifest, BundleModel, BundleTexture, TextureLayer, TextureSource};
 pub use types::{
     Gr2ExtractionOptions, Gr2ExtractionPhase, Gr2ExtractionProgress, Gr2ExtractionProgressCallback,
     Gr2ExtractionResult,
@@ -38,13 +40,15 @@ pub use types::{
 // Crate-internal virtual texture helpers
 pub(crate) use virtual_textures::extract_virtual_textures;
 
-use crate::converter::{convert_dds_to_png, convert_gr2_to_glb};
-use crate::error::{Error, Result};
-use crate::merged::{
-    GameDataResolver, MergedDatabase, TextureRef, VirtualTextureRef, bg3_data_path,
+use crate::converter::{
+    TextureBatchOptions, TextureBatchStatus, batch_convert_textures, convert_gr2_to_glb,
 };
+use crate::error::{Error, Result};
+use crate::formats::gr2::extract_gr2_info;
+use crate::merged::{GameDataResolver, MergedDatabase, TextureRef, VirtualTextureRef};
 use crate::pak::PakOperations;
-use std::collections::HashSet;
+use crate::utils::find_game_install;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 /// Smart extraction of a GR2 file with automatic conversion and texture bundling
@@ -62,17 +66,16 @@ pub fn process_extracted_gr2(
     gr2_path: &Path,
     options: &Gr2ExtractionOptions,
 ) -> Result<Gr2ExtractionResult> {
-    let mut result = Gr2ExtractionResult {
-        gr2_path: gr2_path.to_path_buf(),
-        glb_path: None,
-        texture_paths: Vec::new(),
-        warnings: Vec::new(),
-    };
+    let mut result = Gr2ExtractionResult::new(gr2_path.to_path_buf());
 
     let output_dir = gr2_path
         .parent()
         .ok_or_else(|| Error::InvalidPath("GR2 path has no parent directory".to_string()))?;
 
+    if options.record_meshes {
+        result.meshes = mesh_names(gr2_path, &mut result.warnings);
+    }
+
     // Step 1: Convert GR2 to GLB
     if options.convert_to_glb {
         let glb_path = gr2_path.with_extension("glb");
@@ -98,16 +101,14 @@ pub fn process_extracted_gr2(
         };
 
         if let Some(resolver) = resolver {
-            let textures =
+            let mut textures =
                 extract_textures_for_gr2(gr2_path, resolver.database(), output_dir, options)?;
 
             // Convert DDS to PNG if requested
             if options.convert_to_png {
-                result.texture_paths =
-                    convert_textures_to_png(&textures, options, &mut result.warnings);
-            } else {
-                result.texture_paths = textures;
+                convert_textures_to_png(&mut textures, options, &mut result.warnings);
             }
+            result.set_textures(textures);
         } else {
             result.warnings.push(
                 "Could not find BG3 install path for texture lookup. Use --bg3-path to specify the path.".to_string()
@@ -127,17 +128,16 @@ pub fn process_extracted_gr2_to_dir(
     output_dir: &Path,
     options: &Gr2ExtractionOptions,
 ) -> Result<Gr2ExtractionResult> {
-    let mut result = Gr2ExtractionResult {
-        gr2_path: gr2_path.to_path_buf(),
-        glb_path: None,
-        texture_paths: Vec::new(),
-        warnings: Vec::new(),
-    };
+    let mut result = Gr2ExtractionResult::new(gr2_path.to_path_buf());
 
     // Create output directory if it doesn't exist
     std::fs::create_dir_all(output_dir)
         .map_err(|e| Error::ConversionError(format!("Failed to create output directory: {e}")))?;
 
+    if options.record_meshes {
+        result.meshes = mesh_names(gr2_path, &mut result.warnings);
+    }
+
     // Step 1: Convert GR2 to GLB (if requested)
     if options.convert_to_glb {
         let glb_name = gr2_path.file_stem().unwrap_or_default();
@@ -164,16 +164,14 @@ pub fn process_extracted_gr2_to_dir(
         };
 
         if let Some(resolver) = resolver {
-            let textures =
+            let mut textures =
                 extract_textures_for_gr2(gr2_path, resolver.database(), output_dir, options)?;
 
             // Convert DDS to PNG if requested
             if options.convert_to_png {
-                result.texture_paths =
-                    convert_textures_to_png(&textures, options, &mut result.warnings);
-            } else {
-                result.texture_paths = textures;
+                convert_textures_to_png(&mut textures, options, &mut result.warnings);
             }
+            result.set_textures(textures);
         } else {
             result.warnings.push(
                 "Could not find BG3 install path for texture lookup. Use --bg3-path to specify the path.".to_string()
@@ -222,8 +220,8 @@ pub(crate) fn extract_textures_for_gr2(
     db: &MergedDatabase,
     output_dir: &Path,
     options: &Gr2ExtractionOptions,
-) -> Result<Vec<std::path::PathBuf>> {
-    let mut extracted_paths = Vec::new();
+) -> Result<Vec<BundleTexture>> {
+    let mut extracted = Vec::new();
 
     // Get the GR2 filename for database lookup
     let gr2_filename = gr2_path
@@ -239,14 +237,14 @@ pub(crate) fn extract_textures_for_gr2(
         gr2_filename
     );
     if visuals.is_empty() {
-        return Ok(extracted_paths);
+        return Ok(extracted);
     }
 
     // Get game data path
     let game_data = options
         .bg3_path
         .clone()
-        .or_else(bg3_data_path)
+        .or_else(|| find_game_install().map(|install| install.data_dir))
         .ok_or_else(|| {
             Error::ConversionError("Could not determine BG3 install path".to_string())
         })?;
@@ -277,61 +275,80 @@ pub(crate) fn extract_textures_for_gr2(
     );
 
     // Extract regular DDS textures
-    extracted_paths.extend(extract_dds_textures(
+    extracted.extend(extract_dds_textures(
         &textures_to_extract,
         &game_data,
+        &options.texture_roots,
         output_dir,
     )?);
 
     // Extract virtual textures (from PAK or pre-extracted path)
     if !virtual_textures_to_extract.is_empty() {
-        extracted_paths.extend(extract_virtual_textures(
+        extracted.extend(extract_virtual_textures(
             &virtual_textures_to_extract,
             db,
             options.virtual_textures_path.as_deref(),
             &game_data,
             output_dir,
+            options.vt_naming,
         )?);
     }
 
-    Ok(extracted_paths)
+    Ok(extracted)
 }
 
 /// Convert DDS textures to PNG format
+///
+/// Each converted texture's output becomes its PNG; textures that fail to
+/// convert keep their DDS output and add a warning.
 pub(crate) fn convert_textures_to_png(
-    textures: &[std::path::PathBuf],
+    textures: &mut [BundleTexture],
     options: &Gr2ExtractionOptions,
     warnings: &mut Vec<String>,
-) -> Vec<std::path::PathBuf> {
-    let mut png_paths = Vec::new();
-    for dds_path in textures {
-        let is_dds = dds_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.eq_ignore_ascii_case("dds"))
-            .unwrap_or(false);
-        if is_dds {
-            let png_path = dds_path.with_extension("png");
-            match convert_dds_to_png(dds_path, &png_path) {
-                Ok(()) => {
-                    tracing::info!("Converted {} to PNG", dds_path.display());
-                    if !options.keep_original_dds {
-                        let _ = std::fs::remove_file(dds_path);
-                    }
-                    png_paths.push(png_path);
-                }
-                Err(e) => {
-                    warnings.push(format!(
-                        "Failed to convert {} to PNG: {}",
-                        dds_path.display(),
-                        e
-                    ));
-                    png_paths.push(dds_path.clone());
-                }
-            }
+) {
+    let dds: Vec<std::path::PathBuf> = textures
+        .iter()
+        .map(|texture| &texture.output)
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("dds"))
+        })
+        .cloned()
+        .collect();
+    let batch_options = TextureBatchOptions {
+        delete_source: !options.keep_original_dds,
+        ..TextureBatchOptions::default()
+    };
+    let report = batch_convert_textures(&dds, None, &batch_options, &|_| {});
+
+    let mut converted = HashMap::new();
+    for entry in report.entries {
+        if let TextureBatchStatus::Failed(e) = entry.status {
+            warnings.push(format!(
+                "Failed to convert {} to PNG: {}",
+                entry.source.display(),
+                e
+            ));
         } else {
-            png_paths.push(dds_path.clone());
+            tracing::info!("Converted {} to PNG", entry.source.display());
+            converted.insert(entry.source, entry.output);
+        }
+    }
+
+    for texture in textures {
+        if let Some(png) = converted.remove(&texture.output) {
+            texture.output = png;
+        }
+    }
+}
+
+/// Mesh names in a GR2, or none (with a warning) if it can't be read
+pub(crate) fn mesh_names(gr2_path: &Path, warnings: &mut Vec<String>) -> Vec<String> {
+    match extract_gr2_info(gr2_path) {
+        Ok(info) => info.meshes.into_iter().map(|m| m.name).collect(),
+        Err(e) => {
+            warnings.push(format!("Failed to read mesh names: {e}"));
+            Vec::new()
         }
     }
-    png_paths
 }
@@ -2,6 +2,9 @@
 
 use std::path::PathBuf;
 
+use super::manifest::{BundleModel, BundleTexture};
+use crate::virtual_texture::VTexNaming;
+
 // ============================================================================
 // Progress Types
 // ============================================================================
@@ -110,10 +113,46 @@ pub struct Gr2ExtractionResult {
     pub glb_path: Option<PathBuf>,
     /// Paths to extracted texture files
     pub texture_paths: Vec<PathBuf>,
+    /// Extracted textures with their layer and source
+    pub textures: Vec<BundleTexture>,
+    /// Mesh names in the GR2 (only with [`Gr2ExtractionOptions::record_meshes`])
+    pub meshes: Vec<String>,
     /// Any warnings or errors that occurred during extraction
     pub warnings: Vec<String>,
 }
 
+impl Gr2ExtractionResult {
+    /// Empty result for the GR2 at `gr2_path`
+    pub(crate) fn new(gr2_path: PathBuf) -> Self {
+        Self {
+            gr2_path,
+            glb_path: None,
+            texture_paths: Vec::new(),
+            textures: Vec::new(),
+            meshes: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Record extracted textures, keeping `texture_paths` in step
+    pub(crate) fn set_textures(&mut self, textures: Vec<BundleTexture>) {
+        self.texture_paths = textures.iter().map(|t| t.output.clone()).collect();
+        self.textures = textures;
+    }
+
+    /// Bundle manifest entry for this GR2, read from `source_gr2` in its PAK
+    #[must_use]
+    pub fn to_bundle_model(&self, source_gr2: &str) -> BundleModel {
+        BundleModel {
+            source_gr2: source_gr2.to_string(),
+            gr2: Some(self.gr2_path.clone()).filter(|p| p.exists()),
+            glb: self.glb_path.clone(),
+            meshes: self.meshes.clone(),
+            textures: self.textures.clone(),
+        }
+    }
+}
+
 /// Options for smart GR2 extraction
 #[derive(Debug, Clone)]
 pub struct Gr2ExtractionOptions {
@@ -129,12 +168,18 @@ pub struct Gr2ExtractionOptions {
     /// Path to pre-extracted virtual textures (GTP/GTS files)
     /// If None, virtual textures will be skipped
     pub virtual_textures_path: Option<PathBuf>,
+    /// Directories with pre-extracted DDS textures, searched before the texture PAKs
+    pub texture_roots: Vec<PathBuf>,
     /// Keep the original GR2 file after conversion to GLB (default: true)
     pub keep_original_gr2: bool,
     /// Convert extracted DDS textures to PNG format
     pub convert_to_png: bool,
     /// Keep the original DDS files when converting to PNG
     pub keep_original_dds: bool,
+    /// Read the GR2's mesh names into the result (for bundle manifests)
+    pub record_meshes: bool,
+    /// File naming for extracted virtual textures
+    pub vt_naming: VTexNaming,
 }
 
 impl Default for Gr2ExtractionOptions {
@@ -145,9 +190,12 @@ impl Default for Gr2ExtractionOptions {
             extract_virtual_textures: false,
             bg3_path: None,
             virtual_textures_path: None,
+            texture_roots: Vec::new(),
             keep_original_gr2: true,
             convert_to_png: false,
             keep_original_dds: false,
+            record_meshes: false,
+            vt_naming: VTexNaming::MacPak,
         }
     }
 }
@@ -162,9 +210,12 @@ impl Gr2ExtractionOptions {
             extract_virtual_textures: false,
             bg3_path: None,
             virtual_textures_path: None,
+            texture_roots: Vec::new(),
             keep_original_gr2: true,
             convert_to_png: false,
             keep_original_dds: false,
+            record_meshes: false,
+            vt_naming: VTexNaming::MacPak,
         }
     }
 
@@ -179,9 +230,12 @@ impl Gr2ExtractionOptions {
             extract_virtual_textures: true,
             bg3_path: None,
             virtual_textures_path: None,
+            texture_roots: Vec::new(),
             keep_original_gr2: true,
             convert_to_png: false,
             keep_original_dds: false,
+            record_meshes: false,
+            vt_naming: VTexNaming::MacPak,
         }
     }
 
@@ -205,6 +259,13 @@ impl Gr2ExtractionOptions {
         self
     }
 
+    /// Set directories with pre-extracted DDS textures
+    #[must_use]
+    pub fn with_texture_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.texture_roots = roots;
+        self
+    }
+
     /// Disable GLB conversion
     #[must_use]
     pub fn no_conversion(mut self) -> Self {
@@ -266,4 +327,18 @@ impl Gr2ExtractionOptions {
         self.keep_original_dds = keep;
         self
     }
+
+    /// Set whether to read mesh names for bundle manifests.
+    #[must_use]
+    pub fn with_record_meshes(mut self, record: bool) -> Self {
+        self.record_meshes = record;
+        self
+    }
+
+    /// Set the file naming for extracted virtual textures.
+    #[must_use]
+    pub fn with_vt_naming(mut self, naming: VTexNaming) -> Self {
+        self.vt_naming = naming;
+        self
+    }
 }
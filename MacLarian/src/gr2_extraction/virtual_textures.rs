@@ -1,9 +1,12 @@
 //! Virtual texture extraction and conversion
 
+use super::manifest::{BundleTexture, TextureLayer, TextureSource};
 use crate::error::{Error, Result};
 use crate::merged::{MergedDatabase, VirtualTextureRef};
 use crate::pak::PakOperations;
-use crate::virtual_texture::VirtualTextureExtractor;
+use crate::virtual_texture::{
+    ExtractOptions, VTexNaming, VirtualTextureExtractor, VirtualTextureLayer,
+};
 use std::path::{Path, PathBuf};
 
 /// Extract virtual textures and convert to DDS
@@ -17,11 +20,12 @@ pub fn extract_virtual_textures(
     vt_source_path: Option<&Path>,
     game_data: &Path,
     output_dir: &Path,
-) -> Result<Vec<PathBuf>> {
-    let mut extracted_paths = Vec::new();
+    naming: VTexNaming,
+) -> Result<Vec<BundleTexture>> {
+    let mut extracted = Vec::new();
 
     if virtual_textures.is_empty() {
-        return Ok(extracted_paths);
+        return Ok(extracted);
     }
 
     // Determine if we're using pre-extracted files or extracting from PAK
@@ -30,7 +34,7 @@ pub fn extract_virtual_textures(
     let vt_pak_path = game_data.join("VirtualTextures.pak");
     if use_pak && !vt_pak_path.exists() {
         tracing::warn!("VirtualTextures.pak not found: {}", vt_pak_path.display());
-        return Ok(extracted_paths);
+        return Ok(extracted);
     }
 
     // Collect hashes for batch lookup
@@ -76,8 +80,11 @@ pub fn extract_virtual_textures(
                 &gts_rel_path,
                 &vt.name,
                 output_dir,
+                naming,
             ) {
-                Ok(paths) => extracted_paths.extend(paths),
+                Ok(paths) => {
+                    extracted.extend(bundle_textures(paths, "VirtualTextures.pak", gtp_rel_path));
+                }
                 Err(e) => {
                     tracing::warn!(
                         "Failed to extract virtual texture {} from PAK: {}",
@@ -122,8 +129,10 @@ pub fn extract_virtual_textures(
                 continue;
             }
 
-            match extract_and_rename_virtual_texture(&gtp_path, &gts_path, &vt.name, output_dir) {
-                Ok(paths) => extracted_paths.extend(paths),
+            match extract_and_rename_virtual_texture(
+                &gtp_path, &gts_path, &vt.name, output_dir, naming,
+            ) {
+                Ok(paths) => extracted.extend(bundle_textures(paths, "", &gtp_rel_path)),
                 Err(e) => {
                     tracing::warn!("Failed to extract virtual texture {}: {}", vt.name, e);
                 }
@@ -131,7 +140,28 @@ pub fn extract_virtual_textures(
         }
     }
 
-    Ok(extracted_paths)
+    Ok(extracted)
+}
+
+/// Manifest entries for the layers extracted from one virtual texture
+fn bundle_textures(
+    paths: Vec<(VirtualTextureLayer, PathBuf)>,
+    pak: &str,
+    gtp_path: &str,
+) -> impl Iterator<Item = BundleTexture> {
+    let pak = pak.to_string();
+    let gtp_path = gtp_path.to_string();
+    paths.into_iter().map(move |(layer, output)| BundleTexture {
+        layer: match layer {
+            VirtualTextureLayer::BaseMap => TextureLayer::BaseColor,
+            VirtualTextureLayer::NormalMap => TextureLayer::Normal,
+            VirtualTextureLayer::PhysicalMap => TextureLayer::Physical,
+        },
+        source: TextureSource::VirtualTexture,
+        pak: pak.clone(),
+        internal_path: gtp_path.clone(),
+        output,
+    })
 }
 
 /// Find GTP files in VirtualTextures.pak matching the given hashes
@@ -171,7 +201,8 @@ pub fn extract_virtual_texture_from_pak(
     gts_rel_path: &str,
     vt_name: &str,
     output_dir: &Path,
-) -> Result<Vec<PathBuf>> {
+    naming: VTexNaming,
+) -> Result<Vec<(VirtualTextureLayer, PathBuf)>> {
     // Create a unique temp directory for this extraction (supports parallel processing)
     use std::sync::atomic::{AtomicU64, Ordering};
     static COUNTER: AtomicU64 = AtomicU64::new(0);
@@ -225,7 +256,8 @@ pub fn extract_virtual_texture_from_pak(
     );
 
     // Extract and convert
-    let result = extract_and_rename_virtual_texture(&gtp_path, &gts_path, vt_name, output_dir);
+    let result =
+        extract_and_rename_virtual_texture(&gtp_path, &gts_path, vt_name, output_dir, naming);
 
     // Clean up temp directory
     let _ = std::fs::remove_dir_all(&temp_dir);
@@ -234,28 +266,36 @@ pub fn extract_virtual_texture_from_pak(
 }
 
 /// Extract virtual texture and rename output files with the visual name
+///
+/// With [`VTexNaming::LsLib`] the files keep `LSLib`'s `{GTex}_{index}.dds`
+/// names instead, so they match what its tools would have extracted.
 pub fn extract_and_rename_virtual_texture(
     gtp_path: &Path,
     gts_path: &Path,
     vt_name: &str,
     output_dir: &Path,
-) -> Result<Vec<PathBuf>> {
-    let mut extracted_paths = Vec::new();
+    naming: VTexNaming,
+) -> Result<Vec<(VirtualTextureLayer, PathBuf)>> {
+    let options = ExtractOptions {
+        naming,
+        ..ExtractOptions::default()
+    };
 
     // Extract and convert the virtual texture
-    VirtualTextureExtractor::extract_with_gts(gtp_path, gts_path, output_dir)?;
-
-    // The extractor creates BaseMap.dds, NormalMap.dds, PhysicalMap.dds
-    // Rename them to include the visual name
-    for layer in &["BaseMap", "NormalMap", "PhysicalMap"] {
-        let src = output_dir.join(format!("{layer}.dds"));
-        if src.exists() {
-            let dest = output_dir.join(format!("{}_{}.dds", vt_name, layer));
-            if std::fs::rename(&src, &dest).is_ok() {
-                extracted_paths.push(dest);
-            } else {
-                extracted_paths.push(src);
-            }
+    let written =
+        VirtualTextureExtractor::extract_layers(gtp_path, gts_path, output_dir, &options)?;
+    if naming == VTexNaming::LsLib {
+        return Ok(written);
+    }
+
+    // Rename {TileSet}_{GTex}_{Layer}.dds to include the visual name
+    let mut extracted_paths = Vec::new();
+    for (layer, src) in written {
+        let dest = output_dir.join(format!("{}_{}.dds", vt_name, layer.as_str()));
+        if std::fs::rename(&src, &dest).is_ok() {
+            extracted_paths.push((layer, dest));
+        } else {
+            extracted_paths.push((layer, src));
         }
     }
 
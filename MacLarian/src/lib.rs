@@ -11,6 +11,8 @@
 //! - **Virtual Textures** - GTS/GTP streaming texture extraction
 //! - **LOCA** - Localization files
 //! - **DDS/PNG** - Texture conversion
+//! - **Stats** - `Stats/Generated` text data (entries and treasure tables)
+//! - **WEM** - Wwise audio conversion to Ogg Vorbis and WAV
 //!
 //! ## Quick Start
 //!
@@ -59,6 +61,7 @@
 //! - `cli` - Enables the `maclarian` command-line binary
 
 pub mod compression;
+pub mod config;
 pub mod converter;
 pub mod error;
 pub mod formats;
@@ -71,8 +74,11 @@ pub mod utils;
 // Top-level domain modules (promoted from formats/)
 pub mod virtual_texture;
 
+#[cfg(test)]
+mod test_fixtures;
+
 // Re-exports for convenience
-pub use error::{Error, Result};
+pub use error::{Error, ErrorContext, ErrorKind, Result, ResultExt};
 
 /// Prelude module for common imports
 pub mod prelude {
@@ -91,8 +97,8 @@ pub mod prelude {
 
     // PAK operations
     pub use crate::pak::{
-        BatchPakResult, PakOperations, batch_create, batch_extract, find_packable_folders,
-        find_pak_files,
+        BatchPakResult, PakBuilder, PakOperations, batch_create, batch_extract,
+        find_packable_folders, find_pak_files,
     };
 
     pub use crate::converter;
@@ -107,7 +113,7 @@ pub mod prelude {
 
     // Mods module exports
     pub use crate::mods::{
-        InfoJsonResult, ModValidationResult, generate_info_json, validate_mod_structure,
+        InfoJsonResult, ModValidationResult, Version64, generate_info_json, validate_mod_structure,
     };
 }
 
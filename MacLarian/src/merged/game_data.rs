@@ -22,10 +22,9 @@
 //!
 //! # Path Detection
 //!
-//! The resolver searches for game data in platform-specific Steam paths:
-//! - macOS: `~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/Baldur's Gate 3.app/Contents/Data`
-//! - Windows: `C:\Program Files (x86)\Steam\steamapps\common\Baldurs Gate 3\Data`
-//! - Linux: `~/.steam/steam/steamapps/common/Baldurs Gate 3/Data`
+//! The resolver uses [`crate::utils::find_game_install`], which checks the
+//! `MACPAK_BG3_PATH` environment variable and then the standard Steam, Mac App
+//! Store and GOG install locations for the current platform.
 //!
 //! # Performance
 //!
@@ -114,10 +113,9 @@ impl GameDataResolver {
 
     /// Auto-detect game installation path.
     ///
-    /// Searches platform-specific Steam installation paths:
-    /// - macOS: `~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/Baldur's Gate 3.app/Contents/Data`
-    /// - Windows: `C:\Program Files (x86)\Steam\steamapps\common\Baldurs Gate 3\Data`
-    /// - Linux: `~/.steam/steam/steamapps/common/Baldurs Gate 3/Data`
+    /// Uses [`find_game_install`](crate::utils::find_game_install), which honors
+    /// `MACPAK_BG3_PATH` and searches the standard Steam, Mac App Store and GOG
+    /// locations for the current platform.
     ///
     /// # Errors
     ///
@@ -133,17 +131,8 @@ impl GameDataResolver {
     /// # Ok::<(), maclarian::error::Error>(())
     /// ```
     pub fn auto_detect() -> Result<Self> {
-        // Try macOS Steam path
-        if let Some(path) = super::bg3_data_path() {
-            if path.exists() {
-                return Self::new(path);
-            }
-        }
-
-        // Try Windows Steam path
-        let windows_path = PathBuf::from(BG3_DATA_PATH_WINDOWS);
-        if windows_path.exists() {
-            return Self::new(windows_path);
+        if let Some(install) = crate::utils::find_game_install() {
+            return Self::new(install.data_dir);
         }
 
         Err(Error::InvalidPath(
@@ -2,31 +2,21 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::utils::find_game_install;
+
 /// Default BG3 data path on macOS
 pub const BG3_DATA_PATH_MACOS: &str = "~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/Baldur's Gate 3.app/Contents/Data";
 
 /// Default BG3 data path on Linux
 pub const BG3_DATA_PATH_LINUX: &str = "~/.steam/steam/steamapps/common/Baldurs Gate 3/Data";
 
-/// Get the expanded BG3 data path for the current platform (resolves ~)
+/// Get the BG3 data path, as found by [`find_game_install`]
+///
+/// Honors `MACPAK_BG3_PATH` and the Steam, Mac App Store and GOG locations,
+/// so this may differ from the per-platform default paths above.
 #[must_use]
 pub fn bg3_data_path() -> Option<PathBuf> {
-    let home = std::env::var("HOME").ok()?;
-
-    #[cfg(target_os = "macos")]
-    {
-        Some(PathBuf::from(BG3_DATA_PATH_MACOS.replace('~', &home)))
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        Some(PathBuf::from(BG3_DATA_PATH_LINUX.replace('~', &home)))
-    }
-
-    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-    {
-        None
-    }
+    find_game_install().map(|install| install.data_dir)
 }
 
 /// Get the path to VirtualTextures.pak
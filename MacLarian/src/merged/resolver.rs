@@ -1,8 +1,10 @@
 //! Resolver for building asset databases from _merged.lsf files
 
-use crate::converter::convert_lsf_to_lsx;
+use crate::converter::{convert_lsf_to_lsx, to_lsx};
 use crate::error::{Error, Result};
-use crate::pak::PakOperations;
+use crate::formats::lsf::parse_lsf_bytes;
+use crate::formats::lsx::{LsxDocument, parse_lsx};
+use crate::pak::{PakOperations, VfsView};
 
 use super::parser::{
     merge_databases, parse_material_bank, parse_texture_bank, parse_virtual_texture_bank,
@@ -262,11 +264,58 @@ impl MergedResolver {
         Ok(Self { database })
     }
 
+    /// Create a resolver from every _merged.lsf visible through a [`VfsView`]
+    ///
+    /// Only the highest-priority copy of each merged file is read, so banks
+    /// overridden by a later PAK replace the originals.
+    ///
+    /// # Errors
+    /// Returns an error if no merged files are found or if parsing fails.
+    pub fn from_vfs(vfs: &VfsView) -> Result<Self> {
+        let merged_paths: Vec<String> = vfs
+            .list("")
+            .into_iter()
+            .filter(|p| p.ends_with("_merged.lsf"))
+            .collect();
+        if merged_paths.is_empty() {
+            return Err(Error::FileNotFoundInPak(
+                "No _merged.lsf files found in paks".to_string(),
+            ));
+        }
+        tracing::info!("Found {} _merged.lsf files in VFS", merged_paths.len());
+
+        let mut combined_db = MergedDatabase::new(
+            vfs.paks()
+                .iter()
+                .map(|p| p.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(";"),
+        );
+        for merged_path in &merged_paths {
+            let data = vfs.read(merged_path)?;
+            let doc = parse_lsx(&to_lsx(&parse_lsf_bytes(&data)?)?)?;
+            let db = Self::parse_document(&doc, merged_path);
+            merge_databases(&mut combined_db, db);
+        }
+
+        resolve_references(&mut combined_db);
+        log_stats(&combined_db);
+
+        Ok(Self {
+            database: combined_db,
+        })
+    }
+
     /// Parse a single LSX file and extract the banks
     fn parse_lsx_file<P: AsRef<Path>>(path: P) -> Result<MergedDatabase> {
         let path = path.as_ref();
         let doc = crate::formats::lsx::read_lsx(path)?;
-        let mut db = MergedDatabase::new(path.to_string_lossy());
+        Ok(Self::parse_document(&doc, &path.to_string_lossy()))
+    }
+
+    /// Extract the banks from a parsed merged document
+    fn parse_document(doc: &LsxDocument, source: &str) -> MergedDatabase {
+        let mut db = MergedDatabase::new(source);
 
         for region in &doc.regions {
             match region.id.as_str() {
@@ -278,7 +327,7 @@ impl MergedResolver {
             }
         }
 
-        Ok(db)
+        db
     }
 
     // -------------------------------------------------------------------------
@@ -10,7 +10,7 @@ use crate::pak::PakOperations;
 use super::types::{ModPhase, ModProgress, ModProgressCallback};
 
 /// Result of PAK file integrity check
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct PakIntegrityResult {
     /// Whether the PAK passes integrity checks
     pub valid: bool,
@@ -0,0 +1,306 @@
+//! Find `.lsx`/`.lsf` files that describe the same resource
+//!
+//! Mods often ship `Foo.lsx` next to an older `Foo.lsf`. The game loads the
+//! `.lsf`, so edits to the `.lsx` silently do nothing. Both files are
+//! converted through the same LSF -> LSX round trip before comparing, so
+//! whitespace, attribute quoting and number formatting don't count as
+//! differences.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::converter::{convert_lsf_to_lsx, convert_lsx_to_lsf, from_lsx, to_lsx};
+use crate::error::{Result, ResultExt};
+use crate::formats::lsf::read_lsf;
+use crate::formats::lsx::{LsxDocument, LsxNode, parse_lsx};
+
+/// One file of a companion pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PairSide {
+    /// The `.lsx` file
+    Lsx,
+    /// The `.lsf` file
+    Lsf,
+}
+
+impl PairSide {
+    /// File extension of this side
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Lsx => "lsx",
+            Self::Lsf => "lsf",
+        }
+    }
+}
+
+/// An `.lsx` and `.lsf` with the same name in the same folder
+#[derive(Debug, Clone, Serialize)]
+pub struct CompanionPair {
+    /// The `.lsx` file
+    pub lsx: PathBuf,
+    /// The `.lsf` file
+    pub lsf: PathBuf,
+    /// The file modified more recently, if their times differ
+    pub newer: Option<PairSide>,
+    /// First difference between the two, or `None` if they match
+    pub difference: Option<String>,
+}
+
+impl CompanionPair {
+    /// Whether both files hold the same document
+    #[must_use]
+    pub fn is_in_sync(&self) -> bool {
+        self.difference.is_none()
+    }
+
+    /// Describe a diverged pair, with paths shown relative to `root`
+    #[must_use]
+    pub fn warning(&self, root: &Path) -> String {
+        let lsx = self.lsx.strip_prefix(root).unwrap_or(&self.lsx);
+        let newer = match self.newer {
+            Some(side) => format!(" (the .{} is newer)", side.extension()),
+            None => String::new(),
+        };
+        format!(
+            "{} and its .lsf differ{newer}: {}",
+            lsx.display(),
+            self.difference.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Overwrite the other file of the pair with a conversion of `from`
+    ///
+    /// Returns the file that was written.
+    ///
+    /// # Errors
+    /// Returns an error if `from` cannot be converted or the other file
+    /// cannot be written.
+    pub fn sync(&self, from: PairSide) -> Result<&Path> {
+        match from {
+            PairSide::Lsx => {
+                convert_lsx_to_lsf(&self.lsx, &self.lsf)?;
+                Ok(&self.lsf)
+            }
+            PairSide::Lsf => {
+                convert_lsf_to_lsx(&self.lsf, &self.lsx)?;
+                Ok(&self.lsx)
+            }
+        }
+    }
+}
+
+/// Find `.lsx`/`.lsf` pairs under `dir`
+///
+/// Extensions are matched case-insensitively; hidden folders are skipped.
+/// Pairs are sorted by path.
+#[must_use]
+pub fn find_companion_pairs(dir: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let mut stems: BTreeMap<PathBuf, (Option<PathBuf>, Option<PathBuf>)> = BTreeMap::new();
+    let files = WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file());
+    for entry in files {
+        let path = entry.into_path();
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        let slot = stems.entry(path.with_extension("")).or_default();
+        match ext.as_deref() {
+            Some("lsx") => slot.0 = Some(path),
+            Some("lsf") => slot.1 = Some(path),
+            _ => {}
+        }
+    }
+
+    stems
+        .into_values()
+        .filter_map(|(lsx, lsf)| Some((lsx?, lsf?)))
+        .collect()
+}
+
+/// Find and compare every `.lsx`/`.lsf` pair under `dir`
+///
+/// A pair that can't be compared, e.g. because one file doesn't parse, is
+/// reported as differing with the error as its difference.
+#[must_use]
+pub fn check_companion_pairs(dir: &Path) -> Vec<CompanionPair> {
+    find_companion_pairs(dir)
+        .into_iter()
+        .map(|(lsx, lsf)| {
+            let difference = match compare_companions(&lsx, &lsf) {
+                Ok(difference) => difference,
+                Err(e) => Some(format!("could not compare: {e}")),
+            };
+            CompanionPair {
+                newer: newer_side(&lsx, &lsf),
+                difference,
+                lsx,
+                lsf,
+            }
+        })
+        .collect()
+}
+
+/// Compare an `.lsx` file with an `.lsf` file
+///
+/// Returns a description of the first difference, or `None` if both hold
+/// the same regions, nodes and attributes. Version headers are ignored.
+///
+/// # Errors
+/// Returns an error if either file cannot be read or parsed.
+pub fn compare_companions(lsx: &Path, lsf: &Path) -> Result<Option<String>> {
+    let content = std::fs::read_to_string(lsx).with_path(lsx)?;
+    let from_lsx = parse_lsx(&to_lsx(&from_lsx(&content).with_path(lsx)?)?)?;
+    let from_lsf = parse_lsx(&to_lsx(&read_lsf(lsf).with_path(lsf)?)?)?;
+    Ok(first_difference(&from_lsx, &from_lsf))
+}
+
+/// The more recently modified side, if the times can be read and differ
+fn newer_side(lsx: &Path, lsf: &Path) -> Option<PairSide> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let (lsx, lsf) = (modified(lsx)?, modified(lsf)?);
+    match lsx.cmp(&lsf) {
+        std::cmp::Ordering::Greater => Some(PairSide::Lsx),
+        std::cmp::Ordering::Less => Some(PairSide::Lsf),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Describe the first difference between two documents
+///
+/// LSF files name each region after its root node, so regions are compared
+/// through their nodes.
+fn first_difference(lsx: &LsxDocument, lsf: &LsxDocument) -> Option<String> {
+    let roots = |doc: &LsxDocument| -> Vec<LsxNode> {
+        doc.regions
+            .iter()
+            .flat_map(|region| region.nodes.iter().cloned())
+            .collect()
+    };
+    node_difference("", &roots(lsx), &roots(lsf))
+}
+
+/// Describe the first difference between two lists of sibling nodes
+fn node_difference(path: &str, lsx: &[LsxNode], lsf: &[LsxNode]) -> Option<String> {
+    let parent = if path.is_empty() { "document" } else { path };
+    if lsx.len() != lsf.len() {
+        return Some(format!(
+            "{parent}: {} child nodes in the .lsx, {} in the .lsf",
+            lsx.len(),
+            lsf.len()
+        ));
+    }
+    lsx.iter().zip(lsf).find_map(|(a, b)| {
+        let here = if path.is_empty() {
+            a.id.clone()
+        } else {
+            format!("{path}/{}", a.id)
+        };
+        if a.id != b.id || a.key != b.key {
+            return Some(format!(
+                "{parent}: node {} in the .lsx is {} in the .lsf",
+                a.id, b.id
+            ));
+        }
+        if a.attributes.len() != b.attributes.len() {
+            return Some(format!(
+                "{here}: {} attributes in the .lsx, {} in the .lsf",
+                a.attributes.len(),
+                b.attributes.len()
+            ));
+        }
+        a.attributes
+            .iter()
+            .zip(&b.attributes)
+            .find_map(|(x, y)| {
+                if x.id != y.id {
+                    return Some(format!(
+                        "{here}: attribute {} in the .lsx is {} in the .lsf",
+                        x.id, y.id
+                    ));
+                }
+                let same = x.type_name == y.type_name
+                    && x.value == y.value
+                    && x.handle == y.handle
+                    && x.version == y.version;
+                let show = |value: &str, handle: Option<&str>| match handle {
+                    Some(handle) => format!("{value:?} ({handle})"),
+                    None => format!("{value:?}"),
+                };
+                (!same).then(|| {
+                    format!(
+                        "{here}: {} is {} in the .lsx, {} in the .lsf",
+                        x.id,
+                        show(&x.value, x.handle.as_deref()),
+                        show(&y.value, y.handle.as_deref())
+                    )
+                })
+            })
+            .or_else(|| node_difference(&here, &a.children, &b.children))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::convert_bytes;
+
+    fn lsx(name: &str, level: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+	<version major="4" minor="0" revision="9" build="331" />
+	<region id="Config">
+		<node id="root">
+			<attribute id="Name" type="LSString" value="{name}" />
+			<attribute id="Level" type="float" value="{level}" />
+		</node>
+	</region>
+</save>
+"#
+        )
+    }
+
+    #[test]
+    fn test_companion_pairs() {
+        let base = tempfile::tempdir().unwrap();
+        let dir = base.path().join("Public/Test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let lsf_of = |content: &str| convert_bytes(content.as_bytes(), "lsx", "lsf").unwrap();
+
+        // Same content, different formatting
+        std::fs::write(dir.join("Same.lsx"), lsx("A", "1").replace('\t', "  ")).unwrap();
+        std::fs::write(dir.join("Same.lsf"), lsf_of(&lsx("A", "1.0"))).unwrap();
+        // The .lsx was edited after the .lsf was built
+        std::fs::write(dir.join("Stale.LSF"), lsf_of(&lsx("Old", "1"))).unwrap();
+        std::fs::write(dir.join("Stale.lsx"), lsx("New", "1")).unwrap();
+        // Not a pair
+        std::fs::write(dir.join("Alone.lsx"), lsx("A", "1")).unwrap();
+
+        let pairs = check_companion_pairs(base.path());
+        assert_eq!(pairs.len(), 2);
+        assert!(pairs[0].is_in_sync(), "{:?}", pairs[0].difference);
+        let stale = &pairs[1];
+        assert!(stale.lsx.ends_with("Stale.lsx"));
+        assert_eq!(
+            stale.difference.as_deref(),
+            Some(r#"root: Name is "New" in the .lsx, "Old" in the .lsf"#)
+        );
+        assert!(
+            stale
+                .warning(base.path())
+                .starts_with("Public/Test/Stale.lsx and its .lsf differ")
+        );
+
+        assert!(stale.sync(PairSide::Lsx).unwrap().ends_with("Stale.LSF"));
+        let pairs = check_companion_pairs(base.path());
+        assert!(pairs.iter().all(CompanionPair::is_in_sync));
+    }
+}
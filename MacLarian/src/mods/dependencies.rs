@@ -0,0 +1,494 @@
+//! Asset dependency graph built from LSX/LSF references
+//!
+//! Nodes are assets defined in a document: any LSX node with a `MapKey`,
+//! `ID` or `UUID` attribute. Edges come from attributes in
+//! [`REFERENCE_ATTRIBUTES`] (typed `guid` or `FixedString`) on the defining
+//! node or its children, pointing at the id they name. Referenced ids that
+//! are not defined anywhere in the source set stay in the graph as
+//! unresolved targets, so a mod can be queried without the base game loaded.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::converter::to_lsx;
+use crate::error::{Error, Result};
+use crate::formats::lsf::parse_lsf_bytes;
+use crate::formats::lsx::{LsxDocument, LsxNode, parse_lsx};
+use crate::pak::{PakOperations, VfsView};
+use crate::utils::uuid::{format_guid, parse_guid};
+
+/// Attributes whose value names another asset
+pub const REFERENCE_ATTRIBUTES: &[&str] = &[
+    "ParentTemplateId",
+    "TemplateName",
+    "VisualTemplate",
+    "PhysicsTemplate",
+    "CharacterVisualResourceID",
+    "MaterialResource",
+    "MaterialID",
+    "ColorPreset",
+    "SkinColor",
+    "HairColor",
+    "EyeColor",
+    "Icon",
+];
+
+/// Attributes that give a node its asset id, in priority order
+const ID_ATTRIBUTES: &[&str] = &["MapKey", "ID", "UUID"];
+
+/// Attribute types that can hold a reference
+const REFERENCE_TYPES: &[&str] = &["guid", "FixedString"];
+
+/// An asset defined in the source set
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AssetNode {
+    /// Asset id (GUIDs are lowercased)
+    pub id: String,
+    /// LSX node id of the definition, e.g. `GameObjects` or `Resource`
+    pub kind: String,
+    /// Value of the definition's `Name` attribute, if any
+    pub name: Option<String>,
+    /// File the asset was defined in
+    pub source: String,
+}
+
+/// Which way to follow references
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Assets the root refers to
+    Dependencies,
+    /// Assets that refer to the root
+    Dependents,
+}
+
+/// One asset reached from the root of a query
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DependencyEntry {
+    /// Asset id
+    pub id: String,
+    /// Number of references between the root and this asset
+    pub depth: usize,
+    /// Asset this one was reached from
+    pub via: String,
+    /// Reference attribute connecting `via` and this asset
+    pub attribute: String,
+    /// Whether the asset is defined in the source set
+    pub resolved: bool,
+}
+
+/// Reference graph over a set of LSX/LSF documents
+#[derive(Clone, Debug, Default)]
+pub struct DependencyGraph {
+    nodes: HashMap<String, AssetNode>,
+    /// id -> (attribute, referenced id)
+    forward: HashMap<String, BTreeSet<(String, String)>>,
+    /// id -> (attribute, referring id)
+    reverse: HashMap<String, BTreeSet<(String, String)>>,
+    documents: usize,
+}
+
+impl DependencyGraph {
+    /// Create an empty graph
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of defined assets
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether no assets are defined
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Number of documents added
+    #[must_use]
+    pub fn document_count(&self) -> usize {
+        self.documents
+    }
+
+    /// The definition of `id`, if it is in the source set
+    #[must_use]
+    pub fn node(&self, id: &str) -> Option<&AssetNode> {
+        self.nodes.get(&normalize_id(id))
+    }
+
+    /// Referenced ids with no definition in the source set, sorted
+    #[must_use]
+    pub fn unresolved(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .reverse
+            .keys()
+            .filter(|id| !self.nodes.contains_key(*id))
+            .map(String::as_str)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Add the assets and references in a parsed document
+    pub fn add_document(&mut self, doc: &LsxDocument, source: &str) {
+        self.documents += 1;
+        for region in &doc.regions {
+            for node in &region.nodes {
+                self.add_node(node, None, source);
+            }
+        }
+    }
+
+    /// Parse and add an `.lsx` or `.lsf` file's contents
+    ///
+    /// # Errors
+    /// Returns an error if the data cannot be parsed as the given format.
+    pub fn add_bytes(&mut self, data: &[u8], extension: &str, source: &str) -> Result<()> {
+        let doc = match extension.to_ascii_lowercase().as_str() {
+            "lsf" => parse_lsx(&to_lsx(&parse_lsf_bytes(data)?)?)?,
+            "lsx" => parse_lsx(&String::from_utf8_lossy(data))?,
+            other => {
+                return Err(Error::InvalidFormat(format!(
+                    "cannot read references from .{other}"
+                )));
+            }
+        };
+        self.add_document(&doc, source);
+        Ok(())
+    }
+
+    /// Add every `.lsx` and `.lsf` file under a mod directory
+    ///
+    /// Files that fail to parse are logged and skipped. Returns the number of
+    /// documents added.
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be walked.
+    pub fn add_dir(&mut self, dir: &Path) -> Result<usize> {
+        let mut added = 0;
+        for entry in WalkDir::new(dir) {
+            let entry = entry?;
+            let Some(ext) = document_extension(&entry.path().to_string_lossy()) else {
+                continue;
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let source = entry.path().to_string_lossy().into_owned();
+            match std::fs::read(entry.path())
+                .map_err(Error::from)
+                .and_then(|data| self.add_bytes(&data, ext, &source))
+            {
+                Ok(()) => added += 1,
+                Err(e) => tracing::warn!("Skipping {source}: {e}"),
+            }
+        }
+        Ok(added)
+    }
+
+    /// Add every `.lsx` and `.lsf` file in a PAK
+    ///
+    /// Files that fail to parse are logged and skipped. Returns the number of
+    /// documents added.
+    ///
+    /// # Errors
+    /// Returns an error if the PAK cannot be read.
+    pub fn add_pak(&mut self, pak: &Path) -> Result<usize> {
+        let files: Vec<String> = PakOperations::list(pak)?
+            .into_iter()
+            .filter(|f| document_extension(f).is_some())
+            .collect();
+        let contents = PakOperations::read_files_bytes(pak, &files)?;
+        let mut added = 0;
+        for file in &files {
+            let (Some(data), Some(ext)) = (contents.get(file), document_extension(file)) else {
+                continue;
+            };
+            let source = format!("{}:{file}", pak.display());
+            match self.add_bytes(data, ext, &source) {
+                Ok(()) => added += 1,
+                Err(e) => tracing::warn!("Skipping {source}: {e}"),
+            }
+        }
+        Ok(added)
+    }
+
+    /// Add every `.lsx` and `.lsf` file visible through a [`VfsView`]
+    ///
+    /// Only the highest-priority copy of each file is read. Files that fail
+    /// to parse are logged and skipped. Returns the number of documents
+    /// added.
+    ///
+    /// # Errors
+    /// Returns an error if a PAK cannot be read.
+    pub fn add_vfs(&mut self, vfs: &VfsView) -> Result<usize> {
+        let files: Vec<String> = vfs
+            .list("")
+            .into_iter()
+            .filter(|f| document_extension(f).is_some())
+            .collect();
+        let paths: Vec<&str> = files.iter().map(String::as_str).collect();
+        let contents = vfs.read_many(&paths)?;
+        let mut added = 0;
+        for file in &files {
+            let (Some(data), Some(ext), Some(pak)) = (
+                contents.get(file),
+                document_extension(file),
+                vfs.source_of(file),
+            ) else {
+                continue;
+            };
+            let source = format!("{}:{file}", pak.display());
+            match self.add_bytes(data, ext, &source) {
+                Ok(()) => added += 1,
+                Err(e) => tracing::warn!("Skipping {source}: {e}"),
+            }
+        }
+        Ok(added)
+    }
+
+    /// Everything `id` refers to, directly or transitively
+    #[must_use]
+    pub fn dependencies_of(&self, id: &str) -> Vec<DependencyEntry> {
+        self.closure(id, Direction::Dependencies)
+    }
+
+    /// Everything that refers to `id`, directly or transitively
+    #[must_use]
+    pub fn dependents_of(&self, id: &str) -> Vec<DependencyEntry> {
+        self.closure(id, Direction::Dependents)
+    }
+
+    /// Breadth-first transitive closure from `id`
+    ///
+    /// Each asset is reported once, at its shortest distance from the root,
+    /// so reference cycles terminate. The root itself is never included.
+    #[must_use]
+    pub fn closure(&self, id: &str, direction: Direction) -> Vec<DependencyEntry> {
+        let root = normalize_id(id);
+        let edges = match direction {
+            Direction::Dependencies => &self.forward,
+            Direction::Dependents => &self.reverse,
+        };
+
+        let mut seen: HashSet<&str> = HashSet::from([root.as_str()]);
+        let mut queue: VecDeque<(&str, usize)> = VecDeque::from([(root.as_str(), 0)]);
+        let mut entries = Vec::new();
+        while let Some((current, depth)) = queue.pop_front() {
+            for (attribute, next) in edges.get(current).into_iter().flatten() {
+                if !seen.insert(next) {
+                    continue;
+                }
+                entries.push(DependencyEntry {
+                    id: next.clone(),
+                    depth: depth + 1,
+                    via: current.to_string(),
+                    attribute: attribute.clone(),
+                    resolved: self.nodes.contains_key(next),
+                });
+                queue.push_back((next, depth + 1));
+            }
+        }
+        entries
+    }
+
+    /// Graphviz DOT for the closure of `id`, including every edge between
+    /// the assets it reaches (so cycles are visible)
+    #[must_use]
+    pub fn to_dot(&self, id: &str, direction: Direction) -> String {
+        let root = normalize_id(id);
+        let mut ids: BTreeSet<String> = self
+            .closure(&root, direction)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        ids.insert(root.clone());
+
+        let mut dot =
+            String::from("digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node_id in &ids {
+            let label = match self.nodes.get(node_id) {
+                Some(node) => format!(
+                    "{}\\n{}\\n{node_id}",
+                    node.name.as_deref().unwrap_or("(unnamed)"),
+                    node.kind
+                ),
+                None => format!("{node_id}\\n(unresolved)"),
+            };
+            let mut attrs = format!("label=\"{}\"", escape_dot(&label));
+            if *node_id == root {
+                attrs.push_str(", style=bold");
+            } else if !self.nodes.contains_key(node_id) {
+                attrs.push_str(", style=dashed");
+            }
+            let _ = writeln!(dot, "    \"{}\" [{attrs}];", escape_dot(node_id));
+        }
+
+        let mut edges: BTreeMap<(&str, &str), Vec<&str>> = BTreeMap::new();
+        for from in &ids {
+            for (attribute, to) in self.forward.get(from).into_iter().flatten() {
+                if ids.contains(to) {
+                    edges.entry((from, to)).or_default().push(attribute);
+                }
+            }
+        }
+        for ((from, to), attributes) in edges {
+            let _ = writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape_dot(from),
+                escape_dot(to),
+                escape_dot(&attributes.join(", "))
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Record `node` (if it defines an asset) and the references it holds,
+    /// attributing them to the nearest defining node
+    fn add_node(&mut self, node: &LsxNode, owner: Option<&str>, source: &str) {
+        let defined = ID_ATTRIBUTES.iter().find_map(|attr| {
+            node.attribute_value(attr)
+                .filter(|v| !v.is_empty() && !is_nil(v))
+                .map(normalize_id)
+        });
+        if let Some(id) = &defined {
+            self.nodes.entry(id.clone()).or_insert_with(|| AssetNode {
+                id: id.clone(),
+                kind: node.id.clone(),
+                name: node
+                    .attribute_value("Name")
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string),
+                source: source.to_string(),
+            });
+        }
+        let owner = defined.as_deref().or(owner);
+
+        if let Some(owner) = owner {
+            for attr in &node.attributes {
+                if !REFERENCE_ATTRIBUTES.contains(&attr.id.as_str())
+                    || !REFERENCE_TYPES.contains(&attr.type_name.as_str())
+                    || attr.value.is_empty()
+                    || is_nil(&attr.value)
+                {
+                    continue;
+                }
+                let target = normalize_id(&attr.value);
+                if target == owner {
+                    continue;
+                }
+                self.forward
+                    .entry(owner.to_string())
+                    .or_default()
+                    .insert((attr.id.clone(), target.clone()));
+                self.reverse
+                    .entry(target)
+                    .or_default()
+                    .insert((attr.id.clone(), owner.to_string()));
+            }
+        }
+
+        for child in &node.children {
+            self.add_node(child, owner, source);
+        }
+    }
+}
+
+/// Lowercase hyphenated form for GUIDs, anything else unchanged
+fn normalize_id(value: &str) -> String {
+    parse_guid(value).map_or_else(|| value.to_string(), |guid| format_guid(&guid))
+}
+
+/// Whether `value` is the all-zero GUID used for "no reference"
+fn is_nil(value: &str) -> bool {
+    parse_guid(value).is_some_and(|guid| guid == [0; 16])
+}
+
+/// `lsx` or `lsf` if `path` is a document the graph can read
+fn document_extension(path: &str) -> Option<&'static str> {
+    let (_, ext) = path.rsplit_once('.')?;
+    if ext.eq_ignore_ascii_case("lsx") {
+        Some("lsx")
+    } else if ext.eq_ignore_ascii_case("lsf") {
+        Some("lsf")
+    } else {
+        None
+    }
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "aaaaaaaa-0000-0000-0000-000000000001";
+    const B: &str = "bbbbbbbb-0000-0000-0000-000000000002";
+    const C: &str = "cccccccc-0000-0000-0000-000000000003";
+    const MISSING: &str = "dddddddd-0000-0000-0000-000000000004";
+
+    #[test]
+    fn test_closure_with_cycle() {
+        let lsx = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="331" />
+    <region id="Templates">
+        <node id="Templates">
+            <children>
+                <node id="GameObjects">
+                    <attribute id="MapKey" type="FixedString" value="{A}" />
+                    <attribute id="Name" type="LSString" value="Sword" />
+                    <attribute id="ParentTemplateId" type="FixedString" value="{B}" />
+                </node>
+                <node id="GameObjects">
+                    <attribute id="MapKey" type="FixedString" value="{B}" />
+                    <attribute id="VisualTemplate" type="FixedString" value="{C}" />
+                    <attribute id="Icon" type="FixedString" value="Item_Sword" />
+                </node>
+                <node id="GameObjects">
+                    <attribute id="MapKey" type="FixedString" value="{}" />
+                    <children>
+                        <node id="Material">
+                            <attribute id="MaterialID" type="FixedString" value="{MISSING}" />
+                            <attribute id="ParentTemplateId" type="guid" value="{A}" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#,
+            C.to_uppercase()
+        );
+        let mut graph = DependencyGraph::new();
+        graph.add_document(&parse_lsx(&lsx).unwrap(), "Templates.lsx");
+
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph.node(A).unwrap().name.as_deref(), Some("Sword"));
+        assert_eq!(graph.unresolved(), vec!["Item_Sword", MISSING]);
+
+        let deps = graph.dependencies_of(A);
+        let ids: Vec<(&str, usize)> = deps.iter().map(|e| (e.id.as_str(), e.depth)).collect();
+        assert_eq!(ids, vec![(B, 1), ("Item_Sword", 2), (C, 2), (MISSING, 3)]);
+        assert!(!deps[3].resolved);
+        assert_eq!(deps[3].attribute, "MaterialID");
+
+        let dependents = graph.dependents_of(A);
+        let ids: Vec<&str> = dependents.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec![C, B]);
+
+        let dot = graph.to_dot(A, Direction::Dependencies);
+        assert!(dot.contains(&format!("\"{C}\" -> \"{A}\" [label=\"ParentTemplateId\"]")));
+    }
+}
@@ -0,0 +1,400 @@
+//! Installing mod PAKs into the game's Mods folder
+//!
+//! [`deploy_mod`] copies (or symlinks) a PAK into the user's `Mods` directory
+//! and lists it in the profile's modsettings.lsx; [`undeploy_mod`] reverses
+//! both. Each returns the [`DeployAction`]s it took, and with `dry_run` only
+//! reports them without touching the disk.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::modsettings::ModSettings;
+use crate::error::{Error, Result};
+use crate::formats::{ModDependency, ModMetadata, parse_meta_lsf, parse_meta_lsx};
+use crate::pak::PakOperations;
+use crate::utils::find_game_install;
+
+/// The `Mods` directory and modsettings.lsx a mod is deployed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployTarget {
+    /// Directory the game loads mod PAKs from
+    pub mods_dir: PathBuf,
+    /// Active profile's modsettings.lsx
+    pub modsettings: PathBuf,
+}
+
+impl DeployTarget {
+    /// Target for a `Mods` directory, with modsettings.lsx found in the
+    /// sibling `PlayerProfiles/Public` directory
+    #[must_use]
+    pub fn new<P: Into<PathBuf>>(mods_dir: P) -> Self {
+        let mods_dir = mods_dir.into();
+        let modsettings = mods_dir
+            .parent()
+            .unwrap_or(&mods_dir)
+            .join("PlayerProfiles")
+            .join("Public")
+            .join("modsettings.lsx");
+        Self {
+            mods_dir,
+            modsettings,
+        }
+    }
+
+    /// Use `mods_dir` if given, otherwise the detected game install's
+    ///
+    /// # Errors
+    /// Returns [`Error::ModsDirNotFound`] if no directory is given and the
+    /// game's `Mods` directory can't be found.
+    pub fn locate(mods_dir: Option<&Path>) -> Result<Self> {
+        if let Some(dir) = mods_dir {
+            let expanded = shellexpand::tilde(&dir.to_string_lossy()).into_owned();
+            return Ok(Self::new(expanded));
+        }
+        find_game_install()
+            .and_then(|install| install.mods_dir)
+            .map(Self::new)
+            .ok_or(Error::ModsDirNotFound)
+    }
+}
+
+/// Options for [`deploy_mod`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeployOptions {
+    /// Symlink the PAK instead of copying it, so rebuilds are picked up
+    pub symlink: bool,
+    /// Report the actions without performing them
+    pub dry_run: bool,
+}
+
+/// One change made (or planned) by [`deploy_mod`] or [`undeploy_mod`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployAction {
+    /// Copy the PAK into the Mods directory
+    CopyPak {
+        /// PAK being deployed
+        from: PathBuf,
+        /// Destination in the Mods directory
+        to: PathBuf,
+    },
+    /// Symlink the PAK into the Mods directory
+    LinkPak {
+        /// PAK being deployed
+        from: PathBuf,
+        /// Link created in the Mods directory
+        to: PathBuf,
+    },
+    /// Delete a PAK (or link) from the Mods directory
+    RemovePak {
+        /// File removed
+        path: PathBuf,
+    },
+    /// List the mod in modsettings.lsx
+    AddToModSettings {
+        /// Mod display name
+        name: String,
+        /// Mod UUID
+        uuid: String,
+    },
+    /// Refresh the mod's existing modsettings.lsx entry
+    UpdateModSettings {
+        /// Mod display name
+        name: String,
+        /// Mod UUID
+        uuid: String,
+    },
+    /// Remove the mod from modsettings.lsx
+    RemoveFromModSettings {
+        /// Mod display name
+        name: String,
+        /// Mod UUID
+        uuid: String,
+    },
+}
+
+impl fmt::Display for DeployAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CopyPak { from, to } => {
+                write!(f, "copy {} -> {}", from.display(), to.display())
+            }
+            Self::LinkPak { from, to } => {
+                write!(f, "link {} -> {}", to.display(), from.display())
+            }
+            Self::RemovePak { path } => write!(f, "remove {}", path.display()),
+            Self::AddToModSettings { name, uuid } => {
+                write!(f, "add {name} ({uuid}) to modsettings.lsx")
+            }
+            Self::UpdateModSettings { name, uuid } => {
+                write!(f, "update {name} ({uuid}) in modsettings.lsx")
+            }
+            Self::RemoveFromModSettings { name, uuid } => {
+                write!(f, "remove {name} ({uuid}) from modsettings.lsx")
+            }
+        }
+    }
+}
+
+/// Install a mod PAK and enable it in modsettings.lsx
+///
+/// The PAK's `Mods/*/meta.lsx` supplies the UUID, name, folder and version
+/// for the modsettings entry. A PAK of the same name already in the Mods
+/// directory is replaced.
+///
+/// # Errors
+/// Returns an error if the PAK has no meta.lsx or UUID, or if copying the PAK
+/// or writing modsettings.lsx fails.
+pub fn deploy_mod(
+    pak: &Path,
+    target: &DeployTarget,
+    options: &DeployOptions,
+) -> Result<Vec<DeployAction>> {
+    let meta = read_pak_meta(pak)?;
+    if !meta.is_valid() {
+        return Err(Error::InvalidFormat(format!(
+            "meta.lsx in {} has no UUID",
+            pak.display()
+        )));
+    }
+    let file_name = pak
+        .file_name()
+        .ok_or_else(|| Error::InvalidPath(pak.display().to_string()))?;
+    let source = std::fs::canonicalize(pak)?;
+    let dest = target.mods_dir.join(file_name);
+
+    let mut actions = Vec::new();
+    let existing = dest.symlink_metadata().is_ok();
+    let is_link = dest.is_symlink();
+    // Deploying the PAK already in the Mods directory, or relinking an
+    // existing link, leaves the file alone
+    let in_place = !is_link && std::fs::canonicalize(&dest).is_ok_and(|d| d == source);
+    let linked = is_link && std::fs::read_link(&dest).is_ok_and(|d| d == source);
+    let up_to_date = in_place || (options.symlink && linked);
+    if !up_to_date {
+        if existing {
+            actions.push(DeployAction::RemovePak { path: dest.clone() });
+        }
+        actions.push(if options.symlink {
+            DeployAction::LinkPak {
+                from: source.clone(),
+                to: dest.clone(),
+            }
+        } else {
+            DeployAction::CopyPak {
+                from: source.clone(),
+                to: dest.clone(),
+            }
+        });
+    }
+
+    let module = ModDependency {
+        uuid: meta.uuid.clone(),
+        name: meta.name.clone(),
+        folder: meta.folder.clone(),
+        version64: meta.version64,
+    };
+    let mut settings = ModSettings::read_or_default(&target.modsettings)?;
+    let listed = settings.find(&module.uuid);
+    if listed.as_ref() != Some(&module) {
+        let (name, uuid) = (module.name.clone(), module.uuid.clone());
+        actions.push(if listed.is_some() {
+            DeployAction::UpdateModSettings { name, uuid }
+        } else {
+            DeployAction::AddToModSettings { name, uuid }
+        });
+    }
+
+    if options.dry_run {
+        return Ok(actions);
+    }
+
+    std::fs::create_dir_all(&target.mods_dir)?;
+    for action in &actions {
+        match action {
+            DeployAction::RemovePak { path } => std::fs::remove_file(path)?,
+            DeployAction::CopyPak { from, to } => {
+                std::fs::copy(from, to)?;
+            }
+            DeployAction::LinkPak { from, to } => symlink(from, to)?,
+            _ => {}
+        }
+    }
+    if listed.as_ref() != Some(&module) {
+        settings.upsert(&module);
+        settings.write(&target.modsettings)?;
+    }
+    Ok(actions)
+}
+
+/// Remove a deployed mod's PAK and its modsettings.lsx entry
+///
+/// `mod_ref` is either the mod's UUID or a PAK file name (or path) ending in
+/// `.pak`; for a UUID, the Mods directory is searched for the PAK whose
+/// meta.lsx has that UUID.
+///
+/// # Errors
+/// Returns [`Error::ModNotDeployed`] if neither a PAK nor a modsettings entry
+/// matches, or an error if removing the PAK or writing modsettings.lsx fails.
+pub fn undeploy_mod(
+    mod_ref: &str,
+    target: &DeployTarget,
+    dry_run: bool,
+) -> Result<Vec<DeployAction>> {
+    let (pak, uuid) = if mod_ref.to_lowercase().ends_with(".pak") {
+        let given = Path::new(mod_ref);
+        let pak = given
+            .file_name()
+            .map(|name| target.mods_dir.join(name))
+            .filter(|p| p.symlink_metadata().is_ok());
+        let uuid = [pak.as_deref(), Some(given)]
+            .into_iter()
+            .flatten()
+            .find_map(|p| read_pak_meta(p).ok())
+            .map(|meta| meta.uuid)
+            .filter(|uuid| !uuid.is_empty());
+        (pak, uuid)
+    } else {
+        (
+            find_deployed_pak(&target.mods_dir, mod_ref),
+            Some(mod_ref.to_string()),
+        )
+    };
+
+    let mut settings = ModSettings::read_or_default(&target.modsettings)?;
+    let listed = uuid.as_deref().and_then(|uuid| settings.find(uuid));
+    if pak.is_none() && listed.is_none() {
+        return Err(Error::ModNotDeployed {
+            name: mod_ref.to_string(),
+        });
+    }
+
+    let mut actions = Vec::new();
+    if let Some(path) = &pak {
+        actions.push(DeployAction::RemovePak { path: path.clone() });
+    }
+    if let Some(module) = &listed {
+        actions.push(DeployAction::RemoveFromModSettings {
+            name: module.name.clone(),
+            uuid: module.uuid.clone(),
+        });
+    }
+
+    if dry_run {
+        return Ok(actions);
+    }
+
+    if let Some(path) = &pak {
+        std::fs::remove_file(path)?;
+    }
+    if let Some(module) = &listed {
+        settings.remove(&module.uuid);
+        settings.write(&target.modsettings)?;
+    }
+    Ok(actions)
+}
+
+/// Read the `Mods/*/meta.lsx` of a mod PAK
+///
+/// Falls back to a binary `meta.lsf` when the PAK has no meta.lsx.
+///
+/// # Errors
+/// Returns [`Error::FileNotFoundInPak`] if the PAK has neither, or an
+/// error if the PAK cannot be read.
+pub fn read_pak_meta(pak: &Path) -> Result<ModMetadata> {
+    let entries = PakOperations::list(pak)?;
+    let find_meta = |name: &str| {
+        entries.iter().find(|path| {
+            let parts: Vec<&str> = path.split('/').collect();
+            parts.len() == 3 && parts[0] == "Mods" && parts[2].eq_ignore_ascii_case(name)
+        })
+    };
+
+    if let Some(meta_path) = find_meta("meta.lsx") {
+        let bytes = PakOperations::read_file_bytes(pak, meta_path)?;
+        return Ok(parse_meta_lsx(&String::from_utf8_lossy(&bytes)));
+    }
+    let meta_path =
+        find_meta("meta.lsf").ok_or_else(|| Error::FileNotFoundInPak("meta.lsx".to_string()))?;
+    parse_meta_lsf(&PakOperations::read_file_bytes(pak, meta_path)?)
+}
+
+/// The PAK in `mods_dir` whose meta.lsx has `uuid`
+fn find_deployed_pak(mods_dir: &Path, uuid: &str) -> Option<PathBuf> {
+    std::fs::read_dir(mods_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"))
+        })
+        .find(|path| read_pak_meta(path).is_ok_and(|meta| meta.uuid.eq_ignore_ascii_case(uuid)))
+}
+
+#[cfg(unix)]
+fn symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(from, to)
+}
+
+#[cfg(windows)]
+fn symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::generate_meta_lsx;
+
+    const UUID: &str = "11111111-2222-3333-4444-555555555555";
+
+    #[test]
+    fn test_deploy_and_undeploy() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("MyMod");
+        std::fs::create_dir_all(source.join("Mods/MyMod")).unwrap();
+        let meta = generate_meta_lsx("My Mod", "MyMod", "Me", "", UUID, 1, 0, 0, 0);
+        std::fs::write(source.join("Mods/MyMod/meta.lsx"), meta).unwrap();
+        let pak = dir.path().join("MyMod.pak");
+        PakOperations::create(&source, &pak).unwrap();
+
+        // Stands in for the "Larian Studios/Baldur's Gate 3" profile folder
+        let profile = dir.path().join("Baldur's Gate 3");
+        let target = DeployTarget::new(profile.join("Mods"));
+        let deployed = target.mods_dir.join("MyMod.pak");
+
+        let dry_run = DeployOptions {
+            dry_run: true,
+            ..DeployOptions::default()
+        };
+        let actions = deploy_mod(&pak, &target, &dry_run).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(&actions[0], DeployAction::CopyPak { to, .. } if *to == deployed));
+        assert!(!target.mods_dir.exists() && !target.modsettings.exists());
+
+        deploy_mod(&pak, &target, &DeployOptions::default()).unwrap();
+        assert!(deployed.is_file());
+        let settings = ModSettings::read(&target.modsettings).unwrap();
+        assert_eq!(settings.find(UUID).unwrap().name, "My Mod");
+
+        // Redeploying replaces the PAK but leaves modsettings alone
+        let actions = deploy_mod(&pak, &target, &DeployOptions::default()).unwrap();
+        assert!(matches!(
+            actions.as_slice(),
+            [DeployAction::RemovePak { .. }, DeployAction::CopyPak { .. }]
+        ));
+
+        let actions = undeploy_mod(UUID, &target, true).unwrap();
+        assert_eq!(actions.len(), 2);
+        assert!(deployed.is_file());
+
+        undeploy_mod(UUID, &target, false).unwrap();
+        assert!(!deployed.exists());
+        let settings = ModSettings::read(&target.modsettings).unwrap();
+        assert!(settings.mods().is_empty());
+        assert!(matches!(
+            undeploy_mod("MyMod.pak", &target, false),
+            Err(Error::ModNotDeployed { .. })
+        ));
+    }
+}
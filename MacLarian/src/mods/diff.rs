@@ -0,0 +1,261 @@
+//! Compare a mod source directory against a built PAK
+//!
+//! Source files are mapped to the path they should have inside the PAK,
+//! converted in memory where the build converts them (e.g. `.lsx` -> `.lsf`),
+//! and compared by SHA-256 hash. PAK entries are read one at a time, so the
+//! whole PAK is never held in memory.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::converter::convert_bytes;
+use crate::error::Result;
+use crate::pak::{PakOperations, PakReaderCache};
+
+/// Maps a source path (relative, `/`-separated) to the format it is converted
+/// to during the build, or `None` if it is packed as-is
+pub type ConversionMap<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Differences between a source directory and a PAK
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WorkspaceDiff {
+    /// Source files with no counterpart in the PAK
+    pub only_in_source: Vec<String>,
+    /// PAK files with no counterpart in the source directory
+    pub only_in_pak: Vec<String>,
+    /// PAK paths whose contents differ from the (converted) source
+    pub mismatched: Vec<String>,
+    /// `(source, pak)` pairs that were compared after conversion
+    pub converted: Vec<(String, String)>,
+    /// PAK paths that several source files map to (e.g. `Doc.lsx` and
+    /// `Doc.lsj` both building `Doc.lsf`), with those source files
+    pub collisions: Vec<(String, Vec<String>)>,
+    /// Number of files with identical contents
+    pub matched: usize,
+}
+
+impl WorkspaceDiff {
+    /// Whether the PAK matches the source exactly
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.problem_count() == 0
+    }
+
+    /// Number of differences and collisions
+    #[must_use]
+    pub fn problem_count(&self) -> usize {
+        self.only_in_source.len()
+            + self.only_in_pak.len()
+            + self.mismatched.len()
+            + self.collisions.len()
+    }
+}
+
+/// Compare a source directory with a PAK, inferring conversions
+///
+/// A source `.lsx`/`.lsj` file is compared against the `.lsf` with the same
+/// stem when the PAK has no `.lsx`/`.lsj` at that path, and likewise `.xml`
+/// against `.loca`.
+///
+/// # Errors
+/// Returns an error if the directory or PAK cannot be read.
+pub fn diff_against_pak(source_dir: &Path, pak_path: &Path) -> Result<WorkspaceDiff> {
+    let pak_files: HashSet<String> = PakOperations::list(pak_path)?.into_iter().collect();
+
+    let infer = |rel: &str| -> Option<String> {
+        if pak_files.contains(rel) {
+            return None;
+        }
+        let (stem, ext) = rel.rsplit_once('.')?;
+        let target = match ext.to_lowercase().as_str() {
+            "lsx" | "lsj" => "lsf",
+            "xml" => "loca",
+            _ => return None,
+        };
+        pak_files
+            .contains(&format!("{stem}.{target}"))
+            .then(|| target.to_string())
+    };
+
+    diff_against_pak_with(source_dir, pak_path, &infer)
+}
+
+/// Compare a source directory with a PAK using explicit conversions
+///
+/// Source files that map to the same PAK path are reported in
+/// [`WorkspaceDiff::collisions`] and not compared.
+///
+/// # Errors
+/// Returns an error if the directory or PAK cannot be read, or a source file
+/// fails to convert.
+pub fn diff_against_pak_with(
+    source_dir: &Path,
+    pak_path: &Path,
+    conversion: ConversionMap,
+) -> Result<WorkspaceDiff> {
+    let mut cache = PakReaderCache::new(1);
+    let pak_list: Vec<String> = cache
+        .list_files(pak_path)?
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+    let pak_files: HashSet<&str> = pak_list.iter().map(String::as_str).collect();
+
+    // Expected PAK path -> source files as (path, target format if converted)
+    let mut expected: BTreeMap<String, Vec<(String, Option<String>)>> = BTreeMap::new();
+    let mut source_files = Vec::new();
+    collect_files(source_dir, source_dir, &mut source_files)?;
+    source_files.sort();
+    for rel in source_files {
+        let target = conversion(&rel);
+        let pak_rel = match (&target, rel.rsplit_once('.')) {
+            (Some(target), Some((stem, _))) => format!("{stem}.{target}"),
+            (Some(target), None) => format!("{rel}.{target}"),
+            (None, _) => rel.clone(),
+        };
+        expected.entry(pak_rel).or_default().push((rel, target));
+    }
+
+    let mut diff = WorkspaceDiff::default();
+    for (pak_rel, sources) in &expected {
+        let [(rel, target)] = sources.as_slice() else {
+            let rels = sources.iter().map(|(rel, _)| rel.clone()).collect();
+            diff.collisions.push((pak_rel.clone(), rels));
+            continue;
+        };
+        if !pak_files.contains(pak_rel.as_str()) {
+            diff.only_in_source.push(rel.clone());
+            continue;
+        }
+
+        let data = std::fs::read(source_dir.join(rel))?;
+        let data = match target {
+            Some(target) => {
+                diff.converted.push((rel.clone(), pak_rel.clone()));
+                let ext = rel.rsplit_once('.').map_or("", |(_, ext)| ext);
+                convert_bytes(&data, ext, target)?
+            }
+            None => data,
+        };
+
+        let packed = cache.read_file(pak_path, pak_rel)?;
+        if Sha256::digest(&data) == Sha256::digest(&packed) {
+            diff.matched += 1;
+        } else {
+            diff.mismatched.push(pak_rel.clone());
+        }
+    }
+
+    diff.only_in_pak = pak_list
+        .iter()
+        .filter(|p| !expected.contains_key(*p))
+        .cloned()
+        .collect();
+    diff.only_in_pak.sort();
+
+    Ok(diff)
+}
+
+/// Recursively collect non-hidden files as `/`-separated paths relative to `root`
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() || entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            files.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::CONFIG_LSX;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_diff_against_pak() {
+        let base = tempfile::tempdir().unwrap();
+        let (packed, source) = (base.path().join("packed"), base.path().join("source"));
+        let pak = base.path().join("Test.pak");
+        for dir in [&packed, &source] {
+            std::fs::create_dir_all(dir.join("Public/Test")).unwrap();
+        }
+
+        std::fs::write(
+            packed.join("Public/Test/Doc.lsf"),
+            convert_bytes(CONFIG_LSX.as_bytes(), "lsx", "lsf").unwrap(),
+        )
+        .unwrap();
+        std::fs::write(packed.join("Public/Test/a.txt"), "old").unwrap();
+        std::fs::write(packed.join("Public/Test/stale.txt"), "stale").unwrap();
+        PakOperations::create(&packed, &pak).unwrap();
+
+        std::fs::write(source.join("Public/Test/Doc.lsx"), CONFIG_LSX).unwrap();
+        std::fs::write(source.join("Public/Test/a.txt"), "new").unwrap();
+        std::fs::write(source.join("Public/Test/added.txt"), "added").unwrap();
+
+        let diff = diff_against_pak(&source, &pak).unwrap();
+
+        assert_eq!(diff.matched, 1);
+        assert_eq!(
+            diff.converted,
+            vec![(
+                "Public/Test/Doc.lsx".to_string(),
+                "Public/Test/Doc.lsf".to_string()
+            )]
+        );
+        assert_eq!(diff.mismatched, vec!["Public/Test/a.txt".to_string()]);
+        assert_eq!(
+            diff.only_in_source,
+            vec!["Public/Test/added.txt".to_string()]
+        );
+        assert_eq!(diff.only_in_pak, vec!["Public/Test/stale.txt".to_string()]);
+        assert!(!diff.is_clean());
+    }
+
+    #[test]
+    fn test_diff_reports_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let (packed, source) = (dir.path().join("packed"), dir.path().join("source"));
+        let pak = dir.path().join("Test.pak");
+        for dir in [&packed, &source] {
+            std::fs::create_dir_all(dir.join("Public/Test")).unwrap();
+        }
+
+        let lsf = convert_bytes(CONFIG_LSX.as_bytes(), "lsx", "lsf").unwrap();
+        std::fs::write(packed.join("Public/Test/Doc.lsf"), &lsf).unwrap();
+        PakOperations::create(&packed, &pak).unwrap();
+
+        let lsj = convert_bytes(CONFIG_LSX.as_bytes(), "lsx", "lsj").unwrap();
+        std::fs::write(source.join("Public/Test/Doc.lsx"), CONFIG_LSX).unwrap();
+        std::fs::write(source.join("Public/Test/Doc.lsj"), lsj).unwrap();
+
+        let diff = diff_against_pak(&source, &pak).unwrap();
+        assert_eq!(
+            diff.collisions,
+            vec![(
+                "Public/Test/Doc.lsf".to_string(),
+                vec![
+                    "Public/Test/Doc.lsj".to_string(),
+                    "Public/Test/Doc.lsx".to_string()
+                ]
+            )]
+        );
+        assert_eq!(diff.matched, 0);
+        assert!(diff.only_in_pak.is_empty());
+        assert_eq!(diff.problem_count(), 1);
+        assert!(!diff.is_clean());
+    }
+}
@@ -1,10 +1,14 @@
 //! info.json generation for ``BaldursModManager`` compatibility
 
+use std::collections::BTreeSet;
 use std::fmt::Write;
 use std::io::Read;
 use std::path::Path;
 
-use crate::formats::ModMetadata;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::formats::{ModDependency, ModMetadata};
 use crate::pak::PakOperations;
 
 use super::types::{ModPhase, ModProgress, ModProgressCallback};
@@ -19,6 +23,88 @@ pub struct InfoJsonResult {
     pub message: String,
 }
 
+/// Options controlling info.json generation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InfoJsonOptions {
+    /// Include dependency details, the PAK's internal directories and a SHA-256 hash.
+    ///
+    /// The basic fields are emitted unchanged either way, so consumers that
+    /// only understand the `BaldursModManager` schema keep working.
+    pub extended: bool,
+}
+
+/// info.json document (`BaldursModManager` schema plus optional extended fields)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfoJson {
+    /// Mods described by this file (always one for generated files).
+    #[serde(rename = "Mods")]
+    pub mods: Vec<InfoJsonMod>,
+    /// MD5 of the PAK file.
+    #[serde(rename = "MD5")]
+    pub md5: String,
+    /// SHA-256 of the PAK file (extended only).
+    #[serde(rename = "SHA256", default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Internal `<top>/<second>` directories the PAK contains, for conflict hinting (extended only).
+    #[serde(
+        rename = "Directories",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub directories: Option<Vec<String>>,
+}
+
+/// A mod entry in info.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InfoJsonMod {
+    /// Mod author.
+    pub author: String,
+    /// Mod display name.
+    pub name: String,
+    /// Mod folder name.
+    pub folder: String,
+    /// Raw Version64 integer as a string (matches BG3 Modder's Multitool format).
+    pub version: Option<String>,
+    /// Mod description.
+    pub description: String,
+    /// Mod UUID.
+    #[serde(rename = "UUID")]
+    pub uuid: String,
+    /// Generation timestamp (RFC 3339).
+    pub created: String,
+    /// Dependencies (always empty unless extended output is requested).
+    pub dependencies: Vec<InfoJsonDependency>,
+    /// Random group UUID.
+    pub group: String,
+}
+
+/// A dependency entry in extended info.json output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InfoJsonDependency {
+    /// Dependency UUID.
+    #[serde(rename = "UUID")]
+    pub uuid: String,
+    /// Dependency display name.
+    pub name: String,
+    /// Dependency folder name.
+    pub folder: String,
+    /// Required Version64 as a string, if specified.
+    pub version: Option<String>,
+}
+
+impl From<&ModDependency> for InfoJsonDependency {
+    fn from(dep: &ModDependency) -> Self {
+        Self {
+            uuid: dep.uuid.clone(),
+            name: dep.name.clone(),
+            folder: dep.folder.clone(),
+            version: dep.version64.map(|v| v.to_string()),
+        }
+    }
+}
+
 /// Generate info.json content for a mod
 ///
 /// # Arguments
@@ -46,6 +132,26 @@ pub fn generate_info_json_with_progress(
     source_dir: &str,
     pak_path: &str,
     progress: ModProgressCallback,
+) -> InfoJsonResult {
+    generate_info_json_with_options(source_dir, pak_path, InfoJsonOptions::default(), progress)
+}
+
+/// Generate info.json content for a mod with options and progress callback
+///
+/// # Arguments
+/// * `source_dir` - Path to the mod source directory (extracted PAK contents)
+/// * `pak_path` - Path to the PAK file (for hashing and directory listing)
+/// * `options` - Generation options
+/// * `progress` - Progress callback
+///
+/// # Returns
+/// `InfoJsonResult` with the generated JSON and status
+#[must_use]
+pub fn generate_info_json_with_options(
+    source_dir: &str,
+    pak_path: &str,
+    options: InfoJsonOptions,
+    progress: ModProgressCallback,
 ) -> InfoJsonResult {
     progress(&ModProgress::with_file(
         ModPhase::Validating,
@@ -95,7 +201,7 @@ pub fn generate_info_json_with_progress(
     ));
 
     // Generate the info.json content
-    let json = generate_info_json_content(&metadata, &pak_md5);
+    let json = generate_info_json_content(&metadata, &pak_md5, Path::new(pak_path), options);
 
     progress(&ModProgress::new(ModPhase::Complete, 3, 3));
 
@@ -163,47 +269,97 @@ pub fn calculate_file_md5(file_path: &str) -> Option<String> {
 }
 
 /// Generate info.json content from metadata
-fn generate_info_json_content(metadata: &ModMetadata, pak_md5: &str) -> String {
-    // Use raw Version64 integer as string (matches BG3 Modder's Multitool format)
-    let version_json = match metadata.version64 {
-        Some(v) => format!("\"{v}\""),
-        None => "null".to_string(),
-    };
+fn generate_info_json_content(
+    metadata: &ModMetadata,
+    pak_md5: &str,
+    pak_path: &Path,
+    options: InfoJsonOptions,
+) -> String {
+    let mut info = build_info_json(metadata, pak_md5);
+
+    if options.extended {
+        info.mods[0].dependencies = metadata
+            .dependencies
+            .iter()
+            .map(InfoJsonDependency::from)
+            .collect();
+        info.sha256 = Some(calculate_file_sha256(pak_path).unwrap_or_default());
+        info.directories = Some(list_pak_directories(pak_path));
+    }
 
-    // Escape strings for JSON
-    let name = escape_json_string(&metadata.name);
-    let folder = escape_json_string(&metadata.folder);
-    let author = escape_json_string(&metadata.author);
-    let description = escape_json_string(&metadata.description);
+    serde_json::to_string(&info).unwrap_or_default()
+}
 
+/// Build the basic info.json document from metadata
+fn build_info_json(metadata: &ModMetadata, pak_md5: &str) -> InfoJson {
     // Get current timestamp in ISO format
     let now = chrono::Utc::now();
     let created = now.to_rfc3339_opts(chrono::SecondsFormat::Micros, true);
 
-    // Generate a random Group UUID
-    let group_uuid = uuid::Uuid::new_v4().to_string();
-
-    format!(
-        r#"{{"Mods":[{{"Author":"{}","Name":"{}","Folder":"{}","Version":{},"Description":"{}","UUID":"{}","Created":"{}","Dependencies":[],"Group":"{}"}}],"MD5":"{}"}}"#,
-        author,
-        name,
-        folder,
-        version_json,
-        description,
-        metadata.uuid,
-        created,
-        group_uuid,
-        pak_md5
-    )
+    InfoJson {
+        mods: vec![InfoJsonMod {
+            author: metadata.author.clone(),
+            name: metadata.name.clone(),
+            folder: metadata.folder.clone(),
+            // Use raw Version64 integer as string (matches BG3 Modder's Multitool format)
+            version: metadata.version64.map(|v| v.to_string()),
+            description: metadata.description.clone(),
+            uuid: metadata.uuid.clone(),
+            created,
+            dependencies: Vec::new(),
+            // Generate a random Group UUID
+            group: uuid::Uuid::new_v4().to_string(),
+        }],
+        md5: pak_md5.to_string(),
+        sha256: None,
+        directories: None,
+    }
 }
 
-/// Escape a string for JSON
-fn escape_json_string(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
+/// Calculate SHA-256 hash of a file (streaming for large files)
+#[must_use]
+pub fn calculate_file_sha256(file_path: &Path) -> Option<String> {
+    let mut file = std::fs::File::open(file_path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        let bytes_read = file.read(&mut buffer).ok()?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    let mut hex = String::with_capacity(64);
+    for b in hasher.finalize() {
+        let _ = write!(hex, "{b:02x}");
+    }
+    Some(hex)
+}
+
+/// List the distinct `<top>/<second>` directories inside a PAK (e.g. `Public/MyMod`)
+fn list_pak_directories(pak_path: &Path) -> Vec<String> {
+    let Ok(files) = PakOperations::list(pak_path) else {
+        return Vec::new();
+    };
+
+    let dirs: BTreeSet<String> = files
+        .iter()
+        .filter_map(|f| {
+            let mut parts = f.split('/');
+            let top = parts.next()?;
+            let second = parts.next()?;
+            // Files directly under a top-level folder only contribute the folder itself
+            Some(if parts.next().is_some() {
+                format!("{top}/{second}")
+            } else {
+                top.to_string()
+            })
+        })
+        .collect();
+
+    dirs.into_iter().collect()
 }
 
 /// Generate info.json from a single source (PAK file or mod directory)
@@ -218,20 +374,42 @@ fn escape_json_string(s: &str) -> String {
 pub fn generate_info_json_from_source(
     source: &Path,
     progress: ModProgressCallback,
+) -> InfoJsonResult {
+    generate_info_json_from_source_with_options(source, InfoJsonOptions::default(), progress)
+}
+
+/// Generate info.json from a single source (PAK file or mod directory) with options
+///
+/// # Arguments
+/// * `source` - Path to either a .pak file or a mod directory
+/// * `options` - Generation options
+/// * `progress` - Progress callback
+///
+/// # Returns
+/// `InfoJsonResult` with the generated JSON and status
+#[must_use]
+pub fn generate_info_json_from_source_with_options(
+    source: &Path,
+    options: InfoJsonOptions,
+    progress: ModProgressCallback,
 ) -> InfoJsonResult {
     let is_pak = source
         .extension()
         .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"));
 
     if is_pak {
-        generate_info_json_from_pak(source, progress)
+        generate_info_json_from_pak(source, options, progress)
     } else {
-        generate_info_json_from_directory(source, progress)
+        generate_info_json_from_directory(source, options, progress)
     }
 }
 
 /// Generate info.json from a PAK file (reads meta.lsx from within)
-fn generate_info_json_from_pak(pak_path: &Path, progress: ModProgressCallback) -> InfoJsonResult {
+fn generate_info_json_from_pak(
+    pak_path: &Path,
+    options: InfoJsonOptions,
+    progress: ModProgressCallback,
+) -> InfoJsonResult {
     progress(&ModProgress::with_file(
         ModPhase::Validating,
         0,
@@ -276,7 +454,7 @@ fn generate_info_json_from_pak(pak_path: &Path, progress: ModProgressCallback) -
         "Generating info.json",
     ));
 
-    let json = generate_info_json_content(&metadata, &pak_md5);
+    let json = generate_info_json_content(&metadata, &pak_md5, pak_path, options);
 
     progress(&ModProgress::new(ModPhase::Complete, 3, 3));
 
@@ -290,6 +468,7 @@ fn generate_info_json_from_pak(pak_path: &Path, progress: ModProgressCallback) -
 /// Generate info.json from a mod directory (finds PAK file for MD5)
 fn generate_info_json_from_directory(
     dir_path: &Path,
+    options: InfoJsonOptions,
     progress: ModProgressCallback,
 ) -> InfoJsonResult {
     progress(&ModProgress::with_file(
@@ -331,7 +510,11 @@ fn generate_info_json_from_directory(
     ));
 
     // Find .pak file in directory
-    let pak_md5 = find_pak_and_calculate_md5(dir_path).unwrap_or_default();
+    let pak_path = find_pak_in_directory(dir_path);
+    let pak_md5 = pak_path
+        .as_deref()
+        .and_then(|p| calculate_file_md5(&p.to_string_lossy()))
+        .unwrap_or_default();
 
     if pak_md5.is_empty() {
         return InfoJsonResult {
@@ -348,7 +531,8 @@ fn generate_info_json_from_directory(
         "Generating info.json",
     ));
 
-    let json = generate_info_json_content(&metadata, &pak_md5);
+    let pak_path = pak_path.unwrap_or_default();
+    let json = generate_info_json_content(&metadata, &pak_md5, &pak_path, options);
 
     progress(&ModProgress::new(ModPhase::Complete, 3, 3));
 
@@ -372,20 +556,100 @@ fn find_and_read_meta_lsx_from_pak(pak_path: &Path) -> Option<String> {
     String::from_utf8(bytes).ok()
 }
 
-/// Find a .pak file in a directory and calculate its MD5
-fn find_pak_and_calculate_md5(dir_path: &Path) -> Option<String> {
+/// Find a .pak file in a directory
+fn find_pak_in_directory(dir_path: &Path) -> Option<std::path::PathBuf> {
     let entries = std::fs::read_dir(dir_path).ok()?;
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.is_file()
+    entries.flatten().map(|entry| entry.path()).find(|path| {
+        path.is_file()
             && path
                 .extension()
                 .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"))
-        {
-            return calculate_file_md5(&path.to_string_lossy());
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn sample_metadata() -> ModMetadata {
+        ModMetadata {
+            name: "My \"Mod\"".to_string(),
+            folder: "MyMod".to_string(),
+            author: "Someone".to_string(),
+            description: "Line one\nLine two".to_string(),
+            uuid: "8f2c3a5e-1111-2222-3333-444455556666".to_string(),
+            version64: Some(36_028_797_018_963_968),
+            dependencies: vec![ModDependency {
+                uuid: "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee".to_string(),
+                name: "Library".to_string(),
+                folder: "Library".to_string(),
+                version64: Some(1),
+            }],
         }
     }
 
-    None
+    #[test]
+    fn test_basic_schema_unchanged() {
+        let metadata = sample_metadata();
+        let json = generate_info_json_content(
+            &metadata,
+            "abc123",
+            Path::new("missing.pak"),
+            InfoJsonOptions::default(),
+        );
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let top: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(top, ["Mods", "MD5"]);
+
+        let entry = &value["Mods"][0];
+        let keys: Vec<_> = entry.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(
+            keys,
+            [
+                "Author",
+                "Name",
+                "Folder",
+                "Version",
+                "Description",
+                "UUID",
+                "Created",
+                "Dependencies",
+                "Group"
+            ]
+        );
+        assert_eq!(entry["Name"], "My \"Mod\"");
+        assert_eq!(entry["Description"], "Line one\nLine two");
+        assert_eq!(entry["Version"], "36028797018963968");
+        assert_eq!(entry["Dependencies"], serde_json::json!([]));
+        assert_eq!(value["MD5"], "abc123");
+    }
+
+    #[test]
+    fn test_extended_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("MyMod.pak");
+        std::fs::write(&pak, b"abc").unwrap();
+
+        let json = generate_info_json_content(
+            &sample_metadata(),
+            "abc123",
+            &pak,
+            InfoJsonOptions { extended: true },
+        );
+        let info: InfoJson = serde_json::from_str(&json).unwrap();
+
+        let deps = &info.mods[0].dependencies;
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].uuid, "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee");
+        assert_eq!(deps[0].version.as_deref(), Some("1"));
+        assert_eq!(
+            info.sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        // Not a real PAK, so no directories can be listed
+        assert_eq!(info.directories, Some(Vec::new()));
+    }
 }
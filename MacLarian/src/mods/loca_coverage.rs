@@ -0,0 +1,571 @@
+//! Localization coverage of a mod: referenced handles vs. shipped loca text
+//!
+//! Handles are collected from every `TranslatedString` attribute in the
+//! mod's `.lsx`, `.lsf` and `.lsj` files (root templates, dialogs, ...), from
+//! handle-shaped values in stats `.txt` files, and from handle-shaped text in
+//! script files (Osiris `.txt` and `.khn`, Script Extender `.lua`), with the
+//! file and (for text files) line of each reference. They are checked against
+//! the `.loca` and `.xml` files under `Localization/<language>/`: a handle is
+//! missing from a language when no entry defines it or its text is empty, and
+//! an entry is an orphan when nothing in the mod references it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::converter::loca_from_xml;
+use crate::converter::lsf_lsx_lsj::lsj_document_to_lsx;
+use crate::converter::to_lsx;
+use crate::error::{Error, Result};
+use crate::formats::loca::{LocaResource, normalize_handle, parse_loca_bytes};
+use crate::formats::lsf::parse_lsf_bytes;
+use crate::formats::lsj::parse_lsj;
+use crate::formats::lsx::{LsxDocument, LsxNode, parse_lsx};
+use crate::formats::stats::parse_stats;
+
+/// Placeholder handle the editor writes for strings that were never set
+const UNKNOWN_HANDLE: &str = "ls::translatedstringrepository::s_handleunknown";
+
+/// `h` followed by a GUID with `g` in place of each `-`, anywhere in a line
+static HANDLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bh[0-9a-f]{8}g[0-9a-f]{4}g[0-9a-f]{4}g[0-9a-f]{4}g[0-9a-f]{12}\b")
+        .expect("handle pattern is valid")
+});
+
+/// Where a handle is referenced
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct HandleReference {
+    /// File relative to the mod directory, with `/` separators
+    pub file: String,
+    /// 1-based line, for text files
+    pub line: Option<usize>,
+}
+
+/// Handle coverage of a mod's localization files
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LocaCoverage {
+    /// Language whose gaps block a release
+    pub primary_language: String,
+    /// Languages with a `Localization/<language>` folder, primary first
+    pub languages: Vec<String>,
+    /// Referenced handle -> files referencing it (relative to the mod)
+    pub referenced: BTreeMap<String, BTreeSet<String>>,
+    /// Language -> referenced handles with no text in that language
+    pub missing: BTreeMap<String, Vec<String>>,
+    /// Language -> handles defined in that language but never referenced
+    pub orphans: BTreeMap<String, Vec<String>>,
+}
+
+impl LocaCoverage {
+    /// Referenced handles with no text in the primary language
+    #[must_use]
+    pub fn primary_gaps(&self) -> &[String] {
+        self.missing
+            .get(&self.primary_language)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Referenced handles missing from at least one language, sorted
+    #[must_use]
+    pub fn incomplete_handles(&self) -> BTreeSet<&str> {
+        self.missing
+            .values()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether `handle` is missing from `language`
+    #[must_use]
+    pub fn is_missing(&self, language: &str, handle: &str) -> bool {
+        self.missing
+            .get(language)
+            .is_some_and(|handles| handles.binary_search_by(|h| h.as_str().cmp(handle)).is_ok())
+    }
+}
+
+/// Check every handle referenced by a mod against its localization files
+///
+/// Files that fail to parse are logged and skipped.
+///
+/// # Errors
+/// Returns an error if the directory cannot be walked.
+pub fn loca_coverage(mod_dir: &Path, primary_language: &str) -> Result<LocaCoverage> {
+    let referenced = collect_handle_references(mod_dir)?;
+    let defined = collect_localization(mod_dir)?;
+
+    let mut languages: Vec<String> = defined.keys().cloned().collect();
+    languages.retain(|l| !l.eq_ignore_ascii_case(primary_language));
+    languages.insert(0, primary_language.to_string());
+
+    let mut missing = BTreeMap::new();
+    let mut orphans = BTreeMap::new();
+    for language in &languages {
+        let texts = defined
+            .iter()
+            .find(|(l, _)| l.eq_ignore_ascii_case(language))
+            .map(|(_, texts)| texts);
+        let has_text = |handle: &str| {
+            texts
+                .and_then(|t| t.get(handle))
+                .is_some_and(|text| !text.trim().is_empty())
+        };
+        missing.insert(
+            language.clone(),
+            referenced
+                .keys()
+                .filter(|h| !has_text(h))
+                .cloned()
+                .collect(),
+        );
+        orphans.insert(
+            language.clone(),
+            texts
+                .into_iter()
+                .flat_map(BTreeMap::keys)
+                .filter(|h| !referenced.contains_key(*h))
+                .cloned()
+                .collect(),
+        );
+    }
+
+    Ok(LocaCoverage {
+        primary_language: primary_language.to_string(),
+        languages,
+        referenced,
+        missing,
+        orphans,
+    })
+}
+
+/// Every handle referenced under a mod directory, with the files (relative
+/// to `mod_dir`) that reference it
+///
+/// Files that fail to parse are logged and skipped.
+///
+/// # Errors
+/// Returns an error if the directory cannot be walked.
+pub fn collect_handle_references(mod_dir: &Path) -> Result<BTreeMap<String, BTreeSet<String>>> {
+    Ok(collect_handle_locations(mod_dir)?
+        .into_iter()
+        .map(|(handle, references)| {
+            let files = references.into_iter().map(|r| r.file).collect();
+            (handle, files)
+        })
+        .collect())
+}
+
+/// Every place `handle` is referenced under a mod directory, sorted by file
+/// and line
+///
+/// The handle may carry a `;version` suffix and is matched case-insensitively.
+///
+/// # Errors
+/// Returns an error if the directory cannot be walked.
+pub fn find_references(mod_dir: &Path, handle: &str) -> Result<Vec<HandleReference>> {
+    let mut locations = collect_handle_locations(mod_dir)?;
+    Ok(locations
+        .remove(&normalize_handle(handle))
+        .map(|references| references.into_iter().collect())
+        .unwrap_or_default())
+}
+
+/// Every handle referenced under a mod directory, with where it is referenced
+///
+/// Files that fail to parse are logged and skipped.
+///
+/// # Errors
+/// Returns an error if the directory cannot be walked.
+pub fn collect_handle_locations(
+    mod_dir: &Path,
+) -> Result<BTreeMap<String, BTreeSet<HandleReference>>> {
+    let mut handles: BTreeMap<String, BTreeSet<HandleReference>> = BTreeMap::new();
+    for entry in WalkDir::new(mod_dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let Some(kind) = SourceKind::of(path) else {
+            continue;
+        };
+
+        let file = relative_source(mod_dir, path);
+        let found = std::fs::read(path)
+            .map_err(Error::from)
+            .and_then(|data| file_handles(&data, kind));
+        match found {
+            Ok(found) => {
+                for (handle, line) in found {
+                    handles.entry(handle).or_default().insert(HandleReference {
+                        file: file.clone(),
+                        line,
+                    });
+                }
+            }
+            Err(e) => tracing::warn!("Skipping {file}: {e}"),
+        }
+    }
+    Ok(handles)
+}
+
+/// How handles are read out of a file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SourceKind {
+    /// `.lsx`, `.lsf` or `.lsj`: `TranslatedString` attributes
+    Document(&'static str),
+    /// `.txt` under a `Stats` folder: stat data values
+    Stats,
+    /// Osiris and Script Extender scripts: any handle-shaped text
+    Script,
+}
+
+impl SourceKind {
+    fn of(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match ext.as_str() {
+            "lsx" => Some(Self::Document("lsx")),
+            "lsf" => Some(Self::Document("lsf")),
+            "lsj" => Some(Self::Document("lsj")),
+            "txt" if path.components().any(|c| c.as_os_str() == "Stats") => Some(Self::Stats),
+            "txt" | "khn" | "lua" => Some(Self::Script),
+            _ => None,
+        }
+    }
+}
+
+/// Handles referenced by one file's contents, with their lines where known
+fn file_handles(data: &[u8], kind: SourceKind) -> Result<Vec<(String, Option<usize>)>> {
+    let handles: BTreeSet<String> = match kind {
+        SourceKind::Script => {
+            return Ok(handle_lines(&String::from_utf8_lossy(data))
+                .into_iter()
+                .map(|(handle, line)| (handle, Some(line)))
+                .collect());
+        }
+        SourceKind::Stats => parse_stats(&String::from_utf8_lossy(data))
+            .entries()
+            .flat_map(|entry| entry.data.values())
+            .map(|value| normalize_handle(value))
+            .filter(|value| is_handle(value))
+            .collect(),
+        SourceKind::Document(extension) => document_handles(data, extension)?.into_iter().collect(),
+    };
+
+    // LSF is binary, so its references have no line
+    if kind == SourceKind::Document("lsf") {
+        return Ok(handles.into_iter().map(|h| (h, None)).collect());
+    }
+    let mut found: Vec<(String, Option<usize>)> = handle_lines(&String::from_utf8_lossy(data))
+        .into_iter()
+        .filter(|(handle, _)| handles.contains(handle))
+        .map(|(handle, line)| (handle, Some(line)))
+        .collect();
+    for handle in handles {
+        if !found.iter().any(|(h, _)| *h == handle) {
+            found.push((handle, None));
+        }
+    }
+    Ok(found)
+}
+
+/// Every handle-shaped token in a text with its 1-based line
+fn handle_lines(text: &str) -> Vec<(String, usize)> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            HANDLE_PATTERN
+                .find_iter(line)
+                .map(move |m| (m.as_str().to_ascii_lowercase(), index + 1))
+        })
+        .collect()
+}
+
+/// `TranslatedString` handles in an `.lsx`, `.lsf` or `.lsj` file
+fn document_handles(data: &[u8], extension: &str) -> Result<Vec<String>> {
+    let doc = match extension {
+        "lsf" => parse_lsx(&to_lsx(&parse_lsf_bytes(data)?)?)?,
+        "lsj" => lsj_document_to_lsx(&parse_lsj(&String::from_utf8_lossy(data))?)?,
+        _ => parse_lsx(&String::from_utf8_lossy(data))?,
+    };
+    let mut handles = Vec::new();
+    collect_document(&doc, &mut handles);
+    Ok(handles)
+}
+
+fn collect_document(doc: &LsxDocument, handles: &mut Vec<String>) {
+    for region in &doc.regions {
+        for node in &region.nodes {
+            collect_node(node, handles);
+        }
+    }
+}
+
+fn collect_node(node: &LsxNode, handles: &mut Vec<String>) {
+    for attribute in &node.attributes {
+        let Some(handle) = attribute.handle.as_deref().map(normalize_handle) else {
+            continue;
+        };
+        if !handle.is_empty() && handle != UNKNOWN_HANDLE {
+            handles.push(handle);
+        }
+    }
+    for child in &node.children {
+        collect_node(child, handles);
+    }
+}
+
+/// Language -> handle -> text, from `Localization/<language>/**/*.loca|xml`
+fn collect_localization(mod_dir: &Path) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+    let mut languages: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    for entry in WalkDir::new(mod_dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path = entry.path();
+        let Some(language) = localization_language(mod_dir, path) else {
+            continue;
+        };
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let parsed: Result<LocaResource> = match ext.as_str() {
+            "loca" => std::fs::read(path)
+                .map_err(Error::from)
+                .and_then(|data| parse_loca_bytes(&data)),
+            "xml" => std::fs::read_to_string(path)
+                .map_err(Error::from)
+                .and_then(|text| loca_from_xml(&text)),
+            _ => continue,
+        };
+        match parsed {
+            Ok(resource) => {
+                let texts = languages.entry(language).or_default();
+                for entry in resource.entries {
+                    let text = texts.entry(normalize_handle(&entry.key)).or_default();
+                    // A handle defined twice counts as translated if either has text
+                    if text.trim().is_empty() {
+                        *text = entry.text;
+                    }
+                }
+            }
+            Err(e) => tracing::warn!("Skipping {}: {e}", relative_source(mod_dir, path)),
+        }
+    }
+    Ok(languages)
+}
+
+/// Language folder a file sits in, for files under a `Localization` folder
+fn localization_language(mod_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(mod_dir).ok()?;
+    let mut components = relative
+        .parent()?
+        .components()
+        .map(std::path::Component::as_os_str);
+    components.find(|c| c.eq_ignore_ascii_case("Localization"))?;
+    components.next().map(|c| c.to_string_lossy().into_owned())
+}
+
+/// `h` followed by a GUID with `g` in place of each `-`
+fn is_handle(value: &str) -> bool {
+    value.len() == 37
+        && value.starts_with('h')
+        && value[1..]
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || c == 'g')
+}
+
+fn relative_source(mod_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(mod_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::loca::{LocalizedText, serialize_loca};
+
+    const NAME: &str = "h11111111g1111g1111g1111g111111111111";
+    const DESCRIPTION: &str = "h22222222g2222g2222g2222g222222222222";
+    const STATS_NAME: &str = "h33333333g3333g3333g3333g333333333333";
+    const UNUSED: &str = "h44444444g4444g4444g4444g444444444444";
+
+    fn write(path: &Path, contents: &[u8]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// A mod referencing three handles: English is missing one and has an
+    /// unused entry, French is missing two
+    fn fixture_mod(dir: &Path) {
+        let template = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="328" />
+    <region id="Templates">
+        <node id="Templates">
+            <children>
+                <node id="GameObjects">
+                    <attribute id="MapKey" type="FixedString" value="11111111-2222-3333-4444-555555555555" />
+                    <attribute id="DisplayName" type="TranslatedString" handle="{NAME}" version="1" />
+                    <attribute id="Description" type="TranslatedString" handle="{DESCRIPTION};2" version="2" />
+                    <attribute id="Title" type="TranslatedString" handle="ls::TranslatedStringRepository::s_HandleUnknown" version="0" />
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#
+        );
+        write(
+            &dir.join("Public/TestMod/RootTemplates/items.lsx"),
+            template.as_bytes(),
+        );
+
+        let stats = format!(
+            "new entry \"TEST_Item\"\ntype \"Armor\"\ndata \"DisplayName\" \"{STATS_NAME};1\"\ndata \"Weight\" \"0.5\"\n"
+        );
+        write(
+            &dir.join("Public/TestMod/Stats/Generated/Data/Armor.txt"),
+            stats.as_bytes(),
+        );
+
+        let english = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<contentList>
+    <content contentuid="{NAME}" version="1">Test Sword</content>
+    <content contentuid="{DESCRIPTION}" version="2"></content>
+    <content contentuid="{STATS_NAME}" version="1">Test Armor</content>
+    <content contentuid="{UNUSED}" version="1">Leftover</content>
+</contentList>"#
+        );
+        write(
+            &dir.join("Localization/English/TestMod.xml"),
+            english.as_bytes(),
+        );
+
+        let french = LocaResource::new(vec![LocalizedText::new(
+            NAME.to_string(),
+            1,
+            "Épée de test".to_string(),
+        )]);
+        write(
+            &dir.join("Localization/French/TestMod.loca"),
+            &serialize_loca(&french).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_loca_coverage_reports_gaps_and_orphans() {
+        let dir = tempfile::tempdir().unwrap();
+        fixture_mod(dir.path());
+
+        let coverage = loca_coverage(dir.path(), "English").unwrap();
+
+        assert_eq!(coverage.languages, ["English", "French"]);
+        assert_eq!(
+            coverage.referenced.keys().collect::<Vec<_>>(),
+            [NAME, DESCRIPTION, STATS_NAME]
+        );
+        assert!(
+            coverage.referenced[STATS_NAME]
+                .contains("Public/TestMod/Stats/Generated/Data/Armor.txt")
+        );
+
+        // Empty text counts as missing
+        assert_eq!(coverage.primary_gaps(), [DESCRIPTION]);
+        assert_eq!(coverage.missing["French"], [DESCRIPTION, STATS_NAME]);
+        assert!(coverage.is_missing("French", STATS_NAME));
+        assert!(!coverage.is_missing("French", NAME));
+        assert_eq!(coverage.incomplete_handles().len(), 2);
+
+        assert_eq!(coverage.orphans["English"], [UNUSED]);
+        assert!(coverage.orphans["French"].is_empty());
+    }
+
+    #[test]
+    fn test_loca_coverage_without_primary_language() {
+        let dir = tempfile::tempdir().unwrap();
+        fixture_mod(dir.path());
+
+        let coverage = loca_coverage(dir.path(), "German").unwrap();
+
+        assert_eq!(coverage.languages, ["German", "English", "French"]);
+        assert_eq!(coverage.primary_gaps().len(), 3);
+        assert!(coverage.orphans["German"].is_empty());
+    }
+
+    #[test]
+    fn test_references_from_stats_and_scripts() {
+        const OBJECT_NAME: &str = "h55555555g5555g5555g5555g555555555555";
+        const SCRIPT_TEXT: &str = "h66666666g6666g6666g6666g666666666666";
+        let dir = tempfile::tempdir().unwrap();
+        let object = format!(
+            "new entry \"TEST_Potion\"\ntype \"Object\"\nusing \"_Potion\"\ndata \"DisplayName\" \"{OBJECT_NAME};1\"\n"
+        );
+        write(
+            &dir.path()
+                .join("Public/TestMod/Stats/Generated/Data/Object.txt"),
+            object.as_bytes(),
+        );
+        let goal = format!(
+            "INIT\nEVENTS\n\nIF\nDB_Test(_Char)\nTHEN\nOpenMessageBox(_Char, \"{}\");\n",
+            SCRIPT_TEXT.to_uppercase()
+        );
+        write(
+            &dir.path()
+                .join("Mods/TestMod/Story/RawFiles/Goals/TestGoal.txt"),
+            goal.as_bytes(),
+        );
+        let lua = format!("local text = Ext.Loca.GetTranslatedString(\"{SCRIPT_TEXT}\")\n");
+        write(
+            &dir.path()
+                .join("Mods/TestMod/ScriptExtender/Lua/Server/Text.lua"),
+            lua.as_bytes(),
+        );
+        write(
+            &dir.path().join("Localization/English/TestMod.xml"),
+            format!(
+                r#"<contentList><content contentuid="{OBJECT_NAME}" version="1">Potion</content></contentList>"#
+            )
+            .as_bytes(),
+        );
+
+        let coverage = loca_coverage(dir.path(), "English").unwrap();
+        let object_refs = find_references(dir.path(), &format!("{OBJECT_NAME};1")).unwrap();
+        let script_refs = find_references(dir.path(), SCRIPT_TEXT).unwrap();
+
+        // Referenced only from Object.txt, so it is used, not an orphan
+        assert!(coverage.referenced.contains_key(OBJECT_NAME));
+        assert!(coverage.orphans["English"].is_empty());
+        assert_eq!(
+            object_refs,
+            [HandleReference {
+                file: "Public/TestMod/Stats/Generated/Data/Object.txt".to_string(),
+                line: Some(4),
+            }]
+        );
+
+        assert_eq!(coverage.primary_gaps(), [SCRIPT_TEXT]);
+        assert_eq!(
+            script_refs,
+            [
+                HandleReference {
+                    file: "Mods/TestMod/ScriptExtender/Lua/Server/Text.lua".to_string(),
+                    line: Some(1),
+                },
+                HandleReference {
+                    file: "Mods/TestMod/Story/RawFiles/Goals/TestGoal.txt".to_string(),
+                    line: Some(7),
+                },
+            ]
+        );
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! Generate mod metadata files for BG3 mods.
 
+use super::version::Version64;
+
 /// Convert version components to BG3's int64 format
 ///
 /// BG3 version format: major << 55 | minor << 47 | patch << 31 | build
 #[must_use]
 pub fn version_to_int64(major: u32, minor: u32, patch: u32, build: u32) -> i64 {
-    ((major as i64) << 55) | ((minor as i64) << 47) | ((patch as i64) << 31) | (build as i64)
+    Version64::new(major, minor, patch, build).to_i64()
 }
 
 /// Parse a version string into components
@@ -29,12 +31,7 @@ pub fn parse_version_string(version: &str) -> Option<(u32, u32, u32, u32)> {
     if !trimmed.contains('.') {
         if let Ok(v64) = trimmed.parse::<i64>() {
             if v64 >= VERSION64_THRESHOLD {
-                // Decode Version64: major << 55 | minor << 47 | patch << 31 | build
-                let major = ((v64 >> 55) & 0x7F) as u32;
-                let minor = ((v64 >> 47) & 0xFF) as u32;
-                let patch = ((v64 >> 31) & 0xFFFF) as u32;
-                let build = (v64 & 0x7FFFFFFF) as u32;
-                return Some((major, minor, patch, build));
+                return Some(Version64::decode(v64 as u64));
             }
             // Small integer - treat as major version (e.g., "1" -> 1.0.0.0)
             return Some((v64 as u32, 0, 0, 0));
@@ -173,7 +170,7 @@ pub fn generate_meta_lsx(
 }
 
 /// Escape XML special characters
-fn escape_xml(s: &str) -> String {
+pub(crate) fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -4,27 +4,60 @@
 //! - Generate meta.lsx mod metadata files
 //! - Generate info.json for ``BaldursModManager`` import
 //! - Validate mod directory structure
+//! - Find `.lsx`/`.lsf` pairs whose contents have drifted apart
 //! - PAK integrity checking
+//! - Compare a source directory against a built PAK
+//! - Build asset dependency graphs from LSX/LSF references
+//! - Check localization coverage of referenced `TranslatedString` handles
 //! - Find and parse mod metadata
+//! - Encode, decode and bump `Version64` mod versions
+//! - Deploy mod PAKs to the game and edit modsettings.lsx
+//! - Scaffold new mods from built-in templates
 
 pub mod batch_validate;
+pub mod companions;
+pub mod dependencies;
+pub mod deploy;
+pub mod diff;
 pub mod info_json;
+pub mod loca_coverage;
 pub mod meta_generator;
+pub mod modsettings;
+pub mod template;
 pub mod types;
 pub mod validation;
+pub mod version;
 
 pub use batch_validate::{
     PakIntegrityResult, check_pak_integrity, check_pak_integrity_with_progress,
 };
+pub use companions::{
+    CompanionPair, PairSide, check_companion_pairs, compare_companions, find_companion_pairs,
+};
+pub use dependencies::{AssetNode, DependencyEntry, DependencyGraph, Direction};
+pub use deploy::{
+    DeployAction, DeployOptions, DeployTarget, deploy_mod, read_pak_meta, undeploy_mod,
+};
+pub use diff::{ConversionMap, WorkspaceDiff, diff_against_pak, diff_against_pak_with};
 pub use info_json::{
-    InfoJsonResult, generate_info_json, generate_info_json_from_source,
-    generate_info_json_with_progress,
+    InfoJson, InfoJsonDependency, InfoJsonMod, InfoJsonOptions, InfoJsonResult, generate_info_json,
+    generate_info_json_from_source, generate_info_json_from_source_with_options,
+    generate_info_json_with_options, generate_info_json_with_progress,
+};
+pub use loca_coverage::{
+    HandleReference, LocaCoverage, collect_handle_locations, collect_handle_references,
+    find_references, loca_coverage,
 };
 pub use meta_generator::{
     generate_meta_lsx, parse_version_string, to_folder_name, version_to_int64,
 };
+pub use modsettings::ModSettings;
+pub use template::{MOD_TEMPLATES, ModTemplate, NewMod, NewModOptions, create_mod, find_template};
 pub use types::{ModPhase, ModProgress, ModProgressCallback};
 pub use validation::{
     ModValidationResult, validate_mod_structure, validate_mod_structure_with_progress,
     validate_pak_mod_structure, validate_pak_mod_structure_with_progress,
 };
+pub use version::{
+    Version64, VersionPart, bump_meta_version, meta_version, read_meta_version, set_meta_version,
+};
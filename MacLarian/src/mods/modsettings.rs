@@ -0,0 +1,306 @@
+//! Reading and editing a profile's modsettings.lsx
+//!
+//! The game loads the mods listed as `ModuleShortDesc` nodes under
+//! `ModuleSettings > Mods`, in order. Profiles written by older game versions
+//! also keep a `ModOrder` list of `Module` nodes; it is kept in step when
+//! present.
+
+use std::path::Path;
+
+use crate::error::Result;
+use crate::formats::ModDependency;
+use crate::formats::lsx::{
+    LsxAttribute, LsxDocument, LsxNode, LsxRegion, parse_lsx, read_lsx, serialize_lsx,
+};
+
+/// A parsed modsettings.lsx
+#[derive(Debug, Clone)]
+pub struct ModSettings {
+    doc: LsxDocument,
+}
+
+impl Default for ModSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModSettings {
+    /// An empty mod list
+    #[must_use]
+    pub fn new() -> Self {
+        let mut root = LsxNode::new("root".to_string());
+        root.children.push(LsxNode::new("Mods".to_string()));
+
+        let mut doc = LsxDocument::new(4, 7, 1, 3);
+        doc.regions.push(LsxRegion {
+            id: "ModuleSettings".to_string(),
+            nodes: vec![root],
+        });
+        Self { doc }
+    }
+
+    /// Read a modsettings.lsx file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self {
+            doc: read_lsx(path)?,
+        })
+    }
+
+    /// Read a modsettings.lsx file, or start an empty list if it doesn't exist
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn read_or_default<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            Self::read(path)
+        } else {
+            Ok(Self::new())
+        }
+    }
+
+    /// Parse modsettings.lsx content
+    ///
+    /// # Errors
+    /// Returns an error if the content is not valid LSX.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(Self {
+            doc: parse_lsx(content)?,
+        })
+    }
+
+    /// Mods in load order
+    #[must_use]
+    pub fn mods(&self) -> Vec<ModDependency> {
+        self.doc
+            .find_node("Mods")
+            .map(|mods| {
+                mods.children
+                    .iter()
+                    .filter(|n| n.id == "ModuleShortDesc")
+                    .map(short_desc)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The entry for a mod UUID, if listed
+    #[must_use]
+    pub fn find(&self, uuid: &str) -> Option<ModDependency> {
+        self.mods()
+            .into_iter()
+            .find(|m| m.uuid.eq_ignore_ascii_case(uuid))
+    }
+
+    /// Add a mod to the end of the load order, or update its entry in place
+    ///
+    /// Returns `true` if the mod was not listed before.
+    pub fn upsert(&mut self, module: &ModDependency) -> bool {
+        let mods = self.mods_node();
+        // Match the UUID type existing entries use (`guid` since Patch 7)
+        let uuid_type = mods
+            .children
+            .iter()
+            .find_map(|n| n.attribute("UUID"))
+            .map_or("FixedString", |a| a.type_name.as_str())
+            .to_string();
+
+        if let Some(node) = mods
+            .children
+            .iter_mut()
+            .find(|n| is_module(n, "ModuleShortDesc", &module.uuid))
+        {
+            set_attribute(node, "Folder", "LSString", &module.folder);
+            set_attribute(node, "Name", "LSString", &module.name);
+            if let Some(version) = module.version64 {
+                set_attribute(node, "Version64", "int64", &version.to_string());
+            }
+            return false;
+        }
+
+        let mut node = LsxNode::new("ModuleShortDesc".to_string());
+        set_attribute(&mut node, "Folder", "LSString", &module.folder);
+        set_attribute(&mut node, "MD5", "LSString", "");
+        set_attribute(&mut node, "Name", "LSString", &module.name);
+        set_attribute(&mut node, "PublishHandle", "uint64", "0");
+        set_attribute(&mut node, "UUID", &uuid_type, &module.uuid);
+        set_attribute(
+            &mut node,
+            "Version64",
+            "int64",
+            &module.version64.unwrap_or_default().to_string(),
+        );
+        mods.children.push(node);
+
+        if let Some(order) = self.doc.find_node_mut("ModOrder") {
+            let mut entry = LsxNode::new("Module".to_string());
+            set_attribute(&mut entry, "UUID", &uuid_type, &module.uuid);
+            order.children.push(entry);
+        }
+        true
+    }
+
+    /// Remove a mod by UUID, returning its entry if it was listed
+    pub fn remove(&mut self, uuid: &str) -> Option<ModDependency> {
+        if let Some(order) = self.doc.find_node_mut("ModOrder") {
+            order.children.retain(|n| !is_module(n, "Module", uuid));
+        }
+
+        let mods = self.doc.find_node_mut("Mods")?;
+        let index = mods
+            .children
+            .iter()
+            .position(|n| is_module(n, "ModuleShortDesc", uuid))?;
+        Some(short_desc(&mods.children.remove(index)))
+    }
+
+    /// Serialize to LSX
+    ///
+    /// # Errors
+    /// Returns an error if XML serialization fails.
+    pub fn to_lsx(&self) -> Result<String> {
+        serialize_lsx(&self.doc)
+    }
+
+    /// Write to disk, creating the parent directory if needed
+    ///
+    /// # Errors
+    /// Returns an error if the directory or file cannot be written.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_lsx()?)?;
+        Ok(())
+    }
+
+    /// The `Mods` node, created under `root` if missing
+    fn mods_node(&mut self) -> &mut LsxNode {
+        if self.doc.find_node("Mods").is_none() {
+            if self.doc.regions.is_empty() {
+                *self = Self::new();
+            } else if let Some(root) = self.doc.regions[0].nodes.first_mut() {
+                root.children.push(LsxNode::new("Mods".to_string()));
+            } else {
+                let mut root = LsxNode::new("root".to_string());
+                root.children.push(LsxNode::new("Mods".to_string()));
+                self.doc.regions[0].nodes.push(root);
+            }
+        }
+        self.doc
+            .find_node_mut("Mods")
+            .expect("Mods node was just created")
+    }
+}
+
+/// Whether `node` is a `kind` node for the mod `uuid`
+fn is_module(node: &LsxNode, kind: &str, uuid: &str) -> bool {
+    node.id == kind
+        && node
+            .attribute_value("UUID")
+            .is_some_and(|v| v.eq_ignore_ascii_case(uuid))
+}
+
+/// Read a `ModuleShortDesc` node
+fn short_desc(node: &LsxNode) -> ModDependency {
+    ModDependency {
+        uuid: node.attribute_value("UUID").unwrap_or_default().to_string(),
+        name: node.attribute_value("Name").unwrap_or_default().to_string(),
+        folder: node
+            .attribute_value("Folder")
+            .unwrap_or_default()
+            .to_string(),
+        version64: node
+            .attribute_value("Version64")
+            .and_then(|v| v.parse().ok()),
+    }
+}
+
+/// Set an attribute's value, adding it if missing
+fn set_attribute(node: &mut LsxNode, id: &str, type_name: &str, value: &str) {
+    if let Some(attr) = node.attribute_mut(id) {
+        attr.value = value.to_string();
+    } else {
+        node.attributes.push(LsxAttribute::new(
+            id.to_string(),
+            type_name.to_string(),
+            value.to_string(),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upsert_and_remove_keep_mod_order() {
+        let lsx = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="7" revision="1" build="3" />
+    <region id="ModuleSettings">
+        <node id="root">
+            <children>
+                <node id="ModOrder">
+                    <children>
+                        <node id="Module">
+                            <attribute id="UUID" type="FixedString" value="28ac9ce2-2aba-8cda-b3b5-6e922f71b6b8" />
+                        </node>
+                    </children>
+                </node>
+                <node id="Mods">
+                    <children>
+                        <node id="ModuleShortDesc">
+                            <attribute id="Folder" type="LSString" value="GustavDev" />
+                            <attribute id="Name" type="LSString" value="GustavDev" />
+                            <attribute id="UUID" type="FixedString" value="28ac9ce2-2aba-8cda-b3b5-6e922f71b6b8" />
+                            <attribute id="Version64" type="int64" value="36028797018963968" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>"#;
+        let mut settings = ModSettings::parse(lsx).unwrap();
+        let module = ModDependency {
+            uuid: "11111111-2222-3333-4444-555555555555".to_string(),
+            name: "My Mod".to_string(),
+            folder: "MyMod".to_string(),
+            version64: Some(36_028_797_018_963_968),
+        };
+
+        assert!(settings.upsert(&module));
+        assert!(!settings.upsert(&module));
+        let mods = settings.mods();
+        assert_eq!(mods.len(), 2);
+        assert_eq!(mods[1], module);
+        assert_eq!(
+            settings.doc.find_node("ModOrder").unwrap().children.len(),
+            2
+        );
+
+        let reparsed = ModSettings::parse(&settings.to_lsx().unwrap()).unwrap();
+        assert_eq!(
+            reparsed.find(&module.uuid.to_uppercase()),
+            Some(module.clone())
+        );
+
+        assert_eq!(settings.remove(&module.uuid), Some(module.clone()));
+        assert_eq!(settings.remove(&module.uuid), None);
+        assert_eq!(settings.mods().len(), 1);
+        assert_eq!(
+            settings.doc.find_node("ModOrder").unwrap().children.len(),
+            1
+        );
+
+        let mut empty = ModSettings::new();
+        assert!(empty.upsert(&module));
+        assert_eq!(empty.mods(), [module]);
+    }
+}
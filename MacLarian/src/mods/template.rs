@@ -0,0 +1,433 @@
+//! Scaffolding new mods from built-in templates
+//!
+//! A [`ModTemplate`] is an embedded tree of files under `templates/<id>/`
+//! with `{{placeholder}}` substitution. [`create_mod`] renders one into a new
+//! mod directory, always adding `Mods/<Folder>/meta.lsx` from
+//! [`generate_meta_lsx`] and, optionally, a Script Extender config.
+//!
+//! Placeholders:
+//! - `{{name}}`, `{{folder}}`, `{{author}}`, `{{description}}`, `{{uuid}}`
+//!   (the mod's UUID), `{{version}}` and `{{version64}}`
+//! - `{{guid:<label>}}`: a fresh GUID, the same for every use of `<label>`
+//! - `{{handle:<label>}}`: a fresh `TranslatedString` handle, likewise
+//!
+//! LSX and localization XML files are parsed and re-serialized after
+//! substitution, so a template that renders to a malformed document fails
+//! instead of producing a mod the game can't load.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::meta_generator::{escape_xml, generate_meta_lsx, to_folder_name};
+use super::version::Version64;
+use crate::converter::{loca_from_xml, loca_to_xml_string};
+use crate::error::{Error, Result, ResultExt};
+use crate::formats::lsx::{parse_lsx, serialize_lsx};
+use crate::utils::{UuidFormat, generate_uuid};
+
+/// Lowest Script Extender version the generated config asks for
+const SCRIPT_EXTENDER_VERSION: u32 = 1;
+
+/// A built-in mod template
+#[derive(Debug, Clone, Copy)]
+pub struct ModTemplate {
+    /// Name passed to `--template`
+    pub id: &'static str,
+    /// One-line summary of what the template sets up
+    pub description: &'static str,
+    /// Folders created even when no file lands in them
+    directories: &'static [&'static str],
+    files: &'static [TemplateFile],
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TemplateFile {
+    path: &'static str,
+    content: &'static str,
+}
+
+impl ModTemplate {
+    /// Paths of the files this template writes besides meta.lsx, with
+    /// placeholders left in
+    pub fn file_paths(&self) -> impl Iterator<Item = &'static str> {
+        self.files.iter().map(|file| file.path)
+    }
+}
+
+/// All built-in templates
+pub const MOD_TEMPLATES: &[ModTemplate] = &[
+    ModTemplate {
+        id: "equipment",
+        description: "Armor with stats, a root template, a treasure table entry and localized name",
+        directories: &[
+            "Public/{{folder}}/Assets/Textures/Icons",
+            "Public/{{folder}}/GUI",
+        ],
+        files: &[
+            TemplateFile {
+                path: "Public/{{folder}}/Stats/Generated/Data/Armor.txt",
+                content: include_str!("templates/equipment/Armor.txt"),
+            },
+            TemplateFile {
+                path: "Public/{{folder}}/Stats/Generated/TreasureTable.txt",
+                content: include_str!("templates/equipment/TreasureTable.txt"),
+            },
+            TemplateFile {
+                path: "Public/{{folder}}/RootTemplates/_merged.lsx",
+                content: include_str!("templates/equipment/RootTemplates.lsx"),
+            },
+            TemplateFile {
+                path: "Localization/English/{{folder}}.xml",
+                content: include_str!("templates/equipment/Localization.xml"),
+            },
+        ],
+    },
+    ModTemplate {
+        id: "spell",
+        description: "A cantrip-style projectile spell with localized name and description",
+        directories: &["Public/{{folder}}/Assets/Textures/Icons"],
+        files: &[
+            TemplateFile {
+                path: "Public/{{folder}}/Stats/Generated/Data/Spell.txt",
+                content: include_str!("templates/spell/Spell.txt"),
+            },
+            TemplateFile {
+                path: "Localization/English/{{folder}}.xml",
+                content: include_str!("templates/spell/Localization.xml"),
+            },
+        ],
+    },
+    ModTemplate {
+        id: "class-blank",
+        description: "An empty class: class description and level 1 progression, linked by UUID",
+        directories: &["Public/{{folder}}/Lists"],
+        files: &[
+            TemplateFile {
+                path: "Public/{{folder}}/ClassDescriptions/ClassDescriptions.lsx",
+                content: include_str!("templates/class-blank/ClassDescriptions.lsx"),
+            },
+            TemplateFile {
+                path: "Public/{{folder}}/Progressions/Progressions.lsx",
+                content: include_str!("templates/class-blank/Progressions.lsx"),
+            },
+            TemplateFile {
+                path: "Localization/English/{{folder}}.xml",
+                content: include_str!("templates/class-blank/Localization.xml"),
+            },
+        ],
+    },
+];
+
+/// Look up a built-in template by id
+///
+/// # Errors
+/// Returns [`Error::UnknownModTemplate`] if no template has that id.
+pub fn find_template(id: &str) -> Result<&'static ModTemplate> {
+    MOD_TEMPLATES
+        .iter()
+        .find(|template| template.id.eq_ignore_ascii_case(id))
+        .ok_or_else(|| Error::UnknownModTemplate {
+            name: id.to_string(),
+            available: MOD_TEMPLATES
+                .iter()
+                .map(|t| t.id)
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+}
+
+/// Details of a new mod for [`create_mod`]
+#[derive(Debug, Clone)]
+pub struct NewModOptions {
+    /// Display name
+    pub name: String,
+    /// Author name
+    pub author: String,
+    /// Mod description
+    pub description: String,
+    /// Folder name (defaults to the sanitized display name)
+    pub folder: Option<String>,
+    /// Mod UUID (generated if not set)
+    pub uuid: Option<String>,
+    /// Initial version
+    pub version: Version64,
+    /// Also write `Mods/<Folder>/ScriptExtender/Config.json` and a Lua bootstrap
+    pub script_extender: bool,
+}
+
+impl NewModOptions {
+    /// Options for a mod at version 1.0.0.0
+    #[must_use]
+    pub fn new(name: &str, author: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            author: author.to_string(),
+            description: String::new(),
+            folder: None,
+            uuid: None,
+            version: Version64::new(1, 0, 0, 0),
+            script_extender: false,
+        }
+    }
+}
+
+/// A mod created by [`create_mod`]
+#[derive(Debug, Clone, Serialize)]
+pub struct NewMod {
+    /// Mod root directory
+    pub root: PathBuf,
+    /// Folder name under `Mods/` and `Public/`
+    pub folder: String,
+    /// Mod UUID written to meta.lsx
+    pub uuid: String,
+    /// Files written, relative to `root`
+    pub files: Vec<String>,
+}
+
+/// Create a new mod in `parent/<Folder>` from a template
+///
+/// # Errors
+/// Returns [`Error::ModDirectoryExists`] if the mod directory exists and is
+/// not empty, an error if a template renders to an invalid LSX or
+/// localization file, or an I/O error if writing fails.
+pub fn create_mod(
+    parent: &Path,
+    template: &ModTemplate,
+    options: &NewModOptions,
+) -> Result<NewMod> {
+    let folder = to_folder_name(options.folder.as_deref().unwrap_or(&options.name));
+    if folder.is_empty() {
+        return Err(Error::InvalidFormat(format!(
+            "'{}' has no characters usable in a folder name",
+            options.name
+        )));
+    }
+    let root = parent.join(&folder);
+    if std::fs::read_dir(&root).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(Error::ModDirectoryExists { path: root });
+    }
+
+    let uuid = options
+        .uuid
+        .clone()
+        .unwrap_or_else(|| generate_uuid(UuidFormat::Standard));
+    let mut renderer = Renderer::new(options, &folder, &uuid);
+
+    let mut files = vec![(
+        format!("Mods/{folder}/meta.lsx"),
+        generate_meta_lsx(
+            &options.name,
+            &folder,
+            &options.author,
+            &options.description,
+            &uuid,
+            options.version.major,
+            options.version.minor,
+            options.version.patch,
+            options.version.build,
+        ),
+    )];
+    for file in template.files {
+        let path = renderer.render(file.path, false);
+        let content = render_file(&mut renderer, file).with_path(root.join(&path))?;
+        files.push((path, content));
+    }
+    if options.script_extender {
+        files.extend(script_extender_files(&folder)?);
+    }
+
+    for dir in template.directories {
+        let dir = root.join(renderer.render(dir, false));
+        std::fs::create_dir_all(&dir).with_path(&dir)?;
+    }
+    for (path, content) in &files {
+        let dest = root.join(path);
+        if let Some(dir) = dest.parent() {
+            std::fs::create_dir_all(dir).with_path(dir)?;
+        }
+        std::fs::write(&dest, content).with_path(&dest)?;
+    }
+
+    Ok(NewMod {
+        root,
+        folder,
+        uuid,
+        files: files.into_iter().map(|(path, _)| path).collect(),
+    })
+}
+
+/// Substitute placeholders, then round-trip structured files through their
+/// parsers
+fn render_file(renderer: &mut Renderer, file: &TemplateFile) -> Result<String> {
+    let extension = Path::new(file.path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    match extension {
+        "lsx" => serialize_lsx(&parse_lsx(&renderer.render(file.content, true))?),
+        "xml" => loca_to_xml_string(&loca_from_xml(&renderer.render(file.content, true))?),
+        _ => Ok(renderer.render(file.content, false)),
+    }
+}
+
+/// Script Extender config and an empty server bootstrap
+fn script_extender_files(folder: &str) -> Result<Vec<(String, String)>> {
+    let config = serde_json::json!({
+        "RequiredVersion": SCRIPT_EXTENDER_VERSION,
+        "ModTable": folder,
+        "FeatureFlags": ["Lua"],
+    });
+    Ok(vec![
+        (
+            format!("Mods/{folder}/ScriptExtender/Config.json"),
+            serde_json::to_string_pretty(&config)? + "\n",
+        ),
+        (
+            format!("Mods/{folder}/ScriptExtender/Lua/BootstrapServer.lua"),
+            format!("-- Server-side entry point for {folder}\n"),
+        ),
+    ])
+}
+
+/// Placeholder values for one mod, with generated GUIDs and handles kept
+/// per label
+struct Renderer {
+    values: HashMap<&'static str, String>,
+    generated: HashMap<String, String>,
+}
+
+impl Renderer {
+    fn new(options: &NewModOptions, folder: &str, uuid: &str) -> Self {
+        let values = HashMap::from([
+            ("name", options.name.clone()),
+            ("folder", folder.to_string()),
+            ("author", options.author.clone()),
+            ("description", options.description.clone()),
+            ("uuid", uuid.to_string()),
+            ("version", options.version.to_string()),
+            ("version64", options.version.to_i64().to_string()),
+        ]);
+        Self {
+            values,
+            generated: HashMap::new(),
+        }
+    }
+
+    /// Replace every `{{placeholder}}` in `text`; unknown ones are left as-is
+    fn render(&mut self, text: &str, xml: bool) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let key = &rest[start + 2..start + 2 + len];
+            match self.value(key) {
+                Some(value) if xml => out.push_str(&escape_xml(&value)),
+                Some(value) => out.push_str(&value),
+                None => out.push_str(&rest[start..start + len + 4]),
+            }
+            rest = &rest[start + len + 4..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn value(&mut self, key: &str) -> Option<String> {
+        if let Some(value) = self.values.get(key) {
+            return Some(value.clone());
+        }
+        let format = match key.split_once(':')?.0 {
+            "guid" => UuidFormat::Standard,
+            "handle" => UuidFormat::Larian,
+            _ => return None,
+        };
+        Some(
+            self.generated
+                .entry(key.to_string())
+                .or_insert_with(|| generate_uuid(format))
+                .clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::lsx::read_lsx;
+    use crate::mods::{read_meta_version, validate_mod_structure};
+
+    #[test]
+    fn test_templates_create_valid_mods() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for template in MOD_TEMPLATES {
+            let mut options = NewModOptions::new("Tom & Jerry's Mod", "A <Modder>");
+            options.folder = Some(format!("Test {}", template.id));
+            options.version = Version64::new(1, 2, 0, 0);
+            options.script_extender = true;
+            let created = create_mod(dir.path(), template, &options).unwrap();
+
+            let report = validate_mod_structure(&created.root);
+            assert!(report.valid, "{}: {:?}", template.id, report.warnings);
+
+            let meta_path = created.root.join(&created.files[0]);
+            let meta = read_lsx(&meta_path).unwrap();
+            let info = meta.find_node("ModuleInfo").unwrap();
+            // LSX documents keep attribute values escaped
+            assert_eq!(
+                info.attribute_value("Name"),
+                Some("Tom &amp; Jerry&apos;s Mod")
+            );
+            assert_eq!(info.attribute_value("Author"), Some("A &lt;Modder&gt;"));
+            assert_eq!(info.attribute_value("UUID"), Some(created.uuid.as_str()));
+            assert_eq!(read_meta_version(&meta_path).unwrap(), options.version);
+
+            for file in &created.files {
+                let content = std::fs::read_to_string(created.root.join(file)).unwrap();
+                assert!(
+                    !content.contains("{{"),
+                    "{file} has unrendered placeholders"
+                );
+                if file.ends_with(".lsx") {
+                    read_lsx(created.root.join(file)).unwrap();
+                }
+            }
+        }
+
+        // Labels expand to the same GUID wherever they are used
+        let class = dir.path().join("Test_classblank");
+        let descriptions =
+            read_lsx(class.join("Public/Test_classblank/ClassDescriptions/ClassDescriptions.lsx"))
+                .unwrap();
+        let progressions =
+            read_lsx(class.join("Public/Test_classblank/Progressions/Progressions.lsx")).unwrap();
+        let table = descriptions
+            .find_node("ClassDescription")
+            .and_then(|n| n.attribute_value("ProgressionTableUUID"))
+            .unwrap();
+        assert_eq!(
+            progressions
+                .find_node("Progression")
+                .and_then(|n| n.attribute_value("TableUUID")),
+            Some(table)
+        );
+
+        let equipment = find_template("Equipment").unwrap();
+        assert!(matches!(
+            create_mod(
+                dir.path(),
+                equipment,
+                &NewModOptions::new("Test equipment", "")
+            ),
+            Err(Error::ModDirectoryExists { .. })
+        ));
+        assert!(matches!(
+            find_template("hair"),
+            Err(Error::UnknownModTemplate { .. })
+        ));
+    }
+}
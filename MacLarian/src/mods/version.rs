@@ -0,0 +1,328 @@
+//! BG3 `Version64` packed version numbers
+//!
+//! meta.lsx stores mod versions as a single `int64`:
+//! `major << 55 | minor << 47 | patch << 31 | build`, so `1.0.0.0` is
+//! written as `36028797018963968`.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::formats::lsx::{LsxDocument, read_lsx, write_lsx};
+
+const MAJOR_MASK: u64 = 0x7F;
+const MINOR_MASK: u64 = 0xFF;
+const PATCH_MASK: u64 = 0xFFFF;
+const BUILD_MASK: u64 = 0x7FFF_FFFF;
+
+/// A decoded `Version64` (`major.minor.patch.build`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version64 {
+    /// Major version (7 bits).
+    pub major: u32,
+    /// Minor version (8 bits).
+    pub minor: u32,
+    /// Patch/revision (16 bits).
+    pub patch: u32,
+    /// Build number (31 bits).
+    pub build: u32,
+}
+
+/// Which component to increment with [`Version64::bump`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionPart {
+    /// Increment major, reset minor/patch/build.
+    Major,
+    /// Increment minor, reset patch/build.
+    Minor,
+    /// Increment patch, reset build.
+    Patch,
+    /// Increment build.
+    Build,
+}
+
+impl Version64 {
+    /// Create a version from its components
+    #[must_use]
+    pub const fn new(major: u32, minor: u32, patch: u32, build: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            build,
+        }
+    }
+
+    /// Pack components into the raw `Version64` value
+    ///
+    /// Components wider than their bit field are truncated.
+    #[must_use]
+    pub const fn encode(major: u32, minor: u32, patch: u32, build: u32) -> u64 {
+        ((major as u64 & MAJOR_MASK) << 55)
+            | ((minor as u64 & MINOR_MASK) << 47)
+            | ((patch as u64 & PATCH_MASK) << 31)
+            | (build as u64 & BUILD_MASK)
+    }
+
+    /// Unpack a raw `Version64` value into `(major, minor, patch, build)`
+    #[must_use]
+    pub const fn decode(value: u64) -> (u32, u32, u32, u32) {
+        (
+            ((value >> 55) & MAJOR_MASK) as u32,
+            ((value >> 47) & MINOR_MASK) as u32,
+            ((value >> 31) & PATCH_MASK) as u32,
+            (value & BUILD_MASK) as u32,
+        )
+    }
+
+    /// Create a version from a raw `Version64` value
+    #[must_use]
+    pub const fn from_raw(value: u64) -> Self {
+        let (major, minor, patch, build) = Self::decode(value);
+        Self::new(major, minor, patch, build)
+    }
+
+    /// The raw `Version64` value
+    #[must_use]
+    pub const fn to_raw(self) -> u64 {
+        Self::encode(self.major, self.minor, self.patch, self.build)
+    }
+
+    /// The raw value as stored in meta.lsx (`int64`)
+    #[must_use]
+    pub const fn to_i64(self) -> i64 {
+        self.to_raw() as i64
+    }
+
+    /// Parse a dotted version (`1.2.3.4`, `1.2`) or a raw `Version64` integer
+    ///
+    /// See [`parse_version_string`](super::parse_version_string) for accepted forms.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        super::parse_version_string(s)
+            .map(|(major, minor, patch, build)| Self::new(major, minor, patch, build))
+    }
+
+    /// Return the version with one component incremented
+    ///
+    /// Lower components are reset to zero, except for [`VersionPart::Build`].
+    /// Returns `None` if the component is already at the maximum its bit
+    /// field can hold, rather than wrapping it to zero.
+    #[must_use]
+    pub const fn bump(self, part: VersionPart) -> Option<Self> {
+        let (value, mask) = match part {
+            VersionPart::Major => (self.major, MAJOR_MASK),
+            VersionPart::Minor => (self.minor, MINOR_MASK),
+            VersionPart::Patch => (self.patch, PATCH_MASK),
+            VersionPart::Build => (self.build, BUILD_MASK),
+        };
+        if value as u64 >= mask {
+            return None;
+        }
+        Some(match part {
+            VersionPart::Major => Self::new(self.major + 1, 0, 0, 0),
+            VersionPart::Minor => Self::new(self.major, self.minor + 1, 0, 0),
+            VersionPart::Patch => Self::new(self.major, self.minor, self.patch + 1, 0),
+            VersionPart::Build => Self::new(self.major, self.minor, self.patch, self.build + 1),
+        })
+    }
+}
+
+impl From<u64> for Version64 {
+    fn from(value: u64) -> Self {
+        Self::from_raw(value)
+    }
+}
+
+impl From<i64> for Version64 {
+    fn from(value: i64) -> Self {
+        Self::from_raw(value as u64)
+    }
+}
+
+impl fmt::Display for Version64 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.patch, self.build
+        )
+    }
+}
+
+/// Get the `ModuleInfo` version from a parsed meta.lsx document
+#[must_use]
+pub fn meta_version(doc: &LsxDocument) -> Option<Version64> {
+    doc.find_node("ModuleInfo")?
+        .attribute_value("Version64")?
+        .parse::<i64>()
+        .ok()
+        .map(Version64::from)
+}
+
+/// Set the `ModuleInfo` and `PublishVersion` versions in a parsed meta.lsx document
+///
+/// Returns `false` if the document has no `ModuleInfo` `Version64` attribute.
+/// `PublishVersion` is updated when present.
+pub fn set_meta_version(doc: &mut LsxDocument, version: Version64) -> bool {
+    let Some(module_info) = doc.find_node_mut("ModuleInfo") else {
+        return false;
+    };
+    let value = version.to_i64().to_string();
+
+    let Some(attr) = module_info.attribute_mut("Version64") else {
+        return false;
+    };
+    attr.value.clone_from(&value);
+
+    if let Some(attr) = module_info
+        .children
+        .iter_mut()
+        .find_map(|c| c.find_node_mut("PublishVersion"))
+        .and_then(|n| n.attribute_mut("Version64"))
+    {
+        attr.value = value;
+    }
+    true
+}
+
+/// Read the version from a meta.lsx file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed, or has no
+/// `ModuleInfo` `Version64` attribute.
+pub fn read_meta_version<P: AsRef<Path>>(path: P) -> Result<Version64> {
+    let path = path.as_ref();
+    let doc = read_lsx(path)?;
+    meta_version(&doc).ok_or_else(|| missing_version(path))
+}
+
+/// Increment the version in a meta.lsx file and write it back
+///
+/// Updates both `ModuleInfo` and `PublishVersion`. Returns the old and new versions.
+///
+/// # Errors
+/// Returns an error if the file cannot be read, parsed, or written, has no
+/// `ModuleInfo` `Version64` attribute, or the component is already at its maximum.
+pub fn bump_meta_version<P: AsRef<Path>>(
+    path: P,
+    part: VersionPart,
+) -> Result<(Version64, Version64)> {
+    let path = path.as_ref();
+    let mut doc = read_lsx(path)?;
+    let old = meta_version(&doc).ok_or_else(|| missing_version(path))?;
+    let new = old.bump(part).ok_or_else(|| Error::VersionOutOfRange {
+        part: format!("{part:?}").to_lowercase(),
+        version: old.to_string(),
+    })?;
+
+    set_meta_version(&mut doc, new);
+    write_lsx(&doc, path)?;
+    Ok((old, new))
+}
+
+fn missing_version(path: &Path) -> Error {
+    Error::InvalidFormat(format!(
+        "{} has no ModuleInfo Version64 attribute",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::lsx::parse_lsx;
+    use crate::mods::generate_meta_lsx;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(Version64::encode(1, 0, 0, 0), 36_028_797_018_963_968);
+        assert_eq!(Version64::decode(36_028_797_018_963_968), (1, 0, 0, 0));
+
+        let version = Version64::new(2, 3, 4, 5);
+        assert_eq!(Version64::from_raw(version.to_raw()), version);
+        assert_eq!(version.to_string(), "2.3.4.5");
+    }
+
+    #[test]
+    fn test_bump() {
+        let version = Version64::new(1, 2, 3, 4);
+        assert_eq!(
+            version.bump(VersionPart::Patch),
+            Some(Version64::new(1, 2, 4, 0))
+        );
+        assert_eq!(
+            version.bump(VersionPart::Minor),
+            Some(Version64::new(1, 3, 0, 0))
+        );
+        assert_eq!(
+            version.bump(VersionPart::Major),
+            Some(Version64::new(2, 0, 0, 0))
+        );
+        assert_eq!(
+            version.bump(VersionPart::Build),
+            Some(Version64::new(1, 2, 3, 5))
+        );
+    }
+
+    #[test]
+    fn test_bump_at_field_limits() {
+        let below = Version64::new(126, 254, 65_534, 0x7FFF_FFFE);
+        assert_eq!(
+            below.bump(VersionPart::Patch),
+            Some(Version64::new(126, 254, 65_535, 0))
+        );
+        assert_eq!(
+            below.bump(VersionPart::Minor),
+            Some(Version64::new(126, 255, 0, 0))
+        );
+        assert_eq!(
+            below.bump(VersionPart::Major),
+            Some(Version64::new(127, 0, 0, 0))
+        );
+        let bumped = below.bump(VersionPart::Build).unwrap();
+        assert_eq!(Version64::from_raw(bumped.to_raw()), bumped);
+
+        let full = Version64::new(127, 255, 65_535, 0x7FFF_FFFF);
+        assert_eq!(full.bump(VersionPart::Patch), None);
+        assert_eq!(full.bump(VersionPart::Minor), None);
+        assert_eq!(full.bump(VersionPart::Major), None);
+        assert_eq!(full.bump(VersionPart::Build), None);
+    }
+
+    #[test]
+    fn test_bump_meta_version_overflow() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meta.lsx");
+        let lsx = generate_meta_lsx("Test", "Test", "Me", "", "uuid", 1, 0, 65_535, 0);
+        std::fs::write(&path, &lsx).unwrap();
+
+        let err = bump_meta_version(&path, VersionPart::Patch).unwrap_err();
+        assert!(matches!(err, Error::VersionOutOfRange { ref part, .. } if part == "patch"));
+        assert_eq!(err.kind(), crate::error::ErrorKind::Usage);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), lsx);
+
+        let (old, new) = bump_meta_version(&path, VersionPart::Minor).unwrap();
+        assert_eq!(old, Version64::new(1, 0, 65_535, 0));
+        assert_eq!(new, Version64::new(1, 1, 0, 0));
+        assert_eq!(read_meta_version(&path).unwrap(), new);
+    }
+
+    #[test]
+    fn test_set_meta_version() {
+        let lsx = generate_meta_lsx("Test", "Test", "Me", "", "uuid", 1, 0, 0, 0);
+        let mut doc = parse_lsx(&lsx).unwrap();
+        assert_eq!(meta_version(&doc), Some(Version64::new(1, 0, 0, 0)));
+
+        assert!(set_meta_version(&mut doc, Version64::new(1, 0, 1, 0)));
+        let publish = doc
+            .find_node("PublishVersion")
+            .and_then(|n| n.attribute_value("Version64"));
+        assert_eq!(
+            publish,
+            Some(Version64::new(1, 0, 1, 0).to_i64().to_string().as_str())
+        );
+        assert_eq!(meta_version(&doc), Some(Version64::new(1, 0, 1, 0)));
+    }
+}
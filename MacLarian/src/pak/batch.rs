@@ -10,6 +10,8 @@ use walkdir::WalkDir;
 
 use super::PakOperations;
 use super::lspk::{PakPhase, PakProgress};
+use crate::error::Error;
+use crate::utils::{BatchOutcome, ErrorCollector, ErrorPolicy};
 
 /// Result of a batch PAK operation
 #[derive(Debug, Clone)]
@@ -93,7 +95,8 @@ fn contains_files_recursive(dir: &Path) -> bool {
 ///
 /// Extracts multiple PAK files to a destination directory, preserving the
 /// source directory structure. Each PAK is extracted into a subdirectory
-/// named after the PAK file (without extension).
+/// named after the PAK file (without extension). Failures are collected; see
+/// [`batch_extract_with_policy`] to stop at the first one instead.
 ///
 /// # Arguments
 /// * `pak_files` - List of PAK files to extract
@@ -112,15 +115,69 @@ pub fn batch_extract<F>(
 where
     F: Fn(&PakProgress) + Send + Sync,
 {
-    let success_counter = AtomicUsize::new(0);
-    let fail_counter = AtomicUsize::new(0);
+    let outcome = batch_extract_with_policy(
+        pak_files,
+        source_base,
+        dest_base,
+        ErrorPolicy::Collect,
+        progress,
+    );
+
+    let display = |path: &Path| {
+        path.strip_prefix(source_base)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
+    };
+    let mut results: Vec<String> = outcome
+        .succeeded
+        .iter()
+        .map(|pak_path| format!("Extracted: {}", display(pak_path)))
+        .collect();
+    results.extend(
+        outcome
+            .failed
+            .iter()
+            .map(|(pak_path, e)| format!("Failed {}: {e}", display(pak_path))),
+    );
+
+    BatchPakResult {
+        success_count: outcome.succeeded.len(),
+        fail_count: outcome.failed.len(),
+        results,
+    }
+}
+
+/// Batch extract PAK files in parallel with an explicit [`ErrorPolicy`]
+///
+/// Works like [`batch_extract`], but returns each failed PAK with its error.
+/// With [`ErrorPolicy::FailFast`], PAKs not yet started when the first
+/// failure is recorded are skipped.
+///
+/// # Returns
+/// The PAK files that were extracted and those that failed.
+pub fn batch_extract_with_policy<F>(
+    pak_files: &[PathBuf],
+    source_base: &Path,
+    dest_base: &Path,
+    policy: ErrorPolicy,
+    progress: F,
+) -> BatchOutcome<PathBuf>
+where
+    F: Fn(&PakProgress) + Send + Sync,
+{
+    let collector = ErrorCollector::new(policy);
     let processed = AtomicUsize::new(0);
     let total = pak_files.len();
 
     // Parallel PAK extraction
-    let results: Vec<String> = pak_files
+    let extracted: Vec<PathBuf> = pak_files
         .par_iter()
-        .map(|pak_path| {
+        .filter_map(|pak_path| {
+            if collector.should_stop() {
+                return None;
+            }
+
             // Calculate relative path for display and output structure
             let relative_path = pak_path
                 .strip_prefix(source_base)
@@ -146,32 +203,20 @@ where
 
             let pak_dest = dest_base.join(relative_parent).join(&pak_stem);
 
-            if let Err(e) = std::fs::create_dir_all(&pak_dest) {
-                fail_counter.fetch_add(1, Ordering::SeqCst);
-                return format!("Failed to create folder for {display_path}: {e}");
-            }
-
-            let pak_str = pak_path.to_string_lossy().to_string();
-            let dest_str = pak_dest.to_string_lossy().to_string();
-
-            match PakOperations::extract(&pak_str, &dest_str) {
-                Ok(()) => {
-                    success_counter.fetch_add(1, Ordering::SeqCst);
-                    format!("Extracted: {display_path}")
-                }
+            let result = std::fs::create_dir_all(&pak_dest)
+                .map_err(Error::from)
+                .and_then(|()| PakOperations::extract(pak_path, &pak_dest));
+            match result {
+                Ok(()) => Some(pak_path.clone()),
                 Err(e) => {
-                    fail_counter.fetch_add(1, Ordering::SeqCst);
-                    format!("Failed {display_path}: {e}")
+                    collector.record(pak_path, e);
+                    None
                 }
             }
         })
         .collect();
 
-    BatchPakResult {
-        success_count: success_counter.load(Ordering::SeqCst),
-        fail_count: fail_counter.load(Ordering::SeqCst),
-        results,
-    }
+    collector.finish(extracted)
 }
 
 /// Batch create PAK files in parallel
@@ -259,3 +304,27 @@ where
         results,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_extract_reports_failures() {
+        let base = tempfile::tempdir().unwrap();
+        let (source, dest) = (base.path().join("source"), base.path().join("dest"));
+        std::fs::create_dir_all(&source).unwrap();
+        let bogus = source.join("Bogus.pak");
+        std::fs::write(&bogus, b"not a pak file").unwrap();
+
+        let paks = vec![bogus.clone(), source.join("Missing.pak")];
+        let outcome =
+            batch_extract_with_policy(&paks, &source, &dest, ErrorPolicy::Collect, |_| {});
+        assert!(outcome.succeeded.is_empty());
+        assert_eq!(outcome.failure_count(), 2);
+        assert!(outcome.failed.iter().any(|(path, _)| path == &bogus));
+
+        let result = batch_extract(&paks, &source, &dest, |_| {});
+        assert_eq!(result.fail_count, 2);
+    }
+}
@@ -0,0 +1,201 @@
+//! Include/exclude glob filters for PAK paths
+//!
+//! A [`FilterSet`] decides which entries of a PAK are listed or extracted. A
+//! path matches when it matches any include pattern (or there are none) and
+//! no exclude pattern. Patterns follow [`globset`] syntax: `*` and `?` also
+//! match `/`, `**` spans folders and `{a,b}` expands to alternatives.
+//!
+//! Each pattern is tried against the whole path and against the file name,
+//! so `*.lsf` and `Public/**/*.lsf` both work. A leading `/` on a pattern is
+//! ignored, as PAK paths are relative. A pattern that is just a GUID (any
+//! form [`classify_uuid`] accepts) matches paths containing it hyphenated or
+//! bare, regardless of case.
+
+use std::fmt;
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::error::{Error, Result};
+use crate::utils::{UuidFormat, classify_uuid, convert_uuid};
+
+/// Include and exclude patterns for PAK paths
+#[derive(Debug, Clone)]
+pub struct FilterSet {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    include_set: GlobSet,
+    exclude_set: GlobSet,
+}
+
+impl FilterSet {
+    /// Compile include and exclude patterns
+    ///
+    /// Empty `include` matches every path not excluded.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidPattern`] if a pattern is not a valid glob.
+    pub fn new<S: AsRef<str>>(
+        include: &[S],
+        exclude: &[S],
+        case_insensitive: bool,
+    ) -> Result<Self> {
+        let include: Vec<String> = include.iter().map(|p| p.as_ref().to_string()).collect();
+        let exclude: Vec<String> = exclude.iter().map(|p| p.as_ref().to_string()).collect();
+        Ok(Self {
+            include_set: build_set(&include, case_insensitive)?,
+            exclude_set: build_set(&exclude, case_insensitive)?,
+            include,
+            exclude,
+        })
+    }
+
+    /// A filter matching every path
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            include_set: GlobSet::empty(),
+            exclude_set: GlobSet::empty(),
+        }
+    }
+
+    /// Whether the filter has no patterns at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Include patterns as given
+    #[must_use]
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    /// Exclude patterns as given
+    #[must_use]
+    pub fn exclude(&self) -> &[String] {
+        &self.exclude
+    }
+
+    /// Whether a PAK path passes the filter
+    ///
+    /// Backslashes and a leading `/` in `path` are normalized first.
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        let path = path.trim_start_matches('/');
+        let file_name = Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(path);
+        let hit = |set: &GlobSet| set.is_match(path) || set.is_match(file_name);
+
+        (self.include.is_empty() || hit(&self.include_set)) && !hit(&self.exclude_set)
+    }
+}
+
+impl Default for FilterSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl fmt::Display for FilterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.include.is_empty() {
+            write!(f, "*")?;
+        } else {
+            write!(f, "{}", self.include.join(", "))?;
+        }
+        if !self.exclude.is_empty() {
+            write!(f, " (excluding {})", self.exclude.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+fn build_set(patterns: &[String], case_insensitive: bool) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if classify_uuid(pattern).is_some() {
+            // Paths spell GUIDs in lowercase, hyphenated or not
+            for format in [UuidFormat::Standard, UuidFormat::Compact] {
+                if let Some(guid) = convert_uuid(pattern, format) {
+                    builder.add(compile(pattern, &format!("*{guid}*"), true)?);
+                }
+            }
+        } else {
+            let glob = pattern.trim_start_matches('/');
+            builder.add(compile(pattern, glob, case_insensitive)?);
+        }
+    }
+    builder
+        .build()
+        .map_err(|e| invalid(&patterns.join(", "), &e))
+}
+
+fn compile(pattern: &str, glob: &str, case_insensitive: bool) -> Result<globset::Glob> {
+    GlobBuilder::new(glob)
+        .case_insensitive(case_insensitive)
+        .backslash_escape(false)
+        .build()
+        .map_err(|e| invalid(pattern, &e))
+}
+
+fn invalid(pattern: &str, e: &globset::Error) -> Error {
+    Error::InvalidPattern {
+        pattern: pattern.to_string(),
+        message: e.kind().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_set_patterns() {
+        let filter = FilterSet::new(
+            &["Public/MyMod/**/*.{lsf,lsx}"],
+            &["**/Localization/**"],
+            true,
+        )
+        .unwrap();
+        assert!(filter.matches("Public/MyMod/Stats/Items.lsf"));
+        assert!(filter.matches("Public/MyMod/RootTemplates/_merged.LSX"));
+        assert!(filter.matches("\\Public\\MyMod\\Tags\\a.lsx"));
+        assert!(!filter.matches("Public/MyMod/Localization/English/a.lsx"));
+        assert!(!filter.matches("Public/MyMod/Stats/Items.txt"));
+        assert!(!filter.matches("Public/Other/Stats/Items.lsf"));
+
+        // Either include may match; bare names match anywhere
+        let filter = FilterSet::new(&["/Mods/*/meta.lsx", "*.dds"], &[], false).unwrap();
+        assert!(filter.matches("Mods/MyMod/meta.lsx"));
+        assert!(filter.matches("/Mods/MyMod/meta.lsx"));
+        assert!(filter.matches("Public/MyMod/Assets/Icon.dds"));
+        assert!(!filter.matches("Public/MyMod/Assets/Icon.DDS"));
+        assert!(!filter.matches("Mods/MyMod/Meta.lsx"));
+
+        // `**/` also matches at the top level
+        let filter = FilterSet::new(&["**/*.lsf"], &[], true).unwrap();
+        assert!(filter.matches("meta.lsf"));
+
+        // Excludes alone keep everything else
+        let filter = FilterSet::new(&[], &["*.gr2"], true).unwrap();
+        assert!(filter.matches("Public/MyMod/a.lsx"));
+        assert!(!filter.matches("Generated/Public/MyMod/Body.GR2"));
+
+        let guid = FilterSet::new(&["12345678-9ABC-DEF0-1122-334455667788"], &[], false).unwrap();
+        assert!(guid.matches("Public/MyMod/Content/12345678-9abc-def0-1122-334455667788.lsf"));
+        assert!(guid.matches("Public/MyMod/123456789abcdef01122334455667788.lsx"));
+        assert!(!guid.matches("Public/MyMod/Content/other.lsf"));
+
+        assert!(FilterSet::all().matches("anything"));
+        assert!(matches!(
+            FilterSet::new(&["*.{lsf"], &[], true),
+            Err(Error::InvalidPattern { .. })
+        ));
+    }
+}
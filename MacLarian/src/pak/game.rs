@@ -0,0 +1,248 @@
+//! Finding the PAKs of a game install
+//!
+//! The game reads every `.pak` under its `Data` folder, including
+//! `Localization/` and DLC subfolders. Files later in the load order replace
+//! earlier ones with the same path, so [`find_game_paks`] returns them in
+//! that order: the base archives, then localization, then other subfolders,
+//! then patches and hotfixes.
+
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::error::{Error, Result};
+
+/// Base archives in the order the game mounts them; others follow by name
+const BASE_ORDER: &[&str] = &[
+    "Engine",
+    "EngineShaders",
+    "Game",
+    "GamePlatform",
+    "Shared",
+    "SharedDev",
+    "Gustav",
+    "GustavDev",
+    "GustavX",
+];
+
+/// Resolve the `Data` folder of a game install
+///
+/// Accepts the `Data` folder itself, the install folder containing it, or a
+/// macOS app bundle.
+///
+/// # Errors
+/// Returns [`Error::FileNotFound`] if none of those is a directory.
+pub fn resolve_game_data_dir(dir: &Path) -> Result<PathBuf> {
+    let candidates = [
+        dir.join("Data"),
+        dir.join("Contents").join("Data"),
+        dir.join("Baldur's Gate 3.app")
+            .join("Contents")
+            .join("Data"),
+    ];
+    if let Some(data) = candidates.into_iter().find(|d| d.is_dir()) {
+        return Ok(data);
+    }
+    if dir.is_dir() {
+        return Ok(dir.to_path_buf());
+    }
+    Err(Error::FileNotFound {
+        path: dir.to_path_buf(),
+    })
+}
+
+/// Find the PAKs of a game install in load order
+///
+/// `dir` is resolved with [`resolve_game_data_dir`]. Archive parts
+/// (`Textures_1.pak`, ...) are skipped since they're read through their
+/// main archive. With `skip_media`, archives holding only video, audio or
+/// shader caches (see [`is_media_pak`]) are skipped as well.
+///
+/// # Errors
+/// Returns an error if `dir` is not a game install or `Data` folder.
+pub fn find_game_paks(dir: &Path, skip_media: bool) -> Result<Vec<PathBuf>> {
+    let data_dir = resolve_game_data_dir(dir)?;
+    let mut paks: Vec<PathBuf> = WalkDir::new(&data_dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .map(walkdir::DirEntry::into_path)
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"))
+        })
+        .filter(|p| !is_archive_part(p))
+        .filter(|p| !(skip_media && is_media_pak(p)))
+        .collect();
+
+    paks.sort_by(|a, b| compare_load_order(&data_dir, a, b));
+    Ok(paks)
+}
+
+/// Whether a PAK holds only media or caches that search and extraction
+/// have no use for: videos, sound banks, voice audio and shader caches
+#[must_use]
+pub fn is_media_pak(path: &Path) -> bool {
+    let stem = stem(path).to_ascii_lowercase();
+    stem.contains("video") || stem.contains("sound") || stem.contains("psocache") || stem == "voice"
+}
+
+/// Whether `path` is a part of a multi-part archive (`Name_1.pak` next to
+/// `Name.pak`)
+fn is_archive_part(path: &Path) -> bool {
+    let stem = stem(path);
+    let Some((base, part)) = stem.rsplit_once('_') else {
+        return false;
+    };
+    if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let ext = path.extension().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{base}.{ext}")).is_file()
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Load order group: base archives, localization, other subfolders, patches
+fn group(relative: &Path) -> u8 {
+    let stem = stem(relative).to_ascii_lowercase();
+    if stem.contains("patch") || stem.contains("hotfix") {
+        3
+    } else if relative.components().count() == 1 {
+        0
+    } else if relative.starts_with("Localization") {
+        1
+    } else {
+        2
+    }
+}
+
+fn compare_load_order(data_dir: &Path, a: &Path, b: &Path) -> Ordering {
+    let a = a.strip_prefix(data_dir).unwrap_or(a);
+    let b = b.strip_prefix(data_dir).unwrap_or(b);
+    let base_rank = |p: &Path| {
+        let stem = stem(p);
+        BASE_ORDER
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(&stem))
+            .unwrap_or(BASE_ORDER.len())
+    };
+    group(a)
+        .cmp(&group(b))
+        .then_with(|| base_rank(a).cmp(&base_rank(b)))
+        .then_with(|| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()))
+}
+
+/// Compare strings with digit runs compared as numbers, so `Patch2` sorts
+/// before `Patch10`
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    loop {
+        match (a.first(), b.first()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let a_len = a.iter().take_while(|c| c.is_ascii_digit()).count();
+                let b_len = b.iter().take_while(|c| c.is_ascii_digit()).count();
+                let (a_num, b_num) = (trim_zeros(&a[..a_len]), trim_zeros(&b[..b_len]));
+                let order = a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num));
+                if order != Ordering::Equal {
+                    return order;
+                }
+                a = &a[a_len..];
+                b = &b[b_len..];
+            }
+            (Some(x), Some(y)) => {
+                let order = x.to_ascii_lowercase().cmp(&y.to_ascii_lowercase());
+                if order != Ordering::Equal {
+                    return order;
+                }
+                a = &a[1..];
+                b = &b[1..];
+            }
+        }
+    }
+}
+
+fn trim_zeros(digits: &[u8]) -> &[u8] {
+    let zeros = digits.iter().take_while(|&&d| d == b'0').count();
+    &digits[zeros..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_game_paks_in_load_order() {
+        let root = tempfile::tempdir().unwrap();
+        let data = root.path().join("Data");
+        let files = [
+            "Data/Textures.pak",
+            "Data/Textures_1.pak",
+            "Data/Shared.pak",
+            "Data/Gustav.pak",
+            "Data/Gustav_Video.pak",
+            "Data/SharedSounds.pak",
+            "Data/Patch10_Hotfix1.pak",
+            "Data/Patch2_Hotfix3.pak",
+            "Data/Localization/English.pak",
+            "Data/Localization/Voice.pak",
+            "Data/DLC/Extra.pak",
+            "Data/readme.txt",
+        ];
+        for file in files {
+            let path = root.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+
+        let all = find_game_paks(root.path(), false);
+        let skipped = find_game_paks(&data, true);
+
+        let names = |paks: Vec<PathBuf>| -> Vec<String> {
+            paks.iter()
+                .map(|p| {
+                    p.strip_prefix(&data)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+        assert_eq!(
+            names(all.unwrap()),
+            [
+                "Shared.pak",
+                "Gustav.pak",
+                "Gustav_Video.pak",
+                "SharedSounds.pak",
+                "Textures.pak",
+                "Localization/English.pak",
+                "Localization/Voice.pak",
+                "DLC/Extra.pak",
+                "Patch2_Hotfix3.pak",
+                "Patch10_Hotfix1.pak",
+            ]
+        );
+        assert_eq!(
+            names(skipped.unwrap()),
+            [
+                "Shared.pak",
+                "Gustav.pak",
+                "Textures.pak",
+                "Localization/English.pak",
+                "DLC/Extra.pak",
+                "Patch2_Hotfix3.pak",
+                "Patch10_Hotfix1.pak",
+            ]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Content search inside PAK files without an index
+//!
+//! [`grep_pak`] reads the text-bearing entries of a PAK in batches, extracts
+//! their searchable text and matches it line by line. Only one batch of file
+//! contents is held in memory at a time, so a one-off search of a large PAK
+//! stays cheap compared to building a full-text index.
+
+use std::path::Path;
+
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use super::lspk::{PakPhase, PakProgress};
+use super::pak_tools::{PakReaderCache, ProgressCallback};
+use crate::error::{Error, Result};
+use crate::formats::common::{extract_value, type_name_to_id};
+use crate::formats::lsb::parse_lsb_bytes;
+use crate::formats::lsf::LsfDocument;
+use crate::formats::lsx::LsxNode;
+use crate::utils::CancelToken;
+
+/// Number of files read and searched together
+pub const GREP_BATCH_SIZE: usize = 256;
+
+/// Extensions whose contents can be searched
+pub const SEARCHABLE_EXTENSIONS: &[&str] = &["lsx", "lsf", "lsb", "lsj", "xml", "json"];
+
+/// Longest line kept in a [`LineMatch`], in characters
+const MAX_SNIPPET_CHARS: usize = 200;
+
+/// Literal or regex pattern matched against lines of text
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    /// Substring match
+    Literal {
+        /// The substring, lowercased unless `case_sensitive`
+        needle: String,
+        /// Whether case must match
+        case_sensitive: bool,
+    },
+    /// Regular expression match
+    Regex(Regex),
+}
+
+impl TextMatcher {
+    /// Create a matcher for `pattern`
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidPattern`] if `regex` is set and the pattern
+    /// doesn't compile.
+    pub fn new(pattern: &str, regex: bool, case_sensitive: bool) -> Result<Self> {
+        if regex {
+            let compiled = RegexBuilder::new(pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map_err(|e| Error::InvalidPattern {
+                    pattern: pattern.to_string(),
+                    message: e.to_string(),
+                })?;
+            return Ok(Self::Regex(compiled));
+        }
+
+        Ok(Self::Literal {
+            needle: if case_sensitive {
+                pattern.to_string()
+            } else {
+                pattern.to_lowercase()
+            },
+            case_sensitive,
+        })
+    }
+
+    /// Whether `line` matches
+    #[must_use]
+    pub fn is_match(&self, line: &str) -> bool {
+        match self {
+            Self::Literal {
+                needle,
+                case_sensitive: true,
+            } => line.contains(needle.as_str()),
+            Self::Literal { needle, .. } => line.to_lowercase().contains(needle.as_str()),
+            Self::Regex(regex) => regex.is_match(line),
+        }
+    }
+
+    /// The lines of `text` that match, with 1-based line numbers
+    #[must_use]
+    pub fn matching_lines(&self, text: &str) -> Vec<LineMatch> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| self.is_match(line))
+            .map(|(i, line)| LineMatch {
+                line_number: i + 1,
+                line: snippet(line),
+            })
+            .collect()
+    }
+}
+
+/// A matching line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineMatch {
+    /// Line number (1-based)
+    pub line_number: usize,
+    /// The line, trimmed and shortened to 200 characters
+    pub line: String,
+}
+
+/// A PAK entry with matching lines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GrepHit {
+    /// Internal path of the entry
+    pub path: String,
+    /// Matching lines, in order
+    pub lines: Vec<LineMatch>,
+}
+
+/// Searchable text of a file, chosen by its extension
+///
+/// LSF and LSB files contribute their names and string values, one per line;
+/// text formats are returned as-is. Other files have no searchable text.
+#[must_use]
+pub fn searchable_text(path: &str, bytes: &[u8]) -> String {
+    match extension(path).as_str() {
+        "lsf" => lsf_searchable_text(bytes),
+        "lsb" => lsb_searchable_text(bytes),
+        "lsx" | "lsj" | "xml" | "json" => String::from_utf8_lossy(bytes).into_owned(),
+        _ => String::new(),
+    }
+}
+
+/// Names and string attribute values of an LSF file, one per line
+///
+/// Returns an empty string if the file can't be parsed.
+#[must_use]
+pub fn lsf_searchable_text(bytes: &[u8]) -> String {
+    let Ok(doc) = LsfDocument::open_lazy(bytes) else {
+        return String::new();
+    };
+
+    let names = doc.names().iter().flatten().filter(|name| !name.is_empty());
+
+    // Names stay searchable even if the values can't be decoded
+    let values_data = doc.values().unwrap_or_default();
+
+    // Type IDs: 20-23 (strings), 28-31 (translated strings, UUIDs)
+    let values = doc.attributes().iter().filter_map(|attr| {
+        let type_id = attr.type_info & 0x3F;
+        let value_length = (attr.type_info >> 6) as usize;
+        if !matches!(type_id, 20..=23 | 28..=31) {
+            return None;
+        }
+        extract_value(values_data, attr.offset, value_length, type_id)
+            .ok()
+            .filter(|value| !value.is_empty())
+    });
+
+    let mut parts: Vec<String> = names.cloned().collect();
+    parts.extend(values);
+    parts.join("\n")
+}
+
+/// Node names, attribute names and string values of an LSB file, one per line
+///
+/// Returns an empty string if the file can't be parsed.
+#[must_use]
+pub fn lsb_searchable_text(bytes: &[u8]) -> String {
+    let Ok(doc) = parse_lsb_bytes(bytes) else {
+        return String::new();
+    };
+
+    let mut parts = Vec::new();
+    let mut stack: Vec<&LsxNode> = doc.regions.iter().flat_map(|r| &r.nodes).collect();
+    while let Some(node) = stack.pop() {
+        parts.push(node.id.as_str());
+        for attr in &node.attributes {
+            parts.push(attr.id.as_str());
+            // Type IDs: 20-23 (strings), 28-31 (translated strings, UUIDs)
+            if matches!(type_name_to_id(&attr.type_name), 20..=23 | 28..=31) {
+                parts.extend(attr.handle.as_deref());
+                parts.push(attr.value.as_str());
+            }
+        }
+        stack.extend(&node.children);
+    }
+    parts.retain(|part| !part.is_empty());
+    parts.join("\n")
+}
+
+/// Search the text files of a PAK for `matcher`
+///
+/// `extensions` limits the search to those file types (see
+/// [`SEARCHABLE_EXTENSIONS`]); an empty list searches all of them. Hits are
+/// returned in file table order.
+///
+/// # Errors
+/// Returns [`Error::Cancelled`] if cancelled, or an error if the PAK cannot
+/// be read.
+pub fn grep_pak<P: AsRef<Path>>(
+    pak_path: P,
+    matcher: &TextMatcher,
+    extensions: &[&str],
+    cancel: &CancelToken,
+    progress: ProgressCallback,
+) -> Result<Vec<GrepHit>> {
+    let pak_path = pak_path.as_ref();
+    let mut cache = PakReaderCache::new(1);
+
+    let wanted = if extensions.is_empty() {
+        SEARCHABLE_EXTENSIONS
+    } else {
+        extensions
+    };
+    let files: Vec<String> = cache
+        .list_files(pak_path)?
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| {
+            let ext = extension(path);
+            wanted.iter().any(|w| w.eq_ignore_ascii_case(&ext))
+        })
+        .collect();
+
+    let total = files.len();
+    let mut hits = Vec::new();
+    for (i, batch) in files.chunks(GREP_BATCH_SIZE).enumerate() {
+        cancel.checkpoint()?;
+        progress(&PakProgress::with_file(
+            PakPhase::SearchingContent,
+            i * GREP_BATCH_SIZE,
+            total,
+            &batch[0],
+        ));
+
+        let paths: Vec<&str> = batch.iter().map(String::as_str).collect();
+        let contents = cache.read_files_bulk(pak_path, &paths)?;
+        let batch_hits: Vec<GrepHit> = batch
+            .par_iter()
+            .filter_map(|path| {
+                let text = searchable_text(path, contents.get(path)?);
+                let lines = matcher.matching_lines(&text);
+                (!lines.is_empty()).then(|| GrepHit {
+                    path: path.clone(),
+                    lines,
+                })
+            })
+            .collect();
+        hits.extend(batch_hits);
+    }
+
+    progress(&PakProgress::new(PakPhase::Complete, total, total));
+    Ok(hits)
+}
+
+/// Lowercase extension of a PAK path
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// A line trimmed and cut to [`MAX_SNIPPET_CHARS`] characters
+fn snippet(line: &str) -> String {
+    let line = line.trim();
+    match line.char_indices().nth(MAX_SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::pak::PakBuilder;
+
+    #[test]
+    fn test_grep_pak_literal_and_regex() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Grep.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        let settings = CompressionSettings::default();
+        builder
+            .add_file(
+                "Public/Mod/Stats/Items.lsx",
+                b"<node id=\"Item\">\n  <attribute id=\"Name\" value=\"Shadowheart\"/>\n</node>",
+                settings,
+            )
+            .unwrap();
+        builder
+            .add_file("Public/Mod/meta.json", b"{\"name\": \"shadow\"}", settings)
+            .unwrap();
+        builder
+            .add_file("Public/Mod/Icon.dds", b"Shadowheart", settings)
+            .unwrap();
+        builder.finish().unwrap();
+
+        let cancel = CancelToken::new();
+        let matcher = TextMatcher::new("SHADOW", false, false).unwrap();
+        let hits = grep_pak(&pak, &matcher, &[], &cancel, &|_| {}).unwrap();
+        assert_eq!(hits.len(), 2);
+        let lsx = hits.iter().find(|h| h.path.ends_with(".lsx")).unwrap();
+        assert_eq!(lsx.lines[0].line_number, 2);
+        assert!(lsx.lines[0].line.starts_with("<attribute"));
+
+        let matcher = TextMatcher::new(r"value=\x22Shadow\w+", true, true).unwrap();
+        let hits = grep_pak(&pak, &matcher, &["lsx"], &cancel, &|_| {}).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(TextMatcher::new("(", true, false).is_err());
+
+        cancel.cancel();
+        assert!(matches!(
+            grep_pak(&pak, &matcher, &[], &cancel, &|_| {}),
+            Err(Error::Cancelled)
+        ));
+    }
+}
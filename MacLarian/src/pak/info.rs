@@ -0,0 +1,317 @@
+//! PAK size summaries and duplicate content detection
+//!
+//! [`pak_info`] only reads the header and file table. [`find_duplicates`]
+//! has to decompress entries to compare them, so it is a separate, slower
+//! pass: it only reads entries whose decompressed size matches another
+//! entry's, in batches, and hashes them in parallel.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rayon::prelude::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::PakOperations;
+use super::lspk::{LspkReader, PakPhase, PakProgress};
+use super::pak_tools::{PakReaderCache, ProgressCallback};
+use crate::error::Result;
+use crate::utils::{CancelToken, open_file};
+
+/// Number of files read and hashed together by [`find_duplicates`]
+pub const DUPES_BATCH_SIZE: usize = 256;
+
+/// Sizes of one entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PakEntrySize {
+    /// Internal path of the entry.
+    pub path: String,
+    /// Decompressed size in bytes.
+    pub size: u64,
+    /// Stored size in bytes.
+    pub compressed_size: u64,
+}
+
+/// Totals for all entries with one extension
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ExtensionStats {
+    /// Lowercase extension without the dot (empty for none).
+    pub extension: String,
+    /// Number of entries.
+    pub files: usize,
+    /// Decompressed size in bytes.
+    pub size: u64,
+    /// Stored size in bytes.
+    pub compressed_size: u64,
+}
+
+impl ExtensionStats {
+    /// Stored size as a fraction of the decompressed size (1.0 when empty)
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        ratio(self.compressed_size, self.size)
+    }
+}
+
+/// Size summary of a PAK, from its file table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PakInfo {
+    /// Load priority from the header.
+    pub priority: u8,
+    /// Number of entries.
+    pub files: usize,
+    /// Total decompressed size in bytes.
+    pub size: u64,
+    /// Total stored size in bytes.
+    pub compressed_size: u64,
+    /// Totals per extension, largest first.
+    pub extensions: Vec<ExtensionStats>,
+    /// The largest entries by decompressed size, largest first.
+    pub largest: Vec<PakEntrySize>,
+}
+
+impl PakInfo {
+    /// Stored size as a fraction of the decompressed size (1.0 when empty)
+    #[must_use]
+    pub fn ratio(&self) -> f64 {
+        ratio(self.compressed_size, self.size)
+    }
+}
+
+/// Entries with byte-identical contents
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    /// Decompressed size of each copy in bytes.
+    pub size: u64,
+    /// Internal paths of the copies, sorted.
+    pub paths: Vec<String>,
+}
+
+impl DuplicateGroup {
+    /// Bytes that would be saved by keeping a single copy
+    #[must_use]
+    pub fn wasted(&self) -> u64 {
+        self.size * (self.paths.len() as u64).saturating_sub(1)
+    }
+}
+
+/// Result of [`find_duplicates`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DuplicateReport {
+    /// Number of entries that were decompressed and hashed.
+    pub files_hashed: usize,
+    /// Groups of identical entries, most wasted space first.
+    pub groups: Vec<DuplicateGroup>,
+}
+
+impl DuplicateReport {
+    /// Decompressed bytes that would be saved by removing every extra copy
+    #[must_use]
+    pub fn wasted(&self) -> u64 {
+        self.groups.iter().map(DuplicateGroup::wasted).sum()
+    }
+}
+
+/// Summarize the sizes of a PAK's entries
+///
+/// `top_n` is the number of largest entries to keep.
+///
+/// # Errors
+/// Returns an error if the PAK's file table cannot be read.
+pub fn pak_info(pak_path: impl AsRef<Path>, top_n: usize) -> Result<PakInfo> {
+    let pak_path = pak_path.as_ref();
+    let priority = LspkReader::with_path(open_file(pak_path)?, pak_path).priority()?;
+    let entries = PakOperations::list_detailed(pak_path)?;
+
+    let mut info = PakInfo {
+        priority,
+        files: entries.len(),
+        ..PakInfo::default()
+    };
+    let mut extensions: HashMap<String, ExtensionStats> = HashMap::new();
+    let mut sizes = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        let path = entry.path.to_string_lossy().replace('\\', "/");
+        let size = u64::from(entry.size_decompressed);
+        let compressed_size = u64::from(entry.size_compressed);
+        info.size += size;
+        info.compressed_size += compressed_size;
+
+        let extension = extension(&path);
+        let stats = extensions
+            .entry(extension.clone())
+            .or_insert_with(|| ExtensionStats {
+                extension,
+                ..ExtensionStats::default()
+            });
+        stats.files += 1;
+        stats.size += size;
+        stats.compressed_size += compressed_size;
+
+        sizes.push(PakEntrySize {
+            path,
+            size,
+            compressed_size,
+        });
+    }
+
+    info.extensions = extensions.into_values().collect();
+    info.extensions.sort_by(|a, b| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| a.extension.cmp(&b.extension))
+    });
+    sizes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    sizes.truncate(top_n);
+    info.largest = sizes;
+    Ok(info)
+}
+
+/// Find entries of a PAK with identical decompressed contents
+///
+/// Empty entries are ignored. Only entries sharing their size with another
+/// entry are read; contents are compared by SHA-256.
+///
+/// # Errors
+/// Returns an error if the PAK cannot be read, or [`Error::Cancelled`] if
+/// `cancel` is triggered.
+///
+/// [`Error::Cancelled`]: crate::error::Error::Cancelled
+pub fn find_duplicates(
+    pak_path: impl AsRef<Path>,
+    cancel: &CancelToken,
+    progress: ProgressCallback,
+) -> Result<DuplicateReport> {
+    let pak_path = pak_path.as_ref();
+    let mut cache = PakReaderCache::new(1);
+
+    // Only entries with a size twin can have a duplicate
+    let mut by_size: HashMap<u64, Vec<String>> = HashMap::new();
+    for (path, size) in cache.list_files(pak_path)? {
+        if size > 0 {
+            by_size.entry(size).or_default().push(path);
+        }
+    }
+    let mut candidates: Vec<(String, u64)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (path, size)))
+        .collect();
+    candidates.sort();
+
+    let total = candidates.len();
+    let mut by_hash: HashMap<([u8; 32], u64), Vec<String>> = HashMap::new();
+    for (i, batch) in candidates.chunks(DUPES_BATCH_SIZE).enumerate() {
+        cancel.checkpoint()?;
+        progress(&PakProgress::with_file(
+            PakPhase::HashingContent,
+            i * DUPES_BATCH_SIZE,
+            total,
+            &batch[0].0,
+        ));
+
+        let paths: Vec<&str> = batch.iter().map(|(path, _)| path.as_str()).collect();
+        let contents = cache.read_files_bulk(pak_path, &paths)?;
+        let hashes: Vec<([u8; 32], u64, &String)> = batch
+            .par_iter()
+            .filter_map(|(path, size)| {
+                let hash: [u8; 32] = Sha256::digest(contents.get(path)?).into();
+                Some((hash, *size, path))
+            })
+            .collect();
+        for (hash, size, path) in hashes {
+            by_hash.entry((hash, size)).or_default().push(path.clone());
+        }
+    }
+    progress(&PakProgress::new(PakPhase::Complete, total, total));
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((_, size), mut paths)| {
+            paths.sort();
+            DuplicateGroup { size, paths }
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.wasted()
+            .cmp(&a.wasted())
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+
+    Ok(DuplicateReport {
+        files_hashed: total,
+        groups,
+    })
+}
+
+/// Lowercase extension of a PAK path
+fn extension(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn ratio(compressed: u64, size: u64) -> f64 {
+    if size == 0 {
+        1.0
+    } else {
+        compressed as f64 / size as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::pak::PakBuilder;
+
+    #[test]
+    fn test_pak_info_and_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Dupes.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        let settings = CompressionSettings::default();
+        let icon = vec![7u8; 4096];
+        for path in [
+            "Public/Mod/GUI/Icon.dds",
+            "Public/Mod/GUI/Copy/Icon.dds",
+            "Public/Mod/GUI/Icon_Old.dds",
+        ] {
+            builder.add_file(path, &icon, settings).unwrap();
+        }
+        // Same size as the icons, different content
+        builder
+            .add_file("Public/Mod/GUI/Other.dds", &vec![8u8; 4096], settings)
+            .unwrap();
+        builder
+            .add_file("Mods/Mod/meta.lsx", b"<save />", settings)
+            .unwrap();
+        builder.finish().unwrap();
+
+        let info = pak_info(&pak, 2).unwrap();
+        assert_eq!(info.files, 5);
+        assert_eq!(info.size, 4 * 4096 + 8);
+        assert_eq!(info.extensions[0].extension, "dds");
+        assert_eq!(info.extensions[0].files, 4);
+        assert_eq!(info.largest.len(), 2);
+        assert_eq!(info.largest[0].size, 4096);
+
+        let report = find_duplicates(&pak, &CancelToken::new(), &|_| {}).unwrap();
+        assert_eq!(report.files_hashed, 4);
+        assert_eq!(
+            report.groups,
+            vec![DuplicateGroup {
+                size: 4096,
+                paths: vec![
+                    "Public/Mod/GUI/Copy/Icon.dds".to_string(),
+                    "Public/Mod/GUI/Icon.dds".to_string(),
+                    "Public/Mod/GUI/Icon_Old.dds".to_string(),
+                ],
+            }]
+        );
+        assert_eq!(report.wasted(), 2 * 4096);
+    }
+}
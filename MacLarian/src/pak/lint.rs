@@ -0,0 +1,282 @@
+//! PAK entry path checks
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use walkdir::WalkDir;
+
+use super::PakOperations;
+use crate::error::Result;
+use crate::utils::{is_nfc, path_lookup_key, to_nfc};
+
+/// Longest entry path, in characters, that extracts safely on Windows
+pub const MAX_PATH_LENGTH: usize = 240;
+
+/// Characters Windows doesn't allow in file names
+const INVALID_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+/// Device names Windows reserves, with or without an extension
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Kind of problem found in a PAK entry path
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PakIssueKind {
+    /// The path isn't in Unicode NFC form, so it won't match the composed
+    /// paths the game and LSX files refer to.
+    NonNfcPath,
+    /// The path uses `\\` as a separator; the game only splits on `/`.
+    Backslash,
+    /// A path component contains a character Windows can't store.
+    InvalidCharacter,
+    /// A path component is a reserved Windows device name such as `CON`.
+    ReservedName,
+    /// A path component ends in a dot or space, which Windows strips.
+    TrailingDotOrSpace,
+    /// The path is longer than [`MAX_PATH_LENGTH`] characters.
+    PathTooLong,
+    /// Another entry has the same path apart from letter case, so one
+    /// overwrites the other on case-insensitive file systems.
+    CaseCollision,
+}
+
+impl PakIssueKind {
+    /// Whether this issue is only a warning (the PAK still loads)
+    #[must_use]
+    pub fn is_warning(self) -> bool {
+        matches!(self, Self::NonNfcPath | Self::PathTooLong)
+    }
+
+    /// Short human-readable label
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NonNfcPath => "non-NFC path",
+            Self::Backslash => "backslash in path",
+            Self::InvalidCharacter => "invalid character",
+            Self::ReservedName => "reserved name",
+            Self::TrailingDotOrSpace => "trailing dot or space",
+            Self::PathTooLong => "path too long",
+            Self::CaseCollision => "case collision",
+        }
+    }
+}
+
+/// A problem with one PAK entry path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakIssue {
+    /// Internal path of the entry.
+    pub path: String,
+    /// Issue category.
+    pub kind: PakIssueKind,
+    /// Suggested fix.
+    pub suggestion: String,
+}
+
+impl fmt::Display for PakIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.path,
+            self.kind.as_str(),
+            self.suggestion
+        )
+    }
+}
+
+/// Lint the entry paths of a PAK file
+///
+/// # Errors
+/// Returns an error if the PAK cannot be opened or its file table read.
+pub fn lint_pak<P: AsRef<Path>>(path: P) -> Result<Vec<PakIssue>> {
+    Ok(lint_pak_paths(&PakOperations::list(path)?))
+}
+
+/// Lint the paths a PAK created from `dir` would contain
+///
+/// Files are collected the way PAK creation does (skipping symlinks and
+/// `.DS_Store`), but backslashes in file names are kept so they are
+/// reported instead of silently becoming folder separators.
+#[must_use]
+pub fn lint_dir_paths(dir: &Path) -> Vec<PakIssue> {
+    let mut paths: Vec<String> = WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(std::result::Result::ok)
+        .filter(|e| e.file_type().is_file() && e.file_name() != ".DS_Store")
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(dir).ok()?;
+            let parts: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            Some(parts.join("/"))
+        })
+        .collect();
+    paths.sort();
+    lint_pak_paths(&paths)
+}
+
+/// Lint a list of PAK entry paths, returning issues in path order
+///
+/// Case collisions, including paths that only differ in Unicode
+/// normalization, are reported on the later of the two paths.
+#[must_use]
+pub fn lint_pak_paths<S: AsRef<str>>(paths: &[S]) -> Vec<PakIssue> {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for path in paths.iter().map(AsRef::as_ref) {
+        lint_path(path, &mut issues);
+        let key = path_lookup_key(&path.replace('\\', "/"));
+        match seen.get(&key) {
+            Some(first) if *first != path => issues.push(PakIssue {
+                path: path.to_string(),
+                kind: PakIssueKind::CaseCollision,
+                suggestion: format!(
+                    "differs from '{first}' only by case or normalization; rename one of them"
+                ),
+            }),
+            Some(_) => {}
+            None => {
+                seen.insert(key, path);
+            }
+        }
+    }
+    issues
+}
+
+/// Check a single path, pushing one issue per kind found
+fn lint_path(path: &str, issues: &mut Vec<PakIssue>) {
+    let mut push = |kind, suggestion: String| {
+        issues.push(PakIssue {
+            path: path.to_string(),
+            kind,
+            suggestion,
+        });
+    };
+
+    if !is_nfc(path) {
+        push(
+            PakIssueKind::NonNfcPath,
+            format!("repack so the path is stored as '{}'", to_nfc(path)),
+        );
+    }
+    if path.contains('\\') {
+        push(
+            PakIssueKind::Backslash,
+            format!("use '/' as separator: '{}'", path.replace('\\', "/")),
+        );
+    }
+
+    let components = || path.split(['/', '\\']).filter(|c| !c.is_empty());
+    if let Some(c) = components()
+        .flat_map(str::chars)
+        .find(|c| INVALID_CHARS.contains(c) || c.is_control())
+    {
+        let fixed: String = path
+            .chars()
+            .map(|c| {
+                if INVALID_CHARS.contains(&c) || c.is_control() {
+                    '_'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        push(
+            PakIssueKind::InvalidCharacter,
+            format!("remove {c:?}, e.g. '{fixed}'"),
+        );
+    }
+    if let Some(name) = components().find(|c| is_reserved_name(c)) {
+        push(
+            PakIssueKind::ReservedName,
+            format!("rename '{name}'; Windows reserves it as a device name"),
+        );
+    }
+    if let Some(name) = components().find(|c| c.ends_with(['.', ' '])) {
+        push(
+            PakIssueKind::TrailingDotOrSpace,
+            format!("rename '{name}' to '{}'", name.trim_end_matches(['.', ' '])),
+        );
+    }
+
+    let len = path.chars().count();
+    if len > MAX_PATH_LENGTH {
+        push(
+            PakIssueKind::PathTooLong,
+            format!("shorten to {MAX_PATH_LENGTH} characters or fewer (currently {len})"),
+        );
+    }
+}
+
+/// Whether a path component is a reserved device name like `CON` or `com1.txt`
+fn is_reserved_name(component: &str) -> bool {
+    let stem = component.split('.').next().unwrap_or(component).trim_end();
+    RESERVED_NAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_non_nfc_paths() {
+        let issues = lint_pak_paths(&["Public/Mod/Caf\u{e9}.lsx", "Public/Mod/CAFE\u{301}.lsx"]);
+        let kinds: Vec<_> = issues.iter().map(|issue| issue.kind).collect();
+        assert_eq!(
+            kinds,
+            [PakIssueKind::NonNfcPath, PakIssueKind::CaseCollision]
+        );
+        assert!(
+            issues
+                .iter()
+                .all(|i| i.path == "Public/Mod/CAFE\u{301}.lsx")
+        );
+        assert!(issues[0].suggestion.contains("Public/Mod/CAF\u{c9}.lsx"));
+        assert!(issues[1].suggestion.contains("Public/Mod/Caf\u{e9}.lsx"));
+    }
+
+    #[test]
+    fn test_lint_windows_unsafe_paths() {
+        let long = format!("Public/Mod/{}.lsx", "a".repeat(MAX_PATH_LENGTH));
+        let paths = [
+            "Public\\Mod\\Stats.txt",
+            "Public/Mod/con.lsx",
+            "Public/Mod/What?.lsx",
+            "Public/Mod./Data.lsx",
+            long.as_str(),
+            "Public/Mod/Meta.lsx",
+            "Public/mod/meta.lsx",
+            "Public/Mod/Console.lsx",
+            "Public/Mod/COM0.txt",
+            "Public/Mod/lpt0",
+        ];
+        let kinds: Vec<_> = lint_pak_paths(&paths)
+            .into_iter()
+            .map(|issue| (issue.path, issue.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (paths[0].to_string(), PakIssueKind::Backslash),
+                (paths[1].to_string(), PakIssueKind::ReservedName),
+                (paths[2].to_string(), PakIssueKind::InvalidCharacter),
+                (paths[3].to_string(), PakIssueKind::TrailingDotOrSpace),
+                (long.clone(), PakIssueKind::PathTooLong),
+                (paths[6].to_string(), PakIssueKind::CaseCollision),
+                (paths[8].to_string(), PakIssueKind::ReservedName),
+                (paths[9].to_string(), PakIssueKind::ReservedName),
+            ]
+        );
+        assert!(PakIssueKind::PathTooLong.is_warning());
+        assert!(!PakIssueKind::CaseCollision.is_warning());
+    }
+}
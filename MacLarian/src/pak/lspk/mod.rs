@@ -12,6 +12,7 @@ mod writer;
 // Reader/Writer exports
 pub use reader::LspkReader;
 pub(crate) use writer::LspkWriter;
+pub use writer::PakBuilder;
 
 // Public types that users need
 pub use types::{CompressionMethod, FileTableEntry, PakContents, PakFile, PakPhase, PakProgress};
@@ -20,6 +21,7 @@ pub use types::{CompressionMethod, FileTableEntry, PakContents, PakFile, PakPhas
 pub(crate) const MAGIC: [u8; 4] = [b'L', b'S', b'P', b'K'];
 pub(crate) const MIN_VERSION: u32 = 15;
 pub(crate) const MAX_VERSION: u32 = 18;
+pub(crate) const HEADER_SIZE: usize = 40;
 pub(crate) const PATH_LENGTH: usize = 256;
 pub(crate) const TABLE_ENTRY_SIZE: usize = 272;
 
@@ -127,10 +127,16 @@ impl<R: Read + Seek> LspkReader<R> {
         self.reader.read_exact(&mut offset_bytes)?;
         let footer_offset = u64::from_le_bytes(offset_bytes);
 
+        // File list size (u32), flags (u8), priority (u8)
+        let mut rest = [0u8; 6];
+        self.reader.read_exact(&mut rest)?;
+        let priority = rest[5];
+
         self.header = Some(LspkHeader {
             magic,
             version,
             footer_offset,
+            priority,
         });
 
         Ok(self.header.as_ref().expect("header was just set"))
@@ -254,8 +260,85 @@ impl<R: Read + Seek> LspkReader<R> {
     /// Decompress a single file from the PAK
     ///
     /// # Errors
-    /// Returns an error if reading or decompression fails.
+    /// Returns an error if reading or decompression fails. The error names
+    /// the entry, its offset and, when known, the archive part it is in.
     pub fn decompress_file(&mut self, entry: &FileTableEntry) -> Result<Vec<u8>> {
+        self.read_entry_data(entry).map_err(|e| {
+            let e = e
+                .at_offset(entry.offset)
+                .with_pak_path(entry.path.to_string_lossy());
+            match self.get_part_path(entry.archive_part) {
+                Some(part_path) => e.with_path(part_path),
+                None => e,
+            }
+        })
+    }
+
+    /// Read the first `len` bytes of a file, decompressing only what's needed
+    ///
+    /// Stored and Zlib entries are read only as far as needed, and LZ4 blocks
+    /// are decoded until `len` bytes have been produced. Returns the whole
+    /// file if it is shorter than `len`.
+    ///
+    /// Treat `len` as a minimum: callers get at least `len` bytes (or the
+    /// whole file), but decoders that work in blocks may return more.
+    ///
+    /// # Errors
+    /// Returns an error if reading or decompression fails, like
+    /// [`decompress_file`](Self::decompress_file).
+    pub fn read_file_prefix(&mut self, entry: &FileTableEntry, len: usize) -> Result<Vec<u8>> {
+        self.read_entry_prefix(entry, len).map_err(|e| {
+            let e = e
+                .at_offset(entry.offset)
+                .with_pak_path(entry.path.to_string_lossy());
+            match self.get_part_path(entry.archive_part) {
+                Some(part_path) => e.with_path(part_path),
+                None => e,
+            }
+        })
+    }
+
+    fn read_entry_prefix(&mut self, entry: &FileTableEntry, len: usize) -> Result<Vec<u8>> {
+        let reader = self.get_part_reader(entry.archive_part)?;
+        reader.seek(SeekFrom::Start(entry.offset))?;
+        let data = reader.take(u64::from(entry.size_compressed));
+
+        let mut prefix = Vec::with_capacity(len);
+        match entry.compression {
+            CompressionMethod::Zlib if entry.size_decompressed > 0 => {
+                flate2::read::ZlibDecoder::new(data)
+                    .take(len as u64)
+                    .read_to_end(&mut prefix)
+                    .map_err(|e| {
+                        Error::DecompressionError(format!(
+                            "Failed to decompress Zlib data for {}: {e}",
+                            entry.path.display()
+                        ))
+                    })?;
+            }
+            CompressionMethod::Lz4 if entry.size_decompressed > 0 => {
+                // Literals are stored as-is and matches cost fewer bytes than
+                // they produce, so twice `len` is enough input for `len` bytes
+                let mut compressed = Vec::new();
+                data.take(2 * len as u64 + 32)
+                    .read_to_end(&mut compressed)?;
+                prefix = lz4_block_prefix(&compressed, len);
+
+                // Not a plain block (e.g. an LZ4 frame): decode it all instead
+                let expected = len.min(entry.size_decompressed as usize);
+                if prefix.len() < expected {
+                    prefix = self.read_entry_data(entry)?;
+                    prefix.truncate(len);
+                }
+            }
+            _ => {
+                data.take(len as u64).read_to_end(&mut prefix)?;
+            }
+        }
+        Ok(prefix)
+    }
+
+    fn read_entry_data(&mut self, entry: &FileTableEntry) -> Result<Vec<u8>> {
         // Get the appropriate reader for this archive part
         let reader = self.get_part_reader(entry.archive_part)?;
 
@@ -360,6 +443,8 @@ impl<R: Read + Seek> LspkReader<R> {
             current: 0,
             total: 1,
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         });
         self.read_header()?;
 
@@ -372,6 +457,8 @@ impl<R: Read + Seek> LspkReader<R> {
             current: 0,
             total: 1,
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         });
         self.read_file_table()?;
 
@@ -394,6 +481,8 @@ impl<R: Read + Seek> LspkReader<R> {
                 current: i + 1,
                 total: total_files,
                 current_file: Some(file_name),
+                bytes_done: 0,
+                bytes_total: 0,
             });
 
             match self.decompress_file(entry) {
@@ -415,11 +504,25 @@ impl<R: Read + Seek> LspkReader<R> {
             current: total_files,
             total: total_files,
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         });
 
         Ok(contents)
     }
 
+    /// Load priority stored in the header
+    ///
+    /// # Errors
+    /// Returns an error if the header cannot be read.
+    pub fn priority(&mut self) -> Result<u8> {
+        if self.header.is_none() {
+            self.read_header()?;
+        }
+        let header = self.header.as_ref().ok_or(Error::PakHeaderNotRead)?;
+        Ok(header.priority)
+    }
+
     /// List files in the PAK without decompressing them
     ///
     /// # Errors
@@ -438,3 +541,104 @@ impl<R: Read + Seek> LspkReader<R> {
         Ok(self.file_table.clone())
     }
 }
+
+/// Decode the start of an LZ4 block, stopping once `len` bytes are produced
+///
+/// Stops early, returning what was decoded, if the input ends or a match
+/// points before the start of the output.
+fn lz4_block_prefix(input: &[u8], len: usize) -> Vec<u8> {
+    // Read a length that continues in 255-valued bytes after a 15 nibble
+    fn length(input: &[u8], pos: &mut usize, nibble: u8) -> usize {
+        let mut length = usize::from(nibble);
+        if nibble == 15 {
+            while let Some(&byte) = input.get(*pos) {
+                *pos += 1;
+                length += usize::from(byte);
+                if byte != 255 {
+                    break;
+                }
+            }
+        }
+        length
+    }
+
+    let mut out = Vec::with_capacity(len);
+    let mut pos = 0;
+    while out.len() < len && pos < input.len() {
+        let token = input[pos];
+        pos += 1;
+
+        let literals = length(input, &mut pos, token >> 4);
+        let end = input.len().min(pos + literals);
+        out.extend_from_slice(&input[pos..end]);
+        pos = end;
+        if out.len() >= len || pos + 2 > input.len() {
+            break;
+        }
+
+        let offset = usize::from(u16::from_le_bytes([input[pos], input[pos + 1]]));
+        pos += 2;
+        if offset == 0 || offset > out.len() {
+            break;
+        }
+        let match_len = length(input, &mut pos, token & 0x0F) + 4;
+        let start = out.len() - offset;
+        // Byte by byte: the match may overlap the bytes it produces
+        for i in 0..match_len.min(len - out.len()) {
+            out.push(out[start + i]);
+        }
+    }
+    out.truncate(len);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::pak::PakBuilder;
+
+    #[test]
+    fn test_read_file_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Prefix.pak");
+        let data: Vec<u8> = b"LSOF"
+            .iter()
+            .copied()
+            .chain((0..4000u32).map(|i| (i % 7) as u8))
+            .collect();
+
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        builder
+            .add_file("lz4.lsf", &data, CompressionSettings::lz4())
+            .unwrap();
+        builder
+            .add_file("zlib.lsf", &data, CompressionSettings::zlib(6))
+            .unwrap();
+        builder
+            .add_file("none.lsf", &data, CompressionSettings::none())
+            .unwrap();
+        builder
+            .add_file("short.txt", b"abc", CompressionSettings::lz4())
+            .unwrap();
+        builder.finish().unwrap();
+
+        let mut reader = LspkReader::with_path(File::open(&pak).unwrap(), &pak);
+        let entries = reader.list_files().unwrap();
+        for entry in &entries {
+            let full = reader.decompress_file(entry).unwrap();
+            for len in [4, 16, 100, 5000] {
+                let prefix = reader.read_file_prefix(entry, len).unwrap();
+                assert_eq!(
+                    prefix,
+                    full[..len.min(full.len())],
+                    "{}",
+                    entry.path.display()
+                );
+            }
+        }
+        // The lz4 prefix decoder also handles a raw block on its own
+        let block = lz4_flex::block::compress(&data);
+        assert_eq!(lz4_block_prefix(&block, 64), data[..64]);
+    }
+}
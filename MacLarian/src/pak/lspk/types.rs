@@ -4,6 +4,8 @@
 
 use std::path::PathBuf;
 
+use crate::compression::{CompressionAlgorithm, CompressionSettings};
+
 /// Compression method used for a file in the PAK
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -47,6 +49,30 @@ impl CompressionMethod {
             CompressionMethod::Lz4 => 2,
         }
     }
+
+    /// Settings that compress with this method at its default level
+    #[must_use]
+    pub fn settings(self) -> CompressionSettings {
+        match self {
+            CompressionMethod::None => CompressionSettings::none(),
+            CompressionMethod::Zlib => CompressionSettings {
+                method: CompressionAlgorithm::Zlib,
+                ..CompressionSettings::default()
+            },
+            CompressionMethod::Lz4 => CompressionSettings::lz4(),
+        }
+    }
+
+    /// PAK compression method for `settings`, if a PAK can store it
+    #[must_use]
+    pub fn from_settings(settings: &CompressionSettings) -> Option<Self> {
+        match settings.method {
+            CompressionAlgorithm::None => Some(CompressionMethod::None),
+            CompressionAlgorithm::Zlib => Some(CompressionMethod::Zlib),
+            CompressionAlgorithm::Lz4 => Some(CompressionMethod::Lz4),
+            CompressionAlgorithm::FastLz => None,
+        }
+    }
 }
 
 /// Header of an LSPK PAK file
@@ -61,6 +87,8 @@ pub(crate) struct LspkHeader {
     pub version: u32,
     /// Offset to the footer from the start of the file
     pub footer_offset: u64,
+    /// Load priority; PAKs with a higher priority override lower ones
+    pub priority: u8,
 }
 
 /// Footer/metadata of an LSPK PAK file
@@ -151,6 +179,10 @@ pub struct PakProgress {
     pub total: usize,
     /// Current file being processed (if applicable)
     pub current_file: Option<String>,
+    /// Bytes processed so far in this phase, when the phase tracks them
+    pub bytes_done: u64,
+    /// Bytes to process in this phase (0 when not tracked)
+    pub bytes_total: u64,
 }
 
 impl PakProgress {
@@ -162,6 +194,8 @@ impl PakProgress {
             current,
             total,
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         }
     }
 
@@ -178,9 +212,19 @@ impl PakProgress {
             current,
             total,
             current_file: Some(file.into()),
+            bytes_done: 0,
+            bytes_total: 0,
         }
     }
 
+    /// Add byte counts to a progress update
+    #[must_use]
+    pub fn with_bytes(mut self, done: u64, total: u64) -> Self {
+        self.bytes_done = done;
+        self.bytes_total = total;
+        self
+    }
+
     /// Get the progress percentage (0.0 - 1.0)
     #[must_use]
     pub fn percentage(&self) -> f32 {
@@ -210,10 +254,16 @@ pub enum PakPhase {
     WritingTable,
     /// Writing files to disk (during extraction)
     WritingFiles,
+    /// Converting extracted GR2 models to GLB
+    ConvertingModels,
     /// Extracting associated textures (DDS, virtual textures)
     ExtractingTextures,
     /// Converting textures to PNG
     ConvertingTextures,
+    /// Searching file contents
+    SearchingContent,
+    /// Hashing file contents to find duplicates
+    HashingContent,
     /// Operation complete
     Complete,
 }
@@ -230,8 +280,11 @@ impl PakPhase {
             Self::CompressingFiles => "Compressing files",
             Self::WritingTable => "Writing file table",
             Self::WritingFiles => "Writing files",
+            Self::ConvertingModels => "Converting models",
             Self::ExtractingTextures => "Extracting textures",
             Self::ConvertingTextures => "Converting textures",
+            Self::SearchingContent => "Searching content",
+            Self::HashingContent => "Hashing content",
             Self::Complete => "Complete",
         }
     }
@@ -1,23 +1,34 @@
 //! LSPK PAK file writer with progress callbacks
 //!
 //! Uses parallel compression for improved performance on multi-core systems.
+//! Progress is reported per file, and every [`PROGRESS_CHUNK`] bytes within
+//! large files, so a bar keeps moving through a single multi-gigabyte texture.
 //!
+//! # Header
 //!
+//! Version 18 PAKs start with a 40-byte header: magic, version, file list
+//! offset (u64) and size (u32), flags (u8), load priority (u8), an MD5 of the
+//! contents (16 bytes) and the number of parts (u16). The header is written
+//! as a placeholder and filled in once the file list is written. The MD5 is
+//! left zeroed, as it isn't needed to load mods.
 
 #![allow(clippy::cast_possible_truncation, clippy::too_many_lines)]
 
-use std::collections::VecDeque;
-use std::fs::OpenOptions;
-use std::io::{Seek, SeekFrom, Write};
+use std::collections::{HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
 use rayon::prelude::*;
 
 use super::{
-    CompressionMethod, MAGIC, MAX_VERSION, PATH_LENGTH, PakPhase, PakProgress, TABLE_ENTRY_SIZE,
+    CompressionMethod, HEADER_SIZE, MAGIC, MAX_VERSION, PATH_LENGTH, PakPhase, PakProgress,
+    TABLE_ENTRY_SIZE,
 };
-use crate::error::{Error, Result};
+use crate::compression::{CompressionSettings, Lz4Mode, compress_with};
+use crate::error::{Error, Result, ResultExt};
+use crate::utils::{CancelToken, path_lookup_key, to_nfc};
 
 /// Progress callback type for write operations.
 ///
@@ -25,12 +36,17 @@ use crate::error::{Error, Result};
 /// Must be `Sync + Send` to support parallel compression.
 pub type WriteProgressCallback<'a> = &'a (dyn Fn(&PakProgress) + Sync + Send);
 
+/// Bytes read or written between progress reports within one file
+const PROGRESS_CHUNK: usize = 8 * 1024 * 1024;
+
 /// File to be written to the PAK
 struct FileEntry {
     /// Path relative to the root
     relative_path: PathBuf,
-    /// File contents
-    data: Vec<u8>,
+    /// File on disk, read when it's compressed
+    source: PathBuf,
+    /// Size on disk when collected
+    size: u64,
 }
 
 /// Details about a written file entry
@@ -55,8 +71,12 @@ pub struct LspkWriter {
     files: Vec<FileEntry>,
     /// PAK version to write
     version: u32,
-    /// Compression method to use
-    compression: CompressionMethod,
+    /// Load priority stored in the header
+    priority: u8,
+    /// Compression method and level to use
+    settings: CompressionSettings,
+    /// Bytes between progress reports within one file
+    progress_chunk: usize,
 }
 
 impl LspkWriter {
@@ -65,25 +85,54 @@ impl LspkWriter {
     /// # Errors
     /// Returns an error if the directory cannot be read.
     pub fn new(root_path: impl Into<PathBuf>) -> Result<Self> {
+        Self::new_excluding(root_path, &[])
+    }
+
+    /// Create a new writer for the given directory, leaving out excluded files
+    ///
+    /// A file or folder is skipped when a pattern matches its path relative
+    /// to the root (with `/` separators) or its name, so `*.psd` and `.git`
+    /// both work at any depth.
+    ///
+    /// # Errors
+    /// Returns an error if the directory cannot be read.
+    pub fn new_excluding(root_path: impl Into<PathBuf>, exclude: &[glob::Pattern]) -> Result<Self> {
         let root_path = root_path.into();
-        let files = Self::collect_files(&root_path)?;
+        let files = Self::collect_files(&root_path, exclude)?;
 
         Ok(Self {
             files,
-            version: MAX_VERSION,                // Use latest supported version
-            compression: CompressionMethod::Lz4, // Default to LZ4
+            version: MAX_VERSION, // Use latest supported version
+            priority: 0,
+            settings: CompressionMethod::Lz4.settings(), // Default to fast LZ4
+            progress_chunk: PROGRESS_CHUNK,
         })
     }
 
-    /// Set the compression method to use
+    /// Set the compression method and level to use
+    ///
+    /// LZ4 entries are always written as blocks; settings PAKs can't store
+    /// (such as `FastLZ`) make [`write`](Self::write) fail.
     #[must_use]
-    pub fn with_compression(mut self, compression: CompressionMethod) -> Self {
-        self.compression = compression;
+    pub fn with_settings(mut self, settings: CompressionSettings) -> Self {
+        self.settings = settings.with_lz4_mode(Lz4Mode::Block);
         self
     }
 
+    /// Set the load priority stored in the header (0 by default)
+    #[must_use]
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Number of files that will be written
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
     /// Collect all files from a directory recursively
-    fn collect_files(root: &Path) -> Result<Vec<FileEntry>> {
+    fn collect_files(root: &Path, exclude: &[glob::Pattern]) -> Result<Vec<FileEntry>> {
         let mut files = Vec::new();
         let mut dirs_to_check = VecDeque::new();
         dirs_to_check.push_back(root.to_path_buf());
@@ -106,19 +155,28 @@ impl LspkWriter {
                     continue;
                 }
 
+                let relative_path = path
+                    .strip_prefix(root)
+                    .map_err(|_| Error::InvalidPath(path.display().to_string()))?;
+                if is_excluded(relative_path, exclude) {
+                    continue;
+                }
+
                 if file_type.is_dir() {
                     dirs_to_check.push_back(path);
                 } else {
-                    let relative_path = path
-                        .strip_prefix(root)
-                        .map_err(|_| Error::InvalidPath(path.display().to_string()))?
-                        .to_path_buf();
+                    // Store NFC paths; macOS returns decomposed file names
+                    let relative_path = relative_path.to_str().map_or_else(
+                        || relative_path.to_path_buf(),
+                        |p| to_nfc(p).as_ref().into(),
+                    );
 
-                    let data = std::fs::read(&path)?;
+                    let size = entry.metadata()?.len();
 
                     files.push(FileEntry {
                         relative_path,
-                        data,
+                        source: path,
+                        size,
                     });
                 }
             }
@@ -148,6 +206,26 @@ impl LspkWriter {
         self,
         output_path: impl AsRef<Path>,
         progress: WriteProgressCallback,
+    ) -> Result<()> {
+        self.write_cancellable(output_path, &CancelToken::new(), progress)
+    }
+
+    /// Write the PAK file, stopping early once `cancel` is triggered
+    ///
+    /// Progress is reported when each file starts compressing and when it's
+    /// written, and every [`PROGRESS_CHUNK`] bytes within larger files, with
+    /// [`PakProgress::bytes_done`] counting source bytes while compressing
+    /// and compressed bytes while writing. If writing fails or is cancelled,
+    /// the partly written PAK is removed.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if `cancel` was triggered, or an error if
+    /// a file cannot be read or compressed or the PAK cannot be written.
+    pub fn write_cancellable(
+        self,
+        output_path: impl AsRef<Path>,
+        cancel: &CancelToken,
+        progress: WriteProgressCallback,
     ) -> Result<()> {
         let output_path = output_path.as_ref();
 
@@ -156,184 +234,694 @@ impl LspkWriter {
             std::fs::create_dir_all(parent)?;
         }
 
+        let compression = pak_compression(&self.settings)?;
+        let compressed_entries = self.compress_all(cancel, progress)?;
+
+        let result = write_compressed(
+            output_path,
+            self.version,
+            self.priority,
+            compressed_entries,
+            compression,
+            self.progress_chunk,
+            cancel,
+            progress,
+        );
+        if result.is_err() {
+            let _ = std::fs::remove_file(output_path);
+        }
+        result
+    }
+
+    /// Read and compress every file in parallel
+    fn compress_all(
+        &self,
+        cancel: &CancelToken,
+        progress: WriteProgressCallback,
+    ) -> Result<Vec<CompressedEntry>> {
         let total_files = self.files.len();
+        let bytes_total: u64 = self.files.iter().map(|f| f.size).sum();
         let processed = AtomicUsize::new(0);
+        let bytes_done = AtomicU64::new(0);
 
         // Phase 1: Compress all files in parallel
-        progress(&PakProgress {
-            phase: PakPhase::CompressingFiles,
-            current: 0,
-            total: total_files,
-            current_file: None,
-        });
+        progress(
+            &PakProgress::new(PakPhase::CompressingFiles, 0, total_files)
+                .with_bytes(0, bytes_total),
+        );
 
-        let compression_results: Vec<std::result::Result<CompressedEntry, String>> = self
-            .files
+        self.files
             .par_iter()
             .map(|file| {
-                let file_name = file.relative_path.file_name().map_or_else(
-                    || file.relative_path.to_string_lossy().to_string(),
-                    |n| n.to_string_lossy().to_string(),
-                );
+                cancel.checkpoint()?;
+                let name = file.relative_path.to_string_lossy().replace('\\', "/");
 
                 // Update progress (atomic)
                 let current = processed.fetch_add(1, Ordering::SeqCst) + 1;
-                progress(&PakProgress {
-                    phase: PakPhase::CompressingFiles,
-                    current,
-                    total: total_files,
-                    current_file: Some(file_name.clone()),
-                });
-
-                let size_decompressed = file.data.len();
-
-                // Validate size fits in u32
-                let size_decompressed: u32 = size_decompressed.try_into().map_err(|_| {
-                    format!("File {file_name} is too large: {size_decompressed} bytes")
-                })?;
-
-                let compressed_data = match self.compression {
-                    CompressionMethod::None => file.data.clone(),
-                    CompressionMethod::Lz4 => lz4_flex::block::compress(&file.data),
-                    CompressionMethod::Zlib => {
-                        use flate2::Compression;
-                        use flate2::write::ZlibEncoder;
-                        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
-                        encoder
-                            .write_all(&file.data)
-                            .map_err(|e| format!("Failed to compress {file_name}: {e}"))?;
-                        encoder.finish().map_err(|e| {
-                            format!("Failed to finish compression for {file_name}: {e}")
-                        })?
-                    }
+                let report = |done: u64| {
+                    progress(
+                        &PakProgress::with_file(
+                            PakPhase::CompressingFiles,
+                            current,
+                            total_files,
+                            name.as_str(),
+                        )
+                        .with_bytes(done, bytes_total),
+                    );
                 };
+                report(bytes_done.load(Ordering::SeqCst));
 
-                Ok(CompressedEntry {
-                    path: file.relative_path.clone(),
-                    compressed_data,
-                    size_decompressed,
-                })
+                let data = read_chunked(&file.source, file.size, self.progress_chunk, |n| {
+                    let done = bytes_done.fetch_add(n as u64, Ordering::SeqCst) + n as u64;
+                    if file.size > self.progress_chunk as u64 {
+                        report(done);
+                    }
+                    cancel.checkpoint()
+                })?;
+                compress_entry(file.relative_path.clone(), &data, &self.settings)
             })
-            .collect();
+            .collect()
+    }
+}
 
-        // Check for compression errors
-        let mut compressed_entries = Vec::with_capacity(total_files);
-        for result in compression_results {
-            match result {
-                Ok(entry) => compressed_entries.push(entry),
-                Err(e) => return Err(Error::ConversionError(e)),
-            }
+/// Write compressed entries and the file table to a new PAK
+fn write_compressed(
+    output_path: &Path,
+    version: u32,
+    priority: u8,
+    compressed_entries: Vec<CompressedEntry>,
+    compression: CompressionMethod,
+    progress_chunk: usize,
+    cancel: &CancelToken,
+    progress: WriteProgressCallback,
+) -> Result<()> {
+    let total_files = compressed_entries.len();
+    let bytes_total: u64 = compressed_entries
+        .iter()
+        .map(|e| e.compressed_data.len() as u64)
+        .sum();
+    let mut bytes_done = 0;
+
+    // Phase 2: Write compressed data sequentially (to maintain correct offsets)
+    progress(&PakProgress::new(PakPhase::WritingFiles, 0, total_files).with_bytes(0, bytes_total));
+
+    let mut output = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(output_path)?;
+
+    write_header(&mut output, version)?;
+
+    let mut written_entries = Vec::with_capacity(compressed_entries.len());
+    for (i, entry) in compressed_entries.into_iter().enumerate() {
+        cancel.checkpoint()?;
+        let name = entry.path.to_string_lossy().replace('\\', "/");
+        let is_large = entry.compressed_data.len() > progress_chunk;
+        let report = |done: u64| {
+            progress(
+                &PakProgress::with_file(PakPhase::WritingFiles, i + 1, total_files, name.as_str())
+                    .with_bytes(done, bytes_total),
+            );
+        };
+        report(bytes_done);
+
+        written_entries.push(write_entry(
+            &mut output,
+            entry,
+            compression,
+            progress_chunk,
+            |n| {
+                bytes_done += n as u64;
+                if is_large {
+                    report(bytes_done);
+                }
+                cancel.checkpoint()
+            },
+        )?);
+    }
+
+    // Build file table
+    progress(&PakProgress::new(
+        PakPhase::WritingTable,
+        total_files,
+        total_files,
+    ));
+
+    write_file_table(&mut output, &written_entries, priority)?;
+
+    progress(&PakProgress::new(
+        PakPhase::Complete,
+        total_files,
+        total_files,
+    ));
+
+    Ok(())
+}
+
+/// Read a file in `chunk`-sized pieces, calling `on_chunk` with each
+/// piece's length
+fn read_chunked(
+    path: &Path,
+    size: u64,
+    chunk: usize,
+    mut on_chunk: impl FnMut(usize) -> Result<()>,
+) -> Result<Vec<u8>> {
+    let mut file = File::open(path).with_path(path)?;
+    let mut data = Vec::with_capacity(usize::try_from(size).unwrap_or(0));
+    loop {
+        let start = data.len();
+        let read = (&mut file)
+            .take(chunk as u64)
+            .read_to_end(&mut data)
+            .with_path(path)?;
+        if read == 0 {
+            break;
         }
+        on_chunk(data.len() - start)?;
+    }
+    Ok(data)
+}
 
-        // Phase 2: Write compressed data sequentially (to maintain correct offsets)
-        progress(&PakProgress {
-            phase: PakPhase::WritingFiles,
-            current: total_files,
-            total: total_files,
-            current_file: None,
-        });
+/// PAK writer for files produced in memory
+///
+/// Each file is compressed and written as soon as it's added; only the file
+/// table is kept until [`finish`](Self::finish), so memory use stays bounded
+/// by the largest single file rather than the whole archive. Entry paths are
+/// stored with `/` separators in Unicode NFC form, like [`PakOperations::create`].
+///
+/// ```no_run
+/// use maclarian::compression::CompressionSettings;
+/// use maclarian::pak::PakBuilder;
+///
+/// let mut pak = PakBuilder::write_to("MyMod.pak")?;
+/// pak.add_file("Mods/MyMod/meta.lsx", b"<save/>", CompressionSettings::lz4())?;
+/// pak.add_file_from_path("Public/MyMod/Assets/icon.dds", "icon.dds", CompressionSettings::none())?;
+/// pak.finish()?;
+/// # Ok::<(), maclarian::error::Error>(())
+/// ```
+///
+/// [`PakOperations::create`]: crate::pak::PakOperations::create
+pub struct PakBuilder<W: Write + Seek> {
+    output: W,
+    entries: Vec<WrittenEntry>,
+    /// Lookup keys of the paths added so far
+    paths: HashSet<String>,
+    /// Load priority stored in the header
+    priority: u8,
+}
 
-        let mut output = OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(output_path)?;
-
-        // Write header
-        output.write_all(&MAGIC)?;
-        output.write_all(&self.version.to_le_bytes())?;
-        // Placeholder for footer offset (will be filled in later)
-        output.write_all(&0u64.to_le_bytes())?;
-
-        let mut written_entries = Vec::with_capacity(compressed_entries.len());
-
-        for entry in compressed_entries {
-            let size_compressed: u32 = entry.compressed_data.len().try_into().map_err(|_| {
-                let path_display = entry.path.display();
-                let len = entry.compressed_data.len();
-                Error::ConversionError(format!(
-                    "Compressed file {path_display} is too large: {len} bytes"
-                ))
-            })?;
-
-            let offset = output.stream_position()?;
-            output.write_all(&entry.compressed_data)?;
-
-            written_entries.push(WrittenEntry {
-                path: entry.path,
-                offset,
-                size_compressed,
-                size_decompressed: entry.size_decompressed,
-                compression: self.compression,
-            });
+impl PakBuilder<BufWriter<File>> {
+    /// Start a PAK file at `path`, creating parent directories as needed
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be created.
+    pub fn write_to(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_path(parent)?;
         }
+        let file = File::create(path).with_path(path)?;
+        Self::write_to_writer(BufWriter::new(file))
+    }
+}
 
-        // Record footer position
-        let footer_offset = output.stream_position()?;
-
-        // Write footer: number of files
-        let num_files: u32 = written_entries.len().try_into().map_err(|_| {
-            let count = written_entries.len();
-            Error::ConversionError(format!("Too many files: {count}"))
-        })?;
-        output.write_all(&num_files.to_le_bytes())?;
-
-        // Build file table
-        progress(&PakProgress {
-            phase: PakPhase::WritingTable,
-            current: total_files,
-            total: total_files,
-            current_file: None,
-        });
+impl<W: Write + Seek> PakBuilder<W> {
+    /// Start a PAK in `output`, which should be empty
+    ///
+    /// # Errors
+    /// Returns an error if the header cannot be written.
+    pub fn write_to_writer(mut output: W) -> Result<Self> {
+        write_header(&mut output, MAX_VERSION)?;
+        Ok(Self {
+            output,
+            entries: Vec::new(),
+            paths: HashSet::new(),
+            priority: 0,
+        })
+    }
 
-        let mut table_data = Vec::with_capacity(TABLE_ENTRY_SIZE * written_entries.len());
+    /// Set the load priority stored in the header (0 by default)
+    ///
+    /// The header is completed by [`finish`](Self::finish), so this can be
+    /// called at any point before it.
+    #[must_use]
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
 
-        for entry in &written_entries {
-            let entry_start = table_data.len();
+    /// Compress `data` and write it as `internal_path`
+    ///
+    /// LZ4 data is always stored as blocks. Backslashes in `internal_path`
+    /// are turned into `/`.
+    ///
+    /// # Errors
+    /// Returns an error if the path is empty, too long or already in the PAK,
+    /// if `settings` can't be stored in PAKs (such as `FastLZ`), or if writing
+    /// fails.
+    pub fn add_file(
+        &mut self,
+        internal_path: &str,
+        data: &[u8],
+        settings: CompressionSettings,
+    ) -> Result<()> {
+        let path = entry_path(internal_path)?;
+        if !self.paths.insert(path_lookup_key(&path)) {
+            return Err(Error::InvalidPath(format!("duplicate PAK entry: {path}")));
+        }
+
+        let settings = settings.with_lz4_mode(Lz4Mode::Block);
+        let compression = pak_compression(&settings)?;
+        let entry = compress_entry(PathBuf::from(&path), data, &settings)?;
+        let written = write_entry(&mut self.output, entry, compression, usize::MAX, |_| Ok(()))?;
+        self.entries.push(written);
+        Ok(())
+    }
+
+    /// Read `source` and write it as `internal_path`
+    ///
+    /// # Errors
+    /// Returns an error if `source` cannot be read, or as for
+    /// [`add_file`](Self::add_file).
+    pub fn add_file_from_path(
+        &mut self,
+        internal_path: &str,
+        source: impl AsRef<Path>,
+        settings: CompressionSettings,
+    ) -> Result<()> {
+        let source = source.as_ref();
+        let data = std::fs::read(source).with_path(source)?;
+        self.add_file(internal_path, &data, settings)
+    }
 
-            // Path (256 bytes, null-padded)
-            let path_bytes = entry.path.as_os_str().as_encoded_bytes();
-            table_data.extend_from_slice(path_bytes);
-            table_data.resize(entry_start + PATH_LENGTH, 0);
+    /// Number of files added so far
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
 
-            // Offset: lower 6 bytes (48 bits) of the 64-bit offset
-            let offset_bytes = entry.offset.to_le_bytes();
-            table_data.extend_from_slice(&offset_bytes[0..6]);
+    /// Whether no files have been added
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 
-            // Archive part (1 byte) - always 0 for single-file PAKs
-            table_data.push(0);
+    /// Write the file table and return the output
+    ///
+    /// # Errors
+    /// Returns an error if the table cannot be written.
+    pub fn finish(mut self) -> Result<W> {
+        write_file_table(&mut self.output, &self.entries, self.priority)?;
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
 
-            // Flags (1 byte) - compression method in lower nibble
-            table_data.push(entry.compression.to_flags());
+/// Internal path as stored in the file table: `/`-separated NFC
+fn entry_path(internal_path: &str) -> Result<String> {
+    let path = to_nfc(&internal_path.replace('\\', "/"))
+        .trim_start_matches('/')
+        .to_string();
+    if path.is_empty() || path.len() >= PATH_LENGTH {
+        return Err(Error::InvalidPath(format!(
+            "PAK entry path must be 1-{} bytes: '{internal_path}'",
+            PATH_LENGTH - 1
+        )));
+    }
+    Ok(path)
+}
 
-            // Compressed size (4 bytes)
-            table_data.extend_from_slice(&entry.size_compressed.to_le_bytes());
+/// Whether a path relative to the source root matches an exclude pattern
+fn is_excluded(relative_path: &Path, exclude: &[glob::Pattern]) -> bool {
+    if exclude.is_empty() {
+        return false;
+    }
+    let path = relative_path.to_string_lossy().replace('\\', "/");
+    let name = relative_path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    exclude
+        .iter()
+        .any(|pattern| pattern.matches(&path) || pattern.matches(&name))
+}
 
-            // Decompressed size (4 bytes)
-            table_data.extend_from_slice(&entry.size_decompressed.to_le_bytes());
-        }
+/// Compression method flag for `settings`
+fn pak_compression(settings: &CompressionSettings) -> Result<CompressionMethod> {
+    CompressionMethod::from_settings(settings)
+        .ok_or_else(|| Error::CompressionError(format!("{settings} can't be used in PAK files")))
+}
 
-        // Compress and write file table
-        let compressed_table = lz4_flex::block::compress(&table_data);
-        let table_size: u32 = compressed_table.len().try_into().map_err(|_| {
-            let len = compressed_table.len();
-            Error::ConversionError(format!("File table too large: {len} bytes"))
-        })?;
-
-        output.write_all(&table_size.to_le_bytes())?;
-        output.write_all(&compressed_table)?;
-
-        // Go back and write the footer offset
-        output.seek(SeekFrom::Start(8))?;
-        output.write_all(&footer_offset.to_le_bytes())?;
-
-        progress(&PakProgress {
-            phase: PakPhase::Complete,
-            current: total_files,
-            total: total_files,
-            current_file: None,
+/// Compress one file's data
+fn compress_entry(
+    path: PathBuf,
+    data: &[u8],
+    settings: &CompressionSettings,
+) -> Result<CompressedEntry> {
+    let size_decompressed: u32 = data.len().try_into().map_err(|_| {
+        Error::ConversionError(format!(
+            "File {} is too large: {} bytes",
+            path.display(),
+            data.len()
+        ))
+    })?;
+
+    let compressed_data = compress_with(data, settings).map_err(|e| {
+        Error::ConversionError(format!("Failed to compress {}: {e}", path.display()))
+    })?;
+
+    Ok(CompressedEntry {
+        path,
+        compressed_data,
+        size_decompressed,
+    })
+}
+
+/// Write the LSPK header with placeholders for the fields after the version
+fn write_header<W: Write>(output: &mut W, version: u32) -> Result<()> {
+    output.write_all(&MAGIC)?;
+    output.write_all(&version.to_le_bytes())?;
+    // File list offset, size, flags, priority, MD5 and part count
+    // (filled in by `write_file_table`)
+    output.write_all(&[0u8; HEADER_SIZE - 8])?;
+    Ok(())
+}
+
+/// Write a compressed entry's data at the current position
+///
+/// The data is written in `chunk`-sized pieces, calling `on_chunk` with each
+/// piece's length.
+fn write_entry<W: Write + Seek>(
+    output: &mut W,
+    entry: CompressedEntry,
+    compression: CompressionMethod,
+    chunk: usize,
+    mut on_chunk: impl FnMut(usize) -> Result<()>,
+) -> Result<WrittenEntry> {
+    let size_compressed: u32 = entry.compressed_data.len().try_into().map_err(|_| {
+        let path_display = entry.path.display();
+        let len = entry.compressed_data.len();
+        Error::ConversionError(format!(
+            "Compressed file {path_display} is too large: {len} bytes"
+        ))
+    })?;
+
+    let offset = output.stream_position()?;
+    for piece in entry.compressed_data.chunks(chunk.max(1)) {
+        output.write_all(piece)?;
+        on_chunk(piece.len())?;
+    }
+
+    Ok(WrittenEntry {
+        path: entry.path,
+        offset,
+        size_compressed,
+        size_decompressed: entry.size_decompressed,
+        compression,
+    })
+}
+
+/// Write the footer and file table, then fill in the rest of the header
+fn write_file_table<W: Write + Seek>(
+    output: &mut W,
+    written_entries: &[WrittenEntry],
+    priority: u8,
+) -> Result<()> {
+    // Record footer position
+    let footer_offset = output.stream_position()?;
+
+    // Write footer: number of files
+    let num_files: u32 = written_entries.len().try_into().map_err(|_| {
+        let count = written_entries.len();
+        Error::ConversionError(format!("Too many files: {count}"))
+    })?;
+    output.write_all(&num_files.to_le_bytes())?;
+
+    let mut table_data = Vec::with_capacity(TABLE_ENTRY_SIZE * written_entries.len());
+
+    for entry in written_entries {
+        let entry_start = table_data.len();
+
+        // Path (256 bytes, null-padded)
+        let path_bytes = entry.path.as_os_str().as_encoded_bytes();
+        table_data.extend_from_slice(path_bytes);
+        table_data.resize(entry_start + PATH_LENGTH, 0);
+
+        // Offset: lower 6 bytes (48 bits) of the 64-bit offset
+        let offset_bytes = entry.offset.to_le_bytes();
+        table_data.extend_from_slice(&offset_bytes[0..6]);
+
+        // Archive part (1 byte) - always 0 for single-file PAKs
+        table_data.push(0);
+
+        // Flags (1 byte) - compression method in lower nibble
+        table_data.push(entry.compression.to_flags());
+
+        // Compressed size (4 bytes)
+        table_data.extend_from_slice(&entry.size_compressed.to_le_bytes());
+
+        // Decompressed size (4 bytes)
+        table_data.extend_from_slice(&entry.size_decompressed.to_le_bytes());
+    }
+
+    // Compress and write file table
+    let compressed_table = lz4_flex::block::compress(&table_data);
+    let table_size: u32 = compressed_table.len().try_into().map_err(|_| {
+        let len = compressed_table.len();
+        Error::ConversionError(format!("File table too large: {len} bytes"))
+    })?;
+
+    output.write_all(&table_size.to_le_bytes())?;
+    output.write_all(&compressed_table)?;
+    let end = output.stream_position()?;
+    let list_size: u32 = (end - footer_offset).try_into().map_err(|_| {
+        Error::ConversionError(format!(
+            "File list too large: {} bytes",
+            end - footer_offset
+        ))
+    })?;
+
+    // Go back and complete the header
+    output.seek(SeekFrom::Start(8))?;
+    output.write_all(&footer_offset.to_le_bytes())?;
+    output.write_all(&list_size.to_le_bytes())?;
+    output.write_all(&[0, priority])?; // Flags, priority
+    output.write_all(&[0u8; 16])?; // MD5, not computed
+    output.write_all(&1u16.to_le_bytes())?; // Number of parts
+    output.seek(SeekFrom::Start(end))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::LZ4_HC_MAX_LEVEL;
+    use crate::pak::PakOperations;
+
+    #[test]
+    fn test_builder_round_trip() {
+        let base = tempfile::tempdir().unwrap();
+        let pak = base.path().join("Built.pak");
+
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        builder
+            .add_file(
+                "Mods\\Test\\meta.lsx",
+                b"<save/>",
+                CompressionSettings::lz4(),
+            )
+            .unwrap();
+        builder
+            .add_file(
+                "Public/Test/data.txt",
+                &[7u8; 4096],
+                CompressionSettings::zlib(9),
+            )
+            .unwrap();
+        builder
+            .add_file("Public/Test/raw.bin", b"raw", CompressionSettings::none())
+            .unwrap();
+        assert!(
+            builder
+                .add_file("Mods/Test/META.lsx", b"", CompressionSettings::none())
+                .is_err()
+        );
+        assert!(
+            builder
+                .add_file("a.gts", b"", CompressionSettings::fastlz())
+                .is_err()
+        );
+        assert_eq!(builder.len(), 3);
+        builder.finish().unwrap();
+
+        let mut files = PakOperations::list(&pak).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                "Mods/Test/meta.lsx",
+                "Public/Test/data.txt",
+                "Public/Test/raw.bin"
+            ]
+        );
+        assert_eq!(
+            PakOperations::read_file_bytes(&pak, "Public/Test/data.txt").unwrap(),
+            vec![7u8; 4096]
+        );
+        assert_eq!(
+            PakOperations::read_file_bytes(&pak, "Mods/Test/meta.lsx").unwrap(),
+            b"<save/>"
+        );
+    }
+
+    #[test]
+    fn test_write_progress_and_cancel() {
+        use std::sync::Mutex;
+
+        let base = tempfile::tempdir().unwrap();
+        let source = base.path().join("MyMod");
+        std::fs::create_dir_all(source.join("Public/MyMod")).unwrap();
+        std::fs::write(source.join("Public/MyMod/a.txt"), b"small").unwrap();
+        std::fs::write(source.join("Public/MyMod/b.txt"), b"tiny").unwrap();
+        std::fs::write(source.join("Public/MyMod/big.bin"), [1u8; 40]).unwrap();
+        let pak = base.path().join("MyMod.pak");
+        let writer = || {
+            let mut writer = LspkWriter::new(&source)
+                .unwrap()
+                .with_settings(CompressionSettings::none());
+            writer.progress_chunk = 16;
+            writer
+        };
+
+        let events = Mutex::new(Vec::new());
+        writer()
+            .write_with_progress(&pak, &|p| events.lock().unwrap().push(p.clone()))
+            .unwrap();
+        let events = events.into_inner().unwrap();
+        let count = |phase| events.iter().filter(|p| p.phase == phase).count();
+        // Start + one per file + one per chunk of big.bin (16 + 16 + 8)
+        assert_eq!(count(PakPhase::CompressingFiles), 1 + 3 + 3);
+        assert_eq!(count(PakPhase::WritingFiles), 1 + 3 + 3);
+        assert_eq!(count(PakPhase::Complete), 1);
+        let last = events
+            .iter()
+            .rfind(|p| p.phase == PakPhase::WritingFiles)
+            .unwrap();
+        assert_eq!((last.bytes_done, last.bytes_total), (49, 49));
+        assert!(
+            events
+                .iter()
+                .any(|p| p.current_file.as_deref() == Some("Public/MyMod/big.bin"))
+        );
+
+        // Cancelled partway through writing: the partial PAK is removed
+        let cancel = CancelToken::new();
+        let result = writer().write_cancellable(&pak, &cancel, &|p| {
+            if p.phase == PakPhase::WritingFiles && p.current == 2 {
+                cancel.cancel();
+            }
         });
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(!pak.exists());
+
+        // Cancelled while compressing: nothing is written
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let result = writer().write_cancellable(&pak, &cancel, &|_| {});
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(!pak.exists());
+    }
 
-        Ok(())
+    #[test]
+    fn test_lz4_hc_pak_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("HcMod");
+        std::fs::create_dir_all(source.join("Public/HcMod")).unwrap();
+        let text: Vec<u8> = (0..20_000u32)
+            .flat_map(|i| format!("<node id=\"{}\"/>\n", i % 97).into_bytes())
+            .collect();
+        std::fs::write(source.join("Public/HcMod/data.lsx"), &text).unwrap();
+        std::fs::write(source.join("Public/HcMod/small.txt"), b"hi").unwrap();
+        let pak = dir.path().join("HcMod.pak");
+
+        LspkWriter::new(&source)
+            .unwrap()
+            .with_settings(CompressionSettings::lz4_hc(LZ4_HC_MAX_LEVEL))
+            .write(&pak)
+            .unwrap();
+
+        assert!(std::fs::metadata(&pak).unwrap().len() < text.len() as u64);
+        assert_eq!(
+            PakOperations::read_file_bytes(&pak, "Public/HcMod/data.lsx").unwrap(),
+            text
+        );
+        assert_eq!(
+            PakOperations::read_file_bytes(&pak, "Public/HcMod/small.txt").unwrap(),
+            b"hi"
+        );
+    }
+
+    #[test]
+    fn test_header_records_priority_and_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Priority.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap().with_priority(5);
+        builder
+            .add_file(
+                "Mods/Test/meta.lsx",
+                b"<save/>",
+                CompressionSettings::none(),
+            )
+            .unwrap();
+        builder.finish().unwrap();
+
+        let data = std::fs::read(&pak).unwrap();
+        let list_offset = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let list_size = u32::from_le_bytes(data[16..20].try_into().unwrap());
+        assert_eq!(&data[HEADER_SIZE..HEADER_SIZE + 7], b"<save/>");
+        assert_eq!(list_offset + u64::from(list_size), data.len() as u64);
+        assert_eq!(data[21], 5);
+        assert_eq!(u16::from_le_bytes(data[38..40].try_into().unwrap()), 1);
+
+        let info = crate::pak::pak_info(&pak, 0).unwrap();
+        assert_eq!(info.priority, 5);
+        assert_eq!(info.files, 1);
+    }
+
+    #[test]
+    fn test_create_with_excludes() {
+        use crate::pak::PakCreateOptions;
+
+        let base = tempfile::tempdir().unwrap();
+        let source = base.path().join("MyMod");
+        std::fs::create_dir_all(source.join("Mods/MyMod")).unwrap();
+        std::fs::create_dir_all(source.join("Public/MyMod/.git")).unwrap();
+        std::fs::write(source.join("Mods/MyMod/meta.lsx"), b"<save/>").unwrap();
+        std::fs::write(source.join("Public/MyMod/icon.psd"), b"psd").unwrap();
+        std::fs::write(source.join("Public/MyMod/.git/HEAD"), b"ref").unwrap();
+        let pak = base.path().join("MyMod.pak");
+
+        let options = PakCreateOptions {
+            settings: CompressionSettings::zlib(6),
+            exclude: vec!["*.psd".to_string(), ".git".to_string()],
+            ..PakCreateOptions::default()
+        };
+        let summary = PakOperations::create_with_options(&source, &pak, &options, &|_| {}).unwrap();
+
+        assert_eq!(summary.file_count, 1);
+        assert_eq!(summary.size, std::fs::metadata(&pak).unwrap().len());
+        assert_eq!(summary.md5.len(), 32);
+        assert_eq!(PakOperations::list(&pak).unwrap(), ["Mods/MyMod/meta.lsx"]);
+
+        let bad = PakCreateOptions {
+            exclude: vec!["[".to_string()],
+            ..PakCreateOptions::default()
+        };
+        assert!(matches!(
+            PakOperations::create_with_options(&source, &pak, &bad, &|_| {}),
+            Err(Error::InvalidPattern { .. })
+        ));
     }
 }
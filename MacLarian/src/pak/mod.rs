@@ -3,13 +3,22 @@
 mod batch;
 mod creator;
 mod extractor;
+mod filter;
+mod game;
+mod grep;
+mod info;
+mod lint;
 mod lister;
 pub mod lspk;
 pub mod pak_tools;
 mod smart_extract;
+mod vfs;
 
 // Primary public API
-pub use pak_tools::{PakOperations, PakReaderCache, ProgressCallback};
+pub use lspk::PakBuilder;
+pub use pak_tools::{
+    PakCreateOptions, PakCreateSummary, PakOperations, PakReaderCache, ProgressCallback,
+};
 
 // Internal API (used by search module)
 pub use creator::create_pak;
@@ -24,11 +33,38 @@ pub use lspk::{PakContents, PakFile, PakPhase, PakProgress};
 
 // Re-export batch operations
 pub use batch::{
-    BatchPakResult, batch_create, batch_extract, find_packable_folders, find_pak_files,
+    BatchPakResult, batch_create, batch_extract, batch_extract_with_policy, find_packable_folders,
+    find_pak_files,
+};
+
+// Re-export game install discovery
+pub use game::{find_game_paks, is_media_pak, resolve_game_data_dir};
+
+// Re-export path filters
+pub use filter::FilterSet;
+
+// Re-export content search
+pub use grep::{
+    GREP_BATCH_SIZE, GrepHit, LineMatch, SEARCHABLE_EXTENSIONS, TextMatcher, grep_pak,
+    lsb_searchable_text, lsf_searchable_text, searchable_text,
 };
 
+// Re-export size summaries and duplicate detection
+pub use info::{
+    DUPES_BATCH_SIZE, DuplicateGroup, DuplicateReport, ExtensionStats, PakEntrySize, PakInfo,
+    find_duplicates, pak_info,
+};
+
+// Re-export path checks
+pub use lint::{MAX_PATH_LENGTH, PakIssue, PakIssueKind, lint_dir_paths, lint_pak, lint_pak_paths};
+
+// Re-export the multi-PAK view
+pub use vfs::VfsView;
+
 // Re-export smart extraction
-pub use smart_extract::{SmartExtractionResult, extract_files_smart, extract_pak_smart};
+pub use smart_extract::{
+    ExtractionPipeline, SmartExtractionResult, extract_files_smart, extract_pak_smart,
+};
 
 // Re-export Gr2ExtractionOptions from gr2_extraction for convenience
 pub use crate::gr2_extraction::Gr2ExtractionOptions;
@@ -4,7 +4,9 @@ use super::super::lspk::{CompressionMethod, FileTableEntry, LspkReader};
 use super::decompression::decompress_data;
 use super::helpers::get_part_path;
 use crate::error::{Error, Result};
+use crate::utils::to_nfc;
 use rayon::prelude::*;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
@@ -64,6 +66,119 @@ impl PakReaderCache {
         self.access_order.push(pak_path.to_path_buf());
     }
 
+    /// List the files in a PAK with their decompressed sizes
+    ///
+    /// # Errors
+    /// Returns an error if the PAK's file table cannot be read.
+    pub fn list_files(&mut self, pak_path: &Path) -> Result<Vec<(String, u64)>> {
+        self.ensure_loaded(pak_path)?;
+        let table = self
+            .tables
+            .get(pak_path)
+            .ok_or_else(|| Error::FileNotFoundInPak(pak_path.to_string_lossy().to_string()))?;
+
+        Ok(table
+            .iter()
+            .map(|e| {
+                (
+                    e.path.to_string_lossy().to_string(),
+                    u64::from(e.size_decompressed),
+                )
+            })
+            .collect())
+    }
+
+    /// List the files in a PAK in the order their data is stored
+    ///
+    /// Files are ordered by archive part, then offset, so reading them in
+    /// this order (in batches, through [`read_files_bulk`](Self::read_files_bulk))
+    /// moves through each part file once.
+    ///
+    /// # Errors
+    /// Returns an error if the PAK's file table cannot be read.
+    pub fn list_files_by_offset(&mut self, pak_path: &Path) -> Result<Vec<(String, u64)>> {
+        self.ensure_loaded(pak_path)?;
+        let table = self
+            .tables
+            .get(pak_path)
+            .ok_or_else(|| Error::FileNotFoundInPak(pak_path.to_string_lossy().to_string()))?;
+
+        let mut entries: Vec<&FileTableEntry> = table.iter().collect();
+        entries.sort_by_key(|e| (e.archive_part, e.offset));
+        Ok(entries
+            .into_iter()
+            .map(|e| {
+                (
+                    e.path.to_string_lossy().to_string(),
+                    u64::from(e.size_decompressed),
+                )
+            })
+            .collect())
+    }
+
+    /// Read a single file's bytes
+    ///
+    /// # Errors
+    /// Returns an error if the PAK cannot be read or doesn't contain the file.
+    pub fn read_file(&mut self, pak_path: &Path, file_path: &str) -> Result<Vec<u8>> {
+        self.read_files_bulk(pak_path, &[file_path])?
+            .remove(file_path)
+            .ok_or_else(|| Error::FileNotFoundInPak(file_path.to_string()))
+    }
+
+    /// Read at least the first `len` bytes of a file
+    ///
+    /// Only as much of the entry as needed is read and decompressed, which
+    /// makes this cheap for sniffing headers. `len` is a minimum: the result
+    /// may be longer where decompression works in blocks, and is the whole
+    /// file if that is shorter than `len`.
+    ///
+    /// # Errors
+    /// Returns an error if the PAK cannot be read or doesn't contain the file.
+    pub fn read_prefix(&mut self, pak_path: &Path, file_path: &str, len: usize) -> Result<Vec<u8>> {
+        self.read_prefixes_bulk(pak_path, &[file_path], len)?
+            .remove(file_path)
+            .ok_or_else(|| Error::FileNotFoundInPak(file_path.to_string()))
+    }
+
+    /// Read at least the first `len` bytes of several files
+    ///
+    /// Like [`read_files_bulk`](Self::read_files_bulk), reads are ordered by
+    /// archive part and offset, and files that fail to read are skipped. See
+    /// [`read_prefix`](Self::read_prefix) for how `len` is applied.
+    ///
+    /// # Errors
+    /// Returns an error if the PAK cannot be opened or its file table read.
+    pub fn read_prefixes_bulk(
+        &mut self,
+        pak_path: &Path,
+        file_paths: &[&str],
+        len: usize,
+    ) -> Result<HashMap<String, Vec<u8>>> {
+        self.ensure_loaded(pak_path)?;
+        let table = self
+            .tables
+            .get(pak_path)
+            .ok_or_else(|| Error::FileNotFoundInPak(pak_path.to_string_lossy().to_string()))?;
+
+        let mut entries = requested_entries(table, file_paths);
+        if entries.is_empty() {
+            return Ok(HashMap::new());
+        }
+        entries.sort_by_key(|(_, e)| (e.archive_part, e.offset));
+
+        let mut reader = LspkReader::with_path(File::open(pak_path)?, pak_path);
+        Ok(entries
+            .into_iter()
+            .filter_map(|(path, entry)| {
+                reader
+                    .read_file_prefix(entry, len)
+                    .ok()
+                    .map(|bytes| (path.to_string(), bytes))
+            })
+            .collect())
+    }
+
     /// Read multiple files' bytes in bulk with optimized I/O
     ///
     /// This is optimized for reading many files because:
@@ -83,35 +198,27 @@ impl PakReaderCache {
         pak_path: &Path,
         file_paths: &[&str],
     ) -> Result<HashMap<String, Vec<u8>>> {
-        use std::collections::HashSet;
-
         self.ensure_loaded(pak_path)?;
 
-        // Build set of requested paths for O(1) lookup
-        let requested: HashSet<&str> = file_paths.iter().copied().collect();
-
         // Get matching entries from cached table
         let table = self
             .tables
             .get(pak_path)
             .ok_or_else(|| Error::FileNotFoundInPak(pak_path.to_string_lossy().to_string()))?;
 
-        let entries_to_read: Vec<&FileTableEntry> = table
-            .iter()
-            .filter(|e| requested.contains(e.path.to_string_lossy().as_ref()))
-            .collect();
+        let entries_to_read = requested_entries(table, file_paths);
 
         if entries_to_read.is_empty() {
             return Ok(HashMap::new());
         }
 
         // Group entries by archive part for multi-part PAK support
-        let mut entries_by_part: HashMap<u8, Vec<&FileTableEntry>> = HashMap::new();
-        for entry in entries_to_read {
+        let mut entries_by_part: HashMap<u8, Vec<(&str, &FileTableEntry)>> = HashMap::new();
+        for (path, entry) in entries_to_read {
             entries_by_part
                 .entry(entry.archive_part)
                 .or_default()
-                .push(entry);
+                .push((path, entry));
         }
 
         // Phase 1: Read all compressed data sequentially from each part file
@@ -129,14 +236,14 @@ impl PakReaderCache {
             }
 
             // Sort by offset for sequential I/O within this part
-            part_entries.sort_by_key(|e| e.offset);
+            part_entries.sort_by_key(|(_, e)| e.offset);
 
             let mut part_file = match File::open(&part_path) {
                 Ok(f) => f,
                 Err(_) => continue,
             };
 
-            for entry in part_entries {
+            for (path, entry) in part_entries {
                 // Seek and read from the correct part file
                 if part_file.seek(SeekFrom::Start(entry.offset)).is_err() {
                     continue;
@@ -148,7 +255,7 @@ impl PakReaderCache {
                 }
 
                 compressed_files.push((
-                    entry.path.to_string_lossy().to_string(),
+                    path.to_string(),
                     compressed_data,
                     entry.compression,
                     entry.size_decompressed,
@@ -169,3 +276,103 @@ impl PakReaderCache {
         Ok(results.into_iter().collect())
     }
 }
+
+/// Table entries for the requested paths, ignoring Unicode normalization
+///
+/// Each entry comes with the path as it was requested, so results can be
+/// looked up by the caller's spelling of it.
+fn requested_entries<'a, 'b>(
+    table: &'b [FileTableEntry],
+    file_paths: &[&'a str],
+) -> Vec<(&'a str, &'b FileTableEntry)> {
+    let requested: HashMap<Cow<'a, str>, &'a str> = file_paths
+        .iter()
+        .map(|&path| (to_nfc(path), path))
+        .collect();
+    table
+        .iter()
+        .filter_map(|entry| {
+            let path = entry.path.to_string_lossy();
+            requested
+                .get(to_nfc(&path).as_ref())
+                .map(|&requested| (requested, entry))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::pak::PakBuilder;
+
+    #[test]
+    fn test_read_prefix_per_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Prefix.pak");
+        let long: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        let methods = [
+            ("none", CompressionSettings::none()),
+            ("zlib", CompressionSettings::zlib(6)),
+            ("lz4", CompressionSettings::lz4()),
+        ];
+        for (name, settings) in methods {
+            builder
+                .add_file(&format!("{name}/long.bin"), &long, settings)
+                .unwrap();
+            builder
+                .add_file(&format!("{name}/short.bin"), b"tiny", settings)
+                .unwrap();
+        }
+        builder.finish().unwrap();
+
+        let mut cache = PakReaderCache::new(1);
+        for (name, _) in methods {
+            let prefix = cache
+                .read_prefix(&pak, &format!("{name}/long.bin"), 512)
+                .unwrap();
+            assert!(prefix.len() >= 512, "{name}");
+            assert_eq!(prefix, long[..prefix.len()], "{name}");
+
+            // Entries shorter than the prefix come back whole
+            let short = cache
+                .read_prefix(&pak, &format!("{name}/short.bin"), 512)
+                .unwrap();
+            assert_eq!(short, b"tiny", "{name}");
+        }
+
+        let prefixes = cache
+            .read_prefixes_bulk(&pak, &["lz4/long.bin", "zlib/short.bin", "missing.bin"], 16)
+            .unwrap();
+        assert_eq!(prefixes.len(), 2);
+        assert_eq!(prefixes["lz4/long.bin"][..16], long[..16]);
+        assert!(cache.read_prefix(&pak, "missing.bin", 16).is_err());
+    }
+
+    #[test]
+    fn test_bulk_reads_ignore_unicode_normalization() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Nfc.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        builder
+            .add_file(
+                "Public/Mod/Caf\u{e9}.txt",
+                b"menu",
+                CompressionSettings::lz4(),
+            )
+            .unwrap();
+        builder.finish().unwrap();
+
+        // Stored as NFC, requested in decomposed (NFD) form as macOS spells it
+        let decomposed = "Public/Mod/Cafe\u{301}.txt";
+        let mut cache = PakReaderCache::new(1);
+        let files = cache.read_files_bulk(&pak, &[decomposed]).unwrap();
+        assert_eq!(files[decomposed], b"menu");
+        let prefixes = cache.read_prefixes_bulk(&pak, &[decomposed], 2).unwrap();
+        assert_eq!(prefixes[decomposed][..2], *b"me");
+        assert_eq!(cache.read_file(&pak, decomposed).unwrap(), b"menu");
+        assert!(cache.read_prefix(&pak, decomposed, 2).is_ok());
+    }
+}
@@ -12,7 +12,7 @@ mod helpers;
 mod operations;
 
 pub use cache::PakReaderCache;
-pub use operations::PakOperations;
+pub use operations::{PakCreateOptions, PakCreateSummary, PakOperations};
 
 use super::lspk::PakProgress;
 
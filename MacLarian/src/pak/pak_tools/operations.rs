@@ -1,12 +1,15 @@
 //! Core PAK archive operations
 
+use super::super::filter::FilterSet;
 use super::super::lspk::{
     CompressionMethod, FileTableEntry, LspkReader, LspkWriter, PakPhase, PakProgress,
 };
 use super::ProgressCallback;
 use super::decompression::decompress_data;
 use super::helpers::{get_part_path, get_virtual_texture_subfolder, is_virtual_texture_file};
+use crate::compression::CompressionSettings;
 use crate::error::{Error, Result};
+use crate::utils::{CancelToken, open_file, to_nfc};
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::fs::File;
@@ -20,6 +23,30 @@ struct CompressedFile {
     compressed_data: Vec<u8>,
 }
 
+/// Options for [`PakOperations::create_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct PakCreateOptions {
+    /// Compression method and level
+    pub settings: CompressionSettings,
+    /// Glob patterns for files and folders to leave out, matched against
+    /// paths relative to the source folder and against entry names
+    pub exclude: Vec<String>,
+    /// Load priority stored in the header; PAKs with a higher priority
+    /// override lower ones
+    pub priority: u8,
+}
+
+/// What [`PakOperations::create_with_options`] wrote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakCreateSummary {
+    /// Number of files packed
+    pub file_count: usize,
+    /// Size of the PAK file in bytes
+    pub size: u64,
+    /// MD5 of the PAK file as lowercase hex
+    pub md5: String,
+}
+
 /// High-level PAK archive operations.
 pub struct PakOperations;
 
@@ -64,17 +91,40 @@ impl PakOperations {
         pak_path: P,
         output_dir: P,
         progress: ProgressCallback,
+    ) -> Result<()> {
+        Self::extract_cancellable(pak_path, output_dir, &CancelToken::new(), progress)
+    }
+
+    /// Extract a PAK file to a directory, stopping early once `cancel` is triggered
+    ///
+    /// Works like [`extract_with_progress`](Self::extract_with_progress). Files
+    /// already being written when `cancel` is triggered are finished; the rest
+    /// are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] if `cancel` was triggered, otherwise the
+    /// same errors as [`extract_with_progress`](Self::extract_with_progress).
+    ///
+    /// [`Error::Cancelled`]: crate::Error::Cancelled
+    pub fn extract_cancellable<P: AsRef<Path>>(
+        pak_path: P,
+        output_dir: P,
+        cancel: &CancelToken,
+        progress: ProgressCallback,
     ) -> Result<()> {
         let pak_path = pak_path.as_ref();
         let output_dir = output_dir.as_ref();
 
-        let mut reader = LspkReader::with_path(File::open(pak_path)?, pak_path);
+        let mut reader = LspkReader::with_path(open_file(pak_path)?, pak_path);
 
         progress(&PakProgress {
             phase: PakPhase::ReadingTable,
             current: 1,
             total: 1,
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         });
 
         // Get file list without decompressing
@@ -96,6 +146,10 @@ impl PakOperations {
         let errors: Vec<(PathBuf, String)> = filtered_entries
             .par_iter()
             .filter_map(|entry| {
+                if cancel.is_cancelled() {
+                    return None;
+                }
+
                 let file_name = entry.path.file_name().map_or_else(
                     || entry.path.to_string_lossy().to_string(),
                     |n| n.to_string_lossy().to_string(),
@@ -108,6 +162,8 @@ impl PakOperations {
                     current,
                     total: total_files,
                     current_file: Some(file_name.clone()),
+                    bytes_done: 0,
+                    bytes_total: 0,
                 });
 
                 // Get the correct part file path for this entry
@@ -193,6 +249,7 @@ impl PakOperations {
                 None
             })
             .collect();
+        cancel.checkpoint()?;
 
         // If there were errors, return a summary error
         if !errors.is_empty() {
@@ -278,11 +335,117 @@ impl PakOperations {
         compression: CompressionMethod,
         progress: ProgressCallback,
     ) -> Result<()> {
-        let writer = LspkWriter::new(source_dir.as_ref())?.with_compression(compression);
+        Self::create_with_settings_and_progress(
+            source_dir,
+            output_pak,
+            compression.settings(),
+            progress,
+        )
+    }
+
+    /// Create a PAK file from a directory with a compression level and progress callback
+    ///
+    /// Accepts any [`CompressionSettings`] a PAK can store, e.g. LZ4 HC or
+    /// zlib at a specific level.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the source directory cannot be read or output file cannot be written.
+    /// Returns [`Error::WalkDirError`] if directory traversal fails.
+    /// Returns [`Error::CompressionError`] if file compression fails or the
+    /// settings use a method PAKs can't store.
+    ///
+    /// [`Error::Io`]: crate::Error::Io
+    /// [`Error::WalkDirError`]: crate::Error::WalkDirError
+    /// [`Error::CompressionError`]: crate::Error::CompressionError
+    pub fn create_with_settings_and_progress<P: AsRef<Path>>(
+        source_dir: P,
+        output_pak: P,
+        settings: CompressionSettings,
+        progress: ProgressCallback,
+    ) -> Result<()> {
+        let writer = LspkWriter::new(source_dir.as_ref())?.with_settings(settings);
         writer.write_with_progress(output_pak.as_ref(), progress)?;
         Ok(())
     }
 
+    /// Create a PAK file from a directory with compression, exclusions and progress callback
+    ///
+    /// Returns the packed file count, final size and MD5 of the written PAK.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPattern`] if an exclude pattern is not a valid glob.
+    /// Returns [`Error::Io`] if the source directory cannot be read or output file cannot be written.
+    /// Returns [`Error::CompressionError`] if file compression fails or the
+    /// settings use a method PAKs can't store.
+    ///
+    /// [`Error::InvalidPattern`]: crate::Error::InvalidPattern
+    /// [`Error::Io`]: crate::Error::Io
+    /// [`Error::CompressionError`]: crate::Error::CompressionError
+    pub fn create_with_options<P: AsRef<Path>>(
+        source_dir: P,
+        output_pak: P,
+        options: &PakCreateOptions,
+        progress: ProgressCallback,
+    ) -> Result<PakCreateSummary> {
+        Self::create_cancellable(
+            source_dir,
+            output_pak,
+            options,
+            &CancelToken::new(),
+            progress,
+        )
+    }
+
+    /// Create a PAK file from a directory, stopping early once `cancel` is triggered
+    ///
+    /// Works like [`create_with_options`](Self::create_with_options), with
+    /// progress also reported every few megabytes within large files. If the
+    /// PAK is cancelled or fails partway through writing, the partial output
+    /// file is removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] if `cancel` was triggered, otherwise the
+    /// same errors as [`create_with_options`](Self::create_with_options).
+    ///
+    /// [`Error::Cancelled`]: crate::Error::Cancelled
+    pub fn create_cancellable<P: AsRef<Path>>(
+        source_dir: P,
+        output_pak: P,
+        options: &PakCreateOptions,
+        cancel: &CancelToken,
+        progress: ProgressCallback,
+    ) -> Result<PakCreateSummary> {
+        let exclude = options
+            .exclude
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern).map_err(|e| Error::InvalidPattern {
+                    pattern: pattern.clone(),
+                    message: e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let writer = LspkWriter::new_excluding(source_dir.as_ref(), &exclude)?
+            .with_settings(options.settings)
+            .with_priority(options.priority);
+        let file_count = writer.file_count();
+        writer.write_cancellable(output_pak.as_ref(), cancel, progress)?;
+
+        let mut file = File::open(output_pak.as_ref())?;
+        let mut hasher = md5::Context::new();
+        let size = std::io::copy(&mut file, &mut hasher)?;
+
+        Ok(PakCreateSummary {
+            file_count,
+            size,
+            md5: format!("{:x}", hasher.compute()),
+        })
+    }
+
     /// List contents of a PAK file
     ///
     /// # Errors
@@ -309,7 +472,7 @@ impl PakOperations {
         pak_path: P,
         progress: ProgressCallback,
     ) -> Result<Vec<String>> {
-        let file = File::open(pak_path.as_ref())?;
+        let file = open_file(pak_path.as_ref())?;
 
         let mut reader = LspkReader::with_path(file, pak_path.as_ref());
 
@@ -318,6 +481,8 @@ impl PakOperations {
             current: 1,
             total: 1,
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         });
 
         let entries = reader.list_files()?;
@@ -327,6 +492,8 @@ impl PakOperations {
             current: entries.len(),
             total: entries.len(),
             current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
         });
 
         Ok(entries
@@ -335,6 +502,21 @@ impl PakOperations {
             .collect())
     }
 
+    /// List the paths of a PAK file that pass a [`FilterSet`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the PAK file cannot be opened.
+    /// Returns [`Error::InvalidPakMagic`] if the file is not a valid PAK archive.
+    ///
+    /// [`Error::Io`]: crate::Error::Io
+    /// [`Error::InvalidPakMagic`]: crate::Error::InvalidPakMagic
+    pub fn list_filtered<P: AsRef<Path>>(pak_path: P, filter: &FilterSet) -> Result<Vec<String>> {
+        let mut files = Self::list(pak_path)?;
+        files.retain(|path| filter.matches(path));
+        Ok(files)
+    }
+
     /// List contents of a PAK file with detailed information
     ///
     /// Returns full file entries including sizes and compression info.
@@ -347,7 +529,7 @@ impl PakOperations {
     /// [`Error::Io`]: crate::Error::Io
     /// [`Error::InvalidPakMagic`]: crate::Error::InvalidPakMagic
     pub(crate) fn list_detailed<P: AsRef<Path>>(pak_path: P) -> Result<Vec<FileTableEntry>> {
-        let file = File::open(pak_path.as_ref())?;
+        let file = open_file(pak_path.as_ref())?;
         let mut reader = LspkReader::with_path(file, pak_path.as_ref());
         reader.list_files()
     }
@@ -401,13 +583,41 @@ impl PakOperations {
         output_dir: P,
         file_paths: &[S],
         progress: ProgressCallback,
+    ) -> Result<()> {
+        Self::extract_files_cancellable(
+            pak_path,
+            output_dir,
+            file_paths,
+            &CancelToken::new(),
+            progress,
+        )
+    }
+
+    /// Extract specific files from a PAK, stopping early once `cancel` is triggered
+    ///
+    /// Works like [`extract_files_with_progress`](Self::extract_files_with_progress).
+    /// Files already being written when `cancel` is triggered are finished;
+    /// the rest are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] if `cancel` was triggered, otherwise the
+    /// same errors as [`extract_files_with_progress`](Self::extract_files_with_progress).
+    ///
+    /// [`Error::Cancelled`]: crate::Error::Cancelled
+    pub fn extract_files_cancellable<P: AsRef<Path>, S: AsRef<str>>(
+        pak_path: P,
+        output_dir: P,
+        file_paths: &[S],
+        cancel: &CancelToken,
+        progress: ProgressCallback,
     ) -> Result<()> {
         if file_paths.is_empty() {
             return Ok(());
         }
 
         let pak_path = pak_path.as_ref();
-        let mut reader = LspkReader::with_path(File::open(pak_path)?, pak_path);
+        let mut reader = LspkReader::with_path(open_file(pak_path)?, pak_path);
 
         // Build a set of requested paths for fast lookup
         let requested: std::collections::HashSet<&str> =
@@ -440,6 +650,10 @@ impl PakOperations {
         let errors: Vec<(PathBuf, String)> = entries_to_extract
             .par_iter()
             .filter_map(|entry| {
+                if cancel.is_cancelled() {
+                    return None;
+                }
+
                 let file_name = entry.path.file_name().map_or_else(
                     || entry.path.to_string_lossy().to_string(),
                     |n| n.to_string_lossy().to_string(),
@@ -452,6 +666,8 @@ impl PakOperations {
                     current,
                     total: total_files,
                     current_file: Some(file_name.clone()),
+                    bytes_done: 0,
+                    bytes_total: 0,
                 });
 
                 // Get the correct part file path for this entry
@@ -531,6 +747,7 @@ impl PakOperations {
                 None
             })
             .collect();
+        cancel.checkpoint()?;
 
         // If there were errors, return a summary error
         if !errors.is_empty() {
@@ -544,6 +761,32 @@ impl PakOperations {
         Ok(())
     }
 
+    /// Extract the files of a PAK that pass a [`FilterSet`]
+    ///
+    /// Uses the same matching as [`Self::list_filtered`], so listing and
+    /// extracting with one filter agree. Returns the number of files
+    /// extracted; no match is not an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the PAK file cannot be opened or output directory cannot be created.
+    /// Returns [`Error::InvalidPakMagic`] if the file is not a valid PAK archive.
+    /// Returns [`Error::PakExtractionPartialFailure`] if extraction completes with partial failures.
+    ///
+    /// [`Error::Io`]: crate::Error::Io
+    /// [`Error::InvalidPakMagic`]: crate::Error::InvalidPakMagic
+    /// [`Error::PakExtractionPartialFailure`]: crate::Error::PakExtractionPartialFailure
+    pub fn extract_filtered<P: AsRef<Path>>(
+        pak_path: P,
+        output_dir: P,
+        filter: &FilterSet,
+        progress: ProgressCallback,
+    ) -> Result<usize> {
+        let files = Self::list_filtered(pak_path.as_ref(), filter)?;
+        Self::extract_files_with_progress(pak_path, output_dir, &files, progress)?;
+        Ok(files.len())
+    }
+
     /// Read a single file's bytes from a PAK without writing to disk
     ///
     /// Returns the decompressed file contents, or an error if the file is not found.
@@ -560,22 +803,53 @@ impl PakOperations {
     /// [`Error::FileNotFoundInPak`]: crate::Error::FileNotFoundInPak
     /// [`Error::DecompressionError`]: crate::Error::DecompressionError
     pub fn read_file_bytes<P: AsRef<Path>>(pak_path: P, file_path: &str) -> Result<Vec<u8>> {
-        let file = File::open(pak_path.as_ref())?;
+        let file = open_file(pak_path.as_ref())?;
         let mut reader = LspkReader::with_path(file, pak_path.as_ref());
 
         // Get file list
         let entries = reader.list_files()?;
 
-        // Find the requested file
+        // Find the requested file, accepting either Unicode normalization
+        let file_path_nfc = to_nfc(file_path);
         let entry = entries
             .into_iter()
-            .find(|e| e.path.to_string_lossy() == file_path)
+            .find(|e| to_nfc(&e.path.to_string_lossy()) == file_path_nfc)
             .ok_or_else(|| Error::FileNotFoundInPak(file_path.to_string()))?;
 
         // Decompress and return
         reader.decompress_file(&entry)
     }
 
+    /// Read at least the first `len` bytes of a file in a PAK
+    ///
+    /// Only as much of the entry as needed is read and decompressed. `len`
+    /// is a minimum: the result may be longer where decompression works in
+    /// blocks, and is the whole file if that is shorter than `len`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FileNotFoundInPak`] if the requested file path is not
+    /// in the archive, or an error if the PAK cannot be read.
+    ///
+    /// [`Error::FileNotFoundInPak`]: crate::Error::FileNotFoundInPak
+    pub fn read_file_prefix<P: AsRef<Path>>(
+        pak_path: P,
+        file_path: &str,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let file = open_file(pak_path.as_ref())?;
+        let mut reader = LspkReader::with_path(file, pak_path.as_ref());
+
+        let file_path_nfc = to_nfc(file_path);
+        let entry = reader
+            .list_files()?
+            .into_iter()
+            .find(|e| to_nfc(&e.path.to_string_lossy()) == file_path_nfc)
+            .ok_or_else(|| Error::FileNotFoundInPak(file_path.to_string()))?;
+
+        reader.read_file_prefix(&entry, len)
+    }
+
     /// Read multiple files' bytes from a PAK without writing to disk
     ///
     /// Returns a map of file paths to their decompressed contents.
@@ -601,7 +875,7 @@ impl PakOperations {
         }
 
         let pak_path = pak_path.as_ref();
-        let mut reader = LspkReader::with_path(File::open(pak_path)?, pak_path);
+        let mut reader = LspkReader::with_path(open_file(pak_path)?, pak_path);
 
         // Build a set of requested paths
         let requested: std::collections::HashSet<&str> =
@@ -704,7 +978,7 @@ impl PakOperations {
     /// [`Error::FileNotFoundInPak`]: crate::Error::FileNotFoundInPak
     /// [`Error::ConversionError`]: crate::Error::ConversionError
     pub fn extract_meta<P: AsRef<Path>>(pak_path: P) -> Result<String> {
-        let file = File::open(pak_path.as_ref())?;
+        let file = open_file(pak_path.as_ref())?;
 
         let mut reader = LspkReader::with_path(file, pak_path.as_ref());
         let contents = reader.read_all(None)?;
@@ -732,3 +1006,63 @@ impl PakOperations {
         String::from_utf8(meta_file.data.clone()).map_err(Error::from)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_stops_when_cancelled() {
+        let base = tempfile::tempdir().unwrap();
+        let (source, dest) = (base.path().join("source"), base.path().join("dest"));
+        std::fs::create_dir_all(&source).unwrap();
+        for i in 0..200 {
+            std::fs::write(source.join(format!("file_{i}.txt")), format!("file {i}")).unwrap();
+        }
+        let pak = base.path().join("Test.pak");
+        PakOperations::create(&source, &pak).unwrap();
+
+        // Cancel as soon as the first file starts; only files already in
+        // flight on other workers may still be written
+        let cancel = CancelToken::new();
+        let result = PakOperations::extract_cancellable(&pak, &dest, &cancel, &|p| {
+            if p.phase == PakPhase::DecompressingFiles {
+                cancel.cancel();
+            }
+        });
+        assert!(matches!(result, Err(Error::Cancelled)));
+        let written = std::fs::read_dir(&dest).unwrap().count();
+        assert!(written <= rayon::current_num_threads());
+
+        // Extracting selected files honours an already-cancelled token
+        let files_dest = base.path().join("files");
+        let result = PakOperations::extract_files_cancellable(
+            &pak,
+            &files_dest,
+            &["file_0.txt", "file_1.txt"],
+            &cancel,
+            &|_| {},
+        );
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(std::fs::read_dir(&files_dest).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_nfd_file_names_are_packed_as_nfc() {
+        let base = tempfile::tempdir().unwrap();
+        let (source, dest) = (base.path().join("source"), base.path().join("dest"));
+        std::fs::create_dir_all(&source).unwrap();
+        // File name as macOS reports it: 'e' followed by a combining acute
+        std::fs::write(source.join("Cafe\u{301}.txt"), "menu").unwrap();
+        let pak = base.path().join("Test.pak");
+        PakOperations::create(&source, &pak).unwrap();
+
+        assert_eq!(PakOperations::list(&pak).unwrap(), vec!["Caf\u{e9}.txt"]);
+        assert_eq!(
+            PakOperations::read_file_bytes(&pak, "Cafe\u{301}.txt").unwrap(),
+            b"menu"
+        );
+        PakOperations::extract(&pak, &dest).unwrap();
+        assert!(dest.join("Caf\u{e9}.txt").exists());
+    }
+}
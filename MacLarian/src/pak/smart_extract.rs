@@ -35,9 +35,11 @@ use super::pak_tools::{PakOperations, ProgressCallback};
 use crate::converter::convert_gr2_to_glb;
 use crate::error::Result;
 use crate::gr2_extraction::{
-    Gr2ExtractionOptions, Gr2ExtractionResult, convert_textures_to_png, extract_textures_for_gr2,
+    BundleManifest, BundleModel, Gr2ExtractionOptions, Gr2ExtractionResult,
+    convert_textures_to_png, extract_textures_for_gr2, mesh_names,
 };
 use crate::merged::GameDataResolver;
+use crate::utils::CancelToken;
 
 /// Result of a smart extraction operation
 #[derive(Debug, Clone)]
@@ -52,6 +54,8 @@ pub struct SmartExtractionResult {
     pub textures_extracted: usize,
     /// Paths to extracted GR2 folders (each GR2 gets its own folder)
     pub gr2_folders: Vec<PathBuf>,
+    /// Bundle manifest entry for each processed GR2
+    pub models: Vec<BundleModel>,
     /// Warnings/errors encountered during processing
     pub warnings: Vec<String>,
 }
@@ -65,109 +69,222 @@ impl SmartExtractionResult {
             glb_files_created: 0,
             textures_extracted: 0,
             gr2_folders: Vec::new(),
+            models: Vec::new(),
             warnings: Vec::new(),
         }
     }
+
+    /// Bundle manifest describing the processed GR2s
+    #[must_use]
+    pub fn manifest(&self) -> BundleManifest {
+        BundleManifest::new(self.models.clone())
+    }
 }
 
-/// Extract specific files from a PAK with optional GR2 processing.
-///
-/// When GR2 processing options are enabled, this function will:
-/// 1. Extract all requested files from the PAK
-/// 2. For each GR2 file, create a dedicated subfolder
-/// 3. Move the GR2 into its subfolder
-/// 4. Optionally convert to GLB
-/// 5. Optionally extract associated textures
-///
-/// # Output Structure
-///
-/// When GR2 processing is enabled, each GR2 file gets its own folder:
-/// ```text
-/// output_dir/
-///   HUM_M_ARM_Leather_A_Body/
-///     HUM_M_ARM_Leather_A_Body.GR2     # Original (if keep_original)
-///     HUM_M_ARM_Leather_A_Body.glb     # Converted model
-///     HUM_M_ARM_Leather_A_Body_BC.dds  # Texture (basecolor)
-///     HUM_M_ARM_Leather_A_Body_NM.dds  # Texture (normal)
-///   HUM_F_ARM_Scale_A_Body/
-///     ...
-/// ```
-///
-/// # Arguments
-///
-/// * `pak_path` - Path to the source PAK file
-/// * `output_dir` - Directory where files will be extracted
-/// * `file_paths` - List of file paths within the PAK to extract
-/// * `options` - GR2 processing options
-/// * `progress` - Progress callback receiving [`PakProgress`] with phase and file info
+/// PAK extraction followed by GR2 post-processing
 ///
-/// # Errors
+/// Holds the [`Gr2ExtractionOptions`] applied to every GR2 that gets
+/// extracted, so the CLI and the GUI run the same steps. With no GR2
+/// processing enabled, this is a plain extraction.
 ///
-/// Returns an error if PAK extraction fails. Individual GR2 processing
-/// errors are collected in `SmartExtractionResult::warnings` and do not
-/// cause the entire operation to fail.
-pub fn extract_files_smart<P: AsRef<Path>, S: AsRef<str>>(
-    pak_path: P,
-    output_dir: P,
-    file_paths: &[S],
+/// Progress is reported through one [`PakProgress`] callback:
+/// [`PakPhase::DecompressingFiles`] while files are extracted, then
+/// [`PakPhase::ConvertingModels`] counting processed GR2s, with
+/// [`PakPhase::ExtractingTextures`] and [`PakPhase::ConvertingTextures`]
+/// naming the GR2 being worked on.
+#[derive(Debug, Clone)]
+pub struct ExtractionPipeline {
     options: Gr2ExtractionOptions,
-    progress: ProgressCallback,
-) -> Result<SmartExtractionResult> {
-    let pak_path = pak_path.as_ref();
-    let output_dir = output_dir.as_ref();
-    let mut result = SmartExtractionResult::new();
+    manifest: Option<PathBuf>,
+}
 
-    if file_paths.is_empty() {
-        return Ok(result);
+impl ExtractionPipeline {
+    /// Create a pipeline applying `options` to extracted GR2 files
+    #[must_use]
+    pub fn new(options: Gr2ExtractionOptions) -> Self {
+        Self {
+            options,
+            manifest: None,
+        }
     }
 
-    // Phase 1: Extract all files normally
-    progress(&PakProgress {
-        phase: PakPhase::DecompressingFiles,
-        current: 0,
-        total: file_paths.len(),
-        current_file: None,
-    });
-    PakOperations::extract_files_with_progress(pak_path, output_dir, file_paths, progress)?;
-    result.files_extracted = file_paths.len();
-
-    // If no GR2 processing is enabled, we're done
-    if !options.has_gr2_processing() {
-        return Ok(result);
+    /// Write a [`BundleManifest`] of the processed GR2s to `path`
+    #[must_use]
+    pub fn with_manifest(mut self, path: Option<PathBuf>) -> Self {
+        self.manifest = path;
+        self
     }
 
-    // Phase 2: Find extracted GR2 files
-    let gr2_paths: Vec<PathBuf> = file_paths
-        .iter()
-        .filter(|p| {
-            let path = p.as_ref().to_lowercase();
-            path.ends_with(".gr2")
-        })
-        .map(|p| output_dir.join(p.as_ref()))
-        .filter(|p| p.exists())
-        .collect();
-
-    if gr2_paths.is_empty() {
-        return Ok(result);
+    /// The GR2 processing options
+    #[must_use]
+    pub fn options(&self) -> &Gr2ExtractionOptions {
+        &self.options
     }
 
-    // Phase 3: Process GR2 files
-    let total_gr2 = gr2_paths.len();
-    progress(&PakProgress {
-        phase: PakPhase::WritingFiles,
-        current: 0,
-        total: total_gr2,
-        current_file: Some("Processing GR2 files...".to_string()),
-    });
-
-    // Process GR2 files in parallel, reporting progress as each completes
-    let completed = AtomicUsize::new(0);
-    let processing_results: Vec<(PathBuf, std::result::Result<Gr2ExtractionResult, String>)> =
-        gr2_paths
+    /// Where the bundle manifest is written, if anywhere
+    #[must_use]
+    pub fn manifest_path(&self) -> Option<&Path> {
+        self.manifest.as_deref()
+    }
+
+    /// Whether extracted GR2 files get any post-processing
+    #[must_use]
+    pub fn processes_gr2(&self) -> bool {
+        self.options.has_gr2_processing()
+    }
+
+    /// Extract specific files from a PAK, then process the GR2s among them.
+    ///
+    /// When GR2 processing options are enabled, this will:
+    /// 1. Extract all requested files from the PAK
+    /// 2. For each GR2 file, create a dedicated subfolder
+    /// 3. Move the GR2 into its subfolder
+    /// 4. Optionally convert to GLB
+    /// 5. Optionally extract associated textures
+    ///
+    /// # Output Structure
+    ///
+    /// When GR2 processing is enabled, each GR2 file gets its own folder:
+    /// ```text
+    /// output_dir/
+    ///   HUM_M_ARM_Leather_A_Body/
+    ///     HUM_M_ARM_Leather_A_Body.GR2     # Original (if keep_original)
+    ///     HUM_M_ARM_Leather_A_Body.glb     # Converted model
+    ///     HUM_M_ARM_Leather_A_Body_BC.dds  # Texture (basecolor)
+    ///     HUM_M_ARM_Leather_A_Body_NM.dds  # Texture (normal)
+    ///   HUM_F_ARM_Scale_A_Body/
+    ///     ...
+    /// ```
+    ///
+    /// With a manifest path set, the [`BundleManifest`] is written once the
+    /// GR2s are processed, including when there were none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if PAK extraction or writing the manifest fails.
+    /// Individual GR2 processing errors are collected in
+    /// `SmartExtractionResult::warnings` and do not cause the entire
+    /// operation to fail.
+    pub fn extract_files<S: AsRef<str>>(
+        &self,
+        pak_path: &Path,
+        output_dir: &Path,
+        file_paths: &[S],
+        progress: ProgressCallback,
+    ) -> Result<SmartExtractionResult> {
+        self.extract_files_cancellable(
+            pak_path,
+            output_dir,
+            file_paths,
+            &CancelToken::new(),
+            progress,
+        )
+    }
+
+    /// Extract specific files and process the GR2s among them, stopping early
+    /// once `cancel` is triggered
+    ///
+    /// Works like [`extract_files`](Self::extract_files). Files and GR2s
+    /// already being worked on when `cancel` is triggered are finished; the
+    /// rest are skipped and no manifest is written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cancelled`] if `cancel` was triggered, otherwise the
+    /// same errors as [`extract_files`](Self::extract_files).
+    ///
+    /// [`Error::Cancelled`]: crate::Error::Cancelled
+    pub fn extract_files_cancellable<S: AsRef<str>>(
+        &self,
+        pak_path: &Path,
+        output_dir: &Path,
+        file_paths: &[S],
+        cancel: &CancelToken,
+        progress: ProgressCallback,
+    ) -> Result<SmartExtractionResult> {
+        let result =
+            self.extract_and_process(pak_path, output_dir, file_paths, cancel, progress)?;
+        cancel.checkpoint()?;
+        if let Some(manifest) = &self.manifest {
+            result.manifest().save(manifest)?;
+        }
+        Ok(result)
+    }
+
+    fn extract_and_process<S: AsRef<str>>(
+        &self,
+        pak_path: &Path,
+        output_dir: &Path,
+        file_paths: &[S],
+        cancel: &CancelToken,
+        progress: ProgressCallback,
+    ) -> Result<SmartExtractionResult> {
+        let options = &self
+            .options
+            .clone()
+            .with_record_meshes(self.manifest.is_some());
+        let mut result = SmartExtractionResult::new();
+
+        if file_paths.is_empty() {
+            return Ok(result);
+        }
+
+        // Phase 1: Extract all files normally
+        progress(&PakProgress {
+            phase: PakPhase::DecompressingFiles,
+            current: 0,
+            total: file_paths.len(),
+            current_file: None,
+            bytes_done: 0,
+            bytes_total: 0,
+        });
+        PakOperations::extract_files_cancellable(
+            pak_path, output_dir, file_paths, cancel, progress,
+        )?;
+        result.files_extracted = file_paths.len();
+
+        // If no GR2 processing is enabled, we're done
+        if !options.has_gr2_processing() {
+            return Ok(result);
+        }
+
+        // Phase 2: Find extracted GR2 files, with their paths in the PAK
+        let gr2_paths: Vec<(&str, PathBuf)> = file_paths
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|p| p.to_lowercase().ends_with(".gr2"))
+            .map(|p| (p, output_dir.join(p)))
+            .filter(|(_, p)| p.exists())
+            .collect();
+
+        if gr2_paths.is_empty() {
+            return Ok(result);
+        }
+
+        // Phase 3: Process GR2 files
+        let total_gr2 = gr2_paths.len();
+        progress(&PakProgress {
+            phase: PakPhase::ConvertingModels,
+            current: 0,
+            total: total_gr2,
+            current_file: Some("Processing GR2 files...".to_string()),
+            bytes_done: 0,
+            bytes_total: 0,
+        });
+
+        // Process GR2 files in parallel, reporting progress as each completes
+        // and skipping the ones not started before a cancel
+        let completed = AtomicUsize::new(0);
+        let processing_results: Vec<(
+            &str,
+            PathBuf,
+            std::result::Result<Gr2ExtractionResult, String>,
+        )> = gr2_paths
             .par_iter()
-            .map(|gr2_path| {
+            .filter(|_| !cancel.is_cancelled())
+            .map(|(internal_path, gr2_path)| {
                 let folder_result = process_single_gr2(
-                    gr2_path, output_dir, &options, progress, &completed, total_gr2,
+                    gr2_path, output_dir, options, progress, &completed, total_gr2,
                 );
                 let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
                 let filename = gr2_path
@@ -175,45 +292,97 @@ pub fn extract_files_smart<P: AsRef<Path>, S: AsRef<str>>(
                     .and_then(|n| n.to_str())
                     .unwrap_or("GR2");
                 progress(&PakProgress::with_file(
-                    PakPhase::WritingFiles,
+                    PakPhase::ConvertingModels,
                     done,
                     total_gr2,
                     filename,
                 ));
-                (gr2_path.clone(), folder_result)
+                (*internal_path, gr2_path.clone(), folder_result)
             })
             .collect();
+        cancel.checkpoint()?;
 
-    // Collect results
-    for (gr2_path, process_result) in processing_results {
-        match process_result {
-            Ok(proc_result) => {
-                result.gr2s_processed += 1;
-                if proc_result.glb_path.is_some() {
-                    result.glb_files_created += 1;
-                }
-                result.textures_extracted += proc_result.texture_paths.len();
+        // Collect results
+        for (internal_path, gr2_path, process_result) in processing_results {
+            match process_result {
+                Ok(proc_result) => {
+                    result
+                        .models
+                        .push(proc_result.to_bundle_model(internal_path));
+                    result.gr2s_processed += 1;
+                    if proc_result.glb_path.is_some() {
+                        result.glb_files_created += 1;
+                    }
+                    result.textures_extracted += proc_result.texture_paths.len();
 
-                // Add folder to results
-                if let Some(folder) = gr2_path.parent() {
-                    if !result.gr2_folders.contains(&folder.to_path_buf()) {
-                        result.gr2_folders.push(folder.to_path_buf());
+                    // Add the GR2's own folder (not where it was extracted)
+                    if let Some(folder) = proc_result.gr2_path.parent() {
+                        if !result.gr2_folders.contains(&folder.to_path_buf()) {
+                            result.gr2_folders.push(folder.to_path_buf());
+                        }
                     }
-                }
 
-                // Collect warnings
-                result.warnings.extend(proc_result.warnings);
-            }
-            Err(e) => {
-                let path_display = gr2_path.display();
-                result
-                    .warnings
-                    .push(format!("Failed to process {path_display}: {e}"));
+                    // Collect warnings
+                    result.warnings.extend(proc_result.warnings);
+                }
+                Err(e) => {
+                    let path_display = gr2_path.display();
+                    result
+                        .warnings
+                        .push(format!("Failed to process {path_display}: {e}"));
+                }
             }
         }
+
+        Ok(result)
     }
 
-    Ok(result)
+    /// Extract every file of a PAK, then process the GR2s among them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PAK can't be listed or extraction fails.
+    pub fn extract_all(
+        &self,
+        pak_path: &Path,
+        output_dir: &Path,
+        progress: ProgressCallback,
+    ) -> Result<SmartExtractionResult> {
+        let all_files = PakOperations::list(pak_path)?;
+        self.extract_files(pak_path, output_dir, &all_files, progress)
+    }
+}
+
+/// Extract specific files from a PAK with optional GR2 processing.
+///
+/// Shorthand for [`ExtractionPipeline::extract_files`].
+///
+/// # Arguments
+///
+/// * `pak_path` - Path to the source PAK file
+/// * `output_dir` - Directory where files will be extracted
+/// * `file_paths` - List of file paths within the PAK to extract
+/// * `options` - GR2 processing options
+/// * `progress` - Progress callback receiving [`PakProgress`] with phase and file info
+///
+/// # Errors
+///
+/// Returns an error if PAK extraction fails. Individual GR2 processing
+/// errors are collected in `SmartExtractionResult::warnings` and do not
+/// cause the entire operation to fail.
+pub fn extract_files_smart<P: AsRef<Path>, S: AsRef<str>>(
+    pak_path: P,
+    output_dir: P,
+    file_paths: &[S],
+    options: Gr2ExtractionOptions,
+    progress: ProgressCallback,
+) -> Result<SmartExtractionResult> {
+    ExtractionPipeline::new(options).extract_files(
+        pak_path.as_ref(),
+        output_dir.as_ref(),
+        file_paths,
+        progress,
+    )
 }
 
 /// Process a single GR2 file: move to subfolder, convert, extract textures.
@@ -251,22 +420,21 @@ fn process_single_gr2(
         cleanup_empty_parent_dirs(gr2_path, output_base);
     }
 
-    let mut result = Gr2ExtractionResult {
-        gr2_path: new_gr2_path.clone(),
-        glb_path: None,
-        texture_paths: Vec::new(),
-        warnings: Vec::new(),
-    };
+    let mut result = Gr2ExtractionResult::new(new_gr2_path.clone());
 
     let output_dir = &gr2_folder;
 
+    if options.record_meshes {
+        result.meshes = mesh_names(&new_gr2_path, &mut result.warnings);
+    }
+
     // Step 1: Convert GR2 to GLB
     if options.convert_to_glb {
         progress(&PakProgress::with_file(
-            PakPhase::WritingFiles,
+            PakPhase::ConvertingModels,
             completed.load(Ordering::Relaxed),
             total_gr2,
-            format!("Converting {} to GLB", gr2_filename),
+            format!("Converting {gr2_filename} to GLB"),
         ));
 
         let glb_path = new_gr2_path.with_extension("glb");
@@ -288,7 +456,7 @@ fn process_single_gr2(
             PakPhase::ExtractingTextures,
             completed.load(Ordering::Relaxed),
             total_gr2,
-            format!("Extracting textures for {}", folder_name),
+            format!("Extracting textures for {folder_name}"),
         ));
 
         let resolver = if let Some(ref game_data) = options.bg3_path {
@@ -300,20 +468,18 @@ fn process_single_gr2(
         if let Some(resolver) = resolver {
             match extract_textures_for_gr2(&new_gr2_path, resolver.database(), output_dir, options)
             {
-                Ok(textures) => {
+                Ok(mut textures) => {
                     // Step 3: Convert DDS to PNG if requested
                     if options.convert_to_png {
                         progress(&PakProgress::with_file(
                             PakPhase::ConvertingTextures,
                             completed.load(Ordering::Relaxed),
                             total_gr2,
-                            format!("Converting textures to PNG for {}", folder_name),
+                            format!("Converting textures to PNG for {folder_name}"),
                         ));
-                        result.texture_paths =
-                            convert_textures_to_png(&textures, options, &mut result.warnings);
-                    } else {
-                        result.texture_paths = textures;
+                        convert_textures_to_png(&mut textures, options, &mut result.warnings);
                     }
+                    result.set_textures(textures);
                 }
                 Err(e) => {
                     result
@@ -353,7 +519,7 @@ fn cleanup_empty_parent_dirs(file_path: &Path, base_dir: &Path) {
 
 /// Extract all files from a PAK with optional GR2 processing.
 ///
-/// Similar to `extract_files_smart` but extracts the entire PAK contents.
+/// Shorthand for [`ExtractionPipeline::extract_all`].
 ///
 /// # Errors
 ///
@@ -364,12 +530,311 @@ pub fn extract_pak_smart<P: AsRef<Path>>(
     options: Gr2ExtractionOptions,
     progress: ProgressCallback,
 ) -> Result<SmartExtractionResult> {
-    let pak_path = pak_path.as_ref();
-    let output_dir = output_dir.as_ref();
+    ExtractionPipeline::new(options).extract_all(pak_path.as_ref(), output_dir.as_ref(), progress)
+}
 
-    // List all files in the PAK
-    let all_files = PakOperations::list(pak_path)?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converter::{DdsFormat, convert_gltf_bytes_to_gr2, convert_lsx_to_lsf};
+    use crate::test_fixtures::triangle_gltf;
+    use image::{DynamicImage, RgbaImage};
+
+    const GR2_IN_PAK: &str = "Generated/Public/Shared/Assets/Characters/Tri_Body.GR2";
+    const DDS_IN_PAK: &str = "Generated/Public/Shared/Assets/Characters/Tri_Body_BM.DDS";
+
+    /// Visual, material and texture banks tying the GR2 to one DDS
+    fn merged_lsx() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="331" lslib_meta="v1,bswap_guids" />
+    <region id="VisualBank">
+        <node id="VisualBank">
+            <children>
+                <node id="Resource">
+                    <attribute id="ID" type="FixedString" value="visual-1" />
+                    <attribute id="Name" type="LSString" value="Tri_Body" />
+                    <attribute id="SourceFile" type="LSString" value="{GR2_IN_PAK}" />
+                    <children>
+                        <node id="Objects">
+                            <attribute id="MaterialID" type="FixedString" value="material-1" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+    <region id="MaterialBank">
+        <node id="MaterialBank">
+            <children>
+                <node id="Resource">
+                    <attribute id="ID" type="FixedString" value="material-1" />
+                    <attribute id="Name" type="LSString" value="Tri_Body_Mat" />
+                    <children>
+                        <node id="Texture2DParameters">
+                            <attribute id="ID" type="FixedString" value="texture-1" />
+                            <attribute id="ParameterName" type="FixedString" value="basecolor" />
+                        </node>
+                    </children>
+                </node>
+            </children>
+        </node>
+    </region>
+    <region id="TextureBank">
+        <node id="TextureBank">
+            <children>
+                <node id="Resource">
+                    <attribute id="ID" type="FixedString" value="texture-1" />
+                    <attribute id="Name" type="LSString" value="Tri_Body_BM" />
+                    <attribute id="SourceFile" type="LSString" value="{DDS_IN_PAK}" />
+                </node>
+            </children>
+        </node>
+    </region>
+</save>
+"#
+        )
+    }
+
+    /// `Models.pak` holding the GR2 and a text file, and a game data folder
+    /// whose `Shared.pak` maps the GR2 to a texture in `Textures.pak`
+    fn fixture(dir: &Path) -> (PathBuf, PathBuf) {
+        let write = |path: PathBuf, bytes: &[u8]| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, bytes).unwrap();
+        };
+
+        let models = dir.join("models");
+        let gr2 = convert_gltf_bytes_to_gr2(triangle_gltf().as_bytes()).unwrap();
+        write(models.join(GR2_IN_PAK), &gr2);
+        write(models.join("Public/Notes/readme.txt"), b"notes");
+        let pak = dir.join("Models.pak");
+        PakOperations::create(&models, &pak).unwrap();
+
+        let game_data = dir.join("Data");
+        let shared = dir.join("shared");
+        let lsx = dir.join("_merged.lsx");
+        std::fs::write(&lsx, merged_lsx()).unwrap();
+        let lsf = shared.join("Public/Shared/Content/Assets/Characters/[PAK]_Body/_merged.lsf");
+        std::fs::create_dir_all(lsf.parent().unwrap()).unwrap();
+        convert_lsx_to_lsf(&lsx, &lsf).unwrap();
+        std::fs::create_dir_all(&game_data).unwrap();
+        PakOperations::create(&shared, &game_data.join("Shared.pak")).unwrap();
+
+        let textures = dir.join("textures");
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 4, image::Rgba([9; 4])));
+        let dds = crate::converter::png_image_to_dds_bytes(&image, DdsFormat::Rgba).unwrap();
+        write(textures.join(DDS_IN_PAK), &dds);
+        PakOperations::create(&textures, &game_data.join("Textures.pak")).unwrap();
+
+        (pak, game_data)
+    }
+
+    /// Options as `pak extract` builds them from its flags
+    fn cli_options(
+        game_data: &Path,
+        convert_gr2: bool,
+        extract_textures: bool,
+        png: bool,
+        delete_gr2: bool,
+        keep_dds: bool,
+    ) -> Gr2ExtractionOptions {
+        let textures = extract_textures || png;
+        Gr2ExtractionOptions::new()
+            .with_convert_to_glb(convert_gr2)
+            .with_extract_textures(textures)
+            .with_extract_virtual_textures(textures)
+            .with_png_conversion(png)
+            .with_keep_original(!delete_gr2)
+            .with_keep_original_dds(keep_dds)
+            .with_bg3_path(Some(game_data))
+    }
 
-    // Extract with smart processing
-    extract_files_smart(pak_path, output_dir, &all_files, options, progress)
+    /// Sorted file names in `dir`
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Name, `--convert-gr2`, `--extract-textures`, `--png`, `--delete-gr2`,
+    /// `--keep-dds`, and the files left in the GR2's folder
+    type Case = (
+        &'static str,
+        bool,
+        bool,
+        bool,
+        bool,
+        bool,
+        &'static [&'static str],
+    );
+
+    #[test]
+    fn test_pipeline_flag_combinations() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pak, game_data) = fixture(dir.path());
+        let files = PakOperations::list(&pak).unwrap();
+
+        let cases: [Case; 8] = [
+            (
+                "convert",
+                true,
+                false,
+                false,
+                false,
+                false,
+                &["Tri_Body.GR2", "Tri_Body.glb"],
+            ),
+            (
+                "convert-delete",
+                true,
+                false,
+                false,
+                true,
+                false,
+                &["Tri_Body.glb"],
+            ),
+            (
+                "textures",
+                false,
+                true,
+                false,
+                false,
+                false,
+                &["Tri_Body.GR2", "Tri_Body_BM.DDS"],
+            ),
+            (
+                "png",
+                false,
+                false,
+                true,
+                false,
+                false,
+                &["Tri_Body.GR2", "Tri_Body_BM.png"],
+            ),
+            (
+                "png-keep-dds",
+                false,
+                false,
+                true,
+                false,
+                true,
+                &["Tri_Body.GR2", "Tri_Body_BM.DDS", "Tri_Body_BM.png"],
+            ),
+            // --delete-gr2 without a GLB keeps the GR2
+            (
+                "textures-delete",
+                false,
+                true,
+                false,
+                true,
+                false,
+                &["Tri_Body.GR2", "Tri_Body_BM.DDS"],
+            ),
+            (
+                "bundle",
+                true,
+                true,
+                false,
+                false,
+                false,
+                &["Tri_Body.GR2", "Tri_Body.glb", "Tri_Body_BM.DDS"],
+            ),
+            (
+                "bundle-delete-png-keep-dds",
+                true,
+                true,
+                true,
+                true,
+                true,
+                &["Tri_Body.glb", "Tri_Body_BM.DDS", "Tri_Body_BM.png"],
+            ),
+        ];
+
+        for (name, convert, textures, png, delete_gr2, keep_dds, expected) in cases {
+            let out = dir.path().join("out").join(name);
+            let options = cli_options(&game_data, convert, textures, png, delete_gr2, keep_dds);
+            let result = ExtractionPipeline::new(options)
+                .extract_files(&pak, &out, &files, &|_| {})
+                .unwrap();
+
+            assert_eq!(result.files_extracted, 2, "{name}");
+            assert_eq!(result.gr2s_processed, 1, "{name}");
+            assert_eq!(result.glb_files_created, usize::from(convert), "{name}");
+            assert_eq!(
+                result.textures_extracted,
+                usize::from(textures || png),
+                "{name}"
+            );
+            assert!(result.warnings.is_empty(), "{name}: {:?}", result.warnings);
+            assert_eq!(result.gr2_folders, vec![out.join("Tri_Body")], "{name}");
+
+            // The GR2 moved into its own folder and its old parents are gone
+            assert_eq!(file_names(&out.join("Tri_Body")), expected, "{name}");
+            assert!(!out.join("Generated").exists(), "{name}");
+            assert!(out.join("Public/Notes/readme.txt").exists(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_pipeline_without_processing_is_plain_extract() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pak, game_data) = fixture(dir.path());
+        let files = PakOperations::list(&pak).unwrap();
+
+        let pipeline =
+            ExtractionPipeline::new(cli_options(&game_data, false, false, false, true, false));
+        assert!(!pipeline.processes_gr2());
+        let out = dir.path().join("plain");
+        let result = pipeline.extract_files(&pak, &out, &files, &|_| {}).unwrap();
+
+        assert_eq!(result.files_extracted, 2);
+        assert_eq!(result.gr2s_processed, 0);
+        assert!(out.join(GR2_IN_PAK).exists());
+    }
+
+    #[test]
+    fn test_pipeline_writes_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pak, game_data) = fixture(dir.path());
+        let manifest = dir.path().join("bundle.json");
+
+        let pipeline =
+            ExtractionPipeline::new(cli_options(&game_data, true, true, false, false, false))
+                .with_manifest(Some(manifest.clone()));
+        let out = dir.path().join("out");
+        pipeline.extract_all(&pak, &out, &|_| {}).unwrap();
+
+        let saved = BundleManifest::load(&manifest).unwrap();
+        assert_eq!(saved.models.len(), 1);
+        let model = &saved.models[0];
+        assert_eq!(model.source_gr2, GR2_IN_PAK);
+        assert_eq!(model.glb, Some(out.join("Tri_Body/Tri_Body.glb")));
+        assert_eq!(model.meshes, vec!["Tri_Body".to_string()]);
+        assert_eq!(model.textures.len(), 1);
+    }
+
+    #[test]
+    fn test_pipeline_cancelled() {
+        let dir = tempfile::tempdir().unwrap();
+        let (pak, game_data) = fixture(dir.path());
+        let files = PakOperations::list(&pak).unwrap();
+        let manifest = dir.path().join("bundle.json");
+
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let pipeline =
+            ExtractionPipeline::new(cli_options(&game_data, true, false, false, false, false))
+                .with_manifest(Some(manifest.clone()));
+        let out = dir.path().join("out");
+        let result = pipeline.extract_files_cancellable(&pak, &out, &files, &cancel, &|_| {});
+
+        assert!(matches!(result, Err(crate::Error::Cancelled)));
+        assert!(!out.join("Tri_Body").exists());
+        assert!(!manifest.exists());
+    }
 }
@@ -0,0 +1,236 @@
+//! Effective file view over several PAKs in load order
+//!
+//! The game mounts PAKs one after another and a file in a later PAK
+//! replaces the same path in an earlier one. [`VfsView`] resolves paths the
+//! same way, case-insensitively, so tools can read "the file the game sees"
+//! without extracting anything.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::PakReaderCache;
+use crate::error::{Error, Result};
+use crate::utils::path_lookup_key;
+
+/// A file visible through a [`VfsView`]
+#[derive(Debug, Clone)]
+struct VfsEntry {
+    /// Path as stored in the winning PAK
+    path: String,
+    /// Index of the winning PAK in load order
+    pak: usize,
+}
+
+/// Priority-aware view over multiple PAKs
+///
+/// PAKs are given in load order: the last one has the highest priority.
+pub struct VfsView {
+    paks: Vec<PathBuf>,
+    /// Lookup key -> the highest-priority entry for that path
+    entries: HashMap<String, VfsEntry>,
+    cache: Mutex<PakReaderCache>,
+}
+
+impl VfsView {
+    /// Index the file tables of `paks_in_load_order`
+    ///
+    /// # Errors
+    /// Returns an error if any PAK's file table cannot be read.
+    pub fn new<P: AsRef<Path>>(paks_in_load_order: &[P]) -> Result<Self> {
+        let paks: Vec<PathBuf> = paks_in_load_order
+            .iter()
+            .map(|p| p.as_ref().to_path_buf())
+            .collect();
+        let mut cache = PakReaderCache::new(paks.len());
+        let mut entries = HashMap::new();
+
+        // Walk from the highest priority down so the first entry for a path wins
+        for (index, pak) in paks.iter().enumerate().rev() {
+            for (path, _) in cache.list_files(pak)? {
+                entries
+                    .entry(path_lookup_key(&path))
+                    .or_insert(VfsEntry { path, pak: index });
+            }
+        }
+
+        Ok(Self {
+            paks,
+            entries,
+            cache: Mutex::new(cache),
+        })
+    }
+
+    /// PAKs in load order
+    #[must_use]
+    pub fn paks(&self) -> &[PathBuf] {
+        &self.paks
+    }
+
+    /// Number of distinct files visible through the view
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no PAK contains any files
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether any PAK provides `internal_path`
+    #[must_use]
+    pub fn exists(&self, internal_path: &str) -> bool {
+        self.entry(internal_path).is_some()
+    }
+
+    /// The PAK whose copy of `internal_path` is used
+    #[must_use]
+    pub fn source_of(&self, internal_path: &str) -> Option<&Path> {
+        self.priority_of(internal_path)
+            .map(|index| self.paks[index].as_path())
+    }
+
+    /// Load-order index of the PAK whose copy of `internal_path` is used
+    #[must_use]
+    pub fn priority_of(&self, internal_path: &str) -> Option<usize> {
+        self.entry(internal_path).map(|entry| entry.pak)
+    }
+
+    /// Effective paths under `prefix`, sorted
+    ///
+    /// Matching ignores case and accepts `\` separators; an empty prefix
+    /// lists every file.
+    #[must_use]
+    pub fn list(&self, prefix: &str) -> Vec<String> {
+        let prefix = path_lookup_key(&prefix.replace('\\', "/"));
+        let mut paths: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(_, entry)| entry.path.clone())
+            .collect();
+        paths.sort_unstable();
+        paths
+    }
+
+    /// Read the highest-priority copy of `internal_path`
+    ///
+    /// # Errors
+    /// Returns [`Error::FileNotFoundInPak`] if no PAK provides the file, or
+    /// an error if it cannot be read.
+    pub fn read(&self, internal_path: &str) -> Result<Vec<u8>> {
+        let entry = self
+            .entry(internal_path)
+            .ok_or_else(|| Error::FileNotFoundInPak(internal_path.to_string()))?;
+        self.lock_cache()
+            .read_file(&self.paks[entry.pak], &entry.path)
+    }
+
+    /// Read several files, grouping the reads by PAK
+    ///
+    /// The result is keyed by the paths as given. Paths no PAK provides are
+    /// left out.
+    ///
+    /// # Errors
+    /// Returns an error if a PAK cannot be read.
+    pub fn read_many(&self, internal_paths: &[&str]) -> Result<HashMap<String, Vec<u8>>> {
+        let mut by_pak: HashMap<usize, Vec<(&str, &str)>> = HashMap::new();
+        for &requested in internal_paths {
+            if let Some(entry) = self.entry(requested) {
+                by_pak
+                    .entry(entry.pak)
+                    .or_default()
+                    .push((requested, entry.path.as_str()));
+            }
+        }
+
+        let mut cache = self.lock_cache();
+        let mut contents = HashMap::new();
+        for (pak, files) in by_pak {
+            let stored: Vec<&str> = files.iter().map(|(_, path)| *path).collect();
+            let mut data = cache.read_files_bulk(&self.paks[pak], &stored)?;
+            for (requested, path) in files {
+                if let Some(bytes) = data.remove(path) {
+                    contents.insert(requested.to_string(), bytes);
+                }
+            }
+        }
+        Ok(contents)
+    }
+
+    fn entry(&self, internal_path: &str) -> Option<&VfsEntry> {
+        self.entries
+            .get(&path_lookup_key(&internal_path.replace('\\', "/")))
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, PakReaderCache> {
+        // The cache holds no invariants a panicking reader could break
+        self.cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::CompressionSettings;
+    use crate::pak::PakBuilder;
+
+    #[test]
+    fn test_later_pak_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = CompressionSettings::default();
+        let base = dir.path().join("Shared.pak");
+        let mut builder = PakBuilder::write_to(&base).unwrap();
+        builder
+            .add_file("Public/Shared/Stats/Armor.txt", b"base", settings)
+            .unwrap();
+        builder
+            .add_file("Public/Shared/Stats/Weapon.txt", b"weapon", settings)
+            .unwrap();
+        builder.finish().unwrap();
+
+        let patch = dir.path().join("Patch.pak");
+        let mut builder = PakBuilder::write_to(&patch).unwrap();
+        builder
+            .add_file("Public/Shared/Stats/ARMOR.txt", b"patched", settings)
+            .unwrap();
+        builder.finish().unwrap();
+
+        let vfs = VfsView::new(&[&base, &patch]).unwrap();
+        assert_eq!(vfs.len(), 2);
+        assert_eq!(
+            vfs.read("Public/Shared/Stats/Armor.txt").unwrap(),
+            b"patched"
+        );
+        assert_eq!(
+            vfs.source_of("public\\shared\\stats\\armor.txt"),
+            Some(patch.as_path())
+        );
+        assert_eq!(
+            vfs.read("Public/Shared/Stats/Weapon.txt").unwrap(),
+            b"weapon"
+        );
+        assert!(!vfs.exists("Public/Shared/Stats/Spell.txt"));
+        assert_eq!(
+            vfs.list("public/shared/"),
+            [
+                "Public/Shared/Stats/ARMOR.txt",
+                "Public/Shared/Stats/Weapon.txt"
+            ]
+        );
+
+        let contents = vfs
+            .read_many(&["Public/Shared/Stats/Armor.txt", "Missing.txt"])
+            .unwrap();
+        assert_eq!(contents.len(), 1);
+        assert_eq!(contents["Public/Shared/Stats/Armor.txt"], b"patched");
+
+        // Reversing the load order flips the winner
+        let vfs = VfsView::new(&[&patch, &base]).unwrap();
+        assert_eq!(vfs.read("Public/Shared/Stats/Armor.txt").unwrap(), b"base");
+    }
+}
@@ -0,0 +1,65 @@
+//! Documents shared by unit tests
+
+use base64::Engine;
+
+/// A single `Config` region whose `root` node has `Name = "Test"`
+pub const CONFIG_LSX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+	<version major="4" minor="0" revision="9" build="331" />
+	<region id="Config">
+		<node id="root">
+			<attribute id="Name" type="LSString" value="Test" />
+		</node>
+	</region>
+</save>
+"#;
+
+/// A meta.lsx-like document: `Config/ModuleInfo` and an empty `Dependencies` region
+pub const MODULE_LSX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+	<version major="4" minor="0" revision="9" build="331" />
+	<region id="Config">
+		<node id="Config">
+			<children>
+				<node id="ModuleInfo">
+					<attribute id="Name" type="LSString" value="Test" />
+					<attribute id="Version64" type="int64" value="36028797018963968" />
+				</node>
+			</children>
+		</node>
+	</region>
+	<region id="Dependencies">
+		<node id="Dependencies" />
+	</region>
+</save>
+"#;
+
+/// A one-triangle glTF with an embedded buffer
+pub fn triangle_gltf() -> String {
+    let mut bin = Vec::new();
+    let floats: [f32; 24] = [
+        0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, // positions
+        0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, // normals
+        0.0, 0.0, 1.0, 0.0, 0.0, 1.0, // UVs
+    ];
+    for value in floats {
+        bin.extend_from_slice(&value.to_le_bytes());
+    }
+    for index in [0u16, 1, 2, 0] {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    let uri = base64::engine::general_purpose::STANDARD.encode(&bin);
+    format!(
+        r#"{{"asset":{{"version":"2.0"}},"scene":0,"scenes":[{{"nodes":[0]}}],
+"nodes":[{{"mesh":0,"name":"Tri_Body"}}],
+"meshes":[{{"name":"Tri_Body","primitives":[{{"attributes":{{"POSITION":0,"NORMAL":1,"TEXCOORD_0":2}},"indices":3}}]}}],
+"buffers":[{{"byteLength":{},"uri":"data:application/octet-stream;base64,{uri}"}}],
+"bufferViews":[{{"buffer":0,"byteOffset":0,"byteLength":36}},{{"buffer":0,"byteOffset":36,"byteLength":36}},
+{{"buffer":0,"byteOffset":72,"byteLength":24}},{{"buffer":0,"byteOffset":96,"byteLength":6}}],
+"accessors":[{{"bufferView":0,"componentType":5126,"count":3,"type":"VEC3","min":[0,0,0],"max":[1,1,0]}},
+{{"bufferView":1,"componentType":5126,"count":3,"type":"VEC3"}},
+{{"bufferView":2,"componentType":5126,"count":3,"type":"VEC2"}},
+{{"bufferView":3,"componentType":5123,"count":3,"type":"SCALAR"}}]}}"#,
+        bin.len()
+    )
+}
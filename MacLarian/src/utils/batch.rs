@@ -0,0 +1,153 @@
+//! Error handling for batch operations
+//!
+//! Batch operations take an [`ErrorPolicy`] and report every item they
+//! processed in a [`BatchOutcome`]: the successes plus each failed item with
+//! its error. [`ErrorCollector`] gathers failures from parallel workers.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{Error, Result};
+
+/// What a batch operation does when an item fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// Stop at the first failure; items already in progress are finished
+    FailFast,
+    /// Record the failure and keep going
+    #[default]
+    Collect,
+}
+
+/// Successes and failures of a batch operation
+#[derive(Debug)]
+pub struct BatchOutcome<T> {
+    /// Results of the items that succeeded
+    pub succeeded: Vec<T>,
+    /// Items that failed, with their errors
+    pub failed: Vec<(PathBuf, Error)>,
+    /// Whether a failure stopped the batch under [`ErrorPolicy::FailFast`]
+    pub stopped: bool,
+}
+
+impl<T> Default for BatchOutcome<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+            stopped: false,
+        }
+    }
+}
+
+impl<T> BatchOutcome<T> {
+    /// Whether no item failed
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Number of failed items
+    #[must_use]
+    pub fn failure_count(&self) -> usize {
+        self.failed.len()
+    }
+
+    /// The successes, or the first failure's error
+    ///
+    /// # Errors
+    /// Returns the error of the first failed item, if any.
+    pub fn into_result(self) -> Result<Vec<T>> {
+        match self.failed.into_iter().next() {
+            Some((_, error)) => Err(error),
+            None => Ok(self.succeeded),
+        }
+    }
+}
+
+/// Thread-safe failure collection for a batch operation
+///
+/// Workers call [`should_stop`](Self::should_stop) before starting an item and
+/// [`record`](Self::record) when one fails. Under [`ErrorPolicy::FailFast`]
+/// the first recorded failure makes `should_stop` return true.
+#[derive(Debug, Default)]
+pub struct ErrorCollector {
+    policy: ErrorPolicy,
+    failed: Mutex<Vec<(PathBuf, Error)>>,
+    tripped: AtomicBool,
+}
+
+impl ErrorCollector {
+    /// Create a collector for `policy`
+    #[must_use]
+    pub fn new(policy: ErrorPolicy) -> Self {
+        Self {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Record a failed item
+    pub fn record(&self, item: impl Into<PathBuf>, error: Error) {
+        let item = item.into();
+        tracing::debug!("Batch item {} failed: {error}", item.display());
+        if let Ok(mut failed) = self.failed.lock() {
+            failed.push((item, error));
+        }
+        if self.policy == ErrorPolicy::FailFast {
+            self.tripped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether remaining items should be skipped
+    #[must_use]
+    pub fn should_stop(&self) -> bool {
+        self.tripped.load(Ordering::SeqCst)
+    }
+
+    /// Number of failures recorded so far
+    #[must_use]
+    pub fn failure_count(&self) -> usize {
+        self.failed.lock().map_or(0, |failed| failed.len())
+    }
+
+    /// Combine the recorded failures with `succeeded`
+    #[must_use]
+    pub fn finish<T>(self, succeeded: Vec<T>) -> BatchOutcome<T> {
+        let stopped = self.should_stop();
+        let failed = self
+            .failed
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        BatchOutcome {
+            succeeded,
+            failed,
+            stopped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fail_fast_stops_after_first_failure() {
+        let collect = ErrorCollector::new(ErrorPolicy::Collect);
+        collect.record("a.pak", Error::Cancelled);
+        collect.record("b.pak", Error::Cancelled);
+        assert!(!collect.should_stop());
+        let outcome = collect.finish(vec![1]);
+        assert_eq!(outcome.failure_count(), 2);
+        assert!(!outcome.stopped);
+
+        let fail_fast = ErrorCollector::new(ErrorPolicy::FailFast);
+        assert!(!fail_fast.should_stop());
+        fail_fast.record("a.pak", Error::Cancelled);
+        assert!(fail_fast.should_stop());
+        let outcome = fail_fast.finish(Vec::<()>::new());
+        assert!(outcome.stopped);
+        assert!(matches!(outcome.into_result(), Err(Error::Cancelled)));
+    }
+}
@@ -0,0 +1,62 @@
+//! Cooperative cancellation for long-running operations
+//!
+//! A [`CancelToken`] is shared between the code that starts an operation (a
+//! GUI tab, the CLI's Ctrl-C handler) and the operation itself, which checks
+//! it between items. Clones share the same flag.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{Error, Result};
+
+/// Cheaply clonable cancellation flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every operation holding this token to stop
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Return early from an operation that has been cancelled
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if the token has been cancelled.
+    pub fn checkpoint(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_cancellation() {
+        let token = CancelToken::new();
+        let clone = token.clone();
+        assert!(clone.checkpoint().is_ok());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(matches!(clone.checkpoint(), Err(Error::Cancelled)));
+        assert!(!CancelToken::new().is_cancelled());
+    }
+}
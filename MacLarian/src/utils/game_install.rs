@@ -0,0 +1,387 @@
+//! BG3 installation discovery
+//!
+//! Probes the standard install locations (Steam, Mac App Store, GOG) and the
+//! user's Larian documents folder, returning the paths tools usually need.
+//! Steam libraries on other drives are found through Steam's
+//! `libraryfolders.vdf`. Set `MACPAK_BG3_PATH` to override detection.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides install detection
+pub const BG3_PATH_ENV: &str = "MACPAK_BG3_PATH";
+
+/// Where a detected installation came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstallSource {
+    /// `MACPAK_BG3_PATH` environment variable.
+    Environment,
+    /// Path supplied by the caller (e.g. `--bg3-path`).
+    Explicit,
+    /// Steam library.
+    Steam,
+    /// Mac App Store.
+    AppStore,
+    /// GOG.
+    Gog,
+}
+
+impl InstallSource {
+    /// Human-readable label
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Environment => BG3_PATH_ENV,
+            Self::Explicit => "explicit path",
+            Self::Steam => "Steam",
+            Self::AppStore => "Mac App Store",
+            Self::Gog => "GOG",
+        }
+    }
+}
+
+/// A detected BG3 installation
+#[derive(Clone, Debug)]
+pub struct GameInstall {
+    /// How the installation was found.
+    pub source: InstallSource,
+    /// Game `Data` directory (contains `Shared.pak`, `Gustav.pak`, ...).
+    pub data_dir: PathBuf,
+    /// Base game `.pak` files in the data directory, sorted by name.
+    pub base_paks: Vec<PathBuf>,
+    /// User `Mods` directory, if it exists.
+    pub mods_dir: Option<PathBuf>,
+    /// User `PlayerProfiles` directory, if it exists.
+    pub player_profiles_dir: Option<PathBuf>,
+    /// Active profile's `modsettings.lsx`, if it exists.
+    pub modsettings: Option<PathBuf>,
+}
+
+impl GameInstall {
+    /// Build install info from a game path
+    ///
+    /// Accepts the `Data` directory itself, the install folder containing it,
+    /// or the macOS `.app` bundle. Returns `None` if no `Shared.pak` is found.
+    #[must_use]
+    pub fn from_path(path: &Path, source: InstallSource) -> Option<Self> {
+        let data_dir = resolve_data_dir(path)?;
+        let base_paks = list_paks(&data_dir);
+        let user_dir = user_data_dir();
+
+        let mods_dir = user_dir
+            .as_ref()
+            .map(|d| d.join("Mods"))
+            .filter(|p| p.is_dir());
+        let player_profiles_dir = user_dir
+            .as_ref()
+            .map(|d| d.join("PlayerProfiles"))
+            .filter(|p| p.is_dir());
+        let modsettings = player_profiles_dir
+            .as_ref()
+            .map(|d| d.join("Public").join("modsettings.lsx"))
+            .filter(|p| p.is_file());
+
+        Some(Self {
+            source,
+            data_dir,
+            base_paks,
+            mods_dir,
+            player_profiles_dir,
+            modsettings,
+        })
+    }
+}
+
+/// Folder of the game inside a Steam library
+const STEAM_GAME_DIR: &str = "steamapps/common/Baldurs Gate 3";
+
+/// Find the BG3 installation
+///
+/// Checks `MACPAK_BG3_PATH` first, then the standard Steam, Mac App Store and
+/// GOG locations for the current platform, then every Steam library listed in
+/// Steam's `libraryfolders.vdf`.
+#[must_use]
+pub fn find_game_install() -> Option<GameInstall> {
+    let mut candidates: Vec<(PathBuf, InstallSource)> = candidate_paths()
+        .into_iter()
+        .map(|(path, source)| (PathBuf::from(shellexpand::tilde(path).as_ref()), source))
+        .collect();
+    for root in steam_roots() {
+        let vdf = root.join("steamapps").join("libraryfolders.vdf");
+        if let Ok(text) = std::fs::read_to_string(&vdf) {
+            candidates.extend(
+                parse_library_folders(&text)
+                    .iter()
+                    .map(|library| (steam_game_path(library), InstallSource::Steam)),
+            );
+        }
+    }
+    find_game_install_in(std::env::var_os(BG3_PATH_ENV), candidates)
+}
+
+/// [`find_game_install`] with the environment override and the candidate
+/// locations passed in
+fn find_game_install_in(
+    env_path: Option<OsString>,
+    candidates: Vec<(PathBuf, InstallSource)>,
+) -> Option<GameInstall> {
+    if let Some(path) = env_path.filter(|p| !p.is_empty()) {
+        let expanded = shellexpand::tilde(&path.to_string_lossy()).into_owned();
+        return GameInstall::from_path(Path::new(&expanded), InstallSource::Environment);
+    }
+
+    candidates
+        .into_iter()
+        .find_map(|(path, source)| GameInstall::from_path(&path, source))
+}
+
+/// Standard install locations for the current platform
+fn candidate_paths() -> Vec<(&'static str, InstallSource)> {
+    let mut paths = Vec::new();
+
+    if cfg!(target_os = "macos") {
+        paths.extend([
+            (
+                "~/Library/Application Support/Steam/steamapps/common/Baldurs Gate 3/Baldur's Gate 3.app",
+                InstallSource::Steam,
+            ),
+            ("/Applications/Baldur's Gate 3.app", InstallSource::AppStore),
+            (
+                "/Applications/GOG Games/Baldur's Gate 3.app",
+                InstallSource::Gog,
+            ),
+            ("~/Applications/Baldur's Gate 3.app", InstallSource::Gog),
+        ]);
+    } else if cfg!(target_os = "windows") {
+        paths.extend([
+            (
+                "C:/Program Files (x86)/Steam/steamapps/common/Baldurs Gate 3",
+                InstallSource::Steam,
+            ),
+            ("C:/GOG Games/Baldurs Gate 3", InstallSource::Gog),
+            (
+                "C:/Program Files (x86)/GOG Galaxy/Games/Baldurs Gate 3",
+                InstallSource::Gog,
+            ),
+        ]);
+    } else {
+        paths.extend([
+            (
+                "~/.steam/steam/steamapps/common/Baldurs Gate 3",
+                InstallSource::Steam,
+            ),
+            (
+                "~/.local/share/Steam/steamapps/common/Baldurs Gate 3",
+                InstallSource::Steam,
+            ),
+        ]);
+    }
+
+    paths
+}
+
+/// Steam installs whose `libraryfolders.vdf` lists the user's libraries
+fn steam_roots() -> Vec<PathBuf> {
+    let roots: &[&str] = if cfg!(target_os = "macos") {
+        &["~/Library/Application Support/Steam"]
+    } else if cfg!(target_os = "windows") {
+        &["C:/Program Files (x86)/Steam"]
+    } else {
+        &["~/.steam/steam", "~/.local/share/Steam"]
+    };
+    roots
+        .iter()
+        .map(|root| PathBuf::from(shellexpand::tilde(root).as_ref()))
+        .collect()
+}
+
+/// Where BG3 would be in the Steam library at `library`
+fn steam_game_path(library: &Path) -> PathBuf {
+    let game = library.join(STEAM_GAME_DIR);
+    if cfg!(target_os = "macos") {
+        game.join("Baldur's Gate 3.app")
+    } else {
+        game
+    }
+}
+
+/// Library paths from the text of Steam's `libraryfolders.vdf`
+///
+/// Each library is a block with a `"path"` key; values use backslash
+/// escapes, so Windows paths appear as `"D:\\SteamLibrary"`.
+fn parse_library_folders(vdf: &str) -> Vec<PathBuf> {
+    vdf.lines()
+        .filter_map(|line| {
+            let mut tokens = quoted_tokens(line).into_iter();
+            let key = tokens.next()?;
+            let value = tokens.next()?;
+            (key.eq_ignore_ascii_case("path") && !value.is_empty()).then(|| PathBuf::from(value))
+        })
+        .collect()
+}
+
+/// The quoted strings on one line of a VDF file, unescaped
+fn quoted_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '"' {
+            continue;
+        }
+        let mut token = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => token.extend(chars.next()),
+                _ => token.push(c),
+            }
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Find the `Data` directory for a path (the directory itself, `Data/`, or `.app/Contents/Data`)
+fn resolve_data_dir(path: &Path) -> Option<PathBuf> {
+    [
+        path.to_path_buf(),
+        path.join("Data"),
+        path.join("Contents").join("Data"),
+    ]
+    .into_iter()
+    .find(|p| p.join("Shared.pak").is_file())
+}
+
+/// List `.pak` files directly inside a directory
+fn list_paks(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut paks: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("pak"))
+        })
+        .collect();
+    paks.sort();
+    paks
+}
+
+/// The user's Larian data folder (holds `Mods` and `PlayerProfiles`)
+fn user_data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        PathBuf::from(std::env::var_os("LOCALAPPDATA")?).join("Larian Studios")
+    } else {
+        PathBuf::from(std::env::var_os("HOME")?)
+            .join("Documents")
+            .join("Larian Studios")
+    };
+    Some(base.join("Baldur's Gate 3"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game `Data` folder under `root` with the given PAKs
+    fn data_dir(root: &Path, paks: &[&str]) -> PathBuf {
+        let data = root.join("Data");
+        std::fs::create_dir_all(&data).unwrap();
+        for pak in paks {
+            std::fs::write(data.join(pak), b"").unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn test_env_override() {
+        let env_dir = tempfile::tempdir().unwrap();
+        let data = data_dir(env_dir.path(), &["Shared.pak", "Gustav.pak", "notes.txt"]);
+        let other = tempfile::tempdir().unwrap();
+        data_dir(other.path(), &["Shared.pak"]);
+        let candidates = || vec![(other.path().to_path_buf(), InstallSource::Steam)];
+
+        let install = find_game_install_in(Some(env_dir.path().into()), candidates()).unwrap();
+        assert_eq!(install.source, InstallSource::Environment);
+        assert_eq!(install.data_dir, data);
+        assert_eq!(
+            install.base_paks,
+            vec![data.join("Gustav.pak"), data.join("Shared.pak")]
+        );
+
+        // An empty variable is ignored
+        let install = find_game_install_in(Some(OsString::new()), candidates()).unwrap();
+        assert_eq!(install.source, InstallSource::Steam);
+
+        // A variable pointing somewhere without the game doesn't fall back
+        let empty = tempfile::tempdir().unwrap();
+        assert!(find_game_install_in(Some(empty.path().into()), candidates()).is_none());
+    }
+
+    #[test]
+    fn test_parse_library_folders() {
+        let vdf = r#""libraryfolders"
+{
+	"0"
+	{
+		"path"		"C:\\Program Files (x86)\\Steam"
+		"label"		""
+		"contentid"		"4372394437475452545"
+		"apps"
+		{
+			"228980"		"369312453"
+		}
+	}
+	"1"
+	{
+		"path"		"D:\\Games\\Steam \"Library\""
+		"apps"
+		{
+			"1086940"		"151009237402"
+		}
+	}
+	"2"
+	{
+		"PATH"		"/mnt/games/SteamLibrary"
+	}
+}
+"#;
+        assert_eq!(
+            parse_library_folders(vdf),
+            vec![
+                PathBuf::from(r"C:\Program Files (x86)\Steam"),
+                PathBuf::from(r#"D:\Games\Steam "Library""#),
+                PathBuf::from("/mnt/games/SteamLibrary"),
+            ]
+        );
+        assert!(parse_library_folders("").is_empty());
+        assert!(parse_library_folders(r#""path"		"""#).is_empty());
+    }
+
+    #[test]
+    fn test_steam_library_install() {
+        let library = tempfile::tempdir().unwrap();
+        let game = steam_game_path(library.path());
+        let data = if cfg!(target_os = "macos") {
+            data_dir(&game.join("Contents"), &["Shared.pak"])
+        } else {
+            data_dir(&game, &["Shared.pak"])
+        };
+        let missing = tempfile::tempdir().unwrap();
+
+        let install = find_game_install_in(
+            None,
+            vec![
+                (steam_game_path(missing.path()), InstallSource::Steam),
+                (game, InstallSource::Steam),
+            ],
+        )
+        .unwrap();
+        assert_eq!(install.source, InstallSource::Steam);
+        assert_eq!(install.data_dir, data);
+    }
+}
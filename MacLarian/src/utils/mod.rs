@@ -1,5 +1,16 @@
 //! Utility functions
 
+pub mod batch;
+pub mod cancel;
+pub mod game_install;
 pub mod path;
+pub mod uuid;
 
-pub use path::normalize_path;
+pub use self::uuid::{
+    GuidByteOrder, UuidFormat, byte_swap_guid, classify_uuid, convert_uuid, format_guid,
+    format_uuid, generate_uuid, generate_uuids, guid_search_forms, parse_guid,
+};
+pub use batch::{BatchOutcome, ErrorCollector, ErrorPolicy};
+pub use cancel::CancelToken;
+pub use game_install::{BG3_PATH_ENV, GameInstall, InstallSource, find_game_install};
+pub use path::{is_nfc, normalize_path, open_file, path_lookup_key, paths_match, to_nfc};
@@ -1,12 +1,50 @@
 //! Path utilities
 
+use std::borrow::Cow;
+use std::fs::File;
 use std::path::Path;
 
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::{Error, Result};
+
 /// Normalize path separators to forward slashes (for PAK files)
 pub fn normalize_path<P: AsRef<Path>>(path: P) -> String {
     path.as_ref().to_string_lossy().replace('\\', "/")
 }
 
+/// Unicode NFC form of a PAK path
+///
+/// macOS file systems return decomposed (NFD) file names, e.g. `e` followed by
+/// a combining acute accent for `é`. The game and the paths referenced inside
+/// LSX files use the composed (NFC) form.
+#[must_use]
+pub fn to_nfc(path: &str) -> Cow<'_, str> {
+    if is_nfc(path) {
+        Cow::Borrowed(path)
+    } else {
+        Cow::Owned(path.nfc().collect())
+    }
+}
+
+/// Whether a path is already in Unicode NFC form
+#[must_use]
+pub fn is_nfc(path: &str) -> bool {
+    unicode_normalization::is_nfc(path)
+}
+
+/// Key for looking up PAK paths regardless of case and Unicode normalization
+#[must_use]
+pub fn path_lookup_key(path: &str) -> String {
+    to_nfc(path).to_lowercase()
+}
+
+/// Whether two PAK paths match, ignoring case and Unicode normalization
+#[must_use]
+pub fn paths_match(a: &str, b: &str) -> bool {
+    path_lookup_key(a) == path_lookup_key(b)
+}
+
 /// Get relative path and normalize separators
 pub fn relative_path<P: AsRef<Path>>(path: P, base: P) -> Option<String> {
     path.as_ref()
@@ -14,3 +52,36 @@ pub fn relative_path<P: AsRef<Path>>(path: P, base: P) -> Option<String> {
         .ok()
         .map(normalize_path)
 }
+
+/// Open a file for reading, reporting a missing file as [`Error::FileNotFound`]
+///
+/// # Errors
+/// Returns [`Error::FileNotFound`] if the file doesn't exist, or [`Error::Io`]
+/// if it cannot be opened.
+pub fn open_file<P: AsRef<Path>>(path: P) -> Result<File> {
+    let path = path.as_ref();
+    File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => Error::FileNotFound {
+            path: path.to_path_buf(),
+        },
+        _ => Error::Io(e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nfc_paths() {
+        let decomposed = "Public/Mod/Cafe\u{301}.lsx";
+        let composed = "Public/Mod/Caf\u{e9}.lsx";
+        assert!(!is_nfc(decomposed));
+        assert_eq!(to_nfc(decomposed), composed);
+        assert!(matches!(to_nfc(composed), Cow::Borrowed(_)));
+
+        assert!(paths_match(decomposed, "public/mod/CAF\u{c9}.LSX"));
+        assert!(paths_match("Stra\u{df}e.txt", "STRA\u{df}E.TXT"));
+        assert!(!paths_match(composed, "Public/Mod/Cafe.lsx"));
+    }
+}
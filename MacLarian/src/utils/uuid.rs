@@ -0,0 +1,273 @@
+//! UUID generation and GUID representation helpers
+//!
+//! BG3 data uses three textual forms of the same 16 bytes:
+//! - hyphenated (`8-4-4-4-12`), as written in LSX files
+//! - bare (32 hex digits, no hyphens)
+//! - byte-swapped, the order LSF files store GUIDs in when `bswap_guids` is set
+//!
+//! Searches for a GUID should look for both byte orders, see [`guid_search_forms`].
+
+/// Text format for generated UUIDs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UuidFormat {
+    /// Hyphenated `8-4-4-4-12`, used for `FixedString` GUIDs.
+    Standard,
+    /// 32 hex digits without hyphens.
+    Compact,
+    /// `TranslatedString` handle (`h` prefix, `g` separators).
+    Larian,
+}
+
+/// Generate a new random UUID (v4) in the given format
+#[must_use]
+pub fn generate_uuid(format: UuidFormat) -> String {
+    format_uuid(uuid::Uuid::new_v4().as_bytes(), format)
+}
+
+/// Generate `count` random UUIDs in the given format
+#[must_use]
+pub fn generate_uuids(format: UuidFormat, count: usize) -> Vec<String> {
+    (0..count).map(|_| generate_uuid(format)).collect()
+}
+
+/// Format GUID bytes (textual order) in the given format
+#[must_use]
+pub fn format_uuid(bytes: &[u8; 16], format: UuidFormat) -> String {
+    let uuid = uuid::Uuid::from_bytes(*bytes);
+
+    match format {
+        UuidFormat::Standard => uuid.hyphenated().to_string(),
+        UuidFormat::Compact => uuid.simple().to_string(),
+        UuidFormat::Larian => {
+            let simple = uuid.simple().to_string();
+            format!(
+                "h{}g{}g{}g{}g{}",
+                &simple[0..8],
+                &simple[8..12],
+                &simple[12..16],
+                &simple[16..20],
+                &simple[20..32]
+            )
+        }
+    }
+}
+
+/// Work out which format a pasted value is written in
+///
+/// Unlike [`parse_guid`], the grouping must match the format exactly, so a
+/// handle with misplaced separators or a hyphenated GUID with a missing digit
+/// is rejected. Surrounding whitespace and braces are ignored. Returns `None`
+/// for anything that isn't a UUID or handle.
+#[must_use]
+pub fn classify_uuid(value: &str) -> Option<UuidFormat> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('{')
+        .and_then(|v| v.strip_suffix('}'))
+        .unwrap_or(value);
+
+    let is_hex_groups = |groups: &[&str]| {
+        const LENGTHS: [usize; 5] = [8, 4, 4, 4, 12];
+        groups.len() == LENGTHS.len()
+            && groups.iter().zip(LENGTHS).all(|(group, len)| {
+                group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit())
+            })
+    };
+
+    let is_handle = value
+        .strip_prefix('h')
+        .is_some_and(|handle| is_hex_groups(&handle.split('g').collect::<Vec<_>>()));
+    if is_handle {
+        return Some(UuidFormat::Larian);
+    }
+    if is_hex_groups(&value.split('-').collect::<Vec<_>>()) {
+        return Some(UuidFormat::Standard);
+    }
+    if value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(UuidFormat::Compact);
+    }
+    None
+}
+
+/// Rewrite a UUID or handle in another format
+///
+/// Returns `None` if `value` isn't recognized by [`classify_uuid`].
+#[must_use]
+pub fn convert_uuid(value: &str, format: UuidFormat) -> Option<String> {
+    classify_uuid(value)?;
+    parse_guid(value).map(|bytes| format_uuid(&bytes, format))
+}
+
+/// Parse a GUID in hyphenated, bare, braced or handle (`h...g...`) form
+///
+/// Returns the 16 bytes in textual order, or `None` if the value isn't 32 hex digits.
+#[must_use]
+pub fn parse_guid(value: &str) -> Option<[u8; 16]> {
+    let value = value.trim().trim_start_matches('{').trim_end_matches('}');
+    let hex: String = match value.strip_prefix('h') {
+        Some(handle) if handle.contains('g') => handle.split('g').collect(),
+        _ => value.chars().filter(|c| *c != '-').collect(),
+    };
+
+    if hex.len() != 32 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut bytes = [0u8; 16];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// Format GUID bytes as hyphenated lowercase text
+#[must_use]
+pub fn format_guid(bytes: &[u8; 16]) -> String {
+    format_uuid(bytes, UuidFormat::Standard)
+}
+
+/// Convert between textual and LSF (`bswap_guids`) byte order
+///
+/// Reverses the first three fields and swaps each byte pair in the last
+/// eight bytes. The swap is its own inverse.
+#[must_use]
+pub fn byte_swap_guid(bytes: &[u8; 16]) -> [u8; 16] {
+    [
+        bytes[3], bytes[2], bytes[1], bytes[0], bytes[5], bytes[4], bytes[7], bytes[6], bytes[9],
+        bytes[8], bytes[11], bytes[10], bytes[13], bytes[12], bytes[15], bytes[14],
+    ]
+}
+
+/// Byte order a GUID matched in, see [`guid_search_forms`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GuidByteOrder {
+    /// The order the GUID was searched for in.
+    Canonical,
+    /// Swapped as by [`byte_swap_guid`], how LSF files store GUIDs.
+    ByteSwapped,
+}
+
+impl GuidByteOrder {
+    /// Short human-readable label
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Canonical => "canonical",
+            Self::ByteSwapped => "byte-swapped",
+        }
+    }
+}
+
+/// Hyphenated lowercase forms to search for to find every copy of a GUID
+///
+/// Returns the GUID as given, then byte-swapped, so data that stores it in
+/// LSF order is found too. A GUID that is unchanged by swapping is returned
+/// once. Returns `None` if `value` isn't a GUID (see [`parse_guid`]).
+#[must_use]
+pub fn guid_search_forms(value: &str) -> Option<Vec<(GuidByteOrder, String)>> {
+    let bytes = parse_guid(value)?;
+    let swapped = byte_swap_guid(&bytes);
+
+    let mut forms = vec![(GuidByteOrder::Canonical, format_guid(&bytes))];
+    if swapped != bytes {
+        forms.push((GuidByteOrder::ByteSwapped, format_guid(&swapped)));
+    }
+    Some(forms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_guid_representations() {
+        let bytes = parse_guid("12345678-9abc-def0-1122-334455667788").unwrap();
+        assert_eq!(parse_guid("123456789ABCDEF01122334455667788"), Some(bytes));
+        assert_eq!(
+            parse_guid("h12345678g9abcgdef0g1122g334455667788"),
+            Some(bytes)
+        );
+
+        let swapped = byte_swap_guid(&bytes);
+        assert_eq!(
+            format_guid(&swapped),
+            "78563412-bc9a-f0de-2211-443366558877"
+        );
+        assert_eq!(byte_swap_guid(&swapped), bytes);
+
+        assert!(parse_guid("not-a-guid").is_none());
+        assert!(generate_uuid(UuidFormat::Larian).starts_with('h'));
+
+        assert_eq!(
+            guid_search_forms("h12345678g9abcgdef0g1122g334455667788"),
+            Some(vec![
+                (
+                    GuidByteOrder::Canonical,
+                    "12345678-9abc-def0-1122-334455667788".to_string()
+                ),
+                (
+                    GuidByteOrder::ByteSwapped,
+                    "78563412-bc9a-f0de-2211-443366558877".to_string()
+                ),
+            ])
+        );
+        assert_eq!(
+            guid_search_forms("00000000-0000-0000-0000-000000000000").map(|f| f.len()),
+            Some(1)
+        );
+        assert!(guid_search_forms("1234").is_none());
+    }
+
+    #[test]
+    fn test_classify_and_convert() {
+        assert_eq!(
+            classify_uuid("12345678-9abc-def0-1122-334455667788"),
+            Some(UuidFormat::Standard)
+        );
+        assert_eq!(
+            classify_uuid(" {12345678-9ABC-DEF0-1122-334455667788} "),
+            Some(UuidFormat::Standard)
+        );
+        assert_eq!(
+            classify_uuid("123456789abcdef01122334455667788"),
+            Some(UuidFormat::Compact)
+        );
+        assert_eq!(
+            classify_uuid("h12345678g9abcgdef0g1122g334455667788"),
+            Some(UuidFormat::Larian)
+        );
+
+        // Right digit count, wrong grouping
+        assert_eq!(classify_uuid("1234567-89abc-def0-1122-334455667788"), None);
+        assert_eq!(classify_uuid("h123456789abcgdef0g1122g33445566778"), None);
+        assert_eq!(classify_uuid("12345678-9abc-def0-1122-33445566778z"), None);
+        assert_eq!(classify_uuid(""), None);
+
+        assert_eq!(
+            convert_uuid(
+                "h12345678g9abcgdef0g1122g334455667788",
+                UuidFormat::Standard
+            )
+            .as_deref(),
+            Some("12345678-9abc-def0-1122-334455667788")
+        );
+        assert_eq!(
+            convert_uuid("12345678-9ABC-DEF0-1122-334455667788", UuidFormat::Larian).as_deref(),
+            Some("h12345678g9abcgdef0g1122g334455667788")
+        );
+        assert_eq!(
+            convert_uuid("12345678-9abc-def0-1122-334455667788", UuidFormat::Compact).as_deref(),
+            Some("123456789abcdef01122334455667788")
+        );
+        assert_eq!(convert_uuid("h1234", UuidFormat::Standard), None);
+
+        let handles = generate_uuids(UuidFormat::Larian, 3);
+        assert_eq!(handles.len(), 3);
+        assert!(
+            handles
+                .iter()
+                .all(|h| classify_uuid(h) == Some(UuidFormat::Larian))
+        );
+    }
+}
@@ -11,8 +11,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use super::types::{VTexPhase, VTexProgress};
 use super::utils::find_gts_path;
-use super::{GtsFile, VirtualTextureExtractor};
+use super::{ExtractOptions, GtsFile, VirtualTextureExtractor};
 use crate::error::Error;
+use crate::utils::{BatchOutcome, CancelToken, ErrorCollector, ErrorPolicy};
 
 /// Result of extracting a GTS/GTP file
 #[derive(Debug, Clone)]
@@ -21,6 +22,8 @@ pub struct GtsExtractResult {
     pub texture_count: usize,
     /// Number of GTP files processed
     pub gtp_count: usize,
+    /// GTP filenames that were extracted, one per texture
+    pub textures: Vec<String>,
 }
 
 /// Result of batch extraction
@@ -28,12 +31,14 @@ pub struct GtsExtractResult {
 pub struct BatchExtractResult {
     /// Number of successful extractions
     pub success_count: usize,
-    /// Number of failed extractions
+    /// Number of GTS files, or GTP files within them, that failed
     pub error_count: usize,
     /// Total textures extracted
     pub texture_count: usize,
     /// Messages for each file processed
     pub results: Vec<String>,
+    /// Whether the batch was cancelled before every file was processed
+    pub cancelled: bool,
 }
 
 /// Extract textures from a GTS file (or single GTP with its GTS)
@@ -61,7 +66,69 @@ where
     P: AsRef<Path>,
     F: Fn(&VTexProgress),
 {
-    let input_path = input_path.as_ref();
+    extract_gts_textures(
+        input_path,
+        output_dir,
+        |_| true,
+        &ExtractOptions::default(),
+        progress,
+    )
+}
+
+/// Extract only some of the textures of a GTS file
+///
+/// Like [`extract_gts_file`], but for GTS input only the page files for which
+/// `include` returns true are extracted. `include` is given the GTP filename
+/// as listed by [`list_gts`](super::list_gts). A single GTP input is always
+/// extracted. Selecting nothing is not an error: the result is simply empty.
+///
+/// `options` selects the layers and the output naming; with
+/// [`VTexNaming::LsLib`](super::VTexNaming::LsLib) the DDS files are written
+/// straight into the output directory instead of per-GTS/GTP folders.
+///
+/// # Errors
+/// Returns an error if the GTS/GTP file cannot be read, or if none of the
+/// selected GTP files could be extracted.
+pub fn extract_gts_textures<P, N, F>(
+    input_path: P,
+    output_dir: Option<&Path>,
+    include: N,
+    options: &ExtractOptions,
+    progress: F,
+) -> Result<GtsExtractResult, Error>
+where
+    P: AsRef<Path>,
+    N: Fn(&str) -> bool,
+    F: Fn(&VTexProgress),
+{
+    let collector = ErrorCollector::new(ErrorPolicy::Collect);
+    let result = extract_gts_pages(
+        input_path.as_ref(),
+        output_dir,
+        &include,
+        options,
+        &progress,
+        &collector,
+    );
+    for (gtp_path, e) in collector.finish(Vec::<()>::new()).failed {
+        tracing::warn!("Failed to extract {}: {e}", gtp_path.display());
+    }
+    result
+}
+
+/// Extract the selected GTP files of a GTS, recording each GTP that fails in
+/// `collector`
+///
+/// Fails only if the GTS can't be read or none of the selected GTPs could be
+/// extracted.
+fn extract_gts_pages(
+    input_path: &Path,
+    output_dir: Option<&Path>,
+    include: &dyn Fn(&str) -> bool,
+    options: &ExtractOptions,
+    progress: &dyn Fn(&VTexProgress),
+    collector: &ErrorCollector,
+) -> Result<GtsExtractResult, Error> {
     let input_path_str = input_path.to_string_lossy();
     let input_ext = input_path
         .extension()
@@ -84,11 +151,16 @@ where
     };
 
     // Create subdirectory based on input filename
-    let input_stem = input_path.file_stem().map_or_else(
-        || "textures".to_string(),
-        |n| n.to_string_lossy().to_string(),
-    );
-    let texture_output_dir = output_path.join(&input_stem);
+    let per_file_folders = options.naming.per_file_folders();
+    let texture_output_dir = if per_file_folders {
+        let input_stem = input_path.file_stem().map_or_else(
+            || "textures".to_string(),
+            |n| n.to_string_lossy().to_string(),
+        );
+        output_path.join(&input_stem)
+    } else {
+        output_path
+    };
 
     std::fs::create_dir_all(&texture_output_dir).map_err(|e| {
         Error::Io(std::io::Error::other(format!(
@@ -105,67 +177,101 @@ where
             "Extracting GTP",
         ));
 
-        VirtualTextureExtractor::extract_with_gts(input_path, &gts_path, &texture_output_dir)?;
+        let count = VirtualTextureExtractor::extract_layers(
+            input_path,
+            &gts_path,
+            &texture_output_dir,
+            options,
+        )?
+        .len();
 
         progress(&VTexProgress::new(VTexPhase::Complete, 1, 1));
 
-        // Count output files
-        let count = std::fs::read_dir(&texture_output_dir)
-            .map(|entries| entries.filter_map(std::result::Result::ok).count())
-            .unwrap_or(0);
+        let gtp_name = input_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
 
         Ok(GtsExtractResult {
             texture_count: count,
             gtp_count: 1,
+            textures: vec![gtp_name],
         })
     } else {
         // Full GTS mode: extract all GTPs referenced by this GTS
         let gts = GtsFile::open(&gts_path)?;
 
         let gts_dir = gts_path.parent().unwrap_or(Path::new("."));
-        let total_page_files = gts.page_files.len();
 
-        if total_page_files == 0 {
+        if gts.page_files.is_empty() {
             return Err(Error::InvalidFormat(
                 "No page files found in GTS".to_string(),
             ));
         }
 
-        let mut extracted_count = 0;
+        let selected: Vec<_> = gts
+            .page_files
+            .iter()
+            .enumerate()
+            .filter(|(_, page_file)| include(&page_file.filename))
+            .collect();
+        let total_page_files = selected.len();
+
+        if total_page_files == 0 {
+            progress(&VTexProgress::new(VTexPhase::Complete, 0, 0));
+            return Ok(GtsExtractResult {
+                texture_count: 0,
+                gtp_count: 0,
+                textures: Vec::new(),
+            });
+        }
+
+        let mut extracted = Vec::new();
         let mut failed_count = 0;
 
-        for (i, page_file) in gts.page_files.iter().enumerate() {
+        for (n, (i, page_file)) in selected.into_iter().enumerate() {
+            if collector.should_stop() {
+                break;
+            }
             let gtp_path = gts_dir.join(&page_file.filename);
 
             progress(&VTexProgress::with_file(
                 VTexPhase::ExtractingTiles,
-                i + 1,
+                n + 1,
                 total_page_files,
                 &page_file.filename,
             ));
 
             if gtp_path.exists() {
                 // Create a subdirectory for this GTP's output
-                let gtp_stem = Path::new(&page_file.filename)
-                    .file_stem()
-                    .map_or_else(|| format!("gtp_{i}"), |n| n.to_string_lossy().to_string());
-                let gtp_output_dir = texture_output_dir.join(&gtp_stem);
+                let gtp_output_dir = if per_file_folders {
+                    let gtp_stem = Path::new(&page_file.filename)
+                        .file_stem()
+                        .map_or_else(|| format!("gtp_{i}"), |n| n.to_string_lossy().to_string());
+                    texture_output_dir.join(&gtp_stem)
+                } else {
+                    texture_output_dir.clone()
+                };
 
-                match VirtualTextureExtractor::extract_with_gts(
+                match VirtualTextureExtractor::extract_with_options(
                     &gtp_path,
                     &gts_path,
                     &gtp_output_dir,
+                    options,
                 ) {
-                    Ok(()) => extracted_count += 1,
+                    Ok(()) => extracted.push(page_file.filename.clone()),
                     Err(e) => {
-                        let filename = &page_file.filename;
-                        tracing::warn!("Failed to extract {filename}: {e}");
+                        collector.record(&gtp_path, e);
                         failed_count += 1;
                     }
                 }
             } else {
-                let path_display = gtp_path.display();
-                tracing::warn!("GTP file not found: {path_display}");
+                collector.record(
+                    &gtp_path,
+                    Error::FileNotFound {
+                        path: gtp_path.clone(),
+                    },
+                );
                 failed_count += 1;
             }
         }
@@ -176,15 +282,16 @@ where
             total_page_files,
         ));
 
-        if extracted_count == 0 && total_page_files > 0 {
+        if extracted.is_empty() {
             return Err(Error::InvalidFormat(format!(
                 "No GTP files could be extracted (0/{total_page_files} succeeded, {failed_count} failed)"
             )));
         }
 
         Ok(GtsExtractResult {
-            texture_count: extracted_count,
+            texture_count: extracted.len(),
             gtp_count: total_page_files,
+            textures: extracted,
         })
     }
 }
@@ -206,16 +313,148 @@ pub fn extract_batch<F>(
 where
     F: Fn(&VTexProgress) + Send + Sync,
 {
+    extract_batch_cancellable(gts_files, output_dir, &CancelToken::new(), progress)
+}
+
+/// Batch extract multiple GTS files in parallel, stopping early once `cancel` is triggered
+///
+/// Files already being extracted when `cancel` is triggered are finished; files not
+/// yet started are skipped. The result holds only the files that were processed.
+///
+/// # Returns
+/// Summary of the batch extraction.
+pub fn extract_batch_cancellable<F>(
+    gts_files: &[PathBuf],
+    output_dir: Option<&Path>,
+    cancel: &CancelToken,
+    progress: F,
+) -> BatchExtractResult
+where
+    F: Fn(&VTexProgress) + Send + Sync,
+{
+    extract_batch_filtered(
+        gts_files,
+        output_dir,
+        None,
+        &ExtractOptions::default(),
+        cancel,
+        progress,
+    )
+}
+
+/// Batch extract multiple GTS files in parallel, keeping only textures whose
+/// GTP filename contains `name_filter` (case-insensitive)
+///
+/// GTS files with no matching textures are reported as skipped rather than
+/// failed. Layers and naming are taken from `options` as in
+/// [`extract_gts_textures`]. Cancellation works as in
+/// [`extract_batch_cancellable`].
+///
+/// # Returns
+/// Summary of the batch extraction.
+pub fn extract_batch_filtered<F>(
+    gts_files: &[PathBuf],
+    output_dir: Option<&Path>,
+    name_filter: Option<&str>,
+    options: &ExtractOptions,
+    cancel: &CancelToken,
+    progress: F,
+) -> BatchExtractResult
+where
+    F: Fn(&VTexProgress) + Send + Sync,
+{
+    let outcome = extract_batch_with_policy(
+        gts_files,
+        output_dir,
+        name_filter,
+        options,
+        ErrorPolicy::Collect,
+        cancel,
+        progress,
+    );
+
+    let file_name = |path: &Path| {
+        path.file_name().map_or_else(
+            || "unknown".to_string(),
+            |n| n.to_string_lossy().to_string(),
+        )
+    };
+    let mut results: Vec<String> = outcome
+        .succeeded
+        .iter()
+        .map(|(gts_path, result)| {
+            let gts_name = file_name(gts_path);
+            if result.gtp_count == 0 {
+                format!("Skipped {gts_name}: no matching textures")
+            } else {
+                format!(
+                    "Extracted {} textures from {}",
+                    result.texture_count, gts_name
+                )
+            }
+        })
+        .collect();
+    results.extend(
+        outcome
+            .failed
+            .iter()
+            .map(|(path, e)| format!("Failed {}: {e}", file_name(path))),
+    );
+
+    BatchExtractResult {
+        success_count: outcome.succeeded.len(),
+        error_count: outcome.failed.len(),
+        texture_count: outcome
+            .succeeded
+            .iter()
+            .map(|(_, result)| result.texture_count)
+            .sum(),
+        cancelled: cancel.is_cancelled(),
+        results,
+    }
+}
+
+/// Batch extract multiple GTS files in parallel with an explicit [`ErrorPolicy`]
+///
+/// Works like [`extract_batch_filtered`], but returns each extracted GTS with
+/// its result, and each failure with its error. A GTS that is only partly
+/// extracted counts as a success; each of its GTP files that failed is listed
+/// as a failure as well.
+///
+/// # Returns
+/// The extracted GTS files and the GTS or GTP files that failed.
+pub fn extract_batch_with_policy<F>(
+    gts_files: &[PathBuf],
+    output_dir: Option<&Path>,
+    name_filter: Option<&str>,
+    options: &ExtractOptions,
+    policy: ErrorPolicy,
+    cancel: &CancelToken,
+    progress: F,
+) -> BatchOutcome<(PathBuf, GtsExtractResult)>
+where
+    F: Fn(&VTexProgress) + Send + Sync,
+{
+    let name_filter = name_filter
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty());
+    let include = |filename: &str| {
+        name_filter
+            .as_deref()
+            .is_none_or(|f| filename.to_lowercase().contains(f))
+    };
     let total = gts_files.len();
-    let success_counter = AtomicUsize::new(0);
-    let error_counter = AtomicUsize::new(0);
-    let texture_counter = AtomicUsize::new(0);
+    let collector = ErrorCollector::new(policy);
     let processed = AtomicUsize::new(0);
 
     // Parallel GTS extraction
-    let results: Vec<String> = gts_files
+    let extracted: Vec<(PathBuf, GtsExtractResult)> = gts_files
         .par_iter()
-        .map(|gts_path| {
+        .filter_map(|gts_path| {
+            if cancel.is_cancelled() || collector.should_stop() {
+                return None;
+            }
+
             let gts_name = gts_path.file_name().map_or_else(
                 || "unknown".to_string(),
                 |n| n.to_string_lossy().to_string(),
@@ -233,27 +472,119 @@ where
             // Create a no-op progress callback for individual extractions
             let noop_progress = |_: &VTexProgress| {};
 
-            match extract_gts_file(gts_path, output_dir, noop_progress) {
-                Ok(result) => {
-                    success_counter.fetch_add(1, Ordering::SeqCst);
-                    texture_counter.fetch_add(result.texture_count, Ordering::SeqCst);
-                    format!(
-                        "Extracted {} textures from {}",
-                        result.texture_count, gts_name
-                    )
-                }
+            match extract_gts_pages(
+                gts_path,
+                output_dir,
+                &include,
+                options,
+                &noop_progress,
+                &collector,
+            ) {
+                Ok(result) => Some((gts_path.clone(), result)),
                 Err(e) => {
-                    error_counter.fetch_add(1, Ordering::SeqCst);
-                    format!("Failed {gts_name}: {e}")
+                    collector.record(gts_path, e);
+                    None
                 }
             }
         })
         .collect();
 
-    BatchExtractResult {
-        success_count: success_counter.load(Ordering::SeqCst),
-        error_count: error_counter.load(Ordering::SeqCst),
-        texture_count: texture_counter.load(Ordering::SeqCst),
-        results,
+    collector.finish(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_batch_reports_failures() {
+        let base = tempfile::tempdir().unwrap();
+        let bogus = base.path().join("Bogus.gts");
+        std::fs::write(&bogus, b"not a gts file").unwrap();
+
+        let outcome = extract_batch_with_policy(
+            std::slice::from_ref(&bogus),
+            Some(&base.path().join("out")),
+            None,
+            &ExtractOptions::default(),
+            ErrorPolicy::Collect,
+            &CancelToken::new(),
+            |_| {},
+        );
+        assert!(outcome.succeeded.is_empty());
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].0, bogus);
+    }
+
+    #[test]
+    fn test_cancelled_batch_skips_remaining_files() {
+        let gts_files: Vec<PathBuf> = (0..32).map(|i| PathBuf::from(format!("{i}.gts"))).collect();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        let options = ExtractOptions::default();
+        let result = extract_batch_filtered(&gts_files, None, None, &options, &cancel, |_| {});
+        assert!(result.cancelled);
+        assert_eq!(result.success_count + result.error_count, 0);
+    }
+
+    /// Expected output files for a sample set, relative to the output
+    /// directory; `{gtp}` stands for the GTP file stem
+    const MACPAK_NAMES: &[&str] = &[
+        "Tiles/{gtp}/{gtp}_BaseMap.dds",
+        "Tiles/{gtp}/{gtp}_NormalMap.dds",
+    ];
+    const LSLIB_NAMES: &[&str] = &["Red_0.dds", "Red_1.dds"];
+
+    #[test]
+    fn test_extract_naming_schemes() {
+        use crate::virtual_texture::VTexNaming;
+        use crate::virtual_texture::builder::{
+            SourceTexture, TileCompressionPreference, VirtualTextureBuilder,
+        };
+        use image::{Rgba, RgbaImage};
+        use walkdir::WalkDir;
+
+        let base = tempfile::tempdir().unwrap();
+        let png = base.path().join("Red.png");
+        RgbaImage::from_pixel(256, 256, Rgba([255, 0, 0, 255]))
+            .save(&png)
+            .unwrap();
+        let built = VirtualTextureBuilder::new()
+            .name("Tiles")
+            .compression(TileCompressionPreference::Raw)
+            .add_texture(
+                SourceTexture::new("Red")
+                    .with_base_map(&png)
+                    .with_normal_map(&png),
+            )
+            .build(base.path().join("set"))
+            .unwrap();
+        let gtp = built.gtp_paths[0].file_stem().unwrap().to_string_lossy();
+
+        for (naming, expected) in [
+            (VTexNaming::MacPak, MACPAK_NAMES),
+            (VTexNaming::LsLib, LSLIB_NAMES),
+        ] {
+            let out = base.path().join(naming.as_str());
+            let options = ExtractOptions {
+                naming,
+                ..ExtractOptions::default()
+            };
+            extract_gts_textures(&built.gts_path, Some(&out), |_| true, &options, |_| {}).unwrap();
+
+            let mut files: Vec<String> = WalkDir::new(&out)
+                .into_iter()
+                .filter_map(std::result::Result::ok)
+                .filter(|e| e.file_type().is_file())
+                .map(|e| {
+                    let relative = e.path().strip_prefix(&out).unwrap();
+                    relative.to_string_lossy().replace('\\', "/")
+                })
+                .collect();
+            files.sort();
+            let expected: Vec<String> = expected.iter().map(|n| n.replace("{gtp}", &gtp)).collect();
+            assert_eq!(files, expected, "{naming:?}");
+        }
     }
 }
@@ -1,8 +1,7 @@
 //! Tile compression utilities
 //!
 
-use super::config::TileCompressionPreference;
-use crate::compression::fastlz;
+use crate::compression::{CompressionSettings, compress_with};
 use crate::error::Result;
 
 /// A compressed tile
@@ -12,16 +11,8 @@ pub struct CompressedTile {
     pub data: Vec<u8>,
 }
 
-/// Compress tile data using the specified method
-pub fn compress_tile(data: &[u8], preference: TileCompressionPreference) -> Result<CompressedTile> {
-    let data = match preference {
-        TileCompressionPreference::Raw => data.to_vec(),
-        TileCompressionPreference::FastLZ => compress_fastlz(data)?,
-    };
+/// Compress tile data with the given settings
+pub fn compress_tile(data: &[u8], settings: &CompressionSettings) -> Result<CompressedTile> {
+    let data = compress_with(data, settings)?;
     Ok(CompressedTile { data })
 }
-
-/// Compress data using `FastLZ`
-fn compress_fastlz(data: &[u8]) -> Result<Vec<u8>> {
-    fastlz::compress(data)
-}
@@ -3,6 +3,10 @@
 //!
 
 use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::compression::{CompressionAlgorithm, CompressionSettings};
+use crate::error::Error;
 
 /// Compression preference for tile data
 #[non_exhaustive]
@@ -27,6 +31,28 @@ impl TileCompressionPreference {
             Self::FastLZ => (b"fastlz\0\0\0\0\0\0\0\0\0\0", b"fastlz0.1.0\0\0\0\0\0"),
         }
     }
+
+    /// Compression settings for tile data at `level` (`FastLZ` 1 or 2)
+    #[must_use]
+    pub fn settings(self, level: Option<u32>) -> CompressionSettings {
+        match self {
+            Self::Raw => CompressionSettings::none(),
+            Self::FastLZ => CompressionSettings {
+                level,
+                ..CompressionSettings::fastlz()
+            },
+        }
+    }
+
+    /// Tile compression for `settings`, if tiles can use it
+    #[must_use]
+    pub fn from_settings(settings: &CompressionSettings) -> Option<Self> {
+        match settings.method {
+            CompressionAlgorithm::None => Some(Self::Raw),
+            CompressionAlgorithm::FastLz => Some(Self::FastLZ),
+            _ => None,
+        }
+    }
 }
 
 /// BC format for texture layers
@@ -66,6 +92,42 @@ impl BcFormat {
     }
 }
 
+/// File format of a source texture layer
+///
+/// PNG and TGA sources are encoded to the layer's BC format (BC3 for base
+/// and physical maps, BC5 for normal maps) with a full mip chain before
+/// tiling. DDS sources are tiled as they are.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceFormat {
+    /// Detect from the file contents, falling back to the extension
+    #[default]
+    Auto,
+    /// Block-compressed DDS
+    Dds,
+    /// PNG image
+    Png,
+    /// Truevision TGA image
+    Tga,
+}
+
+impl FromStr for SourceFormat {
+    type Err = Error;
+
+    /// Parse `auto`, `dds`, `png` or `tga` (case-insensitive)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "dds" => Ok(Self::Dds),
+            "png" => Ok(Self::Png),
+            "tga" => Ok(Self::Tga),
+            _ => Err(Error::InvalidFormat(format!(
+                "unknown source format '{s}': expected auto, dds, png or tga"
+            ))),
+        }
+    }
+}
+
 /// Configuration for building a virtual texture set
 #[derive(Debug, Clone)]
 pub struct TileSetConfiguration {
@@ -79,6 +141,8 @@ pub struct TileSetConfiguration {
     pub page_size: u32,
     /// Compression preference for tile data
     pub compression: TileCompressionPreference,
+    /// `FastLZ` level (1 = faster, 2 = smaller); `None` uses level 1
+    pub compression_level: Option<u32>,
     /// Whether to embed next mip level in tile data
     pub embed_mip: bool,
     /// Whether to deduplicate identical tiles
@@ -94,6 +158,7 @@ impl Default for TileSetConfiguration {
             tile_border: 8,
             page_size: 0x0010_0000, // 1MB
             compression: TileCompressionPreference::FastLZ,
+            compression_level: None,
             embed_mip: true,
             deduplicate: true,
         }
@@ -204,12 +269,14 @@ impl TileSetConfiguration {
 pub struct SourceTexture {
     /// Name/identifier for this texture (used in `FourCC` metadata)
     pub name: String,
-    /// Path to the base map DDS (color/albedo) - optional
+    /// Path to the base map (color/albedo) - optional
     pub base_map: Option<PathBuf>,
-    /// Path to the normal map DDS - optional
+    /// Path to the normal map - optional
     pub normal_map: Option<PathBuf>,
-    /// Path to the physical map DDS (roughness/metallic) - optional
+    /// Path to the physical map (roughness/metallic) - optional
     pub physical_map: Option<PathBuf>,
+    /// Format of the layer files (DDS, PNG or TGA)
+    pub source_format: SourceFormat,
 }
 
 impl SourceTexture {
@@ -221,6 +288,7 @@ impl SourceTexture {
             base_map: None,
             normal_map: None,
             physical_map: None,
+            source_format: SourceFormat::Auto,
         }
     }
 
@@ -245,6 +313,13 @@ impl SourceTexture {
         self
     }
 
+    /// Set the format of the layer files instead of detecting it
+    #[must_use]
+    pub fn with_source_format(mut self, format: SourceFormat) -> Self {
+        self.source_format = format;
+        self
+    }
+
     /// Get the layer paths as an array (BaseMap=0, NormalMap=1, PhysicalMap=2)
     #[must_use]
     pub fn layer_paths(&self) -> [Option<&PathBuf>; 3] {
@@ -3,7 +3,7 @@
 //!
 //!
 //! This module provides functionality for creating virtual textures (GTS/GTP files)
-//! from source DDS textures.
+//! from source DDS, PNG or TGA textures.
 //!
 //! # Example
 //!
@@ -14,7 +14,7 @@
 //!     .add_texture(
 //!         SourceTexture::new("MyTexture")
 //!             .with_base_map("base.dds")
-//!             .with_normal_map("normal.dds")
+//!             .with_normal_map("normal.png")
 //!     )
 //!     .build("output/")?;
 //! # Ok::<(), maclarian::error::Error>(())
@@ -24,12 +24,18 @@ pub(crate) mod compression;
 pub mod config;
 pub(crate) mod deduplication;
 pub(crate) mod geometry;
+pub(crate) mod source;
 pub(crate) mod tile_processor;
 
-pub use config::{BcFormat, SourceTexture, TileCompressionPreference, TileSetConfiguration};
+pub use config::{
+    BcFormat, SourceFormat, SourceTexture, TileCompressionPreference, TileSetConfiguration,
+};
 
 use crate::error::{Error, Result};
-use crate::virtual_texture::types::{GtsCodec, GtsFlatTileInfo, VTexPhase, VTexProgress};
+use crate::utils::CancelToken;
+use crate::virtual_texture::types::{
+    GtsCodec, GtsDataType, GtsFlatTileInfo, VTexPhase, VTexProgress,
+};
 use crate::virtual_texture::writer::{
     fourcc::build_metadata_tree,
     gtp_writer::{Chunk, GtpWriter},
@@ -47,6 +53,7 @@ use uuid::Uuid;
 use self::compression::{CompressedTile, compress_tile};
 use self::deduplication::build_dedup_map;
 use self::geometry::calculate_geometry;
+use self::source::load_layer;
 use self::tile_processor::{DdsTexture, ProcessedTile, extract_tiles_from_dds};
 
 /// Result of building a virtual texture set
@@ -124,6 +131,13 @@ impl VirtualTextureBuilder {
         self
     }
 
+    /// Set the `FastLZ` level (1 = faster, 2 = smaller)
+    #[must_use]
+    pub fn compression_level(mut self, level: u32) -> Self {
+        self.config.compression_level = Some(level);
+        self
+    }
+
     /// Set the tile dimensions
     #[must_use]
     pub fn tile_size(mut self, width: u32, height: u32) -> Self {
@@ -168,6 +182,27 @@ impl VirtualTextureBuilder {
     /// Returns an error if validation fails, textures cannot be loaded,
     /// or output files cannot be written.
     pub fn build_with_progress<P, F>(self, output_dir: P, progress: F) -> Result<BuildResult>
+    where
+        P: AsRef<Path>,
+        F: Fn(&VTexProgress) + Send + Sync,
+    {
+        self.build_cancellable(output_dir, &CancelToken::new(), progress)
+    }
+
+    /// Build the virtual texture set, stopping early once `cancel` is triggered
+    ///
+    /// `cancel` is checked between phases, layers and tiles. Nothing is
+    /// written once it has been triggered.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if `cancel` was triggered, or the same
+    /// errors as [`build_with_progress`](Self::build_with_progress).
+    pub fn build_cancellable<P, F>(
+        self,
+        output_dir: P,
+        cancel: &CancelToken,
+        progress: F,
+    ) -> Result<BuildResult>
     where
         P: AsRef<Path>,
         F: Fn(&VTexProgress) + Send + Sync,
@@ -177,6 +212,7 @@ impl VirtualTextureBuilder {
         // Phase: Validate
         progress(&VTexProgress::new(VTexPhase::Validating, 1, 1));
         self.validate()?;
+        cancel.checkpoint()?;
 
         // Determine output name
         let name = self
@@ -197,14 +233,37 @@ impl VirtualTextureBuilder {
             texture.physical_map.is_some(),
         ];
 
+        // Phase: Load Tiles
+        progress(&VTexProgress::new(VTexPhase::LoadingTiles, 0, 3));
+
+        // Load each layer once; PNG and TGA sources are BC-encoded here
+        let layer_paths = texture.layer_paths();
+        let mut dds_textures: [Option<DdsTexture>; 3] = [None, None, None];
+        for (i, path) in layer_paths.iter().enumerate() {
+            if let Some(p) = path {
+                progress(&VTexProgress::with_file(
+                    VTexPhase::LoadingTiles,
+                    i + 1,
+                    3,
+                    format!("Loading layer {i}"),
+                ));
+                cancel.checkpoint()?;
+                dds_textures[i] = Some(load_layer(p, texture.source_format, i)?);
+            }
+        }
+
         // Phase: Calculate Geometry
         progress(&VTexProgress::new(VTexPhase::CalculatingGeometry, 1, 1));
 
-        // Load first available layer to get dimensions
-        let (first_dds, _first_layer_idx) = self.load_first_layer(texture)?;
+        // The first available layer sets the dimensions
+        let first_dds = dds_textures
+            .iter()
+            .flatten()
+            .next()
+            .ok_or_else(|| Error::VirtualTexture("No layers found in texture".to_string()))?;
         let tex_info = (texture.name.clone(), first_dds.width, first_dds.height);
 
-        // Limit mip levels to what's actually in the DDS file
+        // Limit mip levels to what's actually in the source
         let geometry = calculate_geometry(
             &[tex_info],
             layers_present,
@@ -212,9 +271,6 @@ impl VirtualTextureBuilder {
             Some(first_dds.mip_count),
         );
 
-        // Phase: Load Tiles
-        progress(&VTexProgress::new(VTexPhase::LoadingTiles, 0, 3));
-
         // Pre-allocate based on estimated total tiles across all layers
         let estimated_tiles: usize = geometry
             .tiles_per_layer
@@ -222,21 +278,6 @@ impl VirtualTextureBuilder {
             .map(std::vec::Vec::len)
             .sum();
         let mut all_tiles: Vec<ProcessedTile> = Vec::with_capacity(estimated_tiles);
-        let layer_paths = texture.layer_paths();
-
-        // Load DDS textures for each layer
-        let mut dds_textures: [Option<DdsTexture>; 3] = [None, None, None];
-        for (i, path) in layer_paths.iter().enumerate() {
-            if let Some(p) = path {
-                progress(&VTexProgress::with_file(
-                    VTexPhase::LoadingTiles,
-                    i + 1,
-                    3,
-                    format!("Loading layer {i}"),
-                ));
-                dds_textures[i] = Some(DdsTexture::load(p)?);
-            }
-        }
 
         // Extract tiles from each layer
         for (layer_idx, dds_opt) in dds_textures.iter().enumerate() {
@@ -249,6 +290,7 @@ impl VirtualTextureBuilder {
                         3,
                         format!("Extracting {} tiles from layer {}", coords.len(), layer_idx),
                     ));
+                    cancel.checkpoint()?;
                     let tiles = extract_tiles_from_dds(dds, coords, &self.config)?;
                     all_tiles.extend(tiles);
                 }
@@ -290,11 +332,15 @@ impl VirtualTextureBuilder {
         ));
 
         let processed = AtomicUsize::new(0);
-        let compression = self.config.compression;
+        let compression = self
+            .config
+            .compression
+            .settings(self.config.compression_level);
 
         let compressed_unique: Result<Vec<CompressedTile>> = unique_indices
             .par_iter()
             .map(|&idx| {
+                cancel.checkpoint()?;
                 let current = processed.fetch_add(1, Ordering::Relaxed) + 1;
                 if current % 100 == 0 {
                     progress(&VTexProgress::new(
@@ -303,11 +349,12 @@ impl VirtualTextureBuilder {
                         unique_tile_count,
                     ));
                 }
-                compress_tile(&all_tiles[idx].full_data(), compression)
+                compress_tile(&all_tiles[idx].full_data(), &compression)
             })
             .collect();
 
         let compressed_unique = compressed_unique?;
+        cancel.checkpoint()?;
         progress(&VTexProgress::new(
             VTexPhase::Compressing,
             unique_tile_count,
@@ -388,10 +435,12 @@ impl VirtualTextureBuilder {
         // Add layers
         for (i, present) in layers_present.iter().enumerate() {
             if *present {
-                // Data type: 6 = BC3, 12 = BC5, etc.
+                // The base map is color and sampled as sRGB; the other
+                // layers keep the data types they've always been written with
                 let data_type = match i {
+                    0 => GtsDataType::R8G8B8A8Srgb as u32,
                     1 => 12, // Normal map uses BC5
-                    _ => 6,  // Base and physical use BC3
+                    _ => 6,  // Physical uses BC3
                 };
                 gts_writer.add_layer(LayerInfo { data_type });
             }
@@ -478,19 +527,6 @@ impl VirtualTextureBuilder {
         })
     }
 
-    /// Load the first available layer to get texture dimensions
-    fn load_first_layer(&self, texture: &SourceTexture) -> Result<(DdsTexture, usize)> {
-        for (i, path) in texture.layer_paths().iter().enumerate() {
-            if let Some(p) = path {
-                let dds = DdsTexture::load(p)?;
-                return Ok((dds, i));
-            }
-        }
-        Err(Error::VirtualTexture(
-            "No layers found in texture".to_string(),
-        ))
-    }
-
     /// Validate the builder configuration and inputs
     fn validate(&self) -> Result<()> {
         // Validate configuration
@@ -0,0 +1,145 @@
+//! Loading source layers in DDS, PNG or TGA format
+//!
+//!
+
+use super::config::{BcFormat, SourceFormat};
+use super::tile_processor::DdsTexture;
+use crate::converter::{DdsFormat, encode_blocks};
+use crate::error::{Error, Result, ResultExt};
+use image::imageops::FilterType;
+use image::{ImageFormat, RgbaImage};
+use std::path::Path;
+
+/// Footer signature of TGA 2.0 files (older files have none)
+const TGA_SIGNATURE: &[u8] = b"TRUEVISION-XFILE.\0";
+
+/// Load one layer of a source texture
+///
+/// DDS files are used as they are. PNG and TGA images are encoded to the
+/// layer's BC format with a full mip chain.
+pub(crate) fn load_layer(path: &Path, format: SourceFormat, layer: usize) -> Result<DdsTexture> {
+    let data = std::fs::read(path).with_path(path)?;
+    let format = match format {
+        SourceFormat::Auto => detect_format(path, &data),
+        format => format,
+    };
+
+    let image_format = match format {
+        SourceFormat::Png => ImageFormat::Png,
+        SourceFormat::Tga => ImageFormat::Tga,
+        _ => return DdsTexture::from_bytes(&data).with_path(path),
+    };
+    let image = image::load_from_memory_with_format(&data, image_format)
+        .map_err(|e| Error::ImageDecodeFailed {
+            format: format!("{image_format:?}").to_uppercase(),
+            message: e.to_string(),
+        })
+        .with_path(path)?;
+    encode_layer(image.to_rgba8(), layer_format(layer)).with_path(path)
+}
+
+/// BC format the GTS declares for a layer (BaseMap=0, NormalMap=1, PhysicalMap=2)
+fn layer_format(layer: usize) -> BcFormat {
+    match layer {
+        1 => BcFormat::Bc5,
+        _ => BcFormat::Bc3,
+    }
+}
+
+/// Work out a layer file's format from its magic bytes or extension
+fn detect_format(path: &Path, data: &[u8]) -> SourceFormat {
+    if data.starts_with(b"DDS ") {
+        return SourceFormat::Dds;
+    }
+    if data.starts_with(b"\x89PNG") {
+        return SourceFormat::Png;
+    }
+    // TGA has no header magic
+    let is_tga_ext = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tga"));
+    if data.ends_with(TGA_SIGNATURE) || is_tga_ext {
+        return SourceFormat::Tga;
+    }
+    SourceFormat::Dds
+}
+
+/// Block-compress an image and its mips into a texture ready for tiling
+fn encode_layer(image: RgbaImage, format: BcFormat) -> Result<DdsTexture> {
+    let (width, height) = image.dimensions();
+    if !width.is_power_of_two() || !height.is_power_of_two() || width < 4 || height < 4 {
+        return Err(Error::VirtualTextureSourceSize { width, height });
+    }
+
+    let dds_format = match format {
+        BcFormat::Bc1 => DdsFormat::BC1,
+        BcFormat::Bc5 => DdsFormat::BC5,
+        _ => DdsFormat::BC3,
+    };
+
+    let mut data = Vec::new();
+    let mut mip_offsets = Vec::new();
+    let mut mip = image;
+    loop {
+        mip_offsets.push(data.len());
+        data.extend(encode_blocks(
+            mip.as_raw(),
+            mip.width(),
+            mip.height(),
+            dds_format,
+        ));
+
+        if mip.width() == 1 && mip.height() == 1 {
+            break;
+        }
+        let next_width = (mip.width() / 2).max(1);
+        let next_height = (mip.height() / 2).max(1);
+        mip = image::imageops::resize(&mip, next_width, next_height, FilterType::Triangle);
+    }
+
+    Ok(DdsTexture {
+        width,
+        height,
+        block_size: format.block_size(),
+        data,
+        mip_count: mip_offsets.len() as u32,
+        mip_offsets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    #[test]
+    fn test_png_layers_are_encoded_with_mips() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let png = dir.path().join("Normal.png");
+        RgbaImage::from_pixel(16, 8, Rgba([128, 128, 255, 255]))
+            .save(&png)
+            .unwrap();
+        let texture = load_layer(&png, SourceFormat::Auto, 1).unwrap();
+        assert_eq!((texture.width, texture.height), (16, 8));
+        assert_eq!(texture.block_size, 16);
+        // 16x8, 8x4, 4x2, 2x1, 1x1: 8 + 2 + 1 + 1 + 1 BC5 blocks
+        assert_eq!(texture.mip_count, 5);
+        assert_eq!(texture.mip_offsets, [0, 128, 160, 176, 192]);
+        assert_eq!(texture.data.len(), 13 * 16);
+
+        let npot = dir.path().join("Base.png");
+        RgbaImage::new(12, 8).save(&npot).unwrap();
+        let Err(err) = load_layer(&npot, SourceFormat::Png, 0) else {
+            panic!("non-power-of-two source was accepted");
+        };
+        assert!(matches!(
+            err.root(),
+            Error::VirtualTextureSourceSize {
+                width: 12,
+                height: 8
+            }
+        ));
+        assert_eq!(err.path(), Some(npot.as_path()));
+    }
+}
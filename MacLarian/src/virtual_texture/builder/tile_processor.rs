@@ -4,8 +4,8 @@
 
 use super::config::TileSetConfiguration;
 use super::geometry::TileCoord;
+use crate::converter::{DdsInfo, DdsIssue};
 use crate::error::{Error, Result};
-use std::path::Path;
 
 /// A processed tile ready for compression
 #[derive(Debug, Clone)]
@@ -49,18 +49,25 @@ pub struct DdsTexture {
 }
 
 impl DdsTexture {
-    /// Load a DDS texture from a file
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let data = std::fs::read(path.as_ref())?;
-        Self::from_bytes(&data)
-    }
-
     /// Parse a DDS texture from bytes
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
         use ddsfile::Dds;
 
-        let dds = Dds::read(std::io::Cursor::new(data))
-            .map_err(|e| Error::DdsError(format!("Failed to parse DDS: {e}")))?;
+        let dds = Dds::read(std::io::Cursor::new(data)).map_err(|e| Error::DdsParseFailed {
+            message: e.to_string(),
+        })?;
+
+        // Reject headers that would make tile extraction read the wrong
+        // bytes; the rest only matter to other tools
+        let info = DdsInfo::from_dds(&dds);
+        for issue in info.issues {
+            match issue {
+                DdsIssue::MissingDx10Header { .. }
+                | DdsIssue::UnalignedBlockDimensions { .. }
+                | DdsIssue::DataTooShort { .. } => return Err(Error::DdsInvalid { issue }),
+                _ => tracing::warn!("DDS texture: {issue}"),
+            }
+        }
 
         let width = dds.get_width();
         let height = dds.get_height();
@@ -77,7 +84,11 @@ impl DdsTexture {
                 match dds.get_d3d_format() {
                     Some(ddsfile::D3DFormat::DXT1) => 8,
                     Some(ddsfile::D3DFormat::DXT3 | ddsfile::D3DFormat::DXT5) => 16,
-                    _ => return Err(Error::DdsError("Unsupported DDS format".to_string())),
+                    _ => {
+                        return Err(Error::DdsUnsupportedFormat {
+                            format: info.format,
+                        });
+                    }
                 }
             }
         };
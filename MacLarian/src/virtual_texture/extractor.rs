@@ -14,12 +14,11 @@
 
 use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use super::gtp::GtpFile;
-use super::gts::GtsFile;
 use super::mod_config;
-use super::types::VirtualTextureLayer;
+use super::tile_set::VirtualTextureSet;
+use super::types::{VTexNaming, VirtualTextureLayer};
 use crate::error::{Error, Result};
 
 /// DDS file writer for BC/DXT5 compressed textures
@@ -47,77 +46,61 @@ impl DdsWriter {
     pub fn write<P: AsRef<Path>>(path: P, data: &[u8], width: u32, height: u32) -> Result<()> {
         let file = File::create(path.as_ref())?;
         let mut writer = BufWriter::new(file);
+        writer.write_all(&Self::to_bytes(data, width, height))?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Wrap BC/DXT5 texture data in a DDS header, in memory
+    #[must_use]
+    pub fn to_bytes(data: &[u8], width: u32, height: u32) -> Vec<u8> {
+        let mut out = Vec::with_capacity(128 + data.len());
+        let mut put = |value: u32| out.extend_from_slice(&value.to_le_bytes());
 
         // Calculate pitch for BC5/DXT5
         let pitch = width.div_ceil(4) * 16;
         let linear_size = pitch * height.div_ceil(4);
 
-        // Write DDS magic
-        writer.write_all(&Self::DDS_MAGIC.to_le_bytes())?;
-
-        // Header size
-        writer.write_all(&Self::DDS_HEADER_SIZE.to_le_bytes())?;
+        // Magic and header size
+        put(Self::DDS_MAGIC);
+        put(Self::DDS_HEADER_SIZE);
 
         // Flags
-        let flags = Self::DDSD_CAPS
+        put(Self::DDSD_CAPS
             | Self::DDSD_HEIGHT
             | Self::DDSD_WIDTH
             | Self::DDSD_PIXELFORMAT
-            | Self::DDSD_LINEARSIZE;
-        writer.write_all(&flags.to_le_bytes())?;
-
-        // Height
-        writer.write_all(&height.to_le_bytes())?;
+            | Self::DDSD_LINEARSIZE);
 
-        // Width
-        writer.write_all(&width.to_le_bytes())?;
-
-        // Linear size
-        writer.write_all(&linear_size.to_le_bytes())?;
-
-        // Depth
-        writer.write_all(&0u32.to_le_bytes())?;
-
-        // Mipmap count
-        writer.write_all(&1u32.to_le_bytes())?;
+        // Height, width, linear size, depth, mipmap count
+        put(height);
+        put(width);
+        put(linear_size);
+        put(0);
+        put(1);
 
         // Reserved (44 bytes)
-        writer.write_all(&[0u8; 44])?;
+        for _ in 0..11 {
+            put(0);
+        }
 
-        // Pixel format
-        // Size
-        writer.write_all(&32u32.to_le_bytes())?;
-        // Flags
-        writer.write_all(&Self::DDPF_FOURCC.to_le_bytes())?;
-        // FourCC
-        writer.write_all(&Self::FOURCC_DXT5.to_le_bytes())?;
-        // RGB bit count
-        writer.write_all(&0u32.to_le_bytes())?;
-        // R mask
-        writer.write_all(&0u32.to_le_bytes())?;
-        // G mask
-        writer.write_all(&0u32.to_le_bytes())?;
-        // B mask
-        writer.write_all(&0u32.to_le_bytes())?;
-        // A mask
-        writer.write_all(&0u32.to_le_bytes())?;
-
-        // Caps
-        writer.write_all(&Self::DDSCAPS_TEXTURE.to_le_bytes())?;
-        // Caps2
-        writer.write_all(&0u32.to_le_bytes())?;
-        // Caps3
-        writer.write_all(&0u32.to_le_bytes())?;
-        // Caps4
-        writer.write_all(&0u32.to_le_bytes())?;
-        // Reserved2
-        writer.write_all(&0u32.to_le_bytes())?;
-
-        // Write texture data
-        writer.write_all(data)?;
+        // Pixel format: size, flags, FourCC, RGB bit count, R/G/B/A masks
+        put(32);
+        put(Self::DDPF_FOURCC);
+        put(Self::FOURCC_DXT5);
+        for _ in 0..5 {
+            put(0);
+        }
 
-        writer.flush()?;
-        Ok(())
+        // Caps, Caps2-4, Reserved2
+        put(Self::DDSCAPS_TEXTURE);
+        for _ in 0..4 {
+            put(0);
+        }
+
+        // Texture data
+        out.extend_from_slice(data);
+        out
     }
 }
 
@@ -129,6 +112,8 @@ pub struct ExtractOptions {
     pub layers: Vec<usize>,
     /// Extract all layers with numbered suffixes (_0, _1, _2)
     pub all_layers: bool,
+    /// Output file naming and folder layout
+    pub naming: VTexNaming,
 }
 
 /// Virtual texture extractor
@@ -203,15 +188,29 @@ impl VirtualTextureExtractor {
         output_dir: P3,
         options: &ExtractOptions,
     ) -> Result<()> {
+        Self::extract_layers(gtp_path, gts_path, output_dir, options).map(drop)
+    }
+
+    /// Like [`Self::extract_with_options`], returning the DDS file written
+    /// for each layer
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::extract_with_options`].
+    pub fn extract_layers<P1: AsRef<Path>, P2: AsRef<Path>, P3: AsRef<Path>>(
+        gtp_path: P1,
+        gts_path: P2,
+        output_dir: P3,
+        options: &ExtractOptions,
+    ) -> Result<Vec<(VirtualTextureLayer, PathBuf)>> {
         let gtp_path = gtp_path.as_ref();
         let gts_path = gts_path.as_ref();
         let output_dir = output_dir.as_ref();
 
-        // Parse GTS file
-        let gts = GtsFile::open(gts_path)?;
-
-        // Open GTP file
-        let mut gtp = GtpFile::open(gtp_path, &gts)?;
+        // Index the tile set; tiles are read from the GTP's own folder
+        let set = VirtualTextureSet::open(gts_path)?
+            .with_page_file_dir(gtp_path.parent().unwrap_or(Path::new("")));
+        let gts = set.gts();
 
         // Extract GTP filename for matching
         let gtp_name = gtp_path
@@ -285,27 +284,16 @@ impl VirtualTextureExtractor {
                 (idx, name, gtex_hash)
             };
 
-        // Get tile locations for each layer
-        let tiles_by_layer = gts.get_tiles_for_page_file(page_file_idx);
-
         // Create output directory
         std::fs::create_dir_all(output_dir)?;
 
-        // BC block dimensions
-        let tile_width = gts.header.tile_width as usize;
-        let tile_height = gts.header.tile_height as usize;
-        let tile_border = gts.header.tile_border as usize;
-        let content_width = gts.content_width() as usize;
-        let content_height = gts.content_height() as usize;
-
-        let src_block_width = tile_width.div_ceil(4);
-        let src_block_height = tile_height.div_ceil(4);
+        let Some(texture) = set.textures().get(usize::from(page_file_idx)) else {
+            return Ok(Vec::new());
+        };
+        set.open_page_file(texture)?;
+        let content_block_width = (gts.content_width() as usize).div_ceil(4);
+        let content_block_height = (gts.content_height() as usize).div_ceil(4);
         let bytes_per_block = 16;
-        let tile_bc_size = src_block_width * src_block_height * bytes_per_block;
-
-        let border_blocks = tile_border / 4;
-        let content_block_width = content_width.div_ceil(4);
-        let content_block_height = content_height.div_ceil(4);
 
         // Determine which layers to extract based on options
         let layers_to_extract: Vec<usize> = if options.layers.is_empty() {
@@ -324,90 +312,73 @@ impl VirtualTextureExtractor {
         };
 
         // Process selected layers
+        let mut written = Vec::new();
         for layer_idx in layers_to_extract {
             let layer = VirtualTextureLayer::from_index(layer_idx as u8)
                 .ok_or(Error::InvalidLayerIndex { index: layer_idx })?;
 
-            let tiles = &tiles_by_layer[layer_idx];
-
-            if tiles.is_empty() {
+            // Prefer level 0, but some layers (often PhysicalMap) are only
+            // stored at lower resolution
+            let Some(level) = texture.finest_level(layer) else {
                 continue;
+            };
+            let grid = texture.grid(layer, level).expect("finest level has a grid");
+            if level != 0 {
+                tracing::debug!("Layer {layer_idx} using level {level} (level 0 not available)");
             }
 
-            // Find bounds
-            let min_x = tiles.iter().map(|t| t.x).min().expect("tiles is non-empty") as usize;
-            let max_x = tiles.iter().map(|t| t.x).max().expect("tiles is non-empty") as usize;
-            let min_y = tiles.iter().map(|t| t.y).min().expect("tiles is non-empty") as usize;
-            let max_y = tiles.iter().map(|t| t.y).max().expect("tiles is non-empty") as usize;
-
-            let width_tiles = max_x - min_x + 1;
-            let height_tiles = max_y - min_y + 1;
-
             // Output dimensions (content size, no border)
-            let output_width = width_tiles * content_width;
-            let output_height = height_tiles * content_height;
-            let output_block_width = width_tiles * content_block_width;
-            let output_block_height = height_tiles * content_block_height;
+            let output_block_width = grid.columns as usize * content_block_width;
+            let output_block_height = grid.rows as usize * content_block_height;
             let output_bc_size = output_block_width * output_block_height * bytes_per_block;
 
             let mut output_data = vec![0u8; output_bc_size];
 
             // Extract and place each tile
-            for tile in tiles {
-                // Extract tile
-                let tile_data =
-                    match gtp.extract_chunk(tile.page as usize, tile.chunk as usize, &gts) {
-                        Ok(data) => data,
-                        Err(e) => {
-                            eprintln!("Warning: Failed to extract chunk: {e}");
-                            continue;
-                        }
-                    };
-
-                if tile_data.len() < tile_bc_size {
-                    continue;
-                }
-
-                let out_x = tile.x as usize - min_x;
-                let out_y = tile.y as usize - min_y;
+            for (x, y) in set.tile_positions(texture, layer, level) {
+                let tile = match set.read_tile(texture, layer, level, x, y) {
+                    Ok(tile) => tile,
+                    Err(e) => {
+                        eprintln!("Warning: Failed to extract chunk: {e}");
+                        continue;
+                    }
+                };
 
                 // Copy content blocks only (skip border)
-                for content_row in 0..content_block_height {
-                    let src_row = border_blocks + content_row;
-                    let src_offset = (src_row * src_block_width + border_blocks) * bytes_per_block;
-
-                    let dst_block_row = out_y * content_block_height + content_row;
-                    let dst_block_col = out_x * content_block_width;
+                for (content_row, row) in tile.content_rows().enumerate() {
+                    let dst_block_row = y as usize * content_block_height + content_row;
+                    let dst_block_col = x as usize * content_block_width;
                     let dst_offset =
                         (dst_block_row * output_block_width + dst_block_col) * bytes_per_block;
 
-                    let row_size = content_block_width * bytes_per_block;
-
-                    if src_offset + row_size <= tile_data.len()
-                        && dst_offset + row_size <= output_data.len()
-                    {
-                        output_data[dst_offset..dst_offset + row_size]
-                            .copy_from_slice(&tile_data[src_offset..src_offset + row_size]);
+                    if dst_offset + row.len() <= output_data.len() {
+                        output_data[dst_offset..dst_offset + row.len()].copy_from_slice(row);
                     }
                 }
             }
 
-            // Write DDS file: {mod_name}_{gtex_hash}_{Layer}.dds
-            let filename = if options.all_layers {
-                format!("{mod_name}_{gtex_hash}_{layer_idx}.dds")
-            } else {
-                format!("{}_{}_{}.dds", mod_name, gtex_hash, layer.as_str())
+            // Write DDS file: {mod_name}_{gtex_hash}_{Layer}.dds, or LSLib's
+            // {GTex}_{index}.dds named after the texture in the GTS metadata
+            let filename = match options.naming {
+                VTexNaming::LsLib => format!("{}_{layer_idx}.dds", texture.info.name),
+                VTexNaming::MacPak if options.all_layers => {
+                    format!("{mod_name}_{gtex_hash}_{layer_idx}.dds")
+                }
+                VTexNaming::MacPak => {
+                    format!("{}_{}_{}.dds", mod_name, gtex_hash, layer.as_str())
+                }
             };
             let output_path = output_dir.join(filename);
             DdsWriter::write(
                 &output_path,
                 &output_data,
-                output_width as u32,
-                output_height as u32,
+                (output_block_width * 4) as u32,
+                (output_block_height * 4) as u32,
             )?;
+            written.push((layer, output_path));
         }
 
-        Ok(())
+        Ok(written)
     }
 
     /// Extract the 32-character hash from a GTP filename
@@ -17,7 +17,7 @@ use std::path::Path;
 
 use super::gts::GtsFile;
 use super::types::GtpHeader;
-use crate::error::{Error, Result};
+use crate::error::{Error, Result, ResultExt};
 
 /// GTP file reader.
 pub struct GtpFile<R: Read + Seek> {
@@ -36,8 +36,9 @@ impl GtpFile<File> {
     /// # Errors
     /// Returns an error if the file cannot be read or has an invalid format.
     pub fn open<P: AsRef<Path>>(path: P, gts: &GtsFile) -> Result<Self> {
-        let file = File::open(path.as_ref())?;
-        Self::new(file, gts)
+        let path = path.as_ref();
+        let file = File::open(path).with_path(path)?;
+        Self::new(file, gts).with_path(path)
     }
 }
 
@@ -1,7 +1,10 @@
 //! Public accessor methods for GtsFile.
 
-use super::super::types::{GtsParameterBlock, TileCompression, TileLocation};
+use super::super::types::{GtsParameterBlock, TileCompression};
+use super::super::utils::TextureInfo;
+use super::super::writer::fourcc::{FourCCNode, FourCCTree};
 use super::GtsFile;
+use crate::utils::format_guid;
 
 impl GtsFile {
     /// Get compression method for a parameter block.
@@ -37,78 +40,6 @@ impl GtsFile {
         None
     }
 
-    /// Get tiles for a specific page file, organized by layer.
-    ///
-    /// Prefers level 0 (full resolution) but falls back to higher level numbers
-    /// if a layer doesn't have level 0 tiles (e.g., PhysicalMap is often stored
-    /// at lower resolution).
-    #[must_use]
-    pub(crate) fn get_tiles_for_page_file(&self, page_file_index: u16) -> [Vec<TileLocation>; 3] {
-        // First pass: collect all tiles by layer and level
-        let mut tiles_by_layer_level: [std::collections::HashMap<u8, Vec<TileLocation>>; 3] = [
-            std::collections::HashMap::new(),
-            std::collections::HashMap::new(),
-            std::collections::HashMap::new(),
-        ];
-
-        for tile_info in &self.flat_tile_infos {
-            if tile_info.page_file_index != page_file_index {
-                continue;
-            }
-
-            let packed_idx = tile_info.packed_tile_id_index as usize;
-            if packed_idx >= self.packed_tiles.len() {
-                continue;
-            }
-
-            let packed = &self.packed_tiles[packed_idx];
-            let layer_idx = packed.layer as usize;
-
-            if layer_idx >= 3 {
-                continue;
-            }
-
-            tiles_by_layer_level[layer_idx]
-                .entry(packed.level)
-                .or_default()
-                .push(TileLocation {
-                    page: tile_info.page_index,
-                    chunk: tile_info.chunk_index,
-                    x: packed.x,
-                    y: packed.y,
-                });
-        }
-
-        // Second pass: for each layer, select the best available level (lowest number = highest res)
-        let mut tiles_by_layer: [Vec<TileLocation>; 3] = [Vec::new(), Vec::new(), Vec::new()];
-
-        for (layer_idx, level_map) in tiles_by_layer_level.iter().enumerate() {
-            if level_map.is_empty() {
-                continue;
-            }
-
-            // Find the minimum level (highest resolution available)
-            let best_level = *level_map.keys().min().expect("level_map is non-empty");
-            tiles_by_layer[layer_idx] = level_map.get(&best_level).cloned().unwrap_or_default();
-
-            if best_level != 0 {
-                tracing::debug!(
-                    "Layer {layer_idx} using level {best_level} (level 0 not available)"
-                );
-            }
-        }
-
-        let num_layers = self.header.num_layers;
-        let l0 = tiles_by_layer[0].len();
-        let l1 = tiles_by_layer[1].len();
-        let l2 = tiles_by_layer[2].len();
-        tracing::debug!(
-            "GTS num_layers={num_layers}, selected tiles by layer: [0]={l0}, [1]={l1}, [2]={l2}"
-        );
-
-        tiles_by_layer
-    }
-
     /// Get content dimensions (tile size minus border).
     #[must_use]
     pub fn content_width(&self) -> i32 {
@@ -120,4 +51,51 @@ impl GtsFile {
     pub fn content_height(&self) -> i32 {
         self.header.tile_height - self.header.tile_border * 2
     }
+
+    /// The `FourCC` metadata tree (empty if the GTS has none, or it was unreadable).
+    #[must_use]
+    pub fn metadata(&self) -> &FourCCTree {
+        &self.metadata
+    }
+
+    /// Textures listed in the `FourCC` metadata, in file order.
+    ///
+    /// Returns an empty list if the GTS has no (readable) metadata.
+    #[must_use]
+    pub fn textures(&self) -> Vec<TextureInfo> {
+        let Some(txts) = self
+            .metadata
+            .root()
+            .and_then(|meta| meta.child(*b"ATLS"))
+            .and_then(|atls| atls.child(*b"TXTS"))
+        else {
+            return Vec::new();
+        };
+
+        txts.children()
+            .iter()
+            .filter(|node| node.fourcc() == *b"TXTR")
+            .map(texture_info)
+            .collect()
+    }
+}
+
+/// Name, GUID and size of a `TXTR` metadata node
+pub(super) fn texture_info(txtr: &FourCCNode) -> TextureInfo {
+    let int = |fourcc| match txtr.child(fourcc) {
+        Some(FourCCNode::Int { value, .. }) => *value,
+        _ => 0,
+    };
+    TextureInfo {
+        name: match txtr.child(*b"NAME") {
+            Some(FourCCNode::String { value, .. }) => value.clone(),
+            _ => String::new(),
+        },
+        guid: match txtr.child(*b"THMB") {
+            Some(FourCCNode::Guid { guid, .. }) => Some(format_guid(guid)),
+            _ => None,
+        },
+        width: int(*b"WDTH"),
+        height: int(*b"HGHT"),
+    }
 }
@@ -0,0 +1,288 @@
+//! Reading and rewriting the `FourCC` metadata of a GTS file in place.
+//!
+//! Only the metadata section and the two header fields that locate it are
+//! written; tile data, page file references and every other section keep
+//! their bytes and offsets.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::super::types::GtsHeader;
+use super::super::utils::TextureInfo;
+use super::super::writer::fourcc::{FourCCNode, FourCCTree};
+use super::accessors::texture_info;
+use super::{read_header, read_sections};
+use crate::error::{Error, Result, ResultExt};
+use crate::utils::parse_guid;
+
+/// Byte offset of `FourCCListSize` (followed by the u64 `FourCCListOffset`)
+const FOURCC_LIST_SIZE_OFFSET: u64 = 144;
+
+/// Read the `FourCC` metadata tree of a GTS file.
+///
+/// Unlike [`GtsFile::open`](super::GtsFile::open), a damaged tree is an error
+/// rather than being replaced by an empty one.
+///
+/// # Errors
+/// Returns an error if the file cannot be read, isn't a GTS file, or its
+/// metadata cannot be parsed.
+pub fn read_gts_metadata<P: AsRef<Path>>(path: P) -> Result<FourCCTree> {
+    let path = path.as_ref();
+    let mut file = File::open(path).with_path(path)?;
+    let header = read_gts_header(&mut file).with_path(path)?;
+    read_sections::read_metadata(&mut file, &header)
+        .at_offset(header.fourcc_list_offset)
+        .with_path(path)
+}
+
+/// Replace the `FourCC` metadata tree of a GTS file.
+///
+/// The tree is written over the old one when it fits (the rest of the old
+/// section is zeroed), otherwise it is appended at the end of the file. A
+/// tree that is already last in the file is rewritten where it is and the
+/// file is cut or extended to fit, so repeated edits don't keep appending.
+/// Writing back an unchanged tree leaves the file byte-identical.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or written, or isn't a GTS file.
+pub fn write_gts_metadata<P: AsRef<Path>>(path: P, tree: &FourCCTree) -> Result<()> {
+    let path = path.as_ref();
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_path(path)?;
+    if let Some(len) = write_metadata(&mut file, tree).with_path(path)? {
+        file.set_len(len).with_path(path)?;
+    }
+    Ok(())
+}
+
+/// Rename a texture and/or change its GUID in the GTS metadata.
+///
+/// `texture` selects the `TXTR` entry by its current name; it may be omitted
+/// when the GTS lists a single texture. Returns the updated texture.
+///
+/// # Errors
+/// Returns an error if the GUID is malformed, the texture can't be selected,
+/// or the file cannot be read or written.
+pub fn set_texture_metadata<P: AsRef<Path>>(
+    path: P,
+    texture: Option<&str>,
+    name: Option<&str>,
+    guid: Option<&str>,
+) -> Result<TextureInfo> {
+    let path = path.as_ref();
+    let guid = guid
+        .map(|g| {
+            parse_guid(g).ok_or_else(|| Error::InvalidGuid {
+                guid: g.to_string(),
+            })
+        })
+        .transpose()?;
+
+    let mut tree = read_gts_metadata(path)?;
+    let txtr = select_texture(&mut tree, texture).with_path(path)?;
+    if let Some(name) = name {
+        set_child(txtr, FourCCNode::string(*b"NAME", name));
+    }
+    if let Some(guid) = guid {
+        set_child(txtr, FourCCNode::guid(*b"THMB", guid));
+    }
+    let info = texture_info(txtr);
+
+    write_gts_metadata(path, &tree)?;
+    Ok(info)
+}
+
+/// Read the header and check the magic.
+fn read_gts_header<R: Read + Seek>(reader: &mut R) -> Result<GtsHeader> {
+    let header = read_header::read_header(reader).at_offset(0)?;
+    if header.magic != GtsHeader::MAGIC {
+        return Err(Error::InvalidGtsMagic.at_offset(0));
+    }
+    Ok(header)
+}
+
+/// Write `tree` into an open GTS file and update the header to point at it.
+///
+/// Returns the length to cut the file to when a tree at the end of the file
+/// was replaced by a shorter one.
+fn write_metadata<F: Read + Write + Seek>(file: &mut F, tree: &FourCCTree) -> Result<Option<u64>> {
+    let header = read_gts_header(file)?;
+    let bytes = tree.to_bytes();
+    let size = u32::try_from(bytes.len())
+        .map_err(|_| Error::InvalidFormat("FourCC metadata exceeds 4 GiB".to_string()))?;
+
+    let old_size = header.fourcc_list_size as usize;
+    let end = file.seek(SeekFrom::End(0))?;
+    let old_end = header.fourcc_list_offset + u64::from(header.fourcc_list_size);
+    let (offset, new_len) = if header.fourcc_list_size > 0 && old_end == end {
+        // Already last in the file, so it can grow or shrink where it is
+        file.seek(SeekFrom::Start(header.fourcc_list_offset))?;
+        file.write_all(&bytes)?;
+        let new_end = header.fourcc_list_offset + u64::from(size);
+        (
+            header.fourcc_list_offset,
+            (new_end < end).then_some(new_end),
+        )
+    } else if header.fourcc_list_size > 0 && bytes.len() <= old_size {
+        file.seek(SeekFrom::Start(header.fourcc_list_offset))?;
+        file.write_all(&bytes)?;
+        file.write_all(&vec![0u8; old_size - bytes.len()])?;
+        (header.fourcc_list_offset, None)
+    } else {
+        let offset = end.next_multiple_of(4);
+        file.write_all(&vec![0u8; (offset - end) as usize])?;
+        file.write_all(&bytes)?;
+        (offset, None)
+    };
+
+    file.seek(SeekFrom::Start(FOURCC_LIST_SIZE_OFFSET))?;
+    file.write_all(&size.to_le_bytes())?;
+    file.write_all(&offset.to_le_bytes())?;
+    file.flush()?;
+    Ok(new_len)
+}
+
+/// The `TXTR` node named `texture`, or the only one if no name is given.
+fn select_texture<'a>(
+    tree: &'a mut FourCCTree,
+    texture: Option<&str>,
+) -> Result<&'a mut FourCCNode> {
+    let mut textures: Vec<&mut FourCCNode> = tree
+        .root_mut()
+        .and_then(|meta| meta.child_mut(*b"ATLS"))
+        .and_then(|atls| atls.child_mut(*b"TXTS"))
+        .map(|txts| {
+            txts.children_mut()
+                .iter_mut()
+                .filter(|node| node.fourcc() == *b"TXTR")
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match texture {
+        Some(name) => textures
+            .into_iter()
+            .find(|txtr| texture_info(txtr).name == name)
+            .ok_or_else(|| Error::GtsTextureNotFound {
+                name: name.to_string(),
+            }),
+        None if textures.len() == 1 => Ok(textures.remove(0)),
+        None if textures.is_empty() => Err(Error::InvalidFormat(
+            "GTS metadata lists no textures".to_string(),
+        )),
+        None => Err(Error::GtsTextureNotSpecified {
+            count: textures.len(),
+        }),
+    }
+}
+
+/// Replace the child with the same code as `node`, or append it.
+fn set_child(parent: &mut FourCCNode, node: FourCCNode) {
+    match parent.child_mut(node.fourcc()) {
+        Some(existing) => *existing = node,
+        None => parent.add_child(node),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::GtsFile;
+    use super::*;
+    use crate::virtual_texture::writer::fourcc::build_metadata_tree;
+    use crate::virtual_texture::writer::gts_writer::GtsWriter;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_metadata_rewrite_round_trip() {
+        let guid = *b"0123456789abcdef";
+        let mut writer = GtsWriter::new(guid, 144, 144, 8, 0x0010_0000);
+        writer.set_fourcc_tree(build_metadata_tree(
+            "Stone",
+            512,
+            256,
+            0,
+            0,
+            &[("BaseMap", "BaseColor")],
+            &guid,
+        ));
+        let mut file = Cursor::new(Vec::new());
+        writer.write(&mut file).unwrap();
+        let original = file.get_ref().clone();
+
+        // An unchanged tree writes back the same bytes
+        file.set_position(0);
+        let header = read_gts_header(&mut file).unwrap();
+        let tree = read_sections::read_metadata(&mut file, &header).unwrap();
+        write_metadata(&mut file, &tree).unwrap();
+        assert_eq!(file.get_ref(), &original);
+
+        // A longer name moves the tree to the end without touching the rest
+        let mut tree = tree;
+        let txtr = select_texture(&mut tree, None).unwrap();
+        set_child(txtr, FourCCNode::string(*b"NAME", "Stone_Wall_Mossy_A"));
+        write_metadata(&mut file, &tree).unwrap();
+        let edited = file.get_ref();
+        assert_eq!(edited[..144], original[..144]);
+        assert_eq!(edited[156..original.len()], original[156..]);
+
+        file.set_position(0);
+        let gts = GtsFile::read(&mut file).unwrap();
+        assert_eq!(gts.textures()[0].name, "Stone_Wall_Mossy_A");
+        assert_eq!(
+            gts.header.fourcc_list_offset,
+            (original.len() as u64).next_multiple_of(4)
+        );
+        assert!(matches!(
+            select_texture(&mut tree, Some("Missing")),
+            Err(Error::GtsTextureNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_repeated_edits_reuse_the_moved_tree() {
+        let guid = *b"0123456789abcdef";
+        let mut writer = GtsWriter::new(guid, 144, 144, 8, 0x0010_0000);
+        writer.set_fourcc_tree(build_metadata_tree(
+            "Stone",
+            512,
+            256,
+            0,
+            0,
+            &[("BaseMap", "BaseColor")],
+            &guid,
+        ));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Stone.gts");
+        let mut file = File::create(&path).unwrap();
+        writer.write(&mut file).unwrap();
+        drop(file);
+        let original_len = std::fs::metadata(&path).unwrap().len();
+
+        // The first growth moves the tree to the end; later edits rewrite it there
+        set_texture_metadata(&path, None, Some("Stone_Wall_Mossy_A"), None).unwrap();
+        let moved_len = std::fs::metadata(&path).unwrap().len();
+        assert!(moved_len > original_len);
+        for _ in 0..3 {
+            set_texture_metadata(&path, None, Some("Stone"), None).unwrap();
+            assert!(std::fs::metadata(&path).unwrap().len() < moved_len);
+            set_texture_metadata(&path, None, Some("Stone_Wall_Mossy_B"), None).unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().len(), moved_len);
+        }
+
+        // A longer name extends the tree in place instead of appending again
+        let name = "Stone_Wall_Mossy_Cracked_A";
+        set_texture_metadata(&path, None, Some(name), None).unwrap();
+        let grown_len = std::fs::metadata(&path).unwrap().len();
+        let header = read_gts_header(&mut File::open(&path).unwrap()).unwrap();
+        assert_eq!(header.fourcc_list_offset, original_len.next_multiple_of(4));
+        assert_eq!(
+            grown_len,
+            header.fourcc_list_offset + u64::from(header.fourcc_list_size)
+        );
+        assert_eq!(GtsFile::open(&path).unwrap().textures()[0].name, name);
+    }
+}
@@ -16,6 +16,7 @@
 )]
 
 mod accessors;
+mod metadata;
 mod read_header;
 mod read_sections;
 
@@ -27,7 +28,10 @@ use std::path::Path;
 use super::types::{
     GtsFlatTileInfo, GtsHeader, GtsLevelInfo, GtsPackedTileId, GtsPageFileInfo, GtsParameterBlock,
 };
-use crate::error::{Error, Result};
+use super::writer::fourcc::FourCCTree;
+use crate::error::{Error, Result, ResultExt};
+
+pub use metadata::{read_gts_metadata, set_texture_metadata, write_gts_metadata};
 
 /// GTS file reader and parser.
 #[derive(Debug)]
@@ -40,6 +44,8 @@ pub struct GtsFile {
     pub(crate) page_files: Vec<GtsPageFileInfo>,
     pub(crate) packed_tiles: Vec<GtsPackedTileId>,
     pub(crate) flat_tile_infos: Vec<GtsFlatTileInfo>,
+    /// `FourCC` metadata (texture names, GUIDs, layer names).
+    pub(crate) metadata: FourCCTree,
 }
 
 impl GtsFile {
@@ -48,9 +54,10 @@ impl GtsFile {
     /// # Errors
     /// Returns an error if the file cannot be read or has an invalid format.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let file = File::open(path.as_ref())?;
+        let path = path.as_ref();
+        let file = File::open(path).with_path(path)?;
         let mut reader = BufReader::new(file);
-        Self::read(&mut reader)
+        Self::read(&mut reader).with_path(path)
     }
 
     /// Read and parse GTS from a reader.
@@ -58,26 +65,41 @@ impl GtsFile {
     /// # Errors
     /// Returns an error if reading fails or the data has an invalid format.
     pub fn read<R: Read + Seek>(reader: &mut R) -> Result<Self> {
-        let header = read_header::read_header(reader)?;
+        let header = read_header::read_header(reader).at_offset(0)?;
 
         if header.magic != GtsHeader::MAGIC {
-            return Err(Error::InvalidGtsMagic);
+            return Err(Error::InvalidGtsMagic.at_offset(0));
         }
 
+        // Errors point at the start of the section they were found in
+
         // Read parameter blocks
-        let parameter_blocks = read_sections::read_parameter_blocks(reader, &header)?;
+        let parameter_blocks = read_sections::read_parameter_blocks(reader, &header)
+            .at_offset(header.parameter_block_headers_offset)?;
 
         // Read levels
-        let levels = read_sections::read_levels(reader, &header)?;
+        let levels = read_sections::read_levels(reader, &header).at_offset(header.levels_offset)?;
 
         // Read page file metadata
-        let page_files = read_sections::read_page_files(reader, &header)?;
+        let page_files = read_sections::read_page_files(reader, &header)
+            .at_offset(header.page_file_metadata_offset)?;
 
         // Read packed tile IDs
-        let packed_tiles = read_sections::read_packed_tiles(reader, &header)?;
+        let packed_tiles = read_sections::read_packed_tiles(reader, &header)
+            .at_offset(header.packed_tile_ids_offset)?;
 
         // Read flat tile infos
-        let flat_tile_infos = read_sections::read_flat_tile_infos(reader, &header)?;
+        let flat_tile_infos = read_sections::read_flat_tile_infos(reader, &header)
+            .at_offset(header.flat_tile_info_offset)?;
+
+        // Metadata is informational; a damaged tree shouldn't stop extraction
+        let metadata = read_sections::read_metadata(reader, &header).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Ignoring unreadable GTS metadata at {:#x}: {e}",
+                header.fourcc_list_offset
+            );
+            FourCCTree::new()
+        });
 
         Ok(Self {
             header,
@@ -86,6 +108,31 @@ impl GtsFile {
             page_files,
             packed_tiles,
             flat_tile_infos,
+            metadata,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_corrupt_gts_reports_offset() {
+        let mut data = vec![0u8; 192];
+        let err = GtsFile::read(&mut Cursor::new(&data)).unwrap_err();
+        assert!(matches!(err.root(), Error::InvalidGtsMagic));
+        assert_eq!(err.offset(), Some(0));
+
+        // One parameter block header, stored past the end of the file
+        data[..4].copy_from_slice(&GtsHeader::MAGIC.to_le_bytes());
+        data[156..160].copy_from_slice(&1u32.to_le_bytes());
+        data[160..168].copy_from_slice(&0x1000u64.to_le_bytes());
+        let err = GtsFile::read(&mut Cursor::new(&data)).unwrap_err();
+        assert_eq!(err.offset(), Some(0x1000));
+
+        let err = GtsFile::open("missing.gts").unwrap_err();
+        assert_eq!(err.path(), Some(Path::new("missing.gts")));
+    }
+}
@@ -4,7 +4,8 @@ use super::super::types::{
     GtsBCParameterBlock, GtsCodec, GtsDataType, GtsFlatTileInfo, GtsHeader, GtsLevelInfo,
     GtsPackedTileId, GtsPageFileInfo, GtsParameterBlock, GtsUniformParameterBlock,
 };
-use crate::error::Result;
+use super::super::writer::fourcc::FourCCTree;
+use crate::error::{Error, Result};
 use std::collections::HashMap;
 use std::io::{Read, Seek, SeekFrom};
 
@@ -294,3 +295,29 @@ pub(super) fn read_flat_tile_infos<R: Read + Seek>(
 
     Ok(tile_infos)
 }
+
+/// Read the `FourCC` metadata tree from GTS file.
+pub(super) fn read_metadata<R: Read + Seek>(
+    reader: &mut R,
+    header: &GtsHeader,
+) -> Result<FourCCTree> {
+    if header.fourcc_list_size == 0 {
+        return Ok(FourCCTree::new());
+    }
+
+    reader.seek(SeekFrom::Start(header.fourcc_list_offset))?;
+
+    // Read through `take` so a corrupt size can't trigger a huge allocation
+    let mut data = Vec::new();
+    reader
+        .by_ref()
+        .take(u64::from(header.fourcc_list_size))
+        .read_to_end(&mut data)?;
+    if data.len() != header.fourcc_list_size as usize {
+        return Err(Error::InvalidFormat(
+            "FourCC metadata runs past the end of the file".to_string(),
+        ));
+    }
+
+    FourCCTree::parse(&data)
+}
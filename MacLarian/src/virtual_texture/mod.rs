@@ -18,6 +18,19 @@
 //! ).unwrap();
 //! ```
 //!
+//! # Tile Access Example
+//!
+//! ```no_run
+//! use maclarian::virtual_texture::{VirtualTextureLayer, VirtualTextureSet};
+//!
+//! // Stream individual tiles, e.g. for a viewer
+//! let set = VirtualTextureSet::open("path/to/texture.gts")?;
+//! let texture = &set.textures()[0];
+//! let tile = set.read_tile(texture, VirtualTextureLayer::BaseMap, 0, 0, 0)?;
+//! let image = tile.to_rgba()?;
+//! # Ok::<(), maclarian::error::Error>(())
+//! ```
+//!
 //! # Creation Example
 //!
 //! ```no_run
@@ -40,31 +53,41 @@ mod extractor;
 mod gtp;
 mod gts;
 pub mod mod_config;
+mod preview;
+mod tile_set;
 mod types;
 mod utils;
 pub(crate) mod writer;
 
 // Re-exports - public types
 pub use gtp::GtpFile;
-pub use gts::GtsFile;
+pub use gts::{GtsFile, read_gts_metadata, set_texture_metadata, write_gts_metadata};
+pub use writer::fourcc::{FourCCFormat, FourCCNode, FourCCTree};
 
 // Re-export only public types from types module (not internal format structs)
 pub use types::{
-    GtsCodec, TileCompression, VTexPhase, VTexProgress, VTexProgressCallback, VirtualTextureLayer,
-    VirtualTextureOutput,
+    GtsCodec, TileCompression, VTexNaming, VTexPhase, VTexProgress, VTexProgressCallback,
+    VirtualTextureLayer, VirtualTextureOutput,
 };
 
 // Public extractor
 pub use extractor::{ExtractOptions, VirtualTextureExtractor};
+pub use preview::decode_lowest_mip;
+
+// Tile-level access
+pub use tile_set::{DEFAULT_TILE_CACHE, DecodedTile, TileGrid, TileSetTexture, VirtualTextureSet};
 
 // Utility functions
 pub use utils::{
-    ExtractResult, GtpInfo, GtsInfo, PageFileInfo, extract_all, find_base_name, find_gts_path,
-    get_subfolder_name, gtp_info, list_gts,
+    ExtractResult, GtpInfo, GtsInfo, MIN_GUID_PREFIX_LEN, PageFileInfo, TextureInfo, extract_all,
+    find_base_name, find_gts_path, find_texture_by_guid, get_subfolder_name, gtp_info, list_gts,
 };
 
 // Batch operations
-pub use batch::{BatchExtractResult, GtsExtractResult, extract_batch, extract_gts_file};
+pub use batch::{
+    BatchExtractResult, GtsExtractResult, extract_batch, extract_batch_cancellable,
+    extract_batch_filtered, extract_batch_with_policy, extract_gts_file, extract_gts_textures,
+};
 
 // Mod config discovery and lookup
 pub use mod_config::{
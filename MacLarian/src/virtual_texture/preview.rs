@@ -0,0 +1,128 @@
+//! Low-resolution previews of virtual textures
+//!
+//! A tile set stores every layer as a mip chain of tiles. The coarsest level
+//! usually fits in one or two tiles, so it can be decoded for a thumbnail
+//! without reading the whole page files.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+
+use image::RgbaImage;
+
+use super::extractor::DdsWriter;
+use super::gtp::GtpFile;
+use super::gts::GtsFile;
+use super::types::VirtualTextureLayer;
+use crate::converter::decode_dds_bytes;
+use crate::error::{Error, Result};
+
+/// Decode the coarsest mip level stored for `layer` of a tile set
+///
+/// `open_page_file` is called with the file name of each GTP holding a tile
+/// of that level (as listed in the GTS), so page files can come from disk or
+/// from a PAK.
+///
+/// # Errors
+/// Returns an error if the layer has no tiles, a page file can't be opened
+/// or read, or the assembled texture can't be decoded.
+pub fn decode_lowest_mip<R, F>(
+    gts: &GtsFile,
+    layer: VirtualTextureLayer,
+    mut open_page_file: F,
+) -> Result<RgbaImage>
+where
+    R: Read + Seek,
+    F: FnMut(&str) -> Result<R>,
+{
+    let layer = layer as u8;
+
+    // Tiles of the layer at its highest level number (lowest resolution)
+    let mut level = None;
+    let mut tiles: Vec<(u16, u16, u16, u16, u16)> = Vec::new();
+    for info in &gts.flat_tile_infos {
+        let Some(packed) = gts.packed_tiles.get(info.packed_tile_id_index as usize) else {
+            continue;
+        };
+        if packed.layer != layer || level.is_some_and(|l| packed.level < l) {
+            continue;
+        }
+        if level != Some(packed.level) {
+            level = Some(packed.level);
+            tiles.clear();
+        }
+        tiles.push((
+            info.page_file_index,
+            info.page_index,
+            info.chunk_index,
+            packed.x,
+            packed.y,
+        ));
+    }
+    if tiles.is_empty() {
+        return Err(Error::InvalidFormat(format!(
+            "tile set has no tiles for layer {layer}"
+        )));
+    }
+
+    let tile_width = gts.header.tile_width as usize;
+    let tile_height = gts.header.tile_height as usize;
+    let border_blocks = gts.header.tile_border as usize / 4;
+    let content_block_width = (gts.content_width() as usize).div_ceil(4);
+    let content_block_height = (gts.content_height() as usize).div_ceil(4);
+    let src_block_width = tile_width.div_ceil(4);
+    let tile_bc_size = src_block_width * tile_height.div_ceil(4) * 16;
+
+    let min_x = tiles.iter().map(|t| t.3).min().unwrap_or(0) as usize;
+    let min_y = tiles.iter().map(|t| t.4).min().unwrap_or(0) as usize;
+    let width_tiles = tiles.iter().map(|t| t.3).max().unwrap_or(0) as usize - min_x + 1;
+    let height_tiles = tiles.iter().map(|t| t.4).max().unwrap_or(0) as usize - min_y + 1;
+    let output_block_width = width_tiles * content_block_width;
+    let output_block_height = height_tiles * content_block_height;
+    let mut output = vec![0u8; output_block_width * output_block_height * 16];
+
+    // Open each page file once
+    let mut by_page_file: BTreeMap<u16, Vec<(u16, u16, u16, u16)>> = BTreeMap::new();
+    for (page_file, page, chunk, x, y) in tiles {
+        by_page_file
+            .entry(page_file)
+            .or_default()
+            .push((page, chunk, x, y));
+    }
+    for (page_file, tiles) in by_page_file {
+        let name = gts
+            .page_files
+            .get(page_file as usize)
+            .map(|pf| pf.filename.as_str())
+            .ok_or(Error::InvalidPageIndex {
+                index: page_file as usize,
+            })?;
+        let mut gtp = GtpFile::new(open_page_file(name)?, gts)?;
+
+        for (page, chunk, x, y) in tiles {
+            let tile = gtp.extract_chunk(page as usize, chunk as usize, gts)?;
+            if tile.len() < tile_bc_size {
+                continue;
+            }
+            let (out_x, out_y) = (x as usize - min_x, y as usize - min_y);
+            let row_size = content_block_width * 16;
+            for row in 0..content_block_height {
+                let src = ((border_blocks + row) * src_block_width + border_blocks) * 16;
+                let dst = ((out_y * content_block_height + row) * output_block_width
+                    + out_x * content_block_width)
+                    * 16;
+                if src + row_size <= tile.len() && dst + row_size <= output.len() {
+                    output[dst..dst + row_size].copy_from_slice(&tile[src..src + row_size]);
+                }
+            }
+        }
+    }
+
+    let dds = DdsWriter::to_bytes(
+        &output,
+        (output_block_width * 4) as u32,
+        (output_block_height * 4) as u32,
+    );
+    Ok(decode_dds_bytes(&dds)?.1)
+}
@@ -0,0 +1,554 @@
+//! Tile-level access to virtual texture sets
+//!
+//! [`VirtualTextureSet`] indexes the tiles of a GTS file and reads them from
+//! its GTP page files on demand, for viewers that stream tiles instead of
+//! extracting whole textures. Page files stay open once used and recently
+//! read tiles are kept in a small LRU cache, so panning back over a region
+//! doesn't decompress it again. The set is `Sync`; tiles from different page
+//! files can be read from several threads at once.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use image::RgbaImage;
+use serde::Serialize;
+
+use super::extractor::DdsWriter;
+use super::gtp::GtpFile;
+use super::gts::GtsFile;
+use super::types::VirtualTextureLayer;
+use super::utils::TextureInfo;
+use crate::converter::decode_dds_bytes;
+use crate::error::{Error, Result};
+
+/// Decompressed tiles kept by default
+pub const DEFAULT_TILE_CACHE: usize = 256;
+
+/// Page file, page and chunk of a stored tile
+type ChunkKey = (u16, u16, u16);
+
+/// Page file, layer, level, x and y of a tile in the tile set
+type TileKey = (u16, u8, u8, u16, u16);
+
+/// Smallest x and y, then largest x and y, of the tiles in a grid
+type GridBounds = (u16, u16, u16, u16);
+
+/// The tiles of one layer of a texture at one mip level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TileGrid {
+    /// Mip level (0 is full resolution)
+    pub level: u8,
+    /// Columns of tiles
+    pub columns: u32,
+    /// Rows of tiles
+    pub rows: u32,
+    /// Tile set coordinates of the top-left tile
+    origin: (u16, u16),
+}
+
+/// A texture of a tile set: the tiles stored in one GTP page file
+#[derive(Debug, Clone, Serialize)]
+pub struct TileSetTexture {
+    /// Name, GUID and size from the GTS metadata
+    ///
+    /// Page files the metadata doesn't describe are named after the file,
+    /// with the tile-aligned size of their finest level.
+    pub info: TextureInfo,
+    /// GTP file holding the tiles
+    pub page_file: String,
+    /// Number of mip levels with tiles, across all layers
+    pub levels: u8,
+    /// Grids by layer index, finest level first
+    #[serde(skip)]
+    grids: [Vec<TileGrid>; 3],
+    #[serde(skip)]
+    page_file_index: u16,
+}
+
+impl TileSetTexture {
+    /// The tiles of `layer` at `level`, if the texture has any
+    #[must_use]
+    pub fn grid(&self, layer: VirtualTextureLayer, level: u8) -> Option<TileGrid> {
+        self.grids[layer as usize]
+            .iter()
+            .find(|grid| grid.level == level)
+            .copied()
+    }
+
+    /// The finest (lowest numbered) level stored for `layer`
+    ///
+    /// Physical maps are often stored from level 1, at half resolution.
+    #[must_use]
+    pub fn finest_level(&self, layer: VirtualTextureLayer) -> Option<u8> {
+        self.grids[layer as usize].first().map(|grid| grid.level)
+    }
+}
+
+/// A tile read from a page file
+#[derive(Debug, Clone)]
+pub struct DecodedTile {
+    /// BC3 (DXT5) blocks of the whole tile, border included, row by row
+    pub data: Arc<[u8]>,
+    /// Tile width in pixels, border included
+    pub width: u32,
+    /// Tile height in pixels, border included
+    pub height: u32,
+    /// Border on each side in pixels, repeated from neighboring tiles
+    pub border: u32,
+}
+
+impl DecodedTile {
+    /// Rows of BC blocks inside the border
+    pub fn content_rows(&self) -> impl Iterator<Item = &[u8]> {
+        let row_blocks = self.width.div_ceil(4) as usize;
+        let border_blocks = (self.border / 4) as usize;
+        let content_blocks = row_blocks - 2 * border_blocks;
+        let content_rows = self.height.div_ceil(4) as usize - 2 * border_blocks;
+        (border_blocks..border_blocks + content_rows).map(move |row| {
+            let start = (row * row_blocks + border_blocks) * 16;
+            &self.data[start..start + content_blocks * 16]
+        })
+    }
+
+    /// Decode the whole tile, border included
+    ///
+    /// # Errors
+    /// Returns an error if the blocks can't be decoded.
+    pub fn to_rgba(&self) -> Result<RgbaImage> {
+        let dds = DdsWriter::to_bytes(&self.data, self.width, self.height);
+        Ok(decode_dds_bytes(&dds)?.1)
+    }
+}
+
+/// A GTS tile set whose tiles can be read one at a time
+pub struct VirtualTextureSet {
+    gts: GtsFile,
+    page_file_dir: PathBuf,
+    textures: Vec<TileSetTexture>,
+    tiles: HashMap<TileKey, (u16, u16)>,
+    page_files: Mutex<HashMap<u16, Arc<Mutex<GtpFile<File>>>>>,
+    cache: Mutex<TileCache>,
+}
+
+impl VirtualTextureSet {
+    /// Open a GTS file and index its tiles
+    ///
+    /// Page files are looked up next to the GTS unless
+    /// [`with_page_file_dir`](Self::with_page_file_dir) says otherwise, and
+    /// are only opened once a tile from them is read.
+    ///
+    /// # Errors
+    /// Returns an error if the GTS file cannot be read or parsed.
+    pub fn open<P: AsRef<Path>>(gts_path: P) -> Result<Self> {
+        let gts_path = gts_path.as_ref();
+        let gts = GtsFile::open(gts_path)?;
+        let dir = gts_path.parent().unwrap_or(Path::new("")).to_path_buf();
+        Ok(Self::new(gts, dir))
+    }
+
+    /// Index the tiles of a parsed GTS, with page files in `page_file_dir`
+    #[must_use]
+    pub fn new(gts: GtsFile, page_file_dir: impl Into<PathBuf>) -> Self {
+        let mut tiles = HashMap::new();
+        // Bounds by page file, layer and level
+        let mut bounds: BTreeMap<(u16, u8, u8), GridBounds> = BTreeMap::new();
+        for info in &gts.flat_tile_infos {
+            let Some(packed) = gts.packed_tiles.get(info.packed_tile_id_index as usize) else {
+                continue;
+            };
+            if packed.layer >= 3 {
+                continue;
+            }
+            tiles.insert(
+                (
+                    info.page_file_index,
+                    packed.layer,
+                    packed.level,
+                    packed.x,
+                    packed.y,
+                ),
+                (info.page_index, info.chunk_index),
+            );
+            bounds
+                .entry((info.page_file_index, packed.layer, packed.level))
+                .and_modify(|b| {
+                    *b = (
+                        b.0.min(packed.x),
+                        b.1.min(packed.y),
+                        b.2.max(packed.x),
+                        b.3.max(packed.y),
+                    );
+                })
+                .or_insert((packed.x, packed.y, packed.x, packed.y));
+        }
+
+        let metadata = gts.textures();
+        let textures = gts
+            .page_files
+            .iter()
+            .enumerate()
+            .map(|(index, page_file)| {
+                let index = index as u16;
+                let mut grids: [Vec<TileGrid>; 3] = Default::default();
+                let mut levels = Vec::new();
+                for (&(_, layer, level), &(x0, y0, x1, y1)) in
+                    bounds.range((index, 0, 0)..=(index, u8::MAX, u8::MAX))
+                {
+                    grids[layer as usize].push(TileGrid {
+                        level,
+                        columns: u32::from(x1 - x0) + 1,
+                        rows: u32::from(y1 - y0) + 1,
+                        origin: (x0, y0),
+                    });
+                    if !levels.contains(&level) {
+                        levels.push(level);
+                    }
+                }
+
+                let info = describe(&gts, &metadata, &page_file.filename, &grids);
+                TileSetTexture {
+                    info,
+                    page_file: page_file.filename.clone(),
+                    levels: levels.len() as u8,
+                    grids,
+                    page_file_index: index,
+                }
+            })
+            .collect();
+
+        Self {
+            gts,
+            page_file_dir: page_file_dir.into(),
+            textures,
+            tiles,
+            page_files: Mutex::new(HashMap::new()),
+            cache: Mutex::new(TileCache::new(DEFAULT_TILE_CACHE)),
+        }
+    }
+
+    /// Look for page files in `dir` instead of next to the GTS
+    #[must_use]
+    pub fn with_page_file_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.page_file_dir = dir.into();
+        self
+    }
+
+    /// Keep up to `tiles` decompressed tiles cached (0 disables the cache)
+    #[must_use]
+    pub fn with_cache_capacity(self, tiles: usize) -> Self {
+        *lock(&self.cache) = TileCache::new(tiles);
+        self
+    }
+
+    /// The parsed GTS file
+    #[must_use]
+    pub fn gts(&self) -> &GtsFile {
+        &self.gts
+    }
+
+    /// Textures of the set, one per page file, in GTS order
+    #[must_use]
+    pub fn textures(&self) -> &[TileSetTexture] {
+        &self.textures
+    }
+
+    /// The texture stored in the page file named `page_file`
+    #[must_use]
+    pub fn texture_in(&self, page_file: &str) -> Option<&TileSetTexture> {
+        self.textures.iter().find(|t| t.page_file == page_file)
+    }
+
+    /// Open the page file of `texture` now instead of on its first read
+    ///
+    /// Useful to report a missing or damaged GTP before reading any tiles.
+    ///
+    /// # Errors
+    /// Returns an error if the page file cannot be opened or its header is
+    /// invalid.
+    pub fn open_page_file(&self, texture: &TileSetTexture) -> Result<()> {
+        self.page_file(texture.page_file_index).map(drop)
+    }
+
+    /// Read one tile of a texture
+    ///
+    /// `x` and `y` count tiles from the top-left of the texture's
+    /// [`grid`](TileSetTexture::grid) for `layer` at `level`.
+    ///
+    /// # Errors
+    /// Returns [`Error::VirtualTextureTileNotFound`] if the texture has no
+    /// tile there, or an error if the page file can't be opened or the tile
+    /// can't be read or decompressed.
+    pub fn read_tile(
+        &self,
+        texture: &TileSetTexture,
+        layer: VirtualTextureLayer,
+        level: u8,
+        x: u32,
+        y: u32,
+    ) -> Result<DecodedTile> {
+        let not_found = || Error::VirtualTextureTileNotFound {
+            texture: texture.info.name.clone(),
+            layer: layer.as_str(),
+            level,
+            x,
+            y,
+        };
+        let grid = texture
+            .grid(layer, level)
+            .filter(|grid| x < grid.columns && y < grid.rows)
+            .ok_or_else(not_found)?;
+        let key = (
+            texture.page_file_index,
+            layer as u8,
+            level,
+            grid.origin.0 + x as u16,
+            grid.origin.1 + y as u16,
+        );
+        let &(page, chunk) = self.tiles.get(&key).ok_or_else(not_found)?;
+        let chunk_key = (texture.page_file_index, page, chunk);
+
+        let cached = lock(&self.cache).get(chunk_key);
+        let data = if let Some(data) = cached {
+            data
+        } else {
+            let gtp = self.page_file(texture.page_file_index)?;
+            let mut data =
+                lock(&gtp).extract_chunk(usize::from(page), usize::from(chunk), &self.gts)?;
+            // Drop the embedded half-size mip that follows the tile
+            let size = self.tile_bc_size();
+            if data.len() < size {
+                return Err(Error::InvalidFormat(format!(
+                    "tile {x},{y} of {} is {} bytes, expected {size}",
+                    texture.page_file,
+                    data.len()
+                )));
+            }
+            data.truncate(size);
+            let data: Arc<[u8]> = data.into();
+            lock(&self.cache).insert(chunk_key, Arc::clone(&data));
+            data
+        };
+
+        Ok(DecodedTile {
+            data,
+            width: self.gts.header.tile_width as u32,
+            height: self.gts.header.tile_height as u32,
+            border: self.gts.header.tile_border as u32,
+        })
+    }
+
+    /// Positions of the tiles stored for `layer` at `level`, row by row
+    ///
+    /// Grids can have holes, e.g. where a builder dropped empty tiles.
+    #[must_use]
+    pub fn tile_positions(
+        &self,
+        texture: &TileSetTexture,
+        layer: VirtualTextureLayer,
+        level: u8,
+    ) -> Vec<(u32, u32)> {
+        let Some(grid) = texture.grid(layer, level) else {
+            return Vec::new();
+        };
+        (0..grid.rows)
+            .flat_map(|y| (0..grid.columns).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                self.tiles.contains_key(&(
+                    texture.page_file_index,
+                    layer as u8,
+                    level,
+                    grid.origin.0 + x as u16,
+                    grid.origin.1 + y as u16,
+                ))
+            })
+            .collect()
+    }
+
+    /// Bytes of BC data in one tile, border included
+    fn tile_bc_size(&self) -> usize {
+        let width = self.gts.header.tile_width as usize;
+        let height = self.gts.header.tile_height as usize;
+        width.div_ceil(4) * height.div_ceil(4) * 16
+    }
+
+    /// The open handle of a page file, opening it on first use
+    fn page_file(&self, index: u16) -> Result<Arc<Mutex<GtpFile<File>>>> {
+        let mut open = lock(&self.page_files);
+        if let Some(gtp) = open.get(&index) {
+            return Ok(Arc::clone(gtp));
+        }
+        let name = &self
+            .gts
+            .page_files
+            .get(usize::from(index))
+            .ok_or(Error::InvalidPageIndex {
+                index: usize::from(index),
+            })?
+            .filename;
+        let gtp = Arc::new(Mutex::new(GtpFile::open(
+            self.page_file_dir.join(name),
+            &self.gts,
+        )?));
+        open.insert(index, Arc::clone(&gtp));
+        Ok(gtp)
+    }
+}
+
+/// Name, GUID and size of the texture in a page file
+fn describe(
+    gts: &GtsFile,
+    metadata: &[TextureInfo],
+    page_file: &str,
+    grids: &[Vec<TileGrid>; 3],
+) -> TextureInfo {
+    // Page files are named `<name>_<hash>.gtp`, with the GTex name or hash
+    let lower = page_file.to_lowercase();
+    let named = metadata
+        .iter()
+        .find(|t| !t.name.is_empty() && lower.contains(&t.name.to_lowercase()));
+    let only = (metadata.len() == 1 && gts.page_files.len() == 1).then(|| &metadata[0]);
+    if let Some(info) = named.or(only) {
+        return info.clone();
+    }
+
+    let finest = grids
+        .iter()
+        .filter_map(|g| g.first())
+        .min_by_key(|g| g.level);
+    let content = |size: i32| (size - 2 * gts.header.tile_border) as u32;
+    TextureInfo {
+        name: page_file
+            .strip_suffix(".gtp")
+            .unwrap_or(page_file)
+            .to_string(),
+        guid: None,
+        width: finest.map_or(0, |g| g.columns * content(gts.header.tile_width)),
+        height: finest.map_or(0, |g| g.rows * content(gts.header.tile_height)),
+    }
+}
+
+/// Lock a mutex whose data a panicking reader can't leave inconsistent
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Least recently used cache of decompressed tiles
+struct TileCache {
+    capacity: usize,
+    tiles: HashMap<ChunkKey, Arc<[u8]>>,
+    /// Keys from least to most recently used
+    order: VecDeque<ChunkKey>,
+}
+
+impl TileCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tiles: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&mut self, key: ChunkKey) -> Option<Arc<[u8]>> {
+        let data = self.tiles.get(&key)?;
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+        Some(Arc::clone(data))
+    }
+
+    fn insert(&mut self, key: ChunkKey, data: Arc<[u8]>) {
+        if self.capacity == 0 || self.tiles.contains_key(&key) {
+            return;
+        }
+        if self.tiles.len() >= self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.tiles.remove(&oldest);
+        }
+        self.tiles.insert(key, data);
+        self.order.push_back(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_cache_evicts_least_recently_used() {
+        let mut cache = TileCache::new(2);
+        let data = |n: u8| -> Arc<[u8]> { vec![n].into() };
+        cache.insert((0, 0, 1), data(1));
+        cache.insert((0, 0, 2), data(2));
+        // Touch 1 so 2 is the oldest
+        assert_eq!(cache.get((0, 0, 1)).as_deref(), Some(&[1u8][..]));
+        cache.insert((0, 0, 3), data(3));
+        assert!(cache.get((0, 0, 2)).is_none());
+        assert!(cache.get((0, 0, 1)).is_some());
+        assert!(cache.get((0, 0, 3)).is_some());
+
+        let mut off = TileCache::new(0);
+        off.insert((0, 0, 1), data(1));
+        assert!(off.get((0, 0, 1)).is_none());
+    }
+
+    #[test]
+    fn test_read_tiles_of_built_set() {
+        use crate::virtual_texture::builder::{
+            SourceTexture, TileCompressionPreference, VirtualTextureBuilder,
+        };
+        use image::Rgba;
+
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("Red.png");
+        RgbaImage::from_pixel(256, 256, Rgba([255, 0, 0, 255]))
+            .save(&png)
+            .unwrap();
+        let built = VirtualTextureBuilder::new()
+            .name("Tiles")
+            .compression(TileCompressionPreference::Raw)
+            .add_texture(SourceTexture::new("Red").with_base_map(&png))
+            .build(dir.path().join("out"))
+            .unwrap();
+
+        let set = VirtualTextureSet::open(&built.gts_path).unwrap();
+        let texture = &set.textures()[0];
+        let layer = VirtualTextureLayer::BaseMap;
+        let level = texture.finest_level(layer).unwrap();
+        let grid = texture.grid(layer, level).unwrap();
+        let positions = set.tile_positions(texture, layer, level);
+        assert_eq!(positions.len(), (grid.columns * grid.rows) as usize);
+
+        let (x, y) = positions[0];
+        let tile = set.read_tile(texture, layer, level, x, y).unwrap();
+        let again = set.read_tile(texture, layer, level, x, y).unwrap();
+        assert!(Arc::ptr_eq(&tile.data, &again.data));
+        let content = (tile.width - 2 * tile.border) / 4;
+        let rows: Vec<&[u8]> = tile.content_rows().collect();
+        assert_eq!(rows.len(), content as usize);
+        assert!(rows.iter().all(|row| row.len() == content as usize * 16));
+        // Opaque alpha block, then a solid red (0xF800) color block
+        assert_eq!(rows[0][..2], [255, 255]);
+        assert_eq!(rows[0][8..10], 0xF800u16.to_le_bytes());
+        let rgba = tile.to_rgba().unwrap();
+        assert_eq!(rgba.dimensions(), (tile.width, tile.height));
+
+        let missing = set.read_tile(texture, layer, level, grid.columns, 0);
+        assert!(matches!(
+            missing.unwrap_err().root(),
+            Error::VirtualTextureTileNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn test_tile_set_is_sync() {
+        fn assert_sync<T: Send + Sync>() {}
+        assert_sync::<VirtualTextureSet>();
+    }
+}
@@ -260,7 +260,7 @@ pub enum TileCompression {
     FastLZ,
 }
 
-/// GTS file header (156 bytes)
+/// GTS file header (192 bytes)
 ///
 /// Many fields are parsed for binary format completeness but not currently used.
 /// Fields like `i2`, `i6`, `i7`, `m-s`, `xjj-xmm` are reserved/unknown in BG3 files.
@@ -460,15 +460,6 @@ pub(crate) struct GtpChunkHeader {
     pub size: u32,
 }
 
-/// Tile location info for combining
-#[derive(Debug, Clone)]
-pub(crate) struct TileLocation {
-    pub page: u16,
-    pub chunk: u16,
-    pub x: u16,
-    pub y: u16,
-}
-
 /// Layer type for virtual textures
 ///
 /// BG3 virtual textures have 3 layers:
@@ -509,6 +500,36 @@ impl VirtualTextureLayer {
     }
 }
 
+/// File naming and folder layout for extracted virtual textures
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum VTexNaming {
+    /// `{GTS}/{GTP}/{TileSet}_{GTex}_{Layer}.dds`: a folder per GTS and GTP,
+    /// layers named `BaseMap`, `NormalMap` and `PhysicalMap`
+    #[default]
+    MacPak,
+    /// `{GTex}_{index}.dds` directly in the output folder, as `LSLib`'s
+    /// `ConverterApp` and `VTexTool` write them
+    LsLib,
+}
+
+impl VTexNaming {
+    /// Whether each GTS and GTP gets its own output folder
+    #[must_use]
+    pub fn per_file_folders(self) -> bool {
+        matches!(self, Self::MacPak)
+    }
+
+    /// Short name, as accepted by the CLI
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MacPak => "macpak",
+            Self::LsLib => "lslib",
+        }
+    }
+}
+
 /// Output from virtual texture extraction
 #[derive(Debug)]
 pub struct VirtualTextureOutput {
@@ -7,7 +7,11 @@
 use super::{GtpFile, GtsFile};
 use crate::error::{Error, Result};
 use serde::Serialize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Minimum number of hex digits in a GUID prefix passed to
+/// [`find_texture_by_guid`]
+pub const MIN_GUID_PREFIX_LEN: usize = 8;
 
 /// Information about a GTS file
 #[derive(Debug, Clone, Serialize)]
@@ -28,6 +32,37 @@ pub struct GtsInfo {
     pub num_levels: u32,
     /// List of GTP page files.
     pub page_files: Vec<PageFileInfo>,
+    /// Textures listed in the GTS metadata.
+    pub textures: Vec<TextureInfo>,
+}
+
+/// A texture listed in the GTS metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextureInfo {
+    /// Texture (`GTex`) name.
+    pub name: String,
+    /// Texture GUID, hyphenated lowercase, if the metadata has one.
+    pub guid: Option<String>,
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+}
+
+impl TextureInfo {
+    /// Whether this texture's GUID starts with `guid`
+    ///
+    /// Hyphens, braces and case are ignored, so full GUIDs and prefixes can
+    /// be given in any of the usual forms.
+    #[must_use]
+    pub fn matches_guid(&self, guid: &str) -> bool {
+        let query = normalize_guid(guid);
+        !query.is_empty()
+            && self
+                .guid
+                .as_deref()
+                .is_some_and(|own| normalize_guid(own).starts_with(&query))
+    }
 }
 
 /// Information about a page file
@@ -88,9 +123,85 @@ pub fn list_gts<P: AsRef<Path>>(gts_path: P) -> Result<GtsInfo> {
                 num_pages: pf.num_pages,
             })
             .collect(),
+        textures: gts.textures(),
     })
 }
 
+/// Find the texture with a given GUID across one or more GTS files
+///
+/// `guid` is a full GUID or a prefix of at least [`MIN_GUID_PREFIX_LEN`] hex
+/// digits, with or without hyphens. GTP paths are resolved to their GTS, and
+/// each GTS is searched once.
+///
+/// # Returns
+/// The GTS path and the matching texture.
+///
+/// # Errors
+/// Returns [`Error::InvalidGuid`] if `guid` is too short,
+/// [`Error::VirtualTextureGuidNotFound`] if no texture matches,
+/// [`Error::VirtualTextureGuidAmbiguous`] if a prefix matches more than one
+/// texture, or an error if a GTS file cannot be read.
+pub fn find_texture_by_guid<P: AsRef<Path>>(
+    paths: &[P],
+    guid: &str,
+) -> Result<(PathBuf, TextureInfo)> {
+    if normalize_guid(guid).len() < MIN_GUID_PREFIX_LEN {
+        return Err(Error::InvalidGuid {
+            guid: guid.to_string(),
+        });
+    }
+
+    let mut gts_paths: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        let gts_path = PathBuf::from(find_gts_path(&path.as_ref().to_string_lossy())?);
+        if !gts_paths.contains(&gts_path) {
+            gts_paths.push(gts_path);
+        }
+    }
+
+    let mut matches = Vec::new();
+    for gts_path in gts_paths {
+        let gts = GtsFile::open(&gts_path)?;
+        matches.extend(
+            gts.textures()
+                .into_iter()
+                .filter(|texture| texture.matches_guid(guid))
+                .map(|texture| (gts_path.clone(), texture)),
+        );
+    }
+
+    select_guid_match(guid, matches)
+}
+
+/// The single texture matching `guid`, or an error naming the candidates
+fn select_guid_match(
+    guid: &str,
+    mut matches: Vec<(PathBuf, TextureInfo)>,
+) -> Result<(PathBuf, TextureInfo)> {
+    match matches.len() {
+        0 => Err(Error::VirtualTextureGuidNotFound {
+            guid: guid.to_string(),
+        }),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Error::VirtualTextureGuidAmbiguous {
+            guid: guid.to_string(),
+            matches: matches
+                .iter()
+                .map(|(path, texture)| format!("{} in {}", texture.name, path.display()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        }),
+    }
+}
+
+/// Hex digits of a GUID or GUID prefix, lowercased
+fn normalize_guid(guid: &str) -> String {
+    guid.chars()
+        .filter(char::is_ascii_hexdigit)
+        .collect::<String>()
+        .to_lowercase()
+}
+
 /// Get information about a GTP file
 ///
 /// # Errors
@@ -309,3 +420,38 @@ pub fn extract_all<P1: AsRef<Path>, P2: AsRef<Path>>(
         errors,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guid_prefix_matching() {
+        let texture = |name: &str, guid: &str| TextureInfo {
+            name: name.to_string(),
+            guid: Some(guid.to_string()),
+            width: 1024,
+            height: 1024,
+        };
+        let stone = texture("Stone", "0a1b2c3d-4e5f-6071-8293-a4b5c6d7e8f9");
+        let wood = texture("Wood", "0a1b2c3d-ffff-6071-8293-a4b5c6d7e8f9");
+
+        assert!(stone.matches_guid("0A1B2C3D4E5F"));
+        assert!(stone.matches_guid("{0a1b2c3d-4e5f-6071-8293-a4b5c6d7e8f9}"));
+        assert!(!wood.matches_guid("0a1b2c3d-4e5f"));
+
+        let candidates = vec![
+            (PathBuf::from("a.gts"), stone.clone()),
+            (PathBuf::from("b.gts"), wood),
+        ];
+        let err = select_guid_match("0a1b2c3d", candidates.clone()).unwrap_err();
+        assert!(matches!(err, Error::VirtualTextureGuidAmbiguous { .. }));
+        let (path, found) = select_guid_match("0a1b2c3d-4e5f", candidates[..1].to_vec()).unwrap();
+        assert_eq!((path.as_path(), found), (Path::new("a.gts"), stone));
+
+        assert!(matches!(
+            find_texture_by_guid(&["a.gts"], "0a1b"),
+            Err(Error::InvalidGuid { .. })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-//! `FourCC` metadata tree builder and serializer
+//! `FourCC` metadata tree builder, serializer and parser
 //!
 //!
 //!
@@ -9,10 +9,18 @@
 //! - 3: Int (u32)
 //! - 8: Binary data
 //! - 0x0D: GUID (16 bytes)
+//!
+//! Each node has an 8-byte header (code, format, extended-length flag, u16
+//! length) and is padded to 4 bytes. When the extended flag is set, a u32
+//! holding the upper bits of the length follows the header.
+
+use crate::error::{Error, Result};
+use crate::utils::format_guid;
+use std::fmt;
+use std::io::{Seek, Write};
 
-use crate::error::Result;
-use byteorder::{LittleEndian, WriteBytesExt};
-use std::io::{Seek, SeekFrom, Write};
+/// Size of a node header: code, format, extended-length flag and length
+const NODE_HEADER_SIZE: usize = 8;
 
 /// Format codes for `FourCC` nodes
 #[non_exhaustive]
@@ -48,6 +56,13 @@ pub enum FourCCNode {
     Binary { fourcc: [u8; 4], data: Vec<u8> },
     /// GUID value
     Guid { fourcc: [u8; 4], guid: [u8; 16] },
+    /// Node with an unknown format code, or a value that isn't stored in the
+    /// usual form, kept as-is
+    Other {
+        fourcc: [u8; 4],
+        format: u8,
+        data: Vec<u8>,
+    },
 }
 
 impl FourCCNode {
@@ -93,6 +108,100 @@ impl FourCCNode {
             children.push(child);
         }
     }
+
+    /// The node's four-character code
+    #[must_use]
+    pub fn fourcc(&self) -> [u8; 4] {
+        match self {
+            Self::Container { fourcc, .. }
+            | Self::String { fourcc, .. }
+            | Self::Int { fourcc, .. }
+            | Self::Binary { fourcc, .. }
+            | Self::Guid { fourcc, .. }
+            | Self::Other { fourcc, .. } => *fourcc,
+        }
+    }
+
+    /// Children of a container node (empty for value nodes)
+    #[must_use]
+    pub fn children(&self) -> &[FourCCNode] {
+        match self {
+            Self::Container { children, .. } => children,
+            _ => &[],
+        }
+    }
+
+    /// First child with the given code
+    #[must_use]
+    pub fn child(&self, fourcc: [u8; 4]) -> Option<&FourCCNode> {
+        self.children().iter().find(|c| c.fourcc() == fourcc)
+    }
+
+    /// Mutable children of a container node (empty for value nodes)
+    pub fn children_mut(&mut self) -> &mut [FourCCNode] {
+        match self {
+            Self::Container { children, .. } => children,
+            _ => &mut [],
+        }
+    }
+
+    /// First child with the given code, mutably
+    pub fn child_mut(&mut self, fourcc: [u8; 4]) -> Option<&mut FourCCNode> {
+        self.children_mut()
+            .iter_mut()
+            .find(|c| c.fourcc() == fourcc)
+    }
+
+    /// Format code and value bytes (unpadded) of a value node
+    fn value_bytes(&self) -> Option<(u8, Vec<u8>)> {
+        match self {
+            Self::Container { .. } => None,
+            Self::String { value, .. } => Some((FourCCFormat::String as u8, encode_string(value))),
+            Self::Int { value, .. } => {
+                Some((FourCCFormat::Int as u8, value.to_le_bytes().to_vec()))
+            }
+            Self::Binary { data, .. } => Some((FourCCFormat::Binary as u8, data.clone())),
+            Self::Guid { guid, .. } => Some((FourCCFormat::Guid as u8, guid.to_vec())),
+            Self::Other { format, data, .. } => Some((*format, data.clone())),
+        }
+    }
+
+    /// Append the node to `out`, padded to 4 bytes
+    fn encode(&self, out: &mut Vec<u8>) {
+        if let Some((format, data)) = self.value_bytes() {
+            write_node_header(out, self.fourcc(), format, data.len());
+            out.extend_from_slice(&data);
+        } else {
+            let mut content = Vec::new();
+            for child in self.children() {
+                child.encode(&mut content);
+            }
+            write_node_header(out, self.fourcc(), FourCCFormat::Node as u8, content.len());
+            out.extend_from_slice(&content);
+        }
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+}
+
+/// Null-terminated UTF-16LE
+fn encode_string(value: &str) -> Vec<u8> {
+    value
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+/// Append a node header for `len` bytes of content
+fn write_node_header(out: &mut Vec<u8>, fourcc: [u8; 4], format: u8, len: usize) {
+    out.extend_from_slice(&fourcc);
+    out.push(format);
+    let extended = len > usize::from(u16::MAX);
+    out.push(u8::from(extended));
+    out.extend_from_slice(&(len as u16).to_le_bytes());
+    if extended {
+        out.extend_from_slice(&((len >> 16) as u32).to_le_bytes());
+    }
 }
 
 /// `FourCC` metadata tree
@@ -113,115 +222,159 @@ impl FourCCTree {
         self.root = Some(node);
     }
 
-    /// Write the tree to a writer
-    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<u32> {
-        let start_pos = writer.stream_position()?;
-
-        if let Some(ref root) = self.root {
-            Self::write_node(writer, root)?;
-        }
-
-        let end_pos = writer.stream_position()?;
-        Ok((end_pos - start_pos) as u32)
+    /// The root node, if the tree isn't empty
+    #[must_use]
+    pub fn root(&self) -> Option<&FourCCNode> {
+        self.root.as_ref()
     }
 
-    /// Write a single node
-    fn write_node<W: Write + Seek>(writer: &mut W, node: &FourCCNode) -> Result<()> {
-        match node {
-            FourCCNode::Container { fourcc, children } => {
-                // Write header with placeholder length
-                let header_pos = writer.stream_position()?;
-                writer.write_all(fourcc)?;
-                writer.write_u8(FourCCFormat::Node as u8)?;
-                writer.write_u8(0)?; // Extended length flag (0 for now)
-                writer.write_u16::<LittleEndian>(0)?; // Placeholder length
-
-                // Write children
-                for child in children {
-                    Self::write_node(writer, child)?;
-                }
+    /// Parse a tree from the bytes of a GTS `FourCC` list
+    ///
+    /// Nodes with an unknown format code, and values stored in an unusual
+    /// form, are kept as [`FourCCNode::Other`] so the tree encodes back to
+    /// the same bytes.
+    ///
+    /// # Errors
+    /// Returns an error if a node's length runs past the end of `data`.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut pos = 0;
+        let root = if data.len() >= NODE_HEADER_SIZE {
+            Some(Self::parse_node(data, &mut pos)?)
+        } else {
+            None
+        };
+        Ok(Self { root })
+    }
 
-                // Calculate and update length
-                let end_pos = writer.stream_position()?;
-                let content_len = end_pos - header_pos - 8; // Exclude header
-
-                // Seek back and update length
-                writer.seek(SeekFrom::Start(header_pos + 6))?;
-                if content_len > 0xFFFF {
-                    // Need extended length
-                    writer.seek(SeekFrom::Start(header_pos + 5))?;
-                    writer.write_u8(1)?; // Extended length flag
-                    writer.write_u16::<LittleEndian>((content_len >> 16) as u16)?;
-                    // Write lower 16 bits after seek
-                    writer.seek(SeekFrom::Start(header_pos + 6))?;
-                    writer.write_u16::<LittleEndian>(content_len as u16)?;
-                } else {
-                    writer.write_u16::<LittleEndian>(content_len as u16)?;
-                }
+    /// Parse the node at `pos`, leaving `pos` at the next 4-byte aligned node
+    fn parse_node(data: &[u8], pos: &mut usize) -> Result<FourCCNode> {
+        let truncated = |at: usize| {
+            Error::InvalidFormat(format!(
+                "FourCC node at {at:#x} runs past the end of the list"
+            ))
+        };
+        let start = *pos;
+        let header = data
+            .get(start..start + NODE_HEADER_SIZE)
+            .ok_or_else(|| truncated(start))?;
+        let fourcc = [header[0], header[1], header[2], header[3]];
+        let format = header[4];
+        let mut len = usize::from(u16::from_le_bytes([header[6], header[7]]));
+        *pos += NODE_HEADER_SIZE;
+
+        // Extended lengths store the upper bits in a u32 after the header
+        if header[5] != 0 {
+            let high = data.get(*pos..*pos + 4).ok_or_else(|| truncated(start))?;
+            len |= (u32::from_le_bytes([high[0], high[1], high[2], high[3]]) as usize) << 16;
+            *pos += 4;
+        }
 
-                // Seek back to end
-                writer.seek(SeekFrom::Start(end_pos))?;
+        let end = *pos + len;
+        let content = data.get(*pos..end).ok_or_else(|| truncated(start))?;
 
-                // Align to 4 bytes
-                Self::align_to_4(writer)?;
+        let node = match format {
+            f if f == FourCCFormat::Node as u8 => {
+                let mut children = Vec::new();
+                while *pos + NODE_HEADER_SIZE <= end {
+                    children.push(Self::parse_node(data, pos)?);
+                }
+                FourCCNode::Container { fourcc, children }
             }
-
-            FourCCNode::String { fourcc, value } => {
-                // Convert to UTF-16LE with null terminator
-                let utf16: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
-                let bytes: Vec<u8> = utf16.iter().flat_map(|&c| c.to_le_bytes()).collect();
-
-                writer.write_all(fourcc)?;
-                writer.write_u8(FourCCFormat::String as u8)?;
-                writer.write_u8(0)?;
-                writer.write_u16::<LittleEndian>(bytes.len() as u16)?;
-                writer.write_all(&bytes)?;
-
-                Self::align_to_4(writer)?;
+            f if f == FourCCFormat::String as u8 => {
+                let units: Vec<u16> = content
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .take_while(|&c| c != 0)
+                    .collect();
+                FourCCNode::string(fourcc, String::from_utf16_lossy(&units))
             }
-
-            FourCCNode::Int { fourcc, value } => {
-                writer.write_all(fourcc)?;
-                writer.write_u8(FourCCFormat::Int as u8)?;
-                writer.write_u8(0)?;
-                writer.write_u16::<LittleEndian>(4)?;
-                writer.write_u32::<LittleEndian>(*value)?;
-
-                // Already aligned (4 bytes of data)
+            f if f == FourCCFormat::Int as u8 && len == 4 => FourCCNode::int(
+                fourcc,
+                u32::from_le_bytes([content[0], content[1], content[2], content[3]]),
+            ),
+            f if f == FourCCFormat::Binary as u8 => FourCCNode::binary(fourcc, content.to_vec()),
+            f if f == FourCCFormat::Guid as u8 && len == 16 => {
+                let mut guid = [0u8; 16];
+                guid.copy_from_slice(content);
+                FourCCNode::guid(fourcc, guid)
             }
+            _ => FourCCNode::Other {
+                fourcc,
+                format,
+                data: content.to_vec(),
+            },
+        };
+
+        // Keep values that wouldn't be written back the same way as raw bytes
+        let node = match node.value_bytes() {
+            Some((_, bytes)) if bytes != content => FourCCNode::Other {
+                fourcc,
+                format,
+                data: content.to_vec(),
+            },
+            _ => node,
+        };
+
+        *pos = end.next_multiple_of(4);
+        Ok(node)
+    }
 
-            FourCCNode::Binary { fourcc, data } => {
-                writer.write_all(fourcc)?;
-                writer.write_u8(FourCCFormat::Binary as u8)?;
-                writer.write_u8(0)?;
-                writer.write_u16::<LittleEndian>(data.len() as u16)?;
-                writer.write_all(data)?;
+    /// The root node, mutably
+    pub fn root_mut(&mut self) -> Option<&mut FourCCNode> {
+        self.root.as_mut()
+    }
 
-                Self::align_to_4(writer)?;
-            }
+    /// Encode the tree as stored in a GTS file
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        if let Some(ref root) = self.root {
+            root.encode(&mut out);
+        }
+        out
+    }
 
-            FourCCNode::Guid { fourcc, guid } => {
-                writer.write_all(fourcc)?;
-                writer.write_u8(FourCCFormat::Guid as u8)?;
-                writer.write_u8(0)?;
-                writer.write_u16::<LittleEndian>(16)?;
-                writer.write_all(guid)?;
+    /// Write the tree to a writer, returning the number of bytes written
+    ///
+    /// # Errors
+    /// Returns an error if writing fails.
+    pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<u32> {
+        let bytes = self.to_bytes();
+        writer.write_all(&bytes)?;
+        Ok(bytes.len() as u32)
+    }
+}
 
-                // Already aligned (16 bytes of data)
+impl fmt::Display for FourCCTree {
+    /// Indented listing, one node per line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_node(f: &mut fmt::Formatter<'_>, node: &FourCCNode, depth: usize) -> fmt::Result {
+            let indent = depth * 2;
+            let code = String::from_utf8_lossy(&node.fourcc()).into_owned();
+            match node {
+                FourCCNode::Container { children, .. } => {
+                    writeln!(f, "{:indent$}{code}", "")?;
+                    for child in children {
+                        write_node(f, child, depth + 1)?;
+                    }
+                    Ok(())
+                }
+                FourCCNode::String { value, .. } => writeln!(f, "{:indent$}{code}  {value:?}", ""),
+                FourCCNode::Int { value, .. } => writeln!(f, "{:indent$}{code}  {value}", ""),
+                FourCCNode::Guid { guid, .. } => {
+                    writeln!(f, "{:indent$}{code}  {}", "", format_guid(guid))
+                }
+                FourCCNode::Binary { data, .. } | FourCCNode::Other { data, .. } => {
+                    let hex: Vec<String> = data.iter().map(|b| format!("{b:02x}")).collect();
+                    writeln!(f, "{:indent$}{code}  [{}]", "", hex.join(" "))
+                }
             }
         }
 
-        Ok(())
-    }
-
-    /// Align writer position to 4-byte boundary
-    fn align_to_4<W: Write + Seek>(writer: &mut W) -> Result<()> {
-        let pos = writer.stream_position()?;
-        let padding = (4 - (pos % 4)) % 4;
-        for _ in 0..padding {
-            writer.write_u8(0)?;
+        match self.root {
+            Some(ref root) => write_node(f, root, 0),
+            None => Ok(()),
         }
-        Ok(())
     }
 }
 
@@ -293,3 +446,39 @@ pub fn build_metadata_tree(
     tree.set_root(meta);
     tree
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_written_metadata() {
+        let guid = *b"0123456789abcdef";
+        let tree = build_metadata_tree("Stone", 512, 256, 0, 0, &[("BaseMap", "BaseColor")], &guid);
+        let mut out = Cursor::new(Vec::new());
+        tree.write(&mut out).unwrap();
+
+        let parsed = FourCCTree::parse(out.get_ref()).unwrap();
+        let root = parsed.root().unwrap();
+        assert_eq!(root.fourcc(), *b"META");
+        let txtr = root
+            .child(*b"ATLS")
+            .and_then(|n| n.child(*b"TXTS"))
+            .and_then(|n| n.child(*b"TXTR"))
+            .unwrap();
+        assert!(
+            matches!(txtr.child(*b"NAME"), Some(FourCCNode::String { value, .. }) if value == "Stone")
+        );
+        assert!(matches!(
+            txtr.child(*b"HGHT"),
+            Some(FourCCNode::Int { value: 256, .. })
+        ));
+        assert!(
+            matches!(txtr.child(*b"THMB"), Some(FourCCNode::Guid { guid: g, .. }) if *g == guid)
+        );
+        assert_eq!(root.child(*b"LINF").unwrap().children().len(), 1);
+
+        assert!(FourCCTree::parse(&out.get_ref()[..20]).is_err());
+    }
+}
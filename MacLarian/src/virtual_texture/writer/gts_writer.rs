@@ -131,7 +131,7 @@ impl GtsWriter {
     pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<()> {
         // Write placeholder header
         let header_pos = writer.stream_position()?;
-        let placeholder_header = [0u8; 156];
+        let placeholder_header = [0u8; 192];
         writer.write_all(&placeholder_header)?;
 
         // Write layers
@@ -0,0 +1,237 @@
+//! Filename search latency over a base-game-sized index
+//!
+//! Run with `cargo bench --bench filename_search`. The index holds 600,000
+//! synthetic entries named like BG3's assets; each query should finish in
+//! under 50ms, and each fuzzy query (with typos) in under 200ms. The first
+//! fuzzy query also builds the token list and is timed separately.
+//!
+//! The resident set size before and after building the index is printed on
+//! Linux and macOS. It measures the synthetic entries only; the size of a
+//! real base-game index has not been measured.
+
+#![allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use macpak::search::{FileType, IndexedFile, SearchIndex};
+
+/// Entries in the synthetic index, roughly the base game's PAK contents
+const ENTRY_COUNT: usize = 600_000;
+
+/// Queries timed, in table order
+const QUERIES: &[&str] = &[
+    "barbarian",
+    "barbarian tattoo",
+    "hum m tattoo",
+    "elf body armor",
+    "zzz_no_match",
+];
+
+/// Fuzzy queries timed, in table order
+const FUZZY_QUERIES: &[&str] = &[
+    "barbarain",
+    "barbarain tatoo",
+    "hum m tatoo",
+    "elf bodi armr",
+    "a",
+    "zzz_no_match",
+];
+
+/// Edits allowed per word in fuzzy queries
+const FUZZY_MAX_DISTANCE: u32 = 2;
+
+/// Latency the three-term queries must stay under
+const TARGET: Duration = Duration::from_millis(50);
+
+/// Latency every fuzzy query must stay under
+const FUZZY_TARGET: Duration = Duration::from_millis(200);
+
+/// Minimum time spent per query
+const MIN_DURATION: Duration = Duration::from_millis(500);
+
+fn main() {
+    let rss_before = resident_kib();
+    let start = Instant::now();
+    let index = synthetic_index();
+    println!(
+        "Built index of {} entries in {:.2?}",
+        index.file_count(),
+        start.elapsed()
+    );
+    if let (Some(before), Some(after)) = (rss_before, resident_kib()) {
+        println!(
+            "  RSS {:.1} MiB -> {:.1} MiB (index {:.1} MiB)",
+            before as f64 / 1024.0,
+            after as f64 / 1024.0,
+            after.saturating_sub(before) as f64 / 1024.0
+        );
+    }
+    println!("  {:<20} {:>10} {:>10}", "query", "results", "ms/query");
+
+    let mut over_target = false;
+    for query in QUERIES {
+        let (results, elapsed) = measure(&index, query);
+        let millis = elapsed.as_secs_f64() * 1000.0;
+        println!("  {query:<20} {results:>10} {millis:>10.2}");
+        if query.split_whitespace().count() >= 3 && elapsed > TARGET {
+            over_target = true;
+        }
+    }
+
+    let start = Instant::now();
+    let _ = index.search_filename_fuzzy("warmup", FUZZY_MAX_DISTANCE, None);
+    println!("\nBuilt fuzzy token list in {:.2?}", start.elapsed());
+    println!(
+        "  {:<20} {:>10} {:>10}",
+        "fuzzy query", "results", "ms/query"
+    );
+
+    let mut fuzzy_over_target = false;
+    for query in FUZZY_QUERIES {
+        let (results, elapsed) = measure_fuzzy(&index, query);
+        let millis = elapsed.as_secs_f64() * 1000.0;
+        println!("  {query:<20} {results:>10} {millis:>10.2}");
+        fuzzy_over_target |= elapsed > FUZZY_TARGET;
+    }
+
+    if over_target {
+        println!("\nThree-term queries exceeded the {TARGET:?} target");
+    }
+    if fuzzy_over_target {
+        println!("\nFuzzy queries exceeded the {FUZZY_TARGET:?} target");
+    }
+    if over_target || fuzzy_over_target {
+        std::process::exit(1);
+    }
+}
+
+/// Resident set size of this process in KiB, from `/proc/self/status`
+#[cfg(target_os = "linux")]
+fn resident_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Resident set size of this process in KiB, from `ps`
+#[cfg(target_os = "macos")]
+fn resident_kib() -> Option<u64> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Resident set size isn't read on this platform
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn resident_kib() -> Option<u64> {
+    None
+}
+
+/// Result count and mean time per search
+fn measure(index: &SearchIndex, query: &str) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut runs = 0u32;
+    let mut results = 0;
+    while runs == 0 || start.elapsed() < MIN_DURATION {
+        results = index.search_filename(query, None).len();
+        runs += 1;
+    }
+    (results, start.elapsed() / runs)
+}
+
+/// Result count and mean time per fuzzy search
+fn measure_fuzzy(index: &SearchIndex, query: &str) -> (usize, Duration) {
+    let start = Instant::now();
+    let mut runs = 0u32;
+    let mut results = 0;
+    while runs == 0 || start.elapsed() < MIN_DURATION {
+        results = index
+            .search_filename_fuzzy(query, FUZZY_MAX_DISTANCE, None)
+            .len();
+        runs += 1;
+    }
+    (results, start.elapsed() / runs)
+}
+
+/// Deterministic pseudo-random numbers (xorshift)
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+/// Index of asset-like paths such as
+/// `Generated/Public/Shared/Assets/Characters/HUM_M_ARM_BarbarianTattoo_A_017.GR2`
+fn synthetic_index() -> SearchIndex {
+    const RACES: &[&str] = &["HUM", "ELF", "DWR", "GNO", "HLF", "TIF", "ORC", "GTY"];
+    const BODIES: &[&str] = &["M", "F", "MS", "FS"];
+    const PARTS: &[&str] = &["ARM", "BODY", "HEAD", "HAIR", "FEET", "HAND", "CLOTH"];
+    const WORDS: &[&str] = &[
+        "Barbarian",
+        "Tattoo",
+        "Armor",
+        "Leather",
+        "Robe",
+        "Paladin",
+        "Wizard",
+        "Boots",
+        "Gloves",
+        "Circlet",
+        "Camp",
+        "Shadow",
+        "Scale",
+        "Chain",
+        "Plate",
+        "Cape",
+    ];
+    const DIRS: &[&str] = &[
+        "Generated/Public/Shared/Assets/Characters",
+        "Public/Shared/Content/Assets/Characters/[PAK]_Armor",
+        "Public/SharedDev/Content/Assets/Effects",
+        "Mods/Gustav/Story/DialogsBinary",
+        "Public/Game/GUI/Assets/Tooltips/Icons",
+    ];
+    const EXTENSIONS: &[(&str, FileType)] = &[
+        ("GR2", FileType::Gr2),
+        ("lsf", FileType::Lsf),
+        ("lsx", FileType::Lsx),
+        ("DDS", FileType::Dds),
+    ];
+
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let mut index = SearchIndex::new();
+    for i in 0..ENTRY_COUNT {
+        let (ext, file_type) = EXTENSIONS[(rng.next() % EXTENSIONS.len() as u64) as usize];
+        let name = format!(
+            "{}_{}_{}_{}{}_{}_{:03}.{ext}",
+            rng.pick(RACES),
+            rng.pick(BODIES),
+            rng.pick(PARTS),
+            rng.pick(WORDS),
+            rng.pick(WORDS),
+            (b'A' + (rng.next() % 4) as u8) as char,
+            i % 1000,
+        );
+        index.add_entry(IndexedFile {
+            path: format!("{}/{name}", rng.pick(DIRS)),
+            name,
+            pak_file: PathBuf::from(format!("Data/Pak{}.pak", i % 12)),
+            declared_type: file_type,
+            detected_type: None,
+            size: rng.next() % 4_000_000,
+        });
+    }
+    index
+}
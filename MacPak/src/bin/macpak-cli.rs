@@ -0,0 +1,5 @@
+//! `MacPak` CLI binary entry point
+
+fn main() -> std::process::ExitCode {
+    macpak::cli::run_cli()
+}
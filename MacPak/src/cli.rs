@@ -0,0 +1,469 @@
+//! Command-line entry point for building workbench projects and searching
+//! PAKs
+//!
+//! `mod pack` and `mod watch` go through [`Toolkit::build_project`], like the
+//! GUI Build button, so a project builds the same way everywhere. `search`
+//! and `index` use the same [`SearchIndex`] as the Search tab.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+use maclarian::pak::find_game_paks;
+use maclarian::utils::find_game_install;
+
+use crate::Toolkit;
+use crate::error::Error;
+use crate::search::{FileType, IndexedFileRef, SearchIndex, SearchProgress};
+use crate::workbench::Workbench;
+use crate::workbench::pipeline::{BuildProgress, BuildReport};
+
+#[derive(Parser)]
+#[command(name = "macpak-cli")]
+#[command(about = "Build MacPak workbench projects and search PAKs from the command line")]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Workbench project commands
+    #[command(subcommand)]
+    Mod(ModCommands),
+
+    /// Search indexed PAKs
+    #[command(subcommand)]
+    Search(SearchCommands),
+
+    /// Search index commands
+    #[command(subcommand)]
+    Index(IndexCommands),
+}
+
+/// Where `search` and `index` commands get their index from
+#[derive(Args)]
+struct IndexSource {
+    /// PAK files or directories of PAKs to index
+    #[arg(required_unless_present = "index")]
+    paks: Vec<PathBuf>,
+
+    /// Load an index exported from MacPak instead of indexing PAKs
+    #[arg(long, conflicts_with = "paks")]
+    index: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum SearchCommands {
+    /// Find files by name
+    #[command(long_about = "Find files by name
+
+Every word of the query must appear in the file name. Results are printed as
+pak:path, best matches first.
+
+--type matches a file by its extension or by the type detected from its
+content, so LSX text in a .lsf file is found by both lsx and lsf. Use
+--strict-type to match the extension only.
+
+--fuzzy tolerates typos, up to --max-distance edits per word. It is slower
+than the default search.
+
+Examples:
+  macpak-cli search filename Karlach ~/BG3/Data
+  macpak-cli search filename \"barbarian tattoo\" Shared.pak Gustav.pak --type gr2
+  macpak-cli search filename merged --index ~/MacPakIndex --type lsx --strict-type
+  macpak-cli search filename Karlatch --index ~/MacPakIndex --fuzzy")]
+    Filename {
+        /// Words to find in file names
+        query: String,
+
+        #[command(flatten)]
+        source: IndexSource,
+
+        /// Only files of this type (lsx, lsf, lsb, lsj, lsbc, xml, json, dds,
+        /// png, gr2, wem, gts, gtp)
+        #[arg(short = 't', long = "type")]
+        file_type: Option<String>,
+
+        /// Match --type against file extensions only
+        #[arg(long, requires = "file_type")]
+        strict_type: bool,
+
+        /// Tolerate typos in the query
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Edits allowed per word with --fuzzy
+        #[arg(long, default_value_t = 2, requires = "fuzzy")]
+        max_distance: u32,
+
+        /// Maximum number of results to print
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexCommands {
+    /// Index PAKs or a game install and export the index
+    #[command(long_about = "Index PAKs or a game install and export the index
+
+Builds the filename and full-text index and exports it to --output, where the
+Search tab and --index can load it.
+
+--game indexes every PAK of a game install in load order, including
+Localization and DLC folders. Without a directory, the install is detected
+(MACPAK_BG3_PATH, then the usual Steam, GOG and App Store locations). The
+install is recorded in the index so PAKs added to it later are reported as
+stale. --skip-media leaves out video, audio and shader cache PAKs.
+
+Examples:
+  macpak-cli index build --game --output ~/MacPakIndex
+  macpak-cli index build --game ~/BG3 --skip-media --output ~/MacPakIndex
+  macpak-cli index build Shared.pak Gustav.pak --output ~/MacPakIndex")]
+    Build {
+        /// PAK files or directories of PAKs to index
+        #[arg(required_unless_present = "game", conflicts_with = "game")]
+        paks: Vec<PathBuf>,
+
+        /// Index a game install, detected when DIR is omitted
+        #[arg(long, value_name = "DIR", num_args = 0..=1)]
+        #[allow(clippy::option_option)] // clap's flag with an optional value
+        game: Option<Option<PathBuf>>,
+
+        /// Leave out video, audio and shader cache PAKs with --game
+        #[arg(long, requires = "game", conflicts_with = "paks")]
+        skip_media: bool,
+
+        /// Directory to export the index to
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// List files whose content doesn't match their extension
+    #[command(long_about = "List files whose content doesn't match their extension
+
+Each file's type is detected from its first bytes while indexing. Files whose
+content is a different format than their extension says, such as LSX text in
+a _merged.lsf, are printed as pak:path with both types.
+
+Examples:
+  macpak-cli index lint ~/BG3/Data
+  macpak-cli index lint --index ~/MacPakIndex")]
+    Lint {
+        #[command(flatten)]
+        source: IndexSource,
+    },
+}
+
+#[derive(Subcommand)]
+enum ModCommands {
+    /// Build a project into a PAK
+    #[command(long_about = "Build a project into a PAK
+
+Reads macpak.toml in the project directory, converts the files matched by its
+[[build.convert]] rules, validates the mod and writes the PAK (and info.json
+when enabled) to the configured output.
+
+Examples:
+  macpak-cli mod pack ./MyMod")]
+    Pack {
+        /// Project directory containing macpak.toml
+        #[arg(default_value = ".")]
+        project: PathBuf,
+    },
+
+    /// Rebuild a project whenever its files change
+    #[command(long_about = "Rebuild a project whenever its files change
+
+Builds once, then polls the source directory and macpak.toml and rebuilds
+after any file is added, removed or modified. The build output is ignored.
+A failed build is reported and watching continues. Stop with Ctrl+C.
+
+Examples:
+  macpak-cli mod watch ./MyMod
+  macpak-cli mod watch ./MyMod --interval 2000")]
+    Watch {
+        /// Project directory containing macpak.toml
+        #[arg(default_value = ".")]
+        project: PathBuf,
+
+        /// Polling interval in milliseconds
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+}
+
+/// Run the CLI with the process arguments
+pub fn run_cli() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Commands::Mod(ModCommands::Pack { project }) => pack(&project).map(|_| ()),
+        Commands::Mod(ModCommands::Watch { project, interval }) => {
+            watch(&project, Duration::from_millis(interval))
+        }
+        Commands::Search(SearchCommands::Filename {
+            query,
+            source,
+            file_type,
+            strict_type,
+            fuzzy,
+            max_distance,
+            limit,
+        }) => search_filename(
+            &query,
+            &source,
+            file_type.as_deref(),
+            strict_type,
+            fuzzy.then_some(max_distance),
+            limit,
+        ),
+        Commands::Index(IndexCommands::Build {
+            paks,
+            game,
+            skip_media,
+            output,
+        }) => game
+            .map(|dir| dir.map_or_else(detect_game_dir, Ok))
+            .transpose()
+            .and_then(|game| build_index(&paks, game.as_deref(), skip_media, &output)),
+        Commands::Index(IndexCommands::Lint { source }) => lint_index(&source),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Build the project once and print the report
+fn pack(project: &Path) -> crate::Result<BuildReport> {
+    let mut toolkit = Toolkit::new()?;
+    toolkit.open_workbench(project)?;
+    let report = toolkit.build_project(&print_progress)?;
+    print_report(&report);
+    Ok(report)
+}
+
+/// Build, then rebuild every time the project's source stamp changes
+fn watch(project: &Path, interval: Duration) -> crate::Result<()> {
+    let stamp = |project: &Path| {
+        Workbench::open(project)
+            .and_then(|ws| ws.source_stamp())
+            .map_err(crate::Error::Workbench)
+    };
+
+    let mut last = stamp(project)?;
+    build_and_report(project);
+    println!("Watching {} for changes...", project.display());
+
+    loop {
+        thread::sleep(interval);
+        let current = match stamp(project) {
+            Ok(current) => current,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                continue;
+            }
+        };
+        if current != last {
+            last = current;
+            build_and_report(project);
+        }
+    }
+}
+
+/// Build for `watch`, printing failures instead of stopping
+fn build_and_report(project: &Path) {
+    if let Err(e) = pack(project) {
+        eprintln!("Build failed: {e}");
+    }
+}
+
+/// Expand directories into the PAKs inside them, in game load order
+fn pak_sources(sources: &[PathBuf]) -> crate::Result<Vec<PathBuf>> {
+    let mut paks = Vec::new();
+    for source in sources {
+        if source.is_dir() {
+            let found = find_game_paks(source, false)?;
+            if found.is_empty() {
+                return Err(Error::Index(format!(
+                    "No .pak files found in {}",
+                    source.display()
+                )));
+            }
+            paks.extend(found);
+        } else if !source.is_file() {
+            return Err(Error::Index(format!("{} not found", source.display())));
+        } else if !paks.contains(source) {
+            paks.push(source.clone());
+        }
+    }
+    Ok(paks)
+}
+
+/// Import the exported index, or build one over the given PAKs
+fn load_index(source: &IndexSource) -> crate::Result<SearchIndex> {
+    let mut index = SearchIndex::new();
+    match &source.index {
+        Some(dir) => index.import_index(dir)?,
+        None => {
+            let paks = pak_sources(&source.paks)?;
+            index.build_index_with_progress(&paks, &print_search_progress)?;
+        }
+    }
+    Ok(index)
+}
+
+fn parse_file_type(name: &str) -> crate::Result<FileType> {
+    match FileType::from_extension(name.trim_start_matches('.')) {
+        FileType::Other => Err(Error::Index(format!("Unknown file type '{name}'"))),
+        file_type => Ok(file_type),
+    }
+}
+
+/// Search file names, exactly or with `max_distance` typos per word
+fn search_filename(
+    query: &str,
+    source: &IndexSource,
+    file_type: Option<&str>,
+    strict_type: bool,
+    max_distance: Option<u32>,
+    limit: usize,
+) -> crate::Result<()> {
+    let filter = file_type.map(parse_file_type).transpose()?;
+    let mut index = load_index(source)?;
+    index.set_strict_types(strict_type);
+
+    let files: Vec<IndexedFileRef> = match max_distance {
+        Some(max_distance) => index
+            .search_filename_fuzzy(query, max_distance, filter)
+            .into_iter()
+            .map(|m| m.entry)
+            .collect(),
+        None => index
+            .search_filename(query, filter)
+            .into_iter()
+            .map(|m| m.entry)
+            .collect(),
+    };
+
+    for file in files.iter().take(limit) {
+        println!("{}:{}", file.pak_file.display(), file.path);
+    }
+    if files.len() > limit {
+        println!("\nShowing {limit} of {} matches", files.len());
+    } else {
+        println!("\n{} match(es)", files.len());
+    }
+    Ok(())
+}
+
+/// Data folder of the detected game install
+fn detect_game_dir() -> crate::Result<PathBuf> {
+    find_game_install()
+        .map(|install| install.data_dir)
+        .ok_or_else(|| {
+            Error::Index(
+                "No BG3 install found; pass --game <DIR> or set MACPAK_BG3_PATH".to_string(),
+            )
+        })
+}
+
+/// Index the PAKs or the game install, then export the index to `output`
+fn build_index(
+    paks: &[PathBuf],
+    game: Option<&Path>,
+    skip_media: bool,
+    output: &Path,
+) -> crate::Result<()> {
+    let mut index = SearchIndex::new();
+    match game {
+        Some(dir) => {
+            index.build_game_index_with_progress(dir, skip_media, &print_search_progress)?;
+        }
+        None => {
+            let paks = pak_sources(paks)?;
+            index.build_index_with_progress(&paks, &print_search_progress)?;
+        }
+    }
+    index.build_fulltext_index(&print_search_progress)?;
+    index.export_index_with_progress(output, &print_search_progress)?;
+
+    println!(
+        "Indexed {} files from {} PAK(s) into {}",
+        index.file_count(),
+        index.pak_count(),
+        output.display()
+    );
+    if let Some(game) = index.game_source() {
+        println!("Game install: {}", game.dir.display());
+    }
+    Ok(())
+}
+
+/// Print the files whose content doesn't match their extension
+fn lint_index(source: &IndexSource) -> crate::Result<()> {
+    let index = load_index(source)?;
+    let misnamed = index.misnamed_files();
+    for file in &misnamed {
+        let detected = file.detected_type.unwrap_or(FileType::Other);
+        println!(
+            "{}:{}: {} content, named as {}",
+            file.pak_file.display(),
+            file.path,
+            detected.display_name(),
+            file.declared_type.display_name()
+        );
+    }
+    println!(
+        "\n{} misnamed file(s) in {} indexed",
+        misnamed.len(),
+        index.file_count()
+    );
+    Ok(())
+}
+
+fn print_search_progress(progress: &SearchProgress) {
+    match &progress.current_file {
+        Some(file) => eprintln!(
+            "[{}/{}] {}: {file}",
+            progress.current,
+            progress.total,
+            progress.phase.as_str()
+        ),
+        None => eprintln!("{}", progress.phase.as_str()),
+    }
+}
+
+fn print_progress(progress: &BuildProgress) {
+    match &progress.current_file {
+        Some(file) => eprintln!(
+            "[{}/{}] {}: {file}",
+            progress.current,
+            progress.total,
+            progress.phase.as_str()
+        ),
+        None => eprintln!("{}", progress.phase.as_str()),
+    }
+}
+
+fn print_report(report: &BuildReport) {
+    for warning in &report.warnings {
+        eprintln!("Warning: {warning}");
+    }
+    println!(
+        "Built {} ({} files, {} converted)",
+        report.output_pak.display(),
+        report.pak_files.len(),
+        report.converted.len()
+    );
+    if let Some(info_json) = &report.info_json {
+        println!("Wrote {}", info_json.display());
+    }
+}
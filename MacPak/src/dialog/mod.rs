@@ -1,7 +1,8 @@
 //! Dialog format handling for Baldur's Gate 3
 //!
-//! This module provides types and parsers for BG3 dialog files (.lsf/.lsj),
-//! as well as localization caching for dialog text display.
+//! The dialog types and parser live in `maclarian::formats::dialog` and are
+//! re-exported here, along with the localization, flag, speaker and
+//! difficulty caches the dialogue tab uses for display.
 //!
 //! # Overview
 //!
@@ -37,9 +38,7 @@
 mod difficulty;
 mod flags;
 mod localization;
-mod parser;
 mod speakers;
-mod types;
 
 pub use difficulty::{DifficultyClassCache, DifficultyClassError, DifficultyClassInfo};
 pub use flags::{FlagCache, FlagCacheError};
@@ -47,9 +46,21 @@ pub use localization::{
     LocalizationCache, LocalizationError, LocalizedEntry, get_available_languages,
     load_localization_from_pak_parallel,
 };
-pub use parser::{DialogParseError, parse_dialog};
+pub use maclarian::formats::dialog::{
+    Dialog, DialogEditorData, DialogNode, Flag, FlagGroup, FlagType, GameData, NodeConstructor,
+    Rule, RuleGroup, SpeakerInfo, TagTextEntry, TaggedText,
+};
 pub use speakers::{SpeakerCache, SpeakerCacheError};
-pub use types::*;
+
+use maclarian::formats::lsj::LsjDocument;
+
+/// Parse a dialog from an LSJ document
+///
+/// # Errors
+/// Returns an error if the document has no dialog region.
+pub fn parse_dialog(doc: &LsjDocument) -> Result<Dialog, DialogParseError> {
+    maclarian::formats::dialog::parse_dialog(doc).map_err(DialogParseError::from_maclarian)
+}
 
 /// Parse dialog from LSJ bytes
 ///
@@ -73,7 +84,7 @@ pub fn parse_dialog_file<P: AsRef<std::path::Path>>(path: P) -> Result<Dialog, D
     parse_dialog(&doc)
 }
 
-/// Parse dialog from an LSF file (converts to LSJ internally)
+/// Parse dialog from an LSF file
 ///
 /// # Errors
 /// Returns an error if the file cannot be read or parsed.
@@ -82,33 +93,45 @@ pub fn parse_dialog_lsf<P: AsRef<std::path::Path>>(path: P) -> Result<Dialog, Di
     parse_dialog_lsf_bytes(&data)
 }
 
-/// Parse dialog from LSF bytes (converts through LSF → LSX → LSJ → Dialog pipeline)
+/// Parse dialog from LSF bytes
 ///
 /// This is useful when reading dialog data from PAK files or other sources
 /// where you have the raw bytes rather than a file path.
 ///
 /// # Errors
-/// Returns an error if the data cannot be parsed through the conversion pipeline.
+/// Returns an error if the data cannot be parsed as a dialog.
 pub fn parse_dialog_lsf_bytes(data: &[u8]) -> Result<Dialog, DialogParseError> {
-    use maclarian::converter::{to_lsj, to_lsx};
-    use maclarian::formats::lsf::parse_lsf_bytes;
-    use maclarian::formats::lsx::parse_lsx;
+    maclarian::formats::dialog::parse_dialog_bytes(data).map_err(DialogParseError::from_maclarian)
+}
 
-    // Parse LSF binary
-    let lsf_doc = parse_lsf_bytes(data)
-        .map_err(|e| DialogParseError::InvalidFormat(format!("LSF parse error: {e}")))?;
+/// Error type for dialog parsing
+#[derive(Debug)]
+pub enum DialogParseError {
+    MissingRegion(String),
+    InvalidFormat(String),
+    IoError(std::io::Error),
+}
 
-    // Convert LSF to LSX XML string
-    let lsx_xml = to_lsx(&lsf_doc)
-        .map_err(|e| DialogParseError::InvalidFormat(format!("LSF→LSX error: {e}")))?;
+impl DialogParseError {
+    fn from_maclarian(err: maclarian::Error) -> Self {
+        Self::InvalidFormat(err.to_string())
+    }
+}
 
-    // Parse LSX XML string to document
-    let lsx_doc = parse_lsx(&lsx_xml)
-        .map_err(|e| DialogParseError::InvalidFormat(format!("LSX parse error: {e}")))?;
+impl std::fmt::Display for DialogParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DialogParseError::MissingRegion(r) => write!(f, "Missing region: {r}"),
+            DialogParseError::InvalidFormat(s) => write!(f, "Invalid format: {s}"),
+            DialogParseError::IoError(e) => write!(f, "IO error: {e}"),
+        }
+    }
+}
 
-    // Convert to LSJ
-    let lsj_doc = to_lsj(&lsx_doc)
-        .map_err(|e| DialogParseError::InvalidFormat(format!("LSX→LSJ error: {e}")))?;
+impl std::error::Error for DialogParseError {}
 
-    parse_dialog(&lsj_doc)
+impl From<std::io::Error> for DialogParseError {
+    fn from(err: std::io::Error) -> Self {
+        DialogParseError::IoError(err)
+    }
 }
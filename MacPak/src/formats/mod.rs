@@ -17,7 +17,6 @@ pub use voice_meta::{
 // Re-export WEM/audio types
 #[cfg(feature = "gui")]
 pub use wem::{
-    AudioCache, AudioCacheError, CacheStats, CachedAudio, DecodedAudio, WemError, WemHeader,
-    WwiseVorbisHeader, decode_wwise_vorbis_fallback, load_wem_file_vgmstream, parse_wem_header,
-    parse_wwise_vorbis_header,
+    AudioCache, AudioCacheError, CacheStats, CachedAudio, DecodedAudio, WemError, decode_wem,
+    decode_wwise_vorbis_fallback, load_wem_file,
 };
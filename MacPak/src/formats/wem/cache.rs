@@ -396,13 +396,13 @@ impl AudioCache {
             find_wem_file(voice_path, wem_filename)?
         };
 
-        let audio = super::decoder::load_wem_file_vgmstream(&wem_path)
-            .map_err(AudioCacheError::DecodeError)?;
+        let audio =
+            super::decoder::load_wem_file(&wem_path).map_err(AudioCacheError::DecodeError)?;
 
         Ok((audio, wem_path))
     }
 
-    /// Load audio from a PAK file (extracts to temp file, decodes, cleans up)
+    /// Load audio from a PAK file
     #[cfg(feature = "gui")]
     fn load_from_pak(
         &self,
@@ -435,21 +435,7 @@ impl AudioCache {
             ))
         })?;
 
-        // Write to temp file for vgmstream
-        let temp_dir = std::env::temp_dir();
-        let temp_path = temp_dir.join(format!("macpak_wem_{}.wem", std::process::id()));
-
-        std::fs::write(&temp_path, &wem_data).map_err(|e| {
-            AudioCacheError::FileNotFound(format!("Failed to write temp file: {}", e))
-        })?;
-
-        // Decode with vgmstream
-        let result = super::decoder::load_wem_file_vgmstream(&temp_path);
-
-        // Clean up temp file
-        let _ = std::fs::remove_file(&temp_path);
-
-        let audio = result.map_err(AudioCacheError::DecodeError)?;
+        let audio = super::decoder::decode_wem(&wem_data).map_err(AudioCacheError::DecodeError)?;
 
         // Return PAK path as the source (for debugging/display)
         Ok((audio, pak_path.clone()))
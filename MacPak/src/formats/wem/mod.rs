@@ -12,8 +12,10 @@
 //!
 //! ## Decoding
 //!
-//! Wwise Vorbis uses a proprietary format that requires external tools to decode.
-//! Use `load_wem_file_vgmstream()` which shells out to vgmstream-cli.
+//! Parsing and conversion live in `maclarian::formats::wem`. Use
+//! `load_wem_file()` or `decode_wem()`: PCM is decoded directly, and Wwise
+//! Vorbis is rebuilt as Ogg when `packed_codebooks_aoTuV_603.bin` ships next
+//! to the executable, falling back to vgmstream-cli otherwise.
 //!
 //! Install vgmstream: `brew install vgmstream` (macOS)
 //!
@@ -25,11 +27,7 @@ mod cache;
 mod decoder;
 
 pub use decoder::{
-    DecodedAudio, WemError, WemHeader, WwiseVorbisHeader, decode_wwise_vorbis_fallback,
-    parse_wem_header, parse_wwise_vorbis_header,
+    DecodedAudio, WemError, decode_wem, decode_wwise_vorbis_fallback, load_wem_file,
 };
 
 pub use cache::{AudioCache, AudioCacheError, CacheStats, CachedAudio};
-
-#[cfg(feature = "gui")]
-pub use decoder::load_wem_file_vgmstream;
@@ -29,11 +29,13 @@ use state::*;
 use tabs::browser::{cleanup_temp_files, open_folder_dialog};
 use tabs::convert::{open_gr2_file, open_gts_file, open_lsf_file};
 use tabs::dyes::import_from_mod_folder;
-use tabs::editor::{init_config_state, open_file_dialog, save_file};
+use tabs::editor::{
+    check_external_changes, format_document, init_config_state, open_file_dialog, restore_session,
+    save_file, start_autosave, start_recovery_autosave,
+};
 use tabs::pak_ops::extract_pak_file;
 use tabs::*;
-use utils::config_dialog;
-use utils::uuid::{UuidFormat, generate_uuid};
+use utils::{UuidFormat, config_dialog, generate_uuid, uuid_dialog};
 
 /// Channel sender for cross-thread notifications (safe to call from any thread)
 static NOTIFICATION_TX: std::sync::OnceLock<std::sync::mpsc::Sender<String>> =
@@ -75,8 +77,24 @@ fn poll_notifications(rx: std::sync::mpsc::Receiver<String>, signal: RwSignal<St
     });
 }
 
+/// Log to stderr at the level set in config.toml
+fn init_logging() {
+    let level = maclarian::config::Config::load_default()
+        .ok()
+        .and_then(|config| config.log_level)
+        .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string())
+        .parse()
+        .unwrap_or(tracing::Level::WARN);
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
 /// Run the MacPak GUI application
 pub fn run_app() {
+    init_logging();
+
     // Load persisted config for window size
     let persisted = state::PersistedConfig::load();
     let window_width = persisted.window.width;
@@ -116,8 +134,12 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
 
     let gr2_state = Gr2State::new();
     let vt_state = VirtualTexturesState::new();
-    let lsf_convert_state = LsfConvertState::new();
+    let lsf_convert_state = LsfConvertState {
+        backup_count: editor_tabs_state.backup_count,
+        ..LsfConvertState::new()
+    };
     let dyes_state = DyesState::new();
+    dyes_state.apply_persisted(&persisted.dyes);
 
     let dialogue_state = DialogueState::new();
     dialogue_state.apply_persisted(&persisted.dialogue);
@@ -125,6 +147,9 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
     let workbench_state = WorkbenchState::new();
     workbench_state.apply_persisted(&persisted.workbench);
 
+    let uuid_state = UuidGeneratorState::new();
+    uuid_state.apply_persisted(&persisted.uuid);
+
     let active_tab = app_state.active_tab;
     let convert_subtab: RwSignal<usize> = RwSignal::new(0);
     let config_state_for_keyboard = config_state.clone();
@@ -137,7 +162,13 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
     poll_notifications(rx, notification);
 
     // Set up native macOS menu with Preferences
-    native_menu::setup_native_menu(editor_tabs_state.clone(), active_tab, config_state.clone());
+    native_menu::setup_native_menu(
+        editor_tabs_state.clone(),
+        browser_state.clone(),
+        active_tab,
+        config_state.clone(),
+        uuid_state.show_dialog,
+    );
 
     let editor_tabs_for_keyboard = editor_tabs_state.clone();
     let editor_tabs_for_close = editor_tabs_state.clone();
@@ -155,9 +186,18 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
     let browser_state_for_close = browser_state.clone();
     let search_state_for_close = search_state.clone();
     let dialogue_state_for_close = dialogue_state.clone();
+    let dyes_state_for_close = dyes_state.clone();
     let workbench_state_for_close = workbench_state.clone();
+    let uuid_state_for_close = uuid_state.clone();
+    let uuid_dialog_for_keyboard = uuid_state.show_dialog;
     let config_state_for_close = config_state.clone();
 
+    // Reopen the last session's tabs once the window is up
+    let editor_tabs_for_restore = editor_tabs_state.clone();
+    let open_files = persisted.editor.open_files.clone();
+    start_recovery_autosave(editor_tabs_state.clone());
+    start_autosave(editor_tabs_state.clone());
+
     v_stack((
         // Tab bar (with conditional subtab row for Convert)
         tab_bar(active_tab, convert_subtab),
@@ -182,6 +222,8 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
         notification_bar(notification),
         // Config dialog (overlays when visible)
         config_dialog(config_state.clone()),
+        // UUID generator dialog (overlays when visible)
+        uuid_dialog(uuid_state),
     ))
     .style(|s| {
         s.width_full()
@@ -193,6 +235,9 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
     .on_event_cont(EventListener::WindowGotFocus, move |_| {
         if !config_state.is_ready() {
             config_state.set_ready();
+            restore_session(editor_tabs_for_restore.clone(), &open_files);
+        } else {
+            check_external_changes(editor_tabs_for_restore.clone());
         }
     })
     .on_event(EventListener::WindowClosed, move |_| {
@@ -218,7 +263,9 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
                 &browser_state_for_close,
                 &search_state_for_close,
                 &dialogue_state_for_close,
+                &dyes_state_for_close,
                 &workbench_state_for_close,
+                &uuid_state_for_close,
             );
 
             // Kill any running preview process before exiting
@@ -240,12 +287,39 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
                 || key_event.modifiers.contains(Modifiers::CONTROL);
             let current_tab = active_tab.get();
 
-            // CMD+F / Ctrl+F - Find (Editor tab only)
-            let is_named_find = key_event.key.logical_key == Key::Named(NamedKey::Find);
+            // CMD+SHIFT+I / Ctrl+Shift+I - Format Document (Editor tab only)
+            let is_i_key = matches!(
+                &key_event.key.logical_key,
+                Key::Character(c) if c.as_str().eq_ignore_ascii_case("i")
+            );
+            if is_cmd_or_ctrl && is_i_key && key_event.modifiers.contains(Modifiers::SHIFT) {
+                if current_tab == 1 {
+                    if let Some(tab) = editor_tabs_for_keyboard.active_tab() {
+                        format_document(
+                            tab,
+                            editor_tabs_for_keyboard.format_options.get(),
+                            editor_tabs_for_keyboard.status_message,
+                        );
+                    }
+                }
+                return;
+            }
+
+            // CMD+SHIFT+F / Ctrl+Shift+F - Find in Files (opens the Editor tab)
             let is_f_key = matches!(
                 &key_event.key.logical_key,
                 Key::Character(c) if c.as_str().eq_ignore_ascii_case("f")
             );
+            if is_cmd_or_ctrl && is_f_key && key_event.modifiers.contains(Modifiers::SHIFT) {
+                active_tab.set(1);
+                editor_tabs_for_keyboard
+                    .project_search
+                    .show(browser_state_for_keyboard.current_path.get_untracked());
+                return;
+            }
+
+            // CMD+F / Ctrl+F - Find (Editor tab only)
+            let is_named_find = key_event.key.logical_key == Key::Named(NamedKey::Find);
             if is_named_find || (is_cmd_or_ctrl && is_f_key) {
                 if current_tab == 1 {
                     // Editor tab - toggle search panel
@@ -313,7 +387,7 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
                     if let Some(tab) = editor_tabs_for_keyboard.active_tab() {
                         // Only save if modified and not converted from LSF
                         if tab.modified.get() && !tab.converted_from_lsf.get() {
-                            save_file(tab);
+                            save_file(editor_tabs_for_keyboard.clone(), tab);
                         }
                     }
                 }
@@ -336,11 +410,17 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
                 return;
             }
 
-            // CMD+U / Ctrl+U - Generate UUID and copy to clipboard
+            // CMD+SHIFT+U / Ctrl+Shift+U - UUID generator (toggle dialog)
             let is_u_key = matches!(
                 &key_event.key.logical_key,
                 Key::Character(c) if c.as_str().eq_ignore_ascii_case("u")
             );
+            if is_cmd_or_ctrl && is_u_key && key_event.modifiers.contains(Modifiers::SHIFT) {
+                uuid_dialog_for_keyboard.set(!uuid_dialog_for_keyboard.get());
+                return;
+            }
+
+            // CMD+U / Ctrl+U - Generate UUID and copy to clipboard
             if is_cmd_or_ctrl && is_u_key {
                 let uuid = generate_uuid_to_clipboard();
                 send_notification(format!("Generated UUID ({uuid}) copied to clipboard"));
@@ -358,12 +438,16 @@ fn app_view(persisted: state::PersistedConfig) -> impl IntoView {
                 return;
             }
 
-            // Escape - close config dialog if open
+            // Escape - close config or UUID dialog if open
             if key_event.key.logical_key == Key::Named(NamedKey::Escape) {
                 if config_state_for_keyboard.show_dialog.get() {
                     config_state_for_keyboard.show_dialog.set(false);
                     return;
                 }
+                if uuid_dialog_for_keyboard.get() {
+                    uuid_dialog_for_keyboard.set(false);
+                    return;
+                }
             }
         }
     })
@@ -472,7 +556,12 @@ fn tab_content(
                 config_state.clone(),
             )
             .into_any(),
-            1 => editor_tab(app_state.clone(), editor_tabs_state.clone()).into_any(),
+            1 => editor_tab(
+                app_state.clone(),
+                editor_tabs_state.clone(),
+                config_state.clone(),
+            )
+            .into_any(),
             2 => pak_ops_tab(
                 app_state.clone(),
                 pak_ops_state.clone(),
@@ -521,11 +610,14 @@ fn tab_content(
         },
     )
     .style(|s| {
+        let colors = theme_signal()
+            .map(|t| ThemeColors::for_theme(t.get().effective()))
+            .unwrap_or_else(ThemeColors::dark);
         s.width_full()
             .flex_grow(1.0)
             .flex_basis(0.0)
             .min_height(0.0) // Allow content to shrink for scroll
-            .background(Color::WHITE)
+            .background(colors.bg_base)
     })
 }
 
@@ -10,42 +10,62 @@ use muda::{
 use std::collections::HashMap;
 use std::sync::Mutex;
 
-use crate::gui::state::{ConfigState, EditorTabsState};
-use crate::gui::tabs::editor::open_file_at_path;
+use crate::gui::state::{BrowserState, ConfigState, EditorTabsState, pinned_then_recent};
+use crate::gui::tabs::browser::load_pak_directory;
+use crate::gui::tabs::editor::{format_document, open_file_at_path};
 use crate::gui::{generate_uuid_to_clipboard, send_notification};
 
 /// Menu item IDs for event handling
 static PREFERENCES_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
 static CLEAR_RECENT_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
+static CLEAR_RECENT_PAKS_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
 static GENERATE_UUID_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
+static UUID_GENERATOR_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
+static FORMAT_DOCUMENT_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
+static FIND_IN_FILES_ID: std::sync::OnceLock<muda::MenuId> = std::sync::OnceLock::new();
 
 /// Map of menu item IDs to file paths for recent files
 static RECENT_FILE_IDS: std::sync::OnceLock<Mutex<HashMap<muda::MenuId, String>>> =
     std::sync::OnceLock::new();
 
+/// Map of menu item IDs to PAK paths for recent PAKs
+static RECENT_PAK_IDS: std::sync::OnceLock<Mutex<HashMap<muda::MenuId, String>>> =
+    std::sync::OnceLock::new();
+
 /// Config state for menu event handler
 static CONFIG_STATE: std::sync::OnceLock<ConfigState> = std::sync::OnceLock::new();
 
 /// Editor tabs state for opening files
 static EDITOR_TABS_STATE: std::sync::OnceLock<EditorTabsState> = std::sync::OnceLock::new();
 
+/// Browser state for opening PAKs
+static BROWSER_STATE: std::sync::OnceLock<BrowserState> = std::sync::OnceLock::new();
+
 /// Active tab signal for switching to editor
 static ACTIVE_TAB: std::sync::OnceLock<RwSignal<usize>> = std::sync::OnceLock::new();
 
+/// Visibility signal of the UUID generator dialog
+static UUID_DIALOG: std::sync::OnceLock<RwSignal<bool>> = std::sync::OnceLock::new();
+
 /// Set up the native macOS menu bar
 ///
 /// Spawns a thread that waits for the app_ready signal, then sends signal
 /// to the main thread to replace floem's default menu
 pub fn setup_native_menu(
     editor_tabs_state: EditorTabsState,
+    browser_state: BrowserState,
     active_tab: RwSignal<usize>,
     config_state: ConfigState,
+    uuid_dialog: RwSignal<bool>,
 ) {
     // Store states for later use by menu event handler
     let _ = CONFIG_STATE.set(config_state.clone());
     let _ = EDITOR_TABS_STATE.set(editor_tabs_state);
+    let _ = BROWSER_STATE.set(browser_state);
     let _ = ACTIVE_TAB.set(active_tab);
+    let _ = UUID_DIALOG.set(uuid_dialog);
     let _ = RECENT_FILE_IDS.set(Mutex::new(HashMap::new()));
+    let _ = RECENT_PAK_IDS.set(Mutex::new(HashMap::new()));
 
     // Spawn a thread to set up the menu after app is ready
     std::thread::spawn(move || {
@@ -110,43 +130,62 @@ fn create_menu_bar(config_state: ConfigState) {
     // ============ File submenu ============
     let file_submenu = Submenu::new("File", true);
 
-    // Open Recent submenu
+    // Open Recent submenu (pinned files first)
     let recent_submenu = Submenu::new("Open Recent", true);
-    let recent_files = config_state.recent_files.get();
-
-    if recent_files.is_empty() {
-        // Show disabled "No Recent Files" item
-        let no_recent = MenuItem::new("No Recent Files", false, None::<Accelerator>);
-        let _ = recent_submenu.append(&no_recent);
-    } else {
-        // Add recent files
-        if let Some(map) = RECENT_FILE_IDS.get() {
-            if let Ok(mut map) = map.lock() {
-                map.clear();
-                for path in &recent_files {
-                    // Show just the filename in the menu
-                    let display_name = std::path::Path::new(path)
-                        .file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_else(|| path.clone());
-
-                    let item = MenuItem::new(&display_name, true, None::<Accelerator>);
-                    map.insert(item.id().clone(), path.clone());
-                    let _ = recent_submenu.append(&item);
-                }
-            }
-        }
+    append_recent_items(
+        &recent_submenu,
+        &pinned_then_recent(
+            &config_state.pinned_files.get(),
+            &config_state.recent_files.get(),
+        ),
+        &RECENT_FILE_IDS,
+        "No Recent Files",
+        &CLEAR_RECENT_ID,
+    );
+    let _ = file_submenu.append(&recent_submenu);
+
+    // Open Recent PAK submenu (pinned PAKs first)
+    let recent_paks_submenu = Submenu::new("Open Recent PAK", true);
+    append_recent_items(
+        &recent_paks_submenu,
+        &pinned_then_recent(
+            &config_state.pinned_paks.get(),
+            &config_state.recent_paks.get(),
+        ),
+        &RECENT_PAK_IDS,
+        "No Recent PAKs",
+        &CLEAR_RECENT_PAKS_ID,
+    );
+    let _ = file_submenu.append(&recent_paks_submenu);
+    let _ = menu_bar.append(&file_submenu);
 
-        let _ = recent_submenu.append(&PredefinedMenuItem::separator());
+    // ============ Edit submenu ============
+    let edit_submenu = Submenu::new("Edit", true);
 
-        // Clear Recent Files
-        let clear_recent = MenuItem::new("Clear Menu", true, None::<Accelerator>);
-        let _ = CLEAR_RECENT_ID.set(clear_recent.id().clone());
-        let _ = recent_submenu.append(&clear_recent);
-    }
+    // Format Document (CMD+SHIFT+I)
+    let format_document_item = MenuItem::new(
+        "Format Document",
+        true,
+        Some(Accelerator::new(
+            Some(Modifiers::META | Modifiers::SHIFT),
+            Code::KeyI,
+        )),
+    );
+    let _ = FORMAT_DOCUMENT_ID.set(format_document_item.id().clone());
+    let _ = edit_submenu.append(&format_document_item);
 
-    let _ = file_submenu.append(&recent_submenu);
-    let _ = menu_bar.append(&file_submenu);
+    // Find in Files (CMD+SHIFT+F)
+    let find_in_files_item = MenuItem::new(
+        "Find in Files...",
+        true,
+        Some(Accelerator::new(
+            Some(Modifiers::META | Modifiers::SHIFT),
+            Code::KeyF,
+        )),
+    );
+    let _ = FIND_IN_FILES_ID.set(find_in_files_item.id().clone());
+    let _ = edit_submenu.append(&find_in_files_item);
+    let _ = menu_bar.append(&edit_submenu);
 
     // ============ Tools submenu ============
     let tools_submenu = Submenu::new("Tools", true);
@@ -160,6 +199,18 @@ fn create_menu_bar(config_state: ConfigState) {
     let _ = GENERATE_UUID_ID.set(generate_uuid_item.id().clone());
     let _ = tools_submenu.append(&generate_uuid_item);
 
+    // UUID Generator (CMD+SHIFT+U)
+    let uuid_generator_item = MenuItem::new(
+        "UUID Generator...",
+        true,
+        Some(Accelerator::new(
+            Some(Modifiers::META | Modifiers::SHIFT),
+            Code::KeyU,
+        )),
+    );
+    let _ = UUID_GENERATOR_ID.set(uuid_generator_item.id().clone());
+    let _ = tools_submenu.append(&uuid_generator_item);
+
     let _ = menu_bar.append(&tools_submenu);
 
     // Initialize as macOS app menu
@@ -200,6 +251,19 @@ fn create_menu_bar(config_state: ConfigState) {
                     }
                 }
 
+                // Check for Clear Recent PAKs
+                if let Some(clear_id) = CLEAR_RECENT_PAKS_ID.get() {
+                    if &event.id == clear_id {
+                        #[cfg(target_os = "macos")]
+                        dispatch::Queue::main().exec_async(|| {
+                            if let Some(cfg) = CONFIG_STATE.get() {
+                                cfg.clear_recent_paks();
+                            }
+                        });
+                        continue;
+                    }
+                }
+
                 // Check for Generate UUID
                 if let Some(uuid_id) = GENERATE_UUID_ID.get() {
                     if &event.id == uuid_id {
@@ -209,6 +273,62 @@ fn create_menu_bar(config_state: ConfigState) {
                     }
                 }
 
+                // Check for UUID Generator
+                if let Some(generator_id) = UUID_GENERATOR_ID.get() {
+                    if &event.id == generator_id {
+                        #[cfg(target_os = "macos")]
+                        dispatch::Queue::main().exec_async(|| {
+                            if let Some(show) = UUID_DIALOG.get() {
+                                show.set(!show.get());
+                            }
+                        });
+                        continue;
+                    }
+                }
+
+                // Check for Format Document (Editor tab only)
+                if let Some(format_id) = FORMAT_DOCUMENT_ID.get() {
+                    if &event.id == format_id {
+                        #[cfg(target_os = "macos")]
+                        dispatch::Queue::main().exec_async(|| {
+                            if let (Some(editor_state), Some(active_tab)) =
+                                (EDITOR_TABS_STATE.get(), ACTIVE_TAB.get())
+                            {
+                                if active_tab.get() != 1 {
+                                    return;
+                                }
+                                if let Some(tab) = editor_state.active_tab() {
+                                    format_document(
+                                        tab,
+                                        editor_state.format_options.get(),
+                                        editor_state.status_message,
+                                    );
+                                }
+                            }
+                        });
+                        continue;
+                    }
+                }
+
+                // Check for Find in Files
+                if let Some(find_id) = FIND_IN_FILES_ID.get() {
+                    if &event.id == find_id {
+                        #[cfg(target_os = "macos")]
+                        dispatch::Queue::main().exec_async(|| {
+                            if let (Some(editor_state), Some(active_tab)) =
+                                (EDITOR_TABS_STATE.get(), ACTIVE_TAB.get())
+                            {
+                                active_tab.set(1);
+                                let browser_path = BROWSER_STATE
+                                    .get()
+                                    .and_then(|browser| browser.current_path.get_untracked());
+                                editor_state.project_search.show(browser_path);
+                            }
+                        });
+                        continue;
+                    }
+                }
+
                 // Check for recent file click
                 if let Some(map) = RECENT_FILE_IDS.get() {
                     if let Ok(map) = map.lock() {
@@ -225,6 +345,26 @@ fn create_menu_bar(config_state: ConfigState) {
                                     open_file_at_path(editor_state.clone(), &path);
                                 }
                             });
+                            continue;
+                        }
+                    }
+                }
+
+                // Check for recent PAK click
+                if let Some(map) = RECENT_PAK_IDS.get() {
+                    if let Ok(map) = map.lock() {
+                        if let Some(path) = map.get(&event.id) {
+                            let path = path.clone();
+                            #[cfg(target_os = "macos")]
+                            dispatch::Queue::main().exec_async(move || {
+                                if let (Some(browser_state), Some(active_tab)) =
+                                    (BROWSER_STATE.get(), ACTIVE_TAB.get())
+                                {
+                                    // Switch to Browser tab and list the PAK's root
+                                    active_tab.set(0);
+                                    load_pak_directory(&path, "", browser_state.clone());
+                                }
+                            });
                         }
                     }
                 }
@@ -232,3 +372,48 @@ fn create_menu_bar(config_state: ConfigState) {
         }
     });
 }
+
+/// Fill a recent submenu with `entries` (pinned ones marked with a star) and
+/// a "Clear Menu" item, or a disabled placeholder when there are none
+fn append_recent_items(
+    submenu: &Submenu,
+    entries: &[(String, bool)],
+    ids: &std::sync::OnceLock<Mutex<HashMap<muda::MenuId, String>>>,
+    empty_label: &str,
+    clear_id: &std::sync::OnceLock<muda::MenuId>,
+) {
+    if entries.is_empty() {
+        let no_recent = MenuItem::new(empty_label, false, None::<Accelerator>);
+        let _ = submenu.append(&no_recent);
+        return;
+    }
+
+    if let Some(map) = ids.get() {
+        if let Ok(mut map) = map.lock() {
+            map.clear();
+            for (path, pinned) in entries {
+                // Show just the filename in the menu
+                let file_name = std::path::Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone());
+                let display_name = if *pinned {
+                    format!("★ {}", file_name)
+                } else {
+                    file_name
+                };
+
+                let item = MenuItem::new(&display_name, true, None::<Accelerator>);
+                map.insert(item.id().clone(), path.clone());
+                let _ = submenu.append(&item);
+            }
+        }
+    }
+
+    let _ = submenu.append(&PredefinedMenuItem::separator());
+
+    // Clear the unpinned entries
+    let clear_recent = MenuItem::new("Clear Menu", true, None::<Accelerator>);
+    let _ = clear_id.set(clear_recent.id().clone());
+    let _ = submenu.append(&clear_recent);
+}
@@ -1,5 +1,6 @@
 //! Shared drop zone component for drag-and-drop file operations
 
+use super::theme::colors;
 use floem::event::{Event, EventListener};
 use floem::prelude::*;
 
@@ -18,11 +19,10 @@ pub fn drop_zone(
             label(move || emoji.to_string()).style(|s| s.font_size(32.0)),
             label(|| "Drag files here".to_string()).style(|s| {
                 s.font_size(14.0)
-                    .color(Color::rgb8(100, 100, 100))
+                    .color(colors().text_secondary)
                     .margin_top(8.0)
             }),
-            label(move || hint.to_string())
-                .style(|s| s.font_size(12.0).color(Color::rgb8(150, 150, 150))),
+            label(move || hint.to_string()).style(|s| s.font_size(12.0).color(colors().text_muted)),
         ))
         .style(|s| s.items_center()),
     );
@@ -40,9 +40,9 @@ pub fn drop_zone(
             .padding(16.0)
             .items_center()
             .justify_center()
-            .background(Color::rgb8(249, 249, 249))
+            .background(colors().bg_surface)
             .border(2.0)
-            .border_color(Color::rgb8(204, 204, 204))
+            .border_color(colors().border_strong)
             .border_radius(8.0)
     })
 }
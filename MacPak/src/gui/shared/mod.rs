@@ -5,15 +5,23 @@
 
 mod drop_zone;
 mod progress;
+mod progress_channel;
+mod recent_list;
+mod result_entry;
 mod results;
 mod styles;
 pub mod theme;
+mod thumbnail;
 
 pub use drop_zone::drop_zone;
-pub use progress::{SharedProgress, progress_overlay};
-pub use results::results_section;
+pub use progress::{ProgressSource, SharedProgress, progress_overlay};
+pub use progress_channel::{ProgressChannel, ProgressEvent, ProgressSender};
+pub use recent_list::recent_list;
+pub use result_entry::{ResultEntry, ResultLevel, save_log};
+pub use results::{ResultAction, results_section, results_section_with_actions};
 pub use styles::{card_style, checkbox_option, header_section, operation_button};
 pub use theme::{EffectiveTheme, Theme, ThemeColors, colors, init_theme, theme_signal, themed};
+pub use thumbnail::thumbnail_cell;
 
 use floem::prelude::*;
 use im::Vector as ImVector;
@@ -27,7 +35,7 @@ pub trait BatchOperationState: Clone + 'static {
     fn is_processing(&self) -> RwSignal<bool>;
 
     /// Returns the signal containing the results log
-    fn results_log(&self) -> RwSignal<ImVector<String>>;
+    fn results_log(&self) -> RwSignal<ImVector<ResultEntry>>;
 
     /// Returns the signal containing the status message
     fn status_message(&self) -> RwSignal<String>;
@@ -38,9 +46,14 @@ pub trait BatchOperationState: Clone + 'static {
     /// Add multiple result messages in a batch
     fn add_results_batch(&self, messages: Vec<String>);
 
+    /// Add entries that already know their level and output file
+    fn add_entries(&self, entries: Vec<ResultEntry>) {
+        self.results_log().update(|log| log.extend(entries));
+    }
+
     /// Clear all results from the log
     fn clear_results(&self);
 
-    /// Get the shared progress instance for this operation type
-    fn get_shared_progress(&self) -> &'static SharedProgress;
+    /// Where the progress overlay reads this tab's progress from
+    fn progress_source(&self) -> ProgressSource;
 }
@@ -4,11 +4,14 @@ use floem::action::exec_after;
 use floem::prelude::*;
 use floem::style::Position;
 use floem_reactive::create_effect;
-use std::sync::atomic::{AtomicU32, Ordering};
+use maclarian::utils::CancelToken;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use super::BatchOperationState;
+use super::progress_channel::{ProgressChannel, ProgressEvent};
+use super::theme::colors;
 
 /// Shared progress state that can be updated from background threads
 /// and polled from the UI thread.
@@ -25,6 +28,11 @@ pub struct SharedProgress {
     pub total: Arc<AtomicU32>,
     /// Current progress message (filename)
     pub message: Arc<Mutex<String>>,
+    /// Whether the running operation checks its cancel token (shows the Cancel button)
+    pub cancellable: Arc<AtomicBool>,
+    /// Token of the running operation; [`reset`](Self::reset) replaces it so a
+    /// cancelled operation can't affect the next one
+    cancel_token: Arc<Mutex<CancelToken>>,
 }
 
 impl SharedProgress {
@@ -34,6 +42,8 @@ impl SharedProgress {
             current: Arc::new(AtomicU32::new(0)),
             total: Arc::new(AtomicU32::new(0)),
             message: Arc::new(Mutex::new(String::new())),
+            cancellable: Arc::new(AtomicBool::new(false)),
+            cancel_token: Arc::new(Mutex::new(CancelToken::new())),
         }
     }
 
@@ -72,8 +82,42 @@ impl SharedProgress {
         self.message.lock().map(|m| m.clone()).unwrap_or_default()
     }
 
-    /// Reset progress
+    /// Mark the running operation as one that stops when cancelled, and
+    /// return the token it should check
+    pub fn allow_cancel(&self) -> CancelToken {
+        self.cancellable.store(true, Ordering::SeqCst);
+        self.cancel_token()
+    }
+
+    /// Token of the running operation
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel_token
+            .lock()
+            .map(|token| token.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether the running operation can be cancelled
+    pub fn is_cancellable(&self) -> bool {
+        self.cancellable.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running operation to stop after the items already in progress
+    pub fn cancel(&self) {
+        self.cancel_token().cancel();
+    }
+
+    /// Whether the running operation has been asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token().is_cancelled()
+    }
+
+    /// Reset progress and start a new cancel token
     pub fn reset(&self) {
+        self.cancellable.store(false, Ordering::SeqCst);
+        if let Ok(mut token) = self.cancel_token.lock() {
+            *token = CancelToken::new();
+        }
         self.progress_pct.store(0, Ordering::SeqCst);
         self.current.store(0, Ordering::SeqCst);
         self.total.store(0, Ordering::SeqCst);
@@ -89,85 +133,91 @@ impl Default for SharedProgress {
     }
 }
 
+/// Where the progress overlay reads an operation's progress from
+#[derive(Clone)]
+pub enum ProgressSource {
+    /// Atomics sampled on each poll
+    Shared(&'static SharedProgress),
+    /// Events drained on each poll
+    Channel(ProgressChannel),
+}
+
+impl ProgressSource {
+    /// Progress since the last poll, oldest first
+    fn poll(&self) -> Vec<ProgressEvent> {
+        match self {
+            Self::Shared(shared) => {
+                let (current, total) = shared.get_counts();
+                vec![ProgressEvent {
+                    current: current as usize,
+                    total: total as usize,
+                    pct: shared.get_pct(),
+                    message: shared.get_message(),
+                    ..ProgressEvent::default()
+                }]
+            }
+            Self::Channel(channel) => channel.drain(),
+        }
+    }
+
+    fn is_cancellable(&self) -> bool {
+        match self {
+            Self::Shared(shared) => shared.is_cancellable(),
+            Self::Channel(channel) => channel.is_cancellable(),
+        }
+    }
+
+    fn cancel(&self) {
+        match self {
+            Self::Shared(shared) => shared.cancel(),
+            Self::Channel(channel) => channel.cancel(),
+        }
+    }
+
+    fn reset(&self) {
+        match self {
+            Self::Shared(shared) => shared.reset(),
+            Self::Channel(channel) => channel.reset(),
+        }
+    }
+}
+
 /// Generic progress overlay that works with any BatchOperationState.
 ///
 /// Shows a centered modal with progress bar, percentage, and current file name.
-/// Automatically polls the shared progress state and updates the UI.
+/// Automatically polls the state's [`ProgressSource`] and updates the UI.
+/// Operations that call `allow_cancel` get a Cancel button.
 pub fn progress_overlay<S: BatchOperationState>(state: S) -> impl IntoView {
     let show = state.is_processing();
-    let shared_progress = state.get_shared_progress();
+    let source = state.progress_source();
 
     // Local signals for polled values - updated by timer
-    let polled_pct = RwSignal::new(0u32);
-    let polled_current = RwSignal::new(0u32);
-    let polled_total = RwSignal::new(0u32);
-    let polled_msg = RwSignal::new(String::new());
+    let polled = PolledProgress {
+        pct: RwSignal::new(0u32),
+        current: RwSignal::new(0usize),
+        total: RwSignal::new(0usize),
+        msg: RwSignal::new(String::new()),
+        error: RwSignal::new(None),
+        cancellable: RwSignal::new(false),
+    };
+    let cancelling = RwSignal::new(false);
     let timer_active = RwSignal::new(false);
 
-    // Polling function
-    fn poll_and_schedule(
-        shared: &'static SharedProgress,
-        polled_pct: RwSignal<u32>,
-        polled_current: RwSignal<u32>,
-        polled_total: RwSignal<u32>,
-        polled_msg: RwSignal<String>,
-        show: RwSignal<bool>,
-        timer_active: RwSignal<bool>,
-    ) {
-        // Read from shared atomic state
-        let pct = shared.get_pct();
-        let (current, total) = shared.get_counts();
-        let msg = shared.get_message();
-
-        // Update local signals
-        polled_pct.set(pct);
-        polled_current.set(current);
-        polled_total.set(total);
-        if !msg.is_empty() {
-            polled_msg.set(msg);
-        }
-
-        // Schedule next poll if still active
-        if show.get_untracked() && timer_active.get_untracked() {
-            exec_after(Duration::from_millis(50), move |_| {
-                if show.get_untracked() && timer_active.get_untracked() {
-                    poll_and_schedule(
-                        shared,
-                        polled_pct,
-                        polled_current,
-                        polled_total,
-                        polled_msg,
-                        show,
-                        timer_active,
-                    );
-                }
-            });
-        }
-    }
-
     // Start/stop polling based on visibility
+    let source_effect = source.clone();
     create_effect(move |_| {
         let visible = show.get();
         if visible {
             // Reset and start polling
-            shared_progress.reset();
-            polled_pct.set(0);
-            polled_current.set(0);
-            polled_total.set(0);
-            polled_msg.set("Starting...".to_string());
+            source_effect.reset();
+            cancelling.set(false);
+            polled.reset();
             timer_active.set(true);
 
+            let source = source_effect.clone();
             exec_after(Duration::from_millis(50), move |_| {
                 if show.get_untracked() {
-                    poll_and_schedule(
-                        shared_progress,
-                        polled_pct,
-                        polled_current,
-                        polled_total,
-                        polled_msg,
-                        show,
-                        timer_active,
-                    );
+                    poll_and_schedule(source, polled, show, timer_active);
                 }
             });
         } else {
@@ -175,6 +225,15 @@ pub fn progress_overlay<S: BatchOperationState>(state: S) -> impl IntoView {
         }
     });
 
+    let PolledProgress {
+        pct: polled_pct,
+        current: polled_current,
+        total: polled_total,
+        msg: polled_msg,
+        error: polled_error,
+        cancellable: polled_cancellable,
+    } = polled;
+
     dyn_container(
         move || show.get(),
         move |is_processing| {
@@ -192,37 +251,52 @@ pub fn progress_overlay<S: BatchOperationState>(state: S) -> impl IntoView {
                         })
                         .style(|s| {
                             s.font_size(13.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
                                 .margin_bottom(4.0)
                         }),
                         // Filename
                         label(move || polled_msg.get())
                             .style(|s| s.font_size(14.0).margin_bottom(12.0)),
+                        // Error reported by the operation, if any
+                        label(move || polled_error.get().unwrap_or_default()).style(move |s| {
+                            if polled_error.with(Option::is_some) {
+                                s.font_size(13.0).color(colors().error).margin_bottom(12.0)
+                            } else {
+                                s.display(floem::style::Display::None)
+                            }
+                        }),
                         // Progress bar - full width
                         container(container(empty()).style(move |s| {
                             let pct = polled_pct.get();
                             s.height_full()
                                 .width_pct(pct as f64)
-                                .background(Color::rgb8(76, 175, 80))
+                                .background(colors().success)
                                 .border_radius(4.0)
                         }))
                         .style(|s| {
                             s.width_full()
                                 .height(8.0)
-                                .background(Color::rgb8(220, 220, 220))
+                                .background(colors().bg_selected)
                                 .border_radius(4.0)
                         }),
                         label(move || format!("{}%", polled_pct.get())).style(|s| {
                             s.font_size(12.0)
                                 .margin_top(8.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
+                        }),
+                        cancel_button(source.clone(), cancelling).style(move |s| {
+                            if polled_cancellable.get() {
+                                s.margin_top(12.0)
+                            } else {
+                                s.display(floem::style::Display::None)
+                            }
                         }),
                     ))
                     .style(|s| {
                         s.padding(24.0)
-                            .background(Color::WHITE)
+                            .background(colors().bg_base)
                             .border(1.0)
-                            .border_color(Color::rgb8(200, 200, 200))
+                            .border_color(colors().border_strong)
                             .border_radius(8.0)
                             .width(500.0)
                     }),
@@ -249,3 +323,82 @@ pub fn progress_overlay<S: BatchOperationState>(state: S) -> impl IntoView {
         }
     })
 }
+
+/// Cancel button: cancels the running operation's token. Items already in progress
+/// finish, and the operation reports what completed.
+fn cancel_button(source: ProgressSource, cancelling: RwSignal<bool>) -> impl IntoView {
+    button(label(move || {
+        if cancelling.get() {
+            "Cancelling...".to_string()
+        } else {
+            "Cancel".to_string()
+        }
+    }))
+    .disabled(move || cancelling.get())
+    .action(move || {
+        source.cancel();
+        cancelling.set(true);
+    })
+}
+
+/// Overlay signals updated from the polled progress
+#[derive(Clone, Copy)]
+struct PolledProgress {
+    pct: RwSignal<u32>,
+    current: RwSignal<usize>,
+    total: RwSignal<usize>,
+    msg: RwSignal<String>,
+    error: RwSignal<Option<String>>,
+    cancellable: RwSignal<bool>,
+}
+
+impl PolledProgress {
+    fn reset(self) {
+        self.pct.set(0);
+        self.current.set(0);
+        self.total.set(0);
+        self.msg.set("Starting...".to_string());
+        self.error.set(None);
+        self.cancellable.set(false);
+    }
+
+    /// Apply one event; counts stay as they are for events without a total,
+    /// and the bar stays where it is when the operation fails
+    fn apply(self, event: ProgressEvent) {
+        if event.error.is_none() {
+            self.pct.set(event.pct);
+        }
+        if event.total > 0 {
+            self.current.set(event.current);
+            self.total.set(event.total);
+        }
+        if !event.message.is_empty() {
+            self.msg.set(event.message);
+        }
+        if event.error.is_some() {
+            self.error.set(event.error);
+        }
+    }
+}
+
+/// Apply the progress since the last poll and schedule the next poll
+fn poll_and_schedule(
+    source: ProgressSource,
+    polled: PolledProgress,
+    show: RwSignal<bool>,
+    timer_active: RwSignal<bool>,
+) {
+    for event in source.poll() {
+        polled.apply(event);
+    }
+    polled.cancellable.set(source.is_cancellable());
+
+    // Schedule next poll if still active
+    if show.get_untracked() && timer_active.get_untracked() {
+        exec_after(Duration::from_millis(50), move |_| {
+            if show.get_untracked() && timer_active.get_untracked() {
+                poll_and_schedule(source, polled, show, timer_active);
+            }
+        });
+    }
+}
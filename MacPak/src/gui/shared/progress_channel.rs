@@ -0,0 +1,237 @@
+//! Typed progress events sent from worker threads to a tab's overlay
+//!
+//! Each tab owns a [`ProgressChannel`]. Its worker threads send
+//! [`ProgressEvent`]s through a [`ProgressSender`], and the overlay drains
+//! them on its timer, so every update, the finish and any error arrive in
+//! order and one tab's operation can't overwrite another's progress.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+use maclarian::utils::CancelToken;
+
+/// One progress update from a worker thread
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgressEvent {
+    /// Current item (1-based for display)
+    pub current: usize,
+    /// Total items; 0 leaves the displayed counts as they are
+    pub total: usize,
+    /// Progress as integer percentage (0-100)
+    pub pct: u32,
+    /// What is being processed (usually a file name)
+    pub message: String,
+    /// The operation has completed
+    pub finished: bool,
+    /// The operation has failed with this error
+    pub error: Option<String>,
+}
+
+impl ProgressEvent {
+    fn update(current: usize, total: usize, description: &str) -> Self {
+        let pct = if total > 0 {
+            ((current as f64 / total as f64) * 100.0).min(100.0) as u32
+        } else {
+            0
+        };
+        Self {
+            current,
+            total,
+            pct,
+            message: description.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Cancellation state shared by a channel and its senders
+#[derive(Clone)]
+struct CancelState {
+    /// Whether the running operation checks its cancel token (shows the Cancel button)
+    cancellable: Arc<AtomicBool>,
+    /// Token of the running operation; replaced by [`ProgressChannel::reset`]
+    token: Arc<Mutex<CancelToken>>,
+}
+
+impl CancelState {
+    fn token(&self) -> CancelToken {
+        self.token
+            .lock()
+            .map(|token| token.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// A tab's progress channel, stored in its state struct
+#[derive(Clone)]
+pub struct ProgressChannel {
+    sender: Sender<ProgressEvent>,
+    receiver: Arc<Mutex<Receiver<ProgressEvent>>>,
+    cancel: CancelState,
+}
+
+impl ProgressChannel {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            cancel: CancelState {
+                cancellable: Arc::new(AtomicBool::new(false)),
+                token: Arc::new(Mutex::new(CancelToken::new())),
+            },
+        }
+    }
+
+    /// Sender for a worker thread
+    pub fn sender(&self) -> ProgressSender {
+        ProgressSender {
+            sender: self.sender.clone(),
+            cancel: self.cancel.clone(),
+        }
+    }
+
+    /// All events sent since the last drain, oldest first
+    pub fn drain(&self) -> Vec<ProgressEvent> {
+        self.receiver
+            .lock()
+            .map(|receiver| receiver.try_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the running operation can be cancelled
+    pub fn is_cancellable(&self) -> bool {
+        self.cancel.cancellable.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running operation to stop after the items already in progress
+    pub fn cancel(&self) {
+        self.cancel.token().cancel();
+    }
+
+    /// Drop events left over from the last operation and start a new cancel token
+    pub fn reset(&self) {
+        self.drain();
+        self.cancel.cancellable.store(false, Ordering::SeqCst);
+        if let Ok(mut token) = self.cancel.token.lock() {
+            *token = CancelToken::new();
+        }
+    }
+}
+
+impl Default for ProgressChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sending half of a [`ProgressChannel`], moved into worker threads
+#[derive(Clone)]
+pub struct ProgressSender {
+    sender: Sender<ProgressEvent>,
+    cancel: CancelState,
+}
+
+impl ProgressSender {
+    /// Report progress
+    ///
+    /// All values should be 1-indexed for display (first item = 1, not 0).
+    pub fn update(&self, current: usize, total: usize, description: &str) {
+        self.send(ProgressEvent::update(current, total, description));
+    }
+
+    /// Report that the operation has completed
+    pub fn finish(&self) {
+        self.send(ProgressEvent {
+            pct: 100,
+            message: "Complete".to_string(),
+            finished: true,
+            ..ProgressEvent::default()
+        });
+    }
+
+    /// Report that the operation has failed
+    pub fn fail(&self, error: &str) {
+        self.send(ProgressEvent {
+            finished: true,
+            error: Some(error.to_string()),
+            ..ProgressEvent::default()
+        });
+    }
+
+    /// Mark the running operation as one that stops when cancelled, and
+    /// return the token it should check
+    pub fn allow_cancel(&self) -> CancelToken {
+        self.cancel.cancellable.store(true, Ordering::SeqCst);
+        self.cancel.token()
+    }
+
+    fn send(&self, event: ProgressEvent) {
+        // The receiver lives in the tab state, so it outlives every operation
+        let _ = self.sender.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rapid_updates_are_not_dropped() {
+        const THREADS: usize = 4;
+        const UPDATES: usize = 10_000;
+
+        let channel = ProgressChannel::new();
+        let workers: Vec<_> = (0..THREADS)
+            .map(|worker| {
+                let progress = channel.sender();
+                std::thread::spawn(move || {
+                    for i in 1..=UPDATES {
+                        progress.update(i, UPDATES, &worker.to_string());
+                    }
+                })
+            })
+            .collect();
+
+        // Drain while the workers are still sending, like the overlay's timer
+        let mut events = Vec::new();
+        while !workers.iter().all(std::thread::JoinHandle::is_finished) {
+            events.extend(channel.drain());
+        }
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        channel.sender().finish();
+        events.extend(channel.drain());
+
+        assert_eq!(events.len(), THREADS * UPDATES + 1);
+        assert!(events.last().unwrap().finished);
+        for worker in 0..THREADS {
+            let name = worker.to_string();
+            let counts: Vec<usize> = events
+                .iter()
+                .filter(|e| e.message == name)
+                .map(|e| e.current)
+                .collect();
+            assert_eq!(counts, (1..=UPDATES).collect::<Vec<_>>());
+        }
+        assert_eq!(events[events.len() - 2].pct, 100);
+    }
+
+    #[test]
+    fn test_reset_drops_stale_events_and_cancel() {
+        let channel = ProgressChannel::new();
+        let progress = channel.sender();
+        let cancel = progress.allow_cancel();
+        progress.fail("broken");
+        channel.cancel();
+        assert!(cancel.is_cancelled());
+        assert!(channel.is_cancellable());
+
+        channel.reset();
+        assert!(channel.drain().is_empty());
+        assert!(!channel.is_cancellable());
+        assert!(!progress.allow_cancel().is_cancelled());
+    }
+}
@@ -0,0 +1,104 @@
+//! Quick list of pinned and recent paths for empty tab states
+
+use std::path::Path;
+use std::rc::Rc;
+
+use floem::prelude::*;
+use floem::text::Weight;
+
+use super::theme::colors;
+use crate::gui::state::pinned_then_recent;
+
+/// Pinned entries followed by recent ones; clicking a row opens it and the
+/// pin button toggles it as a favorite. Hidden while both lists are empty.
+pub fn recent_list(
+    title: &'static str,
+    pinned: RwSignal<Vec<String>>,
+    recent: RwSignal<Vec<String>>,
+    on_open: impl Fn(String) + 'static,
+    on_toggle_pin: impl Fn(String) + 'static,
+) -> impl IntoView {
+    let on_open = Rc::new(on_open);
+    let on_toggle_pin = Rc::new(on_toggle_pin);
+    let entries = move || pinned_then_recent(&pinned.get(), &recent.get());
+
+    v_stack((
+        label(move || title).style(|s| {
+            s.font_size(12.0)
+                .font_weight(Weight::SEMIBOLD)
+                .color(colors().text_secondary)
+                .margin_bottom(4.0)
+        }),
+        dyn_stack(
+            entries,
+            |(path, is_pinned)| (path.clone(), *is_pinned),
+            move |(path, is_pinned)| {
+                recent_row(path, is_pinned, on_open.clone(), on_toggle_pin.clone())
+            },
+        )
+        .style(|s| s.flex_col().width_full()),
+    ))
+    .style(move |s| {
+        let s = s.width(420.0).margin_top(16.0);
+        if pinned.with(Vec::is_empty) && recent.with(Vec::is_empty) {
+            s.display(floem::style::Display::None)
+        } else {
+            s
+        }
+    })
+}
+
+fn recent_row(
+    path: String,
+    is_pinned: bool,
+    on_open: Rc<dyn Fn(String)>,
+    on_toggle_pin: Rc<dyn Fn(String)>,
+) -> impl IntoView {
+    let name = Path::new(&path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    let folder = Path::new(&path)
+        .parent()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    let path_for_open = path.clone();
+
+    h_stack((
+        label(move || name.clone()).style(|s| s.font_size(13.0).text_ellipsis().min_width(0.0)),
+        label(move || folder.clone()).style(|s| {
+            s.font_size(11.0)
+                .color(colors().text_muted)
+                .text_ellipsis()
+                .min_width(0.0)
+                .flex_grow(1.0)
+                .flex_basis(0.0)
+        }),
+        button(if is_pinned { "Unpin" } else { "Pin" })
+            .action(move || on_toggle_pin(path.clone()))
+            .style(move |s| {
+                let s = s
+                    .padding_vert(2.0)
+                    .padding_horiz(6.0)
+                    .font_size(11.0)
+                    .border_radius(4.0)
+                    .background(colors().bg_elevated);
+                if is_pinned {
+                    s.color(colors().accent)
+                } else {
+                    s.color(colors().text_secondary)
+                }
+            }),
+    ))
+    .on_click_stop(move |_| on_open(path_for_open.clone()))
+    .style(|s| {
+        s.width_full()
+            .padding_vert(4.0)
+            .padding_horiz(8.0)
+            .gap(8.0)
+            .items_center()
+            .border_radius(4.0)
+            .cursor(floem::style::CursorStyle::Pointer)
+            .hover(|s| s.background(colors().bg_hover))
+    })
+}
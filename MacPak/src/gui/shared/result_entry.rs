@@ -0,0 +1,180 @@
+//! Entries of the shared results log
+//!
+//! Each row carries a timestamp, a level and optionally the file it produced,
+//! so the log can be filtered by level, copied and saved as text or CSV.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+/// Severity of a results log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResultLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ResultLevel {
+    /// Name used in saved logs
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "INFO",
+            Self::Success => "OK",
+            Self::Warning => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+
+    /// Classify a plain message from an operation that reports strings
+    pub fn classify(message: &str) -> Self {
+        let message = message.trim_start();
+        if message.starts_with("Error") || message.starts_with("Failed") {
+            Self::Error
+        } else if message.starts_with('⚠')
+            || message.starts_with("Warning")
+            || message.starts_with("Cancelled")
+        {
+            Self::Warning
+        } else {
+            Self::Success
+        }
+    }
+}
+
+/// One row of a results log
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultEntry {
+    pub timestamp: DateTime<Local>,
+    pub level: ResultLevel,
+    pub message: String,
+    /// File the operation wrote, if any
+    pub path: Option<PathBuf>,
+}
+
+impl ResultEntry {
+    pub fn new(level: ResultLevel, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: Local::now(),
+            level,
+            message: message.into(),
+            path: None,
+        }
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(ResultLevel::Success, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(ResultLevel::Error, message)
+    }
+
+    /// Entry whose level is read from the message wording
+    pub fn from_message(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::new(ResultLevel::classify(&message), message)
+    }
+
+    /// Attach the file this entry refers to
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Time of day shown next to the row
+    pub fn time_label(&self) -> String {
+        self.timestamp.format("%H:%M:%S").to_string()
+    }
+}
+
+/// Write `entries` to `path` as CSV if it ends in `.csv`, plain text otherwise
+pub fn save_log(path: &Path, entries: &[ResultEntry]) -> Result<(), String> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let contents = if is_csv {
+        format_csv(entries)
+    } else {
+        format_text(entries)
+    };
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn format_text(entries: &[ResultEntry]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let _ = write!(
+            out,
+            "[{}] {:<5} {}",
+            entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            entry.level.label(),
+            entry.message
+        );
+        if let Some(path) = &entry.path {
+            let _ = write!(out, " ({})", path.display());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn format_csv(entries: &[ResultEntry]) -> String {
+    let mut out = String::from("timestamp,level,message,path\n");
+    for entry in entries {
+        let path = entry
+            .path
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "{},{},{},{}",
+            entry.timestamp.to_rfc3339(),
+            entry.level.label(),
+            csv_field(&entry.message),
+            csv_field(&path)
+        );
+    }
+    out
+}
+
+/// Quote a field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_and_csv() {
+        assert_eq!(
+            ResultLevel::classify("Failed a.gr2: bad"),
+            ResultLevel::Error
+        );
+        assert_eq!(ResultLevel::classify("  Warning: x"), ResultLevel::Warning);
+        assert_eq!(
+            ResultLevel::classify("Converted a -> b"),
+            ResultLevel::Success
+        );
+
+        let entry = ResultEntry::error("Failed \"a, b\"").with_path("/out/a.glb");
+        let csv = format_csv(std::slice::from_ref(&entry));
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            format!(
+                "{},ERROR,\"Failed \"\"a, b\"\"\",/out/a.glb",
+                entry.timestamp.to_rfc3339()
+            )
+        );
+    }
+}
@@ -1,37 +1,60 @@
 //! Shared results log section for batch operations
 
+use std::process::Command;
+
+use floem::action::show_context_menu;
+use floem::event::EventListener;
+use floem::menu::{Menu, MenuItem};
 use floem::prelude::*;
 use floem::text::Weight;
 use floem::views::{VirtualDirection, VirtualItemSize, virtual_list};
 use im::Vector as ImVector;
 
 use super::BatchOperationState;
+use super::result_entry::{ResultEntry, ResultLevel, save_log};
 use super::styles::card_style;
+use super::theme::colors;
+use crate::gui::utils::copy_to_clipboard;
+
+/// Button on a results log row
+pub struct ResultAction {
+    pub label: &'static str,
+    pub run: Box<dyn Fn()>,
+}
 
 /// Generic results log section that works with any BatchOperationState.
 ///
 /// Displays a scrollable, virtualized list of operation results with:
-/// - Color-coded success (green) and failure (red) messages
+/// - Timestamped rows color-coded by level
 /// - "Show Failures Only" filter toggle with failure count badge
-/// - Clear button to reset the log
+/// - Right-click menu to copy a row or reveal its output file
+/// - "Save Log…" (text or CSV) and Clear buttons
 pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
+    results_section_with_actions(state, |_| None)
+}
+
+/// Results log section where `row_action` can add a button to a row
+pub fn results_section_with_actions<S: BatchOperationState>(
+    state: S,
+    row_action: impl Fn(&ResultEntry) -> Option<ResultAction> + 'static,
+) -> impl IntoView {
     let state_for_clear = state.clone();
+    let state_for_save = state.clone();
     let state_for_log = state.clone();
     let state_for_filter = state.clone();
     let state_for_filter2 = state.clone();
     let show_failures_only = RwSignal::new(false);
 
-    // Filtered results based on toggle
+    // Filtered results based on toggle, keyed by position in the log
     let filtered_results = move || {
-        let log = state_for_log.results_log().get();
         let filter = show_failures_only.get();
-        if filter {
-            log.into_iter()
-                .filter(|msg| msg.starts_with("Error") || msg.starts_with("Failed"))
-                .collect::<ImVector<_>>()
-        } else {
-            log
-        }
+        state_for_log
+            .results_log()
+            .get()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, entry)| !filter || entry.level == ResultLevel::Error)
+            .collect::<ImVector<_>>()
     };
 
     v_stack((
@@ -44,7 +67,7 @@ pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
                     .results_log()
                     .get()
                     .iter()
-                    .filter(|msg| msg.starts_with("Error") || msg.starts_with("Failed"))
+                    .filter(|entry| entry.level == ResultLevel::Error)
                     .count();
                 if show_failures_only.get() {
                     "Show All".to_string()
@@ -60,7 +83,7 @@ pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
                     .results_log()
                     .get()
                     .iter()
-                    .any(|msg| msg.starts_with("Error") || msg.starts_with("Failed"));
+                    .any(|entry| entry.level == ResultLevel::Error);
                 let s = s
                     .padding_horiz(10.0)
                     .padding_vert(4.0)
@@ -69,32 +92,26 @@ pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
                     .margin_right(8.0);
 
                 if is_active {
-                    s.background(Color::rgb8(211, 47, 47)).color(Color::WHITE)
+                    s.background(colors().error).color(colors().text_on_accent)
                 } else if has_failures {
-                    s.background(Color::rgb8(255, 235, 235))
-                        .color(Color::rgb8(180, 30, 30))
-                        .hover(|s| s.background(Color::rgb8(255, 220, 220)))
+                    s.background(colors().error_bg)
+                        .color(colors().error)
+                        .hover(|s| s.background(colors().error_bg))
                 } else {
-                    s.background(Color::rgb8(240, 240, 240))
-                        .color(Color::rgb8(150, 150, 150))
+                    s.background(colors().bg_elevated)
+                        .color(colors().text_muted)
                 }
             })
             .action(move || {
                 show_failures_only.set(!show_failures_only.get());
             }),
-            button("Clear")
-                .style(|s| {
-                    s.padding_horiz(12.0)
-                        .padding_vert(4.0)
-                        .font_size(11.0)
-                        .background(Color::rgb8(240, 240, 240))
-                        .border_radius(4.0)
-                        .hover(|s| s.background(Color::rgb8(220, 220, 220)))
-                })
-                .action(move || {
-                    state_for_clear.clear_results();
-                    show_failures_only.set(false);
-                }),
+            button("Save Log…")
+                .style(|s| header_button_style(s).margin_right(8.0))
+                .action(move || save_log_dialog(&state_for_save)),
+            button("Clear").style(header_button_style).action(move || {
+                state_for_clear.clear_results();
+                show_failures_only.set(false);
+            }),
         ))
         .style(|s| s.width_full().margin_bottom(8.0)),
         scroll(
@@ -102,25 +119,54 @@ pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
                 VirtualDirection::Vertical,
                 VirtualItemSize::Fixed(Box::new(|| 22.0)),
                 filtered_results,
-                |msg: &String| msg.clone(),
-                |msg| {
-                    let is_error = msg.starts_with("Error") || msg.starts_with("Failed");
-                    container(label(move || msg.clone()).style(move |s| {
-                        let s = s.font_size(11.0).font_family("monospace".to_string());
-                        if is_error {
-                            s.color(Color::rgb8(180, 30, 30))
-                        } else {
-                            s.color(Color::rgb8(46, 125, 50))
-                        }
-                    }))
+                |(index, _): &(usize, ResultEntry)| *index,
+                move |(_, entry)| {
+                    let level = entry.level;
+                    let is_error = level == ResultLevel::Error;
+                    let action = row_action(&entry);
+                    let time = entry.time_label();
+                    let message = entry.message.clone();
+                    h_stack((
+                        label(move || time.clone()).style(|s| {
+                            s.font_size(10.0)
+                                .font_family("monospace".to_string())
+                                .color(colors().text_muted)
+                                .margin_right(8.0)
+                        }),
+                        label(move || message.clone()).style(move |s| {
+                            let s = s
+                                .font_size(11.0)
+                                .font_family("monospace".to_string())
+                                .flex_grow(1.0)
+                                .min_width(0.0)
+                                .text_ellipsis();
+                            match level {
+                                ResultLevel::Error => s.color(colors().error),
+                                ResultLevel::Warning => s.color(colors().warning),
+                                ResultLevel::Success => s.color(colors().success),
+                                ResultLevel::Info => s.color(colors().text_secondary),
+                            }
+                        }),
+                        match action {
+                            Some(action) => button(action.label)
+                                .style(|s| s.font_size(10.0).padding_horiz(6.0).padding_vert(0.0))
+                                .action(move || (action.run)())
+                                .into_any(),
+                            None => empty().into_any(),
+                        },
+                    ))
+                    .on_event_stop(EventListener::SecondaryClick, move |_| {
+                        show_row_menu(&entry);
+                    })
                     .style(move |s| {
                         let s = s
                             .width_full()
                             .height(22.0)
+                            .items_center()
                             .padding_vert(2.0)
                             .padding_horiz(4.0);
                         if is_error {
-                            s.background(Color::rgb8(255, 235, 235))
+                            s.background(colors().error_bg)
                         } else {
                             s
                         }
@@ -135,9 +181,9 @@ pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
                 .min_height(0.0)
                 .flex_grow(1.0)
                 .flex_basis(0.0)
-                .background(Color::rgb8(250, 250, 250))
+                .background(colors().bg_surface)
                 .border(1.0)
-                .border_color(Color::rgb8(220, 220, 220))
+                .border_color(colors().border)
                 .border_radius(4.0)
         }),
     ))
@@ -149,3 +195,56 @@ pub fn results_section<S: BatchOperationState>(state: S) -> impl IntoView {
             .flex_basis(0.0)
     })
 }
+
+fn header_button_style(s: floem::style::Style) -> floem::style::Style {
+    s.padding_horiz(12.0)
+        .padding_vert(4.0)
+        .font_size(11.0)
+        .background(colors().bg_elevated)
+        .border_radius(4.0)
+        .hover(|s| s.background(colors().bg_selected))
+}
+
+/// Right-click menu of a results log row
+fn show_row_menu(entry: &ResultEntry) {
+    let message = entry.message.clone();
+    let mut menu = Menu::new("")
+        .entry(MenuItem::new("Copy Message").action(move || copy_to_clipboard(&message)));
+
+    if let Some(path) = entry.path.clone() {
+        let path_for_copy = path.display().to_string();
+        menu = menu
+            .separator()
+            .entry(MenuItem::new("Show in Finder").action(move || {
+                let _ = Command::new("open").arg("-R").arg(&path).spawn();
+            }))
+            .entry(MenuItem::new("Copy Path").action(move || copy_to_clipboard(&path_for_copy)));
+    }
+
+    show_context_menu(menu, None);
+}
+
+/// Ask where to save the log and write it as text or CSV
+fn save_log_dialog<S: BatchOperationState>(state: &S) {
+    let entries: Vec<ResultEntry> = state.results_log().get_untracked().into_iter().collect();
+    if entries.is_empty() {
+        state.status_message().set("Nothing to save".to_string());
+        return;
+    }
+
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Save Results Log")
+        .set_file_name("results.txt")
+        .add_filter("Text", &["txt"])
+        .add_filter("CSV", &["csv"])
+        .save_file()
+    else {
+        return;
+    };
+
+    let status = match save_log(&path, &entries) {
+        Ok(()) => format!("Saved {} log entries to {}", entries.len(), path.display()),
+        Err(e) => e,
+    };
+    state.status_message().set(status);
+}
@@ -4,6 +4,7 @@ use floem::prelude::*;
 use floem::text::Weight;
 
 use super::BatchOperationState;
+use super::theme::colors;
 
 /// Standard card style used for sections in batch operation tabs.
 ///
@@ -11,9 +12,9 @@ use super::BatchOperationState;
 pub fn card_style(s: floem::style::Style) -> floem::style::Style {
     s.width_full()
         .padding(16.0)
-        .background(Color::WHITE)
+        .background(colors().bg_base)
         .border(1.0)
-        .border_color(Color::rgb8(220, 220, 220))
+        .border_color(colors().border)
         .border_radius(8.0)
 }
 
@@ -40,11 +41,9 @@ pub fn header_section<S: BatchOperationState>(title: &'static str, state: S) ->
                                 .border_radius(4.0)
                                 .font_size(12.0);
                             if is_error {
-                                s.background(Color::rgb8(255, 235, 235))
-                                    .color(Color::rgb8(180, 30, 30))
+                                s.background(colors().error_bg).color(colors().error)
                             } else {
-                                s.background(Color::rgb8(232, 245, 233))
-                                    .color(Color::rgb8(46, 125, 50))
+                                s.background(colors().success_bg).color(colors().success)
                             }
                         })
                         .into_any()
@@ -57,9 +56,9 @@ pub fn header_section<S: BatchOperationState>(title: &'static str, state: S) ->
             .padding(16.0)
             .gap(8.0)
             .items_center()
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -71,13 +70,13 @@ pub fn operation_button(text: &'static str, on_click: impl Fn() + 'static) -> im
         s.width_full()
             .padding_vert(10.0)
             .padding_horiz(16.0)
-            .background(Color::rgb8(245, 245, 245))
+            .background(colors().bg_elevated)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(6.0)
             .hover(|s| {
-                s.background(Color::rgb8(230, 230, 230))
-                    .border_color(Color::rgb8(180, 180, 180))
+                s.background(colors().bg_hover)
+                    .border_color(colors().border_strong)
             })
     })
 }
@@ -40,14 +40,20 @@ pub enum EffectiveTheme {
 }
 
 /// Check if macOS is in dark mode
+///
+/// Style closures resolve the theme on every restyle, so the answer is read
+/// once per run instead of spawning `defaults` each time.
 #[cfg(target_os = "macos")]
 fn is_system_dark_mode() -> bool {
-    use std::process::Command;
-    Command::new("defaults")
-        .args(["read", "-g", "AppleInterfaceStyle"])
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).contains("Dark"))
-        .unwrap_or(false)
+    static SYSTEM_DARK: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *SYSTEM_DARK.get_or_init(|| {
+        use std::process::Command;
+        Command::new("defaults")
+            .args(["read", "-g", "AppleInterfaceStyle"])
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).contains("Dark"))
+            .unwrap_or(false)
+    })
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -70,6 +76,10 @@ pub struct ThemeColors {
     pub text_secondary: Color,
     pub text_muted: Color,
     pub text_inverse: Color,
+    /// Text on accent, success and error backgrounds
+    pub text_on_accent: Color,
+    /// Placeholder-like text and disabled labels
+    pub text_disabled: Color,
 
     // Borders
     pub border: Color,
@@ -78,6 +88,8 @@ pub struct ThemeColors {
     // Accents
     pub accent: Color,
     pub accent_hover: Color,
+    /// Tint behind selected or highlighted rows
+    pub accent_bg: Color,
     pub success: Color,
     pub success_bg: Color,
     pub error: Color,
@@ -102,6 +114,8 @@ impl ThemeColors {
             text_secondary: Color::rgb8(180, 180, 180),
             text_muted: Color::rgb8(128, 128, 128),
             text_inverse: Color::rgb8(30, 30, 30),
+            text_on_accent: Color::WHITE,
+            text_disabled: Color::rgb8(90, 90, 90),
 
             // Borders
             border: Color::rgb8(60, 60, 60),
@@ -110,6 +124,7 @@ impl ThemeColors {
             // Accents
             accent: Color::rgb8(33, 150, 243),
             accent_hover: Color::rgb8(66, 165, 245),
+            accent_bg: Color::rgb8(30, 55, 80),
             success: Color::rgb8(46, 125, 50),
             success_bg: Color::rgb8(30, 60, 35),
             error: Color::rgb8(211, 47, 47),
@@ -134,6 +149,8 @@ impl ThemeColors {
             text_secondary: Color::rgb8(80, 80, 80),
             text_muted: Color::rgb8(128, 128, 128),
             text_inverse: Color::WHITE,
+            text_on_accent: Color::WHITE,
+            text_disabled: Color::rgb8(180, 180, 180),
 
             // Borders
             border: Color::rgb8(220, 220, 220),
@@ -142,6 +159,7 @@ impl ThemeColors {
             // Accents
             accent: Color::rgb8(25, 118, 210),
             accent_hover: Color::rgb8(21, 101, 192),
+            accent_bg: Color::rgb8(227, 242, 253),
             success: Color::rgb8(46, 125, 50),
             success_bg: Color::rgb8(232, 245, 233),
             error: Color::rgb8(180, 30, 30),
@@ -0,0 +1,72 @@
+//! Thumbnail cell for file rows - shows the type icon until a preview is ready
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, OnceLock};
+
+use floem::ext_event::create_ext_action;
+use floem::prelude::*;
+use floem_reactive::Scope;
+
+use crate::gui::tabs::browser::raw_img::raw_img;
+use crate::search::IndexedFile;
+use crate::thumbnails::{
+    DEFAULT_WORKERS, Thumbnail, ThumbnailCache, ThumbnailService, has_thumbnail,
+};
+
+/// Global thumbnail service, shared by the browser and search tabs
+static THUMBNAIL_SERVICE: OnceLock<Option<ThumbnailService>> = OnceLock::new();
+
+/// Global scope for thumbnail callbacks - reused to prevent scope accumulation
+static EXT_ACTION_SCOPE: OnceLock<Scope> = OnceLock::new();
+
+fn thumbnail_service() -> Option<&'static ThumbnailService> {
+    THUMBNAIL_SERVICE
+        .get_or_init(|| {
+            ThumbnailCache::default_location()
+                .map(|cache| ThumbnailService::new(cache, DEFAULT_WORKERS))
+        })
+        .as_ref()
+}
+
+/// A `size`x`size` cell showing the thumbnail of `file`, or `icon` while it
+/// loads and for files without one
+pub fn thumbnail_cell(file: Option<IndexedFile>, size: u32, icon: String) -> impl IntoView {
+    let thumbnail: RwSignal<Option<Arc<Thumbnail>>> = RwSignal::new(None);
+    let mut cache_key = 0;
+
+    let service = thumbnail_service();
+    if let (Some(file), Some(service)) = (file.filter(|f| has_thumbnail(f.file_type())), service) {
+        let mut hasher = DefaultHasher::new();
+        (&file.pak_file, &file.path, size).hash(&mut hasher);
+        cache_key = hasher.finish();
+
+        match service.cached(&file, size) {
+            Some(known) => thumbnail.set(known),
+            None => {
+                let scope = *EXT_ACTION_SCOPE.get_or_init(Scope::new);
+                let send = create_ext_action(scope, move |loaded: Option<Arc<Thumbnail>>| {
+                    thumbnail.set(loaded);
+                });
+                service.request(&file, size, send);
+            }
+        }
+    }
+
+    dyn_container(
+        move || thumbnail.get(),
+        move |loaded| match loaded {
+            Some(t) => raw_img(t.width, t.height, t.rgba_data.clone(), cache_key).into_any(),
+            None => {
+                let icon = icon.clone();
+                label(move || icon.clone()).into_any()
+            }
+        },
+    )
+    .style(move |s| {
+        s.width(size as f64)
+            .height(size as f64)
+            .flex_shrink(0.0)
+            .items_center()
+            .justify_center()
+    })
+}
@@ -1,11 +1,29 @@
 //! Browser tab state
 
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, RwLock};
+
 use floem::prelude::*;
+use im::Vector as ImVector;
+use maclarian::pak::PakReaderCache;
+
+use crate::gui::shared::{BatchOperationState, ProgressSource, ResultEntry, SharedProgress};
+use crate::index::FileIndex;
+use crate::search::ContentCache;
+
+/// Global shared progress instance for extractions started from the browser
+static BROWSER_SHARED_PROGRESS: std::sync::OnceLock<SharedProgress> = std::sync::OnceLock::new();
+
+/// Get or create the global shared progress instance for browser extractions
+pub fn get_shared_progress() -> &'static SharedProgress {
+    BROWSER_SHARED_PROGRESS.get_or_init(SharedProgress::new)
+}
 
 /// Sort column options for the file browser
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SortColumn {
     Name,
+    Path,
     Type,
     Size,
     Modified,
@@ -24,7 +42,10 @@ pub struct RawImageData {
 pub struct BrowserState {
     pub current_path: RwSignal<Option<String>>,
     pub browser_path: RwSignal<String>, // Editable path shown in toolbar
-    pub selected_index: RwSignal<Option<usize>>,
+    pub selected_index: RwSignal<Option<usize>>, // Focused row (shown in the preview)
+    // Multi-selection: highlighted rows, and where shift-click ranges start
+    pub selected_indices: RwSignal<BTreeSet<usize>>,
+    pub selection_anchor: RwSignal<Option<usize>>,
     pub files: RwSignal<Vec<FileEntry>>,
     pub all_files: RwSignal<Vec<FileEntry>>,
     pub search_query: RwSignal<String>,
@@ -39,6 +60,11 @@ pub struct BrowserState {
     pub status_message: RwSignal<String>,
     pub sort_column: RwSignal<SortColumn>,
     pub sort_ascending: RwSignal<bool>,
+    // Group-by-type mode: types whose sections are folded, and (type, count)
+    // of each section in display order
+    pub group_by_type: RwSignal<bool>,
+    pub collapsed_types: RwSignal<BTreeSet<String>>,
+    pub type_groups: RwSignal<Vec<(String, usize)>>,
     // Inline rename state
     pub renaming_path: RwSignal<Option<String>>, // Path of file being renamed (None = not renaming)
     pub rename_text: RwSignal<String>,           // Current text in rename input
@@ -57,6 +83,16 @@ pub struct BrowserState {
     pub gr2_convert_to_gltf: RwSignal<bool>,
     pub gr2_extract_textures: RwSignal<bool>,
     pub gr2_convert_to_png: RwSignal<bool>,
+    // PAK browsing state
+    pub pak_path: RwSignal<Option<String>>, // PAK being browsed (None = filesystem)
+    pub pak_cache: Arc<Mutex<PakReaderCache>>,
+    // Index of the folder being browsed, so refreshes only apply what changed
+    pub file_index: Arc<Mutex<Option<FileIndex>>>,
+    // Decoded image previews, keyed by pak + path
+    pub preview_cache: Arc<RwLock<ContentCache>>,
+    // Extraction from PAKs (progress overlay and results log)
+    pub is_extracting: RwSignal<bool>,
+    pub results_log: RwSignal<ImVector<ResultEntry>>,
 }
 
 impl BrowserState {
@@ -65,6 +101,8 @@ impl BrowserState {
             current_path: RwSignal::new(None),
             browser_path: RwSignal::new(String::new()),
             selected_index: RwSignal::new(None),
+            selected_indices: RwSignal::new(BTreeSet::new()),
+            selection_anchor: RwSignal::new(None),
             files: RwSignal::new(Vec::new()),
             all_files: RwSignal::new(Vec::new()),
             search_query: RwSignal::new(String::new()),
@@ -79,6 +117,9 @@ impl BrowserState {
             status_message: RwSignal::new(String::new()),
             sort_column: RwSignal::new(SortColumn::Name),
             sort_ascending: RwSignal::new(true),
+            group_by_type: RwSignal::new(false),
+            collapsed_types: RwSignal::new(BTreeSet::new()),
+            type_groups: RwSignal::new(Vec::new()),
             renaming_path: RwSignal::new(None),
             rename_text: RwSignal::new(String::new()),
             preview_3d_path: RwSignal::new(None),
@@ -93,13 +134,37 @@ impl BrowserState {
             gr2_convert_to_gltf: RwSignal::new(false),
             gr2_extract_textures: RwSignal::new(false),
             gr2_convert_to_png: RwSignal::new(false),
+            // PAK browsing
+            pak_path: RwSignal::new(None),
+            pak_cache: Arc::new(Mutex::new(PakReaderCache::new(4))),
+            file_index: Arc::new(Mutex::new(None)),
+            preview_cache: Arc::new(RwLock::new(ContentCache::new())),
+            is_extracting: RwSignal::new(false),
+            results_log: RwSignal::new(ImVector::new()),
         }
     }
 
+    pub fn add_result(&self, message: &str) {
+        self.results_log.update(|log| {
+            log.push_back(ResultEntry::from_message(message));
+        });
+    }
+
+    pub fn add_results_batch(&self, messages: Vec<String>) {
+        self.results_log.update(|log| {
+            log.extend(messages.into_iter().map(ResultEntry::from_message));
+        });
+    }
+
+    pub fn clear_results(&self) {
+        self.results_log.set(ImVector::new());
+    }
+
     /// Apply persisted state (call after new())
     pub fn apply_persisted(&self, persisted: &super::PersistedBrowserState) {
         // Restore sort preferences
         let sort_column = match persisted.sort_column.as_str() {
+            "Path" => SortColumn::Path,
             "Type" => SortColumn::Type,
             "Size" => SortColumn::Size,
             "Modified" => SortColumn::Modified,
@@ -107,6 +172,7 @@ impl BrowserState {
         };
         self.sort_column.set(sort_column);
         self.sort_ascending.set(persisted.sort_ascending);
+        self.group_by_type.set(persisted.group_by_type);
 
         // Restore layout
         self.file_list_width.set(persisted.file_list_width);
@@ -127,6 +193,36 @@ impl Default for BrowserState {
     }
 }
 
+impl BatchOperationState for BrowserState {
+    fn is_processing(&self) -> RwSignal<bool> {
+        self.is_extracting
+    }
+
+    fn results_log(&self) -> RwSignal<ImVector<ResultEntry>> {
+        self.results_log
+    }
+
+    fn status_message(&self) -> RwSignal<String> {
+        self.status_message
+    }
+
+    fn add_result(&self, message: &str) {
+        BrowserState::add_result(self, message);
+    }
+
+    fn add_results_batch(&self, messages: Vec<String>) {
+        BrowserState::add_results_batch(self, messages);
+    }
+
+    fn clear_results(&self) {
+        BrowserState::clear_results(self);
+    }
+
+    fn progress_source(&self) -> ProgressSource {
+        ProgressSource::Shared(get_shared_progress())
+    }
+}
+
 /// File entry for the asset browser
 #[derive(Clone, Debug)]
 pub struct FileEntry {
@@ -139,4 +235,6 @@ pub struct FileEntry {
     pub file_type: String,
     pub modified: String,
     pub icon: String,
+    /// PAK containing this entry (`path` is then the path inside the PAK)
+    pub pak_path: Option<String>,
 }
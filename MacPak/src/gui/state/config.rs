@@ -7,10 +7,14 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-/// Maximum number of recent files to track
-const MAX_RECENT_FILES: usize = 10;
+/// Maximum number of recent files (and recent PAKs) to track
+const MAX_RECENT_FILES: usize = 15;
+
+/// Log level used when config.toml doesn't set one
+pub const DEFAULT_LOG_LEVEL: &str = "warn";
 
 use crate::gui::shared::Theme;
+use maclarian::config::Config as SharedConfig;
 
 // Default value functions for serde
 fn default_window_width() -> f64 {
@@ -28,6 +32,12 @@ fn default_file_list_width() -> f64 {
 fn default_browser_panel_width() -> f64 {
     400.0
 }
+fn default_backup_count() -> usize {
+    crate::workspace::backup::DEFAULT_KEEP
+}
+fn default_format_indent() -> String {
+    "tab".to_string()
+}
 
 /// Window geometry and state
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,6 +75,18 @@ pub struct PersistedEditorState {
     /// Show line numbers preference
     #[serde(default = "default_true")]
     pub show_line_numbers: bool,
+    /// Backups kept per file when a save or conversion overwrites it (0 disables)
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+    /// Format Document indentation: "tab" or a number of spaces
+    #[serde(default = "default_format_indent")]
+    pub format_indent: String,
+    /// Format Document sorts attributes by name
+    #[serde(default)]
+    pub format_sort_attributes: bool,
+    /// Minutes between autosaves of modified tabs (0 disables)
+    #[serde(default)]
+    pub autosave_minutes: u32,
 }
 
 impl Default for PersistedEditorState {
@@ -73,6 +95,10 @@ impl Default for PersistedEditorState {
             open_files: Vec::new(),
             active_tab_index: 0,
             show_line_numbers: true,
+            backup_count: default_backup_count(),
+            format_indent: default_format_indent(),
+            format_sort_attributes: false,
+            autosave_minutes: 0,
         }
     }
 }
@@ -90,6 +116,8 @@ pub struct PersistedBrowserState {
     pub file_list_width: f64,
     #[serde(default)]
     pub type_filter: String,
+    #[serde(default)]
+    pub group_by_type: bool,
 }
 
 impl Default for PersistedBrowserState {
@@ -100,6 +128,7 @@ impl Default for PersistedBrowserState {
             sort_ascending: true,
             file_list_width: default_file_list_width(),
             type_filter: String::new(),
+            group_by_type: false,
         }
     }
 }
@@ -152,12 +181,61 @@ impl Default for PersistedDialogueState {
     }
 }
 
+fn default_dye_count() -> u32 {
+    1
+}
+
+/// Dyes tab export settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedDyesState {
+    /// Ids of the selected vendor tables (`None` keeps the defaults)
+    #[serde(default)]
+    pub selected_vendors: Option<Vec<String>>,
+    #[serde(default)]
+    pub custom_tables: Vec<super::CustomTreasureTable>,
+    #[serde(default)]
+    pub add_individual_dyes: bool,
+    #[serde(default = "default_dye_count")]
+    pub individual_dye_count: u32,
+}
+
+impl Default for PersistedDyesState {
+    fn default() -> Self {
+        Self {
+            selected_vendors: None,
+            custom_tables: Vec::new(),
+            add_individual_dyes: false,
+            individual_dye_count: default_dye_count(),
+        }
+    }
+}
+
+/// UUID generator history
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PersistedUuidState {
+    #[serde(default)]
+    pub uuid_history: Vec<String>,
+    #[serde(default)]
+    pub handle_history: Vec<String>,
+}
+
 /// Persistable configuration (saved to disk)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PersistedConfig {
-    // Existing fields
+    /// Legacy location of the BG3 path, now stored in the shared config.toml
+    /// (read once for migration, never written)
+    #[serde(default, skip_serializing)]
     pub bg3_data_path: Option<String>,
     pub recent_files: Vec<String>,
+    /// Recently opened PAKs (browser and PAK operations), most recent first
+    #[serde(default)]
+    pub recent_paks: Vec<String>,
+    /// Favorite files, listed above the recent ones and kept when clearing
+    #[serde(default)]
+    pub pinned_files: Vec<String>,
+    /// Favorite PAKs, listed above the recent ones and kept when clearing
+    #[serde(default)]
+    pub pinned_paks: Vec<String>,
     #[serde(default)]
     pub theme: Theme,
 
@@ -179,7 +257,11 @@ pub struct PersistedConfig {
     #[serde(default)]
     pub dialogue: PersistedDialogueState,
     #[serde(default)]
+    pub dyes: PersistedDyesState,
+    #[serde(default)]
     pub workbench: super::PersistedWorkbenchState,
+    #[serde(default)]
+    pub uuid: PersistedUuidState,
 }
 
 impl PersistedConfig {
@@ -237,6 +319,71 @@ pub fn expand_tilde(path: &str) -> String {
     path.to_string()
 }
 
+/// Detect the BG3 Data directory (honors `MACPAK_BG3_PATH`)
+pub fn detect_bg3_path() -> Option<String> {
+    maclarian::utils::find_game_install().map(|install| install.data_dir.display().to_string())
+}
+
+/// Default destination for extracted and converted files, from the shared
+/// config.toml (`None` if unset or missing on disk)
+pub fn configured_output_dir() -> Option<PathBuf> {
+    SharedConfig::load_default()
+        .ok()
+        .and_then(|config| config.output_dir)
+        .filter(|dir| dir.is_dir())
+}
+
+/// Search index directory from the shared config.toml (`None` if unset or
+/// missing on disk)
+pub fn configured_index_dir() -> Option<PathBuf> {
+    SharedConfig::load_default()
+        .ok()
+        .and_then(|config| config.index_dir)
+        .filter(|dir| dir.is_dir())
+}
+
+/// Warning shown when config.toml can't be loaded
+fn config_error_message(error: &maclarian::Error) -> String {
+    format!("config.toml could not be loaded, so settings won't be saved to it: {error}")
+}
+
+/// Move `path` to the front of `list`, keeping at most [`MAX_RECENT_FILES`]
+fn push_recent(list: &mut Vec<String>, path: &str) {
+    list.retain(|p| p != path);
+    list.insert(0, path.to_string());
+    list.truncate(MAX_RECENT_FILES);
+}
+
+/// Add `path` to `list`, or remove it if it's already there
+fn toggle_entry(list: &mut Vec<String>, path: &str) {
+    if list.iter().any(|p| p == path) {
+        list.retain(|p| p != path);
+    } else {
+        list.push(path.to_string());
+    }
+}
+
+/// Drop entries whose file no longer exists
+fn prune_missing(list: Vec<String>) -> Vec<String> {
+    list.into_iter()
+        .filter(|p| Path::new(p).is_file())
+        .collect()
+}
+
+/// Pinned entries first, then the recent ones that aren't pinned
+pub fn pinned_then_recent(pinned: &[String], recent: &[String]) -> Vec<(String, bool)> {
+    pinned
+        .iter()
+        .map(|p| (p.clone(), true))
+        .chain(
+            recent
+                .iter()
+                .filter(|p| !pinned.contains(p))
+                .map(|p| (p.clone(), false)),
+        )
+        .collect()
+}
+
 /// Check if a path exists and is a directory
 pub fn path_exists(path: &str) -> bool {
     if path.is_empty() {
@@ -256,10 +403,24 @@ pub struct ConfigState {
     pub app_ready: Arc<AtomicBool>,
     /// Warning message for invalid path
     pub path_warning: RwSignal<Option<String>>,
+    /// Why the shared config.toml couldn't be loaded; it isn't saved over while set
+    pub config_error: RwSignal<Option<String>>,
     /// Recent files list (most recent first)
     pub recent_files: RwSignal<Vec<String>>,
+    /// Recent PAKs list (most recent first)
+    pub recent_paks: RwSignal<Vec<String>>,
+    /// Pinned favorite files
+    pub pinned_files: RwSignal<Vec<String>>,
+    /// Pinned favorite PAKs
+    pub pinned_paks: RwSignal<Vec<String>>,
     /// Current theme
     pub theme: RwSignal<Theme>,
+    /// Default output directory for extraction and conversion (empty = ask)
+    pub output_dir: RwSignal<String>,
+    /// Search index directory (empty = default)
+    pub index_dir: RwSignal<String>,
+    /// Log level (`error` to `trace`), applied on next launch
+    pub log_level: RwSignal<String>,
 }
 
 impl ConfigState {
@@ -267,10 +428,33 @@ impl ConfigState {
         // Load persisted config
         let persisted = PersistedConfig::load();
 
-        // Use persisted BG3 path if available, otherwise use default
-        let expanded_path = persisted
-            .bg3_data_path
+        // Use the shared config.toml path (also used by the CLI), then the
+        // legacy GUI-only setting, otherwise detect the install
+        let (shared, config_error) = match SharedConfig::load_default() {
+            Ok(shared) => (shared, None),
+            Err(e) => {
+                tracing::error!("Failed to load config.toml: {}", e);
+                (SharedConfig::default(), Some(config_error_message(&e)))
+            }
+        };
+        let display = |p: &Option<PathBuf>| {
+            p.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        };
+        let output_dir = display(&shared.output_dir);
+        let index_dir = display(&shared.index_dir);
+        let log_level = shared
+            .log_level
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string());
+        let expanded_path = shared
+            .bg3_path
+            .map(|p| p.display().to_string())
+            .or(persisted.bg3_data_path)
+            .filter(|p| !p.is_empty())
             .map(|p| expand_tilde(&p))
+            .or_else(detect_bg3_path)
             .unwrap_or_else(|| expand_tilde(DEFAULT_BG3_PATH));
         let path_valid = path_exists(&expanded_path);
 
@@ -285,34 +469,57 @@ impl ConfigState {
 
         Self {
             bg3_data_path: RwSignal::new(expanded_path),
-            show_dialog: RwSignal::new(false),
+            // Open preferences straight away so a broken config.toml is noticed
+            show_dialog: RwSignal::new(config_error.is_some()),
             app_ready: Arc::new(AtomicBool::new(false)),
             path_warning: RwSignal::new(warning),
-            recent_files: RwSignal::new(persisted.recent_files),
+            config_error: RwSignal::new(config_error),
+            // Files that were moved or deleted since the last launch are dropped
+            recent_files: RwSignal::new(prune_missing(persisted.recent_files)),
+            recent_paks: RwSignal::new(prune_missing(persisted.recent_paks)),
+            pinned_files: RwSignal::new(prune_missing(persisted.pinned_files)),
+            pinned_paks: RwSignal::new(prune_missing(persisted.pinned_paks)),
             theme: RwSignal::new(persisted.theme),
+            output_dir: RwSignal::new(output_dir),
+            index_dir: RwSignal::new(index_dir),
+            log_level: RwSignal::new(log_level),
         }
     }
 
     /// Add a file to the recent files list
     pub fn add_recent_file(&self, path: &str) {
-        let mut files = self.recent_files.get();
+        self.recent_files.update(|files| push_recent(files, path));
+        self.save();
+    }
 
-        // Remove if already in list (we'll re-add at front)
-        files.retain(|p| p != path);
+    /// Add a PAK to the recent PAKs list
+    pub fn add_recent_pak(&self, path: &str) {
+        self.recent_paks.update(|paks| push_recent(paks, path));
+        self.save();
+    }
 
-        // Add to front
-        files.insert(0, path.to_string());
+    /// Clear the recent files (pinned files stay)
+    pub fn clear_recent_files(&self) {
+        self.recent_files.set(Vec::new());
+        self.save();
+    }
 
-        // Truncate to max
-        files.truncate(MAX_RECENT_FILES);
+    /// Clear the recent PAKs (pinned PAKs stay)
+    pub fn clear_recent_paks(&self) {
+        self.recent_paks.set(Vec::new());
+        self.save();
+    }
 
-        self.recent_files.set(files);
+    /// Pin or unpin a file
+    pub fn toggle_pinned_file(&self, path: &str) {
+        self.pinned_files
+            .update(|pinned| toggle_entry(pinned, path));
         self.save();
     }
 
-    /// Clear all recent files
-    pub fn clear_recent_files(&self) {
-        self.recent_files.set(Vec::new());
+    /// Pin or unpin a PAK
+    pub fn toggle_pinned_pak(&self, path: &str) {
+        self.pinned_paks.update(|pinned| toggle_entry(pinned, path));
         self.save();
     }
 
@@ -322,13 +529,45 @@ impl ConfigState {
         let mut persisted = PersistedConfig::load();
 
         // Update only the core config fields
-        persisted.bg3_data_path = Some(self.bg3_data_path.get());
+        self.save_shared();
         persisted.recent_files = self.recent_files.get();
+        persisted.recent_paks = self.recent_paks.get();
+        persisted.pinned_files = self.pinned_files.get();
+        persisted.pinned_paks = self.pinned_paks.get();
         persisted.theme = self.theme.get();
 
         persisted.save();
     }
 
+    /// Write settings shared with the CLI to config.toml
+    ///
+    /// Skipped while the file can't be parsed, so hand edits aren't lost.
+    fn save_shared(&self) {
+        if self.config_error.get_untracked().is_some() {
+            return;
+        }
+        let Some(path) = SharedConfig::default_path() else {
+            return;
+        };
+        let mut shared = match SharedConfig::load(&path) {
+            Ok(shared) => shared,
+            Err(e) => {
+                tracing::error!("Not saving {}: {}", path.display(), e);
+                self.config_error.set(Some(config_error_message(&e)));
+                return;
+            }
+        };
+        let dir = |value: String| (!value.is_empty()).then(|| PathBuf::from(value));
+        shared.bg3_path = dir(self.bg3_data_path.get());
+        shared.output_dir = dir(self.output_dir.get());
+        shared.index_dir = dir(self.index_dir.get());
+        let log_level = self.log_level.get();
+        shared.log_level = (log_level != DEFAULT_LOG_LEVEL).then_some(log_level);
+        if let Err(e) = shared.save(&path) {
+            tracing::warn!("Failed to save {}: {}", path.display(), e);
+        }
+    }
+
     /// Set the theme and save
     pub fn set_theme(&self, theme: Theme) {
         self.theme.set(theme);
@@ -372,8 +611,15 @@ impl ConfigState {
         browser: &super::BrowserState,
         search: &super::SearchState,
         dialogue: &super::DialogueState,
+        dyes: &super::DyesState,
         workbench: &super::WorkbenchState,
+        uuid: &super::UuidGeneratorState,
     ) {
+        // Unsaved buffers go to the session store, restored on next launch
+        if let Err(e) = editor_tabs.save_recovery() {
+            tracing::warn!("Failed to write recovery files: {}", e);
+        }
+
         // Collect editor tab file paths (only tabs with saved files)
         let open_files: Vec<String> = editor_tabs
             .tabs
@@ -382,10 +628,15 @@ impl ConfigState {
             .filter_map(|tab| tab.file_path.get())
             .collect();
 
+        self.save_shared();
+
         let persisted = PersistedConfig {
             // Existing fields
-            bg3_data_path: Some(self.bg3_data_path.get()),
+            bg3_data_path: None,
             recent_files: self.recent_files.get(),
+            recent_paks: self.recent_paks.get(),
+            pinned_files: self.pinned_files.get(),
+            pinned_paks: self.pinned_paks.get(),
             theme: self.theme.get(),
 
             // Window state (placeholder - actual values would need Floem window API)
@@ -399,6 +650,10 @@ impl ConfigState {
                 open_files,
                 active_tab_index: editor_tabs.active_tab_index.get(),
                 show_line_numbers: editor_tabs.show_line_numbers.get(),
+                backup_count: editor_tabs.backup_count.get(),
+                format_indent: editor_tabs.format_options.get().indent.to_string(),
+                format_sort_attributes: editor_tabs.format_options.get().sort_attributes,
+                autosave_minutes: editor_tabs.autosave_minutes.get(),
             },
 
             // Browser state
@@ -408,6 +663,7 @@ impl ConfigState {
                 sort_ascending: browser.sort_ascending.get(),
                 file_list_width: browser.file_list_width.get(),
                 type_filter: browser.type_filter.get(),
+                group_by_type: browser.group_by_type.get(),
             },
 
             // Search state
@@ -429,6 +685,14 @@ impl ConfigState {
                 browser_panel_width: dialogue.browser_panel_width.get(),
             },
 
+            // Dyes export settings
+            dyes: PersistedDyesState {
+                selected_vendors: Some(dyes.selected_vendor_ids()),
+                custom_tables: dyes.custom_tables.get(),
+                add_individual_dyes: dyes.add_individual_dyes.get(),
+                individual_dye_count: dyes.individual_dye_count.get(),
+            },
+
             // Workbench state
             workbench: {
                 let ws = workbench.workbench.get();
@@ -439,6 +703,20 @@ impl ConfigState {
                     recent_projects: Vec::new(),
                 }
             },
+
+            // UUID generator history
+            uuid: {
+                let recent = |history: Vec<String>| {
+                    history
+                        .into_iter()
+                        .take(super::MAX_PERSISTED_HISTORY)
+                        .collect()
+                };
+                PersistedUuidState {
+                    uuid_history: recent(uuid.uuid_history.get()),
+                    handle_history: recent(uuid.handle_history.get()),
+                }
+            },
         };
 
         persisted.save();
@@ -450,3 +728,37 @@ impl Default for ConfigState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_and_pinned_lists() {
+        let mut recent = Vec::new();
+        for i in 0..20 {
+            push_recent(&mut recent, &format!("/mods/{}.pak", i));
+        }
+        push_recent(&mut recent, "/mods/10.pak");
+        assert_eq!(recent.len(), MAX_RECENT_FILES);
+        assert_eq!(recent[0], "/mods/10.pak");
+        assert_eq!(recent[1], "/mods/19.pak");
+        assert_eq!(recent.iter().filter(|p| *p == "/mods/10.pak").count(), 1);
+
+        let mut pinned = Vec::new();
+        toggle_entry(&mut pinned, "/mods/19.pak");
+        toggle_entry(&mut pinned, "/mods/3.pak");
+        toggle_entry(&mut pinned, "/mods/3.pak");
+        let listed = pinned_then_recent(&pinned, &recent[..3]);
+        assert_eq!(
+            listed,
+            vec![
+                ("/mods/19.pak".to_string(), true),
+                ("/mods/10.pak".to_string(), false),
+                ("/mods/18.pak".to_string(), false),
+            ]
+        );
+
+        assert!(prune_missing(vec!["/nonexistent/a.pak".to_string()]).is_empty());
+    }
+}
@@ -297,6 +297,24 @@ pub const VENDOR_DEFS: &[VendorDef] = &[
     }, // 44, Commented out of dialog
 ];
 
+/// Treasure table id of the Nautiloid tutorial chest
+pub const TUTORIAL_CHEST_TABLE: &str = "TUT_Chest_Potions";
+
+/// A user-defined treasure table the pouch (and optionally the dyes) is added to
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CustomTreasureTable {
+    /// Label shown in the export dialog
+    pub name: String,
+    /// Treasure table id, e.g. `TUT_Chest_Potions`
+    pub table_id: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
 /// A single dye color entry with its category name and color value
 #[derive(Clone)]
 pub struct DyeColorEntry {
@@ -333,6 +351,8 @@ pub struct GeneratedDyeEntry {
     pub desc_handle: String,
     /// Color parameters: parameter name -> hex color
     pub colors: HashMap<String, String>,
+    /// PNG or DDS icon chosen for this dye (a color swatch is used when unset)
+    pub icon_path: Option<String>,
 }
 
 /// Dyes tab state for custom dye color creation
@@ -384,6 +404,17 @@ pub struct DyesState {
 
     // Vendor selection for export (indices into VENDOR_DEFS that are enabled)
     pub selected_vendors: RwSignal<Vec<bool>>,
+
+    // User-defined treasure tables (added alongside the selected vendors)
+    pub custom_tables: RwSignal<Vec<CustomTreasureTable>>,
+    /// Also put each dye (not just the pouch) into the selected tables
+    pub add_individual_dyes: RwSignal<bool>,
+    /// How many of each dye a selected table drops
+    pub individual_dye_count: RwSignal<u32>,
+
+    // Pack the exported folder into a .pak (and optionally install it)
+    pub export_as_pak: RwSignal<bool>,
+    pub copy_to_game_mods: RwSignal<bool>,
 }
 
 impl DyesState {
@@ -436,9 +467,76 @@ impl DyesState {
 
             // Vendor selection - default to none (except always_enabled ones)
             selected_vendors: RwSignal::new(VENDOR_DEFS.iter().map(|v| v.always_enabled).collect()),
+
+            // Custom treasure tables
+            custom_tables: RwSignal::new(Vec::new()),
+            add_individual_dyes: RwSignal::new(false),
+            individual_dye_count: RwSignal::new(1),
+
+            // Export packing
+            export_as_pak: RwSignal::new(false),
+            copy_to_game_mods: RwSignal::new(false),
         }
     }
 
+    /// Apply persisted state (call after new())
+    pub fn apply_persisted(&self, persisted: &super::PersistedDyesState) {
+        // Vendors are stored by table id so the list can change between versions
+        if let Some(ids) = &persisted.selected_vendors {
+            self.selected_vendors.set(
+                VENDOR_DEFS
+                    .iter()
+                    .map(|v| v.always_enabled || ids.iter().any(|id| id == v.id))
+                    .collect(),
+            );
+        }
+        self.custom_tables.set(persisted.custom_tables.clone());
+        self.add_individual_dyes.set(persisted.add_individual_dyes);
+        self.individual_dye_count
+            .set(persisted.individual_dye_count.max(1));
+    }
+
+    /// Ids of the vendor tables selected for export (always-enabled ones included)
+    pub fn selected_vendor_ids(&self) -> Vec<String> {
+        let selected = self.selected_vendors.get();
+        VENDOR_DEFS
+            .iter()
+            .enumerate()
+            .filter(|(idx, v)| v.always_enabled || selected.get(*idx).copied().unwrap_or(false))
+            .map(|(_, v)| v.id.to_string())
+            .collect()
+    }
+
+    /// Every table the export adds the pouch to: selected vendors, then
+    /// enabled custom tables, without blanks or repeats
+    pub fn target_treasure_tables(&self) -> Vec<String> {
+        let mut tables = self.selected_vendor_ids();
+        for custom in self.custom_tables.get() {
+            let id = custom.table_id.trim();
+            if custom.enabled && !id.is_empty() && !tables.iter().any(|t| t == id) {
+                tables.push(id.to_string());
+            }
+        }
+        tables
+    }
+
+    /// Tutorial chest preset: only the tutorial chest, with the dyes
+    /// themselves added so they can be tried on straight away
+    pub fn apply_tutorial_chest_preset(&self) {
+        self.selected_vendors.set(
+            VENDOR_DEFS
+                .iter()
+                .map(|v| v.id == TUTORIAL_CHEST_TABLE)
+                .collect(),
+        );
+        self.custom_tables.update(|tables| {
+            for table in tables.iter_mut() {
+                table.enabled = false;
+            }
+        });
+        self.add_individual_dyes.set(true);
+    }
+
     /// Get all color entries
     pub fn all_colors(&self) -> &[DyeColorEntry] {
         &self.colors
@@ -1,6 +1,159 @@
 //! Editor tab state
 
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hasher};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::SystemTime;
+
 use floem::prelude::*;
+use floem::views::editor::core::cursor::Cursor;
+use floem::views::editor::text::Document;
+use maclarian::converter::PrettyOptions;
+use maclarian::formats::stats::StatsIssue;
+use maclarian::utils::CancelToken;
+
+use crate::index::search::FileMatches;
+use crate::workbench::session::{RecoveredBuffer, SessionStore};
+
+/// PAK entry an editor tab was opened from
+#[derive(Clone, Debug, PartialEq)]
+pub struct PakSource {
+    pub pak_path: String,
+    /// Path of the file inside the PAK
+    pub internal_path: String,
+}
+
+/// Handles into a tab's editor view, set each time the view is built
+#[derive(Clone)]
+pub struct EditorHandle {
+    pub doc: Rc<dyn Document>,
+    pub cursor: RwSignal<Cursor>,
+}
+
+// Editor views by tab id (the document is an Rc, so it stays on the main
+// thread instead of in the shared tab state)
+thread_local! {
+    static EDITOR_HANDLES: RefCell<HashMap<u64, EditorHandle>> = RefCell::new(HashMap::new());
+}
+
+impl EditorHandle {
+    /// Move the cursor to the start of a 0-based line (the view scrolls to it)
+    pub fn go_to_line(&self, line: usize) {
+        let text = self.doc.text();
+        let offset = text.offset_of_line(line.min(text.line_of_offset(text.len())));
+        self.cursor
+            .update(|cursor| cursor.set_offset(offset, false, false));
+    }
+}
+
+/// Text before and after a replace, so it can be undone in one step
+#[derive(Clone, Debug)]
+pub struct ReplaceUndo {
+    pub before: String,
+    pub after: String,
+}
+
+/// One side of an editor comparison
+#[derive(Clone, Debug)]
+pub struct DiffSide {
+    /// Shown above the pane (tab name, saved file or PAK entry)
+    pub title: String,
+    pub text: String,
+    /// Tab the text belongs to; hunks can only be copied into tab sides
+    pub tab_id: Option<u64>,
+}
+
+/// Two texts compared side by side in place of the editor
+#[derive(Clone, Debug)]
+pub struct DiffSession {
+    pub left: DiffSide,
+    pub right: DiffSide,
+}
+
+/// A file as it was on disk when a tab last loaded or saved it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiskStamp {
+    pub modified: Option<SystemTime>,
+    pub len: u64,
+    /// Hash of the file's bytes, checked only when the time or length moved
+    pub hash: u64,
+}
+
+/// How a file changed on disk since its [`DiskStamp`] was taken
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiskChange {
+    Unchanged,
+    /// Only the modification time moved; the bytes are the same
+    Touched(DiskStamp),
+    /// Another program wrote different content
+    Modified(DiskStamp),
+    Deleted,
+}
+
+/// File system access used to notice external changes (swapped out in tests)
+pub trait DiskProbe {
+    /// Modification time and length, or `None` if the file is gone
+    fn metadata(&self, path: &Path) -> Option<(Option<SystemTime>, u64)>;
+    /// Hash of the file's bytes
+    fn hash(&self, path: &Path) -> Option<u64>;
+}
+
+/// [`DiskProbe`] reading the real file system
+pub struct RealDisk;
+
+impl DiskProbe for RealDisk {
+    fn metadata(&self, path: &Path) -> Option<(Option<SystemTime>, u64)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.modified().ok(), metadata.len()))
+    }
+
+    fn hash(&self, path: &Path) -> Option<u64> {
+        let data = std::fs::read(path).ok()?;
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&data);
+        Some(hasher.finish())
+    }
+}
+
+impl DiskStamp {
+    /// Stamp of the file as it is now, or `None` if it can't be read
+    pub fn read(path: &Path, probe: &dyn DiskProbe) -> Option<Self> {
+        let (modified, len) = probe.metadata(path)?;
+        Some(Self {
+            modified,
+            len,
+            hash: probe.hash(path)?,
+        })
+    }
+
+    /// Compare with the file as it is now
+    ///
+    /// Files are only hashed when their time or length changed, so checking
+    /// every open tab on focus stays cheap.
+    pub fn check(&self, path: &Path, probe: &dyn DiskProbe) -> DiskChange {
+        let Some((modified, len)) = probe.metadata(path) else {
+            return DiskChange::Deleted;
+        };
+        if modified == self.modified && len == self.len {
+            return DiskChange::Unchanged;
+        }
+        let Some(hash) = probe.hash(path) else {
+            return DiskChange::Deleted;
+        };
+        let stamp = Self {
+            modified,
+            len,
+            hash,
+        };
+        if len == self.len && hash == self.hash {
+            DiskChange::Touched(stamp)
+        } else {
+            DiskChange::Modified(stamp)
+        }
+    }
+}
 
 /// State for a single editor tab
 #[derive(Clone)]
@@ -16,6 +169,8 @@ pub struct EditorTab {
     pub live_content: RwSignal<String>,
     pub modified: RwSignal<bool>,
     pub converted_from_lsf: RwSignal<bool>,
+    /// Set when the tab was opened from inside a PAK rather than from disk
+    pub pak_source: RwSignal<Option<PakSource>>,
     /// Whether the tab is currently loading a file
     pub is_loading: RwSignal<bool>,
     /// Loading progress message (shown in overlay)
@@ -35,6 +190,26 @@ pub struct EditorTab {
     pub search_status: RwSignal<String>,
     /// Offset to jump to (set by find next/prev, consumed by editor)
     pub goto_offset: RwSignal<Option<usize>>,
+    /// Replace All applies to every open tab
+    pub replace_in_all_tabs: RwSignal<bool>,
+    /// Last replace, undone by Cmd+Z if the text hasn't changed since
+    pub replace_undo: RwSignal<Option<ReplaceUndo>>,
+
+    /// Show the node tree (LSX/LSF) or the handle table (LOCA) instead of the text
+    pub structured_view: RwSignal<bool>,
+    /// Bumped to rebuild the editor view from `content`
+    pub editor_revision: RwSignal<u64>,
+    /// Stats lint results from the last save (stats files only)
+    pub stats_issues: RwSignal<Vec<StatsIssue>>,
+    /// Unsaved text restored from the last session's recovery file
+    pub recovered: RwSignal<bool>,
+    /// Cursor offset to apply when the editor view is next built
+    pub restore_cursor: RwSignal<Option<usize>>,
+    /// 0-based line to move to once the file has loaded (find in files)
+    pub restore_line: RwSignal<Option<usize>>,
+    /// The file on disk as last loaded or saved, to notice other programs
+    /// writing to it
+    pub disk_stamp: RwSignal<Option<DiskStamp>>,
 }
 
 impl PartialEq for EditorTab {
@@ -53,6 +228,7 @@ impl EditorTab {
             live_content: RwSignal::new(String::new()),
             modified: RwSignal::new(false),
             converted_from_lsf: RwSignal::new(false),
+            pak_source: RwSignal::new(None),
             is_loading: RwSignal::new(false),
             loading_message: RwSignal::new(String::new()),
             save_status: RwSignal::new(String::new()),
@@ -67,10 +243,117 @@ impl EditorTab {
             current_match: RwSignal::new(0),
             search_status: RwSignal::new(String::new()),
             goto_offset: RwSignal::new(None),
+            replace_in_all_tabs: RwSignal::new(false),
+            replace_undo: RwSignal::new(None),
+
+            structured_view: RwSignal::new(false),
+            editor_revision: RwSignal::new(0),
+            stats_issues: RwSignal::new(Vec::new()),
+            recovered: RwSignal::new(false),
+            restore_cursor: RwSignal::new(None),
+            restore_line: RwSignal::new(None),
+            disk_stamp: RwSignal::new(None),
+        }
+    }
+
+    /// The live editor view (only meaningful for the active tab)
+    pub fn editor(&self) -> Option<EditorHandle> {
+        EDITOR_HANDLES.with(|handles| handles.borrow().get(&self.id).cloned())
+    }
+
+    /// Remember the editor view built for this tab
+    pub fn set_editor(&self, handle: EditorHandle) {
+        EDITOR_HANDLES.with(|handles| {
+            handles.borrow_mut().insert(self.id, handle);
+        });
+    }
+
+    /// Forget the editor view once the tab is closed
+    fn drop_editor(&self) {
+        EDITOR_HANDLES.with(|handles| {
+            handles.borrow_mut().remove(&self.id);
+        });
+    }
+
+    /// Replace the tab's text and rebuild its editor view
+    pub fn set_text(&self, text: String) {
+        self.content.set(text.clone());
+        self.live_content.set(text);
+        self.editor_revision.update(|revision| *revision += 1);
+    }
+
+    /// Replace the tab's text as one edit (find & replace, tree view), keeping
+    /// the old text so Cmd+Z can restore it in one step
+    pub fn replace_text(&self, text: String) {
+        self.replace_undo.set(Some(ReplaceUndo {
+            before: self.live_content.get_untracked(),
+            after: text.clone(),
+        }));
+        self.set_text(text);
+        self.modified.set(true);
+    }
+
+    /// Remember the file on disk as it is now (after loading or saving it)
+    pub fn record_disk_stamp(&self) {
+        let stamp = self
+            .file_path
+            .get_untracked()
+            .and_then(|path| DiskStamp::read(Path::new(&path), &RealDisk));
+        self.disk_stamp.set(stamp);
+    }
+
+    /// How the tab's file changed on disk since it was loaded or saved
+    ///
+    /// Tabs without a file, or whose file was never read, are `Unchanged`.
+    pub fn disk_change(&self, probe: &dyn DiskProbe) -> DiskChange {
+        match (
+            self.file_path.get_untracked(),
+            self.disk_stamp.get_untracked(),
+        ) {
+            (Some(path), Some(stamp)) => stamp.check(Path::new(&path), probe),
+            _ => DiskChange::Unchanged,
+        }
+    }
+
+    /// Name of this tab's recovery file in the session store
+    pub fn recovery_key(&self) -> String {
+        format!("tab-{}", self.id)
+    }
+
+    /// Snapshot of the unsaved text for the session store (`None` if unmodified)
+    fn recovery_buffer(&self, cursor_offset: usize) -> Option<RecoveredBuffer> {
+        if !self.modified.get_untracked() {
+            return None;
         }
+        let pak_source = self.pak_source.get_untracked();
+        Some(RecoveredBuffer {
+            key: self.recovery_key(),
+            original_path: self.file_path.get_untracked(),
+            pak_path: pak_source.as_ref().map(|s| s.pak_path.clone()),
+            pak_internal_path: pak_source.map(|s| s.internal_path),
+            format: self.file_format.get_untracked(),
+            converted_from_binary: self.converted_from_lsf.get_untracked(),
+            cursor_offset,
+            content: self.live_content.get_untracked(),
+        })
     }
 
-    /// Get display name for tab (filename or "Untitled")
+    /// Delete this tab's recovery file (after a clean save or a discarding close)
+    pub fn discard_recovery(&self) {
+        self.recovered.set(false);
+        if let Some(store) = SessionStore::default_location() {
+            store.remove(&self.recovery_key());
+        }
+    }
+
+    /// Path of the file on disk, or inside the PAK it was opened from
+    pub fn source_path(&self) -> Option<String> {
+        self.file_path
+            .get_untracked()
+            .or_else(|| self.pak_source.get_untracked().map(|s| s.internal_path))
+    }
+
+    /// Get display name for tab (filename, PAK entry name, or "Untitled")
     pub fn display_name(&self) -> String {
         self.file_path
             .get()
@@ -79,10 +362,81 @@ impl EditorTab {
                     .file_name()
                     .map(|n| n.to_string_lossy().to_string())
             })
+            .or_else(|| {
+                self.pak_source.get().map(|source| {
+                    let name = source
+                        .internal_path
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string();
+                    format!("{} (PAK)", name)
+                })
+            })
             .unwrap_or_else(|| "Untitled".to_string())
     }
 }
 
+/// Find in files panel state
+#[derive(Clone)]
+pub struct ProjectSearchState {
+    pub visible: RwSignal<bool>,
+    /// Folder searched (defaults to the browser's folder when first opened)
+    pub root: RwSignal<Option<String>>,
+    pub query: RwSignal<String>,
+    /// Comma-separated globs of files to search
+    pub include: RwSignal<String>,
+    /// Comma-separated globs of files to leave out
+    pub exclude: RwSignal<String>,
+    pub case_sensitive: RwSignal<bool>,
+    /// Also search LSF files, converted to LSX in memory
+    pub convert_lsf: RwSignal<bool>,
+    /// Files with matches, appended as they are found
+    pub results: RwSignal<Vec<FileMatches>>,
+    /// Result files folded in the tree
+    pub collapsed: RwSignal<HashSet<String>>,
+    pub is_searching: RwSignal<bool>,
+    pub status: RwSignal<String>,
+    /// Token of the running search
+    pub cancel: RwSignal<CancelToken>,
+    /// Bumped by each search so results of an older one are dropped
+    pub generation: RwSignal<u64>,
+}
+
+impl ProjectSearchState {
+    pub fn new() -> Self {
+        Self {
+            visible: RwSignal::new(false),
+            root: RwSignal::new(None),
+            query: RwSignal::new(String::new()),
+            include: RwSignal::new(String::new()),
+            exclude: RwSignal::new(String::new()),
+            case_sensitive: RwSignal::new(false),
+            convert_lsf: RwSignal::new(false),
+            results: RwSignal::new(Vec::new()),
+            collapsed: RwSignal::new(HashSet::new()),
+            is_searching: RwSignal::new(false),
+            status: RwSignal::new(String::new()),
+            cancel: RwSignal::new(CancelToken::new()),
+            generation: RwSignal::new(0),
+        }
+    }
+
+    /// Show the panel, searching `default_root` if no folder was chosen yet
+    pub fn show(&self, default_root: Option<String>) {
+        if self.root.get_untracked().is_none() {
+            self.root.set(default_root);
+        }
+        self.visible.set(true);
+    }
+}
+
+impl Default for ProjectSearchState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Multi-tab editor state
 #[derive(Clone)]
 pub struct EditorTabsState {
@@ -96,9 +450,25 @@ pub struct EditorTabsState {
     pub status_message: RwSignal<String>,
     /// Show line numbers (global setting)
     pub show_line_numbers: RwSignal<bool>,
+    /// Backups kept per file when saving over it (global setting)
+    pub backup_count: RwSignal<usize>,
+    /// Indentation and attribute order for Format Document (global setting)
+    pub format_options: RwSignal<PrettyOptions>,
+    /// Minutes between saves of modified tabs to their files, 0 for off
+    /// (global setting)
+    pub autosave_minutes: RwSignal<u32>,
 
     // Meta.lsx Generator Dialog visibility
     pub show_meta_dialog: RwSignal<bool>,
+    // "Restore previous version" dialog visibility
+    pub show_restore_dialog: RwSignal<bool>,
+
+    /// Comparison shown instead of the editor, if any
+    pub diff: RwSignal<Option<DiffSession>>,
+    /// Hunk the diff view is scrolled to
+    pub diff_hunk: RwSignal<usize>,
+    /// Find in files panel
+    pub project_search: ProjectSearchState,
 }
 
 impl EditorTabsState {
@@ -111,8 +481,16 @@ impl EditorTabsState {
             next_tab_id: RwSignal::new(1),
             status_message: RwSignal::new(String::new()),
             show_line_numbers: RwSignal::new(true),
+            backup_count: RwSignal::new(crate::workspace::backup::DEFAULT_KEEP),
+            format_options: RwSignal::new(PrettyOptions::default()),
+            autosave_minutes: RwSignal::new(0),
 
             show_meta_dialog: RwSignal::new(false),
+            show_restore_dialog: RwSignal::new(false),
+
+            diff: RwSignal::new(None),
+            diff_hunk: RwSignal::new(0),
+            project_search: ProjectSearchState::new(),
         }
     }
 
@@ -120,6 +498,12 @@ impl EditorTabsState {
     pub fn apply_persisted(&self, persisted: &super::PersistedEditorState) {
         // Restore show_line_numbers preference
         self.show_line_numbers.set(persisted.show_line_numbers);
+        self.backup_count.set(persisted.backup_count);
+        self.format_options.set(PrettyOptions {
+            indent: persisted.format_indent.parse().unwrap_or_default(),
+            sort_attributes: persisted.format_sort_attributes,
+        });
+        self.autosave_minutes.set(persisted.autosave_minutes);
 
         // Note: File reopening should happen asynchronously after UI is ready
     }
@@ -131,6 +515,20 @@ impl EditorTabsState {
         tabs.get(index).cloned()
     }
 
+    /// Find an open tab by its ID
+    pub fn tab_by_id(&self, id: u64) -> Option<EditorTab> {
+        self.tabs
+            .get_untracked()
+            .into_iter()
+            .find(|tab| tab.id == id)
+    }
+
+    /// Show a comparison, starting at its first hunk
+    pub fn open_diff(&self, session: DiffSession) {
+        self.diff_hunk.set(0);
+        self.diff.set(Some(session));
+    }
+
     /// Create a new empty tab and make it active
     pub fn new_tab(&self) -> EditorTab {
         let id = self.next_tab_id.get();
@@ -194,10 +592,20 @@ impl EditorTabsState {
                 tab.is_loading.set(false);
                 tab.loading_message.set(String::new());
                 tab.save_status.set(String::new());
+                tab.replace_undo.set(None);
+                tab.structured_view.set(false);
+                tab.stats_issues.set(Vec::new());
+                tab.disk_stamp.set(None);
+                tab.discard_recovery();
             }
             return;
         }
 
+        if let Some(tab) = tabs.get(index) {
+            tab.discard_recovery();
+            tab.drop_editor();
+        }
+
         self.tabs.update(|tabs| {
             if index < tabs.len() {
                 tabs.remove(index);
@@ -215,6 +623,9 @@ impl EditorTabsState {
     pub fn close_others(&self, keep_index: usize) {
         let tabs = self.tabs.get();
         if let Some(tab_to_keep) = tabs.get(keep_index).cloned() {
+            for tab in tabs.iter().filter(|tab| tab.id != tab_to_keep.id) {
+                tab.drop_editor();
+            }
             self.tabs.set(vec![tab_to_keep]);
             self.active_tab_index.set(0);
         }
@@ -224,6 +635,9 @@ impl EditorTabsState {
     pub fn close_all(&self) {
         let id = self.next_tab_id.get();
         self.next_tab_id.set(id + 1);
+        for tab in self.tabs.get_untracked() {
+            tab.drop_editor();
+        }
         self.tabs.set(vec![EditorTab::new(id)]);
         self.active_tab_index.set(0);
     }
@@ -242,10 +656,126 @@ impl EditorTabsState {
         false
     }
 
+    /// Switch to the tab opened from a PAK entry, if one exists
+    pub fn switch_to_pak_entry(&self, source: &PakSource) -> bool {
+        let tabs = self.tabs.get();
+        match tabs
+            .iter()
+            .position(|tab| tab.pak_source.get().as_ref() == Some(source))
+        {
+            Some(index) => {
+                self.active_tab_index.set(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tabs whose files other programs changed or deleted since they were
+    /// loaded or saved
+    ///
+    /// Files that were only touched get their stamp refreshed and aren't
+    /// reported.
+    pub fn changed_on_disk(&self, probe: &dyn DiskProbe) -> Vec<(EditorTab, DiskChange)> {
+        self.tabs
+            .get_untracked()
+            .into_iter()
+            .filter(|tab| !tab.is_loading.get_untracked())
+            .filter_map(|tab| match tab.disk_change(probe) {
+                DiskChange::Unchanged => None,
+                DiskChange::Touched(stamp) => {
+                    tab.disk_stamp.set(Some(stamp));
+                    None
+                }
+                change => Some((tab, change)),
+            })
+            .collect()
+    }
+
+    /// Modified tabs that autosave can write to their files
+    ///
+    /// Leaves out tabs without a file, tabs converted from a binary format
+    /// (saving re-encodes them, which fails on half-edited XML) and files
+    /// changed on disk, which wait for the user to decide.
+    pub fn autosave_candidates(&self, probe: &dyn DiskProbe) -> Vec<EditorTab> {
+        self.tabs
+            .get_untracked()
+            .into_iter()
+            .filter(|tab| {
+                tab.modified.get_untracked()
+                    && !tab.is_loading.get_untracked()
+                    && !tab.converted_from_lsf.get_untracked()
+                    && tab.file_path.get_untracked().is_some()
+                    && matches!(
+                        tab.disk_change(probe),
+                        DiskChange::Unchanged | DiskChange::Touched(_)
+                    )
+            })
+            .collect()
+    }
+
     /// Check if any tab has unsaved changes
     pub fn has_unsaved_changes(&self) -> bool {
         self.tabs.get().iter().any(|tab| tab.modified.get())
     }
+
+    /// Write every modified tab to the session store and drop the recovery
+    /// files of tabs that are no longer modified
+    pub fn save_recovery(&self) -> Result<(), String> {
+        let Some(store) = SessionStore::default_location() else {
+            return Ok(());
+        };
+        let active_index = self.active_tab_index.get_untracked();
+        let buffers: Vec<RecoveredBuffer> = self
+            .tabs
+            .get_untracked()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tab)| {
+                // Only the active tab's editor view is live
+                let cursor_offset = if index == active_index {
+                    tab.editor()
+                        .map(|handle| handle.cursor.get_untracked().offset())
+                        .unwrap_or(0)
+                } else {
+                    0
+                };
+                tab.recovery_buffer(cursor_offset)
+            })
+            .collect();
+        store.sync(&buffers)
+    }
+
+    /// Open a recovered buffer as a modified tab, reusing the active tab if it is empty
+    pub fn open_recovered(&self, buffer: RecoveredBuffer) -> EditorTab {
+        let tab = match self.active_tab() {
+            Some(active)
+                if active.file_path.get().is_none()
+                    && active.content.get().is_empty()
+                    && !active.modified.get() =>
+            {
+                active
+            }
+            _ => self.new_tab(),
+        };
+
+        let pak_source = match (buffer.pak_path, buffer.pak_internal_path) {
+            (Some(pak_path), Some(internal_path)) => Some(PakSource {
+                pak_path,
+                internal_path,
+            }),
+            _ => None,
+        };
+        tab.file_path.set(buffer.original_path);
+        tab.pak_source.set(pak_source);
+        tab.converted_from_lsf.set(buffer.converted_from_binary);
+        tab.file_format.set(buffer.format);
+        tab.restore_cursor.set(Some(buffer.cursor_offset));
+        tab.set_text(buffer.content);
+        tab.modified.set(true);
+        tab.recovered.set(true);
+        tab
+    }
 }
 
 impl Default for EditorTabsState {
@@ -256,3 +786,82 @@ impl Default for EditorTabsState {
 
 /// Legacy EditorState alias for backward compatibility during transition
 pub type EditorState = EditorTab;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    /// In-memory files with a modification time each
+    #[derive(Default)]
+    struct FakeDisk {
+        files: RefCell<HashMap<PathBuf, (SystemTime, Vec<u8>)>>,
+        hashed: RefCell<usize>,
+    }
+
+    impl FakeDisk {
+        fn write(&self, path: &str, secs: u64, data: &[u8]) {
+            let time = SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+            self.files
+                .borrow_mut()
+                .insert(PathBuf::from(path), (time, data.to_vec()));
+        }
+    }
+
+    impl DiskProbe for FakeDisk {
+        fn metadata(&self, path: &Path) -> Option<(Option<SystemTime>, u64)> {
+            let files = self.files.borrow();
+            let (time, data) = files.get(path)?;
+            Some((Some(*time), data.len() as u64))
+        }
+
+        fn hash(&self, path: &Path) -> Option<u64> {
+            *self.hashed.borrow_mut() += 1;
+            let files = self.files.borrow();
+            let mut hasher = DefaultHasher::new();
+            hasher.write(&files.get(path)?.1);
+            Some(hasher.finish())
+        }
+    }
+
+    #[test]
+    fn test_disk_stamp_detects_external_changes() {
+        let disk = FakeDisk::default();
+        let path = Path::new("/mod/meta.lsx");
+        disk.write("/mod/meta.lsx", 100, b"<save/>");
+        let stamp = DiskStamp::read(path, &disk).unwrap();
+
+        *disk.hashed.borrow_mut() = 0;
+        assert_eq!(stamp.check(path, &disk), DiskChange::Unchanged);
+        assert_eq!(*disk.hashed.borrow(), 0, "unchanged files aren't hashed");
+
+        // Re-extracted with the same bytes
+        disk.write("/mod/meta.lsx", 200, b"<save/>");
+        let DiskChange::Touched(touched) = stamp.check(path, &disk) else {
+            panic!("same bytes with a new time should count as touched");
+        };
+        assert_eq!(touched.hash, stamp.hash);
+        assert_eq!(touched.check(path, &disk), DiskChange::Unchanged);
+
+        // Same length, different bytes
+        disk.write("/mod/meta.lsx", 300, b"<SAVE/>");
+        assert!(matches!(
+            touched.check(path, &disk),
+            DiskChange::Modified(_)
+        ));
+
+        // A write within the same tick that changes the length
+        disk.write("/mod/meta.lsx", 200, b"<save></save>");
+        assert!(matches!(
+            touched.check(path, &disk),
+            DiskChange::Modified(_)
+        ));
+
+        disk.files.borrow_mut().clear();
+        assert_eq!(stamp.check(path, &disk), DiskChange::Deleted);
+        assert_eq!(DiskStamp::read(path, &disk), None);
+    }
+}
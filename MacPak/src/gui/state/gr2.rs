@@ -3,15 +3,7 @@
 use floem::prelude::*;
 use im::Vector as ImVector;
 
-use crate::gui::shared::{BatchOperationState, SharedProgress};
-
-/// Global shared progress instance for GR2 operations
-static GR2_SHARED_PROGRESS: std::sync::OnceLock<SharedProgress> = std::sync::OnceLock::new();
-
-/// Get or create the global shared progress instance for GR2
-pub fn get_shared_progress() -> &'static SharedProgress {
-    GR2_SHARED_PROGRESS.get_or_init(SharedProgress::new)
-}
+use crate::gui::shared::{BatchOperationState, ProgressChannel, ProgressSource, ResultEntry};
 
 /// GR2 Conversion tab state
 #[derive(Clone)]
@@ -23,11 +15,12 @@ pub struct Gr2State {
     pub batch_input_dir: RwSignal<Option<String>>,
     pub batch_files: RwSignal<Vec<String>>,
 
-    // Progress (uses shared atomic state for thread-safe updates)
+    // Progress (worker threads send events through the channel)
     pub is_converting: RwSignal<bool>,
+    pub progress: ProgressChannel,
 
     // Results - uses ImVector for efficient batch updates with virtual_list
-    pub results_log: RwSignal<ImVector<String>>,
+    pub results_log: RwSignal<ImVector<ResultEntry>>,
     pub status_message: RwSignal<String>,
 
     // Working directory for file dialogs
@@ -38,6 +31,12 @@ pub struct Gr2State {
     pub convert_to_png: RwSignal<bool>,
     pub keep_original_dds: RwSignal<bool>,
     pub keep_original_gr2: RwSignal<bool>,
+
+    // Model preview
+    /// Output of the last successful single-file conversion
+    pub last_output: RwSignal<Option<String>>,
+    /// Open the running single-file conversion's output once it's done
+    pub preview_after_convert: RwSignal<bool>,
 }
 
 impl Gr2State {
@@ -47,6 +46,7 @@ impl Gr2State {
             batch_input_dir: RwSignal::new(None),
             batch_files: RwSignal::new(Vec::new()),
             is_converting: RwSignal::new(false),
+            progress: ProgressChannel::new(),
             results_log: RwSignal::new(ImVector::new()),
             status_message: RwSignal::new(String::new()),
             working_dir: RwSignal::new(None),
@@ -55,21 +55,21 @@ impl Gr2State {
             convert_to_png: RwSignal::new(false),
             keep_original_dds: RwSignal::new(false),
             keep_original_gr2: RwSignal::new(false),
+            last_output: RwSignal::new(None),
+            preview_after_convert: RwSignal::new(false),
         }
     }
 
     pub fn add_result(&self, message: &str) {
         self.results_log.update(|log| {
-            log.push_back(message.to_string());
+            log.push_back(ResultEntry::from_message(message));
         });
     }
 
     /// Add multiple results in a single batch update (avoids UI freezing)
     pub fn add_results_batch(&self, messages: Vec<String>) {
         self.results_log.update(|log| {
-            for msg in messages {
-                log.push_back(msg);
-            }
+            log.extend(messages.into_iter().map(ResultEntry::from_message));
         });
     }
 
@@ -89,7 +89,7 @@ impl BatchOperationState for Gr2State {
         self.is_converting
     }
 
-    fn results_log(&self) -> RwSignal<ImVector<String>> {
+    fn results_log(&self) -> RwSignal<ImVector<ResultEntry>> {
         self.results_log
     }
 
@@ -109,7 +109,7 @@ impl BatchOperationState for Gr2State {
         Gr2State::clear_results(self);
     }
 
-    fn get_shared_progress(&self) -> &'static SharedProgress {
-        get_shared_progress()
+    fn progress_source(&self) -> ProgressSource {
+        ProgressSource::Channel(self.progress.clone())
     }
 }
@@ -3,7 +3,7 @@
 use floem::prelude::*;
 use im::Vector as ImVector;
 
-use crate::gui::shared::{BatchOperationState, SharedProgress};
+use crate::gui::shared::{BatchOperationState, ProgressSource, ResultEntry, SharedProgress};
 
 /// Global shared progress instance for LSF conversion operations
 static LSF_SHARED_PROGRESS: std::sync::OnceLock<SharedProgress> = std::sync::OnceLock::new();
@@ -35,11 +35,14 @@ pub struct LsfConvertState {
     pub is_converting: RwSignal<bool>,
 
     // Results - uses ImVector for efficient batch updates with virtual_list
-    pub results_log: RwSignal<ImVector<String>>,
+    pub results_log: RwSignal<ImVector<ResultEntry>>,
     pub status_message: RwSignal<String>,
 
     // Working directory for file dialogs
     pub working_dir: RwSignal<Option<String>>,
+
+    // Backups kept when a conversion overwrites a file (the editor's setting)
+    pub backup_count: RwSignal<usize>,
 }
 
 impl LsfConvertState {
@@ -58,20 +61,19 @@ impl LsfConvertState {
             results_log: RwSignal::new(ImVector::new()),
             status_message: RwSignal::new(String::new()),
             working_dir: RwSignal::new(None),
+            backup_count: RwSignal::new(crate::workspace::backup::DEFAULT_KEEP),
         }
     }
 
     pub fn add_result(&self, message: &str) {
         self.results_log.update(|log| {
-            log.push_back(message.to_string());
+            log.push_back(ResultEntry::from_message(message));
         });
     }
 
     pub fn add_results_batch(&self, messages: Vec<String>) {
         self.results_log.update(|log| {
-            for msg in messages {
-                log.push_back(msg);
-            }
+            log.extend(messages.into_iter().map(ResultEntry::from_message));
         });
     }
 
@@ -91,7 +93,7 @@ impl BatchOperationState for LsfConvertState {
         self.is_converting
     }
 
-    fn results_log(&self) -> RwSignal<ImVector<String>> {
+    fn results_log(&self) -> RwSignal<ImVector<ResultEntry>> {
         self.results_log
     }
 
@@ -111,7 +113,7 @@ impl BatchOperationState for LsfConvertState {
         LsfConvertState::clear_results(self);
     }
 
-    fn get_shared_progress(&self) -> &'static SharedProgress {
-        get_shared_progress()
+    fn progress_source(&self) -> ProgressSource {
+        ProgressSource::Shared(get_shared_progress())
     }
 }
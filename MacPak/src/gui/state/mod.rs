@@ -1,7 +1,7 @@
 //! Shared application state for MacPak
 
 mod app;
-mod browser;
+pub mod browser;
 mod config;
 mod dialogue;
 mod dyes;
@@ -10,6 +10,7 @@ pub mod gr2;
 pub mod lsf_convert;
 mod pak_ops;
 mod search;
+mod uuid_generator;
 pub mod virtual_textures;
 mod workbench;
 
@@ -17,20 +18,29 @@ mod workbench;
 pub use app::AppState;
 pub use browser::{BrowserState, FileEntry, RawImageData, SortColumn};
 pub use config::{
-    ConfigState, PersistedBrowserState, PersistedConfig, PersistedDialogueState,
-    PersistedEditorState, PersistedSearchState, PersistedWindowState,
+    ConfigState, DEFAULT_LOG_LEVEL, PersistedBrowserState, PersistedConfig, PersistedDialogueState,
+    PersistedDyesState, PersistedEditorState, PersistedSearchState, PersistedUuidState,
+    PersistedWindowState, configured_index_dir, configured_output_dir, detect_bg3_path,
+    pinned_then_recent,
 };
 pub use dialogue::{
     DialogEntry, DialogSource, DialogueState, DisplayFlag, DisplayNode, NODE_TYPE_OPTIONS,
     VoiceMetaCache, VoiceMetaEntry,
 };
 pub use dyes::{
-    DyeColorEntry, DyesState, GeneratedDyeEntry, ImportedDyeEntry, VENDOR_DEFS, VendorDef,
+    CustomTreasureTable, DyeColorEntry, DyesState, GeneratedDyeEntry, ImportedDyeEntry,
+    TUTORIAL_CHEST_TABLE, VENDOR_DEFS, VendorDef,
+};
+pub use editor::{
+    DiffSession, DiffSide, DiskChange, DiskStamp, EditorHandle, EditorState, EditorTab,
+    EditorTabsState, PakSource, ProjectSearchState, RealDisk, ReplaceUndo,
 };
-pub use editor::{EditorState, EditorTab, EditorTabsState};
 pub use gr2::Gr2State;
 pub use lsf_convert::LsfConvertState;
-pub use pak_ops::{ActiveDialog, PakCompression, PakOpsState};
+pub use pak_ops::{
+    ActiveDialog, BatchExtractLayout, MergeConflictPolicy, PakCompression, PakOpsState,
+};
 pub use search::{IndexStatus, SearchResult, SearchSortColumn, SearchState, SortDirection};
+pub use uuid_generator::{MAX_BULK_COUNT, MAX_PERSISTED_HISTORY, UuidGeneratorState};
 pub use virtual_textures::VirtualTexturesState;
 pub use workbench::{PersistedWorkbenchState, WorkbenchState};
@@ -2,6 +2,7 @@
 
 use floem::prelude::*;
 use im::Vector as ImVector;
+use maclarian::pak::{ExtractionPipeline, Gr2ExtractionOptions};
 use std::collections::HashSet;
 
 /// Which dialog is currently active (only one at a time)
@@ -15,6 +16,7 @@ pub enum ActiveDialog {
     FileSelect,
     FolderDropAction,
     ValidateChoice,
+    ModRootWarning,
 }
 
 /// Compression options for PAK creation
@@ -22,6 +24,7 @@ pub enum ActiveDialog {
 pub enum PakCompression {
     Lz4Hc,
     Lz4,
+    Zlib,
     None,
 }
 
@@ -30,6 +33,7 @@ impl PakCompression {
         match self {
             PakCompression::Lz4Hc => "lz4hc",
             PakCompression::Lz4 => "lz4",
+            PakCompression::Zlib => "zlib",
             PakCompression::None => "none",
         }
     }
@@ -39,11 +43,48 @@ impl PakCompression {
         match self {
             PakCompression::Lz4Hc => "Best compression (default)",
             PakCompression::Lz4 => "Fast compression",
+            PakCompression::Zlib => "Better ratio, slower to load",
             PakCompression::None => "No compression",
         }
     }
 }
 
+/// Where batch extraction puts each PAK's files
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BatchExtractLayout {
+    /// One subfolder per PAK, named after it
+    PerPak,
+    /// All PAKs into one tree, in path order
+    Merged,
+}
+
+impl BatchExtractLayout {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchExtractLayout::PerPak => "Folder per PAK",
+            BatchExtractLayout::Merged => "Merged tree",
+        }
+    }
+}
+
+/// What a merged batch extraction does with files that already exist
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeConflictPolicy {
+    /// Later PAKs replace earlier files
+    Overwrite,
+    /// The first copy of a file is kept
+    KeepExisting,
+}
+
+impl MergeConflictPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeConflictPolicy::Overwrite => "Overwrite",
+            MergeConflictPolicy::KeepExisting => "Keep existing",
+        }
+    }
+}
+
 /// PAK Operations state
 #[derive(Clone)]
 pub struct PakOpsState {
@@ -73,9 +114,13 @@ pub struct PakOpsState {
     pub compression: RwSignal<PakCompression>,
     pub priority: RwSignal<i32>,
     pub generate_info_json: RwSignal<bool>,
+    /// Glob patterns left out of created PAKs, comma or newline separated
+    pub exclude_patterns: RwSignal<String>,
 
     // Pending create operation (source, dest)
     pub pending_create: RwSignal<Option<(String, String)>>,
+    /// Why the pending create folder doesn't look like a mod root
+    pub create_warnings: RwSignal<Vec<String>>,
 
     // Working directory
     pub working_dir: RwSignal<Option<String>>,
@@ -100,11 +145,23 @@ pub struct PakOpsState {
     pub gr2_convert_to_png: RwSignal<bool>,
     pub game_data_path: RwSignal<Option<String>>,
 
+    // GR2 processing for "Extract PAK File" (same steps as `pak extract --bundle`)
+    pub pipeline_convert_gr2: RwSignal<bool>,
+    pub pipeline_extract_textures: RwSignal<bool>,
+    pub pipeline_convert_png: RwSignal<bool>,
+    pub pipeline_delete_gr2: RwSignal<bool>,
+    pub pipeline_keep_dds: RwSignal<bool>,
+
+    // Batch extraction options
+    pub batch_layout: RwSignal<BatchExtractLayout>,
+    pub batch_conflict: RwSignal<MergeConflictPolicy>,
+
     // Progress polling signals (persistent to avoid accumulation on tab switch)
     pub polled_pct: RwSignal<u32>,
     pub polled_current: RwSignal<u32>,
     pub polled_total: RwSignal<u32>,
     pub polled_msg: RwSignal<String>,
+    pub polled_cancellable: RwSignal<bool>,
     pub timer_active: RwSignal<bool>,
     /// Guards against multiple effect registrations
     pub polling_effect_registered: RwSignal<bool>,
@@ -130,7 +187,9 @@ impl PakOpsState {
             compression: RwSignal::new(PakCompression::Lz4Hc),
             priority: RwSignal::new(0),
             generate_info_json: RwSignal::new(true), // Default to true for BaldursModManager compatibility
+            exclude_patterns: RwSignal::new(String::new()),
             pending_create: RwSignal::new(None),
+            create_warnings: RwSignal::new(Vec::new()),
 
             working_dir: RwSignal::new(None),
 
@@ -150,10 +209,20 @@ impl PakOpsState {
             gr2_convert_to_png: RwSignal::new(false),
             game_data_path: RwSignal::new(None),
 
+            pipeline_convert_gr2: RwSignal::new(false),
+            pipeline_extract_textures: RwSignal::new(false),
+            pipeline_convert_png: RwSignal::new(false),
+            pipeline_delete_gr2: RwSignal::new(false),
+            pipeline_keep_dds: RwSignal::new(false),
+
+            batch_layout: RwSignal::new(BatchExtractLayout::PerPak),
+            batch_conflict: RwSignal::new(MergeConflictPolicy::Overwrite),
+
             polled_pct: RwSignal::new(0),
             polled_current: RwSignal::new(0),
             polled_total: RwSignal::new(0),
             polled_msg: RwSignal::new(String::new()),
+            polled_cancellable: RwSignal::new(false),
             timer_active: RwSignal::new(false),
             polling_effect_registered: RwSignal::new(false),
         }
@@ -166,6 +235,21 @@ impl PakOpsState {
             || self.is_validating.get()
     }
 
+    /// Extraction pipeline built from the GR2 processing checkboxes
+    pub fn extraction_pipeline(&self) -> ExtractionPipeline {
+        let png = self.pipeline_convert_png.get();
+        let textures = self.pipeline_extract_textures.get() || png;
+        let options = Gr2ExtractionOptions::new()
+            .with_convert_to_glb(self.pipeline_convert_gr2.get())
+            .with_extract_textures(textures)
+            .with_extract_virtual_textures(textures)
+            .with_png_conversion(png)
+            .with_keep_original(!self.pipeline_delete_gr2.get())
+            .with_keep_original_dds(self.pipeline_keep_dds.get())
+            .with_bg3_path(self.game_data_path.get().map(std::path::PathBuf::from));
+        ExtractionPipeline::new(options)
+    }
+
     pub fn add_result(&self, message: &str) {
         self.results_log.update(|log| {
             log.push_back(message.to_string());
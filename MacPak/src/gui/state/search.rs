@@ -1,9 +1,11 @@
 //! Search tab state
 
 use std::collections::HashSet;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
-use crate::search::{ContentCache, FileType, IndexedFile, SearchIndex};
+use maclarian::pak::PakReaderCache;
+
+use crate::search::{ContentCache, FileType, IndexStats, IndexedFile, IndexedFileRef, SearchIndex};
 use floem::prelude::*;
 
 /// Index status for UI display
@@ -63,6 +65,8 @@ pub struct SearchState {
     pub is_searching: RwSignal<bool>,
     /// Active file type filter (None = all types)
     pub active_filter: RwSignal<Option<FileType>>,
+    /// Also match filenames with typos (slower, off by default)
+    pub fuzzy: RwSignal<bool>,
     /// Index status for display
     pub index_status: RwSignal<IndexStatus>,
     /// Shared search index (thread-safe)
@@ -71,6 +75,14 @@ pub struct SearchState {
     pub content_cache: Arc<RwLock<ContentCache>>,
     /// Configured PAK paths to search
     pub pak_paths: RwSignal<Vec<std::path::PathBuf>>,
+    /// Also build the full-text index when indexing
+    pub build_fulltext: RwSignal<bool>,
+    /// Skip video, audio and shader cache PAKs when indexing a game install
+    pub skip_media_paks: RwSignal<bool>,
+    /// Counts and staleness of the loaded index (None until one is built or loaded)
+    pub index_stats: RwSignal<Option<IndexStats>>,
+    /// Outcome of the last index save/load, shown in the index bar
+    pub index_message: RwSignal<String>,
     /// Show progress dialog during long operations
     pub show_progress: RwSignal<bool>,
     /// Progress message for dialog
@@ -91,6 +103,10 @@ pub struct SearchState {
     pub show_all_matches: RwSignal<bool>,
     /// Current file for "Show All Matches" dialog
     pub all_matches_file: RwSignal<Option<SearchResult>>,
+    /// Image shown in the preview dialog
+    pub preview_file: RwSignal<Option<IndexedFile>>,
+    /// Open PAK readers, for opening results in the editor
+    pub pak_cache: Arc<Mutex<PakReaderCache>>,
 
     // Extraction dialog state
     /// Show extraction options dialog
@@ -113,10 +129,15 @@ impl SearchState {
             results: RwSignal::new(Vec::new()),
             is_searching: RwSignal::new(false),
             active_filter: RwSignal::new(None),
+            fuzzy: RwSignal::new(false),
             index_status: RwSignal::new(IndexStatus::NotBuilt),
             index: Arc::new(RwLock::new(SearchIndex::new())),
             content_cache: Arc::new(RwLock::new(ContentCache::new())),
             pak_paths: RwSignal::new(Vec::new()),
+            build_fulltext: RwSignal::new(true),
+            skip_media_paks: RwSignal::new(true),
+            index_stats: RwSignal::new(None),
+            index_message: RwSignal::new(String::new()),
             show_progress: RwSignal::new(false),
             progress_message: RwSignal::new(String::new()),
             progress_percent: RwSignal::new(0),
@@ -127,6 +148,8 @@ impl SearchState {
             selected_results: RwSignal::new(HashSet::new()),
             show_all_matches: RwSignal::new(false),
             all_matches_file: RwSignal::new(None),
+            preview_file: RwSignal::new(None),
+            pak_cache: Arc::new(Mutex::new(PakReaderCache::new(4))),
 
             // Extraction dialog
             show_extract_dialog: RwSignal::new(false),
@@ -191,18 +214,13 @@ impl Default for SearchState {
 }
 
 /// Search result entry for display
+///
+/// Carries the index entry itself, so actions (extract, open, preview) work
+/// from the same data the search matched on.
 #[derive(Clone, Debug)]
 pub struct SearchResult {
-    /// Filename
-    pub name: String,
-    /// Full internal path
-    pub path: String,
-    /// Source PAK file name
-    pub pak_file: String,
-    /// File type for icon display
-    pub file_type: String,
-    /// Full PAK path for extraction
-    pub pak_path: std::path::PathBuf,
+    /// Index entry of the matched file
+    pub file: IndexedFile,
     /// Match context (for content search)
     pub context: Option<String>,
     /// Number of matches in the file (for content search)
@@ -210,28 +228,33 @@ pub struct SearchResult {
 }
 
 impl SearchResult {
-    /// Create from an IndexedFile
-    pub fn from_indexed_file(file: &IndexedFile) -> Self {
+    /// Create from an index entry
+    pub fn from_indexed_file(file: IndexedFileRef<'_>) -> Self {
         Self {
-            name: file.name.clone(),
-            path: file.path.clone(),
-            pak_file: file
-                .pak_file
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string()),
-            file_type: file.file_type.display_name().to_string(),
-            pak_path: file.pak_file.clone(),
+            file: file.into(),
             context: None,
             match_count: None,
         }
     }
 
     /// Create from a content match
-    pub fn from_content_match(file: &IndexedFile, context: String, match_count: usize) -> Self {
+    pub fn from_content_match(
+        file: IndexedFileRef<'_>,
+        context: String,
+        match_count: usize,
+    ) -> Self {
         let mut result = Self::from_indexed_file(file);
         result.context = Some(context);
         result.match_count = Some(match_count);
         result
     }
+
+    /// File name of the source PAK, for display
+    pub fn pak_name(&self) -> String {
+        self.file
+            .pak_file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
 }
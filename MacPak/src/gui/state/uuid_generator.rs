@@ -0,0 +1,97 @@
+//! UUID generator dialog state
+
+use floem::prelude::*;
+use maclarian::utils::{UuidFormat, generate_uuids};
+
+/// Most values a single Generate click produces
+pub const MAX_BULK_COUNT: usize = 1000;
+
+/// History entries per kind kept across restarts
+pub const MAX_PERSISTED_HISTORY: usize = 50;
+
+/// UUID generator dialog state
+#[derive(Clone)]
+pub struct UuidGeneratorState {
+    /// Whether the dialog is visible
+    pub show_dialog: RwSignal<bool>,
+    /// Generate `TranslatedString` handles instead of UUIDs
+    pub generate_handles: RwSignal<bool>,
+    /// How many values to generate (text field)
+    pub count: RwSignal<String>,
+    /// Generated UUIDs, newest batch first
+    pub uuid_history: RwSignal<Vec<String>>,
+    /// Generated handles, newest batch first
+    pub handle_history: RwSignal<Vec<String>>,
+    /// Values from the last Generate click
+    pub last_batch: RwSignal<Vec<String>>,
+    /// Text pasted into the validation box
+    pub validate_input: RwSignal<String>,
+    /// Status line (copy confirmations, count errors)
+    pub status: RwSignal<String>,
+}
+
+impl UuidGeneratorState {
+    pub fn new() -> Self {
+        Self {
+            show_dialog: RwSignal::new(false),
+            generate_handles: RwSignal::new(false),
+            count: RwSignal::new("1".to_string()),
+            uuid_history: RwSignal::new(Vec::new()),
+            handle_history: RwSignal::new(Vec::new()),
+            last_batch: RwSignal::new(Vec::new()),
+            validate_input: RwSignal::new(String::new()),
+            status: RwSignal::new(String::new()),
+        }
+    }
+
+    pub fn apply_persisted(&self, persisted: &super::PersistedUuidState) {
+        self.uuid_history.set(persisted.uuid_history.clone());
+        self.handle_history.set(persisted.handle_history.clone());
+    }
+
+    /// History of the kind currently being generated
+    pub fn active_history(&self) -> RwSignal<Vec<String>> {
+        if self.generate_handles.get() {
+            self.handle_history
+        } else {
+            self.uuid_history
+        }
+    }
+
+    /// Generate `count` values of the active kind and prepend them to its history.
+    /// Returns the number generated, or an error if the count isn't 1 to
+    /// [`MAX_BULK_COUNT`].
+    pub fn generate(&self) -> Result<usize, String> {
+        let count = self
+            .count
+            .get()
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=MAX_BULK_COUNT).contains(n))
+            .ok_or_else(|| format!("Count must be between 1 and {}", MAX_BULK_COUNT))?;
+
+        let format = if self.generate_handles.get() {
+            UuidFormat::Larian
+        } else {
+            UuidFormat::Standard
+        };
+        let batch = generate_uuids(format, count);
+
+        // Newest batch first, in the order it was generated
+        self.active_history().update(|history| {
+            let mut updated = batch.clone();
+            updated.append(history);
+            updated.truncate(MAX_BULK_COUNT);
+            *history = updated;
+        });
+        self.last_batch.set(batch);
+        Ok(count)
+    }
+}
+
+impl Default for UuidGeneratorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
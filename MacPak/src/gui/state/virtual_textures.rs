@@ -1,38 +1,42 @@
 //! Virtual Textures tab state
 
+use std::collections::HashSet;
+
 use floem::prelude::*;
 use im::Vector as ImVector;
 use maclarian::converter::DdsFormat;
+use maclarian::virtual_texture::{ExtractOptions, VTexNaming};
 
-use crate::gui::shared::{BatchOperationState, SharedProgress};
-
-/// Global shared progress instance for Virtual Textures operations
-static VT_SHARED_PROGRESS: std::sync::OnceLock<SharedProgress> = std::sync::OnceLock::new();
-
-/// Get or create the global shared progress instance for Virtual Textures
-pub fn get_shared_progress() -> &'static SharedProgress {
-    VT_SHARED_PROGRESS.get_or_init(SharedProgress::new)
-}
+use crate::gui::shared::{BatchOperationState, ProgressChannel, ProgressSource, ResultEntry};
 
 /// Virtual Textures extraction state
 #[derive(Clone)]
 pub struct VirtualTexturesState {
     // Single file extraction
     pub gts_file: RwSignal<Option<String>>,
+    // Textures (GTP filenames) in the selected GTS; empty until one is loaded
+    pub gts_textures: RwSignal<Vec<String>>,
+    pub selected_textures: RwSignal<HashSet<String>>,
+    pub texture_filter: RwSignal<String>,
 
     // Batch extraction
     pub batch_input_dir: RwSignal<Option<String>>,
     pub batch_output_dir: RwSignal<Option<String>>,
     pub batch_gts_files: RwSignal<Vec<String>>,
+    // Only extract textures whose GTP filename contains this (empty = all)
+    pub batch_name_filter: RwSignal<String>,
 
     // Layer selection: None = All layers, Some(n) = specific layer
     pub selected_layer: RwSignal<Option<usize>>,
+    // Output file naming: MacPak folders or LSLib-compatible names
+    pub vt_naming: RwSignal<VTexNaming>,
 
-    // Progress (uses shared atomic state for thread-safe updates)
+    // Progress (worker threads send events through the channel)
     pub is_extracting: RwSignal<bool>,
+    pub progress: ProgressChannel,
 
     // Results - uses ImVector for efficient batch updates with virtual_list
-    pub results_log: RwSignal<ImVector<String>>,
+    pub results_log: RwSignal<ImVector<ResultEntry>>,
     pub status_message: RwSignal<String>,
 
     // Working directory for file dialogs
@@ -55,11 +59,17 @@ impl VirtualTexturesState {
     pub fn new() -> Self {
         Self {
             gts_file: RwSignal::new(None),
+            gts_textures: RwSignal::new(Vec::new()),
+            selected_textures: RwSignal::new(HashSet::new()),
+            texture_filter: RwSignal::new(String::new()),
             batch_input_dir: RwSignal::new(None),
             batch_output_dir: RwSignal::new(None),
             batch_gts_files: RwSignal::new(Vec::new()),
+            batch_name_filter: RwSignal::new(String::new()),
             selected_layer: RwSignal::new(None), // Default to All Layers
+            vt_naming: RwSignal::new(VTexNaming::MacPak),
             is_extracting: RwSignal::new(false),
+            progress: ProgressChannel::new(),
             results_log: RwSignal::new(ImVector::new()),
             status_message: RwSignal::new(String::new()),
             working_dir: RwSignal::new(None),
@@ -77,22 +87,36 @@ impl VirtualTexturesState {
 
     pub fn add_result(&self, message: &str) {
         self.results_log.update(|log| {
-            log.push_back(message.to_string());
+            log.push_back(ResultEntry::from_message(message));
         });
     }
 
     /// Add multiple results in a single batch update (avoids UI freezing)
     pub fn add_results_batch(&self, messages: Vec<String>) {
         self.results_log.update(|log| {
-            for msg in messages {
-                log.push_back(msg);
-            }
+            log.extend(messages.into_iter().map(ResultEntry::from_message));
         });
     }
 
     pub fn clear_results(&self) {
         self.results_log.set(ImVector::new());
     }
+
+    /// Extraction options for the selected layer and naming
+    pub fn extract_options(&self) -> ExtractOptions {
+        ExtractOptions {
+            layers: self.selected_layer.get_untracked().into_iter().collect(),
+            naming: self.vt_naming.get_untracked(),
+            ..ExtractOptions::default()
+        }
+    }
+
+    /// Forget the texture list of the selected GTS
+    pub fn clear_textures(&self) {
+        self.gts_textures.set(Vec::new());
+        self.selected_textures.set(HashSet::new());
+        self.texture_filter.set(String::new());
+    }
 }
 
 impl Default for VirtualTexturesState {
@@ -106,7 +130,7 @@ impl BatchOperationState for VirtualTexturesState {
         self.is_extracting
     }
 
-    fn results_log(&self) -> RwSignal<ImVector<String>> {
+    fn results_log(&self) -> RwSignal<ImVector<ResultEntry>> {
         self.results_log
     }
 
@@ -126,7 +150,7 @@ impl BatchOperationState for VirtualTexturesState {
         VirtualTexturesState::clear_results(self);
     }
 
-    fn get_shared_progress(&self) -> &'static SharedProgress {
-        get_shared_progress()
+    fn progress_source(&self) -> ProgressSource {
+        ProgressSource::Channel(self.progress.clone())
     }
 }
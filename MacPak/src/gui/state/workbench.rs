@@ -1,10 +1,13 @@
 //! Workbench tab state
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use floem::prelude::*;
 
+use crate::operations::task_queue::{QueueRun, TaskQueue, TaskStatus};
 use crate::workbench::Workbench;
 use crate::workbench::recipe::Recipe;
 
@@ -26,6 +29,12 @@ pub struct WorkbenchState {
     /// Persisted expanded state for the file tree (full path → expanded).
     /// Survives tab switches since WorkbenchState lives in app state.
     pub file_tree_expanded: RwSignal<HashMap<PathBuf, bool>>,
+    /// Queued operations shown in the task list
+    pub task_queue: RwSignal<TaskQueue>,
+    /// Latest status of each queued task, by index
+    pub task_status: RwSignal<Vec<TaskStatus>>,
+    /// Queue runs still in progress, polled on a timer
+    pub task_runs: Rc<RefCell<Vec<QueueRun>>>,
 }
 
 impl WorkbenchState {
@@ -39,6 +48,9 @@ impl WorkbenchState {
             result_message: RwSignal::new(None),
             error_message: RwSignal::new(None),
             file_tree_expanded: RwSignal::new(HashMap::new()),
+            task_queue: RwSignal::new(TaskQueue::new()),
+            task_status: RwSignal::new(Vec::new()),
+            task_runs: Rc::new(RefCell::new(Vec::new())),
         }
     }
 
@@ -7,9 +7,11 @@ use floem::action::show_context_menu;
 use floem::menu::{Menu, MenuItem};
 use floem::prelude::*;
 
-use super::operations::{convert_file_quick, delete_file, is_text_file};
-use crate::gui::state::{BrowserState, EditorTabsState, FileEntry};
-use crate::gui::tabs::load_file_in_tab;
+use super::operations::{
+    convert_file_quick, delete_file, extract_folder, extract_selected, is_text_file,
+};
+use crate::gui::state::{BrowserState, EditorTabsState, FileEntry, PakSource};
+use crate::gui::tabs::{load_file_in_tab, open_pak_entry};
 use crate::gui::utils::copy_to_clipboard;
 
 /// Show context menu for a file entry
@@ -24,6 +26,11 @@ pub fn show_file_context_menu(
     let is_dir = file.is_dir;
     let file_name = file.name.clone();
 
+    if let Some(pak_path) = &file.pak_path {
+        show_pak_entry_context_menu(file, pak_path, state, editor_tabs_state, active_tab);
+        return;
+    }
+
     let mut menu = Menu::new("");
 
     // Open in Editor (text files only)
@@ -150,3 +157,53 @@ pub fn show_file_context_menu(
 
     show_context_menu(menu, None);
 }
+
+/// Context menu for an entry inside a PAK (read-only: open, extract, and copy path)
+fn show_pak_entry_context_menu(
+    file: &FileEntry,
+    pak_path: &str,
+    state: BrowserState,
+    editor_tabs_state: EditorTabsState,
+    active_tab: RwSignal<usize>,
+) {
+    let mut menu = Menu::new("");
+
+    // Open in Editor (text files only), converting binary formats in memory
+    if !file.is_dir && is_text_file(&file.extension) {
+        let source = PakSource {
+            pak_path: pak_path.to_string(),
+            internal_path: file.path.clone(),
+        };
+        let cache = state.pak_cache.clone();
+        menu = menu.entry(MenuItem::new("Open in Editor").action(move || {
+            open_pak_entry(source.clone(), cache.clone(), editor_tabs_state.clone());
+            active_tab.set(1);
+        }));
+        menu = menu.separator();
+    }
+
+    // Extract the selection, or the folder (this one, or the one being shown)
+    {
+        let browser_state = state.clone();
+        menu = menu.entry(MenuItem::new("Extract selected...").action(move || {
+            extract_selected(browser_state.clone());
+        }));
+
+        let folder = file.is_dir.then(|| file.path.clone());
+        let browser_state = state.clone();
+        menu = menu.entry(MenuItem::new("Extract this folder...").action(move || {
+            extract_folder(folder.clone(), browser_state.clone());
+        }));
+        menu = menu.separator();
+    }
+
+    // Copy Path (path inside the PAK)
+    {
+        let path = file.path.clone();
+        menu = menu.entry(MenuItem::new("Copy Path").action(move || {
+            copy_to_clipboard(&path);
+        }));
+    }
+
+    show_context_menu(menu, None);
+}
@@ -1,13 +1,18 @@
 //! File list widget with sortable columns and keyboard navigation
 
-use floem::event::{EventListener, EventPropagation};
-use floem::keyboard::{Key, NamedKey};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use floem::event::{Event, EventListener, EventPropagation};
+use floem::keyboard::{Key, Modifiers, NamedKey};
 use floem::prelude::*;
 use floem::text::Weight;
 use floem::views::{VirtualDirection, VirtualItemSize, virtual_list};
 use im::Vector as ImVector;
 
-use crate::gui::state::{BrowserState, EditorTabsState, FileEntry, SortColumn};
+use crate::gui::shared::{colors, recent_list, thumbnail_cell};
+use crate::gui::state::{BrowserState, ConfigState, EditorTabsState, FileEntry, SortColumn};
+use crate::search::{FileType, IndexedFile};
 
 const FILE_ROW_HEIGHT: f64 = 37.0;
 
@@ -20,6 +25,60 @@ const ROW_PADDING: f64 = 16.0; // 8px on each side
 const COLUMN_GAPS: f64 = 32.0; // gaps between columns
 const CHAR_WIDTH: f64 = 7.5; // estimated average character width
 
+/// One row of the file list: a type section header in group-by-type mode,
+/// or a file with its index in `BrowserState::files`
+#[derive(Clone)]
+enum ListRow {
+    Group {
+        file_type: String,
+        count: usize,
+        collapsed: bool,
+    },
+    File(usize, FileEntry),
+}
+
+impl ListRow {
+    fn key(&self) -> String {
+        match self {
+            // Rebuild the header when its count or folding changes
+            ListRow::Group {
+                file_type,
+                count,
+                collapsed,
+            } => format!("\0{}\0{}\0{}", file_type, count, collapsed),
+            ListRow::File(_, file) => file.path.clone(),
+        }
+    }
+}
+
+/// Rows to display, with a header before each type's files when grouping
+fn list_rows(state: &BrowserState) -> ImVector<ListRow> {
+    let files = state.files.get();
+    if !state.group_by_type.get() {
+        return files
+            .into_iter()
+            .enumerate()
+            .map(|(i, file)| ListRow::File(i, file))
+            .collect();
+    }
+
+    // Files are sorted by type and collapsed sections have none
+    let collapsed = state.collapsed_types.get();
+    let mut files = files.into_iter().enumerate().peekable();
+    let mut rows = ImVector::new();
+    for (file_type, count) in state.type_groups.get() {
+        rows.push_back(ListRow::Group {
+            collapsed: collapsed.contains(&file_type),
+            file_type: file_type.clone(),
+            count,
+        });
+        while let Some((i, file)) = files.next_if(|(_, f)| f.file_type == file_type) {
+            rows.push_back(ListRow::File(i, file));
+        }
+    }
+    rows
+}
+
 /// Truncate filename with middle ellipsis like macOS Finder
 /// e.g., "VeryLongFileName.extension" -> "VeryLong...ension"
 fn truncate_middle(name: &str, max_chars: usize) -> String {
@@ -51,15 +110,18 @@ fn truncate_middle(name: &str, max_chars: usize) -> String {
     format!("{}...{}", prefix, suffix)
 }
 use super::context_menu::show_file_context_menu;
-use super::operations::{open_file_or_folder_filtered, perform_rename, select_file, sort_files};
+use super::operations::{
+    SelectMode, is_row_selected, load_pak_directory, open_file_or_folder_filtered, perform_rename,
+    select_row, sort_files, toggle_type_group,
+};
 
 pub fn file_list(
     state: BrowserState,
     editor_tabs_state: EditorTabsState,
     active_tab: RwSignal<usize>,
+    config_state: ConfigState,
 ) -> impl IntoView {
-    let files = state.files;
-    let selected = state.selected_index;
+    let selected = state.selected_indices;
     let state_select = state.clone();
     let sort_column = state.sort_column;
     let sort_ascending = state.sort_ascending;
@@ -74,18 +136,28 @@ pub fn file_list(
     let state_keyboard_up = state.clone();
     let state_keyboard_enter = state.clone();
     let editor_keyboard = editor_tabs_state.clone();
+    let state_recent = state.clone();
 
     dyn_container(
         move || current_path.get().is_some(),
         move |has_folder| {
             if !has_folder {
-                // Placeholder when no folder is opened
+                // Placeholder when no folder is opened, with recent PAKs
+                let state_recent = state_recent.clone();
+                let config_pin = config_state.clone();
                 v_stack((
                     label(|| "📁").style(|s| s.font_size(64.0)),
                     label(|| "Select a folder to browse")
-                        .style(|s| s.font_size(16.0).color(Color::rgb8(120, 120, 120))),
+                        .style(|s| s.font_size(16.0).color(colors().text_muted)),
                     label(|| "Click \"Browse\" to open a folder")
-                        .style(|s| s.font_size(13.0).color(Color::rgb8(160, 160, 160))),
+                        .style(|s| s.font_size(13.0).color(colors().text_muted)),
+                    recent_list(
+                        "Recent PAKs",
+                        config_state.pinned_paks,
+                        config_state.recent_paks,
+                        move |path| load_pak_directory(&path, "", state_recent.clone()),
+                        move |path| config_pin.toggle_pinned_pak(&path),
+                    ),
                 ))
                 .style(|s| {
                     s.width_full()
@@ -93,12 +165,11 @@ pub fn file_list(
                         .items_center()
                         .justify_center()
                         .gap(8.0)
-                        .background(Color::WHITE)
+                        .background(colors().bg_base)
                 })
                 .into_any()
             } else {
                 file_list_content(
-                    files,
                     selected,
                     sort_column,
                     sort_ascending,
@@ -123,13 +194,12 @@ pub fn file_list(
         s.width(file_list_width.get())
             .flex_shrink(0.0) // Don't shrink below specified width
             .min_height(0.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
     })
 }
 
 fn file_list_content(
-    files: RwSignal<Vec<FileEntry>>,
-    selected: RwSignal<Option<usize>>,
+    selected: RwSignal<BTreeSet<usize>>,
     sort_column: RwSignal<SortColumn>,
     sort_ascending: RwSignal<bool>,
     state_select: BrowserState,
@@ -145,6 +215,9 @@ fn file_list_content(
     active_tab: RwSignal<usize>,
     file_list_width: RwSignal<f64>,
 ) -> impl IntoView {
+    let state_path = state_name.clone();
+    let state_rows = state_select.clone();
+
     v_stack((
         // Column headers
         h_stack((
@@ -155,6 +228,14 @@ fn file_list_content(
                 sort_ascending,
                 state_name,
             ),
+            // Sorts by full path; shown in the name column
+            sortable_header(
+                "Path",
+                SortColumn::Path,
+                sort_column,
+                sort_ascending,
+                state_path,
+            ),
             sortable_header(
                 "Type",
                 SortColumn::Type,
@@ -184,39 +265,52 @@ fn file_list_content(
             s.width_full()
                 .padding(8.0)
                 .gap(8.0)
-                .background(Color::rgb8(240, 240, 240))
+                .background(colors().bg_elevated)
                 .border_bottom(1.0)
-                .border_color(Color::rgb8(200, 200, 200))
+                .border_color(colors().border_strong)
         }),
         // File rows with virtual scroll - only renders visible items
         scroll(
             virtual_list(
                 VirtualDirection::Vertical,
                 VirtualItemSize::Fixed(Box::new(|| FILE_ROW_HEIGHT)),
-                move || files.get().into_iter().collect::<ImVector<_>>(),
-                |file| file.path.clone(),
-                move |file| {
+                move || list_rows(&state_rows),
+                |row| row.key(),
+                move |row| {
+                    let (idx, file) = match row {
+                        ListRow::Group {
+                            file_type,
+                            count,
+                            collapsed,
+                        } => {
+                            return group_header(file_type, count, collapsed, state_select.clone())
+                                .into_any();
+                        }
+                        ListRow::File(i, file) => (Some(i), file),
+                    };
                     let state_row = state_select.clone();
                     let state_dbl = state_select.clone();
                     let state_ctx = state_select.clone();
                     let editor_for_open = editor_tabs_state.clone();
                     let editor_for_ctx = editor_tabs_state.clone();
-                    let file_path = file.path.clone();
-                    let file_for_select = file.clone();
                     let file_for_open = file.clone();
                     let file_for_ctx = file.clone();
-                    let idx = files.get().iter().position(|f| f.path == file_path);
 
                     file_row(file, selected, idx, state_row.clone(), file_list_width)
                         // Stop PointerDown propagation to prevent scroll container from
                         // resetting scroll position when clicking on rows
                         .on_event_stop(EventListener::PointerDown, |_| {})
-                        .on_click_stop(move |_| {
+                        .on_click_stop(move |e| {
                             // Cancel any ongoing rename when clicking elsewhere
                             state_row.renaming_path.set(None);
                             if let Some(i) = idx {
-                                state_row.selected_index.set(Some(i));
-                                select_file(&file_for_select, state_row.clone());
+                                let mode = match e {
+                                    Event::PointerUp(pe) => {
+                                        SelectMode::from_modifiers(pe.modifiers)
+                                    }
+                                    _ => SelectMode::Single,
+                                };
+                                select_row(i, mode, state_row.clone());
                             }
                         })
                         .on_double_click(move |_| {
@@ -230,10 +324,11 @@ fn file_list_content(
                             EventPropagation::Stop
                         })
                         .on_secondary_click(move |_| {
-                            // Select the file first
+                            // Select the file first, keeping a multi-selection it belongs to
                             if let Some(i) = idx {
-                                state_ctx.selected_index.set(Some(i));
-                                select_file(&file_for_ctx, state_ctx.clone());
+                                if !is_row_selected(i, &state_ctx) {
+                                    select_row(i, SelectMode::Single, state_ctx.clone());
+                                }
                             }
                             // Show context menu
                             show_file_context_menu(
@@ -244,6 +339,7 @@ fn file_list_content(
                             );
                             EventPropagation::Stop
                         })
+                        .into_any()
                 },
             )
             .style(|s| s.width_full().flex_col()),
@@ -259,13 +355,13 @@ fn file_list_content(
         s.width_full()
             .height_full()
             .min_height(0.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
     })
     .keyboard_navigable()
     .on_key_down(
         Key::Named(NamedKey::ArrowDown),
         |_| true,
-        move |_| {
+        move |e| {
             let files_list = state_keyboard_down.files.get();
             let current = state_keyboard_down.selected_index.get();
             let new_idx = match current {
@@ -274,11 +370,8 @@ fn file_list_content(
                 _ => current,
             };
             if new_idx != current {
-                state_keyboard_down.selected_index.set(new_idx);
                 if let Some(i) = new_idx {
-                    if let Some(file) = files_list.get(i) {
-                        select_file(file, state_keyboard_down.clone());
-                    }
+                    select_row(i, arrow_select_mode(e), state_keyboard_down.clone());
                 }
             }
         },
@@ -286,7 +379,7 @@ fn file_list_content(
     .on_key_down(
         Key::Named(NamedKey::ArrowUp),
         |_| true,
-        move |_| {
+        move |e| {
             let files_list = state_keyboard_up.files.get();
             let current = state_keyboard_up.selected_index.get();
             let new_idx = match current {
@@ -295,11 +388,8 @@ fn file_list_content(
                 _ => current,
             };
             if new_idx != current {
-                state_keyboard_up.selected_index.set(new_idx);
                 if let Some(i) = new_idx {
-                    if let Some(file) = files_list.get(i) {
-                        select_file(file, state_keyboard_up.clone());
-                    }
+                    select_row(i, arrow_select_mode(e), state_keyboard_up.clone());
                 }
             }
         },
@@ -323,15 +413,48 @@ fn file_list_content(
     )
 }
 
+/// Shift+arrow extends the selection; a plain arrow moves it
+fn arrow_select_mode(e: &Event) -> SelectMode {
+    match e {
+        Event::KeyDown(key_event) if key_event.modifiers.contains(Modifiers::SHIFT) => {
+            SelectMode::Range
+        }
+        _ => SelectMode::Single,
+    }
+}
+
+/// Where to read a file's thumbnail from: its PAK, or its folder for loose files
+fn thumbnail_source(file: &FileEntry) -> Option<IndexedFile> {
+    if file.is_dir {
+        return None;
+    }
+    let (pak_file, path) = match &file.pak_path {
+        Some(pak) => (PathBuf::from(pak), file.path.clone()),
+        None => (
+            Path::new(&file.path).parent()?.to_path_buf(),
+            file.name.clone(),
+        ),
+    };
+    Some(IndexedFile {
+        name: file.name.clone(),
+        path,
+        pak_file,
+        declared_type: FileType::from_extension(&file.extension),
+        detected_type: None,
+        size: file.size,
+    })
+}
+
 fn file_row(
     file: FileEntry,
-    selected: RwSignal<Option<usize>>,
+    selected: RwSignal<BTreeSet<usize>>,
     idx: Option<usize>,
     state: BrowserState,
     list_width: RwSignal<f64>,
 ) -> impl IntoView {
-    let is_selected = move || selected.get() == idx;
+    let is_selected = move || idx.is_some_and(|i| selected.get().contains(&i));
     let icon = file.icon.clone();
+    let indexed_file = thumbnail_source(&file);
     let name = file.name.clone();
     let file_type = file.file_type.clone();
     let size = file.size_formatted.clone();
@@ -345,7 +468,7 @@ fn file_row(
     h_stack((
         // Icon + Name (with inline rename support)
         h_stack((
-            label(move || icon.clone()).style(|s| s.width(ICON_WIDTH).flex_shrink(0.0)),
+            thumbnail_cell(indexed_file, ICON_WIDTH as u32, icon),
             dyn_container(
                 move || {
                     let is_renaming = renaming_path.get().as_ref() == Some(&file_path);
@@ -366,9 +489,9 @@ fn file_row(
                                         .min_width(50.0)
                                         .padding(2.0)
                                         .border(1.0)
-                                        .border_color(Color::rgb8(33, 150, 243))
+                                        .border_color(colors().accent)
                                         .border_radius(2.0)
-                                        .background(Color::WHITE)
+                                        .background(colors().bg_base)
                                 })
                                 .on_key_down(
                                     Key::Named(NamedKey::Enter),
@@ -424,21 +547,21 @@ fn file_row(
             s.width(TYPE_WIDTH)
                 .flex_shrink(0.0)
                 .font_size(12.0)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
         }),
         // Size
         label(move || size.clone()).style(|s| {
             s.width(SIZE_WIDTH)
                 .flex_shrink(0.0)
                 .font_size(12.0)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
         }),
         // Modified
         label(move || modified.clone()).style(|s| {
             s.width(MODIFIED_WIDTH)
                 .flex_shrink(0.0)
                 .font_size(12.0)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
         }),
     ))
     .style(move |s| {
@@ -449,17 +572,48 @@ fn file_row(
             .gap(8.0)
             .items_center()
             .border_bottom(1.0)
-            .border_color(Color::rgb8(245, 245, 245));
+            .border_color(colors().border);
 
         if is_selected() {
-            s.background(Color::rgb8(227, 242, 253))
+            s.background(colors().accent_bg)
         } else {
-            s.background(Color::WHITE)
-                .hover(|s| s.background(Color::rgb8(250, 250, 250)))
+            s.background(colors().bg_base)
+                .hover(|s| s.background(colors().bg_surface))
         }
     })
 }
 
+/// Clickable header of a type's section in group-by-type mode
+fn group_header(
+    file_type: String,
+    count: usize,
+    collapsed: bool,
+    state: BrowserState,
+) -> impl IntoView {
+    let arrow = if collapsed { "▶" } else { "▼" };
+    let title = format!("{} {}", arrow, file_type);
+
+    h_stack((
+        label(move || title.clone()).style(|s| s.font_weight(Weight::BOLD)),
+        label(move || format!("({})", count))
+            .style(|s| s.font_size(12.0).color(colors().text_secondary)),
+    ))
+    .style(|s| {
+        s.width_full()
+            .height(FILE_ROW_HEIGHT)
+            .padding(8.0)
+            .gap(8.0)
+            .items_center()
+            .cursor(floem::style::CursorStyle::Pointer)
+            .background(colors().bg_elevated)
+            .border_bottom(1.0)
+            .border_color(colors().border)
+            .hover(|s| s.background(colors().bg_hover))
+    })
+    .on_event_stop(EventListener::PointerDown, |_| {})
+    .on_click_stop(move |_| toggle_type_group(&file_type, state.clone()))
+}
+
 fn sortable_header(
     name: &'static str,
     column: SortColumn,
@@ -483,7 +637,7 @@ fn sortable_header(
     .style(|s| s.font_weight(Weight::BOLD)),))
     .style(move |s| {
         s.cursor(floem::style::CursorStyle::Pointer)
-            .hover(|s| s.background(Color::rgb8(230, 230, 230)))
+            .hover(|s| s.background(colors().bg_hover))
             .padding_vert(2.0)
             .padding_horiz(4.0)
             .border_radius(4.0)
@@ -8,13 +8,14 @@ mod gr2_dialog;
 mod operations;
 mod preview;
 mod preview_3d;
-mod raw_img;
+pub(crate) mod raw_img;
 mod status_bar;
 mod toolbar;
 
 use floem::prelude::*;
 use floem::style::Position;
 
+use crate::gui::shared::{colors, progress_overlay, results_section};
 use crate::gui::state::{AppState, BrowserState, ConfigState, EditorTabsState};
 use file_list::file_list;
 use gr2_dialog::gr2_conversion_dialog;
@@ -23,8 +24,9 @@ use status_bar::browser_status_bar;
 use toolbar::browser_toolbar;
 
 pub use operations::cleanup_temp_files;
+pub use operations::load_pak_directory;
 pub use operations::open_folder_dialog;
-pub use preview_3d::kill_preview_process;
+pub use preview_3d::{kill_preview_process, open_preview_window};
 
 pub fn browser_tab(
     _app_state: AppState,
@@ -35,10 +37,18 @@ pub fn browser_tab(
 ) -> impl IntoView {
     let browser_state_overlay = browser_state.clone();
     let browser_state_dialog = browser_state.clone();
+    let browser_state_progress = browser_state.clone();
+    let config_state_recent = config_state.clone();
 
     let main_content = v_stack((
         browser_toolbar(browser_state.clone()),
-        browser_content(browser_state.clone(), editor_tabs_state, active_tab),
+        browser_content(
+            browser_state.clone(),
+            editor_tabs_state,
+            active_tab,
+            config_state_recent,
+        ),
+        extraction_log(browser_state.clone()),
         browser_status_bar(browser_state),
     ))
     .style(|s| {
@@ -48,10 +58,11 @@ pub fn browser_tab(
             .min_height(0.0) // Allow shrinking for scroll to work
     });
 
-    // Stack main content with loading overlay and GR2 dialog
+    // Stack main content with loading/progress overlays and GR2 dialog
     (
         main_content,
         loading_overlay(browser_state_overlay),
+        progress_overlay(browser_state_progress),
         gr2_conversion_dialog(browser_state_dialog, config_state),
     )
         .style(|s| s.width_full().height_full().position(Position::Relative))
@@ -61,6 +72,7 @@ fn browser_content(
     state: BrowserState,
     editor_tabs_state: EditorTabsState,
     active_tab: RwSignal<usize>,
+    config_state: ConfigState,
 ) -> impl IntoView {
     let file_list_width = state.file_list_width;
 
@@ -71,7 +83,7 @@ fn browser_content(
 
     h_stack((
         // File list (left side) - fixed width from signal
-        file_list(state.clone(), editor_tabs_state, active_tab),
+        file_list(state.clone(), editor_tabs_state, active_tab, config_state),
         // Draggable divider
         divider_handle(is_dragging, drag_offset, file_list_width),
         // Preview panel (right side) - takes remaining space
@@ -103,6 +115,19 @@ fn browser_content(
     })
 }
 
+/// Results of the last extraction, hidden until something has been extracted
+fn extraction_log(state: BrowserState) -> impl IntoView {
+    let results_log = state.results_log;
+
+    container(results_section(state)).style(move |s| {
+        if results_log.get().is_empty() {
+            s.display(floem::style::Display::None)
+        } else {
+            s.width_full().height(180.0).flex_shrink(0.0).padding(8.0)
+        }
+    })
+}
+
 /// Divider handle that initiates drag
 fn divider_handle(
     is_dragging: RwSignal<bool>,
@@ -116,11 +141,11 @@ fn divider_handle(
                 .height_full()
                 .cursor(floem::style::CursorStyle::ColResize)
                 .background(if dragging {
-                    Color::rgb8(33, 150, 243)
+                    colors().accent
                 } else {
-                    Color::rgb8(200, 200, 200)
+                    colors().border
                 })
-                .hover(|s| s.background(Color::rgb8(150, 150, 150)))
+                .hover(|s| s.background(colors().border_strong))
         })
         .on_event_stop(floem::event::EventListener::PointerDown, move |e| {
             if let floem::event::Event::PointerDown(pe) = e {
@@ -153,21 +178,21 @@ fn loading_overlay(state: BrowserState) -> impl IntoView {
                         container(container(empty()).style(|s| {
                             s.height_full()
                                 .width_pct(30.0)
-                                .background(Color::rgb8(76, 175, 80))
+                                .background(colors().success)
                                 .border_radius(4.0)
                         }))
                         .style(|s| {
                             s.width_full()
                                 .height(8.0)
-                                .background(Color::rgb8(220, 220, 220))
+                                .background(colors().bg_selected)
                                 .border_radius(4.0)
                         }),
                     ))
                     .style(|s| {
                         s.padding(24.0)
-                            .background(Color::WHITE)
+                            .background(colors().bg_base)
                             .border(1.0)
-                            .border_color(Color::rgb8(200, 200, 200))
+                            .border_color(colors().border_strong)
                             .border_radius(8.0)
                             .width(400.0)
                     }),
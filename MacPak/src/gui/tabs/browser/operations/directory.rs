@@ -1,12 +1,20 @@
 //! Directory loading, navigation, filtering, and sorting
 
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+use std::fs::Metadata;
 use std::path::Path;
 use std::time::UNIX_EPOCH;
 
+use floem::ext_event::create_ext_action;
 use floem::prelude::*;
+use floem_reactive::Scope;
 
 use crate::gui::state::{BrowserState, FileEntry, SortColumn};
+use crate::index::FileIndex;
 
+use super::pak::{load_pak_directory, pak_dir};
+use super::selection::clear_selection;
 use super::utils::format_size;
 
 pub fn open_folder_dialog(state: BrowserState) {
@@ -19,6 +27,20 @@ pub fn open_folder_dialog(state: BrowserState) {
 }
 
 pub fn go_up(state: BrowserState) {
+    if let Some(pak_path) = state.pak_path.get() {
+        // Inside a PAK: go up one folder, or back to the PAK's directory
+        match pak_dir(&state).rsplit_once('/') {
+            Some((parent, _)) => load_pak_directory(&pak_path, parent, state),
+            None if !pak_dir(&state).is_empty() => load_pak_directory(&pak_path, "", state),
+            None => {
+                if let Some(parent) = Path::new(&pak_path).parent() {
+                    load_directory(&parent.to_string_lossy(), state);
+                }
+            }
+        }
+        return;
+    }
+
     if let Some(current) = state.current_path.get() {
         if let Some(parent) = Path::new(&current).parent() {
             let parent_str = parent.to_string_lossy().to_string();
@@ -28,11 +50,98 @@ pub fn go_up(state: BrowserState) {
 }
 
 pub fn refresh(state: BrowserState) {
+    if let Some(pak_path) = state.pak_path.get() {
+        let dir = pak_dir(&state);
+        load_pak_directory(&pak_path, &dir, state);
+        return;
+    }
+
     if let Some(path) = state.current_path.get() {
-        load_directory(&path, state);
+        if !apply_index_refresh(&path, &state) {
+            load_directory(&path, state);
+        }
     }
 }
 
+/// Update the listing from the folder's index instead of reloading it
+///
+/// Returns `false` if there is no index of `dir` yet or a subfolder was
+/// added or removed, in which case the folder has to be reloaded.
+fn apply_index_refresh(dir: &str, state: &BrowserState) -> bool {
+    let Ok(mut guard) = state.file_index.lock() else {
+        return false;
+    };
+    let Some(index) = guard
+        .as_mut()
+        .filter(|index| index.root() == Some(Path::new(dir)))
+    else {
+        return false;
+    };
+
+    let subdirs = index.subdirs("").to_vec();
+    let Ok(delta) = index.refresh() else {
+        return false;
+    };
+    if index.subdirs("") != subdirs.as_slice() {
+        return false;
+    }
+    drop(guard);
+    if delta.is_empty() {
+        return true;
+    }
+
+    let root = Path::new(dir);
+    let stale: HashSet<String> = delta
+        .removed
+        .iter()
+        .chain(&delta.modified)
+        .chain(delta.renamed.iter().map(|(old, _)| old))
+        .map(|rel| root.join(rel).to_string_lossy().to_string())
+        .collect();
+    let fresh: Vec<FileEntry> = delta
+        .added
+        .iter()
+        .chain(&delta.modified)
+        .chain(delta.renamed.iter().map(|(_, new)| new))
+        .filter_map(|rel| {
+            let path = root.join(rel);
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some(file_entry(&path, &metadata))
+        })
+        .collect();
+
+    state.all_files.update(|files| {
+        files.retain(|file| !stale.contains(&file.path));
+        files.extend(fresh);
+    });
+    let (file_count, total_size) = state.all_files.with_untracked(|files| {
+        files
+            .iter()
+            .filter(|file| !file.is_dir)
+            .fold((0, 0), |(count, size), file| (count + 1, size + file.size))
+    });
+    state.file_count.set(file_count);
+    state.total_size.set(format_size(total_size));
+    sort_files(state.clone());
+    true
+}
+
+/// Index `dir` on a worker thread, for later refreshes
+fn start_indexing(dir: &str, state: &BrowserState) {
+    let file_index = state.file_index.clone();
+    if let Ok(mut guard) = file_index.lock() {
+        *guard = None;
+    }
+    let dir = dir.to_string();
+    rayon::spawn(move || {
+        let index = FileIndex::open_shallow(&dir).ok();
+        if let Ok(mut guard) = file_index.lock() {
+            // Another folder may have been opened meanwhile; refresh checks the root
+            *guard = index;
+        }
+    });
+}
+
 pub fn load_directory(dir_path: &str, state: BrowserState) {
     let path = Path::new(dir_path);
     if !path.exists() || !path.is_dir() {
@@ -49,96 +158,125 @@ pub fn load_directory(dir_path: &str, state: BrowserState) {
 
     if let Ok(dir_entries) = std::fs::read_dir(path) {
         for entry in dir_entries.flatten() {
+            // Skip hidden files
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
             if let Ok(metadata) = entry.metadata() {
-                let name = entry.file_name().to_string_lossy().to_string();
-
-                // Skip hidden files
-                if name.starts_with('.') {
-                    continue;
-                }
-
-                let full_path = entry.path().to_string_lossy().to_string();
-                let is_dir = metadata.is_dir();
-
-                let (file_type, icon) = if is_dir {
+                let file = file_entry(&entry.path(), &metadata);
+                if file.is_dir {
                     folder_count += 1;
-                    ("Folder".to_string(), "📁".to_string())
                 } else {
                     file_count += 1;
-                    total_size += metadata.len();
-                    let ext = Path::new(&name)
-                        .extension()
-                        .and_then(|e| e.to_str())
-                        .unwrap_or("")
-                        .to_uppercase();
-
-                    let icon = match ext.as_str() {
-                        "PAK" => "📦",
-                        "LSF" | "LSX" | "LSJ" | "LSFX" | "LSBC" | "LSBS" => "📖",
-                        "DDS" | "PNG" | "JPG" | "JPEG" => "🖼️",
-                        "GR2" | "DAE" | "glTF" | "GLB" => "🎨",
-                        "WEM" | "WAV" => "🔊",
-                        "LUA" | "OSI" | "gameScript" | "itemScript" => "📜",
-                        "XML" | "TXT" | "KHN" | "TMPL" => "📝",
-                        "LOCA" => "🌐",
-                        "SHD" | "BSHD" | "METAL" => "✏️",
-                        "DAT" | "DATA" | "PATCH" | "CLC" | "CLM" | "CLN" => "🖥️",
-                        "ANC" | "ANM" | "ANN" => "🪄",
-                        _ => "📄",
-                    };
-                    (ext, icon.to_string())
-                };
-
-                let size = if is_dir { 0 } else { metadata.len() };
-                let size_formatted = if is_dir {
-                    "--".to_string()
-                } else {
-                    format_size(size)
-                };
-
-                let modified = metadata
-                    .modified()
-                    .ok()
-                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-                    .map(|d| {
-                        let secs = d.as_secs();
-                        let dt =
-                            chrono::DateTime::from_timestamp(secs as i64, 0).unwrap_or_default();
-                        dt.format("%Y-%m-%d %H:%M").to_string()
-                    })
-                    .unwrap_or_else(|| "--".to_string());
-
-                let extension = Path::new(&name)
-                    .extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                entries.push(FileEntry {
-                    name,
-                    path: full_path,
-                    is_dir,
-                    size,
-                    size_formatted,
-                    extension,
-                    file_type,
-                    modified,
-                    icon,
-                });
+                    total_size += file.size;
+                }
+                entries.push(file);
             }
         }
     }
 
-    // Store all files for filtering
-    state.all_files.set(entries.clone());
-    state.files.set(entries);
+    state.pak_path.set(None);
+    start_indexing(dir_path, &state);
+    set_entries(entries, file_count, folder_count, total_size, state);
+}
 
-    // Apply current sort settings
-    sort_files(state.clone());
+/// Listing row for a file or folder on disk
+fn file_entry(path: &Path, metadata: &Metadata) -> FileEntry {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let full_path = path.to_string_lossy().to_string();
+    let is_dir = metadata.is_dir();
+
+    let (file_type, icon) = if is_dir {
+        ("Folder".to_string(), "📁".to_string())
+    } else {
+        let ext = Path::new(&name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_uppercase();
+
+        let icon = file_icon(&ext);
+        (ext, icon.to_string())
+    };
+
+    let size = if is_dir { 0 } else { metadata.len() };
+    let size_formatted = if is_dir {
+        "--".to_string()
+    } else {
+        format_size(size)
+    };
+
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| {
+            let secs = d.as_secs();
+            let dt = chrono::DateTime::from_timestamp(secs as i64, 0).unwrap_or_default();
+            dt.format("%Y-%m-%d %H:%M").to_string()
+        })
+        .unwrap_or_else(|| "--".to_string());
+
+    let extension = Path::new(&name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    FileEntry {
+        name,
+        path: full_path,
+        is_dir,
+        size,
+        size_formatted,
+        extension,
+        file_type,
+        modified,
+        icon,
+        pak_path: None,
+    }
+}
+
+/// Icon for an uppercase file extension
+pub(super) fn file_icon(ext: &str) -> &'static str {
+    match ext {
+        "PAK" => "📦",
+        "LSF" | "LSX" | "LSJ" | "LSFX" | "LSBC" | "LSBS" => "📖",
+        "DDS" | "PNG" | "JPG" | "JPEG" => "🖼️",
+        "GR2" | "DAE" | "glTF" | "GLB" => "🎨",
+        "WEM" | "WAV" => "🔊",
+        "LUA" | "OSI" | "gameScript" | "itemScript" => "📜",
+        "XML" | "TXT" | "KHN" | "TMPL" => "📝",
+        "LOCA" => "🌐",
+        "SHD" | "BSHD" | "METAL" => "✏️",
+        "DAT" | "DATA" | "PATCH" | "CLC" | "CLM" | "CLN" => "🖥️",
+        "ANC" | "ANM" | "ANN" => "🪄",
+        _ => "📄",
+    }
+}
 
+/// Listings at least this long are sorted on a worker thread
+const BACKGROUND_SORT_THRESHOLD: usize = 20_000;
+
+/// Show a freshly loaded listing, resetting filters, counts, and preview
+pub(super) fn set_entries(
+    entries: Vec<FileEntry>,
+    file_count: usize,
+    folder_count: usize,
+    total_size: u64,
+    state: BrowserState,
+) {
     // Reset filters
     state.search_query.set(String::new());
     state.type_filter.set("All".to_string());
+    state.collapsed_types.set(BTreeSet::new());
+
+    // Store all files for filtering, then sort and filter them
+    state.all_files.set(entries);
+    sort_files(state.clone());
 
     // Update counts
     state.file_count.set(file_count);
@@ -146,70 +284,197 @@ pub fn load_directory(dir_path: &str, state: BrowserState) {
     state.total_size.set(format_size(total_size));
 
     // Clear selection and preview
-    state.selected_index.set(None);
+    clear_selection(&state);
     state.preview_name.set(String::new());
     state.preview_info.set(String::new());
     state.preview_content.set(String::new());
 }
 
+/// Rebuild the visible rows from the sorted listing
+///
+/// In group-by-type mode this also counts each type's section and leaves
+/// out the rows of collapsed sections.
 pub fn apply_filters(state: BrowserState) {
     let all_files = state.all_files.get();
     let search = state.search_query.get().to_lowercase();
     let type_filter = state.type_filter.get();
+    let grouped = state.group_by_type.get();
+    let collapsed = state.collapsed_types.get();
 
+    let mut groups: Vec<(String, usize)> = Vec::new();
     let filtered: Vec<FileEntry> = all_files
         .iter()
         .filter(|file| {
-            // Always show directories (but filter by name)
-            if file.is_dir {
-                if !search.is_empty() && !file.name.to_lowercase().contains(&search) {
-                    return false;
-                }
-                return true;
-            }
-
             // Search text filter
             if !search.is_empty() && !file.name.to_lowercase().contains(&search) {
                 return false;
             }
 
-            // Type filter
-            if type_filter != "All" && file.file_type != type_filter {
+            // Type filter (directories are always shown)
+            if !file.is_dir && type_filter != "All" && file.file_type != type_filter {
                 return false;
             }
 
-            true
+            if !grouped {
+                return true;
+            }
+            // Sorted by type first, so each section is one run
+            match groups.last_mut() {
+                Some((file_type, count)) if *file_type == file.file_type => *count += 1,
+                _ => groups.push((file.file_type.clone(), 1)),
+            }
+            !collapsed.contains(&file.file_type)
         })
         .cloned()
         .collect();
 
+    state.type_groups.set(groups);
     state.files.set(filtered);
-    sort_files(state.clone());
-    state.selected_index.set(None);
+    // Row indices changed, so the old selection no longer applies
+    clear_selection(&state);
+}
+
+/// Fold or unfold one type's section in group-by-type mode
+pub fn toggle_type_group(file_type: &str, state: BrowserState) {
+    state.collapsed_types.update(|collapsed| {
+        if !collapsed.remove(file_type) {
+            collapsed.insert(file_type.to_string());
+        }
+    });
+    apply_filters(state);
 }
 
+/// Sort the full listing by the current column, then re-apply the filters
+///
+/// Large PAK folders are sorted on a worker thread behind the loading
+/// overlay.
 pub fn sort_files(state: BrowserState) {
-    let sort_col = state.sort_column.get();
+    let column = state.sort_column.get();
     let ascending = state.sort_ascending.get();
-    let mut files = state.files.get();
+    let grouped = state.group_by_type.get();
+    let mut files = state.all_files.get();
 
+    if files.len() < BACKGROUND_SORT_THRESHOLD {
+        sort_entries(&mut files, column, ascending, grouped);
+        state.all_files.set(files);
+        apply_filters(state);
+        return;
+    }
+
+    state.is_loading.set(true);
+    state
+        .loading_message
+        .set(format!("Sorting {} entries...", files.len()));
+
+    let send = create_ext_action(Scope::new(), move |files: Vec<FileEntry>| {
+        state.is_loading.set(false);
+        state.all_files.set(files);
+        apply_filters(state);
+    });
+
+    rayon::spawn(move || {
+        sort_entries(&mut files, column, ascending, grouped);
+        send(files);
+    });
+}
+
+/// Stable sort of a listing: folders first, then by type when grouping,
+/// then by `column`
+pub(super) fn sort_entries(
+    files: &mut [FileEntry],
+    column: SortColumn,
+    ascending: bool,
+    group_by_type: bool,
+) {
     files.sort_by(|a, b| {
         // Always put directories first
-        match (a.is_dir, b.is_dir) {
-            (true, false) => return std::cmp::Ordering::Less,
-            (false, true) => return std::cmp::Ordering::Greater,
-            _ => {}
-        }
+        let dirs = b.is_dir.cmp(&a.is_dir);
+        let groups = if group_by_type {
+            a.file_type.cmp(&b.file_type)
+        } else {
+            Ordering::Equal
+        };
 
-        let cmp = match sort_col {
-            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        let cmp = match column {
+            SortColumn::Name => cmp_ignore_case(&a.name, &b.name),
+            SortColumn::Path => cmp_ignore_case(&a.path, &b.path),
             SortColumn::Type => a.file_type.cmp(&b.file_type),
             SortColumn::Size => a.size.cmp(&b.size),
             SortColumn::Modified => a.modified.cmp(&b.modified),
         };
 
-        if ascending { cmp } else { cmp.reverse() }
+        dirs.then(groups)
+            .then(if ascending { cmp } else { cmp.reverse() })
     });
+}
 
-    state.files.set(files);
+/// Case-insensitive comparison without allocating lowercase copies
+fn cmp_ignore_case(a: &str, b: &str) -> Ordering {
+    a.bytes()
+        .map(|c| c.to_ascii_lowercase())
+        .cmp(b.bytes().map(|c| c.to_ascii_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, size: u64) -> FileEntry {
+        let name = path.rsplit('/').next().unwrap_or(path).to_string();
+        let is_dir = !name.contains('.');
+        let file_type = match name.rsplit_once('.') {
+            Some((_, ext)) => ext.to_uppercase(),
+            None => "Folder".to_string(),
+        };
+        FileEntry {
+            name,
+            path: path.to_string(),
+            is_dir,
+            size,
+            size_formatted: format_size(size),
+            extension: String::new(),
+            file_type,
+            modified: "--".to_string(),
+            icon: String::new(),
+            pak_path: None,
+        }
+    }
+
+    fn paths(files: &[FileEntry]) -> Vec<&str> {
+        files.iter().map(|f| f.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_entries() {
+        let mut files = vec![
+            entry("b/x.lsf", 10),
+            entry("a/Y.lsx", 30),
+            entry("Folder", 0),
+            entry("a/x.lsf", 10),
+            entry("c/z.dds", 20),
+        ];
+
+        sort_entries(&mut files, SortColumn::Name, true, false);
+        assert_eq!(
+            paths(&files),
+            ["Folder", "b/x.lsf", "a/x.lsf", "a/Y.lsx", "c/z.dds"]
+        );
+
+        // Descending keeps equal sizes in their previous order
+        sort_entries(&mut files, SortColumn::Size, false, false);
+        assert_eq!(
+            paths(&files),
+            ["Folder", "a/Y.lsx", "c/z.dds", "b/x.lsf", "a/x.lsf"]
+        );
+
+        sort_entries(&mut files, SortColumn::Path, true, true);
+        assert_eq!(
+            paths(&files),
+            ["Folder", "c/z.dds", "a/x.lsf", "b/x.lsf", "a/Y.lsx"]
+        );
+
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
 }
@@ -0,0 +1,156 @@
+//! Extracting selected entries or folders from the PAK being browsed
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+
+use floem::ext_event::create_ext_action;
+use floem::prelude::*;
+use floem_reactive::Scope;
+use maclarian::pak::{PakOperations, PakProgress, PakReaderCache};
+
+use crate::gui::shared::ResultLevel;
+use crate::gui::state::browser::get_shared_progress;
+use crate::gui::state::{BrowserState, configured_output_dir};
+
+use super::pak::pak_dir;
+use super::selection::selected_entries;
+
+/// Extract the selected PAK entries. Selected folders include everything under them.
+pub fn extract_selected(state: BrowserState) {
+    let entries = selected_entries(&state);
+    if entries.is_empty() {
+        return;
+    }
+
+    let (folders, files): (Vec<_>, Vec<_>) = entries.into_iter().partition(|e| e.is_dir);
+    start_extraction(
+        files.into_iter().map(|e| e.path).collect(),
+        folders.into_iter().map(|e| e.path).collect(),
+        state,
+    );
+}
+
+/// Extract a folder of the PAK being browsed (`None` for the folder being shown)
+pub fn extract_folder(folder: Option<String>, state: BrowserState) {
+    let folder = folder.unwrap_or_else(|| pak_dir(&state));
+    start_extraction(Vec::new(), vec![folder], state);
+}
+
+/// Ask for a destination and extract in the background.
+///
+/// Files keep their internal directory structure under the destination.
+fn start_extraction(files: Vec<String>, folders: Vec<String>, state: BrowserState) {
+    let Some(pak_path) = state.pak_path.get_untracked() else {
+        return;
+    };
+
+    let dialog = rfd::FileDialog::new()
+        .set_title("Select Extraction Destination")
+        .set_directory(configured_output_dir().unwrap_or_else(|| {
+            Path::new(&pak_path)
+                .parent()
+                .unwrap_or(Path::new("/"))
+                .to_path_buf()
+        }));
+    let Some(dest) = dialog.pick_folder() else {
+        return;
+    };
+
+    state.clear_results();
+    state.add_result(&format!("Extracting to {}...", dest.display()));
+    get_shared_progress().reset();
+    state.is_extracting.set(true);
+
+    let cache = state.pak_cache.clone();
+    let send = create_ext_action(Scope::new(), move |results: Vec<String>| {
+        state.is_extracting.set(false);
+        let failed = results
+            .iter()
+            .any(|msg| ResultLevel::classify(msg) == ResultLevel::Error);
+        let extracted = results
+            .iter()
+            .filter(|msg| msg.starts_with("Extracted"))
+            .count();
+        state.status_message.set(if failed {
+            "Extraction failed - see results log".to_string()
+        } else {
+            format!("Extracted {} files", extracted)
+        });
+        state.add_results_batch(results);
+    });
+
+    thread::spawn(move || {
+        send(extract_entries(
+            Path::new(&pak_path),
+            &dest,
+            files,
+            &folders,
+            &cache,
+        ));
+    });
+}
+
+/// Resolve folders to their files and extract everything (runs in background thread).
+/// Returns one results-log line per file.
+fn extract_entries(
+    pak_path: &Path,
+    dest: &Path,
+    mut files: Vec<String>,
+    folders: &[String],
+    cache: &Mutex<PakReaderCache>,
+) -> Vec<String> {
+    let shared = get_shared_progress();
+
+    if !folders.is_empty() {
+        shared.update(0, 0, "Reading file table...");
+        let listing = match cache.lock() {
+            Ok(mut cache) => cache.list_files(pak_path).map_err(|e| e.to_string()),
+            Err(_) => Err("PAK cache is unavailable".to_string()),
+        };
+        match listing {
+            Ok(listing) => files.extend(
+                listing
+                    .into_iter()
+                    .map(|(path, _)| path)
+                    .filter(|path| folders.iter().any(|f| is_in_folder(path, f))),
+            ),
+            Err(e) => return vec![format!("Error: {}", e)],
+        }
+    }
+
+    files.sort();
+    files.dedup();
+    if files.is_empty() {
+        return vec!["Error: Nothing to extract".to_string()];
+    }
+
+    let progress = |p: &PakProgress| {
+        let description = p.current_file.as_deref().unwrap_or(p.phase.as_str());
+        shared.update(p.current, p.total, description);
+    };
+
+    match PakOperations::extract_files_with_progress(pak_path, dest, &files, &progress) {
+        Ok(()) => files
+            .iter()
+            .map(|path| format!("Extracted {}", path))
+            .collect(),
+        Err(maclarian::Error::PakExtractionPartialFailure {
+            total,
+            failed,
+            first_error,
+        }) => vec![format!(
+            "Failed: {} of {} files could not be extracted (first error: {})",
+            failed, total, first_error
+        )],
+        Err(e) => vec![format!("Error: {}", e)],
+    }
+}
+
+/// Whether a PAK path lies under `folder` (`""` is the PAK root)
+fn is_in_folder(path: &str, folder: &str) -> bool {
+    folder.is_empty()
+        || path
+            .strip_prefix(folder)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
@@ -4,11 +4,12 @@ use std::path::Path;
 
 use floem::prelude::*;
 
-use crate::gui::state::{BrowserState, EditorTabsState, FileEntry};
-use crate::gui::tabs::load_file_in_tab;
+use crate::gui::state::{BrowserState, EditorTabsState, FileEntry, PakSource};
+use crate::gui::tabs::{load_file_in_tab, open_pak_entry};
 use crate::gui::utils::show_file_error;
 
 use super::directory::{load_directory, refresh};
+use super::pak::load_pak_directory;
 use super::utils::is_text_file;
 
 /// Open file or folder, but only open text files in editor (not images, audio, etc.)
@@ -18,8 +19,25 @@ pub fn open_file_or_folder_filtered(
     editor_tabs_state: EditorTabsState,
     active_tab: floem::prelude::RwSignal<usize>,
 ) {
-    if file.is_dir {
+    if let Some(pak_path) = &file.pak_path {
+        // Entry inside a PAK: browse folders, open text files straight from the PAK
+        if file.is_dir {
+            load_pak_directory(pak_path, &file.path, state);
+        } else if is_text_file(&file.extension) {
+            open_pak_entry(
+                PakSource {
+                    pak_path: pak_path.clone(),
+                    internal_path: file.path.clone(),
+                },
+                state.pak_cache.clone(),
+                editor_tabs_state,
+            );
+            active_tab.set(1);
+        }
+    } else if file.is_dir {
         load_directory(&file.path, state);
+    } else if file.extension.eq_ignore_ascii_case("pak") {
+        load_pak_directory(&file.path, "", state);
     } else if is_text_file(&file.extension) {
         // Only open text files in Editor tab (opens in new tab or switches to existing)
         let path = Path::new(&file.path);
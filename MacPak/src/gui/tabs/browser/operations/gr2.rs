@@ -153,10 +153,13 @@ fn perform_gr2_conversion(
                 Some(std::path::PathBuf::from(game_data_path))
             },
             virtual_textures_path: None,
+            texture_roots: Vec::new(),
             keep_original_gr2: true,
             convert_to_png,
             // Keep DDS if "Extract textures DDS" is checked (even if also converting to PNG)
             keep_original_dds: true,
+            record_meshes: false,
+            vt_naming: maclarian::virtual_texture::VTexNaming::default(),
         };
 
         match maclarian::gr2_extraction::process_extracted_gr2_to_dir(
@@ -2,16 +2,22 @@
 
 mod conversion;
 mod directory;
+mod extract;
 mod file_ops;
 mod gr2;
+mod pak;
 mod preview;
+mod selection;
 mod utils;
 
 pub use conversion::convert_file_quick;
 pub use directory::{
     apply_filters, go_up, load_directory, open_folder_dialog, refresh, sort_files,
+    toggle_type_group,
 };
+pub use extract::{extract_folder, extract_selected};
 pub use file_ops::{delete_file, open_file_or_folder_filtered, perform_rename};
 pub use gr2::convert_gr2_file;
-pub use preview::select_file;
+pub use pak::load_pak_directory;
+pub use selection::{SelectMode, is_row_selected, select_row};
 pub use utils::{cleanup_temp_files, is_text_file};
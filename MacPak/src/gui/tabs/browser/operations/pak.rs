@@ -0,0 +1,138 @@
+//! Browsing the contents of a PAK as if it were a folder
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use floem::ext_event::create_ext_action;
+use floem::prelude::*;
+use floem_reactive::Scope;
+
+use crate::gui::state::{BrowserState, FileEntry};
+use crate::gui::tabs::editor::track_recent_pak;
+use crate::gui::utils::show_file_error;
+
+use super::directory::{file_icon, set_entries};
+use super::utils::format_size;
+
+/// Folder inside the current PAK (`""` for the root), or `""` outside a PAK
+pub fn pak_dir(state: &BrowserState) -> String {
+    let (Some(pak_path), Some(current)) = (state.pak_path.get(), state.current_path.get()) else {
+        return String::new();
+    };
+    current
+        .strip_prefix(&pak_path)
+        .unwrap_or("")
+        .trim_matches('/')
+        .to_string()
+}
+
+/// List one folder of a PAK. The file table is read through the shared
+/// `PakReaderCache`, so moving between folders doesn't re-read the PAK.
+pub fn load_pak_directory(pak_path: &str, dir: &str, state: BrowserState) {
+    let pak = pak_path.to_string();
+    let dir = dir.trim_matches('/').to_string();
+    let cache = state.pak_cache.clone();
+
+    state.is_loading.set(true);
+    state.loading_message.set(format!(
+        "Reading {}...",
+        Path::new(&pak)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    ));
+
+    let pak_for_result = pak.clone();
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<Vec<(String, u64)>, String>| {
+            state.is_loading.set(false);
+            match result {
+                Ok(files) => {
+                    if dir.is_empty() {
+                        track_recent_pak(&pak_for_result);
+                    }
+                    show_pak_folder(&pak_for_result, &dir, &files, state);
+                }
+                Err(e) => show_file_error(Path::new(&pak_for_result), "Opening", &e),
+            }
+        },
+    );
+
+    rayon::spawn(move || {
+        let result = match cache.lock() {
+            Ok(mut cache) => cache.list_files(Path::new(&pak)).map_err(|e| e.to_string()),
+            Err(_) => Err("PAK cache is unavailable".to_string()),
+        };
+        send(result);
+    });
+}
+
+/// Build the entries of one PAK folder and show them
+fn show_pak_folder(pak_path: &str, dir: &str, files: &[(String, u64)], state: BrowserState) {
+    let prefix = if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", dir)
+    };
+
+    let mut entries = Vec::new();
+    let mut folders = BTreeSet::new();
+    let mut total_size = 0;
+
+    for (path, size) in files {
+        let Some(rest) = path.strip_prefix(&prefix) else {
+            continue;
+        };
+        if let Some((folder, _)) = rest.split_once('/') {
+            folders.insert(folder.to_string());
+            continue;
+        }
+
+        let extension = Path::new(rest)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_string();
+        let file_type = extension.to_uppercase();
+        total_size += size;
+        entries.push(FileEntry {
+            name: rest.to_string(),
+            path: path.clone(),
+            is_dir: false,
+            size: *size,
+            size_formatted: format_size(*size),
+            icon: file_icon(&file_type).to_string(),
+            extension,
+            file_type,
+            modified: "--".to_string(),
+            pak_path: Some(pak_path.to_string()),
+        });
+    }
+
+    let file_count = entries.len();
+    let folder_count = folders.len();
+    entries.extend(folders.into_iter().map(|name| FileEntry {
+        path: format!("{}{}", prefix, name),
+        name,
+        is_dir: true,
+        size: 0,
+        size_formatted: "--".to_string(),
+        extension: String::new(),
+        file_type: "Folder".to_string(),
+        modified: "--".to_string(),
+        icon: "📁".to_string(),
+        pak_path: Some(pak_path.to_string()),
+    }));
+
+    let display_path = if dir.is_empty() {
+        pak_path.to_string()
+    } else {
+        format!("{}/{}", pak_path, dir)
+    };
+    state.current_path.set(Some(display_path.clone()));
+    state.browser_path.set(display_path);
+    state.pak_path.set(Some(pak_path.to_string()));
+
+    set_entries(entries, file_count, folder_count, total_size, state);
+}
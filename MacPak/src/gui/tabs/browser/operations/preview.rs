@@ -1,14 +1,27 @@
 //! File preview and selection operations
 
-use std::path::Path;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 
+use floem::ext_event::create_ext_action;
 use floem::prelude::*;
+use floem_reactive::Scope;
+use maclarian::converter::{dds_info, decode_dds_bytes};
 
 use crate::gui::state::{BrowserState, FileEntry, RawImageData};
+use crate::search::CachedPreview;
+
+use super::utils::{format_size, is_text_file};
 
 /// Maximum preview dimension (width or height) for resizing
-/// Note: Kept small to avoid filling vger's texture atlas (each 256x256 RGBA = 256KB)
-const MAX_PREVIEW_SIZE: u32 = 256;
+/// Note: Kept small to avoid filling vger's texture atlas (each 512x512 RGBA = 1MB)
+const MAX_PREVIEW_SIZE: u32 = 512;
+
+/// Files larger than this are not decoded for preview
+const MAX_PREVIEW_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Images with more pixels than this (4096x4096) are not decoded for preview
+const MAX_PREVIEW_PIXELS: u64 = 4096 * 4096;
 
 pub fn select_file(file: &FileEntry, state: BrowserState) {
     state.preview_name.set(file.name.clone());
@@ -30,6 +43,27 @@ pub fn select_file(file: &FileEntry, state: BrowserState) {
         .preview_info
         .set(format!("{} | {}", file.file_type, file.size_formatted));
 
+    if let Some(pak_path) = &file.pak_path {
+        if is_image_file(&file.extension) {
+            load_image_preview(file, state);
+            return;
+        }
+
+        let pak_name = Path::new(pak_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let hint = if is_text_file(&file.extension) {
+            "\n\nDouble-click to open in the Editor"
+        } else {
+            ""
+        };
+        state
+            .preview_content
+            .set(format!("[Inside {}]\n{}{}", pak_name, file.path, hint));
+        return;
+    }
+
     let path = Path::new(&file.path);
     let ext = file.extension.to_lowercase();
 
@@ -91,7 +125,7 @@ pub fn select_file(file: &FileEntry, state: BrowserState) {
         "pak" => match maclarian::pak::PakOperations::list(path) {
             Ok(pak_files) => {
                 let preview = format!(
-                    "PAK Archive: {} files\n\n{}",
+                    "PAK Archive: {} files (double-click to browse)\n\n{}",
                     pak_files.len(),
                     pak_files
                         .iter()
@@ -108,40 +142,7 @@ pub fn select_file(file: &FileEntry, state: BrowserState) {
                     .set(format!("[Error reading PAK: {}]", e));
             }
         },
-        "dds" => {
-            // Load DDS synchronously (decode to raw RGBA, no PNG encoding)
-            let current_version = state.preview_image.get().0;
-            match load_dds_image(path) {
-                Ok(img_data) => {
-                    state.preview_content.set(String::new());
-                    state
-                        .preview_image
-                        .set((current_version + 1, Some(img_data)));
-                }
-                Err(e) => {
-                    state
-                        .preview_content
-                        .set(format!("[Error loading DDS: {}]", e));
-                }
-            }
-        }
-        "png" | "jpg" | "jpeg" => {
-            // Load image synchronously (decode to raw RGBA, no PNG encoding)
-            let current_version = state.preview_image.get().0;
-            match load_standard_image(path) {
-                Ok(img_data) => {
-                    state.preview_content.set(String::new());
-                    state
-                        .preview_image
-                        .set((current_version + 1, Some(img_data)));
-                }
-                Err(e) => {
-                    state
-                        .preview_content
-                        .set(format!("[Error loading image: {}]", e));
-                }
-            }
-        }
+        "dds" | "png" | "jpg" | "jpeg" => load_image_preview(file, state),
         "glb" | "gltf" => {
             state
                 .preview_content
@@ -165,55 +166,150 @@ pub fn select_file(file: &FileEntry, state: BrowserState) {
     }
 }
 
-/// Load a DDS file, resize for preview, and return raw RGBA data
-fn load_dds_image(path: &Path) -> Result<RawImageData, String> {
-    use dds::{ColorFormat, Decoder, ImageViewMut};
+/// Whether the preview pane can decode this file extension
+fn is_image_file(ext: &str) -> bool {
+    matches!(ext.to_lowercase().as_str(), "dds" | "png" | "jpg" | "jpeg")
+}
+
+/// Decode an image (DDS, PNG, JPG) on a background thread and show it in the
+/// preview pane. Works for files on disk and inside PAKs; decoded previews are
+/// cached by pak + path.
+fn load_image_preview(file: &FileEntry, state: BrowserState) {
+    let (source, internal_path) = match &file.pak_path {
+        Some(pak_path) => (PathBuf::from(pak_path), file.path.clone()),
+        None => (PathBuf::from(&file.path), String::new()),
+    };
 
-    // Open and decode the DDS file
-    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
-    let mut decoder = Decoder::new(file).map_err(|e| e.to_string())?;
+    let cached = state
+        .preview_cache
+        .write()
+        .ok()
+        .and_then(|mut cache| cache.get_preview(&source, &internal_path).cloned());
+    if let Some(preview) = cached {
+        show_image_preview(&state, preview);
+        return;
+    }
 
-    // Get dimensions
-    let size = decoder.main_size();
+    state.preview_content.set("Loading preview...".to_string());
 
-    // Create buffer for RGBA8 output (4 bytes per pixel)
-    let mut rgba_buffer = vec![0u8; size.pixels() as usize * 4];
+    // Selecting another file bumps the version; stale results are dropped
+    let version = state.preview_image.get_untracked().0;
+    let in_pak = file.pak_path.is_some();
+    let ext = file.extension.to_lowercase();
+    let size = file.size;
+    let pak_cache = state.pak_cache.clone();
+    let preview_cache = state.preview_cache.clone();
 
-    // Create image view and decode
-    let view = ImageViewMut::new(&mut rgba_buffer, size, ColorFormat::RGBA_U8)
-        .ok_or_else(|| "Failed to create image view".to_string())?;
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<CachedPreview, String>| {
+            if state.preview_image.get_untracked().0 != version {
+                return;
+            }
+            match result {
+                Ok(preview) => show_image_preview(&state, preview),
+                Err(reason) => state
+                    .preview_content
+                    .set(format!("[No preview: {}]", reason)),
+            }
+        },
+    );
 
-    decoder
-        .read_surface(view)
-        .map_err(|e| format!("Failed to decode DDS: {:?}", e))?;
+    rayon::spawn(move || {
+        let result = check_file_size(size)
+            .and_then(|()| {
+                if in_pak {
+                    let mut cache = pak_cache
+                        .lock()
+                        .map_err(|_| "PAK cache is unavailable".to_string())?;
+                    cache
+                        .read_file(&source, &internal_path)
+                        .map_err(|e| e.to_string())
+                } else {
+                    std::fs::read(&source).map_err(|e| e.to_string())
+                }
+            })
+            .and_then(|data| decode_image_preview(&data, &ext, size));
 
-    // Create image from raw bytes for resizing
-    let img: image::RgbaImage = image::ImageBuffer::from_raw(size.width, size.height, rgba_buffer)
-        .ok_or_else(|| "Failed to create image buffer".to_string())?;
+        if let Ok(preview) = &result {
+            if let Ok(mut cache) = preview_cache.write() {
+                cache.insert_preview(&source, &internal_path, preview.clone());
+            }
+        }
+        send(result);
+    });
+}
 
-    // Resize if larger than preview size
-    let img = resize_for_preview(img);
+/// Show a decoded preview with its details
+fn show_image_preview(state: &BrowserState, preview: CachedPreview) {
+    let version = state.preview_image.get_untracked().0;
+    state.preview_content.set(String::new());
+    state.preview_info.set(preview.details);
+    state.preview_image.set((
+        version + 1,
+        Some(RawImageData {
+            width: preview.width,
+            height: preview.height,
+            rgba_data: preview.rgba_data,
+        }),
+    ));
+}
 
-    Ok(RawImageData {
-        width: img.width(),
-        height: img.height(),
-        rgba_data: img.into_raw(),
-    })
+fn check_file_size(size: u64) -> Result<(), String> {
+    if size > MAX_PREVIEW_BYTES {
+        return Err(format!("file is too large ({})", format_size(size)));
+    }
+    Ok(())
 }
 
-/// Load a regular image file (PNG, JPG), resize for preview, and return raw RGBA data
-fn load_standard_image(path: &Path) -> Result<RawImageData, String> {
-    // Load the image
-    let img = image::open(path).map_err(|e| e.to_string())?;
-    let img = img.into_rgba8();
+fn check_dimensions(width: u32, height: u32) -> Result<(), String> {
+    if u64::from(width) * u64::from(height) > MAX_PREVIEW_PIXELS {
+        return Err(format!("{}x{} is too large to decode", width, height));
+    }
+    Ok(())
+}
 
-    // Resize if larger than preview size
-    let img = resize_for_preview(img);
+/// Decode image bytes and downscale them for the preview pane (background thread)
+fn decode_image_preview(data: &[u8], ext: &str, size: u64) -> Result<CachedPreview, String> {
+    let (img, details) = if ext == "dds" {
+        // Check the header first so huge textures are rejected before decoding
+        let info = dds_info(data).map_err(|e| e.to_string())?;
+        check_dimensions(info.width, info.height)?;
+        let (_, img) = decode_dds_bytes(data).map_err(|e| e.to_string())?;
+        let mips = if info.mip_count == 1 {
+            "1 mip".to_string()
+        } else {
+            format!("{} mips", info.mip_count)
+        };
+        let details = format!(
+            "{}x{} | {} | {}",
+            info.width, info.height, info.format, mips
+        );
+        (img, details)
+    } else {
+        let (width, height) = image::ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .map_err(|e| e.to_string())?
+            .into_dimensions()
+            .map_err(|e| e.to_string())?;
+        check_dimensions(width, height)?;
+        let img = image::load_from_memory(data)
+            .map_err(|e| e.to_string())?
+            .into_rgba8();
+        (img, format!("{}x{}", width, height))
+    };
 
-    Ok(RawImageData {
+    let img = resize_for_preview(img);
+    Ok(CachedPreview {
         width: img.width(),
         height: img.height(),
         rgba_data: img.into_raw(),
+        details: format!(
+            "{} | {} | {}",
+            ext.to_uppercase(),
+            format_size(size),
+            details
+        ),
     })
 }
 
@@ -0,0 +1,90 @@
+//! Multi-selection in the file list (click, cmd/ctrl-click, shift-click)
+
+use std::collections::BTreeSet;
+
+use floem::keyboard::Modifiers;
+use floem::reactive::{SignalGet, SignalUpdate, SignalWith};
+
+use crate::gui::state::{BrowserState, FileEntry};
+
+use super::preview::select_file;
+
+/// How a click or arrow key changes the selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectMode {
+    /// Select only this row
+    Single,
+    /// Add or remove this row (cmd/ctrl)
+    Toggle,
+    /// Select everything from the anchor to this row (shift)
+    Range,
+}
+
+impl SelectMode {
+    pub fn from_modifiers(modifiers: Modifiers) -> Self {
+        if modifiers.contains(Modifiers::SHIFT) {
+            SelectMode::Range
+        } else if modifiers.contains(Modifiers::META) || modifiers.contains(Modifiers::CONTROL) {
+            SelectMode::Toggle
+        } else {
+            SelectMode::Single
+        }
+    }
+}
+
+/// Update the selection for row `index` and preview it
+pub fn select_row(index: usize, mode: SelectMode, state: BrowserState) {
+    let files = state.files.get_untracked();
+    let Some(file) = files.get(index) else {
+        return;
+    };
+
+    match mode {
+        SelectMode::Single => {
+            state.selected_indices.set(BTreeSet::from([index]));
+            state.selection_anchor.set(Some(index));
+        }
+        SelectMode::Toggle => {
+            state.selected_indices.update(|selected| {
+                if !selected.remove(&index) {
+                    selected.insert(index);
+                }
+            });
+            state.selection_anchor.set(Some(index));
+        }
+        SelectMode::Range => {
+            let anchor = state.selection_anchor.get_untracked().unwrap_or(index);
+            let range = anchor.min(index)..=anchor.max(index);
+            state.selected_indices.set(range.collect());
+            state.selection_anchor.set(Some(anchor));
+        }
+    }
+
+    state.selected_index.set(Some(index));
+    select_file(file, state);
+}
+
+/// Selected entries in list order
+pub fn selected_entries(state: &BrowserState) -> Vec<FileEntry> {
+    let files = state.files.get_untracked();
+    state
+        .selected_indices
+        .get_untracked()
+        .iter()
+        .filter_map(|&i| files.get(i).cloned())
+        .collect()
+}
+
+/// Whether row `index` is part of the selection
+pub fn is_row_selected(index: usize, state: &BrowserState) -> bool {
+    state
+        .selected_indices
+        .with_untracked(|s| s.contains(&index))
+}
+
+/// Forget the selection (after the list is reloaded, filtered, or re-sorted)
+pub fn clear_selection(state: &BrowserState) {
+    state.selected_index.set(None);
+    state.selected_indices.set(BTreeSet::new());
+    state.selection_anchor.set(None);
+}
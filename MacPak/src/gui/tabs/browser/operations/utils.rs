@@ -1,16 +1,19 @@
 //! Utility functions for the browser
 
-/// Format file size for display
+/// Format file size for display in binary units (1 KiB = 1024 bytes)
 pub fn format_size(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
-    let mut size = bytes as f64;
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
     for unit in UNITS {
         if size < 1024.0 {
             return format!("{:.1} {}", size, unit);
         }
         size /= 1024.0;
     }
-    format!("{:.1} PB", size)
+    format!("{:.1} PiB", size)
 }
 
 /// Check if a file extension is a text/editable file type
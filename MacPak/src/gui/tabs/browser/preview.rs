@@ -5,6 +5,7 @@ use floem::text::Weight;
 
 use super::preview_3d::preview_3d_button;
 use super::raw_img::raw_img;
+use crate::gui::shared::colors;
 use crate::gui::state::BrowserState;
 
 pub fn preview_panel(state: BrowserState) -> impl IntoView {
@@ -25,17 +26,17 @@ pub fn preview_panel(state: BrowserState) -> impl IntoView {
                             .font_weight(Weight::BOLD)
                             .width_full()
                             .padding(12.0)
-                            .background(Color::rgb8(248, 248, 248))
+                            .background(colors().bg_surface)
                             .border_bottom(1.0)
-                            .border_color(Color::rgb8(220, 220, 220))
+                            .border_color(colors().border)
                     }),
                     // Placeholder content
                     v_stack((
                         label(|| "📄").style(|s| s.font_size(64.0)),
                         label(|| "Select a file to preview")
-                            .style(|s| s.font_size(16.0).color(Color::rgb8(120, 120, 120))),
+                            .style(|s| s.font_size(16.0).color(colors().text_muted)),
                         label(|| "Click on a file in the list to see its contents")
-                            .style(|s| s.font_size(13.0).color(Color::rgb8(160, 160, 160))),
+                            .style(|s| s.font_size(13.0).color(colors().text_muted)),
                     ))
                     .style(|s| {
                         s.flex_grow(1.0)
@@ -43,7 +44,7 @@ pub fn preview_panel(state: BrowserState) -> impl IntoView {
                             .items_center()
                             .justify_center()
                             .gap(8.0)
-                            .background(Color::WHITE)
+                            .background(colors().bg_base)
                     }),
                 ))
                 .style(|s| s.width_full().height_full())
@@ -66,7 +67,7 @@ pub fn preview_panel(state: BrowserState) -> impl IntoView {
             .min_width(200.0) // Minimum preview width
             .min_height(0.0)
             .border_left(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -83,15 +84,15 @@ fn preview_content_view(
             label(move || preview_name.get())
                 .style(|s| s.font_size(16.0).font_weight(Weight::BOLD)),
             label(move || preview_info.get())
-                .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+                .style(|s| s.font_size(12.0).color(colors().text_secondary)),
         ))
         .style(|s| {
             s.width_full()
                 .padding(12.0)
                 .gap(4.0)
-                .background(Color::rgb8(248, 248, 248))
+                .background(colors().bg_surface)
                 .border_bottom(1.0)
-                .border_color(Color::rgb8(220, 220, 220))
+                .border_color(colors().border)
         }),
         // Preview content (either image or text)
         // Uses dyn_stack with version as key to force complete view recreation on each image change.
@@ -139,7 +140,7 @@ fn preview_content_view(
                 .flex_grow(1.0)
                 .flex_basis(0.0)
                 .min_height(0.0)
-                .background(Color::WHITE)
+                .background(colors().bg_base)
         }),
     ))
     .style(|s| s.width_full().height_full().min_height(0.0))
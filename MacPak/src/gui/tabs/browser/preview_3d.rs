@@ -1,18 +1,24 @@
 //! 3D Model preview launcher
 //!
-//! Spawns the macpak-bevy binary as a subprocess to display .glb/.gr2 files
+//! Spawns the viewer binary as a subprocess to display .glb/.gr2 files
 
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 
 use floem::prelude::*;
 
+use crate::gui::shared::colors;
 use crate::gui::state::BrowserState;
 
 /// Global handle to the preview process (only one at a time)
 static PREVIEW_PROCESS: OnceLock<Arc<Mutex<Option<Child>>>> = OnceLock::new();
 
+/// Bumped for every preview opened so a monitor thread can tell its
+/// process was replaced
+static PREVIEW_GENERATION: AtomicU64 = AtomicU64::new(0);
+
 /// Global handle to the temp file path (to clean up when preview closes)
 static TEMP_GLB_PATH: OnceLock<Arc<Mutex<Option<std::path::PathBuf>>>> = OnceLock::new();
 
@@ -73,67 +79,92 @@ pub fn launch_3d_preview(file_path: &str, state: BrowserState) {
         file_path.to_string()
     };
 
-    // Find and launch the preview binary
-    let preview_binary = find_preview_binary();
     state
         .status_message
         .set("Loading 3D preview...".to_string());
 
-    match Command::new(&preview_binary).arg(&preview_path).spawn() {
-        Ok(child) => {
-            if let Ok(mut handle) = get_preview_handle().lock() {
-                *handle = Some(child);
+    let state_on_exit = state.clone();
+    if let Err(e) = open_preview_window(Path::new(&preview_path), move || {
+        // Clear loading message
+        state_on_exit.status_message.set(String::new());
+    }) {
+        state.status_message.set(e);
+    }
+}
+
+/// Open a .glb/.gltf file in the preview window, replacing any preview that
+/// is already open. `on_exit` runs (on a background thread) once the window
+/// is closed; temp files from a GR2 preview are cleaned up then too.
+pub fn open_preview_window(
+    path: &Path,
+    on_exit: impl FnOnce() + Send + 'static,
+) -> Result<(), String> {
+    let preview_binary = find_preview_binary()?;
+
+    // Only one preview at a time
+    if let Ok(mut handle) = get_preview_handle().lock() {
+        if let Some(mut child) = handle.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    let child = Command::new(&preview_binary)
+        .arg(path)
+        .spawn()
+        .map_err(|e| format!("Failed to open preview: {}", e))?;
+    let generation = PREVIEW_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if let Ok(mut handle) = get_preview_handle().lock() {
+        *handle = Some(child);
+    }
+
+    // Spawn a background thread to monitor when the preview window closes
+    std::thread::spawn(move || {
+        // Wait for the process to exit
+        loop {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            if PREVIEW_GENERATION.load(Ordering::SeqCst) != generation {
+                // A newer preview took over; its own thread monitors it
+                break;
             }
 
-            // Spawn a background thread to monitor when the preview window closes
-            std::thread::spawn(move || {
-                // Wait for the process to exit
-                loop {
-                    std::thread::sleep(std::time::Duration::from_millis(500));
-
-                    let should_cleanup = if let Ok(mut handle) = get_preview_handle().lock() {
-                        if let Some(ref mut child) = *handle {
-                            // Check if process has exited
-                            match child.try_wait() {
-                                Ok(Some(_)) => {
-                                    // Process exited, remove from handle
-                                    *handle = None;
-                                    true
-                                }
-                                Ok(None) => false, // Still running
-                                Err(_) => {
-                                    *handle = None;
-                                    true
-                                }
-                            }
-                        } else {
-                            // No process, stop monitoring
-                            break;
+            let should_cleanup = if let Ok(mut handle) = get_preview_handle().lock() {
+                if let Some(ref mut child) = *handle {
+                    // Check if process has exited
+                    match child.try_wait() {
+                        Ok(Some(_)) => {
+                            // Process exited, remove from handle
+                            *handle = None;
+                            true
                         }
-                    } else {
-                        break;
-                    };
-
-                    if should_cleanup {
-                        // Clean up temp file
-                        if let Ok(mut temp_handle) = get_temp_path_handle().lock() {
-                            if let Some(temp_path) = temp_handle.take() {
-                                let _ = std::fs::remove_file(temp_path);
-                            }
+                        Ok(None) => false, // Still running
+                        Err(_) => {
+                            *handle = None;
+                            true
                         }
-                        // Clear loading message
-                        state.status_message.set(String::new());
-                        break;
                     }
+                } else {
+                    // No process (replaced or killed), stop monitoring
+                    break;
                 }
-            });
-        }
-        Err(e) => {
-            state
-                .status_message
-                .set(format!("Failed to open preview: {}", e));
+            } else {
+                break;
+            };
+
+            if should_cleanup {
+                // Clean up temp file
+                if let Ok(mut temp_handle) = get_temp_path_handle().lock() {
+                    if let Some(temp_path) = temp_handle.take() {
+                        let _ = std::fs::remove_file(temp_path);
+                    }
+                }
+                on_exit();
+                break;
+            }
         }
-    }
+    });
+
+    Ok(())
 }
 
 /// Result of GR2 to GLB conversion
@@ -199,23 +230,43 @@ pub fn close_3d_preview(state: BrowserState) {
     state.status_message.set(String::new());
 }
 
-/// Find the preview binary path
-fn find_preview_binary() -> String {
-    // Check if we're running from cargo (development)
-    let exe_path = std::env::current_exe().ok();
-
-    if let Some(exe) = exe_path {
-        // Check sibling directory (release/debug build)
-        if let Some(parent) = exe.parent() {
-            let sibling = parent.join("macpak-bevy");
-            if sibling.exists() {
-                return sibling.to_string_lossy().to_string();
+/// Names the viewer binary has been built under (`macpak-bevy` is the
+/// name older bundles shipped it as)
+const PREVIEW_BINARY_NAMES: &[&str] = &["macpak-viewer", "macpak-bevy"];
+
+/// Find the preview binary: next to the app's executable (inside the app
+/// bundle for releases, the target dir in development), then on PATH
+fn find_preview_binary() -> Result<PathBuf, String> {
+    let file_names: Vec<String> = PREVIEW_BINARY_NAMES
+        .iter()
+        .map(|name| format!("{}{}", name, std::env::consts::EXE_SUFFIX))
+        .collect();
+
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let path_dirs = std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    for dir in exe_dir.iter().chain(&path_dirs) {
+        for name in &file_names {
+            let candidate = dir.join(name);
+            if candidate.is_file() {
+                return Ok(candidate);
             }
         }
     }
 
-    // Fallback: assume it's in PATH or current directory
-    "macpak-bevy".to_string()
+    Err(match exe_dir {
+        Some(dir) => format!(
+            "3D preview unavailable: {} not found. Expected it at {} \
+             (build it with `cargo build --features viewer --bin macpak-viewer`)",
+            file_names[0],
+            dir.join(&file_names[0]).display()
+        ),
+        None => format!("3D preview unavailable: {} not found", file_names[0]),
+    })
 }
 
 /// Create a button to launch 3D preview
@@ -233,11 +284,11 @@ pub fn preview_3d_button(state: BrowserState) -> impl IntoView {
                     .style(|s| {
                         s.padding_horiz(16.0)
                             .padding_vert(8.0)
-                            .background(Color::rgb8(33, 150, 243))
-                            .color(Color::WHITE)
+                            .background(colors().accent)
+                            .color(colors().text_on_accent)
                             .border_radius(4.0)
                             .margin_top(12.0)
-                            .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                            .hover(|s| s.background(colors().accent_hover))
                     })
                     .action(move || {
                         if let Some(ref p) = path {
@@ -2,6 +2,7 @@
 
 use floem::prelude::*;
 
+use crate::gui::shared::colors;
 use crate::gui::state::BrowserState;
 
 pub fn browser_status_bar(state: BrowserState) -> impl IntoView {
@@ -13,21 +14,21 @@ pub fn browser_status_bar(state: BrowserState) -> impl IntoView {
                 state.folder_count.get()
             )
         })
-        .style(|s| s.color(Color::rgb8(100, 100, 100)).font_size(12.0)),
+        .style(|s| s.color(colors().text_secondary).font_size(12.0)),
         empty().style(|s| s.flex_grow(1.0)),
         label(move || state.total_size.get())
-            .style(|s| s.color(Color::rgb8(100, 100, 100)).font_size(12.0)),
+            .style(|s| s.color(colors().text_secondary).font_size(12.0)),
         empty().style(|s| s.width(16.0)),
         label(move || state.status_message.get())
-            .style(|s| s.color(Color::rgb8(76, 175, 80)).font_size(12.0)),
+            .style(|s| s.color(colors().success).font_size(12.0)),
     ))
     .style(|s| {
         s.width_full()
             .height(32.0)
             .padding_horiz(12.0)
             .items_center()
-            .background(Color::rgb8(248, 248, 248))
+            .background(colors().bg_surface)
             .border_top(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
@@ -4,7 +4,10 @@ use floem::keyboard::{Key, NamedKey};
 use floem::prelude::*;
 use floem::views::PlaceholderTextClass;
 
-use super::operations::{apply_filters, go_up, load_directory, open_folder_dialog, refresh};
+use super::operations::{
+    apply_filters, go_up, load_directory, open_folder_dialog, refresh, sort_files,
+};
+use crate::gui::shared::colors;
 use crate::gui::state::BrowserState;
 
 pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
@@ -18,6 +21,7 @@ pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
     let state_filter = state.clone();
     let state_all = state.clone();
     let state_pak = state.clone();
+    let state_group = state.clone();
 
     // Binary files (LSBC and LSBS not included)
     let state_lsx = state.clone();
@@ -62,11 +66,9 @@ pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
                         .min_width(100.0)
                         .padding(6.0)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
-                        .class(PlaceholderTextClass, |s| {
-                            s.color(Color::rgb8(120, 120, 120))
-                        })
+                        .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
                 })
                 .on_key_down(
                     Key::Named(NamedKey::Enter),
@@ -95,11 +97,9 @@ pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
                     s.width(200.0)
                         .padding(6.0)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
-                        .class(PlaceholderTextClass, |s| {
-                            s.color(Color::rgb8(120, 120, 120))
-                        })
+                        .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
                 })
                 .on_key_down(
                     Key::Named(NamedKey::Enter),
@@ -112,7 +112,7 @@ pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
                 apply_filters(state_filter.clone());
             }),
             separator(),
-            label(|| "Quick Filter:").style(|s| s.color(Color::rgb8(100, 100, 100))),
+            label(|| "Quick Filter:").style(|s| s.color(colors().text_secondary)),
             filter_button("All", state_all),
             separator(),
             filter_button("PAK", state_pak),
@@ -136,6 +136,7 @@ pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
             ))
             .style(|s| s.gap(8.0)),
             empty().style(|s| s.flex_grow(1.0)),
+            group_button(state_group),
         ))
         .style(|s| s.width_full().gap(8.0).items_center()),
     ))
@@ -143,9 +144,9 @@ pub fn browser_toolbar(state: BrowserState) -> impl IntoView {
         s.width_full()
             .padding(10.0)
             .gap(8.0)
-            .background(Color::rgb8(245, 245, 245))
+            .background(colors().bg_elevated)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -159,11 +160,11 @@ fn filter_button(filter_type: &'static str, state: BrowserState) -> impl IntoVie
             let s = s.padding_horiz(8.0).padding_vert(4.0).border_radius(4.0);
 
             if is_active {
-                s.background(Color::rgb8(33, 150, 243)).color(Color::WHITE)
+                s.background(colors().accent).color(colors().text_on_accent)
             } else {
-                s.background(Color::rgb8(230, 230, 230))
-                    .color(Color::rgb8(60, 60, 60))
-                    .hover(|s| s.background(Color::rgb8(200, 200, 200)))
+                s.background(colors().bg_hover)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_selected))
             }
         })
         .action(move || {
@@ -172,11 +173,34 @@ fn filter_button(filter_type: &'static str, state: BrowserState) -> impl IntoVie
         })
 }
 
+/// Toggles collapsible per-type sections in the file list
+fn group_button(state: BrowserState) -> impl IntoView {
+    let group_by_type = state.group_by_type;
+
+    button("🗂️ Group by Type")
+        .style(move |s| {
+            let s = s.padding_horiz(8.0).padding_vert(4.0).border_radius(4.0);
+
+            if group_by_type.get() {
+                s.background(colors().accent).color(colors().text_on_accent)
+            } else {
+                s.background(colors().bg_hover)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_selected))
+            }
+        })
+        .action(move || {
+            group_by_type.set(!group_by_type.get());
+            // Grouping changes the sort order
+            sort_files(state.clone());
+        })
+}
+
 fn separator() -> impl IntoView {
     empty().style(|s| {
         s.width(1.0)
             .height(30.0)
-            .background(Color::rgb8(200, 200, 200))
+            .background(colors().bg_selected)
             .margin_horiz(4.0)
     })
 }
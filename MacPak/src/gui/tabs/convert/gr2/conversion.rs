@@ -7,7 +7,7 @@ use std::thread;
 use floem::prelude::*;
 use rayon::prelude::*;
 
-use super::types::{Gr2Result, create_result_sender, get_shared_progress};
+use super::types::{Gr2Result, create_result_sender};
 use crate::gui::state::Gr2State;
 
 /// Convert a single file with explicit options (for operation buttons UI)
@@ -78,13 +78,14 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
     // Start conversion
     state.is_converting.set(true);
     state.clear_results();
+    state.last_output.set(None);
 
     let input_str = input_path.clone();
+    let sender = state.progress.sender();
     let send_result = create_result_sender(state);
 
     thread::spawn(move || {
-        let shared = get_shared_progress();
-        shared.update(1, 1, &input_name);
+        sender.update(1, 1, &input_name);
 
         // Create output directory if needed
         if use_subdir {
@@ -97,7 +98,7 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
                     Path::new(&input_str),
                     Path::new(&output_str),
                     &|progress| {
-                        shared.update(
+                        sender.update(
                             progress.current,
                             progress.total + 1,
                             progress.phase.as_str(),
@@ -109,7 +110,7 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
                     Path::new(&input_str),
                     Path::new(&output_str),
                     &|progress| {
-                        shared.update(
+                        sender.update(
                             progress.current,
                             progress.total + 1,
                             progress.phase.as_str(),
@@ -122,7 +123,7 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
                 Path::new(&input_str),
                 Path::new(&output_str),
                 &|progress| {
-                    shared.update(progress.current, progress.total, progress.phase.as_str());
+                    sender.update(progress.current, progress.total, progress.phase.as_str());
                 },
             )
         };
@@ -130,7 +131,7 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
         // Handle texture extraction for GR2→GLB/glTF conversions
         let mut texture_info = String::new();
         if result.is_ok() && is_gr2_input && extract_textures {
-            shared.update(1, 2, "Extracting textures...");
+            sender.update(1, 2, "Extracting textures...");
 
             let options = maclarian::gr2_extraction::Gr2ExtractionOptions {
                 convert_to_glb: false, // Already converted
@@ -142,9 +143,12 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
                     Some(PathBuf::from(&game_data_path))
                 },
                 virtual_textures_path: None,
+                texture_roots: Vec::new(),
                 keep_original_gr2: true,
                 convert_to_png,
                 keep_original_dds,
+                record_meshes: false,
+                vt_naming: maclarian::virtual_texture::VTexNaming::default(),
             };
 
             match maclarian::gr2_extraction::process_extracted_gr2_to_dir(
@@ -173,8 +177,6 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
             let _ = std::fs::copy(Path::new(&input_str), &gr2_dest);
         }
 
-        shared.update(2, 2, "Complete");
-
         // Show the subdirectory in output name
         let display_output = if use_subdir {
             format!("{}/{}{}", stem, output_name, texture_info)
@@ -184,18 +186,22 @@ pub fn convert_single_with_options(state: Gr2State, to_glb: bool, game_data_path
 
         match result {
             Ok(()) => {
+                sender.finish();
                 send_result(Gr2Result::SingleDone {
                     success: true,
                     input_name,
                     output_name: display_output,
+                    output_path: Some(output_str),
                     error: None,
                 });
             }
             Err(e) => {
+                sender.fail(&e.to_string());
                 send_result(Gr2Result::SingleDone {
                     success: false,
                     input_name,
                     output_name,
+                    output_path: None,
                     error: Some(e.to_string()),
                 });
             }
@@ -225,23 +231,29 @@ pub fn convert_batch_with_options(
     let keep_original_dds = state.keep_original_dds.get_untracked();
     let keep_original_gr2 = state.keep_original_gr2.get_untracked();
 
-    // Start conversion
+    // Start conversion (reset first so a previous cancel doesn't carry over)
+    state.progress.reset();
     state.is_converting.set(true);
     state.clear_results();
 
     let total = files.len();
+    let sender = state.progress.sender();
     let send_result = create_result_sender(state);
 
     thread::spawn(move || {
-        let shared = get_shared_progress();
+        let cancel = sender.allow_cancel();
         let success_counter = AtomicUsize::new(0);
         let error_counter = AtomicUsize::new(0);
         let processed = AtomicUsize::new(0);
 
-        // Parallel conversion
-        let results: Vec<String> = files
+        // Parallel conversion; files not started before a cancel are skipped
+        let results: Vec<(String, Option<String>)> = files
             .par_iter()
-            .map(|input_path| {
+            .filter_map(|input_path| {
+                if cancel.is_cancelled() {
+                    return None;
+                }
+
                 let input = Path::new(input_path);
                 let stem = input
                     .file_stem()
@@ -294,7 +306,7 @@ pub fn convert_batch_with_options(
 
                 // Update progress (atomic)
                 let current = processed.fetch_add(1, Ordering::SeqCst) + 1;
-                shared.update(current, total, &input_name);
+                sender.update(current, total, &input_name);
 
                 // Create output directory if needed
                 if let Some(ref dir) = file_output_dir {
@@ -324,7 +336,7 @@ pub fn convert_batch_with_options(
                     input_name.clone()
                 };
 
-                match result {
+                let message = match result {
                     Ok(()) => {
                         success_counter.fetch_add(1, Ordering::SeqCst);
                         let output_name = output_path
@@ -344,9 +356,12 @@ pub fn convert_batch_with_options(
                                     Some(PathBuf::from(&game_data_path))
                                 },
                                 virtual_textures_path: None,
+                                texture_roots: Vec::new(),
                                 keep_original_gr2: true,
                                 convert_to_png,
                                 keep_original_dds,
+                                record_meshes: false,
+                                vt_naming: maclarian::virtual_texture::VTexNaming::default(),
                             };
 
                             let tex_output_dir = file_output_dir.as_ref().unwrap_or(&output_parent);
@@ -379,23 +394,30 @@ pub fn convert_batch_with_options(
                             format!("{}{}", output_name, texture_info)
                         };
 
-                        format!("Converted {} -> {}", display_path, display_output)
+                        (
+                            format!("Converted {} -> {}", display_path, display_output),
+                            Some(output_path.to_string_lossy().to_string()),
+                        )
                     }
                     Err(e) => {
                         error_counter.fetch_add(1, Ordering::SeqCst);
-                        format!("Failed {}: {}", display_path, e)
+                        (format!("Failed {}: {}", display_path, e), None)
                     }
-                }
+                };
+                Some(message)
             })
             .collect();
 
-        // Final progress update
-        shared.update(total, total, "Complete");
+        sender.finish();
 
+        let skipped_count = total - results.len();
+        let (results, outputs): (Vec<String>, Vec<Option<String>>) = results.into_iter().unzip();
         send_result(Gr2Result::BatchDone {
             success_count: success_counter.load(Ordering::SeqCst),
             error_count: error_counter.load(Ordering::SeqCst),
+            skipped_count,
             results,
+            outputs,
         });
     });
 }
@@ -6,6 +6,7 @@
 //! - Drag & drop support
 
 mod conversion;
+mod preview;
 mod sections;
 pub mod types;
 
@@ -14,8 +15,9 @@ pub use sections::open_gr2_file;
 use floem::prelude::*;
 use floem::style::Position;
 
-use crate::gui::shared::{header_section, progress_overlay, results_section};
+use crate::gui::shared::{colors, header_section, progress_overlay, results_section_with_actions};
 use crate::gui::state::{AppState, ConfigState, Gr2State};
+use preview::preview_row_action;
 use sections::operations_row;
 
 pub fn gr2_tab(
@@ -33,7 +35,7 @@ pub fn gr2_tab(
             // Operations row - 3 columns
             operations_row(gr2_state.clone(), config_state),
             // Results area (using shared component)
-            results_section(gr2_state.clone()),
+            results_section_with_actions(gr2_state.clone(), preview_row_action(&gr2_state)),
         ))
         .style(|s| {
             s.width_full()
@@ -50,7 +52,7 @@ pub fn gr2_tab(
     .style(|s| {
         s.width_full()
             .height_full()
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
             .position(Position::Relative)
     })
 }
@@ -0,0 +1,46 @@
+//! Opening converted models in the 3D preview window
+
+use std::path::Path;
+
+use floem::prelude::*;
+
+use crate::gui::shared::{ResultAction, ResultEntry};
+use crate::gui::state::Gr2State;
+use crate::gui::tabs::browser::open_preview_window;
+
+/// Whether the viewer can open a conversion output (glTF/GLB, not GR2)
+pub fn is_previewable(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("glb") || ext.eq_ignore_ascii_case("gltf"))
+}
+
+/// Open a converted model in the preview window (only one runs at a time)
+pub fn preview_model(path: &str, status: RwSignal<String>) {
+    let name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    match open_preview_window(Path::new(path), || {}) {
+        Ok(()) => status.set(format!("Previewing {}", name)),
+        Err(e) => status.set(e),
+    }
+}
+
+/// "Preview" button for results log rows of converted models
+pub fn preview_row_action(
+    state: &Gr2State,
+) -> impl Fn(&ResultEntry) -> Option<ResultAction> + 'static {
+    let status = state.status_message;
+    move |entry| {
+        let path = entry
+            .path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|p| is_previewable(p))?;
+        Some(ResultAction {
+            label: "Preview",
+            run: Box::new(move || preview_model(&path, status)),
+        })
+    }
+}
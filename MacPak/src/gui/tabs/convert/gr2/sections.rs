@@ -5,7 +5,8 @@ use floem::prelude::*;
 use floem::text::Weight;
 
 use super::conversion::{convert_batch_with_options, convert_single_with_options};
-use crate::gui::shared::{card_style, checkbox_option, drop_zone, operation_button};
+use super::preview::preview_model;
+use crate::gui::shared::{card_style, checkbox_option, colors, drop_zone, operation_button};
 use crate::gui::state::{ConfigState, Gr2State};
 
 /// Main operations row with 3 columns
@@ -42,8 +43,10 @@ fn gr2_to_gltf_group(
 ) -> impl IntoView {
     let state1 = state.clone();
     let state2 = state.clone();
+    let state3 = state.clone();
     let config1 = config.clone();
-    let config2 = config;
+    let config2 = config.clone();
+    let config3 = config;
 
     v_stack((
         // Header row with title and format toggle
@@ -51,7 +54,7 @@ fn gr2_to_gltf_group(
             label(|| "GR2 → glTF/GLB").style(|s| {
                 s.font_size(13.0)
                     .font_weight(Weight::SEMIBOLD)
-                    .color(Color::rgb8(80, 80, 80))
+                    .color(colors().text_secondary)
             }),
             empty().style(|s| s.flex_grow(1.0)),
             format_toggle_button("GLB", true, use_glb),
@@ -88,10 +91,59 @@ fn gr2_to_gltf_group(
                 select_and_convert_gr2(state2.clone(), config2.clone(), to_glb, true);
             },
         ),
+        // Convert to GLB and open the result in the preview window
+        operation_button("👁 Convert & Preview", move || {
+            convert_and_preview(state3.clone(), config3.clone());
+        }),
+        last_output_preview(state),
     ))
     .style(|s| card_style(s).flex_grow(1.0).flex_basis(0.0).gap(8.0))
 }
 
+/// Preview button for the last single-file conversion, once there is one
+fn last_output_preview(state: Gr2State) -> impl IntoView {
+    let last_output = state.last_output;
+    let status = state.status_message;
+
+    dyn_container(
+        move || last_output.get(),
+        move |output| match output {
+            Some(path) => {
+                let name = std::path::Path::new(&path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                button(label(move || format!("👁 Preview {}", name)))
+                    .action(move || preview_model(&path, status))
+                    .style(|s| {
+                        s.width_full()
+                            .padding_vert(10.0)
+                            .padding_horiz(16.0)
+                            .background(colors().accent_bg)
+                            .color(colors().accent)
+                            .border(1.0)
+                            .border_color(colors().accent)
+                            .border_radius(6.0)
+                            .hover(|s| s.background(colors().accent_bg))
+                    })
+                    .into_any()
+            }
+            None => empty().into_any(),
+        },
+    )
+    .style(|s| s.width_full())
+}
+
+/// Pick a GR2, convert it to GLB next to the source and open the result
+fn convert_and_preview(state: Gr2State, config: ConfigState) {
+    state.preview_after_convert.set(true);
+    select_and_convert_gr2(state.clone(), config, true, false);
+    // Nothing started if the file dialog was cancelled
+    if !state.is_converting.get_untracked() {
+        state.preview_after_convert.set(false);
+    }
+}
+
 fn gltf_to_gr2_group(state: Gr2State) -> impl IntoView {
     let state1 = state.clone();
     let state2 = state.clone();
@@ -100,7 +152,7 @@ fn gltf_to_gr2_group(state: Gr2State) -> impl IntoView {
         label(|| "glTF/GLB → GR2").style(|s| {
             s.font_size(13.0)
                 .font_weight(Weight::SEMIBOLD)
-                .color(Color::rgb8(80, 80, 80))
+                .color(colors().text_secondary)
                 .margin_bottom(14.0)
                 .margin_top(4.0)
         }),
@@ -164,16 +216,16 @@ fn format_toggle_button(text: &'static str, value: bool, signal: RwSignal<bool>)
                 .font_size(12.0)
                 .cursor(floem::style::CursorStyle::Pointer);
             if is_selected {
-                s.background(Color::rgb8(66, 133, 244))
-                    .color(Color::WHITE)
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border(1.0)
-                    .border_color(Color::rgb8(66, 133, 244))
+                    .border_color(colors().accent)
             } else {
-                s.background(Color::rgb8(245, 245, 245))
-                    .color(Color::rgb8(100, 100, 100))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_secondary)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .hover(|s| s.background(Color::rgb8(235, 235, 235)))
+                    .border_color(colors().border_strong)
+                    .hover(|s| s.background(colors().bg_hover))
             }
         })
 }
@@ -190,13 +242,13 @@ fn dynamic_operation_button(
             s.width_full()
                 .padding_vert(10.0)
                 .padding_horiz(16.0)
-                .background(Color::rgb8(245, 245, 245))
+                .background(colors().bg_elevated)
                 .border(1.0)
-                .border_color(Color::rgb8(200, 200, 200))
+                .border_color(colors().border_strong)
                 .border_radius(6.0)
                 .hover(|s| {
-                    s.background(Color::rgb8(230, 230, 230))
-                        .border_color(Color::rgb8(180, 180, 180))
+                    s.background(colors().bg_hover)
+                        .border_color(colors().border_strong)
                 })
         })
 }
@@ -394,7 +446,7 @@ fn bundle_options_panel(
         .style(|s| {
             s.font_size(13.0)
                 .font_weight(Weight::SEMIBOLD)
-                .color(Color::rgb8(80, 80, 80))
+                .color(colors().text_secondary)
                 .margin_bottom(8.0)
         }),
         // Checkboxes in a row
@@ -411,11 +463,7 @@ fn bundle_options_panel(
             move |show_warning| {
                 if show_warning {
                     label(|| "⚠ BG3 game data path not set in Settings")
-                        .style(|s| {
-                            s.font_size(11.0)
-                                .color(Color::rgb8(180, 80, 30))
-                                .margin_top(8.0)
-                        })
+                        .style(|s| s.font_size(11.0).color(colors().warning).margin_top(8.0))
                         .into_any()
                 } else {
                     empty().into_any()
@@ -426,9 +474,9 @@ fn bundle_options_panel(
     .style(|s| {
         s.width_full()
             .padding(16.0)
-            .background(Color::rgb8(248, 248, 252))
+            .background(colors().bg_surface)
             .border(1.0)
-            .border_color(Color::rgb8(220, 220, 230))
+            .border_color(colors().border)
             .border_radius(8.0)
     })
 }
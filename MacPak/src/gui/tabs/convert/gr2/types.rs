@@ -1,25 +1,30 @@
 //! Types and shared state for GR2 operations
 
 use floem::ext_event::create_ext_action;
-use floem_reactive::{Scope, SignalUpdate};
+use floem_reactive::{Scope, SignalGet, SignalUpdate};
 
+use super::preview::{is_previewable, preview_model};
+use crate::gui::shared::{BatchOperationState, ResultEntry};
 use crate::gui::state::Gr2State;
 
-// Re-export get_shared_progress for use by conversion.rs
-pub use crate::gui::state::gr2::get_shared_progress;
-
 /// Result type for background GR2 operations
 pub enum Gr2Result {
     SingleDone {
         success: bool,
         input_name: String,
         output_name: String,
+        /// Full path of the converted file
+        output_path: Option<String>,
         error: Option<String>,
     },
     BatchDone {
         success_count: usize,
         error_count: usize,
+        /// Files not processed because the batch was cancelled
+        skipped_count: usize,
         results: Vec<String>,
+        /// Output file of each result, for the ones that succeeded
+        outputs: Vec<Option<String>>,
     },
 }
 
@@ -37,26 +42,66 @@ pub fn handle_gr2_result(state: Gr2State, result: Gr2Result) {
             success,
             input_name,
             output_name,
+            output_path,
             error,
         } => {
+            let preview_after = state.preview_after_convert.get_untracked();
+            state.preview_after_convert.set(false);
+            state.is_converting.set(false);
+
             if success {
-                state.add_result(&format!("Converted {} -> {}", input_name, output_name));
+                let mut entry =
+                    ResultEntry::success(format!("Converted {} -> {}", input_name, output_name));
+                if let Some(path) = &output_path {
+                    entry = entry.with_path(path);
+                }
+                state.add_entries(vec![entry]);
                 state.status_message.set("Conversion complete!".to_string());
+
+                if let Some(path) = output_path.filter(|p| is_previewable(p)) {
+                    state.last_output.set(Some(path.clone()));
+                    if preview_after {
+                        preview_model(&path, state.status_message);
+                    }
+                }
             } else {
-                state.add_result(&format!("Error: {}", error.unwrap_or_default()));
+                state.add_entries(vec![ResultEntry::error(format!(
+                    "Error: {}",
+                    error.unwrap_or_default()
+                ))]);
                 state.status_message.set("Conversion failed".to_string());
             }
-            state.is_converting.set(false);
         }
         Gr2Result::BatchDone {
             success_count,
             error_count,
+            skipped_count,
             results,
+            outputs,
         } => {
+            let mut entries: Vec<ResultEntry> = results
+                .into_iter()
+                .zip(outputs)
+                .map(|(message, output)| match output {
+                    Some(path) => ResultEntry::from_message(message).with_path(path),
+                    None => ResultEntry::from_message(message),
+                })
+                .collect();
+            if skipped_count > 0 {
+                entries.push(ResultEntry::from_message(format!(
+                    "Cancelled: {} files not converted",
+                    skipped_count
+                )));
+            }
             // Use batch update to avoid UI freezing with large result sets
-            state.add_results_batch(results);
+            state.add_entries(entries);
 
-            let status = if error_count == 0 {
+            let status = if skipped_count > 0 {
+                format!(
+                    "Cancelled: {} succeeded, {} failed, {} skipped",
+                    success_count, error_count, skipped_count
+                )
+            } else if error_count == 0 {
                 format!("Converted {} files successfully!", success_count)
             } else {
                 format!(
@@ -9,6 +9,7 @@ use rayon::prelude::*;
 
 use super::types::{LsfResult, create_result_sender, get_shared_progress};
 use crate::gui::state::LsfConvertState;
+use crate::workspace::backup::BackupStore;
 
 /// Determine the output extension for a given conversion
 fn output_extension(source_ext: &str, target_format: &str) -> &'static str {
@@ -25,14 +26,20 @@ fn output_extension(source_ext: &str, target_format: &str) -> &'static str {
     }
 }
 
-/// Perform the actual file conversion
+/// Perform the actual file conversion, backing up `dest` if it already exists
+/// in a workspace
 fn do_convert(
     source: &Path,
     dest: &Path,
     source_ext: &str,
     target_format: &str,
+    backup_count: usize,
     progress_cb: &(dyn Fn(&maclarian::converter::ConvertProgress) + Sync + Send),
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(store) = BackupStore::for_file(dest, backup_count) {
+        store.backup(dest)?;
+    }
+
     match (source_ext, target_format) {
         ("lsf", "LSX") => {
             maclarian::converter::lsf_to_lsx_with_progress(source, dest, &|p| progress_cb(p))?;
@@ -111,6 +118,7 @@ pub fn convert_single(state: LsfConvertState, output_dir: String) {
         .to_string_lossy()
         .to_string();
 
+    let backup_count = state.backup_count.get_untracked();
     state.is_converting.set(true);
     state.clear_results();
 
@@ -126,6 +134,7 @@ pub fn convert_single(state: LsfConvertState, output_dir: String) {
             Path::new(&output_str),
             &source_ext,
             &target_format,
+            backup_count,
             &|progress| {
                 shared.update(
                     progress.current,
@@ -171,6 +180,7 @@ pub fn convert_batch(state: LsfConvertState, output_dir: String) {
     let source_ext = source_format.to_lowercase();
     let input_base_dir = state.batch_input_dir.get();
 
+    let backup_count = state.backup_count.get_untracked();
     state.is_converting.set(true);
     state.clear_results();
 
@@ -224,6 +234,7 @@ pub fn convert_batch(state: LsfConvertState, output_dir: String) {
                     &output_path,
                     &source_ext,
                     &target_format,
+                    backup_count,
                     &|_| {}, // No per-file progress for batch
                 );
 
@@ -14,7 +14,7 @@ pub use sections::open_lsf_file;
 use floem::prelude::*;
 use floem::style::Position;
 
-use crate::gui::shared::{header_section, progress_overlay, results_section};
+use crate::gui::shared::{colors, header_section, progress_overlay, results_section};
 use crate::gui::state::LsfConvertState;
 use sections::operations_row;
 
@@ -46,7 +46,7 @@ pub fn lsf_subtab(lsf_state: LsfConvertState) -> impl IntoView {
     .style(|s| {
         s.width_full()
             .height_full()
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
             .position(Position::Relative)
     })
 }
@@ -5,7 +5,7 @@ use floem::prelude::*;
 use floem::text::Weight;
 
 use super::conversion::{convert_batch, convert_single};
-use crate::gui::shared::{card_style, drop_zone};
+use crate::gui::shared::{card_style, colors, drop_zone};
 use crate::gui::state::LsfConvertState;
 
 /// Detect format from file extension
@@ -64,7 +64,7 @@ fn lsf_conversion_group(state: LsfConvertState) -> impl IntoView {
         label(|| "LSF / LSX / LSJ").style(|s| {
             s.font_size(13.0)
                 .font_weight(Weight::SEMIBOLD)
-                .color(Color::rgb8(80, 80, 80))
+                .color(colors().text_secondary)
                 .margin_bottom(8.0)
         }),
         // Source → Target format selector row
@@ -80,7 +80,7 @@ fn lsf_conversion_group(state: LsfConvertState) -> impl IntoView {
             label(|| "→").style(|s| {
                 s.font_size(16.0)
                     .font_weight(Weight::BOLD)
-                    .color(Color::rgb8(120, 120, 120))
+                    .color(colors().text_muted)
                     .padding_horiz(12.0)
             }),
             // Target format buttons
@@ -121,7 +121,7 @@ fn loca_conversion_group(state: LsfConvertState) -> impl IntoView {
         label(|| "LOCA / XML").style(|s| {
             s.font_size(13.0)
                 .font_weight(Weight::SEMIBOLD)
-                .color(Color::rgb8(80, 80, 80))
+                .color(colors().text_secondary)
                 .margin_bottom(8.0)
         }),
         // Source → Target format selector row
@@ -136,7 +136,7 @@ fn loca_conversion_group(state: LsfConvertState) -> impl IntoView {
             label(|| "→").style(|s| {
                 s.font_size(16.0)
                     .font_weight(Weight::BOLD)
-                    .color(Color::rgb8(120, 120, 120))
+                    .color(colors().text_muted)
                     .padding_horiz(12.0)
             }),
             // Target format buttons
@@ -220,16 +220,16 @@ fn format_source_button(
                 .font_size(12.0)
                 .cursor(floem::style::CursorStyle::Pointer);
             if is_selected {
-                s.background(Color::rgb8(66, 133, 244))
-                    .color(Color::WHITE)
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border(1.0)
-                    .border_color(Color::rgb8(66, 133, 244))
+                    .border_color(colors().accent)
             } else {
-                s.background(Color::rgb8(245, 245, 245))
-                    .color(Color::rgb8(100, 100, 100))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_secondary)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .hover(|s| s.background(Color::rgb8(235, 235, 235)))
+                    .border_color(colors().border_strong)
+                    .hover(|s| s.background(colors().bg_hover))
             }
         })
 }
@@ -257,24 +257,24 @@ fn format_target_button(
                 .font_size(12.0);
             if is_disabled {
                 // Grayed out — same as source format
-                s.background(Color::rgb8(240, 240, 240))
-                    .color(Color::rgb8(190, 190, 190))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_disabled)
                     .border(1.0)
-                    .border_color(Color::rgb8(220, 220, 220))
+                    .border_color(colors().border)
                     .cursor(floem::style::CursorStyle::Default)
             } else if is_selected {
-                s.background(Color::rgb8(66, 133, 244))
-                    .color(Color::WHITE)
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border(1.0)
-                    .border_color(Color::rgb8(66, 133, 244))
+                    .border_color(colors().accent)
                     .cursor(floem::style::CursorStyle::Pointer)
             } else {
-                s.background(Color::rgb8(245, 245, 245))
-                    .color(Color::rgb8(100, 100, 100))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_secondary)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .cursor(floem::style::CursorStyle::Pointer)
-                    .hover(|s| s.background(Color::rgb8(235, 235, 235)))
+                    .hover(|s| s.background(colors().bg_hover))
             }
         })
 }
@@ -295,13 +295,13 @@ fn lsf_dynamic_button(
         s.width_full()
             .padding_vert(10.0)
             .padding_horiz(16.0)
-            .background(Color::rgb8(245, 245, 245))
+            .background(colors().bg_elevated)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(6.0)
             .hover(|s| {
-                s.background(Color::rgb8(230, 230, 230))
-                    .border_color(Color::rgb8(180, 180, 180))
+                s.background(colors().bg_hover)
+                    .border_color(colors().border_strong)
             })
     })
 }
@@ -58,7 +58,7 @@ pub fn subtab_bar(active_subtab: RwSignal<usize>) -> impl IntoView {
             .justify_center()
             .background(colors.bg_surface)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors.border)
     })
 }
 
@@ -78,12 +78,15 @@ fn subtab_button(
                 .border_radius(4.0)
                 .cursor(floem::style::CursorStyle::Pointer);
 
+            let colors = theme_signal()
+                .map(|t| ThemeColors::for_theme(t.get().effective()))
+                .unwrap_or_else(ThemeColors::dark);
             if is_active {
-                s.background(Color::rgb8(66, 133, 244)).color(Color::WHITE)
+                s.background(colors.accent).color(colors.text_on_accent)
             } else {
                 s.background(Color::TRANSPARENT)
-                    .color(Color::rgb8(100, 100, 100))
-                    .hover(|s| s.background(Color::rgb8(235, 235, 235)))
+                    .color(colors.text_secondary)
+                    .hover(move |s| s.background(colors.bg_hover))
             }
         })
         .action(move || {
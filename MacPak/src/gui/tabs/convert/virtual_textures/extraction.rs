@@ -6,31 +6,58 @@ use floem_reactive::{SignalGet, SignalUpdate};
 
 use walkdir::WalkDir;
 
-use super::types::{VtResult, create_result_sender, get_shared_progress};
+use super::types::{VtResult, create_result_sender};
 use crate::gui::state::{ConfigState, VirtualTexturesState};
 use maclarian::virtual_texture::{
-    extract_batch as vt_extract_batch, extract_by_gtex, extract_gts_file,
+    extract_batch_filtered, extract_by_gtex, extract_gts_textures, list_gts,
 };
 
-/// Extract textures from a single GTS file
+/// Select a GTS file and list its textures, all checked, for extraction
+pub fn load_gts_textures(state: VirtualTexturesState, gts_path: String) {
+    state.clear_textures();
+    match list_gts(&gts_path) {
+        Ok(info) => {
+            let textures: Vec<String> = info.page_files.into_iter().map(|p| p.filename).collect();
+            state
+                .status_message
+                .set(format!("{} textures found", textures.len()));
+            state
+                .selected_textures
+                .set(textures.iter().cloned().collect());
+            state.gts_textures.set(textures);
+            state.gts_file.set(Some(gts_path));
+        }
+        Err(e) => {
+            state.add_result(&format!("Error reading {}: {}", gts_path, e));
+            state.gts_file.set(None);
+        }
+    }
+}
+
+/// Extract textures from a single GTS file. When a texture list was loaded
+/// for it, only the checked textures are extracted.
 pub fn extract_single(state: VirtualTexturesState, _game_data_path: String) {
     let gts_path = match state.gts_file.get() {
         Some(path) => path,
         None => return,
     };
+    let selection = if state.gts_textures.get_untracked().is_empty() {
+        None
+    } else {
+        Some(state.selected_textures.get_untracked())
+    };
 
     let output_dir = state.batch_output_dir.get();
     let convert_to_png = state.convert_to_png.get_untracked();
+    let options = state.extract_options();
 
     state.is_extracting.set(true);
     state.status_message.set("Extracting...".to_string());
 
+    let progress = state.progress.sender();
     let send_result = create_result_sender(state.clone());
 
     std::thread::spawn(move || {
-        let progress = get_shared_progress();
-        progress.reset();
-
         let gts_name = Path::new(&gts_path)
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
@@ -40,9 +67,11 @@ pub fn extract_single(state: VirtualTexturesState, _game_data_path: String) {
 
         // Use maclarian's extraction function
         let output_path = output_dir.as_ref().map(|s| Path::new(s.as_str()));
-        let result = extract_gts_file(
+        let result = extract_gts_textures(
             &gts_path,
             output_path,
+            |name| selection.as_ref().is_none_or(|s| s.contains(name)),
+            &options,
             |p: &maclarian::virtual_texture::VTexProgress| {
                 let desc = p.current_file.as_deref().unwrap_or(p.phase.as_str());
                 progress.update(p.current, p.total, desc);
@@ -78,7 +107,7 @@ pub fn extract_single(state: VirtualTexturesState, _game_data_path: String) {
                     }
                 }
 
-                progress.update(1, 1, "Complete");
+                progress.finish();
                 let texture_info = if png_converted > 0 {
                     format!(
                         "{} (converted {} to PNG)",
@@ -91,14 +120,17 @@ pub fn extract_single(state: VirtualTexturesState, _game_data_path: String) {
                     success: true,
                     gts_name: format!("{} - {} textures", gts_name, texture_info),
                     texture_count: extract_result.texture_count,
+                    textures: extract_result.textures,
                     error: None,
                 });
             }
             Err(e) => {
+                progress.fail(&e.to_string());
                 send_result(VtResult::SingleDone {
                     success: false,
                     gts_name,
                     texture_count: 0,
+                    textures: Vec::new(),
                     error: Some(e.to_string()),
                 });
             }
@@ -115,25 +147,31 @@ pub fn extract_batch(state: VirtualTexturesState, _game_data_path: String) {
 
     let output_dir = state.batch_output_dir.get();
     let convert_to_png = state.convert_to_png.get_untracked();
+    let name_filter = state.batch_name_filter.get_untracked();
+    let options = state.extract_options();
 
+    // Reset before starting so a previous cancel doesn't carry over
+    state.progress.reset();
     state.is_extracting.set(true);
     state.status_message.set("Extracting...".to_string());
 
+    let progress = state.progress.sender();
     let send_result = create_result_sender(state.clone());
 
     std::thread::spawn(move || {
-        let progress = get_shared_progress();
-        progress.reset();
+        let cancel = progress.allow_cancel();
 
         // Convert file list to PathBuf
         let gts_files: Vec<PathBuf> = files.iter().map(PathBuf::from).collect();
         let output_path = output_dir.as_ref().map(|s| Path::new(s.as_str()));
-        let total = gts_files.len();
 
         // Use maclarian's batch extraction function
-        let result = vt_extract_batch(
+        let result = extract_batch_filtered(
             &gts_files,
             output_path,
+            Some(name_filter.as_str()),
+            &options,
+            &cancel,
             |p: &maclarian::virtual_texture::VTexProgress| {
                 let desc = p.current_file.as_deref().unwrap_or(p.phase.as_str());
                 progress.update(p.current, p.total, desc);
@@ -142,7 +180,7 @@ pub fn extract_batch(state: VirtualTexturesState, _game_data_path: String) {
 
         // Convert to PNG if requested (scan output directory for DDS files)
         let mut png_converted = 0;
-        if convert_to_png {
+        if convert_to_png && !result.cancelled {
             if let Some(out_dir) = output_path {
                 if let Ok(entries) = std::fs::read_dir(out_dir) {
                     for entry in entries.filter_map(|e| e.ok()) {
@@ -162,17 +200,24 @@ pub fn extract_batch(state: VirtualTexturesState, _game_data_path: String) {
             }
         }
 
-        progress.update(total, total, "Complete");
+        progress.finish();
 
         let mut results = result.results.clone();
         if png_converted > 0 {
             results.push(format!("Converted {} DDS files to PNG", png_converted));
         }
+        if result.cancelled {
+            results.push(format!(
+                "Cancelled: {} GTS files not extracted",
+                gts_files.len() - result.results.len()
+            ));
+        }
 
         send_result(VtResult::BatchDone {
             success_count: result.success_count,
             error_count: result.error_count,
             texture_count: result.texture_count,
+            cancelled: result.cancelled,
             results,
         });
     });
@@ -181,17 +226,21 @@ pub fn extract_batch(state: VirtualTexturesState, _game_data_path: String) {
 /// Extract virtual textures from a PAK file
 pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_dir: String) {
     let convert_to_png = state.convert_to_png.get_untracked();
+    let name_filter = state.batch_name_filter.get_untracked();
+    let options = state.extract_options();
 
+    // Reset before starting so a previous cancel doesn't carry over
+    state.progress.reset();
     state.is_extracting.set(true);
     state
         .status_message
         .set("Extracting VT files from PAK...".to_string());
 
+    let progress = state.progress.sender();
     let send_result = create_result_sender(state.clone());
 
     std::thread::spawn(move || {
-        let progress = get_shared_progress();
-        progress.reset();
+        let cancel = progress.allow_cancel();
         progress.update(0, 1, "Listing PAK contents...");
 
         // List PAK contents and filter for VT files
@@ -202,6 +251,7 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
                     success_count: 0,
                     error_count: 1,
                     texture_count: 0,
+                    cancelled: false,
                     results: vec![format!("Failed to list PAK: {}", e)],
                 });
                 return;
@@ -221,19 +271,20 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
                 success_count: 0,
                 error_count: 0,
                 texture_count: 0,
+                cancelled: false,
                 results: vec!["No .gts/.gtp files found in PAK".to_string()],
             });
             return;
         }
 
         // Create temp dir for extraction
-        let temp_dir =
-            std::env::temp_dir().join(format!("macpak_vt_pak_{}", std::process::id()));
+        let temp_dir = std::env::temp_dir().join(format!("macpak_vt_pak_{}", std::process::id()));
         if let Err(e) = std::fs::create_dir_all(&temp_dir) {
             send_result(VtResult::BatchDone {
                 success_count: 0,
                 error_count: 1,
                 texture_count: 0,
+                cancelled: false,
                 results: vec![format!("Failed to create temp dir: {}", e)],
             });
             return;
@@ -259,6 +310,7 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
                 success_count: 0,
                 error_count: 1,
                 texture_count: 0,
+                cancelled: false,
                 results: vec![format!("Failed to extract from PAK: {}", e)],
             });
             return;
@@ -285,6 +337,7 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
                 success_count: 0,
                 error_count: 0,
                 texture_count: 0,
+                cancelled: false,
                 results: vec!["No .gts files found after PAK extraction".to_string()],
             });
             return;
@@ -292,9 +345,12 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
 
         // Run VT batch extraction on the loose files
         let output_path = Path::new(&output_dir);
-        let result = vt_extract_batch(
+        let result = extract_batch_filtered(
             &gts_files,
             Some(output_path),
+            Some(name_filter.as_str()),
+            &options,
+            &cancel,
             |p: &maclarian::virtual_texture::VTexProgress| {
                 let desc = p.current_file.as_deref().unwrap_or(p.phase.as_str());
                 progress.update(p.current, p.total, desc);
@@ -303,7 +359,7 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
 
         // Convert to PNG if requested (walk output dir recursively for DDS files)
         let mut png_converted = 0;
-        if convert_to_png {
+        if convert_to_png && !result.cancelled {
             for entry in WalkDir::new(output_path)
                 .follow_links(true)
                 .into_iter()
@@ -326,21 +382,24 @@ pub fn extract_from_pak(state: VirtualTexturesState, pak_path: String, output_di
         // Clean up temp dir
         let _ = std::fs::remove_dir_all(&temp_dir);
 
-        progress.update(
-            result.success_count + result.error_count,
-            result.success_count + result.error_count,
-            "Complete",
-        );
+        progress.finish();
 
         let mut results = result.results.clone();
         if png_converted > 0 {
             results.push(format!("Converted {} DDS files to PNG", png_converted));
         }
+        if result.cancelled {
+            results.push(format!(
+                "Cancelled: {} GTS files not extracted",
+                gts_files.len() - result.results.len()
+            ));
+        }
 
         send_result(VtResult::BatchDone {
             success_count: result.success_count,
             error_count: result.error_count,
             texture_count: result.texture_count,
+            cancelled: result.cancelled,
             results,
         });
     });
@@ -378,16 +437,15 @@ pub fn convert_dds_to_png_file(state: VirtualTexturesState) {
             .status_message
             .set("Converting DDS → PNG...".to_string());
 
+        let progress = state.progress.sender();
         let send_result = create_result_sender(state);
 
         std::thread::spawn(move || {
-            let progress = get_shared_progress();
-            progress.reset();
             progress.update(0, 1, &input_name);
 
             match maclarian::converter::convert_dds_to_png(&file, &png_path) {
                 Ok(()) => {
-                    progress.update(1, 1, "Complete");
+                    progress.finish();
                     send_result(VtResult::DdsConvertDone {
                         success: true,
                         input_name,
@@ -396,6 +454,7 @@ pub fn convert_dds_to_png_file(state: VirtualTexturesState) {
                     });
                 }
                 Err(e) => {
+                    progress.fail(&e.to_string());
                     send_result(VtResult::DdsConvertDone {
                         success: false,
                         input_name,
@@ -442,16 +501,15 @@ pub fn convert_png_to_dds_file(state: VirtualTexturesState) {
             .status_message
             .set(format!("Converting PNG → DDS ({:?})...", format));
 
+        let progress = state.progress.sender();
         let send_result = create_result_sender(state);
 
         std::thread::spawn(move || {
-            let progress = get_shared_progress();
-            progress.reset();
             progress.update(0, 1, &input_name);
 
             match maclarian::converter::convert_png_to_dds_with_format(&file, &dds_path, format) {
                 Ok(()) => {
-                    progress.update(1, 1, "Complete");
+                    progress.finish();
                     send_result(VtResult::DdsConvertDone {
                         success: true,
                         input_name,
@@ -460,6 +518,7 @@ pub fn convert_png_to_dds_file(state: VirtualTexturesState) {
                     });
                 }
                 Err(e) => {
+                    progress.fail(&e.to_string());
                     send_result(VtResult::DdsConvertDone {
                         success: false,
                         input_name,
@@ -487,16 +546,18 @@ pub fn convert_dds_png_batch(state: VirtualTexturesState) {
             .working_dir
             .set(Some(dir.to_string_lossy().to_string()));
 
+        // Reset before starting so a previous cancel doesn't carry over
+        state.progress.reset();
         state.is_extracting.set(true);
         state
             .status_message
             .set("Batch converting DDS ↔ PNG...".to_string());
 
+        let progress = state.progress.sender();
         let send_result = create_result_sender(state);
 
         std::thread::spawn(move || {
-            let progress = get_shared_progress();
-            progress.reset();
+            let cancel = progress.allow_cancel();
 
             let mut dds_files = Vec::new();
             let mut png_files = Vec::new();
@@ -525,6 +586,9 @@ pub fn convert_dds_png_batch(state: VirtualTexturesState) {
 
             // DDS → PNG
             for (i, dds_path) in dds_files.iter().enumerate() {
+                if cancel.is_cancelled() {
+                    break;
+                }
                 let name = dds_path
                     .file_name()
                     .map(|n| n.to_string_lossy().to_string())
@@ -546,6 +610,9 @@ pub fn convert_dds_png_batch(state: VirtualTexturesState) {
 
             // PNG → DDS
             for (i, png_path) in png_files.iter().enumerate() {
+                if cancel.is_cancelled() {
+                    break;
+                }
                 let name = png_path
                     .file_name()
                     .map(|n| n.to_string_lossy().to_string())
@@ -567,11 +634,19 @@ pub fn convert_dds_png_batch(state: VirtualTexturesState) {
                 }
             }
 
-            progress.update(total, total, "Complete");
+            let cancelled = success_count + error_count < total;
+            if cancelled {
+                results.push(format!(
+                    "Cancelled: {} files not converted",
+                    total - success_count - error_count
+                ));
+            }
+            progress.finish();
 
             send_result(VtResult::DdsBatchDone {
                 success_count,
                 error_count,
+                cancelled,
                 results,
             });
         });
@@ -621,16 +696,18 @@ pub fn extract_by_gtex_hash(state: VirtualTexturesState, config: ConfigState) {
 
     // Close dialog and start extraction
     state.show_gtex_dialog.set(false);
+    // Reset before starting so a previous cancel doesn't carry over
+    state.progress.reset();
     state.is_extracting.set(true);
     state
         .status_message
         .set(format!("Extracting {} GTex hash(es)...", hashes.len()));
 
+    let progress = state.progress.sender();
     let send_result = create_result_sender(state);
 
     std::thread::spawn(move || {
-        let progress = get_shared_progress();
-        progress.reset();
+        let cancel = progress.allow_cancel();
 
         let total = hashes.len();
         let mut success_count = 0;
@@ -638,7 +715,13 @@ pub fn extract_by_gtex_hash(state: VirtualTexturesState, config: ConfigState) {
         let mut texture_count = 0;
         let mut results = Vec::new();
 
+        let mut cancelled = false;
         for (i, hash) in hashes.iter().enumerate() {
+            if cancel.is_cancelled() {
+                results.push(format!("Cancelled: {} hashes not extracted", total - i));
+                cancelled = true;
+                break;
+            }
             progress.update(i, total, hash);
 
             match extract_by_gtex(hash, &search_paths, &output_dir) {
@@ -663,7 +746,7 @@ pub fn extract_by_gtex_hash(state: VirtualTexturesState, config: ConfigState) {
         }
 
         // Convert to PNG if requested
-        if convert_to_png {
+        if convert_to_png && !cancelled {
             let mut png_converted = 0;
             if let Ok(entries) = std::fs::read_dir(&output_dir) {
                 for entry in entries.filter_map(|e| e.ok()) {
@@ -683,12 +766,13 @@ pub fn extract_by_gtex_hash(state: VirtualTexturesState, config: ConfigState) {
             }
         }
 
-        progress.update(total, total, "Complete");
+        progress.finish();
 
         send_result(VtResult::BatchDone {
             success_count,
             error_count,
             texture_count,
+            cancelled,
             results,
         });
     });
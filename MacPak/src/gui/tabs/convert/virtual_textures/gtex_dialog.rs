@@ -5,7 +5,7 @@ use floem::text::Weight;
 use floem::views::{PlaceholderTextClass, text_input};
 
 use super::extraction::extract_by_gtex_hash;
-use crate::gui::shared::checkbox_option;
+use crate::gui::shared::{checkbox_option, colors};
 use crate::gui::state::{ConfigState, VirtualTexturesState};
 
 /// Dialog overlay for extracting textures by GTex hash
@@ -42,7 +42,7 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                     // Hash input
                     label(|| "GTex Hash(es)").style(|s| {
                         s.font_size(12.0)
-                            .color(Color::rgb8(80, 80, 80))
+                            .color(colors().text_secondary)
                             .margin_bottom(4.0)
                     }),
                     text_input(hash_input)
@@ -52,20 +52,18 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                                 .height(32.0)
                                 .padding_horiz(8.0)
                                 .border(1.0)
-                                .border_color(Color::rgb8(200, 200, 200))
+                                .border_color(colors().border_strong)
                                 .border_radius(4.0)
                                 .font_size(13.0)
-                                .background(Color::WHITE)
+                                .background(colors().bg_base)
                                 .margin_bottom(16.0)
-                                .class(PlaceholderTextClass, |s| {
-                                    s.color(Color::rgb8(120, 120, 120))
-                                })
+                                .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
                         }),
                     // Search directories section
                     label(|| "Search Directories").style(|s| {
                         s.font_size(12.0)
                             .font_weight(Weight::SEMIBOLD)
-                            .color(Color::rgb8(80, 80, 80))
+                            .color(colors().text_secondary)
                             .margin_bottom(4.0)
                     }),
                     // Game data path (auto-included)
@@ -75,16 +73,14 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                             if path.is_empty() {
                                 label(|| "Game data path not set (configure in Settings)")
                                     .style(|s| {
-                                        s.font_size(11.0)
-                                            .color(Color::rgb8(180, 80, 30))
-                                            .margin_bottom(4.0)
+                                        s.font_size(11.0).color(colors().warning).margin_bottom(4.0)
                                     })
                                     .into_any()
                             } else {
                                 label(move || format!("Game data: {}", path))
                                     .style(|s| {
                                         s.font_size(11.0)
-                                            .color(Color::rgb8(100, 100, 100))
+                                            .color(colors().text_secondary)
                                             .margin_bottom(4.0)
                                             .max_width_full()
                                             .text_overflow(floem::style::TextOverflow::Ellipsis)
@@ -108,7 +104,7 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                             h_stack((
                                 label(move || p_display.clone()).style(|s| {
                                     s.font_size(11.0)
-                                        .color(Color::rgb8(100, 100, 100))
+                                        .color(colors().text_secondary)
                                         .flex_grow(1.0)
                                         .min_width(0.0)
                                         .text_overflow(floem::style::TextOverflow::Ellipsis)
@@ -125,9 +121,9 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                                         s.padding_vert(2.0)
                                             .padding_horiz(6.0)
                                             .font_size(10.0)
-                                            .background(Color::rgb8(240, 240, 240))
+                                            .background(colors().bg_elevated)
                                             .border(1.0)
-                                            .border_color(Color::rgb8(200, 200, 200))
+                                            .border_color(colors().border_strong)
                                             .border_radius(3.0)
                                             .cursor(floem::style::CursorStyle::Pointer)
                                     }),
@@ -152,9 +148,9 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                             s.padding_vert(6.0)
                                 .padding_horiz(12.0)
                                 .font_size(12.0)
-                                .background(Color::rgb8(240, 240, 240))
+                                .background(colors().bg_elevated)
                                 .border(1.0)
-                                .border_color(Color::rgb8(200, 200, 200))
+                                .border_color(colors().border_strong)
                                 .border_radius(4.0)
                                 .margin_top(4.0)
                                 .margin_bottom(16.0)
@@ -172,9 +168,9 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                                 s.padding_vert(8.0)
                                     .padding_horiz(20.0)
                                     .margin_right(8.0)
-                                    .background(Color::rgb8(240, 240, 240))
+                                    .background(colors().bg_elevated)
                                     .border(1.0)
-                                    .border_color(Color::rgb8(200, 200, 200))
+                                    .border_color(colors().border_strong)
                                     .border_radius(4.0)
                             }),
                         button("Extract")
@@ -186,12 +182,12 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                                 let disabled = hash_input.get().trim().is_empty();
                                 let s = s.padding_vert(8.0).padding_horiz(20.0).border_radius(4.0);
                                 if disabled {
-                                    s.background(Color::rgb8(200, 200, 200))
-                                        .color(Color::rgb8(150, 150, 150))
+                                    s.background(colors().bg_selected)
+                                        .color(colors().text_muted)
                                 } else {
-                                    s.background(Color::rgb8(33, 150, 243))
-                                        .color(Color::WHITE)
-                                        .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                                    s.background(colors().accent)
+                                        .color(colors().text_on_accent)
+                                        .hover(|s| s.background(colors().accent_hover))
                                 }
                             }),
                     ))
@@ -199,9 +195,9 @@ pub fn gtex_dialog_overlay(state: VirtualTexturesState, config: ConfigState) ->
                 ))
                 .style(|s| {
                     s.padding(24.0)
-                        .background(Color::WHITE)
+                        .background(colors().bg_base)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(8.0)
                         .width(600.0)
                         .box_shadow_blur(20.0)
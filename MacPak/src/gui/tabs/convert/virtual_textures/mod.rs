@@ -2,12 +2,14 @@
 //!
 //! Extract individual DDS textures from GTS/GTP virtual texture files:
 //! - Single file extraction with operation buttons
+//! - Per-texture selection for GTS files
 //! - Batch extraction of directories
 //! - Drag & drop support
 
 mod extraction;
 mod gtex_dialog;
 mod sections;
+mod texture_select;
 pub mod types;
 
 pub use sections::open_gts_file;
@@ -15,9 +17,10 @@ pub use sections::open_gts_file;
 use floem::prelude::*;
 use floem::style::Position;
 
-use crate::gui::shared::{header_section, progress_overlay, results_section};
+use crate::gui::shared::{colors, header_section, progress_overlay, results_section};
 use crate::gui::state::{AppState, ConfigState, VirtualTexturesState};
 use sections::operations_row;
+use texture_select::texture_select_panel;
 
 pub fn virtual_textures_tab(
     _app_state: AppState,
@@ -26,6 +29,7 @@ pub fn virtual_textures_tab(
 ) -> impl IntoView {
     let state = vt_state.clone();
     let config_for_dialog = config_state.clone();
+    let config_for_textures = config_state.clone();
 
     v_stack((
         // Header with title and status message (using shared component)
@@ -34,6 +38,8 @@ pub fn virtual_textures_tab(
         v_stack((
             // Operations row
             operations_row(vt_state.clone(), config_state),
+            // Textures of the selected GTS
+            texture_select_panel(vt_state.clone(), config_for_textures),
             // Results area (using shared component)
             results_section(vt_state.clone()),
         ))
@@ -54,7 +60,7 @@ pub fn virtual_textures_tab(
     .style(|s| {
         s.width_full()
             .height_full()
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
             .position(Position::Relative)
     })
 }
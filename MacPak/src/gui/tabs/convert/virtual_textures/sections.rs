@@ -4,13 +4,14 @@ use floem::event::Event;
 use floem::prelude::*;
 use floem::text::Weight;
 use maclarian::converter::DdsFormat;
+use maclarian::virtual_texture::VTexNaming;
 use walkdir::WalkDir;
 
 use super::extraction::{
     convert_dds_png_batch, convert_dds_to_png_file, convert_png_to_dds_file, extract_batch,
-    extract_from_pak, extract_single,
+    extract_from_pak, extract_single, load_gts_textures,
 };
-use crate::gui::shared::{card_style, drop_zone, operation_button};
+use crate::gui::shared::{card_style, colors, drop_zone, operation_button};
 use crate::gui::state::{ConfigState, VirtualTexturesState};
 
 /// Main operations row with columns
@@ -39,16 +40,24 @@ fn extraction_group(state: VirtualTexturesState, config: ConfigState) -> impl In
             label(|| "Extract Textures").style(|s| {
                 s.font_size(13.0)
                     .font_weight(Weight::SEMIBOLD)
-                    .color(Color::rgb8(80, 80, 80))
+                    .color(colors().text_secondary)
             }),
             empty().style(|s| s.flex_grow(1.0)),
             // Layer selector
-            label(|| "Layer:").style(|s| s.font_size(11.0).color(Color::rgb8(100, 100, 100))),
+            label(|| "Layer:").style(|s| s.font_size(11.0).color(colors().text_secondary)),
             layer_toggle_button("All", None, state.clone()),
             layer_toggle_button("0", Some(0), state.clone()),
             layer_toggle_button("1", Some(1), state.clone()),
             layer_toggle_button("2", Some(2), state.clone()),
         ))
+        .style(|s| s.width_full().gap(4.0).items_center()),
+        // Output naming: MacPak folders or the names LSLib's tools write
+        h_stack((
+            empty().style(|s| s.flex_grow(1.0)),
+            label(|| "Naming:").style(|s| s.font_size(11.0).color(colors().text_secondary)),
+            format_toggle_button("MacPak", VTexNaming::MacPak, state.vt_naming),
+            format_toggle_button("LSLib", VTexNaming::LsLib, state.vt_naming),
+        ))
         .style(|s| s.width_full().gap(4.0).items_center().margin_bottom(8.0)),
         // Extract single file
         operation_button("Extract GTS/GTP File", move || {
@@ -58,6 +67,19 @@ fn extraction_group(state: VirtualTexturesState, config: ConfigState) -> impl In
         operation_button("Batch Extract Directory", move || {
             select_and_extract_batch(state2.clone(), config2.clone());
         }),
+        // Texture name filter for batch and PAK extraction
+        text_input(state.batch_name_filter)
+            .placeholder("Batch filter: texture name contains...")
+            .style(|s| {
+                s.width_full()
+                    .height(26.0)
+                    .padding_horiz(8.0)
+                    .border(1.0)
+                    .border_color(colors().border_strong)
+                    .border_radius(4.0)
+                    .font_size(12.0)
+                    .background(colors().bg_base)
+            }),
         // Extract by GTex hash
         operation_button("Extract by GTex Hash", move || {
             state3.show_gtex_dialog.set(true);
@@ -78,10 +100,10 @@ fn dds_png_group(state: VirtualTexturesState) -> impl IntoView {
             label(|| "DDS \u{2194} PNG").style(|s| {
                 s.font_size(13.0)
                     .font_weight(Weight::SEMIBOLD)
-                    .color(Color::rgb8(80, 80, 80))
+                    .color(colors().text_secondary)
             }),
             empty().style(|s| s.flex_grow(1.0)),
-            label(|| "Format:").style(|s| s.font_size(11.0).color(Color::rgb8(100, 100, 100))),
+            label(|| "Format:").style(|s| s.font_size(11.0).color(colors().text_secondary)),
             format_toggle_button("BC1", DdsFormat::BC1, dds_format),
             format_toggle_button("BC3", DdsFormat::BC3, dds_format),
             format_toggle_button("RGBA", DdsFormat::Rgba, dds_format),
@@ -118,8 +140,12 @@ fn vt_drop_zone(state: VirtualTexturesState, config: ConfigState) -> impl IntoVi
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            if path_lower.ends_with(".gts") || path_lower.ends_with(".gtp") {
+            if path_lower.ends_with(".gts") {
+                state_for_drop.from_pak.set(false);
+                load_gts_textures(state_for_drop.clone(), path);
+            } else if path_lower.ends_with(".gtp") {
                 state_for_drop.from_pak.set(false);
+                state_for_drop.clear_textures();
                 state_for_drop.add_result(&format!("Extracting: {}", file_name));
                 state_for_drop.gts_file.set(Some(path));
                 let game_data = config_for_drop.bg3_data_path.get_untracked();
@@ -246,25 +272,25 @@ fn layer_toggle_button(
                 .font_size(11.0)
                 .cursor(floem::style::CursorStyle::Pointer);
             if is_selected {
-                s.background(Color::rgb8(33, 150, 243))
-                    .color(Color::WHITE)
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border(1.0)
-                    .border_color(Color::rgb8(33, 150, 243))
+                    .border_color(colors().accent)
             } else {
-                s.background(Color::rgb8(245, 245, 245))
-                    .color(Color::rgb8(100, 100, 100))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_secondary)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .hover(|s| s.background(Color::rgb8(235, 235, 235)))
+                    .border_color(colors().border_strong)
+                    .hover(|s| s.background(colors().bg_hover))
             }
         })
 }
 
-/// Toggle button for DDS format selection
-fn format_toggle_button(
+/// Toggle button for DDS format or output naming selection
+fn format_toggle_button<T: Copy + PartialEq + 'static>(
     text: &'static str,
-    value: DdsFormat,
-    signal: RwSignal<DdsFormat>,
+    value: T,
+    signal: RwSignal<T>,
 ) -> impl IntoView {
     button(text)
         .action(move || signal.set(value))
@@ -277,21 +303,22 @@ fn format_toggle_button(
                 .font_size(11.0)
                 .cursor(floem::style::CursorStyle::Pointer);
             if is_selected {
-                s.background(Color::rgb8(33, 150, 243))
-                    .color(Color::WHITE)
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border(1.0)
-                    .border_color(Color::rgb8(33, 150, 243))
+                    .border_color(colors().accent)
             } else {
-                s.background(Color::rgb8(245, 245, 245))
-                    .color(Color::rgb8(100, 100, 100))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_secondary)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .hover(|s| s.background(Color::rgb8(235, 235, 235)))
+                    .border_color(colors().border_strong)
+                    .hover(|s| s.background(colors().bg_hover))
             }
         })
 }
 
-/// Select a GTS/GTP or PAK file and extract it
+/// Select a GTS/GTP or PAK file. A GTS lists its textures for selection;
+/// GTP and PAK files are extracted straight away.
 fn select_and_extract_single(state: VirtualTexturesState, config: ConfigState) {
     let game_data = config.bg3_data_path.get_untracked();
 
@@ -335,8 +362,14 @@ fn select_and_extract_single(state: VirtualTexturesState, config: ConfigState) {
         } else {
             // Loose GTS/GTP file
             state.from_pak.set(false);
-            state.gts_file.set(Some(file.to_string_lossy().to_string()));
-            extract_single(state, game_data);
+            let path = file.to_string_lossy().to_string();
+            if path.to_lowercase().ends_with(".gts") {
+                load_gts_textures(state, path);
+            } else {
+                state.clear_textures();
+                state.gts_file.set(Some(path));
+                extract_single(state, game_data);
+            }
         }
     }
 }
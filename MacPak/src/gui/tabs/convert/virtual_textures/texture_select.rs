@@ -0,0 +1,203 @@
+//! Texture list of the selected GTS file: check the textures to extract
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use floem::prelude::*;
+use floem::text::Weight;
+use floem::views::{VirtualDirection, VirtualItemSize, virtual_list};
+use im::Vector as ImVector;
+use maclarian::virtual_texture::get_subfolder_name;
+
+use super::extraction::extract_single;
+use crate::gui::shared::{card_style, colors};
+use crate::gui::state::{ConfigState, VirtualTexturesState};
+
+const ROW_HEIGHT: f64 = 26.0;
+
+/// Checkbox list of the textures in the selected GTS, with a name filter.
+/// Hidden until a GTS file has been loaded.
+pub fn texture_select_panel(state: VirtualTexturesState, config: ConfigState) -> impl IntoView {
+    let textures = state.gts_textures;
+    let selected = state.selected_textures;
+    let filter = state.texture_filter;
+    let gts_file = state.gts_file;
+    let is_extracting = state.is_extracting;
+    let state_extract = state.clone();
+    let state_close = state;
+
+    let filtered = move || {
+        let query = filter.get().to_lowercase();
+        textures
+            .get()
+            .into_iter()
+            .filter(|t| query.is_empty() || t.to_lowercase().contains(&query))
+            .collect::<ImVector<_>>()
+    };
+
+    v_stack((
+        h_stack((
+            label(move || {
+                let gts_name = gts_file
+                    .get()
+                    .and_then(|p| {
+                        Path::new(&p)
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                    })
+                    .unwrap_or_default();
+                format!("Textures in {}", gts_name)
+            })
+            .style(|s| {
+                s.font_size(13.0)
+                    .font_weight(Weight::SEMIBOLD)
+                    .color(colors().text_secondary)
+            }),
+            label(move || {
+                let shown = filtered().len();
+                let total = textures.get().len();
+                let checked = selected.get().len();
+                if shown == total {
+                    format!("{} of {} selected", checked, total)
+                } else {
+                    format!("{} selected, {} of {} shown", checked, shown, total)
+                }
+            })
+            .style(|s| s.font_size(11.0).color(colors().text_secondary)),
+            empty().style(|s| s.flex_grow(1.0)),
+            text_input(filter)
+                .placeholder("Filter textures...")
+                .style(|s| {
+                    s.width(180.0)
+                        .height(26.0)
+                        .padding_horiz(8.0)
+                        .border(1.0)
+                        .border_color(colors().border_strong)
+                        .border_radius(4.0)
+                        .font_size(12.0)
+                        .background(colors().bg_base)
+                }),
+            small_button("Select Visible", move || {
+                let visible: HashSet<String> = filtered().into_iter().collect();
+                selected.update(|set| set.extend(visible));
+            }),
+            small_button("Deselect All", move || selected.set(HashSet::new())),
+            small_button("\u{2715}", move || state_close.clear_textures()),
+        ))
+        .style(|s| s.width_full().gap(8.0).items_center()),
+        scroll(
+            virtual_list(
+                VirtualDirection::Vertical,
+                VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+                filtered,
+                |texture: &String| texture.clone(),
+                move |texture| texture_row(texture, selected),
+            )
+            .style(|s| s.flex_col().width_full()),
+        )
+        .scroll_style(|s| s.handle_thickness(8.0))
+        .style(|s| {
+            s.width_full()
+                .max_height(200.0)
+                .border(1.0)
+                .border_color(colors().border)
+                .border_radius(4.0)
+                .background(colors().bg_base)
+        }),
+        h_stack((
+            empty().style(|s| s.flex_grow(1.0)),
+            button("Extract Selected")
+                .action(move || {
+                    let game_data = config.bg3_data_path.get_untracked();
+                    extract_single(state_extract.clone(), game_data);
+                })
+                .disabled(move || selected.with(HashSet::is_empty) || is_extracting.get())
+                .style(move |s| {
+                    let disabled = selected.with(HashSet::is_empty) || is_extracting.get();
+                    let s = s.padding_vert(6.0).padding_horiz(16.0).border_radius(4.0);
+                    if disabled {
+                        s.background(colors().bg_selected)
+                            .color(colors().text_muted)
+                    } else {
+                        s.background(colors().accent)
+                            .color(colors().text_on_accent)
+                            .hover(|s| s.background(colors().accent_hover))
+                    }
+                }),
+        ))
+        .style(|s| s.width_full()),
+    ))
+    .style(move |s| {
+        let s = card_style(s).width_full().gap(8.0);
+        if textures.with(Vec::is_empty) {
+            s.display(floem::style::Display::None)
+        } else {
+            s
+        }
+    })
+}
+
+fn texture_row(texture: String, selected: RwSignal<HashSet<String>>) -> impl IntoView {
+    let for_check = texture.clone();
+    let for_update = texture.clone();
+    let for_click = texture.clone();
+    let name = get_subfolder_name(&texture);
+
+    h_stack((
+        checkbox(move || selected.with(|set| set.contains(&for_check)))
+            .on_update(move |checked| {
+                let texture = for_update.clone();
+                selected.update(|set| {
+                    if checked {
+                        set.insert(texture);
+                    } else {
+                        set.remove(&texture);
+                    }
+                });
+            })
+            .style(|s| s.margin_right(8.0)),
+        h_stack((
+            label(move || name.clone()).style(|s| s.font_size(12.0)),
+            label(move || texture.clone()).style(|s| {
+                s.font_size(11.0)
+                    .color(colors().text_muted)
+                    .margin_left(8.0)
+                    .text_ellipsis()
+                    .min_width(0.0)
+            }),
+        ))
+        .on_click_stop(move |_| {
+            let texture = for_click.clone();
+            selected.update(|set| {
+                if !set.remove(&texture) {
+                    set.insert(texture);
+                }
+            });
+        })
+        .style(|s| {
+            s.flex_grow(1.0)
+                .min_width(0.0)
+                .items_center()
+                .cursor(floem::style::CursorStyle::Pointer)
+        }),
+    ))
+    .style(|s| {
+        s.width_full()
+            .height(ROW_HEIGHT)
+            .padding_horiz(8.0)
+            .items_center()
+            .hover(|s| s.background(colors().bg_elevated))
+    })
+}
+
+fn small_button(text: &'static str, action: impl Fn() + 'static) -> impl IntoView {
+    button(text).action(action).style(|s| {
+        s.padding_vert(4.0)
+            .padding_horiz(8.0)
+            .font_size(11.0)
+            .background(colors().bg_elevated)
+            .border(1.0)
+            .border_color(colors().border_strong)
+            .border_radius(4.0)
+    })
+}
@@ -4,9 +4,7 @@ use floem::ext_event::create_ext_action;
 use floem_reactive::{Scope, SignalUpdate};
 
 use crate::gui::state::VirtualTexturesState;
-
-// Re-export get_shared_progress for use by extraction.rs
-pub use crate::gui::state::virtual_textures::get_shared_progress;
+use maclarian::virtual_texture::get_subfolder_name;
 
 /// Result type for background Virtual Texture operations
 pub enum VtResult {
@@ -14,12 +12,16 @@ pub enum VtResult {
         success: bool,
         gts_name: String,
         texture_count: usize,
+        /// GTP filenames of the extracted textures
+        textures: Vec<String>,
         error: Option<String>,
     },
     BatchDone {
         success_count: usize,
         error_count: usize,
         texture_count: usize,
+        /// Stopped early by the Cancel button
+        cancelled: bool,
         results: Vec<String>,
     },
     DdsConvertDone {
@@ -31,6 +33,8 @@ pub enum VtResult {
     DdsBatchDone {
         success_count: usize,
         error_count: usize,
+        /// Stopped early by the Cancel button
+        cancelled: bool,
         results: Vec<String>,
     },
 }
@@ -49,6 +53,7 @@ pub fn handle_vt_result(state: VirtualTexturesState, result: VtResult) {
             success,
             gts_name,
             texture_count,
+            textures,
             error,
         } => {
             if success {
@@ -56,6 +61,13 @@ pub fn handle_vt_result(state: VirtualTexturesState, result: VtResult) {
                     "Extracted {} textures from {}",
                     texture_count, gts_name
                 ));
+                // One row per texture, named without the GTP hash suffix
+                state.add_results_batch(
+                    textures
+                        .iter()
+                        .map(|t| format!("  {}", get_subfolder_name(t)))
+                        .collect(),
+                );
                 state.status_message.set("Extraction complete!".to_string());
             } else {
                 state.add_result(&format!("Error: {}", error.unwrap_or_default()));
@@ -67,12 +79,18 @@ pub fn handle_vt_result(state: VirtualTexturesState, result: VtResult) {
             success_count,
             error_count,
             texture_count,
+            cancelled,
             results,
         } => {
             // Use batch update to avoid UI freezing with large result sets
             state.add_results_batch(results);
 
-            let status = if error_count == 0 {
+            let status = if cancelled {
+                format!(
+                    "Cancelled: {} succeeded, {} failed ({} textures)",
+                    success_count, error_count, texture_count
+                )
+            } else if error_count == 0 {
                 format!(
                     "Extracted {} textures from {} GTS files!",
                     texture_count, success_count
@@ -108,10 +126,16 @@ pub fn handle_vt_result(state: VirtualTexturesState, result: VtResult) {
         VtResult::DdsBatchDone {
             success_count,
             error_count,
+            cancelled,
             results,
         } => {
             state.add_results_batch(results);
-            let status = if error_count == 0 {
+            let status = if cancelled {
+                format!(
+                    "Cancelled: {} succeeded, {} failed",
+                    success_count, error_count
+                )
+            } else if error_count == 0 {
                 format!("Converted {} files!", success_count)
             } else {
                 format!(
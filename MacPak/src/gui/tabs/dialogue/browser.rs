@@ -1,6 +1,7 @@
 //! Dialog file browser panel
 
 use super::operations;
+use crate::gui::shared::colors;
 use crate::gui::state::{DialogEntry, DialogueState};
 use floem::prelude::*;
 use floem::text::Weight;
@@ -28,9 +29,9 @@ pub fn browser_panel(state: DialogueState) -> impl IntoView {
         .style(|s| {
             s.padding(8.0)
                 .font_size(12.0)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
                 .border_bottom(1.0)
-                .border_color(Color::rgb8(230, 230, 230))
+                .border_color(colors().border)
         }),
         // Dialog list using virtual_list like browser tab
         dialog_list(state_for_list, state.browser_panel_width),
@@ -39,9 +40,9 @@ pub fn browser_panel(state: DialogueState) -> impl IntoView {
         s.width_full()
             .height_full()
             .min_height(0.0) // Critical for scroll to work
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border_right(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -58,7 +59,7 @@ fn search_box(state: DialogueState) -> impl IntoView {
                     .padding(8.0)
                     .border(1.0)
                     .border_radius(4.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .font_size(13.0)
             }),
     )
@@ -184,7 +185,7 @@ fn dialog_row(
                     let available = width - ROW_PADDING - 8.0;
                     s.font_size(13.0)
                         .font_weight(Weight::MEDIUM)
-                        .color(Color::rgb8(40, 40, 40))
+                        .color(colors().text_primary)
                         .width(available as f32)
                 })
             },
@@ -201,7 +202,7 @@ fn dialog_row(
                     let width = panel_width.get();
                     let available = width - ROW_PADDING - 8.0;
                     s.font_size(11.0)
-                        .color(Color::rgb8(120, 120, 120))
+                        .color(colors().text_muted)
                         .width(available as f32)
                 })
             },
@@ -219,14 +220,14 @@ fn dialog_row(
             .gap(8.0)
             .items_center()
             .border_bottom(1.0)
-            .border_color(Color::rgb8(245, 245, 245))
+            .border_color(colors().border)
             .cursor(floem::style::CursorStyle::Pointer);
 
         if is_sel {
-            base.background(Color::rgb8(227, 242, 253))
+            base.background(colors().accent_bg)
         } else {
-            base.background(Color::WHITE)
-                .hover(|s| s.background(Color::rgb8(250, 250, 250)))
+            base.background(colors().bg_base)
+                .hover(|s| s.background(colors().bg_surface))
         }
     })
 }
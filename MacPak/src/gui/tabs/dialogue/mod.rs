@@ -18,6 +18,7 @@ use floem::reactive::create_effect;
 use floem::style::{FlexDirection, Position};
 use floem::text::Weight;
 
+use crate::gui::shared::colors;
 pub use operations::{load_dialog_from_pak, open_dialog_folder};
 
 /// Main dialogue tab view
@@ -98,7 +99,7 @@ pub fn dialogue_tab(
         s.width_full()
             .height_full()
             .flex_direction(FlexDirection::Column)
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
     });
 
     // Stack main content with loading overlay
@@ -174,11 +175,11 @@ fn divider_handle(is_dragging: RwSignal<bool>, drag_offset: RwSignal<f64>) -> im
                 .height_full()
                 .cursor(floem::style::CursorStyle::ColResize)
                 .background(if dragging {
-                    Color::rgb8(33, 150, 243)
+                    colors().accent
                 } else {
-                    Color::rgb8(200, 200, 200)
+                    colors().border
                 })
-                .hover(|s| s.background(Color::rgb8(150, 150, 150)))
+                .hover(|s| s.background(colors().border_strong))
         })
         .on_event_stop(floem::event::EventListener::PointerDown, move |e| {
             if let floem::event::Event::PointerDown(pe) = e {
@@ -227,14 +228,10 @@ fn empty_state() -> impl IntoView {
         label(|| "No Dialog Loaded").style(|s| {
             s.font_size(18.0)
                 .font_weight(Weight::MEDIUM)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
         }),
         label(|| "Select a dialog file from the browser\nor open a folder containing dialogs")
-            .style(|s| {
-                s.font_size(13.0)
-                    .color(Color::rgb8(150, 150, 150))
-                    .margin_top(8.0)
-            }),
+            .style(|s| s.font_size(13.0).color(colors().text_muted).margin_top(8.0)),
     ))
     .style(|s| {
         s.width_full()
@@ -257,9 +254,9 @@ fn loading_overlay(state: DialogueState) -> impl IntoView {
                 container(label(move || message.get()).style(|s| s.font_size(14.0)))
                     .style(|s| {
                         s.padding(24.0)
-                            .background(Color::WHITE)
+                            .background(colors().bg_base)
                             .border(1.0)
-                            .border_color(Color::rgb8(200, 200, 200))
+                            .border_color(colors().border_strong)
                             .border_radius(8.0)
                             .min_width(300.0)
                     })
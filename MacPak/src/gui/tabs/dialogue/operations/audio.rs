@@ -1,7 +1,7 @@
 //! Audio playback operations for dialogue voice lines
 //!
 //! This module provides the rodio-based playback infrastructure.
-//! WEM decoding is handled by the wem module via vgmstream.
+//! WEM decoding is handled by the wem module.
 //! Decoded audio is cached via AudioCache for efficient replay.
 
 use crate::formats::wem::{AudioCacheError, DecodedAudio, WemError};
@@ -56,9 +56,9 @@ impl AudioPlayer {
         Ok(())
     }
 
-    /// Play a WEM file from disk using vgmstream
+    /// Play a WEM file from disk
     pub fn play_file(&self, path: &Path) -> Result<(), AudioError> {
-        let audio = crate::formats::wem::load_wem_file_vgmstream(path)?;
+        let audio = crate::formats::wem::load_wem_file(path)?;
         self.play(audio)
     }
 
@@ -159,7 +159,7 @@ impl Source for DecodedAudioSource {
 /// Play audio for a dialogue node using the audio cache
 ///
 /// This uses the AudioCache for efficient playback:
-/// - First playback: decodes WEM via vgmstream and caches the result
+/// - First playback: decodes the WEM and caches the result
 /// - Subsequent playback: uses cached decoded audio (O(1) lookup)
 pub fn play_node_audio(
     player: &AudioPlayer,
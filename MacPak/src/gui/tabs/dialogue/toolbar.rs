@@ -1,6 +1,7 @@
 //! Dialogue tab toolbar
 
 use super::operations;
+use crate::gui::shared::colors;
 use crate::gui::state::{ConfigState, DialogueState};
 use floem::prelude::*;
 use floem::reactive::SignalGet;
@@ -48,9 +49,9 @@ pub fn toolbar(state: DialogueState, config: ConfigState) -> impl IntoView {
                     .padding_vert(6.0)
                     .border_radius(4.0)
                     .font_size(13.0)
-                    .background(Color::rgb8(59, 130, 246))
-                    .color(Color::WHITE)
-                    .hover(|s| s.background(Color::rgb8(37, 99, 235)))
+                    .background(colors().accent)
+                    .color(colors().text_on_accent)
+                    .hover(|s| s.background(colors().accent))
             })
             .action(move || {
                 let bg3_path = config_for_gustav.bg3_data_path.get();
@@ -71,7 +72,7 @@ pub fn toolbar(state: DialogueState, config: ConfigState) -> impl IntoView {
                     .border_radius(4.0)
                     .font_size(13.0)
                     .background(Color::rgb8(107, 114, 128))
-                    .color(Color::WHITE)
+                    .color(colors().text_on_accent)
                     .hover(|s| s.background(Color::rgb8(75, 85, 99)))
             })
             .action(move || {
@@ -95,10 +96,10 @@ pub fn toolbar(state: DialogueState, config: ConfigState) -> impl IntoView {
                     .border_radius(4.0)
                     .font_size(12.0)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .background(Color::WHITE)
-                    .color(Color::rgb8(60, 60, 60))
-                    .hover(|s| s.background(Color::rgb8(245, 245, 245)))
+                    .border_color(colors().border_strong)
+                    .background(colors().bg_base)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_elevated))
             })
             .action(move || {
                 expand_all_nodes(&state_for_expand);
@@ -110,10 +111,10 @@ pub fn toolbar(state: DialogueState, config: ConfigState) -> impl IntoView {
                     .border_radius(4.0)
                     .font_size(12.0)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .background(Color::WHITE)
-                    .color(Color::rgb8(60, 60, 60))
-                    .hover(|s| s.background(Color::rgb8(245, 245, 245)))
+                    .border_color(colors().border_strong)
+                    .background(colors().bg_base)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_elevated))
             })
             .action(move || {
                 collapse_all_nodes(&state_for_collapse);
@@ -125,7 +126,7 @@ pub fn toolbar(state: DialogueState, config: ConfigState) -> impl IntoView {
         // Status message
         label(move || state_for_status.status_message.get()).style(|s| {
             s.font_size(12.0)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
                 .margin_left(12.0)
         }),
     ))
@@ -136,9 +137,9 @@ pub fn toolbar(state: DialogueState, config: ConfigState) -> impl IntoView {
             .padding_vert(8.0)
             .gap(8.0)
             .items_center()
-            .background(Color::rgb8(245, 245, 245))
+            .background(colors().bg_elevated)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -147,15 +148,15 @@ fn language_selector(state: DialogueState) -> impl IntoView {
     let state_for_label = state.clone();
 
     h_stack((
-        label(|| "Language:").style(|s| s.font_size(13.0).color(Color::rgb8(80, 80, 80))),
+        label(|| "Language:").style(|s| s.font_size(13.0).color(colors().text_secondary)),
         label(move || state_for_label.language.get()).style(|s| {
             s.font_size(13.0)
                 .padding_horiz(8.0)
                 .padding_vert(4.0)
                 .border(1.0)
                 .border_radius(4.0)
-                .border_color(Color::rgb8(200, 200, 200))
-                .background(Color::WHITE)
+                .border_color(colors().border_strong)
+                .background(colors().bg_base)
         }),
     ))
     .style(|s| s.gap(4.0).items_center())
@@ -176,15 +177,15 @@ fn export_buttons(state: DialogueState) -> impl IntoView {
                     .border_radius(4.0)
                     .font_size(12.0)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200));
+                    .border_color(colors().border_strong);
 
                 if has_dialog.get().is_some() {
-                    base.background(Color::WHITE)
-                        .color(Color::rgb8(60, 60, 60))
-                        .hover(|s| s.background(Color::rgb8(245, 245, 245)))
+                    base.background(colors().bg_base)
+                        .color(colors().text_primary)
+                        .hover(|s| s.background(colors().bg_elevated))
                 } else {
-                    base.background(Color::rgb8(240, 240, 240))
-                        .color(Color::rgb8(180, 180, 180))
+                    base.background(colors().bg_elevated)
+                        .color(colors().text_disabled)
                 }
             })
             .action(move || {
@@ -200,15 +201,15 @@ fn export_buttons(state: DialogueState) -> impl IntoView {
                     .border_radius(4.0)
                     .font_size(12.0)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200));
+                    .border_color(colors().border_strong);
 
                 if has_dialog.get().is_some() {
-                    base.background(Color::WHITE)
-                        .color(Color::rgb8(60, 60, 60))
-                        .hover(|s| s.background(Color::rgb8(245, 245, 245)))
+                    base.background(colors().bg_base)
+                        .color(colors().text_primary)
+                        .hover(|s| s.background(colors().bg_elevated))
                 } else {
-                    base.background(Color::rgb8(240, 240, 240))
-                        .color(Color::rgb8(180, 180, 180))
+                    base.background(colors().bg_elevated)
+                        .color(colors().text_disabled)
                 }
             })
             .action(move || {
@@ -2,6 +2,7 @@
 
 use super::context_menu::show_node_context_menu;
 use crate::dialog::NodeConstructor;
+use crate::gui::shared::colors;
 use crate::gui::state::{DialogueState, DisplayNode};
 use floem::event::EventPropagation;
 use floem::prelude::*;
@@ -150,7 +151,7 @@ pub fn tree_view_panel(state: DialogueState) -> impl IntoView {
         s.width_full()
             .height_full()
             .min_height(0.0) // Critical for scroll to work
-            .background(Color::WHITE)
+            .background(colors().bg_base)
     })
 }
 
@@ -174,27 +175,25 @@ fn dialog_header(state: DialogueState) -> impl IntoView {
                     h_stack((
                         label(move || format!("{} nodes", node_count)).style(|s| {
                             s.font_size(12.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
                                 .padding_horiz(8.0)
                                 .padding_vert(2.0)
-                                .background(Color::rgb8(240, 240, 240))
+                                .background(colors().bg_elevated)
                                 .border_radius(4.0)
                         }),
                         label(move || format!("{} roots", root_count)).style(|s| {
                             s.font_size(12.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
                                 .padding_horiz(8.0)
                                 .padding_vert(2.0)
-                                .background(Color::rgb8(240, 240, 240))
+                                .background(colors().bg_elevated)
                                 .border_radius(4.0)
                         }),
                     ))
                     .style(|s| s.gap(8.0)),
                     rich_text(move || {
                         let mut layout = TextLayout::new();
-                        let attrs = Attrs::new()
-                            .font_size(12.0)
-                            .color(floem::peniko::Color::rgba8(80, 80, 80, 255));
+                        let attrs = Attrs::new().font_size(12.0).color(colors().text_secondary);
                         layout.set_text(&synopsis, AttrsList::new(attrs));
                         layout.set_wrap(Wrap::Word);
                         layout
@@ -211,7 +210,7 @@ fn dialog_header(state: DialogueState) -> impl IntoView {
         s.width_full()
             .padding(12.0)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(230, 230, 230))
+            .border_color(colors().border)
     })
 }
 
@@ -432,7 +431,7 @@ fn node_row(
                     .style(|s| {
                         s.font_size(10.0)
                             .width(16.0)
-                            .color(Color::rgb8(120, 120, 120))
+                            .color(colors().text_muted)
                             .cursor(floem::style::CursorStyle::Pointer)
                     })
                     .on_click_stop(move |_| {
@@ -474,7 +473,7 @@ fn node_row(
                                             label(move || format!("IF [{}]", flags.clone()))
                                                 .style(|s| {
                                                     s.font_size(10.0)
-                                                        .color(Color::rgb8(180, 100, 60))
+                                                        .color(colors().warning)
                                                         .margin_right(8.0)
                                                 })
                                                 .into_any()
@@ -491,8 +490,7 @@ fn node_row(
                                         if show_set {
                                             label(move || format!("SET [{}]", flags.clone()))
                                                 .style(|s| {
-                                                    s.font_size(10.0)
-                                                        .color(Color::rgb8(60, 140, 60))
+                                                    s.font_size(10.0).color(colors().success)
                                                 })
                                                 .into_any()
                                         } else {
@@ -545,7 +543,7 @@ fn node_row(
                                 },
                                 // Node text
                                 rich_text(move || {
-                                    let text_color = floem::peniko::Color::rgba8(40, 40, 40, 255);
+                                    let text_color = colors().text_primary;
                                     create_styled_text_layout(&display_text, 13.0, text_color)
                                 })
                                 .style(|s| s.flex_shrink(0.0)),
@@ -568,8 +566,8 @@ fn node_row(
                             s.font_size(10.0)
                                 .padding_horiz(4.0)
                                 .padding_vert(1.0)
-                                .background(Color::rgb8(239, 68, 68))
-                                .color(Color::WHITE)
+                                .background(colors().error)
+                                .color(colors().text_on_accent)
                                 .border_radius(2.0)
                         })
                         .into_any()
@@ -596,7 +594,7 @@ fn node_row(
                                 .padding_horiz(4.0)
                                 .padding_vert(1.0)
                                 .background(bg_color)
-                                .color(Color::WHITE)
+                                .color(colors().text_on_accent)
                                 .border_radius(2.0)
                         })
                         .into_any()
@@ -638,7 +636,7 @@ fn node_row(
                         label(move || format!("📝 {}", notes_inner.clone()))
                             .style(|s| {
                                 s.font_size(10.0)
-                                    .color(Color::rgb8(100, 100, 100))
+                                    .color(colors().text_secondary)
                                     .font_style(floem::text::Style::Italic)
                                     .max_width(500.0)
                             })
@@ -676,14 +674,14 @@ fn node_row(
             .gap(4.0)
             .items_center()
             .border_bottom(1.0)
-            .border_color(Color::rgb8(245, 245, 245))
+            .border_color(colors().border)
             .cursor(floem::style::CursorStyle::Pointer);
 
         if is_selected {
-            base.background(Color::rgb8(227, 242, 253))
+            base.background(colors().accent_bg)
         } else {
-            base.background(Color::WHITE)
-                .hover(|s| s.background(Color::rgb8(250, 250, 250)))
+            base.background(colors().bg_base)
+                .hover(|s| s.background(colors().bg_surface))
         }
     })
 }
@@ -714,7 +712,7 @@ fn node_type_badge(constructor: NodeConstructor) -> impl IntoView {
             .padding_vert(2.0)
             .min_width(20.0)
             .background(bg_color)
-            .color(Color::WHITE)
+            .color(colors().text_on_accent)
             .border_radius(3.0)
     })
 }
@@ -4,6 +4,7 @@ use floem::prelude::*;
 
 use super::shared::constants::*;
 use super::shared::{copy_to_clipboard, normalize_hex, parse_hex_color, parse_hex_to_color};
+use crate::gui::shared::colors;
 use crate::gui::state::DyeColorEntry;
 
 /// Creates a single color row with label, color swatch (clickable to open picker), hex input, and sRGB
@@ -34,7 +35,7 @@ pub fn color_row(
                             .height(18.0)
                             .border_radius(RADIUS_SM)
                             .border(1.0)
-                            .border_color(BORDER_DARK)
+                            .border_color(border_dark())
                             .background(color)
                             .cursor(floem::style::CursorStyle::Pointer)
                     })
@@ -55,9 +56,9 @@ pub fn color_row(
                         .padding(3.0)
                         .font_size(FONT_SMALL)
                         .font_family("monospace".to_string())
-                        .background(Color::WHITE)
+                        .background(colors().bg_base)
                         .border(1.0)
-                        .border_color(BORDER_INPUT)
+                        .border_color(border_input())
                         .border_radius(RADIUS_SM)
                 })
                 .on_event_stop(floem::event::EventListener::FocusLost, move |_| {
@@ -84,12 +85,12 @@ pub fn color_row(
                         .padding(3.0)
                         .font_size(FONT_TINY)
                         .font_family("monospace".to_string())
-                        .background(BG_DISABLED)
+                        .background(bg_disabled())
                         .border(1.0)
-                        .border_color(BORDER_INPUT)
+                        .border_color(border_input())
                         .border_radius(RADIUS_SM)
                         .cursor(floem::style::CursorStyle::Pointer)
-                        .color(TEXT_DARK)
+                        .color(text_dark())
                 })
                 .on_click_stop(move |_| {
                     let (r, g, b) = parse_hex_color(&hex_copy.get()).unwrap_or((128, 128, 128));
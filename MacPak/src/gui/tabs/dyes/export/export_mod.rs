@@ -5,7 +5,11 @@ use std::path::Path;
 
 use floem::prelude::*;
 
-use super::super::shared::{generate_color_nodes, parse_hex_color, required_colors};
+use super::super::shared::{generate_color_nodes, required_colors};
+use super::icons::{
+    AtlasLayout, ICON_SIZE, LARGE_ICON_SIZE, build_atlas, encode_dds, fit_square, load_dye_icons,
+};
+use super::treasure::{TreasureTargets, treasure_table_txt};
 use crate::gui::state::{DyesState, GeneratedDyeEntry};
 use crate::gui::utils::{UuidFormat, generate_meta_lsx, generate_uuid};
 
@@ -17,6 +21,7 @@ const DYE_PARENT_TEMPLATE_ID: &str = "1a750a66-e5c2-40be-9f62-0a4bf3ddb403";
 use crate::maclarian::converter::{from_lsx, loca_from_xml};
 use crate::maclarian::formats::loca::write_loca;
 use crate::maclarian::formats::lsf::write_lsf;
+use crate::maclarian::mods::Version64;
 
 /// Write LSX content as LSF binary file
 fn write_lsx_as_lsf<P: AsRef<Path>>(lsx_content: &str, dest: P) -> std::io::Result<()> {
@@ -42,11 +47,16 @@ pub fn check_required_colors_at_default(state: &DyesState) -> Vec<&'static str>
         .collect()
 }
 
-/// Export a complete dye mod to the specified directory
-pub fn export_dye_mod(state: &DyesState, output_dir: &Path, mod_name: &str) -> String {
+/// Export a complete dye mod to the specified directory, returning the
+/// status message
+pub fn export_dye_mod(
+    state: &DyesState,
+    output_dir: &Path,
+    mod_name: &str,
+) -> Result<String, String> {
     // Validate inputs
     if mod_name.is_empty() {
-        return "Mod name is required".to_string();
+        return Err("Mod name is required".to_string());
     }
 
     // Append mod_name to output directory
@@ -55,7 +65,7 @@ pub fn export_dye_mod(state: &DyesState, output_dir: &Path, mod_name: &str) -> S
 
     let dyes = state.generated_dyes.get();
     if dyes.is_empty() {
-        return "No dyes generated. Use 'Generate Dye' first.".to_string();
+        return Err("No dyes generated. Use 'Generate Dye' first.".to_string());
     }
 
     // Get mod metadata from state (or generate if empty)
@@ -69,10 +79,12 @@ pub fn export_dye_mod(state: &DyesState, output_dir: &Path, mod_name: &str) -> S
     };
     let author = state.mod_author.get();
     let description = state.mod_description.get();
-    let version_major = state.mod_version_major.get();
-    let version_minor = state.mod_version_minor.get();
-    let version_patch = state.mod_version_patch.get();
-    let version_build = state.mod_version_build.get();
+    let version = Version64::new(
+        state.mod_version_major.get(),
+        state.mod_version_minor.get(),
+        state.mod_version_patch.get(),
+        state.mod_version_build.get(),
+    );
 
     // Generate container (pouch) UUIDs and handles
     let container_template_uuid = generate_uuid(UuidFormat::Standard);
@@ -81,7 +93,7 @@ pub fn export_dye_mod(state: &DyesState, output_dir: &Path, mod_name: &str) -> S
 
     // Create directory structure
     if let Err(e) = create_mod_structure(output_dir, mod_name) {
-        return format!("Failed to create directories: {}", e);
+        return Err(format!("Failed to create directories: {}", e));
     }
 
     // Generate and write all files
@@ -101,15 +113,23 @@ pub fn export_dye_mod(state: &DyesState, output_dir: &Path, mod_name: &str) -> S
             &mod_uuid,
             &author,
             &description,
-            version_major,
-            version_minor,
-            version_patch,
-            version_build,
+            version,
         ),
         // Stats (all dyes combined)
         write_object_txt(output_dir, mod_name, &dyes, &container_template_uuid),
         write_item_combos_txt(output_dir, mod_name, &dyes),
-        write_treasure_table_txt(output_dir, mod_name, &dyes, &state.selected_vendors.get()),
+        write_treasure_table_txt(
+            output_dir,
+            mod_name,
+            &dyes,
+            &TreasureTargets {
+                tables: &state.target_treasure_tables(),
+                individual_dye_count: state
+                    .add_individual_dyes
+                    .get()
+                    .then(|| state.individual_dye_count.get()),
+            },
+        ),
         // RootTemplates (all dyes combined)
         write_root_templates_lsx(
             output_dir,
@@ -124,23 +144,29 @@ pub fn export_dye_mod(state: &DyesState, output_dir: &Path, mod_name: &str) -> S
         // GUI / Icons
         write_texture_atlas_info_lsx(output_dir, mod_name, &dyes),
         write_texture_bank_lsx(output_dir, mod_name),
-        write_placeholder_dds(output_dir, mod_name, &dyes),
     ];
 
     // Check for errors
     for result in &results {
         if let Err(e) = result {
-            return format!("Failed to write file: {}", e);
+            return Err(format!("Failed to write file: {}", e));
         }
     }
 
+    let icon_warnings = write_icon_dds(output_dir, mod_name, &dyes)
+        .map_err(|e| format!("Failed to write icons: {}", e))?;
+
     let count = dyes.len();
-    format!(
+    let mut message = format!(
         "Exported {} dye{} to {}",
         count,
         if count == 1 { "" } else { "s" },
         output_dir.display()
-    )
+    );
+    for warning in icon_warnings {
+        message.push_str(&format!("\nWarning: {}", warning));
+    }
+    Ok(message)
 }
 
 /// Create the mod directory structure
@@ -224,10 +250,7 @@ fn write_meta_lsx(
     mod_uuid: &str,
     author: &str,
     description: &str,
-    version_major: u32,
-    version_minor: u32,
-    version_patch: u32,
-    version_build: u32,
+    version: Version64,
 ) -> std::io::Result<()> {
     let content = generate_meta_lsx(
         mod_name,
@@ -235,10 +258,10 @@ fn write_meta_lsx(
         author,
         description,
         mod_uuid,
-        version_major,
-        version_minor,
-        version_patch,
-        version_build,
+        version.major,
+        version.minor,
+        version.patch,
+        version.build,
     );
 
     let path = output_dir.join(format!("Mods/{}/meta.lsx", mod_name));
@@ -326,65 +349,13 @@ fn write_treasure_table_txt(
     output_dir: &Path,
     mod_name: &str,
     dyes: &[GeneratedDyeEntry],
-    selected_vendors: &[bool],
+    targets: &TreasureTargets,
 ) -> std::io::Result<()> {
-    use crate::gui::state::VENDOR_DEFS;
-
-    let mut content = String::new();
-
-    // Main treasure table containing all individual dyes
-    let subtables: Vec<String> = dyes
-        .iter()
-        .map(|dye| {
-            format!(
-                r#"new subtable "1,1"
-object category "I_{}",1,0,0,0,0,0,0,0"#,
-                dye.name
-            )
-        })
-        .collect();
-
-    content.push_str(&format!(
-        r#"new treasuretable "{mod_name}_Dyes"
-{subtables}
-
-"#,
-        subtables = subtables.join("\n")
-    ));
-
-    // Container treasure table (what the pouch spawns)
-    content.push_str(&format!(
-        r#"new treasuretable "{mod_name}_DyePouch_Contents"
-CanMerge 1
-new subtable "1,1"
-object category "I_{mod_name}_DyePouch",1,0,0,0,0,0,0,0
-
-"#
-    ));
-
-    // Add container to selected vendor tables
-    for (idx, vendor) in VENDOR_DEFS.iter().enumerate() {
-        // Include if always enabled OR if selected
-        let is_selected =
-            vendor.always_enabled || selected_vendors.get(idx).copied().unwrap_or(false);
-        if is_selected {
-            content.push_str(&format!(
-                r#"new treasuretable "{}"
-CanMerge 1
-new subtable "1,1"
-object category "I_{mod_name}_DyePouch",1,0,0,0,0,0,0,0
-
-"#,
-                vendor.id
-            ));
-        }
-    }
-
     let path = output_dir.join(format!(
         "Public/{}/Stats/Generated/TreasureTable.txt",
         mod_name
     ));
-    fs::write(path, content.trim_end())
+    fs::write(path, treasure_table_txt(mod_name, dyes, targets))
 }
 
 /// Base game pouch template that the dye container inherits from
@@ -534,13 +505,11 @@ fn write_texture_atlas_info_lsx(
     dyes: &[GeneratedDyeEntry],
 ) -> std::io::Result<()> {
     let atlas_uuid = generate_uuid(UuidFormat::Standard);
-    let dye_count = dyes.len();
 
-    // Calculate atlas dimensions (arrange icons in a grid)
-    let icons_per_row = (dye_count as f32).sqrt().ceil() as u32;
-    let num_rows = ((dye_count as f32) / (icons_per_row as f32)).ceil() as u32;
-    let atlas_width = icons_per_row * 64;
-    let atlas_height = num_rows * 64;
+    // Same grid the atlas DDS is packed with
+    let layout = AtlasLayout::new(dyes.len());
+    let atlas_width = layout.width();
+    let atlas_height = layout.height();
 
     // Generate UV entries for each dye icon
     let uv_entries: Vec<String> = dyes
@@ -548,12 +517,7 @@ fn write_texture_atlas_info_lsx(
         .enumerate()
         .map(|(i, dye)| {
             let icon_name = format!("{}_Icon", dye.name);
-            let col = (i as u32) % icons_per_row;
-            let row = (i as u32) / icons_per_row;
-            let u1 = (col * 64) as f32 / atlas_width as f32;
-            let u2 = ((col + 1) * 64) as f32 / atlas_width as f32;
-            let v1 = (row * 64) as f32 / atlas_height as f32;
-            let v2 = ((row + 1) * 64) as f32 / atlas_height as f32;
+            let (u1, u2, v1, v2) = layout.uv(i);
             format!(
                 r#"				<node id="IconUV">
 					<attribute id="MapKey" type="FixedString" value="{}"/>
@@ -575,8 +539,8 @@ fn write_texture_atlas_info_lsx(
 		<node id="root">
 			<children>
 				<node id="TextureAtlasIconSize">
-					<attribute id="Height" type="int64" value="64"/>
-					<attribute id="Width" type="int64" value="64"/>
+					<attribute id="Height" type="int64" value="{ICON_SIZE}"/>
+					<attribute id="Width" type="int64" value="{ICON_SIZE}"/>
 				</node>
 				<node id="TextureAtlasPath">
 					<attribute id="Path" type="LSString" value="Assets/Textures/Icons/{mod_name}_Icons.dds"/>
@@ -643,33 +607,24 @@ fn write_texture_bank_lsx(output_dir: &Path, mod_name: &str) -> std::io::Result<
     write_lsx_as_lsf(&content, path)
 }
 
-/// Write placeholder DDS files (64x64 magenta for visibility)
-fn write_placeholder_dds(
+/// Write the icon atlas and each dye's tooltip/controller icons, returning
+/// a warning for every dye that fell back to its color swatch
+fn write_icon_dds(
     output_dir: &Path,
     mod_name: &str,
     dyes: &[GeneratedDyeEntry],
-) -> std::io::Result<()> {
-    let dye_count = dyes.len();
-
-    // Calculate atlas dimensions
-    let icons_per_row = (dye_count as f32).sqrt().ceil() as u32;
-    let num_rows = ((dye_count as f32) / (icons_per_row as f32)).ceil() as u32;
-    let atlas_width = icons_per_row * 64;
-    let atlas_height = num_rows * 64;
-
-    // Create atlas DDS
-    let atlas_dds = create_dds_data(atlas_width, atlas_height, dyes);
+) -> std::io::Result<Vec<String>> {
+    let to_io = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    let (icons, warnings) = load_dye_icons(dyes);
 
-    // Write atlas
     let atlas_path = output_dir.join(format!(
         "Public/{}/Assets/Textures/Icons/{}_Icons.dds",
         mod_name, mod_name
     ));
-    fs::write(atlas_path, &atlas_dds)?;
+    fs::write(atlas_path, encode_dds(build_atlas(&icons)).map_err(to_io)?)?;
 
-    // Write individual icon DDS files for each dye
-    let single_icon_dds = create_single_icon_dds();
-    for dye in dyes {
+    for (dye, icon) in dyes.iter().zip(&icons) {
+        let icon_dds = encode_dds(fit_square(icon, LARGE_ICON_SIZE)).map_err(to_io)?;
         let icon_name = format!("{}_Icon", dye.name);
         let paths = [
             format!(
@@ -682,114 +637,9 @@ fn write_placeholder_dds(
             ),
         ];
         for path in &paths {
-            fs::write(output_dir.join(path), &single_icon_dds)?;
-        }
-    }
-
-    Ok(())
-}
-
-/// Create DDS data for an atlas with multiple icons
-fn create_dds_data(width: u32, height: u32, dyes: &[GeneratedDyeEntry]) -> Vec<u8> {
-    let icons_per_row = (dyes.len() as f32).sqrt().ceil() as u32;
-
-    let mut dds_data = Vec::with_capacity(128 + (width * height * 4) as usize);
-
-    // DDS header
-    dds_data.extend_from_slice(b"DDS ");
-    dds_data.extend_from_slice(&124u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x1007u32.to_le_bytes());
-    dds_data.extend_from_slice(&height.to_le_bytes());
-    dds_data.extend_from_slice(&width.to_le_bytes());
-    dds_data.extend_from_slice(&(width * 4).to_le_bytes());
-    dds_data.extend_from_slice(&0u32.to_le_bytes());
-    dds_data.extend_from_slice(&1u32.to_le_bytes());
-    for _ in 0..11 {
-        dds_data.extend_from_slice(&0u32.to_le_bytes());
-    }
-    dds_data.extend_from_slice(&32u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x41u32.to_le_bytes());
-    dds_data.extend_from_slice(&0u32.to_le_bytes());
-    dds_data.extend_from_slice(&32u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x00FF0000u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x0000FF00u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x000000FFu32.to_le_bytes());
-    dds_data.extend_from_slice(&0xFF000000u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x1000u32.to_le_bytes());
-    for _ in 0..4 {
-        dds_data.extend_from_slice(&0u32.to_le_bytes());
-    }
-
-    // Generate pixel data with different colors for each dye
-    let colors: Vec<(u8, u8, u8)> = dyes
-        .iter()
-        .map(|dye| {
-            // Use the Cloth_Primary color as the icon color (magenta if missing/invalid)
-            dye.colors
-                .get("Cloth_Primary")
-                .and_then(|hex| parse_hex_color(hex))
-                .unwrap_or((255, 0, 255))
-        })
-        .collect();
-
-    for y in 0..height {
-        for x in 0..width {
-            let icon_col = x / 64;
-            let icon_row = y / 64;
-            let icon_idx = (icon_row * icons_per_row + icon_col) as usize;
-
-            let (r, g, b) = if icon_idx < colors.len() {
-                colors[icon_idx]
-            } else {
-                (128, 128, 128) // Gray for empty slots
-            };
-
-            dds_data.push(b); // Blue
-            dds_data.push(g); // Green
-            dds_data.push(r); // Red
-            dds_data.push(255); // Alpha
+            fs::write(output_dir.join(path), &icon_dds)?;
         }
     }
 
-    dds_data
-}
-
-/// Create a single 64x64 icon DDS
-fn create_single_icon_dds() -> Vec<u8> {
-    let mut dds_data = Vec::with_capacity(128 + 64 * 64 * 4);
-
-    // DDS header
-    dds_data.extend_from_slice(b"DDS ");
-    dds_data.extend_from_slice(&124u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x1007u32.to_le_bytes());
-    dds_data.extend_from_slice(&64u32.to_le_bytes());
-    dds_data.extend_from_slice(&64u32.to_le_bytes());
-    dds_data.extend_from_slice(&(64 * 4u32).to_le_bytes());
-    dds_data.extend_from_slice(&0u32.to_le_bytes());
-    dds_data.extend_from_slice(&1u32.to_le_bytes());
-    for _ in 0..11 {
-        dds_data.extend_from_slice(&0u32.to_le_bytes());
-    }
-    dds_data.extend_from_slice(&32u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x41u32.to_le_bytes());
-    dds_data.extend_from_slice(&0u32.to_le_bytes());
-    dds_data.extend_from_slice(&32u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x00FF0000u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x0000FF00u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x000000FFu32.to_le_bytes());
-    dds_data.extend_from_slice(&0xFF000000u32.to_le_bytes());
-    dds_data.extend_from_slice(&0x1000u32.to_le_bytes());
-    for _ in 0..4 {
-        dds_data.extend_from_slice(&0u32.to_le_bytes());
-    }
-
-    // Magenta pixels
-    for _ in 0..(64 * 64) {
-        dds_data.push(0xFF); // Blue
-        dds_data.push(0x00); // Green
-        dds_data.push(0xFF); // Red
-        dds_data.push(0xFF); // Alpha
-    }
-
-    dds_data
+    Ok(warnings)
 }
@@ -0,0 +1,229 @@
+//! Dye icons: user images (or color swatches) packed into the icon atlas
+//!
+//! Each dye gets one cell in the atlas plus standalone tooltip and controller
+//! icons. Images of any shape are scaled to fit their square and centered on a
+//! transparent background.
+
+use std::path::Path;
+
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use super::super::shared::parse_hex_color;
+use crate::gui::state::GeneratedDyeEntry;
+use crate::maclarian::converter::{
+    ColorSpace, DdsFormat, decode_dds_bytes, png_image_to_dds_bytes_with_color_space,
+};
+
+/// Size of an atlas cell
+pub const ICON_SIZE: u32 = 64;
+/// Size of the standalone tooltip and controller icons
+pub const LARGE_ICON_SIZE: u32 = 380;
+
+/// Swatch color when a dye has no valid Cloth_Primary
+const FALLBACK_COLOR: (u8, u8, u8) = (255, 0, 255);
+
+/// Grid the atlas icons are laid out in, filled row by row
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasLayout {
+    pub icons_per_row: u32,
+    pub rows: u32,
+}
+
+impl AtlasLayout {
+    /// Smallest near-square grid holding `count` icons
+    pub fn new(count: usize) -> Self {
+        let count = count.max(1) as u32;
+        let icons_per_row = (count as f32).sqrt().ceil() as u32;
+        Self {
+            icons_per_row,
+            rows: count.div_ceil(icons_per_row),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.icons_per_row * ICON_SIZE
+    }
+
+    pub fn height(&self) -> u32 {
+        self.rows * ICON_SIZE
+    }
+
+    /// Top-left pixel of an icon's cell
+    pub fn origin(&self, index: usize) -> (u32, u32) {
+        let index = index as u32;
+        (
+            (index % self.icons_per_row) * ICON_SIZE,
+            (index / self.icons_per_row) * ICON_SIZE,
+        )
+    }
+
+    /// `(U1, U2, V1, V2)` of an icon's cell
+    pub fn uv(&self, index: usize) -> (f32, f32, f32, f32) {
+        let (x, y) = self.origin(index);
+        let width = self.width() as f32;
+        let height = self.height() as f32;
+        (
+            x as f32 / width,
+            (x + ICON_SIZE) as f32 / width,
+            y as f32 / height,
+            (y + ICON_SIZE) as f32 / height,
+        )
+    }
+}
+
+/// Source image for every dye, in dye order, plus a warning for each dye
+/// whose icon couldn't be used
+pub fn load_dye_icons(dyes: &[GeneratedDyeEntry]) -> (Vec<RgbaImage>, Vec<String>) {
+    let mut warnings = Vec::new();
+    let icons = dyes
+        .iter()
+        .map(|dye| {
+            let Some(path) = dye.icon_path.as_deref().filter(|p| !p.is_empty()) else {
+                return swatch(dye);
+            };
+            load_icon(Path::new(path)).unwrap_or_else(|e| {
+                warnings.push(format!(
+                    "Icon for '{}' not used ({}), using its color instead",
+                    dye.name, e
+                ));
+                swatch(dye)
+            })
+        })
+        .collect();
+    (icons, warnings)
+}
+
+/// Decode a PNG (or anything else the image crate reads) or DDS icon
+pub fn load_icon(path: &Path) -> Result<RgbaImage, String> {
+    let is_dds = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dds"));
+    if is_dds {
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        decode_dds_bytes(&data)
+            .map(|(_, img)| img)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    } else {
+        image::open(path)
+            .map(|img| img.to_rgba8())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+/// Flat square of the dye's primary cloth color
+fn swatch(dye: &GeneratedDyeEntry) -> RgbaImage {
+    let (r, g, b) = dye
+        .colors
+        .get("Cloth_Primary")
+        .and_then(|hex| parse_hex_color(hex))
+        .unwrap_or(FALLBACK_COLOR);
+    RgbaImage::from_pixel(ICON_SIZE, ICON_SIZE, Rgba([r, g, b, 255]))
+}
+
+/// Scale `img` to fit a `size`×`size` square, keeping its aspect ratio and
+/// centering it on a transparent background
+pub fn fit_square(img: &RgbaImage, size: u32) -> RgbaImage {
+    let (width, height) = img.dimensions();
+    if width == size && height == size {
+        return img.clone();
+    }
+    let scale = size as f32 / width.max(height).max(1) as f32;
+    let new_width = ((width as f32 * scale).round() as u32).clamp(1, size);
+    let new_height = ((height as f32 * scale).round() as u32).clamp(1, size);
+    let resized = imageops::resize(img, new_width, new_height, FilterType::Lanczos3);
+
+    let mut canvas = RgbaImage::new(size, size);
+    imageops::overlay(
+        &mut canvas,
+        &resized,
+        i64::from((size - new_width) / 2),
+        i64::from((size - new_height) / 2),
+    );
+    canvas
+}
+
+/// Pack icons into an atlas laid out by [`AtlasLayout::new`]; unused cells
+/// stay transparent
+pub fn build_atlas(icons: &[RgbaImage]) -> RgbaImage {
+    let layout = AtlasLayout::new(icons.len());
+    let mut atlas = RgbaImage::new(layout.width(), layout.height());
+    for (index, icon) in icons.iter().enumerate() {
+        let (x, y) = layout.origin(index);
+        imageops::replace(
+            &mut atlas,
+            &fit_square(icon, ICON_SIZE),
+            i64::from(x),
+            i64::from(y),
+        );
+    }
+    atlas
+}
+
+/// Encode an icon or atlas as a BC3 DDS marked sRGB, as the game samples icons
+pub fn encode_dds(img: RgbaImage) -> Result<Vec<u8>, String> {
+    png_image_to_dds_bytes_with_color_space(
+        &DynamicImage::ImageRgba8(img),
+        DdsFormat::BC3,
+        Some(ColorSpace::Srgb),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_fits_non_square_images() {
+        let wide = fit_square(&solid(200, 100, 200), ICON_SIZE);
+        assert_eq!(wide.dimensions(), (ICON_SIZE, ICON_SIZE));
+        // 64x32 band in the middle, transparent above and below
+        assert_eq!(wide.get_pixel(32, 32)[3], 255);
+        assert_eq!(wide.get_pixel(32, 4)[3], 0);
+        assert_eq!(wide.get_pixel(32, 60)[3], 0);
+
+        let tall = fit_square(&solid(10, 40, 200), LARGE_ICON_SIZE);
+        assert_eq!(tall.dimensions(), (LARGE_ICON_SIZE, LARGE_ICON_SIZE));
+        assert_eq!(tall.get_pixel(190, 190)[3], 255);
+        assert_eq!(tall.get_pixel(10, 190)[3], 0);
+    }
+
+    #[test]
+    fn test_packs_more_than_64_icons() {
+        let icons: Vec<_> = (0..70u8).map(|i| solid(48, 96, i)).collect();
+        let layout = AtlasLayout::new(icons.len());
+        assert_eq!(
+            layout,
+            AtlasLayout {
+                icons_per_row: 9,
+                rows: 8
+            }
+        );
+
+        let atlas = build_atlas(&icons);
+        assert_eq!(atlas.dimensions(), (9 * ICON_SIZE, 8 * ICON_SIZE));
+        for (index, value) in [(0usize, 0u8), (8, 8), (9, 9), (64, 64), (69, 69)] {
+            let (x, y) = layout.origin(index);
+            let pixel = atlas.get_pixel(x + ICON_SIZE / 2, y + ICON_SIZE / 2);
+            assert_eq!(pixel.0, [value, value, value, 255], "icon {}", index);
+        }
+        // Cell past the last icon is empty
+        let (x, y) = layout.origin(70);
+        assert_eq!(atlas.get_pixel(x + 32, y + 32)[3], 0);
+
+        let (u1, u2, v1, v2) = layout.uv(69);
+        for (actual, expected) in [(u1, 6.0 / 9.0), (u2, 7.0 / 9.0), (v1, 7.0 / 8.0), (v2, 1.0)] {
+            assert!(
+                (actual - expected).abs() < 1e-6,
+                "{} != {}",
+                actual,
+                expected
+            );
+        }
+    }
+}
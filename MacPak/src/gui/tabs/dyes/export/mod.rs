@@ -1,11 +1,16 @@
 //! Export functionality for the Dyes tab
 
 mod export_mod;
+mod icons;
+mod pack;
+mod treasure;
 
 pub use export_mod::export_dye_mod;
+pub use pack::{PackedMod, pack_dye_mod};
 
 use floem::prelude::*;
 use floem::text::Weight;
+use floem::views::checkbox;
 
 use super::shared::constants::*;
 use super::shared::{
@@ -14,8 +19,40 @@ use super::shared::{
 };
 use crate::gui::state::DyesState;
 
+use crate::gui::shared::colors;
 pub use export_mod::check_required_colors_at_default;
 
+/// Packing options shown in the export dialog under the vendor selection
+pub fn pack_options(export_as_pak: RwSignal<bool>, copy_to_mods: RwSignal<bool>) -> impl IntoView {
+    v_stack((
+        h_stack((
+            checkbox(move || export_as_pak.get()).on_update(move |checked| {
+                export_as_pak.set(checked);
+                if !checked {
+                    copy_to_mods.set(false);
+                }
+            }),
+            label(|| "Export as .pak (LZ4, with info.json)").style(|s| s.font_size(FONT_BODY)),
+        ))
+        .style(|s| s.items_center().gap(6.0)),
+        h_stack((
+            checkbox(move || copy_to_mods.get())
+                .disabled(move || !export_as_pak.get())
+                .on_update(move |checked| copy_to_mods.set(checked)),
+            label(|| "Also copy to the game's Mods folder").style(move |s| {
+                let s = s.font_size(FONT_BODY);
+                if export_as_pak.get() {
+                    s
+                } else {
+                    s.color(text_muted())
+                }
+            }),
+        ))
+        .style(|s| s.items_center().gap(6.0).margin_left(20.0)),
+    ))
+    .style(|s| s.width_full().gap(4.0).margin_top(12.0))
+}
+
 /// Export section for generating mod files
 pub fn export_section(state: DyesState) -> impl IntoView {
     let generated_dyes = state.generated_dyes;
@@ -63,9 +100,9 @@ pub fn export_section(state: DyesState) -> impl IntoView {
         .style(|s| {
             s.width_full()
                 .padding(PADDING_BTN_H)
-                .background(BG_CARD)
+                .background(bg_card())
                 .border(1.0)
-                .border_color(BORDER_CARD)
+                .border_color(border_card())
                 .border_radius(RADIUS_STD)
         }),
     ))
@@ -73,9 +110,9 @@ pub fn export_section(state: DyesState) -> impl IntoView {
         s.flex_grow(1.0)
             .flex_basis(0.0)
             .padding(PADDING_LG)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(BORDER_CARD)
+            .border_color(border_card())
             .border_radius(6.0)
     })
 }
@@ -238,6 +275,7 @@ fn selected_dye_display(
     let display_name: RwSignal<String> = RwSignal::new(String::new());
     let display_description: RwSignal<String> = RwSignal::new(String::new());
     let display_uuid: RwSignal<String> = RwSignal::new(String::new());
+    let display_icon: RwSignal<Option<String>> = RwSignal::new(None);
 
     // Sync display values when selection or dyes change
     let _ = floem::reactive::create_effect(move |prev_idx: Option<Option<usize>>| {
@@ -254,6 +292,7 @@ fn selected_dye_display(
                 }
                 // Always update UUID (read-only, in case dye was just generated)
                 display_uuid.set(dye.preset_uuid.clone());
+                display_icon.set(dye.icon_path.clone());
             }
         }
         idx
@@ -288,9 +327,9 @@ fn selected_dye_display(
                                     .padding(PADDING_BTN_V)
                                     .font_size(FONT_BODY)
                                     .font_family("monospace".to_string())
-                                    .background(Color::WHITE)
+                                    .background(colors().bg_base)
                                     .border(1.0)
-                                    .border_color(BORDER_INPUT)
+                                    .border_color(border_input())
                                     .border_radius(RADIUS_STD)
                             }),
                     ))
@@ -316,9 +355,9 @@ fn selected_dye_display(
                                     .min_width(INPUT_MIN_WIDTH)
                                     .padding(PADDING_BTN_V)
                                     .font_size(FONT_BODY)
-                                    .background(Color::WHITE)
+                                    .background(colors().bg_base)
                                     .border(1.0)
-                                    .border_color(BORDER_INPUT)
+                                    .border_color(border_input())
                                     .border_radius(RADIUS_STD)
                             }),
                     ))
@@ -344,9 +383,9 @@ fn selected_dye_display(
                                     .min_width(INPUT_MIN_WIDTH)
                                     .padding(PADDING_BTN_V)
                                     .font_size(FONT_BODY)
-                                    .background(Color::WHITE)
+                                    .background(colors().bg_base)
                                     .border(1.0)
-                                    .border_color(BORDER_INPUT)
+                                    .border_color(border_input())
                                     .border_radius(RADIUS_STD)
                             }),
                     ))
@@ -359,13 +398,14 @@ fn selected_dye_display(
                                 .padding(PADDING_BTN_V)
                                 .font_size(FONT_BODY)
                                 .font_family("monospace".to_string())
-                                .background(BG_INPUT_READONLY)
+                                .background(bg_input_readonly())
                                 .border(1.0)
-                                .border_color(BORDER_INPUT)
+                                .border_color(border_input())
                                 .border_radius(RADIUS_STD)
                         }),
                     ))
                     .style(|s| s.width_full().items_center().gap(GAP_STD)),
+                    icon_row(generated_dyes, selected_index, display_icon, status),
                 ))
                 .style(|s| s.width_full().gap(GAP_STD))
                 .into_any()
@@ -375,3 +415,70 @@ fn selected_dye_display(
         },
     )
 }
+
+/// Icon picker for the selected dye; without an icon the export uses the
+/// dye's Cloth_Primary color as a swatch
+fn icon_row(
+    generated_dyes: RwSignal<Vec<crate::gui::state::GeneratedDyeEntry>>,
+    selected_index: RwSignal<Option<usize>>,
+    display_icon: RwSignal<Option<String>>,
+    status: RwSignal<String>,
+) -> impl IntoView {
+    let set_icon = move |icon: Option<String>| {
+        let idx = selected_index.get().unwrap_or(0);
+        let mut dyes = generated_dyes.get();
+        if idx < dyes.len() {
+            dyes[idx].icon_path = icon.clone();
+            generated_dyes.set(dyes);
+            display_icon.set(icon);
+        }
+    };
+
+    h_stack((
+        label(|| "Icon").style(|s| s.width(LABEL_WIDTH).font_size(FONT_BODY)),
+        label(move || {
+            display_icon.get().map_or_else(
+                || "Color swatch".to_string(),
+                |path| {
+                    std::path::Path::new(&path)
+                        .file_name()
+                        .map_or(path.clone(), |name| name.to_string_lossy().to_string())
+                },
+            )
+        })
+        .style(move |s| {
+            let s = s
+                .flex_grow(1.0)
+                .padding(PADDING_BTN_V)
+                .font_size(FONT_BODY)
+                .background(bg_input_readonly())
+                .border(1.0)
+                .border_color(border_input())
+                .border_radius(RADIUS_STD)
+                .text_ellipsis();
+            if display_icon.get().is_some() {
+                s
+            } else {
+                s.color(text_muted())
+            }
+        }),
+        button("Choose...")
+            .style(secondary_button_style)
+            .action(move || {
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_title("Select dye icon")
+                    .add_filter("Images", &["png", "dds", "PNG", "DDS"])
+                    .pick_file()
+                {
+                    let path = path.to_string_lossy().to_string();
+                    status.set(format!("Icon set to {}", path));
+                    set_icon(Some(path));
+                }
+            }),
+        button("Clear")
+            .style(secondary_button_style)
+            .disabled(move || display_icon.get().is_none())
+            .action(move || set_icon(None)),
+    ))
+    .style(|s| s.width_full().items_center().gap(GAP_STD))
+}
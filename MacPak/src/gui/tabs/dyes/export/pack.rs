@@ -0,0 +1,82 @@
+//! Packing an exported dye mod folder into a `.pak`
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::maclarian::mods::generate_info_json;
+use crate::maclarian::mods::info_json::calculate_file_md5;
+use crate::maclarian::pak::{CompressionMethod, PakOperations};
+use crate::maclarian::utils::find_game_install;
+
+/// A packed dye mod
+pub struct PackedMod {
+    pub pak_path: PathBuf,
+    pub md5: String,
+    /// Copy in the game's Mods folder, if one was made
+    pub installed_path: Option<PathBuf>,
+    /// Problems that didn't stop packing (info.json, copying to Mods)
+    pub warnings: Vec<String>,
+}
+
+/// Pack `mod_dir` into `<ModName>.pak` beside it (LZ4), write info.json next
+/// to the PAK and optionally copy the PAK into the game's Mods folder.
+///
+/// The exported folder is left alone whatever happens.
+pub fn pack_dye_mod(
+    mod_dir: &Path,
+    mod_name: &str,
+    copy_to_mods: bool,
+) -> Result<PackedMod, String> {
+    let parent = mod_dir.parent().unwrap_or(mod_dir);
+    let pak_path = parent.join(format!("{}.pak", mod_name));
+
+    PakOperations::create_with_compression(mod_dir, pak_path.as_path(), CompressionMethod::Lz4)
+        .map_err(|e| format!("Failed to create {}: {}", pak_path.display(), e))?;
+
+    let pak_str = pak_path.to_string_lossy().to_string();
+    let md5 = calculate_file_md5(&pak_str).unwrap_or_default();
+    let mut warnings = Vec::new();
+
+    let info = generate_info_json(&mod_dir.to_string_lossy(), &pak_str);
+    match info.content {
+        Some(content) if info.success => {
+            if let Err(e) = fs::write(parent.join("info.json"), content) {
+                warnings.push(format!("Failed to write info.json: {}", e));
+            }
+        }
+        _ => warnings.push(format!("Skipped info.json: {}", info.message)),
+    }
+
+    let installed_path = if copy_to_mods {
+        match copy_to_game_mods(&pak_path) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                warnings.push(e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok(PackedMod {
+        pak_path,
+        md5,
+        installed_path,
+        warnings,
+    })
+}
+
+/// Copy a PAK into the detected game's Mods folder
+fn copy_to_game_mods(pak_path: &Path) -> Result<PathBuf, String> {
+    let mods_dir = find_game_install()
+        .and_then(|install| install.mods_dir)
+        .ok_or_else(|| "Couldn't find the game's Mods folder".to_string())?;
+    let file_name = pak_path
+        .file_name()
+        .ok_or_else(|| "PAK path has no file name".to_string())?;
+    let dest = mods_dir.join(file_name);
+    fs::copy(pak_path, &dest)
+        .map_err(|e| format!("Failed to copy to {}: {}", mods_dir.display(), e))?;
+    Ok(dest)
+}
@@ -0,0 +1,125 @@
+//! TreasureTable.txt for an exported dye mod
+
+use crate::gui::state::GeneratedDyeEntry;
+
+/// Where the exported dyes end up
+pub struct TreasureTargets<'a> {
+    /// Vendor and custom table ids the pouch is added to
+    pub tables: &'a [String],
+    /// Also add this many of each dye to those tables
+    pub individual_dye_count: Option<u32>,
+}
+
+/// Build TreasureTable.txt: a table with every dye, the pouch's contents
+/// table and one block per target table. Each table appears once, so the
+/// file passes the stats lint.
+pub fn treasure_table_txt(
+    mod_name: &str,
+    dyes: &[GeneratedDyeEntry],
+    targets: &TreasureTargets,
+) -> String {
+    let mut content = String::new();
+
+    // Main treasure table containing all individual dyes
+    let subtables: Vec<String> = dyes
+        .iter()
+        .map(|dye| object_subtable(&dye.name, 1))
+        .collect();
+    content.push_str(&format!(
+        "new treasuretable \"{mod_name}_Dyes\"\n{}\n\n",
+        subtables.join("\n")
+    ));
+
+    // Container treasure table (what the pouch spawns)
+    let own_tables = [
+        format!("{mod_name}_Dyes"),
+        format!("{mod_name}_DyePouch_Contents"),
+    ];
+    content.push_str(&format!(
+        "new treasuretable \"{}\"\nCanMerge 1\n{}\n\n",
+        own_tables[1],
+        object_subtable(&format!("{mod_name}_DyePouch"), 1)
+    ));
+
+    // Add the pouch (and the dyes, if asked) to every target table
+    let mut written: Vec<&str> = Vec::new();
+    for table in targets.tables {
+        let table = table.trim();
+        if table.is_empty() || written.contains(&table) || own_tables.iter().any(|t| t == table) {
+            continue;
+        }
+        written.push(table);
+
+        content.push_str(&format!("new treasuretable \"{table}\"\nCanMerge 1\n"));
+        content.push_str(&object_subtable(&format!("{mod_name}_DyePouch"), 1));
+        content.push('\n');
+        if let Some(count) = targets.individual_dye_count {
+            for dye in dyes {
+                content.push_str(&object_subtable(&dye.name, count.max(1)));
+                content.push('\n');
+            }
+        }
+        content.push('\n');
+    }
+
+    content.trim_end().to_string()
+}
+
+/// Subtable dropping `count` of one item
+fn object_subtable(item_name: &str, count: u32) -> String {
+    format!("new subtable \"{count},1\"\nobject category \"I_{item_name}\",1,0,0,0,0,0,0,0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maclarian::formats::stats::lint_stats_str;
+    use std::collections::HashMap;
+
+    fn dye(name: &str) -> GeneratedDyeEntry {
+        GeneratedDyeEntry {
+            name: name.to_string(),
+            display_name: String::new(),
+            description: String::new(),
+            preset_uuid: String::new(),
+            template_uuid: String::new(),
+            name_handle: String::new(),
+            desc_handle: String::new(),
+            colors: HashMap::new(),
+            icon_path: None,
+        }
+    }
+
+    #[test]
+    fn test_writes_each_table_once_and_lints_clean() {
+        let dyes = [dye("MyMod_Red"), dye("MyMod_Blue")];
+        let tables = [
+            "TUT_Chest_Potions".to_string(),
+            "DEN_Entrance_Trade".to_string(),
+            // Repeats, blanks and the mod's own tables are skipped
+            " TUT_Chest_Potions ".to_string(),
+            String::new(),
+            "MyMod_Dyes".to_string(),
+        ];
+        let txt = treasure_table_txt(
+            "MyMod",
+            &dyes,
+            &TreasureTargets {
+                tables: &tables,
+                individual_dye_count: Some(3),
+            },
+        );
+
+        assert_eq!(lint_stats_str(&txt), Vec::new());
+        assert_eq!(txt.matches("new treasuretable").count(), 4);
+        assert_eq!(
+            txt.matches("new treasuretable \"TUT_Chest_Potions\"")
+                .count(),
+            1
+        );
+        // Pouch contents table + one pouch per target
+        assert_eq!(txt.matches("\"I_MyMod_DyePouch\"").count(), 3);
+        // Each target gets 3 of every dye
+        assert_eq!(txt.matches("new subtable \"3,1\"").count(), 4);
+    }
+}
@@ -7,6 +7,7 @@ use floem::views::PlaceholderTextClass;
 use super::export::check_required_colors_at_default;
 use super::shared::constants::*;
 use super::shared::{button_style, collect_colors_skip_defaults, input_style};
+use crate::gui::shared::colors;
 use crate::gui::state::{DyesState, GeneratedDyeEntry};
 use crate::gui::utils::{UuidFormat, generate_uuid};
 
@@ -76,9 +77,7 @@ pub fn generate_dye_section(state: DyesState) -> impl IntoView {
                 text_input(individual_dye_name)
                     .placeholder("e.g. MyMod_Dye_Crimson")
                     .style(|s| {
-                        input_style(s).class(PlaceholderTextClass, |s| {
-                            s.color(Color::rgb8(120, 120, 120))
-                        })
+                        input_style(s).class(PlaceholderTextClass, |s| s.color(colors().text_muted))
                     }),
             ))
             .style(|s| s.width_full().items_center().gap(GAP_STD)),
@@ -88,9 +87,7 @@ pub fn generate_dye_section(state: DyesState) -> impl IntoView {
                 text_input(individual_display_name)
                     .placeholder("e.g. Crimson Dye")
                     .style(|s| {
-                        input_style(s).class(PlaceholderTextClass, |s| {
-                            s.color(Color::rgb8(120, 120, 120))
-                        })
+                        input_style(s).class(PlaceholderTextClass, |s| s.color(colors().text_muted))
                     }),
             ))
             .style(|s| s.width_full().items_center().gap(GAP_STD)),
@@ -100,9 +97,7 @@ pub fn generate_dye_section(state: DyesState) -> impl IntoView {
                 text_input(individual_description)
                     .placeholder("e.g. A deep crimson dye")
                     .style(|s| {
-                        input_style(s).class(PlaceholderTextClass, |s| {
-                            s.color(Color::rgb8(120, 120, 120))
-                        })
+                        input_style(s).class(PlaceholderTextClass, |s| s.color(colors().text_muted))
                     }),
             ))
             .style(|s| s.width_full().items_center().gap(GAP_STD)),
@@ -118,13 +113,13 @@ pub fn generate_dye_section(state: DyesState) -> impl IntoView {
                 label(|| "Generate Dye")
                     .style(move |s| {
                         let base = button_style(s)
-                            .color(Color::WHITE)
+                            .color(colors().text_on_accent)
                             .font_weight(Weight::SEMIBOLD);
                         // Dim the button if validation fails
                         if missing.get().is_empty() {
                             base.background(ACCENT_SUCCESS)
                         } else {
-                            base.background(Color::rgb8(150, 150, 150))
+                            base.background(colors().text_disabled)
                         }
                     })
                     .on_click_stop(move |_| {
@@ -178,6 +173,7 @@ pub fn generate_dye_section(state: DyesState) -> impl IntoView {
                             name_handle,
                             desc_handle,
                             colors,
+                            icon_path: None,
                         };
 
                         // Add to list and select the new entry
@@ -200,18 +196,18 @@ pub fn generate_dye_section(state: DyesState) -> impl IntoView {
             s.width_full()
                 .padding(PADDING_BTN_H)
                 .gap(GAP_STD)
-                .background(BG_CARD)
+                .background(bg_card())
                 .border(1.0)
-                .border_color(BORDER_CARD)
+                .border_color(border_card())
                 .border_radius(RADIUS_STD)
         }),
     ))
     .style(|s| {
         s.width_full()
             .padding(PADDING_LG)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(BORDER_CARD)
+            .border_color(border_card())
             .border_radius(6.0)
     })
 }
@@ -34,7 +34,7 @@ pub fn imported_fields_display(
                 label(|| "No data imported")
                     .style(|s| {
                         s.font_size(FONT_BODY)
-                            .color(TEXT_MUTED)
+                            .color(text_muted())
                             .padding(PADDING_STD)
                     })
                     .into_any()
@@ -57,12 +57,12 @@ pub fn imported_fields_display(
                                 .padding(PADDING_BTN_V)
                                 .font_size(FONT_BODY)
                                 .font_family("monospace".to_string())
-                                .background(BG_INPUT_READONLY)
+                                .background(bg_input_readonly())
                                 .border(1.0)
-                                .border_color(BORDER_INPUT)
+                                .border_color(border_input())
                                 .border_radius(RADIUS_STD);
                             if dye_name.get().is_empty() {
-                                s.color(TEXT_MUTED)
+                                s.color(text_muted())
                             } else {
                                 s
                             }
@@ -86,12 +86,12 @@ pub fn imported_fields_display(
                                 .flex_grow(1.0)
                                 .padding(PADDING_BTN_V)
                                 .font_size(FONT_BODY)
-                                .background(BG_INPUT_READONLY)
+                                .background(bg_input_readonly())
                                 .border(1.0)
-                                .border_color(BORDER_INPUT)
+                                .border_color(border_input())
                                 .border_radius(RADIUS_STD);
                             if display_name.get().is_empty() {
-                                s.color(TEXT_MUTED)
+                                s.color(text_muted())
                             } else {
                                 s
                             }
@@ -114,12 +114,12 @@ pub fn imported_fields_display(
                                 .flex_grow(1.0)
                                 .padding(PADDING_BTN_V)
                                 .font_size(FONT_BODY)
-                                .background(BG_INPUT_READONLY)
+                                .background(bg_input_readonly())
                                 .border(1.0)
-                                .border_color(BORDER_INPUT)
+                                .border_color(border_input())
                                 .border_radius(RADIUS_STD);
                             if mod_name.get().is_empty() {
-                                s.color(TEXT_MUTED)
+                                s.color(text_muted())
                             } else {
                                 s
                             }
@@ -142,12 +142,12 @@ pub fn imported_fields_display(
                                 .flex_grow(1.0)
                                 .padding(PADDING_BTN_V)
                                 .font_size(FONT_BODY)
-                                .background(BG_INPUT_READONLY)
+                                .background(bg_input_readonly())
                                 .border(1.0)
-                                .border_color(BORDER_INPUT)
+                                .border_color(border_input())
                                 .border_radius(RADIUS_STD);
                             if mod_author.get().is_empty() {
-                                s.color(TEXT_MUTED)
+                                s.color(text_muted())
                             } else {
                                 s
                             }
@@ -10,6 +10,7 @@ use super::shared::constants::*;
 use super::shared::secondary_button_style;
 use crate::gui::state::DyesState;
 
+use crate::gui::shared::colors;
 use components::{imported_fields_display, lsf_import_selector, txt_import_selector};
 pub use operations::import_from_mod_folder;
 
@@ -81,9 +82,9 @@ pub fn import_section(state: DyesState) -> impl IntoView {
         .style(|s| {
             s.width_full()
                 .padding(PADDING_BTN_H)
-                .background(BG_CARD)
+                .background(bg_card())
                 .border(1.0)
-                .border_color(BORDER_CARD)
+                .border_color(border_card())
                 .border_radius(RADIUS_STD)
         }),
     ))
@@ -91,9 +92,9 @@ pub fn import_section(state: DyesState) -> impl IntoView {
         s.flex_grow(1.0)
             .flex_basis(0.0)
             .padding(PADDING_LG)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(BORDER_CARD)
+            .border_color(border_card())
             .border_radius(6.0)
     })
 }
@@ -14,7 +14,7 @@ use floem::prelude::*;
 use self::vendors::vendor_selection_section;
 use crate::gui::state::{AppState, DyesState};
 use crate::gui::utils::meta_dialog::{MetaDialogSignals, meta_dialog_with_signals_and_extra};
-use export::export_section;
+use export::{export_section, pack_options};
 use generate::generate_dye_section;
 pub use import::import_from_mod_folder;
 use import::import_section;
@@ -27,7 +27,9 @@ pub fn dyes_tab(_app_state: AppState, state: DyesState) -> impl IntoView {
     let show_meta = state.show_meta_dialog;
     let active_picker_color = state.active_picker_color;
     let state_for_export = state.clone();
-    let selected_vendors = state.selected_vendors;
+    let state_for_vendors = state.clone();
+    let export_as_pak = state.export_as_pak;
+    let copy_to_game_mods = state.copy_to_game_mods;
 
     // Create signals struct for meta dialog
     let meta_signals = MetaDialogSignals {
@@ -57,13 +59,29 @@ pub fn dyes_tab(_app_state: AppState, state: DyesState) -> impl IntoView {
             .set_title("Select output folder for mod")
             .pick_folder()
         {
-            let message = export::export_dye_mod(&state_for_export, &path, &name);
+            let message = match export::export_dye_mod(&state_for_export, &path, &name) {
+                Ok(message) if export_as_pak.get() => {
+                    // A failed pack keeps the exported folder
+                    match export::pack_dye_mod(&path.join(&name), &name, copy_to_game_mods.get()) {
+                        Ok(packed) => packed_message(&packed),
+                        Err(e) => format!("{}, but packing failed: {}", message, e),
+                    }
+                }
+                Ok(message) => message,
+                Err(e) => e,
+            };
             state_for_export.status_message.set(message);
         }
     };
 
-    // Vendor selection extra content for export dialog
-    let vendor_selection_content = move || vendor_selection_section(selected_vendors);
+    // Vendor selection and packing options for the export dialog
+    let vendor_selection_content = move || {
+        v_stack((
+            vendor_selection_section(state_for_vendors.clone()),
+            pack_options(export_as_pak, copy_to_game_mods),
+        ))
+        .style(|s| s.width_full())
+    };
 
     v_stack((
         // Header - matches PAK Ops style
@@ -107,7 +125,19 @@ pub fn dyes_tab(_app_state: AppState, state: DyesState) -> impl IntoView {
     .style(|s| {
         s.width_full()
             .height_full()
-            .background(BG_CARD)
+            .background(bg_card())
             .position(floem::style::Position::Relative)
     })
 }
+
+/// Status line for a packed mod: where it went and its MD5
+fn packed_message(packed: &export::PackedMod) -> String {
+    let mut message = format!("Packed {} (MD5 {})", packed.pak_path.display(), packed.md5);
+    if let Some(installed) = &packed.installed_path {
+        message.push_str(&format!(", copied to {}", installed.display()));
+    }
+    for warning in &packed.warnings {
+        message.push_str(&format!(". {}", warning));
+    }
+    message
+}
@@ -11,6 +11,7 @@ use floem_picker::{SolidColor, solid_picker};
 use floem_reactive::create_effect;
 
 use super::shared::constants::*;
+use crate::gui::shared::colors;
 use crate::gui::state::DyesState;
 
 /// Creates the color picker overlay.
@@ -99,7 +100,7 @@ pub fn color_picker_overlay(state: DyesState) -> impl IntoView {
                 s.width(320.0)
                     .min_height(500.0)
                     .padding(12.0)
-                    .background(Color::WHITE)
+                    .background(colors().bg_base)
                     .border_radius(8.0)
                     .box_shadow_blur(20.0)
                     .box_shadow_color(Color::rgba8(0, 0, 0, 50))
@@ -6,6 +6,7 @@ use floem::text::Weight;
 use super::color_row::color_row;
 use super::shared::ColorCategory;
 use super::shared::constants::*;
+use crate::gui::shared::colors;
 use crate::gui::state::{DyeColorEntry, DyesState};
 
 /// Creates a section header - matches Results Log style
@@ -20,9 +21,9 @@ fn section_header(title: &'static str) -> impl IntoView {
 /// Inner card style for the color rows
 fn inner_card_style(s: floem::style::Style) -> floem::style::Style {
     s.width_full()
-        .background(BG_CARD)
+        .background(bg_card())
         .border(1.0)
-        .border_color(BORDER_CARD)
+        .border_color(border_card())
         .border_radius(RADIUS_STD)
 }
 
@@ -31,9 +32,9 @@ fn outer_card_style(s: floem::style::Style) -> floem::style::Style {
     s.flex_grow(1.0)
         .flex_basis(0.0)
         .padding(PADDING_LG)
-        .background(Color::WHITE)
+        .background(colors().bg_base)
         .border(1.0)
-        .border_color(BORDER_CARD)
+        .border_color(border_card())
         .border_radius(6.0)
 }
 
@@ -122,8 +123,8 @@ pub fn header_section(status: RwSignal<String>) -> impl IntoView {
                                 .padding_vert(PADDING_BTN_V)
                                 .border_radius(RADIUS_STD)
                                 .font_size(FONT_STATUS)
-                                .background(BG_SUCCESS)
-                                .color(TEXT_SUCCESS)
+                                .background(bg_success())
+                                .color(text_success())
                         })
                         .into_any()
                 }
@@ -135,8 +136,8 @@ pub fn header_section(status: RwSignal<String>) -> impl IntoView {
             .padding(PADDING_LG)
             .gap(GAP_STD)
             .items_center()
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border_bottom(1.0)
-            .border_color(BORDER_CARD)
+            .border_color(border_card())
     })
 }
@@ -1,44 +1,69 @@
 //! Shared constants for colors, spacing, and typography
+//!
+//! Neutral colors are functions that follow the app theme; call them inside
+//! style closures so they update when the theme changes.
 
 use floem::prelude::Color;
 
+use crate::gui::shared::colors;
+
 // =============================================================================
 // COLORS - Background
 // =============================================================================
 
-/// Card background - very light gray (250, 250, 250)
-pub const BG_CARD: Color = Color::rgb8(250, 250, 250);
+/// Card background - very light gray
+pub fn bg_card() -> Color {
+    colors().bg_surface
+}
 
-/// Read-only input background (245, 245, 245)
-pub const BG_INPUT_READONLY: Color = Color::rgb8(245, 245, 245);
+/// Read-only input background
+pub fn bg_input_readonly() -> Color {
+    colors().bg_elevated
+}
 
-/// Disabled/subtle background (240, 240, 240)
-pub const BG_DISABLED: Color = Color::rgb8(240, 240, 240);
+/// Disabled/subtle background
+pub fn bg_disabled() -> Color {
+    colors().bg_elevated
+}
 
-/// Secondary button background - Tailwind gray-100 (243, 244, 246)
-pub const BG_SECONDARY: Color = Color::rgb8(243, 244, 246);
+/// Secondary button background - Tailwind gray-100
+pub fn bg_secondary() -> Color {
+    colors().bg_elevated
+}
 
-/// Navigation button background - Tailwind gray-200 (229, 231, 235)
-pub const BG_NAV_BUTTON: Color = Color::rgb8(229, 231, 235);
+/// Navigation button background - Tailwind gray-200
+pub fn bg_nav_button() -> Color {
+    colors().bg_hover
+}
 
-/// Success/green background (232, 245, 233)
-pub const BG_SUCCESS: Color = Color::rgb8(232, 245, 233);
+/// Success/green background
+pub fn bg_success() -> Color {
+    colors().success_bg
+}
 
 // =============================================================================
 // COLORS - Border
 // =============================================================================
 
-/// Input border - medium gray (200, 200, 200)
-pub const BORDER_INPUT: Color = Color::rgb8(200, 200, 200);
+/// Input border - medium gray
+pub fn border_input() -> Color {
+    colors().border_strong
+}
 
-/// Card border - light gray (220, 220, 220)
-pub const BORDER_CARD: Color = Color::rgb8(220, 220, 220);
+/// Card border - light gray
+pub fn border_card() -> Color {
+    colors().border
+}
 
-/// Darker border (180, 180, 180)
-pub const BORDER_DARK: Color = Color::rgb8(180, 180, 180);
+/// Darker border
+pub fn border_dark() -> Color {
+    colors().border_strong
+}
 
-/// Secondary border - Tailwind gray-300 (209, 213, 219)
-pub const BORDER_SECONDARY: Color = Color::rgb8(209, 213, 219);
+/// Secondary border - Tailwind gray-300
+pub fn border_secondary() -> Color {
+    colors().border
+}
 
 /// Success/green border (129, 199, 132)
 pub const BORDER_SUCCESS: Color = Color::rgb8(129, 199, 132);
@@ -47,17 +72,25 @@ pub const BORDER_SUCCESS: Color = Color::rgb8(129, 199, 132);
 // COLORS - Text
 // =============================================================================
 
-/// Muted text - placeholder gray (150, 150, 150)
-pub const TEXT_MUTED: Color = Color::rgb8(150, 150, 150);
-
-/// Dark text (80, 80, 80)
-pub const TEXT_DARK: Color = Color::rgb8(80, 80, 80);
-
-/// Button text - Tailwind gray-700 (55, 65, 81)
-pub const TEXT_BUTTON: Color = Color::rgb8(55, 65, 81);
-
-/// Success text - green (46, 125, 50)
-pub const TEXT_SUCCESS: Color = Color::rgb8(46, 125, 50);
+/// Muted text - placeholder gray
+pub fn text_muted() -> Color {
+    colors().text_muted
+}
+
+/// Dark text
+pub fn text_dark() -> Color {
+    colors().text_secondary
+}
+
+/// Button text - Tailwind gray-700
+pub fn text_button() -> Color {
+    colors().text_primary
+}
+
+/// Success text - green
+pub fn text_success() -> Color {
+    colors().success
+}
 
 // =============================================================================
 // COLORS - Accent
@@ -29,7 +29,7 @@ pub fn selector_container_green(s: floem::style::Style) -> floem::style::Style {
         .margin_bottom(PADDING_STD)
         .gap(GAP_STD)
         .items_center()
-        .background(BG_SUCCESS)
+        .background(bg_success())
         .border(1.0)
         .border_color(BORDER_SUCCESS)
         .border_radius(RADIUS_STD)
@@ -42,16 +42,16 @@ pub fn selector_container_gray(s: floem::style::Style) -> floem::style::Style {
         .margin_bottom(PADDING_STD)
         .gap(GAP_STD)
         .items_center()
-        .background(BG_SECONDARY)
+        .background(bg_secondary())
         .border(1.0)
-        .border_color(BORDER_SECONDARY)
+        .border_color(border_secondary())
         .border_radius(RADIUS_STD)
 }
 
 /// Empty state label style
 pub fn empty_state_style(s: floem::style::Style) -> floem::style::Style {
     s.font_size(FONT_BODY)
-        .color(TEXT_MUTED)
+        .color(text_muted())
         .padding(PADDING_STD)
 }
 
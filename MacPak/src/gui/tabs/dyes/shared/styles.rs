@@ -1,7 +1,7 @@
 //! UI styles shared between import and export sections
 
 use super::constants::*;
-use floem::prelude::*;
+use crate::gui::shared::colors;
 
 /// Input field style - fills available width
 pub fn input_style(s: floem::style::Style) -> floem::style::Style {
@@ -12,9 +12,9 @@ pub fn input_style(s: floem::style::Style) -> floem::style::Style {
         .padding(PADDING_BTN_V)
         .font_size(FONT_BODY)
         .font_family("monospace".to_string())
-        .background(Color::WHITE)
+        .background(colors().bg_base)
         .border(1.0)
-        .border_color(BORDER_INPUT)
+        .border_color(border_input())
         .border_radius(RADIUS_STD)
 }
 
@@ -24,7 +24,7 @@ pub fn button_style(s: floem::style::Style) -> floem::style::Style {
         .padding_vert(PADDING_BTN_V)
         .font_size(FONT_BODY)
         .background(ACCENT_PRIMARY)
-        .color(Color::WHITE)
+        .color(colors().text_on_accent)
         .border_radius(RADIUS_STD)
         .cursor(floem::style::CursorStyle::Pointer)
 }
@@ -34,10 +34,10 @@ pub fn secondary_button_style(s: floem::style::Style) -> floem::style::Style {
     s.padding_horiz(PADDING_BTN_H)
         .padding_vert(PADDING_BTN_V)
         .font_size(FONT_BODY)
-        .background(BG_SECONDARY)
-        .color(TEXT_BUTTON)
+        .background(bg_secondary())
+        .color(text_button())
         .border(1.0)
-        .border_color(BORDER_SECONDARY)
+        .border_color(border_secondary())
         .border_radius(RADIUS_STD)
         .cursor(floem::style::CursorStyle::Pointer)
 }
@@ -47,7 +47,7 @@ pub fn nav_button_style(s: floem::style::Style) -> floem::style::Style {
     s.padding_horiz(PADDING_STD)
         .padding_vert(4.0)
         .font_size(FONT_BODY)
-        .background(BG_NAV_BUTTON)
+        .background(bg_nav_button())
         .border_radius(RADIUS_SM)
         .cursor(floem::style::CursorStyle::Pointer)
 }
@@ -58,8 +58,8 @@ pub fn selector_display_style(s: floem::style::Style) -> floem::style::Style {
         .padding(PADDING_BTN_V)
         .font_size(FONT_BODY)
         .font_family("monospace".to_string())
-        .background(Color::WHITE)
+        .background(colors().bg_base)
         .border(1.0)
-        .border_color(BORDER_SECONDARY)
+        .border_color(border_secondary())
         .border_radius(RADIUS_SM)
 }
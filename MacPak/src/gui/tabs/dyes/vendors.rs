@@ -1,11 +1,14 @@
 //! Vendor selection UI for dye export
 //!
 //! Provides vendor checkboxes organized by act for selecting
-//! where dyes should spawn in the game.
+//! where dyes should spawn in the game, plus user-defined treasure tables.
 
+use floem::event::{Event, EventListener};
+use floem::keyboard::{Key, NamedKey};
 use floem::prelude::*;
 
-use crate::gui::state::VENDOR_DEFS;
+use crate::gui::shared::colors;
+use crate::gui::state::{CustomTreasureTable, DyesState, VENDOR_DEFS};
 
 /// Vendor indices by act (slimmed-down selection)
 const ACT1_VENDOR_INDICES: &[usize] = &[0, 1, 2, 6, 7, 10, 14, 15, 16];
@@ -13,28 +16,30 @@ const ACT2_VENDOR_INDICES: &[usize] = &[17, 18, 19, 20, 21];
 const ACT3_VENDOR_INDICES: &[usize] = &[24, 25, 26, 29, 33, 34, 37, 38, 40];
 
 /// Vendor selection section for export dialog
-pub fn vendor_selection_section(selected_vendors: RwSignal<Vec<bool>>) -> impl IntoView {
+pub fn vendor_selection_section(state: DyesState) -> impl IntoView {
+    let selected_vendors = state.selected_vendors;
+    let state_preset = state.clone();
+
     v_stack((
         h_stack((
-            label(|| "Spawn Locations")
-                .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+            label(|| "Spawn Locations").style(|s| s.font_size(12.0).color(colors().text_secondary)),
             empty().style(|s| s.flex_grow(1.0)),
             // Select All / Deselect All buttons
             label(|| "All")
                 .style(|s| {
                     s.font_size(11.0)
-                        .color(Color::rgb8(59, 130, 246))
+                        .color(colors().accent)
                         .cursor(floem::style::CursorStyle::Pointer)
                         .padding_horiz(4.0)
                 })
                 .on_click_stop(move |_| {
                     selected_vendors.set(vec![true; VENDOR_DEFS.len()]);
                 }),
-            label(|| "|").style(|s| s.font_size(11.0).color(Color::rgb8(180, 180, 180))),
+            label(|| "|").style(|s| s.font_size(11.0).color(colors().text_disabled)),
             label(|| "None")
                 .style(|s| {
                     s.font_size(11.0)
-                        .color(Color::rgb8(59, 130, 246))
+                        .color(colors().accent)
                         .cursor(floem::style::CursorStyle::Pointer)
                         .padding_horiz(4.0)
                 })
@@ -42,6 +47,15 @@ pub fn vendor_selection_section(selected_vendors: RwSignal<Vec<bool>>) -> impl I
                     let vendors: Vec<bool> = VENDOR_DEFS.iter().map(|v| v.always_enabled).collect();
                     selected_vendors.set(vendors);
                 }),
+            label(|| "|").style(|s| s.font_size(11.0).color(colors().text_disabled)),
+            label(|| "Tutorial Chest")
+                .style(|s| {
+                    s.font_size(11.0)
+                        .color(colors().accent)
+                        .cursor(floem::style::CursorStyle::Pointer)
+                        .padding_horiz(4.0)
+                })
+                .on_click_stop(move |_| state_preset.apply_tutorial_chest_preset()),
         ))
         .style(|s| s.width_full().items_center().margin_top(12.0)),
         // 3-column layout by act
@@ -51,6 +65,7 @@ pub fn vendor_selection_section(selected_vendors: RwSignal<Vec<bool>>) -> impl I
             vendor_column("Act 3", ACT3_VENDOR_INDICES, selected_vendors),
         ))
         .style(|s| s.width_full().gap(8.0).margin_top(4.0)),
+        custom_tables_section(state),
     ))
     .style(|s| s.width_full())
 }
@@ -65,7 +80,7 @@ fn vendor_column(
         label(move || title).style(|s| {
             s.font_size(11.0)
                 .font_weight(floem::text::Weight::SEMIBOLD)
-                .color(Color::rgb8(80, 80, 80))
+                .color(colors().text_secondary)
                 .padding_bottom(4.0)
         }),
         scroll(
@@ -110,13 +125,13 @@ fn vendor_column(
                             label(move || name.to_string()).style(move |s| {
                                 let base = s.font_size(11.0);
                                 if is_always {
-                                    base.color(Color::rgb8(140, 140, 140))
+                                    base.color(colors().text_muted)
                                 } else {
                                     base
                                 }
                             }),
                             label(move || loc.to_string())
-                                .style(|s| s.font_size(9.0).color(Color::rgb8(140, 140, 140))),
+                                .style(|s| s.font_size(9.0).color(colors().text_muted)),
                         ))
                         .style(|s| s.margin_left(4.0)),
                     ))
@@ -134,9 +149,161 @@ fn vendor_column(
         s.flex_grow(1.0)
             .flex_basis(0.0)
             .padding(8.0)
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
             .border(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
             .border_radius(4.0)
     })
 }
+
+/// User-defined treasure tables and the individual dye option
+fn custom_tables_section(state: DyesState) -> impl IntoView {
+    let custom_tables = state.custom_tables;
+    let add_individual = state.add_individual_dyes;
+    let dye_count = state.individual_dye_count;
+    let count_text = RwSignal::new(dye_count.get_untracked().to_string());
+    let commit_count = move || match count_text.get_untracked().trim().parse::<u32>() {
+        Ok(count) if count > 0 => dye_count.set(count),
+        _ => count_text.set(dye_count.get_untracked().to_string()),
+    };
+
+    v_stack((
+        h_stack((
+            label(|| "Custom Treasure Tables")
+                .style(|s| s.font_size(12.0).color(colors().text_secondary)),
+            empty().style(|s| s.flex_grow(1.0)),
+            label(|| "+ Add Table")
+                .style(|s| {
+                    s.font_size(11.0)
+                        .color(colors().accent)
+                        .cursor(floem::style::CursorStyle::Pointer)
+                        .padding_horiz(4.0)
+                })
+                .on_click_stop(move |_| {
+                    custom_tables.update(|tables| {
+                        tables.push(CustomTreasureTable {
+                            enabled: true,
+                            ..Default::default()
+                        });
+                    });
+                }),
+        ))
+        .style(|s| s.width_full().items_center().margin_top(12.0)),
+        dyn_container(
+            move || custom_tables.with(Vec::len),
+            move |count| {
+                if count == 0 {
+                    return label(|| "No custom tables. Add one by its treasure table id.")
+                        .style(|s| s.font_size(11.0).color(colors().text_muted))
+                        .into_any();
+                }
+                v_stack_from_iter((0..count).map(|idx| custom_table_row(custom_tables, idx)))
+                    .style(|s| s.width_full().gap(4.0))
+                    .into_any()
+            },
+        )
+        .style(|s| s.width_full().margin_top(4.0)),
+        h_stack((
+            checkbox(move || add_individual.get())
+                .on_update(move |checked| add_individual.set(checked)),
+            label(|| "Also add each dye to the selected tables, count:")
+                .style(|s| s.font_size(11.0)),
+            text_input(count_text)
+                .disabled(move || !add_individual.get())
+                .style(|s| {
+                    s.width(40.0)
+                        .padding(2.0)
+                        .font_size(11.0)
+                        .border(1.0)
+                        .border_color(colors().border_strong)
+                        .border_radius(4.0)
+                })
+                .on_event_cont(EventListener::KeyDown, move |e| {
+                    if let Event::KeyDown(key_event) = e {
+                        if key_event.key.logical_key == Key::Named(NamedKey::Enter) {
+                            commit_count();
+                        }
+                    }
+                })
+                .on_event_cont(EventListener::FocusLost, move |_| commit_count()),
+        ))
+        .style(|s| s.items_center().gap(6.0).margin_top(8.0)),
+    ))
+    .style(|s| s.width_full())
+}
+
+/// Enabled checkbox, name and table id inputs and a remove button
+fn custom_table_row(
+    custom_tables: RwSignal<Vec<CustomTreasureTable>>,
+    idx: usize,
+) -> impl IntoView {
+    let table = custom_tables.with_untracked(|tables| tables.get(idx).cloned().unwrap_or_default());
+    let name = RwSignal::new(table.name);
+    let table_id = RwSignal::new(table.table_id);
+
+    h_stack((
+        checkbox(move || custom_tables.with(|tables| tables.get(idx).is_some_and(|t| t.enabled)))
+            .on_update(move |checked| update_table(custom_tables, idx, |t| t.enabled = checked)),
+        table_input(name, "Name", 140.0, move || {
+            update_table(custom_tables, idx, |t| t.name = name.get_untracked());
+        }),
+        table_input(table_id, "Treasure table id", 0.0, move || {
+            update_table(custom_tables, idx, |t| {
+                t.table_id = table_id.get_untracked().trim().to_string();
+            });
+        }),
+        label(|| "✕")
+            .style(|s| {
+                s.font_size(11.0)
+                    .color(colors().error)
+                    .cursor(floem::style::CursorStyle::Pointer)
+                    .padding_horiz(4.0)
+            })
+            .on_click_stop(move |_| {
+                custom_tables.update(|tables| {
+                    if idx < tables.len() {
+                        tables.remove(idx);
+                    }
+                });
+            }),
+    ))
+    .style(|s| s.width_full().items_center().gap(6.0))
+}
+
+fn update_table(
+    custom_tables: RwSignal<Vec<CustomTreasureTable>>,
+    idx: usize,
+    change: impl FnOnce(&mut CustomTreasureTable),
+) {
+    custom_tables.update(|tables| {
+        if let Some(table) = tables.get_mut(idx) {
+            change(table);
+        }
+    });
+}
+
+/// Text input in a custom table row, committed when focus leaves; a `width`
+/// of 0 fills the remaining space
+fn table_input(
+    value: RwSignal<String>,
+    placeholder: &str,
+    width: f32,
+    commit: impl Fn() + 'static,
+) -> impl IntoView {
+    text_input(value)
+        .placeholder(placeholder)
+        .style(move |s| {
+            let s = s
+                .padding(2.0)
+                .font_size(11.0)
+                .border(1.0)
+                .border_color(colors().border_strong)
+                .border_radius(4.0);
+            if width > 0.0 {
+                s.width(width)
+            } else {
+                s.flex_grow(1.0).min_width(0.0)
+            }
+        })
+        .on_event_cont(EventListener::FocusLost, move |_| commit())
+}
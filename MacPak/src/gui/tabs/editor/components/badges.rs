@@ -3,6 +3,7 @@
 use floem::prelude::*;
 use floem::text::Weight;
 
+use crate::gui::shared::colors;
 use crate::gui::state::EditorTabsState;
 
 pub fn format_badge(tabs_state: EditorTabsState) -> impl IntoView {
@@ -75,8 +76,8 @@ pub fn save_status_badge(tabs_state: EditorTabsState) -> impl IntoView {
                             .padding_vert(6.0)
                             .border_radius(4.0)
                             .font_size(12.0)
-                            .background(Color::rgb8(232, 245, 233)) // BG_SUCCESS
-                            .color(Color::rgb8(46, 125, 50)) // TEXT_SUCCESS
+                            .background(colors().success_bg) // BG_SUCCESS
+                            .color(colors().success) // TEXT_SUCCESS
                     })
                     .into_any()
             }
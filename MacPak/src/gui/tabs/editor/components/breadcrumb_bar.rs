@@ -0,0 +1,130 @@
+//! Breadcrumb bar and node folding for LSX documents
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use floem::prelude::*;
+use floem::views::editor::core::cursor::Cursor;
+use floem::views::editor::core::editor::EditType;
+use floem::views::editor::core::selection::Selection;
+use floem::views::editor::text::Document;
+
+use super::super::lsx::{FoldStore, LsxLines, breadcrumb};
+use crate::gui::shared::colors;
+
+/// LSX state shared by the editor's key handler, its styling and the bar
+#[derive(Clone)]
+pub struct LsxEditorState {
+    /// Lines the styling tokenizes; kept in step with the document
+    lines: Rc<RefCell<LsxLines>>,
+    folds: Rc<RefCell<FoldStore>>,
+}
+
+#[derive(Clone, Copy)]
+enum FoldAction {
+    Fold,
+    Unfold,
+    UnfoldAll,
+}
+
+impl LsxEditorState {
+    pub fn new(lines: Rc<RefCell<LsxLines>>) -> Self {
+        Self {
+            lines,
+            folds: Rc::new(RefCell::new(FoldStore::default())),
+        }
+    }
+
+    /// Re-tokenize after the document changed
+    pub fn sync_text(&self, text: &str) {
+        self.lines.borrow_mut().set_text(text);
+    }
+
+    /// Document text with folds expanded, for saving
+    pub fn expanded(&self, text: &str) -> String {
+        self.folds.borrow().expand(text)
+    }
+
+    /// Fold or unfold at the cursor by editing the document
+    fn apply(&self, doc: &dyn Document, offset: usize, action: FoldAction) {
+        let rope = doc.text();
+        let text = rope.to_string();
+        let line = rope.line_of_offset(offset.min(rope.len()));
+
+        let edit = match action {
+            FoldAction::Fold => self.folds.borrow_mut().fold_at(&text, line),
+            FoldAction::Unfold => self.folds.borrow().unfold_at(&text, line),
+            FoldAction::UnfoldAll => self.folds.borrow().unfold_all(&text),
+        };
+        let Some(edit) = edit else {
+            return;
+        };
+
+        doc.edit_single(
+            Selection::region(edit.range.start, edit.range.end),
+            &edit.replacement,
+            EditType::Other,
+        );
+        self.sync_text(&doc.text().to_string());
+    }
+}
+
+fn bar_button_style(s: floem::style::Style) -> floem::style::Style {
+    s.min_height(0.0)
+        .height(20.0)
+        .padding_horiz(6.0)
+        .font_size(11.0)
+        .items_center()
+        .justify_center()
+}
+
+/// Path of region/node ids around the cursor, with fold controls
+pub fn breadcrumb_bar(
+    state: LsxEditorState,
+    doc: Rc<dyn Document>,
+    cursor: RwSignal<Cursor>,
+) -> impl IntoView {
+    let doc_path = doc.clone();
+    let fold_button = |text: &'static str, action: FoldAction| {
+        let state = state.clone();
+        let doc = doc.clone();
+        button(text)
+            .style(bar_button_style)
+            .action(move || state.apply(&*doc, cursor.get_untracked().offset(), action))
+    };
+
+    h_stack((
+        label(move || {
+            // Recompute when the cursor moves or the document is edited
+            let offset = cursor.get().offset();
+            doc_path.cache_rev().get();
+            let text = doc_path.text();
+            let path = breadcrumb(&text.slice_to_cow(0..offset.min(text.len())));
+            if path.is_empty() {
+                "(document root)".to_string()
+            } else {
+                path.join(" › ")
+            }
+        })
+        .style(|s| {
+            s.color(colors().text_secondary)
+                .font_size(12.0)
+                .text_ellipsis()
+                .min_width(0.0)
+                .flex_grow(1.0)
+        }),
+        fold_button("Fold", FoldAction::Fold),
+        fold_button("Unfold", FoldAction::Unfold),
+        fold_button("Unfold All", FoldAction::UnfoldAll),
+    ))
+    .style(|s| {
+        s.width_full()
+            .height(28.0)
+            .padding_horiz(12.0)
+            .gap(6.0)
+            .items_center()
+            .background(colors().bg_surface)
+            .border_bottom(1.0)
+            .border_color(colors().border)
+    })
+}
@@ -9,10 +9,14 @@ use floem::views::editor::command::CommandExecuted;
 use floem::views::editor::keypress::{default_key_handler, key::KeyInput, press::KeyPress};
 use floem::views::text_editor_keys;
 
-use crate::gui::state::{EditorTab, EditorTabsState};
+use crate::gui::state::{EditorHandle, EditorTab, EditorTabsState};
 
-use super::super::operations::{open_file_dialog, save_file, save_file_as_dialog};
+use super::super::operations::{format_document, open_file_dialog, save_file, save_file_as_dialog};
+use super::super::search::undo_replace;
+use super::super::stats::is_stats_path;
 use super::super::syntax::SyntaxStyling;
+use super::breadcrumb_bar::{LsxEditorState, breadcrumb_bar};
+use super::stats_panels::{stats_issues_panel, stats_outline};
 
 pub fn editor_content(
     tab: EditorTab,
@@ -23,24 +27,43 @@ pub fn editor_content(
     let live_content = tab.live_content;
     let modified = tab.modified;
     let file_format = tab.file_format;
+    let editor_revision = tab.editor_revision;
+    let tab_for_editor = tab.clone();
     let goto_offset = tab.goto_offset;
+    let restore_cursor = tab.restore_cursor;
+    let restore_line = tab.restore_line;
+    let is_loading = tab.is_loading;
     let search_visible = tab.search_visible;
     let converted_from_lsf = tab.converted_from_lsf;
+    let stats_issues = tab.stats_issues;
     let tab_for_save = tab.clone();
     let tabs_state_for_open = tabs_state.clone();
+    let backup_count = tabs_state.backup_count;
 
     // Recreate editor only when format changes (for syntax highlighting)
+    // or the text is replaced (find & replace bumps the revision)
     // Width/resize and line numbers are handled reactively
     // Loading state is now handled by the overlay in mod.rs
     dyn_container(
-        move || file_format.get(),
-        move |format| {
+        move || (file_format.get(), editor_revision.get()),
+        move |(format, _)| {
             let show_lines = show_line_numbers;
             // Use get_untracked to avoid creating a reactive subscription
             let text = content.get_untracked();
             let state_change = modified;
             // Create syntax highlighting based on file format
-            let styling = SyntaxStyling::new(&text, &format);
+            let is_stats = tab_for_save
+                .source_path()
+                .is_some_and(|path| is_stats_path(&path));
+            let styling = if is_stats {
+                SyntaxStyling::for_stats(&text)
+            } else {
+                SyntaxStyling::new(&text, &format)
+            };
+            // LSX documents also get folding and a breadcrumb bar
+            let lsx = styling.lsx_lines().map(LsxEditorState::new);
+            let lsx_for_keys = lsx.clone();
+            let stats_lines = styling.stats_lines();
 
             // Clone tab and state for the key handler
             let tab_for_keys = tab_for_save.clone();
@@ -80,6 +103,29 @@ pub fn editor_content(
 
                     if is_cmd_or_ctrl {
                         if let KeyInput::Keyboard(Key::Character(c), _) = &keypress.key {
+                            // CMD+Z right after a replace undoes the whole replace
+                            if c.as_str().eq_ignore_ascii_case("z") && !mods.shift() {
+                                let text = editor_sig.get_untracked().doc().text().to_string();
+                                if undo_replace(&tab_for_keys, &text) {
+                                    return CommandExecuted::Yes;
+                                }
+                            }
+                            // CMD+SHIFT+I - Format Document (sync content from editor first)
+                            if c.as_str().eq_ignore_ascii_case("i") && mods.shift() {
+                                let new_text = editor_sig.get_untracked().doc().text().to_string();
+                                live_content.set(saved_text(lsx_for_keys.as_ref(), new_text));
+                                format_document(
+                                    tab_for_keys.clone(),
+                                    tabs_state_for_keys.format_options.get_untracked(),
+                                    tabs_state_for_keys.status_message,
+                                );
+                                return CommandExecuted::Yes;
+                            }
+                            // CMD+SHIFT+F - Find in Files
+                            if c.as_str().eq_ignore_ascii_case("f") && mods.shift() {
+                                tabs_state_for_keys.project_search.show(None);
+                                return CommandExecuted::Yes;
+                            }
                             // CMD+F - Find
                             if c.as_str().eq_ignore_ascii_case("f") {
                                 search_visible.set(!search_visible.get());
@@ -89,16 +135,17 @@ pub fn editor_content(
                             if c.as_str().eq_ignore_ascii_case("s") {
                                 // Sync editor content to live_content before saving
                                 let new_text = editor_sig.get_untracked().doc().text().to_string();
-                                live_content.set(new_text);
+                                live_content.set(saved_text(lsx_for_keys.as_ref(), new_text));
 
                                 if modified.get() {
                                     if converted_from_lsf.get() {
                                         let tab_clone = tab_for_keys.clone();
                                         exec_after(Duration::from_millis(50), move |_| {
-                                            save_file_as_dialog(tab_clone);
+                                            save_file_as_dialog(tab_clone, backup_count.get());
                                         });
                                     } else {
-                                        save_file(tab_for_keys.clone());
+                                        let tabs_state = tabs_state_for_keys.clone();
+                                        save_file(tabs_state, tab_for_keys.clone());
                                     }
                                 }
                                 return CommandExecuted::Yes;
@@ -121,14 +168,20 @@ pub fn editor_content(
                     // live_content is NOT watched by dyn_container, so this won't cause cascades
                     if might_edit {
                         let new_text = editor_sig.get_untracked().doc().text().to_string();
-                        live_content.set(new_text);
+                        if let Some(lsx) = &lsx_for_keys {
+                            lsx.sync_text(&new_text);
+                        }
+                        if let Some(stats) = &stats_lines {
+                            stats.borrow_mut().set_text(&new_text);
+                        }
+                        live_content.set(saved_text(lsx_for_keys.as_ref(), new_text));
                         state_change.set(true);
                     }
 
                     result
                 };
 
-            text_editor_keys(text, key_handler)
+            let editor_view = text_editor_keys(text, key_handler)
                 .styling(styling)
                 .editor_style(move |s| s.hide_gutter(!show_lines.get()))
                 .style(move |s| {
@@ -162,9 +215,56 @@ pub fn editor_content(
                     // Content is synced on-demand (e.g., when saving).
                     let _ = (content, state_change); // Suppress unused warnings
                 })
-                .style(|s| s.size_full().flex_grow(1.0))
+                .style(|s| s.size_full().flex_grow(1.0));
+
+            let doc = editor_view.doc();
+            let cursor = editor_view.editor().cursor;
+            let handle = EditorHandle {
+                doc: doc.clone(),
+                cursor,
+            };
+            tab_for_editor.set_editor(handle.clone());
+
+            // Put the cursor back where it was in a recovered buffer
+            if let Some(offset) = restore_cursor.get_untracked() {
+                restore_cursor.set(None);
+                let offset = offset.min(doc.text().len());
+                cursor.update(|cursor| cursor.set_offset(offset, false, false));
+            }
+            // Jump to a find in files result once its file has loaded
+            if let Some(line) = restore_line.get_untracked() {
+                if !is_loading.get_untracked() && !doc.text().is_empty() {
+                    restore_line.set(None);
+                    handle.go_to_line(line);
+                }
+            }
+
+            // Stats files get an entry outline and the lint results from saving
+            if is_stats {
+                return h_stack((
+                    stats_outline(handle.clone(), live_content, stats_issues),
+                    v_stack((editor_view, stats_issues_panel(handle, stats_issues)))
+                        .style(|s| s.flex_grow(1.0).min_width(0.0).height_full()),
+                ))
+                .style(|s| s.size_full().flex_grow(1.0).min_height(0.0))
+                .into_any();
+            }
+
+            let Some(lsx) = lsx else {
+                return editor_view.into_any();
+            };
+            v_stack((breadcrumb_bar(lsx, doc, cursor), editor_view))
+                .style(|s| s.size_full().flex_grow(1.0).min_height(0.0))
                 .into_any()
         },
     )
     .style(|s| s.size_full().flex_grow(1.0))
 }
+
+/// Document text as it should be saved (with LSX folds expanded)
+fn saved_text(lsx: Option<&LsxEditorState>, text: String) -> String {
+    match lsx {
+        Some(lsx) => lsx.expanded(&text),
+        None => text,
+    }
+}
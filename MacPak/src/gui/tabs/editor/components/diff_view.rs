@@ -0,0 +1,238 @@
+//! Side-by-side diff view shown in place of the editor
+//!
+//! Both panes live in one row per line pair, so they always scroll together.
+//! Changed characters within a line get a stronger highlight than the line.
+
+use std::rc::Rc;
+
+use floem::kurbo::Point;
+use floem::prelude::*;
+use floem::views::{VirtualDirection, VirtualItemSize, clip, virtual_list};
+use im::Vector as ImVector;
+
+use crate::gui::shared::colors;
+use crate::gui::state::{DiffSession, EditorTabsState};
+
+use super::super::diff::{DiffLine, DiffRow, RowKind, Side, TextDiff};
+use super::super::operations::merge_hunk;
+
+const ROW_HEIGHT: f64 = 20.0;
+/// Highlights for changed characters, drawn over the line tint
+const REMOVED_CHARS: Color = Color::rgba8(220, 80, 80, 110);
+const ADDED_CHARS: Color = Color::rgba8(80, 200, 110, 110);
+/// Rows of context kept above a hunk when jumping to it
+const CONTEXT_ROWS: usize = 3;
+
+pub fn diff_view(tabs_state: EditorTabsState) -> impl IntoView {
+    let session = tabs_state.diff;
+
+    dyn_container(
+        move || session.get(),
+        move |session| match session {
+            Some(session) => diff_panel(session, tabs_state.clone()).into_any(),
+            None => empty().into_any(),
+        },
+    )
+    .style(move |s| {
+        if session.with(Option::is_some) {
+            s.width_full()
+                .min_width(0.0)
+                .flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_height(0.0)
+        } else {
+            s.display(floem::style::Display::None)
+        }
+    })
+}
+
+fn diff_panel(session: DiffSession, tabs_state: EditorTabsState) -> impl IntoView {
+    let diff = Rc::new(TextDiff::new(&session.left.text, &session.right.text));
+    let current = tabs_state.diff_hunk;
+    let hunk_count = diff.hunks.len();
+
+    let diff_scroll = diff.clone();
+    let rows: ImVector<(usize, DiffRow)> = diff.rows.iter().cloned().enumerate().collect();
+
+    v_stack((
+        diff_header(session, hunk_count, tabs_state.clone()),
+        scroll(
+            virtual_list(
+                VirtualDirection::Vertical,
+                VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+                move || rows.clone(),
+                |(index, _)| *index,
+                move |(index, row)| {
+                    let diff = diff.clone();
+                    diff_row(row).style(move |s| {
+                        let in_current = diff.hunk_at_row(index) == Some(current.get());
+                        if in_current {
+                            s.border_left(3.0).border_color(colors().accent)
+                        } else {
+                            s.border_left(3.0).border_color(Color::TRANSPARENT)
+                        }
+                    })
+                },
+            )
+            .style(|s| s.width_full().flex_col()),
+        )
+        .scroll_to(move || {
+            let hunk = diff_scroll.hunks.get(current.get())?;
+            let row = hunk.rows.start.saturating_sub(CONTEXT_ROWS);
+            Some(Point::new(0.0, row as f64 * ROW_HEIGHT))
+        })
+        .scroll_style(|s| s.handle_thickness(10.0))
+        .style(|s| {
+            s.width_full()
+                .flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_height(0.0)
+                .background(colors().bg_base)
+        }),
+    ))
+    .style(|s| s.size_full().min_height(0.0))
+}
+
+/// Pane titles, hunk navigation and merge buttons
+fn diff_header(
+    session: DiffSession,
+    hunk_count: usize,
+    tabs_state: EditorTabsState,
+) -> impl IntoView {
+    let current = tabs_state.diff_hunk;
+    let diff = tabs_state.diff;
+    let can_copy_left = session.left.tab_id.is_some() && hunk_count > 0;
+    let can_copy_right = session.right.tab_id.is_some() && hunk_count > 0;
+    let tabs_state_left = tabs_state.clone();
+    let tabs_state_right = tabs_state;
+
+    h_stack((
+        pane_title(session.left.title.clone()),
+        button("◀")
+            .disabled(move || current.get() == 0)
+            .action(move || current.update(|i| *i = i.saturating_sub(1))),
+        label(move || {
+            if hunk_count == 0 {
+                "No differences".to_string()
+            } else {
+                format!("Change {} of {}", current.get() + 1, hunk_count)
+            }
+        })
+        .style(|s| s.font_size(12.0).min_width(110.0).justify_center()),
+        button("▶")
+            .disabled(move || current.get() + 1 >= hunk_count)
+            .action(move || current.update(|i| *i += 1)),
+        button("← Copy")
+            .disabled(move || !can_copy_left)
+            .action(move || merge_hunk(tabs_state_left.clone(), Side::Left)),
+        button("Copy →")
+            .disabled(move || !can_copy_right)
+            .action(move || merge_hunk(tabs_state_right.clone(), Side::Right)),
+        button("✕ Close").action(move || diff.set(None)),
+        pane_title(session.right.title.clone()),
+    ))
+    .style(|s| {
+        s.width_full()
+            .padding(8.0)
+            .gap(8.0)
+            .items_center()
+            .background(colors().bg_surface)
+            .border_bottom(1.0)
+            .border_color(colors().border)
+    })
+}
+
+fn pane_title(title: String) -> impl IntoView {
+    label(move || title.clone()).style(|s| {
+        s.flex_grow(1.0)
+            .flex_basis(0.0)
+            .min_width(0.0)
+            .font_size(12.0)
+            .font_bold()
+            .text_ellipsis()
+    })
+}
+
+fn diff_row(row: DiffRow) -> impl IntoView {
+    let (left_bg, right_bg) = match row.kind {
+        RowKind::Equal => (None, None),
+        RowKind::Changed => (Some(colors().error_bg), Some(colors().success_bg)),
+        RowKind::Removed => (Some(colors().error_bg), None),
+        RowKind::Added => (None, Some(colors().success_bg)),
+    };
+
+    h_stack((
+        diff_cell(row.left, left_bg, REMOVED_CHARS),
+        empty().style(|s| s.width(1.0).height_full().background(colors().border)),
+        diff_cell(row.right, right_bg, ADDED_CHARS),
+    ))
+    .style(|s| s.width_full().height(ROW_HEIGHT))
+}
+
+/// One pane of a row: line number and text, or a gap if the line is missing
+fn diff_cell(line: Option<DiffLine>, background: Option<Color>, highlight: Color) -> impl IntoView {
+    let Some(line) = line else {
+        return empty()
+            .style(|s| {
+                s.flex_grow(1.0)
+                    .flex_basis(0.0)
+                    .height_full()
+                    .background(colors().bg_surface)
+            })
+            .into_any();
+    };
+
+    let number = line.number + 1;
+    let segments: Vec<_> = line_segments(&line)
+        .into_iter()
+        .map(|(text, changed)| {
+            label(move || text.clone()).style(move |s| {
+                let s = s.font_family("monospace".to_string()).font_size(12.0);
+                if changed { s.background(highlight) } else { s }
+            })
+        })
+        .collect();
+
+    h_stack((
+        label(move || number.to_string()).style(|s| {
+            s.width(48.0)
+                .padding_right(8.0)
+                .justify_end()
+                .font_family("monospace".to_string())
+                .font_size(11.0)
+                .color(colors().text_muted)
+        }),
+        clip(h_stack_from_iter(segments).style(|s| s.items_center()))
+            .style(|s| s.flex_grow(1.0).min_width(0.0)),
+    ))
+    .style(move |s| {
+        let s = s
+            .flex_grow(1.0)
+            .flex_basis(0.0)
+            .min_width(0.0)
+            .height_full()
+            .items_center();
+        match background {
+            Some(color) => s.background(color),
+            None => s,
+        }
+    })
+    .into_any()
+}
+
+/// Split a line into runs of unchanged and changed text
+fn line_segments(line: &DiffLine) -> Vec<(String, bool)> {
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    for range in &line.changes {
+        if range.start > pos {
+            segments.push((line.text[pos..range.start].to_string(), false));
+        }
+        segments.push((line.text[range.clone()].to_string(), true));
+        pos = range.end;
+    }
+    if pos < line.text.len() || segments.is_empty() {
+        segments.push((line.text[pos..].to_string(), false));
+    }
+    segments
+}
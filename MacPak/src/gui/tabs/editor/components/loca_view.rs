@@ -0,0 +1,250 @@
+//! Handle/text table for LOCA and localization XML documents
+//!
+//! Like the tree view, the table is parsed from the tab's text when it opens
+//! and every edit is written straight back, so saving as `.loca` or `.xml`
+//! goes through the usual save path.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use floem::event::{Event, EventListener};
+use floem::keyboard::{Key, NamedKey};
+use floem::prelude::*;
+use floem::views::{VirtualDirection, VirtualItemSize, virtual_list};
+use im::Vector as ImVector;
+
+use crate::gui::shared::colors;
+use crate::gui::state::EditorTab;
+
+use super::super::loca_table::{LocaRow, LocaTable};
+
+const ROW_HEIGHT: f64 = 32.0;
+
+/// Shared between the list and its rows
+#[derive(Clone)]
+struct TableContext {
+    tab: EditorTab,
+    table: Rc<RefCell<LocaTable>>,
+    /// Bumped after every change so the list re-filters
+    revision: RwSignal<u64>,
+    error: RwSignal<String>,
+}
+
+impl TableContext {
+    /// Apply a change to the table and write it back to the tab's text
+    fn edit(&self, change: impl FnOnce(&mut LocaTable) -> Result<bool, String>) -> bool {
+        let result = change(&mut self.table.borrow_mut());
+        let text = result.and_then(|changed| {
+            if changed {
+                self.table.borrow().to_xml().map(Some)
+            } else {
+                Ok(None)
+            }
+        });
+
+        match text {
+            Ok(text) => {
+                self.error.set(String::new());
+                if let Some(text) = text {
+                    self.tab.replace_text(text);
+                    self.revision.update(|r| *r += 1);
+                }
+                true
+            }
+            Err(e) => {
+                self.error.set(e);
+                false
+            }
+        }
+    }
+}
+
+pub fn loca_view(tab: EditorTab) -> impl IntoView {
+    let table = match LocaTable::parse(&tab.live_content.get_untracked()) {
+        Ok(table) => table,
+        Err(e) => {
+            return label(move || format!("Can't show the table until the text parses: {}", e))
+                .style(|s| s.padding(24.0).font_size(13.0).color(colors().error))
+                .into_any();
+        }
+    };
+
+    let ctx = TableContext {
+        tab,
+        table: Rc::new(RefCell::new(table)),
+        revision: RwSignal::new(0),
+        error: RwSignal::new(String::new()),
+    };
+    let query = RwSignal::new(String::new());
+    let visible_count = RwSignal::new(0usize);
+
+    let ctx_rows = ctx.clone();
+    let filtered_rows = move || {
+        ctx_rows.revision.get();
+        let rows = ctx_rows.table.borrow_mut().filter(&query.get());
+        visible_count.set(rows.len());
+        rows.into_iter().collect::<ImVector<_>>()
+    };
+
+    let ctx_count = ctx.clone();
+    let ctx_add = ctx.clone();
+    let ctx_list = ctx.clone();
+    let error = ctx.error;
+
+    v_stack((
+        h_stack((
+            text_input(query)
+                .placeholder("Filter handles and text...")
+                .style(|s| {
+                    s.width(260.0)
+                        .padding(4.0)
+                        .font_size(12.0)
+                        .border(1.0)
+                        .border_color(colors().border_strong)
+                        .border_radius(4.0)
+                }),
+            label(move || {
+                ctx_count.revision.get();
+                let total = ctx_count.table.borrow().len();
+                let shown = visible_count.get();
+                if shown == total {
+                    format!("{} entries", total)
+                } else {
+                    format!("{} of {} entries", shown, total)
+                }
+            })
+            .style(|s| s.font_size(12.0).color(colors().text_muted)),
+            empty().style(|s| s.flex_grow(1.0)),
+            button("+ Add Row").action(move || {
+                // Clear the filter so the new row is visible at the bottom
+                query.set(String::new());
+                ctx_add.edit(|table| {
+                    table.add_row();
+                    Ok(true)
+                });
+            }),
+        ))
+        .style(|s| {
+            s.width_full()
+                .padding(8.0)
+                .gap(8.0)
+                .items_center()
+                .background(colors().bg_surface)
+                .border_bottom(1.0)
+                .border_color(colors().border)
+        }),
+        label(move || error.get()).style(move |s| {
+            let s = s
+                .font_size(12.0)
+                .color(colors().error)
+                .padding_horiz(8.0)
+                .padding_vert(4.0);
+            if error.get().is_empty() {
+                s.display(floem::style::Display::None)
+            } else {
+                s
+            }
+        }),
+        scroll(
+            virtual_list(
+                VirtualDirection::Vertical,
+                VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+                filtered_rows,
+                // Rebuild a row when its handle is regenerated
+                |row: &LocaRow| (row.id, row.handle.clone()),
+                move |row| loca_row(row, ctx_list.clone()),
+            )
+            .style(|s| s.width_full().flex_col()),
+        )
+        .scroll_style(|s| s.handle_thickness(10.0))
+        .style(|s| {
+            s.width_full()
+                .flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_height(0.0)
+        }),
+    ))
+    .style(|s| s.size_full().flex_grow(1.0).min_height(0.0))
+    .into_any()
+}
+
+fn loca_row(row: LocaRow, ctx: TableContext) -> impl IntoView {
+    let id = row.id;
+    let ctx_handle = ctx.clone();
+    let ctx_text = ctx.clone();
+    let ctx_generate = ctx.clone();
+
+    h_stack((
+        cell_input(row.handle, 330.0, move |value| {
+            ctx_handle.edit(|table| table.set_handle(id, &value))
+        }),
+        button("⟳")
+            .style(|s| s.font_size(12.0).padding_horiz(6.0))
+            .action(move || {
+                ctx_generate.edit(|table| table.regenerate_handle(id));
+            }),
+        cell_input(row.text, 0.0, move |value| {
+            ctx_text.edit(|table| table.set_text(id, &value))
+        }),
+        button("✕")
+            .style(|s| s.font_size(12.0).padding_horiz(6.0).color(colors().error))
+            .action(move || {
+                ctx.edit(|table| Ok(table.delete_row(id)));
+            }),
+    ))
+    .style(|s| {
+        s.width_full()
+            .height(ROW_HEIGHT)
+            .padding_horiz(8.0)
+            .gap(6.0)
+            .items_center()
+            .border_bottom(1.0)
+            .border_color(colors().border)
+    })
+}
+
+/// Text field committed on Enter or when focus leaves; a refused value is
+/// put back to the last good one. A `width` of 0 fills the remaining space.
+fn cell_input(
+    value: String,
+    width: f32,
+    commit: impl Fn(String) -> bool + Clone + 'static,
+) -> impl IntoView {
+    let text = RwSignal::new(value.clone());
+    let committed = RwSignal::new(value);
+    let try_commit = move || {
+        let value = text.get_untracked();
+        if value == committed.get_untracked() {
+            return;
+        }
+        if commit(value.clone()) {
+            committed.set(value);
+        } else {
+            text.set(committed.get_untracked());
+        }
+    };
+    let try_commit_enter = try_commit.clone();
+
+    text_input(text)
+        .style(move |s| {
+            let s = s
+                .padding(4.0)
+                .font_size(12.0)
+                .border(1.0)
+                .border_color(colors().border)
+                .border_radius(4.0);
+            if width > 0.0 {
+                s.width(width).flex_shrink(0.0)
+            } else {
+                s.flex_grow(1.0).min_width(0.0)
+            }
+        })
+        .on_event_cont(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(key_event) = e {
+                if key_event.key.logical_key == Key::Named(NamedKey::Enter) {
+                    try_commit_enter();
+                }
+            }
+        })
+        .on_event_cont(EventListener::FocusLost, move |_| try_commit())
+}
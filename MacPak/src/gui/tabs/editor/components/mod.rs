@@ -1,12 +1,24 @@
 //! UI components for the editor
 
 mod badges;
+mod breadcrumb_bar;
 mod content;
+mod diff_view;
+mod loca_view;
+mod project_search_panel;
+mod restore_dialog;
 mod search_panel;
+mod stats_panels;
 mod status_bar;
 mod toolbar;
+mod tree_view;
 
 pub use content::editor_content;
+pub use diff_view::diff_view;
+pub use loca_view::loca_view;
+pub use project_search_panel::project_search_panel;
+pub use restore_dialog::restore_dialog;
 pub use search_panel::search_panel;
 pub use status_bar::editor_status_bar;
 pub use toolbar::editor_toolbar;
+pub use tree_view::tree_view;
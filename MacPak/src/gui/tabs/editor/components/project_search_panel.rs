@@ -0,0 +1,298 @@
+//! Find in files sidebar: query, folder and glob options, and the results
+//! grouped by file
+
+use floem::event::{Event, EventListener};
+use floem::keyboard::{Key, NamedKey};
+use floem::prelude::*;
+use floem::text::Weight;
+use floem::views::{VirtualDirection, VirtualItemSize, virtual_list};
+use im::Vector as ImVector;
+
+use crate::gui::shared::colors;
+use crate::gui::state::{EditorTabsState, ProjectSearchState};
+
+use super::super::project_search::{cancel_project_search, open_result, run_project_search};
+
+const RESULT_ROW_HEIGHT: f64 = 22.0;
+
+/// One row of the result tree
+#[derive(Clone)]
+enum ResultRow {
+    File {
+        path: String,
+        total: usize,
+        converted: bool,
+        collapsed: bool,
+    },
+    Line {
+        path: String,
+        line: usize,
+        text: String,
+    },
+}
+
+impl ResultRow {
+    fn key(&self) -> (String, usize, bool) {
+        match self {
+            ResultRow::File {
+                path, collapsed, ..
+            } => (path.clone(), 0, *collapsed),
+            ResultRow::Line { path, line, .. } => (path.clone(), *line, false),
+        }
+    }
+}
+
+pub fn project_search_panel(tabs_state: EditorTabsState) -> impl IntoView {
+    let search = tabs_state.project_search.clone();
+    let visible = search.visible;
+
+    dyn_container(
+        move || visible.get(),
+        move |is_visible| {
+            if !is_visible {
+                return empty().into_any();
+            }
+            panel(search.clone(), tabs_state.clone()).into_any()
+        },
+    )
+}
+
+fn panel(search: ProjectSearchState, tabs_state: EditorTabsState) -> impl IntoView {
+    let visible = search.visible;
+    let query = search.query;
+    let root = search.root;
+    let is_searching = search.is_searching;
+    let status = search.status;
+    let results = search.results;
+    let collapsed = search.collapsed;
+
+    let rows = move || {
+        let collapsed = collapsed.get();
+        let mut rows = ImVector::new();
+        for file in results.get() {
+            let is_collapsed = collapsed.contains(&file.path);
+            rows.push_back(ResultRow::File {
+                path: file.path.clone(),
+                total: file.total,
+                converted: file.converted,
+                collapsed: is_collapsed,
+            });
+            if !is_collapsed {
+                for m in file.matches {
+                    rows.push_back(ResultRow::Line {
+                        path: file.path.clone(),
+                        line: m.line,
+                        text: m.text,
+                    });
+                }
+            }
+        }
+        rows
+    };
+
+    let search_enter = search.clone();
+    let search_button = search.clone();
+    let search_rows = search.clone();
+
+    v_stack((
+        h_stack((
+            label(|| "Find in Files").style(|s| s.font_size(13.0).font_weight(Weight::SEMIBOLD)),
+            empty().style(|s| s.flex_grow(1.0)),
+            button("✕")
+                .style(|s| s.font_size(11.0).padding_horiz(6.0))
+                .action(move || visible.set(false)),
+        ))
+        .style(|s| s.width_full().items_center()),
+        {
+            let input = text_input(query)
+                .placeholder("Search workspace...")
+                .style(input_style);
+            input.id().request_focus();
+            input
+        }
+        .on_event_cont(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(key_event) = e {
+                if key_event.key.logical_key == Key::Named(NamedKey::Enter) {
+                    run_project_search(search_enter.clone());
+                }
+            }
+        }),
+        h_stack((
+            label(move || root.get().unwrap_or_else(|| "No folder chosen".to_string())).style(
+                |s| {
+                    s.flex_grow(1.0)
+                        .min_width(0.0)
+                        .font_size(11.0)
+                        .color(colors().text_secondary)
+                        .text_ellipsis()
+                },
+            ),
+            button("Folder...")
+                .style(|s| s.font_size(11.0))
+                .action(move || {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_title("Folder to Search")
+                        .pick_folder()
+                    {
+                        root.set(Some(path.to_string_lossy().to_string()));
+                    }
+                }),
+        ))
+        .style(|s| s.width_full().gap(6.0).items_center()),
+        text_input(search.include)
+            .placeholder("Include, e.g. *.lsx, Public/**")
+            .style(input_style),
+        text_input(search.exclude)
+            .placeholder("Exclude, e.g. *.dds")
+            .style(input_style),
+        h_stack((
+            option_toggle("Aa", search.case_sensitive),
+            option_toggle("LSF", search.convert_lsf),
+            empty().style(|s| s.flex_grow(1.0)),
+            button(label(move || {
+                if is_searching.get() {
+                    "Cancel"
+                } else {
+                    "Search"
+                }
+            }))
+            .action(move || {
+                if is_searching.get_untracked() {
+                    cancel_project_search(&search_button);
+                } else {
+                    run_project_search(search_button.clone());
+                }
+            }),
+        ))
+        .style(|s| s.width_full().gap(6.0).items_center()),
+        label(move || status.get()).style(|s| s.font_size(11.0).color(colors().text_secondary)),
+        scroll(
+            virtual_list(
+                VirtualDirection::Vertical,
+                VirtualItemSize::Fixed(Box::new(|| RESULT_ROW_HEIGHT)),
+                rows,
+                ResultRow::key,
+                move |row| result_row(row, search_rows.clone(), tabs_state.clone()),
+            )
+            .style(|s| s.width_full().flex_col()),
+        )
+        .style(|s| {
+            s.width_full()
+                .flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_height(0.0)
+        }),
+    ))
+    .style(|s| {
+        s.width(320.0)
+            .height_full()
+            .padding(8.0)
+            .gap(6.0)
+            .flex_shrink(0.0)
+            .background(colors().bg_surface)
+            .border_right(1.0)
+            .border_color(colors().border)
+    })
+}
+
+/// A file header (click to fold) or a matched line (click to open)
+fn result_row(
+    row: ResultRow,
+    search: ProjectSearchState,
+    tabs_state: EditorTabsState,
+) -> impl IntoView {
+    let base = |s: floem::style::Style| {
+        s.width_full()
+            .height(RESULT_ROW_HEIGHT)
+            .padding_horiz(4.0)
+            .items_center()
+            .cursor(floem::style::CursorStyle::Pointer)
+            .hover(|s| s.background(colors().bg_hover))
+    };
+
+    match row {
+        ResultRow::File {
+            path,
+            total,
+            converted,
+            collapsed,
+        } => {
+            let arrow = if collapsed { "▸" } else { "▾" };
+            let count = if converted {
+                format!("{total} (LSF)")
+            } else {
+                total.to_string()
+            };
+            let name = path.clone();
+            let folds = search.collapsed;
+            h_stack((
+                label(move || arrow).style(|s| s.font_size(11.0).width(14.0)),
+                label(move || name.clone()).style(|s| {
+                    s.font_size(12.0)
+                        .font_weight(Weight::SEMIBOLD)
+                        .text_ellipsis()
+                        .min_width(0.0)
+                        .flex_grow(1.0)
+                }),
+                label(move || count.clone())
+                    .style(|s| s.font_size(11.0).color(colors().text_secondary)),
+            ))
+            .style(move |s| base(s).gap(4.0))
+            .on_click_stop(move |_| {
+                folds.update(|folds| {
+                    if !folds.remove(&path) {
+                        folds.insert(path.clone());
+                    }
+                });
+            })
+            .into_any()
+        }
+        ResultRow::Line { path, line, text } => h_stack((
+            label(move || line.to_string()).style(|s| {
+                s.font_size(11.0)
+                    .width(52.0)
+                    .padding_left(14.0)
+                    .color(colors().text_muted)
+            }),
+            label(move || text.clone()).style(|s| {
+                s.font_size(12.0)
+                    .font_family("monospace".to_string())
+                    .text_ellipsis()
+                    .min_width(0.0)
+                    .flex_grow(1.0)
+            }),
+        ))
+        .style(base)
+        .on_click_stop(move |_| open_result(&search, tabs_state.clone(), &path, line))
+        .into_any(),
+    }
+}
+
+fn input_style(s: floem::style::Style) -> floem::style::Style {
+    s.width_full()
+        .padding(5.0)
+        .font_size(12.0)
+        .border(1.0)
+        .border_color(colors().border_strong)
+        .border_radius(4.0)
+}
+
+fn option_toggle(label_text: &'static str, signal: RwSignal<bool>) -> impl IntoView {
+    button(label_text)
+        .style(move |s| {
+            let s = s
+                .padding_horiz(8.0)
+                .padding_vert(3.0)
+                .border_radius(4.0)
+                .font_size(12.0)
+                .font_family("monospace".to_string());
+            if signal.get() {
+                s.background(colors().accent).color(colors().text_on_accent)
+            } else {
+                s.background(colors().bg_hover)
+                    .color(colors().text_secondary)
+                    .hover(|s| s.background(colors().bg_selected))
+            }
+        })
+        .action(move || signal.set(!signal.get()))
+}
@@ -0,0 +1,165 @@
+//! "Restore previous version" dialog listing the backups of the active file
+
+use std::path::Path;
+
+use floem::prelude::*;
+use floem::text::Weight;
+
+use crate::gui::shared::colors;
+use crate::gui::state::EditorTabsState;
+use crate::gui::utils::show_file_error;
+use crate::workspace::backup::{Backup, BackupStore};
+
+use super::super::operations::load_file;
+
+/// Dialog listing backup timestamps for the active tab's file
+pub fn restore_dialog(tabs_state: EditorTabsState) -> impl IntoView {
+    let show = tabs_state.show_restore_dialog;
+
+    dyn_container(
+        move || show.get(),
+        move |visible| {
+            if !visible {
+                return empty().into_any();
+            }
+
+            let file_path = tabs_state
+                .active_tab()
+                .and_then(|tab| tab.file_path.get_untracked())
+                .unwrap_or_default();
+            let store = BackupStore::for_file(Path::new(&file_path), tabs_state.backup_count.get());
+            let backups = store
+                .as_ref()
+                .map(|store| (store, store.list(Path::new(&file_path))));
+
+            let body = match backups {
+                None => label(|| "Backups are only kept for files in a workspace")
+                    .style(|s| s.padding(20.0).color(colors().text_secondary))
+                    .into_any(),
+                Some((_, Err(e))) => label(move || format!("Error: {}", e))
+                    .style(|s| s.padding(20.0).color(colors().error))
+                    .into_any(),
+                Some((_, Ok(backups))) if backups.is_empty() => {
+                    label(|| "No previous versions of this file")
+                        .style(|s| s.padding(20.0).color(colors().text_secondary))
+                        .into_any()
+                }
+                Some((store, Ok(backups))) => scroll(
+                    v_stack_from_iter(backups.into_iter().map(|backup| {
+                        backup_row(backup, store.clone(), file_path.clone(), tabs_state.clone())
+                    }))
+                    .style(|s| s.width_full().gap(4.0)),
+                )
+                .scroll_style(|s| s.handle_thickness(6.0))
+                .style(|s| s.width_full().max_height(360.0))
+                .into_any(),
+            };
+
+            let file_name = Path::new(&file_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            container(
+                v_stack((
+                    h_stack((
+                        label(move || format!("Restore Previous Version: {}", file_name))
+                            .style(|s| s.font_size(16.0).font_weight(Weight::BOLD)),
+                        empty().style(|s| s.flex_grow(1.0)),
+                        button("Close")
+                            .style(|s| {
+                                s.padding_horiz(16.0)
+                                    .padding_vert(6.0)
+                                    .background(colors().bg_elevated)
+                                    .color(colors().text_primary)
+                                    .border_radius(4.0)
+                            })
+                            .action(move || show.set(false)),
+                    ))
+                    .style(|s| s.width_full().items_center().margin_bottom(16.0)),
+                    body,
+                ))
+                .style(|s| {
+                    s.padding(24.0)
+                        .background(colors().bg_base)
+                        .border(1.0)
+                        .border_color(colors().border_strong)
+                        .border_radius(8.0)
+                        .width(480.0)
+                        .box_shadow_blur(20.0)
+                        .box_shadow_color(Color::rgba8(0, 0, 0, 50))
+                }),
+            )
+            .into_any()
+        },
+    )
+    .style(move |s| {
+        if show.get() {
+            s.position(floem::style::Position::Absolute)
+                .inset_top(0.0)
+                .inset_left(0.0)
+                .inset_bottom(0.0)
+                .inset_right(0.0)
+                .items_center()
+                .justify_center()
+                .background(Color::rgba8(0, 0, 0, 100))
+                .z_index(100)
+        } else {
+            s.display(floem::style::Display::None)
+        }
+    })
+}
+
+/// One backup: timestamp, size, and a restore button
+fn backup_row(
+    backup: Backup,
+    store: BackupStore,
+    file_path: String,
+    tabs_state: EditorTabsState,
+) -> impl IntoView {
+    let when = format_backup_time(&backup);
+    let size = format!("{:.1} KB", backup.size as f64 / 1024.0);
+    let timestamp = backup.timestamp.clone();
+
+    h_stack((
+        label(move || when.clone()).style(|s| s.font_size(13.0)),
+        label(move || size.clone()).style(|s| s.font_size(12.0).color(colors().text_secondary)),
+        empty().style(|s| s.flex_grow(1.0)),
+        button("Restore").action(move || {
+            let path = Path::new(&file_path);
+            match store.restore(path, &timestamp) {
+                Ok(()) => {
+                    if let Some(tab) = tabs_state.active_tab() {
+                        load_file(path, tab);
+                    }
+                    tabs_state.status_message.set(format!(
+                        "Restored version from {}",
+                        format_backup_time(&backup)
+                    ));
+                    tabs_state.show_restore_dialog.set(false);
+                }
+                Err(e) => show_file_error(path, "Restoring", &e),
+            }
+        }),
+    ))
+    .style(|s| {
+        s.width_full()
+            .gap(12.0)
+            .items_center()
+            .padding(6.0)
+            .border_bottom(1.0)
+            .border_color(colors().border)
+    })
+}
+
+/// Local date and time a backup was taken
+fn format_backup_time(backup: &Backup) -> String {
+    backup
+        .time()
+        .map(|t| {
+            chrono::DateTime::<chrono::Local>::from(t)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| backup.timestamp.clone())
+}
@@ -4,13 +4,14 @@ use floem::event::{Event, EventListener};
 use floem::keyboard::{Key, Modifiers, NamedKey};
 use floem::prelude::*;
 
-use crate::gui::state::EditorTab;
+use crate::gui::shared::colors;
+use crate::gui::state::{EditorTab, EditorTabsState};
 
 use super::super::search::{
-    find_next, find_previous, perform_search, replace_all, replace_current,
+    find_next, find_previous, perform_search, replace_all, replace_all_tabs, replace_current,
 };
 
-pub fn search_panel(tab: EditorTab) -> impl IntoView {
+pub fn search_panel(tab: EditorTab, tabs_state: EditorTabsState) -> impl IntoView {
     let visible = tab.search_visible;
     let search_text = tab.search_text;
     let replace_text = tab.replace_text;
@@ -20,7 +21,8 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
     let match_count = tab.match_count;
     let current_match = tab.current_match;
     let search_status = tab.search_status;
-    let content = tab.content;
+    let replace_in_all_tabs = tab.replace_in_all_tabs;
+    let live_content = tab.live_content;
 
     // State clones for button actions
     let tab_find_next = tab.clone();
@@ -40,6 +42,7 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
             let tab_find_prev = tab_find_prev.clone();
             let tab_replace = tab_replace.clone();
             let tab_replace_all = tab_replace_all.clone();
+            let tabs_state = tabs_state.clone();
             let tab_close = tab_close.clone();
 
             // Clone tab for Enter key handler
@@ -54,7 +57,7 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                             s.width(250.0)
                                 .padding(6.0)
                                 .border(1.0)
-                                .border_color(Color::rgb8(200, 200, 200))
+                                .border_color(colors().border_strong)
                                 .border_radius(4.0)
                         });
                         // Auto-focus the search input when panel opens
@@ -92,7 +95,7 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                             }
                         }
                         perform_search(
-                            content.get(),
+                            live_content.get(),
                             search_text.get(),
                             case_sensitive.get(),
                             whole_words.get(),
@@ -120,11 +123,7 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                             format!("{} / {}", current + 1, count)
                         }
                     })
-                    .style(|s| {
-                        s.width(80.0)
-                            .font_size(12.0)
-                            .color(Color::rgb8(100, 100, 100))
-                    }),
+                    .style(|s| s.width(80.0).font_size(12.0).color(colors().text_secondary)),
                     button("✕").action({
                         let tab = tab_close.clone();
                         move || {
@@ -145,7 +144,7 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                             s.width(250.0)
                                 .padding(6.0)
                                 .border(1.0)
-                                .border_color(Color::rgb8(200, 200, 200))
+                                .border_color(colors().border_strong)
                                 .border_radius(4.0)
                         })
                         .on_event_stop(EventListener::KeyDown, move |e| {
@@ -168,7 +167,13 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                     }),
                     button("Replace All").action({
                         let tab = tab_replace_all.clone();
-                        move || replace_all(tab.clone())
+                        move || {
+                            if replace_in_all_tabs.get() {
+                                replace_all_tabs(tab.clone(), &tabs_state);
+                            } else {
+                                replace_all(tab.clone());
+                            }
+                        }
                     }),
                 ))
                 .style(|s| s.width_full().gap(8.0).items_center()),
@@ -177,9 +182,14 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                     search_option_toggle("Aa", "Case sensitive", case_sensitive),
                     search_option_toggle("W", "Whole words", whole_words),
                     search_option_toggle(".*", "Use regex", use_regex),
+                    search_option_toggle(
+                        "All tabs",
+                        "Replace All in every open tab",
+                        replace_in_all_tabs,
+                    ),
                     empty().style(|s| s.flex_grow(1.0)),
                     label(move || search_status.get())
-                        .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+                        .style(|s| s.font_size(12.0).color(colors().text_secondary)),
                 ))
                 .style(|s| s.width_full().gap(8.0).items_center()),
             ))
@@ -201,9 +211,9 @@ pub fn search_panel(tab: EditorTab) -> impl IntoView {
                 s.width_full()
                     .padding(12.0)
                     .gap(8.0)
-                    .background(Color::rgb8(250, 250, 250))
+                    .background(colors().bg_surface)
                     .border_bottom(1.0)
-                    .border_color(Color::rgb8(220, 220, 220))
+                    .border_color(colors().border)
             })
             .into_any()
         },
@@ -226,11 +236,11 @@ fn search_option_toggle(
                 .font_family("monospace".to_string());
 
             if is_active {
-                s.background(Color::rgb8(33, 150, 243)).color(Color::WHITE)
+                s.background(colors().accent).color(colors().text_on_accent)
             } else {
-                s.background(Color::rgb8(230, 230, 230))
-                    .color(Color::rgb8(80, 80, 80))
-                    .hover(|s| s.background(Color::rgb8(210, 210, 210)))
+                s.background(colors().bg_hover)
+                    .color(colors().text_secondary)
+                    .hover(|s| s.background(colors().bg_selected))
             }
         })
         .action(move || {
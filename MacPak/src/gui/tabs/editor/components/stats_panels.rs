@@ -0,0 +1,180 @@
+//! Outline sidebar and lint results for stats `.txt` files
+
+use floem::prelude::*;
+use floem::text::Weight;
+use floem::views::{VirtualDirection, VirtualItemSize, virtual_list};
+use im::Vector as ImVector;
+use maclarian::formats::stats::StatsIssue;
+
+use crate::gui::shared::colors;
+use crate::gui::state::EditorHandle;
+
+use super::super::stats::{OutlineEntry, find_entry_line, outline};
+
+const OUTLINE_ROW_HEIGHT: f64 = 24.0;
+
+/// Entries and treasure tables of the file, click to jump. Rebuilt from the
+/// saved text each time the file is saved.
+pub fn stats_outline(
+    handle: EditorHandle,
+    live_content: RwSignal<String>,
+    issues: RwSignal<Vec<StatsIssue>>,
+) -> impl IntoView {
+    let filter = RwSignal::new(String::new());
+    let entries = move || {
+        issues.track();
+        let query = filter.get().to_lowercase();
+        outline(&live_content.get_untracked())
+            .into_iter()
+            .filter(|entry| query.is_empty() || entry.name.to_lowercase().contains(&query))
+            .collect::<ImVector<_>>()
+    };
+
+    v_stack((
+        text_input(filter)
+            .placeholder("Filter entries...")
+            .style(|s| {
+                s.width_full()
+                    .padding(4.0)
+                    .font_size(12.0)
+                    .border(1.0)
+                    .border_color(colors().border_strong)
+                    .border_radius(4.0)
+            }),
+        scroll(
+            virtual_list(
+                VirtualDirection::Vertical,
+                VirtualItemSize::Fixed(Box::new(|| OUTLINE_ROW_HEIGHT)),
+                entries,
+                |entry: &OutlineEntry| (entry.line, entry.name.clone()),
+                move |entry| outline_row(entry, handle.clone()),
+            )
+            .style(|s| s.width_full().flex_col()),
+        )
+        .style(|s| {
+            s.width_full()
+                .flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_height(0.0)
+        }),
+    ))
+    .style(|s| {
+        s.width(240.0)
+            .height_full()
+            .padding(6.0)
+            .gap(6.0)
+            .flex_shrink(0.0)
+            .background(colors().bg_surface)
+            .border_right(1.0)
+            .border_color(colors().border)
+    })
+}
+
+fn outline_row(entry: OutlineEntry, handle: EditorHandle) -> impl IntoView {
+    let icon = if entry.keyword == "treasuretable" {
+        "💰"
+    } else {
+        "📄"
+    };
+    let name = entry.name.clone();
+
+    h_stack((
+        label(move || icon).style(|s| s.font_size(11.0).width(18.0)),
+        label(move || name.clone()).style(|s| s.font_size(12.0).text_ellipsis().min_width(0.0)),
+    ))
+    .style(|s| {
+        s.width_full()
+            .height(OUTLINE_ROW_HEIGHT)
+            .padding_horiz(4.0)
+            .items_center()
+            .cursor(floem::style::CursorStyle::Pointer)
+            .hover(|s| s.background(colors().bg_hover))
+    })
+    .on_click_stop(move |_| {
+        let line = find_entry_line(&handle.doc.text().to_string(), &entry);
+        handle.go_to_line(line);
+    })
+}
+
+/// Issues from the last save's lint, click to jump to the line. Hidden when
+/// the file is clean.
+pub fn stats_issues_panel(
+    handle: EditorHandle,
+    issues: RwSignal<Vec<StatsIssue>>,
+) -> impl IntoView {
+    v_stack((
+        h_stack((
+            label(move || {
+                let issues = issues.get();
+                let warnings = issues.iter().filter(|i| i.kind.is_warning()).count();
+                format!(
+                    "Stats lint: {} errors, {} warnings",
+                    issues.len() - warnings,
+                    warnings
+                )
+            })
+            .style(|s| s.font_size(12.0).font_weight(Weight::SEMIBOLD)),
+            empty().style(|s| s.flex_grow(1.0)),
+            button("✕")
+                .style(|s| s.font_size(11.0).padding_horiz(6.0))
+                .action(move || issues.set(Vec::new())),
+        ))
+        .style(|s| s.width_full().items_center()),
+        scroll(dyn_container(
+            move || issues.get(),
+            move |issues| {
+                let handle = handle.clone();
+                v_stack_from_iter(
+                    issues
+                        .into_iter()
+                        .map(move |issue| issue_row(issue, handle.clone())),
+                )
+                .style(|s| s.width_full())
+                .into_any()
+            },
+        ))
+        .style(|s| s.width_full().max_height(140.0)),
+    ))
+    .style(move |s| {
+        let s = s
+            .width_full()
+            .padding(8.0)
+            .gap(4.0)
+            .background(colors().warning_bg)
+            .border_top(1.0)
+            .border_color(colors().border);
+        if issues.with(Vec::is_empty) {
+            s.display(floem::style::Display::None)
+        } else {
+            s
+        }
+    })
+}
+
+fn issue_row(issue: StatsIssue, handle: EditorHandle) -> impl IntoView {
+    let color = if issue.kind.is_warning() {
+        colors().warning
+    } else {
+        colors().error
+    };
+    let line = issue.line;
+    let text = format!(
+        "Line {}: {}: {}",
+        issue.line,
+        issue.kind.as_str(),
+        issue.message
+    );
+
+    label(move || text.clone())
+        .style(move |s| {
+            s.width_full()
+                .font_size(12.0)
+                .color(color)
+                .padding_vert(2.0)
+                .text_ellipsis()
+                .cursor(floem::style::CursorStyle::Pointer)
+                .hover(|s| s.background(colors().warning_bg))
+        })
+        // Issue lines are 1-based
+        .on_click_stop(move |_| handle.go_to_line(line.saturating_sub(1)))
+}
@@ -2,6 +2,7 @@
 
 use floem::prelude::*;
 
+use crate::gui::shared::colors;
 use crate::gui::state::EditorTabsState;
 
 pub fn editor_status_bar(tabs_state: EditorTabsState) -> impl IntoView {
@@ -20,7 +21,7 @@ pub fn editor_status_bar(tabs_state: EditorTabsState) -> impl IntoView {
                             .unwrap_or_else(|| "No file loaded".to_string())
                     })
                     .style(|s| {
-                        s.color(Color::rgb8(100, 100, 100))
+                        s.color(colors().text_secondary)
                             .font_size(12.0)
                             .text_ellipsis()
                             .max_width(500.0)
@@ -28,33 +29,29 @@ pub fn editor_status_bar(tabs_state: EditorTabsState) -> impl IntoView {
                     empty().style(|s| s.flex_grow(1.0)),
                     // Modified indicator
                     label(move || if modified.get() { "● Modified" } else { "" }.to_string())
-                        .style(|s| {
-                            s.color(Color::rgb8(255, 152, 0))
-                                .font_size(12.0)
-                                .margin_right(12.0)
-                        }),
+                        .style(|s| s.color(colors().warning).font_size(12.0).margin_right(12.0)),
                 ))
                 .style(|s| {
                     s.width_full()
                         .height(32.0)
                         .padding_horiz(12.0)
                         .items_center()
-                        .background(Color::rgb8(248, 248, 248))
+                        .background(colors().bg_surface)
                         .border_top(1.0)
-                        .border_color(Color::rgb8(220, 220, 220))
+                        .border_color(colors().border)
                 })
                 .into_any()
             } else {
                 h_stack((label(|| "No file loaded".to_string())
-                    .style(|s| s.color(Color::rgb8(100, 100, 100)).font_size(12.0)),))
+                    .style(|s| s.color(colors().text_secondary).font_size(12.0)),))
                 .style(|s| {
                     s.width_full()
                         .height(32.0)
                         .padding_horiz(12.0)
                         .items_center()
-                        .background(Color::rgb8(248, 248, 248))
+                        .background(colors().bg_surface)
                         .border_top(1.0)
-                        .border_color(Color::rgb8(220, 220, 220))
+                        .border_color(colors().border)
                 })
                 .into_any()
             }
@@ -3,10 +3,13 @@
 use floem::prelude::*;
 use floem::views::checkbox;
 
+use crate::gui::shared::colors;
 use crate::gui::state::EditorTabsState;
 
+use super::super::loca_table::is_loca_format;
 use super::super::operations::{
-    convert_file, open_file_dialog, save_file, save_file_as_dialog, validate_content,
+    can_format, compare_with_pak, compare_with_saved, compare_with_tab, convert_file,
+    format_document, open_file_dialog, save_file, save_file_as_dialog, validate_content,
 };
 use super::badges::{format_badge, save_status_badge};
 
@@ -26,7 +29,11 @@ pub fn editor_toolbar(tabs_state: EditorTabsState) -> impl IntoView {
     let tabs_state_save_check = tabs_state.clone();
     let tabs_state_save_action = tabs_state.clone();
     let tabs_state_save_as = tabs_state.clone();
+    let tabs_state_restore_check = tabs_state.clone();
+    let tabs_state_restore = tabs_state.clone();
     let tabs_state_validate = tabs_state.clone();
+    let tabs_state_format_check = tabs_state.clone();
+    let tabs_state_format = tabs_state.clone();
     let tabs_state_find = tabs_state.clone();
     let tabs_state_lsx = tabs_state.clone();
     let tabs_state_lsj = tabs_state.clone();
@@ -52,16 +59,26 @@ pub fn editor_toolbar(tabs_state: EditorTabsState) -> impl IntoView {
                 })
                 .action(move || {
                     if let Some(tab) = tabs_state_save_action.active_tab() {
-                        save_file(tab);
+                        save_file(tabs_state_save_action.clone(), tab);
                     }
                 }),
             button("💾 Save As...")
                 .style(toolbar_button_style)
                 .action(move || {
                     if let Some(tab) = tabs_state_save_as.active_tab() {
-                        save_file_as_dialog(tab);
+                        save_file_as_dialog(tab, tabs_state_save_as.backup_count.get());
                     }
                 }),
+            button("⏪ Restore...")
+                .style(toolbar_button_style)
+                .disabled(move || {
+                    tabs_state_restore_check
+                        .active_tab()
+                        .map_or(true, |tab| tab.file_path.get().is_none())
+                })
+                .action(move || {
+                    tabs_state_restore.show_restore_dialog.set(true);
+                }),
         ))
         .style(|s| s.gap(8.0).items_center()),
         separator(),
@@ -82,6 +99,24 @@ pub fn editor_toolbar(tabs_state: EditorTabsState) -> impl IntoView {
                         validate_content(tab, tabs_state.status_message);
                     }
                 }),
+            button("⇥ Format")
+                .style(toolbar_button_style)
+                .disabled(move || {
+                    tabs_state_format_check.active_tab().map_or(true, |tab| {
+                        !can_format(&tab.file_format.get()) || tab.content.get().is_empty()
+                    })
+                })
+                .action(move || {
+                    if let Some(tab) = tabs_state_format.active_tab() {
+                        format_document(
+                            tab,
+                            tabs_state_format.format_options.get(),
+                            tabs_state_format.status_message,
+                        );
+                    }
+                }),
+            compare_button(tabs_state.clone()),
+            structured_toggle(tabs_state.clone()),
             line_number_toggle(tabs_state.show_line_numbers),
         ))
         .style(|s| s.gap(8.0).items_center()),
@@ -114,9 +149,9 @@ pub fn editor_toolbar(tabs_state: EditorTabsState) -> impl IntoView {
             .padding(10.0)
             .gap(8.0)
             .items_center()
-            .background(Color::rgb8(245, 245, 245))
+            .background(colors().bg_elevated)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -164,11 +199,97 @@ fn convert_button_loca_group(format: &'static str, tabs_state: EditorTabsState)
         })
 }
 
+/// Open a side-by-side diff of the active tab against another source
+fn compare_button(tabs_state: EditorTabsState) -> impl IntoView {
+    let tabs_state_check = tabs_state.clone();
+
+    button("⇄ Compare")
+        .style(toolbar_button_style)
+        .disabled(move || {
+            tabs_state_check.diff.with(Option::is_some) || tabs_state_check.active_tab().is_none()
+        })
+        .action(move || show_compare_menu(tabs_state.clone()))
+}
+
+fn show_compare_menu(tabs_state: EditorTabsState) {
+    use floem::action::show_context_menu;
+    use floem::menu::{Menu, MenuItem};
+
+    let Some(active) = tabs_state.active_tab() else {
+        return;
+    };
+    let mut menu = Menu::new("");
+
+    if active.file_path.get_untracked().is_some() {
+        let tabs_state = tabs_state.clone();
+        menu = menu.entry(
+            MenuItem::new("With Saved File").action(move || compare_with_saved(tabs_state.clone())),
+        );
+    }
+    let tabs_state_pak = tabs_state.clone();
+    menu = menu.entry(
+        MenuItem::new("With PAK Version...")
+            .action(move || compare_with_pak(tabs_state_pak.clone())),
+    );
+
+    let others: Vec<_> = tabs_state
+        .tabs
+        .get_untracked()
+        .into_iter()
+        .filter(|tab| tab.id != active.id)
+        .collect();
+    if !others.is_empty() {
+        menu = menu.separator();
+    }
+    for other in others {
+        let tabs_state = tabs_state.clone();
+        let title = format!("With {}", other.display_name());
+        menu = menu.entry(
+            MenuItem::new(title)
+                .action(move || compare_with_tab(tabs_state.clone(), other.clone())),
+        );
+    }
+
+    show_context_menu(menu, None);
+}
+
+/// Switch between the text and the node tree (LSX and converted LSF) or the
+/// handle table (LOCA and localization XML)
+fn structured_toggle(tabs_state: EditorTabsState) -> impl IntoView {
+    let tabs_state_label = tabs_state.clone();
+    let tabs_state_check = tabs_state.clone();
+
+    button(label(move || match tabs_state_label.active_tab() {
+        Some(tab) if tab.structured_view.get() => "📝 Text",
+        Some(tab) if is_loca_format(&tab.file_format.get()) => "📋 Table",
+        _ => "🌳 Tree",
+    }))
+    .style(toolbar_button_style)
+    .disabled(move || {
+        tabs_state_check.active_tab().map_or(true, |tab| {
+            let f = tab.file_format.get().to_uppercase();
+            tab.live_content.get().is_empty()
+                || !(matches!(f.as_str(), "LSX" | "LSF" | "LSFX" | "LSBC" | "LSBS")
+                    || is_loca_format(&f))
+        })
+    })
+    .action(move || {
+        if let Some(tab) = tabs_state.active_tab() {
+            let show_structured = !tab.structured_view.get_untracked();
+            if show_structured {
+                // The text view is rebuilt from `content` when it comes back
+                tab.content.set(tab.live_content.get_untracked());
+            }
+            tab.structured_view.set(show_structured);
+        }
+    })
+}
+
 fn separator() -> impl IntoView {
     empty().style(|s| {
         s.width(1.0)
             .height(30.0)
-            .background(Color::rgb8(200, 200, 200))
+            .background(colors().bg_selected)
             .margin_horiz(4.0)
     })
 }
@@ -0,0 +1,283 @@
+//! Tree view of an LSX/LSF document with editable attribute values
+//!
+//! The tree is parsed from the tab's text when the view opens and every edit
+//! is written straight back into the text, so the two never disagree. The
+//! text can't be edited while the tree is shown.
+
+use std::collections::HashSet;
+
+use floem::event::{Event, EventListener};
+use floem::keyboard::{Key, NamedKey};
+use floem::prelude::*;
+use floem::views::checkbox;
+use maclarian::formats::lsx::{LsxDocument, parse_lsx};
+
+use crate::gui::shared::colors;
+use crate::gui::state::EditorTab;
+
+use super::super::tree::{
+    NodePath, ValueKind, document_text, node_at, set_attribute_value, value_kind, visible_rows,
+};
+
+pub fn tree_view(tab: EditorTab) -> impl IntoView {
+    let doc = match parse_lsx(&tab.live_content.get_untracked()) {
+        Ok(doc) => doc,
+        Err(e) => {
+            return label(move || format!("Can't show the tree until the text parses: {}", e))
+                .style(|s| s.padding(24.0).font_size(13.0).color(colors().error))
+                .into_any();
+        }
+    };
+
+    // Regions start expanded
+    let expanded: HashSet<NodePath> = (0..doc.regions.len()).map(|i| vec![i]).collect();
+    let doc = RwSignal::new(doc);
+    let expanded = RwSignal::new(expanded);
+    let selected: RwSignal<Option<NodePath>> = RwSignal::new(None);
+    let error = RwSignal::new(String::new());
+
+    h_stack((
+        scroll(node_tree(doc, expanded, selected)).style(|s| {
+            s.width_pct(45.0)
+                .height_full()
+                .border_right(1.0)
+                .border_color(colors().border)
+        }),
+        v_stack((
+            attribute_table(tab, doc, selected, error),
+            label(move || error.get()).style(move |s| {
+                let s = s.font_size(12.0).color(colors().error).padding(8.0);
+                if error.get().is_empty() {
+                    s.display(floem::style::Display::None)
+                } else {
+                    s
+                }
+            }),
+        ))
+        .style(|s| {
+            s.flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_width(0.0)
+                .height_full()
+        }),
+    ))
+    .style(|s| s.size_full().flex_grow(1.0).min_height(0.0))
+    .into_any()
+}
+
+/// Collapsible list of regions and nodes
+fn node_tree(
+    doc: RwSignal<LsxDocument>,
+    expanded: RwSignal<HashSet<NodePath>>,
+    selected: RwSignal<Option<NodePath>>,
+) -> impl IntoView {
+    dyn_container(
+        move || expanded.get(),
+        move |expanded_paths| {
+            let rows = doc.with_untracked(|doc| visible_rows(doc, &expanded_paths));
+            v_stack_from_iter(rows.into_iter().map(|row| {
+                let path_toggle = row.path.clone();
+                let path_select = row.path.clone();
+                let path_style = row.path.clone();
+                let is_expanded = expanded_paths.contains(&row.path);
+                let is_region = row.path.len() == 1;
+
+                h_stack((
+                    empty().style(move |s| s.width((row.depth * 16) as f32)),
+                    if row.has_children {
+                        label(move || if is_expanded { "▼" } else { "▶" })
+                            .style(|s| {
+                                s.font_size(10.0)
+                                    .width(14.0)
+                                    .color(colors().text_muted)
+                                    .cursor(floem::style::CursorStyle::Pointer)
+                            })
+                            .on_click_stop(move |_| {
+                                expanded.update(|paths| {
+                                    if !paths.remove(&path_toggle) {
+                                        paths.insert(path_toggle.clone());
+                                    }
+                                });
+                            })
+                            .into_any()
+                    } else {
+                        empty().style(|s| s.width(14.0)).into_any()
+                    },
+                    label(move || row.label.clone()).style(move |s| {
+                        let s = s.font_size(12.0);
+                        if is_region {
+                            s.font_weight(floem::text::Weight::SEMIBOLD)
+                        } else {
+                            s
+                        }
+                    }),
+                ))
+                .style(move |s| {
+                    let is_selected = selected.with(|p| p.as_ref() == Some(&path_style));
+                    let s = s
+                        .width_full()
+                        .padding_vert(2.0)
+                        .padding_horiz(4.0)
+                        .items_center();
+                    if is_selected {
+                        s.background(colors().accent_bg)
+                    } else {
+                        s.hover(|s| s.background(colors().bg_elevated))
+                    }
+                })
+                .on_click_stop(move |_| selected.set(Some(path_select.clone())))
+            }))
+            .style(|s| s.width_full().padding(4.0))
+            .into_any()
+        },
+    )
+}
+
+/// Attributes of the selected node, with an input per type
+fn attribute_table(
+    tab: EditorTab,
+    doc: RwSignal<LsxDocument>,
+    selected: RwSignal<Option<NodePath>>,
+    error: RwSignal<String>,
+) -> impl IntoView {
+    scroll(dyn_container(
+        move || selected.get(),
+        move |path| {
+            let Some(path) = path else {
+                return placeholder("Select a node to see its attributes").into_any();
+            };
+            let Some(attributes) =
+                doc.with_untracked(|doc| node_at(doc, &path).map(|node| node.attributes.clone()))
+            else {
+                return placeholder("Regions have no attributes").into_any();
+            };
+            if attributes.is_empty() {
+                return placeholder("This node has no attributes").into_any();
+            }
+
+            let tab = tab.clone();
+            v_stack_from_iter(attributes.into_iter().enumerate().map(|(index, attr)| {
+                let commit = {
+                    let tab = tab.clone();
+                    let path = path.clone();
+                    move |value: String| commit_value(&tab, doc, &path, index, &value, error)
+                };
+                let kind = value_kind(&attr.type_name);
+                let (id, type_name) = (attr.id, attr.type_name);
+
+                h_stack((
+                    label(move || id.clone()).style(|s| s.width(180.0).font_size(12.0)),
+                    label(move || type_name.clone())
+                        .style(|s| s.width(110.0).font_size(11.0).color(colors().text_muted)),
+                    value_input(kind, attr.value, commit),
+                ))
+                .style(|s| s.width_full().gap(8.0).items_center().padding_vert(3.0))
+            }))
+            .style(|s| s.width_full().padding(12.0))
+            .into_any()
+        },
+    ))
+    .style(|s| s.width_full().flex_grow(1.0).min_height(0.0))
+}
+
+fn placeholder(text: &'static str) -> impl IntoView {
+    label(move || text).style(|s| s.padding(24.0).color(colors().text_muted))
+}
+
+/// Checkbox for booleans, a text field (committed on Enter or when focus
+/// leaves) for everything else
+fn value_input(
+    kind: ValueKind,
+    value: String,
+    commit: impl Fn(String) -> Result<(), String> + Clone + 'static,
+) -> impl IntoView {
+    if kind == ValueKind::Bool {
+        let checked = RwSignal::new(value.eq_ignore_ascii_case("true") || value == "1");
+        return checkbox(move || checked.get())
+            .on_update(move |is_checked| {
+                let value = if is_checked { "True" } else { "False" };
+                if commit(value.to_string()).is_ok() {
+                    checked.set(is_checked);
+                }
+            })
+            .into_any();
+    }
+
+    let text = RwSignal::new(value.clone());
+    let committed = RwSignal::new(value);
+    let try_commit = move || {
+        let value = text.get_untracked();
+        if value == committed.get_untracked() {
+            return;
+        }
+        match commit(value.clone()) {
+            Ok(()) => committed.set(value),
+            // Put the last good value back
+            Err(_) => text.set(committed.get_untracked()),
+        }
+    };
+    let try_commit_enter = try_commit.clone();
+
+    let placeholder = match kind {
+        ValueKind::Guid => "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx",
+        ValueKind::Number => "0",
+        _ => "",
+    };
+
+    text_input(text)
+        .placeholder(placeholder)
+        .style(|s| {
+            s.flex_grow(1.0)
+                .min_width(120.0)
+                .padding(4.0)
+                .font_size(12.0)
+                .border(1.0)
+                .border_color(colors().border_strong)
+                .border_radius(4.0)
+        })
+        .on_event_cont(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(key_event) = e {
+                if key_event.key.logical_key == Key::Named(NamedKey::Enter) {
+                    try_commit_enter();
+                }
+            }
+        })
+        .on_event_cont(EventListener::FocusLost, move |_| try_commit())
+        .into_any()
+}
+
+/// Validate and store an edited value, then write the document back to the
+/// tab's text (one undo step per edit)
+fn commit_value(
+    tab: &EditorTab,
+    doc: RwSignal<LsxDocument>,
+    path: &[usize],
+    attribute: usize,
+    value: &str,
+    error: RwSignal<String>,
+) -> Result<(), String> {
+    let mut result = Ok(false);
+    doc.update(|doc| result = set_attribute_value(doc, path, attribute, value));
+
+    let text = result.and_then(|changed| {
+        if changed {
+            doc.with_untracked(document_text).map(Some)
+        } else {
+            Ok(None)
+        }
+    });
+
+    match text {
+        Ok(text) => {
+            error.set(String::new());
+            if let Some(text) = text {
+                tab.replace_text(text);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            error.set(e.clone());
+            Err(e)
+        }
+    }
+}
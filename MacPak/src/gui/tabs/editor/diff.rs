@@ -0,0 +1,491 @@
+//! Line and character diffs for the editor's compare view
+//!
+//! Lines are matched with Myers' O(ND) algorithm after trimming the common
+//! prefix and suffix. Each changed line is then paired with its counterpart
+//! and diffed again per character for intraline highlighting. Nothing here
+//! depends on floem.
+
+use std::ops::Range;
+
+/// Edit distance after which the diff stops searching and reports the rest
+/// as one replaced block (keeps memory bounded on unrelated files)
+const MAX_EDIT_COST: usize = 2000;
+/// Lines longer than this (in chars) are highlighted whole
+const MAX_INTRALINE_CHARS: usize = 2000;
+
+/// One step of an edit script turning `a` into `b`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edit {
+    /// Next element of `a` and `b` match
+    Equal,
+    /// Next element of `a` is removed
+    Delete,
+    /// Next element of `b` is inserted
+    Insert,
+}
+
+/// Pane of the side-by-side view
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// How a row differs between the two texts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowKind {
+    Equal,
+    /// Both sides have a line, with different text
+    Changed,
+    /// Only the left side has a line
+    Removed,
+    /// Only the right side has a line
+    Added,
+}
+
+/// A line in one pane of the diff
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffLine {
+    /// 0-based line number in its text
+    pub number: usize,
+    /// Line text without its line ending
+    pub text: String,
+    /// Byte ranges of `text` that differ from the line on the other side
+    pub changes: Vec<Range<usize>>,
+}
+
+/// One row of the side-by-side view; a missing side is shown as a gap
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffRow {
+    pub kind: RowKind,
+    pub left: Option<DiffLine>,
+    pub right: Option<DiffLine>,
+}
+
+/// A run of consecutive changed rows
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hunk {
+    /// Rows of the diff the hunk covers
+    pub rows: Range<usize>,
+    /// Lines of the left text the hunk replaces
+    pub left: Range<usize>,
+    /// Lines of the right text the hunk replaces
+    pub right: Range<usize>,
+}
+
+/// Side-by-side diff of two texts
+#[derive(Clone, Debug, Default)]
+pub struct TextDiff {
+    pub rows: Vec<DiffRow>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl TextDiff {
+    /// Diff `left` against `right` line by line
+    pub fn new(left: &str, right: &str) -> Self {
+        let left_lines = split_lines(left);
+        let right_lines = split_lines(right);
+        let script = myers(&left_lines, &right_lines);
+
+        let mut diff = Self::default();
+        let (mut l, mut r) = (0, 0);
+        let mut i = 0;
+        while i < script.len() {
+            if script[i] == Edit::Equal {
+                diff.rows.push(DiffRow {
+                    kind: RowKind::Equal,
+                    left: Some(plain_line(l, left_lines[l])),
+                    right: Some(plain_line(r, right_lines[r])),
+                });
+                l += 1;
+                r += 1;
+                i += 1;
+                continue;
+            }
+
+            // A block of deletes and inserts becomes one hunk
+            let start = i;
+            while i < script.len() && script[i] != Edit::Equal {
+                i += 1;
+            }
+            let deleted = script[start..i]
+                .iter()
+                .filter(|e| **e == Edit::Delete)
+                .count();
+            let inserted = i - start - deleted;
+            let hunk_rows = diff.rows.len();
+            for offset in 0..deleted.max(inserted) {
+                let old = (offset < deleted).then(|| l + offset);
+                let new = (offset < inserted).then(|| r + offset);
+                diff.rows.push(match (old, new) {
+                    (Some(old), Some(new)) => {
+                        changed_row(old, left_lines[old], new, right_lines[new])
+                    }
+                    (Some(old), None) => DiffRow {
+                        kind: RowKind::Removed,
+                        left: Some(whole_line(old, left_lines[old])),
+                        right: None,
+                    },
+                    (None, Some(new)) => DiffRow {
+                        kind: RowKind::Added,
+                        left: None,
+                        right: Some(whole_line(new, right_lines[new])),
+                    },
+                    (None, None) => unreachable!("offset is below deleted or inserted"),
+                });
+            }
+            diff.hunks.push(Hunk {
+                rows: hunk_rows..diff.rows.len(),
+                left: l..l + deleted,
+                right: r..r + inserted,
+            });
+            l += deleted;
+            r += inserted;
+        }
+        diff
+    }
+
+    /// Index of the hunk containing `row`, if any
+    pub fn hunk_at_row(&self, row: usize) -> Option<usize> {
+        self.hunks.iter().position(|hunk| hunk.rows.contains(&row))
+    }
+}
+
+/// Copy a hunk's lines from one text over the matching lines of the other
+///
+/// `to` names the side being changed; the returned string is its new text.
+pub fn copy_hunk(left: &str, right: &str, hunk: &Hunk, to: Side) -> String {
+    let (source, source_range, target, target_range) = match to {
+        Side::Left => (right, &hunk.right, left, &hunk.left),
+        Side::Right => (left, &hunk.left, right, &hunk.right),
+    };
+    let source_lines = split_lines(source);
+    let target_lines = split_lines(target);
+
+    let mut result = String::with_capacity(target.len());
+    for line in &target_lines[..target_range.start] {
+        result.push_str(line);
+    }
+    for line in &source_lines[source_range.clone()] {
+        result.push_str(line);
+    }
+    for line in &target_lines[target_range.end..] {
+        result.push_str(line);
+    }
+    result
+}
+
+/// Split text into lines, each keeping its line ending
+fn split_lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+fn trim_ending(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+fn plain_line(number: usize, line: &str) -> DiffLine {
+    DiffLine {
+        number,
+        text: trim_ending(line).to_string(),
+        changes: Vec::new(),
+    }
+}
+
+fn whole_line(number: usize, line: &str) -> DiffLine {
+    let text = trim_ending(line);
+    DiffLine {
+        number,
+        text: text.to_string(),
+        changes: whole_range(text),
+    }
+}
+
+fn changed_row(old: usize, old_line: &str, new: usize, new_line: &str) -> DiffRow {
+    let (old_text, new_text) = (trim_ending(old_line), trim_ending(new_line));
+    let (old_changes, new_changes) = char_changes(old_text, new_text);
+    DiffRow {
+        kind: RowKind::Changed,
+        left: Some(DiffLine {
+            number: old,
+            text: old_text.to_string(),
+            changes: old_changes,
+        }),
+        right: Some(DiffLine {
+            number: new,
+            text: new_text.to_string(),
+            changes: new_changes,
+        }),
+    }
+}
+
+/// Byte ranges that differ between two lines, on each side
+pub fn char_changes(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let old_chars: Vec<(usize, char)> = old.char_indices().collect();
+    let new_chars: Vec<(usize, char)> = new.char_indices().collect();
+    if old_chars.len() > MAX_INTRALINE_CHARS || new_chars.len() > MAX_INTRALINE_CHARS {
+        return (whole_range(old), whole_range(new));
+    }
+
+    let a: Vec<char> = old_chars.iter().map(|(_, c)| *c).collect();
+    let b: Vec<char> = new_chars.iter().map(|(_, c)| *c).collect();
+    let mut old_changes = Vec::new();
+    let mut new_changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for edit in myers(&a, &b) {
+        match edit {
+            Edit::Equal => {
+                i += 1;
+                j += 1;
+            }
+            Edit::Delete => {
+                push_range(&mut old_changes, char_range(old, &old_chars, i));
+                i += 1;
+            }
+            Edit::Insert => {
+                push_range(&mut new_changes, char_range(new, &new_chars, j));
+                j += 1;
+            }
+        }
+    }
+    (old_changes, new_changes)
+}
+
+fn whole_range(text: &str) -> Vec<Range<usize>> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        std::iter::once(0..text.len()).collect()
+    }
+}
+
+fn char_range(text: &str, chars: &[(usize, char)], index: usize) -> Range<usize> {
+    let start = chars[index].0;
+    let end = chars
+        .get(index + 1)
+        .map_or(text.len(), |(offset, _)| *offset);
+    start..end
+}
+
+/// Append a range, merging it into the previous one when they touch
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+/// Shortest edit script turning `a` into `b`
+pub fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let mut script = vec![Edit::Equal; prefix];
+    script.extend(myers_middle(
+        &a[prefix..a.len() - suffix],
+        &b[prefix..b.len() - suffix],
+    ));
+    script.extend(std::iter::repeat_n(Edit::Equal, suffix));
+    script
+}
+
+/// Myers' greedy forward search, keeping each round's furthest points for
+/// the backtrack
+fn myers_middle<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 {
+        return replace_all(n, m);
+    }
+
+    let max = (n + m).min(MAX_EDIT_COST) as isize;
+    let offset = max + 1;
+    let index = |k: isize| (k + offset) as usize;
+    let mut v = vec![0usize; (2 * offset + 1) as usize];
+    // trace[d] holds v[-d..=d] as it was before round d
+    let mut trace: Vec<Vec<usize>> = Vec::new();
+
+    for d in 0..=max {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        let mut k = -d;
+        while k <= d {
+            let down = k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]);
+            let mut x = if down {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            while x < n && y < m && a[x] == b[y] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                return backtrack(&trace, n, m);
+            }
+            k += 2;
+        }
+    }
+
+    // Too different to be worth the search
+    replace_all(n, m)
+}
+
+fn backtrack(trace: &[Vec<usize>], n: usize, m: usize) -> Vec<Edit> {
+    let mut script = Vec::with_capacity(n + m);
+    let (mut x, mut y) = (n as isize, m as isize);
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let get = |k: isize| v[(k + d) as usize] as isize;
+        let k = x - y;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let prev_k = if k == -d || (k != d && get(k - 1) < get(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            let prev_x = get(prev_k);
+            (prev_x, prev_x - prev_k)
+        };
+
+        while x > prev_x && y > prev_y {
+            script.push(Edit::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            script.push(if x == prev_x {
+                Edit::Insert
+            } else {
+                Edit::Delete
+            });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+
+    script.reverse();
+    script
+}
+
+fn replace_all(deleted: usize, inserted: usize) -> Vec<Edit> {
+    let mut script = vec![Edit::Delete; deleted];
+    script.extend(std::iter::repeat_n(Edit::Insert, inserted));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply an edit script to check it really turns `a` into `b`
+    fn apply(a: &[char], b: &[char], script: &[Edit]) -> Vec<char> {
+        let (mut i, mut j) = (0, 0);
+        let mut out = Vec::new();
+        for edit in script {
+            match edit {
+                Edit::Equal => {
+                    assert_eq!(a[i], b[j]);
+                    out.push(a[i]);
+                    i += 1;
+                    j += 1;
+                }
+                Edit::Delete => i += 1,
+                Edit::Insert => {
+                    out.push(b[j]);
+                    j += 1;
+                }
+            }
+        }
+        assert_eq!((i, j), (a.len(), b.len()));
+        out
+    }
+
+    #[test]
+    fn test_myers_finds_shortest_script() {
+        let a: Vec<char> = "ABCABBA".chars().collect();
+        let b: Vec<char> = "CBABAC".chars().collect();
+        let script = myers(&a, &b);
+        assert_eq!(apply(&a, &b, &script), b);
+        assert_eq!(script.iter().filter(|e| **e != Edit::Equal).count(), 5);
+
+        assert_eq!(myers::<char>(&[], &['x']), [Edit::Insert]);
+        assert_eq!(myers(&a, &a), vec![Edit::Equal; a.len()]);
+    }
+
+    #[test]
+    fn test_rows_hunks_and_intraline_changes() {
+        let left = "<save>\n    <attribute id=\"Name\" value=\"Sword\" />\n    <old />\n</save>\n";
+        let right =
+            "<save>\n    <attribute id=\"Name\" value=\"Spear\" />\n</save>\n<!-- end -->\n";
+        let diff = TextDiff::new(left, right);
+
+        let kinds: Vec<RowKind> = diff.rows.iter().map(|row| row.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RowKind::Equal,
+                RowKind::Changed,
+                RowKind::Removed,
+                RowKind::Equal,
+                RowKind::Added,
+            ]
+        );
+        assert_eq!(diff.hunks.len(), 2);
+        assert_eq!(diff.hunks[0].left, 1..3);
+        assert_eq!(diff.hunks[0].right, 1..2);
+        assert_eq!(diff.hunk_at_row(2), Some(0));
+        assert_eq!(diff.hunk_at_row(3), None);
+
+        // "Sword" -> "Spear" keeps the shared S and r
+        let changed = &diff.rows[1];
+        let old = changed.left.as_ref().unwrap();
+        let new = changed.right.as_ref().unwrap();
+        let marked = |line: &DiffLine| -> String {
+            line.changes
+                .iter()
+                .map(|range| &line.text[range.clone()])
+                .collect()
+        };
+        assert_eq!(marked(old), "wod");
+        assert_eq!(marked(new), "pea");
+
+        assert!(TextDiff::new(left, left).hunks.is_empty());
+    }
+
+    #[test]
+    fn test_copy_hunk_merges_both_ways() {
+        let left = "a\nb\nc\nd\n";
+        let right = "a\nB\nc\nd\ne\n";
+        let diff = TextDiff::new(left, right);
+        assert_eq!(diff.hunks.len(), 2);
+
+        assert_eq!(
+            copy_hunk(left, right, &diff.hunks[0], Side::Left),
+            "a\nB\nc\nd\n"
+        );
+        assert_eq!(
+            copy_hunk(left, right, &diff.hunks[1], Side::Right),
+            "a\nB\nc\nd\n"
+        );
+
+        // Copying every hunk left makes the texts equal
+        let mut merged = left.to_string();
+        loop {
+            let diff = TextDiff::new(&merged, right);
+            let Some(hunk) = diff.hunks.first() else {
+                break;
+            };
+            merged = copy_hunk(&merged, right, hunk, Side::Left);
+        }
+        assert_eq!(merged, right);
+    }
+}
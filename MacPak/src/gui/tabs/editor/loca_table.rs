@@ -0,0 +1,247 @@
+//! Handle/text table behind the editor's LOCA view
+//!
+//! Rows are parsed from the tab's localization XML and written back to it
+//! after every edit. Filtering is incremental: a query that extends the last
+//! one only searches the rows that matched before.
+
+use maclarian::converter::{loca_from_xml, loca_to_xml_string};
+use maclarian::formats::loca::{LocaResource, LocalizedText};
+
+use crate::gui::utils::{UuidFormat, generate_uuid};
+
+/// Formats shown as a table rather than a node tree
+pub fn is_loca_format(format: &str) -> bool {
+    matches!(format.to_uppercase().as_str(), "LOCA" | "XML")
+}
+
+/// One localized string
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocaRow {
+    /// Stable id for the list view (survives edits, unlike the index)
+    pub id: u64,
+    pub handle: String,
+    pub version: u16,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct LocaTable {
+    rows: Vec<LocaRow>,
+    next_id: u64,
+    /// Last query and the ids of the rows it matched
+    last_filter: Option<(String, Vec<u64>)>,
+}
+
+impl LocaTable {
+    /// Parse localization XML (the text form of a `.loca` file)
+    pub fn parse(xml: &str) -> Result<Self, String> {
+        // Other XML parses as an empty table, which would wipe it on the first edit
+        if !xml.contains("<contentList") {
+            return Err("Not a localization file (no <contentList>)".to_string());
+        }
+        let resource = loca_from_xml(xml).map_err(|e| e.to_string())?;
+        let mut table = Self::default();
+        for entry in resource.entries {
+            table.push(entry.key, entry.version, entry.text);
+        }
+        Ok(table)
+    }
+
+    /// Serialize back to XML
+    ///
+    /// The writer already puts one entry per line; `format_xml` would move
+    /// each text onto its own line and change it.
+    pub fn to_xml(&self) -> Result<String, String> {
+        let entries = self
+            .rows
+            .iter()
+            .map(|row| LocalizedText::new(row.handle.clone(), row.version, row.text.clone()))
+            .collect();
+        loca_to_xml_string(&LocaResource::new(entries)).map_err(|e| e.to_string())
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Rows stay in id order (new rows are appended), so ids can be
+    /// binary searched
+    fn index_of(&self, id: u64) -> Option<usize> {
+        self.rows.binary_search_by_key(&id, |row| row.id).ok()
+    }
+
+    fn row(&self, id: u64) -> Option<&LocaRow> {
+        self.index_of(id).map(|i| &self.rows[i])
+    }
+
+    fn push(&mut self, handle: String, version: u16, text: String) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.rows.push(LocaRow {
+            id,
+            handle,
+            version,
+            text,
+        });
+        id
+    }
+
+    /// Rows whose handle or text contains `query` (case-insensitive)
+    pub fn filter(&mut self, query: &str) -> Vec<LocaRow> {
+        let query = query.trim().to_lowercase();
+        let matches = |row: &LocaRow| {
+            query.is_empty()
+                || row.handle.to_lowercase().contains(&query)
+                || row.text.to_lowercase().contains(&query)
+        };
+
+        let ids: Vec<u64> = match &self.last_filter {
+            // Narrowing the last query can only drop rows
+            Some((last, ids)) if query.contains(last.as_str()) => ids
+                .iter()
+                .copied()
+                .filter(|&id| self.row(id).is_some_and(matches))
+                .collect(),
+            _ => self
+                .rows
+                .iter()
+                .filter(|row| matches(row))
+                .map(|row| row.id)
+                .collect(),
+        };
+
+        let rows = if ids.len() == self.rows.len() {
+            self.rows.clone()
+        } else {
+            ids.iter().filter_map(|&id| self.row(id)).cloned().collect()
+        };
+        self.last_filter = Some((query, ids));
+        rows
+    }
+
+    /// Change a row's handle; empty and duplicate handles are refused
+    pub fn set_handle(&mut self, id: u64, handle: &str) -> Result<bool, String> {
+        let handle = handle.trim();
+        if handle.is_empty() {
+            return Err("Handle can't be empty".to_string());
+        }
+        if self
+            .rows
+            .iter()
+            .any(|row| row.id != id && row.handle == handle)
+        {
+            return Err(format!("Handle {} is already in the table", handle));
+        }
+        let row = self.row_mut(id)?;
+        if row.handle == handle {
+            return Ok(false);
+        }
+        row.handle = handle.to_string();
+        self.last_filter = None;
+        Ok(true)
+    }
+
+    pub fn set_text(&mut self, id: u64, text: &str) -> Result<bool, String> {
+        let row = self.row_mut(id)?;
+        if row.text == text {
+            return Ok(false);
+        }
+        row.text = text.to_string();
+        self.last_filter = None;
+        Ok(true)
+    }
+
+    /// Append an empty row with a freshly generated handle
+    pub fn add_row(&mut self) -> u64 {
+        let handle = self.new_handle();
+        self.last_filter = None;
+        self.push(handle, 1, String::new())
+    }
+
+    /// Give a row a freshly generated handle
+    pub fn regenerate_handle(&mut self, id: u64) -> Result<bool, String> {
+        let handle = self.new_handle();
+        self.set_handle(id, &handle)
+    }
+
+    fn new_handle(&self) -> String {
+        loop {
+            let handle = generate_uuid(UuidFormat::Larian);
+            if !self.rows.iter().any(|row| row.handle == handle) {
+                return handle;
+            }
+        }
+    }
+
+    pub fn delete_row(&mut self, id: u64) -> bool {
+        let Some(index) = self.index_of(id) else {
+            return false;
+        };
+        self.rows.remove(index);
+        self.last_filter = None;
+        true
+    }
+
+    fn row_mut(&mut self, id: u64) -> Result<&mut LocaRow, String> {
+        let index = self
+            .index_of(id)
+            .ok_or_else(|| "Row no longer exists".to_string())?;
+        Ok(&mut self.rows[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<contentList>
+    <content contentuid="h0001" version="1">Longsword</content>
+    <content contentuid="h0002" version="2">Shortsword</content>
+    <content contentuid="h0003" version="1">Shield</content>
+</contentList>
+"#;
+
+    fn handles(rows: &[LocaRow]) -> Vec<&str> {
+        rows.iter().map(|row| row.handle.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filters_incrementally() {
+        assert!(LocaTable::parse("<save></save>").is_err());
+        let mut table = LocaTable::parse(SAMPLE).unwrap();
+        assert_eq!(table.filter("").len(), 3);
+        assert_eq!(handles(&table.filter("s")), vec!["h0001", "h0002", "h0003"]);
+        assert_eq!(handles(&table.filter("sword")), vec!["h0001", "h0002"]);
+        assert_eq!(handles(&table.filter("SHORT")), vec!["h0002"]);
+        // Widening starts over
+        assert_eq!(handles(&table.filter("h000")).len(), 3);
+    }
+
+    #[test]
+    fn test_refuses_duplicate_handles() {
+        let mut table = LocaTable::parse(SAMPLE).unwrap();
+        let id = table.filter("h0003")[0].id;
+        assert!(table.set_handle(id, "h0001").is_err());
+        assert!(table.set_handle(id, " ").is_err());
+        assert!(table.set_handle(id, "h0004").unwrap());
+        assert!(!table.set_handle(id, "h0004").unwrap());
+    }
+
+    #[test]
+    fn test_edits_round_trip_through_xml() {
+        let mut table = LocaTable::parse(SAMPLE).unwrap();
+        let new_id = table.add_row();
+        assert!(table.row(new_id).unwrap().handle.starts_with('h'));
+        assert!(table.regenerate_handle(new_id).unwrap());
+        table.set_text(new_id, "Tower & Shield").unwrap();
+        let first = table.filter("h0001")[0].id;
+        assert!(table.delete_row(first));
+
+        let reparsed = LocaTable::parse(&table.to_xml().unwrap()).unwrap();
+        assert_eq!(reparsed.len(), 3);
+        let texts: Vec<_> = reparsed.rows.iter().map(|r| r.text.as_str()).collect();
+        assert_eq!(texts, vec!["Shortsword", "Shield", "Tower & Shield"]);
+        assert_eq!(reparsed.rows[0].version, 2);
+    }
+}
@@ -0,0 +1,644 @@
+//! LSX tokenizing, node ranges, folding and breadcrumbs
+//!
+//! Everything works a line at a time so the editor only tokenizes the lines it
+//! draws. Nothing here depends on floem.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Kinds of LSX token, each drawn in its own color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LsxTokenKind {
+    /// `<`, `</`, `>`, `/>` and `=`
+    Punctuation,
+    /// Element name (`node`, `attribute`, `region`...)
+    TagName,
+    /// Attribute name
+    AttributeName,
+    /// Quoted value of an `id` attribute
+    Id,
+    /// Quoted value of a `type` attribute
+    Type,
+    /// Quoted value of any other attribute
+    Value,
+    /// `<!-- ... -->`
+    Comment,
+    /// `<?xml ... ?>`
+    Declaration,
+}
+
+/// A token as a byte range within its line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LsxToken {
+    pub start: usize,
+    pub end: usize,
+    pub kind: LsxTokenKind,
+}
+
+/// What a line starts inside of (carried over from the previous line)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineState {
+    #[default]
+    Text,
+    Comment,
+    Tag,
+}
+
+/// Whether content is XML at all. Anything else is shown as plain text.
+pub fn looks_like_xml(text: &str) -> bool {
+    text.trim_start_matches('\u{feff}')
+        .trim_start()
+        .starts_with('<')
+}
+
+/// Tokenize one line (without its newline), starting in `state`.
+/// Returns the tokens and the state the next line starts in.
+pub fn tokenize_line(line: &str, state: LineState) -> (Vec<LsxToken>, LineState) {
+    let bytes = line.as_bytes();
+    let mut tokens = Vec::new();
+    let mut state = state;
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        match state {
+            LineState::Comment => {
+                let (end, closed) = match line[pos..].find("-->") {
+                    Some(i) => (pos + i + 3, true),
+                    None => (bytes.len(), false),
+                };
+                push(&mut tokens, pos, end, LsxTokenKind::Comment);
+                pos = end;
+                if closed {
+                    state = LineState::Text;
+                }
+            }
+            LineState::Tag => {
+                let (end, next) = tokenize_tag_body(line, pos, &mut tokens);
+                pos = end;
+                state = next;
+            }
+            LineState::Text => {
+                let Some(i) = line[pos..].find('<') else {
+                    break;
+                };
+                pos += i;
+                let rest = &line[pos..];
+
+                if rest.starts_with("<!--") {
+                    state = LineState::Comment;
+                } else if rest.starts_with("<?") {
+                    let end = rest.find("?>").map_or(bytes.len(), |i| pos + i + 2);
+                    push(&mut tokens, pos, end, LsxTokenKind::Declaration);
+                    pos = end;
+                } else {
+                    let open = if rest.starts_with("</") { 2 } else { 1 };
+                    push(&mut tokens, pos, pos + open, LsxTokenKind::Punctuation);
+                    pos += open;
+                    let name_end = name_end(bytes, pos);
+                    push(&mut tokens, pos, name_end, LsxTokenKind::TagName);
+                    pos = name_end;
+                    state = LineState::Tag;
+                }
+            }
+        }
+    }
+
+    (tokens, state)
+}
+
+/// Tokenize attributes up to the end of a tag (or the line)
+fn tokenize_tag_body(line: &str, mut pos: usize, tokens: &mut Vec<LsxToken>) -> (usize, LineState) {
+    let bytes = line.as_bytes();
+    let mut attribute = "";
+
+    while pos < bytes.len() {
+        let b = bytes[pos];
+        if b.is_ascii_whitespace() {
+            pos += 1;
+        } else if line[pos..].starts_with("/>") {
+            push(tokens, pos, pos + 2, LsxTokenKind::Punctuation);
+            return (pos + 2, LineState::Text);
+        } else if b == b'>' {
+            push(tokens, pos, pos + 1, LsxTokenKind::Punctuation);
+            return (pos + 1, LineState::Text);
+        } else if b == b'=' {
+            push(tokens, pos, pos + 1, LsxTokenKind::Punctuation);
+            pos += 1;
+        } else if b == b'"' || b == b'\'' {
+            let end = line[pos + 1..]
+                .find(b as char)
+                .map_or(bytes.len(), |i| pos + 1 + i + 1);
+            let kind = match attribute {
+                "id" => LsxTokenKind::Id,
+                "type" => LsxTokenKind::Type,
+                _ => LsxTokenKind::Value,
+            };
+            push(tokens, pos, end, kind);
+            pos = end;
+        } else if is_name_byte(b) {
+            let end = name_end(bytes, pos);
+            push(tokens, pos, end, LsxTokenKind::AttributeName);
+            attribute = &line[pos..end];
+            pos = end;
+        } else {
+            pos += line[pos..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    (pos, LineState::Tag)
+}
+
+fn push(tokens: &mut Vec<LsxToken>, start: usize, end: usize, kind: LsxTokenKind) {
+    if start < end {
+        tokens.push(LsxToken { start, end, kind });
+    }
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b':' | b'.')
+}
+
+fn name_end(bytes: &[u8], start: usize) -> usize {
+    let mut end = start;
+    while end < bytes.len() && is_name_byte(bytes[end]) {
+        end += 1;
+    }
+    end
+}
+
+/// Byte offset of the start of each line
+pub fn line_offsets(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Document text tokenized lazily, one line at a time.
+///
+/// Only lines that are asked for are tokenized (and cached); the state each
+/// line starts in is worked out up to the furthest line asked for.
+#[derive(Default)]
+pub struct LsxLines {
+    text: String,
+    offsets: Vec<usize>,
+    states: Vec<LineState>,
+    tokens: HashMap<usize, Vec<LsxToken>>,
+}
+
+impl LsxLines {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            offsets: line_offsets(text),
+            states: vec![LineState::Text],
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Replace the text after an edit, dropping cached tokens
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            *self = Self::new(text);
+        }
+    }
+
+    /// Text of a line, without its line ending
+    pub fn line(&self, line: usize) -> &str {
+        let Some(&start) = self.offsets.get(line) else {
+            return "";
+        };
+        let end = self
+            .offsets
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        self.text[start..end].trim_end_matches(['\n', '\r'])
+    }
+
+    /// Tokens of a line
+    pub fn tokens(&mut self, line: usize) -> &[LsxToken] {
+        if line >= self.offsets.len() {
+            return &[];
+        }
+        while self.states.len() <= line {
+            let previous = self.states.len() - 1;
+            let (_, next) = tokenize_line(self.line(previous), self.states[previous]);
+            self.states.push(next);
+        }
+        if !self.tokens.contains_key(&line) {
+            let (tokens, _) = tokenize_line(self.line(line), self.states[line]);
+            self.tokens.insert(line, tokens);
+        }
+        &self.tokens[&line]
+    }
+}
+
+/// Lines spanned by a `<node>` or `<region>` element
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeRange {
+    /// `node` or `region`
+    pub tag: String,
+    /// Its `id` attribute
+    pub id: String,
+    pub start_line: usize,
+    /// Line of the closing tag (same as `start_line` for `<node ... />`)
+    pub end_line: usize,
+}
+
+/// A complete tag found while scanning
+enum TagEvent {
+    Open {
+        name: String,
+        id: String,
+        line: usize,
+    },
+    Close {
+        name: String,
+        line: usize,
+    },
+    Empty {
+        name: String,
+        id: String,
+        line: usize,
+    },
+}
+
+/// Scan text for complete tags, in order
+fn tag_events(text: &str) -> Vec<TagEvent> {
+    let mut events = Vec::new();
+    let mut state = LineState::Text;
+    // Tag being read: (name, id, is closing tag, line it started on)
+    let mut pending: Option<(String, String, bool, usize)> = None;
+
+    for (number, line) in text.split('\n').enumerate() {
+        let line = line.trim_end_matches('\r');
+        let (tokens, next) = tokenize_line(line, state);
+        state = next;
+
+        for token in tokens {
+            let content = &line[token.start..token.end];
+            match token.kind {
+                LsxTokenKind::Punctuation if content == "<" || content == "</" => {
+                    pending = Some((String::new(), String::new(), content == "</", number));
+                }
+                LsxTokenKind::TagName => {
+                    if let Some(tag) = &mut pending {
+                        tag.0 = content.to_string();
+                    }
+                }
+                LsxTokenKind::Id => {
+                    if let Some(tag) = &mut pending {
+                        tag.1 = content.trim_matches(['"', '\'']).to_string();
+                    }
+                }
+                LsxTokenKind::Punctuation if content == ">" || content == "/>" => {
+                    if let Some((name, id, closing, line)) = pending.take() {
+                        events.push(if closing {
+                            TagEvent::Close { name, line: number }
+                        } else if content == "/>" {
+                            TagEvent::Empty { name, id, line }
+                        } else {
+                            TagEvent::Open { name, id, line }
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    events
+}
+
+fn is_node_tag(name: &str) -> bool {
+    name == "node" || name == "region"
+}
+
+/// All `<node>` and `<region>` elements, ordered by start line
+pub fn node_ranges(text: &str) -> Vec<NodeRange> {
+    let mut ranges = Vec::new();
+    let mut open: Vec<(String, String, usize)> = Vec::new();
+
+    for event in tag_events(text) {
+        match event {
+            TagEvent::Open { name, id, line } => open.push((name, id, line)),
+            TagEvent::Empty { name, id, line } => {
+                if is_node_tag(&name) {
+                    ranges.push(NodeRange {
+                        tag: name,
+                        id,
+                        start_line: line,
+                        end_line: line,
+                    });
+                }
+            }
+            TagEvent::Close { name, line } => {
+                // Pop up to the matching open tag; unmatched closes are ignored
+                let Some(index) = open.iter().rposition(|(open_name, ..)| *open_name == name)
+                else {
+                    continue;
+                };
+                let (name, id, start_line) = open.remove(index);
+                open.truncate(index);
+                if is_node_tag(&name) {
+                    ranges.push(NodeRange {
+                        tag: name,
+                        id,
+                        start_line,
+                        end_line: line,
+                    });
+                }
+            }
+        }
+    }
+
+    ranges.sort_by_key(|r| r.start_line);
+    ranges
+}
+
+/// Region and node ids enclosing the end of `prefix` (the text before the cursor)
+pub fn breadcrumb(prefix: &str) -> Vec<String> {
+    let mut path: Vec<(String, String)> = Vec::new();
+
+    for event in tag_events(prefix) {
+        match event {
+            TagEvent::Open { name, id, .. } => path.push((name, id)),
+            TagEvent::Close { name, .. } => {
+                if let Some(index) = path.iter().rposition(|(open, _)| *open == name) {
+                    path.truncate(index);
+                }
+            }
+            TagEvent::Empty { .. } => {}
+        }
+    }
+
+    path.into_iter()
+        .filter(|(name, _)| is_node_tag(name))
+        .map(|(name, id)| if id.is_empty() { name } else { id })
+        .collect()
+}
+
+/// A replacement to apply to the document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+const FOLD_MARKER_PREFIX: &str = "<!-- folded: ";
+
+fn fold_marker(id: usize, lines: usize) -> String {
+    format!("{}{} lines #{} -->", FOLD_MARKER_PREFIX, lines, id)
+}
+
+/// Fold id of a marker line
+fn parse_fold_marker(line: &str) -> Option<usize> {
+    line.trim()
+        .strip_prefix(FOLD_MARKER_PREFIX)?
+        .strip_suffix(" -->")?
+        .rsplit_once(" #")?
+        .1
+        .parse()
+        .ok()
+}
+
+/// Folded node contents, keyed by the id in their marker.
+///
+/// Folding replaces a node's children with a marker comment, so the document
+/// stays valid XML. Blocks are kept even after unfolding so undo/redo of a fold
+/// can still be expanded.
+#[derive(Default)]
+pub struct FoldStore {
+    blocks: HashMap<usize, String>,
+    next_id: usize,
+}
+
+impl FoldStore {
+    /// Fold the innermost multi-line `<node>` around `line`.
+    /// Returns `None` if there is nothing to fold.
+    pub fn fold_at(&mut self, text: &str, line: usize) -> Option<TextEdit> {
+        let node = node_ranges(text)
+            .into_iter()
+            .filter(|n| n.tag == "node" && n.start_line <= line && line <= n.end_line)
+            .filter(|n| n.end_line > n.start_line + 1)
+            .max_by_key(|n| n.start_line)?;
+
+        let offsets = line_offsets(text);
+        let start = offsets[node.start_line + 1];
+        let end = offsets[node.end_line];
+        let hidden = &text[start..end];
+        let indent: String = hidden
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.blocks.insert(id, hidden.to_string());
+
+        Some(TextEdit {
+            range: start..end,
+            replacement: format!(
+                "{}{}\n",
+                indent,
+                fold_marker(id, node.end_line - node.start_line - 1)
+            ),
+        })
+    }
+
+    /// Unfold the marker on `line`, if there is one
+    pub fn unfold_at(&self, text: &str, line: usize) -> Option<TextEdit> {
+        let offsets = line_offsets(text);
+        let start = *offsets.get(line)?;
+        let end = offsets.get(line + 1).copied().unwrap_or(text.len());
+        let hidden = self.blocks.get(&parse_fold_marker(&text[start..end])?)?;
+        Some(TextEdit {
+            range: start..end,
+            replacement: hidden.clone(),
+        })
+    }
+
+    /// Unfold everything, if anything is folded
+    pub fn unfold_all(&self, text: &str) -> Option<TextEdit> {
+        let expanded = self.expand(text);
+        (expanded != text).then_some(TextEdit {
+            range: 0..text.len(),
+            replacement: expanded,
+        })
+    }
+
+    /// Text with every fold expanded (what gets saved)
+    pub fn expand(&self, text: &str) -> String {
+        if self.blocks.is_empty() {
+            return text.to_string();
+        }
+        self.expand_below(text, self.next_id)
+    }
+
+    /// Expand markers with ids below `limit`. A block can only contain folds
+    /// made before it, which keeps a hand-typed marker from recursing forever.
+    fn expand_below(&self, text: &str, limit: usize) -> String {
+        let mut out = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            match parse_fold_marker(line)
+                .filter(|id| *id < limit)
+                .and_then(|id| self.blocks.get(&id).map(|block| (id, block)))
+            {
+                Some((id, block)) => out.push_str(&self.expand_below(block, id)),
+                None => out.push_str(line),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use LsxTokenKind::*;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<save>
+    <region id="Config">
+        <node id="root">
+            <children>
+                <node id="Item">
+                    <attribute id="Name" type="LSString" value="Sword"/>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>
+"#;
+
+    fn kinds(line: &str) -> Vec<(LsxTokenKind, &str)> {
+        tokenize_line(line, LineState::Text)
+            .0
+            .into_iter()
+            .map(|t| (t.kind, &line[t.start..t.end]))
+            .collect()
+    }
+
+    fn apply(text: &str, edit: &TextEdit) -> String {
+        let mut out = text.to_string();
+        out.replace_range(edit.range.clone(), &edit.replacement);
+        out
+    }
+
+    #[test]
+    fn test_tokenizes_attribute_line() {
+        let line = r#"<attribute id="Name" type="LSString" value="Sword"/>"#;
+        assert_eq!(
+            kinds(line),
+            vec![
+                (Punctuation, "<"),
+                (TagName, "attribute"),
+                (AttributeName, "id"),
+                (Punctuation, "="),
+                (Id, "\"Name\""),
+                (AttributeName, "type"),
+                (Punctuation, "="),
+                (Type, "\"LSString\""),
+                (AttributeName, "value"),
+                (Punctuation, "="),
+                (Value, "\"Sword\""),
+                (Punctuation, "/>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comment_state_carries_across_lines() {
+        let (tokens, state) = tokenize_line("<node> <!-- start", LineState::Text);
+        assert_eq!(state, LineState::Comment);
+        assert_eq!(tokens.last().map(|t| t.kind), Some(Comment));
+
+        let (tokens, state) = tokenize_line("end --> </node>", LineState::Comment);
+        assert_eq!(state, LineState::Text);
+        assert_eq!(tokens[0].kind, Comment);
+        assert_eq!(tokens[1].kind, Punctuation);
+    }
+
+    #[test]
+    fn test_tag_state_carries_across_lines() {
+        let (_, state) = tokenize_line(r#"<node id="a""#, LineState::Text);
+        assert_eq!(state, LineState::Tag);
+        let (tokens, state) = tokenize_line(r#"  type="b">"#, LineState::Tag);
+        assert_eq!(state, LineState::Text);
+        assert_eq!(tokens[2].kind, Type);
+    }
+
+    #[test]
+    fn test_non_ascii_text_is_skipped() {
+        let line = "<attribute value=\"Épée\"/> — ünïcode";
+        let tokens = kinds(line);
+        assert_eq!(tokens[4], (Value, "\"Épée\""));
+        assert!(looks_like_xml("\u{feff}  <save/>"));
+        assert!(!looks_like_xml("{\"save\": {}}"));
+    }
+
+    #[test]
+    fn test_lazy_lines_match_full_tokenizing() {
+        let mut lines = LsxLines::new(SAMPLE);
+        let line = r#"                    <attribute id="Name" type="LSString" value="Sword"/>"#;
+        assert_eq!(lines.line(6), line);
+        assert_eq!(lines.tokens(6), tokenize_line(line, LineState::Text).0);
+        assert!(lines.tokens(100).is_empty());
+    }
+
+    #[test]
+    fn test_finds_node_ranges() {
+        let ranges = node_ranges(SAMPLE);
+        let summary: Vec<_> = ranges
+            .iter()
+            .map(|r| (r.id.as_str(), r.start_line, r.end_line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("Config", 2, 10), ("root", 3, 9), ("Item", 5, 7)]
+        );
+    }
+
+    #[test]
+    fn test_breadcrumb_follows_cursor() {
+        let offset = SAMPLE.find("<attribute").unwrap();
+        assert_eq!(
+            breadcrumb(&SAMPLE[..offset]),
+            vec!["Config", "root", "Item"]
+        );
+
+        let offset = SAMPLE.find("</children>").unwrap();
+        assert_eq!(breadcrumb(&SAMPLE[..offset]), vec!["Config", "root"]);
+
+        assert!(breadcrumb("").is_empty());
+    }
+
+    #[test]
+    fn test_fold_and_unfold_round_trip() {
+        let mut folds = FoldStore::default();
+        let edit = folds.fold_at(SAMPLE, 6).unwrap();
+        let folded = apply(SAMPLE, &edit);
+        assert!(folded.contains("<!-- folded: 1 lines #0 -->"));
+        assert!(!folded.contains("Sword"));
+        assert_eq!(folds.expand(&folded), SAMPLE);
+
+        // Fold the enclosing node too, then unfold everything
+        let edit = folds.fold_at(&folded, 3).unwrap();
+        let folded_twice = apply(&folded, &edit);
+        assert_eq!(node_ranges(&folded_twice).len(), 2);
+        assert_eq!(folds.expand(&folded_twice), SAMPLE);
+
+        let edit = folds.unfold_all(&folded_twice).unwrap();
+        assert_eq!(apply(&folded_twice, &edit), SAMPLE);
+        assert!(folds.unfold_all(SAMPLE).is_none());
+    }
+
+    #[test]
+    fn test_unfold_at_marker_line() {
+        let mut folds = FoldStore::default();
+        let folded = apply(SAMPLE, &folds.fold_at(SAMPLE, 5).unwrap());
+        assert!(folds.unfold_at(&folded, 5).is_none());
+        let edit = folds.unfold_at(&folded, 6).unwrap();
+        assert_eq!(apply(&folded, &edit), SAMPLE);
+    }
+}
@@ -4,26 +4,44 @@
 //! (the same component that powers Lapce).
 
 mod components;
+mod diff;
 mod formatting;
+mod loca_table;
+mod lsx;
 mod operations;
+mod project_search;
 mod search;
+mod stats;
 mod syntax;
+mod tree;
 
 use floem::event::{Event, EventListener};
 use floem::prelude::*;
 use floem::style::Position;
 use std::path::Path;
 
-use crate::gui::state::{AppState, EditorTab, EditorTabsState};
+use crate::gui::shared::{colors, recent_list};
+use crate::gui::state::{AppState, ConfigState, EditorTab, EditorTabsState};
 use crate::gui::utils::meta_dialog::meta_dialog;
-use components::{editor_content, editor_status_bar, editor_toolbar, search_panel};
+use components::{
+    diff_view, editor_content, editor_status_bar, editor_toolbar, loca_view, project_search_panel,
+    restore_dialog, search_panel, tree_view,
+};
+use loca_table::is_loca_format;
 
 // Re-export for external use
+pub use operations::check_external_changes;
+pub use operations::format_document;
 pub use operations::init_config_state;
 pub use operations::load_file_in_tab;
 pub use operations::open_file_at_path;
 pub use operations::open_file_dialog;
+pub use operations::open_pak_entry;
+pub use operations::restore_session;
 pub use operations::save_file;
+pub use operations::start_autosave;
+pub use operations::start_recovery_autosave;
+pub use operations::track_recent_pak;
 
 /// File extensions that can be opened in the editor
 fn is_editable_file(path: &Path) -> bool {
@@ -50,15 +68,21 @@ fn is_editable_file(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-pub fn editor_tab(_app_state: AppState, tabs_state: EditorTabsState) -> impl IntoView {
+pub fn editor_tab(
+    _app_state: AppState,
+    tabs_state: EditorTabsState,
+    config_state: ConfigState,
+) -> impl IntoView {
     let tabs_state_toolbar = tabs_state.clone();
     let tabs_state_content = tabs_state.clone();
     let tabs_state_status = tabs_state.clone();
     let tabs_state_drop = tabs_state.clone();
     let tabs_state_dialog = tabs_state.clone();
     let tabs_state_overlay = tabs_state.clone();
+    let tabs_state_recent = tabs_state.clone();
     let _tabs_state_keyboard = tabs_state.clone();
     let show_line_numbers = tabs_state.show_line_numbers;
+    let diff = tabs_state.diff;
 
     // Callback for meta dialog - creates a new tab with the generated content
     let on_meta_create = move |content: String| {
@@ -69,8 +93,8 @@ pub fn editor_tab(_app_state: AppState, tabs_state: EditorTabsState) -> impl Int
         tab.modified.set(true);
     };
 
-    // Main content
-    let main_content = v_stack((
+    // Main content, with the find in files panel on the left
+    let editor_column = v_stack((
         file_tab_bar(tabs_state.clone()),
         editor_toolbar(tabs_state_toolbar),
         dyn_container(
@@ -80,10 +104,31 @@ pub fn editor_tab(_app_state: AppState, tabs_state: EditorTabsState) -> impl Int
             },
             move |maybe_tab| {
                 if let Some(tab) = maybe_tab {
-                    v_stack((
-                        search_panel(tab.clone()),
-                        editor_content(tab.clone(), tabs_state_content.clone(), show_line_numbers),
-                    ))
+                    let structured = tab.structured_view;
+                    let tabs_state = tabs_state_content.clone();
+                    dyn_container(
+                        move || structured.get(),
+                        move |show_structured| {
+                            if show_structured {
+                                if is_loca_format(&tab.file_format.get_untracked()) {
+                                    loca_view(tab.clone()).into_any()
+                                } else {
+                                    tree_view(tab.clone()).into_any()
+                                }
+                            } else {
+                                v_stack((
+                                    search_panel(tab.clone(), tabs_state.clone()),
+                                    editor_content(
+                                        tab.clone(),
+                                        tabs_state.clone(),
+                                        show_line_numbers,
+                                    ),
+                                ))
+                                .style(|s| s.size_full())
+                                .into_any()
+                            }
+                        },
+                    )
                     .style(|s| {
                         s.width_full()
                             .min_width(0.0)
@@ -93,11 +138,20 @@ pub fn editor_tab(_app_state: AppState, tabs_state: EditorTabsState) -> impl Int
                     })
                     .into_any()
                 } else {
-                    // Empty state with drop hint
+                    // Empty state with drop hint and recent files
+                    let tabs_state = tabs_state_recent.clone();
+                    let config_pin = config_state.clone();
                     v_stack((
                         label(|| "📄").style(|s| s.font_size(48.0)),
                         label(|| "Drop files here to open")
-                            .style(|s| s.font_size(14.0).color(Color::rgb8(150, 150, 150))),
+                            .style(|s| s.font_size(14.0).color(colors().text_muted)),
+                        recent_list(
+                            "Recent Files",
+                            config_state.pinned_files,
+                            config_state.recent_files,
+                            move |path| open_file_at_path(tabs_state.clone(), &path),
+                            move |path| config_pin.toggle_pinned_file(&path),
+                        ),
                     ))
                     .style(|s| {
                         s.flex_grow(1.0)
@@ -111,13 +165,18 @@ pub fn editor_tab(_app_state: AppState, tabs_state: EditorTabsState) -> impl Int
                 }
             },
         )
-        .style(|s| {
+        .style(move |s| {
+            // The diff view takes the editor's place while it is open
+            if diff.with(Option::is_some) {
+                return s.display(floem::style::Display::None);
+            }
             s.width_full()
                 .min_width(0.0)
                 .flex_grow(1.0)
                 .flex_basis(0.0)
                 .min_height(0.0)
         }),
+        diff_view(tabs_state.clone()),
         editor_status_bar(tabs_state_status),
         // Dialog overlay - uses shared meta_dialog from utils
         meta_dialog(
@@ -126,8 +185,11 @@ pub fn editor_tab(_app_state: AppState, tabs_state: EditorTabsState) -> impl Int
             on_meta_create,
             Some(tabs_state.status_message),
         ),
+        restore_dialog(tabs_state.clone()),
     ))
-    .style(|s| s.width_full().height_full());
+    .style(|s| s.flex_grow(1.0).min_width(0.0).height_full());
+    let main_content = h_stack((project_search_panel(tabs_state.clone()), editor_column))
+        .style(|s| s.width_full().height_full());
 
     // Stack main content with loading overlay
     (main_content, loading_overlay(tabs_state_overlay))
@@ -164,21 +226,21 @@ fn loading_overlay(tabs_state: EditorTabsState) -> impl IntoView {
                         container(container(empty()).style(|s| {
                             s.height_full()
                                 .width_pct(30.0)
-                                .background(Color::rgb8(76, 175, 80))
+                                .background(colors().success)
                                 .border_radius(4.0)
                         }))
                         .style(|s| {
                             s.width_full()
                                 .height(8.0)
-                                .background(Color::rgb8(220, 220, 220))
+                                .background(colors().bg_selected)
                                 .border_radius(4.0)
                         }),
                     ))
                     .style(|s| {
                         s.padding(24.0)
-                            .background(Color::WHITE)
+                            .background(colors().bg_base)
                             .border(1.0)
-                            .border_color(Color::rgb8(200, 200, 200))
+                            .border_color(colors().border_strong)
                             .border_radius(8.0)
                             .width(400.0)
                     }),
@@ -243,8 +305,8 @@ fn file_tab_bar(tabs_state: EditorTabsState) -> impl IntoView {
                     .font_size(14.0)
                     .border_radius(4.0)
                     .background(Color::TRANSPARENT)
-                    .color(Color::rgb8(100, 100, 100))
-                    .hover(|s| s.background(Color::rgb8(230, 230, 230)))
+                    .color(colors().text_secondary)
+                    .hover(|s| s.background(colors().bg_hover))
             })
             .action(move || {
                 tabs_state_for_new.new_tab();
@@ -259,9 +321,9 @@ fn file_tab_bar(tabs_state: EditorTabsState) -> impl IntoView {
             .padding_vert(4.0)
             .gap(4.0)
             .items_center()
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -274,6 +336,7 @@ fn file_tab_button(
 ) -> impl IntoView {
     let tab_for_name = tab.clone();
     let modified = tab.modified;
+    let recovered = tab.recovered;
     let file_path = tab.file_path;
     let tabs_state_switch = tabs_state.clone();
     let tabs_state_close = tabs_state.clone();
@@ -283,7 +346,9 @@ fn file_tab_button(
         // Tab label with modified indicator
         label(move || {
             let name = tab_for_name.display_name();
-            if modified.get() {
+            if recovered.get() {
+                format!("● {} (recovered)", name)
+            } else if modified.get() {
                 format!("● {}", name)
             } else {
                 name
@@ -296,12 +361,12 @@ fn file_tab_button(
                 s.color(if is_active {
                     Color::rgb8(255, 220, 150)
                 } else {
-                    Color::rgb8(255, 152, 0)
+                    colors().warning
                 })
             } else if is_active {
-                s.color(Color::WHITE)
+                s.color(colors().text_on_accent)
             } else {
-                s.color(Color::rgb8(60, 60, 60))
+                s.color(colors().text_primary)
             }
         }),
         // Close button
@@ -315,18 +380,18 @@ fn file_tab_button(
                     .color(if is_active {
                         Color::rgba8(255, 255, 255, 180)
                     } else {
-                        Color::rgb8(150, 150, 150)
+                        colors().text_muted
                     })
                     .hover(|s| {
                         s.background(if is_active {
                             Color::rgba8(255, 255, 255, 50)
                         } else {
-                            Color::rgb8(200, 200, 200)
+                            colors().bg_selected
                         })
                         .color(if is_active {
-                            Color::WHITE
+                            colors().text_on_accent
                         } else {
-                            Color::rgb8(80, 80, 80)
+                            colors().text_secondary
                         })
                     })
                     .cursor(floem::style::CursorStyle::Pointer)
@@ -346,10 +411,10 @@ fn file_tab_button(
             .cursor(floem::style::CursorStyle::Pointer);
 
         if is_active {
-            s.background(Color::rgb8(33, 150, 243))
+            s.background(colors().accent)
         } else {
-            s.background(Color::rgb8(240, 240, 240))
-                .hover(|s| s.background(Color::rgb8(225, 225, 225)))
+            s.background(colors().bg_elevated)
+                .hover(|s| s.background(colors().bg_selected))
         }
     })
     .on_click_stop(move |_| {
@@ -16,3 +16,10 @@ pub fn track_recent_file(path: &str) {
         config.add_recent_file(path);
     }
 }
+
+/// Add a PAK to recent PAKs (if config state is available)
+pub fn track_recent_pak(path: &str) {
+    if let Some(config) = CONFIG_STATE.get() {
+        config.add_recent_pak(path);
+    }
+}
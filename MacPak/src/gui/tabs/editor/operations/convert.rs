@@ -1,6 +1,8 @@
-//! Content validation and file format conversion
+//! Content validation, formatting and file format conversion
 
 use floem::prelude::*;
+use maclarian::Error;
+use maclarian::converter::{PrettyOptions, pretty_print_xml};
 
 use crate::gui::state::EditorTab;
 
@@ -31,6 +33,48 @@ pub fn validate_content(tab: EditorTab, status_message: RwSignal<String>) {
     }
 }
 
+/// Whether "Format Document" applies to a tab's format (shown as XML)
+pub fn can_format(format: &str) -> bool {
+    matches!(
+        format.to_uppercase().as_str(),
+        "LSX" | "LSF" | "LSFX" | "LSBC" | "LSBS" | "LOCA" | "XML"
+    )
+}
+
+/// Re-indent the tab's XML as one undoable edit. On a parse error the text
+/// is left alone and the cursor moves to the offending line.
+pub fn format_document(tab: EditorTab, options: PrettyOptions, status_message: RwSignal<String>) {
+    if !can_format(&tab.file_format.get_untracked()) {
+        status_message.set("Format Document is only available for LSX and XML".to_string());
+        return;
+    }
+
+    let content = tab.live_content.get_untracked();
+    if content.is_empty() {
+        status_message.set("No content to format".to_string());
+        return;
+    }
+
+    match pretty_print_xml(&content, &options) {
+        Ok(formatted) if formatted == content => {
+            status_message.set("Document is already formatted".to_string());
+        }
+        Ok(formatted) => {
+            tab.replace_text(formatted);
+            status_message.set("Formatted document".to_string());
+        }
+        Err(e) => {
+            status_message.set(format!("Cannot format: {}", e));
+            // Line numbers only match the view while nothing is folded
+            if let (Error::XmlSyntax { line, .. }, Some(editor)) = (e.root(), tab.editor()) {
+                if editor.doc.text().to_string() == content {
+                    editor.go_to_line(line.saturating_sub(1));
+                }
+            }
+        }
+    }
+}
+
 pub fn convert_file(tab: EditorTab, target_format: &str) {
     use floem::action::exec_after;
     use std::time::Duration;
@@ -0,0 +1,197 @@
+//! Start comparisons and merge hunks between the two sides
+
+use std::path::{Path, PathBuf};
+
+use floem::ext_event::create_ext_action;
+use floem::prelude::*;
+use floem_reactive::Scope;
+use maclarian::pak::VfsView;
+
+use crate::gui::state::{DiffSession, DiffSide, EditorTab, EditorTabsState};
+
+use super::super::diff::{Side, TextDiff, copy_hunk};
+use super::pak::{decode_entry, display_text};
+
+/// Top-level folders of a game data tree, where PAK paths start
+const DATA_ROOTS: &[&str] = &["Mods", "Public", "Localization", "Generated"];
+
+/// The active tab's text as the left side of a comparison
+fn tab_side(tab: &EditorTab) -> DiffSide {
+    DiffSide {
+        title: tab.display_name(),
+        text: tab.live_content.get_untracked(),
+        tab_id: Some(tab.id),
+    }
+}
+
+/// Compare the active tab with another open tab
+pub fn compare_with_tab(tabs_state: EditorTabsState, other: EditorTab) {
+    let Some(tab) = tabs_state.active_tab() else {
+        return;
+    };
+    tabs_state.open_diff(DiffSession {
+        left: tab_side(&tab),
+        right: tab_side(&other),
+    });
+}
+
+/// Compare the active tab's unsaved text with the file on disk
+pub fn compare_with_saved(tabs_state: EditorTabsState) {
+    if let Some(tab) = tabs_state.active_tab() {
+        compare_tab_with_saved(tabs_state, &tab);
+    }
+}
+
+/// Compare a tab's unsaved text with its file on disk
+pub(super) fn compare_tab_with_saved(tabs_state: EditorTabsState, tab: &EditorTab) {
+    let Some(path) = tab.file_path.get_untracked() else {
+        return;
+    };
+
+    let left = tab_side(tab);
+    let status = tabs_state.status_message;
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<String, String>| match result {
+            Ok(text) => tabs_state.open_diff(DiffSession {
+                left,
+                right: DiffSide {
+                    title: "Saved".to_string(),
+                    text,
+                    tab_id: None,
+                },
+            }),
+            Err(e) => status.set(format!("Compare failed: {}", e)),
+        },
+    );
+
+    rayon::spawn(move || {
+        let result = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| decode_entry(&path, data))
+            .map(display_text);
+        send(result);
+    });
+}
+
+/// Compare the active tab with the same file as the game would load it
+/// from a set of PAKs, picked in load order
+pub fn compare_with_pak(tabs_state: EditorTabsState) {
+    let Some(tab) = tabs_state.active_tab() else {
+        return;
+    };
+    let status = tabs_state.status_message;
+    let Some(internal_path) = tab_internal_path(&tab) else {
+        status.set("Compare failed: the file is not inside a mod or data folder".to_string());
+        return;
+    };
+    let Some(paks) = rfd::FileDialog::new()
+        .set_title("Select PAKs (later ones override earlier ones)")
+        .add_filter("PAK Files", &["pak"])
+        .pick_files()
+    else {
+        return;
+    };
+
+    let left = tab_side(&tab);
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<(String, String), String>| match result {
+            Ok((title, text)) => tabs_state.open_diff(DiffSession {
+                left,
+                right: DiffSide {
+                    title,
+                    text,
+                    tab_id: None,
+                },
+            }),
+            Err(e) => status.set(format!("Compare failed: {}", e)),
+        },
+    );
+
+    rayon::spawn(move || {
+        send(read_from_paks(&paks, &internal_path));
+    });
+}
+
+/// Read the effective copy of `internal_path` (or its binary/text twin)
+fn read_from_paks(paks: &[PathBuf], internal_path: &str) -> Result<(String, String), String> {
+    let vfs = VfsView::new(paks).map_err(|e| e.to_string())?;
+    let path = path_variants(internal_path)
+        .into_iter()
+        .find(|path| vfs.exists(path))
+        .ok_or_else(|| format!("{} is not in the selected PAKs", internal_path))?;
+    let data = vfs.read(&path).map_err(|e| e.to_string())?;
+
+    let pak_name = vfs
+        .source_of(&path)
+        .and_then(Path::file_name)
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let text = display_text(decode_entry(&path, data)?);
+    Ok((format!("{}: {}", pak_name, path), text))
+}
+
+/// The path and the paths of the same file in its other editable form
+/// (a mod's `.lsx` usually ships as `.lsf`)
+fn path_variants(internal_path: &str) -> Vec<String> {
+    let mut paths = vec![internal_path.to_string()];
+    if let Some((stem, ext)) = internal_path.rsplit_once('.') {
+        let twins: &[&str] = match ext.to_lowercase().as_str() {
+            "lsx" => &["lsf"],
+            "lsf" => &["lsx"],
+            "xml" => &["loca"],
+            "loca" => &["xml"],
+            _ => &[],
+        };
+        paths.extend(twins.iter().map(|twin| format!("{}.{}", stem, twin)));
+    }
+    paths
+}
+
+/// Path of a tab's file inside a PAK: its PAK entry, or the part of its
+/// disk path from the first data folder on
+fn tab_internal_path(tab: &EditorTab) -> Option<String> {
+    if let Some(source) = tab.pak_source.get_untracked() {
+        return Some(source.internal_path);
+    }
+    let path = tab.file_path.get_untracked()?;
+    let components: Vec<String> = Path::new(&path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let start = components
+        .iter()
+        .position(|c| DATA_ROOTS.iter().any(|root| c.eq_ignore_ascii_case(root)))?;
+    Some(components[start..].join("/"))
+}
+
+/// Copy the current hunk into the tab on side `to`
+pub fn merge_hunk(tabs_state: EditorTabsState, to: Side) {
+    let Some(mut session) = tabs_state.diff.get_untracked() else {
+        return;
+    };
+    let diff = TextDiff::new(&session.left.text, &session.right.text);
+    let index = tabs_state.diff_hunk.get_untracked();
+    let Some(hunk) = diff.hunks.get(index) else {
+        return;
+    };
+
+    let text = copy_hunk(&session.left.text, &session.right.text, hunk, to);
+    let side = match to {
+        Side::Left => &mut session.left,
+        Side::Right => &mut session.right,
+    };
+    let Some(tab) = side.tab_id.and_then(|id| tabs_state.tab_by_id(id)) else {
+        return;
+    };
+    tab.replace_text(text.clone());
+    side.text = text;
+
+    // The merged hunk is gone, so the same index is now the next one
+    let remaining = diff.hunks.len() - 1;
+    tabs_state
+        .diff_hunk
+        .set(index.min(remaining.saturating_sub(1)));
+    tabs_state.diff.set(Some(session));
+}
@@ -0,0 +1,131 @@
+//! Notice files that other programs changed under open tabs
+
+use std::path::Path;
+
+use floem::prelude::*;
+
+use crate::gui::state::{DiskChange, EditorTab, EditorTabsState, RealDisk};
+
+use super::diff::compare_tab_with_saved;
+use super::open::load_file;
+use super::save::save_file;
+
+/// What to do with a tab whose file another program changed
+enum ExternalChoice {
+    /// Discard the tab's edits and load the file again
+    Reload,
+    /// Save the tab over the other program's version
+    Overwrite,
+    /// Show the tab and the file side by side
+    Compare,
+    /// Dialog dismissed
+    Cancel,
+}
+
+/// Ask what to do about a changed file; `saving` when asked before a save
+fn ask_about_change(name: &str, saving: bool) -> ExternalChoice {
+    let overwrite = if saving {
+        "Overwrite saves your version anyway"
+    } else {
+        "Overwrite saves your version over it"
+    };
+    let result = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("File Changed on Disk")
+        .set_description(&format!(
+            "'{}' was changed by another program since it was opened here.\n\n\
+            Reload discards your unsaved edits, {}, Compare shows both side by side.",
+            name, overwrite
+        ))
+        .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+            "Reload".to_string(),
+            "Overwrite".to_string(),
+            "Compare".to_string(),
+        ))
+        .show();
+
+    match result {
+        rfd::MessageDialogResult::Custom(ref s) if s == "Reload" => ExternalChoice::Reload,
+        rfd::MessageDialogResult::Custom(ref s) if s == "Overwrite" => ExternalChoice::Overwrite,
+        rfd::MessageDialogResult::Custom(ref s) if s == "Compare" => ExternalChoice::Compare,
+        _ => ExternalChoice::Cancel,
+    }
+}
+
+/// Load a tab's file again, dropping its unsaved text
+fn reload_tab(tab: EditorTab) {
+    if let Some(path) = tab.file_path.get_untracked() {
+        tab.discard_recovery();
+        load_file(Path::new(&path), tab);
+    }
+}
+
+/// Make `tab` the active tab and compare it with its file
+fn show_comparison(tabs_state: EditorTabsState, tab: &EditorTab) {
+    if let Some(index) = tabs_state
+        .tabs
+        .get_untracked()
+        .iter()
+        .position(|t| t.id == tab.id)
+    {
+        tabs_state.active_tab_index.set(index);
+    }
+    compare_tab_with_saved(tabs_state, tab);
+}
+
+/// Check every open tab against its file, e.g. when the window regains focus
+///
+/// Tabs without unsaved edits are reloaded quietly; the others ask whether
+/// to reload, overwrite or compare. Either way the new version on disk
+/// becomes the tab's baseline, so the same change isn't reported twice.
+pub fn check_external_changes(tabs_state: EditorTabsState) {
+    let status = tabs_state.status_message;
+    for (tab, change) in tabs_state.changed_on_disk(&RealDisk) {
+        let name = tab.display_name();
+        match change {
+            DiskChange::Modified(stamp) if tab.modified.get_untracked() => {
+                let choice = ask_about_change(&name, false);
+                tab.disk_stamp.set(Some(stamp));
+                match choice {
+                    ExternalChoice::Reload => reload_tab(tab),
+                    ExternalChoice::Overwrite => save_file(tabs_state.clone(), tab),
+                    ExternalChoice::Compare => show_comparison(tabs_state.clone(), &tab),
+                    ExternalChoice::Cancel => {}
+                }
+            }
+            DiskChange::Modified(stamp) => {
+                tab.disk_stamp.set(Some(stamp));
+                reload_tab(tab);
+                status.set(format!("Reloaded {} (changed on disk)", name));
+            }
+            DiskChange::Deleted => {
+                tab.disk_stamp.set(None);
+                tab.modified.set(true);
+                status.set(format!("{} was deleted on disk; save to recreate it", name));
+            }
+            DiskChange::Unchanged | DiskChange::Touched(_) => {}
+        }
+    }
+}
+
+/// Before saving over `tab`'s file, ask what to do if another program
+/// changed it. Returns whether to go ahead with the save.
+pub(super) fn confirm_save(tabs_state: &EditorTabsState, tab: &EditorTab) -> bool {
+    let DiskChange::Modified(stamp) = tab.disk_change(&RealDisk) else {
+        return true;
+    };
+    match ask_about_change(&tab.display_name(), true) {
+        ExternalChoice::Overwrite => true,
+        ExternalChoice::Reload => {
+            tab.disk_stamp.set(Some(stamp));
+            reload_tab(tab.clone());
+            false
+        }
+        ExternalChoice::Compare => {
+            tab.disk_stamp.set(Some(stamp));
+            show_comparison(tabs_state.clone(), tab);
+            false
+        }
+        ExternalChoice::Cancel => false,
+    }
+}
@@ -399,10 +399,11 @@ pub fn finalize_file_load(tab: EditorTab, result: FileLoadResult) {
     // Populate the tab
     tab.file_format.set(result.format);
     tab.file_path.set(Some(result.path_str));
-    tab.content.set(content.clone());
-    tab.live_content.set(content);
+    // Bumps the editor revision, so reloading a file rebuilds the view
+    tab.set_text(content);
     tab.modified.set(false);
     tab.converted_from_lsf.set(result.converted_from_binary);
+    tab.record_disk_stamp();
 }
 
 /// Handle phase 1 result on the main thread
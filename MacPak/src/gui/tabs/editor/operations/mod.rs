@@ -3,12 +3,22 @@
 mod config;
 mod convert;
 mod dialogs;
+mod diff;
+mod external;
 mod loading;
 mod open;
+mod pak;
 mod save;
+mod session;
 mod types;
 
-pub use config::init_config_state;
-pub use convert::{convert_file, validate_content};
-pub use open::{load_file_in_tab, open_file_at_path, open_file_dialog};
+pub use config::{init_config_state, track_recent_pak};
+pub use convert::{can_format, convert_file, format_document, validate_content};
+pub use diff::{compare_with_pak, compare_with_saved, compare_with_tab, merge_hunk};
+pub use external::check_external_changes;
+pub use open::{
+    load_file, load_file_in_tab, open_file_at_line, open_file_at_path, open_file_dialog,
+};
+pub use pak::open_pak_entry;
 pub use save::{save_file, save_file_as_dialog};
+pub use session::{restore_session, start_autosave, start_recovery_autosave};
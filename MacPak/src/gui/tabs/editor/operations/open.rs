@@ -91,6 +91,40 @@ pub fn open_file_at_path(tabs_state: EditorTabsState, path: &str) {
     load_file(&path_buf, tab);
 }
 
+/// Open a file with the cursor on a 0-based line (used by find in files)
+///
+/// The line is applied by the editor view once the file has loaded.
+pub fn open_file_at_line(tabs_state: EditorTabsState, path: &str, line: usize) {
+    let open_tab = tabs_state
+        .tabs
+        .get_untracked()
+        .into_iter()
+        .find(|tab| tab.file_path.get_untracked().as_deref() == Some(path));
+    if let Some(tab) = open_tab {
+        // Already showing the file: move the live view
+        let is_active = tabs_state
+            .active_tab()
+            .is_some_and(|active| active.id == tab.id);
+        if let (true, Some(editor)) = (is_active, tab.editor()) {
+            editor.go_to_line(line);
+            return;
+        }
+        tab.restore_line.set(Some(line));
+        tabs_state.switch_to_file(path);
+        return;
+    }
+
+    if !Path::new(path).exists() {
+        // Shows the "File Not Found" message
+        open_file_at_path(tabs_state, path);
+        return;
+    }
+    open_file_at_path(tabs_state.clone(), path);
+    if let Some(tab) = tabs_state.active_tab() {
+        tab.restore_line.set(Some(line));
+    }
+}
+
 /// Load a file into a specific tab (used by browser and other components)
 pub fn load_file_in_tab(path: &Path, tabs_state: EditorTabsState) {
     let path_str = path.to_string_lossy().to_string();
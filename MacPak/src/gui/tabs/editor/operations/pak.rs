@@ -0,0 +1,152 @@
+//! Open files from inside a PAK without extracting them
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use floem::ext_event::create_ext_action;
+use floem::prelude::*;
+use floem_reactive::Scope;
+use maclarian::pak::PakReaderCache;
+
+use crate::gui::state::{EditorTab, EditorTabsState, PakSource};
+use crate::gui::utils::show_file_error;
+
+use super::super::formatting::{format_json, format_xml};
+
+/// Text form of a PAK entry, ready for the editor
+pub(super) struct PakEntryContent {
+    pub format: String,
+    pub content: String,
+    pub converted_from_binary: bool,
+}
+
+/// Read a PAK entry through the cache and open it in an editor tab.
+///
+/// Binary formats (LSF, LOCA) are converted to their text form in memory.
+/// The tab has no file path; saving it prompts for an export location.
+pub fn open_pak_entry(
+    source: PakSource,
+    cache: Arc<Mutex<PakReaderCache>>,
+    tabs_state: EditorTabsState,
+) {
+    if tabs_state.switch_to_pak_entry(&source) {
+        return;
+    }
+
+    // Reuse the current tab if it's empty, otherwise create a new one
+    let tab = match tabs_state.active_tab() {
+        Some(active)
+            if active.file_path.get().is_none()
+                && active.pak_source.get().is_none()
+                && active.content.get().is_empty()
+                && !active.modified.get() =>
+        {
+            active
+        }
+        _ => tabs_state.new_tab(),
+    };
+
+    let filename = entry_name(&source.internal_path).to_string();
+    tab.pak_source.set(Some(source.clone()));
+    tab.is_loading.set(true);
+    tab.loading_message.set(format!("Reading {}...", filename));
+
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<PakEntryContent, String>| {
+            finalize_pak_load(tab, &filename, result);
+        },
+    );
+
+    rayon::spawn(move || {
+        send(read_pak_entry(&source, &cache));
+    });
+}
+
+/// Read and convert a PAK entry (runs in background thread)
+fn read_pak_entry(
+    source: &PakSource,
+    cache: &Mutex<PakReaderCache>,
+) -> Result<PakEntryContent, String> {
+    let data = {
+        let mut cache = cache
+            .lock()
+            .map_err(|_| "PAK cache is unavailable".to_string())?;
+        cache
+            .read_file(Path::new(&source.pak_path), &source.internal_path)
+            .map_err(|e| e.to_string())?
+    };
+    decode_entry(&source.internal_path, data)
+}
+
+/// Convert a file's bytes to the text the editor shows for it
+pub(super) fn decode_entry(path: &str, data: Vec<u8>) -> Result<PakEntryContent, String> {
+    let ext = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    let (target, converted_from_binary) = match ext.as_str() {
+        "lsf" | "lsfx" | "lsbc" | "lsbs" | "lsb" => (Some("lsx"), true),
+        "loca" => (Some("xml"), true),
+        _ => (None, false),
+    };
+
+    let content = match target {
+        Some(target) => maclarian::converter::convert_bytes(&data, &ext, target)
+            .map_err(|e| format!("Failed to convert {}: {}", ext.to_uppercase(), e))?,
+        None => data,
+    };
+
+    Ok(PakEntryContent {
+        format: ext.to_uppercase(),
+        content: String::from_utf8_lossy(&content).into_owned(),
+        converted_from_binary,
+    })
+}
+
+/// Populate the tab once the entry has been read (main thread)
+fn finalize_pak_load(tab: EditorTab, filename: &str, result: Result<PakEntryContent, String>) {
+    tab.is_loading.set(false);
+    tab.loading_message.set(String::new());
+
+    let entry = match result {
+        Ok(entry) => entry,
+        Err(e) => {
+            tab.pak_source.set(None);
+            show_file_error(Path::new(filename), "Opening", &e);
+            return;
+        }
+    };
+
+    let format = entry.format.clone();
+    let converted_from_binary = entry.converted_from_binary;
+    let content = display_text(entry);
+
+    tab.file_format.set(format);
+    tab.file_path.set(None);
+    tab.disk_stamp.set(None);
+    tab.content.set(content.clone());
+    tab.live_content.set(content);
+    tab.modified.set(false);
+    tab.converted_from_lsf.set(converted_from_binary);
+}
+
+/// Format decoded text the way the editor shows it
+pub(super) fn display_text(entry: PakEntryContent) -> String {
+    // Skip very large files (>500KB) to keep the editor responsive
+    if entry.content.len() > 500_000 {
+        return entry.content;
+    }
+    match entry.format.as_str() {
+        "LSX" | "LSF" | "LSFX" | "LSBC" | "LSBS" | "LOCA" => format_xml(&entry.content),
+        "LSJ" => format_json(&entry.content),
+        _ => entry.content,
+    }
+}
+
+/// File name part of a `/`-separated PAK path
+pub fn entry_name(internal_path: &str) -> &str {
+    internal_path.rsplit('/').next().unwrap_or(internal_path)
+}
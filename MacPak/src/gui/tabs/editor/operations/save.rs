@@ -4,50 +4,101 @@ use std::fs;
 use std::path::Path;
 
 use floem::prelude::*;
+use maclarian::formats::stats::lint_stats_str;
 
-use crate::gui::state::EditorTab;
+use crate::gui::state::{EditorTab, EditorTabsState};
 use crate::gui::utils::show_file_error;
+use crate::workspace::backup::BackupStore;
 
-pub fn save_file(tab: EditorTab) {
-    if let Some(path_str) = tab.file_path.get() {
-        let path = Path::new(&path_str);
-        // Use live_content which is synced from the editor
-        let content = tab.live_content.get();
-        let format = tab.file_format.get().to_uppercase();
-        let converted_from_binary = tab.converted_from_lsf.get();
-
-        let result =
-            if (format == "LSF" || format == "LSFX") && converted_from_binary {
-                // Convert XML back to LSF binary
-                match maclarian::converter::from_lsx(&content) {
-                    Ok(lsf_doc) => maclarian::formats::lsf::write_lsf(&lsf_doc, path)
-                        .map_err(|e| e.to_string()),
-                    Err(e) => Err(format!("Failed to parse LSX: {}", e)),
-                }
-            } else if format == "LOCA" && converted_from_binary {
-                // Convert XML back to LOCA binary
-                match maclarian::converter::loca_from_xml(&content) {
-                    Ok(resource) => maclarian::formats::loca::write_loca(path, &resource)
-                        .map_err(|e| e.to_string()),
-                    Err(e) => Err(format!("Failed to parse LOCA XML: {}", e)),
-                }
-            } else {
-                // Write as plain text
-                fs::write(path, &content).map_err(|e| e.to_string())
-            };
+use super::super::stats::is_stats_path;
+use super::external::confirm_save;
+use super::pak::entry_name;
 
-        match result {
-            Ok(_) => {
-                tab.modified.set(false);
+/// Back up the file about to be overwritten, keeping `backup_count` versions
+///
+/// Files outside a workspace are saved without a backup.
+fn backup_before_save(path: &Path, backup_count: usize) -> Result<(), String> {
+    match BackupStore::for_file(path, backup_count) {
+        Some(store) => store.backup(path).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+/// Lint stats files as they are saved; the issues are listed under the editor
+fn lint_saved_stats(tab: &EditorTab, content: &str) {
+    if tab.source_path().is_some_and(|path| is_stats_path(&path)) {
+        tab.stats_issues.set(lint_stats_str(content));
+    }
+}
+
+/// Back up the tab's file and write the tab over it, converting back to
+/// LSF/LOCA if it was opened from one. Returns the saved text.
+///
+/// Errors carry the step that failed ("Backing up" or "Saving").
+pub(super) fn write_tab(
+    tab: &EditorTab,
+    path: &Path,
+    backup_count: usize,
+) -> Result<String, (&'static str, String)> {
+    backup_before_save(path, backup_count).map_err(|e| ("Backing up", e))?;
+    // Use live_content which is synced from the editor
+    let content = tab.live_content.get_untracked();
+    let format = tab.file_format.get_untracked().to_uppercase();
+    let converted_from_binary = tab.converted_from_lsf.get_untracked();
+
+    let result = if (format == "LSF" || format == "LSFX") && converted_from_binary {
+        // Convert XML back to LSF binary
+        match maclarian::converter::from_lsx(&content) {
+            Ok(lsf_doc) => {
+                maclarian::formats::lsf::write_lsf(&lsf_doc, path).map_err(|e| e.to_string())
             }
-            Err(e) => {
-                show_file_error(path, "Saving", &e);
+            Err(e) => Err(format!("Failed to parse LSX: {}", e)),
+        }
+    } else if format == "LOCA" && converted_from_binary {
+        // Convert XML back to LOCA binary
+        match maclarian::converter::loca_from_xml(&content) {
+            Ok(resource) => {
+                maclarian::formats::loca::write_loca(path, &resource).map_err(|e| e.to_string())
             }
+            Err(e) => Err(format!("Failed to parse LOCA XML: {}", e)),
+        }
+    } else {
+        // Write as plain text
+        fs::write(path, &content).map_err(|e| e.to_string())
+    };
+
+    result.map(|_| content).map_err(|e| ("Saving", e))
+}
+
+/// Mark a tab as saved with `content`
+pub(super) fn mark_saved(tab: &EditorTab, content: &str) {
+    tab.modified.set(false);
+    tab.discard_recovery();
+    tab.record_disk_stamp();
+    lint_saved_stats(tab, content);
+}
+
+pub fn save_file(tabs_state: EditorTabsState, tab: EditorTab) {
+    let backup_count = tabs_state.backup_count.get_untracked();
+    // Files opened from a PAK have no path on disk yet; ask where to export them
+    if tab.file_path.get().is_none() && tab.pak_source.get().is_some() {
+        save_file_as_dialog(tab, backup_count);
+        return;
+    }
+
+    if let Some(path_str) = tab.file_path.get() {
+        if !confirm_save(&tabs_state, &tab) {
+            return;
+        }
+        let path = Path::new(&path_str);
+        match write_tab(&tab, path, backup_count) {
+            Ok(content) => mark_saved(&tab, &content),
+            Err((operation, e)) => show_file_error(path, operation, &e),
         }
     }
 }
 
-pub fn save_file_as_dialog(tab: EditorTab) {
+pub fn save_file_as_dialog(tab: EditorTab, backup_count: usize) {
     let current_format = tab.file_format.get().to_uppercase();
 
     // Build dialog with filters based on current format
@@ -83,9 +134,16 @@ pub fn save_file_as_dialog(tab: EditorTab) {
         if let Some(parent) = Path::new(&current_path).parent() {
             dialog = dialog.set_directory(parent);
         }
+    } else if let Some(source) = tab.pak_source.get() {
+        dialog = dialog.set_file_name(entry_name(&source.internal_path));
     }
 
     if let Some(path) = dialog.save_file() {
+        if let Err(e) = backup_before_save(&path, backup_count) {
+            show_file_error(&path, "Backing up", &e);
+            return;
+        }
+
         let content = tab.content.get();
         let target_ext = path
             .extension()
@@ -121,11 +179,15 @@ pub fn save_file_as_dialog(tab: EditorTab) {
             Ok(_) => {
                 let path_str = path.to_string_lossy().to_string();
                 tab.file_path.set(Some(path_str));
+                tab.pak_source.set(None);
                 tab.modified.set(false);
+                tab.discard_recovery();
+                tab.record_disk_stamp();
                 // Mark as converted from binary even if saved to a binary format
                 tab.converted_from_lsf
                     .set(matches!(target_ext.as_str(), "LSF" | "LOCA"));
                 tab.file_format.set(target_ext);
+                lint_saved_stats(&tab, &content);
             }
             Err(e) => {
                 show_file_error(&path, "Saving", &e);
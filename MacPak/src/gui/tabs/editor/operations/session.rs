@@ -0,0 +1,126 @@
+//! Session restore and crash recovery for editor tabs
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use floem::action::exec_after;
+use floem::prelude::*;
+
+use crate::gui::state::{EditorTabsState, RealDisk};
+use crate::workbench::session::SessionStore;
+
+use super::open::load_file;
+use super::save::{mark_saved, write_tab};
+
+/// How often unsaved buffers are written to the session store
+const RECOVERY_INTERVAL: Duration = Duration::from_mins(2);
+
+/// How often autosave checks whether it was switched on
+const AUTOSAVE_POLL: Duration = Duration::from_mins(1);
+
+/// Reopen the last session's files, then the unsaved buffers from the
+/// session store as modified "(recovered)" tabs
+pub fn restore_session(tabs_state: EditorTabsState, open_files: &[String]) {
+    let recovered = SessionStore::default_location()
+        .map(|store| store.load_all())
+        .unwrap_or_default();
+    let recovered_paths: HashSet<&str> = recovered
+        .iter()
+        .filter_map(|buffer| buffer.original_path.as_deref())
+        .collect();
+
+    for path in open_files {
+        if recovered_paths.contains(path.as_str()) || !Path::new(path).is_file() {
+            continue;
+        }
+        if tabs_state.switch_to_file(path) {
+            continue;
+        }
+        let tab = match tabs_state.active_tab() {
+            Some(active)
+                if active.file_path.get().is_none()
+                    && active.content.get().is_empty()
+                    && !active.modified.get() =>
+            {
+                active
+            }
+            _ => tabs_state.new_tab(),
+        };
+        load_file(Path::new(path), tab);
+    }
+
+    let count = recovered.len();
+    for buffer in recovered {
+        tabs_state.open_recovered(buffer);
+    }
+    if count > 0 {
+        tabs_state.status_message.set(format!(
+            "Recovered {} unsaved {}",
+            count,
+            if count == 1 { "file" } else { "files" }
+        ));
+    }
+}
+
+/// Write unsaved buffers to the session store every [`RECOVERY_INTERVAL`],
+/// so they survive a crash
+pub fn start_recovery_autosave(tabs_state: EditorTabsState) {
+    exec_after(RECOVERY_INTERVAL, move |_| {
+        if let Err(e) = tabs_state.save_recovery() {
+            tracing::warn!("Failed to write recovery files: {}", e);
+        }
+        start_recovery_autosave(tabs_state);
+    });
+}
+
+/// Save modified tabs to their own files every `autosave_minutes` (off at 0)
+///
+/// Files changed on disk by another program are skipped until the user has
+/// decided what to do with them.
+pub fn start_autosave(tabs_state: EditorTabsState) {
+    let minutes = tabs_state.autosave_minutes.get_untracked();
+    let delay = if minutes == 0 {
+        AUTOSAVE_POLL
+    } else {
+        Duration::from_secs(u64::from(minutes) * 60)
+    };
+    exec_after(delay, move |_| {
+        if minutes > 0 && tabs_state.autosave_minutes.get_untracked() > 0 {
+            autosave(&tabs_state);
+        }
+        start_autosave(tabs_state);
+    });
+}
+
+/// Write every modified tab that can be saved without asking
+fn autosave(tabs_state: &EditorTabsState) {
+    let backup_count = tabs_state.backup_count.get_untracked();
+    let mut saved = 0;
+    for tab in tabs_state.autosave_candidates(&RealDisk) {
+        let Some(path) = tab.file_path.get_untracked() else {
+            continue;
+        };
+        match write_tab(&tab, Path::new(&path), backup_count) {
+            Ok(content) => {
+                mark_saved(&tab, &content);
+                saved += 1;
+            }
+            Err((operation, e)) => {
+                tracing::warn!(
+                    "Autosave of {} failed ({}): {}",
+                    path,
+                    operation.to_lowercase(),
+                    e
+                );
+            }
+        }
+    }
+    if saved > 0 {
+        tabs_state.status_message.set(format!(
+            "Autosaved {} {}",
+            saved,
+            if saved == 1 { "file" } else { "files" }
+        ));
+    }
+}
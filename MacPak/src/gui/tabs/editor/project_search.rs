@@ -0,0 +1,169 @@
+//! Find in files: runs a workspace search in the background and streams its
+//! results into the panel
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use floem::action::exec_after;
+use floem::prelude::*;
+use maclarian::utils::CancelToken;
+
+use crate::gui::state::{EditorTabsState, ProjectSearchState};
+use crate::index::FileIndex;
+use crate::index::search::{
+    FileMatches, ProjectSearchOptions, ProjectSearchSummary, search_workspace,
+};
+
+use super::operations::open_file_at_line;
+
+/// Matched lines listed per file; the rest are only counted
+const MAX_LINES_PER_FILE: usize = 200;
+
+/// How often results are moved from the worker into the panel
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Message from the search thread
+enum SearchEvent {
+    File(FileMatches),
+    Done(Result<ProjectSearchSummary, String>),
+}
+
+/// Start a search with the panel's query and options, replacing any running one
+pub fn run_project_search(search: ProjectSearchState) {
+    let query = search.query.get_untracked();
+    if query.is_empty() {
+        return;
+    }
+    let Some(root) = search.root.get_untracked() else {
+        search.status.set("Choose a folder to search".to_string());
+        return;
+    };
+
+    search.cancel.get_untracked().cancel();
+    let cancel = CancelToken::new();
+    search.cancel.set(cancel.clone());
+    search.generation.update(|generation| *generation += 1);
+    search.results.set(Vec::new());
+    search.collapsed.set(HashSet::new());
+    search.is_searching.set(true);
+    search.status.set("Searching...".to_string());
+
+    let options = ProjectSearchOptions {
+        case_sensitive: search.case_sensitive.get_untracked(),
+        include: split_globs(&search.include.get_untracked()),
+        exclude: split_globs(&search.exclude.get_untracked()),
+        convert_lsf: search.convert_lsf.get_untracked(),
+        max_matches_per_file: MAX_LINES_PER_FILE,
+    };
+
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let outcome = FileIndex::open(&root).and_then(|index| {
+            search_workspace(&index, &query, &options, &cancel, &|file| {
+                let _ = tx.send(SearchEvent::File(file));
+            })
+        });
+        let _ = tx.send(SearchEvent::Done(outcome.map_err(|e| e.to_string())));
+    });
+
+    let generation = search.generation.get_untracked();
+    poll_results(search, rx, generation);
+}
+
+/// Stop the running search; results found so far stay listed
+pub fn cancel_project_search(search: &ProjectSearchState) {
+    search.cancel.get_untracked().cancel();
+}
+
+/// Open a result in the editor at its (1-based) line
+pub fn open_result(
+    search: &ProjectSearchState,
+    tabs_state: EditorTabsState,
+    path: &str,
+    line: usize,
+) {
+    let Some(root) = search.root.get_untracked() else {
+        return;
+    };
+    let full_path = Path::new(&root).join(path);
+    open_file_at_line(
+        tabs_state,
+        &full_path.to_string_lossy(),
+        line.saturating_sub(1),
+    );
+}
+
+/// Move results from the search thread into the panel until it finishes or
+/// a newer search starts
+fn poll_results(search: ProjectSearchState, rx: Receiver<SearchEvent>, generation: u64) {
+    if search.generation.get_untracked() != generation {
+        return;
+    }
+
+    let mut found = Vec::new();
+    let mut done = None;
+    for event in rx.try_iter() {
+        match event {
+            SearchEvent::File(file) => found.push(file),
+            SearchEvent::Done(outcome) => done = Some(outcome),
+        }
+    }
+    if !found.is_empty() {
+        search.results.update(|results| results.extend(found));
+    }
+
+    match done {
+        Some(outcome) => finish(&search, outcome),
+        None => {
+            exec_after(POLL_INTERVAL, move |_| poll_results(search, rx, generation));
+        }
+    }
+}
+
+/// Sort the results and show the totals
+fn finish(search: &ProjectSearchState, outcome: Result<ProjectSearchSummary, String>) {
+    search.is_searching.set(false);
+    let summary = match outcome {
+        Ok(summary) => summary,
+        Err(e) => {
+            search.status.set(format!("Search failed: {e}"));
+            return;
+        }
+    };
+
+    search
+        .results
+        .update(|results| results.sort_by(|a, b| a.path.cmp(&b.path)));
+    let matches: usize = search
+        .results
+        .with_untracked(|results| results.iter().map(|file| file.total).sum());
+
+    let mut status = format!(
+        "{} matches in {} of {} files",
+        matches, summary.files_matched, summary.files_searched
+    );
+    if summary.files_skipped > 0 {
+        status.push_str(&format!(", {} binary skipped", summary.files_skipped));
+    }
+    if !summary.failed.is_empty() {
+        status.push_str(&format!(", {} unreadable", summary.failed.len()));
+        for (path, e) in &summary.failed {
+            tracing::warn!("Find in files: {}: {}", path, e);
+        }
+    }
+    if summary.cancelled {
+        status.push_str(" (cancelled)");
+    }
+    search.status.set(status);
+}
+
+/// Globs from a comma-separated list
+fn split_globs(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -1,10 +1,11 @@
 //! Find and Replace functionality
 
 use floem::prelude::*;
-use regex::RegexBuilder;
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
 use std::sync::{Arc, Mutex};
 
-use crate::gui::state::EditorState;
+use crate::gui::state::{EditorState, EditorTab, EditorTabsState};
 
 /// Search state for Find & Replace
 pub struct SearchState {
@@ -25,6 +26,53 @@ lazy_static::lazy_static! {
     pub static ref SEARCH_STATE: Arc<Mutex<SearchState>> = Arc::new(Mutex::new(SearchState::default()));
 }
 
+/// Build the search regex from the panel's options
+fn build_regex(
+    search_text: &str,
+    case_sensitive: bool,
+    whole_words: bool,
+    use_regex: bool,
+) -> Result<Regex, regex::Error> {
+    let pattern = if use_regex {
+        search_text.to_string()
+    } else {
+        regex::escape(search_text)
+    };
+
+    let pattern = if whole_words {
+        format!(r"\b{}\b", pattern)
+    } else {
+        pattern
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .build()
+}
+
+fn tab_regex(state: &EditorState) -> Result<Regex, regex::Error> {
+    build_regex(
+        &state.search_text.get(),
+        state.case_sensitive.get(),
+        state.whole_words.get(),
+        state.use_regex.get(),
+    )
+}
+
+/// Re-run the search after the tab's text changed
+fn search_again(state: &EditorState, content: String) {
+    perform_search(
+        content,
+        state.search_text.get(),
+        state.case_sensitive.get(),
+        state.whole_words.get(),
+        state.use_regex.get(),
+        state.match_count,
+        state.current_match,
+        state.search_status,
+    );
+}
+
 pub fn perform_search(
     content: String,
     search_text: String,
@@ -46,23 +94,7 @@ pub fn perform_search(
         return;
     }
 
-    // Build the regex pattern
-    let pattern = if use_regex {
-        search_text.clone()
-    } else {
-        regex::escape(&search_text)
-    };
-
-    let pattern = if whole_words {
-        format!(r"\b{}\b", pattern)
-    } else {
-        pattern
-    };
-
-    match RegexBuilder::new(&pattern)
-        .case_insensitive(!case_sensitive)
-        .build()
-    {
+    match build_regex(&search_text, case_sensitive, whole_words, use_regex) {
         Ok(regex) => {
             let matches: Vec<(usize, usize)> = regex
                 .find_iter(&content)
@@ -131,6 +163,70 @@ pub fn find_previous(state: EditorState) {
     }
 }
 
+/// Replace the matches in `text`, or only those inside `range` if given.
+///
+/// In regex mode (`expand`), `$1` and `${name}` in the replacement refer to
+/// capture groups; otherwise it is inserted as-is. Zero-length matches (`^`,
+/// `\b`) insert the replacement without consuming any text.
+/// Returns the new text and the number of replacements.
+pub fn replace_matches(
+    text: &str,
+    regex: &Regex,
+    replacement: &str,
+    expand: bool,
+    range: Option<Range<usize>>,
+) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+
+    for caps in regex.captures_iter(text) {
+        let Some(m) = caps.get(0) else {
+            continue;
+        };
+        if let Some(range) = &range {
+            if m.start() < range.start || m.end() > range.end {
+                continue;
+            }
+        }
+
+        out.push_str(&text[last..m.start()]);
+        if expand {
+            caps.expand(replacement, &mut out);
+        } else {
+            out.push_str(replacement);
+        }
+        last = m.end();
+        count += 1;
+    }
+
+    out.push_str(&text[last..]);
+    (out, count)
+}
+
+/// Undo the last replace if the text hasn't been edited since.
+/// Returns false to let the editor's own undo handle the key.
+pub fn undo_replace(tab: &EditorTab, current_text: &str) -> bool {
+    let Some(undo) = tab.replace_undo.get_untracked() else {
+        return false;
+    };
+    if undo.after != current_text {
+        return false;
+    }
+
+    tab.replace_undo.set(None);
+    tab.set_text(undo.before);
+    true
+}
+
+/// The editor's selection, if there is one and its offsets match the tab's
+/// text (they don't while LSX nodes are folded)
+fn selection_range(tab: &EditorTab, content: &str) -> Option<Range<usize>> {
+    let editor = tab.editor()?;
+    let (start, end) = editor.cursor.get_untracked().get_selection()?;
+    (editor.doc.text().to_string() == content && end <= content.len()).then_some(start..end)
+}
+
 pub fn replace_current(state: EditorState) {
     let count = state.match_count.get();
     if count == 0 {
@@ -138,103 +234,204 @@ pub fn replace_current(state: EditorState) {
         return;
     }
 
-    let replace_with = state.replace_text.get();
+    let regex = match tab_regex(&state) {
+        Ok(regex) => regex,
+        Err(e) => {
+            state.search_status.set(format!("Invalid regex: {}", e));
+            return;
+        }
+    };
+
+    let content = state.live_content.get();
     let current_idx = state.current_match.get();
+    let Some((start, end)) = SEARCH_STATE
+        .lock()
+        .ok()
+        .and_then(|search_state| search_state.matches.get(current_idx).copied())
+    else {
+        return;
+    };
 
-    if let Ok(search_state) = SEARCH_STATE.lock() {
-        if let Some(&(start, end)) = search_state.matches.get(current_idx) {
-            let content = state.content.get();
-            let mut new_content = String::new();
-            new_content.push_str(&content[..start]);
-            new_content.push_str(&replace_with);
-            new_content.push_str(&content[end..]);
-
-            state.content.set(new_content.clone());
-            state.live_content.set(new_content.clone());
-            state.modified.set(true);
-
-            // Re-run search to update matches
-            drop(search_state);
-            perform_search(
-                new_content,
-                state.search_text.get(),
-                state.case_sensitive.get(),
-                state.whole_words.get(),
-                state.use_regex.get(),
-                state.match_count,
-                state.current_match,
-                state.search_status,
-            );
-
-            state.search_status.set("Replaced 1 occurrence".to_string());
-        }
+    // Match again at the same spot to get its capture groups
+    let Some(caps) = regex
+        .captures_at(&content, start)
+        .filter(|caps| caps.get(0).is_some_and(|m| m.range() == (start..end)))
+    else {
+        state
+            .search_status
+            .set("Text changed since the search - search again".to_string());
+        return;
+    };
+
+    let replace_with = state.replace_text.get();
+    let mut replacement = String::new();
+    if state.use_regex.get() {
+        caps.expand(&replace_with, &mut replacement);
+    } else {
+        replacement.push_str(&replace_with);
+    }
+
+    let new_content = format!("{}{}{}", &content[..start], replacement, &content[end..]);
+    state.replace_text(new_content.clone());
+    search_again(&state, new_content);
+
+    // Carry on from the first match after the replacement. An empty match
+    // right where it ends is the spot just replaced, so skip it.
+    let resume = start + replacement.len();
+    if let Ok(mut search_state) = SEARCH_STATE.lock() {
+        let next = search_state
+            .matches
+            .iter()
+            .position(|&(s, e)| s > resume || (s == resume && e > s))
+            .unwrap_or(0);
+        search_state.current_index = next;
+        state.current_match.set(next);
     }
+
+    state.search_status.set("Replaced 1 occurrence".to_string());
 }
 
 pub fn replace_all(state: EditorState) {
     let search_text = state.search_text.get();
-    let replace_with = state.replace_text.get();
-    let case_sensitive = state.case_sensitive.get();
-    let whole_words = state.whole_words.get();
-    let use_regex = state.use_regex.get();
-
     if search_text.is_empty() {
         state.search_status.set("Nothing to replace".to_string());
         return;
     }
 
-    // Build the regex pattern
-    let pattern = if use_regex {
-        search_text.clone()
-    } else {
-        regex::escape(&search_text)
-    };
-
-    let pattern = if whole_words {
-        format!(r"\b{}\b", pattern)
-    } else {
-        pattern
+    let regex = match tab_regex(&state) {
+        Ok(regex) => regex,
+        Err(e) => {
+            state.search_status.set(format!("Invalid regex: {}", e));
+            return;
+        }
     };
 
-    match RegexBuilder::new(&pattern)
-        .case_insensitive(!case_sensitive)
-        .build()
-    {
-        Ok(regex) => {
-            let content = state.content.get();
-            let count = regex.find_iter(&content).count();
+    // Only replace inside the selection when there is one
+    let content = state.live_content.get();
+    let selection = selection_range(&state, &content);
+    let in_selection = selection.is_some();
+    let (new_content, count) = replace_matches(
+        &content,
+        &regex,
+        &state.replace_text.get(),
+        state.use_regex.get(),
+        selection,
+    );
 
-            if count == 0 {
-                state.search_status.set("No matches found".to_string());
-                return;
+    if count == 0 {
+        state.search_status.set(
+            if in_selection {
+                "No matches in selection"
+            } else {
+                "No matches found"
             }
+            .to_string(),
+        );
+        return;
+    }
 
-            let new_content = regex
-                .replace_all(&content, replace_with.as_str())
-                .to_string();
-
-            state.content.set(new_content.clone());
-            state.live_content.set(new_content.clone());
-            state.modified.set(true);
-
-            // Re-run search
-            perform_search(
-                new_content,
-                state.search_text.get(),
-                state.case_sensitive.get(),
-                state.whole_words.get(),
-                state.use_regex.get(),
-                state.match_count,
-                state.current_match,
-                state.search_status,
-            );
-
-            state
-                .search_status
-                .set(format!("Replaced {} occurrences", count));
-        }
+    state.replace_text(new_content.clone());
+    search_again(&state, new_content);
+
+    state.search_status.set(if in_selection {
+        format!("Replaced {} occurrences in selection", count)
+    } else {
+        format!("Replaced {} occurrences", count)
+    });
+}
+
+/// Replace All in every open tab, using the active tab's search options.
+/// Each changed tab is marked modified and can be undone on its own.
+pub fn replace_all_tabs(state: EditorState, tabs_state: &EditorTabsState) {
+    let search_text = state.search_text.get();
+    if search_text.is_empty() {
+        state.search_status.set("Nothing to replace".to_string());
+        return;
+    }
+
+    let regex = match tab_regex(&state) {
+        Ok(regex) => regex,
         Err(e) => {
             state.search_status.set(format!("Invalid regex: {}", e));
+            return;
         }
+    };
+
+    let replace_with = state.replace_text.get();
+    let expand = state.use_regex.get();
+    let mut per_tab = Vec::new();
+    let mut total = 0;
+
+    for tab in tabs_state.tabs.get() {
+        let content = tab.live_content.get();
+        let (new_content, count) = replace_matches(&content, &regex, &replace_with, expand, None);
+        if count == 0 {
+            continue;
+        }
+        tab.replace_text(new_content);
+        total += count;
+        per_tab.push(format!("{}: {}", tab.display_name(), count));
+    }
+
+    if total == 0 {
+        state
+            .search_status
+            .set("No matches in open tabs".to_string());
+        return;
+    }
+
+    search_again(&state, state.live_content.get());
+    state.search_status.set(format!(
+        "Replaced {} occurrences in {} tabs ({})",
+        total,
+        per_tab.len(),
+        per_tab.join(", ")
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regex(pattern: &str) -> Regex {
+        Regex::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn test_replace_expands_capture_groups() {
+        let (text, count) = replace_matches(
+            r#"id="Foo" id="Bar""#,
+            &regex(r#"id="([A-Za-z]+)""#),
+            "id=\"${1}_2\"",
+            true,
+            None,
+        );
+        assert_eq!(text, r#"id="Foo_2" id="Bar_2""#);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_literal_replacement_keeps_dollar_signs() {
+        let (text, _) = replace_matches("cost", &regex("cost"), "$1 each", false, None);
+        assert_eq!(text, "$1 each");
+    }
+
+    #[test]
+    fn test_zero_length_matches_insert_without_looping() {
+        let (text, count) = replace_matches("a\nb", &regex("(?m)^"), "> ", false, None);
+        assert_eq!(text, "> a\n> b");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_replace_limited_to_range() {
+        let (text, count) = replace_matches("aaa aaa", &regex("a"), "b", false, Some(4..7));
+        assert_eq!(text, "aaa bbb");
+        assert_eq!(count, 3);
+
+        // Matches crossing the range edge are left alone
+        let (text, count) = replace_matches("abab", &regex("ab"), "x", false, Some(1..4));
+        assert_eq!(text, "abx");
+        assert_eq!(count, 1);
     }
 }
@@ -0,0 +1,236 @@
+//! Stats `.txt` support: line coloring and the entry outline
+//!
+//! Stats lines are independent of each other, so each line is tokenized on
+//! its own when drawn. Nothing here depends on floem.
+
+use std::path::Path;
+
+use super::lsx::line_offsets;
+
+/// Kinds of stats token, each drawn in its own color
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatsTokenKind {
+    /// Leading `new entry`, `type`, `using`, `data`, `object category`...
+    Keyword,
+    /// Quoted entry, parent or table name
+    Name,
+    /// First quoted string of a `data` line
+    DataKey,
+    /// Second quoted string of a `data` line
+    DataValue,
+    /// `//` comment to the end of the line
+    Comment,
+}
+
+/// A token as a byte range within its line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatsToken {
+    pub start: usize,
+    pub end: usize,
+    pub kind: StatsTokenKind,
+}
+
+/// Whether a file is a stats file (anything under `Stats/Generated`)
+pub fn is_stats_path(path: &str) -> bool {
+    let path = path.replace('\\', "/").to_lowercase();
+    Path::new(&path).extension().is_some_and(|ext| ext == "txt")
+        && path.contains("stats/generated/")
+}
+
+/// Tokenize one line (without its newline)
+pub fn tokenize_line(line: &str) -> Vec<StatsToken> {
+    let mut tokens = Vec::new();
+    let comment = line.find("//").filter(|&i| !inside_quotes(line, i));
+    let code = &line[..comment.unwrap_or(line.len())];
+
+    let keyword_start = code.len() - code.trim_start().len();
+    let keyword_end = code.find('"').unwrap_or(code.len());
+    let keyword = code[keyword_start..keyword_end].trim_end();
+    if !keyword.is_empty() {
+        tokens.push(StatsToken {
+            start: keyword_start,
+            end: keyword_start + keyword.len(),
+            kind: StatsTokenKind::Keyword,
+        });
+    }
+
+    let is_data = keyword == "data";
+    let mut pos = keyword_end;
+    let mut index = 0;
+    while let Some(open) = code[pos..].find('"').map(|i| pos + i) {
+        let close = code[open + 1..]
+            .find('"')
+            .map_or(code.len(), |i| open + 1 + i + 1);
+        let kind = match (is_data, index) {
+            (true, 0) => StatsTokenKind::DataKey,
+            (true, _) => StatsTokenKind::DataValue,
+            (false, _) => StatsTokenKind::Name,
+        };
+        tokens.push(StatsToken {
+            start: open,
+            end: close,
+            kind,
+        });
+        pos = close;
+        index += 1;
+    }
+
+    if let Some(start) = comment {
+        tokens.push(StatsToken {
+            start,
+            end: line.len(),
+            kind: StatsTokenKind::Comment,
+        });
+    }
+    tokens
+}
+
+/// Whether byte `pos` falls inside a quoted string
+fn inside_quotes(line: &str, pos: usize) -> bool {
+    line[..pos].matches('"').count() % 2 == 1
+}
+
+/// Document text split into lines for the editor's styling
+#[derive(Default)]
+pub struct StatsLines {
+    text: String,
+    offsets: Vec<usize>,
+}
+
+impl StatsLines {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            offsets: line_offsets(text),
+        }
+    }
+
+    /// Replace the text after an edit
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            *self = Self::new(text);
+        }
+    }
+
+    pub fn tokens(&self, line: usize) -> Vec<StatsToken> {
+        let Some(&start) = self.offsets.get(line) else {
+            return Vec::new();
+        };
+        let end = self
+            .offsets
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        tokenize_line(self.text[start..end].trim_end_matches(['\n', '\r']))
+    }
+}
+
+/// A `new entry`/`new treasuretable`/... block in the outline
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutlineEntry {
+    /// 0-based line of the `new` line
+    pub line: usize,
+    pub keyword: String,
+    pub name: String,
+}
+
+/// Every top-level `new <keyword> "Name"` block, in file order
+/// (treasure table subtables are not blocks of their own)
+pub fn outline(text: &str) -> Vec<OutlineEntry> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, content)| {
+            let (keyword, name) = block_header(content)?;
+            Some(OutlineEntry {
+                line,
+                keyword: keyword.to_string(),
+                name: name.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Keyword and name of a `new <keyword> "Name"` line
+fn block_header(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim_start().strip_prefix("new ")?;
+    let (keyword, rest) = rest.split_once('"')?;
+    let keyword = keyword.trim();
+    if keyword.is_empty() || keyword == "subtable" {
+        return None;
+    }
+    let (name, _) = rest.split_once('"')?;
+    Some((keyword, name))
+}
+
+/// Current line of an outline entry. Lines move as the text is edited, so
+/// the header is looked up by name, falling back to the recorded line.
+pub fn find_entry_line(text: &str, entry: &OutlineEntry) -> usize {
+    text.lines()
+        .position(|line| block_header(line) == Some((&entry.keyword, &entry.name)))
+        .unwrap_or(entry.line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"new entry "WPN_Longsword"
+type "Weapon"
+using "_BaseWeapon"
+data "Damage" "1d8" // base die
+
+new treasuretable "TT_Chest"
+new subtable "1,1"
+object category "I_WPN_Longsword",1,0,0,0,0,0,0,0
+"#;
+
+    fn kinds(line: &str) -> Vec<(&str, StatsTokenKind)> {
+        tokenize_line(line)
+            .into_iter()
+            .map(|t| (&line[t.start..t.end], t.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenizes_data_lines() {
+        assert_eq!(
+            kinds(r#"data "Damage" "1d8" // base die"#),
+            vec![
+                ("data", StatsTokenKind::Keyword),
+                ("\"Damage\"", StatsTokenKind::DataKey),
+                ("\"1d8\"", StatsTokenKind::DataValue),
+                ("// base die", StatsTokenKind::Comment),
+            ]
+        );
+        assert_eq!(
+            kinds(r#"  new entry "A//B""#),
+            vec![
+                ("new entry", StatsTokenKind::Keyword),
+                ("\"A//B\"", StatsTokenKind::Name),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_outlines_top_level_blocks() {
+        let entries = outline(SAMPLE);
+        let names: Vec<_> = entries
+            .iter()
+            .map(|e| (e.line, e.keyword.as_str(), e.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (0, "entry", "WPN_Longsword"),
+                (5, "treasuretable", "TT_Chest")
+            ]
+        );
+
+        let edited = format!("// header\n\n{}", SAMPLE);
+        assert_eq!(find_entry_line(&edited, &entries[1]), 7);
+        assert!(is_stats_path(
+            "Mods/MyMod/Public/MyMod/Stats/Generated/Data/Weapon.txt"
+        ));
+        assert!(!is_stats_path("Mods/MyMod/Story/RawFiles/Goals/Start.txt"));
+    }
+}
@@ -1,4 +1,7 @@
-//! Syntax highlighting for XML and JSON
+//! Syntax highlighting for XML, JSON and stats files
+
+use std::cell::RefCell;
+use std::rc::Rc;
 
 use floem::peniko::Color as PenikoColor;
 use floem::text::{Attrs, AttrsList, FamilyOwned, Weight};
@@ -7,6 +10,9 @@ use floem::views::editor::id::EditorId;
 use floem::views::editor::text::Styling;
 use std::borrow::Cow;
 
+use super::lsx::{LsxLines, LsxTokenKind, looks_like_xml};
+use super::stats::{StatsLines, StatsTokenKind};
+
 /// Token types for syntax highlighting
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TokenType {
@@ -49,6 +55,17 @@ impl SyntaxColors {
     pub const XML_COMMENT: PenikoColor = PenikoColor::rgba8(106, 153, 85, 255); // Green
     pub const XML_DECLARATION: PenikoColor = PenikoColor::rgba8(197, 134, 192, 255); // Purple
 
+    // LSX attribute values
+    pub const LSX_ID: PenikoColor = PenikoColor::rgba8(220, 220, 170, 255); // Pale yellow
+    pub const LSX_TYPE: PenikoColor = PenikoColor::rgba8(78, 201, 176, 255); // Teal
+    pub const LSX_PUNCTUATION: PenikoColor = PenikoColor::rgba8(128, 128, 128, 255); // Gray
+
+    // Stats colors
+    pub const STATS_KEYWORD: PenikoColor = PenikoColor::rgba8(197, 134, 192, 255); // Purple
+    pub const STATS_NAME: PenikoColor = PenikoColor::rgba8(220, 220, 170, 255); // Pale yellow
+    pub const STATS_DATA_KEY: PenikoColor = PenikoColor::rgba8(156, 220, 254, 255); // Light cyan
+    pub const STATS_DATA_VALUE: PenikoColor = PenikoColor::rgba8(206, 145, 120, 255); // Orange/brown
+
     // JSON colors
     pub const JSON_KEY: PenikoColor = PenikoColor::rgba8(156, 220, 254, 255); // Light cyan
     pub const JSON_STRING: PenikoColor = PenikoColor::rgba8(206, 145, 120, 255); // Orange/brown
@@ -77,6 +94,29 @@ impl SyntaxColors {
             TokenType::Plain => Self::PLAIN,
         }
     }
+
+    pub fn for_stats_token(kind: StatsTokenKind) -> PenikoColor {
+        match kind {
+            StatsTokenKind::Keyword => Self::STATS_KEYWORD,
+            StatsTokenKind::Name => Self::STATS_NAME,
+            StatsTokenKind::DataKey => Self::STATS_DATA_KEY,
+            StatsTokenKind::DataValue => Self::STATS_DATA_VALUE,
+            StatsTokenKind::Comment => Self::XML_COMMENT,
+        }
+    }
+
+    pub fn for_lsx_token(kind: LsxTokenKind) -> PenikoColor {
+        match kind {
+            LsxTokenKind::Punctuation => Self::LSX_PUNCTUATION,
+            LsxTokenKind::TagName => Self::XML_TAG,
+            LsxTokenKind::AttributeName => Self::XML_ATTRIBUTE,
+            LsxTokenKind::Id => Self::LSX_ID,
+            LsxTokenKind::Type => Self::LSX_TYPE,
+            LsxTokenKind::Value => Self::XML_STRING,
+            LsxTokenKind::Comment => Self::XML_COMMENT,
+            LsxTokenKind::Declaration => Self::XML_DECLARATION,
+        }
+    }
 }
 
 /// Tokenize XML content for syntax highlighting
@@ -461,15 +501,26 @@ pub struct SyntaxStyling {
     tokens: Vec<TokenSpan>,
     line_offsets: Vec<usize>, // Byte offset where each line starts
     font_size: usize,
+    /// LSX documents are tokenized per line as they are drawn. Shared with the
+    /// editor, which updates the text after edits.
+    lsx: Option<Rc<RefCell<LsxLines>>>,
+    /// Stats files, likewise tokenized per line
+    stats: Option<Rc<RefCell<StatsLines>>>,
 }
 
 impl SyntaxStyling {
     pub fn new(text: &str, format: &str) -> Self {
-        let tokens = match format.to_uppercase().as_str() {
-            "LSX" | "LSF" | "LSFX" | "LOCA" | "XML" => tokenize_xml(text),
+        let format = format.to_uppercase();
+        let is_lsx = matches!(format.as_str(), "LSX" | "LSF" | "LSFX" | "LSBC" | "LSBS");
+
+        // LSX is tokenized lazily below; XML that isn't XML falls back to plain text
+        let tokens = match format.as_str() {
+            "LOCA" | "XML" if looks_like_xml(text) => tokenize_xml(text),
             "LSJ" | "JSON" => tokenize_json(text),
             _ => Vec::new(),
         };
+        let lsx = (is_lsx && (text.trim().is_empty() || looks_like_xml(text)))
+            .then(|| Rc::new(RefCell::new(LsxLines::new(text))));
 
         // Compute line offsets
         let mut line_offsets = vec![0];
@@ -492,9 +543,29 @@ impl SyntaxStyling {
             tokens,
             line_offsets,
             font_size: 14,
+            lsx,
+            stats: None,
+        }
+    }
+
+    /// Styling for a stats `.txt` file
+    pub fn for_stats(text: &str) -> Self {
+        Self {
+            stats: Some(Rc::new(RefCell::new(StatsLines::new(text)))),
+            ..Self::new(text, "TXT")
         }
     }
 
+    /// Line-by-line LSX state, if this is an LSX document
+    pub fn lsx_lines(&self) -> Option<Rc<RefCell<LsxLines>>> {
+        self.lsx.clone()
+    }
+
+    /// Per-line stats state, if this is a stats file
+    pub fn stats_lines(&self) -> Option<Rc<RefCell<StatsLines>>> {
+        self.stats.clone()
+    }
+
     /// Get the byte offset for the start of a line
     fn line_start(&self, line: usize) -> usize {
         self.line_offsets.get(line).copied().unwrap_or(0)
@@ -535,6 +606,21 @@ impl Styling for SyntaxStyling {
         _default: Attrs,
         attrs: &mut AttrsList,
     ) {
+        if let Some(lsx) = &self.lsx {
+            for token in lsx.borrow_mut().tokens(line) {
+                let color = SyntaxColors::for_lsx_token(token.kind);
+                attrs.add_span(token.start..token.end, Attrs::new().color(color));
+            }
+            return;
+        }
+        if let Some(stats) = &self.stats {
+            for token in stats.borrow().tokens(line) {
+                let color = SyntaxColors::for_stats_token(token.kind);
+                attrs.add_span(token.start..token.end, Attrs::new().color(color));
+            }
+            return;
+        }
+
         let line_start = self.line_start(line);
         // Get line end from next line offset, or use a large value
         let line_end = self
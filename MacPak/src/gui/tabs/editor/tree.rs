@@ -0,0 +1,275 @@
+//! Node tree model behind the editor's tree view
+//!
+//! Nodes are addressed by path: region index, then the index of each node
+//! below it. Attribute edits are validated against the attribute's type and
+//! the document is serialized back to text.
+
+use std::collections::HashSet;
+
+use maclarian::formats::lsx::{LsxDocument, LsxNode, serialize_lsx};
+
+use super::formatting::format_xml;
+
+/// Region index followed by node indices
+pub type NodePath = Vec<usize>;
+
+/// Attributes worth showing next to a node's id in the tree
+const LABEL_ATTRIBUTES: &[&str] = &["Name", "MapKey", "UUID", "ID", "Key"];
+
+/// One visible row of the tree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeRow {
+    pub path: NodePath,
+    pub depth: usize,
+    pub label: String,
+    pub has_children: bool,
+}
+
+/// Regions, plus the children of every expanded entry
+pub fn visible_rows(doc: &LsxDocument, expanded: &HashSet<NodePath>) -> Vec<TreeRow> {
+    let mut rows = Vec::new();
+    for (index, region) in doc.regions.iter().enumerate() {
+        let path = vec![index];
+        let is_expanded = expanded.contains(&path);
+        rows.push(TreeRow {
+            path: path.clone(),
+            depth: 0,
+            label: region.id.clone(),
+            has_children: !region.nodes.is_empty(),
+        });
+        if is_expanded {
+            push_nodes(&region.nodes, &path, 1, expanded, &mut rows);
+        }
+    }
+    rows
+}
+
+fn push_nodes(
+    nodes: &[LsxNode],
+    parent: &[usize],
+    depth: usize,
+    expanded: &HashSet<NodePath>,
+    rows: &mut Vec<TreeRow>,
+) {
+    for (index, node) in nodes.iter().enumerate() {
+        let mut path = parent.to_vec();
+        path.push(index);
+        rows.push(TreeRow {
+            path: path.clone(),
+            depth,
+            label: node_label(node),
+            has_children: !node.children.is_empty(),
+        });
+        if expanded.contains(&path) {
+            push_nodes(&node.children, &path, depth + 1, expanded, rows);
+        }
+    }
+}
+
+/// Node id, with a name-like attribute when there is one
+fn node_label(node: &LsxNode) -> String {
+    LABEL_ATTRIBUTES
+        .iter()
+        .find_map(|id| node.attribute_value(id).filter(|v| !v.is_empty()))
+        .map_or_else(
+            || node.id.clone(),
+            |value| format!("{} ({})", node.id, value),
+        )
+}
+
+/// Node at `path` (`None` for a region path or an out of range index)
+pub fn node_at<'a>(doc: &'a LsxDocument, path: &[usize]) -> Option<&'a LsxNode> {
+    let (&region, rest) = path.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let mut node = doc.regions.get(region)?.nodes.get(first)?;
+    for &index in rest {
+        node = node.children.get(index)?;
+    }
+    Some(node)
+}
+
+fn node_at_mut<'a>(doc: &'a mut LsxDocument, path: &[usize]) -> Option<&'a mut LsxNode> {
+    let (&region, rest) = path.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let mut node = doc.regions.get_mut(region)?.nodes.get_mut(first)?;
+    for &index in rest {
+        node = node.children.get_mut(index)?;
+    }
+    Some(node)
+}
+
+/// Which input the tree view uses for an attribute type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    Bool,
+    Number,
+    Guid,
+    Text,
+}
+
+pub fn value_kind(type_name: &str) -> ValueKind {
+    match type_name {
+        "bool" | "Bool" => ValueKind::Bool,
+        "guid" | "UUID" => ValueKind::Guid,
+        "int8" | "Int8" | "uint8" | "Byte" | "int16" | "Short" | "uint16" | "UShort" | "int32"
+        | "Int" | "uint32" | "UInt" | "int64" | "Int64" | "old_int64" | "Long" | "uint64"
+        | "ULongLong" | "float" | "Float" | "double" | "Double" => ValueKind::Number,
+        _ => ValueKind::Text,
+    }
+}
+
+/// Check a value against its attribute type. Returns the value to store
+/// (booleans are normalized to `True`/`False`).
+pub fn validate_value(type_name: &str, value: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    let valid = match type_name {
+        "bool" | "Bool" => {
+            return match trimmed.to_lowercase().as_str() {
+                "true" | "1" => Ok("True".to_string()),
+                "false" | "0" => Ok("False".to_string()),
+                _ => Err(format!("'{}' is not True or False", value)),
+            };
+        }
+        "int8" | "Int8" => trimmed.parse::<i8>().is_ok(),
+        "uint8" | "Byte" => trimmed.parse::<u8>().is_ok(),
+        "int16" | "Short" => trimmed.parse::<i16>().is_ok(),
+        "uint16" | "UShort" => trimmed.parse::<u16>().is_ok(),
+        "int32" | "Int" => trimmed.parse::<i32>().is_ok(),
+        "uint32" | "UInt" => trimmed.parse::<u32>().is_ok(),
+        "int64" | "Int64" | "old_int64" | "Long" => trimmed.parse::<i64>().is_ok(),
+        "uint64" | "ULongLong" => trimmed.parse::<u64>().is_ok(),
+        "float" | "Float" => trimmed.parse::<f32>().is_ok_and(f32::is_finite),
+        "double" | "Double" => trimmed.parse::<f64>().is_ok_and(f64::is_finite),
+        "guid" | "UUID" => is_guid(trimmed),
+        "ivec2" | "IVec2" | "fvec2" | "Vec2" => has_components(trimmed, 2),
+        "ivec3" | "IVec3" | "fvec3" | "Vec3" => has_components(trimmed, 3),
+        "ivec4" | "IVec4" | "fvec4" | "Vec4" => has_components(trimmed, 4),
+        _ => return Ok(value.to_string()),
+    };
+
+    if valid {
+        Ok(trimmed.to_string())
+    } else {
+        Err(format!("'{}' is not a valid {}", value, type_name))
+    }
+}
+
+/// `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` with hex digits
+fn is_guid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Space-separated numbers, `count` of them
+fn has_components(value: &str, count: usize) -> bool {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    parts.len() == count && parts.iter().all(|p| p.parse::<f64>().is_ok())
+}
+
+/// Validate and set the value of attribute `attribute` on the node at `path`.
+/// Returns whether the value changed.
+pub fn set_attribute_value(
+    doc: &mut LsxDocument,
+    path: &[usize],
+    attribute: usize,
+    value: &str,
+) -> Result<bool, String> {
+    let attr = node_at_mut(doc, path)
+        .and_then(|node| node.attributes.get_mut(attribute))
+        .ok_or_else(|| "Attribute no longer exists".to_string())?;
+    let value = validate_value(&attr.type_name, value)?;
+    if attr.value == value {
+        return Ok(false);
+    }
+    attr.value = value;
+    Ok(true)
+}
+
+/// Serialize the document the way the editor shows LSX text
+pub fn document_text(doc: &LsxDocument) -> Result<String, String> {
+    let xml = serialize_lsx(doc).map_err(|e| e.to_string())?;
+    Ok(format_xml(xml.trim_start_matches('\u{feff}')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maclarian::formats::lsx::parse_lsx;
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="330"/>
+    <region id="Templates">
+        <node id="Templates">
+            <children>
+                <node id="GameObjects">
+                    <attribute id="Name" type="LSString" value="Sword"/>
+                    <attribute id="IsTradable" type="bool" value="True"/>
+                    <attribute id="Weight" type="float" value="1.5"/>
+                </node>
+            </children>
+        </node>
+    </region>
+</save>
+"#;
+
+    #[test]
+    fn test_rows_follow_expanded_paths() {
+        let doc = parse_lsx(SAMPLE).unwrap();
+
+        let rows = visible_rows(&doc, &HashSet::new());
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].label, "Templates");
+
+        let expanded = HashSet::from([vec![0], vec![0, 0]]);
+        let rows = visible_rows(&doc, &expanded);
+        let labels: Vec<_> = rows.iter().map(|r| (r.label.as_str(), r.depth)).collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("Templates", 0),
+                ("Templates", 1),
+                ("GameObjects (Sword)", 2)
+            ]
+        );
+        assert_eq!(rows[2].path, vec![0, 0, 0]);
+        assert!(node_at(&doc, &[0]).is_none());
+    }
+
+    #[test]
+    fn test_validates_by_type() {
+        assert_eq!(validate_value("bool", "true").unwrap(), "True");
+        assert_eq!(validate_value("bool", "0").unwrap(), "False");
+        assert!(validate_value("bool", "yes").is_err());
+        assert_eq!(validate_value("int32", " 42 ").unwrap(), "42");
+        assert!(validate_value("uint8", "300").is_err());
+        assert!(validate_value("float", "NaN").is_err());
+        assert!(validate_value("fvec3", "1 2.5 -3").is_ok());
+        assert!(validate_value("fvec3", "1 2").is_err());
+        assert!(validate_value("guid", "c7c9e0f4-3b4a-4f0e-9f3b-2f1d0a6b8e21").is_ok());
+        assert!(validate_value("guid", "c7c9e0f4-3b4a-4f0e-9f3b").is_err());
+        assert_eq!(
+            validate_value("LSString", " kept as is ").unwrap(),
+            " kept as is "
+        );
+    }
+
+    #[test]
+    fn test_edits_round_trip_through_text() {
+        let mut doc = parse_lsx(SAMPLE).unwrap();
+        let path = [0, 0, 0];
+
+        assert!(set_attribute_value(&mut doc, &path, 2, "2.25").unwrap());
+        assert!(!set_attribute_value(&mut doc, &path, 2, "2.25").unwrap());
+        assert!(set_attribute_value(&mut doc, &path, 1, "nope").is_err());
+
+        let reparsed = parse_lsx(&document_text(&doc).unwrap()).unwrap();
+        let node = node_at(&reparsed, &path).unwrap();
+        assert_eq!(node.attribute_value("Weight"), Some("2.25"));
+        assert_eq!(node.attribute_value("IsTradable"), Some("True"));
+    }
+}
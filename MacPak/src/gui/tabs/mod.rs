@@ -16,6 +16,7 @@ pub use dialogue::dialogue_tab;
 pub use dyes::dyes_tab;
 pub use editor::editor_tab;
 pub use editor::load_file_in_tab;
+pub use editor::open_pak_entry;
 pub use pak_ops::pak_ops_tab;
 pub use search::search_tab;
 pub use workbench::workbench_tab;
@@ -2,16 +2,18 @@
 
 use floem::prelude::*;
 use floem::text::Weight;
+use floem::views::text_input;
 
-use super::super::operations::execute_create_pak;
+use super::super::operations::confirm_create_pak;
 use super::super::widgets::{compression_selector, priority_input};
+use crate::gui::shared::colors;
 use crate::gui::state::{ActiveDialog, PakOpsState};
 
 pub fn create_options_content(state: PakOpsState) -> impl IntoView {
     let compression = state.compression;
     let priority = state.priority;
     let generate_info_json = state.generate_info_json;
-    let pending = state.pending_create;
+    let exclude_patterns = state.exclude_patterns;
     let state_confirm = state.clone();
     let state_cancel = state.clone();
 
@@ -31,6 +33,20 @@ pub fn create_options_content(state: PakOpsState) -> impl IntoView {
             priority_input(priority),
         ))
         .style(|s| s.width_full().items_center().margin_bottom(12.0)),
+        h_stack((
+            label(|| "Exclude:".to_string()).style(|s| s.width(120.0)),
+            text_input(exclude_patterns)
+                .placeholder("*.psd, .git")
+                .style(|s| {
+                    s.flex_grow(1.0)
+                        .padding(6.0)
+                        .background(colors().bg_base)
+                        .border(1.0)
+                        .border_color(colors().border_strong)
+                        .border_radius(4.0)
+                }),
+        ))
+        .style(|s| s.width_full().items_center().margin_bottom(12.0)),
         h_stack((
             checkbox(move || generate_info_json.get())
                 .on_update(move |checked| {
@@ -46,14 +62,16 @@ pub fn create_options_content(state: PakOpsState) -> impl IntoView {
         .style(|s| s.width_full().items_center().margin_bottom(12.0)),
         label(|| {
             "lz4hc = best compression (default)\n\
-             lz4 = fast compression, none = no compression\n\
+             lz4 = fast compression, zlib = smaller but slower to load\n\
+             none = no compression\n\
              Priority 0 = normal mod, 50+ = override mod\n\
+             Exclude takes glob patterns, comma separated\n\
              info.json enables drag-and-drop import in BaldursModManager"
                 .to_string()
         })
         .style(|s| {
             s.font_size(11.0)
-                .color(Color::rgb8(100, 100, 100))
+                .color(colors().text_secondary)
                 .margin_bottom(16.0)
         }),
         h_stack((
@@ -67,34 +85,29 @@ pub fn create_options_content(state: PakOpsState) -> impl IntoView {
                     s.padding_vert(8.0)
                         .padding_horiz(20.0)
                         .margin_right(8.0)
-                        .background(Color::rgb8(240, 240, 240))
+                        .background(colors().bg_elevated)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
                 }),
             button("Create PAK")
-                .action(move || {
-                    if let Some((source, dest)) = pending.get() {
-                        state_confirm.active_dialog.set(ActiveDialog::None);
-                        execute_create_pak(state_confirm.clone(), source, dest);
-                    }
-                })
+                .action(move || confirm_create_pak(state_confirm.clone()))
                 .style(|s| {
                     s.padding_vert(8.0)
                         .padding_horiz(20.0)
-                        .background(Color::rgb8(33, 150, 243))
-                        .color(Color::WHITE)
+                        .background(colors().accent)
+                        .color(colors().text_on_accent)
                         .border_radius(4.0)
-                        .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                        .hover(|s| s.background(colors().accent_hover))
                 }),
         ))
         .style(|s| s.width_full()),
     ))
     .style(|s| {
         s.padding(24.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(8.0)
             .width(400.0)
     })
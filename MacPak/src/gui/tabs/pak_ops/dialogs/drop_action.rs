@@ -7,6 +7,7 @@ use std::path::Path;
 use super::super::operations::{
     extract_dropped_file, extract_individual_dropped_file, list_dropped_file, validate_dropped_pak,
 };
+use crate::gui::shared::colors;
 use crate::gui::state::{ActiveDialog, PakOpsState};
 
 pub fn drop_action_content(state: PakOpsState) -> impl IntoView {
@@ -43,10 +44,10 @@ pub fn drop_action_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(33, 150, 243))
-                    .color(Color::WHITE)
+                    .background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                    .hover(|s| s.background(colors().accent_hover))
             }),
         button("📄 Extract Individual Files")
             .action(move || {
@@ -61,7 +62,7 @@ pub fn drop_action_content(state: PakOpsState) -> impl IntoView {
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
                     .background(Color::rgb8(156, 39, 176))
-                    .color(Color::WHITE)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
                     .hover(|s| s.background(Color::rgb8(123, 31, 162)))
             }),
@@ -74,10 +75,10 @@ pub fn drop_action_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(76, 175, 80))
-                    .color(Color::WHITE)
+                    .background(colors().success)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(56, 142, 60)))
+                    .hover(|s| s.background(colors().success))
             }),
         button("✓ Validate Mod Structure")
             .action(move || {
@@ -87,10 +88,10 @@ pub fn drop_action_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(255, 152, 0))
-                    .color(Color::WHITE)
+                    .background(colors().warning)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(245, 124, 0)))
+                    .hover(|s| s.background(colors().warning))
             }),
         button("Cancel")
             .action(move || {
@@ -100,17 +101,17 @@ pub fn drop_action_content(state: PakOpsState) -> impl IntoView {
             .style(|s| {
                 s.width_full()
                     .padding_vert(10.0)
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .border_radius(4.0)
             }),
     ))
     .style(|s| {
         s.padding(24.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(8.0)
             .width(320.0)
     })
@@ -7,6 +7,7 @@ use im::Vector as ImVector;
 use std::path::Path;
 
 use super::super::operations::execute_individual_extract;
+use crate::gui::shared::colors;
 use crate::gui::state::{ActiveDialog, ConfigState, PakOpsState};
 
 pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> impl IntoView {
@@ -68,7 +69,7 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                     format!("{} selected, {} of {} shown", sel_count, filtered, total)
                 }
             })
-            .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+            .style(|s| s.font_size(12.0).color(colors().text_secondary)),
             empty().style(|s| s.flex_grow(1.0)),
             text_input(ext_filter)
                 .placeholder("Filter (e.g. .lsf, .xml)")
@@ -77,10 +78,10 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                         .height(26.0)
                         .padding_horiz(8.0)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
                         .font_size(12.0)
-                        .background(Color::WHITE)
+                        .background(colors().bg_base)
                 }),
         ))
         .style(|s| s.width_full().margin_bottom(12.0).items_center()),
@@ -99,9 +100,9 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                     s.padding_vert(6.0)
                         .padding_horiz(12.0)
                         .font_size(12.0)
-                        .background(Color::rgb8(240, 240, 240))
+                        .background(colors().bg_elevated)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
                 }),
             button("Deselect All")
@@ -115,9 +116,9 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                         .padding_horiz(12.0)
                         .font_size(12.0)
                         .margin_left(8.0)
-                        .background(Color::rgb8(240, 240, 240))
+                        .background(colors().bg_elevated)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
                 }),
         ))
@@ -170,7 +171,7 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                             .padding_horiz(8.0)
                             .items_center()
                             .flex_shrink(0.0)
-                            .hover(|s| s.background(Color::rgb8(245, 245, 245)))
+                            .hover(|s| s.background(colors().bg_elevated))
                     })
                 },
             )
@@ -181,9 +182,9 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
             s.width_full()
                 .height(300.0)
                 .border(1.0)
-                .border_color(Color::rgb8(220, 220, 220))
+                .border_color(colors().border)
                 .border_radius(4.0)
-                .background(Color::WHITE)
+                .background(colors().bg_base)
         }),
         // GR2 Processing Options (shown only when GR2 files are selected)
         gr2_options_panel(
@@ -213,9 +214,9 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                     s.padding_vert(8.0)
                         .padding_horiz(20.0)
                         .margin_right(8.0)
-                        .background(Color::rgb8(240, 240, 240))
+                        .background(colors().bg_elevated)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(4.0)
                 }),
             button("Extract Selected")
@@ -227,12 +228,12 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
                     let disabled = selected.get().is_empty();
                     let s = s.padding_vert(8.0).padding_horiz(20.0).border_radius(4.0);
                     if disabled {
-                        s.background(Color::rgb8(200, 200, 200))
-                            .color(Color::rgb8(150, 150, 150))
+                        s.background(colors().bg_selected)
+                            .color(colors().text_muted)
                     } else {
-                        s.background(Color::rgb8(33, 150, 243))
-                            .color(Color::WHITE)
-                            .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                        s.background(colors().accent)
+                            .color(colors().text_on_accent)
+                            .hover(|s| s.background(colors().accent_hover))
                     }
                 }),
         ))
@@ -240,9 +241,9 @@ pub fn file_select_content(state: PakOpsState, config_state: ConfigState) -> imp
     ))
     .style(|s| {
         s.padding(24.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(8.0)
             .width(800.0)
             .max_height(750.0)
@@ -320,8 +321,7 @@ fn gr2_options_panel(
                 })
                 .style(|s| s.margin_right(8.0)),
             label(|| "Full Bundle").style(|s| s.font_size(12.0).font_weight(Weight::MEDIUM)),
-            label(|| " (all options)")
-                .style(|s| s.font_size(11.0).color(Color::rgb8(100, 100, 100))),
+            label(|| " (all options)").style(|s| s.font_size(11.0).color(colors().text_secondary)),
         ))
         .style(|s| s.items_center().margin_bottom(10.0)),
         // Extract GR2
@@ -345,9 +345,9 @@ fn gr2_options_panel(
             label(|| "Convert to GLB").style(move |s| {
                 let disabled = convert_to_gltf.get();
                 s.font_size(12.0).color(if disabled {
-                    Color::rgb8(160, 160, 160)
+                    colors().text_disabled
                 } else {
-                    Color::BLACK
+                    colors().text_primary
                 })
             }),
         ))
@@ -365,9 +365,9 @@ fn gr2_options_panel(
             label(|| "Convert to glTF").style(move |s| {
                 let disabled = convert_to_glb.get();
                 s.font_size(12.0).color(if disabled {
-                    Color::rgb8(160, 160, 160)
+                    colors().text_disabled
                 } else {
-                    Color::BLACK
+                    colors().text_primary
                 })
             }),
         ))
@@ -394,7 +394,7 @@ fn gr2_options_panel(
                 label(|| "Warning: BG3 game data path not set in Settings")
                     .style(|s| {
                         s.font_size(11.0)
-                            .color(Color::rgb8(180, 80, 30))
+                            .color(colors().warning)
                             .margin_top(8.0)
                             .margin_left(20.0)
                     })
@@ -410,9 +410,9 @@ fn gr2_options_panel(
             .width_full()
             .margin_top(12.0)
             .padding(12.0)
-            .background(Color::rgb8(248, 248, 248))
+            .background(colors().bg_surface)
             .border(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
             .border_radius(4.0);
         if visible {
             s
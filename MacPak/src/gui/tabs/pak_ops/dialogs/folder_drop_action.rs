@@ -7,6 +7,7 @@ use std::path::Path;
 use super::super::operations::{
     create_pak_from_dropped_folder, rebuild_pak_from_dropped_folder, validate_dropped_folder,
 };
+use crate::gui::shared::colors;
 use crate::gui::state::{ActiveDialog, PakOpsState};
 
 pub fn folder_drop_action_content(state: PakOpsState) -> impl IntoView {
@@ -41,10 +42,10 @@ pub fn folder_drop_action_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(33, 150, 243))
-                    .color(Color::WHITE)
+                    .background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                    .hover(|s| s.background(colors().accent_hover))
             }),
         button("🔧 Rebuild Modified PAK")
             .action(move || {
@@ -55,10 +56,10 @@ pub fn folder_drop_action_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(76, 175, 80))
-                    .color(Color::WHITE)
+                    .background(colors().success)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(56, 142, 60)))
+                    .hover(|s| s.background(colors().success))
             }),
         button("✓ Validate Mod Structure")
             .action(move || {
@@ -69,10 +70,10 @@ pub fn folder_drop_action_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(255, 152, 0))
-                    .color(Color::WHITE)
+                    .background(colors().warning)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(245, 124, 0)))
+                    .hover(|s| s.background(colors().warning))
             }),
         button("Cancel")
             .action(move || {
@@ -82,17 +83,17 @@ pub fn folder_drop_action_content(state: PakOpsState) -> impl IntoView {
             .style(|s| {
                 s.width_full()
                     .padding_vert(10.0)
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .border_radius(4.0)
             }),
     ))
     .style(|s| {
         s.padding(24.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(8.0)
             .width(320.0)
     })
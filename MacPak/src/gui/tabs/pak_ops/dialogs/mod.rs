@@ -9,6 +9,7 @@ mod create_options;
 mod drop_action;
 mod file_select;
 mod folder_drop_action;
+mod mod_root_warning;
 mod progress;
 mod validate_choice;
 
@@ -26,6 +27,7 @@ use create_options::create_options_content;
 use drop_action::drop_action_content;
 use file_select::file_select_content;
 use folder_drop_action::folder_drop_action_content;
+use mod_root_warning::mod_root_warning_content;
 use progress::progress_content;
 use validate_choice::validate_choice_content;
 
@@ -38,6 +40,7 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
     let polled_current = state.polled_current;
     let polled_total = state.polled_total;
     let polled_msg = state.polled_msg;
+    let polled_cancellable = state.polled_cancellable;
     let timer_active = state.timer_active;
 
     // Polling function for progress
@@ -46,6 +49,7 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
         polled_current: RwSignal<u32>,
         polled_total: RwSignal<u32>,
         polled_msg: RwSignal<String>,
+        polled_cancellable: RwSignal<bool>,
         active: RwSignal<ActiveDialog>,
         timer_active: RwSignal<bool>,
     ) {
@@ -60,6 +64,7 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
         if !msg.is_empty() {
             polled_msg.set(msg);
         }
+        polled_cancellable.set(shared.is_cancellable());
 
         if matches!(active.get_untracked(), ActiveDialog::Progress) && timer_active.get_untracked()
         {
@@ -72,6 +77,7 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
                         polled_current,
                         polled_total,
                         polled_msg,
+                        polled_cancellable,
                         active,
                         timer_active,
                     );
@@ -93,6 +99,7 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
                 polled_current.set(0);
                 polled_total.set(0);
                 polled_msg.set("Starting...".to_string());
+                polled_cancellable.set(false);
                 timer_active.set(true);
 
                 exec_after(Duration::from_millis(50), move |_| {
@@ -102,6 +109,7 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
                             polled_current,
                             polled_total,
                             polled_msg,
+                            polled_cancellable,
                             active,
                             timer_active,
                         );
@@ -131,15 +139,20 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
             // For active dialogs, just return the content - backdrop is on dyn_container
             match dialog {
                 ActiveDialog::None => unreachable!(),
-                ActiveDialog::Progress => {
-                    progress_content(polled_pct, polled_current, polled_total, polled_msg)
-                        .into_any()
-                }
+                ActiveDialog::Progress => progress_content(
+                    polled_pct,
+                    polled_current,
+                    polled_total,
+                    polled_msg,
+                    polled_cancellable,
+                )
+                .into_any(),
                 ActiveDialog::CreateOptions => create_options_content(state).into_any(),
                 ActiveDialog::DropAction => drop_action_content(state).into_any(),
                 ActiveDialog::FileSelect => file_select_content(state, config).into_any(),
                 ActiveDialog::FolderDropAction => folder_drop_action_content(state).into_any(),
                 ActiveDialog::ValidateChoice => validate_choice_content(state).into_any(),
+                ActiveDialog::ModRootWarning => mod_root_warning_content(state).into_any(),
             }
         },
     )
@@ -193,6 +206,10 @@ pub fn dialog_overlay(state: PakOpsState, config_state: ConfigState) -> impl Int
                     ActiveDialog::ValidateChoice => {
                         state.active_dialog.set(ActiveDialog::None);
                     }
+                    ActiveDialog::ModRootWarning => {
+                        state.create_warnings.set(Vec::new());
+                        state.active_dialog.set(ActiveDialog::CreateOptions);
+                    }
                 }
             }
         }
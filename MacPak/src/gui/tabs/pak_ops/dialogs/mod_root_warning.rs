@@ -0,0 +1,74 @@
+//! Mod root warning dialog - shown before packing a folder without meta.lsx
+
+use floem::prelude::*;
+use floem::text::Weight;
+
+use super::super::operations::execute_create_pak;
+use crate::gui::shared::colors;
+use crate::gui::state::{ActiveDialog, PakOpsState};
+
+pub fn mod_root_warning_content(state: PakOpsState) -> impl IntoView {
+    let warnings = state.create_warnings.get_untracked();
+    let state_back = state.clone();
+    let state_confirm = state.clone();
+
+    v_stack((
+        label(|| "Not a Mod Folder?").style(|s| {
+            s.font_size(16.0)
+                .font_weight(Weight::BOLD)
+                .margin_bottom(12.0)
+        }),
+        label(|| {
+            "This folder doesn't look like a mod root (expected Mods/<name>/meta.lsx). \
+             The game may not load the PAK."
+                .to_string()
+        })
+        .style(|s| s.margin_bottom(8.0)),
+        v_stack_from_iter(warnings.into_iter().map(|warning| {
+            label(move || format!("⚠ {}", warning))
+                .style(|s| s.font_size(12.0).color(colors().warning))
+        }))
+        .style(|s| s.gap(4.0).margin_bottom(16.0)),
+        h_stack((
+            empty().style(|s| s.flex_grow(1.0)),
+            button("Back")
+                .action(move || {
+                    state_back.create_warnings.set(Vec::new());
+                    state_back.active_dialog.set(ActiveDialog::CreateOptions);
+                })
+                .style(|s| {
+                    s.padding_vert(8.0)
+                        .padding_horiz(20.0)
+                        .margin_right(8.0)
+                        .background(colors().bg_elevated)
+                        .border(1.0)
+                        .border_color(colors().border_strong)
+                        .border_radius(4.0)
+                }),
+            button("Create Anyway")
+                .action(move || {
+                    if let Some((source, dest)) = state_confirm.pending_create.get() {
+                        state_confirm.active_dialog.set(ActiveDialog::None);
+                        execute_create_pak(state_confirm.clone(), source, dest);
+                    }
+                })
+                .style(|s| {
+                    s.padding_vert(8.0)
+                        .padding_horiz(20.0)
+                        .background(colors().accent)
+                        .color(colors().text_on_accent)
+                        .border_radius(4.0)
+                        .hover(|s| s.background(colors().accent_hover))
+                }),
+        ))
+        .style(|s| s.width_full()),
+    ))
+    .style(|s| {
+        s.padding(24.0)
+            .background(colors().bg_base)
+            .border(1.0)
+            .border_color(colors().border_strong)
+            .border_radius(8.0)
+            .width(400.0)
+    })
+}
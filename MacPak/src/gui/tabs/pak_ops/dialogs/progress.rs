@@ -1,5 +1,7 @@
 //! Progress dialog content
 
+use super::super::types::get_shared_progress;
+use crate::gui::shared::colors;
 use floem::prelude::*;
 
 pub fn progress_content(
@@ -7,7 +9,10 @@ pub fn progress_content(
     polled_current: RwSignal<u32>,
     polled_total: RwSignal<u32>,
     polled_msg: RwSignal<String>,
+    polled_cancellable: RwSignal<bool>,
 ) -> impl IntoView {
+    let cancelling = RwSignal::new(false);
+
     container(
         v_stack((
             label(move || {
@@ -21,7 +26,7 @@ pub fn progress_content(
             })
             .style(|s| {
                 s.font_size(13.0)
-                    .color(Color::rgb8(100, 100, 100))
+                    .color(colors().text_secondary)
                     .margin_bottom(4.0)
             }),
             label(move || polled_msg.get()).style(|s| s.font_size(14.0).margin_bottom(12.0)),
@@ -29,26 +34,45 @@ pub fn progress_content(
                 let pct = polled_pct.get();
                 s.height_full()
                     .width_pct(pct as f64)
-                    .background(Color::rgb8(76, 175, 80))
+                    .background(colors().success)
                     .border_radius(4.0)
             }))
             .style(|s| {
                 s.width_full()
                     .height(8.0)
-                    .background(Color::rgb8(220, 220, 220))
+                    .background(colors().bg_selected)
                     .border_radius(4.0)
             }),
             label(move || format!("{}%", polled_pct.get())).style(|s| {
                 s.font_size(12.0)
                     .margin_top(8.0)
-                    .color(Color::rgb8(100, 100, 100))
+                    .color(colors().text_secondary)
+            }),
+            button(label(move || {
+                if cancelling.get() {
+                    "Cancelling...".to_string()
+                } else {
+                    "Cancel".to_string()
+                }
+            }))
+            .disabled(move || cancelling.get())
+            .action(move || {
+                get_shared_progress().cancel();
+                cancelling.set(true);
+            })
+            .style(move |s| {
+                if polled_cancellable.get() {
+                    s.margin_top(12.0)
+                } else {
+                    s.display(floem::style::Display::None)
+                }
             }),
         ))
         .style(|s| {
             s.padding(24.0)
-                .background(Color::WHITE)
+                .background(colors().bg_base)
                 .border(1.0)
-                .border_color(Color::rgb8(200, 200, 200))
+                .border_color(colors().border_strong)
                 .border_radius(8.0)
                 .width(500.0)
         }),
@@ -4,6 +4,7 @@ use floem::prelude::*;
 use floem::text::Weight;
 
 use super::super::operations::{validate_mod_structure, validate_pak_mod_structure};
+use crate::gui::shared::colors;
 use crate::gui::state::{ActiveDialog, PakOpsState};
 
 pub fn validate_choice_content(state: PakOpsState) -> impl IntoView {
@@ -27,10 +28,10 @@ pub fn validate_choice_content(state: PakOpsState) -> impl IntoView {
                 s.width_full()
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
-                    .background(Color::rgb8(33, 150, 243))
-                    .color(Color::WHITE)
+                    .background(colors().accent)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
-                    .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                    .hover(|s| s.background(colors().accent_hover))
             }),
         button("📦 Select PAK File")
             .action(move || {
@@ -42,7 +43,7 @@ pub fn validate_choice_content(state: PakOpsState) -> impl IntoView {
                     .padding_vert(10.0)
                     .margin_bottom(8.0)
                     .background(Color::rgb8(156, 39, 176))
-                    .color(Color::WHITE)
+                    .color(colors().text_on_accent)
                     .border_radius(4.0)
                     .hover(|s| s.background(Color::rgb8(123, 31, 162)))
             }),
@@ -53,17 +54,17 @@ pub fn validate_choice_content(state: PakOpsState) -> impl IntoView {
             .style(|s| {
                 s.width_full()
                     .padding_vert(10.0)
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .border_radius(4.0)
             }),
     ))
     .style(|s| {
         s.padding(24.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(Color::rgb8(200, 200, 200))
+            .border_color(colors().border_strong)
             .border_radius(8.0)
             .width(320.0)
     })
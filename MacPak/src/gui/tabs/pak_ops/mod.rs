@@ -15,6 +15,7 @@ pub use operations::extract_pak_file;
 use floem::prelude::*;
 use floem::style::Position;
 
+use crate::gui::shared::colors;
 use crate::gui::state::{AppState, ConfigState, PakOpsState};
 use dialogs::dialog_overlay;
 use results::results_area;
@@ -50,7 +51,7 @@ pub fn pak_ops_tab(
     .style(|s| {
         s.width_full()
             .height_full()
-            .background(Color::rgb8(250, 250, 250))
+            .background(colors().bg_surface)
             .position(Position::Relative)
     })
 }
@@ -1,14 +1,23 @@
 //! Batch PAK operations
 
+use std::path::Path;
 use std::thread;
 
 use floem::prelude::*;
-use maclarian::pak::{batch_create, batch_extract, find_packable_folders, find_pak_files};
+use maclarian::pak::{
+    PakOperations, PakProgress, batch_create, find_packable_folders, find_pak_files,
+};
+use maclarian::utils::CancelToken;
 
 use super::super::types::{PakResult, create_result_sender, get_shared_progress};
-use crate::gui::state::{ActiveDialog, PakOpsState};
+use crate::gui::state::{
+    ActiveDialog, BatchExtractLayout, MergeConflictPolicy, PakOpsState, configured_output_dir,
+};
 
 /// Batch extract multiple PAK files from a folder (recursively)
+///
+/// Each PAK goes into its own subfolder or into one merged tree, depending
+/// on the batch layout setting.
 pub fn batch_extract_paks(state: PakOpsState) {
     state.clear_results();
 
@@ -42,13 +51,15 @@ pub fn batch_extract_paks(state: PakOpsState) {
     // Ask for destination folder
     let dest_dialog = rfd::FileDialog::new()
         .set_title("Select Destination Folder for Extracted Files")
-        .set_directory(&source_dir);
+        .set_directory(configured_output_dir().unwrap_or_else(|| source_dir.clone()));
 
     let Some(dest_dir) = dest_dialog.pick_folder() else {
         return;
     };
 
     let pak_count = pak_files.len();
+    let layout = state.batch_layout.get();
+    let conflict = state.batch_conflict.get();
 
     state.is_extracting.set(true);
     state.active_dialog.set(ActiveDialog::Progress);
@@ -62,30 +73,125 @@ pub fn batch_extract_paks(state: PakOpsState) {
 
     let send = create_result_sender(state.clone());
     let shared_progress = get_shared_progress();
+    let cancel = shared_progress.allow_cancel();
 
     thread::spawn(move || {
-        let result = batch_extract(
-            &pak_files,
-            &source_dir,
-            &dest_dir,
-            |progress: &maclarian::pak::PakProgress| {
+        let mut success_count = 0;
+        let mut fail_count = 0;
+        let mut skipped_count = 0;
+        let mut results = Vec::new();
+
+        // One PAK at a time, in path order, so a merged tree is deterministic
+        for (index, pak_path) in pak_files.iter().enumerate() {
+            if cancel.is_cancelled() {
+                skipped_count = pak_count - index;
+                break;
+            }
+
+            let relative = pak_path
+                .strip_prefix(&source_dir)
+                .unwrap_or(pak_path.as_path());
+            let name = relative.to_string_lossy().to_string();
+            let pak_dest = match layout {
+                BatchExtractLayout::PerPak => dest_dir
+                    .join(relative.parent().unwrap_or(Path::new("")))
+                    .join(pak_path.file_stem().unwrap_or_default()),
+                BatchExtractLayout::Merged => dest_dir.clone(),
+            };
+
+            let progress = |progress: &PakProgress| {
                 let desc = progress
                     .current_file
                     .as_deref()
                     .unwrap_or(progress.phase.as_str());
-                shared_progress.update(progress.current, progress.total, desc);
-            },
-        );
+                shared_progress.update_nested(
+                    index + 1,
+                    pak_count,
+                    progress.current,
+                    progress.total,
+                    &format!("{}: {}", name, desc),
+                );
+            };
+
+            let outcome = match layout {
+                BatchExtractLayout::PerPak => {
+                    PakOperations::extract_cancellable(pak_path, &pak_dest, &cancel, &progress)
+                        .map(|()| String::new())
+                }
+                BatchExtractLayout::Merged => {
+                    extract_merged(pak_path, &pak_dest, conflict, &cancel, &progress)
+                }
+            };
+
+            match outcome {
+                Ok(note) => {
+                    success_count += 1;
+                    results.push(format!("Extracted: {}{}", name, note));
+                }
+                // The PAK was partly written; count it as not finished
+                Err(maclarian::Error::Cancelled) => {
+                    skipped_count = pak_count - index;
+                    results.push(format!("Cancelled: {}", name));
+                    break;
+                }
+                Err(e) => {
+                    fail_count += 1;
+                    results.push(format!("Failed {}: {}", name, e));
+                }
+            }
+        }
 
         send(PakResult::BatchExtractDone {
-            success_count: result.success_count,
-            fail_count: result.fail_count,
-            results: result.results,
+            success_count,
+            fail_count,
+            skipped_count,
+            results,
             dest: dest_dir.to_string_lossy().to_string(),
         });
     });
 }
 
+/// Extract one PAK into a shared tree, applying `conflict` to files an
+/// earlier PAK already wrote
+///
+/// Returns a note on the conflicts for the results log.
+fn extract_merged(
+    pak_path: &Path,
+    dest: &Path,
+    conflict: MergeConflictPolicy,
+    cancel: &CancelToken,
+    progress: &(dyn Fn(&PakProgress) + Send + Sync),
+) -> maclarian::Result<String> {
+    let files = PakOperations::list(pak_path)?;
+    let (existing, new): (Vec<String>, Vec<String>) =
+        files.into_iter().partition(|file| dest.join(file).exists());
+
+    match conflict {
+        MergeConflictPolicy::Overwrite => {
+            PakOperations::extract_cancellable(pak_path, dest, cancel, progress)?;
+            Ok(match existing.len() {
+                0 => String::new(),
+                n => format!(" ({} existing files overwritten)", n),
+            })
+        }
+        MergeConflictPolicy::KeepExisting => {
+            if !new.is_empty() {
+                PakOperations::extract_files_cancellable(
+                    pak_path,
+                    dest,
+                    new.as_slice(),
+                    cancel,
+                    progress,
+                )?;
+            }
+            Ok(match existing.len() {
+                0 => String::new(),
+                n => format!(" ({} existing files kept)", n),
+            })
+        }
+    }
+}
+
 /// Batch create PAK files from subfolders (recursively finds all packable folders)
 pub fn batch_create_paks(state: PakOpsState) {
     state.clear_results();
@@ -120,7 +226,7 @@ pub fn batch_create_paks(state: PakOpsState) {
     // Ask for destination folder for PAK files
     let dest_dialog = rfd::FileDialog::new()
         .set_title("Select Destination Folder for PAK Files")
-        .set_directory(&source_dir);
+        .set_directory(configured_output_dir().unwrap_or_else(|| source_dir.clone()));
 
     let Some(dest_dir) = dest_dialog.pick_folder() else {
         return;
@@ -1,6 +1,8 @@
 //! PAK creation and rebuild operations
 
 use floem::prelude::*;
+use maclarian::compression::CompressionSettings;
+use maclarian::pak::{PakCreateOptions, PakOperations};
 use std::path::Path;
 use std::thread;
 
@@ -189,11 +191,31 @@ pub fn rebuild_pak_file(state: PakOpsState) {
     state.active_dialog.set(ActiveDialog::CreateOptions);
 }
 
+/// Check the pending folder looks like a mod root, then create the PAK
+///
+/// Shows the mod root warning instead when `Mods/<name>/meta.lsx` is
+/// missing, so the user can go back or create the PAK anyway.
+pub fn confirm_create_pak(state: PakOpsState) {
+    let Some((source, dest)) = state.pending_create.get() else {
+        return;
+    };
+
+    let validation = maclarian::mods::validate_mod_structure(Path::new(&source));
+    if validation.valid {
+        state.active_dialog.set(ActiveDialog::None);
+        execute_create_pak(state, source, dest);
+    } else {
+        state.create_warnings.set(validation.warnings);
+        state.active_dialog.set(ActiveDialog::ModRootWarning);
+    }
+}
+
 /// Execute the actual PAK creation after options are set
 pub fn execute_create_pak(state: PakOpsState, source: String, dest: String) {
     let compression = state.compression.get();
     let priority = state.priority.get();
     let generate_info_json = state.generate_info_json.get();
+    let exclude = parse_exclude_patterns(&state.exclude_patterns.get());
 
     let source_name = Path::new(&source)
         .file_name()
@@ -211,6 +233,9 @@ pub fn execute_create_pak(state: PakOpsState, source: String, dest: String) {
         compression.as_str(),
         priority
     ));
+    if !exclude.is_empty() {
+        state.add_result(&format!("Excluding: {}", exclude.join(", ")));
+    }
     if generate_info_json {
         state.add_result("Will generate info.json for BaldursModManager");
     }
@@ -222,60 +247,66 @@ pub fn execute_create_pak(state: PakOpsState, source: String, dest: String) {
         .progress_message
         .set(format!("Creating {}...", pak_name));
     state.pending_create.set(None);
+    state.create_warnings.set(Vec::new());
 
     // Reset shared progress state
     get_shared_progress().reset();
 
     let send = create_result_sender(state.clone());
     let progress_sender = create_progress_sender(state);
+    let cancel = get_shared_progress().allow_cancel();
 
     let pak_name_clone = pak_name.clone();
 
-    // Convert GUI compression enum to maclarian compression enum
-    let mac_compression = match compression {
-        crate::gui::state::PakCompression::Lz4Hc => maclarian::pak::CompressionMethod::Lz4, // LZ4 HC not yet supported, fall back to LZ4
-        crate::gui::state::PakCompression::Lz4 => maclarian::pak::CompressionMethod::Lz4,
-        crate::gui::state::PakCompression::None => maclarian::pak::CompressionMethod::None,
+    // Convert GUI compression enum to maclarian compression settings
+    let options = PakCreateOptions {
+        settings: match compression {
+            crate::gui::state::PakCompression::Lz4Hc => CompressionSettings::lz4_hc(9),
+            crate::gui::state::PakCompression::Lz4 => CompressionSettings::lz4(),
+            crate::gui::state::PakCompression::Zlib => CompressionSettings::zlib(6),
+            crate::gui::state::PakCompression::None => CompressionSettings::none(),
+        },
+        exclude,
+        // The priority input keeps the value within 0-100
+        priority: u8::try_from(priority).unwrap_or_default(),
     };
 
     thread::spawn(move || {
-        // Collect source files for listing
-        let source_path = std::path::Path::new(&source);
-        let files: Vec<String> = walkdir::WalkDir::new(&source)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-            .filter_map(|e| {
-                e.path()
-                    .strip_prefix(source_path)
-                    .ok()
-                    .map(|p| p.to_string_lossy().to_string())
-            })
-            .collect();
-
-        let result = maclarian::pak::PakOperations::create_with_compression_and_progress(
-            &source,
-            &dest,
-            mac_compression,
-            &progress_sender,
-        );
+        let result =
+            PakOperations::create_cancellable(&source, &dest, &options, &cancel, &progress_sender);
 
         let pak_result = match result {
-            Ok(_) => {
-                let mut info_json_result = None;
+            Ok(summary) => {
+                // List what was packed, so excluded files don't show up
+                let mut files = PakOperations::list(&dest).unwrap_or_default();
+                files.sort();
+
+                let mut message = format!(
+                    "{} files, {:.2} MB, MD5 {}",
+                    summary.file_count,
+                    summary.size as f64 / (1024.0 * 1024.0),
+                    summary.md5
+                );
 
                 // Generate info.json if requested
                 if generate_info_json {
-                    info_json_result = Some(generate_info_json_file(&source, &dest));
+                    message.push('\n');
+                    message.push_str(&generate_info_json_file(&source, &dest));
                 }
 
                 PakResult::CreateDone {
                     success: true,
-                    message: info_json_result.unwrap_or_default(),
+                    message,
                     files,
                     pak_name: pak_name_clone,
                 }
             }
+            Err(maclarian::Error::Cancelled) => PakResult::CreateDone {
+                success: false,
+                message: "Cancelled, partial PAK removed".to_string(),
+                files: Vec::new(),
+                pak_name: pak_name_clone,
+            },
             Err(e) => PakResult::CreateDone {
                 success: false,
                 message: e.to_string(),
@@ -288,6 +319,15 @@ pub fn execute_create_pak(state: PakOpsState, source: String, dest: String) {
     });
 }
 
+/// Split the exclude text box into glob patterns
+fn parse_exclude_patterns(text: &str) -> Vec<String> {
+    text.split([',', '\n'])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 /// Generate info.json file for BaldursModManager compatibility
 fn generate_info_json_file(source_dir: &str, pak_path: &str) -> String {
     // Use maclarian's info.json generation
@@ -7,7 +7,8 @@ use std::thread;
 use super::super::types::{
     PakResult, create_progress_sender, create_result_sender, get_shared_progress,
 };
-use crate::gui::state::{ActiveDialog, PakOpsState};
+use crate::gui::state::{ActiveDialog, PakOpsState, configured_output_dir};
+use crate::gui::tabs::editor::track_recent_pak;
 
 /// Extract a PAK file via file dialog
 pub fn extract_pak_file(state: PakOpsState) {
@@ -26,6 +27,7 @@ pub fn extract_pak_file(state: PakOpsState) {
     let Some(pak_file) = dialog.pick_file() else {
         return;
     };
+    track_recent_pak(&pak_file.to_string_lossy());
 
     // Update working directory
     if let Some(parent) = pak_file.parent() {
@@ -36,7 +38,10 @@ pub fn extract_pak_file(state: PakOpsState) {
 
     let dest_dialog = rfd::FileDialog::new()
         .set_title("Select Extraction Destination")
-        .set_directory(pak_file.parent().unwrap_or(Path::new("/")));
+        .set_directory(
+            configured_output_dir()
+                .unwrap_or_else(|| pak_file.parent().unwrap_or(Path::new("/")).to_path_buf()),
+        );
 
     let Some(dest_dir) = dest_dialog.pick_folder() else {
         return;
@@ -60,23 +65,42 @@ pub fn extract_pak_file(state: PakOpsState) {
 
     let pak_path = pak_file.to_string_lossy().to_string();
     let dest_path = dest_dir.to_string_lossy().to_string();
+    let pipeline = state.extraction_pipeline();
 
     let send = create_result_sender(state.clone());
     let progress_sender = create_progress_sender(state);
 
     thread::spawn(move || {
-        let result = maclarian::pak::PakOperations::extract_with_progress(
-            &pak_path,
-            &dest_path,
-            &progress_sender,
-        );
+        let result = if pipeline.processes_gr2() {
+            pipeline
+                .extract_all(
+                    Path::new(&pak_path),
+                    Path::new(&dest_path),
+                    &progress_sender,
+                )
+                .map(|smart_result| {
+                    format!(
+                        "{} GR2s processed, {} GLB created, {} textures extracted",
+                        smart_result.gr2s_processed,
+                        smart_result.glb_files_created,
+                        smart_result.textures_extracted
+                    )
+                })
+        } else {
+            maclarian::pak::PakOperations::extract_with_progress(
+                &pak_path,
+                &dest_path,
+                &progress_sender,
+            )
+            .map(|()| String::new())
+        };
 
         let files = maclarian::pak::PakOperations::list(&pak_path).unwrap_or_default();
 
         let pak_result = match result {
-            Ok(_) => PakResult::ExtractDone {
+            Ok(message) => PakResult::ExtractDone {
                 success: true,
-                message: String::new(),
+                message,
                 files,
                 dest: dest_path,
             },
@@ -109,6 +133,7 @@ pub fn extract_individual_files(state: PakOpsState) {
     let Some(pak_file) = dialog.pick_file() else {
         return;
     };
+    track_recent_pak(&pak_file.to_string_lossy());
 
     if let Some(parent) = pak_file.parent() {
         state
@@ -187,7 +212,12 @@ pub fn execute_individual_extract(state: PakOpsState) {
     // Ask for destination
     let dest_dialog = rfd::FileDialog::new()
         .set_title("Select Extraction Destination")
-        .set_directory(Path::new(&pak_path).parent().unwrap_or(Path::new("/")));
+        .set_directory(configured_output_dir().unwrap_or_else(|| {
+            Path::new(&pak_path)
+                .parent()
+                .unwrap_or(Path::new("/"))
+                .to_path_buf()
+        }));
 
     let Some(dest_dir) = dest_dialog.pick_folder() else {
         return;
@@ -235,11 +265,10 @@ pub fn execute_individual_extract(state: PakOpsState) {
                 .with_bg3_path(game_data.map(std::path::PathBuf::from))
                 .with_virtual_textures_path(None::<std::path::PathBuf>); // Uses game data path for VT lookup
 
-            let result = maclarian::pak::extract_files_smart(
-                &pak_path,
-                &dest_path,
+            let result = maclarian::pak::ExtractionPipeline::new(extraction_opts).extract_files(
+                Path::new(&pak_path),
+                Path::new(&dest_path),
                 &selected,
-                extraction_opts,
                 &progress_sender,
             );
 
@@ -297,6 +326,7 @@ pub fn execute_individual_extract(state: PakOpsState) {
 /// Extract individual files from a dropped PAK file (shows file selection dialog)
 pub fn extract_individual_dropped_file(state: PakOpsState, pak_path: String) {
     state.clear_results();
+    track_recent_pak(&pak_path);
 
     let pak_name = Path::new(&pak_path)
         .file_name()
@@ -342,6 +372,7 @@ pub fn extract_individual_dropped_file(state: PakOpsState, pak_path: String) {
 /// Extract a dropped PAK file
 pub fn extract_dropped_file(state: PakOpsState, pak_path: String) {
     state.clear_results();
+    track_recent_pak(&pak_path);
 
     let pak_name = Path::new(&pak_path)
         .file_name()
@@ -351,7 +382,12 @@ pub fn extract_dropped_file(state: PakOpsState, pak_path: String) {
     // Ask for destination
     let dest_dialog = rfd::FileDialog::new()
         .set_title("Select Extraction Destination")
-        .set_directory(Path::new(&pak_path).parent().unwrap_or(Path::new("/")));
+        .set_directory(configured_output_dir().unwrap_or_else(|| {
+            Path::new(&pak_path)
+                .parent()
+                .unwrap_or(Path::new("/"))
+                .to_path_buf()
+        }));
 
     let Some(dest_dir) = dest_dialog.pick_folder() else {
         state.dropped_file.set(None);
@@ -8,6 +8,7 @@ use super::super::types::{
     PakResult, create_progress_sender, create_result_sender, get_shared_progress,
 };
 use crate::gui::state::{ActiveDialog, PakOpsState};
+use crate::gui::tabs::editor::track_recent_pak;
 
 /// List contents of a PAK file via file dialog
 pub fn list_pak_contents(state: PakOpsState) {
@@ -26,6 +27,7 @@ pub fn list_pak_contents(state: PakOpsState) {
     let Some(pak_file) = dialog.pick_file() else {
         return;
     };
+    track_recent_pak(&pak_file.to_string_lossy());
 
     if let Some(parent) = pak_file.parent() {
         state
@@ -80,6 +82,7 @@ pub fn list_pak_contents(state: PakOpsState) {
 /// List contents of a dropped PAK file
 pub fn list_dropped_file(state: PakOpsState, pak_path: String) {
     state.clear_results();
+    track_recent_pak(&pak_path);
 
     let pak_name = Path::new(&pak_path)
         .file_name()
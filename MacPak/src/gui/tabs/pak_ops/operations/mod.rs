@@ -15,8 +15,8 @@ mod validate;
 
 pub use batch::{batch_create_paks, batch_extract_paks};
 pub use create::{
-    create_pak_file, create_pak_from_dropped_folder, execute_create_pak, rebuild_pak_file,
-    rebuild_pak_from_dropped_folder,
+    confirm_create_pak, create_pak_file, create_pak_from_dropped_folder, execute_create_pak,
+    rebuild_pak_file, rebuild_pak_from_dropped_folder,
 };
 pub use extract::{
     execute_individual_extract, extract_dropped_file, extract_individual_dropped_file,
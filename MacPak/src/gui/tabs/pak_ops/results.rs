@@ -6,6 +6,7 @@ use floem::text::Weight;
 use floem::views::{VirtualDirection, VirtualItemSize, text_input, virtual_list};
 use im::Vector as ImVector;
 
+use crate::gui::shared::colors;
 use crate::gui::state::PakOpsState;
 
 const LOG_ITEM_HEIGHT: f64 = 22.0;
@@ -106,10 +107,10 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
                     .height(26.0)
                     .padding_horiz(8.0)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .border_radius(4.0)
                     .font_size(12.0)
-                    .background(Color::WHITE)
+                    .background(colors().bg_base)
                     .margin_right(8.0)
             }),
             // Show Failures Only toggle button
@@ -134,14 +135,14 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
                     .margin_right(8.0);
 
                 if is_active {
-                    s.background(Color::rgb8(211, 47, 47)).color(Color::WHITE)
+                    s.background(colors().error).color(colors().text_on_accent)
                 } else if has_failures {
-                    s.background(Color::rgb8(255, 235, 235))
-                        .color(Color::rgb8(180, 30, 30))
-                        .hover(|s| s.background(Color::rgb8(255, 220, 220)))
+                    s.background(colors().error_bg)
+                        .color(colors().error)
+                        .hover(|s| s.background(colors().error_bg))
                 } else {
-                    s.background(Color::rgb8(240, 240, 240))
-                        .color(Color::rgb8(150, 150, 150))
+                    s.background(colors().bg_elevated)
+                        .color(colors().text_muted)
                 }
             })
             .action(move || {
@@ -153,9 +154,9 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
                     s.padding_horiz(12.0)
                         .padding_vert(5.0)
                         .font_size(11.0)
-                        .background(Color::rgb8(240, 240, 240))
+                        .background(colors().bg_elevated)
                         .border_radius(4.0)
-                        .hover(|s| s.background(Color::rgb8(220, 220, 220)))
+                        .hover(|s| s.background(colors().bg_selected))
                 })
                 .action(move || {
                     state_clear.clear_results();
@@ -185,11 +186,11 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
                             .font_size(11.0)
                             .font_family("Monaco, Menlo, monospace".to_string());
                         if is_error {
-                            s.color(Color::rgb8(180, 30, 30))
+                            s.color(colors().error)
                         } else if is_success {
-                            s.color(Color::rgb8(46, 125, 50))
+                            s.color(colors().success)
                         } else {
-                            s.color(Color::rgb8(60, 60, 60))
+                            s.color(colors().text_primary)
                         }
                     }))
                     .style(move |s| {
@@ -199,7 +200,7 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
                             .padding_vert(2.0)
                             .padding_horiz(8.0);
                         if is_error {
-                            s.background(Color::rgb8(255, 235, 235))
+                            s.background(colors().error_bg)
                         } else {
                             s
                         }
@@ -213,9 +214,9 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
                 .flex_grow(1.0)
                 .flex_basis(0.0)
                 .min_height(0.0)
-                .background(Color::rgb8(250, 250, 250))
+                .background(colors().bg_surface)
                 .border(1.0)
-                .border_color(Color::rgb8(220, 220, 220))
+                .border_color(colors().border)
                 .border_radius(4.0)
         }),
     ))
@@ -226,9 +227,9 @@ fn results_log_section(state: PakOpsState) -> impl IntoView {
             .flex_grow(1.0)
             .flex_basis(0.0)
             .padding(16.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
             .border_radius(6.0)
     })
 }
@@ -9,8 +9,9 @@ use super::operations::{
     extract_pak_file, list_pak_contents, rebuild_pak_file,
 };
 use super::results::is_error_message;
-use crate::gui::shared::{card_style, drop_zone};
-use crate::gui::state::{ActiveDialog, PakOpsState};
+use super::widgets::{conflict_selector, layout_selector};
+use crate::gui::shared::{card_style, colors, drop_zone};
+use crate::gui::state::{ActiveDialog, BatchExtractLayout, PakOpsState};
 
 pub fn header_section(state: PakOpsState) -> impl IntoView {
     h_stack((
@@ -32,11 +33,9 @@ pub fn header_section(state: PakOpsState) -> impl IntoView {
                                 .border_radius(4.0)
                                 .font_size(12.0);
                             if is_error {
-                                s.background(Color::rgb8(255, 235, 235))
-                                    .color(Color::rgb8(180, 30, 30))
+                                s.background(colors().error_bg).color(colors().error)
                             } else {
-                                s.background(Color::rgb8(232, 245, 233))
-                                    .color(Color::rgb8(46, 125, 50))
+                                s.background(colors().success_bg).color(colors().success)
                             }
                         })
                         .into_any()
@@ -49,19 +48,21 @@ pub fn header_section(state: PakOpsState) -> impl IntoView {
             .padding(16.0)
             .gap(8.0)
             .items_center()
-            .background(Color::WHITE)
+            .background(colors().bg_base)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
-/// Main operations row with 3 columns
+/// Main operations row with 4 columns
 pub fn operations_row(state: PakOpsState) -> impl IntoView {
     h_stack((
         // Extract operations group
         extract_group(state.clone()),
         // Create operations group
         create_group(state.clone()),
+        // Batch extraction group
+        batch_group(state.clone()),
         // Drop zone
         pak_drop_zone(state),
     ))
@@ -72,7 +73,6 @@ fn extract_group(state: PakOpsState) -> impl IntoView {
     let state1 = state.clone();
     let state2 = state.clone();
     let state3 = state.clone();
-    let state4 = state.clone();
 
     v_stack((
         // Extract PAK button
@@ -87,14 +87,88 @@ fn extract_group(state: PakOpsState) -> impl IntoView {
         operation_button("📄 Extract Individual Files", state.clone(), move || {
             extract_individual_files(state3.clone());
         }),
-        // Batch Extract button
+        gr2_processing_group(state),
+    ))
+    .style(|s| card_style(s).flex_grow(1.0).flex_basis(0.0).gap(8.0))
+}
+
+/// Extract every PAK in a folder into one destination
+fn batch_group(state: PakOpsState) -> impl IntoView {
+    let state1 = state.clone();
+    let layout = state.batch_layout;
+    let merged = move || layout.get() == BatchExtractLayout::Merged;
+
+    v_stack((
         operation_button("📦 Batch Extract PAKs", state.clone(), move || {
-            batch_extract_paks(state4.clone());
+            batch_extract_paks(state1.clone());
+        }),
+        label(|| "Destination layout")
+            .style(|s| s.font_size(12.0).font_weight(Weight::BOLD).margin_top(4.0)),
+        layout_selector(layout),
+        label(|| "When a file already exists").style(move |s| {
+            s.font_size(12.0)
+                .font_weight(Weight::BOLD)
+                .margin_top(4.0)
+                .color(if merged() {
+                    colors().text_primary
+                } else {
+                    colors().text_disabled
+                })
         }),
+        conflict_selector(state.batch_conflict, merged),
+        label(|| "PAKs are extracted in path order, so with Overwrite the last one wins")
+            .style(|s| s.font_size(11.0).color(colors().text_muted)),
     ))
     .style(|s| card_style(s).flex_grow(1.0).flex_basis(0.0).gap(8.0))
 }
 
+/// GR2 processing applied by "Extract PAK File"
+fn gr2_processing_group(state: PakOpsState) -> impl IntoView {
+    let convert_gr2 = state.pipeline_convert_gr2;
+    let extract_textures = state.pipeline_extract_textures;
+    let convert_png = state.pipeline_convert_png;
+
+    v_stack((
+        label(|| "GR2 processing")
+            .style(|s| s.font_size(12.0).font_weight(Weight::BOLD).margin_top(4.0)),
+        pipeline_checkbox("Convert GR2 to GLB", convert_gr2, || true),
+        pipeline_checkbox("Extract textures", extract_textures, || true),
+        pipeline_checkbox("Convert textures to PNG", convert_png, || true),
+        pipeline_checkbox(
+            "Delete GR2 after conversion",
+            state.pipeline_delete_gr2,
+            move || convert_gr2.get(),
+        ),
+        pipeline_checkbox(
+            "Keep DDS after PNG conversion",
+            state.pipeline_keep_dds,
+            move || convert_png.get(),
+        ),
+    ))
+    .style(|s| s.gap(4.0))
+}
+
+fn pipeline_checkbox(
+    text: &'static str,
+    value: RwSignal<bool>,
+    enabled: impl Fn() -> bool + Copy + 'static,
+) -> impl IntoView {
+    h_stack((
+        checkbox(move || value.get())
+            .on_update(move |checked| value.set(checked))
+            .disabled(move || !enabled())
+            .style(|s| s.margin_right(6.0)),
+        label(move || text).style(move |s| {
+            s.font_size(12.0).color(if enabled() {
+                colors().text_primary
+            } else {
+                colors().text_disabled
+            })
+        }),
+    ))
+    .style(|s| s.items_center())
+}
+
 fn create_group(state: PakOpsState) -> impl IntoView {
     let state1 = state.clone();
     let state2 = state.clone();
@@ -176,15 +250,15 @@ fn operation_button(
                 .border_radius(6.0);
 
             if busy {
-                s.background(Color::rgb8(230, 230, 230))
-                    .border_color(Color::rgb8(210, 210, 210))
-                    .color(Color::rgb8(160, 160, 160))
+                s.background(colors().bg_hover)
+                    .border_color(colors().border)
+                    .color(colors().text_muted)
             } else {
-                s.background(Color::rgb8(245, 245, 245))
-                    .border_color(Color::rgb8(200, 200, 200))
+                s.background(colors().bg_elevated)
+                    .border_color(colors().border_strong)
                     .hover(|s| {
-                        s.background(Color::rgb8(230, 230, 230))
-                            .border_color(Color::rgb8(180, 180, 180))
+                        s.background(colors().bg_hover)
+                            .border_color(colors().border_strong)
                     })
             }
         })
@@ -3,11 +3,12 @@
 use floem::ext_event::create_ext_action;
 use floem::prelude::*;
 use floem_reactive::Scope;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 
 use crate::gui::state::{ActiveDialog, PakOpsState};
 use maclarian::pak::PakProgress;
+use maclarian::utils::CancelToken;
 
 /// Result type for background PAK operations
 pub enum PakResult {
@@ -37,6 +38,8 @@ pub enum PakResult {
     BatchExtractDone {
         success_count: usize,
         fail_count: usize,
+        /// PAKs not started because the batch was cancelled
+        skipped_count: usize,
         results: Vec<String>,
         dest: String,
     },
@@ -74,6 +77,10 @@ pub struct SharedProgress {
     pub total: Arc<AtomicU32>,
     /// Current progress message
     pub message: Arc<Mutex<String>>,
+    /// Whether the running operation checks its cancel token (shows the Cancel button)
+    pub cancellable: Arc<AtomicBool>,
+    /// Token of the running operation, replaced by [`reset`](Self::reset)
+    cancel_token: Arc<Mutex<CancelToken>>,
 }
 
 impl SharedProgress {
@@ -83,6 +90,8 @@ impl SharedProgress {
             current: Arc::new(AtomicU32::new(0)),
             total: Arc::new(AtomicU32::new(0)),
             message: Arc::new(Mutex::new(String::new())),
+            cancellable: Arc::new(AtomicBool::new(false)),
+            cancel_token: Arc::new(Mutex::new(CancelToken::new())),
         }
     }
 
@@ -103,6 +112,42 @@ impl SharedProgress {
         }
     }
 
+    /// Update progress of step `current`/`total` within item `item`/`items`
+    ///
+    /// The count shows the item, and the percentage covers the whole run.
+    pub fn update_nested(
+        &self,
+        item: usize,
+        items: usize,
+        current: usize,
+        total: usize,
+        description: &str,
+    ) {
+        self.update(item, items, description);
+        if items > 0 && total > 0 {
+            let done = item.saturating_sub(1) as f64 + current as f64 / total as f64;
+            let pct = (done / items as f64 * 100.0) as u32;
+            self.progress_pct.store(pct.min(100), Ordering::SeqCst);
+        }
+    }
+
+    /// Update progress of item `current`/`total`, with the percentage taken
+    /// from `done`/`bytes` so it keeps moving within large items
+    pub fn update_bytes(
+        &self,
+        current: usize,
+        total: usize,
+        done: u64,
+        bytes: u64,
+        description: &str,
+    ) {
+        self.update(current, total, description);
+        if bytes > 0 {
+            let pct = (done as f64 / bytes as f64 * 100.0) as u32;
+            self.progress_pct.store(pct.min(100), Ordering::SeqCst);
+        }
+    }
+
     /// Get current progress percentage (0-100)
     pub fn get_pct(&self) -> u32 {
         self.progress_pct.load(Ordering::SeqCst)
@@ -121,8 +166,34 @@ impl SharedProgress {
         self.message.lock().map(|m| m.clone()).unwrap_or_default()
     }
 
+    /// Mark the running operation as one that stops when cancelled, and
+    /// return the token it should check
+    pub fn allow_cancel(&self) -> CancelToken {
+        self.cancellable.store(true, Ordering::SeqCst);
+        self.cancel_token
+            .lock()
+            .map(|token| token.clone())
+            .unwrap_or_default()
+    }
+
+    /// Whether the running operation can be cancelled
+    pub fn is_cancellable(&self) -> bool {
+        self.cancellable.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running operation to stop after the files already in progress
+    pub fn cancel(&self) {
+        if let Ok(token) = self.cancel_token.lock() {
+            token.cancel();
+        }
+    }
+
     /// Reset progress to initial state (call when starting a new operation)
     pub fn reset(&self) {
+        self.cancellable.store(false, Ordering::SeqCst);
+        if let Ok(mut token) = self.cancel_token.lock() {
+            *token = CancelToken::new();
+        }
         self.progress_pct.store(0, Ordering::SeqCst);
         self.current.store(0, Ordering::SeqCst);
         self.total.store(0, Ordering::SeqCst);
@@ -170,7 +241,13 @@ pub fn create_progress_sender(_state: PakOpsState) -> impl Fn(&PakProgress) + Se
             .current_file
             .as_deref()
             .unwrap_or(progress.phase.as_str());
-        shared.update(progress.current, progress.total, description);
+        shared.update_bytes(
+            progress.current,
+            progress.total,
+            progress.bytes_done,
+            progress.bytes_total,
+            description,
+        );
     }
 }
 
@@ -214,6 +291,9 @@ pub fn handle_pak_result(state: PakOpsState, result: PakResult) {
                 // Clear results and batch add extracted files
                 state.clear_results();
                 state.add_results_batch(files.clone());
+                if !message.is_empty() {
+                    state.add_result(&message);
+                }
                 state
                     .status_message
                     .set(format!("Extracted {} files to {}", files.len(), dest));
@@ -236,9 +316,12 @@ pub fn handle_pak_result(state: PakOpsState, result: PakResult) {
             state.progress.set(1.0);
 
             if success {
-                // Clear results and batch add source files
+                // Clear results and batch add packed files, then the summary
                 state.clear_results();
                 state.add_results_batch(files.clone());
+                for line in message.lines() {
+                    state.add_result(line);
+                }
                 state
                     .status_message
                     .set(format!("Created {} ({} files)", pak_name, files.len()));
@@ -287,16 +370,25 @@ pub fn handle_pak_result(state: PakOpsState, result: PakResult) {
         PakResult::BatchExtractDone {
             success_count,
             fail_count,
+            skipped_count,
             results,
             dest,
         } => {
             state.progress.set(1.0);
 
-            let mut all_results = vec![
+            let summary = if skipped_count > 0 {
+                format!(
+                    "Batch extraction cancelled: {} succeeded, {} failed, {} not started",
+                    success_count, fail_count, skipped_count
+                )
+            } else {
                 format!(
                     "Batch extraction complete: {} succeeded, {} failed",
                     success_count, fail_count
-                ),
+                )
+            };
+            let mut all_results = vec![
+                summary,
                 format!("Destination: {}", dest),
                 "------------------------------------------------------------".to_string(),
             ];
@@ -2,7 +2,8 @@
 
 use floem::prelude::*;
 
-use crate::gui::state::PakCompression;
+use crate::gui::shared::colors;
+use crate::gui::state::{BatchExtractLayout, MergeConflictPolicy, PakCompression};
 
 /// Compression option button
 fn compression_button(compression: RwSignal<PakCompression>, opt: PakCompression) -> impl IntoView {
@@ -20,13 +21,13 @@ fn compression_button(compression: RwSignal<PakCompression>, opt: PakCompression
                 .border_radius(4.0);
 
             if is_selected() {
-                s = s.background(Color::rgb8(33, 150, 243)).color(Color::WHITE);
+                s = s.background(colors().accent).color(colors().text_on_accent);
             } else {
                 s = s
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
-                    .hover(|s| s.background(Color::rgb8(230, 230, 230)));
+                    .border_color(colors().border_strong)
+                    .hover(|s| s.background(colors().bg_hover));
             }
             s
         })
@@ -37,6 +38,7 @@ pub fn compression_selector(compression: RwSignal<PakCompression>) -> impl IntoV
     h_stack((
         compression_button(compression, PakCompression::Lz4Hc),
         compression_button(compression, PakCompression::Lz4),
+        compression_button(compression, PakCompression::Zlib),
         compression_button(compression, PakCompression::None),
     ))
     .style(|s| s.gap(4.0))
@@ -57,9 +59,9 @@ pub fn priority_input(priority: RwSignal<i32>) -> impl IntoView {
                     .height(30.0)
                     .items_center()
                     .justify_center()
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .border_radius(4.0)
             }),
         label(move || format!("{}", priority.get())).style(|s| {
@@ -67,9 +69,9 @@ pub fn priority_input(priority: RwSignal<i32>) -> impl IntoView {
                 .height(30.0)
                 .items_center()
                 .justify_center()
-                .background(Color::WHITE)
+                .background(colors().bg_base)
                 .border(1.0)
-                .border_color(Color::rgb8(200, 200, 200))
+                .border_color(colors().border_strong)
         }),
         button("+")
             .action(move || {
@@ -83,16 +85,90 @@ pub fn priority_input(priority: RwSignal<i32>) -> impl IntoView {
                     .height(30.0)
                     .items_center()
                     .justify_center()
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border(1.0)
-                    .border_color(Color::rgb8(200, 200, 200))
+                    .border_color(colors().border_strong)
                     .border_radius(4.0)
             }),
         label(|| "(0-100)".to_string()).style(|s| {
             s.font_size(11.0)
-                .color(Color::rgb8(120, 120, 120))
+                .color(colors().text_muted)
                 .margin_left(8.0)
         }),
     ))
     .style(|s| s.items_center())
 }
+
+/// Option button for one value of a batch extraction setting
+fn choice_button<T: Copy + PartialEq + 'static>(
+    value: RwSignal<T>,
+    opt: T,
+    text: &'static str,
+    enabled: impl Fn() -> bool + Copy + 'static,
+) -> impl IntoView {
+    button(text)
+        .action(move || value.set(opt))
+        .disabled(move || !enabled())
+        .style(move |s| {
+            let s = s
+                .padding_vert(6.0)
+                .padding_horiz(10.0)
+                .font_size(12.0)
+                .border_radius(4.0);
+
+            if !enabled() {
+                s.background(colors().bg_elevated)
+                    .color(colors().text_disabled)
+                    .border(1.0)
+                    .border_color(colors().border)
+            } else if value.get() == opt {
+                s.background(colors().accent).color(colors().text_on_accent)
+            } else {
+                s.background(colors().bg_elevated)
+                    .border(1.0)
+                    .border_color(colors().border_strong)
+                    .hover(|s| s.background(colors().bg_hover))
+            }
+        })
+}
+
+/// Whether batch extraction writes a folder per PAK or one merged tree
+pub fn layout_selector(layout: RwSignal<BatchExtractLayout>) -> impl IntoView {
+    h_stack((
+        choice_button(
+            layout,
+            BatchExtractLayout::PerPak,
+            BatchExtractLayout::PerPak.as_str(),
+            || true,
+        ),
+        choice_button(
+            layout,
+            BatchExtractLayout::Merged,
+            BatchExtractLayout::Merged.as_str(),
+            || true,
+        ),
+    ))
+    .style(|s| s.gap(4.0))
+}
+
+/// What a merged batch extraction does with files that already exist
+pub fn conflict_selector(
+    conflict: RwSignal<MergeConflictPolicy>,
+    enabled: impl Fn() -> bool + Copy + 'static,
+) -> impl IntoView {
+    h_stack((
+        choice_button(
+            conflict,
+            MergeConflictPolicy::Overwrite,
+            MergeConflictPolicy::Overwrite.as_str(),
+            enabled,
+        ),
+        choice_button(
+            conflict,
+            MergeConflictPolicy::KeepExisting,
+            MergeConflictPolicy::KeepExisting.as_str(),
+            enabled,
+        ),
+    ))
+    .style(|s| s.gap(4.0))
+}
@@ -10,6 +10,7 @@ use maclarian::converter::to_lsx;
 use maclarian::formats::lsf::parse_lsf_bytes;
 use maclarian::pak::PakOperations;
 
+use crate::gui::shared::colors;
 use crate::gui::state::{SearchResult, SearchState};
 
 /// A single match with surrounding context
@@ -55,7 +56,7 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
         }
 
         if let Some(ref file) = current_file {
-            let file_path = file.path.clone();
+            let file_path = file.file.path.clone();
             let already_loaded = loaded_path.get().as_ref() == Some(&file_path);
 
             // Only load if not already loaded
@@ -80,11 +81,11 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
             let current_file = file.get();
             let file_name = current_file
                 .as_ref()
-                .map(|f| f.name.clone())
+                .map(|f| f.file.name.clone())
                 .unwrap_or_else(|| "Unknown".to_string());
             let file_path = current_file
                 .as_ref()
-                .map(|f| f.path.clone())
+                .map(|f| f.file.path.clone())
                 .unwrap_or_default();
 
             container(
@@ -95,7 +96,7 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
                             label(move || format!("All Matches: {}", file_name.clone()))
                                 .style(|s| s.font_size(16.0).font_weight(Weight::BOLD)),
                             label(move || file_path.clone())
-                                .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+                                .style(|s| s.font_size(12.0).color(colors().text_secondary)),
                         )),
                         empty().style(|s| s.flex_grow(1.0)),
                         button("Close")
@@ -103,7 +104,7 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
                                 s.padding_horiz(16.0)
                                     .padding_vert(6.0)
                                     .background(Color::rgb8(100, 100, 100))
-                                    .color(Color::WHITE)
+                                    .color(colors().text_on_accent)
                                     .border_radius(4.0)
                             })
                             .action(move || {
@@ -119,15 +120,15 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
                         move |(loading, error, match_count)| {
                             if loading {
                                 label(|| "Loading matches...")
-                                    .style(|s| s.padding(40.0).color(Color::rgb8(100, 100, 100)))
+                                    .style(|s| s.padding(40.0).color(colors().text_secondary))
                                     .into_any()
                             } else if let Some(err) = error {
                                 label(move || format!("Error: {}", err))
-                                    .style(|s| s.padding(20.0).color(Color::rgb8(200, 50, 50)))
+                                    .style(|s| s.padding(20.0).color(colors().error))
                                     .into_any()
                             } else if match_count == 0 {
                                 label(|| "No matches found in file content")
-                                    .style(|s| s.padding(40.0).color(Color::rgb8(100, 100, 100)))
+                                    .style(|s| s.padding(40.0).color(colors().text_secondary))
                                     .into_any()
                             } else {
                                 // Show matches
@@ -156,7 +157,7 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
                                     .style(|s| {
                                         s.margin_top(12.0)
                                             .font_size(12.0)
-                                            .color(Color::rgb8(100, 100, 100))
+                                            .color(colors().text_secondary)
                                     })
                                     .into_any()
                             } else {
@@ -167,9 +168,9 @@ pub fn all_matches_dialog(state: SearchState) -> impl IntoView {
                 ))
                 .style(|s| {
                     s.padding(24.0)
-                        .background(Color::WHITE)
+                        .background(colors().bg_base)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(8.0)
                         .width(900.0)
                         .max_height(550.0)
@@ -211,7 +212,7 @@ fn match_row(m: MatchWithContext, index: usize) -> impl IntoView {
                 .style(|s| s.font_weight(Weight::SEMIBOLD).font_size(12.0)),
             label(move || format!("Line {}", line_num)).style(|s| {
                 s.font_size(11.0)
-                    .color(Color::rgb8(100, 100, 100))
+                    .color(colors().text_secondary)
                     .margin_left(8.0)
             }),
         )),
@@ -242,7 +243,7 @@ fn match_row(m: MatchWithContext, index: usize) -> impl IntoView {
         .style(|s| {
             s.width_full()
                 .max_height(200.0)
-                .background(Color::rgb8(248, 248, 248))
+                .background(colors().bg_surface)
                 .border_radius(4.0)
                 .margin_top(4.0)
         }),
@@ -251,7 +252,7 @@ fn match_row(m: MatchWithContext, index: usize) -> impl IntoView {
         s.width_full()
             .padding(8.0)
             .border(1.0)
-            .border_color(Color::rgb8(230, 230, 230))
+            .border_color(colors().border)
             .border_radius(4.0)
     })
 }
@@ -263,7 +264,7 @@ fn context_line(line_num: usize, text: String, is_match: bool) -> impl IntoView
         label(move || format!("{:4}", line_num)).style(|s| {
             s.font_size(11.0)
                 .font_family("monospace".to_string())
-                .color(Color::rgb8(150, 150, 150))
+                .color(colors().text_muted)
                 .width(40.0)
                 .flex_shrink(0.0)
         }),
@@ -274,10 +275,10 @@ fn context_line(line_num: usize, text: String, is_match: bool) -> impl IntoView
                 .font_family("monospace".to_string())
                 .flex_shrink(0.0);
             if is_match {
-                s.background(Color::rgb8(255, 255, 200))
+                s.background(colors().warning_bg)
                     .font_weight(Weight::MEDIUM)
             } else {
-                s.color(Color::rgb8(80, 80, 80))
+                s.color(colors().text_secondary)
             }
         }),
     ))
@@ -291,8 +292,8 @@ fn load_all_matches(
     is_loading: RwSignal<bool>,
     error_msg: RwSignal<Option<String>>,
 ) {
-    let pak_path = result.pak_path.clone();
-    let file_path = result.path.clone();
+    let pak_path = result.file.pak_file.clone();
+    let file_path = result.file.path.clone();
 
     let send = create_ext_action(
         Scope::new(),
@@ -12,11 +12,19 @@ use maclarian::pak::PakOperations;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use crate::gui::state::{DialogSource, DialogueState, EditorTabsState, SearchResult, SearchState};
+use crate::gui::state::{
+    DialogSource, DialogueState, EditorTabsState, PakSource, SearchResult, SearchState,
+};
+use crate::gui::tabs::open_pak_entry;
+use crate::search::FileType;
+use crate::thumbnails::has_thumbnail;
 
-use super::operations::{copy_to_clipboard, extract_single_result};
+use super::operations::{copy_to_clipboard, extract_selected_results, extract_single_result};
 
 /// Show context menu for a search result
+///
+/// When the row is part of a multi-selection, the menu starts with actions
+/// that apply to every selected row.
 pub fn show_search_result_context_menu(
     result: &SearchResult,
     state: SearchState,
@@ -24,32 +32,74 @@ pub fn show_search_result_context_menu(
     dialogue_state: DialogueState,
     active_tab: RwSignal<usize>,
 ) {
-    let result_clone = result.clone();
-    let result_for_open = result.clone();
-    let result_for_dialogue = result.clone();
-    let result_for_extract = result.clone();
-    let result_for_matches = result.clone();
-    let state_for_matches = state.clone();
+    let file = result.file.clone();
+    let file_type = file.file_type();
+    let pak_path = file.pak_file.clone();
 
     let mut menu = Menu::new("");
 
-    // Open in Editor (text files only)
-    let file_type = result.file_type.to_lowercase();
+    // Bulk actions for the selected rows
+    let selected = state.selected_results.get();
+    if selected.len() > 1 && selected.contains(&file.path) {
+        let count = selected.len();
+        let state_for_extract = state.clone();
+        menu = menu.entry(
+            MenuItem::new(format!("Extract {} Selected Files...", count)).action(move || {
+                extract_selected_results(state_for_extract.clone());
+            }),
+        );
+        let results = state.results;
+        menu = menu.entry(
+            MenuItem::new(format!("Copy {} Paths", count)).action(move || {
+                let paths: Vec<String> = results
+                    .get()
+                    .into_iter()
+                    .filter(|r| selected.contains(&r.file.path))
+                    .map(|r| r.file.path)
+                    .collect();
+                copy_to_clipboard(&paths.join("\n"));
+            }),
+        );
+        menu = menu.separator();
+    }
+
+    // Open in Editor (text formats; binary ones are converted in memory)
     if matches!(
-        file_type.as_str(),
-        "lsx" | "lsf" | "lsj" | "xml" | "json" | "txt"
+        file_type,
+        FileType::Lsx
+            | FileType::Lsf
+            | FileType::Lsb
+            | FileType::Lsbc
+            | FileType::Lsj
+            | FileType::Xml
+            | FileType::Json
     ) {
+        let source = PakSource {
+            pak_path: pak_path.to_string_lossy().to_string(),
+            internal_path: file.path.clone(),
+        };
+        let cache = state.pak_cache.clone();
         let editor_tabs = editor_tabs_state.clone();
         menu = menu.entry(MenuItem::new("Open in Editor").action(move || {
-            open_result_in_editor(&result_for_open, editor_tabs.clone(), active_tab);
+            open_pak_entry(source.clone(), cache.clone(), editor_tabs.clone());
+            active_tab.set(1); // Switch to Editor tab
+        }));
+    }
+
+    // Preview (images)
+    if has_thumbnail(file_type) {
+        let preview_file = state.preview_file;
+        let file_for_preview = file.clone();
+        menu = menu.entry(MenuItem::new("Preview").action(move || {
+            preview_file.set(Some(file_for_preview.clone()));
         }));
     }
 
     // Open in Dialogue (LSJ files only)
-    if file_type == "lsj" {
+    if file_type == FileType::Lsj {
         let dialogue = dialogue_state.clone();
-        let pak_path = result_for_dialogue.pak_path.clone();
-        let internal_path = result_for_dialogue.path.clone();
+        let pak_path = pak_path.clone();
+        let internal_path = file.path.clone();
         menu = menu.entry(MenuItem::new("Open in Dialogue").action(move || {
             open_in_dialogue(
                 dialogue.clone(),
@@ -63,6 +113,8 @@ pub fn show_search_result_context_menu(
     // Show All Matches in File (only if there are content matches)
     let has_content_matches = result.match_count.map_or(false, |n| n > 0);
     if has_content_matches {
+        let state_for_matches = state.clone();
+        let result_for_matches = result.clone();
         menu = menu.entry(MenuItem::new("Show All Matches").action(move || {
             state_for_matches
                 .all_matches_file
@@ -74,93 +126,44 @@ pub fn show_search_result_context_menu(
     menu = menu.separator();
 
     // Extract File (shows options dialog)
-    let state_for_extract = state.clone();
-    let internal_path = result_for_extract.path.clone();
-    let pak_path = result_for_extract.pak_path.clone();
-    menu = menu.entry(MenuItem::new("Extract File...").action(move || {
-        extract_single_result(
-            state_for_extract.clone(),
-            internal_path.clone(),
-            pak_path.clone(),
-        );
-    }));
+    {
+        let state_for_extract = state.clone();
+        let internal_path = file.path.clone();
+        let pak_path = pak_path.clone();
+        menu = menu.entry(MenuItem::new("Extract File...").action(move || {
+            extract_single_result(
+                state_for_extract.clone(),
+                internal_path.clone(),
+                pak_path.clone(),
+            );
+        }));
+    }
 
     // Copy Path
     {
-        let path = result_clone.path.clone();
+        let path = file.path.clone();
         menu = menu.entry(MenuItem::new("Copy Path").action(move || {
             copy_to_clipboard(&path);
         }));
     }
 
-    show_context_menu(menu, None);
-}
+    // Copy PAK Path
+    {
+        let pak_path = pak_path.to_string_lossy().to_string();
+        menu = menu.entry(MenuItem::new("Copy PAK Path").action(move || {
+            copy_to_clipboard(&pak_path);
+        }));
+    }
 
-/// Open a search result in the Editor tab
-fn open_result_in_editor(
-    result: &SearchResult,
-    editor_tabs_state: EditorTabsState,
-    active_tab: RwSignal<usize>,
-) {
-    use crate::gui::tabs::load_file_in_tab;
-    use floem::ext_event::create_ext_action;
-    use floem_reactive::Scope;
-    use maclarian::pak::PakOperations;
-    use std::env::temp_dir;
-
-    let result = result.clone();
-    let pak_path = result.pak_path.clone();
-    let file_path = result.path.clone();
-
-    // Run extraction in background thread
-    let send = create_ext_action(
-        Scope::new(),
-        move |extracted_path: Result<std::path::PathBuf, String>| {
-            match extracted_path {
-                Ok(path) => {
-                    load_file_in_tab(&path, editor_tabs_state.clone());
-                    active_tab.set(1); // Switch to Editor tab
-                }
-                Err(e) => {
-                    rfd::MessageDialog::new()
-                        .set_title("Extraction Failed")
-                        .set_description(&e)
-                        .show();
-                }
-            }
-        },
-    );
-
-    std::thread::spawn(move || {
-        // Create temp directory for extracted file
-        let temp_base = temp_dir().join("macpak_search_preview");
-        let pak_name = pak_path
-            .file_stem()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| "unknown".to_string());
-        let temp_dir = temp_base.join(&pak_name);
-
-        if let Err(e) = std::fs::create_dir_all(&temp_dir) {
-            send(Err(format!("Failed to create temp directory: {}", e)));
-            return;
-        }
-
-        // Extract the single file
-        match PakOperations::extract_files_with_progress(
-            &pak_path,
-            &temp_dir,
-            &[file_path.as_str()],
-            &|_| {},
-        ) {
-            Ok(_) => {
-                let extracted = temp_dir.join(&file_path);
-                send(Ok(extracted));
-            }
-            Err(e) => {
-                send(Err(format!("Extraction failed: {}", e)));
-            }
-        }
-    });
+    // Reveal the source PAK
+    menu = menu.entry(MenuItem::new("Show PAK in Finder").action(move || {
+        let _ = std::process::Command::new("open")
+            .arg("-R")
+            .arg(&pak_path)
+            .spawn();
+    }));
+
+    show_context_menu(menu, None);
 }
 
 /// Open a dialog file in the Dialogue tab, initializing caches if needed
@@ -5,6 +5,7 @@
 use floem::prelude::*;
 use floem::text::Weight;
 
+use crate::gui::shared::colors;
 use crate::gui::state::{ConfigState, SearchState};
 
 use super::operations::execute_extraction;
@@ -78,7 +79,7 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                     })
                     .style(|s| {
                         s.font_size(12.0)
-                            .color(Color::rgb8(100, 100, 100))
+                            .color(colors().text_secondary)
                             .margin_bottom(16.0)
                     }),
                     // GR2 options panel (only shown when GR2 files are in selection)
@@ -102,7 +103,7 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                             label(|| "Full Bundle")
                                 .style(|s| s.font_size(12.0).font_weight(Weight::MEDIUM)),
                             label(|| " (all options)")
-                                .style(|s| s.font_size(11.0).color(Color::rgb8(100, 100, 100))),
+                                .style(|s| s.font_size(11.0).color(colors().text_secondary)),
                         ))
                         .style(|s| s.items_center().margin_bottom(10.0)),
                         // Extract GR2
@@ -126,9 +127,9 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                             label(|| "Convert to GLB").style(move |s| {
                                 let disabled = convert_to_gltf.get();
                                 s.font_size(12.0).color(if disabled {
-                                    Color::rgb8(160, 160, 160)
+                                    colors().text_disabled
                                 } else {
-                                    Color::BLACK
+                                    colors().text_primary
                                 })
                             }),
                         ))
@@ -146,9 +147,9 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                             label(|| "Convert to glTF").style(move |s| {
                                 let disabled = convert_to_glb.get();
                                 s.font_size(12.0).color(if disabled {
-                                    Color::rgb8(160, 160, 160)
+                                    colors().text_disabled
                                 } else {
-                                    Color::BLACK
+                                    colors().text_primary
                                 })
                             }),
                         ))
@@ -175,7 +176,7 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                                 label(|| "Warning: BG3 game data path not set in Settings")
                                     .style(|s| {
                                         s.font_size(11.0)
-                                            .color(Color::rgb8(180, 80, 30))
+                                            .color(colors().warning)
                                             .margin_top(8.0)
                                             .margin_left(20.0)
                                     })
@@ -191,9 +192,9 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                             .width_full()
                             .margin_bottom(16.0)
                             .padding(12.0)
-                            .background(Color::rgb8(248, 248, 248))
+                            .background(colors().bg_surface)
                             .border(1.0)
-                            .border_color(Color::rgb8(220, 220, 220))
+                            .border_color(colors().border)
                             .border_radius(4.0);
                         if visible {
                             s
@@ -213,9 +214,9 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                                 s.padding_vert(8.0)
                                     .padding_horiz(20.0)
                                     .margin_right(8.0)
-                                    .background(Color::rgb8(240, 240, 240))
+                                    .background(colors().bg_elevated)
                                     .border(1.0)
-                                    .border_color(Color::rgb8(200, 200, 200))
+                                    .border_color(colors().border_strong)
                                     .border_radius(4.0)
                             }),
                         button("Extract")
@@ -228,19 +229,19 @@ pub fn extract_dialog(state: SearchState, config_state: ConfigState) -> impl Int
                             .style(|s| {
                                 s.padding_vert(8.0)
                                     .padding_horiz(20.0)
-                                    .background(Color::rgb8(33, 150, 243))
-                                    .color(Color::WHITE)
+                                    .background(colors().accent)
+                                    .color(colors().text_on_accent)
                                     .border_radius(4.0)
-                                    .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                                    .hover(|s| s.background(colors().accent_hover))
                             }),
                     ))
                     .style(|s| s.width_full()),
                 ))
                 .style(|s| {
                     s.padding(24.0)
-                        .background(Color::WHITE)
+                        .background(colors().bg_base)
                         .border(1.0)
-                        .border_color(Color::rgb8(200, 200, 200))
+                        .border_color(colors().border_strong)
                         .border_radius(8.0)
                         .width(450.0)
                         .box_shadow_blur(20.0)
@@ -0,0 +1,183 @@
+//! Index header strip: index stats plus building, saving and loading indexes
+
+use std::path::PathBuf;
+
+use floem::prelude::*;
+
+use crate::gui::shared::colors;
+use crate::gui::state::{IndexStatus, SearchState, configured_index_dir};
+
+use super::operations::{build_index, find_pak_files, load_index_from, save_index_to};
+
+pub fn index_bar(state: SearchState) -> impl IntoView {
+    let index_status = state.index_status;
+    let index_stats = state.index_stats;
+    let index_message = state.index_message;
+    let build_fulltext = state.build_fulltext;
+    let skip_media_paks = state.skip_media_paks;
+    let busy = move || matches!(index_status.get(), IndexStatus::Building { .. });
+
+    let state_paks = state.clone();
+    let state_folder = state.clone();
+    let state_save = state.clone();
+    let state_load = state;
+
+    h_stack((
+        // Counts
+        label(move || match index_stats.get() {
+            Some(stats) => format!(
+                "{} files · {} PAKs · {} full-text docs",
+                stats.file_count, stats.pak_count, stats.fulltext_doc_count
+            ),
+            None => "No index loaded".to_string(),
+        })
+        .style(|s| s.font_size(12.0).color(colors().text_secondary)),
+        // Staleness warning
+        label(move || {
+            let stale = index_stats
+                .get()
+                .map(|stats| stats.stale_paks.len())
+                .unwrap_or(0);
+            if stale == 1 {
+                "⚠ 1 PAK changed since indexing".to_string()
+            } else {
+                format!("⚠ {} PAKs changed since indexing", stale)
+            }
+        })
+        .style(move |s| {
+            let stale = index_stats.with(|stats| {
+                stats
+                    .as_ref()
+                    .is_some_and(|stats| !stats.stale_paks.is_empty())
+            });
+            let s = s.font_size(12.0).color(colors().warning);
+            if stale {
+                s
+            } else {
+                s.display(floem::style::Display::None)
+            }
+        }),
+        label(move || index_message.get()).style(|s| {
+            s.font_size(11.0)
+                .color(colors().text_muted)
+                .text_ellipsis()
+                .min_width(0.0)
+        }),
+        empty().style(|s| s.flex_grow(1.0)),
+        checkbox(move || build_fulltext.get())
+            .on_update(move |checked| build_fulltext.set(checked))
+            .style(|s| s.margin_right(4.0)),
+        label(|| "Full-text").style(|s| s.font_size(12.0).margin_right(8.0)),
+        checkbox(move || skip_media_paks.get())
+            .on_update(move |checked| skip_media_paks.set(checked))
+            .style(|s| s.margin_right(4.0)),
+        label(|| "Skip media PAKs").style(|s| s.font_size(12.0).margin_right(8.0)),
+        bar_button("Index PAKs…", busy, move || {
+            if let Some(paks) = rfd::FileDialog::new()
+                .set_title("Select PAK Files to Index")
+                .add_filter("PAK Files", &["pak"])
+                .pick_files()
+            {
+                index_paks(state_paks.clone(), paks);
+            }
+        }),
+        bar_button("Index Folder…", busy, move || {
+            if let Some(dir) = rfd::FileDialog::new()
+                .set_title("Select Folder with PAK Files")
+                .pick_folder()
+            {
+                let paks = find_pak_files(&dir);
+                if paks.is_empty() {
+                    state_folder
+                        .index_message
+                        .set(format!("No PAK files in {}", dir.display()));
+                } else {
+                    index_paks(state_folder.clone(), paks);
+                }
+            }
+        }),
+        bar_button("Save Index…", busy, move || {
+            if !state_save.index_stats.with(|stats| {
+                stats
+                    .as_ref()
+                    .is_some_and(|stats| stats.fulltext_doc_count > 0)
+            }) {
+                state_save
+                    .index_message
+                    .set("Only indexes with full-text content can be saved".to_string());
+                return;
+            }
+            if let Some(dir) = index_folder_dialog("Save Index to Folder").pick_folder() {
+                save_index_to(state_save.clone(), dir);
+            }
+        }),
+        bar_button("Load Index…", busy, move || {
+            if let Some(dir) = index_folder_dialog("Select Saved Index Folder").pick_folder() {
+                if dir.join("metadata.json").exists() {
+                    load_index_from(state_load.clone(), dir);
+                } else {
+                    state_load
+                        .index_message
+                        .set(format!("{} is not a saved index", dir.display()));
+                }
+            }
+        }),
+    ))
+    .style(|s| {
+        s.width_full()
+            .height(36.0)
+            .padding_horiz(12.0)
+            .gap(8.0)
+            .items_center()
+            .background(colors().bg_surface)
+            .border_bottom(1.0)
+            .border_color(colors().border)
+    })
+}
+
+/// Folder picker that starts in the configured index folder
+fn index_folder_dialog(title: &str) -> rfd::FileDialog {
+    let dialog = rfd::FileDialog::new().set_title(title);
+    match configured_index_dir() {
+        Some(dir) => dialog.set_directory(dir),
+        None => dialog,
+    }
+}
+
+/// Index the given PAKs, asking first if an index is already loaded
+fn index_paks(state: SearchState, paks: Vec<PathBuf>) {
+    if state.is_indexed() {
+        let replace = rfd::MessageDialog::new()
+            .set_title("Replace Index?")
+            .set_description(format!(
+                "Indexing {} PAK files replaces the current index.\n\nContinue?",
+                paks.len()
+            ))
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show()
+            == rfd::MessageDialogResult::Yes;
+        if !replace {
+            return;
+        }
+    }
+    state.index_message.set(String::new());
+    state.pak_paths.set(paks);
+    build_index(state);
+}
+
+fn bar_button(
+    text: &'static str,
+    busy: impl Fn() -> bool + Copy + 'static,
+    action: impl Fn() + 'static,
+) -> impl IntoView {
+    button(text).action(action).disabled(busy).style(|s| {
+        s.padding_horiz(10.0)
+            .padding_vert(4.0)
+            .font_size(12.0)
+            .background(colors().bg_elevated)
+            .border(1.0)
+            .border_color(colors().border_strong)
+            .border_radius(4.0)
+            .hover(|s| s.background(colors().bg_hover))
+    })
+}
@@ -9,7 +9,9 @@
 mod all_matches_dialog;
 mod context_menu;
 mod extract_dialog;
+mod index_bar;
 mod operations;
+mod preview_dialog;
 mod results;
 mod toolbar;
 
@@ -19,7 +21,9 @@ use crate::gui::state::{AppState, ConfigState, DialogueState, EditorTabsState, S
 
 use all_matches_dialog::all_matches_dialog;
 use extract_dialog::extract_dialog;
+use index_bar::index_bar;
 use operations::{auto_load_cached_index, progress_overlay, search_overlay};
+use preview_dialog::preview_dialog;
 use results::{search_results, search_status_bar};
 use toolbar::search_toolbar;
 
@@ -37,6 +41,7 @@ pub fn search_tab(
     let active_filter = search_state.active_filter;
     v_stack((
         search_toolbar(search_state.clone(), config_state.clone()),
+        index_bar(search_state.clone()),
         search_results(
             search_state.clone(),
             active_filter,
@@ -51,6 +56,8 @@ pub fn search_tab(
         search_overlay(search_state.clone()),
         // All matches dialog - absolutely positioned
         all_matches_dialog(search_state.clone()),
+        // Image preview dialog - absolutely positioned
+        preview_dialog(search_state.clone()),
         // Extraction options dialog - absolutely positioned
         extract_dialog(search_state, config_state),
     ))
@@ -9,8 +9,10 @@ use floem::prelude::*;
 use floem_reactive::Scope;
 
 use crate::gui::state::{IndexStatus, SearchState};
+use crate::search::IndexStats;
+use maclarian::error::Error;
 
-use super::progress::INDEX_AUTO_LOADED;
+use super::progress::{INDEX_AUTO_LOADED, SEARCH_PROGRESS, report_index_progress};
 
 /// Get the standard cache directory for the search index
 pub fn get_index_cache_path() -> Option<PathBuf> {
@@ -37,6 +39,7 @@ pub fn auto_load_cached_index(state: SearchState) {
 
     let index = state.index.clone();
     let index_status = state.index_status;
+    let index_stats = state.index_stats;
 
     // Set a loading status
     index_status.set(IndexStatus::Building {
@@ -44,29 +47,28 @@ pub fn auto_load_cached_index(state: SearchState) {
     });
 
     // Load in background thread
-    let send = create_ext_action(
-        Scope::new(),
-        move |result: Result<(usize, usize), String>| {
-            match result {
-                Ok((file_count, pak_count)) => {
-                    index_status.set(IndexStatus::Ready {
-                        file_count,
-                        pak_count,
-                    });
-                    tracing::info!(
-                        "Auto-loaded cached index: {} files from {} PAKs",
-                        file_count,
-                        pak_count
-                    );
-                }
-                Err(e) => {
-                    // Silently fail - user can manually rebuild
-                    tracing::warn!("Failed to auto-load cached index: {}", e);
-                    index_status.set(IndexStatus::NotBuilt);
-                }
+    let send = create_ext_action(Scope::new(), move |result: Result<IndexStats, String>| {
+        match result {
+            Ok(stats) => {
+                let (file_count, pak_count) = (stats.file_count, stats.pak_count);
+                index_status.set(IndexStatus::Ready {
+                    file_count,
+                    pak_count,
+                });
+                index_stats.set(Some(stats));
+                tracing::info!(
+                    "Auto-loaded cached index: {} files from {} PAKs",
+                    file_count,
+                    pak_count
+                );
             }
-        },
-    );
+            Err(e) => {
+                // Silently fail - user can manually rebuild
+                tracing::warn!("Failed to auto-load cached index: {}", e);
+                index_status.set(IndexStatus::NotBuilt);
+            }
+        }
+    });
 
     std::thread::spawn(move || {
         let result = index
@@ -74,7 +76,7 @@ pub fn auto_load_cached_index(state: SearchState) {
             .map_err(|e| e.to_string())
             .and_then(|mut idx| {
                 idx.import_index(&cache_path).map_err(|e| e.to_string())?;
-                Ok((idx.file_count(), idx.pak_count()))
+                Ok(idx.stats())
             });
         send(result);
     });
@@ -109,3 +111,109 @@ pub fn auto_save_index(index: Arc<RwLock<crate::search::SearchIndex>>) {
         }
     });
 }
+
+/// Why a user-started index save or load didn't finish
+enum TransferError {
+    Cancelled,
+    Failed(String),
+}
+
+impl From<Error> for TransferError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Cancelled => TransferError::Cancelled,
+            e => TransferError::Failed(e.to_string()),
+        }
+    }
+}
+
+/// Save the index to a user-chosen directory in a background thread
+pub fn save_index_to(state: SearchState, dir: PathBuf) {
+    let index = state.index.clone();
+    let show_progress = state.show_progress;
+    let index_message = state.index_message;
+
+    show_progress.set(true);
+    SEARCH_PROGRESS.reset();
+    SEARCH_PROGRESS.set_active(true);
+
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<PathBuf, TransferError>| {
+            SEARCH_PROGRESS.set_active(false);
+            show_progress.set(false);
+            index_message.set(match result {
+                Ok(dir) => format!("Saved index to {}", dir.display()),
+                Err(TransferError::Cancelled) => "Save cancelled".to_string(),
+                Err(TransferError::Failed(e)) => format!("Save failed: {}", e),
+            });
+        },
+    );
+
+    std::thread::spawn(move || {
+        let cancel = SEARCH_PROGRESS.allow_cancel();
+        let result = match index.read() {
+            Ok(idx) => idx
+                .export_index_cancellable(&dir, &cancel, &report_index_progress)
+                .map(|()| dir)
+                .map_err(TransferError::from),
+            Err(e) => Err(TransferError::Failed(e.to_string())),
+        };
+        send(result);
+    });
+}
+
+/// Replace the index with one saved in a user-chosen directory, in a
+/// background thread. The current index is kept if loading fails.
+pub fn load_index_from(state: SearchState, dir: PathBuf) {
+    let index = state.index.clone();
+    let index_status = state.index_status;
+    let index_stats = state.index_stats;
+    let show_progress = state.show_progress;
+    let index_message = state.index_message;
+    let previous_status = index_status.get_untracked();
+
+    index_status.set(IndexStatus::Building {
+        progress: "Loading index...".to_string(),
+    });
+    show_progress.set(true);
+    SEARCH_PROGRESS.reset();
+    SEARCH_PROGRESS.set_active(true);
+
+    let send = create_ext_action(
+        Scope::new(),
+        move |result: Result<(PathBuf, IndexStats), TransferError>| {
+            SEARCH_PROGRESS.set_active(false);
+            show_progress.set(false);
+            match result {
+                Ok((dir, stats)) => {
+                    index_status.set(IndexStatus::Ready {
+                        file_count: stats.file_count,
+                        pak_count: stats.pak_count,
+                    });
+                    index_stats.set(Some(stats));
+                    index_message.set(format!("Loaded index from {}", dir.display()));
+                }
+                Err(e) => {
+                    index_status.set(previous_status);
+                    index_message.set(match e {
+                        TransferError::Cancelled => "Load cancelled".to_string(),
+                        TransferError::Failed(e) => format!("Load failed: {}", e),
+                    });
+                }
+            }
+        },
+    );
+
+    std::thread::spawn(move || {
+        let cancel = SEARCH_PROGRESS.allow_cancel();
+        let result = match index.write() {
+            Ok(mut idx) => idx
+                .import_index_cancellable(&dir, &cancel, &report_index_progress)
+                .map(|()| (dir, idx.stats()))
+                .map_err(TransferError::from),
+            Err(e) => Err(TransferError::Failed(e.to_string())),
+        };
+        send(result);
+    });
+}
@@ -8,7 +8,7 @@ use floem::prelude::*;
 use floem_reactive::Scope;
 use maclarian::pak::PakOperations;
 
-use crate::gui::state::SearchState;
+use crate::gui::state::{SearchState, configured_output_dir};
 
 use super::progress::SEARCH_PROGRESS;
 
@@ -24,8 +24,8 @@ pub fn extract_selected_results(state: SearchState) {
     // Filter to selected results and collect (internal_path, pak_path)
     let to_extract: Vec<(String, PathBuf)> = all_results
         .into_iter()
-        .filter(|r| selected_paths.contains(&r.path))
-        .map(|r| (r.path, r.pak_path))
+        .filter(|r| selected_paths.contains(&r.file.path))
+        .map(|r| (r.file.path, r.file.pak_file))
         .collect();
 
     if to_extract.is_empty() {
@@ -68,10 +68,11 @@ pub fn execute_extraction(state: SearchState, config: crate::gui::state::ConfigS
     state.show_extract_dialog.set(false);
 
     // Get destination folder
-    let dest = match rfd::FileDialog::new()
-        .set_title("Extract Files To...")
-        .pick_folder()
-    {
+    let mut dialog = rfd::FileDialog::new().set_title("Extract Files To...");
+    if let Some(dir) = configured_output_dir() {
+        dialog = dialog.set_directory(dir);
+    }
+    let dest = match dialog.pick_folder() {
         Some(d) => d,
         None => {
             state.pending_extract_files.set(Vec::new());
@@ -7,13 +7,21 @@ use floem::prelude::*;
 use floem_reactive::Scope;
 
 use crate::gui::state::{IndexStatus, SearchState};
+use crate::search::IndexStats;
+use maclarian::error::Error;
 
 use super::cache::auto_save_index;
-use super::progress::SEARCH_PROGRESS;
+use super::progress::{SEARCH_PROGRESS, report_index_progress};
 
 /// Messages from background indexing thread
 pub enum IndexMessage {
-    Complete { file_count: usize, pak_count: usize },
+    Complete {
+        file_count: usize,
+        pak_count: usize,
+        stats: IndexStats,
+    },
+    /// Cancelled before the file index was finished
+    Cancelled,
     Error(String),
 }
 
@@ -35,22 +43,48 @@ pub fn find_pak_files(dir: &PathBuf) -> Vec<PathBuf> {
     paks
 }
 
+/// Where the PAKs to index come from
+enum IndexSource {
+    /// An explicit list of PAKs
+    Paks(Vec<PathBuf>),
+    /// Every PAK of a game install, in load order
+    Game { dir: PathBuf, skip_media: bool },
+}
+
 /// Build the search index in a background thread
+///
+/// Builds the file index and, if enabled, the full-text index. Cancelling
+/// during the full-text phase keeps the file index.
 pub fn build_index(state: SearchState) {
     let pak_paths = state.pak_paths.get();
     if pak_paths.is_empty() {
         return;
     }
+    spawn_build(state, IndexSource::Paks(pak_paths));
+}
+
+/// Build the search index from a game install in a background thread
+///
+/// `dir` can be the install folder or its `Data` folder. All PAKs below it
+/// are indexed in the game's load order, so patched files win.
+pub fn build_game_index(state: SearchState, dir: PathBuf) {
+    let skip_media = state.skip_media_paks.get_untracked();
+    spawn_build(state, IndexSource::Game { dir, skip_media });
+}
 
+fn spawn_build(state: SearchState, source: IndexSource) {
     let index = state.index.clone();
     let index_status = state.index_status;
+    let index_stats = state.index_stats;
     let show_progress = state.show_progress;
-    let pak_count_display = pak_paths.len();
+    let with_fulltext = state.build_fulltext.get_untracked();
+    let progress = match &source {
+        IndexSource::Paks(paks) => format!("Indexing {} PAK files...", paks.len()),
+        IndexSource::Game { .. } => "Indexing game install...".to_string(),
+    };
 
     // Set building status
-    index_status.set(IndexStatus::Building {
-        progress: format!("Indexing {} PAK files...", pak_count_display),
-    });
+    index_status.set(IndexStatus::Building { progress });
 
     // Show progress dialog for content indexing
     show_progress.set(true);
@@ -60,20 +94,26 @@ pub fn build_index(state: SearchState) {
     // Create action for sending result back to UI thread
     let send = create_ext_action(Scope::new(), move |msg: IndexMessage| {
         SEARCH_PROGRESS.set_active(false);
+        show_progress.set(false);
         match msg {
             IndexMessage::Complete {
                 file_count,
                 pak_count,
+                stats,
             } => {
-                show_progress.set(false);
                 index_status.set(IndexStatus::Ready {
                     file_count,
                     pak_count,
                 });
+                index_stats.set(Some(stats));
+            }
+            IndexMessage::Cancelled => {
+                index_status.set(IndexStatus::NotBuilt);
+                index_stats.set(None);
             }
             IndexMessage::Error(msg) => {
-                show_progress.set(false);
                 index_status.set(IndexStatus::Error(msg));
+                index_stats.set(None);
             }
         }
     });
@@ -83,59 +123,66 @@ pub fn build_index(state: SearchState) {
 
     // Spawn background thread
     std::thread::spawn(move || {
-        let build_succeeded;
-        match index.write() {
-            Ok(mut idx) => {
-                // Phase 1: Build metadata index (fast)
-                SEARCH_PROGRESS.set(0, 1, "Building file index...".to_string());
-
-                match idx.build_index(&pak_paths) {
-                    Ok(file_count) => {
-                        let pak_count = idx.pak_count();
-
-                        // Phase 2: Build fulltext index (slower, extracts content)
-                        // Progress is reported via SEARCH_PROGRESS in the callback
-                        let progress_callback = |progress: &crate::search::SearchProgress| {
-                            let name = progress
-                                .current_file
-                                .as_deref()
-                                .unwrap_or(progress.phase.as_str());
-                            SEARCH_PROGRESS.set(progress.current, progress.total, name.to_string());
-                        };
-
-                        match idx.build_fulltext_index(&progress_callback) {
-                            Ok(indexed) => {
-                                tracing::info!("Fulltext index built for {} files", indexed);
-                            }
-                            Err(e) => {
-                                tracing::warn!("Fulltext index failed: {}", e);
-                                // Continue anyway - deep search will use fallback
-                            }
-                        }
-
-                        send(IndexMessage::Complete {
-                            file_count,
-                            pak_count,
-                        });
-                        build_succeeded = true;
-                    }
-                    Err(e) => {
-                        send(IndexMessage::Error(format!("Index build failed: {}", e)));
-                        build_succeeded = false;
-                    }
-                }
-            }
+        let cancel = SEARCH_PROGRESS.allow_cancel();
+        let mut idx = match index.write() {
+            Ok(idx) => idx,
             Err(e) => {
                 send(IndexMessage::Error(format!(
                     "Failed to acquire lock: {}",
                     e
                 )));
-                build_succeeded = false;
+                return;
+            }
+        };
+
+        // Phase 1: Build metadata index (fast)
+        let built = match &source {
+            IndexSource::Paks(paks) => {
+                idx.build_index_cancellable(paks, &cancel, &report_index_progress)
+            }
+            IndexSource::Game { dir, skip_media } => {
+                idx.build_game_index_cancellable(dir, *skip_media, &cancel, &report_index_progress)
+            }
+        };
+        let file_count = match built {
+            Ok(file_count) => file_count,
+            Err(Error::Cancelled) => {
+                send(IndexMessage::Cancelled);
+                return;
+            }
+            Err(e) => {
+                send(IndexMessage::Error(format!("Index build failed: {}", e)));
+                return;
+            }
+        };
+
+        // Phase 2: Build fulltext index (slower, extracts content)
+        let mut fulltext_built = false;
+        if with_fulltext {
+            match idx.build_fulltext_index_cancellable(&cancel, &report_index_progress) {
+                Ok(indexed) => {
+                    tracing::info!("Fulltext index built for {} files", indexed);
+                    fulltext_built = true;
+                }
+                Err(Error::Cancelled) => {
+                    tracing::info!("Fulltext indexing cancelled, keeping file index");
+                }
+                Err(e) => {
+                    tracing::warn!("Fulltext index failed: {}", e);
+                    // Continue anyway - deep search will use fallback
+                }
             }
         }
 
-        // Auto-save index to cache after successful build
-        if build_succeeded {
+        send(IndexMessage::Complete {
+            file_count,
+            pak_count: idx.pak_count(),
+            stats: idx.stats(),
+        });
+        drop(idx);
+
+        // Auto-save index to cache (saving needs the fulltext index)
+        if fulltext_built {
             auto_save_index(index_for_save);
         }
     });
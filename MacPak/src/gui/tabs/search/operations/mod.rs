@@ -7,8 +7,8 @@ mod overlays;
 mod progress;
 mod search;
 
-pub use cache::auto_load_cached_index;
+pub use cache::{auto_load_cached_index, load_index_from, save_index_to};
 pub use extraction::{execute_extraction, extract_selected_results, extract_single_result};
-pub use indexing::{build_index, find_pak_files};
+pub use indexing::{build_game_index, build_index, find_pak_files};
 pub use overlays::{progress_overlay, search_overlay};
 pub use search::{copy_to_clipboard, perform_search};
@@ -7,6 +7,7 @@ use floem::prelude::*;
 use floem::text::Weight;
 use floem_reactive::create_effect;
 
+use crate::gui::shared::colors;
 use crate::gui::state::SearchState;
 
 use super::progress::SEARCH_PROGRESS;
@@ -28,6 +29,8 @@ fn search_progress_overlay(
     let polled_total = RwSignal::new(0usize);
     let polled_msg = RwSignal::new(String::new());
     let polled_pct = RwSignal::new(0u32);
+    let polled_cancellable = RwSignal::new(false);
+    let cancelling = RwSignal::new(false);
     let timer_active = RwSignal::new(false);
 
     // Polling function
@@ -36,10 +39,12 @@ fn search_progress_overlay(
         polled_total: RwSignal<usize>,
         polled_msg: RwSignal<String>,
         polled_pct: RwSignal<u32>,
+        polled_cancellable: RwSignal<bool>,
         show: RwSignal<bool>,
         timer_active: RwSignal<bool>,
     ) {
         let (current, total, msg) = SEARCH_PROGRESS.get();
+        polled_cancellable.set(SEARCH_PROGRESS.is_cancellable());
         polled_current.set(current);
         polled_total.set(total);
         if !msg.is_empty() {
@@ -58,6 +63,7 @@ fn search_progress_overlay(
                         polled_total,
                         polled_msg,
                         polled_pct,
+                        polled_cancellable,
                         show,
                         timer_active,
                     );
@@ -77,6 +83,8 @@ fn search_progress_overlay(
             polled_total.set(0);
             polled_msg.set(initial_msg.to_string());
             polled_pct.set(0);
+            polled_cancellable.set(false);
+            cancelling.set(false);
             timer_active.set(true);
 
             exec_after(Duration::from_millis(50), move |_| {
@@ -86,6 +94,7 @@ fn search_progress_overlay(
                         polled_total,
                         polled_msg,
                         polled_pct,
+                        polled_cancellable,
                         show,
                         timer_active,
                     );
@@ -120,13 +129,13 @@ fn search_progress_overlay(
                         })
                         .style(|s| {
                             s.font_size(13.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
                                 .margin_bottom(4.0)
                         }),
                         // Current file being processed
                         label(move || polled_msg.get()).style(|s| {
                             s.font_size(12.0)
-                                .color(Color::rgb8(120, 120, 120))
+                                .color(colors().text_muted)
                                 .margin_bottom(12.0)
                                 .text_ellipsis()
                                 .max_width(450.0)
@@ -136,26 +145,33 @@ fn search_progress_overlay(
                             let pct = polled_pct.get();
                             s.height_full()
                                 .width_pct(pct as f64)
-                                .background(Color::rgb8(33, 150, 243))
+                                .background(colors().accent)
                                 .border_radius(4.0)
                         }))
                         .style(|s| {
                             s.width_full()
                                 .height(8.0)
-                                .background(Color::rgb8(220, 220, 220))
+                                .background(colors().bg_selected)
                                 .border_radius(4.0)
                         }),
                         label(move || format!("{}%", polled_pct.get())).style(|s| {
                             s.font_size(12.0)
                                 .margin_top(8.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
+                        }),
+                        cancel_button(cancelling).style(move |s| {
+                            if polled_cancellable.get() {
+                                s.margin_top(12.0)
+                            } else {
+                                s.display(floem::style::Display::None)
+                            }
                         }),
                     ))
                     .style(|s| {
                         s.padding(24.0)
-                            .background(Color::WHITE)
+                            .background(colors().bg_base)
                             .border(1.0)
-                            .border_color(Color::rgb8(200, 200, 200))
+                            .border_color(colors().border_strong)
                             .border_radius(8.0)
                             .width(500.0)
                     }),
@@ -183,6 +199,22 @@ fn search_progress_overlay(
     })
 }
 
+/// Cancel button for operations that called `SEARCH_PROGRESS.allow_cancel()`
+fn cancel_button(cancelling: RwSignal<bool>) -> impl IntoView {
+    button(label(move || {
+        if cancelling.get() {
+            "Cancelling...".to_string()
+        } else {
+            "Cancel".to_string()
+        }
+    }))
+    .disabled(move || cancelling.get())
+    .action(move || {
+        SEARCH_PROGRESS.cancel();
+        cancelling.set(true);
+    })
+}
+
 /// Progress overlay shown during long-running indexing operations
 pub fn progress_overlay(state: SearchState) -> impl IntoView {
     search_progress_overlay("Indexing...", state.show_progress, true, "Preparing...")
@@ -3,9 +3,16 @@
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+use maclarian::utils::CancelToken;
+
+use crate::search::SearchProgress;
+
 /// Maximum results for fulltext search
 pub const MAX_RESULTS: usize = 50000;
 
+/// Edits allowed per word when fuzzy filename search is on
+pub const FUZZY_MAX_DISTANCE: u32 = 2;
+
 /// Shared progress state for search operations (thread-safe)
 #[derive(Default)]
 pub struct SharedSearchProgress {
@@ -13,6 +20,10 @@ pub struct SharedSearchProgress {
     total: AtomicUsize,
     message: Mutex<String>,
     active: AtomicBool,
+    /// Whether the running operation checks its cancel token (shows the Cancel button)
+    cancellable: AtomicBool,
+    /// Token of the running operation, replaced on reset
+    cancel_token: Mutex<CancelToken>,
 }
 
 impl SharedSearchProgress {
@@ -42,7 +53,36 @@ impl SharedSearchProgress {
         self.active.load(Ordering::SeqCst)
     }
 
+    /// Mark the running operation as one that stops when cancelled, and
+    /// return the token to pass to the search index's `*_cancellable` methods
+    pub fn allow_cancel(&self) -> CancelToken {
+        self.cancellable.store(true, Ordering::SeqCst);
+        self.cancel_token()
+    }
+
+    pub fn is_cancellable(&self) -> bool {
+        self.cancellable.load(Ordering::SeqCst)
+    }
+
+    /// Ask the running operation to stop
+    pub fn cancel(&self) {
+        self.cancel_token().cancel();
+    }
+
+    /// Token of the running operation
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel_token
+            .lock()
+            .map(|token| token.clone())
+            .unwrap_or_default()
+    }
+
+    /// Reset progress and start a new cancel token
     pub fn reset(&self) {
+        self.cancellable.store(false, Ordering::SeqCst);
+        if let Ok(mut token) = self.cancel_token.lock() {
+            *token = CancelToken::new();
+        }
         self.current.store(0, Ordering::SeqCst);
         self.total.store(0, Ordering::SeqCst);
         if let Ok(mut msg) = self.message.lock() {
@@ -51,6 +91,15 @@ impl SharedSearchProgress {
     }
 }
 
+/// Forward index build/save/load progress to the progress overlay
+pub fn report_index_progress(progress: &SearchProgress) {
+    let name = progress
+        .current_file
+        .as_deref()
+        .unwrap_or(progress.phase.as_str());
+    SEARCH_PROGRESS.set(progress.current, progress.total, name.to_string());
+}
+
 lazy_static::lazy_static! {
     pub static ref SEARCH_PROGRESS: Arc<SharedSearchProgress> = Arc::new(SharedSearchProgress::default());
     /// Track whether we've already attempted to auto-load the cached index
@@ -5,11 +5,13 @@ use std::collections::HashSet;
 use floem::ext_event::create_ext_action;
 use floem::prelude::*;
 use floem_reactive::Scope;
+use maclarian::utils::{GuidByteOrder, classify_uuid};
 
 use crate::gui::state::{SearchResult, SearchState};
 pub use crate::gui::utils::copy_to_clipboard;
+use crate::search::FullTextResult;
 
-use super::progress::{MAX_RESULTS, SEARCH_PROGRESS};
+use super::progress::{FUZZY_MAX_DISTANCE, MAX_RESULTS, SEARCH_PROGRESS};
 
 /// Messages from background search thread
 enum SearchMessage {
@@ -26,6 +28,7 @@ pub fn perform_search(state: SearchState) {
 
     let index = state.index.clone();
     let active_filter = state.active_filter.get();
+    let fuzzy = state.fuzzy.get();
     let is_searching = state.is_searching;
     let results_signal = state.results;
 
@@ -71,36 +74,42 @@ pub fn perform_search(state: SearchState) {
                     .unwrap_or(progress.phase.as_str());
                 SEARCH_PROGRESS.set(progress.current, progress.total, name.to_string());
             };
-            let ft_results = idx
-                .search_fulltext_with_progress(&query, MAX_RESULTS, &progress_callback)
-                .unwrap_or_default();
-
+            // A GUID is also looked for in the byte order LSF files store it in
+            let ft_results: Vec<(FullTextResult, GuidByteOrder)> =
+                match idx.search_fulltext_uuid(&query, MAX_RESULTS) {
+                    Some(matches) => matches.into_iter().map(|m| (m.result, m.order)).collect(),
+                    None => idx
+                        .search_fulltext_with_progress(&query, MAX_RESULTS, &progress_callback)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|r| (r, GuidByteOrder::Canonical))
+                        .collect(),
+                };
+
+            // Hits carry the index entry, so actions work on them like on filename matches
             ft_results
                 .into_iter()
-                .filter(|r| {
-                    active_filter.map_or(true, |ft| {
-                        r.file_type.to_lowercase() == ft.display_name().to_lowercase()
-                    })
-                })
-                .map(|r| {
+                .filter_map(|(r, order)| {
+                    let file = idx.get_by_path(&r.path)?;
+                    if active_filter.is_some_and(|ft| !file.matches_type(ft, idx.strict_types)) {
+                        return None;
+                    }
                     let match_count = if r.match_count > 0 {
                         Some(r.match_count)
                     } else {
                         None
                     };
-                    SearchResult {
-                        name: r.name,
-                        path: r.path,
-                        pak_file: r
-                            .pak_file
-                            .file_name()
-                            .map(|n| n.to_string_lossy().to_string())
-                            .unwrap_or_default(),
-                        file_type: r.file_type,
-                        pak_path: r.pak_file,
-                        context: r.snippet,
+                    let context = match order {
+                        GuidByteOrder::ByteSwapped => {
+                            r.snippet.map(|s| format!("[byte-swapped GUID] {}", s))
+                        }
+                        _ => r.snippet,
+                    };
+                    Some(SearchResult {
+                        file: file.into(),
+                        context,
                         match_count,
-                    }
+                    })
                 })
                 .collect()
         } else {
@@ -108,11 +117,32 @@ pub fn perform_search(state: SearchState) {
         };
 
         // 2. Get filename/path matches (ALL file types including images, audio, models)
+        // Ranked filename matches come first, then fuzzy matches (if enabled),
+        // then files matched only by path
+        let fuzzy_matches = if fuzzy {
+            idx.search_filename_fuzzy(&query, FUZZY_MAX_DISTANCE, active_filter)
+        } else {
+            Vec::new()
+        };
+        // Paths holding a GUID, in either byte order
+        let uuid_matches: Vec<_> = if classify_uuid(&query).is_some() {
+            idx.search_uuid(&query)
+                .into_iter()
+                .map(|m| m.entry)
+                .filter(|f| active_filter.map_or(true, |ft| f.matches_type(ft, idx.strict_types)))
+                .collect()
+        } else {
+            Vec::new()
+        };
         let filename_results: Vec<SearchResult> = idx
-            .search_path(&query, active_filter)
+            .search_filename(&query, active_filter)
             .into_iter()
+            .map(|m| m.entry)
+            .chain(fuzzy_matches.into_iter().map(|m| m.entry))
+            .chain(idx.search_path(&query, active_filter))
+            .chain(uuid_matches)
             .take(MAX_RESULTS)
-            .map(|f| SearchResult::from_indexed_file(f))
+            .map(SearchResult::from_indexed_file)
             .collect();
 
         // 3. Merge results with deduplication (fulltext results take priority - they have snippets)
@@ -122,14 +152,14 @@ pub fn perform_search(state: SearchState) {
 
         // Add fulltext results first (they have context snippets)
         for result in fulltext_results {
-            if seen_paths.insert(result.path.clone()) {
+            if seen_paths.insert(result.file.path.clone()) {
                 merged.push(result);
             }
         }
 
         // Add filename matches that weren't already found via fulltext
         for result in filename_results {
-            if seen_paths.insert(result.path.clone()) {
+            if seen_paths.insert(result.file.path.clone()) {
                 merged.push(result);
             }
         }
@@ -0,0 +1,81 @@
+//! Image preview dialog for search results
+
+use floem::prelude::*;
+use floem::text::Weight;
+
+use crate::gui::shared::{colors, thumbnail_cell};
+use crate::gui::state::SearchState;
+
+/// Edge length of the preview image
+const PREVIEW_SIZE: u32 = 512;
+
+/// Dialog showing a large preview of an image result (DDS, PNG, GTS)
+pub fn preview_dialog(state: SearchState) -> impl IntoView {
+    let preview_file = state.preview_file;
+
+    dyn_container(
+        move || preview_file.get(),
+        move |file| {
+            let Some(file) = file else {
+                return empty().into_any();
+            };
+            let name = file.name.clone();
+            let path = file.path.clone();
+
+            container(
+                v_stack((
+                    // Header
+                    h_stack((
+                        v_stack((
+                            label(move || name.clone())
+                                .style(|s| s.font_size(16.0).font_weight(Weight::BOLD)),
+                            label(move || path.clone())
+                                .style(|s| s.font_size(12.0).color(colors().text_secondary)),
+                        ))
+                        .style(|s| s.min_width(0.0)),
+                        empty().style(|s| s.flex_grow(1.0)),
+                        button("Close")
+                            .style(|s| {
+                                s.padding_horiz(16.0)
+                                    .padding_vert(6.0)
+                                    .background(colors().bg_elevated)
+                                    .color(colors().text_primary)
+                                    .border_radius(4.0)
+                            })
+                            .action(move || preview_file.set(None)),
+                    ))
+                    .style(|s| s.width_full().margin_bottom(16.0)),
+                    // Decoded in the background; shows the type icon until ready
+                    thumbnail_cell(Some(file), PREVIEW_SIZE, "🖼️".to_string())
+                        .style(|s| s.font_size(48.0)),
+                ))
+                .style(|s| s.items_center()),
+            )
+            .style(|s| {
+                s.padding(24.0)
+                    .background(colors().bg_base)
+                    .border(1.0)
+                    .border_color(colors().border_strong)
+                    .border_radius(8.0)
+                    .box_shadow_blur(20.0)
+                    .box_shadow_color(Color::rgba8(0, 0, 0, 50))
+            })
+            .into_any()
+        },
+    )
+    .style(move |s| {
+        if preview_file.get().is_some() {
+            s.position(floem::style::Position::Absolute)
+                .inset_top(0.0)
+                .inset_left(0.0)
+                .inset_bottom(0.0)
+                .inset_right(0.0)
+                .items_center()
+                .justify_center()
+                .background(Color::rgba8(0, 0, 0, 100))
+                .z_index(100)
+        } else {
+            s.display(floem::style::Display::None)
+        }
+    })
+}
@@ -7,6 +7,7 @@ use floem::text::Weight;
 use floem::views::{VirtualDirection, VirtualItemSize};
 use im::Vector as ImVector;
 
+use crate::gui::shared::{colors, thumbnail_cell};
 use crate::gui::state::{DialogueState, EditorTabsState, IndexStatus, SearchResult, SearchState};
 
 use super::context_menu::show_search_result_context_menu;
@@ -14,6 +15,9 @@ use super::context_menu::show_search_result_context_menu;
 /// Fixed row height for virtual list (must accommodate context snippets from deep search)
 const RESULT_ROW_HEIGHT: f64 = 72.0;
 
+/// Edge length of result thumbnails (fits the name and path lines)
+const THUMBNAIL_SIZE: u32 = 32;
+
 pub fn search_results(
     state: SearchState,
     active_filter: RwSignal<Option<FileType>>,
@@ -34,13 +38,10 @@ pub fn search_results(
 
         match filter {
             None => all_results,
-            Some(ft) => {
-                let filter_name = ft.display_name().to_lowercase();
-                all_results
-                    .into_iter()
-                    .filter(|r| r.file_type.to_lowercase() == filter_name)
-                    .collect()
-            }
+            Some(ft) => all_results
+                .into_iter()
+                .filter(|r| r.file.matches_type(ft, false))
+                .collect(),
         }
     };
 
@@ -57,14 +58,14 @@ pub fn search_results(
                     format!("{} of {} matches", filtered.len(), total)
                 }
             })
-            .style(|s| s.color(Color::rgb8(128, 128, 128)).font_size(12.0)),
+            .style(|s| s.color(colors().text_muted).font_size(12.0)),
         ))
         .style(|s| {
             s.width_full()
                 .padding(8.0)
-                .background(Color::rgb8(250, 250, 250))
+                .background(colors().bg_surface)
                 .border_bottom(1.0)
-                .border_color(Color::rgb8(220, 220, 220))
+                .border_color(colors().border)
         }),
         // Status overlay for empty/loading states
         dyn_container(
@@ -82,7 +83,7 @@ pub fn search_results(
                             s.width_full()
                                 .padding(40.0)
                                 .items_center()
-                                .color(Color::rgb8(128, 128, 128))
+                                .color(colors().text_muted)
                         })
                         .into_any()
                 } else if !matches!(status, IndexStatus::Ready { .. }) {
@@ -90,12 +91,12 @@ pub fn search_results(
                         label(|| "No Index Built").style(|s| {
                             s.font_size(18.0)
                                 .font_weight(Weight::SEMIBOLD)
-                                .color(Color::rgb8(80, 80, 80))
+                                .color(colors().text_secondary)
                         }),
                         label(
                             || "Click 'Build Index' to index PAK files from your BG3 installation.",
                         )
-                        .style(|s| s.color(Color::rgb8(120, 120, 120)).margin_top(8.0)),
+                        .style(|s| s.color(colors().text_muted).margin_top(8.0)),
                     ))
                     .style(|s| s.width_full().padding(40.0).items_center().justify_center())
                     .into_any()
@@ -104,12 +105,12 @@ pub fn search_results(
                         label(|| "Ready to Search").style(|s| {
                             s.font_size(18.0)
                                 .font_weight(Weight::SEMIBOLD)
-                                .color(Color::rgb8(80, 80, 80))
+                                .color(colors().text_secondary)
                         }),
                         label(|| "Search by filename, UUID, or enable Deep Search for content.")
-                            .style(|s| s.color(Color::rgb8(120, 120, 120)).margin_top(8.0)),
+                            .style(|s| s.color(colors().text_muted).margin_top(8.0)),
                         label(|| "Use filters to narrow results by file type.")
-                            .style(|s| s.color(Color::rgb8(140, 140, 140)).margin_top(4.0)),
+                            .style(|s| s.color(colors().text_muted).margin_top(4.0)),
                     ))
                     .style(|s| s.width_full().padding(40.0).items_center().justify_center())
                     .into_any()
@@ -124,7 +125,7 @@ pub fn search_results(
                 VirtualDirection::Vertical,
                 VirtualItemSize::Fixed(Box::new(|| RESULT_ROW_HEIGHT)),
                 move || filtered_results().into_iter().collect::<ImVector<_>>(),
-                |result| result.path.clone(),
+                |result| result.file.path.clone(),
                 {
                     let state = state_for_rows.clone();
                     let editor_tabs = editor_tabs_state.clone();
@@ -154,7 +155,7 @@ pub fn search_results(
         s.width_full()
             .height_full()
             .min_height(0.0)
-            .background(Color::WHITE)
+            .background(colors().bg_base)
     })
 }
 
@@ -165,18 +166,19 @@ fn search_result_row(
     dialogue_state: DialogueState,
     active_tab: RwSignal<usize>,
 ) -> impl IntoView {
-    let icon = get_type_icon(&result.file_type);
+    let icon = get_type_icon(result.file.file_type().display_name());
     let has_context = result.context.is_some();
     let context_text = result.context.clone().unwrap_or_default();
     let match_count = result.match_count;
 
     // Clone values that need to be used in multiple closures
-    let name = result.name.clone();
-    let path_display = result.path.clone();
-    let path_for_select = result.path.clone();
-    let path_for_select_check = result.path.clone();
-    let pak_file = result.pak_file.clone();
+    let name = result.file.name.clone();
+    let path_display = result.file.path.clone();
+    let path_for_select = result.file.path.clone();
+    let path_for_select_check = result.file.path.clone();
+    let pak_file = result.pak_name();
     let result_for_ctx = result.clone();
+    let indexed_file = Some(result.file.clone());
     let selected_results = state.selected_results;
 
     v_stack((
@@ -195,15 +197,15 @@ fn search_result_row(
                     });
                 })
                 .style(|s| s.margin_right(8.0)),
-            // Icon - fixed width
-            label(move || icon.to_string()).style(|s| s.width(30.0).flex_shrink(0.0)),
+            // Thumbnail, or the type icon - fixed width
+            thumbnail_cell(indexed_file, THUMBNAIL_SIZE, icon.to_string()),
             // File info - flexible, can shrink with text ellipsis
             v_stack((
                 label(move || name.clone())
                     .style(|s| s.font_weight(Weight::MEDIUM).text_ellipsis().min_width(0.0)),
                 label(move || path_display.clone()).style(|s| {
                     s.font_size(12.0)
-                        .color(Color::rgb8(128, 128, 128))
+                        .color(colors().text_muted)
                         .text_ellipsis()
                         .min_width(0.0)
                 }),
@@ -212,10 +214,10 @@ fn search_result_row(
             // PAK file badge - fixed width
             label(move || pak_file.clone()).style(|s| {
                 s.font_size(12.0)
-                    .color(Color::rgb8(100, 100, 100))
+                    .color(colors().text_secondary)
                     .padding_horiz(8.0)
                     .padding_vert(4.0)
-                    .background(Color::rgb8(240, 240, 240))
+                    .background(colors().bg_elevated)
                     .border_radius(4.0)
                     .flex_shrink(0.0)
             }),
@@ -236,10 +238,10 @@ fn search_result_row(
                                     label(move || format!("{} matches", n))
                                         .style(|s| {
                                             s.font_size(10.0)
-                                                .color(Color::rgb8(80, 80, 80))
+                                                .color(colors().text_secondary)
                                                 .padding_horiz(6.0)
                                                 .padding_vert(2.0)
-                                                .background(Color::rgb8(220, 230, 245))
+                                                .background(colors().accent_bg)
                                                 .border_radius(8.0)
                                                 .margin_right(8.0)
                                                 .flex_shrink(0.0)
@@ -253,9 +255,9 @@ fn search_result_row(
                         // Snippet text
                         label(move || ctx.clone()).style(|s| {
                             s.font_size(11.0)
-                                .color(Color::rgb8(80, 80, 80))
+                                .color(colors().text_secondary)
                                 .padding(4.0)
-                                .background(Color::rgb8(255, 255, 230))
+                                .background(colors().warning_bg)
                                 .border_radius(2.0)
                                 .text_ellipsis()
                                 .min_width(0.0)
@@ -275,10 +277,10 @@ fn search_result_row(
                         .style(|s| {
                             s.font_size(10.0)
                                 .font_style(floem::text::Style::Italic)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
                                 .padding_horiz(6.0)
                                 .padding_vert(2.0)
-                                .background(Color::rgb8(235, 235, 235))
+                                .background(colors().bg_hover)
                                 .border_radius(8.0)
                                 .margin_left(30.0)
                                 .margin_top(4.0)
@@ -296,8 +298,8 @@ fn search_result_row(
             .min_width(0.0)
             .padding(12.0)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(240, 240, 240))
-            .hover(|s| s.background(Color::rgb8(250, 252, 255)))
+            .border_color(colors().border)
+            .hover(|s| s.background(colors().bg_surface))
     })
     .on_event_stop(floem::event::EventListener::PointerDown, |_| {})
     .on_secondary_click(move |_| {
@@ -323,21 +325,19 @@ pub fn search_status_bar(state: SearchState) -> impl IntoView {
                 let (text, color) = match status {
                     IndexStatus::NotBuilt => (
                         "Click 'Build Index' to index PAK files".to_string(),
-                        Color::rgb8(150, 150, 150),
+                        colors().text_muted,
                     ),
                     IndexStatus::Building { progress } => {
-                        (format!("Building: {}", progress), Color::rgb8(255, 152, 0))
+                        (format!("Building: {}", progress), colors().warning)
                     }
                     IndexStatus::Ready {
                         file_count,
                         pak_count,
                     } => (
                         format!("Ready: {} files from {} PAKs", file_count, pak_count),
-                        Color::rgb8(76, 175, 80),
+                        colors().success,
                     ),
-                    IndexStatus::Error(msg) => {
-                        (format!("Error: {}", msg), Color::rgb8(244, 67, 54))
-                    }
+                    IndexStatus::Error(msg) => (format!("Error: {}", msg), colors().error),
                 };
 
                 label(move || text.clone())
@@ -348,16 +348,16 @@ pub fn search_status_bar(state: SearchState) -> impl IntoView {
         empty().style(|s| s.flex_grow(1.0)),
         // Hint about preferences
         label(|| "BG3 path configured in Preferences")
-            .style(|s| s.color(Color::rgb8(130, 130, 130)).font_size(11.0)),
+            .style(|s| s.color(colors().text_muted).font_size(11.0)),
     ))
     .style(|s| {
         s.width_full()
             .height(32.0)
             .padding_horiz(12.0)
             .items_center()
-            .background(Color::rgb8(248, 248, 248))
+            .background(colors().bg_surface)
             .border_top(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -365,7 +365,7 @@ fn get_type_icon(file_type: &str) -> &'static str {
     match file_type.to_lowercase().as_str() {
         "lsx" | "xml" => "📄",
         "lsj" | "json" => "📋",
-        "lsf" | "lsbc" => "🔷",
+        "lsf" | "lsb" | "lsbc" => "🔷",
         "dds" | "image" | "png" | "jpg" => "🖼️",
         "gr2" => "🦴",
         "wem" | "audio" | "ogg" | "wav" => "🔊",
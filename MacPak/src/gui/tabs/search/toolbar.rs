@@ -7,9 +7,10 @@ use floem::keyboard::{Key, NamedKey};
 use floem::prelude::*;
 use floem::views::PlaceholderTextClass;
 
+use crate::gui::shared::colors;
 use crate::gui::state::{ConfigState, IndexStatus, SearchState};
 
-use super::operations::{build_index, extract_selected_results, find_pak_files, perform_search};
+use super::operations::{build_game_index, extract_selected_results, perform_search};
 
 pub fn search_toolbar(state: SearchState, config_state: ConfigState) -> impl IntoView {
     let query = state.query;
@@ -27,9 +28,7 @@ pub fn search_toolbar(state: SearchState, config_state: ConfigState) -> impl Int
                     .flex_basis(0.0)
                     .width_full()
                     .min_width(100.0)
-                    .class(PlaceholderTextClass, |s| {
-                        s.color(Color::rgb8(120, 120, 120))
-                    })
+                    .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
             })
             .on_key_down(
                 Key::Named(NamedKey::Enter),
@@ -40,6 +39,7 @@ pub fn search_toolbar(state: SearchState, config_state: ConfigState) -> impl Int
             ),
         // Search button
         search_button(state.clone()),
+        fuzzy_toggle(state.fuzzy),
         separator(),
         // Filter buttons
         filter_buttons(active_filter),
@@ -56,9 +56,9 @@ pub fn search_toolbar(state: SearchState, config_state: ConfigState) -> impl Int
             .padding(10.0)
             .gap(8.0)
             .items_center()
-            .background(Color::rgb8(245, 245, 245))
+            .background(colors().bg_elevated)
             .border_bottom(1.0)
-            .border_color(Color::rgb8(220, 220, 220))
+            .border_color(colors().border)
     })
 }
 
@@ -77,8 +77,8 @@ fn search_button(state: SearchState) -> impl IntoView {
                     .disabled(|| true)
                     .style(|s| {
                         s.padding_horiz(16.0)
-                            .background(Color::rgb8(150, 150, 150))
-                            .color(Color::WHITE)
+                            .background(colors().text_disabled)
+                            .color(colors().text_on_accent)
                             .border_radius(4.0)
                     })
                     .into_any()
@@ -88,15 +88,15 @@ fn search_button(state: SearchState) -> impl IntoView {
                     .disabled(move || !is_ready || query.get().is_empty())
                     .style(move |s| {
                         let bg = if is_ready {
-                            Color::rgb8(33, 150, 243)
+                            colors().accent
                         } else {
-                            Color::rgb8(150, 150, 150)
+                            colors().text_disabled
                         };
                         s.padding_horiz(16.0)
                             .background(bg)
-                            .color(Color::WHITE)
+                            .color(colors().text_on_accent)
                             .border_radius(4.0)
-                            .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                            .hover(|s| s.background(colors().accent_hover))
                     })
                     .action(move || {
                         perform_search(state_clone.clone());
@@ -107,6 +107,14 @@ fn search_button(state: SearchState) -> impl IntoView {
     )
 }
 
+fn fuzzy_toggle(fuzzy: RwSignal<bool>) -> impl IntoView {
+    h_stack((
+        checkbox(move || fuzzy.get()).on_update(move |checked| fuzzy.set(checked)),
+        label(|| "Fuzzy").style(|s| s.font_size(12.0)),
+    ))
+    .style(|s| s.gap(4.0).items_center())
+}
+
 fn filter_buttons(active_filter: RwSignal<Option<FileType>>) -> impl IntoView {
     h_stack((
         filter_button("All", None, active_filter),
@@ -129,14 +137,14 @@ fn filter_button(
         move || active_filter.get() == filter_type,
         move |is_active| {
             let bg = if is_active {
-                Color::rgb8(33, 150, 243)
+                colors().accent
             } else {
-                Color::rgb8(230, 230, 230)
+                colors().bg_hover
             };
             let fg = if is_active {
-                Color::WHITE
+                colors().text_on_accent
             } else {
-                Color::rgb8(60, 60, 60)
+                colors().text_primary
             };
 
             button(label_text)
@@ -176,8 +184,8 @@ fn rebuild_index_button(
                     .disabled(|| true)
                     .style(|s| {
                         s.padding_horiz(12.0)
-                            .background(Color::rgb8(150, 150, 150))
-                            .color(Color::WHITE)
+                            .background(colors().text_disabled)
+                            .color(colors().text_on_accent)
                             .border_radius(4.0)
                     })
                     .into_any()
@@ -194,15 +202,15 @@ fn rebuild_index_button(
                     .disabled(move || !path_valid)
                     .style(move |s| {
                         let bg = if path_valid {
-                            Color::rgb8(76, 175, 80)
+                            colors().success
                         } else {
-                            Color::rgb8(150, 150, 150)
+                            colors().text_disabled
                         };
                         s.padding_horiz(12.0)
                             .background(bg)
-                            .color(Color::WHITE)
+                            .color(colors().text_on_accent)
                             .border_radius(4.0)
-                            .hover(|s| s.background(Color::rgb8(56, 142, 60)))
+                            .hover(|s| s.background(colors().success))
                     })
                     .action(move || {
                         // Show confirmation dialog if index already exists
@@ -221,12 +229,8 @@ fn rebuild_index_button(
                         };
 
                         if should_build {
-                            // Find PAK files in the configured BG3 data path
-                            let paks = find_pak_files(&PathBuf::from(&path_for_action));
-                            if !paks.is_empty() {
-                                state_clone.pak_paths.set(paks);
-                                build_index(state_clone.clone());
-                            }
+                            // Index every PAK of the configured game install
+                            build_game_index(state_clone.clone(), PathBuf::from(&path_for_action));
                         }
                     })
                     .into_any()
@@ -239,7 +243,7 @@ pub fn separator() -> impl IntoView {
     empty().style(|s| {
         s.width(1.0)
             .height(30.0)
-            .background(Color::rgb8(200, 200, 200))
+            .background(colors().bg_selected)
             .margin_horiz(4.0)
     })
 }
@@ -258,17 +262,17 @@ fn extract_selected_button(state: SearchState) -> impl IntoView {
                 .disabled(move || !has_selection)
                 .style(move |s| {
                     let bg = if has_selection {
-                        Color::rgb8(33, 150, 243)
+                        colors().accent
                     } else {
-                        Color::rgb8(180, 180, 180)
+                        colors().text_disabled
                     };
                     s.padding_horiz(10.0)
                         .padding_vert(4.0)
                         .font_size(12.0)
                         .background(bg)
-                        .color(Color::WHITE)
+                        .color(colors().text_on_accent)
                         .border_radius(4.0)
-                        .hover(|s| s.background(Color::rgb8(25, 118, 210)))
+                        .hover(|s| s.background(colors().accent_hover))
                 })
                 .action(move || {
                     extract_selected_results(state_clone.clone());
@@ -8,6 +8,7 @@ use crate::gui::state::{EditorTabsState, WorkbenchState};
 use super::build::build_panel;
 use super::file_checklist::file_checklist;
 use super::file_tree::file_tree_card;
+use super::task_queue::task_queue_panel;
 
 /// Main dashboard view for an open project
 pub fn project_dashboard(
@@ -90,9 +91,10 @@ pub fn project_dashboard(
             // File checklist (left, takes most space)
             file_checklist(state.clone(), editor_tabs_state.clone(), active_tab)
                 .style(|s| s.flex_grow(1.0).flex_basis(0.0).min_width(0.0)),
-            // Right sidebar: build panel + file tree
+            // Right sidebar: build panel + task queue + file tree
             v_stack((
                 build_panel(state.clone()),
+                task_queue_panel(state.clone()),
                 file_tree_card(state.clone(), editor_tabs_state.clone(), active_tab),
             ))
             .style(|s| s.width(280.0).gap(16.0).min_height(0.0)),
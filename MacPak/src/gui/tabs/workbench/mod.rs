@@ -5,6 +5,7 @@ mod dashboard;
 mod file_checklist;
 mod file_tree;
 mod new_project;
+mod task_queue;
 
 use floem::prelude::*;
 
@@ -0,0 +1,303 @@
+//! Task queue panel: run, cancel and retry queued operations
+
+use std::time::Duration;
+
+use floem::action::exec_after;
+use floem::prelude::*;
+
+use crate::gui::shared::{ThemeColors, theme_signal};
+use crate::gui::state::WorkbenchState;
+use crate::operations::task_queue::{TaskQueue, TaskStatus};
+
+/// How often running queues are polled for status changes
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Task list sidebar card
+pub fn task_queue_panel(state: WorkbenchState) -> impl IntoView {
+    let queue = state.task_queue;
+    let status = state.task_status;
+    let state_for_load = state.clone();
+    let state_for_save = state.clone();
+    let state_for_run = state.clone();
+    let state_for_rows = state.clone();
+
+    v_stack((
+        label(|| "Tasks").style(move |s| {
+            let colors = theme_signal()
+                .map(|t| ThemeColors::for_theme(t.get().effective()))
+                .unwrap_or_else(ThemeColors::dark);
+            s.font_size(15.0)
+                .font_weight(floem::text::Weight::SEMIBOLD)
+                .color(colors.text_primary)
+                .margin_bottom(12.0)
+        }),
+        h_stack((
+            small_button("Load...", move || load_queue(state_for_load.clone())),
+            small_button("Save...", move || save_queue(state_for_save.clone())),
+            empty().style(|s| s.flex_grow(1.0)),
+            small_button("Run All", move || {
+                let all: Vec<usize> =
+                    (0..state_for_run.task_queue.get_untracked().tasks.len()).collect();
+                start_tasks(state_for_run.clone(), &all);
+            }),
+        ))
+        .style(|s| s.width_full().gap(6.0).margin_bottom(8.0)),
+        dyn_container(
+            move || (queue.get(), status.get()),
+            move |(queue, status)| {
+                if queue.tasks.is_empty() {
+                    return label(|| "No tasks. Load a saved queue to start.")
+                        .style(move |s| {
+                            let colors = theme_signal()
+                                .map(|t| ThemeColors::for_theme(t.get().effective()))
+                                .unwrap_or_else(ThemeColors::dark);
+                            s.font_size(12.0).color(colors.text_muted)
+                        })
+                        .into_any();
+                }
+                let rows: Vec<_> = queue
+                    .tasks
+                    .iter()
+                    .enumerate()
+                    .map(|(index, task)| {
+                        let task_status = status.get(index).cloned().unwrap_or_default();
+                        task_row(state_for_rows.clone(), index, task.label(), task_status)
+                    })
+                    .collect();
+                v_stack_from_iter(rows)
+                    .style(|s| s.width_full().gap(6.0))
+                    .into_any()
+            },
+        ),
+    ))
+    .style(move |s| {
+        let colors = theme_signal()
+            .map(|t| ThemeColors::for_theme(t.get().effective()))
+            .unwrap_or_else(ThemeColors::dark);
+        s.width_full()
+            .padding(16.0)
+            .background(colors.bg_surface)
+            .border(1.0)
+            .border_color(colors.border)
+            .border_radius(6.0)
+    })
+}
+
+fn task_row(
+    state: WorkbenchState,
+    index: usize,
+    name: String,
+    task_status: TaskStatus,
+) -> impl IntoView {
+    let finished = task_status.is_finished();
+    let is_error = matches!(
+        task_status,
+        TaskStatus::Failed(_) | TaskStatus::Cancelled | TaskStatus::Skipped
+    );
+    let is_done = matches!(task_status, TaskStatus::Done(_));
+    let detail = status_text(&task_status);
+
+    let in_run = !finished
+        && state
+            .task_runs
+            .borrow()
+            .iter()
+            .any(|run| run.contains(index));
+
+    let action = if !in_run {
+        let text = if finished { "Retry" } else { "Run" };
+        small_button(text, move || start_tasks(state.clone(), &[index])).into_any()
+    } else {
+        small_button("Cancel", move || {
+            for run in state.task_runs.borrow().iter() {
+                run.cancel(index);
+            }
+        })
+        .into_any()
+    };
+
+    h_stack((
+        v_stack((
+            label(move || name.clone()).style(move |s| {
+                let colors = theme_signal()
+                    .map(|t| ThemeColors::for_theme(t.get().effective()))
+                    .unwrap_or_else(ThemeColors::dark);
+                s.font_size(12.0).color(colors.text_primary)
+            }),
+            label(move || detail.clone()).style(move |s| {
+                let colors = theme_signal()
+                    .map(|t| ThemeColors::for_theme(t.get().effective()))
+                    .unwrap_or_else(ThemeColors::dark);
+                let color = if is_error {
+                    colors.error
+                } else if is_done {
+                    colors.success
+                } else {
+                    colors.text_muted
+                };
+                s.font_size(11.0).color(color)
+            }),
+        ))
+        .style(|s| s.flex_grow(1.0).flex_basis(0.0).min_width(0.0)),
+        action,
+    ))
+    .style(|s| s.width_full().gap(6.0).items_center())
+}
+
+fn status_text(status: &TaskStatus) -> String {
+    match status {
+        TaskStatus::Pending => "Pending".to_string(),
+        TaskStatus::Running { total: 0, .. } => "Running...".to_string(),
+        TaskStatus::Running {
+            current,
+            total,
+            file,
+        } => match file {
+            Some(file) => format!("{}/{} {}", current, total, file),
+            None => format!("{}/{}", current, total),
+        },
+        TaskStatus::Done(summary) => summary.clone(),
+        TaskStatus::Failed(error) => format!("Failed: {}", error),
+        TaskStatus::Cancelled => "Cancelled".to_string(),
+        TaskStatus::Skipped => "Skipped: a task it depends on didn't finish".to_string(),
+    }
+}
+
+/// Run some of the queued tasks, polling for status until the run ends
+fn start_tasks(state: WorkbenchState, indices: &[usize]) {
+    let queue = state.task_queue.get_untracked();
+    let running_before = {
+        let runs = state.task_runs.borrow();
+        if indices
+            .iter()
+            .any(|&i| runs.iter().any(|run| run.contains(i)))
+        {
+            state
+                .error_message
+                .set(Some("Task is already queued or running".to_string()));
+            return;
+        }
+        !runs.is_empty()
+    };
+
+    // Add the run first so the rows rebuilt below show Cancel buttons
+    state
+        .task_runs
+        .borrow_mut()
+        .push(queue.start_tasks(indices));
+    state.task_status.update(|status| {
+        status.resize(queue.tasks.len(), TaskStatus::Pending);
+        for &i in indices {
+            status[i] = TaskStatus::Pending;
+        }
+    });
+
+    // A poll loop is already going for the earlier run
+    if !running_before {
+        poll_runs(state);
+    }
+}
+
+/// Apply status changes from running queues, then check again shortly
+fn poll_runs(state: WorkbenchState) {
+    exec_after(POLL_INTERVAL, move |_| {
+        let events: Vec<_> = {
+            let mut runs = state.task_runs.borrow_mut();
+            let events = runs.iter_mut().flat_map(|run| run.poll()).collect();
+            runs.retain(|run| !run.is_finished());
+            events
+        };
+        if !events.is_empty() {
+            state.task_status.update(|status| {
+                for event in events {
+                    if let Some(slot) = status.get_mut(event.index) {
+                        *slot = event.status;
+                    }
+                }
+            });
+        }
+        if !state.task_runs.borrow().is_empty() {
+            poll_runs(state);
+        }
+    });
+}
+
+fn load_queue(state: WorkbenchState) {
+    if !state.task_runs.borrow().is_empty() {
+        state
+            .error_message
+            .set(Some("Wait for running tasks to finish first".to_string()));
+        return;
+    }
+
+    let mut dialog = rfd::FileDialog::new()
+        .set_title("Load Task Queue")
+        .add_filter("JSON", &["json"]);
+    if let Some(ref w) = state.workbench.get_untracked() {
+        dialog = dialog.set_directory(&w.project_dir);
+    }
+
+    if let Some(path) = dialog.pick_file() {
+        match TaskQueue::load(&path) {
+            Ok(queue) => {
+                state
+                    .task_status
+                    .set(vec![TaskStatus::Pending; queue.tasks.len()]);
+                state
+                    .result_message
+                    .set(Some(format!("Loaded {} tasks", queue.tasks.len())));
+                state.error_message.set(None);
+                state.task_queue.set(queue);
+            }
+            Err(e) => {
+                state
+                    .error_message
+                    .set(Some(format!("Failed to load task queue: {}", e)));
+            }
+        }
+    }
+}
+
+fn save_queue(state: WorkbenchState) {
+    let mut dialog = rfd::FileDialog::new()
+        .set_title("Save Task Queue")
+        .set_file_name("tasks.json")
+        .add_filter("JSON", &["json"]);
+    if let Some(ref w) = state.workbench.get_untracked() {
+        dialog = dialog.set_directory(&w.project_dir);
+    }
+
+    if let Some(path) = dialog.save_file() {
+        match state.task_queue.get_untracked().save(&path) {
+            Ok(()) => {
+                state.error_message.set(None);
+                state.result_message.set(Some(format!(
+                    "Saved: {}",
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                )));
+            }
+            Err(e) => {
+                state
+                    .error_message
+                    .set(Some(format!("Failed to save task queue: {}", e)));
+            }
+        }
+    }
+}
+
+fn small_button(text: &'static str, action: impl Fn() + 'static) -> impl IntoView {
+    button(text).action(action).style(move |s| {
+        let colors = theme_signal()
+            .map(|t| ThemeColors::for_theme(t.get().effective()))
+            .unwrap_or_else(ThemeColors::dark);
+        s.padding_horiz(8.0)
+            .padding_vert(4.0)
+            .font_size(11.0)
+            .background(colors.bg_elevated)
+            .color(colors.text_primary)
+            .border(1.0)
+            .border_color(colors.border)
+            .border_radius(4.0)
+            .hover(|s| s.background(colors.bg_hover))
+    })
+}
@@ -8,18 +8,23 @@ use floem::prelude::*;
 use floem::text::Weight;
 use floem::views::PlaceholderTextClass;
 
-use crate::gui::shared::Theme;
-use crate::gui::state::ConfigState;
+use crate::gui::shared::{Theme, colors};
+use crate::gui::state::{ConfigState, detect_bg3_path};
+use maclarian::config::LOG_LEVELS;
 
 /// Create the configuration dialog UI
 pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
     let show = config_state.show_dialog;
     let bg3_path = config_state.bg3_data_path;
     let path_warning = config_state.path_warning;
+    let config_error = config_state.config_error;
     let config_for_save = config_state.clone();
 
-    // Local edit signal (allows cancelling without saving)
+    // Local edit signals (allow cancelling without saving)
     let edit_path = RwSignal::new(bg3_path.get());
+    let edit_output_dir = RwSignal::new(String::new());
+    let edit_index_dir = RwSignal::new(String::new());
+    let config_for_sync = config_state.clone();
 
     // Sync when dialog opens
     let show_for_sync = show;
@@ -33,8 +38,16 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                 return empty().into_any();
             }
 
-            // Sync edit_path with current bg3_path when dialog opens
-            edit_path_for_sync.set(bg3_path_for_sync.get());
+            // Sync edit_path with current bg3_path when dialog opens,
+            // prefilling from the detected install if nothing is configured
+            let current = bg3_path_for_sync.get();
+            if current.is_empty() {
+                edit_path_for_sync.set(detect_bg3_path().unwrap_or_default());
+            } else {
+                edit_path_for_sync.set(current);
+            }
+            edit_output_dir.set(config_for_sync.output_dir.get_untracked());
+            edit_index_dir.set(config_for_sync.index_dir.get_untracked());
 
             let config_for_save = config_for_save.clone();
 
@@ -46,9 +59,9 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                         .font_weight(Weight::BOLD)
                         .margin_bottom(16.0)
                 }),
-                // Warning message if path is invalid
+                // Warning message if config.toml is broken or the path is invalid
                 dyn_container(
-                    move || path_warning.get(),
+                    move || config_error.get().or_else(|| path_warning.get()),
                     move |warning| {
                         if let Some(msg) = warning {
                             label(move || msg.clone())
@@ -56,11 +69,11 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                                     s.width_full()
                                         .padding(8.0)
                                         .margin_bottom(12.0)
-                                        .background(Color::rgb8(255, 243, 205))
+                                        .background(colors().warning_bg)
                                         .border(1.0)
-                                        .border_color(Color::rgb8(255, 193, 7))
+                                        .border_color(colors().warning)
                                         .border_radius(4.0)
-                                        .color(Color::rgb8(133, 100, 4))
+                                        .color(colors().warning)
                                         .font_size(12.0)
                                 })
                                 .into_any()
@@ -72,7 +85,7 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                 // BG3 Path field
                 v_stack((
                     label(|| "Baldur's Gate 3 Data Path")
-                        .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+                        .style(|s| s.font_size(12.0).color(colors().text_secondary)),
                     h_stack((
                         {
                             let show_for_input = show;
@@ -85,12 +98,12 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                                         .min_width(100.0)
                                         .padding(8.0)
                                         .font_size(13.0)
-                                        .background(Color::WHITE)
+                                        .background(colors().bg_base)
                                         .border(1.0)
-                                        .border_color(Color::rgb8(200, 200, 200))
+                                        .border_color(colors().border_strong)
                                         .border_radius(4.0)
                                         .class(PlaceholderTextClass, |s| {
-                                            s.color(Color::rgb8(120, 120, 120))
+                                            s.color(colors().text_muted)
                                         })
                                 })
                                 .on_event_cont(EventListener::KeyDown, move |e| {
@@ -130,16 +143,28 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                                     }
                                 })
                         },
+                        {
+                            let edit_path = edit_path;
+                            button("Detect")
+                                .style(|s| s.margin_left(8.0).flex_shrink(0.0))
+                                .action(move || {
+                                    if let Some(path) = detect_bg3_path() {
+                                        edit_path.set(path);
+                                    } else {
+                                        path_warning.set(Some(
+                                            "No BG3 installation found in the standard locations."
+                                                .to_string(),
+                                        ));
+                                    }
+                                })
+                        },
                     ))
                     .style(|s| s.width_full().items_center()),
                 ))
                 .style(|s| s.width_full().gap(4.0)),
                 // Help text
-                label(|| "This should point to the Data folder containing .pak files").style(|s| {
-                    s.font_size(11.0)
-                        .color(Color::rgb8(128, 128, 128))
-                        .margin_top(4.0)
-                }),
+                label(|| "This should point to the Data folder containing .pak files")
+                    .style(|s| s.font_size(11.0).color(colors().text_muted).margin_top(4.0)),
                 // Theme selector
                 {
                     let theme_signal = config_state.theme;
@@ -147,7 +172,7 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                     v_stack((
                         label(|| "Appearance").style(|s| {
                             s.font_size(12.0)
-                                .color(Color::rgb8(100, 100, 100))
+                                .color(colors().text_secondary)
                                 .margin_top(16.0)
                         }),
                         h_stack((
@@ -169,6 +194,36 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                     ))
                     .style(|s| s.width_full().gap(4.0))
                 },
+                // Default folders
+                folder_field(
+                    "Default Output Folder",
+                    "Ask every time",
+                    "Select Default Output Folder",
+                    edit_output_dir,
+                ),
+                folder_field(
+                    "Search Index Folder",
+                    "Default location",
+                    "Select Search Index Folder",
+                    edit_index_dir,
+                ),
+                // Log level selector
+                {
+                    let log_level = config_state.log_level;
+                    let config_for_level = config_state.clone();
+                    v_stack((
+                        label(|| "Log Level (applies on next launch)").style(|s| {
+                            s.font_size(12.0)
+                                .color(colors().text_secondary)
+                                .margin_top(16.0)
+                        }),
+                        h_stack_from_iter(LOG_LEVELS.iter().map(|level| {
+                            log_level_button(level, log_level, config_for_level.clone())
+                        }))
+                        .style(|s| s.gap(8.0)),
+                    ))
+                    .style(|s| s.width_full().gap(4.0))
+                },
                 // Buttons
                 h_stack((
                     {
@@ -177,7 +232,7 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                             .style(|s| {
                                 s.padding(8.0)
                                     .padding_horiz(16.0)
-                                    .background(Color::rgb8(240, 240, 240))
+                                    .background(colors().bg_elevated)
                                     .border_radius(4.0)
                             })
                             .action(move || {
@@ -192,16 +247,23 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
                             .style(|s| {
                                 s.padding(8.0)
                                     .padding_horiz(16.0)
-                                    .background(Color::rgb8(59, 130, 246))
-                                    .color(Color::WHITE)
+                                    .background(colors().accent)
+                                    .color(colors().text_on_accent)
                                     .border_radius(4.0)
                                     .font_weight(Weight::SEMIBOLD)
                             })
                             .action(move || {
-                                // Save the edited path
+                                // Save the edited paths
                                 bg3_path.set(edit_path.get());
+                                config_for_save
+                                    .output_dir
+                                    .set(edit_output_dir.get().trim().to_string());
+                                config_for_save
+                                    .index_dir
+                                    .set(edit_index_dir.get().trim().to_string());
                                 // Validate the new path
                                 config_for_save.validate_path();
+                                config_for_save.save();
                                 show.set(false);
                             })
                     },
@@ -211,7 +273,7 @@ pub fn config_dialog(config_state: ConfigState) -> impl IntoView {
             .style(|s| {
                 s.width(600.0)
                     .padding(24.0)
-                    .background(Color::WHITE)
+                    .background(colors().bg_base)
                     .border_radius(8.0)
                     .box_shadow_blur(20.0)
                     .box_shadow_color(Color::rgba8(0, 0, 0, 50))
@@ -277,13 +339,13 @@ fn theme_button(
                 .font_size(13.0);
 
             if is_selected {
-                s.background(Color::rgb8(59, 130, 246))
-                    .color(Color::WHITE)
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
                     .font_weight(Weight::SEMIBOLD)
             } else {
-                s.background(Color::rgb8(240, 240, 240))
-                    .color(Color::rgb8(60, 60, 60))
-                    .hover(|s| s.background(Color::rgb8(230, 230, 230)))
+                s.background(colors().bg_elevated)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_hover))
             }
         })
         .action(move || {
@@ -294,3 +356,79 @@ fn theme_button(
             }
         })
 }
+
+/// Folder setting with Browse and Clear buttons
+fn folder_field(
+    label_text: &'static str,
+    placeholder: &'static str,
+    dialog_title: &'static str,
+    value: RwSignal<String>,
+) -> impl IntoView {
+    v_stack((
+        label(move || label_text).style(|s| {
+            s.font_size(12.0)
+                .color(colors().text_secondary)
+                .margin_top(16.0)
+        }),
+        h_stack((
+            text_input(value).placeholder(placeholder).style(|s| {
+                s.flex_grow(1.0)
+                    .flex_basis(0.0)
+                    .width_full()
+                    .min_width(100.0)
+                    .padding(8.0)
+                    .font_size(13.0)
+                    .background(colors().bg_base)
+                    .border(1.0)
+                    .border_color(colors().border_strong)
+                    .border_radius(4.0)
+                    .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
+            }),
+            button("Browse...")
+                .style(|s| s.margin_left(8.0).flex_shrink(0.0))
+                .action(move || {
+                    if let Some(folder) =
+                        rfd::FileDialog::new().set_title(dialog_title).pick_folder()
+                    {
+                        value.set(folder.display().to_string());
+                    }
+                }),
+            button("Clear")
+                .style(|s| s.margin_left(8.0).flex_shrink(0.0))
+                .action(move || value.set(String::new())),
+        ))
+        .style(|s| s.width_full().items_center()),
+    ))
+    .style(|s| s.width_full().gap(4.0))
+}
+
+/// Log level selection button (saved immediately, like the theme)
+fn log_level_button(
+    level: &'static str,
+    current_level: RwSignal<String>,
+    config_state: ConfigState,
+) -> impl IntoView {
+    button(level)
+        .style(move |s| {
+            let is_selected = current_level.get() == level;
+            let s = s
+                .padding(6.0)
+                .padding_horiz(12.0)
+                .border_radius(4.0)
+                .font_size(12.0);
+
+            if is_selected {
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
+                    .font_weight(Weight::SEMIBOLD)
+            } else {
+                s.background(colors().bg_elevated)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_hover))
+            }
+        })
+        .action(move || {
+            current_level.set(level.to_string());
+            config_state.save();
+        })
+}
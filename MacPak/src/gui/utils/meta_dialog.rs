@@ -9,6 +9,7 @@ use floem::text::Weight;
 use floem::views::PlaceholderTextClass;
 
 use super::{UuidFormat, generate_uuid, meta_generator};
+use crate::gui::shared::colors;
 
 /// Check if a string is a valid UUID format (8-4-4-4-12 hex chars with dashes)
 fn is_valid_uuid(s: &str) -> bool {
@@ -232,7 +233,7 @@ where
                     // UUID with regenerate button
                     v_stack((
                         label(|| "UUID")
-                            .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+                            .style(|s| s.font_size(12.0).color(colors().text_secondary)),
                         h_stack((
                             text_input(uuid)
                                 .style(|s| {
@@ -240,7 +241,7 @@ where
                                         .flex_grow(1.0)
                                         .padding(8.0)
                                         .border(1.0)
-                                        .border_color(Color::rgb8(200, 200, 200))
+                                        .border_color(colors().border_strong)
                                         .border_radius(4.0)
                                         .font_family("monospace".to_string())
                                 })
@@ -266,7 +267,7 @@ where
                     // Version fields
                     v_stack((
                         label(|| "Version")
-                            .style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+                            .style(|s| s.font_size(12.0).color(colors().text_secondary)),
                         h_stack((
                             meta_version_field(version_major, "Major"),
                             label(|| ".").style(|s| s.font_size(16.0).margin_horiz(2.0)),
@@ -292,7 +293,7 @@ where
                         .style(|s| {
                             s.padding(8.0)
                                 .padding_horiz(16.0)
-                                .background(Color::rgb8(240, 240, 240))
+                                .background(colors().bg_elevated)
                                 .border_radius(4.0)
                         })
                         .action(move || {
@@ -303,8 +304,8 @@ where
                     .style(|s| {
                         s.padding(8.0)
                             .padding_horiz(16.0)
-                            .background(Color::rgb8(59, 130, 246))
-                            .color(Color::WHITE)
+                            .background(colors().accent)
+                            .color(colors().text_on_accent)
                             .border_radius(4.0)
                             .font_weight(Weight::SEMIBOLD)
                     })
@@ -372,7 +373,7 @@ where
             .style(move |s| {
                 s.width(dialog_width)
                     .padding(24.0)
-                    .background(Color::WHITE)
+                    .background(colors().bg_base)
                     .border_radius(8.0)
                     .box_shadow_blur(20.0)
                     .box_shadow_color(Color::rgba8(0, 0, 0, 50))
@@ -412,16 +413,14 @@ fn meta_text_field(
     placeholder: &'static str,
 ) -> impl IntoView {
     v_stack((
-        label(move || label_text).style(|s| s.font_size(12.0).color(Color::rgb8(100, 100, 100))),
+        label(move || label_text).style(|s| s.font_size(12.0).color(colors().text_secondary)),
         text_input(signal).placeholder(placeholder).style(|s| {
             s.width_full()
                 .padding(8.0)
                 .border(1.0)
-                .border_color(Color::rgb8(200, 200, 200))
+                .border_color(colors().border_strong)
                 .border_radius(4.0)
-                .class(PlaceholderTextClass, |s| {
-                    s.color(Color::rgb8(120, 120, 120))
-                })
+                .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
         }),
     ))
     .style(|s| s.flex_grow(1.0).gap(4.0))
@@ -436,11 +435,9 @@ fn meta_version_field(signal: RwSignal<u32>, placeholder: &'static str) -> impl
             s.width(50.0)
                 .padding(8.0)
                 .border(1.0)
-                .border_color(Color::rgb8(200, 200, 200))
+                .border_color(colors().border_strong)
                 .border_radius(4.0)
-                .class(PlaceholderTextClass, |s| {
-                    s.color(Color::rgb8(120, 120, 120))
-                })
+                .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
         })
         .on_event(floem::event::EventListener::FocusLost, move |_| {
             // Parse and update the u32 signal when focus is lost
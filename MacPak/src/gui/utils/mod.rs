@@ -5,10 +5,11 @@ pub mod config_dialog;
 pub mod errors;
 pub mod meta_dialog;
 pub mod meta_generator;
-pub mod uuid;
+pub mod uuid_dialog;
 
 pub use clipboard::copy_to_clipboard;
 pub use config_dialog::config_dialog;
 pub use errors::show_file_error;
+pub use maclarian::utils::{UuidFormat, generate_uuid};
 pub use meta_generator::generate_meta_lsx;
-pub use uuid::{UuidFormat, generate_uuid};
+pub use uuid_dialog::uuid_dialog;
@@ -0,0 +1,327 @@
+//! UUID Generator Dialog UI Component
+//!
+//! Bulk UUID/handle generation with a history list, plus a validation box
+//! that identifies a pasted value and converts it between formats.
+
+use floem::event::{Event, EventListener};
+use floem::keyboard::{Key, NamedKey};
+use floem::prelude::*;
+use floem::text::Weight;
+use floem::views::{PlaceholderTextClass, VirtualDirection, VirtualItemSize, virtual_list};
+use im::Vector as ImVector;
+use maclarian::utils::{classify_uuid, convert_uuid};
+
+use super::{UuidFormat, copy_to_clipboard};
+use crate::gui::shared::colors;
+use crate::gui::state::{MAX_BULK_COUNT, UuidGeneratorState};
+
+const ROW_HEIGHT: f64 = 24.0;
+
+/// Create the UUID generator dialog UI
+pub fn uuid_dialog(state: UuidGeneratorState) -> impl IntoView {
+    let show = state.show_dialog;
+    let status = state.status;
+
+    dyn_container(
+        move || show.get(),
+        move |visible| {
+            if !visible {
+                return empty().into_any();
+            }
+
+            v_stack((
+                label(|| "UUID Generator").style(|s| {
+                    s.font_size(18.0)
+                        .font_weight(Weight::BOLD)
+                        .margin_bottom(12.0)
+                }),
+                generate_row(state.clone()),
+                history_list(state.clone()),
+                label(move || status.get()).style(|s| {
+                    s.font_size(11.0)
+                        .color(colors().text_secondary)
+                        .height(16.0)
+                }),
+                validation_section(state.clone()),
+                h_stack((
+                    empty().style(|s| s.flex_grow(1.0)),
+                    button("Close")
+                        .style(|s| {
+                            s.padding(8.0)
+                                .padding_horiz(16.0)
+                                .background(colors().bg_elevated)
+                                .border_radius(4.0)
+                        })
+                        .action(move || show.set(false)),
+                ))
+                .style(|s| s.width_full().margin_top(16.0)),
+            ))
+            .style(|s| {
+                s.width(600.0)
+                    .padding(24.0)
+                    .gap(8.0)
+                    .background(colors().bg_base)
+                    .border_radius(8.0)
+                    .box_shadow_blur(20.0)
+                    .box_shadow_color(Color::rgba8(0, 0, 0, 50))
+            })
+            .into_any()
+        },
+    )
+    .style(move |s| {
+        if show.get() {
+            s.position(floem::style::Position::Absolute)
+                .inset_top(0.0)
+                .inset_left(0.0)
+                .inset_bottom(0.0)
+                .inset_right(0.0)
+                .items_center()
+                .justify_center()
+                .background(Color::rgba8(0, 0, 0, 100))
+                .z_index(100)
+        } else {
+            s.display(floem::style::Display::None)
+        }
+    })
+    .on_event_stop(EventListener::KeyDown, move |e| {
+        if let Event::KeyDown(key_event) = e {
+            if key_event.key.logical_key == Key::Named(NamedKey::Escape) {
+                show.set(false);
+            }
+        }
+    })
+    .keyboard_navigable()
+}
+
+/// Kind toggle, count field and Generate / Copy All / Clear buttons
+fn generate_row(state: UuidGeneratorState) -> impl IntoView {
+    let generate_handles = state.generate_handles;
+    let last_batch = state.last_batch;
+    let status = state.status;
+    let state_generate = state.clone();
+    let state_clear = state.clone();
+
+    h_stack((
+        kind_button("UUID", false, generate_handles),
+        kind_button("Handle", true, generate_handles),
+        label(|| "Count").style(|s| {
+            s.font_size(12.0)
+                .color(colors().text_secondary)
+                .margin_left(8.0)
+        }),
+        text_input(state.count)
+            .placeholder(format!("1-{}", MAX_BULK_COUNT))
+            .style(|s| input_style(s).width(70.0)),
+        button("Generate")
+            .action(move || match state_generate.generate() {
+                Ok(1) => {
+                    let value = state_generate
+                        .last_batch
+                        .with(|batch| batch.first().cloned())
+                        .unwrap_or_default();
+                    copy_to_clipboard(&value);
+                    status.set(format!("{} copied to clipboard", value));
+                }
+                Ok(count) => status.set(format!(
+                    "Generated {} values - use Copy All to copy them",
+                    count
+                )),
+                Err(e) => status.set(e),
+            })
+            .style(|s| {
+                s.padding(6.0)
+                    .padding_horiz(16.0)
+                    .background(colors().accent)
+                    .color(colors().text_on_accent)
+                    .border_radius(4.0)
+                    .font_weight(Weight::SEMIBOLD)
+            }),
+        empty().style(|s| s.flex_grow(1.0)),
+        small_button("Copy All", move || {
+            let batch = last_batch.get();
+            if batch.is_empty() {
+                status.set("Nothing generated yet".to_string());
+            } else {
+                copy_to_clipboard(&batch.join("\n"));
+                status.set(format!("Copied {} values to clipboard", batch.len()));
+            }
+        }),
+        small_button("Clear History", move || {
+            state_clear.active_history().set(Vec::new());
+            state_clear.last_batch.set(Vec::new());
+            status.set(String::new());
+        }),
+    ))
+    .style(|s| s.width_full().gap(8.0).items_center())
+}
+
+/// History of the selected kind; clicking a row copies it
+fn history_list(state: UuidGeneratorState) -> impl IntoView {
+    let status = state.status;
+    let items = move || {
+        state
+            .active_history()
+            .get()
+            .into_iter()
+            .collect::<ImVector<_>>()
+    };
+
+    scroll(
+        virtual_list(
+            VirtualDirection::Vertical,
+            VirtualItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+            items,
+            |value: &String| value.clone(),
+            move |value| {
+                let for_click = value.clone();
+                label(move || value.clone())
+                    .on_click_stop(move |_| {
+                        copy_to_clipboard(&for_click);
+                        status.set(format!("{} copied to clipboard", for_click));
+                    })
+                    .style(|s| {
+                        s.width_full()
+                            .height(ROW_HEIGHT)
+                            .padding_horiz(8.0)
+                            .items_center()
+                            .font_family("monospace".to_string())
+                            .font_size(12.0)
+                            .cursor(floem::style::CursorStyle::Pointer)
+                            .hover(|s| s.background(colors().bg_elevated))
+                    })
+            },
+        )
+        .style(|s| s.flex_col().width_full()),
+    )
+    .scroll_style(|s| s.handle_thickness(8.0))
+    .style(|s| {
+        s.width_full()
+            .height(200.0)
+            .border(1.0)
+            .border_color(colors().border)
+            .border_radius(4.0)
+            .background(colors().bg_base)
+    })
+}
+
+/// Paste box that names the value's format and offers it in the others
+fn validation_section(state: UuidGeneratorState) -> impl IntoView {
+    let input = state.validate_input;
+    let status = state.status;
+
+    v_stack((
+        label(|| "Validate / Convert").style(|s| {
+            s.font_size(12.0)
+                .color(colors().text_secondary)
+                .margin_top(8.0)
+        }),
+        text_input(input)
+            .placeholder("Paste a UUID or handle...")
+            .style(|s| input_style(s).width_full()),
+        label(move || {
+            let value = input.get();
+            if value.trim().is_empty() {
+                return String::new();
+            }
+            match classify_uuid(&value) {
+                Some(UuidFormat::Standard) => "✓ Standard UUID (FixedString GUID)".to_string(),
+                Some(UuidFormat::Compact) => "✓ Compact UUID (no hyphens)".to_string(),
+                Some(UuidFormat::Larian) => "✓ TranslatedString handle".to_string(),
+                None => "✗ Not a UUID or handle".to_string(),
+            }
+        })
+        .style(move |s| {
+            let valid = classify_uuid(&input.get()).is_some();
+            s.font_size(12.0).color(if valid {
+                colors().success
+            } else {
+                colors().error
+            })
+        }),
+        conversion_row("UUID", UuidFormat::Standard, input, status),
+        conversion_row("Compact", UuidFormat::Compact, input, status),
+        conversion_row("Handle", UuidFormat::Larian, input, status),
+    ))
+    .style(|s| s.width_full().gap(6.0))
+}
+
+/// One converted form of the pasted value, hidden while the input is invalid
+fn conversion_row(
+    name: &'static str,
+    format: UuidFormat,
+    input: RwSignal<String>,
+    status: RwSignal<String>,
+) -> impl IntoView {
+    let converted = move || convert_uuid(&input.get(), format);
+
+    h_stack((
+        label(move || name).style(|s| s.width(70.0).font_size(12.0).color(colors().text_secondary)),
+        label(move || converted().unwrap_or_default()).style(|s| {
+            s.flex_grow(1.0)
+                .font_family("monospace".to_string())
+                .font_size(12.0)
+        }),
+        small_button("Copy", move || {
+            if let Some(value) = converted() {
+                copy_to_clipboard(&value);
+                status.set(format!("{} copied to clipboard", value));
+            }
+        }),
+    ))
+    .style(move |s| {
+        let s = s.width_full().gap(8.0).items_center();
+        if converted().is_some() {
+            s
+        } else {
+            s.display(floem::style::Display::None)
+        }
+    })
+}
+
+/// UUID / Handle toggle button
+fn kind_button(
+    text: &'static str,
+    handles: bool,
+    generate_handles: RwSignal<bool>,
+) -> impl IntoView {
+    button(text)
+        .style(move |s| {
+            let s = s
+                .padding(6.0)
+                .padding_horiz(12.0)
+                .border_radius(4.0)
+                .font_size(13.0);
+            if generate_handles.get() == handles {
+                s.background(colors().accent)
+                    .color(colors().text_on_accent)
+                    .font_weight(Weight::SEMIBOLD)
+            } else {
+                s.background(colors().bg_elevated)
+                    .color(colors().text_primary)
+                    .hover(|s| s.background(colors().bg_hover))
+            }
+        })
+        .action(move || generate_handles.set(handles))
+}
+
+fn small_button(text: &'static str, action: impl Fn() + 'static) -> impl IntoView {
+    button(text).action(action).style(|s| {
+        s.padding_vert(4.0)
+            .padding_horiz(8.0)
+            .font_size(11.0)
+            .background(colors().bg_elevated)
+            .border(1.0)
+            .border_color(colors().border_strong)
+            .border_radius(4.0)
+    })
+}
+
+fn input_style(s: floem::style::Style) -> floem::style::Style {
+    s.padding(6.0)
+        .font_size(13.0)
+        .background(colors().bg_base)
+        .border(1.0)
+        .border_color(colors().border_strong)
+        .border_radius(4.0)
+        .class(PlaceholderTextClass, |s| s.color(colors().text_muted))
+}
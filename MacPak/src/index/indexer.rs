@@ -1 +1,177 @@
 //! File indexing logic
+//!
+//! Walks a workspace and records per-directory listings plus per-file
+//! size/mtime stamps. Directory listings are only re-read when the
+//! directory's own mtime changes, so a refresh of an unchanged tree costs one
+//! `stat` per entry instead of a full walk. Files are hashed when they are
+//! first seen or their stamp changes, so renames can be told apart from
+//! unrelated files that happen to share a size and mtime.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Directory mtimes this close to the scan time may hide same-tick changes
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Bytes hashed from each end of a file
+const HASH_SAMPLE: u64 = 64 * 1024;
+
+/// Size, modification time and content hash of an indexed file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Hash of the first and last 64 KiB (the whole file if smaller),
+    /// `None` if the file couldn't be read
+    pub hash: Option<u64>,
+}
+
+/// Cached listing of one directory
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DirListing {
+    pub modified: Option<SystemTime>,
+    pub files: Vec<String>,
+    pub subdirs: Vec<String>,
+}
+
+/// Everything known about a workspace after a scan
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Snapshot {
+    /// Files by `/`-separated path relative to the root
+    pub files: BTreeMap<String, FileStamp>,
+    /// Directory listings by relative path (`""` is the root)
+    pub dirs: HashMap<String, DirListing>,
+    /// When the snapshot was taken
+    pub scanned_at: Option<SystemTime>,
+}
+
+/// Scan `root`, reusing listings from `previous` for directories that haven't changed
+///
+/// Unless `recursive` is set only the files directly in `root` are indexed.
+pub(crate) fn scan(root: &Path, previous: &Snapshot, recursive: bool) -> std::io::Result<Snapshot> {
+    let mut next = Snapshot {
+        scanned_at: Some(SystemTime::now()),
+        ..Snapshot::default()
+    };
+    scan_dir(root, "", previous, recursive, &mut next)?;
+    Ok(next)
+}
+
+fn scan_dir(
+    root: &Path,
+    rel: &str,
+    previous: &Snapshot,
+    recursive: bool,
+    next: &mut Snapshot,
+) -> std::io::Result<()> {
+    let dir = if rel.is_empty() {
+        root.to_path_buf()
+    } else {
+        root.join(rel)
+    };
+    let modified = fs::metadata(&dir)?.modified().ok();
+
+    let listing = match previous.dirs.get(rel) {
+        Some(cached) if cached.modified == modified && !is_racy(modified, previous.scanned_at) => {
+            cached.clone()
+        }
+        _ => read_listing(&dir, modified)?,
+    };
+
+    for name in &listing.files {
+        let path = join(rel, name);
+        // A file can vanish between listing and stat; treat it as removed
+        if let Ok(metadata) = fs::metadata(dir.join(name)) {
+            let size = metadata.len();
+            let modified = metadata.modified().ok();
+            // Only hash files that are new or whose stamp changed
+            let hash = match previous.files.get(&path) {
+                Some(old) if old.size == size && old.modified == modified => old.hash,
+                _ => sample_hash(&dir.join(name), size),
+            };
+            next.files.insert(
+                path,
+                FileStamp {
+                    size,
+                    modified,
+                    hash,
+                },
+            );
+        }
+    }
+
+    if recursive {
+        for name in &listing.subdirs {
+            let path = join(rel, name);
+            if scan_dir(root, &path, previous, recursive, next).is_err() {
+                tracing::debug!("Skipping unreadable directory {}", path);
+            }
+        }
+    }
+
+    next.dirs.insert(rel.to_string(), listing);
+    Ok(())
+}
+
+fn read_listing(dir: &Path, modified: Option<SystemTime>) -> std::io::Result<DirListing> {
+    let mut listing = DirListing {
+        modified,
+        ..DirListing::default()
+    };
+
+    for entry in fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Skip hidden files
+        if name.starts_with('.') {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(t) if t.is_dir() => listing.subdirs.push(name),
+            Ok(_) => listing.files.push(name),
+            Err(_) => {}
+        }
+    }
+
+    listing.files.sort();
+    listing.subdirs.sort();
+    Ok(listing)
+}
+
+/// Hash the first and last [`HASH_SAMPLE`] bytes of a file
+///
+/// Hashing samples keeps large files cheap; two files of equal size and
+/// mtime that only differ in the middle are rare enough to accept.
+fn sample_hash(path: &Path, size: u64) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    let mut buf = Vec::new();
+
+    (&mut file).take(HASH_SAMPLE).read_to_end(&mut buf).ok()?;
+    if size > 2 * HASH_SAMPLE {
+        file.seek(SeekFrom::End(-(HASH_SAMPLE as i64))).ok()?;
+    }
+    file.read_to_end(&mut buf).ok()?;
+
+    hasher.write(&buf);
+    Some(hasher.finish())
+}
+
+/// Whether a directory changed too close to the last scan to trust its mtime
+fn is_racy(modified: Option<SystemTime>, scanned_at: Option<SystemTime>) -> bool {
+    match (modified, scanned_at) {
+        (Some(modified), Some(scanned_at)) => modified + RACY_WINDOW >= scanned_at,
+        _ => true,
+    }
+}
+
+fn join(rel: &str, name: &str) -> String {
+    if rel.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", rel, name)
+    }
+}
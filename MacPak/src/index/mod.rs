@@ -2,12 +2,44 @@ pub mod database;
 pub mod indexer;
 pub mod search;
 
-use crate::error::Result;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+pub use indexer::FileStamp;
+use indexer::Snapshot;
 
 /// File index for tracking and searching files.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct FileIndex {
-    // Will implement later
+    root: Option<PathBuf>,
+    snapshot: Snapshot,
+    shallow: bool,
+}
+
+/// Changes found by [`FileIndex::refresh`]
+///
+/// Paths are `/`-separated and relative to the index root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RefreshDelta {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+    /// `(old, new)` pairs: a removed and an added file with the same size,
+    /// mtime and content hash
+    pub renamed: Vec<(String, String)>,
+}
+
+impl RefreshDelta {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+    }
 }
 
 impl FileIndex {
@@ -17,6 +49,255 @@ impl FileIndex {
     ///
     /// Returns an error if index initialization fails.
     pub fn new() -> Result<Self> {
-        Ok(Self {})
+        Ok(Self::default())
+    }
+
+    /// Creates an index of a workspace directory and scans it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read.
+    pub fn open(root: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with(root.as_ref(), false)
+    }
+
+    /// Creates an index of the files directly in `dir`, without descending
+    /// into subdirectories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read.
+    pub fn open_shallow(dir: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with(dir.as_ref(), true)
+    }
+
+    fn open_with(root: &Path, shallow: bool) -> Result<Self> {
+        let root = root.to_path_buf();
+        let snapshot = indexer::scan(&root, &Snapshot::default(), !shallow)
+            .map_err(|e| Error::Index(format!("Failed to scan {}: {}", root.display(), e)))?;
+        Ok(Self {
+            root: Some(root),
+            snapshot,
+            shallow,
+        })
+    }
+
+    /// The indexed workspace directory, if any.
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Number of indexed files.
+    pub fn len(&self) -> usize {
+        self.snapshot.files.len()
+    }
+
+    /// Whether the index has no files.
+    pub fn is_empty(&self) -> bool {
+        self.snapshot.files.is_empty()
+    }
+
+    /// Indexed files in path order.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &FileStamp)> {
+        self.snapshot.files.iter().map(|(p, s)| (p.as_str(), s))
+    }
+
+    /// Names of the subdirectories of `dir` (relative to the root, `""` for
+    /// the root itself) as of the last scan.
+    pub fn subdirs(&self, dir: &str) -> &[String] {
+        self.snapshot
+            .dirs
+            .get(dir)
+            .map_or(&[], |listing| listing.subdirs.as_slice())
+    }
+
+    /// Re-stat the workspace and return what changed since the last scan.
+    ///
+    /// Every known file is stat'ed, but directory listings are only re-read
+    /// for directories whose mtime changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no root is set or the root can't be read.
+    pub fn refresh(&mut self) -> Result<RefreshDelta> {
+        let root = self
+            .root
+            .as_ref()
+            .ok_or_else(|| Error::Index("No workspace indexed".to_string()))?;
+        let next = indexer::scan(root, &self.snapshot, !self.shallow)
+            .map_err(|e| Error::Index(format!("Failed to scan {}: {}", root.display(), e)))?;
+
+        let delta = diff(&self.snapshot, &next);
+        self.snapshot = next;
+        Ok(delta)
+    }
+
+    /// Move the index to a background thread that refreshes it every `interval`.
+    ///
+    /// Non-empty deltas are sent on the returned channel. The thread exits
+    /// when the receiver is dropped or the root becomes unreadable.
+    pub fn watch(mut self, interval: Duration) -> Receiver<RefreshDelta> {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+                let delta = match self.refresh() {
+                    Ok(delta) => delta,
+                    Err(e) => {
+                        tracing::warn!("Stopping index watcher: {}", e);
+                        break;
+                    }
+                };
+                if !delta.is_empty() && tx.send(delta).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+}
+
+/// Compare two snapshots
+fn diff(old: &Snapshot, new: &Snapshot) -> RefreshDelta {
+    let mut delta = RefreshDelta::default();
+
+    for (path, stamp) in &new.files {
+        match old.files.get(path) {
+            None => delta.added.push(path.clone()),
+            Some(old_stamp) if old_stamp != stamp => delta.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+    delta.removed = old
+        .files
+        .keys()
+        .filter(|p| !new.files.contains_key(*p))
+        .cloned()
+        .collect();
+
+    // Pair up removals and additions with identical stamps as renames. The
+    // content hash keeps unrelated files that share a size and mtime apart.
+    let mut removed = Vec::new();
+    for old_path in std::mem::take(&mut delta.removed) {
+        let stamp = old.files[&old_path];
+        let candidate = (stamp.modified.is_some() && stamp.hash.is_some())
+            .then(|| delta.added.iter().position(|p| new.files[p] == stamp))
+            .flatten();
+        match candidate {
+            Some(i) => {
+                let new_path = delta.added.remove(i);
+                delta.renamed.push((old_path, new_path));
+            }
+            None => removed.push(old_path),
+        }
+    }
+    delta.removed = removed;
+
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Public/Textures")).unwrap();
+        fs::write(dir.path().join("meta.lsx"), "meta").unwrap();
+        fs::write(dir.path().join("Public/a.lsx"), "a").unwrap();
+        fs::write(dir.path().join("Public/Textures/b.dds"), "bb").unwrap();
+        fs::write(dir.path().join("Public/Textures/c.dds"), "ccc").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_refresh_tracks_changes() {
+        let dir = temp_workspace();
+        let mut index = FileIndex::open(dir.path()).unwrap();
+        assert_eq!(index.len(), 4);
+        assert!(index.refresh().unwrap().is_empty());
+
+        fs::rename(
+            dir.path().join("Public/a.lsx"),
+            dir.path().join("Public/renamed.lsx"),
+        )
+        .unwrap();
+        fs::remove_file(dir.path().join("Public/Textures/b.dds")).unwrap();
+        fs::write(dir.path().join("meta.lsx"), "meta v2").unwrap();
+        fs::write(dir.path().join("Public/new.lsx"), "new file").unwrap();
+
+        let delta = index.refresh().unwrap();
+
+        assert_eq!(
+            delta.renamed,
+            vec![("Public/a.lsx".to_string(), "Public/renamed.lsx".to_string())]
+        );
+        assert_eq!(delta.removed, vec!["Public/Textures/b.dds".to_string()]);
+        assert_eq!(delta.modified, vec!["meta.lsx".to_string()]);
+        assert_eq!(delta.added, vec!["Public/new.lsx".to_string()]);
+        assert_eq!(index.len(), 4);
+    }
+
+    #[test]
+    fn test_refresh_removed_directory() {
+        let dir = temp_workspace();
+        let mut index = FileIndex::open(dir.path()).unwrap();
+
+        fs::remove_dir_all(dir.path().join("Public/Textures")).unwrap();
+        let delta = index.refresh().unwrap();
+
+        assert_eq!(
+            delta.removed,
+            vec![
+                "Public/Textures/b.dds".to_string(),
+                "Public/Textures/c.dds".to_string()
+            ]
+        );
+        assert!(delta.added.is_empty() && delta.renamed.is_empty());
+        assert_eq!(index.files().count(), 2);
+    }
+
+    #[test]
+    fn test_same_stamp_different_content_is_not_a_rename() {
+        let dir = temp_workspace();
+        let old_path = dir.path().join("Public/a.lsx");
+        fs::write(&old_path, "same size").unwrap();
+        let modified = fs::metadata(&old_path).unwrap().modified().unwrap();
+        let mut index = FileIndex::open(dir.path()).unwrap();
+
+        fs::remove_file(&old_path).unwrap();
+        let new_path = dir.path().join("Public/other.lsx");
+        fs::write(&new_path, "different").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&new_path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let delta = index.refresh().unwrap();
+
+        assert!(delta.renamed.is_empty());
+        assert_eq!(delta.removed, vec!["Public/a.lsx".to_string()]);
+        assert_eq!(delta.added, vec!["Public/other.lsx".to_string()]);
+    }
+
+    #[test]
+    fn test_shallow_index_skips_subdirectories() {
+        let dir = temp_workspace();
+        let mut index = FileIndex::open_shallow(dir.path()).unwrap();
+        assert_eq!(
+            index.files().map(|(p, _)| p).collect::<Vec<_>>(),
+            ["meta.lsx"]
+        );
+        assert_eq!(index.subdirs(""), ["Public".to_string()]);
+
+        fs::write(dir.path().join("Public/deep.lsx"), "deep").unwrap();
+        fs::write(dir.path().join("top.lsx"), "top").unwrap();
+        let delta = index.refresh().unwrap();
+
+        assert_eq!(delta.added, vec!["top.lsx".to_string()]);
+        assert!(delta.removed.is_empty() && delta.modified.is_empty());
     }
 }
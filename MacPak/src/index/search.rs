@@ -1 +1,299 @@
-//! Search functionality
+//! Workspace-wide text search ("find in files")
+//!
+//! [`search_workspace`] reads every indexed file that passes the include and
+//! exclude globs on a small pool of worker threads. Files with a NUL byte
+//! near the start are treated as binary and skipped, except LSF files, which
+//! can be converted to LSX in memory (the same way the editor opens them, so
+//! line numbers agree). Each file with matches is passed to the callback as
+//! soon as it has been searched, so results can be shown while the rest of
+//! the workspace is still being read.
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use maclarian::pak::FilterSet;
+use maclarian::utils::CancelToken;
+
+use super::FileIndex;
+use crate::error::{Error, Result};
+
+/// Bytes checked for NUL when deciding whether a file is binary
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Matched lines longer than this are cut short in results
+const MAX_LINE_PREVIEW: usize = 240;
+
+/// Upper bound on search threads
+const MAX_WORKERS: usize = 8;
+
+/// Options for [`search_workspace`]
+#[derive(Debug, Clone, Default)]
+pub struct ProjectSearchOptions {
+    pub case_sensitive: bool,
+    /// Globs of files to search; empty searches every file
+    pub include: Vec<String>,
+    /// Globs of files to leave out
+    pub exclude: Vec<String>,
+    /// Convert LSF files to LSX in memory and search that text
+    pub convert_lsf: bool,
+    /// Matched lines kept per file (0 keeps all)
+    pub max_matches_per_file: usize,
+}
+
+/// A matched line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+    /// 1-based line number
+    pub line: usize,
+    /// The line with surrounding whitespace trimmed, cut to a preview length
+    pub text: String,
+}
+
+/// Matches in one file
+#[derive(Debug, Clone)]
+pub struct FileMatches {
+    /// `/`-separated path relative to the index root
+    pub path: String,
+    pub matches: Vec<LineMatch>,
+    /// Lines matched, including any past `max_matches_per_file`
+    pub total: usize,
+    /// The matches are in the file's LSX conversion
+    pub converted: bool,
+}
+
+/// Counts from a finished (or cancelled) search
+#[derive(Debug, Default)]
+pub struct ProjectSearchSummary {
+    pub files_searched: usize,
+    pub files_matched: usize,
+    /// Files skipped as binary
+    pub files_skipped: usize,
+    /// Files that couldn't be read or converted, with their errors
+    pub failed: Vec<(String, Error)>,
+    pub cancelled: bool,
+}
+
+/// Search every text file in `index` for `query`
+///
+/// `on_file` is called from the worker threads, once per file with at least
+/// one match, in no particular order.
+///
+/// # Errors
+///
+/// Returns an error if the index has no root or a glob is invalid.
+pub fn search_workspace(
+    index: &FileIndex,
+    query: &str,
+    options: &ProjectSearchOptions,
+    cancel: &CancelToken,
+    on_file: &(dyn Fn(FileMatches) + Sync),
+) -> Result<ProjectSearchSummary> {
+    let root = index
+        .root()
+        .ok_or_else(|| Error::Index("No workspace indexed".to_string()))?;
+    let filter = FilterSet::new(&options.include, &options.exclude, true)?;
+    let paths: Vec<&str> = index
+        .files()
+        .map(|(path, _)| path)
+        .filter(|path| filter.matches(path))
+        .collect();
+    if query.is_empty() || paths.is_empty() {
+        return Ok(ProjectSearchSummary::default());
+    }
+
+    let matcher = Matcher::new(query, options.case_sensitive);
+    let next = AtomicUsize::new(0);
+    let summary = Mutex::new(ProjectSearchSummary::default());
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_WORKERS)
+        .min(paths.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    let Some(path) = paths.get(next.fetch_add(1, Ordering::SeqCst)) else {
+                        break;
+                    };
+                    let outcome = search_file(&root.join(path), path, &matcher, options);
+                    let Ok(mut summary) = summary.lock() else {
+                        break;
+                    };
+                    match outcome {
+                        Ok(Some(matches)) => {
+                            summary.files_searched += 1;
+                            if matches.total > 0 {
+                                summary.files_matched += 1;
+                                // Release the lock before handing the result out
+                                drop(summary);
+                                on_file(matches);
+                            }
+                        }
+                        Ok(None) => summary.files_skipped += 1,
+                        Err(e) => summary.failed.push(((*path).to_string(), e)),
+                    }
+                }
+            });
+        }
+    });
+
+    let mut summary = summary
+        .into_inner()
+        .map_err(|_| Error::Index("Search worker panicked".to_string()))?;
+    summary.cancelled = cancel.is_cancelled();
+    Ok(summary)
+}
+
+/// Search one file, or `None` if it was skipped as binary
+fn search_file(
+    full_path: &Path,
+    path: &str,
+    matcher: &Matcher,
+    options: &ProjectSearchOptions,
+) -> Result<Option<FileMatches>> {
+    let data = std::fs::read(full_path)?;
+    let is_lsf = full_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("lsf"));
+
+    let (text, converted) = if is_lsf && options.convert_lsf {
+        let doc = maclarian::formats::lsf::parse_lsf_bytes(&data)?;
+        (maclarian::converter::to_lsx(&doc)?, true)
+    } else if is_binary(&data) {
+        return Ok(None);
+    } else {
+        (String::from_utf8_lossy(&data).into_owned(), false)
+    };
+
+    let mut result = FileMatches {
+        path: path.to_string(),
+        matches: Vec::new(),
+        total: 0,
+        converted,
+    };
+    for (i, line) in text.lines().enumerate() {
+        if !matcher.is_match(line) {
+            continue;
+        }
+        result.total += 1;
+        if options.max_matches_per_file == 0 || result.matches.len() < options.max_matches_per_file
+        {
+            result.matches.push(LineMatch {
+                line: i + 1,
+                text: preview(line),
+            });
+        }
+    }
+    Ok(Some(result))
+}
+
+/// Whether the start of a file contains a NUL byte
+fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+/// A line trimmed and cut to [`MAX_LINE_PREVIEW`] characters
+fn preview(line: &str) -> String {
+    let line = line.trim();
+    match line.char_indices().nth(MAX_LINE_PREVIEW) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// Substring matcher, lowercasing both sides when case-insensitive
+struct Matcher {
+    needle: String,
+    case_sensitive: bool,
+}
+
+impl Matcher {
+    fn new(query: &str, case_sensitive: bool) -> Self {
+        let needle = if case_sensitive {
+            query.to_string()
+        } else {
+            query.to_lowercase()
+        };
+        Self {
+            needle,
+            case_sensitive,
+        }
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        if self.case_sensitive {
+            line.contains(&self.needle)
+        } else {
+            line.to_lowercase().contains(&self.needle)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::TEMPLATES_LSX;
+    use std::fs;
+
+    #[test]
+    fn test_search_workspace_filters_and_converts() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Public/RootTemplates")).unwrap();
+        fs::write(dir.path().join("Public/RootTemplates/a.lsx"), TEMPLATES_LSX).unwrap();
+        fs::write(
+            dir.path().join("Public/notes.txt"),
+            "one\nparenttemplateid two\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Public/blob.bin"),
+            b"ParentTemplateId\0\x01",
+        )
+        .unwrap();
+        let lsf =
+            maclarian::converter::convert_bytes(TEMPLATES_LSX.as_bytes(), "lsx", "lsf").unwrap();
+        fs::write(dir.path().join("Public/RootTemplates/b.lsf"), lsf).unwrap();
+
+        let index = FileIndex::open(dir.path()).unwrap();
+        let search = |options: &ProjectSearchOptions| {
+            let found = Mutex::new(Vec::new());
+            let summary = search_workspace(
+                &index,
+                "ParentTemplateId",
+                options,
+                &CancelToken::new(),
+                &|file| found.lock().unwrap().push(file),
+            )
+            .unwrap();
+            let mut found = found.into_inner().unwrap();
+            found.sort_by(|a, b| a.path.cmp(&b.path));
+            (summary, found)
+        };
+
+        let (summary, found) = search(&ProjectSearchOptions::default());
+        let paths: Vec<&str> = found.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["Public/RootTemplates/a.lsx", "Public/notes.txt"]);
+        assert_eq!(found[0].matches[0].line, 8);
+        assert_eq!(found[1].matches[0].text, "parenttemplateid two");
+        assert_eq!(summary.files_skipped, 2);
+
+        let options = ProjectSearchOptions {
+            case_sensitive: true,
+            include: vec!["*.lsf".to_string(), "*.txt".to_string()],
+            convert_lsf: true,
+            ..ProjectSearchOptions::default()
+        };
+        let (summary, found) = search(&options);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "Public/RootTemplates/b.lsf");
+        assert!(found[0].converted);
+        assert_eq!(summary.files_searched, 2);
+        assert_eq!(summary.files_skipped, 0);
+    }
+}
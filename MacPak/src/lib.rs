@@ -99,6 +99,10 @@ pub mod error;
 pub mod index;
 pub mod operations;
 pub mod workbench;
+pub mod workspace;
+
+#[cfg(test)]
+mod test_fixtures;
 
 // GUI-specific modules (moved from MacLarian)
 pub mod dialog;
@@ -106,17 +110,25 @@ pub mod dyes;
 pub mod formats;
 
 // Feature-gated modules
+#[cfg(feature = "cli")]
+pub mod cli;
+
 #[cfg(feature = "gui")]
 pub mod gui;
 
-#[cfg(feature = "gui")]
+#[cfg(any(feature = "gui", feature = "cli"))]
 pub mod search;
 
+#[cfg(feature = "gui")]
+pub mod thumbnails;
+
 #[cfg(feature = "viewer")]
 pub mod viewer;
 
 pub use error::{Error, Result};
 
+use operations::conversion::{ConversionResult, DdsFormat, Format};
+
 /// Main toolkit interface
 pub struct Toolkit {
     workbench: Option<workbench::Workbench>,
@@ -148,6 +160,25 @@ impl Toolkit {
         Ok(())
     }
 
+    /// Builds the open workbench project into a PAK.
+    ///
+    /// Runs the same conversion, validation and packing steps as the GUI
+    /// Build button.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no workbench is open or the build fails.
+    pub fn build_project(
+        &self,
+        progress: workbench::pipeline::BuildProgressCallback,
+    ) -> Result<workbench::pipeline::BuildReport> {
+        let ws = self
+            .workbench
+            .as_ref()
+            .ok_or_else(|| Error::Workbench("No workbench open".to_string()))?;
+        ws.build_with_progress(progress).map_err(Error::Workbench)
+    }
+
     /// Extracts a PAK file to a destination directory.
     ///
     /// # Errors
@@ -157,8 +188,46 @@ impl Toolkit {
         operations::extraction::extract_pak(pak, dest)
     }
 
+    /// Converts a file between any supported pair of formats.
+    ///
+    /// Formats default to the file extensions, as in `maclarian convert`.
+    ///
+    /// ```no_run
+    /// use macpak::Toolkit;
+    /// use macpak::operations::conversion::Format;
+    ///
+    /// let toolkit = Toolkit::new()?;
+    /// let result = toolkit.convert("Item.lsf", "Item.lsx", None, None)?;
+    /// assert_eq!(result.input, Format::Lsf);
+    ///
+    /// // Read a file with a non-standard extension as LSF
+    /// toolkit.convert("Item.bin", "Item.lsj", Some(Format::Lsf), None)?;
+    /// # Ok::<(), macpak::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a format can't be determined, the pair isn't
+    /// supported, or conversion fails.
+    pub fn convert(
+        &self,
+        source: impl AsRef<Path>,
+        dest: impl AsRef<Path>,
+        input: Option<Format>,
+        output: Option<Format>,
+    ) -> Result<ConversionResult> {
+        operations::conversion::convert(source, dest, input, output)
+    }
+
     /// Converts an LSF file to LSX format.
     ///
+    /// ```no_run
+    /// # let toolkit = macpak::Toolkit::new()?;
+    /// let result = toolkit.convert_lsf_to_lsx("meta.lsf", "meta.lsx")?;
+    /// println!("wrote {} bytes", result.bytes_written);
+    /// # Ok::<(), macpak::Error>(())
+    /// ```
+    ///
     /// # Errors
     ///
     /// Returns an error if conversion fails.
@@ -166,12 +235,37 @@ impl Toolkit {
         &self,
         source: impl AsRef<Path>,
         dest: impl AsRef<Path>,
-    ) -> Result<()> {
-        operations::conversion::lsf_to_lsx(source, dest)
+    ) -> Result<ConversionResult> {
+        self.convert(source, dest, Some(Format::Lsf), Some(Format::Lsx))
+    }
+
+    /// Converts an LSX file to LSF format.
+    ///
+    /// ```no_run
+    /// # let toolkit = macpak::Toolkit::new()?;
+    /// toolkit.convert_lsx_to_lsf("RootTemplates/Item.lsx", "RootTemplates/Item.lsf")?;
+    /// # Ok::<(), macpak::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if conversion fails.
+    pub fn convert_lsx_to_lsf(
+        &self,
+        source: impl AsRef<Path>,
+        dest: impl AsRef<Path>,
+    ) -> Result<ConversionResult> {
+        self.convert(source, dest, Some(Format::Lsx), Some(Format::Lsf))
     }
 
     /// Converts a LOCA file to XML format.
     ///
+    /// ```no_run
+    /// # let toolkit = macpak::Toolkit::new()?;
+    /// toolkit.convert_loca_to_xml("english.loca", "english.xml")?;
+    /// # Ok::<(), macpak::Error>(())
+    /// ```
+    ///
     /// # Errors
     ///
     /// Returns an error if conversion fails.
@@ -179,12 +273,18 @@ impl Toolkit {
         &self,
         source: impl AsRef<Path>,
         dest: impl AsRef<Path>,
-    ) -> Result<()> {
-        operations::conversion::loca_to_xml(source, dest)
+    ) -> Result<ConversionResult> {
+        self.convert(source, dest, Some(Format::Loca), Some(Format::Xml))
     }
 
     /// Converts an XML file to LOCA format.
     ///
+    /// ```no_run
+    /// # let toolkit = macpak::Toolkit::new()?;
+    /// toolkit.convert_xml_to_loca("english.xml", "english.loca")?;
+    /// # Ok::<(), macpak::Error>(())
+    /// ```
+    ///
     /// # Errors
     ///
     /// Returns an error if conversion fails.
@@ -192,8 +292,33 @@ impl Toolkit {
         &self,
         source: impl AsRef<Path>,
         dest: impl AsRef<Path>,
-    ) -> Result<()> {
-        operations::conversion::xml_to_loca(source, dest)
+    ) -> Result<ConversionResult> {
+        self.convert(source, dest, Some(Format::Xml), Some(Format::Loca))
+    }
+
+    /// Converts a texture between DDS and PNG, based on the file extensions.
+    ///
+    /// `dds_format` sets the compression for PNG -> DDS (BC3 if `None`).
+    ///
+    /// ```no_run
+    /// use macpak::operations::conversion::DdsFormat;
+    ///
+    /// # let toolkit = macpak::Toolkit::new()?;
+    /// toolkit.convert_texture("icon.dds", "icon.png", None)?;
+    /// toolkit.convert_texture("icon.png", "icon.dds", Some(DdsFormat::BC1))?;
+    /// # Ok::<(), macpak::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the files aren't a DDS/PNG pair or conversion fails.
+    pub fn convert_texture(
+        &self,
+        source: impl AsRef<Path>,
+        dest: impl AsRef<Path>,
+        dds_format: Option<DdsFormat>,
+    ) -> Result<ConversionResult> {
+        operations::conversion::convert_texture(source, dest, dds_format)
     }
 
     // Virtual texture operations
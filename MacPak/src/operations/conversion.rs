@@ -1,8 +1,211 @@
 //! Format conversion operations
 
 use crate::error::Result;
+use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 
+pub use maclarian::converter::DdsFormat;
+
+/// A file format handled by [`convert`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Binary document (also `.lsbc`, `.lsbs`, `.lsfx`)
+    Lsf,
+    /// Legacy binary document; can be read but not written
+    Lsb,
+    /// XML document
+    Lsx,
+    /// JSON document
+    Lsj,
+    /// Binary localization
+    Loca,
+    /// Localization XML (`<contentList>`)
+    Xml,
+    Dds,
+    Png,
+    Gr2,
+    Glb,
+    Gltf,
+}
+
+impl Format {
+    /// Format for a file extension (case-insensitive, without the dot)
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "lsf" | "lsbc" | "lsbs" | "lsfx" => Some(Self::Lsf),
+            "lsb" => Some(Self::Lsb),
+            "lsx" => Some(Self::Lsx),
+            "lsj" => Some(Self::Lsj),
+            "loca" => Some(Self::Loca),
+            "xml" => Some(Self::Xml),
+            "dds" => Some(Self::Dds),
+            "png" => Some(Self::Png),
+            "gr2" => Some(Self::Gr2),
+            "glb" => Some(Self::Glb),
+            "gltf" => Some(Self::Gltf),
+            _ => None,
+        }
+    }
+
+    /// Format for a path's extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    /// Canonical file extension
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Lsf => "lsf",
+            Self::Lsb => "lsb",
+            Self::Lsx => "lsx",
+            Self::Lsj => "lsj",
+            Self::Loca => "loca",
+            Self::Xml => "xml",
+            Self::Dds => "dds",
+            Self::Png => "png",
+            Self::Gr2 => "gr2",
+            Self::Glb => "glb",
+            Self::Gltf => "gltf",
+        }
+    }
+}
+
+/// Result of a file conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionResult {
+    /// Format the source was read as
+    pub input: Format,
+    /// Format written to the destination
+    pub output: Format,
+    /// Size of the written file
+    pub bytes_written: u64,
+}
+
+/// Converts a file, detecting formats the same way the CLI does.
+///
+/// Formats default to the source and destination extensions. If the source
+/// extension isn't recognized, its format is sniffed from the file header.
+///
+/// # Errors
+///
+/// Returns an error if a format can't be determined, the pair isn't supported,
+/// or the conversion fails.
+pub fn convert(
+    source: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+    input: Option<Format>,
+    output: Option<Format>,
+) -> Result<ConversionResult> {
+    let (source, dest) = (source.as_ref(), dest.as_ref());
+
+    let input = match input.or_else(|| Format::from_path(source)) {
+        Some(format) => format,
+        None => detect_format(source)?,
+    };
+    let output = output.or_else(|| Format::from_path(dest)).ok_or_else(|| {
+        unsupported(format!(
+            "Cannot detect output format from {}",
+            dest.display()
+        ))
+    })?;
+
+    use maclarian::converter as mc;
+    match (input, output) {
+        (Format::Lsf, Format::Lsx) => mc::lsf_to_lsx(source, dest)?,
+        (Format::Lsf, Format::Lsj) => mc::lsf_to_lsj(source, dest)?,
+        (Format::Lsx, Format::Lsf) => mc::lsx_to_lsf(source, dest)?,
+        (Format::Lsx, Format::Lsj) => mc::lsx_to_lsj(source, dest)?,
+        (Format::Lsj, Format::Lsf) => mc::lsj_to_lsf(source, dest)?,
+        (Format::Lsj, Format::Lsx) => mc::lsj_to_lsx(source, dest)?,
+        (Format::Lsb, Format::Lsx) => mc::lsb_to_lsx(source, dest)?,
+        (Format::Lsb, Format::Lsf | Format::Lsj) => {
+            let data = std::fs::read(source)?;
+            std::fs::write(dest, mc::convert_bytes(&data, "lsb", output.extension())?)?;
+        }
+        (Format::Loca, Format::Xml) => mc::convert_loca_to_xml(source, dest)?,
+        (Format::Xml, Format::Loca) => mc::convert_xml_to_loca(source, dest)?,
+        (Format::Dds, Format::Png) => mc::convert_dds_to_png(source, dest)?,
+        (Format::Png, Format::Dds) => mc::convert_png_to_dds(source, dest)?,
+        (Format::Gr2, Format::Glb) => mc::convert_gr2_to_glb(source, dest)?,
+        (Format::Gr2, Format::Gltf) => mc::convert_gr2_to_gltf(source, dest)?,
+        (Format::Glb | Format::Gltf, Format::Gr2) => mc::convert_gltf_to_gr2(source, dest)?,
+        (input, output) => {
+            return Err(unsupported(format!(
+                "Conversion from {} to {} is not supported",
+                input.extension(),
+                output.extension()
+            )));
+        }
+    }
+
+    finish(input, output, dest)
+}
+
+/// Converts a texture between DDS and PNG.
+///
+/// The direction comes from the file extensions. `dds_format` picks the
+/// compression when writing DDS (BC3 if `None`) and is ignored for PNG output.
+///
+/// # Errors
+///
+/// Returns an error if the files aren't a DDS/PNG pair or conversion fails.
+pub fn convert_texture(
+    source: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+    dds_format: Option<DdsFormat>,
+) -> Result<ConversionResult> {
+    let (source, dest) = (source.as_ref(), dest.as_ref());
+
+    match (Format::from_path(source), Format::from_path(dest)) {
+        (Some(Format::Dds), Some(Format::Png)) => {
+            maclarian::converter::convert_dds_to_png(source, dest)?;
+            finish(Format::Dds, Format::Png, dest)
+        }
+        (Some(Format::Png), Some(Format::Dds)) => {
+            maclarian::converter::convert_png_to_dds_with_format(
+                source,
+                dest,
+                dds_format.unwrap_or(DdsFormat::BC3),
+            )?;
+            finish(Format::Png, Format::Dds, dest)
+        }
+        _ => Err(unsupported(format!(
+            "Texture conversion needs a .dds/.png pair, got {} -> {}",
+            source.display(),
+            dest.display()
+        ))),
+    }
+}
+
+/// Detect a source format from its header
+fn detect_format(source: &Path) -> Result<Format> {
+    let mut header = Vec::with_capacity(512);
+    File::open(source)?.take(512).read_to_end(&mut header)?;
+    maclarian::converter::sniff_format(&header)
+        .and_then(Format::from_extension)
+        .ok_or_else(|| {
+            unsupported(format!(
+                "Cannot detect input format of {}",
+                source.display()
+            ))
+        })
+}
+
+fn finish(input: Format, output: Format, dest: &Path) -> Result<ConversionResult> {
+    Ok(ConversionResult {
+        input,
+        output,
+        bytes_written: std::fs::metadata(dest)?.len(),
+    })
+}
+
+fn unsupported(message: String) -> crate::Error {
+    maclarian::Error::ConversionError(message).into()
+}
+
 /// Converts an LSF file to LSX format.
 ///
 /// # Errors
@@ -74,3 +277,41 @@ pub fn loca_to_xml(source: impl AsRef<Path>, dest: impl AsRef<Path>) -> Result<(
 pub fn xml_to_loca(source: impl AsRef<Path>, dest: impl AsRef<Path>) -> Result<()> {
     maclarian::converter::convert_xml_to_loca(source.as_ref(), dest.as_ref()).map_err(Into::into)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::CONFIG_LSX;
+
+    #[test]
+    fn test_convert_detects_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("doc.lsx"), CONFIG_LSX).unwrap();
+
+        let to_lsf = convert(
+            dir.path().join("doc.lsx"),
+            dir.path().join("doc.bin"),
+            None,
+            Some(Format::Lsf),
+        );
+        // The .bin source has no known extension, so its format is sniffed
+        let back = convert(
+            dir.path().join("doc.bin"),
+            dir.path().join("back.lsx"),
+            None,
+            None,
+        );
+        let unsupported = convert(
+            dir.path().join("doc.lsx"),
+            dir.path().join("doc.png"),
+            None,
+            None,
+        );
+
+        let to_lsf = to_lsf.unwrap();
+        assert_eq!((to_lsf.input, to_lsf.output), (Format::Lsx, Format::Lsf));
+        assert!(to_lsf.bytes_written > 0);
+        assert_eq!(back.unwrap().input, Format::Lsf);
+        assert!(unsupported.is_err());
+    }
+}
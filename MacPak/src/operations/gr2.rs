@@ -13,8 +13,10 @@ pub use maclarian::formats::gr2::{
     extract_gr2_info, inspect_gr2,
 };
 
-// Re-export progress types
-pub use maclarian::converter::{Gr2Phase, Gr2Progress, Gr2ProgressCallback};
+// Re-export progress and write option types
+pub use maclarian::converter::{
+    Gr2Compression, Gr2Phase, Gr2Progress, Gr2ProgressCallback, Gr2WriteOptions,
+};
 
 /// Convert a GR2 file to GLB (binary glTF) format.
 ///
@@ -41,7 +43,8 @@ pub fn gr2_to_glb_with_progress(
 
 /// Convert a glTF/GLB file to GR2 format.
 ///
-/// Note: Currently outputs uncompressed GR2 files.
+/// Sections are written uncompressed; see [`gltf_to_gr2_with_options`] for
+/// `BitKnit` compression.
 ///
 /// # Errors
 ///
@@ -68,6 +71,26 @@ pub fn gltf_to_gr2_with_progress(
     .map_err(Into::into)
 }
 
+/// Convert a glTF/GLB file to GR2 with write options and progress callback.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or conversion fails.
+pub fn gltf_to_gr2_with_options(
+    source: impl AsRef<Path>,
+    dest: impl AsRef<Path>,
+    options: &Gr2WriteOptions,
+    progress: Gr2ProgressCallback,
+) -> Result<()> {
+    maclarian::converter::convert_gltf_to_gr2_with_options(
+        source.as_ref(),
+        dest.as_ref(),
+        options,
+        progress,
+    )
+    .map_err(Into::into)
+}
+
 /// Decompress all `BitKnit`-compressed sections in a GR2 file.
 ///
 /// # Errors
@@ -1,5 +1,8 @@
 pub mod conversion;
 pub mod extraction;
 pub mod gr2;
+pub mod packing;
+#[cfg(feature = "gui")]
+pub mod task_queue;
 pub mod validation;
 pub mod virtual_texture;
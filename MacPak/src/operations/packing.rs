@@ -0,0 +1,34 @@
+//! PAK packing operations
+
+use crate::error::Result;
+use maclarian::compression::CompressionSettings;
+use std::path::Path;
+
+pub use maclarian::pak::PakBuilder;
+
+/// Writes in-memory files to a new PAK archive.
+///
+/// Files are compressed and written one at a time as they're taken from
+/// `files`, so the iterator can produce them lazily. Returns the number of
+/// files written.
+///
+/// # Errors
+///
+/// Returns an error if a path is invalid or repeated, or the PAK cannot be
+/// written.
+pub fn pack_files<I>(
+    files: I,
+    output: impl AsRef<Path>,
+    settings: CompressionSettings,
+) -> Result<usize>
+where
+    I: IntoIterator<Item = (String, Vec<u8>)>,
+{
+    let mut builder = PakBuilder::write_to(output)?;
+    for (path, data) in files {
+        builder.add_file(&path, &data, settings)?;
+    }
+    let count = builder.len();
+    builder.finish()?;
+    Ok(count)
+}
@@ -0,0 +1,722 @@
+//! Queued operations with dependencies
+//!
+//! A [`TaskQueue`] is an ordered list of [`Task`]s such as "extract these
+//! PAKs, then convert the GR2s, then build the index". Each task declares the
+//! paths it reads and writes; a task waits for every earlier task whose
+//! outputs overlap its own paths, and tasks that don't touch each other run in
+//! parallel. [`TaskQueue::start`] runs the queue on a worker thread and
+//! reports each task's [`TaskStatus`] through the returned [`QueueRun`].
+//!
+//! Queues are saved as JSON so a workflow can be run again later.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread::{self, JoinHandle};
+
+use maclarian::pak::PakOperations;
+use maclarian::utils::CancelToken;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::conversion::{self, Format};
+use crate::error::{Error, Result};
+use crate::search::SearchIndex;
+
+/// Progress callback for a running task: `(current, total, current_file)`
+type TaskProgress<'a> = &'a (dyn Fn(usize, usize, Option<&str>) + Sync + Send);
+
+/// One step of a [`TaskQueue`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Task {
+    /// Extract a PAK into a directory
+    ExtractPak { pak: PathBuf, output: PathBuf },
+    /// Convert every `from` file under `source` to `to`, keeping the folder
+    /// layout under `output`
+    ConvertTree {
+        source: PathBuf,
+        output: PathBuf,
+        /// Source extension, e.g. `lsf`
+        from: String,
+        /// Target extension, e.g. `lsx`
+        to: String,
+    },
+    /// Build a full-text search index of PAKs and export it to `output`
+    BuildIndex { paks: Vec<PathBuf>, output: PathBuf },
+    /// Pack a directory into a PAK
+    PackPak { source: PathBuf, output: PathBuf },
+    /// Convert every GR2 under `source` to GLB, keeping the folder layout
+    /// under `output`
+    Gr2Batch { source: PathBuf, output: PathBuf },
+}
+
+impl Task {
+    /// Short description for task lists
+    pub fn label(&self) -> String {
+        match self {
+            Self::ExtractPak { pak, .. } => format!("Extract {}", file_name(pak)),
+            Self::ConvertTree {
+                source, from, to, ..
+            } => format!("Convert {} → {} in {}", from, to, file_name(source)),
+            Self::BuildIndex { paks, .. } => format!(
+                "Build index of {} {}",
+                paks.len(),
+                if paks.len() == 1 { "PAK" } else { "PAKs" }
+            ),
+            Self::PackPak { output, .. } => format!("Pack {}", file_name(output)),
+            Self::Gr2Batch { source, .. } => format!("Convert GR2 → GLB in {}", file_name(source)),
+        }
+    }
+
+    /// Files and directories the task reads
+    pub fn inputs(&self) -> Vec<&Path> {
+        match self {
+            Self::ExtractPak { pak, .. } => vec![pak],
+            Self::BuildIndex { paks, .. } => paks.iter().map(PathBuf::as_path).collect(),
+            Self::ConvertTree { source, .. }
+            | Self::PackPak { source, .. }
+            | Self::Gr2Batch { source, .. } => vec![source],
+        }
+    }
+
+    /// Files and directories the task writes
+    pub fn outputs(&self) -> Vec<&Path> {
+        match self {
+            Self::ExtractPak { output, .. }
+            | Self::ConvertTree { output, .. }
+            | Self::BuildIndex { output, .. }
+            | Self::PackPak { output, .. }
+            | Self::Gr2Batch { output, .. } => vec![output],
+        }
+    }
+
+    /// Whether one task writes a path the other reads or writes
+    ///
+    /// A path overlaps another if it's the same path or either contains the
+    /// other.
+    pub fn conflicts_with(&self, other: &Task) -> bool {
+        let writes = |a: &Task, b: &Task| {
+            a.outputs().iter().any(|out| {
+                b.inputs()
+                    .iter()
+                    .chain(b.outputs().iter())
+                    .any(|path| overlaps(out, path))
+            })
+        };
+        writes(self, other) || writes(other, self)
+    }
+
+    /// Run the task, returning a summary of what it did
+    ///
+    /// `cancel` is checked between files. Packing can only be cancelled
+    /// before it starts.
+    pub fn run(&self, cancel: &CancelToken, progress: TaskProgress) -> Result<String> {
+        cancel.checkpoint()?;
+        match self {
+            Self::ExtractPak { pak, output } => {
+                PakOperations::extract_cancellable(
+                    pak.as_path(),
+                    output.as_path(),
+                    cancel,
+                    &|p| progress(p.current, p.total, p.current_file.as_deref()),
+                )?;
+                Ok(format!("Extracted to {}", output.display()))
+            }
+            Self::ConvertTree {
+                source,
+                output,
+                from,
+                to,
+            } => {
+                let input = parse_format(from)?;
+                let target = parse_format(to)?;
+                let count =
+                    convert_tree(source, output, from, to, cancel, progress, |src, dest| {
+                        conversion::convert(src, dest, Some(input), Some(target)).map(|_| ())
+                    })?;
+                Ok(format!("Converted {} files", count))
+            }
+            Self::BuildIndex { paks, output } => {
+                let report = |p: &crate::search::SearchProgress| {
+                    progress(p.current, p.total, p.current_file.as_deref())
+                };
+                let mut index = SearchIndex::new();
+                let files = index.build_index_cancellable(paks, cancel, &report)?;
+                index.build_fulltext_index_cancellable(cancel, &report)?;
+                index.export_index_cancellable(output, cancel, &report)?;
+                Ok(format!("Indexed {} files", files))
+            }
+            Self::PackPak { source, output } => {
+                PakOperations::create_with_progress(source.as_path(), output.as_path(), &|p| {
+                    progress(p.current, p.total, p.current_file.as_deref())
+                })?;
+                Ok(format!("Packed {}", file_name(output)))
+            }
+            Self::Gr2Batch { source, output } => {
+                let count = convert_tree(
+                    source,
+                    output,
+                    "gr2",
+                    "glb",
+                    cancel,
+                    progress,
+                    |src, dest| super::gr2::gr2_to_glb(src, dest),
+                )?;
+                Ok(format!("Converted {} models", count))
+            }
+        }
+    }
+}
+
+/// Where a task is in its run
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum TaskStatus {
+    /// Waiting to start
+    #[default]
+    Pending,
+    /// Started; `total` is 0 until the task knows how much work there is
+    Running {
+        current: usize,
+        total: usize,
+        file: Option<String>,
+    },
+    /// Finished, with a summary
+    Done(String),
+    /// Stopped by an error
+    Failed(String),
+    /// Cancelled before or while running
+    Cancelled,
+    /// Not run because a task it depends on didn't finish
+    Skipped,
+}
+
+impl TaskStatus {
+    /// Whether the task has stopped, successfully or not
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, Self::Pending | Self::Running { .. })
+    }
+}
+
+/// A status change reported by a [`QueueRun`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskEvent {
+    /// Index of the task in its queue
+    pub index: usize,
+    pub status: TaskStatus,
+}
+
+/// An ordered list of tasks
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskQueue {
+    pub tasks: Vec<Task>,
+}
+
+impl TaskQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a task to the end of the queue, returning its index
+    pub fn push(&mut self, task: Task) -> usize {
+        self.tasks.push(task);
+        self.tasks.len() - 1
+    }
+
+    /// Read a queue saved with [`save`](Self::save)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Write the queue as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Indices of the earlier tasks that must finish before task `index` starts
+    pub fn dependencies(&self, index: usize) -> Vec<usize> {
+        let task = &self.tasks[index];
+        (0..index)
+            .filter(|&i| self.tasks[i].conflicts_with(task))
+            .collect()
+    }
+
+    /// Run every task on a worker thread
+    pub fn start(&self) -> QueueRun {
+        self.start_tasks(&(0..self.tasks.len()).collect::<Vec<_>>())
+    }
+
+    /// Run some of the tasks on a worker thread, e.g. to retry a failed one
+    ///
+    /// Dependencies on tasks that aren't in `indices` are ignored, so a task
+    /// can be retried without re-running the tasks before it.
+    pub fn start_tasks(&self, indices: &[usize]) -> QueueRun {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let jobs: Vec<Job> = indices
+            .iter()
+            .map(|&index| Job {
+                index,
+                task: self.tasks[index].clone(),
+                dependencies: self
+                    .dependencies(index)
+                    .into_iter()
+                    .filter(|d| indices.contains(d))
+                    .collect(),
+                cancel: CancelToken::new(),
+            })
+            .collect();
+        let cancels = jobs.iter().map(|j| (j.index, j.cancel.clone())).collect();
+
+        let (sender, events) = mpsc::channel();
+        let worker = thread::spawn(move || run_jobs(jobs, &sender));
+
+        QueueRun {
+            events,
+            cancels,
+            worker: Some(worker),
+        }
+    }
+}
+
+/// A queue running on a worker thread
+///
+/// Dropping it doesn't stop the run; call [`cancel_all`](Self::cancel_all)
+/// first for that.
+pub struct QueueRun {
+    events: Receiver<TaskEvent>,
+    cancels: HashMap<usize, CancelToken>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl QueueRun {
+    /// Status changes since the last call, in the order they happened
+    ///
+    /// Never blocks, so a GUI can poll it on a timer.
+    pub fn poll(&mut self) -> Vec<TaskEvent> {
+        let mut events = Vec::new();
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => events.push(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if let Some(worker) = self.worker.take() {
+                        let _ = worker.join();
+                    }
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    /// Block until every task has finished, returning the events not yet polled
+    pub fn wait(mut self) -> Vec<TaskEvent> {
+        let events = self.events.iter().collect();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        events
+    }
+
+    /// Whether the worker thread has finished and every event has been polled
+    pub fn is_finished(&self) -> bool {
+        self.worker.is_none()
+    }
+
+    /// Whether task `index` is part of this run
+    pub fn contains(&self, index: usize) -> bool {
+        self.cancels.contains_key(&index)
+    }
+
+    /// Cancel one task; tasks that depend on it are skipped
+    pub fn cancel(&self, index: usize) {
+        if let Some(token) = self.cancels.get(&index) {
+            token.cancel();
+        }
+    }
+
+    /// Cancel every task that hasn't finished
+    pub fn cancel_all(&self) {
+        for token in self.cancels.values() {
+            token.cancel();
+        }
+    }
+}
+
+/// A task scheduled in a run
+struct Job {
+    index: usize,
+    task: Task,
+    dependencies: Vec<usize>,
+    cancel: CancelToken,
+}
+
+/// Start each job once its dependencies have succeeded, running independent
+/// jobs side by side
+fn run_jobs(jobs: Vec<Job>, events: &mpsc::Sender<TaskEvent>) {
+    let send = |index, status| {
+        let _ = events.send(TaskEvent { index, status });
+    };
+    // Task index -> whether it succeeded
+    let mut finished: HashMap<usize, bool> = HashMap::new();
+    let mut pending = jobs;
+
+    thread::scope(|scope| {
+        let (done_tx, done_rx) = mpsc::channel();
+        let mut running = 0;
+
+        loop {
+            // Jobs only depend on earlier ones, so one pass in order also
+            // skips everything downstream of a failure
+            let mut waiting = Vec::new();
+            for job in pending {
+                let failed_dependency = job
+                    .dependencies
+                    .iter()
+                    .any(|d| finished.get(d) == Some(&false));
+                if failed_dependency || job.cancel.is_cancelled() {
+                    finished.insert(job.index, false);
+                    let status = if failed_dependency {
+                        TaskStatus::Skipped
+                    } else {
+                        TaskStatus::Cancelled
+                    };
+                    send(job.index, status);
+                } else if job.dependencies.iter().all(|d| finished.contains_key(d)) {
+                    running += 1;
+                    let done_tx = done_tx.clone();
+                    let events = events.clone();
+                    scope.spawn(move || {
+                        let index = job.index;
+                        let report = |status| {
+                            let _ = events.send(TaskEvent { index, status });
+                        };
+                        report(TaskStatus::Running {
+                            current: 0,
+                            total: 0,
+                            file: None,
+                        });
+                        let result = job.task.run(&job.cancel, &|current, total, file| {
+                            report(TaskStatus::Running {
+                                current,
+                                total,
+                                file: file.map(str::to_string),
+                            });
+                        });
+                        let status = match result {
+                            Ok(summary) => TaskStatus::Done(summary),
+                            Err(Error::MacLarian(maclarian::Error::Cancelled)) => {
+                                TaskStatus::Cancelled
+                            }
+                            Err(e) => TaskStatus::Failed(e.to_string()),
+                        };
+                        let succeeded = matches!(status, TaskStatus::Done(_));
+                        report(status);
+                        let _ = done_tx.send((index, succeeded));
+                    });
+                } else {
+                    waiting.push(job);
+                }
+            }
+            pending = waiting;
+
+            if running == 0 {
+                break;
+            }
+            let Ok((index, succeeded)) = done_rx.recv() else {
+                break;
+            };
+            running -= 1;
+            finished.insert(index, succeeded);
+        }
+    });
+}
+
+/// Convert every `from` file under `source` into `output`, returning the
+/// number converted
+///
+/// Every file is attempted; the task fails afterwards if any of them failed.
+fn convert_tree(
+    source: &Path,
+    output: &Path,
+    from: &str,
+    to: &str,
+    cancel: &CancelToken,
+    progress: TaskProgress,
+    convert: impl Fn(&Path, &Path) -> Result<()>,
+) -> Result<usize> {
+    let files: Vec<PathBuf> = WalkDir::new(source)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(from))
+        })
+        .collect();
+
+    let total = files.len();
+    let mut failures = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        cancel.checkpoint()?;
+        let relative = file.strip_prefix(source).unwrap_or(file);
+        progress(i + 1, total, Some(&relative.to_string_lossy()));
+
+        let dest = output.join(relative).with_extension(to);
+        let result = match dest.parent() {
+            Some(parent) => std::fs::create_dir_all(parent).map_err(Error::from),
+            None => Ok(()),
+        }
+        .and_then(|()| convert(file, &dest));
+        if let Err(e) = result {
+            failures.push(format!("{}: {}", relative.display(), e));
+        }
+    }
+
+    match failures.first() {
+        None => Ok(total),
+        Some(first) => Err(Error::Workbench(format!(
+            "{} of {} files failed to convert (first: {})",
+            failures.len(),
+            total,
+            first
+        ))),
+    }
+}
+
+fn parse_format(ext: &str) -> Result<Format> {
+    Format::from_extension(ext)
+        .ok_or_else(|| Error::Workbench(format!("Unknown conversion format: {}", ext)))
+}
+
+/// Whether one path is the other or contains it
+fn overlaps(a: &Path, b: &Path) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::CONFIG_LSX;
+
+    #[test]
+    fn test_queue_runs_in_dependency_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lsx/Public")).unwrap();
+        std::fs::write(dir.path().join("lsx/Public/meta.lsx"), CONFIG_LSX).unwrap();
+
+        let mut queue = TaskQueue::new();
+        queue.push(Task::ConvertTree {
+            source: dir.path().join("lsx"),
+            output: dir.path().join("lsf"),
+            from: "lsx".to_string(),
+            to: "lsf".to_string(),
+        });
+        queue.push(Task::ConvertTree {
+            source: dir.path().join("lsf"),
+            output: dir.path().join("back"),
+            from: "lsf".to_string(),
+            to: "lsx".to_string(),
+        });
+        queue.push(Task::ExtractPak {
+            pak: dir.path().join("missing.pak"),
+            output: dir.path().join("extracted"),
+        });
+        queue.push(Task::PackPak {
+            source: dir.path().join("extracted/Mods"),
+            output: dir.path().join("Repacked.pak"),
+        });
+
+        assert_eq!(queue.dependencies(1), [0]);
+        assert!(queue.dependencies(2).is_empty());
+        assert_eq!(queue.dependencies(3), [2]);
+
+        let saved = dir.path().join("queue.json");
+        queue.save(&saved).unwrap();
+        assert_eq!(TaskQueue::load(&saved).unwrap(), queue);
+
+        let mut statuses = vec![TaskStatus::Pending; queue.tasks.len()];
+        for event in queue.start().wait() {
+            statuses[event.index] = event.status;
+        }
+        let converted_back = dir.path().join("back/Public/meta.lsx").exists();
+
+        assert_eq!(
+            statuses[0],
+            TaskStatus::Done("Converted 1 files".to_string())
+        );
+        assert_eq!(
+            statuses[1],
+            TaskStatus::Done("Converted 1 files".to_string())
+        );
+        assert!(matches!(statuses[2], TaskStatus::Failed(_)));
+        assert_eq!(statuses[3], TaskStatus::Skipped);
+        assert!(converted_back);
+    }
+
+    fn convert(source: PathBuf, output: PathBuf, from: &str, to: &str) -> Task {
+        Task::ConvertTree {
+            source,
+            output,
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    /// Last status reported for each task
+    fn final_statuses(events: impl IntoIterator<Item = TaskEvent>) -> HashMap<usize, TaskStatus> {
+        events.into_iter().map(|e| (e.index, e.status)).collect()
+    }
+
+    #[test]
+    fn test_cancelled_task_skips_dependents() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lsx/Public")).unwrap();
+        std::fs::write(dir.path().join("lsx/Public/meta.lsx"), CONFIG_LSX).unwrap();
+
+        let mut queue = TaskQueue::new();
+        queue.push(convert(
+            dir.path().join("lsx"),
+            dir.path().join("lsf"),
+            "lsx",
+            "lsf",
+        ));
+        queue.push(convert(
+            dir.path().join("lsf"),
+            dir.path().join("back"),
+            "lsf",
+            "lsx",
+        ));
+        queue.push(convert(
+            dir.path().join("lsx"),
+            dir.path().join("lsj"),
+            "lsx",
+            "lsj",
+        ));
+        assert_eq!(queue.dependencies(1), [0]);
+        assert!(queue.dependencies(2).is_empty());
+
+        // Cancel task 0 before the run schedules it, as QueueRun::cancel does
+        let jobs: Vec<Job> = (0..queue.tasks.len())
+            .map(|index| Job {
+                index,
+                task: queue.tasks[index].clone(),
+                dependencies: queue.dependencies(index),
+                cancel: CancelToken::new(),
+            })
+            .collect();
+        jobs[0].cancel.cancel();
+        let (sender, events) = mpsc::channel();
+        run_jobs(jobs, &sender);
+        drop(sender);
+        let statuses = final_statuses(events);
+        let converted = (
+            dir.path().join("lsf").exists(),
+            dir.path().join("lsj").exists(),
+        );
+
+        assert_eq!(statuses[&0], TaskStatus::Cancelled);
+        assert_eq!(statuses[&1], TaskStatus::Skipped);
+        assert_eq!(
+            statuses[&2],
+            TaskStatus::Done("Converted 1 files".to_string())
+        );
+        assert_eq!(converted, (false, true));
+    }
+
+    #[test]
+    fn test_failure_skips_dependents_transitively() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lsx")).unwrap();
+        std::fs::write(dir.path().join("lsx/meta.lsx"), CONFIG_LSX).unwrap();
+
+        let mut queue = TaskQueue::new();
+        queue.push(Task::ExtractPak {
+            pak: dir.path().join("missing.pak"),
+            output: dir.path().join("extracted"),
+        });
+        queue.push(Task::PackPak {
+            source: dir.path().join("extracted/Mods"),
+            output: dir.path().join("Repacked.pak"),
+        });
+        queue.push(Task::ExtractPak {
+            pak: dir.path().join("Repacked.pak"),
+            output: dir.path().join("repacked"),
+        });
+        queue.push(convert(
+            dir.path().join("lsx"),
+            dir.path().join("lsf"),
+            "lsx",
+            "lsf",
+        ));
+        assert_eq!(queue.dependencies(1), [0]);
+        assert_eq!(queue.dependencies(2), [1]);
+        assert!(queue.dependencies(3).is_empty());
+
+        let statuses = final_statuses(queue.start().wait());
+
+        assert!(matches!(statuses[&0], TaskStatus::Failed(_)));
+        assert_eq!(statuses[&1], TaskStatus::Skipped);
+        assert_eq!(statuses[&2], TaskStatus::Skipped);
+        assert_eq!(
+            statuses[&3],
+            TaskStatus::Done("Converted 1 files".to_string())
+        );
+    }
+
+    #[test]
+    fn test_start_tasks_retries_failed_task() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("lsx")).unwrap();
+        std::fs::write(dir.path().join("lsx/meta.lsx"), "</save>").unwrap();
+
+        let mut queue = TaskQueue::new();
+        queue.push(convert(
+            dir.path().join("lsx"),
+            dir.path().join("lsf"),
+            "lsx",
+            "lsf",
+        ));
+        queue.push(convert(
+            dir.path().join("lsf"),
+            dir.path().join("back"),
+            "lsf",
+            "lsx",
+        ));
+
+        let first = final_statuses(queue.start().wait());
+
+        // Fix the input and retry both tasks
+        std::fs::write(dir.path().join("lsx/meta.lsx"), CONFIG_LSX).unwrap();
+        let retried = final_statuses(queue.start_tasks(&[0, 1]).wait());
+
+        // Retrying only the second task ignores its dependency on the first
+        std::fs::remove_dir_all(dir.path().join("back")).unwrap();
+        let second_only = queue.start_tasks(&[1]).wait();
+        let converted_back = dir.path().join("back/meta.lsx").exists();
+
+        assert!(matches!(first[&0], TaskStatus::Failed(_)));
+        assert_eq!(first[&1], TaskStatus::Skipped);
+
+        let done = TaskStatus::Done("Converted 1 files".to_string());
+        assert_eq!(retried[&0], done);
+        assert_eq!(retried[&1], done);
+
+        assert!(second_only.iter().all(|e| e.index == 1));
+        assert_eq!(final_statuses(second_only)[&1], done);
+        assert!(converted_back);
+    }
+}
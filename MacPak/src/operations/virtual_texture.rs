@@ -15,6 +15,7 @@ pub use maclarian::virtual_texture::{
     GtsFile,
     GtsInfo,
     PageFileInfo,
+    TextureInfo,
     VirtualTextureExtractor,
     extract_all as maclarian_extract_all,
     find_base_name,
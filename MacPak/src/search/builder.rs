@@ -2,17 +2,25 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use rayon::prelude::*;
 
-use maclarian::error::Result;
-use maclarian::pak::PakReaderCache;
+use maclarian::error::{Error, Result};
 use maclarian::pak::lspk::LspkReader;
+use maclarian::pak::{PakReaderCache, find_game_paks};
+use maclarian::utils::{BatchOutcome, CancelToken, ErrorCollector, ErrorPolicy};
 
-use super::SearchIndex;
 use super::extract;
 use super::fulltext::FullTextIndex;
-use super::types::{FileType, IndexedFile, SearchPhase, SearchProgress, SearchProgressCallback};
+use super::types::{
+    FileType, GameSource, IndexedFile, IndexedFileRef, SearchPhase, SearchProgress,
+    SearchProgressCallback,
+};
+use super::{SearchIndex, unix_secs};
+
+/// Bytes read from the start of each file to detect its type
+const SNIFF_LEN: usize = 64;
 
 impl SearchIndex {
     /// Build index from multiple PAK files
@@ -37,6 +45,23 @@ impl SearchIndex {
         &mut self,
         pak_paths: &[PathBuf],
         progress: SearchProgressCallback,
+    ) -> Result<usize> {
+        self.build_index_cancellable(pak_paths, &CancelToken::new(), progress)
+    }
+
+    /// Build the search index, stopping early once `cancel` is triggered
+    ///
+    /// PAKs already being scanned when `cancel` is triggered are finished, then the
+    /// partial index is discarded.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if cancelled, or an error if any PAK file
+    /// cannot be read.
+    pub fn build_index_cancellable(
+        &mut self,
+        pak_paths: &[PathBuf],
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
     ) -> Result<usize> {
         self.clear();
 
@@ -53,9 +78,14 @@ impl SearchIndex {
         // Process each PAK file in parallel
         let pak_entries: Vec<Result<Vec<IndexedFile>>> = pak_paths
             .par_iter()
-            .map(|pak_path| Self::index_single_pak(pak_path))
+            .map(|pak_path| {
+                cancel.checkpoint()?;
+                Self::index_single_pak(pak_path)
+            })
             .collect();
 
+        cancel.checkpoint()?;
+
         // Merge results sequentially (to avoid lock contention)
         for (i, (pak_path, result)) in pak_paths.iter().zip(pak_entries).enumerate() {
             progress(&SearchProgress::with_file(
@@ -67,20 +97,11 @@ impl SearchIndex {
 
             match result {
                 Ok(entries) => {
+                    // Registered first so PAKs without files are still listed
+                    self.indexed_paks.push(pak_path.clone());
                     for entry in entries {
-                        let path_key = entry.path.clone();
-                        let filename_key = entry.name.to_lowercase();
-
-                        // Add to filename index
-                        self.filename_index
-                            .entry(filename_key)
-                            .or_default()
-                            .push(path_key.clone());
-
-                        // Add to main entries
-                        self.entries.insert(path_key, entry);
+                        self.add_entry(entry);
                     }
-                    self.indexed_paks.push(pak_path.clone());
                 }
                 Err(e) => {
                     tracing::warn!("Failed to index {}: {}", pak_path.display(), e);
@@ -90,21 +111,77 @@ impl SearchIndex {
 
         self.file_count = self.entries.len();
         self.indexed = true;
+        self.built_at = Some(unix_secs(SystemTime::now()));
 
         progress(&SearchProgress::new(SearchPhase::Complete, total, total));
 
         let elapsed = start.elapsed();
         tracing::info!(
-            "Indexed {} files from {} PAKs in {:.2}s",
+            "Indexed {} files from {} PAKs in {:.2}s (entry store ~{:.1} MB)",
             self.file_count,
             self.indexed_paks.len(),
-            elapsed.as_secs_f64()
+            elapsed.as_secs_f64(),
+            self.entries.heap_bytes() as f64 / (1024.0 * 1024.0)
         );
 
         Ok(self.file_count)
     }
 
+    /// Build the index from every PAK of a game install
+    ///
+    /// `dir` is the install or its `Data` folder. PAKs are indexed in the
+    /// game's load order, including `Localization/` and DLC subfolders, so a
+    /// file in a patch replaces the base game's copy. With `skip_media`,
+    /// video, audio and shader cache PAKs are left out. The install is
+    /// recorded so [`Self::stats`] can report PAKs added to it later.
+    ///
+    /// # Errors
+    /// Returns an error if `dir` is not a game install or any PAK file
+    /// cannot be read.
+    pub fn build_game_index(&mut self, dir: &Path, skip_media: bool) -> Result<usize> {
+        self.build_game_index_cancellable(dir, skip_media, &CancelToken::new(), &|_| {})
+    }
+
+    /// Build the index from a game install with progress callback
+    ///
+    /// # Errors
+    /// Returns an error if `dir` is not a game install or any PAK file
+    /// cannot be read.
+    pub fn build_game_index_with_progress(
+        &mut self,
+        dir: &Path,
+        skip_media: bool,
+        progress: SearchProgressCallback,
+    ) -> Result<usize> {
+        self.build_game_index_cancellable(dir, skip_media, &CancelToken::new(), progress)
+    }
+
+    /// Build the index from a game install, stopping early once `cancel` is
+    /// triggered
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if cancelled, or an error if `dir` is not
+    /// a game install or any PAK file cannot be read.
+    pub fn build_game_index_cancellable(
+        &mut self,
+        dir: &Path,
+        skip_media: bool,
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
+    ) -> Result<usize> {
+        let paks = find_game_paks(dir, skip_media)?;
+        let file_count = self.build_index_cancellable(&paks, cancel, progress)?;
+        self.game = Some(GameSource {
+            dir: dir.to_path_buf(),
+            skip_media,
+        });
+        Ok(file_count)
+    }
+
     /// Index a single PAK file
+    ///
+    /// Reads the first [`SNIFF_LEN`] bytes of each file to detect its type.
+    /// Files that can't be read keep only their declared type.
     pub(super) fn index_single_pak(pak_path: &Path) -> Result<Vec<IndexedFile>> {
         let file = std::fs::File::open(pak_path)?;
         let mut reader = LspkReader::with_path(file, pak_path);
@@ -130,11 +207,17 @@ impl SearchIndex {
                     .map(|e| e.to_string_lossy().to_string())
                     .unwrap_or_default();
 
+                let detected_type = reader
+                    .read_file_prefix(&e, SNIFF_LEN)
+                    .ok()
+                    .and_then(|head| FileType::from_magic(&head));
+
                 IndexedFile {
                     name,
                     path: path_str,
                     pak_file: pak_path.to_path_buf(),
-                    file_type: FileType::from_extension(&ext),
+                    declared_type: FileType::from_extension(&ext),
+                    detected_type,
                     size: u64::from(e.size_decompressed),
                 }
             })
@@ -153,18 +236,60 @@ impl SearchIndex {
     /// # Errors
     /// Returns an error if file extraction or indexing fails.
     pub fn build_fulltext_index(&mut self, progress: SearchProgressCallback) -> Result<usize> {
+        self.build_fulltext_index_cancellable(&CancelToken::new(), progress)
+    }
+
+    /// Build the full-text index, stopping early once `cancel` is triggered
+    ///
+    /// Cancelling discards the partial full-text index; the metadata index is
+    /// kept. PAKs whose contents can't be read are skipped and logged; use
+    /// [`Self::build_fulltext_index_with_policy`] to get them back.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if cancelled, or an error if indexing
+    /// fails.
+    pub fn build_fulltext_index_cancellable(
+        &mut self,
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
+    ) -> Result<usize> {
+        let outcome =
+            self.build_fulltext_index_with_policy(ErrorPolicy::Collect, cancel, progress)?;
+        for (pak_path, e) in &outcome.failed {
+            tracing::warn!("Failed to read contents of {}: {}", pak_path.display(), e);
+        }
+        Ok(self
+            .fulltext
+            .as_ref()
+            .map_or(0, |fulltext| fulltext.num_docs() as usize))
+    }
+
+    /// Build the full-text index with an explicit [`ErrorPolicy`]
+    ///
+    /// Returns the PAKs whose contents were indexed and those that couldn't be
+    /// read. With [`ErrorPolicy::FailFast`] the first unreadable PAK aborts the
+    /// build and the partial full-text index is discarded.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if cancelled, the PAK's error if one can't
+    /// be read under [`ErrorPolicy::FailFast`], or an error if indexing fails.
+    pub fn build_fulltext_index_with_policy(
+        &mut self,
+        policy: ErrorPolicy,
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
+    ) -> Result<BatchOutcome<PathBuf>> {
         if !self.indexed {
-            return Ok(0);
+            return Ok(BatchOutcome::default());
         }
 
         // Create new fulltext index
         let fulltext = FullTextIndex::new()?;
 
         // Collect searchable files (skip tiny files < 100 bytes)
-        let searchable_files: Vec<&IndexedFile> = self
-            .entries
-            .values()
-            .filter(|f| f.file_type.is_searchable_text())
+        let searchable_files: Vec<IndexedFileRef> = self
+            .entry_views()
+            .filter(|f| f.file_type().is_searchable_text())
             .filter(|f| f.size >= 100) // Skip tiny files
             .collect();
 
@@ -177,17 +302,21 @@ impl SearchIndex {
         ));
 
         // Group files by PAK for efficient reading
-        let mut by_pak: HashMap<PathBuf, Vec<&IndexedFile>> = HashMap::new();
-        for file in &searchable_files {
-            by_pak.entry(file.pak_file.clone()).or_default().push(file);
+        let mut by_pak: HashMap<&Path, Vec<IndexedFileRef>> = HashMap::new();
+        for file in searchable_files {
+            by_pak.entry(file.pak_file).or_default().push(file);
         }
 
         // Get a writer with 500MB heap (larger = fewer internal commits)
         let mut writer = fulltext.writer(500_000_000)?;
         let mut indexed_count = 0;
+        let collector = ErrorCollector::new(policy);
+        let mut read_paks = Vec::with_capacity(by_pak.len());
 
         // Process each PAK using bulk reading (sorted by offset, parallel decompress)
-        for (pak_path, files) in &by_pak {
+        for (&pak_path, files) in &by_pak {
+            cancel.checkpoint()?;
+
             let pak_name = pak_path.file_name().map_or_else(
                 || "Unknown".to_string(),
                 |n| n.to_string_lossy().to_string(),
@@ -201,25 +330,32 @@ impl SearchIndex {
             ));
 
             // Collect all file paths for bulk reading
-            let file_paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+            let file_paths: Vec<&str> = files.iter().map(|f| f.path).collect();
 
             // Create cache and do bulk read (sorted by offset, parallel decompress)
             let mut cache = PakReaderCache::new(1);
-            let bulk_bytes = cache
-                .read_files_bulk(pak_path, &file_paths)
-                .unwrap_or_default();
+            let bulk_bytes = match cache.read_files_bulk(pak_path, &file_paths) {
+                Ok(bulk_bytes) => bulk_bytes,
+                Err(e) => {
+                    collector.record(pak_path, e);
+                    if collector.should_stop() {
+                        break;
+                    }
+                    continue;
+                }
+            };
 
             // Build list of (file, bytes) pairs
-            let file_bytes: Vec<(&IndexedFile, &Vec<u8>)> = files
+            let file_bytes: Vec<(IndexedFileRef, &Vec<u8>)> = files
                 .iter()
-                .filter_map(|file| bulk_bytes.get(&file.path).map(|bytes| (*file, bytes)))
+                .filter_map(|file| bulk_bytes.get(file.path).map(|bytes| (*file, bytes)))
                 .collect();
 
             // Extract text in parallel (CPU bound)
-            let extracted: Vec<(&IndexedFile, String)> = file_bytes
+            let extracted: Vec<(IndexedFileRef, String)> = file_bytes
                 .par_iter()
                 .map(|(file, bytes)| {
-                    let text = extract::extract_text(bytes, file.file_type);
+                    let text = extract::extract_text(bytes, file.file_type());
                     (*file, text)
                 })
                 .collect();
@@ -242,19 +378,26 @@ impl SearchIndex {
 
                 fulltext.add_document(
                     &writer,
-                    &file.path,
-                    &file.name,
+                    file.path,
+                    file.name,
                     &text,
                     &pak_path.to_string_lossy(),
-                    file.file_type.display_name(),
+                    file.file_type().display_name(),
                 )?;
             }
+            read_paks.push(pak_path.to_path_buf());
+        }
+
+        // Fail fast: the partial index is dropped with the writer
+        let mut outcome = collector.finish(read_paks);
+        if outcome.stopped {
+            return Err(outcome.failed.remove(0).1);
         }
 
         // Commit and reload
         writer
             .commit()
-            .map_err(|e| maclarian::error::Error::SearchError(format!("Commit failed: {e}")))?;
+            .map_err(|e| Error::SearchError(format!("Commit failed: {e}")))?;
         fulltext.reload()?;
 
         tracing::info!(
@@ -270,6 +413,229 @@ impl SearchIndex {
         ));
 
         self.fulltext = Some(fulltext);
-        Ok(indexed_count)
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fulltext_reports_unreadable_paks() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("Missing.pak");
+        let mut index = SearchIndex::new();
+        index.add_entry(IndexedFile {
+            name: "Doc.lsx".to_string(),
+            path: "Public/Test/Doc.lsx".to_string(),
+            pak_file: missing.clone(),
+            declared_type: FileType::Lsx,
+            detected_type: None,
+            size: 1000,
+        });
+        index.indexed = true;
+
+        let cancel = CancelToken::new();
+        let outcome = index
+            .build_fulltext_index_with_policy(ErrorPolicy::Collect, &cancel, &|_| {})
+            .unwrap();
+        assert!(outcome.succeeded.is_empty());
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].0, missing);
+
+        let result =
+            index.build_fulltext_index_with_policy(ErrorPolicy::FailFast, &cancel, &|_| {});
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cancelled_build_scans_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let paks: Vec<PathBuf> = (0..64)
+            .map(|i| dir.path().join(format!("Cancel_{i}.pak")))
+            .collect();
+        let cancel = CancelToken::new();
+        cancel.cancel();
+
+        let mut index = SearchIndex::new();
+        let result = index.build_index_cancellable(&paks, &cancel, &|_| {});
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(index.entries.len(), 0);
+    }
+
+    #[test]
+    fn test_index_detects_misnamed_files() {
+        use maclarian::compression::CompressionSettings;
+        use maclarian::pak::PakBuilder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Sniff.pak");
+        let lsx = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<save>\n</save>\n";
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        builder
+            .add_file("Public/Test/_merged.lsf", lsx, CompressionSettings::lz4())
+            .unwrap();
+        builder
+            .add_file("Public/Test/Real.lsx", lsx, CompressionSettings::zlib(6))
+            .unwrap();
+        builder
+            .add_file(
+                "Public/Test/blob",
+                b"LSOF\x07\0\0\0",
+                CompressionSettings::none(),
+            )
+            .unwrap();
+        builder.finish().unwrap();
+
+        let mut index = SearchIndex::new();
+        let built = index.build_index(&[pak]);
+        assert_eq!(built.unwrap(), 3);
+
+        let merged = index.get_by_path("Public/Test/_merged.lsf").unwrap();
+        assert_eq!(merged.declared_type, FileType::Lsf);
+        assert_eq!(merged.detected_type, Some(FileType::Lsx));
+        assert_eq!(merged.file_type(), FileType::Lsx);
+        assert_eq!(
+            index.get_by_path("Public/Test/blob").unwrap().detected_type,
+            Some(FileType::Lsf)
+        );
+        assert!(
+            !index
+                .get_by_path("Public/Test/Real.lsx")
+                .unwrap()
+                .is_misnamed()
+        );
+
+        let misnamed: Vec<&str> = index.misnamed_files().iter().map(|f| f.path).collect();
+        assert_eq!(misnamed, ["Public/Test/_merged.lsf", "Public/Test/blob"]);
+
+        assert_eq!(
+            index.search_path("Public/Test", Some(FileType::Lsx)).len(),
+            2
+        );
+        index.set_strict_types(true);
+        assert_eq!(
+            index.search_path("Public/Test", Some(FileType::Lsx)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_game_index_follows_load_order() {
+        use maclarian::compression::CompressionSettings;
+        use maclarian::pak::PakBuilder;
+
+        let game = tempfile::tempdir().unwrap();
+        let data = game.path().join("Data");
+        let write_pak = |path: &Path, size: usize| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let mut builder = PakBuilder::write_to(path).unwrap();
+            builder
+                .add_file(
+                    "Public/Shared/Stats/Generated/Data/Armor.txt",
+                    &vec![b' '; size],
+                    CompressionSettings::none(),
+                )
+                .unwrap();
+            builder.finish().unwrap();
+        };
+        write_pak(&data.join("Patch1_Hotfix2.pak"), 3);
+        write_pak(&data.join("Shared.pak"), 1);
+        write_pak(&data.join("Localization/English.pak"), 2);
+        write_pak(&data.join("Gustav_Video.pak"), 4);
+
+        let mut index = SearchIndex::new();
+        let built = index.build_game_index(game.path(), true);
+        let armor = index
+            .get_by_path("Public/Shared/Stats/Generated/Data/Armor.txt")
+            .map(|entry| (entry.size, entry.pak_file.to_path_buf()));
+        let fresh = index.stats().stale_paks;
+        write_pak(&data.join("Patch2.pak"), 5);
+        let stale = index.stats().stale_paks;
+
+        assert_eq!(built.unwrap(), 1);
+        assert_eq!(index.pak_count(), 3);
+        // The patch is indexed last, so its copy wins
+        assert_eq!(armor, Some((3, data.join("Patch1_Hotfix2.pak"))));
+        assert_eq!(
+            index.game_source(),
+            Some(&GameSource {
+                dir: game.path().to_path_buf(),
+                skip_media: true,
+            })
+        );
+        assert!(fresh.is_empty());
+        assert_eq!(stale, [data.join("Patch2.pak")]);
+    }
+
+    #[test]
+    fn test_fulltext_finds_byte_swapped_guid() {
+        use maclarian::compression::CompressionSettings;
+        use maclarian::converter::convert_bytes;
+        use maclarian::pak::PakBuilder;
+        use maclarian::utils::GuidByteOrder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Guid.pak");
+        let swapped = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<save>\n\t\
+            <attribute id=\"MapKey\" type=\"FixedString\" \
+            value=\"78563412-bc9a-f0de-2211-443366558877\" />\n</save>\n";
+        // A guid attribute holding the GUID's bytes in text order, which the
+        // LSF reader shows byte-swapped
+        let lsf = convert_bytes(
+            br#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+    <version major="4" minor="0" revision="9" build="331" lslib_meta="v1,bswap_guids" />
+    <region id="Templates">
+        <node id="Templates">
+            <attribute id="MapKey" type="guid" value="78563412-bc9a-f0de-2211-443366558877" />
+        </node>
+    </region>
+</save>"#,
+            "lsx",
+            "lsf",
+        )
+        .unwrap();
+        let text_order = [
+            0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+            0x77, 0x88,
+        ];
+        assert!(lsf.windows(16).any(|w| w == text_order));
+
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        builder
+            .add_file(
+                "Public/Test/Swapped.lsx",
+                swapped,
+                CompressionSettings::none(),
+            )
+            .unwrap();
+        builder
+            .add_file("Public/Test/Swapped.lsf", &lsf, CompressionSettings::none())
+            .unwrap();
+        builder.finish().unwrap();
+
+        let mut index = SearchIndex::new();
+        index.build_index(&[pak]).unwrap();
+        let built = index.build_fulltext_index(&|_| {});
+        assert_eq!(built.unwrap(), 2);
+
+        let matches = index
+            .search_fulltext_uuid("12345678-9abc-def0-1122-334455667788", 10)
+            .unwrap();
+        let mut found: Vec<(&str, GuidByteOrder)> = matches
+            .iter()
+            .map(|m| (m.result.path.as_str(), m.order))
+            .collect();
+        found.sort_by_key(|(path, _)| *path);
+        assert_eq!(
+            found,
+            [
+                ("Public/Test/Swapped.lsf", GuidByteOrder::ByteSwapped),
+                ("Public/Test/Swapped.lsx", GuidByteOrder::ByteSwapped),
+            ]
+        );
+        assert!(index.search_fulltext_uuid("not a guid", 10).is_none());
     }
 }
@@ -7,12 +7,15 @@
 
 #![allow(clippy::cast_precision_loss)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use maclarian::converter::to_lsx;
 use maclarian::error::{Error, Result};
+use maclarian::formats::lsb::parse_lsb_bytes;
 use maclarian::formats::lsf::parse_lsf_bytes;
+use maclarian::formats::lsx::serialize_lsx;
 use maclarian::pak::PakOperations;
 
 use super::{FileType, IndexedFile};
@@ -20,6 +23,12 @@ use super::{FileType, IndexedFile};
 /// Default maximum number of cached content entries
 const DEFAULT_MAX_ENTRIES: usize = 50;
 
+/// Maximum number of cached image previews
+const MAX_PREVIEWS: usize = 32;
+
+/// Maximum total size of cached content-scan text
+const MAX_SCAN_TEXT_BYTES: usize = 256 * 1024 * 1024;
+
 /// A cached content entry
 #[derive(Debug, Clone)]
 pub struct CachedContent {
@@ -35,6 +44,19 @@ pub struct CachedContent {
     pub internal_path: String,
 }
 
+/// A decoded, downscaled image preview
+#[derive(Debug, Clone)]
+pub struct CachedPreview {
+    /// Preview width in pixels
+    pub width: u32,
+    /// Preview height in pixels
+    pub height: u32,
+    /// RGBA8 pixel data
+    pub rgba_data: Vec<u8>,
+    /// Description of the source image (dimensions, format, mip count)
+    pub details: String,
+}
+
 /// Cache statistics for debugging
 #[derive(Debug, Default, Clone)]
 pub struct ContentCacheStats {
@@ -70,6 +92,16 @@ pub struct ContentCache {
     max_entries: usize,
     /// Cache statistics
     stats: ContentCacheStats,
+    /// Decoded image previews, keyed like `entries`
+    previews: HashMap<String, CachedPreview>,
+    /// Access order for preview eviction (most recent at end)
+    preview_order: Vec<String>,
+    /// Extracted search text for content scans, keyed like `entries`
+    scan_texts: HashMap<String, Arc<str>>,
+    /// Insertion order for scan text eviction (oldest first)
+    scan_text_order: VecDeque<String>,
+    /// Total size of `scan_texts` in bytes
+    scan_text_bytes: usize,
 }
 
 impl ContentCache {
@@ -81,6 +113,11 @@ impl ContentCache {
             access_order: Vec::new(),
             max_entries: DEFAULT_MAX_ENTRIES,
             stats: ContentCacheStats::default(),
+            previews: HashMap::new(),
+            preview_order: Vec::new(),
+            scan_texts: HashMap::new(),
+            scan_text_order: VecDeque::new(),
+            scan_text_bytes: 0,
         }
     }
 
@@ -92,6 +129,11 @@ impl ContentCache {
             access_order: Vec::new(),
             max_entries,
             stats: ContentCacheStats::default(),
+            previews: HashMap::new(),
+            preview_order: Vec::new(),
+            scan_texts: HashMap::new(),
+            scan_text_order: VecDeque::new(),
+            scan_text_bytes: 0,
         }
     }
 
@@ -165,6 +207,11 @@ impl ContentCache {
                 let lsf_doc = parse_lsf_bytes(&raw_bytes)?;
                 to_lsx(&lsf_doc)?
             }
+            FileType::Lsb => {
+                // Read legacy LSB binary as LSX XML
+                self.stats.conversions += 1;
+                serialize_lsx(&parse_lsb_bytes(&raw_bytes)?)?
+            }
             FileType::Lsx | FileType::Xml => {
                 // Already text, just decode
                 String::from_utf8(raw_bytes)
@@ -205,6 +252,56 @@ impl ContentCache {
         Ok(self.entries.get(&key).expect("entry was just inserted"))
     }
 
+    /// Get a cached image preview (updates access order)
+    pub fn get_preview(&mut self, pak_path: &Path, internal_path: &str) -> Option<&CachedPreview> {
+        let key = Self::cache_key(pak_path, internal_path);
+        if self.previews.contains_key(&key) {
+            self.preview_order.retain(|k| k != &key);
+            self.preview_order.push(key.clone());
+        }
+        self.previews.get(&key)
+    }
+
+    /// Cache an image preview, evicting the least recently used one if full
+    pub fn insert_preview(&mut self, pak_path: &Path, internal_path: &str, preview: CachedPreview) {
+        let key = Self::cache_key(pak_path, internal_path);
+        self.preview_order.retain(|k| k != &key);
+        while self.previews.len() >= MAX_PREVIEWS && !self.preview_order.is_empty() {
+            let oldest = self.preview_order.remove(0);
+            self.previews.remove(&oldest);
+        }
+        self.previews.insert(key.clone(), preview);
+        self.preview_order.push(key);
+    }
+
+    /// Get the cached search text of a file from an earlier content scan
+    #[must_use]
+    pub fn get_scan_text(&self, pak_path: &Path, internal_path: &str) -> Option<Arc<str>> {
+        let key = Self::cache_key(pak_path, internal_path);
+        self.scan_texts.get(&key).cloned()
+    }
+
+    /// Cache the search text of a file, evicting the oldest texts once the
+    /// total passes the size limit
+    pub fn insert_scan_text(&mut self, pak_path: &Path, internal_path: &str, text: Arc<str>) {
+        let key = Self::cache_key(pak_path, internal_path);
+        if let Some(old) = self.scan_texts.remove(&key) {
+            self.scan_text_bytes -= old.len();
+            self.scan_text_order.retain(|k| k != &key);
+        }
+        while self.scan_text_bytes + text.len() > MAX_SCAN_TEXT_BYTES {
+            let Some(oldest) = self.scan_text_order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = self.scan_texts.remove(&oldest) {
+                self.scan_text_bytes -= evicted.len();
+            }
+        }
+        self.scan_text_bytes += text.len();
+        self.scan_texts.insert(key.clone(), text);
+        self.scan_text_order.push_back(key);
+    }
+
     /// Search content for a query string
     ///
     /// Loads content on demand and searches for matches.
@@ -219,12 +316,12 @@ impl ContentCache {
         case_sensitive: bool,
     ) -> Result<Option<ContentMatch>> {
         // Only search text-based files
-        if !entry.file_type.is_searchable_text() {
+        if !entry.file_type().is_searchable_text() {
             return Ok(None);
         }
 
         // Load content
-        let content = self.get_or_load(&entry.pak_file, &entry.path, entry.file_type)?;
+        let content = self.get_or_load(&entry.pak_file, &entry.path, entry.file_type())?;
 
         // Search for matches
         let query_normalized = if case_sensitive {
@@ -285,6 +382,11 @@ impl ContentCache {
     pub fn clear(&mut self) {
         self.entries.clear();
         self.access_order.clear();
+        self.previews.clear();
+        self.preview_order.clear();
+        self.scan_texts.clear();
+        self.scan_text_order.clear();
+        self.scan_text_bytes = 0;
         self.stats.total_bytes_cached = 0;
     }
 
@@ -0,0 +1,86 @@
+//! Resolve dependency graph references against indexed PAKs
+//!
+//! A mod's [`DependencyGraph`] usually points at base-game assets it doesn't
+//! define. Rather than parsing every document in the game, each unresolved
+//! id is looked up in the full-text index and only the files that mention it
+//! are loaded, repeating until the closure stops growing.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use maclarian::error::{Error, Result};
+use maclarian::mods::{DependencyGraph, Direction};
+use maclarian::pak::PakOperations;
+
+use super::SearchIndex;
+
+/// Maximum full-text hits loaded per referenced id
+const MAX_FILES_PER_ID: usize = 20;
+
+impl SearchIndex {
+    /// Load indexed files that define or refer to the closure of `id` into `graph`
+    ///
+    /// For [`Direction::Dependencies`] this looks up every unresolved id in
+    /// the closure; for [`Direction::Dependents`] it looks up every id in the
+    /// closure, to find what refers to it. Returns the number of documents
+    /// added.
+    ///
+    /// # Errors
+    /// Returns an error if the full-text index hasn't been built.
+    pub fn resolve_dependencies(
+        &self,
+        graph: &mut DependencyGraph,
+        id: &str,
+        direction: Direction,
+    ) -> Result<usize> {
+        let fulltext = self.fulltext.as_ref().ok_or_else(|| {
+            Error::SearchError("full-text index required to resolve dependencies".to_string())
+        })?;
+
+        let mut searched: HashSet<String> = HashSet::new();
+        let mut loaded: HashSet<(PathBuf, String)> = HashSet::new();
+        let mut added = 0;
+        loop {
+            let closure = graph.closure(id, direction);
+            let pending: Vec<String> = std::iter::once(id.to_string())
+                .chain(closure.into_iter().filter_map(|entry| {
+                    (direction == Direction::Dependents || !entry.resolved).then_some(entry.id)
+                }))
+                .filter(|candidate| searched.insert(candidate.clone()))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+
+            for candidate in pending {
+                let query = format!("\"{}\"", candidate.replace('"', ""));
+                let hits = match fulltext.search(&query, MAX_FILES_PER_ID) {
+                    Ok(hits) => hits,
+                    Err(e) => {
+                        tracing::debug!("Skipping {candidate}: {e}");
+                        continue;
+                    }
+                };
+                for hit in hits {
+                    let Some(ext) = hit.path.rsplit_once('.').map(|(_, ext)| ext) else {
+                        continue;
+                    };
+                    if !ext.eq_ignore_ascii_case("lsx") && !ext.eq_ignore_ascii_case("lsf") {
+                        continue;
+                    }
+                    if !loaded.insert((hit.pak_file.clone(), hit.path.clone())) {
+                        continue;
+                    }
+                    let source = format!("{}:{}", hit.pak_file.display(), hit.path);
+                    match PakOperations::read_file_bytes(&hit.pak_file, &hit.path)
+                        .and_then(|data| graph.add_bytes(&data, ext, &source))
+                    {
+                        Ok(()) => added += 1,
+                        Err(e) => tracing::warn!("Skipping {source}: {e}"),
+                    }
+                }
+            }
+        }
+        Ok(added)
+    }
+}
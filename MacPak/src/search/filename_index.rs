@@ -0,0 +1,473 @@
+//! Tokenized filename index for ranked filename search
+//!
+//! Filenames are split into tokens when they are indexed, on separators
+//! (`_`, `-`, `.`, spaces), case transitions (`BarbarianTattoo`) and
+//! letter/digit boundaries (`Tattoo01`), so multi-word queries can be ranked
+//! by how well each word lines up with the name. All lowercase filenames are
+//! also kept in one newline-separated string, so a query scans a single
+//! buffer instead of testing each name separately.
+//!
+//! Fuzzy search compares query words to the distinct tokens of all names by
+//! edit distance. The token list is built on the first fuzzy search, so the
+//! index costs nothing extra when fuzzy search isn't used.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use maclarian::utils::{path_lookup_key, to_nfc};
+use memchr::memmem::Finder;
+
+use super::store::EntryId;
+use super::types::MatchRank;
+
+/// Names a fuzzy query word may expand to before inexact tokens are dropped
+///
+/// Tokens at distance 0 are always kept; this bounds how far typos of a
+/// common word (`arm` -> `art`, `aim`, `army`, ...) can widen the search.
+const MAX_FUZZY_CANDIDATES: usize = 20_000;
+
+/// Unique filenames with their tokens and the entries that use them
+#[derive(Debug, Default)]
+pub(crate) struct FilenameIndex {
+    /// Every key in `names`, each followed by a newline
+    haystack: String,
+    /// One entry per distinct lowercase filename, in `haystack` order
+    names: Vec<FilenameEntry>,
+    /// Lowercase filename -> position in `names`
+    lookup: HashMap<String, usize>,
+    /// Distinct tokens, built on the first fuzzy search
+    vocabulary: OnceLock<Vocabulary>,
+}
+
+/// Every distinct filename token and the names that contain it
+#[derive(Debug, Default)]
+struct Vocabulary {
+    tokens: Vec<(Vec<char>, Vec<u32>)>,
+}
+
+#[derive(Debug)]
+struct FilenameEntry {
+    /// Lowercase NFC filename
+    key: String,
+    /// Byte offset of `key` in the haystack
+    start: usize,
+    /// Lowercase tokens of the filename
+    tokens: Vec<String>,
+    /// Ids of the entries with this name
+    ids: Vec<EntryId>,
+}
+
+impl FilenameIndex {
+    /// Add a file by its filename and entry id
+    pub(crate) fn insert(&mut self, name: &str, id: EntryId) {
+        let key = path_lookup_key(name);
+        if let Some(&i) = self.lookup.get(&key) {
+            self.names[i].ids.push(id);
+            return;
+        }
+
+        self.lookup.insert(key.clone(), self.names.len());
+        self.vocabulary = OnceLock::new();
+        let start = self.haystack.len();
+        self.haystack.push_str(&key);
+        self.haystack.push('\n');
+        self.names.push(FilenameEntry {
+            key,
+            start,
+            tokens: tokenize(name),
+            ids: vec![id],
+        });
+    }
+
+    /// Remove all filenames
+    pub(crate) fn clear(&mut self) {
+        self.haystack.clear();
+        self.names.clear();
+        self.lookup.clear();
+        self.vocabulary = OnceLock::new();
+    }
+
+    /// Entries whose filename contains every whitespace-separated term of
+    /// `query`, with how well the filename matched
+    ///
+    /// Results are unordered; an empty query matches nothing.
+    pub(crate) fn search(&self, query: &str) -> Vec<(EntryId, MatchRank)> {
+        let full = path_lookup_key(query.trim());
+        let terms: Vec<String> = query.split_whitespace().map(path_lookup_key).collect();
+        // Scan for the longest term, which is usually the rarest, and check
+        // every term only on the names it finds. Terms hold no whitespace, so
+        // a match never spans two names.
+        let Some(scanned) = terms.iter().max_by_key(|term| term.len()) else {
+            return Vec::new();
+        };
+        let scanned = Finder::new(scanned.as_bytes());
+        let finders: Vec<Finder> = terms
+            .iter()
+            .map(|term| Finder::new(term.as_bytes()))
+            .collect();
+
+        let haystack = self.haystack.as_bytes();
+        let mut hits = Vec::new();
+        let mut i = 0;
+        let mut pos = 0;
+        while let Some(offset) = scanned.find(&haystack[pos..]) {
+            let found = pos + offset;
+            while self
+                .names
+                .get(i + 1)
+                .is_some_and(|name| name.start <= found)
+            {
+                i += 1;
+            }
+            let name = &self.names[i];
+            if finders
+                .iter()
+                .all(|term| term.find(name.key.as_bytes()).is_some())
+            {
+                let rank = name.rank(&full, &terms);
+                hits.extend(name.ids.iter().map(|&id| (id, rank)));
+            }
+            // Resume after this name's newline
+            pos = name.start + name.key.len() + 1;
+        }
+        hits
+    }
+
+    /// Entries whose filename has, for every word of `query`, a token within
+    /// `max_distance` edits of it, with the summed distance of the words
+    ///
+    /// Words are split like filenames, so `KarlatchPortrait` is two words. A
+    /// word may differ by at most a third of its length, so short words
+    /// (`m`, `01`) must match exactly. Results are unordered.
+    pub(crate) fn search_fuzzy(&self, query: &str, max_distance: u32) -> Vec<(EntryId, u32)> {
+        let terms: Vec<Vec<char>> = tokenize(query)
+            .into_iter()
+            .map(|term| term.chars().collect())
+            .collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let vocabulary = self
+            .vocabulary
+            .get_or_init(|| Vocabulary::build(&self.names));
+
+        let mut candidates: Option<HashMap<u32, u32>> = None;
+        for term in &terms {
+            let allowed = max_distance.min(term.len() as u32 / 3);
+            let names = vocabulary.expand(term, allowed);
+            let merged: HashMap<u32, u32> = match candidates {
+                None => names,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(name, distance)| {
+                        names.get(&name).map(|more| (name, distance + more))
+                    })
+                    .collect(),
+            };
+            if merged.is_empty() {
+                return Vec::new();
+            }
+            candidates = Some(merged);
+        }
+
+        candidates
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(name, distance)| {
+                self.names[name as usize]
+                    .ids
+                    .iter()
+                    .map(move |&id| (id, distance))
+            })
+            .collect()
+    }
+}
+
+impl Vocabulary {
+    fn build(names: &[FilenameEntry]) -> Self {
+        let mut tokens: HashMap<&str, Vec<u32>> = HashMap::new();
+        for (i, name) in names.iter().enumerate() {
+            for token in &name.tokens {
+                let names = tokens.entry(token.as_str()).or_default();
+                if names.last() != Some(&(i as u32)) {
+                    names.push(i as u32);
+                }
+            }
+        }
+        Self {
+            tokens: tokens
+                .into_iter()
+                .map(|(token, names)| (token.chars().collect(), names))
+                .collect(),
+        }
+    }
+
+    /// Names with a token within `allowed` edits of `term`, with the
+    /// smallest such distance
+    fn expand(&self, term: &[char], allowed: u32) -> HashMap<u32, u32> {
+        let mut matched: Vec<(u32, &[u32])> = self
+            .tokens
+            .iter()
+            .filter(|(token, _)| token.len().abs_diff(term.len()) as u32 <= allowed)
+            .filter_map(|(token, names)| {
+                bounded_levenshtein(term, token, allowed).map(|d| (d, names.as_slice()))
+            })
+            .collect();
+        matched.sort_unstable_by_key(|(distance, names)| (*distance, names.len()));
+
+        let mut expanded = HashMap::new();
+        for (distance, names) in matched {
+            if distance > 0 && expanded.len() >= MAX_FUZZY_CANDIDATES {
+                break;
+            }
+            for &name in names {
+                expanded.entry(name).or_insert(distance);
+            }
+        }
+        expanded
+    }
+}
+
+/// Levenshtein distance between `a` and `b`, or `None` if it exceeds `max`
+fn bounded_levenshtein(a: &[char], b: &[char], max: u32) -> Option<u32> {
+    let max = max as usize;
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        // Every later row only grows from this one
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    let distance = previous[b.len()];
+    (distance <= max).then_some(distance as u32)
+}
+
+impl FilenameEntry {
+    /// How well a name already known to contain every term matches
+    fn rank(&self, full: &str, terms: &[String]) -> MatchRank {
+        let stem = self
+            .key
+            .rsplit_once('.')
+            .map_or(self.key.as_str(), |(stem, _)| stem);
+        if self.key == full || stem == full {
+            MatchRank::Exact
+        } else if terms
+            .first()
+            .is_some_and(|first| self.key.starts_with(first.as_str()))
+        {
+            MatchRank::Prefix
+        } else if terms.iter().all(|term| {
+            self.tokens
+                .iter()
+                .any(|token| token.starts_with(term.as_str()))
+        }) {
+            MatchRank::Token
+        } else {
+            MatchRank::Substring
+        }
+    }
+}
+
+/// Character classes that decide where a filename splits into tokens
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Upper,
+    Lower,
+    Digit,
+    Separator,
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        if c.is_numeric() {
+            Self::Digit
+        } else if c.is_uppercase() {
+            Self::Upper
+        } else if c.is_alphabetic() {
+            Self::Lower
+        } else {
+            Self::Separator
+        }
+    }
+}
+
+/// Split a filename into lowercase tokens
+///
+/// `HUM_M_ARM_BarbarianTattoo01.GR2` becomes `hum`, `m`, `arm`, `barbarian`,
+/// `tattoo`, `01`, `gr`, `2`. A run of capitals followed by a lowercase letter
+/// keeps its last capital for the next token (`GUIButton` -> `gui`, `button`).
+pub(crate) fn tokenize(name: &str) -> Vec<String> {
+    let chars: Vec<char> = to_nfc(name).chars().collect();
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        let class = CharClass::of(c);
+        if class == CharClass::Separator {
+            flush_token(&mut current, &mut tokens);
+            continue;
+        }
+
+        if let Some(&prev) = i.checked_sub(1).and_then(|j| chars.get(j)) {
+            let prev = CharClass::of(prev);
+            let next = chars.get(i + 1).map(|&n| CharClass::of(n));
+            let boundary = (prev == CharClass::Digit) != (class == CharClass::Digit)
+                || (prev == CharClass::Lower && class == CharClass::Upper)
+                || (prev == CharClass::Upper
+                    && class == CharClass::Upper
+                    && next == Some(CharClass::Lower));
+            if boundary {
+                flush_token(&mut current, &mut tokens);
+            }
+        }
+        current.push(c);
+    }
+    flush_token(&mut current, &mut tokens);
+    tokens
+}
+
+/// Move a finished token, lowercased, into `tokens`
+fn flush_token(current: &mut String, tokens: &mut Vec<String>) {
+    if !current.is_empty() {
+        tokens.push(current.to_lowercase());
+        current.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_and_rank() {
+        assert_eq!(
+            tokenize("HUM_M_ARM_BarbarianTattoo01.GR2"),
+            ["hum", "m", "arm", "barbarian", "tattoo", "01", "gr", "2"]
+        );
+        assert_eq!(tokenize("GUIButton-v2"), ["gui", "button", "v", "2"]);
+
+        let mut index = FilenameIndex::default();
+        let paths = [
+            "Public/Shared/Tattoos/Barbarian_Tattoo.lsf",
+            "Public/Shared/Tattoos/Barbarian_Tattoo_Arms.lsf",
+            "Generated/HUM_M_BarbarianTattoo01.GR2",
+            "Generated/Body/HUM_M_BarbarianTattoos_Torso.GR2",
+            "Public/Shared/Skin/SkinBarbarian_Tattooed.lsf",
+            "Public/Shared/Skin/Antibarbarian_Tattoo.lsf",
+            "Public/Shared/Barbarian.lsf",
+        ];
+        insert_paths(&mut index, &paths);
+
+        let mut hits = resolve(&paths, index.search("barbarian tattoo"));
+        hits.sort_by_key(|(path, rank)| (std::cmp::Reverse(*rank), *path));
+        assert_eq!(
+            hits,
+            [
+                (
+                    "Public/Shared/Tattoos/Barbarian_Tattoo.lsf",
+                    MatchRank::Prefix
+                ),
+                (
+                    "Public/Shared/Tattoos/Barbarian_Tattoo_Arms.lsf",
+                    MatchRank::Prefix
+                ),
+                (
+                    "Generated/Body/HUM_M_BarbarianTattoos_Torso.GR2",
+                    MatchRank::Token
+                ),
+                ("Generated/HUM_M_BarbarianTattoo01.GR2", MatchRank::Token),
+                (
+                    "Public/Shared/Skin/SkinBarbarian_Tattooed.lsf",
+                    MatchRank::Token
+                ),
+                (
+                    "Public/Shared/Skin/Antibarbarian_Tattoo.lsf",
+                    MatchRank::Substring
+                ),
+            ]
+        );
+        assert!(
+            resolve(&paths, index.search("BARBARIAN"))
+                .contains(&("Public/Shared/Barbarian.lsf", MatchRank::Exact))
+        );
+        assert!(index.search("  ").is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        assert_eq!(
+            bounded_levenshtein(&chars("karlatch"), &chars("karlach"), 2),
+            Some(1)
+        );
+        assert_eq!(
+            bounded_levenshtein(&chars("portriat"), &chars("portrait"), 2),
+            Some(2)
+        );
+        assert_eq!(
+            bounded_levenshtein(&chars("kitten"), &chars("sitting"), 2),
+            None
+        );
+
+        let mut index = FilenameIndex::default();
+        let paths = [
+            "Public/Shared/Portraits/Karlach_Portrait.DDS",
+            "Mods/Gustav/Story/CAMP_Karlach_Infernal.lsf",
+            "Public/Shared/Karl.lsf",
+            "Generated/HUM_M_ARM_Karlach.GR2",
+        ];
+        insert_paths(&mut index, &paths);
+        assert!(index.search("karlatch").is_empty());
+
+        let mut hits = resolve(&paths, index.search_fuzzy("Karlatch", 2));
+        hits.sort_unstable();
+        assert_eq!(
+            hits,
+            [
+                ("Generated/HUM_M_ARM_Karlach.GR2", 1),
+                ("Mods/Gustav/Story/CAMP_Karlach_Infernal.lsf", 1),
+                ("Public/Shared/Portraits/Karlach_Portrait.DDS", 1),
+            ]
+        );
+        assert_eq!(
+            resolve(&paths, index.search_fuzzy("karlatch portriat", 2)),
+            [("Public/Shared/Portraits/Karlach_Portrait.DDS", 3)]
+        );
+        // Short words must match exactly
+        assert!(index.search_fuzzy("karlach n", 2).is_empty());
+        assert!(index.search_fuzzy("karlatch", 0).is_empty());
+
+        // Adding a name rebuilds the token list
+        index.insert("Karlach_Horns.lsf", 4);
+        assert_eq!(index.search_fuzzy("hornz karlach", 2), [(4, 1)]);
+    }
+
+    /// Index each path under its filename, with its position as the id
+    fn insert_paths(index: &mut FilenameIndex, paths: &[&str]) {
+        for (id, path) in paths.iter().enumerate() {
+            let name = path.rsplit('/').next().unwrap();
+            index.insert(name, id as EntryId);
+        }
+    }
+
+    fn resolve<'a, T>(paths: &[&'a str], hits: Vec<(EntryId, T)>) -> Vec<(&'a str, T)> {
+        hits.into_iter()
+            .map(|(id, value)| (paths[id as usize], value))
+            .collect()
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+}
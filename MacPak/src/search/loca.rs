@@ -0,0 +1,41 @@
+//! Build a LOCA handle resolver from indexed PAKs
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use maclarian::error::Result;
+use maclarian::formats::loca::LocaResolver;
+use maclarian::pak::PakOperations;
+
+use super::SearchIndex;
+
+impl SearchIndex {
+    /// Load every indexed `.loca` file for `language` into a resolver
+    ///
+    /// # Errors
+    /// Returns an error if a PAK or one of its loca files cannot be read.
+    pub fn loca_resolver(&self, language: &str) -> Result<LocaResolver> {
+        let mut resolver = LocaResolver::new(language);
+        let mut by_pak: BTreeMap<&Path, Vec<String>> = BTreeMap::new();
+        for entry in self
+            .entry_views()
+            .filter(|e| resolver.is_language_file(e.path))
+        {
+            by_pak
+                .entry(entry.pak_file)
+                .or_default()
+                .push(entry.path.to_string());
+        }
+
+        for (pak, mut files) in by_pak {
+            files.sort();
+            let contents = PakOperations::read_files_bytes(pak, &files)?;
+            for file in &files {
+                if let Some(data) = contents.get(file) {
+                    resolver.add_bytes(data, Some(pak), file)?;
+                }
+            }
+        }
+        Ok(resolver)
+    }
+}
@@ -5,41 +5,63 @@
 //! - Phase 1: Build file metadata index from PAK listings (fast, no extraction)
 //! - Phase 2: On-demand content loading with LRU caching (for deep search)
 //!
+//! Content can also be scanned without a full-text index via
+//! [`SearchIndex::scan_content`], which suits one-off searches.
+//!
 //! ## Usage
 //!
 //! ```ignore
 //! let mut index = SearchIndex::new();
 //! index.build_index(&[pak_path1, pak_path2])?;
 //!
-//! // Fast filename search
-//! let results = index.search_filename("Barbarian", None);
+//! // Ranked filename search; every term must match
+//! let results = index.search_filename("barbarian tattoo", None);
 //!
 //! // Search with filter
 //! let lsx_only = index.search_filename("Barbarian", Some(FileType::Lsx));
+//!
+//! // Typo-tolerant search, up to two edits per word
+//! let karlach = index.search_filename_fuzzy("Karlatch", 2, None);
 //! ```
+//!
+//! Each file's type is taken from its extension and also detected from its
+//! first bytes while indexing, so filters find misnamed files (LSX text in a
+//! `_merged.lsf`) unless [`SearchIndex::set_strict_types`] is set.
 
 #![allow(clippy::cast_possible_truncation)]
 
 // Submodules
 mod builder;
 pub mod content_cache;
+mod dependencies;
 pub(crate) mod extract;
+mod filename_index;
 mod fulltext;
+mod loca;
 mod persistence;
+mod scan;
 mod search_methods;
+mod store;
 mod types;
 
-use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use maclarian::pak::find_game_paks;
 
 // Internal use within search module
+use filename_index::FilenameIndex;
 use fulltext::FullTextIndex;
+use store::{EntryId, EntryStore};
 
 // Public exports
-pub use content_cache::ContentCache;
+pub use content_cache::{CachedPreview, ContentCache};
 pub use fulltext::FullTextResult;
+pub use scan::ScanHit;
 pub use types::{
-    FileType, IndexMetadata, IndexedFile, SearchPhase, SearchProgress, SearchProgressCallback,
+    FileType, FilenameMatch, FuzzyMatch, GameSource, IndexMetadata, IndexStats, IndexedFile,
+    IndexedFileRef, MatchRank, SearchPhase, SearchProgress, SearchProgressCallback,
+    UuidContentMatch, UuidMatch,
 };
 
 /// Search index for PAK file contents
@@ -49,11 +71,11 @@ pub use types::{
 /// Optionally includes a full-text index for instant content search.
 #[derive(Default)]
 pub struct SearchIndex {
-    /// All file entries, keyed by full internal path
-    pub(crate) entries: HashMap<String, IndexedFile>,
-    /// Reverse index: lowercase filename -> list of full paths
-    pub(crate) filename_index: HashMap<String, Vec<String>>,
-    /// Source PAK files that have been indexed
+    /// All file entries, each path stored once
+    pub(crate) entries: EntryStore,
+    /// Tokenized filenames -> ids of the entries in `entries`
+    pub(crate) filename_index: FilenameIndex,
+    /// Source PAK files that have been indexed; entries refer to them by position
     pub(crate) indexed_paks: Vec<PathBuf>,
     /// Whether the index has been built
     pub(crate) indexed: bool,
@@ -61,6 +83,12 @@ pub struct SearchIndex {
     pub(crate) file_count: usize,
     /// Full-text search index (built separately via `build_fulltext_index`)
     pub(crate) fulltext: Option<FullTextIndex>,
+    /// When the metadata index was built, in seconds since the Unix epoch
+    pub(crate) built_at: Option<u64>,
+    /// Match type filters against declared (extension) types only
+    pub(crate) strict_types: bool,
+    /// Game install the PAKs were found in, if built with `build_game_index`
+    pub(crate) game: Option<GameSource>,
 }
 
 impl SearchIndex {
@@ -94,6 +122,71 @@ impl SearchIndex {
         &self.indexed_paks
     }
 
+    /// Game install the index was built from, if any
+    #[must_use]
+    pub fn game_source(&self) -> Option<&GameSource> {
+        self.game.as_ref()
+    }
+
+    /// Add a file entry to the metadata index
+    ///
+    /// An entry with the same path replaces the existing one. The entry's
+    /// source is added to the indexed PAKs if it isn't one already. Used when
+    /// building an index from sources other than PAK listings.
+    pub fn add_entry(&mut self, entry: IndexedFile) {
+        // Entries usually arrive grouped by PAK, so look from the newest
+        let pak = match self.indexed_paks.iter().rposition(|p| *p == entry.pak_file) {
+            Some(pak) => pak,
+            None => {
+                self.indexed_paks.push(entry.pak_file.clone());
+                self.indexed_paks.len() - 1
+            }
+        };
+        let (id, added) = self.entries.insert(&entry, pak as u32);
+        if added {
+            self.filename_index.insert(&entry.name, id);
+        }
+        self.file_count = self.entries.len();
+    }
+
+    /// View of the entry with id `id`
+    pub(crate) fn entry(&self, id: EntryId) -> IndexedFileRef<'_> {
+        let file = self.entries.get(id);
+        IndexedFileRef {
+            name: file.name(),
+            path: file.path(),
+            pak_file: &self.indexed_paks[file.pak as usize],
+            declared_type: file.declared_type,
+            detected_type: file.detected_type,
+            size: file.size,
+        }
+    }
+
+    /// Views of every entry, in the order they were added
+    pub(crate) fn entry_views(&self) -> impl Iterator<Item = IndexedFileRef<'_>> {
+        self.entries.iter().map(|(id, _)| self.entry(id))
+    }
+
+    /// Match type filters against file extensions only
+    ///
+    /// By default a filter also matches files whose content was detected as
+    /// that type, such as LSX text in a `.lsf` file.
+    pub fn set_strict_types(&mut self, strict: bool) {
+        self.strict_types = strict;
+    }
+
+    /// Files whose content doesn't match their extension, sorted by PAK and
+    /// path
+    #[must_use]
+    pub fn misnamed_files(&self) -> Vec<IndexedFileRef<'_>> {
+        let mut files: Vec<IndexedFileRef> = self
+            .entry_views()
+            .filter(IndexedFileRef::is_misnamed)
+            .collect();
+        files.sort_by(|a, b| a.pak_file.cmp(b.pak_file).then_with(|| a.path.cmp(b.path)));
+        files
+    }
+
     /// Clear the index
     pub fn clear(&mut self) {
         self.entries.clear();
@@ -102,6 +195,8 @@ impl SearchIndex {
         self.indexed = false;
         self.file_count = 0;
         self.fulltext = None;
+        self.built_at = None;
+        self.game = None;
     }
 
     /// Check if full-text index is available
@@ -109,4 +204,42 @@ impl SearchIndex {
     pub fn has_fulltext(&self) -> bool {
         self.fulltext.is_some()
     }
+
+    /// Counts for display, plus the PAKs that have changed on disk since the
+    /// index was built. Indexes saved before build times were recorded only
+    /// report missing PAKs. For an index built from a game install, the
+    /// install is scanned again and PAKs it didn't have are reported too.
+    #[must_use]
+    pub fn stats(&self) -> IndexStats {
+        let mut stale_paks: Vec<PathBuf> = self
+            .indexed_paks
+            .iter()
+            .filter(|pak| {
+                let Ok(modified) = std::fs::metadata(pak).and_then(|m| m.modified()) else {
+                    return true;
+                };
+                self.built_at
+                    .is_some_and(|built_at| unix_secs(modified) > built_at)
+            })
+            .cloned()
+            .collect();
+        if let Some(game) = &self.game
+            && let Ok(paks) = find_game_paks(&game.dir, game.skip_media)
+        {
+            stale_paks.extend(paks.into_iter().filter(|p| !self.indexed_paks.contains(p)));
+        }
+
+        IndexStats {
+            file_count: self.file_count,
+            pak_count: self.indexed_paks.len(),
+            fulltext_doc_count: self.fulltext_doc_count(),
+            stale_paks,
+        }
+    }
+}
+
+/// Seconds since the Unix epoch (0 for times before it)
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
 }
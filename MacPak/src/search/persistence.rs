@@ -1,18 +1,43 @@
 //! Index export and import functionality for `SearchIndex`
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::Path;
 
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use maclarian::error::Result;
+use maclarian::error::{Error, Result};
+use maclarian::utils::CancelToken;
 
 use super::SearchIndex;
 use super::fulltext::FullTextIndex;
 use super::types::{
-    IndexMetadata, IndexedFile, SearchPhase, SearchProgress, SearchProgressCallback,
+    FileType, IndexMetadata, IndexedFile, SearchPhase, SearchProgress, SearchProgressCallback,
 };
 
+/// File entries, one object per file, written by exports before the index
+/// stored each path once; still read when `files.json` is missing
+const LEGACY_ENTRIES_FILE: &str = "entries.json";
+/// File entries, as a list of [`ExportedFile`]
+const FILES_FILE: &str = "files.json";
+
+/// One file in `files.json`
+#[derive(Serialize, Deserialize)]
+struct ExportedFile<'a> {
+    #[serde(borrow)]
+    path: Cow<'a, str>,
+    /// Only written when the name isn't the path's last part
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    name: Option<Cow<'a, str>>,
+    /// Position of the source in the metadata's `indexed_paks`
+    pak: u32,
+    declared_type: FileType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    detected_type: Option<FileType>,
+    size: u64,
+}
+
 impl SearchIndex {
     /// Export the fulltext index to a directory
     ///
@@ -36,12 +61,32 @@ impl SearchIndex {
         &self,
         dir: &Path,
         progress: SearchProgressCallback,
+    ) -> Result<()> {
+        self.export_index_cancellable(dir, &CancelToken::new(), progress)
+    }
+
+    /// Export the fulltext index, stopping early once `cancel` is triggered
+    ///
+    /// `metadata.json` is written last, so a cancelled or failed export is
+    /// never picked up by [`import_index`](Self::import_index).
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if cancelled, or an error if no fulltext
+    /// index exists or writing fails.
+    ///
+    /// # Panics
+    /// This function does not panic under normal conditions.
+    pub fn export_index_cancellable(
+        &self,
+        dir: &Path,
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
     ) -> Result<()> {
         use tantivy::TantivyDocument;
 
         // Checks for fulltext index
         if self.fulltext.is_none() {
-            return Err(maclarian::error::Error::SearchError(
+            return Err(Error::SearchError(
                 "No fulltext index to export".to_string(),
             ));
         }
@@ -63,30 +108,33 @@ impl SearchIndex {
             1,
             "Saving file entries...",
         ));
-        let entries_path = dir.join("entries.json");
-        let entries_json = serde_json::to_string(&self.entries).map_err(|e| {
-            maclarian::error::Error::SearchError(format!("Failed to serialize entries: {e}"))
-        })?;
-        std::fs::write(&entries_path, entries_json)?;
+        let files: Vec<ExportedFile> = self
+            .entries
+            .iter()
+            .map(|(_, file)| ExportedFile {
+                path: Cow::Borrowed(file.path()),
+                name: (file.name() != default_name(file.path()))
+                    .then(|| Cow::Borrowed(file.name())),
+                pak: file.pak,
+                declared_type: file.declared_type,
+                detected_type: file.detected_type,
+                size: file.size,
+            })
+            .collect();
+        let files_json = serde_json::to_string(&files)
+            .map_err(|e| Error::SearchError(format!("Failed to serialize entries: {e}")))?;
+        std::fs::write(dir.join(FILES_FILE), files_json)?;
+        // An old-format copy would go stale; imports prefer files.json anyway
+        let legacy_path = dir.join(LEGACY_ENTRIES_FILE);
+        if legacy_path.exists() {
+            std::fs::remove_file(&legacy_path)?;
+        }
 
-        // Save metadata
-        progress(&SearchProgress::with_file(
-            SearchPhase::ExportingIndex,
-            0,
-            1,
-            "Saving metadata...",
-        ));
-        let metadata = IndexMetadata {
-            file_count: self.file_count,
-            pak_count: self.indexed_paks.len(),
-            indexed_paks: self.indexed_paks.clone(),
-            fulltext_doc_count: self.fulltext_doc_count(),
-        };
+        // Drop any previous metadata so a partial export can't be imported
         let meta_path = dir.join("metadata.json");
-        let meta_json = serde_json::to_string_pretty(&metadata).map_err(|e| {
-            maclarian::error::Error::SearchError(format!("Failed to serialize metadata: {e}"))
-        })?;
-        std::fs::write(&meta_path, meta_json)?;
+        if meta_path.exists() {
+            std::fs::remove_file(&meta_path)?;
+        }
 
         // Create a new index in the directory with larger heap for faster writes
         let ft = self.fulltext.as_ref().unwrap();
@@ -135,9 +183,12 @@ impl SearchIndex {
 
         // Sequential write (IndexWriter is not thread-safe for concurrent adds)
         for (i, doc) in all_docs.into_iter().enumerate() {
-            writer.add_document(doc).map_err(|e| {
-                maclarian::error::Error::SearchError(format!("Failed to copy doc: {e}"))
-            })?;
+            if i % 1000 == 0 {
+                cancel.checkpoint()?;
+            }
+            writer
+                .add_document(doc)
+                .map_err(|e| Error::SearchError(format!("Failed to copy doc: {e}")))?;
             if i % 5000 == 0 {
                 progress(&SearchProgress::with_file(
                     SearchPhase::ExportingIndex,
@@ -154,9 +205,28 @@ impl SearchIndex {
             total_docs,
             "Committing index...",
         ));
-        writer.commit().map_err(|e| {
-            maclarian::error::Error::SearchError(format!("Export commit failed: {e}"))
-        })?;
+        writer
+            .commit()
+            .map_err(|e| Error::SearchError(format!("Export commit failed: {e}")))?;
+
+        // Save metadata
+        progress(&SearchProgress::with_file(
+            SearchPhase::ExportingIndex,
+            0,
+            1,
+            "Saving metadata...",
+        ));
+        let metadata = IndexMetadata {
+            file_count: self.file_count,
+            pak_count: self.indexed_paks.len(),
+            indexed_paks: self.indexed_paks.clone(),
+            fulltext_doc_count: self.fulltext_doc_count(),
+            built_at: self.built_at,
+            game: self.game.clone(),
+        };
+        let meta_json = serde_json::to_string_pretty(&metadata)
+            .map_err(|e| Error::SearchError(format!("Failed to serialize metadata: {e}")))?;
+        std::fs::write(&meta_path, meta_json)?;
 
         progress(&SearchProgress::new(
             SearchPhase::Complete,
@@ -191,6 +261,23 @@ impl SearchIndex {
         &mut self,
         dir: &Path,
         progress: SearchProgressCallback,
+    ) -> Result<()> {
+        self.import_index_cancellable(dir, &CancelToken::new(), progress)
+    }
+
+    /// Import a fulltext index, stopping early once `cancel` is triggered
+    ///
+    /// The current index is only replaced once everything has loaded, so a
+    /// cancelled import leaves it untouched.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`] if cancelled, or an error if reading the
+    /// index fails.
+    pub fn import_index_cancellable(
+        &mut self,
+        dir: &Path,
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
     ) -> Result<()> {
         progress(&SearchProgress::with_file(
             SearchPhase::ImportingIndex,
@@ -202,9 +289,8 @@ impl SearchIndex {
         // Load metadata
         let meta_path = dir.join("metadata.json");
         let meta_json = std::fs::read_to_string(&meta_path)?;
-        let metadata: IndexMetadata = serde_json::from_str(&meta_json).map_err(|e| {
-            maclarian::error::Error::SearchError(format!("Failed to parse metadata: {e}"))
-        })?;
+        let metadata: IndexMetadata = serde_json::from_str(&meta_json)
+            .map_err(|e| Error::SearchError(format!("Failed to parse metadata: {e}")))?;
 
         progress(&SearchProgress::with_file(
             SearchPhase::ImportingIndex,
@@ -212,16 +298,38 @@ impl SearchIndex {
             4,
             "Loading file entries",
         ));
+        cancel.checkpoint()?;
 
-        // Load file entries (if available - for backward compatibility)
-        let entries_path = dir.join("entries.json");
-        let entries: HashMap<String, IndexedFile> = if entries_path.exists() {
-            let entries_json = std::fs::read_to_string(&entries_path)?;
-            serde_json::from_str(&entries_json).map_err(|e| {
-                maclarian::error::Error::SearchError(format!("Failed to parse entries: {e}"))
-            })?
+        // Load file entries into a fresh index; its PAK list is the one the
+        // entries refer to
+        let mut loaded = SearchIndex {
+            indexed_paks: metadata.indexed_paks,
+            ..SearchIndex::default()
+        };
+        let files_path = dir.join(FILES_FILE);
+        let legacy_path = dir.join(LEGACY_ENTRIES_FILE);
+        let files_json = if files_path.exists() {
+            std::fs::read_to_string(&files_path)?
         } else {
+            String::new()
+        };
+        // Exports from before entries were stored compactly
+        let legacy_json = if files_json.is_empty() && legacy_path.exists() {
+            std::fs::read_to_string(&legacy_path)?
+        } else {
+            String::new()
+        };
+        let files: Vec<ExportedFile> = if files_json.is_empty() {
+            Vec::new()
+        } else {
+            serde_json::from_str(&files_json)
+                .map_err(|e| Error::SearchError(format!("Failed to parse entries: {e}")))?
+        };
+        let legacy_entries: HashMap<String, IndexedFile> = if legacy_json.is_empty() {
             HashMap::new()
+        } else {
+            serde_json::from_str(&legacy_json)
+                .map_err(|e| Error::SearchError(format!("Failed to parse entries: {e}")))?
         };
 
         progress(&SearchProgress::with_file(
@@ -230,15 +338,13 @@ impl SearchIndex {
             4,
             "Rebuilding filename index",
         ));
+        cancel.checkpoint()?;
 
-        // Rebuild filename index from entries
-        let mut filename_index: HashMap<String, Vec<String>> = HashMap::new();
-        for (path, file) in &entries {
-            let filename_lower = file.name.to_lowercase();
-            filename_index
-                .entry(filename_lower)
-                .or_default()
-                .push(path.clone());
+        for file in files {
+            loaded.add_exported(file)?;
+        }
+        for entry in legacy_entries.into_values() {
+            loaded.add_entry(entry);
         }
 
         progress(&SearchProgress::with_file(
@@ -247,6 +353,7 @@ impl SearchIndex {
             4,
             "Opening Tantivy index",
         ));
+        cancel.checkpoint()?;
 
         // Open the Tantivy index
         let tantivy_dir = dir.join("tantivy");
@@ -254,14 +361,17 @@ impl SearchIndex {
 
         // Reader must be reloaded to see segments from disk with Manual reload policy
         fulltext.reload()?;
+        cancel.checkpoint()?;
 
         // Update state
-        self.entries = entries;
-        self.filename_index = filename_index;
+        self.entries = loaded.entries;
+        self.filename_index = loaded.filename_index;
         self.fulltext = Some(fulltext);
         self.file_count = metadata.file_count;
-        self.indexed_paks = metadata.indexed_paks;
+        self.indexed_paks = loaded.indexed_paks;
         self.indexed = true;
+        self.built_at = metadata.built_at;
+        self.game = metadata.game;
 
         progress(&SearchProgress::new(SearchPhase::Complete, 4, 4));
 
@@ -275,3 +385,98 @@ impl SearchIndex {
         Ok(())
     }
 }
+
+impl SearchIndex {
+    /// Add an entry read from `files.json`
+    fn add_exported(&mut self, file: ExportedFile<'_>) -> Result<()> {
+        let pak_file = self
+            .indexed_paks
+            .get(file.pak as usize)
+            .cloned()
+            .ok_or_else(|| {
+                Error::SearchError(format!(
+                    "Entry {} refers to PAK {}, but the index lists {}",
+                    file.path,
+                    file.pak,
+                    self.indexed_paks.len()
+                ))
+            })?;
+        let path = file.path.into_owned();
+        let name = match file.name {
+            Some(name) => name.into_owned(),
+            None => default_name(&path).to_string(),
+        };
+        self.add_entry(IndexedFile {
+            name,
+            path,
+            pak_file,
+            declared_type: file.declared_type,
+            detected_type: file.detected_type,
+            size: file.size,
+        });
+        Ok(())
+    }
+}
+
+/// The name `files.json` implies for an entry without one: the path's last part
+fn default_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maclarian::compression::CompressionSettings;
+    use maclarian::pak::PakBuilder;
+
+    #[test]
+    fn test_export_round_trip_and_legacy_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Persist.pak");
+        let lsx = b"<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<save>\n\t\
+            <attribute id=\"Name\" type=\"LSString\" value=\"Persisted Shadowheart\" />\n\
+            </save>\n";
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        builder
+            .add_file("Public/Test/Persist.lsx", lsx, CompressionSettings::none())
+            .unwrap();
+        builder
+            .add_file("Public/Test/Icon.dds", b"DDS ", CompressionSettings::none())
+            .unwrap();
+        builder.finish().unwrap();
+
+        let mut index = SearchIndex::new();
+        index.build_index(std::slice::from_ref(&pak)).unwrap();
+        index.build_fulltext_index(&|_| {}).unwrap();
+        let export = dir.path().join("export");
+        index.export_index(&export).unwrap();
+        assert!(export.join(FILES_FILE).exists());
+
+        let mut imported = SearchIndex::new();
+        imported.import_index(&export).unwrap();
+        let entry = imported.get_by_path("Public/Test/Persist.lsx").unwrap();
+        assert_eq!(entry.name, "Persist.lsx");
+        assert_eq!(entry.pak_file, pak.as_path());
+        assert_eq!(imported.search_filename("icon", None).len(), 1);
+
+        // An export from before files.json keeps one object per path
+        let legacy: HashMap<String, IndexedFile> = index
+            .all_entries()
+            .map(|entry| (entry.path.to_string(), IndexedFile::from(entry)))
+            .collect();
+        std::fs::remove_file(export.join(FILES_FILE)).unwrap();
+        std::fs::write(
+            export.join(LEGACY_ENTRIES_FILE),
+            serde_json::to_string(&legacy).unwrap(),
+        )
+        .unwrap();
+
+        let mut migrated = SearchIndex::new();
+        migrated.import_index(&export).unwrap();
+        assert_eq!(migrated.all_entries().count(), 2);
+        assert_eq!(migrated.indexed_paks(), std::slice::from_ref(&pak));
+        let entry = migrated.get_by_path("Public/Test/Icon.dds").unwrap();
+        assert_eq!(entry.pak_file, pak.as_path());
+        assert_eq!(migrated.search_filename("persist", None).len(), 1);
+    }
+}
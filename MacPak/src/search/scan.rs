@@ -0,0 +1,212 @@
+//! On-demand content scanning for `SearchIndex`
+//!
+//! Searches file contents without a full-text index: the searchable files of
+//! each PAK are read in batches, their text extracted and matched in
+//! parallel. Extracted text is kept in the [`ContentCache`], so repeated scans
+//! of the same PAKs skip reading and extraction.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use rayon::prelude::*;
+
+use maclarian::error::Result;
+use maclarian::pak::{GREP_BATCH_SIZE, LineMatch, PakReaderCache, TextMatcher};
+use maclarian::utils::CancelToken;
+
+use super::SearchIndex;
+use super::content_cache::ContentCache;
+use super::extract;
+use super::types::{
+    FileType, IndexedFile, IndexedFileRef, SearchPhase, SearchProgress, SearchProgressCallback,
+};
+
+/// A file whose content matched a scan
+#[derive(Debug, Clone)]
+pub struct ScanHit {
+    /// The file that matched
+    pub entry: IndexedFile,
+    /// Matching lines with 1-based line numbers
+    pub lines: Vec<LineMatch>,
+}
+
+impl SearchIndex {
+    /// Scan the contents of indexed files for `matcher`
+    ///
+    /// `filter` limits the scan to those file types; an empty filter scans all
+    /// searchable text. Only one batch of file contents per PAK is read at a
+    /// time. PAKs that can't be read are skipped and logged. Hits are sorted
+    /// by PAK, then path.
+    ///
+    /// # Errors
+    /// Returns [`Error::Cancelled`](maclarian::error::Error::Cancelled) if
+    /// cancelled.
+    pub fn scan_content(
+        &self,
+        matcher: &TextMatcher,
+        filter: &[FileType],
+        cache: &mut ContentCache,
+        cancel: &CancelToken,
+        progress: SearchProgressCallback,
+    ) -> Result<Vec<ScanHit>> {
+        let mut by_pak: BTreeMap<&Path, Vec<IndexedFileRef>> = BTreeMap::new();
+        for entry in self.entry_views().filter(|entry| {
+            entry.file_type().is_searchable_text()
+                && (filter.is_empty()
+                    || filter
+                        .iter()
+                        .any(|&f| entry.matches_type(f, self.strict_types)))
+        }) {
+            by_pak.entry(entry.pak_file).or_default().push(entry);
+        }
+
+        let total: usize = by_pak.values().map(Vec::len).sum();
+        let mut scanned = 0;
+        let mut hits = Vec::new();
+        let mut reader = PakReaderCache::new(1);
+
+        'paks: for (pak_path, mut files) in by_pak {
+            files.sort_by(|a, b| a.path.cmp(b.path));
+            let pak_start = scanned;
+            let pak_name = pak_path.file_name().map_or_else(
+                || "Unknown".to_string(),
+                |n| n.to_string_lossy().to_string(),
+            );
+
+            for batch in files.chunks(GREP_BATCH_SIZE) {
+                cancel.checkpoint()?;
+                progress(&SearchProgress::with_file(
+                    SearchPhase::Searching,
+                    scanned,
+                    total,
+                    &pak_name,
+                ));
+
+                let texts = match batch_texts(&mut reader, cache, pak_path, batch) {
+                    Ok(texts) => texts,
+                    Err(e) => {
+                        tracing::warn!("Failed to scan {}: {}", pak_path.display(), e);
+                        scanned = pak_start + files.len();
+                        continue 'paks;
+                    }
+                };
+
+                let batch_hits: Vec<ScanHit> = batch
+                    .par_iter()
+                    .zip(texts.par_iter())
+                    .filter_map(|(entry, text)| {
+                        let lines = matcher.matching_lines(text.as_deref()?);
+                        (!lines.is_empty()).then(|| ScanHit {
+                            entry: IndexedFile::from(*entry),
+                            lines,
+                        })
+                    })
+                    .collect();
+                hits.extend(batch_hits);
+                scanned += batch.len();
+            }
+        }
+
+        progress(&SearchProgress::new(SearchPhase::Complete, total, total));
+        Ok(hits)
+    }
+}
+
+/// Search text of each file in `batch`, from the cache or read from the PAK
+///
+/// Files missing from the PAK get `None`.
+fn batch_texts(
+    reader: &mut PakReaderCache,
+    cache: &mut ContentCache,
+    pak_path: &Path,
+    batch: &[IndexedFileRef],
+) -> Result<Vec<Option<Arc<str>>>> {
+    let mut texts: Vec<Option<Arc<str>>> = batch
+        .iter()
+        .map(|entry| cache.get_scan_text(pak_path, entry.path))
+        .collect();
+
+    let missing: Vec<&str> = batch
+        .iter()
+        .zip(&texts)
+        .filter(|(_, text)| text.is_none())
+        .map(|(entry, _)| entry.path)
+        .collect();
+    if missing.is_empty() {
+        return Ok(texts);
+    }
+
+    let contents = reader.read_files_bulk(pak_path, &missing)?;
+    let extracted: Vec<(usize, Arc<str>)> = batch
+        .par_iter()
+        .enumerate()
+        .filter(|(i, _)| texts[*i].is_none())
+        .filter_map(|(i, entry)| {
+            let bytes = contents.get(entry.path)?;
+            Some((
+                i,
+                Arc::from(extract::extract_text(bytes, entry.file_type())),
+            ))
+        })
+        .collect();
+
+    for (i, text) in extracted {
+        cache.insert_scan_text(pak_path, batch[i].path, Arc::clone(&text));
+        texts[i] = Some(text);
+    }
+    Ok(texts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maclarian::compression::CompressionSettings;
+    use maclarian::pak::PakBuilder;
+
+    #[test]
+    fn test_scan_content_uses_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let pak = dir.path().join("Scan.pak");
+        let mut builder = PakBuilder::write_to(&pak).unwrap();
+        let settings = CompressionSettings::default();
+        builder
+            .add_file(
+                "Public/Mod/Items.lsx",
+                b"<save>\n  <attribute id=\"Name\" value=\"Shadowheart\"/>\n</save>",
+                settings,
+            )
+            .unwrap();
+        builder
+            .add_file(
+                "Public/Mod/Other.json",
+                b"{\"name\": \"Karlach\"}",
+                settings,
+            )
+            .unwrap();
+        builder.finish().unwrap();
+
+        let mut index = SearchIndex::new();
+        index.build_index(std::slice::from_ref(&pak)).unwrap();
+        let mut cache = ContentCache::new();
+        let cancel = CancelToken::new();
+
+        let matcher = TextMatcher::new("shadowheart", false, false).unwrap();
+        let hits = index
+            .scan_content(&matcher, &[], &mut cache, &cancel, &|_| {})
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entry.path, "Public/Mod/Items.lsx");
+        assert_eq!(hits[0].lines[0].line_number, 2);
+        assert!(cache.get_scan_text(&pak, "Public/Mod/Other.json").is_some());
+
+        // A second scan is served from the cache, even with the PAK gone
+        std::fs::remove_file(&pak).unwrap();
+        let matcher = TextMatcher::new("Karlach", false, true).unwrap();
+        let hits = index
+            .scan_content(&matcher, &[FileType::Json], &mut cache, &cancel, &|_| {})
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].entry.name, "Other.json");
+    }
+}
@@ -1,46 +1,104 @@
 //! Search methods for `SearchIndex`
 
+use std::collections::HashSet;
+
+use maclarian::utils::{GuidByteOrder, guid_search_forms};
+
 use super::SearchIndex;
 use super::fulltext::FullTextResult;
-use super::types::{FileType, IndexedFile, SearchProgressCallback};
+use super::types::{
+    FileType, FilenameMatch, FuzzyMatch, IndexedFileRef, SearchProgressCallback, UuidContentMatch,
+    UuidMatch,
+};
 
 impl SearchIndex {
     /// Search for files by filename (case-insensitive)
     ///
-    /// Returns entries where the filename contains the query string.
-    /// Optionally filter by file type.
+    /// The query is split on whitespace and every term must appear in the
+    /// filename, so `barbarian tattoo` finds `HUM_M_BarbarianTattoo01.GR2`.
+    /// Results are sorted best first: exact filename, then prefix, token and
+    /// plain substring matches, with shorter paths first within a rank.
+    /// Optionally filter by file type. Filenames and the query are compared
+    /// in NFC form, so decomposed input such as macOS file names still match.
     #[must_use]
-    pub fn search_filename(&self, query: &str, filter: Option<FileType>) -> Vec<&IndexedFile> {
-        let query_lower = query.to_lowercase();
+    pub fn search_filename(&self, query: &str, filter: Option<FileType>) -> Vec<FilenameMatch<'_>> {
+        let mut matches: Vec<FilenameMatch> = self
+            .filename_index
+            .search(query)
+            .into_iter()
+            .filter_map(|(id, rank)| {
+                let entry = self.entry(id);
+                filter
+                    .is_none_or(|f| entry.matches_type(f, self.strict_types))
+                    .then_some(FilenameMatch { entry, rank })
+            })
+            .collect();
 
-        self.filename_index
-            .iter()
-            .filter(|(filename, _)| filename.contains(&query_lower))
-            .flat_map(|(_, paths)| paths.iter())
-            .filter_map(|path| self.entries.get(path))
-            .filter(|entry| filter.is_none_or(|f| entry.file_type == f))
-            .collect()
+        matches.sort_unstable_by(|a, b| {
+            b.rank
+                .cmp(&a.rank)
+                .then(a.entry.path.len().cmp(&b.entry.path.len()))
+                .then_with(|| a.entry.path.cmp(b.entry.path))
+        });
+        matches
+    }
+
+    /// Search for files by filename, tolerating typos
+    ///
+    /// Each word of the query must be within `max_distance` edits of a token
+    /// of the filename (`Karlatch` finds `Karlach_Portrait.DDS`), though a
+    /// word may differ by at most a third of its length. Results are sorted
+    /// by total distance, then by path length. Slower than
+    /// [`search_filename`](Self::search_filename); the first call also builds
+    /// the token list it searches.
+    #[must_use]
+    pub fn search_filename_fuzzy(
+        &self,
+        query: &str,
+        max_distance: u32,
+        filter: Option<FileType>,
+    ) -> Vec<FuzzyMatch<'_>> {
+        let mut matches: Vec<FuzzyMatch> = self
+            .filename_index
+            .search_fuzzy(query, max_distance)
+            .into_iter()
+            .filter_map(|(id, distance)| {
+                let entry = self.entry(id);
+                filter
+                    .is_none_or(|f| entry.matches_type(f, self.strict_types))
+                    .then_some(FuzzyMatch { entry, distance })
+            })
+            .collect();
+
+        matches.sort_unstable_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then(a.entry.path.len().cmp(&b.entry.path.len()))
+                .then_with(|| a.entry.path.cmp(b.entry.path))
+        });
+        matches
     }
 
     /// Search for files by path (case-insensitive substring match)
     ///
     /// Returns entries where the full path contains the query string.
     #[must_use]
-    pub fn search_path(&self, query: &str, filter: Option<FileType>) -> Vec<&IndexedFile> {
+    pub fn search_path(&self, query: &str, filter: Option<FileType>) -> Vec<IndexedFileRef<'_>> {
         let query_lower = query.to_lowercase();
 
-        self.entries
-            .values()
+        self.entry_views()
             .filter(|entry| entry.path.to_lowercase().contains(&query_lower))
-            .filter(|entry| filter.is_none_or(|f| entry.file_type == f))
+            .filter(|entry| filter.is_none_or(|f| entry.matches_type(f, self.strict_types)))
             .collect()
     }
 
     /// Search for UUIDs in filenames/paths
     ///
     /// Handles various UUID formats (with/without hyphens, with h/g prefix).
+    /// A complete GUID is also looked for byte-swapped (the order LSF files
+    /// store it in); each match says which form its path contains.
     #[must_use]
-    pub fn search_uuid(&self, uuid: &str) -> Vec<&IndexedFile> {
+    pub fn search_uuid(&self, uuid: &str) -> Vec<UuidMatch<'_>> {
         // Normalize UUID: remove hyphens, convert to lowercase
         let normalized: String = uuid
             .chars()
@@ -52,37 +110,46 @@ impl SearchIndex {
             return Vec::new(); // Too short to be meaningful
         }
 
-        self.entries
-            .values()
-            .filter(|entry| {
+        let forms: Vec<(GuidByteOrder, String)> = match guid_search_forms(uuid) {
+            Some(forms) => forms
+                .into_iter()
+                .map(|(order, form)| (order, form.replace('-', "")))
+                .collect(),
+            None => vec![(GuidByteOrder::Canonical, normalized)],
+        };
+
+        self.entry_views()
+            .filter_map(|entry| {
                 let path_normalized: String = entry
                     .path
                     .chars()
                     .filter(char::is_ascii_hexdigit)
                     .collect::<String>()
                     .to_lowercase();
-                path_normalized.contains(&normalized)
+                forms
+                    .iter()
+                    .find(|(_, form)| path_normalized.contains(form.as_str()))
+                    .map(|&(order, _)| UuidMatch { entry, order })
             })
             .collect()
     }
 
     /// Get a file entry by its full path
     #[must_use]
-    pub fn get_by_path(&self, path: &str) -> Option<&IndexedFile> {
-        self.entries.get(path)
+    pub fn get_by_path(&self, path: &str) -> Option<IndexedFileRef<'_>> {
+        self.entries.id_of(path).map(|id| self.entry(id))
     }
 
     /// Get all entries (for iteration)
-    pub fn all_entries(&self) -> impl Iterator<Item = &IndexedFile> {
-        self.entries.values()
+    pub fn all_entries(&self) -> impl Iterator<Item = IndexedFileRef<'_>> {
+        self.entry_views()
     }
 
     /// Get entries filtered by file type
     #[must_use]
-    pub fn entries_by_type(&self, file_type: FileType) -> Vec<&IndexedFile> {
-        self.entries
-            .values()
-            .filter(|e| e.file_type == file_type)
+    pub fn entries_by_type(&self, file_type: FileType) -> Vec<IndexedFileRef<'_>> {
+        self.entry_views()
+            .filter(|e| e.matches_type(file_type, self.strict_types))
             .collect()
     }
 
@@ -111,6 +178,32 @@ impl SearchIndex {
             .and_then(|ft| ft.search_with_progress(query, limit, progress).ok())
     }
 
+    /// Search file contents for a GUID in both byte orders
+    ///
+    /// Runs a phrase query for the hyphenated GUID and for its byte-swapped
+    /// form, and tags each hit with the form it contains. A file holding
+    /// both is reported once, for the form as given.
+    ///
+    /// Returns None if `uuid` isn't a GUID or the fulltext index hasn't been
+    /// built.
+    #[must_use]
+    pub fn search_fulltext_uuid(&self, uuid: &str, limit: usize) -> Option<Vec<UuidContentMatch>> {
+        let forms = guid_search_forms(uuid)?;
+        let fulltext = self.fulltext.as_ref()?;
+
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+        for (order, form) in forms {
+            for result in fulltext.search(&format!("\"{form}\""), limit).ok()? {
+                if seen.insert((result.pak_file.clone(), result.path.clone())) {
+                    matches.push(UuidContentMatch { result, order });
+                }
+            }
+        }
+        matches.truncate(limit);
+        Some(matches)
+    }
+
     /// Get number of documents in fulltext index
     #[must_use]
     pub fn fulltext_doc_count(&self) -> u64 {
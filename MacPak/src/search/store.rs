@@ -0,0 +1,224 @@
+//! Compact storage for the file entries of a `SearchIndex`
+//!
+//! A full game index holds hundreds of thousands of paths, so each is stored
+//! once. An entry keeps its path with the filename as an offset into it, and
+//! its PAK as a position in the index's PAK list. Lookups by path go through
+//! a table of path hashes instead of a second copy of every path; the rare
+//! paths whose hash is already taken are kept in a small overflow map.
+
+use std::collections::HashMap;
+use std::collections::hash_map::{Entry, RandomState};
+use std::hash::BuildHasher;
+
+use super::types::{FileType, IndexedFile};
+
+/// Position of an entry in an [`EntryStore`]
+pub(crate) type EntryId = u32;
+
+/// One indexed file
+#[derive(Debug)]
+pub(crate) struct StoredFile {
+    /// The path, followed by the filename when it isn't the path's last part
+    text: Box<str>,
+    /// Length of the path in `text`
+    path_len: u32,
+    /// Byte offset of the filename in `text`
+    name_start: u32,
+    /// Position of the source in the index's PAK list
+    pub(crate) pak: u32,
+    pub(crate) declared_type: FileType,
+    pub(crate) detected_type: Option<FileType>,
+    pub(crate) size: u64,
+}
+
+impl StoredFile {
+    fn new(file: &IndexedFile, pak: u32) -> Self {
+        let (text, name_start) = if file.path.ends_with(file.name.as_str()) {
+            let start = file.path.len() - file.name.len();
+            (file.path.as_str().into(), start)
+        } else {
+            (
+                format!("{}{}", file.path, file.name).into(),
+                file.path.len(),
+            )
+        };
+        Self {
+            text,
+            path_len: file.path.len() as u32,
+            name_start: name_start as u32,
+            pak,
+            declared_type: file.declared_type,
+            detected_type: file.detected_type,
+            size: file.size,
+        }
+    }
+
+    /// Full internal path within the PAK
+    pub(crate) fn path(&self) -> &str {
+        &self.text[..self.path_len as usize]
+    }
+
+    /// Filename only (without path)
+    pub(crate) fn name(&self) -> &str {
+        &self.text[self.name_start as usize..]
+    }
+}
+
+/// Indexed files, each stored once and looked up by path
+#[derive(Debug, Default)]
+pub(crate) struct EntryStore {
+    files: Vec<StoredFile>,
+    /// Path hash -> the first entry with that hash
+    by_hash: HashMap<u64, EntryId>,
+    /// Entries whose path hash was already taken, by path
+    collisions: HashMap<Box<str>, EntryId>,
+    hasher: RandomState,
+}
+
+impl EntryStore {
+    pub(crate) fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// The entry with id `id`
+    ///
+    /// Ids come from this store, so they are always in range.
+    pub(crate) fn get(&self, id: EntryId) -> &StoredFile {
+        &self.files[id as usize]
+    }
+
+    /// Id of the entry for `path`
+    pub(crate) fn id_of(&self, path: &str) -> Option<EntryId> {
+        match self.by_hash.get(&self.hasher.hash_one(path)) {
+            Some(&id) if self.get(id).path() == path => Some(id),
+            Some(_) => self.collisions.get(path).copied(),
+            None => None,
+        }
+    }
+
+    /// Add `file` from the PAK at `pak`, or replace the entry with its path
+    ///
+    /// Returns the entry's id and whether it is new.
+    pub(crate) fn insert(&mut self, file: &IndexedFile, pak: u32) -> (EntryId, bool) {
+        let stored = StoredFile::new(file, pak);
+        if let Some(id) = self.id_of(&file.path) {
+            self.files[id as usize] = stored;
+            return (id, false);
+        }
+
+        let id = self.files.len() as EntryId;
+        match self.by_hash.entry(self.hasher.hash_one(file.path.as_str())) {
+            Entry::Vacant(slot) => {
+                slot.insert(id);
+            }
+            Entry::Occupied(_) => {
+                self.collisions.insert(file.path.as_str().into(), id);
+            }
+        }
+        self.files.push(stored);
+        (id, true)
+    }
+
+    /// Every entry with its id, in insertion order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (EntryId, &StoredFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(id, file)| (id as EntryId, file))
+    }
+
+    /// Approximate heap bytes held by the store, for logging
+    ///
+    /// Counts allocated capacity, not what the allocator actually reserved,
+    /// so it is a lower bound on the store's share of the process's memory.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let files = self.files.capacity() * size_of::<StoredFile>();
+        let text: usize = self.files.iter().map(|file| file.text.len()).sum();
+        // One control byte per hash table slot
+        let by_hash = self.by_hash.capacity() * (size_of::<(u64, EntryId)>() + 1);
+        let collisions = self.collisions.capacity() * (size_of::<(Box<str>, EntryId)>() + 1)
+            + self.collisions.keys().map(|path| path.len()).sum::<usize>();
+        files + text + by_hash + collisions
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.files.clear();
+        self.by_hash.clear();
+        self.collisions.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn file(path: &str, name: &str) -> IndexedFile {
+        IndexedFile {
+            name: name.to_string(),
+            path: path.to_string(),
+            pak_file: PathBuf::from("Shared.pak"),
+            declared_type: FileType::from_extension(path.rsplit('.').next().unwrap_or("")),
+            detected_type: None,
+            size: 10,
+        }
+    }
+
+    #[test]
+    fn test_store_paths_once() {
+        let mut store = EntryStore::default();
+        let (armor, added) = store.insert(&file("Public/Stats/Armor.txt", "Armor.txt"), 0);
+        assert!(added);
+        // A name that isn't the path's last part is kept after the path
+        let (odd, _) = store.insert(&file("Public/Stats/blob", "Renamed.lsf"), 1);
+
+        assert_eq!(store.get(armor).path(), "Public/Stats/Armor.txt");
+        assert_eq!(store.get(armor).name(), "Armor.txt");
+        assert_eq!(&*store.get(armor).text, "Public/Stats/Armor.txt");
+        assert_eq!(store.get(odd).path(), "Public/Stats/blob");
+        assert_eq!(store.get(odd).name(), "Renamed.lsf");
+        assert_eq!(store.get(odd).pak, 1);
+
+        // Re-adding a path replaces its entry
+        let mut bigger = file("Public/Stats/Armor.txt", "Armor.txt");
+        bigger.size = 99;
+        assert_eq!(store.insert(&bigger, 2), (armor, false));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get(armor).size, 99);
+        assert_eq!(store.id_of("Public/Stats/Armor.txt"), Some(armor));
+        assert_eq!(store.id_of("Public/Stats/Weapon.txt"), None);
+    }
+
+    #[test]
+    fn test_colliding_hashes_are_found() {
+        let mut store = EntryStore::default();
+        let (first, _) = store.insert(&file("A.lsx", "A.lsx"), 0);
+        // Force a collision: claim B.lsx's hash slot for the first entry
+        let hash = store.hasher.hash_one("B.lsx");
+        store.by_hash.insert(hash, first);
+        let (second, added) = store.insert(&file("B.lsx", "B.lsx"), 0);
+
+        assert!(added);
+        assert_eq!(store.collisions.len(), 1);
+        assert_eq!(store.id_of("A.lsx"), Some(first));
+        assert_eq!(store.id_of("B.lsx"), Some(second));
+        assert_eq!(store.id_of("C.lsx"), None);
+    }
+
+    #[test]
+    fn test_heap_bytes_counts_paths() {
+        let mut store = EntryStore::default();
+        assert_eq!(store.heap_bytes(), 0);
+
+        let short = "Public/A.lsx";
+        store.insert(&file(short, "A.lsx"), 0);
+        let one = store.heap_bytes();
+        assert!(one >= size_of::<StoredFile>() + short.len());
+
+        // A second copy of the store with a longer path grows by the extra text
+        let mut long = EntryStore::default();
+        let long_path = format!("Public/{}/A.lsx", "Nested".repeat(50));
+        long.insert(&file(&long_path, "A.lsx"), 0);
+        assert_eq!(long.heap_bytes() - one, long_path.len() - short.len());
+    }
+}
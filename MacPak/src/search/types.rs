@@ -1,13 +1,17 @@
 //! Types for the search index module
 
+use maclarian::utils::GuidByteOrder;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use super::fulltext::FullTextResult;
 
 /// File type classification for filtering
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FileType {
     Lsx,
     Lsf,
+    Lsb,
     Lsj,
     Lsbc,
     Xml,
@@ -28,6 +32,7 @@ impl FileType {
         match ext.to_lowercase().as_str() {
             "lsx" => FileType::Lsx,
             "lsf" => FileType::Lsf,
+            "lsb" => FileType::Lsb,
             "lsj" => FileType::Lsj,
             "lsbc" | "lsbs" | "lsbx" => FileType::Lsbc,
             "xml" => FileType::Xml,
@@ -42,12 +47,55 @@ impl FileType {
         }
     }
 
+    /// Detect a file type from the first bytes of a file
+    ///
+    /// Text is told apart by its root element or key: `<save>` documents are
+    /// LSX and `"save"` objects LSJ, anything else is XML or JSON. Returns
+    /// `None` if the content isn't recognized.
+    #[must_use]
+    pub fn from_magic(head: &[u8]) -> Option<Self> {
+        match head.get(..4)? {
+            b"RIFF" => return Some(FileType::Wem),
+            b"GRPG" => return Some(FileType::Gts),
+            b"GRAP" => return Some(FileType::Gtp),
+            _ => {}
+        }
+
+        let text = || String::from_utf8_lossy(head);
+        match maclarian::converter::sniff_format(head)? {
+            "lsx" | "xml" if text().contains("<save") => Some(FileType::Lsx),
+            "lsx" | "xml" => Some(FileType::Xml),
+            "lsj" | "gltf" if text().contains("\"save\"") => Some(FileType::Lsj),
+            "lsj" | "gltf" => Some(FileType::Json),
+            ext => Some(FileType::from_extension(ext)),
+        }
+    }
+
+    /// Whether content of type `other` is expected in a file of this type
+    ///
+    /// LSX and XML, LSJ and JSON, and LSF and the LSF-based `.lsbc` family
+    /// count as the same.
+    #[must_use]
+    pub fn is_compatible_with(self, other: FileType) -> bool {
+        use FileType::{Json, Lsbc, Lsf, Lsj, Lsx, Xml};
+        self == other
+            || matches!(
+                (self, other),
+                (Lsx | Xml, Lsx | Xml) | (Lsj | Json, Lsj | Json) | (Lsbc | Lsf, Lsbc | Lsf)
+            )
+    }
+
     /// Check if this is a text-based format that can be content-searched
     #[must_use]
     pub fn is_searchable_text(&self) -> bool {
         matches!(
             self,
-            FileType::Lsx | FileType::Lsf | FileType::Lsj | FileType::Xml | FileType::Json
+            FileType::Lsx
+                | FileType::Lsf
+                | FileType::Lsb
+                | FileType::Lsj
+                | FileType::Xml
+                | FileType::Json
         )
     }
 
@@ -57,6 +105,7 @@ impl FileType {
         match self {
             FileType::Lsx => "LSX",
             FileType::Lsf => "LSF",
+            FileType::Lsb => "LSB",
             FileType::Lsj => "LSJ",
             FileType::Lsbc => "LSBC",
             FileType::Xml => "XML",
@@ -81,12 +130,113 @@ pub struct IndexedFile {
     pub path: String,
     /// Source PAK file
     pub pak_file: PathBuf,
-    /// Detected file type
-    pub file_type: FileType,
+    /// File type from the extension
+    #[serde(alias = "file_type")]
+    pub declared_type: FileType,
+    /// File type from the content's first bytes, if recognized
+    #[serde(default)]
+    pub detected_type: Option<FileType>,
+    /// Decompressed file size in bytes
+    pub size: u64,
+}
+
+impl IndexedFile {
+    /// Borrowed view of this file, as returned by the index's accessors
+    #[must_use]
+    pub fn view(&self) -> IndexedFileRef<'_> {
+        IndexedFileRef {
+            name: &self.name,
+            path: &self.path,
+            pak_file: &self.pak_file,
+            declared_type: self.declared_type,
+            detected_type: self.detected_type,
+            size: self.size,
+        }
+    }
+
+    /// The file's actual type: detected from its content, or from its
+    /// extension if the content wasn't recognized
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        self.view().file_type()
+    }
+
+    /// Whether the file matches a type filter
+    ///
+    /// Either the declared or the detected type may match, unless `strict`,
+    /// in which case only the declared type counts.
+    #[must_use]
+    pub fn matches_type(&self, file_type: FileType, strict: bool) -> bool {
+        self.view().matches_type(file_type, strict)
+    }
+
+    /// Whether the content doesn't match the extension, e.g. LSX text in a
+    /// `.lsf` file
+    #[must_use]
+    pub fn is_misnamed(&self) -> bool {
+        self.view().is_misnamed()
+    }
+}
+
+/// An indexed file, borrowed from the index's compact storage
+///
+/// Has the same fields as [`IndexedFile`]; convert it with
+/// `IndexedFile::from` to keep it beyond the index's lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexedFileRef<'a> {
+    /// Filename only (without path)
+    pub name: &'a str,
+    /// Full internal path within PAK
+    pub path: &'a str,
+    /// Source PAK file
+    pub pak_file: &'a Path,
+    /// File type from the extension
+    pub declared_type: FileType,
+    /// File type from the content's first bytes, if recognized
+    pub detected_type: Option<FileType>,
     /// Decompressed file size in bytes
     pub size: u64,
 }
 
+impl IndexedFileRef<'_> {
+    /// The file's actual type: detected from its content, or from its
+    /// extension if the content wasn't recognized
+    #[must_use]
+    pub fn file_type(&self) -> FileType {
+        self.detected_type.unwrap_or(self.declared_type)
+    }
+
+    /// Whether the file matches a type filter
+    ///
+    /// Either the declared or the detected type may match, unless `strict`,
+    /// in which case only the declared type counts.
+    #[must_use]
+    pub fn matches_type(&self, file_type: FileType, strict: bool) -> bool {
+        self.declared_type == file_type || (!strict && self.detected_type == Some(file_type))
+    }
+
+    /// Whether the content doesn't match the extension, e.g. LSX text in a
+    /// `.lsf` file
+    #[must_use]
+    pub fn is_misnamed(&self) -> bool {
+        self.detected_type
+            .is_some_and(|detected| !self.declared_type.is_compatible_with(detected))
+    }
+}
+
+impl From<IndexedFileRef<'_>> for IndexedFile {
+    fn from(file: IndexedFileRef<'_>) -> Self {
+        Self {
+            name: file.name.to_string(),
+            path: file.path.to_string(),
+            pak_file: file.pak_file.to_path_buf(),
+            declared_type: file.declared_type,
+            detected_type: file.detected_type,
+            size: file.size,
+        }
+    }
+}
+
 /// Progress callback type for search operations
 pub type SearchProgressCallback<'a> = &'a (dyn Fn(&SearchProgress) + Sync + Send);
 
@@ -188,4 +338,83 @@ pub struct IndexMetadata {
     pub indexed_paks: Vec<PathBuf>,
     /// Number of documents in the fulltext index
     pub fulltext_doc_count: u64,
+    /// When the index was built, in seconds since the Unix epoch
+    #[serde(default)]
+    pub built_at: Option<u64>,
+    /// Game install the PAKs were found in, for indexes built from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game: Option<GameSource>,
+}
+
+/// Game install a `SearchIndex` was built from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSource {
+    /// Install or `Data` folder that was scanned
+    pub dir: PathBuf,
+    /// Whether video, audio and shader cache PAKs were left out
+    pub skip_media: bool,
+}
+
+/// Summary of an index for display
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    /// Number of files in the metadata index
+    pub file_count: usize,
+    /// Number of PAK files indexed
+    pub pak_count: usize,
+    /// Number of documents in the fulltext index (0 if not built)
+    pub fulltext_doc_count: u64,
+    /// Indexed PAKs that are missing or were modified after the index was
+    /// built, then PAKs added to the game install since
+    pub stale_paks: Vec<PathBuf>,
+}
+
+/// How closely a filename matched a filename search, best last so ranks
+/// compare naturally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MatchRank {
+    /// The query appears inside the filename
+    Substring,
+    /// Every query term starts a token of the filename
+    Token,
+    /// The filename starts with the first query term
+    Prefix,
+    /// The filename, with or without its extension, is the query
+    Exact,
+}
+
+/// A filename search result with its rank
+#[derive(Debug, Clone, Copy)]
+pub struct FilenameMatch<'a> {
+    /// The matching file
+    pub entry: IndexedFileRef<'a>,
+    /// How closely the filename matched
+    pub rank: MatchRank,
+}
+
+/// A fuzzy filename search result with its edit distance
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzyMatch<'a> {
+    /// The matching file
+    pub entry: IndexedFileRef<'a>,
+    /// Total edits between the query words and the filename tokens they matched
+    pub distance: u32,
+}
+
+/// A file whose path contains a searched GUID, in one of its byte orders
+#[derive(Debug, Clone, Copy)]
+pub struct UuidMatch<'a> {
+    /// The matching file
+    pub entry: IndexedFileRef<'a>,
+    /// Which form of the GUID the path contains
+    pub order: GuidByteOrder,
+}
+
+/// A full-text hit for a searched GUID, in one of its byte orders
+#[derive(Debug, Clone)]
+pub struct UuidContentMatch {
+    /// The matching file, with a snippet around the GUID
+    pub result: FullTextResult,
+    /// Which form of the GUID the content contains
+    pub order: GuidByteOrder,
 }
@@ -0,0 +1,29 @@
+//! Documents shared by unit tests
+
+/// A single `Config` region whose `root` node has `Name = "Test"`
+pub const CONFIG_LSX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+	<version major="4" minor="0" revision="9" build="331" />
+	<region id="Config">
+		<node id="root">
+			<attribute id="Name" type="LSString" value="Test" />
+		</node>
+	</region>
+</save>
+"#;
+
+/// A `Templates` region with one `GameObjects` node (`ParentTemplateId = "abc"`)
+pub const TEMPLATES_LSX: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<save>
+	<version major="4" minor="0" revision="9" build="331" />
+	<region id="Templates">
+		<node id="Templates">
+			<children>
+				<node id="GameObjects">
+					<attribute id="ParentTemplateId" type="FixedString" value="abc" />
+				</node>
+			</children>
+		</node>
+	</region>
+</save>
+"#;
@@ -0,0 +1,624 @@
+//! Small previews of indexed files for the browser and search results
+//!
+//! [`ThumbnailService`] decodes thumbnails on a fixed pool of worker threads
+//! and keeps them in a [`ThumbnailCache`] under `~/.macpak/thumbcache/`, one
+//! file per thumbnail named after a hash of the source, internal path and
+//! thumbnail size. Each cache file records the size and modification time of
+//! its source; a thumbnail whose PAK has changed since is discarded and
+//! decoded again. The cache is trimmed to a total size, oldest files first.
+//!
+//! DDS and image files are decoded and downscaled, GTS tile sets use the
+//! lowest mip of their base layer. Other types, including GR2 models, have no
+//! thumbnail and are shown with their type icon.
+//!
+//! The source of an [`IndexedFile`] is normally a PAK. When `pak_file` is a
+//! directory, `path` is read from disk relative to it instead, so loose files
+//! in the browser get thumbnails too.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use image::RgbaImage;
+use maclarian::converter::{dds_info, decode_dds_bytes};
+use maclarian::pak::PakOperations;
+use maclarian::virtual_texture::{GtsFile, VirtualTextureLayer, decode_lowest_mip};
+
+use crate::search::{FileType, IndexedFile};
+
+/// Cache directory, relative to the home directory
+pub const CACHE_DIR: &str = ".macpak/thumbcache";
+
+/// Default edge length of a thumbnail in pixels
+pub const THUMBNAIL_SIZE: u32 = 64;
+
+/// Default limit on the total size of the disk cache
+pub const DEFAULT_MAX_CACHE_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Default number of decoding threads
+pub const DEFAULT_WORKERS: usize = 2;
+
+/// Files larger than this are not decoded
+const MAX_SOURCE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Images with more pixels than this are not decoded
+const MAX_SOURCE_PIXELS: u64 = 8192 * 8192;
+
+/// Thumbnails (and failures) kept in memory
+const MAX_MEMORY_ENTRIES: usize = 1024;
+
+/// Cache file magic and format version
+const CACHE_MAGIC: &[u8; 4] = b"MPTH";
+const CACHE_VERSION: u32 = 1;
+const CACHE_HEADER_SIZE: usize = 32;
+
+/// A decoded, downscaled preview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// RGBA8 pixel data
+    pub rgba_data: Vec<u8>,
+}
+
+/// Whether files of this type get a thumbnail
+pub fn has_thumbnail(file_type: FileType) -> bool {
+    matches!(file_type, FileType::Dds | FileType::Png | FileType::Gts)
+}
+
+/// Decode `file` and scale it to fit in `size`x`size`
+pub fn render_thumbnail(file: &IndexedFile, size: u32) -> Result<Thumbnail, String> {
+    if file.size > MAX_SOURCE_BYTES {
+        return Err(format!("{} is too large", file.name));
+    }
+    let data = read_source(file, &file.path).map_err(|e| e.to_string())?;
+
+    let img = match file.file_type() {
+        FileType::Dds => {
+            let info = dds_info(&data).map_err(|e| e.to_string())?;
+            check_dimensions(info.width, info.height)?;
+            decode_dds_bytes(&data).map_err(|e| e.to_string())?.1
+        }
+        FileType::Png => {
+            let (width, height) = image::ImageReader::new(Cursor::new(&data))
+                .with_guessed_format()
+                .map_err(|e| e.to_string())?
+                .into_dimensions()
+                .map_err(|e| e.to_string())?;
+            check_dimensions(width, height)?;
+            image::load_from_memory(&data)
+                .map_err(|e| e.to_string())?
+                .into_rgba8()
+        }
+        FileType::Gts => {
+            let gts = GtsFile::read(&mut Cursor::new(&data)).map_err(|e| e.to_string())?;
+            // Page files sit next to the tile set
+            let dir = file.path.rsplit_once('/').map_or("", |(dir, _)| dir);
+            decode_lowest_mip(&gts, VirtualTextureLayer::BaseMap, |name| {
+                let path = if dir.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}/{}", dir, name)
+                };
+                read_source(file, &path).map(Cursor::new)
+            })
+            .map_err(|e| e.to_string())?
+        }
+        other => return Err(format!("{} files have no thumbnail", other.display_name())),
+    };
+    Ok(downscale(img, size))
+}
+
+/// Read `path` from the PAK (or directory) `file` comes from
+fn read_source(file: &IndexedFile, path: &str) -> maclarian::error::Result<Vec<u8>> {
+    if file.pak_file.is_dir() {
+        Ok(fs::read(file.pak_file.join(path))?)
+    } else {
+        PakOperations::read_file_bytes(&file.pak_file, path)
+    }
+}
+
+fn check_dimensions(width: u32, height: u32) -> Result<(), String> {
+    if u64::from(width) * u64::from(height) > MAX_SOURCE_PIXELS {
+        return Err(format!("{}x{} is too large to decode", width, height));
+    }
+    Ok(())
+}
+
+/// Scale an image to fit in `size`x`size`, keeping its aspect ratio
+fn downscale(img: RgbaImage, size: u32) -> Thumbnail {
+    let (width, height) = img.dimensions();
+    let img = if width <= size && height <= size {
+        img
+    } else {
+        let scale = size as f32 / width.max(height) as f32;
+        let new_width = ((width as f32 * scale).round() as u32).max(1);
+        let new_height = ((height as f32 * scale).round() as u32).max(1);
+        image::imageops::thumbnail(&img, new_width, new_height)
+    };
+    Thumbnail {
+        width: img.width(),
+        height: img.height(),
+        rgba_data: img.into_raw(),
+    }
+}
+
+/// Size and modification time of the file a thumbnail was made from
+fn source_stamp(file: &IndexedFile) -> Option<(u64, u64)> {
+    let path = if file.pak_file.is_dir() {
+        file.pak_file.join(&file.path)
+    } else {
+        file.pak_file.clone()
+    };
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((meta.len(), modified))
+}
+
+/// Thumbnails on disk, bounded by total size
+#[derive(Debug)]
+pub struct ThumbnailCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Total size of the cache files, once the directory has been scanned
+    total_bytes: Mutex<Option<u64>>,
+}
+
+impl ThumbnailCache {
+    /// Cache in `dir` (created on first write), trimmed to `max_bytes`
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes,
+            total_bytes: Mutex::new(None),
+        }
+    }
+
+    /// Cache in `~/.macpak/thumbcache` with the default size limit
+    pub fn default_location() -> Option<Self> {
+        dirs::home_dir().map(|home| Self::new(home.join(CACHE_DIR), DEFAULT_MAX_CACHE_BYTES))
+    }
+
+    /// Directory holding the cache files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Cache key of a thumbnail: a hash of its source, path and size
+    pub fn key(file: &IndexedFile, size: u32) -> String {
+        let id = format!("{}\n{}\n{}", file.pak_file.display(), file.path, size);
+        format!("{:x}", md5::compute(id.as_bytes()))
+    }
+
+    /// The cached thumbnail of `file`, unless its source changed since it
+    /// was made (stale files are deleted)
+    pub fn load(&self, file: &IndexedFile, size: u32) -> Option<Thumbnail> {
+        let path = self.path_for(&Self::key(file, size));
+        let data = fs::read(&path).ok()?;
+        let stamp = source_stamp(file);
+        match decode_cache_file(&data) {
+            Some((cached_stamp, thumbnail)) if Some(cached_stamp) == stamp => Some(thumbnail),
+            _ => {
+                if fs::remove_file(&path).is_ok() {
+                    self.adjust_total(-(data.len() as i64));
+                }
+                None
+            }
+        }
+    }
+
+    /// Store the thumbnail of `file`, then trim the cache if it is too large
+    pub fn store(
+        &self,
+        file: &IndexedFile,
+        size: u32,
+        thumbnail: &Thumbnail,
+    ) -> Result<(), String> {
+        let Some(stamp) = source_stamp(file) else {
+            return Err(format!("{} is unavailable", file.pak_file.display()));
+        };
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+
+        // Write then rename, so a crash mid-write never leaves a truncated file
+        let path = self.path_for(&Self::key(file, size));
+        let temp = path.with_extension("tmp");
+        let data = encode_cache_file(stamp, thumbnail);
+        let replaced = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        fs::write(&temp, &data)
+            .map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+        fs::rename(&temp, &path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        if self.adjust_total(data.len() as i64 - replaced as i64) > self.max_bytes {
+            self.trim();
+        }
+        Ok(())
+    }
+
+    /// Total size of the cache files
+    pub fn total_bytes(&self) -> u64 {
+        self.adjust_total(0)
+    }
+
+    /// Delete the oldest cache files until the cache fits in its limit
+    pub fn trim(&self) {
+        let mut files: Vec<(std::time::SystemTime, u64, PathBuf)> = self
+            .cache_files()
+            .into_iter()
+            .filter_map(|path| {
+                let meta = fs::metadata(&path).ok()?;
+                Some((meta.modified().ok()?, meta.len(), path))
+            })
+            .collect();
+        files.sort();
+
+        let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+        for (_, len, path) in files {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total -= len;
+            }
+        }
+        if let Ok(mut cached) = self.total_bytes.lock() {
+            *cached = Some(total);
+        }
+    }
+
+    /// Delete every cache file
+    pub fn clear(&self) {
+        for path in self.cache_files() {
+            let _ = fs::remove_file(path);
+        }
+        if let Ok(mut cached) = self.total_bytes.lock() {
+            *cached = Some(0);
+        }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.thumb", key))
+    }
+
+    fn cache_files(&self) -> Vec<PathBuf> {
+        fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "thumb"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Add `delta` to the tracked total (scanning the directory the first
+    /// time) and return the new total
+    fn adjust_total(&self, delta: i64) -> u64 {
+        let Ok(mut cached) = self.total_bytes.lock() else {
+            return 0;
+        };
+        let total = cached.unwrap_or_else(|| {
+            self.cache_files()
+                .iter()
+                .filter_map(|p| fs::metadata(p).ok())
+                .map(|m| m.len())
+                .sum()
+        });
+        let total = total.saturating_add_signed(delta);
+        *cached = Some(total);
+        total
+    }
+}
+
+fn encode_cache_file(stamp: (u64, u64), thumbnail: &Thumbnail) -> Vec<u8> {
+    let mut data = Vec::with_capacity(CACHE_HEADER_SIZE + thumbnail.rgba_data.len());
+    data.extend_from_slice(CACHE_MAGIC);
+    data.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    data.extend_from_slice(&stamp.0.to_le_bytes());
+    data.extend_from_slice(&stamp.1.to_le_bytes());
+    data.extend_from_slice(&thumbnail.width.to_le_bytes());
+    data.extend_from_slice(&thumbnail.height.to_le_bytes());
+    data.extend_from_slice(&thumbnail.rgba_data);
+    data
+}
+
+fn decode_cache_file(data: &[u8]) -> Option<((u64, u64), Thumbnail)> {
+    let u32_at = |at: usize| Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let u64_at = |at: usize| Some(u64::from_le_bytes(data.get(at..at + 8)?.try_into().ok()?));
+    if data.get(..4)? != CACHE_MAGIC || u32_at(4)? != CACHE_VERSION {
+        return None;
+    }
+    let stamp = (u64_at(8)?, u64_at(16)?);
+    let (width, height) = (u32_at(24)?, u32_at(28)?);
+    let rgba_data = data.get(CACHE_HEADER_SIZE..)?.to_vec();
+    if rgba_data.len() as u64 != u64::from(width) * u64::from(height) * 4 {
+        return None;
+    }
+    Some((
+        stamp,
+        Thumbnail {
+            width,
+            height,
+            rgba_data,
+        },
+    ))
+}
+
+/// Called with the thumbnail once it is ready, or `None` if there is none
+pub type ThumbnailCallback = Box<dyn FnOnce(Option<Arc<Thumbnail>>) + Send>;
+
+struct Job {
+    key: String,
+    file: IndexedFile,
+    size: u32,
+}
+
+/// Thumbnails decoded in memory, most recently used at the back
+#[derive(Default)]
+struct MemoryCache {
+    entries: HashMap<String, Option<Arc<Thumbnail>>>,
+    order: VecDeque<String>,
+}
+
+impl MemoryCache {
+    fn get(&mut self, key: &str) -> Option<&Option<Arc<Thumbnail>>> {
+        let entry = self.entries.get(key)?;
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            let key = self.order.remove(pos).unwrap_or_default();
+            self.order.push_back(key);
+        }
+        Some(entry)
+    }
+
+    fn insert(&mut self, key: String, thumbnail: Option<Arc<Thumbnail>>) {
+        if self.entries.insert(key.clone(), thumbnail).is_none() {
+            self.order.push_back(key);
+        }
+        while self.entries.len() > MAX_MEMORY_ENTRIES {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+struct Shared {
+    cache: ThumbnailCache,
+    memory: Mutex<MemoryCache>,
+    /// Callbacks waiting for a thumbnail that is queued or being decoded
+    pending: Mutex<HashMap<String, Vec<ThumbnailCallback>>>,
+    decoded: AtomicU64,
+}
+
+/// Decodes thumbnails in the background, backed by a [`ThumbnailCache`]
+///
+/// Requests for a thumbnail that is already queued share its result. Worker
+/// threads exit when the service is dropped.
+pub struct ThumbnailService {
+    shared: Arc<Shared>,
+    jobs: Sender<Job>,
+}
+
+impl ThumbnailService {
+    /// Service backed by `cache`, decoding on `workers` threads
+    pub fn new(cache: ThumbnailCache, workers: usize) -> Self {
+        let shared = Arc::new(Shared {
+            cache,
+            memory: Mutex::new(MemoryCache::default()),
+            pending: Mutex::new(HashMap::new()),
+            decoded: AtomicU64::new(0),
+        });
+        let (jobs, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..workers.max(1) {
+            let shared = Arc::clone(&shared);
+            let receiver = Arc::clone(&receiver);
+            let spawned = std::thread::Builder::new()
+                .name(format!("thumbnail-{}", i))
+                .spawn(move || run_worker(&shared, &receiver));
+            if let Err(e) = spawned {
+                tracing::warn!("Failed to start thumbnail worker: {}", e);
+            }
+        }
+        Self { shared, jobs }
+    }
+
+    /// The cache the service reads and writes
+    pub fn cache(&self) -> &ThumbnailCache {
+        &self.shared.cache
+    }
+
+    /// Number of thumbnails decoded (not served from a cache) so far
+    pub fn decoded_count(&self) -> u64 {
+        self.shared.decoded.load(Ordering::Relaxed)
+    }
+
+    /// The thumbnail of `file` if it is already in memory: `Some(None)` means
+    /// it has none, `None` that it hasn't been loaded yet
+    pub fn cached(&self, file: &IndexedFile, size: u32) -> Option<Option<Arc<Thumbnail>>> {
+        if !has_thumbnail(file.file_type()) {
+            return Some(None);
+        }
+        let key = ThumbnailCache::key(file, size);
+        self.shared.memory.lock().ok()?.get(&key).cloned()
+    }
+
+    /// Load the thumbnail of `file` in the background and pass it to `done`
+    ///
+    /// `done` runs on a worker thread, or right away if the result is
+    /// already known.
+    pub fn request(
+        &self,
+        file: &IndexedFile,
+        size: u32,
+        done: impl FnOnce(Option<Arc<Thumbnail>>) + Send + 'static,
+    ) {
+        if let Some(known) = self.cached(file, size) {
+            done(known);
+            return;
+        }
+
+        let key = ThumbnailCache::key(file, size);
+        {
+            let Ok(mut pending) = self.shared.pending.lock() else {
+                done(None);
+                return;
+            };
+            if let Some(waiting) = pending.get_mut(&key) {
+                waiting.push(Box::new(done));
+                return;
+            }
+            pending.insert(key.clone(), vec![Box::new(done)]);
+        }
+
+        let job = Job {
+            key,
+            file: file.clone(),
+            size,
+        };
+        if let Err(mpsc::SendError(job)) = self.jobs.send(job) {
+            finish(&self.shared, &job.key, None);
+        }
+    }
+}
+
+fn run_worker(shared: &Shared, receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = match receiver.lock() {
+            Ok(receiver) => match receiver.recv() {
+                Ok(job) => job,
+                Err(_) => return,
+            },
+            Err(_) => return,
+        };
+
+        let thumbnail = shared.cache.load(&job.file, job.size).or_else(|| {
+            match render_thumbnail(&job.file, job.size) {
+                Ok(thumbnail) => {
+                    shared.decoded.fetch_add(1, Ordering::Relaxed);
+                    if let Err(e) = shared.cache.store(&job.file, job.size, &thumbnail) {
+                        tracing::debug!("Thumbnail not cached: {}", e);
+                    }
+                    Some(thumbnail)
+                }
+                Err(e) => {
+                    tracing::debug!("No thumbnail for {}: {}", job.file.path, e);
+                    None
+                }
+            }
+        });
+        finish(shared, &job.key, thumbnail.map(Arc::new));
+    }
+}
+
+/// Remember a result and hand it to everything waiting for it
+fn finish(shared: &Shared, key: &str, thumbnail: Option<Arc<Thumbnail>>) {
+    if let Ok(mut memory) = shared.memory.lock() {
+        memory.insert(key.to_string(), thumbnail.clone());
+    }
+    let waiting = shared
+        .pending
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.remove(key))
+        .unwrap_or_default();
+    for done in waiting {
+        done(thumbnail.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_file(dir: &Path, name: &str, width: u32, height: u32) -> IndexedFile {
+        let img = RgbaImage::from_pixel(width, height, image::Rgba([200, 40, 40, 255]));
+        img.save(dir.join(name)).unwrap();
+        IndexedFile {
+            name: name.to_string(),
+            path: name.to_string(),
+            pak_file: dir.to_path_buf(),
+            declared_type: FileType::Png,
+            detected_type: None,
+            size: fs::metadata(dir.join(name)).unwrap().len(),
+        }
+    }
+
+    #[test]
+    fn test_cache_round_trip_invalidation_and_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        fs::create_dir_all(&source).unwrap();
+
+        let file = png_file(&source, "wide.png", 256, 128);
+        let thumbnail = render_thumbnail(&file, THUMBNAIL_SIZE).unwrap();
+        assert_eq!((thumbnail.width, thumbnail.height), (64, 32));
+
+        // One 64x32 thumbnail fits, a second one pushes the oldest out
+        let entry_size = (CACHE_HEADER_SIZE + 64 * 32 * 4) as u64;
+        let cache = ThumbnailCache::new(dir.path().join("cache"), entry_size + entry_size / 2);
+        cache.store(&file, THUMBNAIL_SIZE, &thumbnail).unwrap();
+        assert_eq!(cache.load(&file, THUMBNAIL_SIZE), Some(thumbnail.clone()));
+        assert_eq!(cache.load(&file, 32), None);
+
+        let other = png_file(&source, "other.png", 128, 64);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache
+            .store(
+                &other,
+                THUMBNAIL_SIZE,
+                &render_thumbnail(&other, THUMBNAIL_SIZE).unwrap(),
+            )
+            .unwrap();
+        assert!(cache.total_bytes() <= entry_size + entry_size / 2);
+        assert!(cache.load(&other, THUMBNAIL_SIZE).is_some());
+
+        // Changing the source invalidates its thumbnail
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        png_file(&source, "other.png", 64, 64);
+        assert_eq!(cache.load(&other, THUMBNAIL_SIZE), None);
+        assert_eq!(cache.total_bytes(), 0);
+    }
+
+    #[test]
+    fn test_service_shares_pending_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = png_file(dir.path(), "icon.png", 32, 32);
+
+        let service =
+            ThumbnailService::new(ThumbnailCache::new(dir.path().join("cache"), u64::MAX), 2);
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..3 {
+            let sender = sender.clone();
+            service.request(&file, THUMBNAIL_SIZE, move |t| {
+                sender.send(t.map(|t| t.width)).unwrap();
+            });
+        }
+        for _ in 0..3 {
+            assert_eq!(receiver.recv().unwrap(), Some(32));
+        }
+        assert_eq!(service.decoded_count(), 1);
+        assert!(service.cached(&file, THUMBNAIL_SIZE).is_some());
+
+        let mut gr2 = file.clone();
+        gr2.declared_type = FileType::Gr2;
+        assert_eq!(service.cached(&gr2, THUMBNAIL_SIZE), Some(None));
+
+        drop(service);
+    }
+}
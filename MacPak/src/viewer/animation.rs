@@ -0,0 +1,486 @@
+//! Skeletal animation playback for models with animation clips
+
+use bevy::animation::RepeatAnimation;
+use bevy::gltf::Gltf;
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+use crate::viewer::types::ModelPath;
+
+/// Playback speeds cycled by the speed buttons
+const SPEEDS: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 1.5, 2.0];
+const NORMAL_SPEED: usize = 3;
+
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.12);
+const ACCENT_COLOR: Color = Color::srgb(0.2, 0.6, 1.0);
+
+/// One animation clip of the loaded model
+struct ClipInfo {
+    name: String,
+    node: AnimationNodeIndex,
+    duration: f32,
+}
+
+/// Animation clips of the model and the playback settings
+#[derive(Resource)]
+pub struct AnimationPlayback {
+    gltf: Handle<Gltf>,
+    clips: Vec<ClipInfo>,
+    current: usize,
+    speed_index: usize,
+    looping: bool,
+    paused: bool,
+    /// Set once the model has loaded (with or without animations)
+    initialized: bool,
+}
+
+/// Something the bottom bar or a shortcut asks playback to do
+#[derive(Clone, Copy)]
+enum PlaybackAction {
+    TogglePause,
+    PrevClip,
+    NextClip,
+    Slower,
+    Faster,
+    ToggleLoop,
+    /// Jump to a fraction (0..1) of the current clip
+    Seek(f32),
+}
+
+// UI component markers
+#[derive(Component)]
+pub struct AnimationBar;
+
+#[derive(Component)]
+pub struct ScrubTrack;
+
+#[derive(Component)]
+pub struct ScrubFill;
+
+/// Buttons of the animation bar
+#[derive(Component, Clone, Copy)]
+pub enum BarButton {
+    PrevClip,
+    NextClip,
+    PlayPause,
+    Slower,
+    Faster,
+    Loop,
+}
+
+/// Text of the animation bar that follows playback
+#[derive(Component, Clone, Copy)]
+pub enum BarText {
+    Clip,
+    Time,
+    Speed,
+    PlayPause,
+    Loop,
+}
+
+impl AnimationPlayback {
+    fn current_clip(&self) -> Option<&ClipInfo> {
+        self.clips.get(self.current)
+    }
+
+    fn speed(&self) -> f32 {
+        SPEEDS[self.speed_index]
+    }
+
+    fn repeat(&self) -> RepeatAnimation {
+        if self.looping {
+            RepeatAnimation::Forever
+        } else {
+            RepeatAnimation::Never
+        }
+    }
+
+    /// Restart the current clip on every player with the current settings
+    fn start_current(&self, players: &mut Query<&mut AnimationPlayer>) {
+        let Some(clip) = self.current_clip() else {
+            return;
+        };
+        for mut player in players.iter_mut() {
+            player.stop_all();
+            let active = player.start(clip.node);
+            active.set_speed(self.speed()).set_repeat(self.repeat());
+            if self.paused {
+                active.pause();
+            }
+        }
+    }
+
+    /// Elapsed time in the current clip
+    fn current_time(&self, players: &Query<&AnimationPlayer>) -> f32 {
+        let Some(clip) = self.current_clip() else {
+            return 0.0;
+        };
+        players
+            .iter()
+            .find_map(|player| player.animation(clip.node))
+            .map(|active| active.seek_time().clamp(0.0, clip.duration))
+            .unwrap_or(0.0)
+    }
+
+    fn apply(&mut self, action: PlaybackAction, players: &mut Query<&mut AnimationPlayer>) {
+        if self.clips.is_empty() {
+            return;
+        }
+        let node = self.clips[self.current].node;
+        match action {
+            PlaybackAction::TogglePause => {
+                self.paused = !self.paused;
+                for mut player in players.iter_mut() {
+                    if let Some(active) = player.animation_mut(node) {
+                        if self.paused {
+                            active.pause();
+                        } else if active.is_finished() {
+                            active.replay();
+                            active.resume();
+                        } else {
+                            active.resume();
+                        }
+                    }
+                }
+            }
+            PlaybackAction::PrevClip | PlaybackAction::NextClip => {
+                let count = self.clips.len();
+                self.current = if matches!(action, PlaybackAction::NextClip) {
+                    (self.current + 1) % count
+                } else {
+                    (self.current + count - 1) % count
+                };
+                self.start_current(players);
+            }
+            PlaybackAction::Slower | PlaybackAction::Faster => {
+                self.speed_index = if matches!(action, PlaybackAction::Faster) {
+                    (self.speed_index + 1).min(SPEEDS.len() - 1)
+                } else {
+                    self.speed_index.saturating_sub(1)
+                };
+                let speed = self.speed();
+                for mut player in players.iter_mut() {
+                    if let Some(active) = player.animation_mut(node) {
+                        active.set_speed(speed);
+                    }
+                }
+            }
+            PlaybackAction::ToggleLoop => {
+                self.looping = !self.looping;
+                let repeat = self.repeat();
+                for mut player in players.iter_mut() {
+                    if let Some(active) = player.animation_mut(node) {
+                        active.set_repeat(repeat);
+                    }
+                }
+            }
+            PlaybackAction::Seek(fraction) => {
+                let time = fraction.clamp(0.0, 1.0) * self.clips[self.current].duration;
+                for mut player in players.iter_mut() {
+                    if let Some(active) = player.animation_mut(node) {
+                        // Scrubbing a finished clip makes it playable again
+                        if active.is_finished() {
+                            active.replay();
+                            if self.paused {
+                                active.pause();
+                            }
+                        }
+                        active.seek_to(time);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Start loading the model's animation clips
+pub fn setup_animation(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    model_path: Res<ModelPath>,
+) {
+    commands.insert_resource(AnimationPlayback {
+        gltf: match &model_path.loadable {
+            Ok(path) => asset_server.load(path.clone()),
+            Err(_) => Handle::default(),
+        },
+        clips: Vec::new(),
+        current: 0,
+        speed_index: NORMAL_SPEED,
+        looping: true,
+        paused: false,
+        initialized: false,
+    });
+}
+
+/// Once the model and its animation players exist, build an animation graph
+/// from the clips and start the first one
+pub fn init_animations(
+    mut commands: Commands,
+    mut playback: ResMut<AnimationPlayback>,
+    gltfs: Res<Assets<Gltf>>,
+    clips: Res<Assets<AnimationClip>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    player_entities: Query<Entity, With<AnimationPlayer>>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    if playback.initialized {
+        return;
+    }
+    let Some(gltf) = gltfs.get(&playback.gltf) else {
+        return;
+    };
+    if gltf.animations.is_empty() {
+        info!("Model has no animations");
+        playback.initialized = true;
+        return;
+    }
+    // Animation players are added when the scene spawns, after the asset loads
+    if player_entities.is_empty() {
+        return;
+    }
+
+    let (graph, nodes) = AnimationGraph::from_clips(gltf.animations.iter().cloned());
+    let graph = graphs.add(graph);
+    for entity in &player_entities {
+        commands
+            .entity(entity)
+            .insert(AnimationGraphHandle(graph.clone()));
+    }
+
+    playback.clips = gltf
+        .animations
+        .iter()
+        .zip(nodes)
+        .enumerate()
+        .map(|(i, (handle, node))| ClipInfo {
+            name: gltf
+                .named_animations
+                .iter()
+                .find(|(_, named)| *named == handle)
+                .map(|(name, _)| name.to_string())
+                .unwrap_or_else(|| format!("Animation {}", i + 1)),
+            node,
+            duration: clips.get(handle).map(|c| c.duration()).unwrap_or(0.0),
+        })
+        .collect();
+    playback.initialized = true;
+    info!("Model has {} animation clips", playback.clips.len());
+
+    playback.start_current(&mut players);
+}
+
+/// Space toggles playback, [ and ] switch clips
+pub fn handle_animation_keys(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut playback: ResMut<AnimationPlayback>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        playback.apply(PlaybackAction::TogglePause, &mut players);
+    }
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        playback.apply(PlaybackAction::PrevClip, &mut players);
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        playback.apply(PlaybackAction::NextClip, &mut players);
+    }
+}
+
+/// Handle animation bar button clicks and scrubbing
+pub fn handle_animation_bar(
+    mut playback: ResMut<AnimationPlayback>,
+    mut players: Query<&mut AnimationPlayer>,
+    button_query: Query<(&Interaction, &BarButton), Changed<Interaction>>,
+    track_query: Query<(&Interaction, &RelativeCursorPosition), With<ScrubTrack>>,
+) {
+    for (interaction, button) in &button_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let action = match button {
+            BarButton::PrevClip => PlaybackAction::PrevClip,
+            BarButton::NextClip => PlaybackAction::NextClip,
+            BarButton::PlayPause => PlaybackAction::TogglePause,
+            BarButton::Slower => PlaybackAction::Slower,
+            BarButton::Faster => PlaybackAction::Faster,
+            BarButton::Loop => PlaybackAction::ToggleLoop,
+        };
+        playback.apply(action, &mut players);
+    }
+
+    // Seek while the track is held, so dragging scrubs
+    for (interaction, cursor) in &track_query {
+        if *interaction == Interaction::Pressed {
+            if let Some(position) = cursor.normalized {
+                // Normalized positions are relative to the track's center
+                playback.apply(PlaybackAction::Seek(position.x + 0.5), &mut players);
+            }
+        }
+    }
+}
+
+/// Setup the animation bar along the bottom (hidden until clips are found)
+pub fn setup_animation_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                left: Val::Px(12.0),
+                right: Val::Px(12.0),
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            BorderRadius::all(Val::Px(8.0)),
+            AnimationBar,
+        ))
+        .with_children(|parent| {
+            spawn_bar_button(parent, "<", BarButton::PrevClip, None);
+            spawn_bar_text(parent, BarText::Clip, 160.0);
+            spawn_bar_button(parent, ">", BarButton::NextClip, None);
+            spawn_bar_button(
+                parent,
+                "Pause",
+                BarButton::PlayPause,
+                Some(BarText::PlayPause),
+            );
+
+            // Scrub track with a fill showing the current time
+            parent
+                .spawn((
+                    Node {
+                        flex_grow: 1.0,
+                        height: Val::Px(8.0),
+                        ..default()
+                    },
+                    BackgroundColor(BUTTON_COLOR),
+                    BorderRadius::all(Val::Px(4.0)),
+                    Button,
+                    RelativeCursorPosition::default(),
+                    ScrubTrack,
+                ))
+                .with_children(|track| {
+                    track.spawn((
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(ACCENT_COLOR),
+                        BorderRadius::all(Val::Px(4.0)),
+                        ScrubFill,
+                    ));
+                });
+
+            spawn_bar_text(parent, BarText::Time, 90.0);
+            spawn_bar_button(parent, "-", BarButton::Slower, None);
+            spawn_bar_text(parent, BarText::Speed, 40.0);
+            spawn_bar_button(parent, "+", BarButton::Faster, None);
+            spawn_bar_button(parent, "Loop", BarButton::Loop, Some(BarText::Loop));
+        });
+}
+
+fn spawn_bar_button(
+    parent: &mut ChildSpawnerCommands,
+    label: &str,
+    button: BarButton,
+    text: Option<BarText>,
+) {
+    parent
+        .spawn((
+            Node {
+                padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            BorderRadius::all(Val::Px(4.0)),
+            Button,
+            button,
+        ))
+        .with_children(|button| {
+            let mut label = button.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 13.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            if let Some(text) = text {
+                label.insert(text);
+            }
+        });
+}
+
+fn spawn_bar_text(parent: &mut ChildSpawnerCommands, text: BarText, width: f32) {
+    parent.spawn((
+        Node {
+            width: Val::Px(width),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        Text::new(""),
+        TextFont {
+            font_size: 13.0,
+            ..default()
+        },
+        TextColor(Color::srgba(0.9, 0.9, 0.9, 1.0)),
+        text,
+    ));
+}
+
+/// Sync the animation bar to the playback state
+pub fn update_animation_bar(
+    playback: Res<AnimationPlayback>,
+    players: Query<&AnimationPlayer>,
+    mut bar_query: Query<&mut Node, With<AnimationBar>>,
+    mut fill_query: Query<&mut Node, (With<ScrubFill>, Without<AnimationBar>)>,
+    mut text_query: Query<(&mut Text, &BarText)>,
+) {
+    let Some(clip) = playback.current_clip() else {
+        return;
+    };
+
+    for mut node in &mut bar_query {
+        node.display = Display::Flex;
+    }
+
+    let time = playback.current_time(&players);
+    let fraction = if clip.duration > 0.0 {
+        time / clip.duration
+    } else {
+        0.0
+    };
+    for mut node in &mut fill_query {
+        node.width = Val::Percent(fraction * 100.0);
+    }
+
+    for (mut text, kind) in &mut text_query {
+        let value = match kind {
+            BarText::Clip => format!(
+                "{}/{} {}",
+                playback.current + 1,
+                playback.clips.len(),
+                clip.name
+            ),
+            BarText::Time => format!("{:.2} / {:.2}s", time, clip.duration),
+            BarText::Speed => format!("{}x", playback.speed()),
+            BarText::PlayPause => if playback.paused { "Play" } else { "Pause" }.to_string(),
+            BarText::Loop => if playback.looping {
+                "Loop: On"
+            } else {
+                "Loop: Off"
+            }
+            .to_string(),
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
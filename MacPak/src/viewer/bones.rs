@@ -8,7 +8,7 @@ use bevy::prelude::*;
 
 use crate::viewer::types::ViewSettings;
 
-/// Draw skeleton bones as gizmo lines
+/// Draw skeleton bones as gizmo lines at their current (possibly animated) pose
 pub fn draw_bones(
     view_settings: Res<ViewSettings>,
     mut gizmos: Gizmos,
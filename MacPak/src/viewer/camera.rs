@@ -85,7 +85,13 @@ pub fn orbit_camera(
     mut rotate_events: MessageReader<RotationGesture>,
     mut query: Query<(&mut Transform, &mut OrbitCamera)>,
     model_bounds: Res<ModelBounds>,
+    ui_buttons: Query<&Interaction, With<Button>>,
 ) {
+    // Dragging on a UI control (e.g. the animation scrub bar) doesn't move the camera
+    if ui_buttons.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+
     let mut rotation_delta = Vec2::ZERO;
     let mut pan_delta = Vec2::ZERO;
     let mut zoom_delta: f32 = 0.0;
@@ -187,6 +193,11 @@ pub fn handle_keyboard(
         view_settings.show_bones = !view_settings.show_bones;
     }
 
+    // T - Toggle sidecar textures
+    if keyboard.just_pressed(KeyCode::KeyT) {
+        view_settings.show_textures = !view_settings.show_textures;
+    }
+
     // Arrow keys - Pan camera
     let mut pan = Vec2::ZERO;
     if keyboard.pressed(KeyCode::ArrowLeft) {
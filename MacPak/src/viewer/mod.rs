@@ -1,6 +1,8 @@
 //! MacPak 3D Viewer - Standalone 3D model preview window using Bevy
 //!
-//! Usage: macpak-viewer <path-to-glb-file>
+//! Usage: macpak-viewer <path-to-glb-or-gr2-file>
+//!
+//! GR2 files are converted to a temporary GLB at startup.
 //!
 //! Controls:
 //! - Left mouse drag: Orbit camera
@@ -12,31 +14,45 @@
 //! - W: Toggle wireframe mode
 //! - G: Toggle ground grid
 //! - B: Toggle bone/skeleton visualization
+//! - T: Toggle textures found next to the model
+//! - Space: Play/pause animation
+//! - [ / ]: Previous/next animation clip
 
+mod animation;
 mod bones;
 mod camera;
 mod scene;
+mod textures;
 pub mod types;
 mod ui;
 
+use std::path::{Path, PathBuf};
+
 use bevy::asset::{AssetPlugin, UnapprovedPathMode};
 use bevy::pbr::wireframe::{WireframeConfig, WireframePlugin};
 use bevy::prelude::*;
 use bevy::render::RenderPlugin;
 use bevy::render::settings::{RenderCreation, WgpuFeatures, WgpuSettings};
+use bevy::transform::TransformSystems;
 use clap::Parser;
+use maclarian::converter::convert_gr2_bytes_to_glb;
 
+use animation::{
+    handle_animation_bar, handle_animation_keys, init_animations, setup_animation,
+    setup_animation_bar, update_animation_bar,
+};
 use bones::draw_bones;
 use camera::{fit_camera_to_model, handle_keyboard, orbit_camera};
 use scene::{auto_rotate_model, setup_scene};
+use textures::{apply_sidecar_textures, load_sidecar_textures};
 use types::{CameraFitPending, ModelBounds, ModelPath, ViewSettings};
-use ui::{WireframeState, handle_checkbox_clicks, setup_ui, sync_view_settings};
+use ui::{WireframeState, handle_checkbox_clicks, setup_ui, show_load_error, sync_view_settings};
 
 #[derive(Parser)]
 #[command(name = "macpak-viewer")]
 #[command(about = "3D model preview for MacPak")]
 struct Args {
-    /// Path to the .glb or .gltf file to preview
+    /// Path to the .glb, .gltf or .gr2 file to preview
     file_path: String,
 }
 
@@ -45,11 +61,14 @@ pub fn run_viewer() {
     let args = Args::parse();
 
     // Extract filename for window title
-    let file_name = std::path::Path::new(&args.file_path)
+    let file_name = Path::new(&args.file_path)
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| "3D Preview".to_string());
 
+    // GR2 files are converted up front; the guard deletes the GLB on exit
+    let (loadable, _temp_glb) = prepare_model(&args.file_path);
+
     App::new()
         .add_plugins(
             DefaultPlugins
@@ -74,7 +93,10 @@ pub fn run_viewer() {
                     ..default()
                 }),
         )
-        .insert_resource(ModelPath(args.file_path))
+        .insert_resource(ModelPath {
+            source: args.file_path,
+            loadable,
+        })
         .insert_resource(CameraFitPending(true))
         .insert_resource(ModelBounds::default())
         .insert_resource(ViewSettings::default())
@@ -85,9 +107,78 @@ pub fn run_viewer() {
         })
         .insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.1)))
         .insert_resource(WireframeState::default())
-        .add_systems(Startup, (setup_scene, setup_ui))
+        .add_systems(
+            Startup,
+            (
+                setup_scene,
+                setup_ui,
+                setup_animation,
+                setup_animation_bar,
+                load_sidecar_textures,
+                show_load_error,
+            ),
+        )
         .add_systems(Update, (fit_camera_to_model, orbit_camera))
-        .add_systems(Update, (auto_rotate_model, handle_keyboard, draw_bones))
+        .add_systems(Update, (auto_rotate_model, handle_keyboard))
         .add_systems(Update, (handle_checkbox_clicks, sync_view_settings))
+        .add_systems(Update, apply_sidecar_textures)
+        .add_systems(
+            Update,
+            (
+                init_animations,
+                handle_animation_keys,
+                handle_animation_bar,
+                update_animation_bar,
+            )
+                .chain(),
+        )
+        // Bones are drawn after transform propagation so they follow the animated pose
+        .add_systems(PostUpdate, draw_bones.after(TransformSystems::Propagate))
         .run();
 }
+
+/// Temporary GLB converted from a GR2, deleted when dropped
+struct TempGlb(PathBuf);
+
+impl Drop for TempGlb {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Path the asset loader should read for `file_path`, converting GR2 files
+/// to a temporary GLB first
+fn prepare_model(file_path: &str) -> (Result<String, String>, Option<TempGlb>) {
+    let path = Path::new(file_path);
+    let is_gr2 = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gr2"));
+    if !is_gr2 {
+        return (Ok(file_path.to_string()), None);
+    }
+
+    let glb = match std::fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|data| convert_gr2_bytes_to_glb(&data).map_err(|e| e.to_string()))
+    {
+        Ok(glb) => glb,
+        Err(e) => return (Err(format!("GR2 conversion failed: {}", e)), None),
+    };
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path =
+        std::env::temp_dir().join(format!("macpak_viewer_{}_{}.glb", std::process::id(), stem));
+    match std::fs::write(&temp_path, glb) {
+        Ok(()) => (
+            Ok(temp_path.to_string_lossy().to_string()),
+            Some(TempGlb(temp_path)),
+        ),
+        Err(e) => (
+            Err(format!("Failed to write {}: {}", temp_path.display(), e)),
+            None,
+        ),
+    }
+}
@@ -12,14 +12,15 @@ pub fn setup_scene(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    // Load the GLTF scene
-    let path = model_path.0.clone();
-    commands.spawn((
-        SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(path))),
-        Transform::default(),
-        AutoRotate,
-        ModelRoot,
-    ));
+    // Load the GLTF scene (nothing to load if a GR2 failed to convert)
+    if let Ok(path) = &model_path.loadable {
+        commands.spawn((
+            SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(path.clone()))),
+            Transform::default(),
+            AutoRotate,
+            ModelRoot,
+        ));
+    }
 
     // Camera with orbit controls (will be repositioned once model loads)
     commands.spawn((
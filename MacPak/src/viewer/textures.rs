@@ -0,0 +1,308 @@
+//! Sidecar textures: DDS/PNG files next to the model, as written by GR2
+//! bundle extraction
+//!
+//! When a bundle manifest (`pak extract --bundle --manifest`) in the model's
+//! folder or the folder above lists the model, its texture layers are used.
+//! Otherwise slots are guessed from file names: regular textures keep their
+//! game names (`_BM`/`_BMA` base color, `_NM` normal, `_PM` physical),
+//! virtual textures are written as `_BaseMap`, `_NormalMap` and
+//! `_PhysicalMap`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use maclarian::gr2_extraction::{BundleManifest, TextureLayer};
+
+use crate::viewer::types::{GroundGrid, ModelPath, ViewSettings};
+
+/// Texture slot a sidecar file fills
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum TextureSlot {
+    BaseColor,
+    Normal,
+    Physical,
+}
+
+impl TextureSlot {
+    const ALL: [Self; 3] = [Self::BaseColor, Self::Normal, Self::Physical];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::BaseColor => "base color",
+            Self::Normal => "normal",
+            Self::Physical => "physical",
+        }
+    }
+
+    /// Suffix of the regular (non-virtual) texture for this slot
+    fn game_suffix(self) -> &'static str {
+        match self {
+            Self::BaseColor => "BM",
+            Self::Normal => "NM",
+            Self::Physical => "PM",
+        }
+    }
+
+    /// Slot filled by a manifest texture layer
+    fn from_layer(layer: TextureLayer) -> Option<Self> {
+        match layer {
+            TextureLayer::BaseColor => Some(Self::BaseColor),
+            TextureLayer::Normal => Some(Self::Normal),
+            TextureLayer::Physical => Some(Self::Physical),
+            _ => None,
+        }
+    }
+
+    /// Slot of a texture file, from the suffix of its name
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        if ext != "dds" && ext != "png" {
+            return None;
+        }
+        let stem = path.file_stem()?.to_str()?.to_ascii_lowercase();
+        let suffix = stem.rsplit_once('_')?.1;
+        match suffix {
+            "bm" | "bma" | "basemap" | "basecolor" | "albedo" => Some(Self::BaseColor),
+            "nm" | "normalmap" | "normal" => Some(Self::Normal),
+            "pm" | "physicalmap" | "physical" => Some(Self::Physical),
+            _ => None,
+        }
+    }
+}
+
+/// Material built from the sidecar textures, and the materials it replaced
+#[derive(Resource, Default)]
+pub struct SidecarTextures {
+    material: Option<Handle<StandardMaterial>>,
+    has_normal_map: bool,
+    /// Original material of each mesh, restored when textures are turned off
+    originals: HashMap<Entity, Handle<StandardMaterial>>,
+}
+
+/// Textures listed for the model by a bundle manifest in its folder or the
+/// folder above
+fn find_manifest_textures(model_path: &Path) -> Option<Vec<(TextureSlot, PathBuf)>> {
+    let dir = model_path.parent()?;
+    for folder in [Some(dir), dir.parent()].into_iter().flatten() {
+        let Ok(entries) = std::fs::read_dir(folder) else {
+            continue;
+        };
+        let mut manifests: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+            })
+            .collect();
+        manifests.sort();
+
+        for path in manifests {
+            // Other JSON files simply fail to parse as a manifest
+            let Ok(manifest) = BundleManifest::load(&path) else {
+                continue;
+            };
+            let Some(model) = manifest.model_for(model_path) else {
+                continue;
+            };
+            info!("Using textures from manifest {}", path.display());
+            let mut found: Vec<(TextureSlot, PathBuf)> = Vec::new();
+            for texture in &model.textures {
+                if let Some(slot) = TextureSlot::from_layer(texture.layer) {
+                    if texture.output.exists() && !found.iter().any(|(s, _)| *s == slot) {
+                        found.push((slot, texture.output.clone()));
+                    }
+                }
+            }
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// Find the sidecar texture for each slot, from a bundle manifest if there is
+/// one, else in the model's folder. With several candidates in the folder, the
+/// first by name wins (DDS before PNG of the same name).
+fn find_sidecar_textures(model_path: &Path) -> Vec<(TextureSlot, PathBuf)> {
+    if let Some(found) = find_manifest_textures(model_path) {
+        return found;
+    }
+    let Some(dir) = model_path.parent() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    files.sort();
+
+    let mut found: Vec<(TextureSlot, PathBuf)> = Vec::new();
+    for path in files {
+        if let Some(slot) = TextureSlot::from_path(&path) {
+            if !found.iter().any(|(s, _)| *s == slot) {
+                found.push((slot, path));
+            }
+        }
+    }
+    found
+}
+
+/// Decode a DDS (through maclarian) or PNG file to RGBA pixels
+fn decode_texture(path: &Path) -> Result<(u32, u32, Vec<u8>), String> {
+    let is_dds = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dds"));
+    let image = if is_dds {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        maclarian::converter::decode_dds_bytes(&bytes)
+            .map_err(|e| e.to_string())?
+            .1
+    } else {
+        image::open(path).map_err(|e| e.to_string())?.to_rgba8()
+    };
+    Ok((image.width(), image.height(), image.into_raw()))
+}
+
+/// Rebuild Z for two-channel (BC5) normal maps, which decode with blue at 0
+fn reconstruct_normal_z(pixels: &mut [u8]) {
+    if pixels.chunks_exact(4).any(|p| p[2] != 0) {
+        return;
+    }
+    for p in pixels.chunks_exact_mut(4) {
+        let x = f32::from(p[0]) / 255.0 * 2.0 - 1.0;
+        let y = f32::from(p[1]) / 255.0 * 2.0 - 1.0;
+        let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        p[2] = ((z * 0.5 + 0.5) * 255.0).round() as u8;
+    }
+}
+
+/// Look for sidecar textures and build a material from them
+pub fn load_sidecar_textures(
+    mut commands: Commands,
+    model_path: Res<ModelPath>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Textures sit next to the file the user opened, not a converted copy
+    let model = Path::new(&model_path.source);
+    let found = find_sidecar_textures(model);
+    if found.is_empty() {
+        info!("No sidecar textures next to {}", model.display());
+        commands.insert_resource(SidecarTextures::default());
+        return;
+    }
+
+    let mut handles: HashMap<TextureSlot, Handle<Image>> = HashMap::new();
+    for slot in TextureSlot::ALL {
+        let Some((_, path)) = found.iter().find(|(s, _)| *s == slot) else {
+            warn!(
+                "No {} texture next to {} (expected *_{}.dds or .png)",
+                slot.name(),
+                model.display(),
+                slot.game_suffix()
+            );
+            continue;
+        };
+        let (width, height, mut pixels) = match decode_texture(path) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                warn!("Failed to load {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        // Only base color is stored as sRGB; normal and physical maps are data
+        let format = if slot == TextureSlot::BaseColor {
+            TextureFormat::Rgba8UnormSrgb
+        } else {
+            TextureFormat::Rgba8Unorm
+        };
+        if slot == TextureSlot::Normal {
+            reconstruct_normal_z(&mut pixels);
+        }
+        let image = Image::new(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            pixels,
+            format,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        info!("Loaded {} texture {}", slot.name(), path.display());
+        handles.insert(slot, images.add(image));
+    }
+
+    if handles.is_empty() {
+        commands.insert_resource(SidecarTextures::default());
+        return;
+    }
+
+    let has_normal_map = handles.contains_key(&TextureSlot::Normal);
+    // The physical map is used as glTF metallic-roughness (G = roughness,
+    // B = metallic), as the GR2 converter does
+    let material = materials.add(StandardMaterial {
+        base_color_texture: handles.remove(&TextureSlot::BaseColor),
+        normal_map_texture: handles.remove(&TextureSlot::Normal),
+        metallic_roughness_texture: handles.remove(&TextureSlot::Physical),
+        metallic: 1.0,
+        perceptual_roughness: 1.0,
+        // Larian normal maps use the DirectX (Y-down) convention
+        flip_normal_map_y: true,
+        ..default()
+    });
+
+    commands.insert_resource(SidecarTextures {
+        material: Some(material),
+        has_normal_map,
+        originals: HashMap::new(),
+    });
+}
+
+/// Swap model meshes between the sidecar material and their own materials
+pub fn apply_sidecar_textures(
+    view_settings: Res<ViewSettings>,
+    mut sidecar: ResMut<SidecarTextures>,
+    mut mesh_query: Query<
+        (Entity, &Mesh3d, &mut MeshMaterial3d<StandardMaterial>),
+        Without<GroundGrid>,
+    >,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let Some(material) = sidecar.material.clone() else {
+        return;
+    };
+
+    if view_settings.show_textures {
+        // Checked every frame, since the scene's meshes spawn as it loads
+        for (entity, mesh, mut mesh_material) in &mut mesh_query {
+            if sidecar.originals.contains_key(&entity) {
+                continue;
+            }
+            // Normal maps need tangents, which converted models may lack
+            if sidecar.has_normal_map {
+                if let Some(mesh) = meshes.get_mut(&mesh.0) {
+                    if !mesh.contains_attribute(Mesh::ATTRIBUTE_TANGENT) {
+                        if let Err(e) = mesh.generate_tangents() {
+                            warn!("Failed to generate tangents: {}", e);
+                        }
+                    }
+                }
+            }
+            sidecar.originals.insert(entity, mesh_material.0.clone());
+            mesh_material.0 = material.clone();
+        }
+    } else if !sidecar.originals.is_empty() {
+        for (entity, _, mut mesh_material) in &mut mesh_query {
+            if let Some(original) = sidecar.originals.remove(&entity) {
+                mesh_material.0 = original;
+            }
+        }
+        sidecar.originals.clear();
+    }
+}
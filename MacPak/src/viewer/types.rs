@@ -10,6 +10,7 @@ pub struct ViewSettings {
     pub show_wireframe: bool,
     pub show_grid: bool,
     pub show_bones: bool,
+    pub show_textures: bool,
     pub white_background: bool,
 }
 
@@ -19,14 +20,21 @@ impl Default for ViewSettings {
             show_wireframe: false,
             show_grid: true,
             show_bones: false,
+            show_textures: true,
             white_background: false,
         }
     }
 }
 
-/// Path to the model file being previewed
+/// Model file being previewed
 #[derive(Resource)]
-pub struct ModelPath(pub String);
+pub struct ModelPath {
+    /// File given on the command line
+    pub source: String,
+    /// File the asset loader reads: `source`, or a temporary GLB converted
+    /// from a GR2 source. The error message if that conversion failed.
+    pub loadable: Result<String, String>,
+}
 
 /// Camera needs to fit to the model once it loads
 #[derive(Resource, Default)]
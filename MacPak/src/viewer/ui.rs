@@ -4,7 +4,7 @@ use bevy::pbr::StandardMaterial;
 use bevy::pbr::wireframe::{NoWireframe, Wireframe, WireframeColor};
 use bevy::prelude::*;
 
-use crate::viewer::types::{GroundGrid, ViewSettings};
+use crate::viewer::types::{GroundGrid, ModelPath, ViewSettings};
 
 // UI component markers
 #[derive(Component)]
@@ -19,6 +19,9 @@ pub struct CheckboxGrid;
 #[derive(Component)]
 pub struct CheckboxBones;
 
+#[derive(Component)]
+pub struct CheckboxTextures;
+
 #[derive(Component)]
 pub struct CheckboxBackground;
 
@@ -60,6 +63,9 @@ pub fn setup_ui(mut commands: Commands) {
             // Bones checkbox
             spawn_checkbox(parent, "Skeleton (B)", CheckboxBones, false);
 
+            // Textures checkbox
+            spawn_checkbox(parent, "Textures (T)", CheckboxTextures, true);
+
             // Grid checkbox
             spawn_checkbox(parent, "Grid (G)", CheckboxGrid, true);
 
@@ -68,6 +74,34 @@ pub fn setup_ui(mut commands: Commands) {
         });
 }
 
+/// Show why the model couldn't be loaded in the middle of the window
+pub fn show_load_error(mut commands: Commands, model_path: Res<ModelPath>) {
+    let Err(error) = &model_path.loadable else {
+        return;
+    };
+    error!("Could not load {}: {}", model_path.source, error);
+    commands
+        .spawn(Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            padding: UiRect::all(Val::Px(32.0)),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Could not load {}\n\n{}", model_path.source, error)),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.45, 0.45)),
+                TextLayout::new_with_justify(Justify::Center),
+            ));
+        });
+}
+
 /// Helper to spawn a checkbox row
 fn spawn_checkbox<T: Component>(
     parent: &mut ChildSpawnerCommands,
@@ -141,12 +175,13 @@ pub fn handle_checkbox_clicks(
             Option<&CheckboxWireframe>,
             Option<&CheckboxGrid>,
             Option<&CheckboxBones>,
+            Option<&CheckboxTextures>,
             Option<&CheckboxBackground>,
         ),
         (Changed<Interaction>, With<Button>),
     >,
 ) {
-    for (interaction, wireframe, grid, bones, background) in &interaction_query {
+    for (interaction, wireframe, grid, bones, textures, background) in &interaction_query {
         if *interaction == Interaction::Pressed {
             if wireframe.is_some() {
                 view_settings.show_wireframe = !view_settings.show_wireframe;
@@ -157,6 +192,9 @@ pub fn handle_checkbox_clicks(
             if bones.is_some() {
                 view_settings.show_bones = !view_settings.show_bones;
             }
+            if textures.is_some() {
+                view_settings.show_textures = !view_settings.show_textures;
+            }
             if background.is_some() {
                 view_settings.white_background = !view_settings.white_background;
             }
@@ -184,6 +222,7 @@ pub fn sync_view_settings(
         Option<&CheckboxWireframe>,
         Option<&CheckboxGrid>,
         Option<&CheckboxBones>,
+        Option<&CheckboxTextures>,
         Option<&CheckboxBackground>,
     )>,
     mut commands: Commands,
@@ -267,13 +306,17 @@ pub fn sync_view_settings(
 
     // Update checkbox visuals
     for (child_of, mut bg_color, children) in &mut checkbox_query {
-        if let Ok((wireframe, grid, bones, background)) = parent_query.get(child_of.parent()) {
+        if let Ok((wireframe, grid, bones, textures, background)) =
+            parent_query.get(child_of.parent())
+        {
             let is_checked = if wireframe.is_some() {
                 view_settings.show_wireframe
             } else if grid.is_some() {
                 view_settings.show_grid
             } else if bones.is_some() {
                 view_settings.show_bones
+            } else if textures.is_some() {
+                view_settings.show_textures
             } else if background.is_some() {
                 view_settings.white_background
             } else {
@@ -3,18 +3,21 @@
 //! A workbench represents a mod project on disk with a `macpak.toml` manifest,
 //! a recipe describing the expected structure, and file status tracking.
 
+pub mod pipeline;
 pub mod project;
 pub mod recipe;
 pub mod scaffold;
+pub mod session;
 pub mod settings;
 
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use maclarian::mods::WorkspaceDiff;
 use maclarian::mods::validation::{ModValidationResult, validate_mod_structure};
-use maclarian::pak::{CompressionMethod, PakOperations};
 
+use pipeline::{BuildProgressCallback, BuildReport, SourceStamp};
 use project::ProjectManifest;
 use recipe::{FileKind, Recipe, find_recipe, substitute};
 use scaffold::scaffold_project;
@@ -133,63 +136,39 @@ impl Workbench {
 
     /// Validate the project structure using MacLarian's validator.
     pub fn validate(&self) -> ModValidationResult {
-        validate_mod_structure(&self.project_dir)
+        validate_mod_structure(&self.project_dir.join(&self.manifest.build.source_dir))
     }
 
     /// Build the project into a PAK file.
     ///
     /// Returns the path to the output PAK on success.
     pub fn build(&self) -> Result<PathBuf, String> {
-        // Refresh and check for missing required files
-        let vars = self.build_vars();
-        for file in &self.recipe.files {
-            if file.kind == FileKind::Optional {
-                continue;
-            }
-            let path = substitute(&file.path, &vars);
-            let full_path = self.project_dir.join(&path);
-            if !full_path.exists() {
-                return Err(format!("Required file missing: {}", path));
-            }
-        }
-
-        // Validate structure
-        let validation = self.validate();
-        if !validation.valid {
-            let warnings = validation.warnings.join(", ");
-            return Err(format!("Validation failed: {}", warnings));
-        }
-
-        // Determine output path
-        let output_dir = self.project_dir.join(&self.manifest.build.output_dir);
-        fs::create_dir_all(&output_dir)
-            .map_err(|e| format!("Failed to create output directory: {}", e))?;
-
-        let pak_name = format!("{}.pak", self.manifest.project.folder);
-        let output_pak = output_dir.join(&pak_name);
+        self.build_with_progress(&|_| {})
+            .map(|report| report.output_pak)
+    }
 
-        // Determine compression
-        let compression = match self.manifest.build.compression.to_lowercase().as_str() {
-            "none" => CompressionMethod::None,
-            "zlib" => CompressionMethod::Zlib,
-            _ => CompressionMethod::Lz4,
-        };
+    /// Build the project into a PAK file, reporting progress.
+    ///
+    /// Converts files matched by `[[build.convert]]` rules, validates, packs the
+    /// source directory and lists the written PAK.
+    pub fn build_with_progress(
+        &self,
+        progress: BuildProgressCallback,
+    ) -> Result<BuildReport, String> {
+        pipeline::build_project(self, progress)
+    }
 
-        // Create PAK
-        PakOperations::create_with_compression_and_progress(
-            &self.project_dir,
-            &output_pak,
-            compression,
-            &|_| {},
-        )
-        .map_err(|e| format!("Failed to create PAK: {}", e))?;
-
-        // Generate info.json if requested
-        if self.manifest.build.generate_info_json {
-            let _ = maclarian::mods::generate_info_json_from_source(&output_pak, &|_| {});
-        }
+    /// Stamp the files a build reads, to notice when a rebuild is needed.
+    pub fn source_stamp(&self) -> Result<SourceStamp, String> {
+        pipeline::source_stamp(self)
+    }
 
-        Ok(output_pak)
+    /// Compare the project's source directory with a built PAK.
+    ///
+    /// Files matched by `[[build.convert]]` rules are converted in memory
+    /// before comparing, so a fresh build compares clean.
+    pub fn diff_against_pak(&self, pak: impl AsRef<Path>) -> Result<WorkspaceDiff, String> {
+        pipeline::diff_project(self, pak.as_ref())
     }
 
     /// Get the default base directory for new projects.
@@ -207,7 +186,7 @@ impl Workbench {
     }
 
     /// Build template variable map from the current manifest.
-    fn build_vars(&self) -> HashMap<String, String> {
+    pub(crate) fn build_vars(&self) -> HashMap<String, String> {
         let mut vars = HashMap::new();
         vars.insert("mod_name".to_string(), self.manifest.project.folder.clone());
         vars.insert("uuid".to_string(), self.manifest.project.uuid.clone());
@@ -226,3 +205,21 @@ impl Workbench {
         self.project_dir.join(resolved)
     }
 }
+
+/// Compare a workspace directory with a built PAK.
+///
+/// Uses the conversion rules from `macpak.toml` when the directory is a
+/// workbench project; otherwise conversions are inferred from the PAK
+/// contents (e.g. `Foo.lsx` against `Foo.lsf`).
+pub fn diff_against_pak(
+    workspace_dir: impl AsRef<Path>,
+    pak_path: impl AsRef<Path>,
+) -> Result<WorkspaceDiff, String> {
+    let (workspace_dir, pak_path) = (workspace_dir.as_ref(), pak_path.as_ref());
+    if workspace_dir.join("macpak.toml").exists() {
+        Workbench::open(workspace_dir)?.diff_against_pak(pak_path)
+    } else {
+        maclarian::mods::diff_against_pak(workspace_dir, pak_path)
+            .map_err(|e| format!("Failed to compare with {}: {}", pak_path.display(), e))
+    }
+}
@@ -0,0 +1,527 @@
+//! Project build pipeline
+//!
+//! Turns a workbench project into a PAK: checks required recipe files,
+//! validates the mod structure, packs the source directory (applying the
+//! manifest's conversion rules as files are added) and lists the result.
+//! Converted files go straight from memory into the archive.
+//! The GUI Build button, [`crate::Toolkit::build_project`] and the
+//! `macpak-cli mod pack`/`mod watch` commands all run this.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use maclarian::compression::CompressionSettings;
+use maclarian::converter::convert_bytes;
+use maclarian::mods::{WorkspaceDiff, diff_against_pak_with};
+use maclarian::pak::{PakBuilder, PakOperations};
+
+use super::Workbench;
+use super::project::ConvertRule;
+use super::recipe::{FileKind, substitute};
+
+/// Progress callback type for project builds
+pub type BuildProgressCallback<'a> = &'a (dyn Fn(&BuildProgress) + Sync + Send);
+
+/// Progress information during a project build
+#[derive(Debug, Clone)]
+pub struct BuildProgress {
+    /// Current build phase
+    pub phase: BuildPhase,
+    /// Current item number (1-indexed)
+    pub current: usize,
+    /// Total number of items
+    pub total: usize,
+    /// Current file being processed (if applicable)
+    pub current_file: Option<String>,
+}
+
+impl BuildProgress {
+    /// Create a new progress update
+    pub fn new(phase: BuildPhase, current: usize, total: usize) -> Self {
+        Self {
+            phase,
+            current,
+            total,
+            current_file: None,
+        }
+    }
+
+    /// Create a progress update with a file name
+    pub fn with_file(
+        phase: BuildPhase,
+        current: usize,
+        total: usize,
+        file: impl Into<String>,
+    ) -> Self {
+        Self {
+            phase,
+            current,
+            total,
+            current_file: Some(file.into()),
+        }
+    }
+}
+
+/// Phase of a project build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildPhase {
+    /// Checking required recipe files
+    Checking,
+    /// Validating mod structure
+    Validating,
+    /// Converting source files and writing them to the PAK
+    Packing,
+    /// Generating info.json
+    GeneratingInfoJson,
+    /// Comparing the PAK against the source directory
+    Verifying,
+    /// Build complete
+    Complete,
+}
+
+impl BuildPhase {
+    /// Get a human-readable description of this phase
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Checking => "Checking files",
+            Self::Validating => "Validating structure",
+            Self::Packing => "Packing",
+            Self::GeneratingInfoJson => "Generating info.json",
+            Self::Verifying => "Verifying PAK",
+            Self::Complete => "Complete",
+        }
+    }
+}
+
+/// A file converted by a build rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertedFile {
+    /// Source path, relative to the source directory
+    pub source: String,
+    /// Path inside the PAK
+    pub output: String,
+}
+
+/// Result of a successful project build
+#[derive(Debug, Clone, Default)]
+pub struct BuildReport {
+    /// The written PAK file
+    pub output_pak: PathBuf,
+    /// Files converted by the manifest's rules
+    pub converted: Vec<ConvertedFile>,
+    /// Number of files copied unchanged
+    pub copied: usize,
+    /// Contents of the written PAK
+    pub pak_files: Vec<String>,
+    /// The written info.json, if generated
+    pub info_json: Option<PathBuf>,
+    /// Source/PAK comparison, if `build.verify` is set
+    pub diff: Option<WorkspaceDiff>,
+    /// Validation, info.json and verification warnings that didn't stop the build
+    pub warnings: Vec<String>,
+}
+
+/// Build a workbench project into a PAK
+pub(crate) fn build_project(
+    workbench: &Workbench,
+    progress: BuildProgressCallback,
+) -> Result<BuildReport, String> {
+    let manifest = &workbench.manifest;
+    let project_dir = &workbench.project_dir;
+    let mut report = BuildReport::default();
+
+    // Check for missing required files
+    let vars = workbench.build_vars();
+    let required: Vec<_> = workbench
+        .recipe
+        .files
+        .iter()
+        .filter(|f| f.kind != FileKind::Optional)
+        .collect();
+    for (i, file) in required.iter().enumerate() {
+        let path = substitute(&file.path, &vars);
+        progress(&BuildProgress::with_file(
+            BuildPhase::Checking,
+            i + 1,
+            required.len(),
+            &path,
+        ));
+        if !project_dir.join(&path).exists() {
+            return Err(format!("Required file missing: {}", path));
+        }
+    }
+
+    // Validate structure
+    let source_dir = project_dir.join(&manifest.build.source_dir);
+    progress(&BuildProgress::new(BuildPhase::Validating, 0, 1));
+    let validation = workbench.validate();
+    if !validation.valid {
+        let warnings = validation.warnings.join(", ");
+        return Err(format!("Validation failed: {}", warnings));
+    }
+    report.warnings.extend(validation.warnings);
+
+    // Determine output path
+    let output_dir = project_dir.join(&manifest.build.output_dir);
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    report.output_pak = output_pak_path(workbench);
+    if let Some(parent) = report.output_pak.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+    }
+
+    let files = source_files(workbench)?;
+
+    // Pack source files, converting the ones matched by a rule. Don't leave
+    // a half-written PAK behind if that fails.
+    let packed = pack_sources(workbench, &source_dir, &files, &mut report, progress);
+    if packed.is_err() {
+        let _ = fs::remove_file(&report.output_pak);
+    }
+    packed?;
+
+    // Generate info.json if requested
+    if manifest.build.generate_info_json {
+        progress(&BuildProgress::new(BuildPhase::GeneratingInfoJson, 0, 1));
+        let result = maclarian::mods::generate_info_json_from_source(&report.output_pak, &|_| {});
+        match result.content {
+            Some(content) if result.success => {
+                let info_path = report.output_pak.with_file_name("info.json");
+                fs::write(&info_path, content)
+                    .map_err(|e| format!("Failed to write info.json: {}", e))?;
+                report.info_json = Some(info_path);
+            }
+            _ => report.warnings.push(result.message),
+        }
+    }
+
+    report.pak_files = PakOperations::list(&report.output_pak)
+        .map_err(|e| format!("Failed to read built PAK: {}", e))?;
+
+    if manifest.build.verify {
+        progress(&BuildProgress::new(BuildPhase::Verifying, 0, 1));
+        let diff = diff_project(workbench, &report.output_pak)?;
+        if !diff.is_clean() {
+            report.warnings.push(format!(
+                "PAK differs from source: {} missing, {} extra, {} changed, {} ambiguous",
+                diff.only_in_source.len(),
+                diff.only_in_pak.len(),
+                diff.mismatched.len(),
+                diff.collisions.len()
+            ));
+        }
+        report.diff = Some(diff);
+    }
+
+    progress(&BuildProgress::new(BuildPhase::Complete, 1, 1));
+    Ok(report)
+}
+
+/// Modification times of everything a build reads
+///
+/// Two stamps compare equal when no source file or the manifest was added,
+/// removed or modified in between, which is how `macpak-cli mod watch`
+/// decides to rebuild.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceStamp {
+    files: Vec<(String, Option<SystemTime>, u64)>,
+}
+
+/// Take a [`SourceStamp`] of a workbench project
+pub(crate) fn source_stamp(workbench: &Workbench) -> Result<SourceStamp, String> {
+    let source_dir = workbench
+        .project_dir
+        .join(&workbench.manifest.build.source_dir);
+    let mut stamp = SourceStamp::default();
+    let manifest = workbench.project_dir.join("macpak.toml");
+    let paths = source_files(workbench)?
+        .into_iter()
+        .map(|rel| (source_dir.join(&rel), rel))
+        .chain(std::iter::once((manifest, "macpak.toml".to_string())));
+    for (path, rel) in paths {
+        // A file deleted while stamping shows up as missing next time
+        if let Ok(meta) = fs::metadata(&path) {
+            stamp.files.push((rel, meta.modified().ok(), meta.len()));
+        }
+    }
+    Ok(stamp)
+}
+
+/// Where a project's PAK is written
+fn output_pak_path(workbench: &Workbench) -> PathBuf {
+    let manifest = &workbench.manifest;
+    let project_dir = &workbench.project_dir;
+    match &manifest.build.output {
+        Some(output) => project_dir.join(output),
+        None => project_dir
+            .join(&manifest.build.output_dir)
+            .join(format!("{}.pak", manifest.project.folder)),
+    }
+}
+
+/// Sorted source files packed by a build, relative to the source directory
+///
+/// The manifest and the build's own output are left out.
+fn source_files(workbench: &Workbench) -> Result<Vec<String>, String> {
+    let manifest = &workbench.manifest;
+    let project_dir = &workbench.project_dir;
+    let source_dir = project_dir.join(&manifest.build.source_dir);
+
+    let mut files = Vec::new();
+    let skip = [
+        project_dir.join("macpak.toml"),
+        project_dir.join(&manifest.build.output_dir),
+        output_pak_path(workbench),
+    ];
+    collect_files(&source_dir, &source_dir, &skip, &mut files)
+        .map_err(|e| format!("Failed to read source directory: {}", e))?;
+    files.sort();
+    Ok(files)
+}
+
+/// Convert and write the collected source files into the output PAK
+fn pack_sources(
+    workbench: &Workbench,
+    source_dir: &Path,
+    files: &[String],
+    report: &mut BuildReport,
+    progress: BuildProgressCallback,
+) -> Result<(), String> {
+    let manifest = &workbench.manifest;
+    let compression: CompressionSettings = manifest.build.compression.parse().unwrap_or_default();
+    let mut pak = PakBuilder::write_to(&report.output_pak)
+        .map_err(|e| format!("Failed to create PAK: {}", e))?
+        .with_priority(manifest.build.priority);
+
+    for (i, rel) in files.iter().enumerate() {
+        progress(&BuildProgress::with_file(
+            BuildPhase::Packing,
+            i + 1,
+            files.len(),
+            rel,
+        ));
+        let data =
+            fs::read(source_dir.join(rel)).map_err(|e| format!("Failed to read {}: {}", rel, e))?;
+
+        let (path, data) = match find_rule(&manifest.build.convert, rel) {
+            Some(rule) => {
+                let output = Path::new(rel)
+                    .with_extension(&rule.to)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                let input_ext = Path::new(rel)
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let converted = convert_bytes(&data, &input_ext, &rule.to)
+                    .map_err(|e| format!("Failed to convert {}: {}", rel, e))?;
+                report.converted.push(ConvertedFile {
+                    source: rel.clone(),
+                    output: output.clone(),
+                });
+                (output, converted)
+            }
+            None => {
+                report.copied += 1;
+                (rel.clone(), data)
+            }
+        };
+
+        pak.add_file(&path, &data, compression)
+            .map_err(|e| format!("Failed to pack {}: {}", path, e))?;
+    }
+
+    pak.finish()
+        .map_err(|e| format!("Failed to create PAK: {}", e))?;
+    Ok(())
+}
+
+/// Compare a project's source directory with a PAK, applying its conversion rules
+pub(crate) fn diff_project(workbench: &Workbench, pak: &Path) -> Result<WorkspaceDiff, String> {
+    let manifest = &workbench.manifest;
+    let project_dir = &workbench.project_dir;
+    let source_dir = project_dir.join(&manifest.build.source_dir);
+
+    let conversion = |rel: &str| find_rule(&manifest.build.convert, rel).map(|r| r.to.clone());
+    let mut diff = diff_against_pak_with(&source_dir, pak, &conversion)
+        .map_err(|e| format!("Failed to compare with {}: {}", pak.display(), e))?;
+
+    // The build never packs the manifest or its own output
+    let manifest_path = project_dir.join("macpak.toml");
+    let output_dir = project_dir.join(&manifest.build.output_dir);
+    diff.only_in_source.retain(|rel| {
+        let path = source_dir.join(rel);
+        path != manifest_path && path != pak && !path.starts_with(&output_dir)
+    });
+    Ok(diff)
+}
+
+/// Recursively collect files under `dir` as `/`-separated paths relative to `root`
+///
+/// Hidden entries and anything in `skip` are left out.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    skip: &[PathBuf],
+    files: &mut Vec<String>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+        if hidden || skip.iter().any(|s| s == &path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_files(root, &path, skip, files)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            files.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+/// Find the first rule whose pattern matches a relative path
+fn find_rule<'a>(rules: &'a [ConvertRule], path: &str) -> Option<&'a ConvertRule> {
+    rules
+        .iter()
+        .find(|rule| matches_pattern(&rule.pattern, path))
+}
+
+/// Match a `/`-separated path against a rule pattern (see [`ConvertRule`])
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let path: Vec<&str> = path.split('/').collect();
+
+    if let Some(anchored) = pattern.strip_prefix('/') {
+        let pattern: Vec<&str> = anchored.split('/').collect();
+        return matches_components(&pattern, &path);
+    }
+
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    (0..path.len()).any(|start| matches_components(&pattern, &path[start..]))
+}
+
+fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_components(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path_rest)) => {
+                matches_component(first, component) && matches_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Case-insensitive `*`/`?` match within a single path component
+fn matches_component(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern.split_first() {
+            None => text.is_empty(),
+            Some(('*', rest)) => (0..=text.len()).any(|i| matches(rest, &text[i..])),
+            Some(('?', rest)) => !text.is_empty() && matches(rest, &text[1..]),
+            Some((c, rest)) => text
+                .first()
+                .is_some_and(|t| t.eq_ignore_ascii_case(c) && matches(rest, &text[1..])),
+        }
+    }
+    matches(&pattern, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copy a fixture directory so the build doesn't write into the source tree
+    fn copy_dir(src: &Path, dest: &Path) {
+        fs::create_dir_all(dest).unwrap();
+        for entry in fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            let target = dest.join(path.file_name().unwrap());
+            if path.is_dir() {
+                copy_dir(&path, &target);
+            } else {
+                fs::copy(&path, &target).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern(
+            "RootTemplates/*.lsx",
+            "Public/MyMod/RootTemplates/Item.lsx"
+        ));
+        assert!(matches_pattern(
+            "Localization/**/*.xml",
+            "Localization/English/MyMod.xml"
+        ));
+        assert!(matches_pattern("/Public/**/*.LSX", "Public/MyMod/a/b.lsx"));
+        assert!(!matches_pattern(
+            "/RootTemplates/*.lsx",
+            "Public/RootTemplates/a.lsx"
+        ));
+        assert!(!matches_pattern(
+            "RootTemplates/*.lsx",
+            "RootTemplates/sub/a.lsx"
+        ));
+    }
+
+    #[test]
+    fn test_build_sample_project() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample_project");
+        let project_dir = tempfile::tempdir().unwrap();
+        copy_dir(&fixture, project_dir.path());
+
+        let workbench = Workbench::open(project_dir.path()).unwrap();
+        let report = workbench.build_with_progress(&|_| {}).unwrap();
+
+        assert_eq!(report.converted.len(), 2);
+        assert_eq!(report.copied, 1);
+        assert!(
+            report
+                .pak_files
+                .iter()
+                .any(|f| f.ends_with("RootTemplates/SampleItem.lsf"))
+        );
+        assert!(
+            report
+                .pak_files
+                .iter()
+                .any(|f| f.ends_with("Localization/English/SampleMod.loca"))
+        );
+        assert!(report.pak_files.iter().any(|f| f.ends_with("meta.lsx")));
+        assert!(!report.pak_files.iter().any(|f| f.ends_with("macpak.toml")));
+        assert!(report.diff.unwrap().is_clean());
+        let info = maclarian::pak::pak_info(&report.output_pak, 0).unwrap();
+        assert_eq!(info.priority, 3);
+    }
+
+    #[test]
+    fn test_source_stamp_ignores_build_output() {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample_project");
+        let project_dir = tempfile::tempdir().unwrap();
+        copy_dir(&fixture, project_dir.path());
+
+        let workbench = Workbench::open(project_dir.path()).unwrap();
+        let before = workbench.source_stamp().unwrap();
+        workbench.build().unwrap();
+        assert_eq!(workbench.source_stamp().unwrap(), before);
+
+        let added = project_dir.path().join("Public/SampleMod/Notes.txt");
+        fs::write(&added, "new file").unwrap();
+        let after_add = workbench.source_stamp().unwrap();
+        assert_ne!(after_add, before);
+
+        fs::write(&added, "edited, and longer").unwrap();
+        assert_ne!(workbench.source_stamp().unwrap(), after_add);
+    }
+}
@@ -15,6 +15,10 @@ fn default_output_dir() -> String {
     "build".to_string()
 }
 
+fn default_source_dir() -> String {
+    ".".to_string()
+}
+
 /// The full project manifest (macpak.toml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectManifest {
@@ -48,6 +52,31 @@ pub struct BuildSettings {
     pub generate_info_json: bool,
     #[serde(default = "default_output_dir")]
     pub output_dir: String,
+    /// Explicit output PAK path (defaults to `<output_dir>/<folder>.pak`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Directory packed into the PAK, relative to the project root
+    #[serde(default = "default_source_dir")]
+    pub source_dir: String,
+    /// Compare the built PAK against the source directory after packing
+    #[serde(default)]
+    pub verify: bool,
+    /// Conversions applied to matching files before packing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub convert: Vec<ConvertRule>,
+}
+
+/// A build conversion rule, e.g. `RootTemplates/*.lsx` -> `lsf`
+///
+/// Patterns use `/` separators; `*` and `?` stay within one path component
+/// and `**` spans any number of components. A pattern without a leading `/`
+/// matches at any depth, so `RootTemplates/*.lsx` covers
+/// `Public/MyMod/RootTemplates/Item.lsx`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConvertRule {
+    pub pattern: String,
+    /// Target format extension (`lsf`, `loca`, `gr2`, ...)
+    pub to: String,
 }
 
 impl Default for BuildSettings {
@@ -57,6 +86,10 @@ impl Default for BuildSettings {
             priority: 0,
             generate_info_json: true,
             output_dir: default_output_dir(),
+            output: None,
+            source_dir: default_source_dir(),
+            verify: false,
+            convert: Vec::new(),
         }
     }
 }
@@ -88,5 +121,7 @@ mod tests {
         assert_eq!(parsed.project.folder, "MyCoolArmor");
         assert_eq!(parsed.build.compression, "lz4");
         assert_eq!(parsed.variables.get("item_type").unwrap(), "Armor");
+        assert_eq!(parsed.build.source_dir, ".");
+        assert!(parsed.build.convert.is_empty());
     }
 }
@@ -0,0 +1,193 @@
+//! Recovery copies of unsaved editor buffers
+//!
+//! Buffers with unsaved changes are written to `~/.macpak/session/<key>.json`
+//! when the app quits and periodically while editing, then reopened as
+//! modified tabs on the next launch. [`SessionStore::sync`] rewrites the
+//! directory to exactly the buffers it is given, so the recovery files of
+//! tabs that were saved or closed in the meantime disappear.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Session directory, relative to the home directory
+pub const SESSION_DIR: &str = ".macpak/session";
+
+/// An unsaved editor buffer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveredBuffer {
+    /// Name of the recovery file (without `.json`), unique per tab
+    pub key: String,
+    /// File the buffer was opened from, if any
+    #[serde(default)]
+    pub original_path: Option<String>,
+    /// PAK the buffer was opened from, if any
+    #[serde(default)]
+    pub pak_path: Option<String>,
+    /// Path of the entry inside `pak_path`
+    #[serde(default)]
+    pub pak_internal_path: Option<String>,
+    /// Editor format (`LSX`, `LSF`, `LOCA`, ...)
+    #[serde(default)]
+    pub format: String,
+    /// Buffer holds a binary file converted to text
+    #[serde(default)]
+    pub converted_from_binary: bool,
+    /// Cursor offset in `content`
+    #[serde(default)]
+    pub cursor_offset: usize,
+    /// The unsaved text
+    pub content: String,
+}
+
+/// Recovery files in one directory
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    /// Store backed by `dir` (created on first write)
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Store in `~/.macpak/session`
+    #[cfg(feature = "gui")]
+    pub fn default_location() -> Option<Self> {
+        dirs::home_dir().map(|home| Self::new(home.join(SESSION_DIR)))
+    }
+
+    /// Directory holding the recovery files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write one buffer's recovery file
+    pub fn save(&self, buffer: &RecoveredBuffer) -> Result<(), String> {
+        if !is_valid_key(&buffer.key) {
+            return Err(format!("Invalid recovery key: {}", buffer.key));
+        }
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+
+        let json = serde_json::to_string(buffer)
+            .map_err(|e| format!("Failed to serialize {}: {}", buffer.key, e))?;
+
+        // Write then rename, so a crash mid-write never leaves a truncated file
+        let path = self.path_for(&buffer.key);
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, json).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+        fs::rename(&temp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Delete a buffer's recovery file, if there is one
+    pub fn remove(&self, key: &str) {
+        if is_valid_key(key) {
+            let _ = fs::remove_file(self.path_for(key));
+        }
+    }
+
+    /// Make the directory hold exactly `buffers`, deleting every other
+    /// recovery file
+    pub fn sync(&self, buffers: &[RecoveredBuffer]) -> Result<(), String> {
+        for buffer in buffers {
+            self.save(buffer)?;
+        }
+        for key in self.keys() {
+            if !buffers.iter().any(|b| b.key == key) {
+                self.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    /// All readable recovery files, sorted by key. Unreadable files are skipped.
+    pub fn load_all(&self) -> Vec<RecoveredBuffer> {
+        let mut buffers: Vec<RecoveredBuffer> = self
+            .keys()
+            .into_iter()
+            .filter_map(|key| {
+                let json = fs::read_to_string(self.path_for(&key)).ok()?;
+                serde_json::from_str::<RecoveredBuffer>(&json).ok()
+            })
+            .collect();
+        buffers.sort_by(|a, b| a.key.cmp(&b.key));
+        buffers
+    }
+
+    /// Keys of the recovery files on disk
+    fn keys(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name();
+                let key = name.to_str()?.strip_suffix(".json")?;
+                is_valid_key(key).then(|| key.to_string())
+            })
+            .collect()
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// Keys become file names, so only allow characters that can't escape the directory
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(key: &str, content: &str) -> RecoveredBuffer {
+        RecoveredBuffer {
+            key: key.to_string(),
+            original_path: Some(format!("/mods/{}.lsx", key)),
+            pak_path: None,
+            pak_internal_path: None,
+            format: "LSX".to_string(),
+            converted_from_binary: false,
+            cursor_offset: 3,
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sync_replaces_stale_buffers() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SessionStore::new(dir.path());
+
+        store
+            .sync(&[buffer("tab-1", "one"), buffer("tab-2", "two")])
+            .unwrap();
+        let first = store.load_all();
+
+        // tab-1 was saved cleanly since, tab-2 changed again
+        store.sync(&[buffer("tab-2", "two, edited")]).unwrap();
+        let second = store.load_all();
+        fs::write(dir.path().join("tab-3.json"), "not json").unwrap();
+        let with_corrupt = store.load_all();
+        let invalid = store.save(&buffer("../escape", ""));
+
+        store.sync(&[]).unwrap();
+        let emptied = store.load_all();
+        let leftover = fs::read_dir(dir.path()).unwrap().count();
+
+        assert_eq!(first, vec![buffer("tab-1", "one"), buffer("tab-2", "two")]);
+        assert_eq!(second, vec![buffer("tab-2", "two, edited")]);
+        assert_eq!(with_corrupt, second);
+        assert!(invalid.is_err());
+        assert!(emptied.is_empty());
+        assert_eq!(leftover, 0);
+    }
+}
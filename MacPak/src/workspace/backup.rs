@@ -0,0 +1,300 @@
+//! Backups of files overwritten by the editor or batch conversion
+//!
+//! Before a file is overwritten its previous contents are copied to
+//! `<workspace>/.macpak/backups/<relative path>/<timestamp>`, where the
+//! workspace is the nearest ancestor containing `macpak.toml` or `.macpak`.
+//! Files outside a workspace aren't backed up. Timestamps are milliseconds
+//! since the Unix epoch, zero-padded so they sort by name. Only the newest
+//! `keep` backups of each file are retained.
+
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Backup directory, relative to the workspace root
+pub const BACKUP_DIR: &str = ".macpak/backups";
+
+/// Backups kept per file unless configured otherwise
+pub const DEFAULT_KEEP: usize = 10;
+
+/// A stored backup of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// Backup timestamp (milliseconds since the Unix epoch)
+    pub timestamp: String,
+    /// Location of the backup copy
+    pub path: PathBuf,
+    /// Size of the backup copy in bytes
+    pub size: u64,
+}
+
+impl Backup {
+    /// When the backup was taken
+    pub fn time(&self) -> Option<SystemTime> {
+        let millis = self.timestamp.parse::<u64>().ok()?;
+        UNIX_EPOCH.checked_add(Duration::from_millis(millis))
+    }
+}
+
+/// Backup storage for one workspace
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    root: PathBuf,
+    keep: usize,
+}
+
+impl BackupStore {
+    /// Store rooted at a workspace directory, keeping `keep` backups per file.
+    ///
+    /// A `keep` of 0 disables backups.
+    pub fn new(root: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            root: root.into(),
+            keep,
+        }
+    }
+
+    /// Store for the workspace containing `path`, if it is in one
+    pub fn for_file(path: &Path, keep: usize) -> Option<Self> {
+        workspace_root(path).map(|root| Self::new(root, keep))
+    }
+
+    /// Directory holding the backups of `path`
+    pub fn backup_dir(&self, path: &Path) -> PathBuf {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        self.root.join(BACKUP_DIR).join(sanitize_relative(relative))
+    }
+
+    /// Copy the current contents of `path` into the store and prune old backups.
+    ///
+    /// Returns `None` if the file doesn't exist yet or backups are disabled.
+    pub fn backup(&self, path: &Path) -> Result<Option<Backup>, String> {
+        if self.keep == 0 || !path.is_file() {
+            return Ok(None);
+        }
+
+        let dir = self.backup_dir(path);
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+        // Stay after the newest backup if saves land in the same millisecond,
+        // even when older ones were pruned, so the new copy sorts newest
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        let newest = self
+            .list(path)?
+            .first()
+            .and_then(|b| b.timestamp.parse::<u64>().ok());
+        let millis = newest.map_or(now, |newest| now.max(newest + 1));
+        let dest = dir.join(format_timestamp(millis));
+
+        let size = fs::copy(path, &dest)
+            .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+        self.prune(&dir)?;
+
+        Ok(Some(Backup {
+            timestamp: format_timestamp(millis),
+            path: dest,
+            size,
+        }))
+    }
+
+    /// Backups of `path`, newest first
+    pub fn list(&self, path: &Path) -> Result<Vec<Backup>, String> {
+        let dir = self.backup_dir(path);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let entries =
+            fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let mut backups: Vec<Backup> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let timestamp = entry
+                    .file_name()
+                    .to_str()
+                    .filter(|n| is_timestamp(n))?
+                    .to_string();
+                let size = entry.metadata().ok()?.len();
+                Some(Backup {
+                    timestamp,
+                    path: entry.path(),
+                    size,
+                })
+            })
+            .collect();
+        backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(backups)
+    }
+
+    /// Replace `path` with the backup taken at `timestamp`.
+    ///
+    /// The current contents are backed up first, so a restore can be undone.
+    pub fn restore(&self, path: &Path, timestamp: &str) -> Result<(), String> {
+        if !is_timestamp(timestamp) {
+            return Err(format!("Invalid backup timestamp: {}", timestamp));
+        }
+        let source = self.backup_dir(path).join(timestamp);
+        if !source.is_file() {
+            return Err(format!("No backup of {} at {}", path.display(), timestamp));
+        }
+
+        // Keep the restored copy even if it would be the oldest after pruning
+        let data =
+            fs::read(&source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        self.backup(path)?;
+        fs::write(path, data).map_err(|e| format!("Failed to restore {}: {}", path.display(), e))
+    }
+
+    /// Delete all but the newest `keep` backups in a directory
+    fn prune(&self, dir: &Path) -> Result<(), String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(str::to_string))
+            .filter(|n| is_timestamp(n))
+            .collect();
+        names.sort_unstable_by(|a, b| b.cmp(a));
+
+        for name in names.iter().skip(self.keep) {
+            let _ = fs::remove_file(dir.join(name));
+        }
+        Ok(())
+    }
+}
+
+/// Back up `path` before it is overwritten, using the default retention
+///
+/// Returns `None` if `path` isn't in a workspace.
+pub fn backup_file(path: &Path) -> Result<Option<Backup>, String> {
+    match BackupStore::for_file(path, DEFAULT_KEEP) {
+        Some(store) => store.backup(path),
+        None => Ok(None),
+    }
+}
+
+/// Backups of `path`, newest first
+pub fn list_backups(path: &Path) -> Result<Vec<Backup>, String> {
+    match BackupStore::for_file(path, DEFAULT_KEEP) {
+        Some(store) => store.list(path),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Replace `path` with the backup taken at `timestamp`
+pub fn restore(path: &Path, timestamp: &str) -> Result<(), String> {
+    BackupStore::for_file(path, DEFAULT_KEEP)
+        .ok_or_else(|| format!("{} is not in a workspace", path.display()))?
+        .restore(path, timestamp)
+}
+
+/// Nearest ancestor of `path` containing `macpak.toml` or `.macpak`
+pub fn workspace_root(path: &Path) -> Option<PathBuf> {
+    path.parent()
+        .unwrap_or(Path::new("."))
+        .ancestors()
+        .find(|dir| dir.join("macpak.toml").is_file() || dir.join(".macpak").is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Turn a path into one that stays inside the backup directory
+///
+/// Root and `.` components are dropped, `..` becomes `__`, and characters
+/// that aren't valid in file names on every platform (`:` and `\`) become `_`.
+fn sanitize_relative(path: &Path) -> PathBuf {
+    let mut sanitized = PathBuf::new();
+    for component in path.components() {
+        let part = match component {
+            Component::Normal(part) => sanitize_component(part),
+            Component::ParentDir => "__".to_string(),
+            Component::Prefix(prefix) => sanitize_component(prefix.as_os_str()),
+            Component::RootDir | Component::CurDir => continue,
+        };
+        sanitized.push(part);
+    }
+    sanitized
+}
+
+fn sanitize_component(part: &OsStr) -> String {
+    let part = part.to_string_lossy().replace([':', '\\'], "_");
+    if part == ".." { "__".to_string() } else { part }
+}
+
+fn format_timestamp(millis: u64) -> String {
+    format!("{:016}", millis)
+}
+
+fn is_timestamp(name: &str) -> bool {
+    name.len() == 16 && name.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Public")).unwrap();
+        fs::write(dir.path().join("macpak.toml"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_sanitize_relative() {
+        assert_eq!(
+            sanitize_relative(Path::new("../../etc/passwd")),
+            PathBuf::from("__/__/etc/passwd")
+        );
+        assert_eq!(
+            sanitize_relative(Path::new("/abs/./C:file.lsx")),
+            PathBuf::from("abs/C_file.lsx")
+        );
+    }
+
+    #[test]
+    fn test_backup_prune_and_restore() {
+        let dir = temp_workspace();
+        let file = dir.path().join("Public/Item.lsx");
+        let store = BackupStore::for_file(&file, 2).unwrap();
+        assert_eq!(store.backup(&file).unwrap(), None);
+
+        for version in ["v1", "v2", "v3"] {
+            fs::write(&file, version).unwrap();
+            store.backup(&file).unwrap();
+        }
+        let backups = store.list(&file).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups[0].path.starts_with(dir.path().join(BACKUP_DIR)));
+        assert_eq!(fs::read_to_string(&backups[1].path).unwrap(), "v2");
+
+        fs::write(&file, "broken").unwrap();
+        store.restore(&file, &backups[1].timestamp).unwrap();
+        let restored = fs::read_to_string(&file).unwrap();
+        let newest = store.list(&file).unwrap().remove(0);
+        let undo = fs::read_to_string(&newest.path).unwrap();
+        let invalid = store.restore(&file, "../macpak.toml");
+
+        assert_eq!(restored, "v2");
+        assert_eq!(undo, "broken");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_no_backups_outside_a_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        fs::create_dir_all(&out).unwrap();
+        let file = out.join("Item.lsx");
+        fs::write(&file, "old").unwrap();
+
+        assert!(workspace_root(&file).is_none());
+        assert!(BackupStore::for_file(&file, DEFAULT_KEEP).is_none());
+        assert_eq!(backup_file(&file).unwrap(), None);
+        assert!(list_backups(&file).unwrap().is_empty());
+        assert!(restore(&file, "0000000000000001").is_err());
+        assert!(!out.join(".macpak").exists());
+    }
+}
@@ -0,0 +1,6 @@
+//! Per-workspace state kept under `.macpak`
+//!
+//! A workspace is the nearest directory containing `macpak.toml` or a
+//! `.macpak` folder. Files outside any workspace get none of this.
+
+pub mod backup;